@@ -0,0 +1,21 @@
+//! Eco demand of a `SovereignAction`, costed by the live spec's
+//! `action_cost_model`.
+
+use crate::spec::ECO_FAIRNESS_SPEC;
+use crate::{EcoEnvelope, SovereignAction};
+
+/// `EcoEnvelope::from_action`: bring this trait into scope to call it.
+pub trait FromAction {
+    /// The demand `action` puts on `route`. Kinds the cost model does not
+    /// list are costed at its worst-case row.
+    fn from_action<A: SovereignAction + ?Sized>(action: &A, route: &str) -> Self;
+}
+
+impl FromAction for EcoEnvelope {
+    fn from_action<A: SovereignAction + ?Sized>(action: &A, route: &str) -> Self {
+        ECO_FAIRNESS_SPEC
+            .read()
+            .action_cost_model
+            .estimate(&action.kind(), route, f64::from(action.lifeforcecost()))
+    }
+}
@@ -0,0 +1,106 @@
+//! EVOLVE tokens: the governed path onto altar routes.
+//!
+//! A token names the one route it unlocks and when it stops doing so, and
+//! carries keyring signatures over exactly those fields. It is accepted
+//! when the route matches, the injected clock is before `expiry`, and at
+//! least `EvolvePolicy::min_signatures` distinct keys of the policy's
+//! purpose have signed it. An accepted token only opens the route: the
+//! action still goes through the budget and equity checks.
+
+use std::collections::BTreeSet;
+
+use eco_types::EvolvePolicy;
+use keyring::{KeyringSignature, SignatureVerifier, VerifyingBundle};
+use serde::{Deserialize, Serialize};
+
+use crate::GuardError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvolveToken {
+    pub token_id: String,
+    pub issued_for_route: String,
+    /// Unix seconds; the token is refused from this instant on.
+    pub expiry: u64,
+    pub signatures: Vec<KeyringSignature>,
+}
+
+/// The fields the signatures cover.
+#[derive(Serialize)]
+struct Grant<'a> {
+    token_id: &'a str,
+    issued_for_route: &'a str,
+    expiry: u64,
+}
+
+impl EvolveToken {
+    /// An unsigned token; add `signatures` made over `signing_bytes`.
+    pub fn new(token_id: impl Into<String>, issued_for_route: impl Into<String>, expiry: u64) -> Self {
+        Self {
+            token_id: token_id.into(),
+            issued_for_route: issued_for_route.into(),
+            expiry,
+            signatures: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let grant = Grant {
+            token_id: &self.token_id,
+            issued_for_route: &self.issued_for_route,
+            expiry: self.expiry,
+        };
+        serde_json::to_vec(&grant).expect("EVOLVE grant serializes")
+    }
+
+    /// Check the token for `route` at `now`. Route binding is checked
+    /// first, then expiry, then every signature, then the quorum.
+    pub fn verify(
+        &self,
+        route: &str,
+        now: u64,
+        authorities: &VerifyingBundle,
+        policy: &EvolvePolicy,
+    ) -> Result<(), GuardError> {
+        if self.issued_for_route != route {
+            return Err(GuardError::EvolveTokenWrongRoute {
+                token_id: self.token_id.clone(),
+                issued_for_route: self.issued_for_route.clone(),
+                route: route.to_string(),
+            });
+        }
+        if now >= self.expiry {
+            return Err(GuardError::EvolveTokenExpired {
+                token_id: self.token_id.clone(),
+                expiry: self.expiry,
+            });
+        }
+
+        let invalid = |reason: String| GuardError::EvolveTokenInvalid {
+            token_id: self.token_id.clone(),
+            reason,
+        };
+        let bytes = self.signing_bytes();
+        let mut signers = BTreeSet::new();
+        for sig in &self.signatures {
+            let meta = authorities
+                .key_meta(&sig.key)
+                .ok_or_else(|| invalid(format!("{} is not an EVOLVE authority", sig.key)))?;
+            if meta.purpose != policy.signer_purpose {
+                return Err(invalid(format!("{} is not an EVOLVE authority", sig.key)));
+            }
+            authorities.verify(&bytes, sig).map_err(|e| invalid(e.to_string()))?;
+            signers.insert(sig.key.as_str());
+        }
+
+        let need = policy.min_signatures.max(1);
+        if signers.len() < need {
+            return Err(GuardError::EvolveTokenUnderSigned {
+                token_id: self.token_id.clone(),
+                got: signers.len(),
+                need,
+            });
+        }
+        Ok(())
+    }
+}
@@ -1,124 +1,205 @@
 #![forbid(unsafe_code)]
 #![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::doc_markdown, clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
-use dashmap::DashMap;
-use once_cell::sync::Lazy;
-use parking_lot::RwLock;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use thiserror::Error;
-use tracing::{info, warn};
-
-pub use rohmodel::RohModel;
-pub use tsafe::{SovereignAction, PolicyEngine, RequestRoute};
-pub use vkernel::ViabilityKernel;
-
-/// Global lazy-loaded .eco-fairness.aln shard (JSON for maximum interoperability)
-static ECO_FAIRNESS_SPEC: Lazy<RwLock<EcoFairnessSpec>> = Lazy::new(|| {
-    let spec = EcoFairnessSpec::load("config/.eco-fairness.aln")
-        .expect("Failed to load .eco-fairness.aln – this invariant must exist");
-    RwLock::new(spec)
-});
-
-/// Per-subject & per-route live usage tracking (concurrent, sharded, zero-cost reads)
-static CURRENT_USAGE: Lazy<DashMap<String, EcoEnvelope>> = Lazy::new(DashMap::new);
+use std::sync::LazyLock;
 
-#[derive(Error, Debug)]
-pub enum GuardError {
-    #[error("Eco budget exceeded on route {route}: {resource} demand {demand} > limit {limit}")]
-    BudgetExceeded { route: String, resource: String, demand: f64, limit: f64 },
+pub use eco_types::{ActionCost, ActionCostModel, EcoEnvelope, EcoFairnessSpec, EcoResource, EvolvePolicy, GuardError, SpecDiff};
+pub use keyring::VerifyingBundle;
 
-    #[error("Equity violation for subject {subject}: below guaranteed minimum")]
-    BelowMinimum { subject: String },
+pub mod demand;
+pub mod evolve;
+pub mod spec;
+pub mod tsafe;
+pub mod usage;
 
-    #[error("RoH ceiling breach (current {current_roh} > {ceiling})")]
-    RohCeilingBreach { current_roh: f64, ceiling: f64 },
+pub use demand::FromAction;
+pub use evolve::EvolveToken;
+pub use spec::{current_spec, install_spec, reload_from_path, reload_spec, DEFAULT_SPEC_PATH};
+pub use tsafe::{RohModel, SovereignAction, ViabilityKernel};
+pub use usage::{
+    ArchivedUsage, ReservationId, SweepStats, UsageLifecycleConfig, UsageSnapshot, UsageTable, UsageWindows,
+};
 
-    #[error("Viability kernel rejection: {reason}")]
-    ViabilityFailure { reason: String },
-
-    #[error("Altar route treated as governed compute – requires EVOLVE token")]
-    AltarRequiresEvolve,
-}
+use spec::ECO_FAIRNESS_SPEC;
 
-/// ALN/JSON friendly – direct mapping for .eco-fairness.aln shard
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EcoEnvelope {
-    pub max_power_watts: f64,
-    pub max_emissions_gco2eq: f64,
-    pub max_compute_cycles: u64,
-    pub priority_uplift_if_eco_positive: bool, // true for earth-restoring tasks
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EcoFairnessSpec {
-    pub global_roh_ceiling: f64,                   // 0.3 immutable unless EVOLVE+multisig
-    pub global_eco_budget: EcoEnvelope,
-    pub per_route_budgets: HashMap<String, EcoEnvelope>,
-    pub per_subject_minimums: HashMap<String, EcoEnvelope>,
-    pub altar_routes: Vec<String>,                 // donation/lesson scheduling routes
-}
-
-impl EcoFairnessSpec {
-    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = std::fs::File::open(path)?;
-        let spec: EcoFairnessSpec = serde_json::from_reader(file)?;
-        info!("Loaded EcoFairnessSpec from {path}");
-        Ok(spec)
-    }
-}
+/// Per-subject live usage tracking with idle-entry expiry and sliding
+/// windows (concurrent, sharded)
+static CURRENT_USAGE: LazyLock<UsageTable> = LazyLock::new(|| {
+    let spec = ECO_FAIRNESS_SPEC.read();
+    UsageTable::with_windows(spec.usage_lifecycle.clone(), spec.usage_windows.clone())
+});
 
 /// Core kernel – pure, stateless math + shared state queries
 pub struct GraceEquityKernel {
-    roh: RohModel,
-    vkernel: ViabilityKernel,
+    roh: Box<dyn RohModel>,
+    vkernel: Box<dyn ViabilityKernel>,
+    evolve_authorities: VerifyingBundle,
+    clock: Box<dyn Fn() -> u64 + Send + Sync>,
 }
 
 impl GraceEquityKernel {
-    pub fn new(roh: RohModel, vkernel: ViabilityKernel) -> Self {
-        Self { roh, vkernel }
+    /// With no EVOLVE authorities every altar route stays closed.
+    pub fn new(roh: impl RohModel + 'static, vkernel: impl ViabilityKernel + 'static) -> Self {
+        Self {
+            roh: Box::new(roh),
+            vkernel: Box::new(vkernel),
+            evolve_authorities: VerifyingBundle::default(),
+            clock: Box::new(usage::unix_now),
+        }
+    }
+
+    /// Keys whose signatures make an EVOLVE token.
+    #[must_use]
+    pub fn with_evolve_authorities(mut self, authorities: VerifyingBundle) -> Self {
+        self.evolve_authorities = authorities;
+        self
+    }
+
+    /// Replace the wall clock (Unix seconds); used by tests and replays.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
     }
 
     /// Short-abbreviation real-world fast path
-    #[inline(always)]
+    #[inline]
     pub fn gek_check(&self, subject: &str, route: &str, demand: &EcoEnvelope) -> Result<(), GuardError> {
         self.check_route(subject, route, demand)
     }
 
-    /// Full invariant check – called on every Auto_Church governed action
+    /// Full invariant check, as `reserve` would run it, without holding
+    /// any budget.
     pub fn check_route(&self, subject: &str, route: &str, demand: &EcoEnvelope) -> Result<(), GuardError> {
+        self.dry_run(subject, route, demand, None)
+    }
+
+    /// `check_route` presenting `token` for an altar route.
+    pub fn check_evolved(
+        &self,
+        subject: &str,
+        route: &str,
+        demand: &EcoEnvelope,
+        token: &EvolveToken,
+    ) -> Result<(), GuardError> {
+        self.dry_run(subject, route, demand, Some(token))
+    }
+
+    fn dry_run(
+        &self,
+        subject: &str,
+        route: &str,
+        demand: &EcoEnvelope,
+        token: Option<&EvolveToken>,
+    ) -> Result<(), GuardError> {
         let spec = ECO_FAIRNESS_SPEC.read();
+        let now = (self.clock)();
+        let held = CURRENT_USAGE.held(subject, now);
+        self.admit(&spec, subject, route, &held, demand, token, now)
+    }
+
+    /// Hold `demand` for `subject` – called on every Auto_Church governed
+    /// action before it runs. `commit` the reservation once the action has
+    /// succeeded and `release` it if the action is refused or fails; one
+    /// left open is released after `reservation_ttl_secs`.
+    pub fn reserve(&self, subject: &str, route: &str, demand: &EcoEnvelope) -> Result<ReservationId, GuardError> {
+        self.hold(subject, route, demand, None)
+    }
+
+    /// `reserve` presenting `token` for an altar route.
+    pub fn reserve_evolved(
+        &self,
+        subject: &str,
+        route: &str,
+        demand: &EcoEnvelope,
+        token: &EvolveToken,
+    ) -> Result<ReservationId, GuardError> {
+        self.hold(subject, route, demand, Some(token))
+    }
 
+    fn hold(
+        &self,
+        subject: &str,
+        route: &str,
+        demand: &EcoEnvelope,
+        token: Option<&EvolveToken>,
+    ) -> Result<ReservationId, GuardError> {
+        let spec = ECO_FAIRNESS_SPEC.read();
+        let now = (self.clock)();
+        // Open reservations are in-flight actions, so this scan stays short.
+        CURRENT_USAGE.expire_reservations(now);
+        let id = CURRENT_USAGE.reserve(subject, demand, now, |held| {
+            self.admit(&spec, subject, route, held, demand, token, now)
+        })?;
+        CURRENT_USAGE.maybe_sweep(now);
+        Ok(id)
+    }
+
+    /// Count a reservation's demand as usage.
+    pub fn commit(&self, id: ReservationId) -> Result<(), GuardError> {
+        if CURRENT_USAGE.commit(id, (self.clock)()) {
+            Ok(())
+        } else {
+            Err(GuardError::ReservationUnknown { id: id.value() })
+        }
+    }
+
+    /// Return a reservation's demand to the budget.
+    pub fn release(&self, id: ReservationId) -> Result<(), GuardError> {
+        if CURRENT_USAGE.release_reservation(id, (self.clock)()) {
+            Ok(())
+        } else {
+            Err(GuardError::ReservationUnknown { id: id.value() })
+        }
+    }
+
+    /// The invariants for `demand`, given `held`: the subject's usage
+    /// inside each resource's window plus its open reservations.
+    #[allow(clippy::too_many_arguments)]
+    fn admit(
+        &self,
+        spec: &EcoFairnessSpec,
+        subject: &str,
+        route: &str,
+        held: &EcoEnvelope,
+        demand: &EcoEnvelope,
+        token: Option<&EvolveToken>,
+        now: u64,
+    ) -> Result<(), GuardError> {
         // 1. RoH ceiling (0.3) – hard invariant
-        if self.roh.current_value() > spec.global_roh_ceiling {
+        let current_roh = f64::from(self.roh.current_value());
+        if current_roh > spec.global_roh_ceiling {
             return Err(GuardError::RohCeilingBreach {
-                current_roh: self.roh.current_value(),
+                current_roh,
                 ceiling: spec.global_roh_ceiling,
             });
         }
 
-        // 2. Per-route budgets
+        // 2. Per-route budgets, across everything the subject holds
         if let Some(budget) = spec.per_route_budgets.get(route) {
-            if demand.max_power_watts > budget.max_power_watts {
+            let power = held.max_power_watts + demand.max_power_watts;
+            if power > budget.max_power_watts {
                 return Err(GuardError::BudgetExceeded {
                     route: route.to_string(),
-                    resource: "power".into(),
-                    demand: demand.max_power_watts,
-                    limit: budget.max_power_watts,
+                    resource: EcoResource::Power,
+                    demand: power.value(),
+                    limit: budget.max_power_watts.value(),
                 });
             }
             // …repeat for emissions & cycles
         }
 
-        // 3. Altar routes are NEVER free throughput
-        if spec.altar_routes.contains(&route.to_string()) {
-            return Err(GuardError::AltarRequiresEvolve);
+        // 3. Altar routes are NEVER free throughput: only a valid EVOLVE
+        //    token opens one, and the checks below still apply
+        if spec.altar_routes.iter().any(|r| r == route) {
+            let token = token.ok_or(GuardError::AltarRequiresEvolve)?;
+            token.verify(route, now, &self.evolve_authorities, &spec.evolve_policy)?;
         }
 
         // 4. Per-subject minimum service guarantee (equity floor)
-        let usage = CURRENT_USAGE.entry(subject.to_string()).or_default();
         if let Some(minimum) = spec.per_subject_minimums.get(subject) {
-            if usage.max_compute_cycles + demand.max_compute_cycles < minimum.max_compute_cycles {
+            if held.max_compute_cycles + demand.max_compute_cycles < minimum.max_compute_cycles {
                 return Err(GuardError::BelowMinimum { subject: subject.into() });
             }
         }
@@ -130,53 +211,122 @@ impl GraceEquityKernel {
             });
         }
 
-        // Success → atomically update live usage (dashmap is lock-free sharded)
-        let mut entry = CURRENT_USAGE.entry(subject.to_string()).or_default();
-        entry.max_power_watts += demand.max_power_watts;
-        entry.max_emissions_gco2eq += demand.max_emissions_gco2eq;
-        entry.max_compute_cycles += demand.max_compute_cycles;
-
         Ok(())
     }
 }
 
+/// Live vs. archived subject counts for dashboards and audit.
+pub fn usage_snapshot() -> UsageSnapshot {
+    CURRENT_USAGE.snapshot()
+}
+
 /// Mandatory guardian – single point of truth for Eco+Equity
+///
+/// Integration into the existing Tsafe Cortex Gate (tsafe/src/cortex_gate.rs)
+/// makes it MANDATORY for all Auto_Church routes:
+///
+/// ```ignore
+/// use ecofairness_guardian::{EcoFairnessGuard, GuardError};
+///
+/// impl PolicyEngine {
+///     pub async fn authorize_request(&self, req: SovereignAction, route: RequestRoute) -> Result<(), Box<dyn std::error::Error>> {
+///         // …existing guards (AuraBoundaryGuard, SoulNonTradeableShield, etc.)
+///
+///         // ← NEW MANDATORY ECO+EQUITY GUARD: hold the budget before actuation.
+///         //   Altar routes (donation, lesson, …) need the request's EVOLVE token.
+///         let held = match &req.evolve_token {
+///             Some(token) => self.eco_fairness_guard.reserve_evolved(&req, route.as_str(), token),
+///             None => self.eco_fairness_guard.reserve(&req, route.as_str()),
+///         };
+///         let reservation = held
+///             .map_err(|e| {
+///                 warn!("EcoFairnessGuard rejected {route:?} for {}: {e}", req.subject_id);
+///                 e
+///             })?;
+///
+///         // The actuation itself may still fail: hand the budget back, and
+///         // count it only once the action ran.
+///         match self.actuate(&req) {
+///             Ok(()) => self.eco_fairness_guard.commit(reservation)?,
+///             Err(e) => {
+///                 self.eco_fairness_guard.release(reservation)?;
+///                 return Err(e);
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
 pub struct EcoFairnessGuard {
     kernel: GraceEquityKernel,
 }
 
 impl EcoFairnessGuard {
-    pub fn new(roh: RohModel, vkernel: ViabilityKernel) -> Self {
+    pub fn new(roh: impl RohModel + 'static, vkernel: impl ViabilityKernel + 'static) -> Self {
         Self {
             kernel: GraceEquityKernel::new(roh, vkernel),
         }
     }
 
-    /// Public API used by Tsafe Cortex Gate
-    pub fn check(&self, action: &SovereignAction, route: RequestRoute) -> Result<(), GuardError> {
-        let demand = EcoEnvelope::from_action(action); // mapping defined elsewhere
-        self.kernel.gek_check(&action.subject_id, route.as_str(), &demand)
+    /// Keys whose signatures make an EVOLVE token.
+    #[must_use]
+    pub fn with_evolve_authorities(mut self, authorities: VerifyingBundle) -> Self {
+        self.kernel = self.kernel.with_evolve_authorities(authorities);
+        self
     }
-}
 
-/// Example integration into existing Tsafe Cortex Gate (drop into tsafe/src/cortex_gate.rs)
-/// This makes EcoFairnessGuard MANDATORY for all Auto_Church routes
-/*
-use ecofairness_guardian::{EcoFairnessGuard, GuardError};
+    /// Replace the wall clock (Unix seconds); used by tests and replays.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.kernel = self.kernel.with_clock(clock);
+        self
+    }
+
+    /// `new`, after installing `spec` as the live spec.
+    pub fn with_spec(
+        roh: impl RohModel + 'static,
+        vkernel: impl ViabilityKernel + 'static,
+        spec: EcoFairnessSpec,
+    ) -> Result<Self, GuardError> {
+        install_spec(spec)?;
+        Ok(Self::new(roh, vkernel))
+    }
 
-impl PolicyEngine {
-    pub async fn authorize_request(&self, req: SovereignAction, route: RequestRoute) -> Result<(), Box<dyn std::error::Error>> {
-        // …existing guards (AuraBoundaryGuard, SoulNonTradeableShield, etc.)
+    /// Dry run of `reserve`; holds nothing.
+    pub fn check(&self, action: &impl SovereignAction, route: &str) -> Result<(), GuardError> {
+        let demand = EcoEnvelope::from_action(action, route);
+        self.kernel.gek_check(action.subject_id(), route, &demand)
+    }
 
-        // ← NEW MANDATORY ECO+EQUITY GUARD
-        self.eco_fairness_guard
-            .check(&req, route)
-            .map_err(|e| {
-                warn!("EcoFairnessGuard rejected {route:?} for {}: {e}", req.subject_id);
-                e
-            })?;
+    /// Public API used by Tsafe Cortex Gate: hold the action's demand until
+    /// it has run.
+    pub fn reserve(&self, action: &impl SovereignAction, route: &str) -> Result<ReservationId, GuardError> {
+        let demand = EcoEnvelope::from_action(action, route);
+        self.kernel.reserve(action.subject_id(), route, &demand)
+    }
 
-        // continue with actuation…
-        Ok(())
+    /// `check` for an altar route, presenting `token`.
+    pub fn check_evolved(&self, action: &impl SovereignAction, route: &str, token: &EvolveToken) -> Result<(), GuardError> {
+        let demand = EcoEnvelope::from_action(action, route);
+        self.kernel.check_evolved(action.subject_id(), route, &demand, token)
+    }
+
+    /// `reserve` for an altar route, presenting `token`.
+    pub fn reserve_evolved(
+        &self,
+        action: &impl SovereignAction,
+        route: &str,
+        token: &EvolveToken,
+    ) -> Result<ReservationId, GuardError> {
+        let demand = EcoEnvelope::from_action(action, route);
+        self.kernel.reserve_evolved(action.subject_id(), route, &demand, token)
+    }
+
+    pub fn commit(&self, id: ReservationId) -> Result<(), GuardError> {
+        self.kernel.commit(id)
+    }
+
+    pub fn release(&self, id: ReservationId) -> Result<(), GuardError> {
+        self.kernel.release(id)
     }
 }
@@ -0,0 +1,86 @@
+//! The guardian's live `.eco-fairness.aln` spec.
+//!
+//! The spec is read from `DEFAULT_SPEC_PATH` on first use. If that shard is
+//! missing or unreadable the guardian starts on `EcoFairnessSpec::default()`
+//! and logs why, rather than panicking inside the first check. Deployments
+//! that load their spec elsewhere call `install_spec` (or build the guard
+//! with `EcoFairnessGuard::with_spec`) before serving.
+//!
+//! `reload_spec` and `reload_from_path` swap the spec under the write lock.
+//! A check holds the read lock for its whole run, so checks in flight
+//! finish against the spec they started with and later checks see the new
+//! one. A reload that would raise the RoH ceiling is refused unless the
+//! caller passes `allow_loosening`. `usage_lifecycle` and `usage_windows`
+//! are read once, when the usage table is built; a reload that changes them
+//! is reported in the diff and takes effect on restart.
+
+use std::path::Path;
+
+use std::sync::LazyLock;
+use parking_lot::RwLock;
+use tracing::{info, warn};
+
+use crate::{EcoFairnessSpec, GuardError, SpecDiff};
+
+/// Where the guardian looks for its shard when none was installed.
+pub const DEFAULT_SPEC_PATH: &str = "config/.eco-fairness.aln";
+
+pub(crate) static ECO_FAIRNESS_SPEC: LazyLock<RwLock<EcoFairnessSpec>> = LazyLock::new(|| {
+    let spec = EcoFairnessSpec::load(DEFAULT_SPEC_PATH)
+        .map_err(|e| GuardError::SpecReload { reason: e.to_string() })
+        .and_then(|spec| spec.validate().map(|()| spec))
+        .unwrap_or_else(|e| {
+            warn!("{DEFAULT_SPEC_PATH} not used ({e}); starting on the default eco-fairness spec");
+            EcoFairnessSpec::default()
+        });
+    RwLock::new(spec)
+});
+
+/// Replace the live spec outright, loosening included. Meant for startup;
+/// use `reload_spec` once the guardian is serving.
+pub fn install_spec(spec: EcoFairnessSpec) -> Result<(), GuardError> {
+    spec.validate()?;
+    *ECO_FAIRNESS_SPEC.write() = spec;
+    info!("eco-fairness spec installed");
+    Ok(())
+}
+
+/// Swap in `spec` and report what changed. An invalid spec, or one that
+/// raises the RoH ceiling without `allow_loosening`, is refused and the
+/// current spec kept.
+pub fn reload_spec(spec: EcoFairnessSpec, allow_loosening: bool) -> Result<SpecDiff, GuardError> {
+    spec.validate()?;
+    let mut current = ECO_FAIRNESS_SPEC.write();
+    let diff = current.diff(&spec);
+    if diff.roh_ceiling_loosened() && !allow_loosening {
+        return Err(GuardError::SpecReload {
+            reason: format!(
+                "RoH ceiling would loosen from {} to {} without allow_loosening",
+                current.global_roh_ceiling, spec.global_roh_ceiling
+            ),
+        });
+    }
+    *current = spec;
+    drop(current);
+
+    if diff.usage_policy_changed {
+        warn!("usage_lifecycle/usage_windows changed on reload; they take effect on restart");
+    }
+    info!(?diff, "eco-fairness spec reloaded");
+    Ok(diff)
+}
+
+/// `reload_spec` with the shard at `path`. A shard that cannot be read or
+/// parsed is refused like an invalid one.
+pub fn reload_from_path<P: AsRef<Path>>(path: P, allow_loosening: bool) -> Result<SpecDiff, GuardError> {
+    let path = path.as_ref();
+    let spec = EcoFairnessSpec::load(path).map_err(|e| GuardError::SpecReload {
+        reason: format!("{}: {e}", path.display()),
+    })?;
+    reload_spec(spec, allow_loosening)
+}
+
+/// A copy of the live spec.
+pub fn current_spec() -> EcoFairnessSpec {
+    ECO_FAIRNESS_SPEC.read().clone()
+}
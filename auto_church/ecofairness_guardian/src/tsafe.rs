@@ -0,0 +1,27 @@
+//! What the guardian asks of the Tsafe stack it runs inside.
+//!
+//! The RoH model, the viability kernel and the actions being authorized
+//! belong to the host's rohmodel, vkernel and tsafe crates. The guardian
+//! only reads the few values below, so it takes them through these traits
+//! and builds and tests on its own; the host implements them on its types.
+
+use crate::EcoEnvelope;
+
+/// The live risk-of-harm reading, checked against `global_roh_ceiling`.
+pub trait RohModel: Send + Sync {
+    fn current_value(&self) -> f32;
+}
+
+/// The Tsafe viability envelope every demand must stay inside.
+pub trait ViabilityKernel: Send + Sync {
+    fn is_viable(&self, demand: &EcoEnvelope) -> bool;
+}
+
+/// An action submitted to the Tsafe Cortex Gate.
+pub trait SovereignAction {
+    /// The subject whose usage the action is charged to.
+    fn subject_id(&self) -> &str;
+    /// The `action_cost_model` row the action is costed by.
+    fn kind(&self) -> String;
+    fn lifeforcecost(&self) -> f32;
+}
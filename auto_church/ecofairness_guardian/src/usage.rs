@@ -0,0 +1,478 @@
+//! Per-subject usage lifecycle for the guardian.
+//!
+//! Live entries carry a `last_touched` timestamp. A lazy sweep, amortised over
+//! guard calls, evicts entries idle past the TTL: negligible ones are dropped,
+//! material ones are folded into a compact archive so long-term fairness
+//! accounting survives eviction. Eviction uses `DashMap::remove_if`, which
+//! re-checks idleness under the shard lock, so an entry touched mid-sweep is
+//! never removed and no update is lost.
+//!
+//! Budget is taken in two phases. `reserve` admits a demand against the
+//! subject's committed usage plus what is already held, and holds it under
+//! the subject's entry lock, so concurrent reservations cannot over-commit.
+//! `commit` turns a hold into usage once the action has run;
+//! `release_reservation` hands it back. A hold left past
+//! `reservation_ttl_secs` is dropped by `expire_reservations`, and an entry
+//! with holds is never evicted.
+//!
+//! Admission only counts recent usage. Committed usage also goes into
+//! per-minute buckets, and `held` sums each resource over its own window
+//! from `UsageWindows`, so old load stops counting as the clock moves on.
+//! Buckets past every window are pruned as entries are written and read.
+//! When a material entry is evicted, its in-window buckets are parked so
+//! that eviction cannot reset a window early. `usage` stays the running
+//! total that the archive and `lifetime` report.
+
+use dashmap::DashMap;
+use eco_units::{Cycles, GramsCo2, Watts};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+use crate::EcoEnvelope;
+
+/// Lives under `usage_lifecycle` in .eco-fairness.aln, next to the spec.
+pub use eco_types::UsageLifecycleConfig;
+pub use eco_types::UsageWindows;
+
+/// Width of one usage bucket.
+pub const BUCKET_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageEntry {
+    /// Everything committed while the entry has been live.
+    pub usage: EcoEnvelope,
+    /// The same usage by `BUCKET_SECS` slot, for windowed checks.
+    pub buckets: Vec<UsageBucket>,
+    /// Demand held by reservations not yet committed or released.
+    pub reserved: EcoEnvelope,
+    /// How many reservations make up `reserved`.
+    pub holds: usize,
+    pub last_touched: u64,
+}
+
+/// Usage committed in the `BUCKET_SECS` starting at `start`.
+#[derive(Debug, Clone, Default)]
+pub struct UsageBucket {
+    pub start: u64,
+    pub usage: EcoEnvelope,
+}
+
+/// Handle on budget held by `UsageTable::reserve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReservationId(u64);
+
+impl ReservationId {
+    #[must_use]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ReservationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Reservation {
+    subject: String,
+    demand: EcoEnvelope,
+    expires_at: u64,
+}
+
+/// Compact long-term summary of a subject's evicted usage.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchivedUsage {
+    pub power_watts: Watts,
+    pub emissions_gco2eq: GramsCo2,
+    pub compute_cycles: Cycles,
+    pub last_archived: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SweepStats {
+    pub scanned: usize,
+    pub archived: usize,
+    pub dropped: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageSnapshot {
+    pub live_subjects: usize,
+    pub archived_subjects: usize,
+    /// Sum of usage discarded as negligible since startup.
+    pub dropped_compute_cycles: u64,
+    pub dropped_power_watts: f64,
+    /// Reservations neither committed, released nor expired.
+    pub open_reservations: usize,
+}
+
+#[must_use]
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+pub struct UsageTable {
+    cfg: UsageLifecycleConfig,
+    windows: UsageWindows,
+    live: DashMap<String, UsageEntry>,
+    archive: DashMap<String, ArchivedUsage>,
+    /// In-window buckets of evicted subjects.
+    parked: DashMap<String, Vec<UsageBucket>>,
+    reservations: DashMap<ReservationId, Reservation>,
+    next_reservation: AtomicU64,
+    ops_since_sweep: AtomicU64,
+    dropped_compute_cycles: AtomicU64,
+    // f64 total stored as bits and updated with a CAS loop.
+    dropped_power_bits: AtomicU64,
+}
+
+impl UsageTable {
+    #[must_use]
+    pub fn new(cfg: UsageLifecycleConfig) -> Self {
+        Self::with_windows(cfg, UsageWindows::default())
+    }
+
+    #[must_use]
+    pub fn with_windows(cfg: UsageLifecycleConfig, windows: UsageWindows) -> Self {
+        Self {
+            cfg,
+            windows,
+            live: DashMap::new(),
+            archive: DashMap::new(),
+            parked: DashMap::new(),
+            reservations: DashMap::new(),
+            next_reservation: AtomicU64::new(1),
+            ops_since_sweep: AtomicU64::new(0),
+            dropped_compute_cycles: AtomicU64::new(0),
+            dropped_power_bits: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn config(&self) -> &UsageLifecycleConfig {
+        &self.cfg
+    }
+
+    pub fn windows(&self) -> &UsageWindows {
+        &self.windows
+    }
+
+    /// Live usage for `subject`, refreshing its `last_touched`.
+    pub fn touch(&self, subject: &str, now: u64) -> EcoEnvelope {
+        let mut entry = self.live.entry(subject.to_string()).or_default();
+        entry.last_touched = now;
+        entry.usage.clone()
+    }
+
+    /// Add committed usage for `subject`.
+    pub fn add(&self, subject: &str, demand: &EcoEnvelope, now: u64) {
+        let mut entry = self.live.entry(subject.to_string()).or_default();
+        self.record(&mut entry, demand, now);
+        entry.last_touched = now;
+    }
+
+    /// Remove previously committed usage (saturating at zero), taking it
+    /// off the newest bucket.
+    pub fn release(&self, subject: &str, amount: &EcoEnvelope, now: u64) {
+        let mut entry = self.live.entry(subject.to_string()).or_default();
+        sub_tracked(&mut entry.usage, amount);
+        if let Some(bucket) = entry.buckets.last_mut() {
+            sub_tracked(&mut bucket.usage, amount);
+        }
+        entry.last_touched = now;
+    }
+
+    /// Usage inside each resource's window plus held reservations for
+    /// `subject`, refreshing its `last_touched`: what a new demand is
+    /// admitted against.
+    pub fn held(&self, subject: &str, now: u64) -> EcoEnvelope {
+        let mut entry = self.live.entry(subject.to_string()).or_default();
+        entry.last_touched = now;
+        self.held_by(subject, &mut entry, now)
+    }
+
+    fn held_by(&self, subject: &str, entry: &mut UsageEntry, now: u64) -> EcoEnvelope {
+        entry.buckets.retain(|b| self.in_window(b, now));
+        let mut held = self.windowed(&entry.buckets, now);
+        if let Some(parked) = self.parked.get(subject) {
+            add_tracked(&mut held, &self.windowed(&parked, now));
+        }
+        add_tracked(&mut held, &entry.reserved);
+        held
+    }
+
+    /// Add `demand` to the entry's total and to the bucket for `now`.
+    fn record(&self, entry: &mut UsageEntry, demand: &EcoEnvelope, now: u64) {
+        add_tracked(&mut entry.usage, demand);
+        let start = now - now % BUCKET_SECS;
+        match entry.buckets.last_mut() {
+            Some(bucket) if bucket.start == start => add_tracked(&mut bucket.usage, demand),
+            _ => {
+                let mut usage = EcoEnvelope::zero();
+                add_tracked(&mut usage, demand);
+                entry.buckets.push(UsageBucket { start, usage });
+            }
+        }
+        entry.buckets.retain(|b| self.in_window(b, now));
+    }
+
+    /// Sum of `buckets`, each resource over its own window.
+    fn windowed(&self, buckets: &[UsageBucket], now: u64) -> EcoEnvelope {
+        let counts = |bucket: &UsageBucket, window: u64| bucket.start + BUCKET_SECS > now.saturating_sub(window);
+        let mut sum = EcoEnvelope::zero();
+        for bucket in buckets {
+            if counts(bucket, self.windows.power_window_seconds) {
+                sum.max_power_watts += bucket.usage.max_power_watts;
+            }
+            if counts(bucket, self.windows.emissions_window_seconds) {
+                sum.max_emissions_gco2eq += bucket.usage.max_emissions_gco2eq;
+            }
+            if counts(bucket, self.windows.compute_window_seconds) {
+                sum.max_compute_cycles += bucket.usage.max_compute_cycles;
+            }
+        }
+        sum
+    }
+
+    /// Whether any of `bucket`'s usage still counts in its window.
+    fn in_window(&self, bucket: &UsageBucket, now: u64) -> bool {
+        self.windowed(std::slice::from_ref(bucket), now) != EcoEnvelope::zero()
+    }
+
+    /// Hold `demand` for `subject` if `admit` accepts it against the
+    /// subject's committed and held usage. `admit` runs under the subject's
+    /// entry lock, so reservations for one subject are admitted one at a
+    /// time.
+    pub fn reserve<E>(
+        &self,
+        subject: &str,
+        demand: &EcoEnvelope,
+        now: u64,
+        admit: impl FnOnce(&EcoEnvelope) -> Result<(), E>,
+    ) -> Result<ReservationId, E> {
+        let mut entry = self.live.entry(subject.to_string()).or_default();
+        entry.last_touched = now;
+        let held = self.held_by(subject, &mut entry, now);
+        admit(&held)?;
+
+        add_tracked(&mut entry.reserved, demand);
+        entry.holds += 1;
+        let id = ReservationId(self.next_reservation.fetch_add(1, Ordering::Relaxed));
+        let expires_at = now.saturating_add(self.cfg.reservation_ttl_secs);
+        self.reservations.insert(id, Reservation { subject: subject.to_string(), demand: demand.clone(), expires_at });
+        Ok(id)
+    }
+
+    /// Turn a hold into committed usage. False if `id` is unknown, already
+    /// settled or past its TTL; an expired hold is released instead.
+    pub fn commit(&self, id: ReservationId, now: u64) -> bool {
+        self.settle(id, now, true) == Some(true)
+    }
+
+    /// Hand a hold back. False if `id` is unknown, already settled or past
+    /// its TTL.
+    pub fn release_reservation(&self, id: ReservationId, now: u64) -> bool {
+        self.settle(id, now, false) == Some(true)
+    }
+
+    /// Release every hold past its TTL; returns how many.
+    pub fn expire_reservations(&self, now: u64) -> usize {
+        let due: Vec<ReservationId> =
+            self.reservations.iter().filter(|r| r.expires_at <= now).map(|r| *r.key()).collect();
+        due.into_iter().filter(|&id| self.settle(id, now, false).is_some()).count()
+    }
+
+    /// Remove `id` and release its hold, committing it if asked and still
+    /// live; `None` if there was no such reservation, else whether it was
+    /// live. The reservation is removed before the subject's entry is
+    /// locked, so this never waits on a `reserve` while holding the map.
+    fn settle(&self, id: ReservationId, now: u64, commit: bool) -> Option<bool> {
+        let (_, r) = self.reservations.remove(&id)?;
+        let live = r.expires_at > now;
+        let mut entry = self.live.entry(r.subject).or_default();
+        entry.holds = entry.holds.saturating_sub(1);
+        if entry.holds == 0 {
+            // Drop any float residue along with the last hold.
+            entry.reserved = EcoEnvelope::zero();
+        } else {
+            sub_tracked(&mut entry.reserved, &r.demand);
+        }
+        if commit && live {
+            self.record(&mut entry, &r.demand, now);
+        }
+        entry.last_touched = now;
+        Some(live)
+    }
+
+    /// Live plus archived usage: the figure long-term fairness accounting uses.
+    pub fn lifetime(&self, subject: &str) -> EcoEnvelope {
+        let mut total = self.live.get(subject).map(|e| e.usage.clone()).unwrap_or_default();
+        if let Some(a) = self.archive.get(subject) {
+            total.max_power_watts += a.power_watts;
+            total.max_emissions_gco2eq += a.emissions_gco2eq;
+            total.max_compute_cycles += a.compute_cycles;
+        }
+        total
+    }
+
+    pub fn archived(&self, subject: &str) -> Option<ArchivedUsage> {
+        self.archive.get(subject).map(|a| a.clone())
+    }
+
+    /// Count one guard operation and sweep if the amortisation budget is spent.
+    pub fn maybe_sweep(&self, now: u64) -> Option<SweepStats> {
+        if self.cfg.sweep_every_ops == 0 {
+            return None;
+        }
+        let ops = self.ops_since_sweep.fetch_add(1, Ordering::Relaxed) + 1;
+        if ops < self.cfg.sweep_every_ops {
+            return None;
+        }
+        // Only one caller wins the reset and performs the sweep.
+        if self.ops_since_sweep.compare_exchange(ops, 0, Ordering::AcqRel, Ordering::Relaxed).is_err() {
+            return None;
+        }
+        Some(self.sweep(now))
+    }
+
+    fn is_idle(&self, entry: &UsageEntry, now: u64) -> bool {
+        now.saturating_sub(entry.last_touched) > self.cfg.ttl_secs && entry.holds == 0
+    }
+
+    fn is_negligible(&self, usage: &EcoEnvelope) -> bool {
+        usage.max_power_watts <= self.cfg.negligible_power_watts
+            && usage.max_emissions_gco2eq <= self.cfg.negligible_emissions_gco2eq
+            && usage.max_compute_cycles <= self.cfg.negligible_compute_cycles
+    }
+
+    /// Evict idle entries. Candidates are collected first (iteration holds
+    /// shard read locks) and each is then removed with `remove_if`, which
+    /// re-checks idleness atomically against concurrent touches.
+    pub fn sweep(&self, now: u64) -> SweepStats {
+        let candidates: Vec<String> = self
+            .live
+            .iter()
+            .filter(|e| self.is_idle(e.value(), now))
+            .map(|e| e.key().clone())
+            .collect();
+
+        let mut stats = SweepStats { scanned: candidates.len(), ..SweepStats::default() };
+        for subject in candidates {
+            // Parked under the entry's lock, so a subject coming straight
+            // back never misses its own window.
+            let evicted = self.live.remove_if(&subject, |subject, e| {
+                let idle = self.is_idle(e, now);
+                let recent: Vec<UsageBucket> = e.buckets.iter().filter(|b| self.in_window(b, now)).cloned().collect();
+                if idle && !self.is_negligible(&e.usage) && !recent.is_empty() {
+                    self.parked.entry(subject.clone()).or_default().extend(recent);
+                }
+                idle
+            });
+            let Some((subject, entry)) = evicted else {
+                continue;
+            };
+            if self.is_negligible(&entry.usage) {
+                self.dropped_compute_cycles.fetch_add(entry.usage.max_compute_cycles.value(), Ordering::Relaxed);
+                let _ = self.dropped_power_bits.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                    Some((f64::from_bits(bits) + entry.usage.max_power_watts.value()).to_bits())
+                });
+                stats.dropped += 1;
+            } else {
+                let mut archived = self.archive.entry(subject).or_default();
+                archived.power_watts += entry.usage.max_power_watts;
+                archived.emissions_gco2eq += entry.usage.max_emissions_gco2eq;
+                archived.compute_cycles += entry.usage.max_compute_cycles;
+                archived.last_archived = now;
+                stats.archived += 1;
+            }
+        }
+
+        self.parked.retain(|_, buckets| {
+            buckets.retain(|b| self.in_window(b, now));
+            !buckets.is_empty()
+        });
+
+        if stats.archived + stats.dropped > 0 {
+            info!("Usage sweep: archived {} dropped {} subjects", stats.archived, stats.dropped);
+        }
+        stats
+    }
+
+    pub fn snapshot(&self) -> UsageSnapshot {
+        UsageSnapshot {
+            live_subjects: self.live.len(),
+            archived_subjects: self.archive.len(),
+            dropped_compute_cycles: self.dropped_compute_cycles.load(Ordering::Relaxed),
+            dropped_power_watts: f64::from_bits(self.dropped_power_bits.load(Ordering::Relaxed)),
+            open_reservations: self.reservations.len(),
+        }
+    }
+}
+
+/// Add the axes the table tracks: power, emissions and compute.
+fn add_tracked(into: &mut EcoEnvelope, amount: &EcoEnvelope) {
+    into.max_power_watts += amount.max_power_watts;
+    into.max_emissions_gco2eq += amount.max_emissions_gco2eq;
+    into.max_compute_cycles += amount.max_compute_cycles;
+}
+
+fn sub_tracked(from: &mut EcoEnvelope, amount: &EcoEnvelope) {
+    from.max_power_watts = from.max_power_watts.saturating_sub(amount.max_power_watts);
+    from.max_emissions_gco2eq = from.max_emissions_gco2eq.saturating_sub(amount.max_emissions_gco2eq);
+    from.max_compute_cycles = from.max_compute_cycles.saturating_sub(amount.max_compute_cycles);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demand(cycles: u64) -> EcoEnvelope {
+        EcoEnvelope { max_compute_cycles: Cycles::new(cycles), ..EcoEnvelope::default() }
+    }
+
+    fn cfg() -> UsageLifecycleConfig {
+        UsageLifecycleConfig { ttl_secs: 10, sweep_every_ops: 0, ..UsageLifecycleConfig::default() }
+    }
+
+    #[test]
+    fn idle_entries_are_archived_or_dropped() {
+        let table = UsageTable::new(cfg());
+        table.add("material", &demand(50_000), 0);
+        table.add("negligible", &demand(10), 0);
+        table.add("fresh", &demand(50_000), 15);
+
+        let stats = table.sweep(20);
+        assert_eq!((stats.archived, stats.dropped), (1, 1));
+
+        let snap = table.snapshot();
+        assert_eq!(snap.live_subjects, 1);
+        assert_eq!(snap.archived_subjects, 1);
+        assert_eq!(snap.dropped_compute_cycles, 10);
+        assert_eq!(table.lifetime("material").max_compute_cycles, Cycles::new(50_000));
+    }
+
+    #[test]
+    fn touch_keeps_entry_alive() {
+        let table = UsageTable::new(cfg());
+        table.add("s", &demand(50_000), 0);
+        table.touch("s", 9);
+        assert_eq!(table.sweep(15).archived, 0);
+        assert_eq!(table.snapshot().live_subjects, 1);
+    }
+
+    #[test]
+    fn resurrected_subject_accumulates_on_top_of_archive() {
+        let table = UsageTable::new(cfg());
+        table.add("s", &demand(50_000), 0);
+        table.sweep(20);
+        table.add("s", &demand(7_000), 21);
+        assert_eq!(table.lifetime("s").max_compute_cycles, Cycles::new(57_000));
+        table.sweep(40);
+        assert_eq!(table.archived("s").unwrap().compute_cycles, Cycles::new(57_000));
+    }
+}
@@ -0,0 +1,87 @@
+use ecofairness_guardian::{EvolvePolicy, EvolveToken, GuardError, VerifyingBundle};
+use keyring::Keyring;
+
+const T: u64 = 1_750_000_000;
+
+struct Authorities {
+    keyring: Keyring,
+    names: Vec<String>,
+    operator: String,
+}
+
+fn authorities() -> Authorities {
+    let mut keyring = Keyring::new().with_clock(|| T);
+    let names = (0..2).map(|_| keyring.generate("evolve-authority").unwrap()).collect();
+    let operator = keyring.generate("operator").unwrap();
+    Authorities { keyring, names, operator }
+}
+
+fn token(auth: &Authorities, route: &str, expiry: u64, signers: &[String]) -> EvolveToken {
+    let mut token = EvolveToken::new("evolve-42", route, expiry);
+    let bytes = token.signing_bytes();
+    token.signatures = signers.iter().map(|s| auth.keyring.sign(s, &bytes).unwrap()).collect();
+    token
+}
+
+fn verify(token: &EvolveToken, route: &str, now: u64, bundle: &VerifyingBundle) -> Result<(), GuardError> {
+    token.verify(route, now, bundle, &EvolvePolicy::default())
+}
+
+#[test]
+fn a_valid_token_opens_its_route() {
+    let auth = authorities();
+    let bundle = auth.keyring.verifying_bundle();
+    let lesson = token(&auth, "lesson", T + 600, &auth.names);
+    verify(&lesson, "lesson", T, &bundle).unwrap();
+    verify(&lesson, "lesson", T + 599, &bundle).unwrap();
+}
+
+#[test]
+fn an_expired_token_is_refused() {
+    let auth = authorities();
+    let bundle = auth.keyring.verifying_bundle();
+    let lesson = token(&auth, "lesson", T + 600, &auth.names);
+    let err = verify(&lesson, "lesson", T + 600, &bundle).unwrap_err();
+    assert_eq!(err, GuardError::EvolveTokenExpired { token_id: "evolve-42".into(), expiry: T + 600 });
+    assert_eq!(err.code(), "ECO_EVOLVE_TOKEN_EXPIRED");
+}
+
+#[test]
+fn a_token_bound_to_another_route_is_refused() {
+    let auth = authorities();
+    let bundle = auth.keyring.verifying_bundle();
+    let lesson = token(&auth, "lesson", T + 600, &auth.names);
+    let err = verify(&lesson, "donation", T, &bundle).unwrap_err();
+    assert!(matches!(&err, GuardError::EvolveTokenWrongRoute { issued_for_route, route, .. }
+        if issued_for_route == "lesson" && route == "donation"));
+    assert_eq!(err.code(), "ECO_EVOLVE_TOKEN_WRONG_ROUTE");
+
+    // Rebinding the token to another route breaks its signatures.
+    let mut rebound = lesson.clone();
+    rebound.issued_for_route = "donation".into();
+    let err = verify(&rebound, "donation", T, &bundle).unwrap_err();
+    assert_eq!(err.code(), "ECO_EVOLVE_TOKEN_INVALID");
+}
+
+#[test]
+fn signatures_must_reach_the_quorum_of_authorities() {
+    let auth = authorities();
+    let bundle = auth.keyring.verifying_bundle();
+
+    let one = token(&auth, "lesson", T + 600, &auth.names[..1]);
+    let err = verify(&one, "lesson", T, &bundle).unwrap_err();
+    assert!(matches!(err, GuardError::EvolveTokenUnderSigned { got: 1, need: 2, .. }));
+    // The same key twice is still one signer.
+    let twice = token(&auth, "lesson", T + 600, &[auth.names[0].clone(), auth.names[0].clone()]);
+    assert!(matches!(verify(&twice, "lesson", T, &bundle), Err(GuardError::EvolveTokenUnderSigned { got: 1, .. })));
+
+    let foreign = token(&auth, "lesson", T + 600, &[auth.names[0].clone(), auth.operator.clone()]);
+    assert_eq!(verify(&foreign, "lesson", T, &bundle).unwrap_err().code(), "ECO_EVOLVE_TOKEN_INVALID");
+    // Keys this node does not know sign nothing.
+    let stranger = token(&authorities(), "lesson", T + 600, &auth.names);
+    assert_eq!(verify(&stranger, "lesson", T, &bundle).unwrap_err().code(), "ECO_EVOLVE_TOKEN_INVALID");
+
+    // The quorum comes from the spec.
+    let policy = EvolvePolicy { min_signatures: 1, ..EvolvePolicy::default() };
+    one.verify("lesson", T, &bundle, &policy).unwrap();
+}
@@ -0,0 +1,65 @@
+use ecofairness_guardian::{
+    EcoEnvelope, EcoFairnessGuard, EcoFairnessSpec, GuardError, RohModel, SovereignAction, ViabilityKernel,
+};
+
+/// A host RoH model pinned at one reading.
+struct Roh(f32);
+
+impl RohModel for Roh {
+    fn current_value(&self) -> f32 {
+        self.0
+    }
+}
+
+/// A host viability kernel that admits any demand under a power cap.
+struct PowerCap(f64);
+
+impl ViabilityKernel for PowerCap {
+    fn is_viable(&self, demand: &EcoEnvelope) -> bool {
+        demand.max_power_watts.value() <= self.0
+    }
+}
+
+struct Action {
+    subject_id: String,
+    kind: &'static str,
+    lifeforcecost: f32,
+}
+
+impl SovereignAction for Action {
+    fn subject_id(&self) -> &str {
+        &self.subject_id
+    }
+
+    fn kind(&self) -> String {
+        self.kind.to_string()
+    }
+
+    fn lifeforcecost(&self) -> f32 {
+        self.lifeforcecost
+    }
+}
+
+fn action(subject: &str, kind: &'static str, lifeforcecost: f32) -> Action {
+    Action { subject_id: subject.to_string(), kind, lifeforcecost }
+}
+
+#[test]
+fn the_guard_reads_its_host_through_the_tsafe_traits() {
+    let guard = EcoFairnessGuard::with_spec(Roh(0.1), PowerCap(100.0), EcoFairnessSpec::default()).unwrap();
+
+    let read = action("host-7", "ReadNeuralShard", 4.0);
+    guard.check(&read, "sim").unwrap();
+    let id = guard.reserve(&read, "sim").unwrap();
+    guard.commit(id).unwrap();
+    assert_eq!(guard.release(id), Err(GuardError::ReservationUnknown { id: id.value() }));
+
+    // 120 W per unit for an OTA is outside the host's viability kernel.
+    let ota = action("host-7", "ApplyOta", 1.0);
+    assert!(matches!(guard.check(&ota, "sim"), Err(GuardError::ViabilityFailure { .. })));
+    // Altar routes stay closed without an EVOLVE token.
+    assert_eq!(guard.check(&read, "lesson"), Err(GuardError::AltarRequiresEvolve));
+
+    let breached = EcoFairnessGuard::new(Roh(0.5), PowerCap(100.0));
+    assert!(matches!(breached.check(&read, "sim"), Err(GuardError::RohCeilingBreach { .. })));
+}
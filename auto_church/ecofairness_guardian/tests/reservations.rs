@@ -0,0 +1,109 @@
+use eco_units::Cycles;
+use ecofairness_guardian::{EcoEnvelope, ReservationId, UsageLifecycleConfig, UsageTable};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const CAP: u64 = 1_000;
+
+fn demand(cycles: u64) -> EcoEnvelope {
+    EcoEnvelope { max_compute_cycles: Cycles::new(cycles), ..EcoEnvelope::default() }
+}
+
+fn table(reservation_ttl_secs: u64) -> UsageTable {
+    UsageTable::new(UsageLifecycleConfig {
+        ttl_secs: 1,
+        sweep_every_ops: 0,
+        reservation_ttl_secs,
+        ..UsageLifecycleConfig::default()
+    })
+}
+
+/// Admit while committed plus held plus `d` stays within `CAP` cycles.
+fn within_cap(d: &EcoEnvelope) -> impl FnOnce(&EcoEnvelope) -> Result<(), u64> + '_ {
+    move |held| {
+        let total = (held.max_compute_cycles + d.max_compute_cycles).value();
+        if total > CAP { Err(total) } else { Ok(()) }
+    }
+}
+
+#[test]
+fn concurrent_reservations_never_over_commit() {
+    let table = Arc::new(table(3_600));
+    let d = demand(10);
+    let barrier = Arc::new(Barrier::new(8));
+
+    // 400 attempts race for room for 100.
+    let workers: Vec<_> = (0..8)
+        .map(|_| {
+            let (table, barrier, d) = (table.clone(), barrier.clone(), d.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                (0..50).filter_map(|_| table.reserve("s", &d, 0, within_cap(&d)).ok()).collect::<Vec<ReservationId>>()
+            })
+        })
+        .collect();
+    let granted: Vec<Vec<ReservationId>> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+    assert_eq!(granted.iter().map(Vec::len).sum::<usize>(), 100);
+    assert_eq!(table.held("s", 0).max_compute_cycles, Cycles::new(CAP));
+    assert!(table.reserve("s", &d, 0, within_cap(&d)).is_err());
+
+    // Each worker commits its even holds and releases its odd ones.
+    let settlers: Vec<_> = granted
+        .into_iter()
+        .map(|ids| {
+            let table = table.clone();
+            thread::spawn(move || {
+                let mut committed = 0;
+                for (i, id) in ids.into_iter().enumerate() {
+                    if i % 2 == 0 {
+                        assert!(table.commit(id, 1));
+                        committed += 10;
+                    } else {
+                        assert!(table.release_reservation(id, 1));
+                    }
+                    assert!(!table.release_reservation(id, 1), "settled twice");
+                }
+                committed
+            })
+        })
+        .collect();
+    let committed: u64 = settlers.into_iter().map(|s| s.join().unwrap()).sum();
+
+    assert_eq!(table.snapshot().open_reservations, 0);
+    assert_eq!(table.lifetime("s").max_compute_cycles, Cycles::new(committed));
+    assert_eq!(table.held("s", 1).max_compute_cycles, Cycles::new(committed));
+
+    // Released holds are room again; committed ones are not.
+    let room = (CAP - committed) / 10;
+    let regranted = (0..room + 5).filter(|_| table.reserve("s", &d, 1, within_cap(&d)).is_ok()).count();
+    assert_eq!(regranted as u64, room);
+}
+
+#[test]
+fn abandoned_holds_expire_and_late_commits_are_refused() {
+    let table = table(5);
+    let d = demand(600);
+    let late = table.reserve("s", &d, 0, within_cap(&d)).unwrap();
+    let other = table.reserve("t", &d, 0, within_cap(&d)).unwrap();
+    assert!(table.reserve("s", &d, 1, within_cap(&d)).is_err());
+
+    // Past the TTL a commit is refused and the hold is handed back.
+    assert!(!table.commit(late, 5));
+    assert_eq!(table.held("s", 5), EcoEnvelope::default());
+    assert_eq!(table.expire_reservations(5), 1);
+    assert!(!table.release_reservation(other, 5));
+    assert_eq!(table.snapshot().open_reservations, 0);
+    table.reserve("s", &d, 5, within_cap(&d)).unwrap();
+}
+
+#[test]
+fn a_subject_with_open_holds_is_not_evicted() {
+    let table = table(3_600);
+    let d = demand(50_000);
+    let id = table.reserve("s", &d, 0, |_| Ok::<(), ()>(())).unwrap();
+    table.sweep(100);
+    assert_eq!(table.snapshot().live_subjects, 1);
+    assert!(table.commit(id, 100));
+    table.sweep(200);
+    assert_eq!(table.archived("s").unwrap().compute_cycles, Cycles::new(50_000));
+}
@@ -0,0 +1,111 @@
+use ecofairness_guardian::{current_spec, install_spec, reload_from_path, reload_spec, EcoFairnessSpec};
+use eco_units::Watts;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Barrier, Mutex, MutexGuard};
+use std::thread;
+
+/// The live spec is process-wide; run these one at a time.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn serial() -> MutexGuard<'static, ()> {
+    SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Two specs a reader can tell apart from any single field.
+fn spec(roh_ceiling: f64, altar_watts: f64) -> EcoFairnessSpec {
+    let mut spec = EcoFairnessSpec { global_roh_ceiling: roh_ceiling, ..EcoFairnessSpec::default() };
+    spec.per_route_budgets.get_mut("altar").unwrap().max_power_watts = Watts::new(altar_watts);
+    spec
+}
+
+/// Reads each reader must make while the writer is swapping specs.
+const READS: u32 = 100;
+
+#[test]
+fn checks_in_flight_see_one_whole_spec() {
+    let _serial = serial();
+    install_spec(spec(0.30, 420.0)).unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+    let reads: Arc<Vec<AtomicU32>> = Arc::new((0..4).map(|_| AtomicU32::new(0)).collect());
+    let start = Arc::new(Barrier::new(reads.len() + 1));
+
+    let readers: Vec<_> = (0..reads.len())
+        .map(|i| {
+            let (done, reads, start) = (done.clone(), reads.clone(), start.clone());
+            thread::spawn(move || {
+                start.wait();
+                while !done.load(Ordering::Acquire) {
+                    let spec = current_spec();
+                    let altar = spec.per_route_budgets["altar"].max_power_watts;
+                    match spec.global_roh_ceiling {
+                        0.30 => assert_eq!(altar, Watts::new(420.0)),
+                        0.25 => assert_eq!(altar, Watts::new(300.0)),
+                        c => panic!("saw a refused ceiling {c}"),
+                    }
+                    reads[i].fetch_add(1, Ordering::Release);
+                }
+            })
+        })
+        .collect();
+
+    // Keep swapping until every reader has read often enough to have
+    // raced the swaps, however the threads were scheduled.
+    start.wait();
+    while reads.iter().any(|r| r.load(Ordering::Acquire) < READS) {
+        let diff = reload_spec(spec(0.25, 300.0), false).unwrap();
+        assert_eq!(diff.roh_ceiling, Some((0.30, 0.25)));
+        assert_eq!(diff.budgets_tightened, ["altar"]);
+        // Invalid, and loosening without the flag: both refused mid-flight.
+        assert!(reload_spec(spec(0.45, 900.0), true).is_err());
+        assert!(reload_spec(spec(0.30, 420.0), false).is_err());
+        let diff = reload_spec(spec(0.30, 420.0), true).unwrap();
+        assert!(diff.roh_ceiling_loosened());
+        if readers.iter().any(thread::JoinHandle::is_finished) {
+            break;
+        }
+    }
+    done.store(true, Ordering::Release);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert!(reads.iter().all(|r| r.load(Ordering::Acquire) >= READS));
+}
+
+#[test]
+fn loosening_the_roh_ceiling_needs_the_flag() {
+    let _serial = serial();
+    install_spec(spec(0.20, 420.0)).unwrap();
+
+    let refused = reload_spec(spec(0.30, 420.0), false).unwrap_err();
+    assert_eq!(refused.code(), "ECO_SPEC_RELOAD_REFUSED");
+    assert_eq!(current_spec().global_roh_ceiling, 0.20);
+
+    // Loosening budgets alone is reported but allowed.
+    let diff = reload_spec(spec(0.20, 500.0), false).unwrap();
+    assert_eq!((diff.roh_ceiling, diff.budgets_loosened), (None, vec!["altar".to_string()]));
+
+    assert!(reload_spec(spec(0.30, 500.0), true).unwrap().roh_ceiling_loosened());
+    assert_eq!(current_spec().global_roh_ceiling, 0.30);
+}
+
+#[test]
+fn reload_from_path_keeps_the_current_spec_on_a_bad_shard() {
+    let _serial = serial();
+    install_spec(spec(0.30, 420.0)).unwrap();
+    let dir = std::env::temp_dir().join(format!("eco-spec-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let missing = reload_from_path(dir.join("absent.aln"), false).unwrap_err();
+    assert_eq!(missing.code(), "ECO_SPEC_RELOAD_REFUSED");
+    let garbled = dir.join("garbled.aln");
+    std::fs::write(&garbled, "{ not json").unwrap();
+    assert!(reload_from_path(&garbled, false).is_err());
+    assert_eq!(current_spec().per_route_budgets["altar"].max_power_watts, Watts::new(420.0));
+
+    let shard = dir.join("tighter.aln");
+    std::fs::write(&shard, serde_json::to_string(&spec(0.25, 420.0)).unwrap()).unwrap();
+    let diff = reload_from_path(&shard, false).unwrap();
+    assert_eq!(diff.roh_ceiling, Some((0.30, 0.25)));
+    assert!(diff.budgets_tightened.is_empty() && diff.routes_added.is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,91 @@
+use eco_units::Cycles;
+use ecofairness_guardian::{EcoEnvelope, UsageLifecycleConfig, UsageTable};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+fn demand(cycles: u64) -> EcoEnvelope {
+    EcoEnvelope { max_compute_cycles: Cycles::new(cycles), ..EcoEnvelope::default() }
+}
+
+#[test]
+fn one_million_ephemeral_subjects_stay_bounded_without_drift() {
+    const SUBJECTS: u64 = 1_000_000;
+    const PER_SEC: u64 = 1_000;
+    let cfg = UsageLifecycleConfig { ttl_secs: 60, sweep_every_ops: 10_000, ..UsageLifecycleConfig::default() };
+    let bound = (cfg.ttl_secs as usize + 1) * PER_SEC as usize + cfg.sweep_every_ops as usize;
+    let table = UsageTable::new(cfg);
+
+    // Ground truth: every 7th subject is material, the rest negligible.
+    let mut truth: HashMap<u64, u64> = HashMap::new();
+    let mut total: u64 = 0;
+    let mut peak_live = 0;
+    for i in 0..SUBJECTS {
+        let now = i / PER_SEC;
+        let cycles = if i % 7 == 0 { 5_000 } else { 100 };
+        table.add(&format!("subject-{i}"), &demand(cycles), now);
+        if i % 7 == 0 {
+            truth.insert(i, cycles);
+        }
+        total += cycles;
+        peak_live = peak_live.max(table.snapshot().live_subjects);
+        table.maybe_sweep(now);
+    }
+    assert!(peak_live <= bound, "live subjects peaked at {peak_live}, bound {bound}");
+
+    let end = SUBJECTS / PER_SEC + 3_600;
+    table.sweep(end);
+    let snap = table.snapshot();
+    assert_eq!(snap.live_subjects, 0);
+    assert_eq!(snap.archived_subjects, truth.len());
+
+    let archived: u64 = truth.keys().map(|i| table.lifetime(&format!("subject-{i}")).max_compute_cycles.value()).sum();
+    assert_eq!(archived + snap.dropped_compute_cycles, total);
+    for (i, cycles) in truth.iter().take(1_000) {
+        assert_eq!(table.lifetime(&format!("subject-{i}")).max_compute_cycles.value(), *cycles);
+    }
+}
+
+#[test]
+fn concurrent_sweeps_lose_no_updates() {
+    let cfg = UsageLifecycleConfig {
+        ttl_secs: 1,
+        sweep_every_ops: 0,
+        negligible_compute_cycles: Cycles::ZERO,
+        ..UsageLifecycleConfig::default()
+    };
+    let table = Arc::new(UsageTable::new(cfg));
+    let clock = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let sweeper = {
+        let (table, clock, stop) = (table.clone(), clock.clone(), stop.clone());
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let now = clock.fetch_add(1, Ordering::Relaxed) + 1;
+                table.sweep(now);
+            }
+        })
+    };
+
+    let writers: Vec<_> = (0..8u64)
+        .map(|t| {
+            let (table, clock) = (table.clone(), clock.clone());
+            thread::spawn(move || {
+                for i in 0..20_000u64 {
+                    let subject = format!("s{}", (i + t) % 64);
+                    table.add(&subject, &demand(1), clock.load(Ordering::Relaxed));
+                }
+            })
+        })
+        .collect();
+    for w in writers {
+        w.join().unwrap();
+    }
+    stop.store(true, Ordering::Relaxed);
+    sweeper.join().unwrap();
+
+    let total: u64 = (0..64).map(|s| table.lifetime(&format!("s{s}")).max_compute_cycles.value()).sum();
+    assert_eq!(total + table.snapshot().dropped_compute_cycles, 8 * 20_000);
+}
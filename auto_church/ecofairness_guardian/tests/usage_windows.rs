@@ -0,0 +1,84 @@
+use eco_units::{GramsCo2, Watts};
+use ecofairness_guardian::{EcoEnvelope, UsageLifecycleConfig, UsageTable, UsageWindows};
+
+const POWER_CAP: Watts = Watts::new(1_000.0);
+
+fn table() -> UsageTable {
+    let cfg = UsageLifecycleConfig { ttl_secs: 60, sweep_every_ops: 0, ..UsageLifecycleConfig::default() };
+    UsageTable::with_windows(cfg, UsageWindows::default())
+}
+
+fn demand(watts: f64, grams: f64) -> EcoEnvelope {
+    EcoEnvelope {
+        max_power_watts: Watts::new(watts),
+        max_emissions_gco2eq: GramsCo2::new(grams),
+        ..EcoEnvelope::default()
+    }
+}
+
+/// Reserve and commit `d` for `subject` at `now` if the power cap allows.
+fn run(table: &UsageTable, subject: &str, d: &EcoEnvelope, now: u64) -> bool {
+    let reserved = table.reserve(subject, d, now, |held| {
+        if held.max_power_watts + d.max_power_watts > POWER_CAP { Err(()) } else { Ok(()) }
+    });
+    reserved.is_ok_and(|id| table.commit(id, now))
+}
+
+#[test]
+fn power_capacity_frees_up_as_the_window_slides() {
+    let table = table();
+    let d = demand(100.0, 0.0);
+    // Fill the 15 minute window inside the first minute.
+    for t in 0..10 {
+        assert!(run(&table, "s", &d, t * 5), "action {t}");
+    }
+    assert!(!run(&table, "s", &d, 100));
+    assert_eq!(table.held("s", 100).max_power_watts, POWER_CAP);
+
+    // The first minute's bucket counts until it has left the window whole.
+    assert!(!run(&table, "s", &d, 959));
+    assert!(run(&table, "s", &d, 960));
+    assert_eq!(table.held("s", 960).max_power_watts, Watts::new(100.0));
+
+    // The running total keeps everything for the archive.
+    assert_eq!(table.lifetime("s").max_power_watts, Watts::new(1_100.0));
+}
+
+#[test]
+fn each_resource_slides_over_its_own_window() {
+    let table = table();
+    assert!(run(&table, "s", &demand(400.0, 500.0), 0));
+
+    let after_power = table.held("s", 15 * 60 + 60);
+    assert_eq!(after_power.max_power_watts, Watts::ZERO);
+    assert_eq!(after_power.max_emissions_gco2eq, GramsCo2::new(500.0));
+
+    let after_emissions = table.held("s", 24 * 3600 + 60);
+    assert_eq!(after_emissions, EcoEnvelope::default());
+
+    // A shorter configured window frees emissions sooner.
+    let cfg = UsageLifecycleConfig { sweep_every_ops: 0, ..UsageLifecycleConfig::default() };
+    let hourly = UsageTable::with_windows(cfg, UsageWindows { emissions_window_seconds: 3_600, ..UsageWindows::default() });
+    hourly.add("s", &demand(0.0, 500.0), 0);
+    assert_eq!(hourly.held("s", 3_659).max_emissions_gco2eq, GramsCo2::new(500.0));
+    assert_eq!(hourly.held("s", 3_660).max_emissions_gco2eq, GramsCo2::ZERO);
+}
+
+#[test]
+fn eviction_does_not_reset_a_window() {
+    let table = table();
+    assert!(run(&table, "s", &demand(0.0, 500.0), 0));
+
+    // Idle past the TTL: archived, but its emissions still count.
+    assert_eq!(table.sweep(1_000).archived, 1);
+    assert_eq!(table.snapshot().live_subjects, 0);
+    assert_eq!(table.held("s", 1_000).max_emissions_gco2eq, GramsCo2::new(500.0));
+    table.add("s", &demand(0.0, 200.0), 1_000);
+    assert_eq!(table.held("s", 1_001).max_emissions_gco2eq, GramsCo2::new(700.0));
+
+    // Once the window has passed, the parked buckets are pruned too.
+    let later = 24 * 3600 + 2_000;
+    table.sweep(later);
+    assert_eq!(table.held("s", later), EcoEnvelope::default());
+    assert_eq!(table.lifetime("s").max_emissions_gco2eq, GramsCo2::new(700.0));
+}
@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
-use zeroize::Zeroize;
+use chrono::Utc;
+
+use crate::CHURCH_RECOMMEND_PER_GOOD_DEED;
 
 /// Exact DeedEvent schema from the Church-of-FEAR moral ledger specification
-#[derive(Debug, Clone, Serialize, Deserialize, Zeroize)]
-#[zeroize(drop)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeedEvent {
     pub event_id: Uuid,
     pub timestamp: i64,                     // Unix epoch seconds
@@ -48,7 +48,7 @@ impl DeedEvent {
 
     /// Convenience constructors – these are the deeds that earn CHURCH recommendations
     pub fn new_ecological_sustainability(actor_id: String, evidence_url: String) -> Self {
-        let mut ctx = serde_json::json!({ "evidence_url": evidence_url });
+        let ctx = serde_json::json!({ "evidence_url": evidence_url });
         Self::new(
             actor_id,
             vec![],
@@ -97,3 +97,53 @@ impl DeedEvent {
         }
     }
 }
+
+impl From<&DeedEvent> for deed_core::DeedEvent {
+    /// The shared shape, keeping the deed's hash: the moral-ledger rule,
+    /// the timestamp in milliseconds.
+    fn from(event: &DeedEvent) -> Self {
+        Self {
+            event_id: event.event_id.to_string(),
+            timestamp: event.timestamp.saturating_mul(1_000),
+            prev_hash: event.prev_hash.clone(),
+            self_hash: event.self_hash.clone(),
+            actor_id: event.actor_id.clone(),
+            target_ids: event.target_ids.clone(),
+            deed_type: event.deed_type.clone(),
+            tags: event.tags.clone(),
+            context_json: event.context_json.clone(),
+            ethics_flags: event.ethics_flags.clone(),
+            life_harm_flag: event.life_harm_flag,
+            ext: serde_json::Map::new(),
+            hash_rule: deed_core::HashRule::MoralLedgerV1,
+        }
+    }
+}
+
+/// Only moral-ledger deeds with a UUID event id and no `ext` fields
+/// convert back.
+impl TryFrom<deed_core::DeedEvent> for DeedEvent {
+    type Error = deed_core::DeedCoreError;
+
+    fn try_from(event: deed_core::DeedEvent) -> Result<Self, Self::Error> {
+        deed_core::DeedCoreError::check_rule(&event, deed_core::HashRule::MoralLedgerV1)?;
+        let shape = |reason: String| deed_core::DeedCoreError::Shape { event_id: event.event_id.clone(), reason };
+        if !event.ext.is_empty() {
+            return Err(shape("ext fields this ledger cannot hold".to_string()));
+        }
+        let event_id = Uuid::parse_str(&event.event_id).map_err(|e| shape(e.to_string()))?;
+        Ok(Self {
+            event_id,
+            timestamp: event.timestamp.div_euclid(1_000),
+            prev_hash: event.prev_hash.clone(),
+            self_hash: event.self_hash.clone(),
+            actor_id: event.actor_id.clone(),
+            target_ids: event.target_ids.clone(),
+            deed_type: event.deed_type.clone(),
+            tags: event.tags.clone(),
+            context_json: event.context_json.clone(),
+            ethics_flags: event.ethics_flags.clone(),
+            life_harm_flag: event.life_harm_flag,
+        })
+    }
+}
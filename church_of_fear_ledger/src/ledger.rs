@@ -3,6 +3,7 @@ use crate::validator::{LedgerValidator, ValidationError};
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
+use uuid::Uuid;
 
 /// Append-only, hash-chained moral ledger (exactly .evolve.jsonl + .donutloop.aln pattern)
 #[derive(Debug)]
@@ -13,7 +14,7 @@ pub struct MoralLedger {
 
 impl MoralLedger {
     pub fn open_or_create(path: PathBuf) -> Result<Self, std::io::Error> {
-        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        OpenOptions::new().read(true).append(true).create(true).open(&path)?;
         let mut last_hash = "0".repeat(64); // genesis
 
         if path.exists() {
@@ -29,6 +30,11 @@ impl MoralLedger {
         Ok(Self { path, last_hash })
     }
 
+    /// The `self_hash` the next deed's `prev_hash` must carry.
+    pub fn last_hash(&self) -> &str {
+        &self.last_hash
+    }
+
     /// Append a new deed – performs full validation + hash chaining
     pub fn append(&mut self, mut event: DeedEvent) -> Result<Uuid, ValidationError> {
         LedgerValidator::validate_new_event(&event, &self.last_hash)?;
@@ -0,0 +1,61 @@
+//! `cargo bench --bench batch_validation`: a 10k-deed batch through the
+//! sequential pipeline and through the two-phase `validate_batch`.
+
+use church_of_fear::compliance::batch::{validate_batch, validate_batch_sequential, DeedRules};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::WatershedCleanupDeed;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::json;
+
+const BATCH: usize = 10_000;
+
+fn batch(tip: String) -> Vec<(DeedEvent, BioloadMetrics)> {
+    let metrics = BioloadMetrics::new(-0.2, 0.1, 0.5);
+    let mut prev = tip;
+    (0..BATCH)
+        .map(|i| {
+            let deed = match i % 4 {
+                0 => WatershedCleanupDeed::builder()
+                    .actor_id(format!("actor-{}", i % 97))
+                    .location("Tempe, AZ")
+                    .volunteers(12)
+                    .waste_kg(80.0)
+                    .evidence_uri("ipfs://cleanup")
+                    .target(format!("target:reach-{}", i))
+                    .build(prev.clone())
+                    .unwrap(),
+                _ => DeedEvent::new(
+                    prev.clone(),
+                    format!("actor-{}", i % 97),
+                    vec![],
+                    "tree_planting".into(),
+                    vec!["eco".into()],
+                    json!({ "trees": i, "notes": "riverbank planting with native cottonwood and willow" }),
+                    vec![],
+                    false,
+                ),
+            };
+            prev = deed.self_hash.clone();
+            (deed, metrics.clone())
+        })
+        .collect()
+}
+
+fn bench_batch_validation(c: &mut Criterion) {
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let deeds = batch(ledger.last_hash());
+    let rules = DeedRules::standard();
+    assert_eq!(validate_batch(&ledger, &deeds, &rules).verdicts, validate_batch_sequential(&ledger, &deeds, &rules));
+
+    let mut group = c.benchmark_group("validate_10k_deeds");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| b.iter(|| validate_batch_sequential(&ledger, black_box(&deeds), &rules)));
+    group.bench_function("two_phase", |b| b.iter(|| validate_batch(&ledger, black_box(&deeds), &rules)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_validation);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! `cargo bench --bench power_gini`: the per-tick POWER Gini read over
+//! 100k accounts, from the histograms against a sort of every balance.
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const ACCOUNTS: usize = 100_000;
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    for i in 0..ACCOUNTS {
+        let id = format!("acct-{}", i);
+        ledger.open_account(&id, &id);
+        // Two thirds churned to dust, the rest spread over six decades.
+        if i % 3 == 0 {
+            let pwr = 10u64.pow((i % 7) as u32) + i as u64;
+            ledger.mint_reward(&id, Token::Pwr, pwr).unwrap();
+        }
+    }
+    ledger
+}
+
+fn power_gini(c: &mut Criterion) {
+    let ledger = ledger();
+    let mut group = c.benchmark_group("power_gini_tick");
+    group.bench_function("histogram", |b| b.iter(|| black_box(ledger.power_gini())));
+    group.bench_function("exact_sort", |b| b.iter(|| black_box(ledger.exact_power_gini())));
+    group.finish();
+}
+
+criterion_group!(benches, power_gini);
+criterion_main!(benches);
@@ -0,0 +1,255 @@
+// Generates typed deed builders from taxonomy/deeds.json.
+// Each category gets a `<Name>` marker with `builder()`, and a typestate
+// builder whose `build()` only exists once every required field is set.
+// Editing the taxonomy regenerates the builders on the next build.
+
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::{env, fs, path::Path};
+
+const TAXONOMY: &str = "taxonomy/deeds.json";
+
+struct Field {
+    name: String,
+    kind: String,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl Field {
+    fn parse(v: &Value) -> Field {
+        Field {
+            name: v["name"].as_str().expect("field name").to_string(),
+            kind: v["kind"].as_str().expect("field kind").to_string(),
+            min: v.get("min").and_then(Value::as_f64),
+            max: v.get("max").and_then(Value::as_f64),
+        }
+    }
+
+    fn rust_type(&self) -> &'static str {
+        match self.kind.as_str() {
+            "string" => "String",
+            "number" => "f64",
+            "integer" => "u64",
+            other => panic!("{}: unknown field kind '{}'", TAXONOMY, other),
+        }
+    }
+
+    fn kind_variant(&self) -> &'static str {
+        match self.kind.as_str() {
+            "string" => "FieldKind::String",
+            "number" => "FieldKind::Number",
+            "integer" => "FieldKind::Integer",
+            other => panic!("{}: unknown field kind '{}'", TAXONOMY, other),
+        }
+    }
+
+    fn setter_arg(&self) -> &'static str {
+        match self.kind.as_str() {
+            "string" => "impl Into<String>",
+            "number" => "f64",
+            _ => "u64",
+        }
+    }
+
+    fn setter_value(&self) -> &'static str {
+        if self.kind == "string" {
+            "value.into()"
+        } else {
+            "value"
+        }
+    }
+
+    fn spec(&self) -> String {
+        format!(
+            "FieldSpec {{ name: {:?}, kind: {}, min: {:?}, max: {:?} }}",
+            self.name,
+            self.kind_variant(),
+            self.min,
+            self.max
+        )
+    }
+
+    fn check(&self, expr: &str) -> String {
+        match self.kind.as_str() {
+            "string" => format!("check_string({:?}, &{})?;", self.name, expr),
+            "number" => format!("check_number({:?}, {}, {:?}, {:?})?;", self.name, expr, self.min, self.max),
+            _ => format!("check_number({:?}, {} as f64, {:?}, {:?})?;", self.name, expr, self.min, self.max),
+        }
+    }
+}
+
+/// `FollowUpSpec` literal for one entry of a category's `follow_ups`.
+fn follow_up_spec(v: &Value) -> String {
+    format!(
+        "FollowUpSpec {{ kind: {:?}, due_after_days: {}, window_days: {}, evidence: {:?} }}",
+        v["kind"].as_str().expect("follow-up kind"),
+        v["due_after_days"].as_u64().expect("follow-up due_after_days"),
+        v["window_days"].as_u64().expect("follow-up window_days"),
+        v["evidence"].as_str().expect("follow-up evidence"),
+    )
+}
+
+/// `Some(CooldownSpec)` literal for a category's `cooldown`.
+fn cooldown_spec(v: Option<&Value>) -> String {
+    match v {
+        Some(v) => format!(
+            "Some(CooldownSpec {{ min_interval_days: {}, per_target: {} }})",
+            v["min_interval_days"].as_u64().expect("cooldown min_interval_days"),
+            v.get("per_target").and_then(Value::as_bool).unwrap_or(false),
+        ),
+        None => "None".to_string(),
+    }
+}
+
+/// `Some(ValidationSpec)` literal for a category's `required_validations`.
+fn validation_spec(v: Option<&Value>) -> String {
+    match v {
+        Some(v) => format!(
+            "Some(ValidationSpec {{ count: {}, min_reputation: {:?} }})",
+            v["count"].as_u64().expect("required_validations count"),
+            v["min_reputation"].as_f64().expect("required_validations min_reputation"),
+        ),
+        None => "None".to_string(),
+    }
+}
+
+fn generate(taxonomy: &Value) -> String {
+    let mut out = String::new();
+    let mut schemas = Vec::new();
+
+    for cat in taxonomy["categories"].as_array().expect("categories array") {
+        let deed_type = cat["deed_type"].as_str().expect("deed_type");
+        let name = cat["builder"].as_str().expect("builder name");
+        let builder = format!("{}Builder", name);
+        let tags: Vec<String> = cat["tags"]
+            .as_array()
+            .map(|a| a.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default();
+        let tech_positive = cat.get("tech_positive").and_then(Value::as_bool).unwrap_or(false);
+        let required: Vec<Field> = cat["required"].as_array().map(|a| a.iter().map(Field::parse).collect()).unwrap_or_default();
+        let optional: Vec<Field> = cat["optional"].as_array().map(|a| a.iter().map(Field::parse).collect()).unwrap_or_default();
+        let follow_ups: Vec<String> = cat["follow_ups"].as_array().map(|a| a.iter().map(follow_up_spec).collect()).unwrap_or_default();
+        let validations = validation_spec(cat.get("required_validations"));
+        let cooldown = cooldown_spec(cat.get("cooldown"));
+
+        // Type parameter 0 is actor_id; 1..=n are the required fields.
+        let params: Vec<String> = (0..=required.len()).map(|i| format!("F{}", i)).collect();
+        let generics = params.join(", ");
+        let missing = vec!["Missing"; params.len()].join(", ");
+        let all_set: Vec<String> = std::iter::once("Set<String>".to_string())
+            .chain(required.iter().map(|f| format!("Set<{}>", f.rust_type())))
+            .collect();
+
+        writeln!(out, "/// Typed builder entry point for `{}` deeds.", deed_type).unwrap();
+        writeln!(out, "pub struct {};\n", name).unwrap();
+        writeln!(out, "impl {} {{", name).unwrap();
+        writeln!(out, "    pub const DEED_TYPE: &'static str = {:?};\n", deed_type).unwrap();
+        writeln!(out, "    pub fn builder() -> {}<{}> {{", builder, missing).unwrap();
+        writeln!(out, "        {} {{", builder).unwrap();
+        writeln!(out, "            actor_id: Missing,").unwrap();
+        for f in &required {
+            writeln!(out, "            {}: Missing,", f.name).unwrap();
+        }
+        for f in &optional {
+            writeln!(out, "            {}: None,", f.name).unwrap();
+        }
+        writeln!(out, "            target_ids: Vec::new(),\n            extra_tags: Vec::new(),\n        }}\n    }}\n}}\n").unwrap();
+
+        writeln!(out, "pub struct {}<{}> {{", builder, generics).unwrap();
+        writeln!(out, "    actor_id: F0,").unwrap();
+        for (i, f) in required.iter().enumerate() {
+            writeln!(out, "    {}: F{},", f.name, i + 1).unwrap();
+        }
+        for f in &optional {
+            writeln!(out, "    {}: Option<{}>,", f.name, f.rust_type()).unwrap();
+        }
+        writeln!(out, "    target_ids: Vec<String>,\n    extra_tags: Vec<String>,\n}}\n").unwrap();
+
+        writeln!(out, "impl<{}> {}<{}> {{", generics, builder, generics).unwrap();
+        let setter = |out: &mut String, idx: usize, fname: &str, arg: &str, ty: &str, value: &str| {
+            let mut ret = params.clone();
+            ret[idx] = format!("Set<{}>", ty);
+            writeln!(out, "    pub fn {}(self, value: {}) -> {}<{}> {{", fname, arg, builder, ret.join(", ")).unwrap();
+            writeln!(out, "        {} {{", builder).unwrap();
+            writeln!(out, "            {}: Set({}),", fname, value).unwrap();
+            if fname != "actor_id" {
+                writeln!(out, "            actor_id: self.actor_id,").unwrap();
+            }
+            for f in required.iter().filter(|f| f.name != fname) {
+                writeln!(out, "            {}: self.{},", f.name, f.name).unwrap();
+            }
+            for f in &optional {
+                writeln!(out, "            {}: self.{},", f.name, f.name).unwrap();
+            }
+            writeln!(out, "            target_ids: self.target_ids,\n            extra_tags: self.extra_tags,\n        }}\n    }}\n").unwrap();
+        };
+        setter(&mut out, 0, "actor_id", "impl Into<String>", "String", "value.into()");
+        for (i, f) in required.iter().enumerate() {
+            setter(&mut out, i + 1, &f.name, f.setter_arg(), f.rust_type(), f.setter_value());
+        }
+        for f in &optional {
+            writeln!(out, "    pub fn {}(mut self, value: {}) -> Self {{", f.name, f.setter_arg()).unwrap();
+            writeln!(out, "        self.{} = Some({});\n        self\n    }}\n", f.name, f.setter_value()).unwrap();
+        }
+        writeln!(out, "    pub fn target(mut self, id: impl Into<String>) -> Self {{\n        self.target_ids.push(id.into());\n        self\n    }}\n").unwrap();
+        writeln!(out, "    pub fn tag(mut self, tag: impl Into<String>) -> Self {{\n        self.extra_tags.push(tag.into());\n        self\n    }}\n}}\n").unwrap();
+
+        writeln!(out, "impl {}<{}> {{", builder, all_set.join(", ")).unwrap();
+        writeln!(out, "    /// Range-check the values and produce a hashed DeedEvent linked to `prev_hash`.").unwrap();
+        writeln!(out, "    pub fn build(self, prev_hash: impl Into<String>) -> Result<DeedEvent, DeedBuildError> {{").unwrap();
+        writeln!(out, "        check_string(\"actor_id\", &self.actor_id.0)?;").unwrap();
+        for f in &required {
+            writeln!(out, "        {}", f.check(&format!("self.{}.0", f.name))).unwrap();
+        }
+        for f in &optional {
+            writeln!(out, "        if let Some(value) = &self.{} {{", f.name).unwrap();
+            let expr = if f.kind == "string" { "value".to_string() } else { "*value".to_string() };
+            writeln!(out, "            {}", f.check(&expr).replace("&value", "value")).unwrap();
+            writeln!(out, "        }}").unwrap();
+        }
+        writeln!(out, "        let mut context = serde_json::Map::new();").unwrap();
+        for f in &required {
+            writeln!(out, "        context.insert({:?}.to_string(), serde_json::json!(self.{}.0));", f.name, f.name).unwrap();
+        }
+        for f in &optional {
+            writeln!(out, "        if let Some(value) = self.{} {{", f.name).unwrap();
+            writeln!(out, "            context.insert({:?}.to_string(), serde_json::json!(value));", f.name).unwrap();
+            writeln!(out, "        }}").unwrap();
+        }
+        let tag_list: Vec<String> = tags.iter().map(|t| format!("{:?}.to_string()", t)).collect();
+        writeln!(out, "        let mut tags = vec![{}];", tag_list.join(", ")).unwrap();
+        writeln!(out, "        tags.extend(self.extra_tags);").unwrap();
+        writeln!(out, "        Ok(DeedEvent::new(").unwrap();
+        writeln!(out, "            prev_hash.into(),\n            self.actor_id.0,\n            self.target_ids,").unwrap();
+        writeln!(out, "            {}::DEED_TYPE.to_string(),\n            tags,", name).unwrap();
+        writeln!(out, "            serde_json::Value::Object(context),\n            Vec::new(),\n            false,\n        ))\n    }}\n}}\n").unwrap();
+
+        let req_specs: Vec<String> = required.iter().map(Field::spec).collect();
+        let opt_specs: Vec<String> = optional.iter().map(Field::spec).collect();
+        schemas.push(format!(
+            "    CategorySchema {{\n        deed_type: {:?},\n        tags: &[{}],\n        tech_positive: {},\n        required: &[{}],\n        optional: &[{}],\n        follow_ups: &[{}],\n        required_validations: {},\n        cooldown: {},\n    }},",
+            deed_type,
+            tags.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>().join(", "),
+            tech_positive,
+            req_specs.join(", "),
+            opt_specs.join(", "),
+            follow_ups.join(", "),
+            validations,
+            cooldown,
+        ));
+    }
+
+    writeln!(out, "/// Every category in the taxonomy, in file order.").unwrap();
+    writeln!(out, "pub const CATEGORIES: &[CategorySchema] = &[\n{}\n];", schemas.join("\n")).unwrap();
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", TAXONOMY);
+    let raw = fs::read_to_string(TAXONOMY).expect("read taxonomy/deeds.json");
+    let taxonomy: Value = serde_json::from_str(&raw).expect("parse taxonomy/deeds.json");
+    let code = generate(&taxonomy);
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("deed_builders.rs");
+    fs::write(out, code).expect("write generated builders");
+}
@@ -0,0 +1,37 @@
+//! Minimal edge logger: appends a few deeds and verifies the hash chain.
+//!
+//! Builds with only the `core` feature:
+//! `cargo run --example minimal_logger --no-default-features --features core`
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::{EcologicalSustainabilityDeed, HomelessnessReliefDeed};
+use church_of_fear::ledger::deed_event::validate_chain;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+
+fn main() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+
+    let relief = HomelessnessReliefDeed::builder()
+        .actor_id("edge:shelter-01")
+        .location("Phoenix, AZ")
+        .hours(3.0)
+        .meals_served(24)
+        .build(ledger.last_hash())
+        .expect("valid relief deed");
+    ledger.append(relief).expect("append relief deed");
+
+    let eco = EcologicalSustainabilityDeed::builder()
+        .actor_id("edge:shelter-01")
+        .location("Phoenix, AZ")
+        .co2_kg(4.2)
+        .evidence_uri("ipfs://edge-logger/eco-1")
+        .build(ledger.last_hash())
+        .expect("valid eco deed");
+    ledger.append(eco).expect("append eco deed");
+
+    for deed in ledger.deeds() {
+        println!("{} {} {}", deed.self_hash, deed.deed_type, deed.actor_id);
+    }
+    validate_chain(ledger.deeds()).expect("hash chain broken");
+    println!("chain verified: {} deeds, tip {}", ledger.deeds().len(), ledger.last_hash());
+}
@@ -0,0 +1,475 @@
+//! Minting-rate anomaly detection.
+//!
+//! Caps stop a single oversized mint, not a compromised client key minting
+//! just under the cap for weeks. `screen_mint` credits a reward the way
+//! `TokenLedger::reward_for` does, but first scores it against
+//! exponentially weighted baselines kept per actor and across the ledger:
+//!
+//! - `rate_z`: how far the actor's smoothed log minting rate (amount per
+//!   hour since their previous mint) sits above the global one, in global
+//!   standard deviations;
+//! - `divergence`: KL divergence of the actor's deed-type mix from the
+//!   global mix;
+//! - `regularity`: one minus the coefficient of variation of the actor's
+//!   inter-mint intervals, so metronomic minting (automation) scores near 1.
+//!
+//! Nothing is scored until both the actor and the ledger have
+//! `min_observations` mints behind them. A mint scoring at or above
+//! `score_threshold` is not blocked: its `mint_screened` deed carries the
+//! `anomaly_suspect` ethics flag, an alert goes to the notifier and, with
+//! `hold_suspects`, the reward is issued into the obligations escrow instead
+//! of to the actor until a reviewer clears (pays out) or confirms (retires)
+//! it with `review_hold`.
+//!
+//! Every screened mint is a ledger-written `mint_screened` deed recording
+//! what was observed and how it scored, and every `snapshot_every` mints an
+//! `anomaly_baseline` deed stores the baselines. `AnomalyState::from_ledger`
+//! loads the latest snapshot and folds the mints after it, so a replayed
+//! ledger scores the next mint exactly as the original would have.
+
+use std::collections::BTreeMap;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::utils::http::post_webhook;
+
+pub const MINT_SCREENED: &str = "mint_screened";
+pub const ANOMALY_BASELINE: &str = "anomaly_baseline";
+pub const ANOMALY_REVIEWED: &str = "anomaly_reviewed";
+/// Ethics flag on the `mint_screened` deed of a suspect mint.
+pub const ANOMALY_SUSPECT: &str = "anomaly_suspect";
+/// Webhook kind of an `AnomalyAlert`.
+pub const ANOMALY_ALERT: &str = "anomaly_alert";
+/// Deed type recorded for mints credited without a source deed.
+pub const DIRECT_MINT: &str = "direct";
+
+/// Log-rate spread below which rates are compared as if it were this wide.
+const RATE_SD_FLOOR: f64 = 0.1;
+/// Probability given to deed types the global mix has never seen.
+const MIX_FLOOR: f64 = 1e-3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyPolicy {
+    /// Score at or above which a mint is suspect.
+    pub score_threshold: f64,
+    /// Mints an actor, and the ledger as a whole, must have before scoring.
+    pub min_observations: u64,
+    /// Weight of each new mint in the exponentially weighted baselines.
+    pub smoothing: f64,
+    pub divergence_weight: f64,
+    pub regularity_weight: f64,
+    /// Escrow suspect rewards until a reviewer clears or confirms them.
+    pub hold_suspects: bool,
+    /// Screened mints between `anomaly_baseline` snapshots.
+    pub snapshot_every: u64,
+    /// Roles that may review a held mint.
+    pub reviewer_roles: Vec<String>,
+    /// Operator webhook (`http://host:port/path`) for anomaly alerts.
+    pub alert_webhook: Option<String>,
+}
+
+impl Default for AnomalyPolicy {
+    fn default() -> Self {
+        Self {
+            score_threshold: 3.0,
+            min_observations: 10,
+            smoothing: 0.1,
+            divergence_weight: 1.0,
+            regularity_weight: 2.0,
+            hold_suspects: false,
+            snapshot_every: 50,
+            reviewer_roles: vec!["Auditor".to_string(), "Regulator".to_string()],
+            alert_webhook: None,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AnomalyError {
+    #[error("role {0} may not review anomaly holds")]
+    RoleNotAllowed(String),
+    #[error("no anomaly hold on screening {0}")]
+    UnknownHold(String),
+    #[error("anomaly hold {0} was already reviewed")]
+    AlreadyReviewed(String),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Exponentially weighted mean and variance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ewm {
+    pub mean: f64,
+    pub var: f64,
+    pub samples: u64,
+}
+
+impl Ewm {
+    fn push(&mut self, x: f64, alpha: f64) {
+        if self.samples == 0 {
+            self.mean = x;
+        } else {
+            let d = x - self.mean;
+            self.mean += alpha * d;
+            self.var = (1.0 - alpha) * (self.var + alpha * d * d);
+        }
+        self.samples += 1;
+    }
+}
+
+/// Minting behaviour of one actor, or of the whole ledger.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub observations: u64,
+    /// ln(amount per hour since the actor's previous mint).
+    pub log_rate: Ewm,
+    /// Seconds between the actor's mints; unused on the global baseline.
+    pub interval_secs: Ewm,
+    /// Share of mints by source deed type.
+    pub type_mix: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_at: Option<i64>,
+}
+
+impl Baseline {
+    fn observe_mix(&mut self, deed_type: &str, alpha: f64) {
+        if self.observations == 0 {
+            self.type_mix.clear();
+            self.type_mix.insert(deed_type.to_string(), 1.0);
+        } else {
+            self.type_mix.values_mut().for_each(|share| *share *= 1.0 - alpha);
+            *self.type_mix.entry(deed_type.to_string()).or_insert(0.0) += alpha;
+        }
+        self.observations += 1;
+    }
+
+    /// Fold one of this actor's mints; returns its log rate once there is
+    /// a previous mint to measure from.
+    fn observe(&mut self, deed_type: &str, amount: u64, at: i64, alpha: f64) -> Option<f64> {
+        let log_rate = self.last_at.map(|last| {
+            let interval = (at - last).max(1);
+            self.interval_secs.push(interval as f64, alpha);
+            (amount.max(1) as f64 * 3600.0 / interval.max(60) as f64).ln()
+        });
+        if let Some(r) = log_rate {
+            self.log_rate.push(r, alpha);
+        }
+        self.observe_mix(deed_type, alpha);
+        self.last_at = Some(at);
+        log_rate
+    }
+}
+
+/// How one mint scored; see the module docs for the components.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyScore {
+    pub rate_z: f64,
+    pub divergence: f64,
+    pub regularity: f64,
+    /// `max(rate_z, 0) + divergence_weight·divergence + regularity_weight·regularity`.
+    pub score: f64,
+}
+
+/// Global and per-actor baselines.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyState {
+    pub global: Baseline,
+    pub actors: BTreeMap<String, Baseline>,
+    /// Screened mints folded in since the last snapshot.
+    #[serde(skip)]
+    pub since_snapshot: u64,
+}
+
+impl AnomalyState {
+    /// The latest `anomaly_baseline` snapshot plus every mint screened after it.
+    pub fn from_ledger(ledger: &TokenLedger) -> Self {
+        let deeds = ledger.deeds();
+        let start = deeds.iter().rposition(|d| d.deed_type == ANOMALY_BASELINE);
+        let mut state = start
+            .and_then(|pos| serde_json::from_value::<AnomalyState>(deeds[pos].context_json["state"].clone()).ok())
+            .unwrap_or_default();
+        let alpha = ledger.config().anomaly.smoothing;
+        for d in &deeds[start.map_or(0, |pos| pos + 1)..] {
+            if let Some(m) = ScreenedMint::from_deed(d) {
+                state.observe(&m.actor_id, &m.deed_type, m.amount, m.at, alpha);
+            }
+        }
+        state
+    }
+
+    /// What a mint of `amount` on a `deed_type` deed by `actor_id` at `at`
+    /// would score, or `None` while either baseline is still cold.
+    pub fn score(&self, actor_id: &str, deed_type: &str, amount: u64, at: i64, policy: &AnomalyPolicy) -> Option<AnomalyScore> {
+        let mut actor = self.actors.get(actor_id).cloned().unwrap_or_default();
+        actor.observe(deed_type, amount, at, policy.smoothing);
+        if actor.observations < policy.min_observations
+            || self.global.observations < policy.min_observations
+            || actor.log_rate.samples == 0
+        {
+            return None;
+        }
+        let rate_z = (actor.log_rate.mean - self.global.log_rate.mean) / self.global.log_rate.var.sqrt().max(RATE_SD_FLOOR);
+        let divergence = actor
+            .type_mix
+            .iter()
+            .filter(|(_, &p)| p > 0.0)
+            .map(|(t, &p)| p * (p / self.global.type_mix.get(t).copied().unwrap_or(0.0).max(MIX_FLOOR)).ln())
+            .sum::<f64>()
+            .max(0.0);
+        let regularity = if actor.interval_secs.samples >= 2 && actor.interval_secs.mean > 0.0 {
+            (1.0 - actor.interval_secs.var.sqrt() / actor.interval_secs.mean).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let score = rate_z.max(0.0) + policy.divergence_weight * divergence + policy.regularity_weight * regularity;
+        Some(AnomalyScore { rate_z, divergence, regularity, score })
+    }
+
+    fn observe(&mut self, actor_id: &str, deed_type: &str, amount: u64, at: i64, alpha: f64) {
+        let log_rate = self.actors.entry(actor_id.to_string()).or_default().observe(deed_type, amount, at, alpha);
+        if let Some(r) = log_rate {
+            self.global.log_rate.push(r, alpha);
+        }
+        self.global.observe_mix(deed_type, alpha);
+        self.since_snapshot += 1;
+    }
+}
+
+/// The observation a `mint_screened` deed records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenedMint {
+    pub screening_event_id: String,
+    pub actor_id: String,
+    pub source_event_id: Option<String>,
+    pub deed_type: String,
+    pub token: Token,
+    pub amount: u64,
+    pub at: i64,
+    pub score: Option<AnomalyScore>,
+    pub flagged: bool,
+    pub held: bool,
+}
+
+impl ScreenedMint {
+    fn from_deed(d: &DeedEvent) -> Option<Self> {
+        if d.deed_type != MINT_SCREENED {
+            return None;
+        }
+        let mut context = d.context_json.clone();
+        context["screening_event_id"] = json!(d.event_id);
+        serde_json::from_value(context).ok()
+    }
+}
+
+/// Every screened mint, oldest first.
+pub fn screened_mints(ledger: &TokenLedger) -> Vec<ScreenedMint> {
+    ledger.deeds().iter().filter_map(ScreenedMint::from_deed).collect()
+}
+
+/// Score every screened mint again from a fold of the whole chain,
+/// ignoring snapshots; a consistent ledger reproduces the recorded scores.
+pub fn rescore(ledger: &TokenLedger) -> Vec<(String, Option<AnomalyScore>)> {
+    let policy = &ledger.config().anomaly;
+    let mut state = AnomalyState::default();
+    let mut out = Vec::new();
+    for m in screened_mints(ledger) {
+        out.push((m.screening_event_id, state.score(&m.actor_id, &m.deed_type, m.amount, m.at, policy)));
+        state.observe(&m.actor_id, &m.deed_type, m.amount, m.at, policy.smoothing);
+    }
+    out
+}
+
+/// Sent when a mint is flagged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyAlert {
+    pub screening_event_id: String,
+    pub actor_id: String,
+    pub source_event_id: Option<String>,
+    pub token: Token,
+    pub amount: u64,
+    pub score: AnomalyScore,
+    pub held: bool,
+}
+
+/// Where anomaly alerts are pushed.
+pub trait AnomalyNotifier: Send {
+    fn notify(&self, alert: &AnomalyAlert) -> Result<(), String>;
+}
+
+/// POSTs each alert as JSON to the policy's webhook.
+pub struct WebhookAnomalyNotifier {
+    pub url: String,
+}
+
+impl AnomalyNotifier for WebhookAnomalyNotifier {
+    fn notify(&self, alert: &AnomalyAlert) -> Result<(), String> {
+        post_webhook(&self.url, ANOMALY_ALERT, alert)
+    }
+}
+
+/// Outcome of `screen_mint`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintScreening {
+    pub screening_event_id: String,
+    /// Credited to the actor now, after the pool tithe.
+    pub credited: u64,
+    /// Issued into escrow pending review.
+    pub held: u64,
+    pub score: Option<AnomalyScore>,
+    pub flagged: bool,
+}
+
+/// Score a reward of `amount` `token` to `actor_id` for deed `source` at
+/// `now`, then credit it (or, for a suspect mint under `hold_suspects`,
+/// escrow it) and record the screening. Alert delivery failures are
+/// logged and never undo the mint.
+pub fn screen_mint(
+    ledger: &mut TokenLedger,
+    actor_id: &str,
+    token: Token,
+    amount: u64,
+    source: Option<&str>,
+    now: i64,
+    notifier: Option<&dyn AnomalyNotifier>,
+) -> Result<MintScreening, AnomalyError> {
+    if !matches!(token, Token::Church | Token::Pwr) {
+        return Err(TokenLedgerError::NotARewardToken(token).into());
+    }
+    let policy = ledger.config().anomaly.clone();
+    let mut state = AnomalyState::from_ledger(ledger);
+    let deed_type = source.and_then(|id| ledger.deed(id)).map_or_else(|| DIRECT_MINT.to_string(), |d| d.deed_type.clone());
+    let score = state.score(actor_id, &deed_type, amount, now, &policy);
+    let flagged = score.is_some_and(|s| s.score >= policy.score_threshold);
+    let held = flagged && policy.hold_suspects;
+
+    ledger.open_account(actor_id, actor_id);
+    let credited = if held { 0 } else { ledger.reward_for(actor_id, token, amount, source)? };
+    let context = json!({
+        "actor_id": actor_id,
+        "source_event_id": source,
+        "deed_type": deed_type,
+        "token": token,
+        "amount": amount,
+        "at": now,
+        "score": score,
+        "flagged": flagged,
+        "held": held,
+    });
+    let screening_event_id = ledger.log_mint_screened(actor_id, context, flagged, held.then_some((token, amount)))?;
+
+    state.observe(actor_id, &deed_type, amount, now, policy.smoothing);
+    if policy.snapshot_every > 0 && state.since_snapshot >= policy.snapshot_every {
+        let state = serde_json::to_value(&state).expect("baselines serialize");
+        ledger.log_anomaly_baseline(json!({ "state": state, "at": now }))?;
+    }
+
+    if let (true, Some(score), Some(notifier)) = (flagged, score, notifier) {
+        let alert = AnomalyAlert {
+            screening_event_id: screening_event_id.clone(),
+            actor_id: actor_id.to_string(),
+            source_event_id: source.map(str::to_string),
+            token,
+            amount,
+            score,
+            held,
+        };
+        if let Err(e) = notifier.notify(&alert) {
+            warn!("anomaly alert for {} not delivered: {}", screening_event_id, e);
+        }
+    }
+    Ok(MintScreening { screening_event_id, credited, held: if held { amount } else { 0 }, score, flagged })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldDecision {
+    /// Not abuse: pay the escrowed reward to the actor.
+    Clear,
+    /// Abuse: retire the escrowed reward.
+    Confirm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum HoldStatus {
+    Pending,
+    Reviewed { decision: HoldDecision, review_event_id: String, reviewer: String, reviewed_at: i64 },
+}
+
+/// A suspect mint held in escrow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnomalyHold {
+    pub screening_event_id: String,
+    pub actor_id: String,
+    pub token: Token,
+    pub amount: u64,
+    #[serde(flatten)]
+    pub status: HoldStatus,
+}
+
+/// Every hold, in the order it was placed.
+pub fn holds(ledger: &TokenLedger) -> Vec<AnomalyHold> {
+    let mut out: Vec<AnomalyHold> = Vec::new();
+    for d in ledger.deeds() {
+        if let Some(m) = ScreenedMint::from_deed(d).filter(|m| m.held) {
+            out.push(AnomalyHold {
+                screening_event_id: m.screening_event_id,
+                actor_id: m.actor_id,
+                token: m.token,
+                amount: m.amount,
+                status: HoldStatus::Pending,
+            });
+        } else if d.deed_type == ANOMALY_REVIEWED {
+            let target = d.target_ids.first().map(String::as_str);
+            if let Some(hold) = out.iter_mut().find(|h| Some(h.screening_event_id.as_str()) == target) {
+                hold.status = HoldStatus::Reviewed {
+                    decision: serde_json::from_value(d.context_json["decision"].clone()).unwrap_or(HoldDecision::Confirm),
+                    review_event_id: d.event_id.clone(),
+                    reviewer: d.context_json["reviewer"].as_str().unwrap_or_default().to_string(),
+                    reviewed_at: d.context_json["reviewed_at"].as_i64().unwrap_or(d.timestamp),
+                };
+            }
+        }
+    }
+    out
+}
+
+/// Clear or confirm the hold placed by screening `screening_event_id`.
+/// Returns the amount paid to the actor (zero on confirm).
+pub fn review_hold(
+    ledger: &mut TokenLedger,
+    screening_event_id: &str,
+    decision: HoldDecision,
+    reviewer: &str,
+    role: &str,
+    reason: &str,
+    now: i64,
+) -> Result<u64, AnomalyError> {
+    if !ledger.config().anomaly.reviewer_roles.iter().any(|r| r == role) {
+        return Err(AnomalyError::RoleNotAllowed(role.to_string()));
+    }
+    let hold = holds(ledger)
+        .into_iter()
+        .find(|h| h.screening_event_id == screening_event_id)
+        .ok_or_else(|| AnomalyError::UnknownHold(screening_event_id.to_string()))?;
+    if hold.status != HoldStatus::Pending {
+        return Err(AnomalyError::AlreadyReviewed(screening_event_id.to_string()));
+    }
+    let payee = (decision == HoldDecision::Clear).then_some(hold.actor_id.as_str());
+    let context = json!({
+        "decision": decision,
+        "reviewer": reviewer,
+        "role": role,
+        "reason": reason,
+        "actor_id": hold.actor_id,
+        "reviewed_at": now,
+    });
+    Ok(ledger.log_anomaly_review(screening_event_id, payee, hold.token, hold.amount, context)?)
+}
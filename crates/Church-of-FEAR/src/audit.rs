@@ -0,0 +1,499 @@
+//! Idle-time integrity self-audit.
+//!
+//! Verifying at startup and trusting memory afterwards lets a bit-flip on
+//! disk or a buggy writer go unnoticed until an auditor complains.
+//! `SelfAuditor` walks the deed log in bounded slices during idle ticks
+//! and caches a checkpoint per verified slice (range → rolling hash and
+//! tip), so after appends only the new deeds are read. Once caught up it
+//! re-verifies one cached slice per tick against its checkpoint; a full lap
+//! sets `last_full_verification`. The state lives in a sidecar file next
+//! to the log, so a restarted node resumes where it stopped.
+//!
+//! A mismatch freezes every mint-bearing ledger operation, logs an
+//! `integrity_violation` deed naming the slice, and pushes a critical
+//! alert. Slices the node's own eco budget cannot afford are skipped.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::ledger::deed_event::{link_fault, DeedEvent, LinkFault};
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::utils::http::post_webhook;
+
+pub const INTEGRITY_VIOLATION: &str = "integrity_violation";
+pub const INTEGRITY_CLEARED: &str = "integrity_cleared";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditPolicy {
+    /// Deeds read per slice.
+    pub slice_len: usize,
+    /// Estimated energy to read and hash one deed.
+    pub joules_per_deed: f64,
+    /// Share of the self-budget a slice may not dip into.
+    pub reserve_fraction: f64,
+    /// Operator webhook (`http://host:port/path`) for integrity violations.
+    pub alert_webhook: Option<String>,
+}
+
+impl Default for AuditPolicy {
+    fn default() -> Self {
+        Self { slice_len: 256, joules_per_deed: 0.05, reserve_fraction: 0.2, alert_webhook: None }
+    }
+}
+
+/// The node's own energy envelope for background work, per period.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SelfBudget {
+    pub budget_joules: f64,
+    pub period_secs: i64,
+    /// Unix seconds.
+    pub period_start: i64,
+    pub spent_joules: f64,
+}
+
+impl SelfBudget {
+    pub fn new(budget_joules: f64, period_secs: i64, now: i64) -> Self {
+        Self { budget_joules, period_secs: period_secs.max(1), period_start: now, spent_joules: 0.0 }
+    }
+
+    pub fn remaining(&self) -> f64 {
+        (self.budget_joules - self.spent_joules).max(0.0)
+    }
+
+    pub fn spend(&mut self, joules: f64) {
+        self.spent_joules += joules;
+    }
+
+    /// Start a fresh period once the current one has elapsed.
+    pub fn roll(&mut self, now: i64) {
+        if now - self.period_start >= self.period_secs {
+            self.period_start = now;
+            self.spent_joules = 0.0;
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("audit state: {0}")]
+    State(#[from] serde_json::Error),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Why a read of the audited log failed.
+#[derive(Error, Debug)]
+pub enum SourceError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+    /// The bytes at `position` are not a deed; treated as a violation.
+    #[error("deed {position} is unreadable: {reason}")]
+    Unreadable { position: usize, reason: String },
+}
+
+/// A deed log the auditor can read back by position.
+pub trait DeedSource {
+    fn deed_count(&self) -> Result<usize, SourceError>;
+    /// Deeds `[start, end)`, fewer if the log is shorter.
+    fn read_range(&self, start: usize, end: usize) -> Result<Vec<DeedEvent>, SourceError>;
+}
+
+impl DeedSource for TokenLedger {
+    fn deed_count(&self) -> Result<usize, SourceError> {
+        Ok(self.deeds().len())
+    }
+
+    fn read_range(&self, start: usize, end: usize) -> Result<Vec<DeedEvent>, SourceError> {
+        let deeds = self.deeds();
+        Ok(deeds[start.min(deeds.len())..end.min(deeds.len())].to_vec())
+    }
+}
+
+/// A JSONL deed log on disk (one `DeedEvent` per line), read fresh on
+/// every call so corruption after startup is seen.
+pub struct JsonlDeeds {
+    pub path: PathBuf,
+}
+
+impl JsonlDeeds {
+    fn lines(&self) -> Result<impl Iterator<Item = std::io::Result<String>>, SourceError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        Ok(reader.lines().filter(|l| l.as_ref().map_or(true, |l| !l.trim().is_empty())))
+    }
+}
+
+impl DeedSource for JsonlDeeds {
+    fn deed_count(&self) -> Result<usize, SourceError> {
+        let mut n = 0;
+        for line in self.lines()? {
+            line?;
+            n += 1;
+        }
+        Ok(n)
+    }
+
+    fn read_range(&self, start: usize, end: usize) -> Result<Vec<DeedEvent>, SourceError> {
+        let mut deeds = Vec::with_capacity(end.saturating_sub(start));
+        for (position, line) in self.lines()?.enumerate().skip(start).take(end.saturating_sub(start)) {
+            let deed = serde_json::from_str(&line?)
+                .map_err(|e| SourceError::Unreadable { position, reason: e.to_string() })?;
+            deeds.push(deed);
+        }
+        Ok(deeds)
+    }
+}
+
+/// `<log path>.audit.json`, next to the `.segments.json` sidecar.
+pub fn sidecar_path(log_path: &Path) -> PathBuf {
+    let mut sidecar = log_path.to_path_buf().into_os_string();
+    sidecar.push(".audit.json");
+    PathBuf::from(sidecar)
+}
+
+/// A verified slice `[start, end)`: the rolling hash over its deeds' hashes
+/// (seeded by the previous checkpoint) and the last deed's hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub start: usize,
+    pub end: usize,
+    pub rolling_hash: String,
+    pub tip_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum AuditFault {
+    Link { fault: LinkFault },
+    Unreadable { reason: String },
+    /// The log holds only `len` deeds where more were verified.
+    Truncated { len: usize },
+    /// Every link checks out but the slice differs from its checkpoint:
+    /// it was rewritten wholesale.
+    Rewritten,
+    /// A cold segment's remote copy failed its spot check (see
+    /// `cold_storage`).
+    ColdSegment { index: usize, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditViolation {
+    /// The slice being verified.
+    pub start: usize,
+    pub end: usize,
+    /// First deed found at fault.
+    pub position: usize,
+    pub event_id: Option<String>,
+    pub fault: AuditFault,
+    pub detected_at: i64,
+    /// The `integrity_violation` deed recording it.
+    pub deed_event_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditTick {
+    /// First verification of new deeds `[start, end)`.
+    Extended { start: usize, end: usize },
+    /// A cached slice re-read and matched its checkpoint.
+    Reverified { start: usize, end: usize },
+    /// The slice would cost more than the budget can spare; nothing read.
+    Skipped { needed_joules: u64, remaining_joules: u64 },
+    /// Nothing to verify yet.
+    Idle,
+    Violation(AuditViolation),
+    /// Stopped at an unacknowledged violation.
+    Halted,
+}
+
+/// Resumable auditor state, persisted in the sidecar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditState {
+    pub checkpoints: Vec<Checkpoint>,
+    /// Next checkpoint to re-verify once caught up.
+    pub sweep: usize,
+    /// Log length seen on the last tick.
+    pub observed_len: usize,
+    pub last_full_verification: Option<i64>,
+    pub last_tick_at: Option<i64>,
+    pub slices_verified: u64,
+    pub slices_skipped: u64,
+    pub violation: Option<AuditViolation>,
+}
+
+impl AuditState {
+    pub fn verified_len(&self) -> usize {
+        self.checkpoints.last().map_or(0, |c| c.end)
+    }
+}
+
+/// Audit progress for operators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditStatus {
+    pub verified_len: usize,
+    pub observed_len: usize,
+    pub checkpoints: usize,
+    pub sweep: usize,
+    pub last_full_verification: Option<i64>,
+    pub last_tick_at: Option<i64>,
+    pub slices_verified: u64,
+    pub slices_skipped: u64,
+    pub violation: Option<AuditViolation>,
+}
+
+impl AuditStatus {
+    /// Prometheus text exposition of the audit gauges.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for (name, kind, value) in [
+            ("cof_audit_verified_deeds", "gauge", self.verified_len as u64),
+            ("cof_audit_observed_deeds", "gauge", self.observed_len as u64),
+            ("cof_audit_last_full_verification_timestamp_seconds", "gauge", self.last_full_verification.unwrap_or(0) as u64),
+            ("cof_audit_slices_verified_total", "counter", self.slices_verified),
+            ("cof_audit_slices_skipped_total", "counter", self.slices_skipped),
+            ("cof_audit_violation", "gauge", self.violation.is_some() as u64),
+        ] {
+            writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+            writeln!(out, "{} {}", name, value).unwrap();
+        }
+        out
+    }
+}
+
+/// Where integrity violations are pushed besides the ledger.
+pub trait IntegrityNotifier: Send {
+    fn notify(&self, violation: &AuditViolation) -> Result<(), String>;
+}
+
+/// POSTs each violation as a critical alert to the policy's webhook.
+pub struct WebhookIntegrityNotifier {
+    pub url: String,
+}
+
+impl IntegrityNotifier for WebhookIntegrityNotifier {
+    fn notify(&self, violation: &AuditViolation) -> Result<(), String> {
+        post_webhook(&self.url, INTEGRITY_VIOLATION, &serde_json::json!({ "severity": "critical", "violation": violation }))
+    }
+}
+
+/// Checkpoint hash chaining: `sha256(rolling ‖ self_hash)`.
+fn roll_hash(rolling: &str, self_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(rolling.as_bytes());
+    hasher.update(self_hash.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub struct SelfAuditor {
+    policy: AuditPolicy,
+    state: AuditState,
+    notifier: Option<Box<dyn IntegrityNotifier>>,
+}
+
+impl SelfAuditor {
+    /// A fresh auditor; with a webhook in the policy the notifier posts to it.
+    pub fn new(policy: AuditPolicy) -> Self {
+        Self::with_state(policy, AuditState::default())
+    }
+
+    pub fn with_state(policy: AuditPolicy, state: AuditState) -> Self {
+        let notifier = policy.alert_webhook.clone().map(|url| Box::new(WebhookIntegrityNotifier { url }) as Box<_>);
+        Self { policy, state, notifier }
+    }
+
+    /// Resume from the sidecar at `path`; a missing file starts fresh.
+    pub fn load(policy: AuditPolicy, path: &Path) -> Result<Self, AuditError> {
+        let state = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AuditState::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self::with_state(policy, state))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AuditError> {
+        std::fs::write(path, serde_json::to_vec_pretty(&self.state)?)?;
+        Ok(())
+    }
+
+    pub fn with_notifier(mut self, notifier: impl IntegrityNotifier + 'static) -> Self {
+        self.notifier = Some(Box::new(notifier));
+        self
+    }
+
+    pub fn state(&self) -> &AuditState {
+        &self.state
+    }
+
+    pub fn status(&self) -> AuditStatus {
+        let s = &self.state;
+        AuditStatus {
+            verified_len: s.verified_len(),
+            observed_len: s.observed_len,
+            checkpoints: s.checkpoints.len(),
+            sweep: s.sweep,
+            last_full_verification: s.last_full_verification,
+            last_tick_at: s.last_tick_at,
+            slices_verified: s.slices_verified,
+            slices_skipped: s.slices_skipped,
+            violation: s.violation.clone(),
+        }
+    }
+
+    /// One idle tick over `source`. A violation freezes mints on `ledger`,
+    /// is logged there and pushed to the notifier.
+    pub fn idle_tick(
+        &mut self,
+        ledger: &mut TokenLedger,
+        source: &dyn DeedSource,
+        budget: &mut SelfBudget,
+        now: i64,
+    ) -> Result<AuditTick, AuditError> {
+        let tick = self.step(source, budget, now)?;
+        self.respond(ledger, tick)
+    }
+
+    /// `idle_tick` over the ledger's own in-memory log.
+    pub fn idle_tick_in_memory(&mut self, ledger: &mut TokenLedger, budget: &mut SelfBudget, now: i64) -> Result<AuditTick, AuditError> {
+        let tick = self.step(&*ledger, budget, now)?;
+        self.respond(ledger, tick)
+    }
+
+    /// Record a violation found outside the auditor's own sweep, as a
+    /// tick's would be.
+    pub fn report(&mut self, ledger: &mut TokenLedger, violation: AuditViolation) -> Result<AuditViolation, AuditError> {
+        match self.respond(ledger, AuditTick::Violation(violation))? {
+            AuditTick::Violation(violation) => Ok(violation),
+            _ => unreachable!("respond keeps the tick's kind"),
+        }
+    }
+
+    /// Forget the checkpoints from the violated slice on, so the repaired
+    /// log is verified afresh from there. The ledger's mint freeze is
+    /// lifted separately (`TokenLedger::lift_mint_freeze`).
+    pub fn acknowledge(&mut self) -> Option<AuditViolation> {
+        let violation = self.state.violation.take()?;
+        self.state.checkpoints.retain(|c| c.end <= violation.start);
+        self.state.sweep = 0;
+        Some(violation)
+    }
+
+    fn respond(&mut self, ledger: &mut TokenLedger, tick: AuditTick) -> Result<AuditTick, AuditError> {
+        let AuditTick::Violation(mut violation) = tick else {
+            return Ok(tick);
+        };
+        let context = serde_json::to_value(&violation).expect("violation serializes");
+        violation.deed_event_id = Some(ledger.record_integrity_violation(context)?.event_id.clone());
+        error!(
+            "Integrity violation at deed {} (slice {}..{}): {:?}; mints frozen",
+            violation.position, violation.start, violation.end, violation.fault
+        );
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify(&violation) {
+                warn!("Integrity alert not delivered: {}", e);
+            }
+        }
+        self.state.violation = Some(violation.clone());
+        Ok(AuditTick::Violation(violation))
+    }
+
+    fn step(&mut self, source: &dyn DeedSource, budget: &mut SelfBudget, now: i64) -> Result<AuditTick, AuditError> {
+        if self.state.violation.is_some() {
+            return Ok(AuditTick::Halted);
+        }
+        self.state.last_tick_at = Some(now);
+        let len = match source.deed_count() {
+            Ok(len) => len,
+            Err(SourceError::Io(e)) => return Err(e.into()),
+            Err(SourceError::Unreadable { position, reason }) => {
+                return Ok(violation(position, position + 1, position, None, AuditFault::Unreadable { reason }, now));
+            }
+        };
+        self.state.observed_len = len;
+        let verified = self.state.verified_len();
+        if len < verified {
+            let fault = AuditFault::Truncated { len };
+            return Ok(violation(len, verified, len, None, fault, now));
+        }
+        // New deeds first; once caught up, re-verify the cached slices in turn.
+        let (start, end, sweep) = if verified < len {
+            (verified, (verified + self.policy.slice_len.max(1)).min(len), None)
+        } else if let Some(cp) = self.state.checkpoints.get(self.state.sweep) {
+            (cp.start, cp.end, Some(self.state.sweep))
+        } else {
+            return Ok(AuditTick::Idle);
+        };
+
+        budget.roll(now);
+        let needed = (end - start) as f64 * self.policy.joules_per_deed;
+        let reserve = budget.budget_joules * self.policy.reserve_fraction;
+        if budget.remaining() - needed < reserve {
+            self.state.slices_skipped += 1;
+            return Ok(AuditTick::Skipped { needed_joules: needed.ceil() as u64, remaining_joules: budget.remaining() as u64 });
+        }
+        budget.spend(needed);
+
+        let seed = match sweep {
+            Some(0) => None,
+            Some(i) => Some(self.state.checkpoints[i - 1].clone()),
+            None => self.state.checkpoints.last().cloned(),
+        };
+        let (mut rolling, mut tip) = seed.map_or_else(|| ("0".repeat(64), "0".repeat(64)), |c| (c.rolling_hash, c.tip_hash));
+        let deeds = match source.read_range(start, end) {
+            Ok(deeds) => deeds,
+            Err(SourceError::Io(e)) => return Err(e.into()),
+            Err(SourceError::Unreadable { position, reason }) => {
+                return Ok(violation(start, end, position, None, AuditFault::Unreadable { reason }, now));
+            }
+        };
+        for (offset, deed) in deeds.iter().enumerate() {
+            if let Some(fault) = link_fault(deed, &tip) {
+                let event_id = Some(deed.event_id.clone());
+                return Ok(violation(start, end, start + offset, event_id, AuditFault::Link { fault }, now));
+            }
+            rolling = roll_hash(&rolling, &deed.self_hash);
+            tip.clone_from(&deed.self_hash);
+        }
+        if deeds.len() < end - start {
+            let len = start + deeds.len();
+            return Ok(violation(start, end, len, None, AuditFault::Truncated { len }, now));
+        }
+
+        self.state.slices_verified += 1;
+        let checkpoint = Checkpoint { start, end, rolling_hash: rolling, tip_hash: tip };
+        match sweep {
+            Some(i) => {
+                if self.state.checkpoints[i] != checkpoint {
+                    return Ok(violation(start, end, start, None, AuditFault::Rewritten, now));
+                }
+                self.state.sweep = i + 1;
+                if self.state.sweep == self.state.checkpoints.len() {
+                    self.state.sweep = 0;
+                    self.state.last_full_verification = Some(now);
+                }
+                Ok(AuditTick::Reverified { start, end })
+            }
+            None => {
+                self.state.checkpoints.push(checkpoint);
+                // The first pass from genesis is itself a full verification.
+                if end == len && self.state.last_full_verification.is_none() {
+                    self.state.last_full_verification = Some(now);
+                }
+                Ok(AuditTick::Extended { start, end })
+            }
+        }
+    }
+}
+
+fn violation(start: usize, end: usize, position: usize, event_id: Option<String>, fault: AuditFault, detected_at: i64) -> AuditTick {
+    AuditTick::Violation(AuditViolation { start, end, position, event_id, fault, detected_at, deed_event_id: None })
+}
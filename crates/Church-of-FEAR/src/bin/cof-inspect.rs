@@ -0,0 +1,42 @@
+//! Read-only ledger inspector: `cof-inspect <ledger.jsonl>`.
+//!
+//! Type to filter (`actor:`, `type:`, `tag:` or bare words), arrows and
+//! PgUp/PgDn to move, Tab to switch between deed detail and account,
+//! Ctrl-R to re-verify the chain, Esc to clear the filter or quit.
+
+use std::io;
+
+use church_of_fear::inspect::{run, App, LedgerIndex};
+use ratatui::crossterm::event;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: cof-inspect <ledger.jsonl>");
+        std::process::exit(2);
+    };
+    let index = match LedgerIndex::open(&path) {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("cannot open {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = session(App::new(index)) {
+        eprintln!("cof-inspect: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn session(mut app: App) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let result = Terminal::new(CrosstermBackend::new(io::stdout()))
+        .and_then(|mut terminal| run(&mut terminal, &mut app, std::iter::repeat_with(event::read)));
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    result
+}
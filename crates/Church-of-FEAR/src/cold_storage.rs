@@ -0,0 +1,791 @@
+//! Cold storage for old sealed segments.
+//!
+//! `ColdStorage` keeps every sealed segment as a JSONL file under
+//! `<dir>/segments/` (named like the segments of an audit bundle,
+//! `000001.jsonl` for segment 0) and a manifest, `<dir>/tiers.json`,
+//! recording where each one lives. A segment whose last deed is older than
+//! `cold_after_secs` moves to the cold tier, a `StorageTier` holding
+//! objects by key: another directory (`LocalDirTier`) or an S3-compatible
+//! bucket (`S3Tier`).
+//!
+//! A migration moves a segment through `Local`, `Uploading`, `Confirmed`
+//! and `Cold`, saving the manifest at every step. Before the upload the
+//! local file is checked against the SHA-256 recorded when it was written
+//! and its Merkle root against the ledger's. After it the object is read
+//! back; only once its hash matches is the segment `Confirmed`, a
+//! `segment_migrated` deed logged with the object's key and hash, and the
+//! local file deleted. A node stopped mid-way picks up on the next run from
+//! the step the manifest records: an `Uploading` segment is uploaded
+//! again, a `Confirmed` one has its deed logged (once) and its local copy
+//! deleted. Nothing is ever deleted before the remote hash is confirmed.
+//!
+//! Reads go through `read_segment` and `stream_events` whatever the tier.
+//! A cold segment is fetched on demand into `<dir>/cache/`, which holds at
+//! most `cache_segments` of them (the least recently read goes first), and
+//! its hash is checked on every read, cached or not, before any deed is
+//! used. `ColdStorage` is also a `DeedSource`, so the self-audit and a
+//! time-travel replay (`TokenLedger::replay` over `stream_events`) read the
+//! sealed log through it. `spot_check` fetches one cold segment straight
+//! from its tier per call, picked by a logged draw from `vrf` so the node
+//! cannot steer checks away from a segment it knows is bad; `tick` runs it
+//! on its own schedule and
+//! hands a mismatch to the self-auditor, which freezes mints as for any
+//! other integrity violation.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use deed_core::merkle_root;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::audit::{AuditFault, AuditViolation, DeedSource, SelfAuditor, SourceError};
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{segment_root, TokenLedger, TokenLedgerError};
+use crate::vrf::{self, VrfError};
+
+pub const SEGMENT_MIGRATED: &str = "segment_migrated";
+/// The `vrf` purpose spot-check draws are logged under.
+pub const SPOT_CHECK_PURPOSE: &str = "cold_spot_check";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColdStoragePolicy {
+    /// Directory for segment files, the manifest and the read cache;
+    /// segments stay in memory only when unset.
+    pub dir: Option<String>,
+    /// Where old segments go; they all stay local when unset.
+    pub tier: Option<ColdTierConfig>,
+    /// Age of a segment's last deed at which it moves to the cold tier.
+    pub cold_after_secs: i64,
+    /// Cold segments kept in the read cache.
+    pub cache_segments: usize,
+    pub migrate_every_secs: u64,
+    pub spot_check_every_secs: u64,
+}
+
+impl Default for ColdStoragePolicy {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            tier: None,
+            cold_after_secs: 90 * 86_400,
+            cache_segments: 8,
+            migrate_every_secs: 6 * 3600,
+            spot_check_every_secs: 12 * 3600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ColdTierConfig {
+    LocalDir { path: String },
+    /// Path-style requests over plain HTTP, signed with AWS Signature V4.
+    S3 {
+        /// `http://host:port`.
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+        /// Prepended to every object key.
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+impl ColdTierConfig {
+    pub fn build(&self) -> Box<dyn StorageTier> {
+        match self {
+            ColdTierConfig::LocalDir { path } => Box::new(LocalDirTier::new(path)),
+            ColdTierConfig::S3 { endpoint, bucket, region, access_key, secret_key, prefix } => Box::new(S3Tier {
+                endpoint: endpoint.clone(),
+                bucket: bucket.clone(),
+                region: region.clone(),
+                access_key: access_key.clone(),
+                secret_key: secret_key.clone(),
+                prefix: prefix.clone(),
+            }),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TierError {
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+    #[error("manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    /// The tier answered, but not with success.
+    #[error("{tier}: {message}")]
+    Remote { tier: String, message: String },
+    #[error("no segment {0}")]
+    UnknownSegment(usize),
+    /// The bytes read do not match the segment's recorded hash or root.
+    #[error("segment {index} failed verification: {reason}")]
+    Tampered { index: usize, reason: String },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error(transparent)]
+    Randomness(#[from] VrfError),
+}
+
+/// Object storage for cold segments.
+pub trait StorageTier: Send {
+    /// Where the objects go, for logs and `segment_migrated` deeds.
+    fn describe(&self) -> String;
+    /// Store `bytes` under `key`, replacing any object there.
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<(), TierError>;
+    /// The object under `key`; `None` when there is none.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TierError>;
+}
+
+/// Objects as files under a directory.
+pub struct LocalDirTier {
+    root: PathBuf,
+}
+
+impl LocalDirTier {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl StorageTier for LocalDirTier {
+    fn describe(&self) -> String {
+        format!("dir:{}", self.root.display())
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<(), TierError> {
+        write_atomically(&self.root.join(key), bytes)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TierError> {
+        match fs::read(self.root.join(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// An S3-compatible bucket (MinIO, Ceph RGW, ...) reached over plain HTTP.
+pub struct S3Tier {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub prefix: String,
+}
+
+impl S3Tier {
+    fn remote(&self, message: impl Into<String>) -> TierError {
+        TierError::Remote { tier: self.describe(), message: message.into() }
+    }
+
+    /// One signed request; the status code and body of the answer.
+    fn request(&self, method: &str, key: &str, body: &[u8]) -> Result<(u16, Vec<u8>), TierError> {
+        let host = self
+            .endpoint
+            .strip_prefix("http://")
+            .map(|h| h.trim_end_matches('/'))
+            .ok_or_else(|| self.remote(format!("unsupported endpoint {}", self.endpoint)))?;
+        let path = format!("/{}/{}", self.bucket, uri_encode(&format!("{}{}", self.prefix, key)));
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let date = &amz_date[..8];
+        let payload_hash = sha256_hex(body);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, sha256_hex(canonical.as_bytes()));
+        let mut signing_key = hmac_sha256(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes());
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac_sha256(&signing_key, part.as_bytes());
+        }
+        let signature = hex(&hmac_sha256(&signing_key, to_sign.as_bytes()));
+
+        let mut stream = TcpStream::connect(host)?;
+        stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nx-amz-date: {}\r\nx-amz-content-sha256: {}\r\n\
+             Authorization: AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            method,
+            path,
+            host,
+            amz_date,
+            payload_hash,
+            self.access_key,
+            scope,
+            signed_headers,
+            signature,
+            body.len()
+        )?;
+        stream.write_all(body)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        parse_response(&response).ok_or_else(|| self.remote("malformed HTTP response"))
+    }
+}
+
+impl StorageTier for S3Tier {
+    fn describe(&self) -> String {
+        format!("s3:{}/{}/{}", self.endpoint, self.bucket, self.prefix)
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<(), TierError> {
+        match self.request("PUT", key, bytes)? {
+            (200..=299, _) => Ok(()),
+            (status, body) => Err(self.remote(format!("PUT {} answered {}: {}", key, status, String::from_utf8_lossy(&body)))),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TierError> {
+        match self.request("GET", key, b"")? {
+            (200..=299, body) => Ok(Some(body)),
+            (404, _) => Ok(None),
+            (status, body) => Err(self.remote(format!("GET {} answered {}: {}", key, status, String::from_utf8_lossy(&body)))),
+        }
+    }
+}
+
+/// Status and body of a complete HTTP/1.1 response, chunked or not.
+fn parse_response(response: &[u8]) -> Option<(u16, Vec<u8>)> {
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&response[..split]).ok()?;
+    let status = head.split_whitespace().nth(1)?.parse().ok()?;
+    let body = &response[split + 4..];
+    let chunked = head
+        .lines()
+        .any(|l| l.to_ascii_lowercase().starts_with("transfer-encoding:") && l.to_ascii_lowercase().contains("chunked"));
+    if !chunked {
+        return Some((status, body.to_vec()));
+    }
+    let (mut out, mut rest) = (Vec::new(), body);
+    loop {
+        let line_end = rest.windows(2).position(|w| w == b"\r\n")?;
+        let size_field = std::str::from_utf8(&rest[..line_end]).ok()?;
+        let size = usize::from_str_radix(size_field.split(';').next()?.trim(), 16).ok()?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            return Some((status, out));
+        }
+        out.extend_from_slice(rest.get(..size)?);
+        rest = rest.get(size + 2..)?;
+    }
+}
+
+/// Percent-encode an object key, leaving `/` and the unreserved characters.
+fn uri_encode(key: &str) -> String {
+    let mut out = String::new();
+    for b in key.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => out.push(b as char),
+            _ => write!(out, "%{:02X}", b).unwrap(),
+        }
+    }
+    out
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        write!(out, "{:02x}", b).unwrap();
+        out
+    })
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+/// Where a segment is, and how far its migration got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Residence {
+    Local,
+    /// Being copied to the cold tier; the local file is still there.
+    Uploading,
+    /// The remote copy's hash matched; the local file may still be there.
+    Confirmed,
+    Cold,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub index: usize,
+    pub first: usize,
+    pub last: usize,
+    pub tip_hash: String,
+    pub merkle_root: String,
+    /// File name under `segments/`, and the object key under the tier.
+    pub key: String,
+    /// SHA-256 of the segment file.
+    pub object_hash: String,
+    /// Timestamp of the segment's last deed.
+    pub sealed_through: i64,
+    pub residence: Residence,
+    /// The `segment_migrated` deed, once logged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration_event_id: Option<String>,
+}
+
+/// Contents of `tiers.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TierManifest {
+    pub segments: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub migrated: Vec<usize>,
+    /// Segments left where they were, and why; they are retried next run.
+    pub failed: Vec<(usize, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpotCheck {
+    pub index: usize,
+    /// Why the remote copy is not the segment; `None` when it is.
+    pub fault: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColdStorageMetrics {
+    pub fetches: u64,
+    pub cache_hits: u64,
+    pub evictions: u64,
+    /// Reads and spot checks whose bytes failed verification.
+    pub tampered: u64,
+}
+
+#[derive(Debug, Default)]
+struct ReadCache {
+    /// Cached segment indices, least recently read first.
+    order: VecDeque<usize>,
+    metrics: ColdStorageMetrics,
+}
+
+/// Sealed segments on disk and in the cold tier. See the module docs.
+pub struct ColdStorage {
+    policy: ColdStoragePolicy,
+    dir: PathBuf,
+    tier: Box<dyn StorageTier>,
+    manifest: TierManifest,
+    cache: Mutex<ReadCache>,
+    next_migration: i64,
+    next_spot_check: i64,
+}
+
+impl ColdStorage {
+    /// Storage for the policy's `dir` and `tier`; `None` unless both are set.
+    pub fn for_policy(policy: &ColdStoragePolicy, now: i64) -> Result<Option<Self>, TierError> {
+        match (&policy.dir, &policy.tier) {
+            (Some(dir), Some(tier)) => Self::open(policy.clone(), dir, tier.build(), now).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Open `dir`, reading its manifest and read cache if present.
+    pub fn open(policy: ColdStoragePolicy, dir: impl Into<PathBuf>, tier: Box<dyn StorageTier>, now: i64) -> Result<Self, TierError> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("segments"))?;
+        fs::create_dir_all(dir.join("cache"))?;
+        let manifest = match fs::read_to_string(dir.join("tiers.json")) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => TierManifest::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut cache = ReadCache::default();
+        for entry in manifest.segments.iter().filter(|e| e.residence == Residence::Cold) {
+            if dir.join("cache").join(&entry.key).exists() {
+                cache.order.push_back(entry.index);
+            }
+        }
+        let next_migration = now + policy.migrate_every_secs.max(1) as i64;
+        let next_spot_check = now + policy.spot_check_every_secs.max(1) as i64;
+        Ok(Self { policy, dir, tier, manifest, cache: Mutex::new(cache), next_migration, next_spot_check })
+    }
+
+    pub fn policy(&self) -> &ColdStoragePolicy {
+        &self.policy
+    }
+
+    pub fn manifest(&self) -> &TierManifest {
+        &self.manifest
+    }
+
+    pub fn entry(&self, index: usize) -> Option<&ManifestEntry> {
+        self.manifest.segments.get(index)
+    }
+
+    pub fn metrics(&self) -> ColdStorageMetrics {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).metrics
+    }
+
+    /// Path of `key`'s local file.
+    pub fn local_path(&self, key: &str) -> PathBuf {
+        self.dir.join("segments").join(key)
+    }
+
+    fn cache_path(&self, key: &str) -> PathBuf {
+        self.dir.join("cache").join(key)
+    }
+
+    fn save(&self) -> Result<(), TierError> {
+        write_atomically(&self.dir.join("tiers.json"), &serde_json::to_vec_pretty(&self.manifest)?)?;
+        Ok(())
+    }
+
+    /// Write every segment `ledger` has sealed since the last call.
+    /// Returns how many were written.
+    pub fn write_sealed(&mut self, ledger: &TokenLedger) -> Result<usize, TierError> {
+        let written = self.manifest.segments.len();
+        for segment in &ledger.sealed_segments()[written.min(ledger.sealed_segments().len())..] {
+            let deeds = &ledger.deeds()[segment.first..=segment.last];
+            let mut bytes = Vec::new();
+            for deed in deeds {
+                serde_json::to_writer(&mut bytes, deed)?;
+                bytes.push(b'\n');
+            }
+            let key = format!("{:06}.jsonl", segment.index + 1);
+            write_atomically(&self.local_path(&key), &bytes)?;
+            self.manifest.segments.push(ManifestEntry {
+                index: segment.index,
+                first: segment.first,
+                last: segment.last,
+                tip_hash: segment.tip_hash.clone(),
+                merkle_root: segment_root(ledger, segment),
+                key,
+                object_hash: sha256_hex(&bytes),
+                sealed_through: deeds.last().map_or(0, |d| d.timestamp),
+                residence: Residence::Local,
+                migration_event_id: None,
+            });
+            self.save()?;
+        }
+        Ok(self.manifest.segments.len() - written)
+    }
+
+    /// Move every local segment older than `cold_after_secs` at `now` to
+    /// the cold tier, finishing interrupted migrations first.
+    pub fn migrate(&mut self, ledger: &mut TokenLedger, now: i64) -> MigrationReport {
+        let mut report = MigrationReport::default();
+        for position in 0..self.manifest.segments.len() {
+            let entry = &self.manifest.segments[position];
+            let due = match entry.residence {
+                Residence::Local => now - entry.sealed_through >= self.policy.cold_after_secs,
+                Residence::Uploading | Residence::Confirmed => true,
+                Residence::Cold => false,
+            };
+            if !due {
+                continue;
+            }
+            let index = entry.index;
+            match self.migrate_one(position, ledger) {
+                Ok(()) => report.migrated.push(index),
+                Err(e) => {
+                    warn!("segment {} stays local: {}", index, e);
+                    report.failed.push((index, e.to_string()));
+                }
+            }
+        }
+        report
+    }
+
+    fn migrate_one(&mut self, position: usize, ledger: &mut TokenLedger) -> Result<(), TierError> {
+        let entry = self.manifest.segments[position].clone();
+        if matches!(entry.residence, Residence::Local | Residence::Uploading) {
+            let bytes = fs::read(self.local_path(&entry.key))?;
+            verify(&entry, &bytes)?;
+            let live_root = ledger.sealed_segments().get(entry.index).map(|s| segment_root(ledger, s));
+            if let Some(live_root) = live_root.filter(|r| *r != entry.merkle_root) {
+                return Err(TierError::Tampered { index: entry.index, reason: format!("ledger root is {}", live_root) });
+            }
+            self.set_residence(position, Residence::Uploading)?;
+            self.tier.put(&entry.key, &bytes)?;
+            match self.tier.get(&entry.key)? {
+                Some(remote) if sha256_hex(&remote) == entry.object_hash => {}
+                Some(remote) => {
+                    return Err(TierError::Tampered {
+                        index: entry.index,
+                        reason: format!("remote copy hashes to {}", sha256_hex(&remote)),
+                    })
+                }
+                None => return Err(TierError::Tampered { index: entry.index, reason: "remote copy is missing".to_string() }),
+            }
+            self.set_residence(position, Residence::Confirmed)?;
+        }
+
+        if self.manifest.segments[position].migration_event_id.is_none() {
+            // A deed logged just before a stop is found again, not logged twice.
+            let logged = ledger
+                .deeds()
+                .iter()
+                .rev()
+                .find(|d| {
+                    d.deed_type == SEGMENT_MIGRATED
+                        && d.context_json["index"] == json!(entry.index)
+                        && d.context_json["object_hash"] == json!(entry.object_hash)
+                })
+                .map(|d| d.event_id.clone());
+            let event_id = match logged {
+                Some(event_id) => event_id,
+                None => {
+                    let context = json!({
+                        "index": entry.index,
+                        "first": entry.first,
+                        "last": entry.last,
+                        "merkle_root": entry.merkle_root,
+                        "key": entry.key,
+                        "object_hash": entry.object_hash,
+                        "tier": self.tier.describe(),
+                    });
+                    ledger.log_segment_migrated(context)?.event_id.clone()
+                }
+            };
+            self.manifest.segments[position].migration_event_id = Some(event_id);
+            self.save()?;
+        }
+
+        if let Err(e) = fs::remove_file(self.local_path(&entry.key)) {
+            if e.kind() != io::ErrorKind::NotFound {
+                return Err(e.into());
+            }
+        }
+        self.set_residence(position, Residence::Cold)?;
+        info!("segment {} moved to {}", entry.index, self.tier.describe());
+        Ok(())
+    }
+
+    fn set_residence(&mut self, position: usize, residence: Residence) -> Result<(), TierError> {
+        self.manifest.segments[position].residence = residence;
+        self.save()
+    }
+
+    /// The deeds of segment `index`, from whichever tier holds it.
+    pub fn read_segment(&self, index: usize) -> Result<Vec<DeedEvent>, TierError> {
+        let entry = self.entry(index).ok_or(TierError::UnknownSegment(index))?;
+        if entry.residence != Residence::Cold {
+            return verify(entry, &fs::read(self.local_path(&entry.key))?);
+        }
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let cached = self.cache_path(&entry.key);
+        if let Some(at) = cache.order.iter().position(|&i| i == index) {
+            cache.order.remove(at);
+            match fs::read(&cached).map_err(TierError::from).and_then(|bytes| verify(entry, &bytes)) {
+                Ok(deeds) => {
+                    cache.order.push_back(index);
+                    cache.metrics.cache_hits += 1;
+                    return Ok(deeds);
+                }
+                // A damaged cache file is dropped and fetched again.
+                Err(e) => warn!("cached segment {} dropped: {}", index, e),
+            }
+        }
+
+        cache.metrics.fetches += 1;
+        let bytes = self
+            .tier
+            .get(&entry.key)?
+            .ok_or_else(|| TierError::Tampered { index, reason: "remote copy is missing".to_string() })?;
+        let deeds = match verify(entry, &bytes) {
+            Ok(deeds) => deeds,
+            Err(e) => {
+                cache.metrics.tampered += 1;
+                return Err(e);
+            }
+        };
+        write_atomically(&cached, &bytes)?;
+        cache.order.push_back(index);
+        while cache.order.len() > self.policy.cache_segments.max(1) {
+            let evicted = cache.order.pop_front().expect("over capacity");
+            if let Some(entry) = self.entry(evicted) {
+                let _ = fs::remove_file(self.cache_path(&entry.key));
+            }
+            cache.metrics.evictions += 1;
+        }
+        Ok(deeds)
+    }
+
+    /// Every sealed deed in order, read segment by segment.
+    pub fn stream_events(&self) -> EventStream<'_> {
+        EventStream { storage: self, next_segment: 0, buffered: Vec::new().into_iter() }
+    }
+
+    /// Fetch a cold segment straight from its tier and verify it, drawing
+    /// which one from `ledger` (a `randomness_drawn` deed for
+    /// `SPOT_CHECK_PURPOSE`). `None` when nothing is cold.
+    pub fn spot_check(&mut self, ledger: &mut TokenLedger) -> Result<Option<SpotCheck>, TierError> {
+        let cold: Vec<usize> = self.manifest.segments.iter().filter(|e| e.residence == Residence::Cold).map(|e| e.index).collect();
+        if cold.is_empty() {
+            return Ok(None);
+        }
+        let index = cold[vrf::sample_indices(ledger, SPOT_CHECK_PURPOSE, 1, cold.len())?[0]];
+        let entry = &self.manifest.segments[index];
+        let fault = match self.tier.get(&entry.key)? {
+            Some(bytes) => verify(entry, &bytes).err().map(|e| e.to_string()),
+            None => Some("remote copy is missing".to_string()),
+        };
+        if fault.is_some() {
+            self.cache.lock().unwrap_or_else(|e| e.into_inner()).metrics.tampered += 1;
+        }
+        Ok(Some(SpotCheck { index, fault }))
+    }
+
+    /// Write newly sealed segments, then migrate and spot-check when due.
+    /// A failed spot check is handed to `auditor` as an integrity violation.
+    pub fn tick(&mut self, ledger: &mut TokenLedger, auditor: &mut SelfAuditor, now: i64) {
+        if let Err(e) = self.write_sealed(ledger) {
+            warn!("cold storage: writing sealed segments: {}", e);
+        }
+        if now >= self.next_migration {
+            self.next_migration = now + self.policy.migrate_every_secs.max(1) as i64;
+            let report = self.migrate(ledger, now);
+            if !report.migrated.is_empty() || !report.failed.is_empty() {
+                info!("cold storage: migrated {:?}, {} left local", report.migrated, report.failed.len());
+            }
+        }
+        if now >= self.next_spot_check {
+            self.next_spot_check = now + self.policy.spot_check_every_secs.max(1) as i64;
+            match self.spot_check(ledger) {
+                Ok(Some(SpotCheck { index, fault: Some(reason) })) => {
+                    error!("cold segment {} failed its spot check: {}", index, reason);
+                    let entry = &self.manifest.segments[index];
+                    let violation = AuditViolation {
+                        start: entry.first,
+                        end: entry.last + 1,
+                        position: entry.first,
+                        event_id: None,
+                        fault: AuditFault::ColdSegment { index, reason },
+                        detected_at: now,
+                        deed_event_id: None,
+                    };
+                    if let Err(e) = auditor.report(ledger, violation) {
+                        warn!("cold storage: recording spot-check violation: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("cold storage: spot check: {}", e),
+            }
+        }
+    }
+}
+
+/// Check `bytes` against `entry`'s hash and Merkle root, and parse them.
+fn verify(entry: &ManifestEntry, bytes: &[u8]) -> Result<Vec<DeedEvent>, TierError> {
+    let tampered = |reason: String| TierError::Tampered { index: entry.index, reason };
+    let hash = sha256_hex(bytes);
+    if hash != entry.object_hash {
+        return Err(tampered(format!("hashes to {}, expected {}", hash, entry.object_hash)));
+    }
+    let deeds = bytes
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(serde_json::from_slice::<DeedEvent>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| tampered(format!("unparseable deed: {}", e)))?;
+    let leaves: Vec<String> = deeds.iter().map(|d| d.self_hash.clone()).collect();
+    let root = merkle_root(leaves);
+    if root != entry.merkle_root || deeds.len() != entry.last + 1 - entry.first {
+        return Err(tampered(format!("Merkle root {} over {} deeds, expected {}", root, deeds.len(), entry.merkle_root)));
+    }
+    Ok(deeds)
+}
+
+/// Iterator over every sealed deed; see `ColdStorage::stream_events`.
+pub struct EventStream<'a> {
+    storage: &'a ColdStorage,
+    next_segment: usize,
+    buffered: std::vec::IntoIter<DeedEvent>,
+}
+
+impl Iterator for EventStream<'_> {
+    type Item = Result<DeedEvent, TierError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(deed) = self.buffered.next() {
+                return Some(Ok(deed));
+            }
+            if self.next_segment >= self.storage.manifest.segments.len() {
+                return None;
+            }
+            self.next_segment += 1;
+            match self.storage.read_segment(self.next_segment - 1) {
+                Ok(deeds) => self.buffered = deeds.into_iter(),
+                Err(e) => {
+                    // Stop after an unreadable segment; what follows would
+                    // not link.
+                    self.next_segment = self.storage.manifest.segments.len();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl DeedSource for ColdStorage {
+    fn deed_count(&self) -> Result<usize, SourceError> {
+        Ok(self.manifest.segments.last().map_or(0, |e| e.last + 1))
+    }
+
+    fn read_range(&self, start: usize, end: usize) -> Result<Vec<DeedEvent>, SourceError> {
+        let mut deeds = Vec::with_capacity(end.saturating_sub(start));
+        for entry in self.manifest.segments.iter().filter(|e| e.last >= start && e.first < end) {
+            let segment = self.read_segment(entry.index).map_err(|e| match e {
+                TierError::Io(e) => SourceError::Io(e),
+                TierError::Remote { .. } => SourceError::Io(io::Error::other(e.to_string())),
+                e => SourceError::Unreadable { position: entry.first.max(start), reason: e.to_string() },
+            })?;
+            let from = start.saturating_sub(entry.first);
+            let to = (end - entry.first).min(segment.len());
+            deeds.extend(segment.into_iter().take(to).skip(from));
+        }
+        Ok(deeds)
+    }
+}
@@ -0,0 +1,412 @@
+//! Two-phase validation of deed batches.
+//!
+//! Importers, replica catch-up and wire batch frames hand over thousands of
+//! deeds at once. Most rules look at one deed alone; only a few depend on
+//! the chain before it. Every `DeedRule` declares which with its
+//! `RuleOrderSensitivity`, and `validate_batch` splits on that:
+//!
+//! 1. the `Independent` rules of every deed run first, in parallel once the
+//!    batch reaches `DeedRules::parallel_threshold`, keeping each deed's
+//!    first failure;
+//! 2. the batch is then walked in chain order: the `ChainOrder` rules run
+//!    against the tip and the deeds accepted so far, and a phase-one
+//!    failure is taken at the position its rule holds in the list.
+//!
+//! Each deed thus gets the verdict `validate_batch_sequential`, which runs
+//! every rule of every deed in order, gives it. A rejected deed does not
+//! advance the tip, so the deeds linked after it are refused as well.
+//! Accepted deeds are checked against their category's cooldown too; as on
+//! append, that does not reject them (see `cooldown`).
+//!
+//! A rule declared `Independent` that reads the chain fails a debug
+//! assertion, so a misdeclared rule shows up in tests rather than as a
+//! verdict that depends on scheduling.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use tracing::info_span;
+
+use crate::compliance::data_minimization::MinimizationPolicy;
+use crate::compliance::eco_reg::EcoRegEnvelope;
+use crate::compliance::ethics::EthicsContext;
+use crate::cooldown::{cooldown_violation, slashed_ids, CooldownViolation};
+use crate::ledger::builders::validate_context;
+use crate::ledger::deed_event::{hash_deed, DeedError, DeedEvent};
+use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::token_ledger::TokenLedger;
+
+/// Batches smaller than this are validated on the calling thread; below it
+/// rayon's scheduling costs more than the per-deed rules.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 64;
+
+/// What a rule's verdict may depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOrderSensitivity {
+    /// The deed and its metrics alone.
+    Independent,
+    /// The chain tip or the deeds accepted before it.
+    ChainOrder,
+}
+
+/// One check a deed must pass.
+pub trait DeedRule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn sensitivity(&self) -> RuleOrderSensitivity;
+    fn check(&self, deed: &DeedEvent, ctx: &RuleContext<'_>) -> Result<(), DeedError>;
+}
+
+/// The chain a deed is validated against.
+pub struct ChainView<'a> {
+    pub ledger: &'a TokenLedger,
+    /// Hash the deed must link to.
+    pub tip: &'a str,
+    /// Deeds of the batch accepted before this one, in order.
+    pub accepted: &'a [&'a DeedEvent],
+}
+
+pub struct RuleContext<'a> {
+    pub metrics: &'a BioloadMetrics,
+    rule: &'static str,
+    chain: Option<ChainView<'a>>,
+}
+
+impl<'a> RuleContext<'a> {
+    /// The chain; `None` for `Independent` rules, where asking for it is a
+    /// misdeclaration and fails a debug assertion.
+    pub fn chain(&self) -> Option<&ChainView<'a>> {
+        debug_assert!(self.chain.is_some(), "rule `{}` is declared Independent but reads the chain", self.rule);
+        self.chain.as_ref()
+    }
+}
+
+/// `self_hash` matches the deed's contents.
+pub struct SelfHash;
+
+impl DeedRule for SelfHash {
+    fn name(&self) -> &'static str {
+        "self_hash"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, _: &RuleContext<'_>) -> Result<(), DeedError> {
+        let mut unhashed = deed.clone();
+        unhashed.self_hash = String::new();
+        match hash_deed(&unhashed) == deed.self_hash {
+            true => Ok(()),
+            false => Err(DeedError::HashMismatch(format!("deed {} does not hash to its self_hash", deed.event_id))),
+        }
+    }
+}
+
+/// `prev_hash` is the tip.
+pub struct ChainLink;
+
+impl DeedRule for ChainLink {
+    fn name(&self) -> &'static str {
+        "chain_link"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::ChainOrder
+    }
+
+    fn check(&self, deed: &DeedEvent, ctx: &RuleContext<'_>) -> Result<(), DeedError> {
+        let tip = ctx.chain().map_or("", |c| c.tip);
+        match deed.prev_hash == tip {
+            true => Ok(()),
+            false => Err(DeedError::HashMismatch(format!("deed {} does not link to tip {}", deed.event_id, tip))),
+        }
+    }
+}
+
+/// RoH and decay within the biophysical ceilings.
+pub struct Biophysical;
+
+impl DeedRule for Biophysical {
+    fn name(&self) -> &'static str {
+        "biophysical"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, ctx: &RuleContext<'_>) -> Result<(), DeedError> {
+        deed.validate_biophysical(ctx.metrics.roh, ctx.metrics.decay)
+    }
+}
+
+/// The context matches the category schema: required fields, kinds and
+/// ranges.
+pub struct ContextSchema;
+
+impl DeedRule for ContextSchema {
+    fn name(&self) -> &'static str {
+        "context_schema"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, _: &RuleContext<'_>) -> Result<(), DeedError> {
+        validate_context(deed).map_err(|e| DeedError::InvariantViolation(e.to_string()))
+    }
+}
+
+/// Neuro deeds carry no raw signal data.
+pub struct Minimization;
+
+impl DeedRule for Minimization {
+    fn name(&self) -> &'static str {
+        "minimization"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, _: &RuleContext<'_>) -> Result<(), DeedError> {
+        let minimization = MinimizationPolicy::default();
+        if !minimization.applies_to(&deed.deed_type, &deed.tags) {
+            return Ok(());
+        }
+        let findings = minimization.scan(&deed.context_json);
+        if findings.is_empty() {
+            return Ok(());
+        }
+        let paths: Vec<&str> = findings.iter().map(|f| f.path.as_str()).collect();
+        Err(DeedError::InvariantViolation(format!("neuro deed carries raw signal data at {}", paths.join(", "))))
+    }
+}
+
+/// RoH and decay within the EcoReg envelope.
+pub struct EcoEnvelope;
+
+impl DeedRule for EcoEnvelope {
+    fn name(&self) -> &'static str {
+        "eco_envelope"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, _: &DeedEvent, ctx: &RuleContext<'_>) -> Result<(), DeedError> {
+        match EcoRegEnvelope::default().within_bounds(ctx.metrics.roh, ctx.metrics.decay) {
+            true => Ok(()),
+            false => Err(DeedError::InvariantViolation("EcoReg envelope breach".to_string())),
+        }
+    }
+}
+
+/// No ethics flags and no life harm.
+pub struct Ethics;
+
+impl DeedRule for Ethics {
+    fn name(&self) -> &'static str {
+        "ethics"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, _: &RuleContext<'_>) -> Result<(), DeedError> {
+        let ctx = EthicsContext { flags: deed.ethics_flags.clone(), life_harm_flag: deed.life_harm_flag };
+        match ctx.is_clean() {
+            true => Ok(()),
+            false => Err(DeedError::InvariantViolation("Ethics flags present".to_string())),
+        }
+    }
+}
+
+/// An ordered rule list; a deed's verdict is its first failing rule.
+pub struct DeedRules {
+    rules: Vec<Box<dyn DeedRule>>,
+    /// Smallest batch validated in parallel.
+    pub parallel_threshold: usize,
+}
+
+impl DeedRules {
+    fn empty() -> Self {
+        Self { rules: Vec::new(), parallel_threshold: DEFAULT_PARALLEL_THRESHOLD }
+    }
+
+    /// The rules of `validate_deed`.
+    pub fn compliance() -> Self {
+        Self::empty().with(Biophysical).with(ContextSchema).with(Minimization).with(EcoEnvelope).with(Ethics)
+    }
+
+    /// Hash and linkage, then the compliance rules: what a deed arriving
+    /// in a batch must pass before it is appended.
+    pub fn standard() -> Self {
+        let mut rules = Self::empty().with(SelfHash).with(ChainLink);
+        rules.rules.extend(Self::compliance().rules);
+        rules
+    }
+
+    /// Append `rule` to the list.
+    pub fn with(mut self, rule: impl DeedRule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    pub fn with_parallel_threshold(self, parallel_threshold: usize) -> Self {
+        Self { parallel_threshold, ..self }
+    }
+
+    /// Whether a batch of `len` deeds is worth spreading over threads.
+    pub fn runs_parallel(&self, len: usize) -> bool {
+        len >= self.parallel_threshold.max(1) && rayon::current_num_threads() > 1
+    }
+
+    /// The first `Independent` rule `deed` fails, by position, without
+    /// looking at any chain.
+    pub(crate) fn first_independent_failure(&self, deed: &DeedEvent, metrics: &BioloadMetrics) -> Option<(usize, DeedError)> {
+        self.rules.iter().enumerate().filter(|(_, r)| r.sensitivity() == RuleOrderSensitivity::Independent).find_map(|(i, rule)| {
+            let ctx = RuleContext { metrics, rule: rule.name(), chain: None };
+            rule.check(deed, &ctx).err().map(|e| (i, e))
+        })
+    }
+
+    /// Walk the rules in order against `chain`, taking `failure` (from
+    /// `first_independent_failure`) at its position.
+    fn verdict(
+        &self,
+        deed: &DeedEvent,
+        metrics: &BioloadMetrics,
+        chain: ChainView<'_>,
+        mut failure: Option<(usize, DeedError)>,
+    ) -> Result<(), (&'static str, DeedError)> {
+        let mut ctx = RuleContext { metrics, rule: "", chain: Some(chain) };
+        for (i, rule) in self.rules.iter().enumerate() {
+            match rule.sensitivity() {
+                RuleOrderSensitivity::Independent => {
+                    if failure.as_ref().is_some_and(|(at, _)| *at == i) {
+                        return Err((rule.name(), failure.take().expect("checked").1));
+                    }
+                }
+                RuleOrderSensitivity::ChainOrder => {
+                    ctx.rule = rule.name();
+                    rule.check(deed, &ctx).map_err(|e| (rule.name(), e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One deed's outcome.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeedVerdict {
+    /// Passed every rule. With `cooldown`, appending it stores it
+    /// `cooldown_suppressed`.
+    Accepted { cooldown: Option<CooldownViolation> },
+    Rejected { rule: &'static str, error: DeedError },
+}
+
+impl DeedVerdict {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, DeedVerdict::Accepted { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchTimings {
+    /// The `Independent` rules of every deed.
+    pub per_deed: Duration,
+    /// The `ChainOrder` rules and cooldowns, in chain order.
+    pub chain_order: Duration,
+    /// Whether the per-deed phase ran on the rayon pool.
+    pub parallel: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchValidation {
+    /// One per deed, in batch order.
+    pub verdicts: Vec<DeedVerdict>,
+    pub timings: BatchTimings,
+}
+
+impl BatchValidation {
+    pub fn accepted(&self) -> usize {
+        self.verdicts.iter().filter(|v| v.is_accepted()).count()
+    }
+}
+
+/// Validate `batch`, each deed with its metrics, as a run to be appended
+/// to `ledger` in order. See the module docs.
+pub fn validate_batch(ledger: &TokenLedger, batch: &[(DeedEvent, BioloadMetrics)], rules: &DeedRules) -> BatchValidation {
+    let parallel = rules.runs_parallel(batch.len());
+    let _span = info_span!("batch_validation", deeds = batch.len(), parallel).entered();
+
+    let started = Instant::now();
+    let check = |(deed, metrics): &(DeedEvent, BioloadMetrics)| rules.first_independent_failure(deed, metrics);
+    let failures: Vec<Option<(usize, DeedError)>> = match parallel {
+        true => batch.par_iter().map(check).collect(),
+        false => batch.iter().map(check).collect(),
+    };
+    let per_deed = started.elapsed();
+
+    let started = Instant::now();
+    let mut walk = ChainWalk::new(ledger);
+    let verdicts = batch.iter().zip(failures).map(|((deed, metrics), failure)| walk.step(rules, deed, metrics, failure)).collect();
+    let chain_order = started.elapsed();
+
+    BatchValidation { verdicts, timings: BatchTimings { per_deed, chain_order, parallel } }
+}
+
+/// The reference pipeline: every rule of every deed, one deed after the
+/// other. `validate_batch` reaches the same verdicts.
+pub fn validate_batch_sequential(ledger: &TokenLedger, batch: &[(DeedEvent, BioloadMetrics)], rules: &DeedRules) -> Vec<DeedVerdict> {
+    let mut walk = ChainWalk::new(ledger);
+    batch
+        .iter()
+        .map(|(deed, metrics)| {
+            let failure = rules.first_independent_failure(deed, metrics);
+            walk.step(rules, deed, metrics, failure)
+        })
+        .collect()
+}
+
+/// The chain as a batch is walked: the tip, the deeds accepted so far and,
+/// for cooldowns, those of them that would mint.
+struct ChainWalk<'a> {
+    ledger: &'a TokenLedger,
+    slashed: HashSet<String>,
+    tip: String,
+    accepted: Vec<&'a DeedEvent>,
+    minted: Vec<&'a DeedEvent>,
+}
+
+impl<'a> ChainWalk<'a> {
+    fn new(ledger: &'a TokenLedger) -> Self {
+        Self { ledger, slashed: slashed_ids(ledger), tip: ledger.last_hash(), accepted: Vec::new(), minted: Vec::new() }
+    }
+
+    fn step(
+        &mut self,
+        rules: &DeedRules,
+        deed: &'a DeedEvent,
+        metrics: &BioloadMetrics,
+        failure: Option<(usize, DeedError)>,
+    ) -> DeedVerdict {
+        let chain = ChainView { ledger: self.ledger, tip: &self.tip, accepted: &self.accepted };
+        if let Err((rule, error)) = rules.verdict(deed, metrics, chain, failure) {
+            return DeedVerdict::Rejected { rule, error };
+        }
+        let cooldown = cooldown_violation(self.ledger.deeds().iter().chain(self.minted.iter().copied()), &self.slashed, deed);
+        if cooldown.is_none() {
+            self.minted.push(deed);
+        }
+        self.accepted.push(deed);
+        self.tip = deed.self_hash.clone();
+        DeedVerdict::Accepted { cooldown }
+    }
+}
@@ -0,0 +1,247 @@
+//! Data minimization for neuro node deeds.
+//!
+//! The neuro-rights posture is "only physical stressors, never neural data".
+//! Before a neuro deed is hashed, its context is scanned for anything that
+//! looks like raw signal: long numeric arrays, EEG-ish keys, base64 blobs
+//! and high-entropy strings. Depending on `MinimizationMode` the deed is
+//! rejected, or the offending fields are stripped and the deed carries the
+//! `minimization_applied` ethics flag. Allowlisted summary keys (sleep stage
+//! percentages, session duration, artifact counts) pass untouched.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::ledger::deed_event::{hash_deed, DeedEvent};
+
+/// Informational ethics flag recorded on deeds whose context was stripped.
+pub const MINIMIZATION_FLAG: &str = "minimization_applied";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimizationMode {
+    Reject,
+    Strip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MinimizationPolicy {
+    pub mode: MinimizationMode,
+    /// Deed types the rule applies to.
+    pub neuro_deed_types: Vec<String>,
+    /// Deeds carrying any of these tags are treated as neuro deeds too.
+    pub neuro_tags: Vec<String>,
+    /// Keys (case-insensitive substring) that are never allowed.
+    pub denied_keys: Vec<String>,
+    /// Keys whose whole subtree is explicitly permitted.
+    pub allowed_keys: Vec<String>,
+    /// Arrays with more numeric elements than this are raw signal.
+    pub max_numeric_array: usize,
+    /// Base64-looking strings decoding to more bytes than this are blobs.
+    pub max_base64_bytes: usize,
+    /// Strings at least this long are entropy-checked.
+    pub entropy_min_len: usize,
+    /// Shannon entropy (bits/char) above which a string is rejected.
+    pub max_entropy_bits: f64,
+}
+
+impl Default for MinimizationPolicy {
+    fn default() -> Self {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Self {
+            mode: MinimizationMode::Reject,
+            neuro_deed_types: strings(&["nsleep_session", "neuro_session", "bci_session"]),
+            neuro_tags: strings(&["neuro", "nsleep", "bci"]),
+            denied_keys: strings(&["eeg_raw", "channels", "samples", "raw_signal", "waveform"]),
+            allowed_keys: strings(&[
+                "sleep_stage_pct",
+                "session_duration_secs",
+                "artifact_count",
+                "artifact_counts",
+            ]),
+            max_numeric_array: 16,
+            max_base64_bytes: 256,
+            entropy_min_len: 64,
+            max_entropy_bits: 5.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum MinimizationRule {
+    DeniedKey,
+    NumericArray { len: usize },
+    Base64Blob { bytes: usize },
+    HighEntropy { bits_per_char: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MinimizationFinding {
+    /// JSON path, e.g. `session.eeg_raw` or `readings[2]`.
+    pub path: String,
+    #[serde(flatten)]
+    pub rule: MinimizationRule,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MinimizationError {
+    #[error("neuro deed context holds raw signal data at {}", paths(.0))]
+    Rejected(Vec<MinimizationFinding>),
+}
+
+fn paths(findings: &[MinimizationFinding]) -> String {
+    findings.iter().map(|f| f.path.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+fn shannon_bits(s: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    let n = s.chars().count() as f64;
+    for c in s.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    counts
+        .values()
+        .map(|&k| {
+            let p = k as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn is_base64(s: &str) -> bool {
+    s.len().is_multiple_of(4) && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=')
+}
+
+impl MinimizationPolicy {
+    pub fn applies_to(&self, deed_type: &str, tags: &[String]) -> bool {
+        self.neuro_deed_types.iter().any(|t| t == deed_type) || tags.iter().any(|t| self.neuro_tags.contains(t))
+    }
+
+    fn key_allowed(&self, key: &str) -> bool {
+        self.allowed_keys.iter().any(|k| k == key)
+    }
+
+    fn key_denied(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.denied_keys.iter().any(|d| key.contains(d.as_str()))
+    }
+
+    fn check_value(&self, value: &Value) -> Option<MinimizationRule> {
+        match value {
+            Value::Array(items) => {
+                let numeric = items.iter().filter(|v| v.is_number()).count();
+                (numeric > self.max_numeric_array).then_some(MinimizationRule::NumericArray { len: numeric })
+            }
+            Value::String(s) => {
+                let bytes = s.len() / 4 * 3;
+                if is_base64(s) && bytes > self.max_base64_bytes {
+                    return Some(MinimizationRule::Base64Blob { bytes });
+                }
+                if s.chars().count() >= self.entropy_min_len {
+                    let bits = shannon_bits(s);
+                    if bits > self.max_entropy_bits {
+                        return Some(MinimizationRule::HighEntropy { bits_per_char: bits });
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Walk `value`, recording findings and returning the stripped copy
+    /// (None if `value` itself must go).
+    fn walk(&self, path: &str, value: &Value, findings: &mut Vec<MinimizationFinding>) -> Option<Value> {
+        if let Some(rule) = self.check_value(value) {
+            findings.push(MinimizationFinding { path: path.to_string(), rule });
+            return None;
+        }
+        match value {
+            Value::Object(map) => {
+                let mut out = serde_json::Map::new();
+                for (key, child) in map {
+                    let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    if self.key_allowed(key) {
+                        out.insert(key.clone(), child.clone());
+                    } else if self.key_denied(key) {
+                        findings.push(MinimizationFinding { path: child_path, rule: MinimizationRule::DeniedKey });
+                    } else if let Some(kept) = self.walk(&child_path, child, findings) {
+                        out.insert(key.clone(), kept);
+                    }
+                }
+                Some(Value::Object(out))
+            }
+            Value::Array(items) => Some(Value::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| self.walk(&format!("{}[{}]", path, i), item, findings))
+                    .collect(),
+            )),
+            other => Some(other.clone()),
+        }
+    }
+
+    /// Every offending field in `context`.
+    pub fn scan(&self, context: &Value) -> Vec<MinimizationFinding> {
+        let mut findings = Vec::new();
+        self.walk("", context, &mut findings);
+        findings
+    }
+
+    /// Apply the policy to a context about to be hashed. Returns the
+    /// (possibly stripped) context and whether anything was removed.
+    pub fn minimize(&self, deed_type: &str, tags: &[String], context: Value) -> Result<(Value, bool), MinimizationError> {
+        if !self.applies_to(deed_type, tags) {
+            return Ok((context, false));
+        }
+        let mut findings = Vec::new();
+        let stripped = self.walk("", &context, &mut findings).unwrap_or(Value::Null);
+        if findings.is_empty() {
+            return Ok((context, false));
+        }
+        match self.mode {
+            MinimizationMode::Reject => Err(MinimizationError::Rejected(findings)),
+            MinimizationMode::Strip => Ok((stripped, true)),
+        }
+    }
+
+    /// Enforce the policy on an already-built deed. Stripping rewrites the
+    /// context, adds `MINIMIZATION_FLAG` and recomputes `self_hash`, so the
+    /// stored hash always covers the minimized content.
+    pub fn enforce(&self, mut deed: DeedEvent) -> Result<DeedEvent, MinimizationError> {
+        let context = std::mem::take(&mut deed.context_json);
+        let (context, stripped) = self.minimize(&deed.deed_type, &deed.tags, context)?;
+        deed.context_json = context;
+        if stripped {
+            if !deed.ethics_flags.iter().any(|f| f == MINIMIZATION_FLAG) {
+                deed.ethics_flags.push(MINIMIZATION_FLAG.to_string());
+            }
+            deed.self_hash = String::new();
+            deed.self_hash = hash_deed(&deed);
+        }
+        Ok(deed)
+    }
+
+    /// `DeedEvent::new` with minimization applied before hashing.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_deed(
+        &self,
+        prev_hash: String,
+        actor_id: String,
+        target_ids: Vec<String>,
+        deed_type: String,
+        tags: Vec<String>,
+        context_json: Value,
+        mut ethics_flags: Vec<String>,
+        life_harm_flag: bool,
+    ) -> Result<DeedEvent, MinimizationError> {
+        let (context_json, stripped) = self.minimize(&deed_type, &tags, context_json)?;
+        if stripped {
+            ethics_flags.push(MINIMIZATION_FLAG.to_string());
+        }
+        Ok(DeedEvent::new(prev_hash, actor_id, target_ids, deed_type, tags, context_json, ethics_flags, life_harm_flag))
+    }
+}
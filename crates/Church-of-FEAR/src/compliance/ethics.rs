@@ -1,3 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+use crate::compliance::data_minimization::MINIMIZATION_FLAG;
+use crate::ledger::concentration::PowerGini;
+
 #[derive(Debug, Clone)]
 pub struct EthicsContext {
     pub flags: Vec<String>,
@@ -6,6 +11,40 @@ pub struct EthicsContext {
 
 impl EthicsContext {
     pub fn is_clean(&self) -> bool {
-        !self.life_harm_flag && self.flags.is_empty()
+        // minimization_applied is informational: the offending data is
+        // already gone from the deed.
+        !self.life_harm_flag && self.flags.iter().all(|f| f == MINIMIZATION_FLAG)
+    }
+}
+
+/// Per-tick regulator input: the figures the node's Regulator bands act on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EthicsSummary {
+    pub bioload: f64,
+    pub bioload_variance: f64,
+    pub mean_trust: f64,
+    /// POWER Gini over participants, leaving out dust and system accounts;
+    /// the PowerConcentration condition reads this one.
+    pub power_gini: f64,
+    /// POWER Gini over every account, for dashboards only.
+    #[serde(default)]
+    pub power_gini_unfiltered: f64,
+    /// Highest POWER / CHURCH ratio across accounts.
+    #[serde(default)]
+    pub power_church_ratio: f64,
+    /// Share of this tick's deeds flagged for life harm.
+    #[serde(default)]
+    pub life_harm_rate: f64,
+    /// Share of this tick's deeds carrying any other ethics flag.
+    #[serde(default)]
+    pub ethics_flag_rate: f64,
+}
+
+impl EthicsSummary {
+    /// Take both POWER Gini figures from the ledger's `power_gini`.
+    pub fn with_power_gini(mut self, gini: &PowerGini) -> Self {
+        self.power_gini = gini.filtered;
+        self.power_gini_unfiltered = gini.unfiltered;
+        self
     }
 }
@@ -0,0 +1,107 @@
+//! Explains a failing GodLike check: which constraints are violated and by
+//! how much, rather than just a pass/fail status.
+
+use serde::{Deserialize, Serialize};
+
+use crate::compliance::ethics::EthicsSummary;
+use crate::ledger::account::Token;
+use crate::ledger::token_ledger::TokenLedger;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Constraint {
+    /// POWER ≤ k·CHURCH per account.
+    PowerCap,
+    BioloadCeiling,
+    PowerConcentration,
+    TrustFloor,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintViolation {
+    pub constraint: Constraint,
+    pub observed: f64,
+    pub limit: f64,
+    /// Distance past the limit; always positive.
+    pub excess: f64,
+}
+
+/// An account holding more POWER than k·CHURCH allows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverCap {
+    pub account_id: String,
+    pub power: u64,
+    pub church: u64,
+    pub excess: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GodLikeEnvelope {
+    pub power_church_k: f64,
+    pub bioload_max: f64,
+    pub power_gini_max: f64,
+    pub trust_floor: f64,
+}
+
+impl Default for GodLikeEnvelope {
+    fn default() -> Self {
+        Self { power_church_k: 1.0, bioload_max: 0.8, power_gini_max: 0.6, trust_floor: 0.3 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GodLikeReport {
+    pub violations: Vec<ConstraintViolation>,
+    pub over_cap: Vec<OverCap>,
+}
+
+impl GodLikeReport {
+    pub fn explain(ledger: &TokenLedger, summary: &EthicsSummary, env: &GodLikeEnvelope) -> Self {
+        let mut over_cap: Vec<OverCap> = ledger
+            .accounts()
+            .filter_map(|a| {
+                let power = a.balance(Token::Pwr);
+                let church = a.balance(Token::Church);
+                let cap = (env.power_church_k * church as f64).floor() as u64;
+                (power > cap).then(|| OverCap { account_id: a.id.clone(), power, church, excess: power - cap })
+            })
+            .collect();
+        over_cap.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+
+        let mut violations = Vec::new();
+        let total_excess: u64 = over_cap.iter().map(|o| o.excess).sum();
+        if total_excess > 0 {
+            violations.push(ConstraintViolation {
+                constraint: Constraint::PowerCap,
+                observed: total_excess as f64,
+                limit: 0.0,
+                excess: total_excess as f64,
+            });
+        }
+        let mut ceiling = |constraint, observed: f64, limit: f64| {
+            if observed > limit {
+                violations.push(ConstraintViolation { constraint, observed, limit, excess: observed - limit });
+            }
+        };
+        ceiling(Constraint::BioloadCeiling, summary.bioload, env.bioload_max);
+        ceiling(Constraint::PowerConcentration, summary.power_gini, env.power_gini_max);
+        // Trust is a floor; its excess is the shortfall.
+        if summary.mean_trust < env.trust_floor {
+            violations.push(ConstraintViolation {
+                constraint: Constraint::TrustFloor,
+                observed: summary.mean_trust,
+                limit: env.trust_floor,
+                excess: env.trust_floor - summary.mean_trust,
+            });
+        }
+        Self { violations, over_cap }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violation(&self, constraint: Constraint) -> Option<&ConstraintViolation> {
+        self.violations.iter().find(|v| v.constraint == constraint)
+    }
+}
@@ -1,3 +1,7 @@
 pub mod ethics;
+pub mod regulator;
 pub mod eco_reg;
 pub mod validator;
+pub mod data_minimization;
+pub mod god_like;
+pub mod batch;
@@ -0,0 +1,257 @@
+//! The nine ethical conditions, evaluated one at a time.
+//!
+//! Each `Condition` reads one figure from the `EthicsSummary` and compares it
+//! with its threshold from the ledger's `ParamRegistry`. Every threshold is
+//! a tighten-only parameter, so governance can make a condition stricter
+//! but never relax it.
+//!
+//! The Regulator's decision is derived from the condition array and from
+//! nothing else (`decide`):
+//!
+//! 1. Every failed condition carries a severity weight: 1 warns, 2 forces
+//!    repair, 3 halts for review. The decision starts at the highest weight
+//!    among failed conditions, or `Allow` if none failed.
+//! 2. `BioloadVariance` is escalating rather than weighted: when it fails
+//!    and the decision is below `ForceRepair`, the decision moves up one
+//!    step. Disagreement between bioload sources never halts on its own.
+//!
+//! The reason lists every failed condition that set the decision.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compliance::ethics::EthicsSummary;
+use crate::params::{ParamKey, ParamRegistry};
+use param_registry::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Bioload below the warn band.
+    BioloadWarn,
+    /// Bioload below the repair band.
+    BioloadRepair,
+    /// Bioload below the halt band.
+    BioloadHalt,
+    /// Bioload sources agree within the variance ceiling.
+    BioloadVariance,
+    /// Mean trust at or above the floor.
+    TrustFloor,
+    /// POWER Gini at or below its ceiling.
+    PowerConcentration,
+    /// POWER ≤ k·CHURCH for every account.
+    PowerCap,
+    /// No more life-harm flags than the ceiling allows.
+    LifeHarm,
+    /// Other ethics flags at or below their ceiling.
+    EthicsFlags,
+}
+
+/// How a measured value is compared with its threshold.
+enum Comparison {
+    /// Passes strictly below the threshold.
+    Band,
+    /// Passes at or above the threshold.
+    Floor,
+    /// Passes at or below the threshold.
+    Ceiling,
+}
+
+impl Condition {
+    pub const ALL: [Condition; 9] = [
+        Condition::BioloadWarn,
+        Condition::BioloadRepair,
+        Condition::BioloadHalt,
+        Condition::BioloadVariance,
+        Condition::TrustFloor,
+        Condition::PowerConcentration,
+        Condition::PowerCap,
+        Condition::LifeHarm,
+        Condition::EthicsFlags,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Condition::BioloadWarn => "bioload_warn",
+            Condition::BioloadRepair => "bioload_repair",
+            Condition::BioloadHalt => "bioload_halt",
+            Condition::BioloadVariance => "bioload_variance",
+            Condition::TrustFloor => "trust_floor",
+            Condition::PowerConcentration => "power_concentration",
+            Condition::PowerCap => "power_cap",
+            Condition::LifeHarm => "life_harm",
+            Condition::EthicsFlags => "ethics_flags",
+        }
+    }
+
+    /// The tighten-only parameter holding this condition's threshold.
+    pub fn param(self) -> Key<f64> {
+        match self {
+            Condition::BioloadWarn => ParamKey::EthicsBioloadWarn,
+            Condition::BioloadRepair => ParamKey::EthicsBioloadRepair,
+            Condition::BioloadHalt => ParamKey::EthicsBioloadHalt,
+            Condition::BioloadVariance => ParamKey::EthicsBioloadVarianceMax,
+            Condition::TrustFloor => ParamKey::EthicsTrustFloor,
+            Condition::PowerConcentration => ParamKey::EthicsPowerGiniMax,
+            Condition::PowerCap => ParamKey::EthicsPowerMultiplier,
+            Condition::LifeHarm => ParamKey::EthicsLifeHarmMax,
+            Condition::EthicsFlags => ParamKey::EthicsFlagRateMax,
+        }
+    }
+
+    /// Decision severity a failure carries; see the module docs.
+    pub fn weight(self) -> u8 {
+        match self {
+            Condition::BioloadHalt | Condition::LifeHarm => 3,
+            Condition::BioloadRepair | Condition::PowerConcentration | Condition::PowerCap => 2,
+            Condition::BioloadWarn | Condition::BioloadVariance | Condition::TrustFloor | Condition::EthicsFlags => 1,
+        }
+    }
+
+    /// Whether a failure raises the decision one step instead of setting it.
+    pub fn escalates(self) -> bool {
+        self == Condition::BioloadVariance
+    }
+
+    fn measure(self, summary: &EthicsSummary) -> f64 {
+        match self {
+            Condition::BioloadWarn | Condition::BioloadRepair | Condition::BioloadHalt => summary.bioload,
+            Condition::BioloadVariance => summary.bioload_variance,
+            Condition::TrustFloor => summary.mean_trust,
+            Condition::PowerConcentration => summary.power_gini,
+            Condition::PowerCap => summary.power_church_ratio,
+            Condition::LifeHarm => summary.life_harm_rate,
+            Condition::EthicsFlags => summary.ethics_flag_rate,
+        }
+    }
+
+    fn comparison(self) -> Comparison {
+        match self {
+            Condition::BioloadWarn | Condition::BioloadRepair | Condition::BioloadHalt => Comparison::Band,
+            Condition::TrustFloor => Comparison::Floor,
+            _ => Comparison::Ceiling,
+        }
+    }
+
+    pub fn evaluate(self, summary: &EthicsSummary, threshold: f64) -> ConditionResult {
+        let measured = self.measure(summary);
+        let passed = match self.comparison() {
+            Comparison::Band => measured < threshold,
+            Comparison::Floor => measured >= threshold,
+            Comparison::Ceiling => measured <= threshold,
+        };
+        ConditionResult { condition: self, measured, threshold, passed, weight: self.weight() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConditionResult {
+    pub condition: Condition,
+    pub measured: f64,
+    pub threshold: f64,
+    pub passed: bool,
+    pub weight: u8,
+}
+
+impl ConditionResult {
+    fn describe(&self) -> String {
+        let relation = match self.condition.comparison() {
+            Comparison::Band => "at or above",
+            Comparison::Floor => "below",
+            Comparison::Ceiling => "above",
+        };
+        format!("{} {:.3} {} {:.3}", self.condition.as_str(), self.measured, relation, self.threshold)
+    }
+}
+
+/// All nine conditions against the default thresholds.
+pub fn evaluate_conditions(summary: &EthicsSummary) -> [ConditionResult; 9] {
+    evaluate_conditions_with(summary, &ParamRegistry::default())
+}
+
+/// All nine conditions against the thresholds in `params`, in `Condition::ALL` order.
+pub fn evaluate_conditions_with(summary: &EthicsSummary, params: &ParamRegistry) -> [ConditionResult; 9] {
+    Condition::ALL.map(|c| c.evaluate(summary, params.get(c.param())))
+}
+
+/// Regulator outcome, ordered by severity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "decision")]
+pub enum EthicsDecision {
+    Allow,
+    Warn { reason: String },
+    ForceRepair { reason: String },
+    HaltAndReview { reason: String },
+}
+
+impl EthicsDecision {
+    pub fn severity(&self) -> u8 {
+        match self {
+            EthicsDecision::Allow => 0,
+            EthicsDecision::Warn { .. } => 1,
+            EthicsDecision::ForceRepair { .. } => 2,
+            EthicsDecision::HaltAndReview { .. } => 3,
+        }
+    }
+
+    fn at(severity: u8, reason: String) -> Self {
+        match severity {
+            0 => EthicsDecision::Allow,
+            1 => EthicsDecision::Warn { reason },
+            2 => EthicsDecision::ForceRepair { reason },
+            _ => EthicsDecision::HaltAndReview { reason },
+        }
+    }
+}
+
+/// The Regulator's decision over an evaluated condition array, aggregated
+/// as the module docs describe.
+pub fn decide(results: &[ConditionResult; 9]) -> EthicsDecision {
+    let failed = || results.iter().filter(|r| !r.passed);
+    let severity = failed().filter(|r| !r.condition.escalates()).map(|r| r.weight).max().unwrap_or(0);
+    let mut reasons: Vec<String> =
+        failed().filter(|r| !r.condition.escalates() && r.weight == severity).map(ConditionResult::describe).collect();
+    let mut decided = severity;
+    if severity < 2 {
+        for r in failed().filter(|r| r.condition.escalates()) {
+            decided = severity + 1;
+            reasons.push(r.describe());
+        }
+    }
+    EthicsDecision::at(decided, reasons.join("; "))
+}
+
+/// The condition array and the decision derived from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EthicsEvaluation {
+    pub conditions: [ConditionResult; 9],
+    pub decision: EthicsDecision,
+}
+
+impl EthicsEvaluation {
+    pub fn new(summary: &EthicsSummary, params: &ParamRegistry) -> Self {
+        let conditions = evaluate_conditions_with(summary, params);
+        Self { decision: decide(&conditions), conditions }
+    }
+
+    /// Prometheus text exposition: one gauge per condition and field, plus
+    /// the decision severity.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for (name, pick) in [
+            ("cof_ethics_condition_passed", (|r: &ConditionResult| f64::from(u8::from(r.passed))) as fn(&ConditionResult) -> f64),
+            ("cof_ethics_condition_measured", |r| r.measured),
+            ("cof_ethics_condition_threshold", |r| r.threshold),
+            ("cof_ethics_condition_weight", |r| f64::from(r.weight)),
+        ] {
+            writeln!(out, "# TYPE {} gauge", name).unwrap();
+            for r in &self.conditions {
+                writeln!(out, "{}{{condition=\"{}\"}} {}", name, r.condition.as_str(), pick(r)).unwrap();
+            }
+        }
+        out.push_str("# TYPE cof_ethics_decision_severity gauge\n");
+        writeln!(out, "cof_ethics_decision_severity {}", self.decision.severity()).unwrap();
+        out
+    }
+}
@@ -1,31 +1,46 @@
+use std::time::Instant;
+
+use tracing::{field, info_span};
+
+use crate::cooldown::CooldownViolation;
 use crate::ledger::deed_event::{DeedError, DeedEvent};
-use crate::compliance::eco_reg::EcoRegEnvelope;
-use crate::compliance::ethics::EthicsContext;
+use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::token_ledger::TokenLedger;
+use crate::compliance::batch::DeedRules;
 
+/// Runs inside a `validation` span recording the actor, deed type and
+/// decision (`ok` or the violation).
 pub fn validate_deed(
     event: &DeedEvent,
     roh: f64,
     decay: f64,
 ) -> Result<(), DeedError> {
-    event.validate_biophysical(roh, decay)?;
-
-    let eco = EcoRegEnvelope::default();
-    if !eco.within_bounds(roh, decay) {
-        return Err(DeedError::InvariantViolation(
-            "EcoReg envelope breach".to_string(),
-        ));
-    }
-
-    let ctx = EthicsContext {
-        flags: event.ethics_flags.clone(),
-        life_harm_flag: event.life_harm_flag,
-    };
+    let span = info_span!(
+        "validation",
+        actor = %event.actor_id,
+        deed_type = %event.deed_type,
+        decision = field::Empty,
+        latency_us = field::Empty,
+    );
+    let _entered = span.enter();
+    let started = Instant::now();
+    let result = check_deed(event, roh, decay);
+    span.record("decision", match &result {
+        Ok(()) => "ok".to_string(),
+        Err(e) => e.to_string(),
+    });
+    span.record("latency_us", started.elapsed().as_micros() as u64);
+    result
+}
 
-    if !ctx.is_clean() {
-        return Err(DeedError::InvariantViolation(
-            "Ethics flags present".to_string(),
-        ));
-    }
+/// The check that needs the ledger: whether `event` falls into its
+/// category's cooldown. A violation does not reject the deed; the ledger
+/// stores it `cooldown_suppressed` and it mints nothing.
+pub fn validate_cooldown(ledger: &TokenLedger, event: &DeedEvent) -> Result<(), CooldownViolation> {
+    ledger.cooldown(event).map_or(Ok(()), Err)
+}
 
-    Ok(())
+fn check_deed(event: &DeedEvent, roh: f64, decay: f64) -> Result<(), DeedError> {
+    let metrics = BioloadMetrics::new(0.0, roh, decay);
+    DeedRules::compliance().first_independent_failure(event, &metrics).map_or(Ok(()), |(_, e)| Err(e))
 }
@@ -1,11 +1,92 @@
+use std::collections::BTreeMap;
+
+use param_registry::ChangePolicy;
 use serde::{Deserialize, Serialize};
 
+use crate::anomaly::AnomalyPolicy;
+use crate::audit::AuditPolicy;
+use crate::cold_storage::ColdStoragePolicy;
+use crate::compliance::data_minimization::MinimizationPolicy;
+use crate::halt_review::FreezePolicy;
+use crate::identity::IdentityPolicy;
+use crate::ledger::concentration::ConcentrationPolicy;
+use crate::near_miss::NearMissPolicy;
+use crate::notifications::NotificationPolicy;
+use crate::obligations::ObligationPolicy;
+use crate::providers::ProviderPolicy;
+use crate::quorum::QuorumPolicy;
+use crate::report::ReportPolicy;
+use crate::residency::ResidencyPolicy;
+use crate::sponsor::equity::RewardEquityPolicy;
+use crate::sponsor::pool::PoolPolicy;
+use crate::submission::SubmissionPolicy;
+use crate::targets::TargetRegistry;
+use crate::token::repair_curve::RepairRewardCurve;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LedgerConfig {
     pub roh_max: f64,
     pub decay_max: f64,
+    /// CHURCH minted per unit of bioload reduction.
     pub token_reward_factor: u64,
-    pub repair_pwr_threshold: f64,
+    /// Upper bound on the CHURCH any single deed mints.
+    pub max_church_per_deed: u64,
+    /// Deed types whose bioload reductions mint CHURCH.
+    pub church_deed_types: Vec<String>,
+    /// FEAR accrued by an account when the regulator moves it to Warn.
+    pub fear_on_warn: u64,
+    /// FEAR accrued by an account when the regulator moves it to ForceRepair.
+    pub fear_on_force_repair: u64,
+    /// Fraction of every FEAR balance removed per decay run, in [0, 1].
+    pub fear_decay_rate: f64,
+    pub fear_decay_every_secs: u64,
+    /// Upper bound on any single account's TECH balance.
+    pub tech_cap: u64,
+    /// Operator roles allowed to tombstone deeds in the open segment.
+    pub correction_roles: Vec<String>,
+    /// Raw-neural-data guard applied to neuro deeds on append.
+    pub minimization: MinimizationPolicy,
+    /// Sponsor pool tithe, top-up, recycling and alert rules.
+    pub pool: PoolPolicy,
+    /// Impact-to-PWR curve, diminishing returns and cap for repair grants.
+    pub repair_curve: RepairRewardCurve,
+    /// Overrides for informational registry parameters, by name.
+    pub param_overrides: BTreeMap<String, serde_json::Value>,
+    /// Authorities and freshness for `parameter_change` deeds.
+    pub param_governance: ChangePolicy,
+    /// Slice size, energy pacing and alerting of the idle-time self-audit.
+    pub audit: AuditPolicy,
+    /// Escrow share and sweep cadence for category follow-ups.
+    pub obligations: ObligationPolicy,
+    /// Near-miss clustering, corroboration credit and digest delivery.
+    pub near_miss: NearMissPolicy,
+    /// Minting anomaly scoring, holds and alerting.
+    pub anomaly: AnomalyPolicy,
+    /// Actor key purposes, recovery quorum, waiting period and contacts.
+    pub identity: IdentityPolicy,
+    /// Validator standing, vote credit and timeouts for validation quorums.
+    pub quorum: QuorumPolicy,
+    /// Actor and microspace jurisdictions and which sinks may receive each.
+    pub residency: ResidencyPolicy,
+    /// Registered beneficiaries: display name, region and category.
+    pub targets: TargetRegistry,
+    /// Where the monthly stewardship report is written, if anywhere.
+    pub report: ReportPolicy,
+    /// Consent-expiry lead times, outbox size and store of subject notifications.
+    pub notifications: NotificationPolicy,
+    /// High-impact classification, queueing and lift quorum under a regulator halt.
+    pub freeze: FreezePolicy,
+    /// Clinical attestation provider scoring window, suspension floor and alerts.
+    pub providers: ProviderPolicy,
+    /// Equity classes, per-class reward share bounds and the equity report cadence.
+    pub reward_equity: RewardEquityPolicy,
+    /// Dust threshold and system accounts left out of the POWER Gini, and its exact-check cadence.
+    pub concentration: ConcentrationPolicy,
+    /// Duplicate submission window, its in-memory bound and window file.
+    pub submissions: SubmissionPolicy,
+    /// Segment directory, cold tier, migration age and read cache size.
+    pub cold_storage: ColdStoragePolicy,
 }
 
 impl Default for LedgerConfig {
@@ -14,7 +95,35 @@ impl Default for LedgerConfig {
             roh_max: 0.3,
             decay_max: 1.0,
             token_reward_factor: 100,
-            repair_pwr_threshold: 0.8,
+            max_church_per_deed: 500,
+            church_deed_types: vec!["ecological_sustainability".to_string(), "watershed_cleanup".to_string()],
+            fear_on_warn: 10,
+            fear_on_force_repair: 25,
+            fear_decay_rate: 0.1,
+            fear_decay_every_secs: 3600,
+            tech_cap: 1000,
+            correction_roles: vec!["Host".to_string(), "Regulator".to_string()],
+            minimization: MinimizationPolicy::default(),
+            pool: PoolPolicy::default(),
+            repair_curve: RepairRewardCurve::default(),
+            param_overrides: BTreeMap::new(),
+            param_governance: ChangePolicy::default(),
+            audit: AuditPolicy::default(),
+            obligations: ObligationPolicy::default(),
+            near_miss: NearMissPolicy::default(),
+            anomaly: AnomalyPolicy::default(),
+            identity: IdentityPolicy::default(),
+            quorum: QuorumPolicy::default(),
+            residency: ResidencyPolicy::default(),
+            targets: TargetRegistry::default(),
+            report: ReportPolicy::default(),
+            notifications: NotificationPolicy::default(),
+            freeze: FreezePolicy::default(),
+            providers: ProviderPolicy::default(),
+            reward_equity: RewardEquityPolicy::default(),
+            concentration: ConcentrationPolicy::default(),
+            submissions: SubmissionPolicy::default(),
+            cold_storage: ColdStoragePolicy::default(),
         }
     }
 }
@@ -0,0 +1,128 @@
+//! Per-category minting cooldowns.
+//!
+//! A taxonomy category can declare a `cooldown`: the shortest gap between
+//! two mint-bearing deeds of that category by one actor and, with
+//! `per_target`, against one target, so two actors cannot both claim the
+//! same cleanup of the same watershed on the same day.
+//!
+//! A deed submitted during its cooldown is not rejected. `TokenLedger::append`
+//! (and `append_sim`) store it with `cooldown_suppressed: true` in its
+//! context, so the activity stays on record but the deed mints nothing
+//! (`mint_church` pays zero) and never restarts the cooldown itself.
+//!
+//! There is no cooldown table: the state is read back from the chain. A
+//! deed that was tombstoned or whose validation quorum rejected it does
+//! not block the next one.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::builders::schema_for;
+use crate::ledger::deed_event::{hash_deed, DeedEvent};
+use crate::ledger::token_ledger::TokenLedger;
+use crate::quorum::{pending_validations, ValidationOutcome, ValidationStatus};
+
+pub const COOLDOWN_ACTIVE: &str = "COOLDOWN_ACTIVE";
+/// Context key marking a deed logged during its cooldown.
+pub const COOLDOWN_SUPPRESSED: &str = "cooldown_suppressed";
+
+/// What a cooldown is counted against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "id")]
+pub enum CooldownScope {
+    Actor(String),
+    Target(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[error("{code}: {deed_type} cooling down for {scope:?} until {next_eligible_at}")]
+pub struct CooldownViolation {
+    /// Always `COOLDOWN_ACTIVE`.
+    pub code: String,
+    pub deed_type: String,
+    pub scope: CooldownScope,
+    /// The earlier deed the cooldown runs from.
+    pub blocking_event_id: String,
+    /// Unix seconds from which a deed in this scope mints again.
+    pub next_eligible_at: i64,
+}
+
+/// The deed was logged during its cooldown and mints nothing.
+pub fn is_suppressed(deed: &DeedEvent) -> bool {
+    deed.context_json[COOLDOWN_SUPPRESSED] == true
+}
+
+/// Mark `deed` as logged during its cooldown, rehashing it.
+pub(crate) fn suppress(mut deed: DeedEvent) -> DeedEvent {
+    deed.context_json[COOLDOWN_SUPPRESSED] = serde_json::Value::Bool(true);
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+/// The cooldown `deed` falls into, given the deeds before it. Only
+/// mint-bearing deeds of the same category count: not suppressed ones,
+/// and not the `slashed` event ids. Of several blocking deeds, the one
+/// whose cooldown ends last is reported.
+pub fn cooldown_violation<'a>(
+    history: impl IntoIterator<Item = &'a DeedEvent>,
+    slashed: &HashSet<String>,
+    deed: &DeedEvent,
+) -> Option<CooldownViolation> {
+    let spec = schema_for(&deed.deed_type)?.cooldown?;
+    let mut worst: Option<CooldownViolation> = None;
+    for earlier in history {
+        if earlier.deed_type != deed.deed_type || is_suppressed(earlier) || slashed.contains(&earlier.event_id) {
+            continue;
+        }
+        let next_eligible_at = earlier.timestamp + spec.interval_secs();
+        if next_eligible_at <= deed.timestamp || worst.as_ref().is_some_and(|w| w.next_eligible_at >= next_eligible_at) {
+            continue;
+        }
+        let scope = if earlier.actor_id == deed.actor_id {
+            CooldownScope::Actor(deed.actor_id.clone())
+        } else if let Some(target) = deed.target_ids.iter().find(|t| spec.per_target && earlier.target_ids.contains(t)) {
+            CooldownScope::Target(target.clone())
+        } else {
+            continue;
+        };
+        worst = Some(CooldownViolation {
+            code: COOLDOWN_ACTIVE.to_string(),
+            deed_type: deed.deed_type.clone(),
+            scope,
+            blocking_event_id: earlier.event_id.clone(),
+            next_eligible_at,
+        });
+    }
+    worst
+}
+
+/// Live deeds that no longer count as minted: tombstoned, or rejected by
+/// their validation quorum.
+pub(crate) fn slashed_ids(ledger: &TokenLedger) -> HashSet<String> {
+    let rejected = pending_validations(ledger).into_iter().filter_map(|p| match p.status {
+        ValidationStatus::Resolved { outcome: ValidationOutcome::Rejected, .. } => Some(p.deed_event_id),
+        _ => None,
+    });
+    ledger.deeds().iter().filter(|d| ledger.is_tombstoned(&d.event_id)).map(|d| d.event_id.clone()).chain(rejected).collect()
+}
+
+impl TokenLedger {
+    /// The cooldown `deed` would fall into if appended to the live chain now.
+    pub fn cooldown(&self, deed: &DeedEvent) -> Option<CooldownViolation> {
+        schema_for(&deed.deed_type)?.cooldown?;
+        cooldown_violation(self.deeds(), &slashed_ids(self), deed)
+    }
+
+    /// `cooldown` inside simulation run `run_id`: the live chain up to the
+    /// tip the run branched from, then the run's own deeds.
+    pub fn sim_cooldown(&self, run_id: &str, deed: &DeedEvent) -> Option<CooldownViolation> {
+        schema_for(&deed.deed_type)?.cooldown?;
+        let run = self.sim_run(run_id)?;
+        let live_tip = run.deeds().first().and_then(|root| root.context_json["live_tip"].as_str());
+        let branch = live_tip.and_then(|tip| self.deeds().iter().rposition(|d| d.self_hash == tip)).map_or(0, |pos| pos + 1);
+        cooldown_violation(self.deeds()[..branch].iter().chain(run.deeds()), &slashed_ids(self), deed)
+    }
+}
@@ -0,0 +1,570 @@
+//! Seeded, realistic multi-actor ledgers for tests.
+//!
+//! `LedgerScenarioBuilder` declares actors (with a class label and a join
+//! time), deed timelines per taxonomy category, injected harm events and
+//! the ledger config, and `build` drives the production ledger through
+//! them in time order: each deed is appended and rewarded the way a node
+//! would (`reward_with_follow_ups`, or held for its validation quorum), and
+//! each harm is a life-harm deed plus the regulator's Warn FEAR. Actors in
+//! categories that name a provider attest through one of their own,
+//! registered at the scenario start.
+//!
+//! Ledger-written deeds get wall-clock timestamps and random event ids, so
+//! the finished chain is normalized: those deeds are stamped with the
+//! scenario time that caused them, every event id is redrawn from the seed
+//! (references in targets and contexts follow), the chain is rehashed and
+//! then replayed. The same seed and declarations therefore give the same
+//! ledger file byte for byte.
+//!
+//! Alongside the ledger comes a `ScenarioManifest` of expected aggregates
+//! (balances, escrow, standings, category and day streaks) computed from
+//! the declarations alone by a separate reference implementation, so a
+//! feature test can assert against ground truth rather than against the
+//! code under test. The ledger records no consent or key state, so actors
+//! carry only a class label.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use uuid::Builder;
+
+use crate::config::LedgerConfig;
+use crate::history::Standing;
+use crate::ledger::builders::{schema_for, FieldKind, FieldSpec};
+use crate::ledger::deed_event::{hash_deed, DeedEvent, ExecutionDomain};
+use crate::ledger::token_ledger::{FearTrigger, TokenLedger, TokenLedgerError, LEDGER_ACTOR};
+use crate::obligations::{reward_with_follow_ups, ObligationError};
+use crate::providers::{names_provider, register_provider, ProviderError, PROVIDER_ID};
+use crate::quorum::{hold_if_required, required_validations, QuorumError};
+
+/// Deed type of an injected harm event.
+pub const HARM_EVENT: &str = "harm_event";
+
+const DAY_SECS: i64 = 86_400;
+const BPS: u128 = 10_000;
+
+#[derive(Error, Debug)]
+pub enum ScenarioError {
+    #[error("actor {0} is declared twice")]
+    DuplicateActor(String),
+    #[error("unknown actor {0}")]
+    UnknownActor(String),
+    #[error("{0} is not a taxonomy category")]
+    UnknownCategory(String),
+    #[error("timeline of {actor} in {deed_type} has no deeds")]
+    EmptyTimeline { actor: String, deed_type: String },
+    #[error("timeline of {actor} in {deed_type} repeats every {every_secs}s; the interval must be positive")]
+    NonPositiveInterval { actor: String, deed_type: String, every_secs: i64 },
+    #[error("{actor} acts at {at}, before joining at {joins_at}")]
+    BeforeJoin { actor: String, at: i64, joins_at: i64 },
+    #[error("event at {at} precedes the scenario start {start}")]
+    BeforeStart { at: i64, start: i64 },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error(transparent)]
+    Obligation(#[from] ObligationError),
+    #[error(transparent)]
+    Quorum(#[from] QuorumError),
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+/// SplitMix64; enough for reproducible fixture values without a dependency.
+#[derive(Debug, Clone)]
+struct Seeded(u64);
+
+impl Seeded {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn event_id(&mut self) -> String {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.next().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.next().to_le_bytes());
+        Builder::from_random_bytes(bytes).into_uuid().to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActorSpec {
+    pub id: String,
+    /// Free-form label, e.g. `resident` or `clinician`.
+    pub class: String,
+    /// Unix seconds; the actor may not act before.
+    pub joins_at: i64,
+}
+
+/// `count` deeds of one category by one actor, `every_secs` apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timeline {
+    pub actor: String,
+    pub deed_type: String,
+    /// Unix seconds of the first deed.
+    pub first_at: i64,
+    pub count: u32,
+    pub every_secs: i64,
+    /// CHURCH reward per deed, before escrow and tithe.
+    pub reward: u64,
+}
+
+impl Timeline {
+    /// One deed at `first_at` with a reward of 10; widen with the setters.
+    pub fn new(actor: &str, deed_type: &str, first_at: i64) -> Self {
+        Self { actor: actor.to_string(), deed_type: deed_type.to_string(), first_at, count: 1, every_secs: DAY_SECS, reward: 10 }
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn every(mut self, secs: i64) -> Self {
+        self.every_secs = secs;
+        self
+    }
+
+    pub fn reward(mut self, reward: u64) -> Self {
+        self.reward = reward;
+        self
+    }
+
+    fn times(&self) -> impl Iterator<Item = i64> + '_ {
+        (0..i64::from(self.count)).map(move |i| self.first_at + i * self.every_secs)
+    }
+}
+
+/// A life-harm deed by `actor`, answered with a regulator Warn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarmEvent {
+    pub actor: String,
+    pub at: i64,
+    pub description: String,
+}
+
+enum Step<'a> {
+    Deed { timeline: &'a Timeline, n: u32 },
+    Harm(&'a HarmEvent),
+}
+
+#[derive(Debug, Clone)]
+pub struct LedgerScenarioBuilder {
+    seed: u64,
+    start: i64,
+    config: LedgerConfig,
+    actors: Vec<ActorSpec>,
+    timelines: Vec<Timeline>,
+    harms: Vec<HarmEvent>,
+}
+
+impl LedgerScenarioBuilder {
+    /// An empty scenario starting at `start` (Unix seconds).
+    pub fn new(seed: u64, start: i64) -> Self {
+        Self { seed, start, config: LedgerConfig::default(), actors: Vec::new(), timelines: Vec::new(), harms: Vec::new() }
+    }
+
+    pub fn config(mut self, config: LedgerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// An actor present from the scenario start.
+    pub fn actor(self, id: &str, class: &str) -> Self {
+        let start = self.start;
+        self.actor_joining(id, class, start)
+    }
+
+    pub fn actor_joining(mut self, id: &str, class: &str, joins_at: i64) -> Self {
+        self.actors.push(ActorSpec { id: id.to_string(), class: class.to_string(), joins_at });
+        self
+    }
+
+    pub fn timeline(mut self, timeline: Timeline) -> Self {
+        self.timelines.push(timeline);
+        self
+    }
+
+    pub fn harm(mut self, actor: &str, at: i64, description: &str) -> Self {
+        self.harms.push(HarmEvent { actor: actor.to_string(), at, description: description.to_string() });
+        self
+    }
+
+    fn check(&self) -> Result<(), ScenarioError> {
+        let mut seen = BTreeMap::new();
+        for a in &self.actors {
+            if seen.insert(a.id.as_str(), a.joins_at).is_some() {
+                return Err(ScenarioError::DuplicateActor(a.id.clone()));
+            }
+        }
+        let acting = |actor: &str, at: i64| {
+            let joins_at = *seen.get(actor).ok_or_else(|| ScenarioError::UnknownActor(actor.to_string()))?;
+            if at < self.start {
+                return Err(ScenarioError::BeforeStart { at, start: self.start });
+            }
+            if at < joins_at {
+                return Err(ScenarioError::BeforeJoin { actor: actor.to_string(), at, joins_at });
+            }
+            Ok(())
+        };
+        for t in &self.timelines {
+            if schema_for(&t.deed_type).is_none() {
+                return Err(ScenarioError::UnknownCategory(t.deed_type.clone()));
+            }
+            if t.count == 0 {
+                return Err(ScenarioError::EmptyTimeline { actor: t.actor.clone(), deed_type: t.deed_type.clone() });
+            }
+            if t.count > 1 && t.every_secs <= 0 {
+                return Err(ScenarioError::NonPositiveInterval {
+                    actor: t.actor.clone(),
+                    deed_type: t.deed_type.clone(),
+                    every_secs: t.every_secs,
+                });
+            }
+            acting(&t.actor, t.first_at)?;
+        }
+        for h in &self.harms {
+            acting(&h.actor, h.at)?;
+        }
+        Ok(())
+    }
+
+    /// Every step in time order; ties keep declaration order, timelines first.
+    fn steps(&self) -> Vec<(i64, Step<'_>)> {
+        let mut steps: Vec<(i64, Step<'_>)> = Vec::new();
+        for t in &self.timelines {
+            steps.extend(t.times().zip(0..).map(|(at, n)| (at, Step::Deed { timeline: t, n })));
+        }
+        steps.extend(self.harms.iter().map(|h| (h.at, Step::Harm(h))));
+        steps.sort_by_key(|(at, _)| *at);
+        steps
+    }
+
+    /// Validate the declarations, build the ledger and compute the manifest.
+    pub fn build(&self) -> Result<Scenario, ScenarioError> {
+        self.check()?;
+        let mut rng = Seeded(self.seed);
+        let mut ledger = TokenLedger::new(self.config.clone());
+        let role = self.config.correction_roles.first().cloned().unwrap_or_default();
+        let mut registered = BTreeSet::new();
+        for t in self.timelines.iter().filter(|t| names_provider(&t.deed_type)) {
+            if registered.insert(t.actor.as_str()) {
+                register_provider(&mut ledger, &fixture_provider(&t.actor), &t.actor, &role, self.start)?;
+            }
+        }
+        // Scenario time of every deed; ledger-written deeds take their step's.
+        let mut times = vec![self.start; ledger.deeds().len()];
+        for (at, step) in self.steps() {
+            match step {
+                Step::Deed { timeline, n } => {
+                    let context = context_for(&timeline.deed_type, &timeline.actor, n, &mut rng);
+                    let event_id =
+                        append_actor_deed(&mut ledger, &timeline.actor, &timeline.deed_type, context, false, at, &mut rng)?;
+                    if hold_if_required(&mut ledger, &event_id, timeline.reward, at)?.is_none() {
+                        reward_with_follow_ups(&mut ledger, &event_id, timeline.reward)?;
+                    }
+                }
+                Step::Harm(harm) => {
+                    let context = json!({ "description": harm.description });
+                    append_actor_deed(&mut ledger, &harm.actor, HARM_EVENT, context, true, at, &mut rng)?;
+                    ledger.open_account(&harm.actor, &harm.actor);
+                    ledger.on_regulator_transition(&harm.actor, FearTrigger::Warn, &harm.description)?;
+                }
+            }
+            times.resize(ledger.deeds().len(), at);
+        }
+        let deeds = normalize(ledger.deeds(), &times, &mut rng);
+        let ledger = TokenLedger::replay(self.config.clone(), deeds)?;
+        Ok(Scenario { ledger, manifest: self.reference() })
+    }
+
+    /// Expected aggregates from the declarations alone; see the module docs.
+    fn reference(&self) -> ScenarioManifest {
+        let cfg = &self.config;
+        let tithe = |amount: u64| (amount as u128 * u128::from(cfg.pool.tithe_bps).min(BPS) / BPS) as u64;
+        let mut actors: BTreeMap<String, ExpectedActor> =
+            self.actors.iter().map(|a| (a.id.clone(), ExpectedActor::new(&a.class))).collect();
+        let (mut pool, mut escrow) = (0, 0);
+        let mut days: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+        for (at, step) in self.steps() {
+            let (actor, harm) = match step {
+                Step::Deed { timeline, .. } => (timeline.actor.as_str(), false),
+                Step::Harm(h) => (h.actor.as_str(), true),
+            };
+            let expected = actors.get_mut(actor).expect("checked");
+            days.entry(actor).or_default().push(at.div_euclid(DAY_SECS));
+            expected.deeds += 1;
+            if harm {
+                expected.life_harm += 1;
+                expected.fear += cfg.fear_on_warn;
+                continue;
+            }
+            let Step::Deed { timeline, .. } = step else { unreachable!() };
+            *expected.deeds_by_type.entry(timeline.deed_type.clone()).or_default() += 1;
+            *expected.category_streaks.entry(timeline.deed_type.clone()).or_default() += 1;
+            let held = if required_validations(&timeline.deed_type).is_some() {
+                timeline.reward
+            } else {
+                let follow_ups = schema_for(&timeline.deed_type).map_or(0, |s| s.follow_ups.len());
+                let fraction = if follow_ups == 0 { 0.0 } else { cfg.obligations.escrow_fraction.clamp(0.0, 1.0) };
+                let held = (timeline.reward as f64 * fraction).floor() as u64;
+                let paid = timeline.reward - held;
+                pool += tithe(paid);
+                expected.church += paid - tithe(paid);
+                held
+            };
+            escrow += held;
+            expected.escrowed += held;
+        }
+        for (actor, days) in days {
+            let expected = actors.get_mut(actor).expect("checked");
+            let (mut current, mut longest) = (0, 0);
+            for (i, day) in days.iter().enumerate() {
+                current = match i {
+                    0 => 1,
+                    _ if *day == days[i - 1] => current,
+                    _ if *day == days[i - 1] + 1 => current + 1,
+                    _ => 1,
+                };
+                longest = longest.max(current);
+            }
+            expected.current_streak_days = current;
+            expected.longest_streak_days = longest;
+        }
+        for expected in actors.values_mut() {
+            expected.standing = Standing::from_counts(0, expected.life_harm);
+        }
+        ScenarioManifest { seed: self.seed, actors, pool, escrow }
+    }
+}
+
+/// A context satisfying `deed_type`'s schema, with seeded values.
+fn context_for(deed_type: &str, actor: &str, n: u32, rng: &mut Seeded) -> Value {
+    let schema = schema_for(deed_type).expect("checked");
+    let mut context = serde_json::Map::new();
+    for field in schema.required {
+        context.insert(field.name.to_string(), value_for(field, actor, n, rng));
+    }
+    Value::Object(context)
+}
+
+fn value_for(field: &FieldSpec, actor: &str, n: u32, rng: &mut Seeded) -> Value {
+    let min = field.min.unwrap_or(0.0);
+    let max = field.max.unwrap_or(min + 100.0);
+    match field.kind {
+        FieldKind::String if field.name == PROVIDER_ID => json!(fixture_provider(actor)),
+        FieldKind::String if field.name == "evidence_uri" => json!(format!("ipfs://fixture/{}/{}/{}", actor, field.name, n)),
+        FieldKind::String => json!(format!("{}-{}-{}", field.name, actor, n)),
+        FieldKind::Number => json!(((min + rng.unit() * (max - min)) * 100.0).round() / 100.0),
+        FieldKind::Integer => json!(min as u64 + rng.next() % ((max - min) as u64 + 1)),
+    }
+}
+
+/// The provider `actor`'s attestations name.
+fn fixture_provider(actor: &str) -> String {
+    format!("provider-{}", actor)
+}
+
+fn append_actor_deed(
+    ledger: &mut TokenLedger,
+    actor: &str,
+    deed_type: &str,
+    context_json: Value,
+    life_harm_flag: bool,
+    at: i64,
+    rng: &mut Seeded,
+) -> Result<String, TokenLedgerError> {
+    let tags = schema_for(deed_type).map_or_else(Vec::new, |s| s.tags.iter().map(|t| t.to_string()).collect());
+    let mut deed = DeedEvent {
+        event_id: rng.event_id(),
+        timestamp: at,
+        prev_hash: ledger.last_hash(),
+        self_hash: String::new(),
+        actor_id: actor.to_string(),
+        target_ids: Vec::new(),
+        deed_type: deed_type.to_string(),
+        tags,
+        context_json,
+        ethics_flags: Vec::new(),
+        life_harm_flag,
+        domain: ExecutionDomain::Live,
+        ext: serde_json::Map::new(),
+    };
+    deed.self_hash = hash_deed(&deed);
+    Ok(ledger.append(deed)?.event_id.clone())
+}
+
+/// Restamp ledger-written deeds with `times`, redraw their event ids from
+/// `rng` (rewriting every reference to them) and rehash the chain.
+fn normalize(deeds: &[DeedEvent], times: &[i64], rng: &mut Seeded) -> Vec<DeedEvent> {
+    let ids: BTreeMap<String, String> =
+        deeds.iter().filter(|d| d.actor_id == LEDGER_ACTOR).map(|d| (d.event_id.clone(), rng.event_id())).collect();
+    let mut prev_hash = "0".repeat(64);
+    let mut out = Vec::with_capacity(deeds.len());
+    for (deed, &at) in deeds.iter().zip(times) {
+        let mut deed = deed.clone();
+        if deed.actor_id == LEDGER_ACTOR {
+            deed.timestamp = at;
+        }
+        rename(&mut deed.context_json, &ids);
+        for id in deed.target_ids.iter_mut().chain(std::iter::once(&mut deed.event_id)) {
+            if let Some(new) = ids.get(id.as_str()) {
+                id.clone_from(new);
+            }
+        }
+        deed.prev_hash = std::mem::take(&mut prev_hash);
+        deed.self_hash = String::new();
+        deed.self_hash = hash_deed(&deed);
+        prev_hash.clone_from(&deed.self_hash);
+        out.push(deed);
+    }
+    out
+}
+
+fn rename(value: &mut Value, ids: &BTreeMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(new) = ids.get(s.as_str()) {
+                s.clone_from(new);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| rename(v, ids)),
+        Value::Object(map) => map.values_mut().for_each(|v| rename(v, ids)),
+        _ => {}
+    }
+}
+
+/// What the reference implementation expects of one actor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedActor {
+    pub class: String,
+    /// Live deeds by the actor, harm events included.
+    pub deeds: usize,
+    pub deeds_by_type: BTreeMap<String, u32>,
+    pub life_harm: usize,
+    pub standing: Standing,
+    pub church: u64,
+    pub fear: u64,
+    /// Reward of theirs sitting in the obligations escrow.
+    pub escrowed: u64,
+    /// `obligations::category_streak` per category.
+    pub category_streaks: BTreeMap<String, u32>,
+    /// Consecutive UTC days with a deed, as `cof-inspect` counts them.
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+impl ExpectedActor {
+    fn new(class: &str) -> Self {
+        Self {
+            class: class.to_string(),
+            deeds: 0,
+            deeds_by_type: BTreeMap::new(),
+            life_harm: 0,
+            standing: Standing::Good,
+            church: 0,
+            fear: 0,
+            escrowed: 0,
+            category_streaks: BTreeMap::new(),
+            current_streak_days: 0,
+            longest_streak_days: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioManifest {
+    pub seed: u64,
+    pub actors: BTreeMap<String, ExpectedActor>,
+    /// CHURCH in the sponsor pool.
+    pub pool: u64,
+    /// CHURCH in the obligations escrow.
+    pub escrow: u64,
+}
+
+/// A built scenario: the replayed ledger and its expected aggregates.
+#[derive(Debug)]
+pub struct Scenario {
+    pub ledger: TokenLedger,
+    pub manifest: ScenarioManifest,
+}
+
+impl Scenario {
+    /// The chain as a JSONL deed log, one `DeedEvent` per line.
+    pub fn to_jsonl(&self) -> String {
+        self.ledger.deeds().iter().map(|d| serde_json::to_string(d).expect("deeds serialize") + "\n").collect()
+    }
+
+    /// Write the ledger file to `path` and the manifest beside it as
+    /// `<path>.manifest.json`.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_jsonl())?;
+        let mut manifest = path.as_os_str().to_owned();
+        manifest.push(".manifest.json");
+        fs::write(manifest, serde_json::to_vec_pretty(&self.manifest).expect("manifest serializes"))
+    }
+}
+
+fn tithed(bps: u32) -> LedgerConfig {
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.tithe_bps = bps;
+    cfg
+}
+
+/// Five neighbours over eight weeks: weekly planting, daily meal service,
+/// lessons every other day and a late joiner.
+pub fn small_community(seed: u64, start: i64) -> LedgerScenarioBuilder {
+    LedgerScenarioBuilder::new(seed, start)
+        .config(tithed(500))
+        .actor("alice", "resident")
+        .actor("bob", "resident")
+        .actor("carol", "volunteer")
+        .actor("dan", "educator")
+        .actor_joining("erin", "resident", start + 21 * DAY_SECS)
+        .timeline(Timeline::new("alice", "ecological_sustainability", start).count(8).every(7 * DAY_SECS).reward(40))
+        .timeline(Timeline::new("bob", "ecological_sustainability", start + 3600).count(4).every(14 * DAY_SECS).reward(60))
+        .timeline(Timeline::new("carol", "homelessness_relief", start + 2 * 3600).count(10).every(DAY_SECS).reward(15))
+        .timeline(Timeline::new("dan", "math_science_education", start + DAY_SECS).count(12).every(2 * DAY_SECS).reward(12))
+        .timeline(Timeline::new("erin", "homelessness_relief", start + 21 * DAY_SECS).count(5).every(DAY_SECS).reward(15))
+}
+
+/// An actor flooding eco claims every ten minutes, with two harm reports
+/// against them, beside one honest planter.
+pub fn abuse_attempt(seed: u64, start: i64) -> LedgerScenarioBuilder {
+    LedgerScenarioBuilder::new(seed, start)
+        .config(tithed(500))
+        .actor("mallory", "resident")
+        .actor("bob", "resident")
+        .timeline(Timeline::new("mallory", "ecological_sustainability", start).count(48).every(600).reward(95))
+        .timeline(Timeline::new("bob", "ecological_sustainability", start + 1800).count(3).every(DAY_SECS).reward(40))
+        .harm("mallory", start + 4 * 3600, "fabricated planting evidence reported by a neighbour")
+        .harm("mallory", start + 6 * 3600, "evidence URIs reused across claims")
+}
+
+/// A clinic whose attestations wait for a validation quorum, staff who
+/// also plant, and one adverse outcome.
+pub fn clinical_deployment(seed: u64, start: i64) -> LedgerScenarioBuilder {
+    LedgerScenarioBuilder::new(seed, start)
+        .config(tithed(250))
+        .actor("clinic-1", "clinic")
+        .actor("nurse-ana", "clinician")
+        .actor("nurse-ben", "clinician")
+        .timeline(Timeline::new("clinic-1", "clinical_attestation", start).count(6).every(7 * DAY_SECS).reward(200))
+        .timeline(Timeline::new("nurse-ana", "clinical_attestation", start + 3600).count(3).every(10 * DAY_SECS).reward(80))
+        .timeline(Timeline::new("nurse-ben", "ecological_sustainability", start + DAY_SECS).count(5).every(DAY_SECS).reward(20))
+        .harm("nurse-ana", start + 12 * DAY_SECS, "adverse reaction after a mislabelled dose")
+}
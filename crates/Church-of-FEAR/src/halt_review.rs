@@ -0,0 +1,410 @@
+//! What a regulator halt freezes, and how the freeze ends.
+//!
+//! When the Regulator decides `HaltAndReview`, `record_regulator_decision`
+//! logs a `high_impact_frozen` deed naming the decision. From then on, deeds
+//! the `FreezePolicy` counts as high-impact (by deed type, or by a CHURCH
+//! reward at or above `reward_threshold`) are not appended. Depending on the
+//! policy, `screen_submission` either rejects them or queues them as
+//! `frozen_deed_queued` deeds. A queued deed carries the submitted deed and
+//! its metrics and moves no balance: it is pending review, not minted.
+//! Other deeds are unaffected.
+//!
+//! While frozen, every regulator decision is logged as a
+//! `freeze_review_tick` deed with its severity. `lift_freeze` (with
+//! `halt-review`) ends the freeze once the last `clear_ticks_required`
+//! ticks in a row were below `HaltAndReview` and enough freeze authorities
+//! signed the lift. It logs a `high_impact_freeze_lifted` deed, then
+//! readmits the queued deeds in the order they were queued. Each is relinked
+//! to the current tip, keeping its event id and timestamp, and goes through
+//! deed validation, the ledger's guards and the validation quorum like any
+//! other submission.
+//!
+//! Only the ledger writes these four deed types, so a replayed ledger is
+//! frozen exactly when the original was, with the same queue and ticks.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::compliance::regulator::EthicsDecision;
+use crate::config::LedgerConfig;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::token::mint::mint_church;
+
+#[cfg(feature = "halt-review")]
+use crate::compliance::validator::{validate_cooldown, validate_deed};
+#[cfg(feature = "halt-review")]
+use crate::cooldown::CooldownViolation;
+#[cfg(feature = "halt-review")]
+use crate::ledger::deed_event::{hash_deed, validate_bioload_delta};
+#[cfg(feature = "halt-review")]
+use crate::quorum::{hold_if_required, QuorumError};
+
+pub const HIGH_IMPACT_FROZEN: &str = "high_impact_frozen";
+pub const FREEZE_REVIEW_TICK: &str = "freeze_review_tick";
+pub const FROZEN_DEED_QUEUED: &str = "frozen_deed_queued";
+pub const HIGH_IMPACT_FREEZE_LIFTED: &str = "high_impact_freeze_lifted";
+
+/// JSON-RPC error code for a high-impact deed refused under a freeze.
+pub const HIGH_IMPACT_FROZEN_CODE: i64 = 1011;
+
+/// Severity of `EthicsDecision::HaltAndReview`.
+const HALT_SEVERITY: u64 = 3;
+
+/// What happens to a high-impact deed submitted while frozen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrozenSubmission {
+    /// Refuse it; the actor resubmits after the lift.
+    Reject,
+    /// Hold it for review and readmit it on the lift.
+    Queue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreezePolicy {
+    /// Deed types that are high-impact whatever their reward.
+    pub high_impact_types: Vec<String>,
+    /// CHURCH reward at or above which any deed is high-impact. `None`
+    /// classifies by type only.
+    pub reward_threshold: Option<u64>,
+    pub during_freeze: FrozenSubmission,
+    /// Consecutive regulator ticks below `HaltAndReview` a lift needs.
+    pub clear_ticks_required: u32,
+    /// Keyring purpose of the authority keys that may approve a lift.
+    pub lift_purpose: String,
+    /// Distinct authority keys a lift must be signed by.
+    pub lift_threshold: usize,
+    /// Lift requests further than this from the node clock are refused.
+    pub lift_max_age_secs: i64,
+}
+
+impl Default for FreezePolicy {
+    fn default() -> Self {
+        Self {
+            high_impact_types: vec!["clinical_attestation".to_string()],
+            reward_threshold: Some(50),
+            during_freeze: FrozenSubmission::Queue,
+            clear_ticks_required: 3,
+            lift_purpose: "freeze-authority".to_string(),
+            lift_threshold: 2,
+            lift_max_age_secs: 3600,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FreezeError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[cfg(feature = "halt-review")]
+    #[error(transparent)]
+    Quorum(#[from] QuorumError),
+    #[error("high-impact deeds are already frozen by {0}")]
+    AlreadyFrozen(String),
+    #[error("high-impact deeds are not frozen")]
+    NotFrozen,
+    #[error("deed {event_id} is high-impact and deeds like it are frozen by {freeze_event_id}")]
+    Frozen { freeze_event_id: String, event_id: String },
+    #[error("lift names freeze {got}, but the active freeze is {active}")]
+    WrongFreeze { active: String, got: String },
+    #[error("regulator clear for {got} consecutive ticks, needs {need}")]
+    NotClear { got: u32, need: u32 },
+    #[cfg(feature = "halt-review")]
+    #[error("lift approval: {0}")]
+    Signature(#[from] keyring::KeyringError),
+    #[error("key {0} is not a freeze authority")]
+    NotAuthority(String),
+    #[error("lift has {got} distinct authority approvals, needs {need}")]
+    NotEnoughApprovals { got: usize, need: usize },
+    #[error("lift of {freeze_event_id} requested at {requested_at} is not fresh")]
+    Stale { freeze_event_id: String, requested_at: i64 },
+}
+
+/// A high-impact deed held by the freeze, as submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedDeed {
+    /// The `frozen_deed_queued` deed holding it.
+    pub queue_event_id: String,
+    pub deed: DeedEvent,
+    pub metrics: BioloadMetrics,
+    /// When the ledger queued it.
+    pub queued_at: i64,
+}
+
+/// The active freeze, read back from its deeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreezeStatus {
+    pub freeze_event_id: String,
+    /// The regulator decision that halted.
+    pub decision_ref: String,
+    pub reason: String,
+    pub frozen_at: i64,
+    /// Consecutive review ticks below `HaltAndReview`, latest last.
+    pub clear_ticks: u32,
+    pub clear_ticks_required: u32,
+    /// Oldest first.
+    pub queued: Vec<QueuedDeed>,
+}
+
+/// What `record_regulator_decision` did with one decision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "tick")]
+pub enum FreezeTick {
+    /// Not frozen and no halt: nothing logged.
+    Idle,
+    /// The decision halted; a new freeze started.
+    Frozen { freeze_event_id: String },
+    /// Already frozen; a review tick was logged.
+    Reviewed { freeze_event_id: String, clear_ticks: u32 },
+}
+
+/// Whether a submission may go on to the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "screening")]
+pub enum Screening {
+    Proceed,
+    /// Held by the freeze; nothing was appended.
+    Queued { freeze_event_id: String, queue_event_id: String },
+}
+
+/// Whether `deed` counts as high-impact under `policy`.
+pub fn is_high_impact(config: &LedgerConfig, deed: &DeedEvent, metrics: &BioloadMetrics) -> bool {
+    let policy = &config.freeze;
+    policy.high_impact_types.contains(&deed.deed_type)
+        || policy.reward_threshold.is_some_and(|min| mint_church(deed, metrics, config) >= min)
+}
+
+/// The active freeze, if any, with its review ticks and queue.
+pub fn freeze_status(ledger: &TokenLedger) -> Option<FreezeStatus> {
+    let freeze_event_id = ledger.high_impact_freeze()?;
+    let since: Vec<&DeedEvent> = ledger.deeds().iter().rev().take_while(|d| d.event_id != freeze_event_id).collect();
+    let frozen = ledger.deed(freeze_event_id)?;
+    let mut status = FreezeStatus {
+        freeze_event_id: freeze_event_id.to_string(),
+        decision_ref: frozen.context_json["decision_ref"].as_str().unwrap_or_default().to_string(),
+        reason: frozen.context_json["reason"].as_str().unwrap_or_default().to_string(),
+        frozen_at: frozen.timestamp,
+        clear_ticks: 0,
+        clear_ticks_required: ledger.config().freeze.clear_ticks_required,
+        queued: Vec::new(),
+    };
+    for d in since.into_iter().rev() {
+        match d.deed_type.as_str() {
+            FREEZE_REVIEW_TICK => match d.context_json["severity"].as_u64() {
+                Some(severity) if severity < HALT_SEVERITY => status.clear_ticks += 1,
+                _ => status.clear_ticks = 0,
+            },
+            FROZEN_DEED_QUEUED => {
+                let deed = serde_json::from_value(d.context_json["deed"].clone());
+                let metrics = serde_json::from_value(d.context_json["metrics"].clone());
+                if let (Ok(deed), Ok(metrics)) = (deed, metrics) {
+                    status.queued.push(QueuedDeed { queue_event_id: d.event_id.clone(), deed, metrics, queued_at: d.timestamp });
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(status)
+}
+
+/// Freeze high-impact deeds on behalf of the regulator decision
+/// `decision_ref`. Returns the `high_impact_frozen` deed's event id.
+pub fn freeze_high_impact_deeds(ledger: &mut TokenLedger, decision_ref: &str, reason: &str) -> Result<String, FreezeError> {
+    if let Some(active) = ledger.high_impact_freeze() {
+        return Err(FreezeError::AlreadyFrozen(active.to_string()));
+    }
+    let context = json!({ "decision_ref": decision_ref, "reason": reason });
+    let event_id = ledger.log_halt_review(HIGH_IMPACT_FROZEN, Vec::new(), context)?.event_id.clone();
+    log::warn!("High-impact deeds frozen by regulator decision {}: {}", decision_ref, reason);
+    Ok(event_id)
+}
+
+/// Feed one regulator decision to the freeze: a halt starts a freeze if
+/// none is active, and every decision while frozen is a review tick.
+pub fn record_regulator_decision(
+    ledger: &mut TokenLedger,
+    decision: &EthicsDecision,
+    decision_ref: &str,
+) -> Result<FreezeTick, FreezeError> {
+    let Some(status) = freeze_status(ledger) else {
+        return match decision {
+            EthicsDecision::HaltAndReview { reason } => {
+                Ok(FreezeTick::Frozen { freeze_event_id: freeze_high_impact_deeds(ledger, decision_ref, reason)? })
+            }
+            _ => Ok(FreezeTick::Idle),
+        };
+    };
+    let severity = u64::from(decision.severity());
+    let clear_ticks = if severity < HALT_SEVERITY { status.clear_ticks + 1 } else { 0 };
+    let context = json!({
+        "freeze_event_id": status.freeze_event_id,
+        "decision_ref": decision_ref,
+        "severity": severity,
+        "clear_ticks": clear_ticks,
+    });
+    ledger.log_halt_review(FREEZE_REVIEW_TICK, vec![status.freeze_event_id.clone()], context)?;
+    Ok(FreezeTick::Reviewed { freeze_event_id: status.freeze_event_id, clear_ticks })
+}
+
+/// Decide whether a submission goes on to the ledger. Under an active
+/// freeze a high-impact deed is rejected or, passed through the ledger's
+/// minimization policy first, queued, as the policy says.
+pub fn screen_submission(
+    ledger: &mut TokenLedger,
+    deed: &DeedEvent,
+    metrics: &BioloadMetrics,
+) -> Result<Screening, FreezeError> {
+    let policy = &ledger.config().freeze;
+    let Some(freeze_event_id) = ledger.high_impact_freeze().map(str::to_string) else {
+        return Ok(Screening::Proceed);
+    };
+    if !is_high_impact(ledger.config(), deed, metrics) {
+        return Ok(Screening::Proceed);
+    }
+    if policy.during_freeze == FrozenSubmission::Reject {
+        return Err(FreezeError::Frozen { freeze_event_id, event_id: deed.event_id.clone() });
+    }
+    let deed = ledger.config().minimization.enforce(deed.clone()).map_err(TokenLedgerError::from)?;
+    let context = json!({ "freeze_event_id": freeze_event_id, "deed": deed, "metrics": metrics });
+    let queue_event_id = ledger.log_halt_review(FROZEN_DEED_QUEUED, vec![freeze_event_id.clone()], context)?.event_id.clone();
+    log::info!("Deed {} queued for review under freeze {}", deed.event_id, freeze_event_id);
+    Ok(Screening::Queued { freeze_event_id, queue_event_id })
+}
+
+/// A lift of the active freeze; signed as its JSON encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FreezeLiftRequest {
+    pub freeze_event_id: String,
+    pub memo: String,
+    /// Unix seconds.
+    pub requested_at: i64,
+}
+
+impl FreezeLiftRequest {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("freeze lift serializes")
+    }
+}
+
+#[cfg(feature = "halt-review")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedFreezeLift {
+    pub request: FreezeLiftRequest,
+    pub approvals: Vec<keyring::KeyringSignature>,
+}
+
+/// What became of one queued deed on the lift.
+#[cfg(feature = "halt-review")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum Readmission {
+    /// Appended in its stored form, as `auto_church.mint_deed` would.
+    Readmitted {
+        queue_event_id: String,
+        deed: Box<DeedEvent>,
+        church_minted: u64,
+        pending_validation: Option<String>,
+        cooldown: Option<CooldownViolation>,
+    },
+    /// Failed validation or the ledger's guards; nothing was appended.
+    Refused { queue_event_id: String, event_id: String, reason: String },
+}
+
+#[cfg(feature = "halt-review")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiftedFreeze {
+    pub lift_event_id: String,
+    pub signers: Vec<String>,
+    /// In queue order.
+    pub readmitted: Vec<Readmission>,
+}
+
+/// Lift the active freeze and readmit its queue. The regulator must have
+/// been below `HaltAndReview` for the policy's `clear_ticks_required`
+/// consecutive ticks, and the approvals must verify against `authorities`,
+/// come from keys with the policy's lift purpose, and number at least
+/// `lift_threshold` distinct keys. A ledger failure part-way through the
+/// queue is returned as is; the deeds not yet readmitted stay on record in
+/// their `frozen_deed_queued` deeds.
+#[cfg(feature = "halt-review")]
+pub fn lift_freeze(
+    ledger: &mut TokenLedger,
+    signed: &SignedFreezeLift,
+    authorities: &keyring::VerifyingBundle,
+    now: i64,
+) -> Result<LiftedFreeze, FreezeError> {
+    use keyring::SignatureVerifier;
+
+    let policy = ledger.config().freeze.clone();
+    let request = &signed.request;
+    let status = freeze_status(ledger).ok_or(FreezeError::NotFrozen)?;
+    if request.freeze_event_id != status.freeze_event_id {
+        return Err(FreezeError::WrongFreeze { active: status.freeze_event_id, got: request.freeze_event_id.clone() });
+    }
+    if status.clear_ticks < policy.clear_ticks_required {
+        return Err(FreezeError::NotClear { got: status.clear_ticks, need: policy.clear_ticks_required });
+    }
+    if (now - request.requested_at).abs() > policy.lift_max_age_secs {
+        return Err(FreezeError::Stale { freeze_event_id: request.freeze_event_id.clone(), requested_at: request.requested_at });
+    }
+    let bytes = request.signing_bytes();
+    let mut signers = std::collections::BTreeSet::new();
+    for approval in &signed.approvals {
+        let meta = authorities.key_meta(&approval.key).ok_or_else(|| FreezeError::NotAuthority(approval.key.clone()))?;
+        if meta.purpose != policy.lift_purpose {
+            return Err(FreezeError::NotAuthority(approval.key.clone()));
+        }
+        authorities.verify(&bytes, approval)?;
+        signers.insert(approval.key.clone());
+    }
+    if signers.len() < policy.lift_threshold.max(1) {
+        return Err(FreezeError::NotEnoughApprovals { got: signers.len(), need: policy.lift_threshold.max(1) });
+    }
+
+    let signers: Vec<String> = signers.into_iter().collect();
+    let context = json!({
+        "freeze_event_id": status.freeze_event_id,
+        "memo": request.memo,
+        "signers": signers,
+        "clear_ticks": status.clear_ticks,
+        "queued": status.queued.len(),
+    });
+    let lift_event_id =
+        ledger.log_halt_review(HIGH_IMPACT_FREEZE_LIFTED, vec![status.freeze_event_id.clone()], context)?.event_id.clone();
+    log::info!("Freeze {} lifted ({} signers); readmitting {} deeds", status.freeze_event_id, signers.len(), status.queued.len());
+    let readmitted = status.queued.into_iter().map(|q| readmit(ledger, q, now)).collect::<Result<_, _>>()?;
+    Ok(LiftedFreeze { lift_event_id, signers, readmitted })
+}
+
+/// Put one queued deed through the submission pipeline on the current tip.
+#[cfg(feature = "halt-review")]
+fn readmit(ledger: &mut TokenLedger, queued: QueuedDeed, now: i64) -> Result<Readmission, FreezeError> {
+    let QueuedDeed { queue_event_id, deed, metrics, .. } = queued;
+    let refused = |event_id: String, reason: String| Readmission::Refused { queue_event_id: queue_event_id.clone(), event_id, reason };
+    if ledger.deed(&deed.event_id).is_some() {
+        let reason = format!("deed {} is already on the chain", deed.event_id);
+        return Ok(refused(deed.event_id, reason));
+    }
+    let mut deed = DeedEvent { prev_hash: ledger.last_hash(), self_hash: String::new(), ..deed };
+    deed.self_hash = hash_deed(&deed);
+    if let Err(e) = validate_deed(&deed, metrics.roh, metrics.decay).and_then(|()| validate_bioload_delta(metrics.bioload_delta)) {
+        return Ok(refused(deed.event_id, e.to_string()));
+    }
+    let cooldown = validate_cooldown(ledger, &deed).err();
+    let event_id = deed.event_id.clone();
+    let stored = match ledger.append(deed) {
+        Ok(stored) => stored.clone(),
+        Err(e) => return Ok(refused(event_id, e.to_string())),
+    };
+    let reward = mint_church(&stored, &metrics, ledger.config());
+    let pending_validation = match cooldown {
+        Some(_) => None,
+        None => hold_if_required(ledger, &stored.event_id, reward, now)?,
+    };
+    let church_minted = if pending_validation.is_some() { 0 } else { reward };
+    Ok(Readmission::Readmitted { queue_event_id, deed: Box::new(stored), church_minted, pending_validation, cooldown })
+}
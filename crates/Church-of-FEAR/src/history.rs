@@ -0,0 +1,267 @@
+//! Read-only ledger state as of an earlier tip.
+//!
+//! `TokenLedger::state_at` answers auditor questions such as "what was this
+//! actor's balance and standing on March 1st?" without touching the live
+//! ledger: it replays the chain up to the requested tip into a scratch
+//! ledger and keeps only a `HistoricalView` of it. A deed prefix never
+//! changes once written, so views are cached by tip hash and never go
+//! stale.
+//!
+//! A point is either a tip hash or a timestamp. Chain order is
+//! authoritative: a timestamp resolves to the prefix ending just before the
+//! first deed stamped after it, and `between_events` is set when the last
+//! deed of that prefix was not stamped at exactly the requested second.
+//!
+//! Views are their own types with private fields and getters only. Nothing
+//! converts a view back into a `TokenLedger` or an `Account`, so history
+//! cannot leak into a mutation path. The ledger records no reputation or
+//! consent state, so a view carries balances, standings, supply and the
+//! mint freeze.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{SealedSegment, SupplyReport, TokenLedger, TokenLedgerError, LEDGER_ACTOR};
+
+/// Views kept per ledger; the oldest is dropped first.
+const CACHED_VIEWS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoricalPoint {
+    /// `self_hash` of the last deed to include.
+    Tip(String),
+    /// Unix seconds.
+    Timestamp(i64),
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum HistoryError {
+    #[error("no deed with hash {0} in the live chain")]
+    UnknownTip(String),
+    #[error("no deed at or before {0}")]
+    BeforeGenesis(i64),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Standing {
+    Good,
+    /// At least one deed carries ethics flags.
+    Flagged,
+    /// At least one deed is marked as life harm.
+    LifeHarm,
+}
+
+impl Standing {
+    pub fn from_counts(flagged: usize, life_harm: usize) -> Self {
+        if life_harm > 0 {
+            Standing::LifeHarm
+        } else if flagged > 0 {
+            Standing::Flagged
+        } else {
+            Standing::Good
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Standing::Good => "good",
+            Standing::Flagged => "flagged",
+            Standing::LifeHarm => "life-harm",
+        }
+    }
+}
+
+/// An actor's live deeds as of the view's tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HistoricalStanding {
+    deeds: usize,
+    flagged: usize,
+    life_harm: usize,
+    standing: Standing,
+}
+
+impl HistoricalStanding {
+    pub fn deeds(&self) -> usize {
+        self.deeds
+    }
+
+    pub fn flagged(&self) -> usize {
+        self.flagged
+    }
+
+    pub fn life_harm(&self) -> usize {
+        self.life_harm
+    }
+
+    pub fn standing(&self) -> Standing {
+        self.standing
+    }
+}
+
+/// Ledger state as of one tip. See the module docs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoricalView {
+    tip_hash: String,
+    tip_timestamp: i64,
+    /// Live deeds up to and including the tip.
+    height: usize,
+    /// Sealed segment holding the tip, if it has been sealed since.
+    segment: Option<usize>,
+    balances: BTreeMap<String, BTreeMap<Token, u64>>,
+    standings: BTreeMap<String, HistoricalStanding>,
+    supply: SupplyReport,
+    mint_freeze: Option<String>,
+}
+
+impl HistoricalView {
+    fn of(replayed: &TokenLedger, segments: &[SealedSegment]) -> Self {
+        let tip = replayed.deeds().last().expect("views are taken of non-empty prefixes");
+        let height = replayed.deeds().len();
+        let balances = replayed
+            .accounts()
+            .map(|a| (a.id.clone(), Token::ALL.into_iter().map(|t| (t, a.balance(t))).filter(|&(_, b)| b > 0).collect()))
+            .collect();
+        let mut counts: BTreeMap<String, (usize, usize, usize)> = BTreeMap::new();
+        for d in replayed.live_deeds().filter(|d| d.actor_id != LEDGER_ACTOR) {
+            let c = counts.entry(d.actor_id.clone()).or_default();
+            c.0 += 1;
+            c.1 += usize::from(!d.ethics_flags.is_empty());
+            c.2 += usize::from(d.life_harm_flag);
+        }
+        let standings = counts
+            .into_iter()
+            .map(|(actor, (deeds, flagged, life_harm))| {
+                let standing = Standing::from_counts(flagged, life_harm);
+                (actor, HistoricalStanding { deeds, flagged, life_harm, standing })
+            })
+            .collect();
+        Self {
+            tip_hash: tip.self_hash.clone(),
+            tip_timestamp: tip.timestamp,
+            height,
+            segment: segments.iter().find(|s| s.first < height && height - 1 <= s.last).map(|s| s.index),
+            balances,
+            standings,
+            supply: replayed.supply_report(),
+            mint_freeze: replayed.mint_freeze().map(str::to_string),
+        }
+    }
+
+    pub fn tip_hash(&self) -> &str {
+        &self.tip_hash
+    }
+
+    pub fn tip_timestamp(&self) -> i64 {
+        self.tip_timestamp
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn segment(&self) -> Option<usize> {
+        self.segment
+    }
+
+    /// Zero for unknown accounts and for tokens the account never held.
+    pub fn balance(&self, account_id: &str, token: Token) -> u64 {
+        self.balances.get(account_id).and_then(|b| b.get(&token)).copied().unwrap_or(0)
+    }
+
+    pub fn balances(&self) -> &BTreeMap<String, BTreeMap<Token, u64>> {
+        &self.balances
+    }
+
+    pub fn standing(&self, actor_id: &str) -> Option<&HistoricalStanding> {
+        self.standings.get(actor_id)
+    }
+
+    pub fn standings(&self) -> &BTreeMap<String, HistoricalStanding> {
+        &self.standings
+    }
+
+    pub fn supply(&self) -> &SupplyReport {
+        &self.supply
+    }
+
+    pub fn mint_freeze(&self) -> Option<&str> {
+        self.mint_freeze.as_deref()
+    }
+}
+
+/// A view and how the requested point mapped onto it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateAt {
+    pub view: Arc<HistoricalView>,
+    /// The requested timestamp fell between two deeds, or after the last.
+    pub between_events: bool,
+}
+
+/// Position of the last deed at `point`, and whether a timestamp fell
+/// between deeds.
+pub(crate) fn resolve(deeds: &[DeedEvent], point: &HistoricalPoint) -> Result<(usize, bool), HistoryError> {
+    match point {
+        HistoricalPoint::Tip(hash) => deeds
+            .iter()
+            .rposition(|d| &d.self_hash == hash)
+            .map(|pos| (pos, false))
+            .ok_or_else(|| HistoryError::UnknownTip(hash.clone())),
+        HistoricalPoint::Timestamp(ts) => {
+            let end = deeds.iter().position(|d| d.timestamp > *ts).unwrap_or(deeds.len());
+            let pos = end.checked_sub(1).ok_or(HistoryError::BeforeGenesis(*ts))?;
+            Ok((pos, deeds[pos].timestamp != *ts))
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CachedViews {
+    by_tip: HashMap<String, Arc<HistoricalView>>,
+    /// Tip hashes, oldest first.
+    order: VecDeque<String>,
+}
+
+/// Views by tip hash. Clones start empty.
+#[derive(Debug, Default)]
+pub(crate) struct HistoryCache {
+    views: Mutex<CachedViews>,
+}
+
+impl Clone for HistoryCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl HistoryCache {
+    /// The cached view at `tip_hash`, or the one `build` replays.
+    pub(crate) fn get_or_build(
+        &self,
+        tip_hash: &str,
+        segments: &[SealedSegment],
+        build: impl FnOnce() -> Result<TokenLedger, TokenLedgerError>,
+    ) -> Result<Arc<HistoricalView>, TokenLedgerError> {
+        if let Some(view) = self.views.lock().unwrap_or_else(|e| e.into_inner()).by_tip.get(tip_hash) {
+            return Ok(view.clone());
+        }
+        let view = Arc::new(HistoricalView::of(&build()?, segments));
+        let mut views = self.views.lock().unwrap_or_else(|e| e.into_inner());
+        if views.by_tip.insert(tip_hash.to_string(), view.clone()).is_none() {
+            views.order.push_back(tip_hash.to_string());
+            while views.order.len() > CACHED_VIEWS {
+                let oldest = views.order.pop_front().expect("non-empty");
+                views.by_tip.remove(&oldest);
+            }
+        }
+        Ok(view)
+    }
+}
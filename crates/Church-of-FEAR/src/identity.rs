@@ -0,0 +1,454 @@
+//! Actor keys, rotation and account recovery.
+//!
+//! An `actor_id` is a bare string; with `actor-keys` it is bound to one
+//! keyring key, and `authenticate` accepts signatures by that key only.
+//! Every change of key is a ledger-written deed targeting the actor, so the
+//! actor's own deeds, balances and streaks never move and the lineage of
+//! keys is read back from the chain (`key_lineage`):
+//!
+//! - `actor_key_bound`: the first key, proven by a signature of the key
+//!   itself;
+//! - `actor_key_rotated`: a successor, signed by the current key or, after
+//!   a recovery, authorized by the guardian quorum;
+//! - `account_recovery_started` / `account_recovery_cancelled`: a lost-key
+//!   recovery approved by `recovery_threshold` distinct guardian keys.
+//!
+//! A recovery does not take effect at once. For `recovery_wait_secs` the
+//! account receives no mints or pool payouts, the actor's registered
+//! contact is notified, and the current key may still cancel it; only then
+//! does `complete_recovery` rotate to the recovered key. The ledger tracks
+//! pending recoveries from these deeds, so a replay freezes the same
+//! accounts and rebuilds the same accepted keys.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "actor-keys")]
+use std::collections::BTreeSet;
+
+#[cfg(feature = "actor-keys")]
+use keyring::{KeyMeta, KeyringError, KeyringSignature, SignatureVerifier, VerifyingBundle};
+#[cfg(feature = "actor-keys")]
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "actor-keys")]
+use serde_json::json;
+#[cfg(feature = "actor-keys")]
+use thiserror::Error;
+
+#[cfg(feature = "actor-keys")]
+use crate::ledger::deed_event::DeedEvent;
+#[cfg(feature = "actor-keys")]
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+#[cfg(feature = "actor-keys")]
+use crate::utils::http::post_webhook;
+
+pub const ACTOR_KEY_BOUND: &str = "actor_key_bound";
+pub const ACTOR_KEY_ROTATED: &str = "actor_key_rotated";
+pub const ACCOUNT_RECOVERY_STARTED: &str = "account_recovery_started";
+pub const ACCOUNT_RECOVERY_CANCELLED: &str = "account_recovery_cancelled";
+/// Webhook kind of a `RecoveryNotice`.
+pub const ACCOUNT_RECOVERY: &str = "account_recovery";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdentityPolicy {
+    /// Keyring purpose actor keys must carry.
+    pub actor_key_purpose: String,
+    /// Keyring purpose of the guardian keys that may approve a recovery.
+    pub recovery_purpose: String,
+    /// Distinct guardian keys a recovery must be signed by.
+    pub recovery_threshold: usize,
+    /// Seconds between starting and completing a recovery.
+    pub recovery_wait_secs: i64,
+    /// Largest gap between a signature and its use, in seconds.
+    pub max_signature_age_secs: i64,
+    /// Webhook (`http://host:port/path`) per actor for recovery notices.
+    pub contacts: BTreeMap<String, String>,
+}
+
+impl Default for IdentityPolicy {
+    fn default() -> Self {
+        Self {
+            actor_key_purpose: "actor".to_string(),
+            recovery_purpose: "recovery-guardian".to_string(),
+            recovery_threshold: 2,
+            recovery_wait_secs: 72 * 3600,
+            max_signature_age_secs: 600,
+            contacts: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "actor-keys")]
+#[derive(Error, Debug)]
+pub enum IdentityError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error(transparent)]
+    Signature(#[from] KeyringError),
+    #[error("actor {0} already has a key")]
+    AlreadyBound(String),
+    #[error("actor {0} has no key")]
+    Unbound(String),
+    #[error("key {key} is not the accepted key of {actor_id}")]
+    NotCurrentKey { actor_id: String, key: String },
+    #[error("key {key} has purpose {purpose}, not an actor key")]
+    NotActorKey { key: String, purpose: String },
+    #[error("public key {0} was already used by this actor")]
+    KeyReused(String),
+    #[error("key {0} is not a recovery guardian")]
+    NotGuardian(String),
+    #[error("recovery has {got} distinct guardian approvals, needs {need}")]
+    NotEnoughApprovals { got: usize, need: usize },
+    #[error("signature by {key} dated {signed_at} is not fresh")]
+    Stale { key: String, signed_at: u64 },
+    #[error("actor {actor_id} is under recovery {recovery_event_id}")]
+    RecoveryPending { actor_id: String, recovery_event_id: String },
+    #[error("actor {0} has no pending recovery")]
+    NoRecovery(String),
+    #[error("recovery of {actor_id} completes at {unlock_at}")]
+    RecoveryWaiting { actor_id: String, unlock_at: i64 },
+}
+
+/// What a key change signs: the actor and the key taking over.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyChange {
+    pub actor_id: String,
+    pub new_key: KeyMeta,
+}
+
+#[cfg(feature = "actor-keys")]
+impl KeyChange {
+    pub fn new(actor_id: &str, new_key: &KeyMeta) -> Self {
+        Self { actor_id: actor_id.to_string(), new_key: new_key.clone() }
+    }
+
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("key change serializes")
+    }
+}
+
+/// Guardian approvals of a lost-key recovery, each over
+/// `KeyChange::new(actor_id, new_key).signing_bytes()`.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryQuorum {
+    pub new_key: KeyMeta,
+    pub approvals: Vec<KeyringSignature>,
+}
+
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "by")]
+pub enum KeyAuthority {
+    /// The first key, proving possession of itself.
+    SelfSigned,
+    /// Signed by the key it replaced.
+    PreviousKey { key: String },
+    /// Approved by guardians and waited out.
+    RecoveryQuorum { recovery_event_id: String, signers: Vec<String> },
+}
+
+/// One accepted key of an actor and the deed that installed it.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyLink {
+    pub event_id: String,
+    pub key: KeyMeta,
+    #[serde(flatten)]
+    pub authority: KeyAuthority,
+    pub at: i64,
+}
+
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRecovery {
+    #[serde(default)]
+    pub recovery_event_id: String,
+    pub actor_id: String,
+    pub new_key: KeyMeta,
+    pub signers: Vec<String>,
+    pub started_at: i64,
+    pub unlock_at: i64,
+}
+
+#[cfg(feature = "actor-keys")]
+impl PendingRecovery {
+    fn from_deed(d: &DeedEvent) -> Option<Self> {
+        (d.deed_type == ACCOUNT_RECOVERY_STARTED)
+            .then(|| serde_json::from_value(d.context_json.clone()).ok())
+            .flatten()
+            .map(|r| PendingRecovery { recovery_event_id: d.event_id.clone(), ..r })
+    }
+}
+
+/// Sent to the actor's contact when a recovery starts.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryNotice {
+    pub recovery_event_id: String,
+    pub actor_id: String,
+    pub new_key: String,
+    pub signers: Vec<String>,
+    pub unlock_at: i64,
+}
+
+/// Where recovery notices are pushed.
+#[cfg(feature = "actor-keys")]
+pub trait RecoveryNotifier: Send {
+    fn notify(&self, notice: &RecoveryNotice) -> Result<(), String>;
+}
+
+/// POSTs each notice as JSON to the actor's registered contact.
+#[cfg(feature = "actor-keys")]
+pub struct WebhookRecoveryNotifier {
+    pub url: String,
+}
+
+#[cfg(feature = "actor-keys")]
+impl RecoveryNotifier for WebhookRecoveryNotifier {
+    fn notify(&self, notice: &RecoveryNotice) -> Result<(), String> {
+        post_webhook(&self.url, ACCOUNT_RECOVERY, notice)
+    }
+}
+
+#[cfg(feature = "actor-keys")]
+fn targets(d: &DeedEvent, actor_id: &str) -> bool {
+    d.target_ids.first().is_some_and(|t| t == actor_id)
+}
+
+/// Every key `actor_id` has had, oldest first; the last is accepted.
+#[cfg(feature = "actor-keys")]
+pub fn key_lineage(ledger: &TokenLedger, actor_id: &str) -> Vec<KeyLink> {
+    ledger
+        .deeds()
+        .iter()
+        .filter(|d| (d.deed_type == ACTOR_KEY_BOUND || d.deed_type == ACTOR_KEY_ROTATED) && targets(d, actor_id))
+        .filter_map(|d| {
+            let key = serde_json::from_value(d.context_json["new_key"].clone()).ok()?;
+            let authority = serde_json::from_value(d.context_json["authority"].clone()).ok()?;
+            Some(KeyLink { event_id: d.event_id.clone(), key, authority, at: d.context_json["at"].as_i64()? })
+        })
+        .collect()
+}
+
+/// The only key `authenticate` accepts for `actor_id`.
+#[cfg(feature = "actor-keys")]
+pub fn accepted_key(ledger: &TokenLedger, actor_id: &str) -> Option<KeyMeta> {
+    key_lineage(ledger, actor_id).pop().map(|link| link.key)
+}
+
+/// The recovery holding `actor_id`'s mints, if any.
+#[cfg(feature = "actor-keys")]
+pub fn pending_recovery(ledger: &TokenLedger, actor_id: &str) -> Option<PendingRecovery> {
+    let event_id = ledger.account_recovery(actor_id)?;
+    ledger.deed(event_id).and_then(PendingRecovery::from_deed)
+}
+
+/// Check `sig` over `bytes` against `key` alone.
+#[cfg(feature = "actor-keys")]
+fn verify_by(actor_id: &str, key: &KeyMeta, bytes: &[u8], sig: &KeyringSignature) -> Result<(), IdentityError> {
+    if sig.key != key.name {
+        return Err(IdentityError::NotCurrentKey { actor_id: actor_id.to_string(), key: sig.key.clone() });
+    }
+    let bundle = VerifyingBundle { keys: vec![KeyMeta { superseded_by: None, ..key.clone() }] };
+    Ok(bundle.verify(bytes, sig)?)
+}
+
+#[cfg(feature = "actor-keys")]
+pub(crate) fn check_fresh(sig: &KeyringSignature, now: i64, policy: &IdentityPolicy) -> Result<(), IdentityError> {
+    if (now - sig.signed_at as i64).abs() > policy.max_signature_age_secs {
+        return Err(IdentityError::Stale { key: sig.key.clone(), signed_at: sig.signed_at });
+    }
+    Ok(())
+}
+
+/// The new key must be an actor key this actor has never held.
+#[cfg(feature = "actor-keys")]
+fn check_new_key(ledger: &TokenLedger, actor_id: &str, key: &KeyMeta) -> Result<(), IdentityError> {
+    let purpose = &ledger.config().identity.actor_key_purpose;
+    if &key.purpose != purpose {
+        return Err(IdentityError::NotActorKey { key: key.name.clone(), purpose: key.purpose.clone() });
+    }
+    if key_lineage(ledger, actor_id).iter().any(|l| l.key.public_key == key.public_key) {
+        return Err(IdentityError::KeyReused(key.public_key.clone()));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "actor-keys")]
+fn check_no_recovery(ledger: &TokenLedger, actor_id: &str) -> Result<(), IdentityError> {
+    match ledger.account_recovery(actor_id) {
+        Some(event_id) => {
+            Err(IdentityError::RecoveryPending { actor_id: actor_id.to_string(), recovery_event_id: event_id.to_string() })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Verify that `sig` over `bytes` was made by `actor_id`'s accepted key.
+#[cfg(feature = "actor-keys")]
+pub fn authenticate(ledger: &TokenLedger, actor_id: &str, bytes: &[u8], sig: &KeyringSignature) -> Result<(), IdentityError> {
+    let key = accepted_key(ledger, actor_id).ok_or_else(|| IdentityError::Unbound(actor_id.to_string()))?;
+    verify_by(actor_id, &key, bytes, sig)
+}
+
+/// Bind the first key of `actor_id`. `proof` is `key`'s own signature
+/// over the `KeyChange`.
+#[cfg(feature = "actor-keys")]
+pub fn bind_actor_key(
+    ledger: &mut TokenLedger,
+    actor_id: &str,
+    key: &KeyMeta,
+    proof: &KeyringSignature,
+    now: i64,
+) -> Result<KeyLink, IdentityError> {
+    if accepted_key(ledger, actor_id).is_some() {
+        return Err(IdentityError::AlreadyBound(actor_id.to_string()));
+    }
+    check_new_key(ledger, actor_id, key)?;
+    check_fresh(proof, now, &ledger.config().identity)?;
+    verify_by(actor_id, key, &KeyChange::new(actor_id, key).signing_bytes(), proof)?;
+    log_key(ledger, ACTOR_KEY_BOUND, actor_id, key, KeyAuthority::SelfSigned, now)
+}
+
+/// Replace `actor_id`'s key with `new_key`. `old_key_proof` is the
+/// accepted key's signature over the `KeyChange`; afterwards only
+/// `new_key` authenticates.
+#[cfg(feature = "actor-keys")]
+pub fn rotate_actor_key(
+    ledger: &mut TokenLedger,
+    actor_id: &str,
+    old_key_proof: &KeyringSignature,
+    new_key: &KeyMeta,
+    now: i64,
+) -> Result<KeyLink, IdentityError> {
+    let old = accepted_key(ledger, actor_id).ok_or_else(|| IdentityError::Unbound(actor_id.to_string()))?;
+    check_no_recovery(ledger, actor_id)?;
+    check_new_key(ledger, actor_id, new_key)?;
+    check_fresh(old_key_proof, now, &ledger.config().identity)?;
+    verify_by(actor_id, &old, &KeyChange::new(actor_id, new_key).signing_bytes(), old_key_proof)?;
+    let link = log_key(ledger, ACTOR_KEY_ROTATED, actor_id, new_key, KeyAuthority::PreviousKey { key: old.public_key }, now)?;
+    info!("Key of {} rotated to {}", actor_id, new_key.name);
+    Ok(link)
+}
+
+#[cfg(feature = "actor-keys")]
+fn log_key(
+    ledger: &mut TokenLedger,
+    deed_type: &str,
+    actor_id: &str,
+    key: &KeyMeta,
+    authority: KeyAuthority,
+    now: i64,
+) -> Result<KeyLink, IdentityError> {
+    let context = json!({ "actor_id": actor_id, "new_key": key, "authority": authority, "at": now });
+    let event_id = ledger.log_identity(deed_type, actor_id, context)?.event_id.clone();
+    Ok(KeyLink { event_id, key: key.clone(), authority, at: now })
+}
+
+/// Start recovering `actor_id` onto `recovery_quorum.new_key` after the
+/// key was lost. Approvals must verify against `guardians`, come from
+/// keys with the policy's recovery purpose and number at least
+/// `recovery_threshold` distinct keys. The account's mints freeze until
+/// the recovery completes or is cancelled, and the notice goes to
+/// `notifier`, else to the actor's contact in the policy; delivery
+/// failures are logged and never undo the recovery.
+#[cfg(feature = "actor-keys")]
+pub fn recover_account(
+    ledger: &mut TokenLedger,
+    actor_id: &str,
+    recovery_quorum: &RecoveryQuorum,
+    guardians: &VerifyingBundle,
+    now: i64,
+    notifier: Option<&dyn RecoveryNotifier>,
+) -> Result<PendingRecovery, IdentityError> {
+    let policy = ledger.config().identity.clone();
+    if accepted_key(ledger, actor_id).is_none() {
+        return Err(IdentityError::Unbound(actor_id.to_string()));
+    }
+    check_no_recovery(ledger, actor_id)?;
+    let new_key = &recovery_quorum.new_key;
+    check_new_key(ledger, actor_id, new_key)?;
+    let bytes = KeyChange::new(actor_id, new_key).signing_bytes();
+    let mut signers = BTreeSet::new();
+    for approval in &recovery_quorum.approvals {
+        let meta = guardians.key_meta(&approval.key).ok_or_else(|| IdentityError::NotGuardian(approval.key.clone()))?;
+        if meta.purpose != policy.recovery_purpose {
+            return Err(IdentityError::NotGuardian(approval.key.clone()));
+        }
+        check_fresh(approval, now, &policy)?;
+        guardians.verify(&bytes, approval)?;
+        signers.insert(approval.key.clone());
+    }
+    let need = policy.recovery_threshold.max(1);
+    if signers.len() < need {
+        return Err(IdentityError::NotEnoughApprovals { got: signers.len(), need });
+    }
+
+    let signers: Vec<String> = signers.into_iter().collect();
+    let unlock_at = now + policy.recovery_wait_secs;
+    let context = json!({
+        "actor_id": actor_id,
+        "new_key": new_key,
+        "signers": signers,
+        "started_at": now,
+        "unlock_at": unlock_at,
+    });
+    let recovery_event_id = ledger.log_identity(ACCOUNT_RECOVERY_STARTED, actor_id, context)?.event_id.clone();
+    warn!("Recovery {} of {} started; mints frozen until {}", recovery_event_id, actor_id, unlock_at);
+
+    let notice = RecoveryNotice {
+        recovery_event_id: recovery_event_id.clone(),
+        actor_id: actor_id.to_string(),
+        new_key: new_key.public_key.clone(),
+        signers: signers.clone(),
+        unlock_at,
+    };
+    let contact = policy.contacts.get(actor_id).map(|url| WebhookRecoveryNotifier { url: url.clone() });
+    if let Some(notifier) = notifier.or(contact.as_ref().map(|c| c as &dyn RecoveryNotifier)) {
+        if let Err(e) = notifier.notify(&notice) {
+            warn!("recovery notice for {} not delivered: {}", recovery_event_id, e);
+        }
+    }
+    Ok(PendingRecovery {
+        recovery_event_id,
+        actor_id: actor_id.to_string(),
+        new_key: new_key.clone(),
+        signers,
+        started_at: now,
+        unlock_at,
+    })
+}
+
+/// Rotate `actor_id` onto the recovered key once the waiting period
+/// is over; this lifts the account's mint freeze.
+#[cfg(feature = "actor-keys")]
+pub fn complete_recovery(ledger: &mut TokenLedger, actor_id: &str, now: i64) -> Result<KeyLink, IdentityError> {
+    let pending = pending_recovery(ledger, actor_id).ok_or_else(|| IdentityError::NoRecovery(actor_id.to_string()))?;
+    if now < pending.unlock_at {
+        return Err(IdentityError::RecoveryWaiting { actor_id: actor_id.to_string(), unlock_at: pending.unlock_at });
+    }
+    let authority =
+        KeyAuthority::RecoveryQuorum { recovery_event_id: pending.recovery_event_id.clone(), signers: pending.signers };
+    let link = log_key(ledger, ACTOR_KEY_ROTATED, actor_id, &pending.new_key, authority, now)?;
+    info!("Recovery {} of {} completed", pending.recovery_event_id, actor_id);
+    Ok(link)
+}
+
+/// Cancel a pending recovery with the still-accepted key, signing the
+/// `KeyChange` the recovery would install; this lifts the mint freeze.
+#[cfg(feature = "actor-keys")]
+pub fn cancel_recovery<'a>(
+    ledger: &'a mut TokenLedger,
+    actor_id: &str,
+    proof: &KeyringSignature,
+    now: i64,
+) -> Result<&'a DeedEvent, IdentityError> {
+    let pending = pending_recovery(ledger, actor_id).ok_or_else(|| IdentityError::NoRecovery(actor_id.to_string()))?;
+    let key = accepted_key(ledger, actor_id).ok_or_else(|| IdentityError::Unbound(actor_id.to_string()))?;
+    check_fresh(proof, now, &ledger.config().identity)?;
+    verify_by(actor_id, &key, &KeyChange::new(actor_id, &pending.new_key).signing_bytes(), proof)?;
+    let context = json!({ "actor_id": actor_id, "recovery_event_id": pending.recovery_event_id, "at": now });
+    Ok(ledger.log_identity(ACCOUNT_RECOVERY_CANCELLED, actor_id, context)?)
+}
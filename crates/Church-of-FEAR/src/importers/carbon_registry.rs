@@ -0,0 +1,94 @@
+//! Carbon-offset registry retirement exports:
+//!
+//! ```json
+//! {
+//!   "registry": "verra",
+//!   "records": [
+//!     {
+//!       "serial": "VCS-1234-2024-0001",
+//!       "holder_id": "acct-77",
+//!       "project_location": "Sonoran Desert, AZ",
+//!       "tonnes_co2e": 1.5,
+//!       "retired_at": "2024-03-01T00:00:00Z",
+//!       "evidence_uri": "https://registry.example/retirements/VCS-1234-2024-0001"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Each retirement becomes an `ecological_sustainability` deed by the
+//! holder, keyed by its serial under source `carbon_registry:<registry>`.
+//! Retired tonnes are a bioload reduction of `bioload_per_tonne` each.
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use super::{ImportBatch, ImportError, ImportRecord, RowError};
+
+pub const DEED_TYPE: &str = "ecological_sustainability";
+
+#[derive(Deserialize)]
+struct Export {
+    registry: String,
+    records: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Retirement {
+    serial: String,
+    holder_id: String,
+    project_location: String,
+    tonnes_co2e: f64,
+    retired_at: String,
+    evidence_uri: String,
+}
+
+pub struct CarbonRegistryImporter {
+    pub bioload_per_tonne: f64,
+}
+
+impl Default for CarbonRegistryImporter {
+    fn default() -> Self {
+        Self { bioload_per_tonne: 0.1 }
+    }
+}
+
+impl CarbonRegistryImporter {
+    pub fn read(&self, path: &Path) -> Result<ImportBatch, ImportError> {
+        self.parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse an export. Records that do not match the schema, or retire a
+    /// negative quantity, only reject themselves.
+    pub fn parse(&self, data: &str) -> Result<ImportBatch, ImportError> {
+        let export: Export = serde_json::from_str(data)?;
+        let rows = export.records.into_iter().enumerate().map(|(i, record)| (i + 1, self.translate(record))).collect();
+        Ok(ImportBatch { source: format!("carbon_registry:{}", export.registry), rows })
+    }
+
+    fn translate(&self, record: Value) -> Result<ImportRecord, RowError> {
+        let r: Retirement =
+            serde_json::from_value(record).map_err(|e| RowError::Malformed { column: "*".to_string(), reason: e.to_string() })?;
+        if r.tonnes_co2e.is_nan() || r.tonnes_co2e < 0.0 {
+            let reason = format!("{} is not a retired quantity", r.tonnes_co2e);
+            return Err(RowError::Malformed { column: "tonnes_co2e".to_string(), reason });
+        }
+        let retired_at = chrono::DateTime::parse_from_rfc3339(&r.retired_at)
+            .map_err(|e| RowError::Malformed { column: "retired_at".to_string(), reason: e.to_string() })?;
+        let mut context = Map::new();
+        context.insert("location".to_string(), json!(r.project_location));
+        context.insert("co2_kg".to_string(), json!(r.tonnes_co2e * 1000.0));
+        context.insert("evidence_uri".to_string(), json!(r.evidence_uri));
+        Ok(ImportRecord {
+            external_id: r.serial,
+            external_actor: r.holder_id,
+            deed_type: DEED_TYPE.to_string(),
+            timestamp: retired_at.timestamp(),
+            tags: vec!["carbon_offset".to_string()],
+            context,
+            bioload_delta: -r.tonnes_co2e * self.bioload_per_tonne,
+        })
+    }
+}
@@ -0,0 +1,193 @@
+//! CSV exports (volunteer hours from shelter management software and the
+//! like), read through a column mapping file:
+//!
+//! ```json
+//! {
+//!   "source": "shelterdb",
+//!   "external_id": "shift_id",
+//!   "actor": "volunteer_id",
+//!   "deed_type": "homelessness_relief",
+//!   "timestamp": { "column": "date", "format": "%Y-%m-%d" },
+//!   "fields": { "location": "site" },
+//!   "quantities": { "hours": "hours", "meals_served": "meals" },
+//!   "evidence_uri": "photo_url"
+//! }
+//! ```
+//!
+//! `fields` and `quantities` map deed-context keys to columns (strings and
+//! numbers respectively). With `deed_type_column` set, each row's deed type
+//! is looked up in `deed_types` by that cell. Timestamp formats are
+//! `rfc3339`, `unix` or a chrono pattern, date-only patterns reading as
+//! midnight UTC.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ::csv::{ReaderBuilder, StringRecord};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::{ImportBatch, ImportError, ImportRecord, RowError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampColumn {
+    pub column: String,
+    pub format: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    /// Recorded as `import_source` on every deed.
+    pub source: String,
+    pub external_id: String,
+    pub actor: String,
+    /// Deed type of every row, unless `deed_type_column` is set.
+    #[serde(default)]
+    pub deed_type: Option<String>,
+    #[serde(default)]
+    pub deed_type_column: Option<String>,
+    /// Cell value → deed type, for `deed_type_column`.
+    #[serde(default)]
+    pub deed_types: BTreeMap<String, String>,
+    pub timestamp: TimestampColumn,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub quantities: BTreeMap<String, String>,
+    #[serde(default)]
+    pub evidence_uri: Option<String>,
+    #[serde(default)]
+    pub bioload_delta: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl ColumnMapping {
+    pub fn load(path: &Path) -> Result<Self, ImportError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn columns(&self) -> impl Iterator<Item = &String> {
+        [&self.external_id, &self.actor, &self.timestamp.column]
+            .into_iter()
+            .chain(&self.deed_type_column)
+            .chain(self.fields.values())
+            .chain(self.quantities.values())
+            .chain(&self.evidence_uri)
+            .chain(&self.bioload_delta)
+    }
+}
+
+pub struct CsvImporter {
+    pub mapping: ColumnMapping,
+}
+
+impl CsvImporter {
+    pub fn new(mapping: ColumnMapping) -> Self {
+        Self { mapping }
+    }
+
+    pub fn read(&self, path: &Path) -> Result<ImportBatch, ImportError> {
+        self.parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse CSV text with a header line. Every mapped column must be in
+    /// the header; problems within a row only reject that row.
+    pub fn parse(&self, data: &str) -> Result<ImportBatch, ImportError> {
+        if self.mapping.deed_type.is_none() && self.mapping.deed_type_column.is_none() {
+            return Err(ImportError::Mapping("one of deed_type and deed_type_column is required".to_string()));
+        }
+        let mut reader = ReaderBuilder::new().trim(::csv::Trim::All).from_reader(data.as_bytes());
+        let header = reader.headers()?.clone();
+        let mut index = BTreeMap::new();
+        for column in self.mapping.columns() {
+            let i = header.iter().position(|h| h == column).ok_or_else(|| ImportError::MissingColumn(column.clone()))?;
+            index.insert(column.as_str(), i);
+        }
+        let mut rows = Vec::new();
+        for (n, record) in reader.records().enumerate() {
+            let parsed = match record {
+                Ok(record) => Row { mapping: &self.mapping, index: &index, record }.translate(),
+                Err(e) => Err(RowError::Malformed { column: "*".to_string(), reason: e.to_string() }),
+            };
+            rows.push((n + 1, parsed));
+        }
+        Ok(ImportBatch { source: self.mapping.source.clone(), rows })
+    }
+}
+
+struct Row<'a> {
+    mapping: &'a ColumnMapping,
+    index: &'a BTreeMap<&'a str, usize>,
+    record: StringRecord,
+}
+
+impl Row<'_> {
+    fn cell(&self, column: &str) -> Option<&str> {
+        self.record.get(self.index[column]).filter(|c| !c.is_empty())
+    }
+
+    fn required(&self, column: &str) -> Result<&str, RowError> {
+        self.cell(column).ok_or_else(|| RowError::Empty { column: column.to_string() })
+    }
+
+    fn number(&self, column: &str) -> Result<Value, RowError> {
+        let cell = self.required(column)?;
+        if let Ok(n) = cell.parse::<i64>() {
+            return Ok(json!(n));
+        }
+        let n: f64 = cell.parse().map_err(|_| malformed(column, format!("{:?} is not a number", cell)))?;
+        Ok(json!(n))
+    }
+
+    fn translate(&self) -> Result<ImportRecord, RowError> {
+        let m = self.mapping;
+        let deed_type = match &m.deed_type_column {
+            Some(column) => {
+                let cell = self.required(column)?;
+                m.deed_types.get(cell).cloned().ok_or_else(|| RowError::UnknownDeedType(cell.to_string()))?
+            }
+            None => m.deed_type.clone().expect("checked in parse"),
+        };
+        let mut context = Map::new();
+        for (key, column) in &m.fields {
+            context.insert(key.clone(), json!(self.required(column)?));
+        }
+        for (key, column) in &m.quantities {
+            context.insert(key.clone(), self.number(column)?);
+        }
+        if let Some(uri) = m.evidence_uri.as_deref().and_then(|c| self.cell(c)) {
+            context.insert("evidence_uri".to_string(), json!(uri));
+        }
+        let bioload_delta = match &m.bioload_delta {
+            Some(column) => self.number(column)?.as_f64().expect("numbers are f64-representable"),
+            None => 0.0,
+        };
+        Ok(ImportRecord {
+            external_id: self.required(&m.external_id)?.to_string(),
+            external_actor: self.required(&m.actor)?.to_string(),
+            deed_type,
+            timestamp: parse_timestamp(&m.timestamp, self.required(&m.timestamp.column)?)?,
+            tags: m.tags.clone(),
+            context,
+            bioload_delta,
+        })
+    }
+}
+
+fn malformed(column: &str, reason: String) -> RowError {
+    RowError::Malformed { column: column.to_string(), reason }
+}
+
+fn parse_timestamp(spec: &TimestampColumn, cell: &str) -> Result<i64, RowError> {
+    let parsed = match spec.format.as_str() {
+        "rfc3339" => DateTime::parse_from_rfc3339(cell).map(|t| t.timestamp()).map_err(|e| e.to_string()),
+        "unix" => cell.parse::<i64>().map_err(|e| e.to_string()),
+        pattern => NaiveDateTime::parse_from_str(cell, pattern)
+            .or_else(|_| NaiveDate::parse_from_str(cell, pattern).map(|d| d.and_hms_opt(0, 0, 0).expect("midnight")))
+            .map(|t| t.and_utc().timestamp())
+            .map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| malformed(&spec.column, format!("{:?}: {}", cell, e)))
+}
@@ -0,0 +1,303 @@
+//! Deed importers for records kept in external systems.
+//!
+//! An adapter (`csv::CsvImporter` for volunteer-hour exports,
+//! `carbon_registry::CarbonRegistryImporter` for offset retirements) turns
+//! a file into an `ImportBatch` of `ImportRecord`s, each carrying the
+//! source record's external id. `import` then runs every record through
+//! the normal pipeline: actor resolution via an `ActorMap`, deed
+//! validation, the ledger's own guards on append and a CHURCH reward.
+//!
+//! Imported deeds carry `import_source` and `external_id` in their
+//! context; a record already on the ledger under the same pair is skipped,
+//! so re-importing a file is a no-op. Until a correction role confirms the
+//! evidence (`verify_import`) only `ImportPolicy::unverified_reward_factor`
+//! of the reward is minted; verification releases the remainder.
+
+pub mod carbon_registry;
+pub mod csv;
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::compliance::validator::validate_deed;
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::{hash_deed, validate_bioload_delta, DeedError, DeedEvent};
+use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::token::mint::mint_church;
+
+pub const IMPORT_VERIFIED: &str = "import_verified";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportPolicy {
+    /// Share of the reward minted while evidence is unverified.
+    pub unverified_reward_factor: f64,
+    /// Biophysical inputs imported deeds are validated and rewarded with.
+    pub roh: f64,
+    pub decay: f64,
+}
+
+impl Default for ImportPolicy {
+    fn default() -> Self {
+        Self { unverified_reward_factor: 0.5, roh: 0.2, decay: 0.7 }
+    }
+}
+
+/// External actor ids to ledger actor ids.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActorMap {
+    pub actors: BTreeMap<String, String>,
+    /// Give unmapped actors a `<source>:<external id>` account instead of
+    /// rejecting their records.
+    pub auto_provision: bool,
+}
+
+impl ActorMap {
+    pub fn load(path: &Path) -> Result<Self, ImportError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn resolve(&self, source: &str, external: &str) -> Result<String, RowError> {
+        match self.actors.get(external) {
+            Some(actor) => Ok(actor.clone()),
+            None if self.auto_provision => Ok(format!("{}:{}", source, external)),
+            None => Err(RowError::UnmappedActor(external.to_string())),
+        }
+    }
+}
+
+/// One source record translated to deed form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRecord {
+    pub external_id: String,
+    pub external_actor: String,
+    pub deed_type: String,
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub tags: Vec<String>,
+    /// Deed context: taxonomy fields, quantities and `evidence_uri`.
+    pub context: Map<String, Value>,
+    pub bioload_delta: f64,
+}
+
+/// Records parsed from one file, by 1-based row (CSV data line or JSON
+/// record index); rows that failed to parse carry their error.
+#[derive(Debug)]
+pub struct ImportBatch {
+    pub source: String,
+    pub rows: Vec<(usize, Result<ImportRecord, RowError>)>,
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("csv: {0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("mapping: {0}")]
+    Mapping(String),
+    #[error("column {0:?} is not in the file header")]
+    MissingColumn(String),
+    #[error("{0} was not imported")]
+    NotImported(String),
+    #[error("import {0} is already verified")]
+    AlreadyVerified(String),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Why one record was not imported; the rest of the batch goes on.
+#[derive(Error, Debug)]
+pub enum RowError {
+    #[error("{column} is empty")]
+    Empty { column: String },
+    #[error("{column}: {reason}")]
+    Malformed { column: String, reason: String },
+    #[error("no deed type mapped for {0:?}")]
+    UnknownDeedType(String),
+    #[error("actor {0:?} is not mapped")]
+    UnmappedActor(String),
+    #[error(transparent)]
+    Invalid(#[from] DeedError),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum RowOutcome {
+    /// `event_id` is `None` in a dry run.
+    Imported { event_id: Option<String>, actor_id: String, deed_type: String, full_reward: u64, minted: u64 },
+    Duplicate,
+    Rejected { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowReport {
+    pub row: usize,
+    pub external_id: Option<String>,
+    #[serde(flatten)]
+    pub outcome: RowOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub dry_run: bool,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub rejected: usize,
+    /// CHURCH credited to actors, after tithes.
+    pub minted: u64,
+    pub rows: Vec<RowReport>,
+}
+
+/// Import `batch` into `ledger`. A dry run imports into a copy and
+/// returns the same report, leaving `ledger` untouched.
+pub fn import(ledger: &mut TokenLedger, batch: &ImportBatch, actors: &ActorMap, policy: &ImportPolicy, dry_run: bool) -> ImportReport {
+    if dry_run {
+        return import_into(&mut ledger.clone(), batch, actors, policy, true);
+    }
+    import_into(ledger, batch, actors, policy, false)
+}
+
+fn import_into(ledger: &mut TokenLedger, batch: &ImportBatch, actors: &ActorMap, policy: &ImportPolicy, dry_run: bool) -> ImportReport {
+    let mut seen: HashSet<String> = ledger
+        .deeds()
+        .iter()
+        .filter(|d| d.context_json["import_source"] == batch.source.as_str())
+        .filter_map(|d| d.context_json["external_id"].as_str().map(str::to_string))
+        .collect();
+    let mut report = ImportReport {
+        source: batch.source.clone(),
+        dry_run,
+        imported: 0,
+        duplicates: 0,
+        rejected: 0,
+        minted: 0,
+        rows: Vec::with_capacity(batch.rows.len()),
+    };
+    for (row, parsed) in &batch.rows {
+        let external_id = parsed.as_ref().ok().map(|r| r.external_id.clone());
+        let outcome = match parsed {
+            Err(e) => RowOutcome::Rejected { reason: e.to_string() },
+            Ok(record) if seen.contains(&record.external_id) => RowOutcome::Duplicate,
+            Ok(record) => match import_record(ledger, &batch.source, record, actors, policy) {
+                Ok(mut imported) => {
+                    seen.insert(record.external_id.clone());
+                    if dry_run {
+                        if let RowOutcome::Imported { event_id, .. } = &mut imported {
+                            *event_id = None;
+                        }
+                    }
+                    imported
+                }
+                Err(e) => RowOutcome::Rejected { reason: e.to_string() },
+            },
+        };
+        match &outcome {
+            RowOutcome::Imported { minted, .. } => {
+                report.imported += 1;
+                report.minted += minted;
+            }
+            RowOutcome::Duplicate => report.duplicates += 1,
+            RowOutcome::Rejected { .. } => report.rejected += 1,
+        }
+        report.rows.push(RowReport { row: *row, external_id, outcome });
+    }
+    report
+}
+
+fn import_record(
+    ledger: &mut TokenLedger,
+    source: &str,
+    record: &ImportRecord,
+    actors: &ActorMap,
+    policy: &ImportPolicy,
+) -> Result<RowOutcome, RowError> {
+    let actor_id = actors.resolve(source, &record.external_actor)?;
+    let mut deed = DeedEvent::new(
+        ledger.last_hash(),
+        actor_id.clone(),
+        Vec::new(),
+        record.deed_type.clone(),
+        record.tags.clone(),
+        Value::Object(record.context.clone()),
+        Vec::new(),
+        false,
+    );
+    let metrics = BioloadMetrics::new(record.bioload_delta, policy.roh, policy.decay);
+    validate_bioload_delta(metrics.bioload_delta)?;
+    let full_reward = mint_church(&deed, &metrics, ledger.config());
+    let discounted = (full_reward as f64 * policy.unverified_reward_factor.clamp(0.0, 1.0)).floor() as u64;
+    deed.timestamp = record.timestamp;
+    deed.context_json["import_source"] = json!(source);
+    deed.context_json["external_id"] = json!(record.external_id);
+    deed.context_json["import_reward"] = json!({ "full": full_reward, "discounted": discounted });
+    deed.self_hash = hash_deed(&deed);
+    validate_deed(&deed, metrics.roh, metrics.decay)?;
+    if discounted > 0 {
+        if let Some(freeze) = ledger.mint_freeze() {
+            return Err(TokenLedgerError::MintsFrozen(freeze.to_string()).into());
+        }
+    }
+
+    ledger.open_account(&actor_id, &actor_id);
+    let event_id = ledger.append(deed)?.event_id.clone();
+    let minted = if discounted > 0 { ledger.reward_for(&actor_id, Token::Church, discounted, Some(&event_id))? } else { 0 };
+    Ok(RowOutcome::Imported {
+        event_id: Some(event_id),
+        actor_id,
+        deed_type: record.deed_type.clone(),
+        full_reward,
+        minted,
+    })
+}
+
+/// Confirm an imported deed's evidence and mint the withheld part of its
+/// reward. Only correction roles may; returns the CHURCH credited.
+pub fn verify_import(ledger: &mut TokenLedger, event_id: &str, verifier_id: &str, verifier_role: &str) -> Result<u64, ImportError> {
+    if !ledger.config().correction_roles.iter().any(|r| r == verifier_role) {
+        return Err(TokenLedgerError::RoleNotAllowed(verifier_role.to_string()).into());
+    }
+    let deed = ledger.deed(event_id).ok_or_else(|| ImportError::NotImported(event_id.to_string()))?;
+    let reward = &deed.context_json["import_reward"];
+    let (Some(full), Some(discounted)) = (reward["full"].as_u64(), reward["discounted"].as_u64()) else {
+        return Err(ImportError::NotImported(event_id.to_string()));
+    };
+    let actor_id = deed.actor_id.clone();
+    if ledger.deeds().iter().any(|d| d.deed_type == IMPORT_VERIFIED && d.target_ids.iter().any(|t| t == event_id)) {
+        return Err(ImportError::AlreadyVerified(event_id.to_string()));
+    }
+    let released = full.saturating_sub(discounted);
+    if released > 0 {
+        if let Some(freeze) = ledger.mint_freeze() {
+            return Err(TokenLedgerError::MintsFrozen(freeze.to_string()).into());
+        }
+    }
+
+    let context = json!({ "verifier_role": verifier_role, "released": released });
+    let deed = DeedEvent::new(
+        ledger.last_hash(),
+        verifier_id.to_string(),
+        vec![event_id.to_string()],
+        IMPORT_VERIFIED.to_string(),
+        Vec::new(),
+        context,
+        Vec::new(),
+        false,
+    );
+    ledger.append(deed)?;
+    if released == 0 {
+        return Ok(0);
+    }
+    Ok(ledger.reward_for(&actor_id, Token::Church, released, Some(event_id))?)
+}
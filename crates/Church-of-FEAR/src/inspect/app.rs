@@ -0,0 +1,226 @@
+//! ratatui front end. `App` owns the view state and reacts to key events;
+//! `run` drives it from any event source, so tests can script input
+//! against a `TestBackend`.
+
+use std::io;
+
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use super::index::LedgerIndex;
+use super::view::{AccountView, ChainHealth, DetailView, Filter, FilteredView};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Detail,
+    Account,
+}
+
+pub struct App {
+    index: LedgerIndex,
+    query: String,
+    view: FilteredView,
+    /// Index into `view`, not a ledger position.
+    selected: usize,
+    /// First visible list row.
+    offset: usize,
+    /// List rows visible at the last draw; drives paging.
+    page: usize,
+    pane: Pane,
+    detail: Option<DetailView>,
+    status: Option<String>,
+    quit: bool,
+}
+
+impl App {
+    pub fn new(index: LedgerIndex) -> Self {
+        let view = FilteredView::new(&index, &Filter::default());
+        Self {
+            index,
+            query: String::new(),
+            view,
+            selected: 0,
+            offset: 0,
+            page: 1,
+            pane: Pane::Detail,
+            detail: None,
+            status: None,
+            quit: false,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn matches(&self) -> usize {
+        self.view.len()
+    }
+
+    /// Ledger position of the selected deed.
+    pub fn selected_position(&self) -> Option<usize> {
+        self.view.position(self.selected)
+    }
+
+    pub fn pane(&self) -> Pane {
+        self.pane
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let last = self.view.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('r') if ctrl => self.reverify(),
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.refilter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Esc if self.query.is_empty() => self.quit = true,
+            KeyCode::Esc => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(self.page),
+            KeyCode::PageDown => self.selected = (self.selected + self.page).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Tab => {
+                self.pane = match self.pane {
+                    Pane::Detail => Pane::Account,
+                    Pane::Account => Pane::Detail,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn refilter(&mut self) {
+        self.view = FilteredView::new(&self.index, &Filter::parse(&self.query));
+        self.selected = 0;
+        self.offset = 0;
+    }
+
+    fn reverify(&mut self) {
+        let selected = self.selected_position();
+        self.status = Some(match self.index.reverify() {
+            Ok(_) => format!("re-verified {} deeds", self.index.len()),
+            Err(e) => format!("re-verify failed: {}", e),
+        });
+        self.detail = None;
+        self.view = FilteredView::new(&self.index, &Filter::parse(&self.query));
+        self.selected = selected
+            .and_then(|p| (0..self.view.len()).find(|&i| self.view.position(i) == Some(p)))
+            .unwrap_or(0);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)])
+            .split(frame.area());
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(rows[1]);
+
+        frame.render_widget(Paragraph::new(ChainHealth::of(&self.index).line()), rows[0]);
+
+        // Event list: only the visible window is formatted.
+        self.page = body[0].height.saturating_sub(2).max(1) as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.page {
+            self.offset = self.selected + 1 - self.page;
+        }
+        let lines: Vec<Line> = self
+            .view
+            .lines(&self.index, self.offset, self.page)
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let line = Line::raw(text);
+                if self.offset + i == self.selected {
+                    line.style(Style::default().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect();
+        let title = format!("events {}/{}", self.view.len(), self.index.len());
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), body[0]);
+
+        let (title, text) = self.side_pane();
+        frame.render_widget(
+            Paragraph::new(text.into_iter().map(Line::raw).collect::<Vec<_>>())
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            body[1],
+        );
+
+        let footer = match &self.status {
+            Some(status) => format!("filter> {}   [{}]", self.query, status),
+            None => format!("filter> {}", self.query),
+        };
+        frame.render_widget(Paragraph::new(footer), rows[2]);
+    }
+
+    fn side_pane(&mut self) -> (&'static str, Vec<String>) {
+        let Some(position) = self.selected_position() else {
+            return ("detail", vec!["no matching deeds".to_string()]);
+        };
+        match self.pane {
+            Pane::Detail => {
+                if self.detail.as_ref().map(|d| d.position) != Some(position) {
+                    self.detail = DetailView::load(&self.index, position).ok();
+                }
+                let lines = match &self.detail {
+                    Some(detail) => detail.lines(),
+                    None => vec![format!("could not read deed #{}", position)],
+                };
+                ("detail", lines)
+            }
+            Pane::Account => {
+                let actor = self.index.actor_of(position).unwrap_or_default();
+                ("account", AccountView::load(&self.index, actor).lines())
+            }
+        }
+    }
+}
+
+/// Draw, wait for the next event, repeat until the user quits or the
+/// events run out.
+pub fn run<B, I>(terminal: &mut Terminal<B>, app: &mut App, events: I) -> io::Result<()>
+where
+    B: Backend,
+    I: IntoIterator<Item = io::Result<Event>>,
+{
+    terminal.draw(|f| app.draw(f))?;
+    for event in events {
+        if let Event::Key(key) = event? {
+            app.handle_key(key);
+        }
+        if app.should_quit() {
+            break;
+        }
+        terminal.draw(|f| app.draw(f))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,301 @@
+//! One-pass index over a JSONL deed log (one `DeedEvent` per line).
+//!
+//! The scan keeps a compact row per deed (byte offset, interned actor,
+//! deed type and tags, timestamp) plus per-actor balances and activity,
+//! and verifies every hash link on the way. Full deeds are read back from
+//! the file on demand, so memory stays proportional to the row count, not
+//! the ledger size. The file is only ever opened for reading. Simulation
+//! deeds in an export that includes them are skipped.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::account::Account;
+use crate::ledger::deed_event::{link_fault, DeedEvent};
+pub use crate::ledger::deed_event::LinkFault;
+use crate::ledger::token_ledger::{movements_of, SealedSegment};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+#[derive(Error, Debug)]
+pub enum InspectError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {line}: {source}")]
+    Parse { line: usize, source: serde_json::Error },
+    #[error("no deed at position {0}")]
+    OutOfRange(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verification {
+    Intact,
+    Broken { position: usize, fault: LinkFault },
+}
+
+/// Per-actor activity gathered during the scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorActivity {
+    pub deeds: usize,
+    /// Deeds carrying at least one ethics flag.
+    pub flagged: usize,
+    pub life_harm: usize,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// Consecutive UTC days with at least one deed, ending at `last_seen`.
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+impl ActorActivity {
+    fn observe(&mut self, deed: &DeedEvent) {
+        let day = deed.timestamp.div_euclid(SECS_PER_DAY);
+        if self.deeds == 0 {
+            self.first_seen = deed.timestamp;
+            self.current_streak_days = 1;
+        } else {
+            let last_day = self.last_seen.div_euclid(SECS_PER_DAY);
+            if day == last_day + 1 {
+                self.current_streak_days += 1;
+            } else if day > last_day + 1 {
+                self.current_streak_days = 1;
+            }
+        }
+        self.longest_streak_days = self.longest_streak_days.max(self.current_streak_days);
+        self.last_seen = self.last_seen.max(deed.timestamp);
+        self.deeds += 1;
+        self.flagged += usize::from(!deed.ethics_flags.is_empty());
+        self.life_harm += usize::from(deed.life_harm_flag);
+    }
+}
+
+/// What the list and filter need for one deed; the body stays on disk.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Row {
+    pub offset: u64,
+    pub len: u32,
+    pub actor: u32,
+    pub deed_type: u32,
+    pub tags_start: u32,
+    pub tags_len: u16,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.names.iter().enumerate().map(|(i, n)| (i as u32, n.as_str()))
+    }
+}
+
+/// Read-only index over a ledger file.
+pub struct LedgerIndex {
+    path: PathBuf,
+    file: File,
+    pub(crate) rows: Vec<Row>,
+    pub(crate) strings: Interner,
+    pub(crate) tags: Vec<u32>,
+    accounts: BTreeMap<String, Account>,
+    activity: HashMap<u32, ActorActivity>,
+    tip: Option<String>,
+    verification: Verification,
+    sealed_len: usize,
+}
+
+impl LedgerIndex {
+    /// Scan `path`. Sealed segments are read from `<path>.segments.json`
+    /// when present; without it the whole chain counts as unsealed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, InspectError> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let mut index = Self {
+            path,
+            file,
+            rows: Vec::new(),
+            strings: Interner::default(),
+            tags: Vec::new(),
+            accounts: BTreeMap::new(),
+            activity: HashMap::new(),
+            tip: None,
+            verification: Verification::Intact,
+            sealed_len: 0,
+        };
+        index.scan()?;
+        index.sealed_len = index.read_sealed_len();
+        Ok(index)
+    }
+
+    fn scan(&mut self) -> Result<(), InspectError> {
+        let mut reader = BufReader::new(&self.file);
+        let mut line = String::new();
+        let mut offset = 0u64;
+        let mut line_no = 0;
+        let mut prev_hash = "0".repeat(64);
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            line_no += 1;
+            let start = offset;
+            offset += read as u64;
+            let body = line.trim_end();
+            if body.is_empty() {
+                continue;
+            }
+            let deed: DeedEvent =
+                serde_json::from_str(body).map_err(|source| InspectError::Parse { line: line_no, source })?;
+            // Simulation sub-chains are not part of the live chain.
+            if !deed.domain.is_live() {
+                continue;
+            }
+
+            let position = self.rows.len();
+            if self.verification == Verification::Intact {
+                if let Some(fault) = link_fault(&deed, &prev_hash) {
+                    self.verification = Verification::Broken { position, fault };
+                }
+            }
+            prev_hash.clone_from(&deed.self_hash);
+
+            let actor = self.strings.intern(&deed.actor_id);
+            let deed_type = self.strings.intern(&deed.deed_type);
+            let tags_start = self.tags.len() as u32;
+            for tag in &deed.tags {
+                let id = self.strings.intern(tag);
+                self.tags.push(id);
+            }
+            self.rows.push(Row {
+                offset: start,
+                len: body.len() as u32,
+                actor,
+                deed_type,
+                tags_start,
+                tags_len: deed.tags.len() as u16,
+                timestamp: deed.timestamp,
+            });
+
+            self.activity.entry(actor).or_default().observe(&deed);
+            for m in movements_of(&deed) {
+                let account = self
+                    .accounts
+                    .entry(m.account_id.clone())
+                    .or_insert_with(|| Account::new(m.account_id.clone(), m.account_id.clone()));
+                if m.delta >= 0 {
+                    account.credit(m.token, m.delta.unsigned_abs());
+                } else {
+                    account.debit(m.token, m.delta.unsigned_abs());
+                }
+            }
+        }
+        self.tip = (!self.rows.is_empty()).then_some(prev_hash);
+        Ok(())
+    }
+
+    fn read_sealed_len(&self) -> usize {
+        let mut sidecar = self.path.clone().into_os_string();
+        sidecar.push(".segments.json");
+        std::fs::read_to_string(sidecar)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Vec<SealedSegment>>(&text).ok())
+            .and_then(|segments| segments.iter().map(|s| s.last + 1).max())
+            .unwrap_or(0)
+            .min(self.rows.len())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn tip(&self) -> Option<&str> {
+        self.tip.as_deref()
+    }
+
+    pub fn verification(&self) -> &Verification {
+        &self.verification
+    }
+
+    /// Deeds appended after the last sealed segment.
+    pub fn unsealed_len(&self) -> usize {
+        self.rows.len() - self.sealed_len
+    }
+
+    pub fn account(&self, id: &str) -> Option<&Account> {
+        self.accounts.get(id)
+    }
+
+    pub fn activity(&self, actor: &str) -> Option<&ActorActivity> {
+        self.strings.ids.get(actor).and_then(|id| self.activity.get(id))
+    }
+
+    pub fn actor_of(&self, position: usize) -> Option<&str> {
+        self.rows.get(position).map(|r| self.strings.name(r.actor))
+    }
+
+    pub(crate) fn row_tags(&self, row: &Row) -> &[u32] {
+        &self.tags[row.tags_start as usize..row.tags_start as usize + row.tags_len as usize]
+    }
+
+    /// Read one deed back from the file.
+    pub fn event(&self, position: usize) -> Result<DeedEvent, InspectError> {
+        let row = self.rows.get(position).ok_or(InspectError::OutOfRange(position))?;
+        let mut buf = vec![0; row.len as usize];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(row.offset))?;
+        file.read_exact(&mut buf)?;
+        serde_json::from_slice(&buf).map_err(|source| InspectError::Parse { line: position + 1, source })
+    }
+
+    /// Verification status of the deed at `position` alone.
+    pub fn link_status(&self, position: usize) -> Result<Option<LinkFault>, InspectError> {
+        let deed = self.event(position)?;
+        let prev = match position {
+            0 => "0".repeat(64),
+            _ => self.event(position - 1)?.self_hash,
+        };
+        Ok(link_fault(&deed, &prev))
+    }
+
+    /// Re-read the file from the start and verify the whole chain again.
+    pub fn reverify(&mut self) -> Result<&Verification, InspectError> {
+        *self = Self::open(&self.path)?;
+        Ok(&self.verification)
+    }
+}
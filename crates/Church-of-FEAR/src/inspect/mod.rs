@@ -0,0 +1,15 @@
+//! `cof-inspect`: read-only ledger browser for operators.
+//!
+//! The ledger is a JSONL deed log. `index` scans it once and keeps a
+//! compact per-deed row so a million-deed chain stays responsive; `view`
+//! holds the terminal-independent view models; `app` is the ratatui UI.
+//! Reading the chain over RPC is not supported yet: the RPC surface has no
+//! chain-read methods.
+
+pub mod app;
+pub mod index;
+pub mod view;
+
+pub use app::{run, App, Pane};
+pub use index::{ActorActivity, InspectError, LedgerIndex, LinkFault, Verification};
+pub use view::{AccountView, ChainHealth, DetailView, Filter, FilteredView, Standing};
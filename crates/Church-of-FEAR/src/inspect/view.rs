@@ -0,0 +1,232 @@
+//! Terminal-independent view models for the inspector: the filtered event
+//! list, the detail pane, the account pane and the chain-health header.
+//! Each renders to plain lines so it can be tested without a terminal.
+
+use chrono::{TimeZone, Utc};
+
+pub use crate::history::Standing;
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::DeedEvent;
+
+use super::index::{ActorActivity, InspectError, LedgerIndex, LinkFault, Row, Verification};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Actor,
+    DeedType,
+    Tag,
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    field: Field,
+    needle: String,
+}
+
+/// Filter typed into the list. Whitespace-separated terms must all match;
+/// `actor:`, `type:` and `tag:` restrict a term to one field, a bare term
+/// matches any of them. Matching is case-insensitive substring.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    terms: Vec<Term>,
+}
+
+impl Filter {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|word| {
+                let (field, needle) = match word.split_once(':') {
+                    Some(("actor", rest)) => (Field::Actor, rest),
+                    Some(("type", rest)) => (Field::DeedType, rest),
+                    Some(("tag", rest)) => (Field::Tag, rest),
+                    _ => (Field::Any, word),
+                };
+                Term { field, needle: needle.to_lowercase() }
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+/// Positions of the deeds matching a filter. Terms are resolved against
+/// the interned strings first, so each row costs a few table lookups.
+#[derive(Debug, Clone, Default)]
+pub struct FilteredView {
+    /// `None` when unfiltered, to avoid materialising every position.
+    positions: Option<Vec<u32>>,
+    total: usize,
+}
+
+impl FilteredView {
+    pub fn new(index: &LedgerIndex, filter: &Filter) -> Self {
+        if filter.is_empty() {
+            return Self { positions: None, total: index.len() };
+        }
+        let hits: Vec<Vec<bool>> = filter
+            .terms
+            .iter()
+            .map(|term| {
+                let mut hit = vec![false; index.strings.count()];
+                for (id, name) in index.strings.names() {
+                    hit[id as usize] = name.to_lowercase().contains(&term.needle);
+                }
+                hit
+            })
+            .collect();
+        let matches = |row: &Row| {
+            filter.terms.iter().zip(&hits).all(|(term, hit)| {
+                let tag = || index.row_tags(row).iter().any(|&t| hit[t as usize]);
+                match term.field {
+                    Field::Actor => hit[row.actor as usize],
+                    Field::DeedType => hit[row.deed_type as usize],
+                    Field::Tag => tag(),
+                    Field::Any => hit[row.actor as usize] || hit[row.deed_type as usize] || tag(),
+                }
+            })
+        };
+        let positions =
+            index.rows.iter().enumerate().filter(|(_, row)| matches(row)).map(|(i, _)| i as u32).collect();
+        Self { positions: Some(positions), total: index.len() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.as_ref().map_or(self.total, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ledger position of the `i`-th match.
+    pub fn position(&self, i: usize) -> Option<usize> {
+        match &self.positions {
+            Some(p) => p.get(i).map(|&p| p as usize),
+            None => (i < self.total).then_some(i),
+        }
+    }
+
+    /// List lines for matches `start..start + count`; reads only the index.
+    pub fn lines(&self, index: &LedgerIndex, start: usize, count: usize) -> Vec<String> {
+        (start..start.saturating_add(count).min(self.len()))
+            .filter_map(|i| self.position(i))
+            .map(|p| {
+                let row = &index.rows[p];
+                let mut line = format!(
+                    "{:>7}  {}  {:<24} {}",
+                    p,
+                    format_ts(row.timestamp),
+                    index.strings.name(row.deed_type),
+                    index.strings.name(row.actor)
+                );
+                for &tag in index.row_tags(row) {
+                    line.push_str(" #");
+                    line.push_str(index.strings.name(tag));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// Full deed and its hash-link status.
+#[derive(Debug, Clone)]
+pub struct DetailView {
+    pub position: usize,
+    pub deed: DeedEvent,
+    pub link: Option<LinkFault>,
+}
+
+impl DetailView {
+    pub fn load(index: &LedgerIndex, position: usize) -> Result<Self, InspectError> {
+        Ok(Self { position, deed: index.event(position)?, link: index.link_status(position)? })
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let link = match self.link {
+            None => "ok".to_string(),
+            Some(LinkFault::PrevHash) if self.position == 0 => "BROKEN: prev_hash is not the genesis hash".into(),
+            Some(LinkFault::PrevHash) => format!("BROKEN: prev_hash does not match #{}", self.position - 1),
+            Some(LinkFault::SelfHash) => "BROKEN: self_hash does not match contents".into(),
+        };
+        let mut out = vec![format!("deed #{}  {}", self.position, self.deed.event_id), format!("link: {}", link)];
+        let json = serde_json::to_string_pretty(&self.deed).unwrap_or_default();
+        out.extend(json.lines().map(str::to_string));
+        out
+    }
+}
+
+impl Standing {
+    pub fn of(activity: &ActorActivity) -> Self {
+        Standing::from_counts(activity.flagged, activity.life_harm)
+    }
+}
+
+/// Balances, standing and streaks for one actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountView {
+    pub actor: String,
+    pub balances: [(Token, u64); 4],
+    pub activity: ActorActivity,
+    pub standing: Standing,
+}
+
+impl AccountView {
+    pub fn load(index: &LedgerIndex, actor: &str) -> Self {
+        let balances = index.account(actor).map_or(Token::ALL.map(|t| (t, 0)), |a| Token::ALL.map(|t| (t, a.balance(t))));
+        let activity = index.activity(actor).cloned().unwrap_or_default();
+        let standing = Standing::of(&activity);
+        Self { actor: actor.to_string(), balances, activity, standing }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let a = &self.activity;
+        let balances: Vec<String> = self.balances.iter().map(|(t, b)| format!("{} {}", t.as_str(), b)).collect();
+        vec![
+            format!("actor {}", self.actor),
+            format!("standing: {}", self.standing.as_str()),
+            balances.join("  "),
+            format!("deeds {} (flagged {}, life-harm {})", a.deeds, a.flagged, a.life_harm),
+            format!("streak {} days (longest {})", a.current_streak_days, a.longest_streak_days),
+            format!("active {} .. {}", format_ts(a.first_seen), format_ts(a.last_seen)),
+        ]
+    }
+}
+
+/// Header line: height, tip, last verification result and open segment size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHealth {
+    pub height: usize,
+    pub tip: Option<String>,
+    pub verification: Verification,
+    pub unsealed: usize,
+}
+
+impl ChainHealth {
+    pub fn of(index: &LedgerIndex) -> Self {
+        Self {
+            height: index.len(),
+            tip: index.tip().map(str::to_string),
+            verification: index.verification().clone(),
+            unsealed: index.unsealed_len(),
+        }
+    }
+
+    pub fn line(&self) -> String {
+        let tip = self.tip.as_deref().map_or("-", |t| &t[..t.len().min(12)]);
+        let verify = match &self.verification {
+            Verification::Intact => "ok".to_string(),
+            Verification::Broken { position, fault } => format!("BROKEN at #{} ({:?})", position, fault),
+        };
+        format!("height {} | tip {} | verify {} | unsealed {}", self.height, tip, verify, self.unsealed)
+    }
+}
+
+fn format_ts(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0).single().map_or_else(|| ts.to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string())
+}
@@ -1,11 +1,40 @@
 use serde::{Deserialize, Serialize};
 
+/// The four ledger tokens. CHURCH and PWR are rewards; FEAR is diagnostic
+/// and only accrues from regulator transitions; TECH is minted solely for
+/// technology-positive deed categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Token {
+    Church,
+    Pwr,
+    Fear,
+    Tech,
+}
+
+impl Token {
+    pub const ALL: [Token; 4] = [Token::Church, Token::Pwr, Token::Fear, Token::Tech];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Token::Church => "church",
+            Token::Pwr => "pwr",
+            Token::Fear => "fear",
+            Token::Tech => "tech",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
     pub id: String,
     pub owner: String,
     pub balance_church: u64,
     pub balance_pwr: u64,
+    #[serde(default)]
+    pub balance_fear: u64,
+    #[serde(default)]
+    pub balance_tech: u64,
 }
 
 impl Account {
@@ -15,18 +44,74 @@ impl Account {
             owner,
             balance_church: 0,
             balance_pwr: 0,
+            balance_fear: 0,
+            balance_tech: 0,
         }
     }
 
+    pub fn balance(&self, token: Token) -> u64 {
+        match token {
+            Token::Church => self.balance_church,
+            Token::Pwr => self.balance_pwr,
+            Token::Fear => self.balance_fear,
+            Token::Tech => self.balance_tech,
+        }
+    }
+
+    fn balance_mut(&mut self, token: Token) -> &mut u64 {
+        match token {
+            Token::Church => &mut self.balance_church,
+            Token::Pwr => &mut self.balance_pwr,
+            Token::Fear => &mut self.balance_fear,
+            Token::Tech => &mut self.balance_tech,
+        }
+    }
+
+    /// Saturating credit; returns the amount actually added.
+    pub fn credit(&mut self, token: Token, amount: u64) -> u64 {
+        let balance = self.balance_mut(token);
+        let before = *balance;
+        *balance = before.saturating_add(amount);
+        *balance - before
+    }
+
+    /// Saturating debit; returns the amount actually removed.
+    pub fn debit(&mut self, token: Token, amount: u64) -> u64 {
+        let balance = self.balance_mut(token);
+        let before = *balance;
+        *balance = before.saturating_sub(amount);
+        before - *balance
+    }
+
     pub fn credit_church(&mut self, amount: u64) {
-        self.balance_church = self.balance_church.saturating_add(amount);
+        self.credit(Token::Church, amount);
     }
 
     pub fn debit_church(&mut self, amount: u64) {
-        self.balance_church = self.balance_church.saturating_sub(amount);
+        self.debit(Token::Church, amount);
     }
 
     pub fn credit_pwr(&mut self, amount: u64) {
-        self.balance_pwr = self.balance_pwr.saturating_add(amount);
+        self.credit(Token::Pwr, amount);
+    }
+
+    pub fn debit_pwr(&mut self, amount: u64) {
+        self.debit(Token::Pwr, amount);
+    }
+
+    pub fn credit_fear(&mut self, amount: u64) {
+        self.credit(Token::Fear, amount);
+    }
+
+    pub fn debit_fear(&mut self, amount: u64) {
+        self.debit(Token::Fear, amount);
+    }
+
+    pub fn credit_tech(&mut self, amount: u64) {
+        self.credit(Token::Tech, amount);
+    }
+
+    pub fn debit_tech(&mut self, amount: u64) {
+        self.debit(Token::Tech, amount);
     }
 }
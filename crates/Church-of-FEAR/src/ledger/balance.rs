@@ -5,5 +5,9 @@ pub struct BalanceSnapshot {
     pub account_id: String,
     pub church: u64,
     pub pwr: u64,
+    #[serde(default)]
+    pub fear: u64,
+    #[serde(default)]
+    pub tech: u64,
     pub timestamp: i64,
 }
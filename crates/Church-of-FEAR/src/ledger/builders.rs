@@ -0,0 +1,149 @@
+//! Typed deed builders, generated at build time from `taxonomy/deeds.json`.
+//!
+//! `EcologicalSustainabilityDeed::builder().actor_id(..).location(..).co2_kg(..).evidence_uri(..).build(prev)`
+//! only compiles once every required field has been set; `build()` fails at
+//! runtime only for value-range problems. Deeds for categories outside the
+//! taxonomy are still built free-form with `DeedEvent::new`.
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::ledger::deed_event::DeedEvent;
+
+/// Typestate marker: required field not yet provided.
+pub struct Missing;
+
+/// Typestate marker: required field provided.
+pub struct Set<T>(T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    Number,
+    Integer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// A re-check a category's deeds owe after minting, e.g. a survival check
+/// of planted trees. See `crate::obligations`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FollowUpSpec {
+    pub kind: &'static str,
+    /// Days after the deed before the follow-up is accepted.
+    pub due_after_days: u32,
+    /// Days after that before it is missed.
+    pub window_days: u32,
+    /// `evidence_kind` the follow-up deed must carry, e.g. `photo`.
+    pub evidence: &'static str,
+}
+
+/// Independent validator approvals a category's deeds need before they
+/// mint. See `crate::quorum`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationSpec {
+    pub count: usize,
+    /// Lowest validator `mp_score` whose vote counts.
+    pub min_reputation: f64,
+}
+
+/// Shortest gap between two mint-bearing deeds of a category by the same
+/// actor, and with `per_target` against the same target. See
+/// `crate::cooldown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CooldownSpec {
+    pub min_interval_days: u32,
+    pub per_target: bool,
+}
+
+impl CooldownSpec {
+    pub fn interval_secs(&self) -> i64 {
+        i64::from(self.min_interval_days) * 86_400
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CategorySchema {
+    pub deed_type: &'static str,
+    pub tags: &'static [&'static str],
+    /// Deeds in this category may mint TECH.
+    pub tech_positive: bool,
+    pub required: &'static [FieldSpec],
+    pub optional: &'static [FieldSpec],
+    pub follow_ups: &'static [FollowUpSpec],
+    pub required_validations: Option<ValidationSpec>,
+    pub cooldown: Option<CooldownSpec>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DeedBuildError {
+    #[error("{field} must not be empty")]
+    Empty { field: &'static str },
+    #[error("{field} must be a finite number")]
+    NotFinite { field: &'static str },
+    #[error("{field} = {value} outside [{min:?}, {max:?}]")]
+    OutOfRange { field: &'static str, value: f64, min: Option<f64>, max: Option<f64> },
+    #[error("{deed_type} context is missing required field {field}")]
+    MissingField { deed_type: String, field: &'static str },
+    #[error("{deed_type} context field {field} should be {expected:?}")]
+    WrongType { deed_type: String, field: &'static str, expected: FieldKind },
+}
+
+fn check_string(field: &'static str, value: &str) -> Result<(), DeedBuildError> {
+    if value.trim().is_empty() {
+        return Err(DeedBuildError::Empty { field });
+    }
+    Ok(())
+}
+
+fn check_number(field: &'static str, value: f64, min: Option<f64>, max: Option<f64>) -> Result<(), DeedBuildError> {
+    if !value.is_finite() {
+        return Err(DeedBuildError::NotFinite { field });
+    }
+    if min.is_some_and(|m| value < m) || max.is_some_and(|m| value > m) {
+        return Err(DeedBuildError::OutOfRange { field, value, min, max });
+    }
+    Ok(())
+}
+
+include!(concat!(env!("OUT_DIR"), "/deed_builders.rs"));
+
+pub fn schema_for(deed_type: &str) -> Option<&'static CategorySchema> {
+    CATEGORIES.iter().find(|c| c.deed_type == deed_type)
+}
+
+fn check_field(deed_type: &str, spec: &FieldSpec, value: &Value) -> Result<(), DeedBuildError> {
+    let wrong = || DeedBuildError::WrongType { deed_type: deed_type.to_string(), field: spec.name, expected: spec.kind };
+    match spec.kind {
+        FieldKind::String => check_string(spec.name, value.as_str().ok_or_else(wrong)?),
+        FieldKind::Number => check_number(spec.name, value.as_f64().ok_or_else(wrong)?, spec.min, spec.max),
+        FieldKind::Integer => check_number(spec.name, value.as_u64().ok_or_else(wrong)? as f64, spec.min, spec.max),
+    }
+}
+
+/// Check a deed's context against its category schema. Categories outside the
+/// taxonomy are free-form and always pass.
+pub fn validate_context(event: &DeedEvent) -> Result<(), DeedBuildError> {
+    let Some(schema) = schema_for(&event.deed_type) else {
+        return Ok(());
+    };
+    for spec in schema.required {
+        let value = event.context_json.get(spec.name).ok_or_else(|| DeedBuildError::MissingField {
+            deed_type: event.deed_type.clone(),
+            field: spec.name,
+        })?;
+        check_field(&event.deed_type, spec, value)?;
+    }
+    for spec in schema.optional {
+        if let Some(value) = event.context_json.get(spec.name) {
+            check_field(&event.deed_type, spec, value)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! POWER concentration for the ethics regulator.
+//!
+//! A Gini over every account drifts towards 1 as zero-balance and dust
+//! accounts pile up, whoever actually holds the POWER. The ledger therefore
+//! keeps two balance histograms up to date on every PWR movement: one over
+//! all accounts, and one over participants, which leaves out accounts
+//! below `dust_threshold` and system accounts such as `sponsor:pool`. The
+//! regulator reads the participant figure.
+//!
+//! Buckets are logarithmic: zero, then `SUB_BUCKETS` equal slices of every
+//! power of two. Each bucket keeps its count and exact sum, so pairs in
+//! different buckets contribute exactly and only pairs inside one bucket
+//! are treated as equal. Within a bucket the largest balance is at most
+//! `1 + 1/SUB_BUCKETS` times the smallest, which bounds how far the
+//! histogram Gini can fall below the exact one by `GINI_ERROR_BOUND`; it
+//! is never above it. Reading it costs one pass over the buckets.
+//!
+//! `gini` sorts the balances instead. The scheduler runs it
+//! periodically through `TokenLedger::check_power_gini`, which rebuilds
+//! the histograms from the accounts if the two ever disagree by more than
+//! the bound.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::obligations::PENDING_OBLIGATIONS;
+use crate::sponsor::pool::SPONSOR_POOL;
+
+/// Slices per power of two.
+pub const SUB_BUCKETS: usize = 64;
+const SUB_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+/// Bucket 0 holds zero balances.
+pub const BUCKETS: usize = 1 + 64 * SUB_BUCKETS;
+/// Largest amount by which the histogram Gini can undershoot the exact one.
+pub const GINI_ERROR_BOUND: f64 = 0.5 / SUB_BUCKETS as f64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcentrationPolicy {
+    /// Accounts holding less POWER than this are not participants.
+    pub dust_threshold: u64,
+    /// System accounts that are never participants.
+    pub excluded_accounts: Vec<String>,
+    /// How often the scheduler compares the histogram with an exact pass.
+    pub exact_every_secs: u64,
+}
+
+impl Default for ConcentrationPolicy {
+    fn default() -> Self {
+        Self {
+            dust_threshold: 1,
+            excluded_accounts: vec![SPONSOR_POOL.to_string(), PENDING_OBLIGATIONS.to_string(), "church:root".to_string()],
+            exact_every_secs: 6 * 3600,
+        }
+    }
+}
+
+impl ConcentrationPolicy {
+    pub fn is_participant(&self, account_id: &str, balance: u64) -> bool {
+        balance >= self.dust_threshold && !self.excluded_accounts.iter().any(|a| a == account_id)
+    }
+}
+
+pub fn bucket_of(balance: u64) -> usize {
+    if balance == 0 {
+        return 0;
+    }
+    let octave = 63 - balance.leading_zeros();
+    let offset = balance - (1u64 << octave);
+    let slice = if octave >= SUB_BITS { offset >> (octave - SUB_BITS) } else { offset << (SUB_BITS - octave) };
+    1 + octave as usize * SUB_BUCKETS + slice as usize
+}
+
+/// Counts and sums of balances per logarithmic bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceHistogram {
+    counts: Vec<u64>,
+    sums: Vec<u128>,
+    accounts: u64,
+    total: u128,
+}
+
+impl Default for BalanceHistogram {
+    fn default() -> Self {
+        Self { counts: vec![0; BUCKETS], sums: vec![0; BUCKETS], accounts: 0, total: 0 }
+    }
+}
+
+impl BalanceHistogram {
+    pub fn from_balances(balances: impl IntoIterator<Item = u64>) -> Self {
+        let mut histogram = Self::default();
+        for balance in balances {
+            histogram.insert(balance);
+        }
+        histogram
+    }
+
+    pub fn insert(&mut self, balance: u64) {
+        let b = bucket_of(balance);
+        self.counts[b] += 1;
+        self.sums[b] += u128::from(balance);
+        self.accounts += 1;
+        self.total += u128::from(balance);
+    }
+
+    /// Remove one account holding `balance`; it must have been inserted.
+    pub fn remove(&mut self, balance: u64) {
+        let b = bucket_of(balance);
+        debug_assert!(self.counts[b] > 0, "removing a balance that was never inserted");
+        self.counts[b] = self.counts[b].saturating_sub(1);
+        self.sums[b] = self.sums[b].saturating_sub(u128::from(balance));
+        self.accounts = self.accounts.saturating_sub(1);
+        self.total = self.total.saturating_sub(u128::from(balance));
+    }
+
+    pub fn accounts(&self) -> u64 {
+        self.accounts
+    }
+
+    pub fn total(&self) -> u128 {
+        self.total
+    }
+
+    /// Gini with every account in a bucket at the bucket's mean; see the
+    /// module docs for the error bound.
+    pub fn gini(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let (mut below_count, mut below_sum, mut spread) = (0.0, 0.0, 0.0);
+        for (&count, &sum) in self.counts.iter().zip(&self.sums).filter(|(c, _)| **c > 0) {
+            let (count, sum) = (count as f64, sum as f64);
+            spread += below_count * sum - count * below_sum;
+            below_count += count;
+            below_sum += sum;
+        }
+        (spread / (self.accounts as f64 * self.total as f64)).clamp(0.0, 1.0)
+    }
+}
+
+/// Gini coefficient of `amounts` by sorting; 0 when empty or all zero.
+pub fn gini(amounts: &[u64]) -> f64 {
+    let total: u128 = amounts.iter().map(|&a| a as u128).sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut sorted = amounts.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len() as f64;
+    let weighted: f64 = sorted.iter().enumerate().map(|(i, &a)| (i + 1) as f64 * a as f64).sum();
+    2.0 * weighted / (n * total as f64) - (n + 1.0) / n
+}
+
+/// The ledger's two POWER histograms, kept in step with every PWR movement.
+#[derive(Debug, Clone)]
+pub struct PowerConcentration {
+    policy: ConcentrationPolicy,
+    all: BalanceHistogram,
+    participants: BalanceHistogram,
+}
+
+impl PowerConcentration {
+    pub fn new(policy: ConcentrationPolicy) -> Self {
+        Self { policy, all: BalanceHistogram::default(), participants: BalanceHistogram::default() }
+    }
+
+    pub fn rebuild<'a>(policy: ConcentrationPolicy, balances: impl IntoIterator<Item = (&'a str, u64)>) -> Self {
+        let mut power = Self::new(policy);
+        for (account_id, balance) in balances {
+            power.opened(account_id, balance);
+        }
+        power
+    }
+
+    pub fn opened(&mut self, account_id: &str, balance: u64) {
+        self.all.insert(balance);
+        if self.policy.is_participant(account_id, balance) {
+            self.participants.insert(balance);
+        }
+    }
+
+    pub fn moved(&mut self, account_id: &str, from: u64, to: u64) {
+        if from == to {
+            return;
+        }
+        self.all.remove(from);
+        self.all.insert(to);
+        if self.policy.is_participant(account_id, from) {
+            self.participants.remove(from);
+        }
+        if self.policy.is_participant(account_id, to) {
+            self.participants.insert(to);
+        }
+    }
+
+    pub fn all(&self) -> &BalanceHistogram {
+        &self.all
+    }
+
+    pub fn participants(&self) -> &BalanceHistogram {
+        &self.participants
+    }
+
+    pub fn gini(&self) -> PowerGini {
+        PowerGini {
+            filtered: self.participants.gini(),
+            unfiltered: self.all.gini(),
+            participants: self.participants.accounts(),
+            accounts: self.all.accounts(),
+        }
+    }
+
+    /// The same figures by sorting `balances`.
+    pub fn exact<'a>(&self, balances: impl IntoIterator<Item = (&'a str, u64)>) -> PowerGini {
+        let (mut all, mut participants) = (Vec::new(), Vec::new());
+        for (account_id, balance) in balances {
+            all.push(balance);
+            if self.policy.is_participant(account_id, balance) {
+                participants.push(balance);
+            }
+        }
+        PowerGini {
+            filtered: gini(&participants),
+            unfiltered: gini(&all),
+            participants: participants.len() as u64,
+            accounts: all.len() as u64,
+        }
+    }
+}
+
+/// POWER Gini over participants (`filtered`, what the regulator reads) and
+/// over every account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerGini {
+    pub filtered: f64,
+    pub unfiltered: f64,
+    pub participants: u64,
+    pub accounts: u64,
+}
+
+impl PowerGini {
+    /// Prometheus text exposition of both figures.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# TYPE cof_power_gini gauge\n");
+        writeln!(out, "cof_power_gini{{scope=\"participants\"}} {}", self.filtered).unwrap();
+        writeln!(out, "cof_power_gini{{scope=\"all\"}} {}", self.unfiltered).unwrap();
+        out.push_str("# TYPE cof_power_gini_accounts gauge\n");
+        writeln!(out, "cof_power_gini_accounts{{scope=\"participants\"}} {}", self.participants).unwrap();
+        writeln!(out, "cof_power_gini_accounts{{scope=\"all\"}} {}", self.accounts).unwrap();
+        out
+    }
+}
+
+/// Outcome of comparing the histograms with an exact pass.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerGiniCheck {
+    pub approximate: PowerGini,
+    pub exact: PowerGini,
+    /// Larger of the two figures' differences.
+    pub drift: f64,
+    /// True when the drift was over the bound and the histograms were
+    /// rebuilt from the accounts.
+    pub rebuilt: bool,
+}
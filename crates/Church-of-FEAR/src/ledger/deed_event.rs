@@ -1,13 +1,17 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::fmt;
 use thiserror::Error;
 use uuid::Uuid;
-use chrono::Utc;
-use nalgebra::VectorN;  // For biophysical vector computations (e.g., RoH vector)
-use rand::Rng;  // For simulation in tests
 use rayon::prelude::*;  // Parallel validation
+use crate::config::LedgerConfig;
+use crate::token::repair_curve::{whole_pwr, RepairRewardCurve};
+use crate::utils::correlation::stamp;
+use crate::utils::time::now_timestamp;
+/// The `prev_hash` of the first deed in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Unknown fields are rejected rather than dropped; see `ledger::schema`.
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct DeedEvent {
 pub event_id: String,  // UUID
 pub timestamp: i64,    // Unix epoch seconds
@@ -20,9 +24,39 @@ pub tags: Vec<String>,
 pub context_json: serde_json::Value,
 pub ethics_flags: Vec<String>,
 pub life_harm_flag: bool,
+/// Live deeds omit the field, so their serialized form and hash are unchanged.
+#[serde(default, skip_serializing_if = "ExecutionDomain::is_live")]
+pub domain: ExecutionDomain,
+/// Fields from newer schema versions, kept as parsed and covered by the
+/// hash. Deeds without any omit the field.
+#[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+pub ext: serde_json::Map<String, serde_json::Value>,
+}
+/// Where a deed executes. Simulation deeds chain on their run's own
+/// sub-chain and only ever move shadow balances.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ExecutionDomain {
+#[default]
+Live,
+Simulation { run_id: String },
+}
+impl ExecutionDomain {
+pub fn is_live(&self) -> bool {
+*self == ExecutionDomain::Live
+}
+/// The simulation run, for simulation deeds.
+pub fn run_id(&self) -> Option<&str> {
+match self {
+ExecutionDomain::Live => None,
+ExecutionDomain::Simulation { run_id } => Some(run_id),
+}
+}
 }
 impl DeedEvent {
-/// Creates a new DeedEvent with auto-generated fields.
+/// Creates a new DeedEvent with auto-generated fields. An object context
+/// is stamped with the current correlation id, if any.
+#[allow(clippy::too_many_arguments)]
 pub fn new(
 prev_hash: String,
 actor_id: String,
@@ -33,8 +67,10 @@ context_json: serde_json::Value,
 ethics_flags: Vec<String>,
 life_harm_flag: bool,
 ) -> Self {
+let mut context_json = context_json;
+stamp(&mut context_json);
 let event_id = Uuid::new_v4().to_string();
-let timestamp = Utc::now().timestamp();
+let timestamp = now_timestamp();
 let mut event = Self {
 event_id,
 timestamp,
@@ -47,10 +83,23 @@ tags,
 context_json,
 ethics_flags,
 life_harm_flag,
+domain: ExecutionDomain::Live,
+ext: serde_json::Map::new(),
 };
 event.self_hash = hash_deed(&event);
 event
 }
+/// The deed carrying `ext` fields from a newer schema version, rehashed.
+pub fn with_ext(mut self, ext: serde_json::Map<String, serde_json::Value>) -> Self {
+self.ext = ext;
+self.self_hash = String::new();
+self.self_hash = hash_deed(&self);
+self
+}
+/// First deed of a fresh chain; its prev_hash is `GENESIS_HASH`.
+pub fn genesis() -> Self {
+Self::new(GENESIS_HASH.to_string(), "genesis".to_string(), Vec::new(), "genesis".to_string(), Vec::new(), serde_json::Value::Null, Vec::new(), false)
+}
 /// Validates biophysical invariants (RoH <= 0.3, DECAY <= 1.0).
 pub fn validate_biophysical(&self, roh: f64, decay: f64) -> Result<(), DeedError> {
 if roh > 0.3 || decay > 1.0 {
@@ -58,17 +107,87 @@ return Err(DeedError::InvariantViolation("Biophysical ceiling breached".to_strin
 }
 Ok(())
 }
-/// Computes CHURCH token reward based on deed impact.
-pub fn compute_church_reward(&self, bioload_delta: f64) -> u64 {
+/// Computes CHURCH token reward based on deed impact: `token_reward_factor`
+/// per unit of bioload reduction for a deed type in `church_deed_types`,
+/// capped at `max_church_per_deed`.
+pub fn compute_church_reward(&self, bioload_delta: f64, config: &LedgerConfig) -> Result<u64, DeedError> {
+validate_bioload_delta(bioload_delta)?;
 if self.life_harm_flag || !self.ethics_flags.is_empty() {
-0
-} else if bioload_delta < 0.0 && self.deed_type == "ecological_sustainability" {
-(biolad_delta.abs() * 100.0) as u64  // Earn for reduction
+Ok(0)
+} else if bioload_delta < 0.0 && config.church_deed_types.contains(&self.deed_type) {
+let reward = (bioload_delta.abs() * config.token_reward_factor as f64) as u64;  // Earn for reduction
+Ok(reward.min(config.max_church_per_deed))
 } else {
-0
+Ok(0)
+}
+}
+}
+/// Bounds on a reported bioload delta; anything outside is refused.
+pub const BIOLOAD_DELTA_RANGE: std::ops::RangeInclusive<f64> = -10.0..=10.0;
+/// Refuses a bioload delta outside `BIOLOAD_DELTA_RANGE`, or NaN.
+pub fn validate_bioload_delta(bioload_delta: f64) -> Result<(), DeedError> {
+if !BIOLOAD_DELTA_RANGE.contains(&bioload_delta) {
+return Err(DeedError::InvariantViolation(format!("bioload delta {} outside [-10, 10]", bioload_delta)));
+}
+Ok(())
+}
+impl From<&DeedEvent> for deed_core::DeedEvent {
+/// The shared shape, keeping the deed's hash: the timestamp in
+/// milliseconds, a simulation `domain` under `ext`.
+fn from(deed: &DeedEvent) -> Self {
+let mut ext = deed.ext.clone();
+if !deed.domain.is_live() {
+ext.insert("domain".to_string(), serde_json::to_value(&deed.domain).expect("domain serializes"));
+}
+deed_core::DeedEvent {
+event_id: deed.event_id.clone(),
+timestamp: deed.timestamp.saturating_mul(1_000),
+prev_hash: deed.prev_hash.clone(),
+self_hash: deed.self_hash.clone(),
+actor_id: deed.actor_id.clone(),
+target_ids: deed.target_ids.clone(),
+deed_type: deed.deed_type.clone(),
+tags: deed.tags.clone(),
+context_json: deed.context_json.clone(),
+ethics_flags: deed.ethics_flags.clone(),
+life_harm_flag: deed.life_harm_flag,
+ext,
+hash_rule: deed_core::HashRule::MoralLedgerV1,
 }
 }
 }
+impl From<DeedEvent> for deed_core::DeedEvent {
+fn from(deed: DeedEvent) -> Self {
+Self::from(&deed)
+}
+}
+/// Only deeds sealed under the moral-ledger rule convert back; any other
+/// would arrive with a `self_hash` this ledger cannot reproduce.
+impl TryFrom<deed_core::DeedEvent> for DeedEvent {
+type Error = deed_core::DeedCoreError;
+fn try_from(mut deed: deed_core::DeedEvent) -> Result<Self, Self::Error> {
+deed_core::DeedCoreError::check_rule(&deed, deed_core::HashRule::MoralLedgerV1)?;
+let domain = match deed.ext.remove("domain") {
+Some(raw) => serde_json::from_value(raw).map_err(|e| deed_core::DeedCoreError::Shape { event_id: deed.event_id.clone(), reason: e.to_string() })?,
+None => ExecutionDomain::Live,
+};
+Ok(Self {
+event_id: deed.event_id,
+timestamp: deed.timestamp.div_euclid(1_000),
+prev_hash: deed.prev_hash,
+self_hash: deed.self_hash,
+actor_id: deed.actor_id,
+target_ids: deed.target_ids,
+deed_type: deed.deed_type,
+tags: deed.tags,
+context_json: deed.context_json,
+ethics_flags: deed.ethics_flags,
+life_harm_flag: deed.life_harm_flag,
+domain,
+ext: deed.ext,
+})
+}
+}
 /// Hashes the DeedEvent (excluding self_hash) using SHA-256.
 pub fn hash_deed(event: &DeedEvent) -> String {
 let mut hasher = Sha256::new();
@@ -76,26 +195,56 @@ let serialized = serde_json::to_string(event).unwrap();  // Safe for hashing
 hasher.update(serialized.as_bytes());
 format!("{:x}", hasher.finalize())
 }
-/// Validates a chain of DeedEvents in parallel.
-pub fn validate_chain(events: &[DeedEvent]) -> bool {
-events.par_windows(2).all(|window| {
-let prev = &window[0];
-let current = &window[1];
-current.prev_hash == prev.self_hash
-})
+/// Why a deed fails verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkFault {
+/// `prev_hash` is not the previous deed's `self_hash`.
+PrevHash,
+/// `self_hash` does not match the deed's contents.
+SelfHash,
+}
+/// How `deed` fails to extend a chain whose tip is `prev_hash`, if it does.
+pub fn link_fault(deed: &DeedEvent, prev_hash: &str) -> Option<LinkFault> {
+if deed.prev_hash != prev_hash {
+return Some(LinkFault::PrevHash);
 }
-/// XR-Grid visualization using Bevy for Jetson-Line deeds.
-pub fn xr_visualize_ledger(events: &[DeedEvent]) -> bevy::prelude::App {
-let mut app = bevy::prelude::App::new();
-// Add Bevy plugins for XR-grid rendering
-app.add_plugins(bevy::DefaultPlugins);
-// Simulate 1D line with deeds as entities
-for event in events {
-// Spawn entity with position based on timestamp
-let pos = VectorN::<f32, nalgebra::U3>::new(event.timestamp as f32, 0.0, 0.0);
-// ... (Bevy entity spawn logic)
+let mut unhashed = deed.clone();
+unhashed.self_hash = String::new();
+(hash_deed(&unhashed) != deed.self_hash).then_some(LinkFault::SelfHash)
 }
-app
+/// Why a chain fails `validate_chain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainFault {
+/// The first deed's `prev_hash` is not `GENESIS_HASH`.
+BadGenesis,
+/// `prev_hash` is not the previous deed's `self_hash`.
+PrevHashMismatch,
+/// `self_hash` does not match the deed's contents.
+SelfHashMismatch,
+}
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("chain breaks at deed {index}: {reason:?}")]
+pub struct ChainError {
+pub index: usize,
+pub reason: ChainFault,
+}
+/// Validates a chain of DeedEvents in parallel: the first links to
+/// `GENESIS_HASH`, each later one to its predecessor, and every deed
+/// hashes to its `self_hash`. Reports the earliest failing deed.
+pub fn validate_chain(events: &[DeedEvent]) -> Result<(), ChainError> {
+let failure = events.par_iter().enumerate().filter_map(|(index, deed)| {
+let prev_hash = match index {
+0 => GENESIS_HASH,
+_ => events[index - 1].self_hash.as_str(),
+};
+let reason = match link_fault(deed, prev_hash)? {
+LinkFault::PrevHash if index == 0 => ChainFault::BadGenesis,
+LinkFault::PrevHash => ChainFault::PrevHashMismatch,
+LinkFault::SelfHash => ChainFault::SelfHashMismatch,
+};
+Some(ChainError { index, reason })
+}).min_by_key(|e| e.index);
+failure.map_or(Ok(()), Err)
 }
 /// System-object: KO_BIOLOAD_REDUCER
 #[derive(Debug)]
@@ -120,15 +269,12 @@ pub struct RepairHero {
 pub impact_score: f64,
 }
 impl RepairHero {
-pub fn grant_pwr(&self) -> u64 {
-if self.impact_score > 0.8 {
-100  // PWR for high-impact repair
-} else {
-0
-}
+/// Undamped PWR on `curve`; ledger grants go through `grant_repair_pwr`.
+pub fn grant_pwr(&self, curve: &RepairRewardCurve) -> u64 {
+whole_pwr(curve.value(self.impact_score))
 }
 }
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum DeedError {
 #[error("Hash mismatch: {0}")]
 HashMismatch(String),
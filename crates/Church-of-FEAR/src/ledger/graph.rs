@@ -0,0 +1,25 @@
+//! Actor → target graph over deeds, for spiderweb and sovereignty views.
+
+use std::collections::HashMap;
+
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::ledger::deed_event::DeedEvent;
+
+/// One node per actor or target id; one edge per (deed, target) weighted
+/// by the deed's event id.
+pub fn deed_graph(events: &[DeedEvent]) -> DiGraph<String, String> {
+    let mut graph = DiGraph::new();
+    let mut nodes: HashMap<String, NodeIndex> = HashMap::new();
+    let mut node = |graph: &mut DiGraph<String, String>, id: &str| {
+        *nodes.entry(id.to_string()).or_insert_with(|| graph.add_node(id.to_string()))
+    };
+    for event in events {
+        let actor = node(&mut graph, &event.actor_id);
+        for target in &event.target_ids {
+            let target = node(&mut graph, target);
+            graph.add_edge(actor, target, event.event_id.clone());
+        }
+    }
+    graph
+}
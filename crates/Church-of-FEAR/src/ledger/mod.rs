@@ -1,5 +1,10 @@
 pub mod deed_event;
 pub mod account;
-pub mod deed;
 pub mod metrics;
 pub mod balance;
+pub mod builders;
+pub mod concentration;
+pub mod schema;
+pub mod token_ledger;
+#[cfg(feature = "graph")]
+pub mod graph;
@@ -0,0 +1,104 @@
+//! Deed schema evolution.
+//!
+//! serde drops fields it does not know, so a deed from a newer client
+//! would be stripped by an older node, hashed in its stripped form, and
+//! the two would disagree about what was recorded. Deeds follow explicit
+//! rules instead:
+//!
+//! - Fields from newer schema versions go under the reserved `ext`
+//!   object. It is kept as parsed through hashing, storage and every
+//!   response (the replica stream included), and the canonical hash
+//!   covers it.
+//! - Any other unknown field, at the top level or inside `domain`, is
+//!   critical: the deed is rejected with `UNKNOWN_CRITICAL_FIELD` and the
+//!   field's path. `DeedEvent` itself denies unknown fields, so no parse
+//!   path can strip one silently.
+//! - `context_json` stays free-form; taxonomy categories validate it.
+//!
+//! Nodes advertise `SCHEMA_VERSION` in `auto_church.handshake`,
+//! `auto_church.get_deeds` and `auto_church.tip_announcement`, so clients
+//! can leave out what an older node would reject.
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::ledger::deed_event::DeedEvent;
+
+/// Deed schema this node reads and writes. Version 2 added `ext`.
+pub const SCHEMA_VERSION: u32 = 2;
+/// Schema version assumed for nodes that do not advertise one.
+pub const UNADVERTISED_SCHEMA_VERSION: u32 = 1;
+pub const UNKNOWN_CRITICAL_FIELD: &str = "UNKNOWN_CRITICAL_FIELD";
+/// JSON-RPC error code for a request or deed carrying an unknown
+/// critical field.
+pub const UNKNOWN_CRITICAL_FIELD_CODE: i64 = 1009;
+/// The reserved object for fields from newer schema versions.
+pub const EXT_FIELD: &str = "ext";
+
+const DEED_FIELDS: &[&str] = &[
+    "event_id",
+    "timestamp",
+    "prev_hash",
+    "self_hash",
+    "actor_id",
+    "target_ids",
+    "deed_type",
+    "tags",
+    "context_json",
+    "ethics_flags",
+    "life_harm_flag",
+    "domain",
+    EXT_FIELD,
+];
+const SIMULATION_FIELDS: &[&str] = &["run_id"];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    #[error("{UNKNOWN_CRITICAL_FIELD}: {path}")]
+    UnknownCriticalField { path: String },
+    #[error("malformed deed: {0}")]
+    Malformed(String),
+}
+
+/// The first key of object `value` outside `known`, as `path.key`.
+/// Non-objects pass; their shape is serde's to check.
+pub fn reject_unknown(value: &Value, path: &str, known: &[&str]) -> Result<(), SchemaError> {
+    let Some(object) = value.as_object() else {
+        return Ok(());
+    };
+    match object.keys().find(|k| !known.contains(&k.as_str())) {
+        Some(key) => Err(SchemaError::UnknownCriticalField { path: join(path, key) }),
+        None => Ok(()),
+    }
+}
+
+/// Check a serialized deed against the evolution rules.
+pub fn check_deed_fields(value: &Value) -> Result<(), SchemaError> {
+    reject_unknown(value, "", DEED_FIELDS)?;
+    if let Some(domain) = value.get("domain").and_then(Value::as_object) {
+        for (variant, body) in domain {
+            if variant != "simulation" {
+                return Err(SchemaError::UnknownCriticalField { path: format!("domain.{variant}") });
+            }
+            reject_unknown(body, "domain.simulation", SIMULATION_FIELDS)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn deed_from_value(value: Value) -> Result<DeedEvent, SchemaError> {
+    check_deed_fields(&value)?;
+    serde_json::from_value(value).map_err(|e| SchemaError::Malformed(e.to_string()))
+}
+
+pub fn parse_deed(text: &str) -> Result<DeedEvent, SchemaError> {
+    deed_from_value(serde_json::from_str(text).map_err(|e| SchemaError::Malformed(e.to_string()))?)
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
@@ -0,0 +1,1495 @@
+//! Balances for all four tokens, with issued/retired counters so the total
+//! supply can be reconciled against account balances at any time.
+//!
+//! Every balance change is logged as a deed whose context carries a
+//! `movements` list, so the ledger can be rebuilt from its deed log alone
+//! (`replay`). FEAR is diagnostic, not a reward: `mint_reward` refuses it,
+//! and the only way in is `accrue_fear`, which records why.
+//!
+//! Deeds in the open (unsealed) segment can be tombstoned. The original
+//! bytes stay in the chain; aggregates skip the target, and any balance
+//! movement it caused is undone by a compensating deed. Once a segment is
+//! sealed its deeds can only be corrected via the slash/quorum path.
+//!
+//! The sponsor pool is an ordinary account (`sponsor:pool`). Every flow in
+//! or out of it is its own `pool_inflow` / `pool_outflow` deed, and the
+//! configured tithe of each CHURCH mint is routed to it here, so no mint
+//! path can skip it.
+//!
+//! The ledger also owns the runtime parameter registry. Its governed values
+//! come from `parameter_change` deeds, which only the ledger writes, so a
+//! replay rebuilds them along with the balances.
+//!
+//! Reward held back for a category's follow-ups sits in the obligations
+//! escrow (`escrow:obligations`), again an ordinary account, until the
+//! follow-up settles it to the actor or a miss sweeps it to the pool.
+//!
+//! An `integrity_violation` deed from the self-audit freezes every
+//! mint-bearing operation until an operator lifts the freeze with an
+//! `integrity_cleared` deed; replay honours both.
+//!
+//! A regulator halt freezes high-impact deeds instead, from a
+//! `high_impact_frozen` deed to the `high_impact_freeze_lifted` deed that
+//! ends it (see `halt_review`); replay honours those too.
+//!
+//! Rewards screened as anomalous can be held in that same escrow by a
+//! `mint_screened` deed until an `anomaly_reviewed` deed pays them out or
+//! retires them (see `anomaly`).
+//!
+//! Deeds in categories that require a validation quorum have their reward
+//! held there too, by a `validation_pending` deed, until a
+//! `validation_resolved` deed pays it out, retires it or returns it to the
+//! pool (see `quorum`).
+//!
+//! While an `account_recovery_started` deed is pending, the recovering
+//! account receives no mints or pool payouts (see `identity`).
+//!
+//! A deed submitted during its category's cooldown is stored flagged
+//! `cooldown_suppressed` and mints nothing (see `cooldown`).
+//!
+//! A clinical attestation must name a registered provider, whose
+//! reliability at the deed's timestamp is pinned into it on append (see
+//! `providers`).
+//!
+//! Deeds the ledger writes itself carry authoritative timestamps: the
+//! node clock floored at the latest one it stamped, so they never go
+//! backwards across a clock correction.
+//!
+//! Simulation runs live beside the chain, not in it: each has its own
+//! sub-chain and shadow balances (see `simulation`), `append` refuses
+//! simulation deeds, and `deeds` / `supply_report` are live only.
+
+use faults::points::LEDGER_FSYNC;
+use log::warn;
+use param_registry::{ParamChangeRecord, ParamRegistry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use thiserror::Error;
+use tracing::{field, info_span};
+
+use crate::anomaly::{ANOMALY_BASELINE, ANOMALY_REVIEWED, ANOMALY_SUSPECT, MINT_SCREENED};
+use crate::compliance::data_minimization::MinimizationError;
+use crate::halt_review::{FREEZE_REVIEW_TICK, FROZEN_DEED_QUEUED, HIGH_IMPACT_FREEZE_LIFTED, HIGH_IMPACT_FROZEN};
+use crate::history::{self, HistoricalPoint, HistoryCache, HistoryError, StateAt};
+use crate::identity::{ACCOUNT_RECOVERY_CANCELLED, ACCOUNT_RECOVERY_STARTED, ACTOR_KEY_BOUND, ACTOR_KEY_ROTATED};
+use crate::audit::{INTEGRITY_CLEARED, INTEGRITY_VIOLATION};
+use crate::cold_storage::SEGMENT_MIGRATED;
+use crate::config::LedgerConfig;
+use crate::cooldown;
+use crate::ledger::account::{Account, Token};
+use crate::ledger::concentration::{PowerConcentration, PowerGini, PowerGiniCheck, GINI_ERROR_BOUND};
+use crate::ledger::builders::schema_for;
+use crate::ledger::deed_event::{hash_deed, DeedEvent, ExecutionDomain};
+use crate::ledger::metrics::BioloadMetrics;
+use crate::near_miss::NEAR_MISS_CORROBORATED;
+use crate::obligations::{OBLIGATION_MISSED, OBLIGATION_OPENED, OBLIGATION_SETTLED, PENDING_OBLIGATIONS};
+use crate::params::PARAMETER_CHANGE;
+use crate::policy_bundle::POLICY_BUNDLE_LOADED;
+use crate::providers::{self, PROVIDER_OUTCOME, PROVIDER_REGISTERED, PROVIDER_REINSTATED, PROVIDER_SUSPENDED};
+use crate::quorum::{VALIDATION_PENDING, VALIDATION_RESOLVED, VALIDATION_VOTE, VALIDATOR_REGISTERED};
+use crate::simulation::{SimRun, SIM_RUN_OPEN, SIM_RUN_PROMOTED};
+use crate::sponsor::equity::REWARD_PLAN;
+use crate::sponsor::pool::{tithe_of, InflowSource, POOL_INFLOW, POOL_OUTFLOW, SPONSOR_POOL};
+use crate::token::rewards::compute_tech_reward;
+use crate::utils::time::now_timestamp;
+use crate::vrf::{RANDOMNESS_COMMITTED, RANDOMNESS_DRAWN, RANDOMNESS_REVEALED};
+
+pub(crate) const LEDGER_ACTOR: &str = "ledger";
+pub const FEAR_ACCRUAL: &str = "fear_accrual";
+/// A CHURCH, PWR or TECH credit to `context_json.account_id`.
+pub const REWARD_CREDIT: &str = "reward_credit";
+pub(crate) const TOMBSTONE: &str = "tombstone";
+/// A sealed segment's index, bounds and Merkle root, logged as the first
+/// deed after it.
+pub const SEGMENT_SEALED: &str = "segment_sealed";
+const COMPENSATION: &str = "compensation";
+
+/// Deed types only the ledger writes; `append` and `append_sim` refuse them.
+const RESERVED: [&str; 35] = [
+    PARAMETER_CHANGE,
+    INTEGRITY_VIOLATION,
+    INTEGRITY_CLEARED,
+    OBLIGATION_OPENED,
+    OBLIGATION_SETTLED,
+    OBLIGATION_MISSED,
+    NEAR_MISS_CORROBORATED,
+    SIM_RUN_OPEN,
+    SIM_RUN_PROMOTED,
+    MINT_SCREENED,
+    ANOMALY_BASELINE,
+    ANOMALY_REVIEWED,
+    ACTOR_KEY_BOUND,
+    ACTOR_KEY_ROTATED,
+    ACCOUNT_RECOVERY_STARTED,
+    ACCOUNT_RECOVERY_CANCELLED,
+    VALIDATOR_REGISTERED,
+    VALIDATION_PENDING,
+    VALIDATION_VOTE,
+    VALIDATION_RESOLVED,
+    HIGH_IMPACT_FROZEN,
+    FREEZE_REVIEW_TICK,
+    FROZEN_DEED_QUEUED,
+    HIGH_IMPACT_FREEZE_LIFTED,
+    PROVIDER_REGISTERED,
+    PROVIDER_OUTCOME,
+    PROVIDER_SUSPENDED,
+    PROVIDER_REINSTATED,
+    REWARD_PLAN,
+    SEGMENT_MIGRATED,
+    RANDOMNESS_DRAWN,
+    RANDOMNESS_COMMITTED,
+    RANDOMNESS_REVEALED,
+    POLICY_BUNDLE_LOADED,
+    SEGMENT_SEALED,
+];
+
+/// Regulator transitions that accrue FEAR on the affected account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FearTrigger {
+    Warn,
+    ForceRepair,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TokenLedgerError {
+    #[error("unknown account {0}")]
+    UnknownAccount(String),
+    #[error("{0:?} is not a reward token")]
+    NotARewardToken(Token),
+    #[error("deed type {0} is not technology-positive")]
+    NotTechPositive(String),
+    #[error("decay rate {0} outside [0, 1]")]
+    InvalidRate(f64),
+    #[error("deed prev_hash {got} does not extend ledger tip {expected}")]
+    ChainBroken { expected: String, got: String },
+    /// The deed is on the chain; re-read the tip instead of retrying it.
+    #[error("deed {0} was appended but its sync failed")]
+    SyncFailed(String),
+    #[error("unknown deed {0}")]
+    UnknownDeed(String),
+    #[error("deed {0} is in a sealed segment; use the slash/quorum path")]
+    Sealed(String),
+    #[error("deed {0} is already tombstoned")]
+    AlreadyTombstoned(String),
+    #[error("{0} deeds cannot be tombstoned")]
+    NotCorrectable(String),
+    #[error("role {0} may not tombstone deeds")]
+    RoleNotAllowed(String),
+    #[error(transparent)]
+    Minimization(#[from] MinimizationError),
+    #[error("mints are frozen by integrity violation {0}")]
+    MintsFrozen(String),
+    #[error("mints to {account} are frozen by account recovery {recovery}")]
+    AccountRecovering { account: String, recovery: String },
+    #[error("mints are not frozen")]
+    NotFrozen,
+    #[error("{0} deeds are written by the ledger only")]
+    ReservedDeedType(String),
+    #[error("deed belongs to simulation run {0} and cannot enter the live chain")]
+    SimulationDeed(String),
+    #[error("unknown simulation run {0}")]
+    UnknownSimRun(String),
+    #[error("simulation run {0} is already open")]
+    SimRunExists(String),
+    #[error("simulation run {0} has been promoted and is closed")]
+    SimRunPromoted(String),
+    /// `id` is the deed's event id on replay, the change id otherwise.
+    #[error("invalid parameter change {id}: {reason}")]
+    InvalidParamChange { id: String, reason: String },
+    #[error("attestations must name a registered provider, not {0:?}")]
+    UnregisteredProvider(String),
+    /// A replayed `segment_sealed` deed that is not the next seal.
+    #[error("segment seal {id} does not match the log: {reason}")]
+    InvalidSeal { id: String, reason: String },
+}
+
+/// One balance change recorded in a deed's context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Movement {
+    pub account_id: String,
+    pub token: Token,
+    /// Positive = issued to the account, negative = retired from it.
+    pub delta: i64,
+}
+
+fn clamp(amount: u64) -> i64 {
+    amount.min(i64::MAX as u64) as i64
+}
+
+pub(crate) fn movements_of(deed: &DeedEvent) -> Vec<Movement> {
+    deed.context_json
+        .get("movements")
+        .and_then(|m| serde_json::from_value(m.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSupply {
+    pub issued: u64,
+    pub retired: u64,
+    /// Sum of account balances.
+    pub circulating: u64,
+}
+
+impl TokenSupply {
+    pub fn reconciles(&self) -> bool {
+        self.issued.checked_sub(self.retired) == Some(self.circulating)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupplyReport {
+    pub accounts: usize,
+    pub tokens: BTreeMap<Token, TokenSupply>,
+}
+
+impl SupplyReport {
+    pub fn reconciles(&self) -> bool {
+        self.tokens.values().all(TokenSupply::reconciles)
+    }
+
+    /// Prometheus text exposition of the supply gauges.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# TYPE cof_accounts gauge\n");
+        writeln!(out, "cof_accounts {}", self.accounts).unwrap();
+        for (name, pick) in [
+            ("cof_token_issued_total", (|s: &TokenSupply| s.issued) as fn(&TokenSupply) -> u64),
+            ("cof_token_retired_total", |s| s.retired),
+            ("cof_token_circulating", |s| s.circulating),
+        ] {
+            let kind = if name.ends_with("_total") { "counter" } else { "gauge" };
+            writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+            for (token, supply) in &self.tokens {
+                writeln!(out, "{}{{token=\"{}\"}} {}", name, token.as_str(), pick(supply)).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// A sealed range of the deed log, `[first, last]` by position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedSegment {
+    pub index: usize,
+    pub first: usize,
+    pub last: usize,
+    pub tip_hash: String,
+}
+
+/// Merkle root over the self_hashes of `segment`'s deeds.
+pub fn segment_root(ledger: &TokenLedger, segment: &SealedSegment) -> String {
+    deed_core::merkle_root(ledger.deeds()[segment.first..=segment.last].iter().map(|d| d.self_hash.clone()).collect())
+}
+
+/// Half-open time window `[start, end)` in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrectionWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrectionEntry {
+    pub tombstone_event_id: String,
+    pub timestamp: i64,
+    pub target_event_id: String,
+    pub target_deed_type: String,
+    pub target_actor_id: String,
+    pub reason: String,
+    pub operator_role: String,
+    /// Movements undone by the compensating deed, if any.
+    pub compensated: Vec<Movement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenLedger {
+    cfg: LedgerConfig,
+    accounts: BTreeMap<String, Account>,
+    deeds: Vec<DeedEvent>,
+    /// event_id → position in `deeds`.
+    positions: HashMap<String, usize>,
+    /// Target id → positions of the deeds naming it, ledger-authored
+    /// deeds excepted.
+    targets: BTreeMap<String, Vec<usize>>,
+    tombstoned: HashSet<String>,
+    /// Deeds before this position are sealed.
+    sealed_len: usize,
+    segments: Vec<SealedSegment>,
+    issued: BTreeMap<Token, u64>,
+    retired: BTreeMap<Token, u64>,
+    params: ParamRegistry,
+    /// Event id of the `integrity_violation` deed holding mints frozen.
+    mint_freeze: Option<String>,
+    /// Event id of the `high_impact_frozen` deed of the active freeze.
+    high_impact_freeze: Option<String>,
+    /// Account → event id of its pending `account_recovery_started` deed.
+    recoveries: BTreeMap<String, String>,
+    /// Simulation runs by run id.
+    sims: BTreeMap<String, SimRun>,
+    /// Historical views by tip hash (see `state_at`).
+    history: HistoryCache,
+    /// Latest timestamp on a ledger-authored deed.
+    stamped_until: i64,
+    /// PWR balance histograms behind `power_gini`.
+    power: PowerConcentration,
+    /// Hash in the latest `policy_bundle_loaded` deed.
+    policy_bundle: Option<String>,
+}
+
+impl TokenLedger {
+    /// Invalid parameter overrides in `cfg` are logged and skipped.
+    pub fn new(cfg: LedgerConfig) -> Self {
+        let mut params = ParamRegistry::default();
+        for (name, value) in &cfg.param_overrides {
+            if let Err(e) = params.apply_config(name, value, "ledger config") {
+                warn!("Ignoring parameter override {}: {}", name, e);
+            }
+        }
+        let power = PowerConcentration::new(cfg.concentration.clone());
+        Self {
+            cfg,
+            accounts: BTreeMap::new(),
+            deeds: Vec::new(),
+            positions: HashMap::new(),
+            targets: BTreeMap::new(),
+            tombstoned: HashSet::new(),
+            sealed_len: 0,
+            segments: Vec::new(),
+            issued: BTreeMap::new(),
+            retired: BTreeMap::new(),
+            params,
+            mint_freeze: None,
+            high_impact_freeze: None,
+            recoveries: BTreeMap::new(),
+            sims: BTreeMap::new(),
+            history: HistoryCache::default(),
+            stamped_until: i64::MIN,
+            power,
+            policy_bundle: None,
+        }
+    }
+
+    /// Rebuild balances, supply counters, tombstones and governed
+    /// parameters from a deed log. Accounts are opened on first reference.
+    /// Simulation deeds rebuild their run's sub-chain and shadow balances.
+    pub fn replay<I: IntoIterator<Item = DeedEvent>>(cfg: LedgerConfig, deeds: I) -> Result<Self, TokenLedgerError> {
+        let mut ledger = Self::new(cfg);
+        for deed in deeds {
+            if let Some(run_id) = deed.domain.run_id() {
+                let run_id = run_id.to_string();
+                if deed.deed_type == SIM_RUN_OPEN && !ledger.sims.contains_key(&run_id) {
+                    ledger.sims.insert(run_id.clone(), SimRun::new(&run_id));
+                }
+                ledger.push_sim(&run_id, deed)?;
+                continue;
+            }
+            ledger.ingest(deed)?;
+        }
+        // Exports list sub-chains after the live chain, so promotions are
+        // matched to their runs last.
+        let promotions: Vec<(String, String)> = ledger
+            .deeds
+            .iter()
+            .filter(|d| d.deed_type == SIM_RUN_PROMOTED)
+            .filter_map(|d| d.context_json["run_id"].as_str().map(|run| (run.to_string(), d.event_id.clone())))
+            .collect();
+        for (run_id, event_id) in promotions {
+            ledger.sims.get_mut(&run_id).ok_or(TokenLedgerError::UnknownSimRun(run_id))?.promoted = Some(event_id);
+        }
+        Ok(ledger)
+    }
+
+    /// Apply one live deed as `replay` does: its movements, tombstones,
+    /// parameter changes, freezes and recoveries, then the chain.
+    fn ingest(&mut self, deed: DeedEvent) -> Result<(), TokenLedgerError> {
+        for m in movements_of(&deed) {
+            self.open_account(&m.account_id, &m.account_id);
+            self.apply(&m)?;
+        }
+        if deed.deed_type == TOMBSTONE {
+            let covered = deed.context_json["covered_event_ids"].as_array().cloned().unwrap_or_default();
+            self.tombstoned.extend(covered.iter().filter_map(|id| id.as_str().map(str::to_string)));
+        }
+        if deed.deed_type == PARAMETER_CHANGE {
+            let invalid = |reason: String| TokenLedgerError::InvalidParamChange { id: deed.event_id.clone(), reason };
+            let record: ParamChangeRecord =
+                serde_json::from_value(deed.context_json.clone()).map_err(|e| invalid(e.to_string()))?;
+            let record = ParamChangeRecord { event_id: Some(deed.event_id.clone()), ..record };
+            self.params.commit(record).map_err(|e| invalid(e.to_string()))?;
+        }
+        if deed.deed_type == POLICY_BUNDLE_LOADED {
+            self.policy_bundle = deed.context_json["bundle_hash"].as_str().map(str::to_string);
+        }
+        if deed.deed_type == SEGMENT_SEALED {
+            self.restore_seal(&deed)?;
+        }
+        if deed.deed_type == INTEGRITY_VIOLATION {
+            self.mint_freeze = Some(deed.event_id.clone());
+        } else if deed.deed_type == INTEGRITY_CLEARED {
+            self.mint_freeze = None;
+        }
+        self.track_high_impact_freeze(&deed.deed_type, &deed.event_id);
+        self.track_recovery(&deed);
+        self.push(deed)?;
+        Ok(())
+    }
+
+    /// Seal again the segment a replayed `segment_sealed` deed records. It
+    /// must be the next segment, end just before the deed, and have the
+    /// recorded root.
+    fn restore_seal(&mut self, deed: &DeedEvent) -> Result<(), TokenLedgerError> {
+        let invalid = |reason: &str| TokenLedgerError::InvalidSeal { id: deed.event_id.clone(), reason: reason.to_string() };
+        let field = |name: &str| deed.context_json[name].as_u64().map(|v| v as usize);
+        let (Some(index), Some(first), Some(last)) = (field("index"), field("first"), field("last")) else {
+            return Err(invalid("missing index or bounds"));
+        };
+        if index != self.segments.len() || first != self.sealed_len || first > last || last + 1 != self.deeds.len() {
+            return Err(invalid("not the next segment, ending just before the seal"));
+        }
+        let segment = SealedSegment { index, first, last, tip_hash: self.last_hash() };
+        if deed.context_json["root"].as_str() != Some(segment_root(self, &segment).as_str()) {
+            return Err(invalid("root differs from the sealed deeds"));
+        }
+        self.sealed_len = self.deeds.len();
+        self.segments.push(segment);
+        Ok(())
+    }
+
+    /// Append a deed copied from another node's live chain, e.g. by a
+    /// replica. Unlike `append` it takes ledger-authored deed types and
+    /// does not re-run the minimization policy, which would change the
+    /// deed's hash; the caller verifies the deed first.
+    pub fn replicate(&mut self, deed: DeedEvent) -> Result<&DeedEvent, TokenLedgerError> {
+        if let Some(run_id) = deed.domain.run_id() {
+            return Err(TokenLedgerError::SimulationDeed(run_id.to_string()));
+        }
+        self.ingest(deed)?;
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Read-only state as of an earlier point of the live chain, replayed
+    /// from the deeds up to it and cached by tip hash (see `history`).
+    pub fn state_at(&self, point: &HistoricalPoint) -> Result<StateAt, HistoryError> {
+        let (pos, between_events) = history::resolve(&self.deeds, point)?;
+        let view = self.history.get_or_build(&self.deeds[pos].self_hash, &self.segments, || {
+            let sims = self.sims.values().flat_map(|run| run.deeds.iter());
+            Self::replay(self.cfg.clone(), self.deeds[..=pos].iter().chain(sims).cloned())
+        })?;
+        Ok(StateAt { view, between_events })
+    }
+
+    pub fn config(&self) -> &LedgerConfig {
+        &self.cfg
+    }
+
+    pub fn params(&self) -> &ParamRegistry {
+        &self.params
+    }
+
+    /// Open `id` if it does not exist yet; existing accounts are left as is.
+    pub fn open_account(&mut self, id: &str, owner: &str) -> &Account {
+        if !self.accounts.contains_key(id) {
+            self.power.opened(id, 0);
+        }
+        self.accounts.entry(id.to_string()).or_insert_with(|| Account::new(id.to_string(), owner.to_string()))
+    }
+
+    pub fn account(&self, id: &str) -> Option<&Account> {
+        self.accounts.get(id)
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    /// The full live chain, tombstoned deeds included.
+    pub fn deeds(&self) -> &[DeedEvent] {
+        &self.deeds
+    }
+
+    /// Deeds to export: the live chain, then with `include_simulations`
+    /// every simulation sub-chain in run id order.
+    pub fn export_deeds(&self, include_simulations: bool) -> Vec<&DeedEvent> {
+        let sims = self.sims.values().filter(|_| include_simulations).flat_map(|run| run.deeds.iter());
+        self.deeds.iter().chain(sims).collect()
+    }
+
+    pub fn deed(&self, event_id: &str) -> Option<&DeedEvent> {
+        self.positions.get(event_id).map(|&pos| &self.deeds[pos])
+    }
+
+    /// Deeds naming `target_id`, in chain order and tombstoned ones
+    /// included. Ledger-authored deeds (tombstones, credits) are not
+    /// indexed: their targets are event ids and accounts.
+    pub fn deeds_naming<'a>(&'a self, target_id: &str) -> impl Iterator<Item = &'a DeedEvent> + 'a {
+        self.targets.get(target_id).into_iter().flatten().map(|&pos| &self.deeds[pos])
+    }
+
+    /// Every target id some deed names, in order.
+    pub fn target_ids(&self) -> impl Iterator<Item = &str> {
+        self.targets.keys().map(String::as_str)
+    }
+
+    pub fn is_tombstoned(&self, event_id: &str) -> bool {
+        self.tombstoned.contains(event_id)
+    }
+
+    /// Deeds that aggregates (eco_score, reputation, analytics) should see:
+    /// everything except tombstoned deeds and correction bookkeeping.
+    pub fn live_deeds(&self) -> impl Iterator<Item = &DeedEvent> {
+        self.deeds.iter().filter(|d| {
+            !self.tombstoned.contains(&d.event_id) && d.deed_type != TOMBSTONE && d.deed_type != COMPENSATION
+        })
+    }
+
+    pub fn deeds_for_actor<'a>(&'a self, actor_id: &'a str) -> impl Iterator<Item = &'a DeedEvent> {
+        self.live_deeds().filter(move |d| d.actor_id == actor_id)
+    }
+
+    /// Live deed counts by deed_type.
+    pub fn deed_type_counts(&self) -> BTreeMap<String, usize> {
+        let mut out = BTreeMap::new();
+        for d in self.live_deeds() {
+            *out.entry(d.deed_type.clone()).or_insert(0) += 1;
+        }
+        out
+    }
+
+    pub fn last_hash(&self) -> String {
+        self.deeds.last().map(|d| d.self_hash.clone()).unwrap_or_else(|| "0".repeat(64))
+    }
+
+    fn push(&mut self, deed: DeedEvent) -> Result<(), TokenLedgerError> {
+        let _span = info_span!(
+            "ledger_write",
+            event_id = %deed.event_id,
+            actor = %deed.actor_id,
+            deed_type = %deed.deed_type,
+            position = self.deeds.len(),
+        )
+        .entered();
+        let expected = self.last_hash();
+        if deed.prev_hash != expected {
+            return Err(TokenLedgerError::ChainBroken { expected, got: deed.prev_hash });
+        }
+        self.positions.insert(deed.event_id.clone(), self.deeds.len());
+        if deed.actor_id != LEDGER_ACTOR {
+            for target in &deed.target_ids {
+                self.targets.entry(target.clone()).or_default().push(self.deeds.len());
+            }
+        } else {
+            self.stamped_until = self.stamped_until.max(deed.timestamp);
+        }
+        self.deeds.push(deed);
+        if faults::hit(LEDGER_FSYNC).is_some() {
+            return Err(TokenLedgerError::SyncFailed(self.deeds.last().expect("just pushed").event_id.clone()));
+        }
+        Ok(())
+    }
+
+    /// The timestamp the ledger stamps its own deeds with: the clock, but
+    /// never earlier than a deed it stamped before, so a clock stepped
+    /// backwards cannot reorder ledger-authored time.
+    pub fn authoritative_now(&self) -> i64 {
+        now_timestamp().max(self.stamped_until)
+    }
+
+    /// Append an externally built deed; it must extend the current tip.
+    /// Neuro deeds pass the data-minimization policy first, which may
+    /// reject them or strip fields (rehashing the deed). A deed in its
+    /// category's cooldown is flagged `cooldown_suppressed` (rehashing it
+    /// as well).
+    pub fn append(&mut self, deed: DeedEvent) -> Result<&DeedEvent, TokenLedgerError> {
+        if let Some(run_id) = deed.domain.run_id() {
+            return Err(TokenLedgerError::SimulationDeed(run_id.to_string()));
+        }
+        if RESERVED.contains(&deed.deed_type.as_str()) {
+            return Err(TokenLedgerError::ReservedDeedType(deed.deed_type));
+        }
+        let deed = {
+            let span = info_span!("guards", actor = %deed.actor_id, deed_type = %deed.deed_type, decision = field::Empty);
+            let _entered = span.enter();
+            let hash = deed.self_hash.clone();
+            let result = self.cfg.minimization.enforce(deed);
+            span.record("decision", match &result {
+                Ok(d) if d.self_hash == hash => "pass",
+                Ok(_) => "stripped",
+                Err(_) => "rejected",
+            });
+            result?
+        };
+        let deed = match self.cooldown(&deed) {
+            Some(_) => cooldown::suppress(deed),
+            None => deed,
+        };
+        let deed = providers::pin_weight(self, deed)?;
+        self.push(deed)?;
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Log a ledger-authored deed carrying `movements` plus `extra` context.
+    fn log(
+        &mut self,
+        deed_type: &str,
+        targets: Vec<String>,
+        context: serde_json::Value,
+        movements: &[Movement],
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log_flagged(deed_type, targets, context, movements, Vec::new())
+    }
+
+    /// `log` with ethics flags on the deed.
+    fn log_flagged(
+        &mut self,
+        deed_type: &str,
+        targets: Vec<String>,
+        mut context: serde_json::Value,
+        movements: &[Movement],
+        ethics_flags: Vec<String>,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        context["movements"] = serde_json::to_value(movements).expect("movements serialize");
+        let mut deed = DeedEvent::new(
+            self.last_hash(),
+            LEDGER_ACTOR.to_string(),
+            targets,
+            deed_type.to_string(),
+            Vec::new(),
+            context,
+            ethics_flags,
+            false,
+        );
+        let stamped = deed.timestamp.max(self.stamped_until);
+        if deed.timestamp != stamped {
+            deed.timestamp = stamped;
+            deed.self_hash = String::new();
+            deed.self_hash = hash_deed(&deed);
+        }
+        self.push(deed)?;
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Log an authorized parameter change and apply it to the registry.
+    #[cfg(feature = "param-governance")]
+    pub(crate) fn log_param_change(&mut self, record: ParamChangeRecord) -> Result<ParamChangeRecord, TokenLedgerError> {
+        self.params.check_change(&record.change_id, &record.param, &record.value).map_err(|e| {
+            TokenLedgerError::InvalidParamChange { id: record.change_id.clone(), reason: e.to_string() }
+        })?;
+        let context = serde_json::to_value(&record).expect("parameter change serializes");
+        let event_id = self.log(PARAMETER_CHANGE, Vec::new(), context, &[])?.event_id.clone();
+        let record = ParamChangeRecord { event_id: Some(event_id), ..record };
+        self.params.commit(record.clone()).expect("checked before logging");
+        Ok(record)
+    }
+
+    /// The `integrity_violation` deed mints are frozen by, if any.
+    pub fn mint_freeze(&self) -> Option<&str> {
+        self.mint_freeze.as_deref()
+    }
+
+    fn check_mints(&self) -> Result<(), TokenLedgerError> {
+        match &self.mint_freeze {
+            Some(event_id) => Err(TokenLedgerError::MintsFrozen(event_id.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Log an `integrity_violation` deed with `context` and freeze mints.
+    pub fn record_integrity_violation(&mut self, context: serde_json::Value) -> Result<&DeedEvent, TokenLedgerError> {
+        let event_id = self.log(INTEGRITY_VIOLATION, Vec::new(), context, &[])?.event_id.clone();
+        self.mint_freeze = Some(event_id);
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Lift the mint freeze once the log has been repaired; only
+    /// correction roles may, and the lift is an `integrity_cleared` deed.
+    pub fn lift_mint_freeze(&mut self, reason: &str, operator_role: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        if !self.cfg.correction_roles.iter().any(|r| r == operator_role) {
+            return Err(TokenLedgerError::RoleNotAllowed(operator_role.to_string()));
+        }
+        let Some(violation) = self.mint_freeze.take() else {
+            return Err(TokenLedgerError::NotFrozen);
+        };
+        let context = serde_json::json!({ "violation_event_id": violation, "reason": reason, "operator_role": operator_role });
+        self.log(INTEGRITY_CLEARED, vec![violation], context, &[])
+    }
+
+    /// The `high_impact_frozen` deed high-impact deeds are frozen by, if any.
+    pub fn high_impact_freeze(&self) -> Option<&str> {
+        self.high_impact_freeze.as_deref()
+    }
+
+    fn track_high_impact_freeze(&mut self, deed_type: &str, event_id: &str) {
+        match deed_type {
+            HIGH_IMPACT_FROZEN => self.high_impact_freeze = Some(event_id.to_string()),
+            HIGH_IMPACT_FREEZE_LIFTED => self.high_impact_freeze = None,
+            _ => {}
+        }
+    }
+
+    /// Log a freeze, review tick, queued deed or lift (see `halt_review`);
+    /// none of them moves a balance.
+    pub(crate) fn log_halt_review(
+        &mut self,
+        deed_type: &str,
+        targets: Vec<String>,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        let event_id = self.log(deed_type, targets, context, &[])?.event_id.clone();
+        self.track_high_impact_freeze(deed_type, &event_id);
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Log a provider registration, outcome, suspension or reinstatement
+    /// (see `providers`); none of them moves a balance.
+    pub(crate) fn log_provider(
+        &mut self,
+        deed_type: &str,
+        provider_id: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(deed_type, vec![provider_id.to_string()], context, &[])
+    }
+
+    /// Log an equity-rebalanced reward plan (see `sponsor::equity`); the
+    /// plan moves nothing until it is funded.
+    pub(crate) fn log_reward_plan(
+        &mut self,
+        accounts: Vec<String>,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(REWARD_PLAN, accounts, context, &[])
+    }
+
+    /// Log a sealed segment's move to the cold tier (see `cold_storage`).
+    pub(crate) fn log_segment_migrated(&mut self, context: serde_json::Value) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(SEGMENT_MIGRATED, Vec::new(), context, &[])
+    }
+
+    /// Log the policy bundle the node now runs under (see `policy_bundle`).
+    pub(crate) fn log_policy_bundle(&mut self, context: serde_json::Value) -> Result<&DeedEvent, TokenLedgerError> {
+        let hash = context["bundle_hash"].as_str().map(str::to_string);
+        self.log(POLICY_BUNDLE_LOADED, Vec::new(), context, &[])?;
+        self.policy_bundle = hash;
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// Hash of the policy bundle the ledger last recorded, if any.
+    pub fn policy_bundle(&self) -> Option<&str> {
+        self.policy_bundle.as_deref()
+    }
+
+    /// Log a randomness draw, commitment or reveal (see `vrf`).
+    pub(crate) fn log_randomness(
+        &mut self,
+        deed_type: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(deed_type, Vec::new(), context, &[])
+    }
+
+    /// The pending recovery freezing mints to `id`, if any.
+    pub fn account_recovery(&self, id: &str) -> Option<&str> {
+        self.recoveries.get(id).map(String::as_str)
+    }
+
+    fn check_account_mints(&self, id: &str) -> Result<(), TokenLedgerError> {
+        match self.recoveries.get(id) {
+            Some(recovery) => Err(TokenLedgerError::AccountRecovering { account: id.to_string(), recovery: recovery.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    /// A recovery starts on its deed and ends on the rotation or
+    /// cancellation that follows it.
+    fn track_recovery(&mut self, deed: &DeedEvent) {
+        let Some(actor_id) = deed.target_ids.first() else { return };
+        match deed.deed_type.as_str() {
+            ACCOUNT_RECOVERY_STARTED => {
+                self.recoveries.insert(actor_id.clone(), deed.event_id.clone());
+            }
+            ACTOR_KEY_ROTATED | ACCOUNT_RECOVERY_CANCELLED => {
+                self.recoveries.remove(actor_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Log a key or recovery deed targeting `actor_id` (see `identity`).
+    #[cfg(feature = "actor-keys")]
+    pub(crate) fn log_identity(
+        &mut self,
+        deed_type: &str,
+        actor_id: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        let deed = self.log(deed_type, vec![actor_id.to_string()], context, &[])?.clone();
+        self.track_recovery(&deed);
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    fn account_mut(&mut self, id: &str) -> Result<&mut Account, TokenLedgerError> {
+        self.accounts.get_mut(id).ok_or_else(|| TokenLedgerError::UnknownAccount(id.to_string()))
+    }
+
+    /// Apply a movement exactly; returns the movement actually applied
+    /// (debits saturate at zero).
+    fn apply(&mut self, m: &Movement) -> Result<Movement, TokenLedgerError> {
+        let account = self.accounts.get_mut(&m.account_id).ok_or_else(|| TokenLedgerError::UnknownAccount(m.account_id.clone()))?;
+        let before = account.balance_pwr;
+        let applied = apply_to(account, &mut self.issued, &mut self.retired, m);
+        self.power.moved(&m.account_id, before, account.balance_pwr);
+        Ok(applied)
+    }
+
+    fn issue(&mut self, id: &str, token: Token, amount: u64) -> Result<Movement, TokenLedgerError> {
+        self.apply(&Movement { account_id: id.to_string(), token, delta: clamp(amount) })
+    }
+
+    /// Credit a reward; for CHURCH the pool's tithe is split off first and
+    /// logged as its own `pool_inflow` deed naming the same source.
+    fn credit_logged(&mut self, id: &str, token: Token, amount: u64, source: Option<&str>) -> Result<u64, TokenLedgerError> {
+        self.check_mints()?;
+        self.check_account_mints(id)?;
+        let tithe = if token == Token::Church && id != SPONSOR_POOL { tithe_of(&self.cfg.pool, amount) } else { 0 };
+        let m = self.issue(id, token, amount - tithe)?;
+        let added = m.delta as u64;
+        let context = serde_json::json!({ "source_event_id": source, "account_id": id, "token": token, "amount": added });
+        let reward_event_id = self.log(REWARD_CREDIT, vec![id.to_string()], context, &[m])?.event_id.clone();
+        if tithe > 0 {
+            self.open_account(SPONSOR_POOL, SPONSOR_POOL);
+            let m = self.issue(SPONSOR_POOL, Token::Church, tithe)?;
+            let context = serde_json::json!({
+                "source": InflowSource::Tithe,
+                "source_event_id": source,
+                "reward_event_id": reward_event_id,
+                "amount": m.delta,
+            });
+            self.log(POOL_INFLOW, vec![id.to_string()], context, &[m])?;
+        }
+        Ok(added)
+    }
+
+    /// CHURCH held by the sponsor pool.
+    pub fn pool_balance(&self) -> u64 {
+        self.account(SPONSOR_POOL).map_or(0, |a| a.balance_church)
+    }
+
+    /// Add CHURCH to the pool. With `from`, the tokens move out of that
+    /// account (saturating at its balance); without, they are newly issued.
+    /// `extra` is merged into the deed context. Returns the amount added.
+    pub fn pool_inflow(
+        &mut self,
+        source: InflowSource,
+        from: Option<&str>,
+        amount: u64,
+        extra: serde_json::Value,
+    ) -> Result<u64, TokenLedgerError> {
+        // Without a paying account the inflow is new supply.
+        if from.is_none() {
+            self.check_mints()?;
+        }
+        self.open_account(SPONSOR_POOL, SPONSOR_POOL);
+        let mut movements = Vec::new();
+        let moved = match from {
+            Some(id) => {
+                let debit = self.apply(&Movement { account_id: id.to_string(), token: Token::Church, delta: -clamp(amount) })?;
+                let moved = debit.delta.unsigned_abs();
+                movements.push(debit);
+                moved
+            }
+            None => amount,
+        };
+        movements.push(self.issue(SPONSOR_POOL, Token::Church, moved)?);
+        let mut context = serde_json::json!({ "source": source, "from": from, "amount": moved });
+        if let (Some(ctx), serde_json::Value::Object(extra)) = (context.as_object_mut(), extra) {
+            ctx.extend(extra);
+        }
+        self.log(POOL_INFLOW, from.map(str::to_string).into_iter().collect(), context, &movements)?;
+        Ok(moved)
+    }
+
+    /// Pay `amount` CHURCH out of the pool to `to`, never more than the
+    /// pool holds. Returns the amount paid.
+    pub fn pool_outflow(&mut self, to: &str, amount: u64, reason: &str) -> Result<u64, TokenLedgerError> {
+        self.account_mut(to)?;
+        self.check_account_mints(to)?;
+        let amount = amount.min(self.pool_balance());
+        if amount == 0 {
+            return Ok(0);
+        }
+        let debit = self.apply(&Movement { account_id: SPONSOR_POOL.to_string(), token: Token::Church, delta: -clamp(amount) })?;
+        let credit = self.issue(to, Token::Church, amount)?;
+        let context = serde_json::json!({ "account_id": to, "amount": amount, "reason": reason });
+        self.log(POOL_OUTFLOW, vec![to.to_string()], context, &[debit, credit])?;
+        Ok(amount)
+    }
+
+    /// Issue `amount` CHURCH into the obligations escrow on behalf of deed
+    /// `source`, logged as an `obligation_opened` deed with `context`.
+    /// Escrowed CHURCH is not tithed. Returns the deed's event id.
+    pub(crate) fn open_obligation(
+        &mut self,
+        source: &str,
+        amount: u64,
+        mut context: serde_json::Value,
+    ) -> Result<String, TokenLedgerError> {
+        self.check_mints()?;
+        self.open_account(PENDING_OBLIGATIONS, PENDING_OBLIGATIONS);
+        let m = self.issue(PENDING_OBLIGATIONS, Token::Church, amount)?;
+        context["source_event_id"] = serde_json::json!(source);
+        context["amount"] = serde_json::json!(m.delta);
+        Ok(self.log(OBLIGATION_OPENED, vec![source.to_string()], context, &[m])?.event_id.clone())
+    }
+
+    /// Pay obligation `obligation_id`'s escrow of `amount` out to `to`,
+    /// logged as an `obligation_settled` deed with `context`.
+    pub(crate) fn settle_obligation(
+        &mut self,
+        obligation_id: &str,
+        to: &str,
+        amount: u64,
+        mut context: serde_json::Value,
+    ) -> Result<u64, TokenLedgerError> {
+        self.account_mut(to)?;
+        let debit =
+            self.apply(&Movement { account_id: PENDING_OBLIGATIONS.to_string(), token: Token::Church, delta: -clamp(amount) })?;
+        let paid = debit.delta.unsigned_abs();
+        let credit = self.issue(to, Token::Church, paid)?;
+        context["account_id"] = serde_json::json!(to);
+        context["amount"] = serde_json::json!(paid);
+        self.log(OBLIGATION_SETTLED, vec![obligation_id.to_string()], context, &[debit, credit])?;
+        Ok(paid)
+    }
+
+    /// Record that obligation `obligation_id` was missed. The escrow itself
+    /// leaves through `pool_inflow` or `burn`.
+    pub(crate) fn log_obligation_missed(
+        &mut self,
+        obligation_id: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(OBLIGATION_MISSED, vec![obligation_id.to_string()], context, &[])
+    }
+
+    /// Record the corroboration of near-miss cluster `cluster_id`; the WISE
+    /// credit it carries is reputation, not a balance movement.
+    pub(crate) fn log_near_miss_corroboration(
+        &mut self,
+        cluster_id: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(NEAR_MISS_CORROBORATED, vec![cluster_id.to_string()], context, &[])
+    }
+
+    /// Record a screened mint of `actor_id`, flagged `anomaly_suspect` if
+    /// `flagged`. With `hold`, that reward is issued into the obligations
+    /// escrow (untithed) instead. Returns the deed's event id.
+    pub(crate) fn log_mint_screened(
+        &mut self,
+        actor_id: &str,
+        context: serde_json::Value,
+        flagged: bool,
+        hold: Option<(Token, u64)>,
+    ) -> Result<String, TokenLedgerError> {
+        let mut movements = Vec::new();
+        if let Some((token, amount)) = hold {
+            self.check_mints()?;
+            self.open_account(PENDING_OBLIGATIONS, PENDING_OBLIGATIONS);
+            movements.push(self.issue(PENDING_OBLIGATIONS, token, amount)?);
+        }
+        let flags = if flagged { vec![ANOMALY_SUSPECT.to_string()] } else { Vec::new() };
+        Ok(self.log_flagged(MINT_SCREENED, vec![actor_id.to_string()], context, &movements, flags)?.event_id.clone())
+    }
+
+    pub(crate) fn log_anomaly_baseline(&mut self, context: serde_json::Value) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(ANOMALY_BASELINE, Vec::new(), context, &[])
+    }
+
+    /// Close the hold placed by `screening_id`: pay `amount` of `token` out
+    /// of escrow to `payee`, or retire it without one. Returns the amount paid.
+    pub(crate) fn log_anomaly_review(
+        &mut self,
+        screening_id: &str,
+        payee: Option<&str>,
+        token: Token,
+        amount: u64,
+        context: serde_json::Value,
+    ) -> Result<u64, TokenLedgerError> {
+        self.release_escrow(ANOMALY_REVIEWED, screening_id, payee, token, amount, context)
+    }
+
+    /// Record validator `validator_id`'s registration (see `quorum`).
+    pub(crate) fn log_validator_registered(
+        &mut self,
+        validator_id: &str,
+        context: serde_json::Value,
+    ) -> Result<&DeedEvent, TokenLedgerError> {
+        self.log(VALIDATOR_REGISTERED, vec![validator_id.to_string()], context, &[])
+    }
+
+    /// Issue `amount` CHURCH into the obligations escrow while deed `source`
+    /// awaits its validation quorum. Returns the `validation_pending` deed's
+    /// event id.
+    pub(crate) fn log_validation_pending(
+        &mut self,
+        source: &str,
+        amount: u64,
+        mut context: serde_json::Value,
+    ) -> Result<String, TokenLedgerError> {
+        self.check_mints()?;
+        self.open_account(PENDING_OBLIGATIONS, PENDING_OBLIGATIONS);
+        let m = self.issue(PENDING_OBLIGATIONS, Token::Church, amount)?;
+        context["amount"] = serde_json::json!(m.delta);
+        Ok(self.log(VALIDATION_PENDING, vec![source.to_string()], context, &[m])?.event_id.clone())
+    }
+
+    #[cfg(feature = "validation-quorum")]
+    pub(crate) fn log_validation_vote(
+        &mut self,
+        pending_id: &str,
+        context: serde_json::Value,
+    ) -> Result<String, TokenLedgerError> {
+        Ok(self.log(VALIDATION_VOTE, vec![pending_id.to_string()], context, &[])?.event_id.clone())
+    }
+
+    /// Close validation `pending_id`: pay its escrow to `payee` (the actor
+    /// on approval, the sponsor pool on expiry) or retire it without one.
+    /// Returns the amount paid.
+    pub(crate) fn log_validation_resolved(
+        &mut self,
+        pending_id: &str,
+        payee: Option<&str>,
+        amount: u64,
+        context: serde_json::Value,
+    ) -> Result<u64, TokenLedgerError> {
+        if payee == Some(SPONSOR_POOL) {
+            self.open_account(SPONSOR_POOL, SPONSOR_POOL);
+        }
+        self.release_escrow(VALIDATION_RESOLVED, pending_id, payee, Token::Church, amount, context)
+    }
+
+    /// Move `amount` of `token` out of the obligations escrow to `payee`,
+    /// or retire it without one, logged as a `deed_type` deed targeting
+    /// `target`. Returns the amount paid.
+    fn release_escrow(
+        &mut self,
+        deed_type: &str,
+        target: &str,
+        payee: Option<&str>,
+        token: Token,
+        amount: u64,
+        context: serde_json::Value,
+    ) -> Result<u64, TokenLedgerError> {
+        if let Some(to) = payee {
+            self.account_mut(to)?;
+        }
+        let debit = self.apply(&Movement { account_id: PENDING_OBLIGATIONS.to_string(), token, delta: -clamp(amount) })?;
+        let released = debit.delta.unsigned_abs();
+        let mut movements = vec![debit];
+        let paid = match payee {
+            Some(to) => {
+                let credit = self.issue(to, token, released)?;
+                let paid = credit.delta as u64;
+                movements.push(credit);
+                paid
+            }
+            None => 0,
+        };
+        self.log(deed_type, vec![target.to_string()], context, &movements)?;
+        Ok(paid)
+    }
+
+    /// Credit a CHURCH or PWR reward. FEAR and TECH are refused: FEAR only
+    /// accrues via `accrue_fear`, TECH only via `mint_tech`.
+    pub fn mint_reward(&mut self, id: &str, token: Token, amount: u64) -> Result<u64, TokenLedgerError> {
+        self.reward_for(id, token, amount, None)
+    }
+
+    /// `mint_reward` on behalf of deed `source`; tombstoning `source`
+    /// reverses the credit.
+    pub fn reward_for(&mut self, id: &str, token: Token, amount: u64, source: Option<&str>) -> Result<u64, TokenLedgerError> {
+        match token {
+            Token::Church | Token::Pwr => self.credit_logged(id, token, amount, source),
+            Token::Fear | Token::Tech => Err(TokenLedgerError::NotARewardToken(token)),
+        }
+    }
+
+    /// Saturating burn; returns the amount actually removed.
+    pub fn burn(&mut self, id: &str, token: Token, amount: u64) -> Result<u64, TokenLedgerError> {
+        let m = self.apply(&Movement { account_id: id.to_string(), token, delta: -clamp(amount) })?;
+        let removed = m.delta.unsigned_abs();
+        let context = serde_json::json!({ "account_id": id, "token": token, "amount": removed });
+        self.log("token_burn", vec![id.to_string()], context, &[m])?;
+        Ok(removed)
+    }
+
+    /// Mint TECH for a deed in a technology-positive category, capped so
+    /// the account never holds more than `tech_cap`.
+    pub fn mint_tech(&mut self, id: &str, event: &DeedEvent, metrics: &BioloadMetrics) -> Result<u64, TokenLedgerError> {
+        if !schema_for(&event.deed_type).is_some_and(|s| s.tech_positive) {
+            return Err(TokenLedgerError::NotTechPositive(event.deed_type.clone()));
+        }
+        let held = self.account_mut(id)?.balance_tech;
+        let amount = match cooldown::is_suppressed(event) {
+            true => 0,
+            false => compute_tech_reward(event, metrics).min(self.cfg.tech_cap.saturating_sub(held)),
+        };
+        self.credit_logged(id, Token::Tech, amount, Some(&event.event_id))
+    }
+
+    /// Add FEAR to `id` and log a `fear_accrual` deed recording the reason.
+    pub fn accrue_fear(&mut self, id: &str, amount: u64, reason: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        let m = self.issue(id, Token::Fear, amount)?;
+        let context = serde_json::json!({ "account_id": id, "amount": m.delta, "reason": reason });
+        self.log(FEAR_ACCRUAL, vec![id.to_string()], context, &[m])
+    }
+
+    /// Accrue the configured FEAR for a regulator transition.
+    pub fn on_regulator_transition(&mut self, id: &str, trigger: FearTrigger, reason: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        let amount = match trigger {
+            FearTrigger::Warn => self.cfg.fear_on_warn,
+            FearTrigger::ForceRepair => self.cfg.fear_on_force_repair,
+        };
+        self.accrue_fear(id, amount, &format!("{:?}: {}", trigger, reason))
+    }
+
+    /// Remove `rate` of every FEAR balance, rounding up so small balances
+    /// still reach zero. Returns the total retired.
+    pub fn decay_fear(&mut self, rate: f64) -> Result<u64, TokenLedgerError> {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(TokenLedgerError::InvalidRate(rate));
+        }
+        let due: Vec<Movement> = self
+            .accounts
+            .values()
+            .filter_map(|a| {
+                // Epsilon keeps exact products (100 * 0.1) from rounding up to 11.
+                let n = (a.balance_fear as f64 * rate - 1e-9).ceil().max(0.0) as i64;
+                (n > 0).then(|| Movement { account_id: a.id.clone(), token: Token::Fear, delta: -n })
+            })
+            .collect();
+        let mut applied = Vec::with_capacity(due.len());
+        for m in &due {
+            applied.push(self.apply(m)?);
+        }
+        let total = applied.iter().map(|m| m.delta.unsigned_abs()).sum();
+        if !applied.is_empty() {
+            self.log("fear_decay", Vec::new(), serde_json::json!({ "rate": rate, "retired": total }), &applied)?;
+        }
+        Ok(total)
+    }
+
+    /// Seal every deed appended so far and log a `segment_sealed` deed
+    /// with the segment's index, bounds and root, which opens the next
+    /// segment. Returns None if nothing but the last seal's deed is open.
+    pub fn seal_segment(&mut self) -> Result<Option<&SealedSegment>, TokenLedgerError> {
+        let open = &self.deeds[self.sealed_len..];
+        if open.is_empty() || (open.len() == 1 && open[0].deed_type == SEGMENT_SEALED) {
+            return Ok(None);
+        }
+        let segment = SealedSegment {
+            index: self.segments.len(),
+            first: self.sealed_len,
+            last: self.deeds.len() - 1,
+            tip_hash: self.last_hash(),
+        };
+        let context = serde_json::json!({
+            "index": segment.index,
+            "first": segment.first,
+            "last": segment.last,
+            "root": segment_root(self, &segment),
+        });
+        self.log(SEGMENT_SEALED, Vec::new(), context, &[])?;
+        self.sealed_len = segment.last + 1;
+        self.segments.push(segment);
+        Ok(self.segments.last())
+    }
+
+    pub fn sealed_segments(&self) -> &[SealedSegment] {
+        &self.segments
+    }
+
+    /// Tombstone a deed in the open segment. Appends a `tombstone` deed and,
+    /// if the target (or any reward credited on its behalf) moved balances,
+    /// a `compensation` deed undoing those movements.
+    pub fn tombstone(&mut self, event_id: &str, reason: &str, operator_role: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        if !self.cfg.correction_roles.iter().any(|r| r == operator_role) {
+            return Err(TokenLedgerError::RoleNotAllowed(operator_role.to_string()));
+        }
+        let pos = *self.positions.get(event_id).ok_or_else(|| TokenLedgerError::UnknownDeed(event_id.to_string()))?;
+        if pos < self.sealed_len {
+            return Err(TokenLedgerError::Sealed(event_id.to_string()));
+        }
+        if self.tombstoned.contains(event_id) {
+            return Err(TokenLedgerError::AlreadyTombstoned(event_id.to_string()));
+        }
+        let target = &self.deeds[pos];
+        // A parameter change is undone by another change, not a tombstone.
+        if target.deed_type == TOMBSTONE || target.deed_type == COMPENSATION || target.deed_type == PARAMETER_CHANGE {
+            return Err(TokenLedgerError::NotCorrectable(target.deed_type.clone()));
+        }
+
+        // Movements caused by the target itself, plus live reward credits
+        // (and the pool tithes and escrow split off them) that name it as
+        // their source.
+        let mut undo: Vec<Movement> = movements_of(target);
+        let mut covered = vec![event_id.to_string()];
+        for d in &self.deeds[pos + 1..] {
+            if (d.deed_type == REWARD_CREDIT || d.deed_type == POOL_INFLOW || d.deed_type == OBLIGATION_OPENED)
+                && d.context_json["source_event_id"].as_str() == Some(event_id)
+                && !self.tombstoned.contains(&d.event_id)
+            {
+                undo.extend(movements_of(d));
+                covered.push(d.event_id.clone());
+            }
+        }
+
+        let context = serde_json::json!({
+            "target_event_id": event_id,
+            "target_deed_type": target.deed_type,
+            "target_actor_id": target.actor_id,
+            "reason": reason,
+            "operator_role": operator_role,
+            "covered_event_ids": covered,
+        });
+        let tombstone_id = self.log(TOMBSTONE, vec![event_id.to_string()], context, &[])?.event_id.clone();
+        for id in covered {
+            self.tombstoned.insert(id);
+        }
+
+        if !undo.is_empty() {
+            let mut applied = Vec::with_capacity(undo.len());
+            for m in undo.iter().rev() {
+                applied.push(self.apply(&Movement { account_id: m.account_id.clone(), token: m.token, delta: -m.delta })?);
+            }
+            let context = serde_json::json!({ "tombstone_event_id": tombstone_id, "target_event_id": event_id });
+            self.log(COMPENSATION, vec![event_id.to_string()], context, &applied)?;
+        }
+        let pos = self.positions[&tombstone_id];
+        Ok(&self.deeds[pos])
+    }
+
+    /// Every tombstone whose timestamp falls in `window`, for audit.
+    pub fn corrections_report(&self, window: CorrectionWindow) -> Vec<CorrectionEntry> {
+        let compensations: HashMap<&str, &DeedEvent> = self
+            .deeds
+            .iter()
+            .filter(|d| d.deed_type == COMPENSATION)
+            .filter_map(|d| d.context_json["tombstone_event_id"].as_str().map(|t| (t, d)))
+            .collect();
+        let text = |d: &DeedEvent, key: &str| d.context_json[key].as_str().unwrap_or_default().to_string();
+        self.deeds
+            .iter()
+            .filter(|d| d.deed_type == TOMBSTONE && d.timestamp >= window.start && d.timestamp < window.end)
+            .map(|d| CorrectionEntry {
+                tombstone_event_id: d.event_id.clone(),
+                timestamp: d.timestamp,
+                target_event_id: text(d, "target_event_id"),
+                target_deed_type: text(d, "target_deed_type"),
+                target_actor_id: text(d, "target_actor_id"),
+                reason: text(d, "reason"),
+                operator_role: text(d, "operator_role"),
+                compensated: compensations.get(d.event_id.as_str()).map(|c| movements_of(c)).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    pub fn sim_run(&self, run_id: &str) -> Option<&SimRun> {
+        self.sims.get(run_id)
+    }
+
+    pub fn sim_runs(&self) -> impl Iterator<Item = &SimRun> {
+        self.sims.values()
+    }
+
+    fn open_run(&self, run_id: &str) -> Result<&SimRun, TokenLedgerError> {
+        let run = self.sims.get(run_id).ok_or_else(|| TokenLedgerError::UnknownSimRun(run_id.to_string()))?;
+        match run.promoted {
+            Some(_) => Err(TokenLedgerError::SimRunPromoted(run_id.to_string())),
+            None => Ok(run),
+        }
+    }
+
+    /// Extend run `run_id`'s sub-chain. Movements count only on
+    /// ledger-authored deeds and land on the run's shadow accounts, which
+    /// are opened on first reference.
+    fn push_sim(&mut self, run_id: &str, deed: DeedEvent) -> Result<(), TokenLedgerError> {
+        let run = self.sims.get_mut(run_id).ok_or_else(|| TokenLedgerError::UnknownSimRun(run_id.to_string()))?;
+        if let Some(tip) = run.deeds.last() {
+            if deed.prev_hash != tip.self_hash {
+                return Err(TokenLedgerError::ChainBroken { expected: tip.self_hash.clone(), got: deed.prev_hash });
+            }
+        }
+        if deed.actor_id == LEDGER_ACTOR {
+            for m in movements_of(&deed) {
+                let account = run
+                    .accounts
+                    .entry(m.account_id.clone())
+                    .or_insert_with(|| Account::new(m.account_id.clone(), m.account_id.clone()));
+                apply_to(account, &mut run.issued, &mut run.retired, &m);
+            }
+        }
+        run.deeds.push(deed);
+        Ok(())
+    }
+
+    /// Open simulation run `run_id` with a `sim_run_open` root deed that
+    /// records the live tip the run branches from.
+    pub fn open_sim_run(&mut self, run_id: &str, opened_by: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        if self.sims.contains_key(run_id) {
+            return Err(TokenLedgerError::SimRunExists(run_id.to_string()));
+        }
+        let live_tip = self.last_hash();
+        let context = serde_json::json!({ "run_id": run_id, "live_tip": live_tip, "opened_by": opened_by });
+        let mut root =
+            DeedEvent::new(live_tip, opened_by.to_string(), Vec::new(), SIM_RUN_OPEN.to_string(), Vec::new(), context, Vec::new(), false);
+        root.domain = ExecutionDomain::Simulation { run_id: run_id.to_string() };
+        root.self_hash = String::new();
+        root.self_hash = hash_deed(&root);
+        self.sims.insert(run_id.to_string(), SimRun::new(run_id));
+        self.push_sim(run_id, root)?;
+        Ok(self.sims[run_id].deeds.last().expect("just pushed"))
+    }
+
+    /// Append an externally built deed to simulation run `run_id`; it must
+    /// extend the run's tip. The deed is forced into the run's domain
+    /// (rehashing it if it arrived tagged otherwise) and passes the same
+    /// guards as a live append.
+    pub fn append_sim(&mut self, run_id: &str, mut deed: DeedEvent) -> Result<&DeedEvent, TokenLedgerError> {
+        self.open_run(run_id)?;
+        if RESERVED.contains(&deed.deed_type.as_str()) {
+            return Err(TokenLedgerError::ReservedDeedType(deed.deed_type));
+        }
+        let domain = ExecutionDomain::Simulation { run_id: run_id.to_string() };
+        if deed.domain != domain {
+            deed.domain = domain;
+            deed.self_hash = String::new();
+            deed.self_hash = hash_deed(&deed);
+        }
+        let deed = self.cfg.minimization.enforce(deed)?;
+        let deed = match self.sim_cooldown(run_id, &deed) {
+            Some(_) => cooldown::suppress(deed),
+            None => deed,
+        };
+        self.push_sim(run_id, deed)?;
+        Ok(self.sims[run_id].deeds.last().expect("just pushed"))
+    }
+
+    /// Credit a CHURCH or PWR reward to shadow account `id` in run `run_id`,
+    /// logged on the run's sub-chain. Shadow rewards are not tithed and
+    /// ignore the live mint freeze; they never reach live supply.
+    pub fn sim_reward(
+        &mut self,
+        run_id: &str,
+        id: &str,
+        token: Token,
+        amount: u64,
+        source: Option<&str>,
+    ) -> Result<u64, TokenLedgerError> {
+        if matches!(token, Token::Fear | Token::Tech) {
+            return Err(TokenLedgerError::NotARewardToken(token));
+        }
+        let tip = self.open_run(run_id)?.tip_hash();
+        let m = Movement { account_id: id.to_string(), token, delta: clamp(amount) };
+        let context = serde_json::json!({
+            "source_event_id": source,
+            "account_id": id,
+            "token": token,
+            "amount": amount,
+            "movements": [m],
+        });
+        let mut deed = DeedEvent::new(
+            tip,
+            LEDGER_ACTOR.to_string(),
+            vec![id.to_string()],
+            REWARD_CREDIT.to_string(),
+            Vec::new(),
+            context,
+            Vec::new(),
+            false,
+        );
+        deed.domain = ExecutionDomain::Simulation { run_id: run_id.to_string() };
+        deed.self_hash = String::new();
+        deed.self_hash = hash_deed(&deed);
+        self.push_sim(run_id, deed)?;
+        Ok(amount)
+    }
+
+    /// Settle run `run_id` into the live chain. Only correction roles may;
+    /// the live chain gains one `sim_run_promoted` deed carrying the run's
+    /// summary, never its raw deeds or balances, and the run closes.
+    pub fn promote_sim_run(&mut self, run_id: &str, reason: &str, operator_role: &str) -> Result<&DeedEvent, TokenLedgerError> {
+        if !self.cfg.correction_roles.iter().any(|r| r == operator_role) {
+            return Err(TokenLedgerError::RoleNotAllowed(operator_role.to_string()));
+        }
+        let summary = self.open_run(run_id)?.summary();
+        let mut context = serde_json::to_value(&summary).expect("summary serializes");
+        context["reason"] = serde_json::json!(reason);
+        context["operator_role"] = serde_json::json!(operator_role);
+        let event_id = self.log(SIM_RUN_PROMOTED, vec![summary.root_event_id.clone()], context, &[])?.event_id.clone();
+        self.sims.get_mut(run_id).expect("checked above").promoted = Some(event_id);
+        Ok(self.deeds.last().expect("just pushed"))
+    }
+
+    /// POWER Gini over participants and over all accounts, from the
+    /// histograms (see `ledger::concentration`).
+    pub fn power_gini(&self) -> PowerGini {
+        self.power.gini()
+    }
+
+    /// `power_gini` by sorting every balance.
+    pub fn exact_power_gini(&self) -> PowerGini {
+        self.power.exact(self.accounts.values().map(|a| (a.id.as_str(), a.balance_pwr)))
+    }
+
+    /// Compare the histograms with an exact pass and rebuild them from the
+    /// accounts if they drifted further apart than the error bound.
+    pub fn check_power_gini(&mut self) -> PowerGiniCheck {
+        let (approximate, exact) = (self.power_gini(), self.exact_power_gini());
+        let drift = (exact.filtered - approximate.filtered).abs().max((exact.unfiltered - approximate.unfiltered).abs());
+        let rebuilt = drift > GINI_ERROR_BOUND || approximate.accounts != exact.accounts;
+        if rebuilt {
+            warn!("POWER Gini histogram drifted {:.4} from the exact value; rebuilding", drift);
+            let balances = self.accounts.values().map(|a| (a.id.as_str(), a.balance_pwr));
+            self.power = PowerConcentration::rebuild(self.cfg.concentration.clone(), balances);
+        }
+        PowerGiniCheck { approximate, exact, drift, rebuilt }
+    }
+
+    pub fn supply_report(&self) -> SupplyReport {
+        let tokens = Token::ALL
+            .iter()
+            .map(|&token| {
+                let supply = TokenSupply {
+                    issued: self.issued.get(&token).copied().unwrap_or(0),
+                    retired: self.retired.get(&token).copied().unwrap_or(0),
+                    circulating: self.accounts.values().map(|a| a.balance(token)).sum(),
+                };
+                (token, supply)
+            })
+            .collect();
+        SupplyReport { accounts: self.accounts.len(), tokens }
+    }
+}
+
+/// Apply a movement to `account` exactly, debits saturating at zero, and
+/// count it in `issued` / `retired`. Returns the movement actually applied.
+fn apply_to(
+    account: &mut Account,
+    issued: &mut BTreeMap<Token, u64>,
+    retired: &mut BTreeMap<Token, u64>,
+    m: &Movement,
+) -> Movement {
+    let delta = if m.delta >= 0 {
+        let added = account.credit(m.token, m.delta as u64);
+        *issued.entry(m.token).or_insert(0) += added;
+        added as i64
+    } else {
+        let removed = account.debit(m.token, m.delta.unsigned_abs());
+        *retired.entry(m.token).or_insert(0) += removed;
+        -(removed as i64)
+    };
+    Movement { account_id: m.account_id.clone(), token: m.token, delta }
+}
@@ -1,7 +1,106 @@
+//! Church-of-FEAR moral ledger.
+//!
+//! Cargo features are additive:
+//! - `core`: deed events, hashing, chain verification and the token ledger;
+//!   no async runtime, suitable for edge devices.
+//! - `rpc`: the line-delimited JSON-RPC server and the node binary.
+//! - `viz`: XR-grid scene export.
+//! - `graph`: petgraph-based actor/target deed graph.
+//! - `manifest`: NeuroEco identity manifests (nalgebra, ed25519).
+//! - `tip-gossip`: signed ledger-tip gossip between nodes.
+//! - `pool-topup`: multisig authority top-ups of the sponsor pool.
+//! - `param-governance`: multisig-approved runtime parameter changes.
+//! - `binary-wire`: compact binary deed frames on their own TCP endpoint.
+//! - `actor-keys`: keyring keys bound to actors, with rotation and
+//!   guardian-approved account recovery.
+//! - `validation-quorum`: signed validator votes on deeds whose category
+//!   requires a validation quorum (implies `actor-keys`).
+//! - `tui`: the `cof-inspect` ledger inspector.
+//! - `json-logs`: JSON log lines for the node binary (`COF_LOG_FORMAT=json`).
+//! - `importers`: deed importers for volunteer-hour CSVs and carbon-registry
+//!   exports.
+//! - `testkit`: seeded multi-actor ledger scenarios with expected
+//!   aggregates, for feature tests.
+//! - `replica`: watch-only replica following a primary node's ledger
+//!   (implies `rpc` and `tip-gossip`).
+//! - `chaos`: programmable fault points (the `faults` crate) at sink
+//!   delivery, ledger sync and the clock, for chaos tests; without it they
+//!   are no-ops.
+//! - `halt-review`: multisig-approved lifts of the high-impact freeze a
+//!   regulator halt starts, readmitting the deeds queued under it.
+//!
+//! The default is `core` + `rpc` + `pool-topup` + `param-governance` +
+//! `halt-review`.
+
+#[cfg(feature = "core")]
 pub mod config;
+#[cfg(feature = "core")]
 pub mod utils;
+#[cfg(feature = "core")]
 pub mod ledger;
+#[cfg(feature = "core")]
 pub mod token;
+#[cfg(feature = "core")]
 pub mod compliance;
+#[cfg(feature = "core")]
 pub mod sponsor;
+#[cfg(feature = "core")]
+pub mod params;
+#[cfg(feature = "core")]
+pub mod audit;
+#[cfg(feature = "core")]
+pub mod obligations;
+#[cfg(feature = "core")]
+pub mod near_miss;
+#[cfg(feature = "core")]
+pub mod simulation;
+#[cfg(feature = "core")]
+pub mod anomaly;
+#[cfg(feature = "core")]
+pub mod history;
+#[cfg(feature = "core")]
+pub mod identity;
+#[cfg(feature = "core")]
+pub mod quorum;
+#[cfg(feature = "core")]
+pub mod residency;
+#[cfg(feature = "core")]
+pub mod cooldown;
+#[cfg(feature = "core")]
+pub mod targets;
+#[cfg(feature = "rpc")]
 pub mod rpc;
+#[cfg(feature = "core")]
+pub mod scheduler;
+#[cfg(feature = "core")]
+pub mod repair_planner;
+#[cfg(feature = "core")]
+pub mod report;
+#[cfg(feature = "core")]
+pub mod notifications;
+#[cfg(feature = "core")]
+pub mod halt_review;
+#[cfg(feature = "core")]
+pub mod providers;
+#[cfg(feature = "core")]
+pub mod submission;
+#[cfg(feature = "core")]
+pub mod cold_storage;
+#[cfg(feature = "core")]
+pub mod vrf;
+#[cfg(feature = "core")]
+pub mod policy_bundle;
+#[cfg(feature = "tip-gossip")]
+pub mod tip_gossip;
+#[cfg(feature = "replica")]
+pub mod replica;
+#[cfg(feature = "viz")]
+pub mod viz;
+#[cfg(feature = "tui")]
+pub mod inspect;
+#[cfg(feature = "importers")]
+pub mod importers;
+#[cfg(feature = "testkit")]
+pub mod fixtures;
+#[cfg(feature = "manifest")]
+pub use neuro_eco_manifest as manifest;
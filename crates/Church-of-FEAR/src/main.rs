@@ -1,58 +1,110 @@
-mod config;
-mod utils;
-mod ledger;
-mod token;
-mod compliance;
-mod sponsor;
-mod rpc;
-
-use crate::ledger::deed_event::{DeedEvent, BioloadReducer, RepairHero};
-use crate::ledger::metrics::BioloadMetrics;
-use crate::token::mint::mint_church;
-use crate::compliance::validator::validate_deed;
-use crate::utils::time::now_timestamp;
-use crate::rpc::server::start_rpc_server;
+use church_of_fear::ledger::builders::EcologicalSustainabilityDeed;
+use church_of_fear::ledger::deed_event::{DeedEvent, BioloadReducer, RepairHero};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::token::mint::mint_church;
+use church_of_fear::compliance::validator::validate_deed;
+use church_of_fear::utils::time::now_timestamp;
+use church_of_fear::rpc::server::{start_rpc_server_with, RpcContext};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::scheduler::RecurringJobs;
+use church_of_fear::audit::{SelfAuditor, SelfBudget};
+use church_of_fear::notifications::{NotificationCenter, WebhookSubjectNotifier};
+use church_of_fear::submission::SubmissionGuard;
+use church_of_fear::cold_storage::ColdStorage;
+use church_of_fear::policy_bundle::{self, PolicyBundle};
 use log::info;
-use serde_json::json;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
+/// Energy the self-audit may spend per hour of idle ticks.
+const AUDIT_JOULES_PER_HOUR: f64 = 360.0;
+
 fn main() {
-    env_logger::init();
+    init_logs();
+
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("report") {
+        return run_report(&args[2..]);
+    }
+
+    #[cfg(feature = "replica")]
+    if let Ok(primary) = std::env::var("COF_REPLICA_OF") {
+        return run_replica(&primary);
+    }
 
     info!("Starting Church-of-FEAR ledger node…");
 
-    // Spawn Auto_Church RPC in the background
-    thread::spawn(|| {
-        if let Err(e) = start_rpc_server("127.0.0.1:4040") {
+    // Refuse to start on a policy set that does not hang together.
+    // `COF_POLICY_BUNDLE` names the bundle directory; the crate's own by default.
+    let bundle_dir = std::env::var("COF_POLICY_BUNDLE").unwrap_or_else(|_| env!("CARGO_MANIFEST_DIR").to_string());
+    let bundle = match PolicyBundle::load_and_verify(std::path::Path::new(&bundle_dir)) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("{}: {}", bundle_dir, e);
+            std::process::exit(1);
+        }
+    };
+    info!("Policy bundle {} from {}", bundle.hash(), bundle_dir);
+
+    // Spawn Auto_Church RPC in the background, sharing the node's ledger.
+    let tokens = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    policy_bundle::record_loaded(&mut tokens.lock().unwrap(), &bundle).expect("policy bundle deed is logged");
+    let auditor = Arc::new(Mutex::new(SelfAuditor::new(tokens.lock().unwrap().config().audit.clone())));
+    let notifications = NotificationCenter::for_policy(&tokens.lock().unwrap().config().notifications)
+        .expect("notification store is readable");
+    let notifications = Arc::new(Mutex::new(notifications));
+    // One duplicate window for every endpoint, so a retry on another
+    // transport is caught too.
+    let submissions = {
+        let ledger = tokens.lock().unwrap();
+        SubmissionGuard::open(ledger.config().submissions.clone(), &ledger, now_timestamp()).expect("submission window is readable")
+    };
+    let submissions = Arc::new(Mutex::new(submissions));
+    let ctx = RpcContext {
+        auditor: Some(auditor.clone()),
+        submissions: Some(submissions.clone()),
+        #[cfg(feature = "actor-keys")]
+        notifications: Some(notifications.clone()),
+        ..RpcContext::with_ledger(tokens.clone())
+    };
+    thread::spawn(move || {
+        if let Err(e) = start_rpc_server_with("127.0.0.1:4040", ctx) {
             eprintln!("RPC server failed: {}", e);
         }
     });
+    #[cfg(feature = "binary-wire")]
+    {
+        let ctx = RpcContext {
+            auditor: Some(auditor.clone()),
+            submissions: Some(submissions.clone()),
+            ..RpcContext::with_ledger(tokens.clone())
+        };
+        thread::spawn(move || {
+            if let Err(e) = church_of_fear::rpc::wire::start_wire_server_with("127.0.0.1:4041", ctx) {
+                eprintln!("Binary deed endpoint failed: {}", e);
+            }
+        });
+    }
 
     let genesis = DeedEvent::genesis();
-    let context = json!({
-        "description": "Tree planting along river bank",
-        "location": "Phoenix, AZ",
-        "roh": 0.2,
-        "decay": 0.7
-    });
-
-    let deed = DeedEvent::new(
-        genesis.self_hash.clone(),
-        "actor:eco-hero".to_string(),
-        vec!["target:local-watershed".to_string()],
-        "ecological_sustainability".to_string(),
-        vec!["tree_planting".to_string()],
-        context,
-        vec![],
-        false,
-    );
+    let deed = EcologicalSustainabilityDeed::builder()
+        .actor_id("actor:eco-hero")
+        .location("Phoenix, AZ")
+        .co2_kg(12.5)
+        .evidence_uri("ipfs://riverbank-planting")
+        .notes("Tree planting along river bank")
+        .target("target:local-watershed")
+        .tag("tree_planting")
+        .build(genesis.self_hash.clone())
+        .expect("taxonomy ranges");
 
     let roh = 0.2;
     let decay = 0.7;
     validate_deed(&deed, roh, decay).expect("deed must be compliant");
 
     let metrics = BioloadMetrics::new(-0.12, roh, decay);
-    let church_delta = mint_church(&deed, &metrics);
+    let church_delta = mint_church(&deed, &metrics, tokens.lock().unwrap().config());
 
     info!(
         "Deed {} at {} minted {} CHURCH tokens",
@@ -66,11 +118,149 @@ fn main() {
     info!("BioloadReducer added {} bonus CHURCH", extra_church);
 
     let hero = RepairHero { impact_score: 0.9 };
-    let pwr = hero.grant_pwr();
+    let pwr = hero.grant_pwr(&tokens.lock().unwrap().config().repair_curve);
     info!("RepairHero granted {} PWR", pwr);
 
-    // Prevent main from exiting immediately so RPC server stays alive in dev.
+    // Keep main alive so the RPC server stays up in dev, running recurring
+    // maintenance (FEAR decay, ...) as it comes due, auditing the log in
+    // between and delivering subject notifications.
+    let mut jobs = RecurringJobs::with_defaults(&tokens.lock().unwrap(), now_timestamp());
+    let mut budget = SelfBudget::new(AUDIT_JOULES_PER_HOUR, 3_600, now_timestamp());
+    let mut cold = ColdStorage::for_policy(&tokens.lock().unwrap().config().cold_storage, now_timestamp())
+        .expect("cold storage manifest is readable");
     loop {
         std::thread::sleep(std::time::Duration::from_secs(60));
+        let now = now_timestamp();
+        budget.roll(now);
+        jobs.tick(&mut tokens.lock().unwrap(), &mut auditor.lock().unwrap(), &mut budget, now);
+        if let Some(cold) = &mut cold {
+            cold.tick(&mut tokens.lock().unwrap(), &mut auditor.lock().unwrap(), now);
+        }
+        let delivered = notifications.lock().unwrap().tick(&tokens.lock().unwrap(), now, &WebhookSubjectNotifier);
+        if let Err(e) = delivered {
+            log::warn!("subject notifications: {}", e);
+        }
     }
 }
+
+/// Follow the primary at `primary` (its JSON-RPC address) instead of
+/// running a ledger of our own. `COF_REPLICA_KEYS` names the primary's
+/// exported `VerifyingBundle`; `COF_REPLICA_PRIMARY` and
+/// `COF_REPLICA_NAMESPACE` are the node id and namespace it announces
+/// its tip under. Serves the replicated ledger read-only on the usual RPC
+/// port and `/healthz` on 4042.
+#[cfg(feature = "replica")]
+fn run_replica(primary: &str) {
+    use church_of_fear::replica::{serve_healthz, start_replica_rpc_server, Replica, ReplicaConfig, TcpPrimary};
+
+    let keys = std::env::var("COF_REPLICA_KEYS").expect("COF_REPLICA_KEYS names the primary's verifying bundle");
+    let keys = std::fs::read(&keys).expect("verifying bundle is readable");
+    let keys = serde_json::from_slice(&keys).expect("verifying bundle is valid JSON");
+    let defaults = ReplicaConfig::default();
+    let cfg = ReplicaConfig {
+        primary_node_id: std::env::var("COF_REPLICA_PRIMARY").unwrap_or(defaults.primary_node_id.clone()),
+        namespace: std::env::var("COF_REPLICA_NAMESPACE").unwrap_or(defaults.namespace.clone()),
+        ..defaults
+    };
+    info!("Starting Church-of-FEAR replica of {} at {}…", cfg.primary_node_id, primary);
+
+    let tokens = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let mut replica = Replica::new(cfg, keys, tokens, TcpPrimary { addr: primary.to_string() });
+    let ctx = replica.rpc_context();
+    thread::spawn(move || {
+        if let Err(e) = start_replica_rpc_server("127.0.0.1:4040", ctx) {
+            eprintln!("RPC server failed: {}", e);
+        }
+    });
+    let health = replica.health_handle();
+    thread::spawn(move || {
+        if let Err(e) = serve_healthz("127.0.0.1:4042", health) {
+            eprintln!("Health endpoint failed: {}", e);
+        }
+    });
+
+    // Keep following until a verification failure stops the replica;
+    // transport errors are retried on the next tick.
+    loop {
+        match replica.tick(now_timestamp()) {
+            Err(e @ (church_of_fear::replica::ReplicaError::Diverged(_) | church_of_fear::replica::ReplicaError::Stopped)) => {
+                eprintln!("Replica stopped: {}", e);
+                break;
+            }
+            Err(e) => log::warn!("replica sync failed: {}", e),
+            Ok(()) => {}
+        }
+        std::thread::sleep(std::time::Duration::from_secs(5));
+    }
+    // Stay up so /healthz and the read-only RPC keep reporting.
+    loop {
+        std::thread::sleep(std::time::Duration::from_secs(60));
+    }
+}
+
+/// `church-of-fear report <ledger.jsonl> <YYYY-MM> [--template file]
+/// [--guard figures.json] [--html out.html]`: print the month's
+/// stewardship report for an exported ledger as Markdown. `--guard` reads
+/// the eco-fairness guard's figures for the same month.
+fn run_report(args: &[String]) {
+    use church_of_fear::report::{generate_monthly_report_with, GuardFigures, ReportPeriod, ReportTemplate};
+
+    const USAGE: &str =
+        "usage: church-of-fear report <ledger.jsonl> <YYYY-MM> [--template file] [--guard figures.json] [--html out.html]";
+    fn fail(msg: String) -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1)
+    }
+    let (Some(path), Some(month)) = (args.first(), args.get(1)) else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let Some(period) = ReportPeriod::parse_month(month) else {
+        fail(format!("not a month: {} (expected YYYY-MM)", month))
+    };
+    let mut template = ReportTemplate::default();
+    let mut guard = GuardFigures::default();
+    let mut html = None;
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        let Some(value) = flags.next() else {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        };
+        let text = || std::fs::read_to_string(value).unwrap_or_else(|e| fail(format!("cannot read {}: {}", value, e)));
+        match flag.as_str() {
+            "--template" => template = ReportTemplate::parse(&text()).unwrap_or_else(|e| fail(format!("{}: {}", value, e))),
+            "--guard" => guard = serde_json::from_str(&text()).unwrap_or_else(|e| fail(format!("{}: {}", value, e))),
+            "--html" => html = Some(value.clone()),
+            _ => {
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| fail(format!("cannot read {}: {}", path, e)));
+    let deeds = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| church_of_fear::ledger::schema::parse_deed(line).unwrap_or_else(|e| fail(format!("{}:{}: {}", path, i + 1, e))));
+    let ledger = TokenLedger::replay(LedgerConfig::default(), deeds).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+    let report = generate_monthly_report_with(&ledger, period, &guard, &template).unwrap_or_else(|e| fail(e.to_string()));
+    if let Some(out) = html {
+        std::fs::write(&out, report.to_html()).unwrap_or_else(|e| fail(format!("cannot write {}: {}", out, e)));
+    }
+    print!("{}", report.markdown);
+}
+
+/// `COF_LOG_FORMAT=json` switches to JSON lines when built with `json-logs`.
+fn init_logs() {
+    #[cfg(feature = "json-logs")]
+    if std::env::var("COF_LOG_FORMAT").as_deref() == Ok("json") {
+        if let Err(e) = church_of_fear::utils::logging::init_json_logging() {
+            eprintln!("JSON logging unavailable: {}", e);
+        }
+        return;
+    }
+    env_logger::init();
+}
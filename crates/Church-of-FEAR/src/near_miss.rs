@@ -0,0 +1,397 @@
+//! Near-miss reporting: the low-friction side of Errority.
+//!
+//! Anyone can file a near miss, something that went wrong or almost did
+//! without tripping a guard (`report_near_miss`). The report is a
+//! diagnostic-only `near_miss` deed by the reporter: no ethics flags, no
+//! life-harm flag, no balance movement, so an unconfirmed report can never
+//! count against the reporter. Reports of the same issue (same category,
+//! sharing a related deed or the same description, within
+//! `cluster_window_secs`) join the first report's cluster.
+//!
+//! A cluster is corroborated when an auditor role confirms it, or when a
+//! guard rejects something in the same category within
+//! `corroboration_window_secs` of the first report
+//! (`observe_guard_rejection`). Corroboration is a ledger-written
+//! `near_miss_corroborated` deed that credits the cluster's first reporter
+//! `credit_per_report` WISE, capped at `credit_cap` per reporter per
+//! `credit_window_secs`; later reporters of the same issue get nothing.
+//!
+//! `errority_log` aggregates reports by category, and a periodic digest of
+//! open clusters goes to the operator webhook.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::ledger::deed_event::{hash_deed, DeedEvent};
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::utils::http::post_webhook;
+
+pub const NEAR_MISS: &str = "near_miss";
+pub const NEAR_MISS_CORROBORATED: &str = "near_miss_corroborated";
+pub const NEAR_MISS_DIGEST: &str = "near_miss_digest";
+
+/// Guard categories `observe_guard_rejection` is called with; a report
+/// filed under one of them is corroborated by that guard's rejections.
+pub const GUARD_DEED_VALIDATION: &str = "deed_validation";
+pub const GUARD_DATA_MINIMIZATION: &str = "data_minimization";
+pub const GUARD_LEDGER: &str = "ledger";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NearMissPolicy {
+    /// WISE credited to a cluster's first reporter on corroboration.
+    pub credit_per_report: u64,
+    /// Most WISE one reporter can earn per `credit_window_secs`.
+    pub credit_cap: u64,
+    pub credit_window_secs: i64,
+    /// A guard rejection corroborates clusters first reported this recently.
+    pub corroboration_window_secs: i64,
+    /// A report joins a cluster last reported to this recently.
+    pub cluster_window_secs: i64,
+    /// Roles that may confirm a near miss.
+    pub auditor_roles: Vec<String>,
+    pub digest_every_secs: u64,
+    /// Operator webhook (`http://host:port/path`) for the open-cluster digest.
+    pub digest_webhook: Option<String>,
+}
+
+impl Default for NearMissPolicy {
+    fn default() -> Self {
+        Self {
+            credit_per_report: 5,
+            credit_cap: 25,
+            credit_window_secs: 30 * 86_400,
+            corroboration_window_secs: 7 * 86_400,
+            cluster_window_secs: 7 * 86_400,
+            auditor_roles: vec!["Auditor".to_string(), "Regulator".to_string()],
+            digest_every_secs: 86_400,
+            digest_webhook: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum NearMissError {
+    #[error("near-miss description is empty")]
+    EmptyDescription,
+    #[error("near-miss category is empty")]
+    EmptyCategory,
+    #[error("role {0} may not confirm near misses")]
+    RoleNotAllowed(String),
+    #[error("no near-miss cluster {0}")]
+    UnknownCluster(String),
+    #[error("near-miss cluster {0} is already corroborated")]
+    AlreadyCorroborated(String),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearMissReceipt {
+    pub event_id: String,
+    pub cluster_id: String,
+    /// The report joined an existing cluster and cannot earn credit.
+    pub clustered: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorroboratedBy {
+    Auditor,
+    Guard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Corroboration {
+    pub event_id: String,
+    pub by: CorroboratedBy,
+    /// Auditor id, or the guard category.
+    pub source: String,
+    pub corroborated_at: i64,
+    pub credited: u64,
+}
+
+/// Reports of one underlying issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearMissCluster {
+    /// Event id of the first report.
+    pub cluster_id: String,
+    pub category: String,
+    /// Highest severity reported.
+    pub severity: Severity,
+    pub description: String,
+    /// First reporter; the only one who can be credited.
+    pub reporter: String,
+    pub report_ids: Vec<String>,
+    pub reporters: BTreeSet<String>,
+    pub related_event_ids: BTreeSet<String>,
+    pub first_reported: i64,
+    pub last_reported: i64,
+    pub corroboration: Option<Corroboration>,
+}
+
+impl NearMissCluster {
+    fn matches(&self, category: &str, description: &str, related: &[String], now: i64, window: i64) -> bool {
+        self.category == category
+            && now - self.last_reported <= window
+            && (normalize(&self.description) == normalize(description)
+                || related.iter().any(|id| self.related_event_ids.contains(id)))
+    }
+}
+
+fn normalize(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Every near-miss cluster, in the order first reported.
+pub fn clusters(ledger: &TokenLedger) -> Vec<NearMissCluster> {
+    let mut out: Vec<NearMissCluster> = Vec::new();
+    for d in ledger.live_deeds() {
+        let ctx = &d.context_json;
+        let text = |key: &str| ctx[key].as_str().unwrap_or_default().to_string();
+        match d.deed_type.as_str() {
+            NEAR_MISS => {
+                let severity = serde_json::from_value(ctx["severity"].clone()).unwrap_or(Severity::Low);
+                let related: Vec<String> = serde_json::from_value(ctx["related_event_ids"].clone()).unwrap_or_default();
+                let cluster_id = ctx["cluster_id"].as_str().unwrap_or(&d.event_id);
+                match out.iter_mut().find(|c| c.cluster_id == cluster_id) {
+                    Some(c) => {
+                        c.severity = c.severity.max(severity);
+                        c.report_ids.push(d.event_id.clone());
+                        c.reporters.insert(d.actor_id.clone());
+                        c.related_event_ids.extend(related);
+                        c.last_reported = c.last_reported.max(d.timestamp);
+                    }
+                    None => out.push(NearMissCluster {
+                        cluster_id: cluster_id.to_string(),
+                        category: text("category"),
+                        severity,
+                        description: text("description"),
+                        reporter: d.actor_id.clone(),
+                        report_ids: vec![d.event_id.clone()],
+                        reporters: BTreeSet::from([d.actor_id.clone()]),
+                        related_event_ids: related.into_iter().collect(),
+                        first_reported: d.timestamp,
+                        last_reported: d.timestamp,
+                        corroboration: None,
+                    }),
+                }
+            }
+            NEAR_MISS_CORROBORATED => {
+                let cluster_id = text("cluster_id");
+                if let Some(c) = out.iter_mut().find(|c| c.cluster_id == cluster_id) {
+                    c.corroboration = Some(Corroboration {
+                        event_id: d.event_id.clone(),
+                        by: serde_json::from_value(ctx["by"].clone()).unwrap_or(CorroboratedBy::Auditor),
+                        source: text("source"),
+                        corroborated_at: ctx["corroborated_at"].as_i64().unwrap_or(d.timestamp),
+                        credited: ctx["credited"].as_u64().unwrap_or_default(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// WISE credited to `reporter` since `since`.
+pub fn wise_credit_since(ledger: &TokenLedger, reporter: &str, since: i64) -> u64 {
+    ledger
+        .live_deeds()
+        .filter(|d| d.deed_type == NEAR_MISS_CORROBORATED && d.context_json["reporter"] == reporter)
+        .filter(|d| d.context_json["corroborated_at"].as_i64().unwrap_or(d.timestamp) >= since)
+        .filter_map(|d| d.context_json["credited"].as_u64())
+        .sum()
+}
+
+/// All WISE credited to `reporter`.
+pub fn wise_credit(ledger: &TokenLedger, reporter: &str) -> u64 {
+    wise_credit_since(ledger, reporter, i64::MIN)
+}
+
+/// File a near miss at `now`.
+pub fn report_near_miss(
+    ledger: &mut TokenLedger,
+    reporter: &str,
+    description: &str,
+    category: &str,
+    severity: Severity,
+    related_event_ids: &[String],
+    now: i64,
+) -> Result<NearMissReceipt, NearMissError> {
+    if description.trim().is_empty() {
+        return Err(NearMissError::EmptyDescription);
+    }
+    if category.trim().is_empty() {
+        return Err(NearMissError::EmptyCategory);
+    }
+    let window = ledger.config().near_miss.cluster_window_secs;
+    let existing = clusters(ledger)
+        .into_iter()
+        .find(|c| c.corroboration.is_none() && c.matches(category, description, related_event_ids, now, window));
+    let mut context = json!({
+        "category": category,
+        "severity": severity,
+        "description": description,
+        "related_event_ids": related_event_ids,
+    });
+    if let Some(c) = &existing {
+        context["cluster_id"] = json!(c.cluster_id);
+    }
+    let mut deed = DeedEvent::new(
+        ledger.last_hash(),
+        reporter.to_string(),
+        related_event_ids.to_vec(),
+        NEAR_MISS.to_string(),
+        vec!["diagnostic".to_string()],
+        context,
+        Vec::new(),
+        false,
+    );
+    deed.timestamp = now;
+    deed.self_hash = hash_deed(&deed);
+    let event_id = ledger.append(deed)?.event_id.clone();
+    Ok(match existing {
+        Some(c) => NearMissReceipt { event_id, cluster_id: c.cluster_id, clustered: true },
+        None => NearMissReceipt { cluster_id: event_id.clone(), event_id, clustered: false },
+    })
+}
+
+fn corroborate(
+    ledger: &mut TokenLedger,
+    cluster: &NearMissCluster,
+    by: CorroboratedBy,
+    source: &str,
+    now: i64,
+) -> Result<Corroboration, NearMissError> {
+    let policy = &ledger.config().near_miss;
+    let earned = wise_credit_since(ledger, &cluster.reporter, now - policy.credit_window_secs);
+    let credited = policy.credit_per_report.min(policy.credit_cap.saturating_sub(earned));
+    let context = json!({
+        "cluster_id": cluster.cluster_id,
+        "category": cluster.category,
+        "reporter": cluster.reporter,
+        "by": by,
+        "source": source,
+        "corroborated_at": now,
+        "credited": credited,
+    });
+    let event_id = ledger.log_near_miss_corroboration(&cluster.cluster_id, context)?.event_id.clone();
+    Ok(Corroboration { event_id, by, source: source.to_string(), corroborated_at: now, credited })
+}
+
+/// An auditor confirms cluster `cluster_id` (or the cluster of report
+/// `cluster_id`), crediting its first reporter.
+pub fn confirm_near_miss(
+    ledger: &mut TokenLedger,
+    cluster_id: &str,
+    auditor_id: &str,
+    auditor_role: &str,
+    now: i64,
+) -> Result<Corroboration, NearMissError> {
+    if !ledger.config().near_miss.auditor_roles.iter().any(|r| r == auditor_role) {
+        return Err(NearMissError::RoleNotAllowed(auditor_role.to_string()));
+    }
+    let cluster = clusters(ledger)
+        .into_iter()
+        .find(|c| c.cluster_id == cluster_id || c.report_ids.iter().any(|id| id == cluster_id))
+        .ok_or_else(|| NearMissError::UnknownCluster(cluster_id.to_string()))?;
+    if cluster.corroboration.is_some() {
+        return Err(NearMissError::AlreadyCorroborated(cluster.cluster_id));
+    }
+    corroborate(ledger, &cluster, CorroboratedBy::Auditor, auditor_id, now)
+}
+
+/// A guard in `category` rejected something at `now`: corroborate every
+/// open cluster in that category first reported within the window.
+pub fn observe_guard_rejection(ledger: &mut TokenLedger, category: &str, now: i64) -> Result<Vec<Corroboration>, NearMissError> {
+    let window = ledger.config().near_miss.corroboration_window_secs;
+    let due: Vec<NearMissCluster> = clusters(ledger)
+        .into_iter()
+        .filter(|c| c.corroboration.is_none() && c.category == category)
+        .filter(|c| c.first_reported <= now && now - c.first_reported <= window)
+        .collect();
+    due.iter().map(|c| corroborate(ledger, c, CorroboratedBy::Guard, category, now)).collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorityTally {
+    pub reports: usize,
+    pub clusters: usize,
+    pub open: usize,
+    pub corroborated: usize,
+}
+
+/// Near misses by category.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorityLog {
+    pub categories: BTreeMap<String, ErrorityTally>,
+}
+
+pub fn errority_log(ledger: &TokenLedger) -> ErrorityLog {
+    let mut log = ErrorityLog::default();
+    for c in clusters(ledger) {
+        let tally = log.categories.entry(c.category.clone()).or_default();
+        tally.reports += c.report_ids.len();
+        tally.clusters += 1;
+        if c.corroboration.is_some() {
+            tally.corroborated += 1;
+        } else {
+            tally.open += 1;
+        }
+    }
+    log
+}
+
+/// Open clusters, most severe first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearMissDigest {
+    pub generated_at: i64,
+    pub open: Vec<NearMissCluster>,
+}
+
+pub fn digest(ledger: &TokenLedger, now: i64) -> NearMissDigest {
+    let mut open: Vec<NearMissCluster> = clusters(ledger).into_iter().filter(|c| c.corroboration.is_none()).collect();
+    open.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.first_reported.cmp(&b.first_reported)));
+    NearMissDigest { generated_at: now, open }
+}
+
+/// Where near-miss digests are pushed.
+pub trait NearMissNotifier: Send {
+    fn notify(&self, digest: &NearMissDigest) -> Result<(), String>;
+}
+
+/// POSTs each digest as JSON to the policy's webhook.
+pub struct WebhookNearMissNotifier {
+    pub url: String,
+}
+
+impl NearMissNotifier for WebhookNearMissNotifier {
+    fn notify(&self, digest: &NearMissDigest) -> Result<(), String> {
+        post_webhook(&self.url, NEAR_MISS_DIGEST, digest)
+    }
+}
+
+/// Send the digest through `notifier` if anything is open; returns the
+/// number of open clusters sent.
+pub fn send_digest(ledger: &TokenLedger, notifier: &dyn NearMissNotifier, now: i64) -> Result<usize, String> {
+    let digest = digest(ledger, now);
+    if digest.open.is_empty() {
+        return Ok(0);
+    }
+    notifier.notify(&digest)?;
+    Ok(digest.open.len())
+}
@@ -0,0 +1,628 @@
+//! Subject-facing notifications.
+//!
+//! The node's webhooks tell operators what happened; this module tells
+//! the subjects themselves. An actor opts in by storing
+//! `NotificationPrefs`: where to deliver (a webhook, or an outbox the
+//! client polls over RPC), which event kinds, a digest frequency and
+//! optional quiet hours. Actors without preferences get nothing.
+//!
+//! `NotificationCenter::tick` reads the ledger on from where it stopped
+//! and raises `SubjectEvent`s:
+//! - `deed_minted`: a `reward_credit` to the actor's account;
+//! - `follow_up_due`: a pending obligation of theirs whose window opened;
+//! - `standing_changed`: their `Standing` moved, through a flagged deed or
+//!   a tombstone;
+//! - `consent_expiring`: a grant recorded with `record_consent` came within
+//!   one of `NotificationPolicy::consent_lead_secs` of expiring. The ledger
+//!   records no consent state, so grants are recorded here.
+//!
+//! Immediate events are released at once, or when the actor's quiet hours
+//! end. Daily and weekly digests hold every event until the period is over
+//! (local midnight; Monday for weekly) and deliver one summary. Each
+//! outbox numbers its notifications from 1; `poll_outbox(since)` returns
+//! those after `since` and remembers `since` as the actor's read cursor, so
+//! polling a cursor twice answers the same. Preferences, grants, queued
+//! events, outboxes, cursors and the ledger position are saved to
+//! `NotificationPolicy::store_path` after every change, so a restart
+//! neither loses nor repeats a notification.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "actor-keys")]
+use keyring::KeyringSignature;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::history::Standing;
+#[cfg(feature = "actor-keys")]
+use crate::identity::{authenticate, check_fresh, IdentityError};
+use crate::ledger::token_ledger::{TokenLedger, LEDGER_ACTOR, REWARD_CREDIT, TOMBSTONE};
+use crate::obligations::{obligations, FollowUpStatus};
+use crate::utils::http::post_webhook;
+
+/// Webhook kind of a subject `Notification`.
+pub const SUBJECT_NOTIFICATION: &str = "subject_notification";
+
+const HOUR_SECS: i64 = 3_600;
+const DAY_SECS: i64 = 86_400;
+const WEEK_SECS: i64 = 7 * DAY_SECS;
+/// 1970-01-05, the first Monday of Unix time.
+const FIRST_MONDAY_SECS: i64 = 4 * DAY_SECS;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPolicy {
+    /// Seconds before a consent grant expires that its subject is warned,
+    /// once per lead time.
+    pub consent_lead_secs: Vec<i64>,
+    /// Notifications kept per outbox; the oldest are dropped first.
+    pub outbox_cap: usize,
+    /// JSON file the notification state is kept in; in memory without one.
+    pub store_path: Option<String>,
+}
+
+impl Default for NotificationPolicy {
+    fn default() -> Self {
+        Self { consent_lead_secs: vec![7 * DAY_SECS, DAY_SECS], outbox_cap: 500, store_path: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    DeedMinted,
+    ConsentExpiring,
+    FollowUpDue,
+    StandingChanged,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 4] =
+        [EventKind::DeedMinted, EventKind::ConsentExpiring, EventKind::FollowUpDue, EventKind::StandingChanged];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "channel")]
+pub enum Channel {
+    /// Kept for the client to fetch with `poll_outbox`.
+    Outbox,
+    /// POSTed to `http://host:port/path`.
+    Webhook { url: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    Immediate,
+    Daily,
+    Weekly,
+}
+
+/// Local hours in which nothing is delivered, from `start_hour` up to
+/// `end_hour`; a start after the end spans midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+impl QuietHours {
+    fn contains(&self, second_of_day: i64) -> bool {
+        let (start, end) = (i64::from(self.start_hour) * HOUR_SECS, i64::from(self.end_hour) * HOUR_SECS);
+        match start < end {
+            true => (start..end).contains(&second_of_day),
+            false => second_of_day >= start || second_of_day < end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPrefs {
+    pub channels: Vec<Channel>,
+    /// Event kinds delivered; the others are dropped when raised.
+    pub events: BTreeSet<EventKind>,
+    pub digest: DigestFrequency,
+    pub quiet_hours: Option<QuietHours>,
+    /// The actor's offset from UTC, for quiet hours and digest periods.
+    pub utc_offset_mins: i32,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            channels: vec![Channel::Outbox],
+            events: EventKind::ALL.into_iter().collect(),
+            digest: DigestFrequency::Immediate,
+            quiet_hours: None,
+            utc_offset_mins: 0,
+        }
+    }
+}
+
+impl NotificationPrefs {
+    pub fn validate(&self) -> Result<(), NotificationError> {
+        if self.channels.is_empty() {
+            return Err(NotificationError::InvalidPrefs("no channels".to_string()));
+        }
+        for channel in &self.channels {
+            if let Channel::Webhook { url } = channel {
+                if !url.starts_with("http://") {
+                    return Err(NotificationError::InvalidPrefs(format!("unsupported webhook url {}", url)));
+                }
+            }
+        }
+        if let Some(q) = self.quiet_hours {
+            if q.start_hour >= 24 || q.end_hour >= 24 || q.start_hour == q.end_hour {
+                return Err(NotificationError::InvalidPrefs(format!("quiet hours {}-{}", q.start_hour, q.end_hour)));
+            }
+        }
+        if self.utc_offset_mins.abs() > 14 * 60 {
+            return Err(NotificationError::InvalidPrefs(format!("UTC offset of {} minutes", self.utc_offset_mins)));
+        }
+        Ok(())
+    }
+
+    fn offset_secs(&self) -> i64 {
+        i64::from(self.utc_offset_mins) * 60
+    }
+
+    /// The first second at or after `at` outside quiet hours.
+    fn after_quiet_hours(&self, at: i64) -> i64 {
+        let second_of_day = (at + self.offset_secs()).rem_euclid(DAY_SECS);
+        match self.quiet_hours {
+            Some(q) if q.contains(second_of_day) => at + (i64::from(q.end_hour) * HOUR_SECS - second_of_day).rem_euclid(DAY_SECS),
+            _ => at,
+        }
+    }
+
+    /// When an event raised at `at` goes out: at once, or at the end of its
+    /// digest period, and never in quiet hours.
+    fn release_at(&self, at: i64) -> i64 {
+        let offset = self.offset_secs();
+        let local = at + offset;
+        let due = match self.digest {
+            DigestFrequency::Immediate => at,
+            DigestFrequency::Daily => (local.div_euclid(DAY_SECS) + 1) * DAY_SECS - offset,
+            DigestFrequency::Weekly => {
+                ((local - FIRST_MONDAY_SECS).div_euclid(WEEK_SECS) + 1) * WEEK_SECS + FIRST_MONDAY_SECS - offset
+            }
+        };
+        self.after_quiet_hours(due)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubjectEvent {
+    pub kind: EventKind,
+    pub actor_id: String,
+    /// Unix seconds.
+    pub at: i64,
+    /// What the event is about: the rewarded deed, the obligation, the
+    /// consent scope, or the deed that moved the standing.
+    pub reference: String,
+    pub detail: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum NotificationBody {
+    Event(SubjectEvent),
+    /// Every event of one digest period, oldest first.
+    Digest { frequency: DigestFrequency, counts: BTreeMap<EventKind, usize>, events: Vec<SubjectEvent> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Position in the actor's outbox, from 1.
+    pub cursor: u64,
+    pub actor_id: String,
+    pub delivered_at: i64,
+    pub body: NotificationBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxPage {
+    pub notifications: Vec<Notification>,
+    /// Cursor to poll from next.
+    pub next_cursor: u64,
+    /// Notifications after the polled cursor were dropped for `outbox_cap`.
+    pub missed: bool,
+}
+
+/// A consent `scope` the actor granted until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsentGrant {
+    pub scope: String,
+    pub expires_at: i64,
+    /// Lead times already warned of.
+    pub warned: Vec<i64>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum NotificationError {
+    #[error("invalid notification preferences: {0}")]
+    InvalidPrefs(String),
+    #[error("notification store {path}: {reason}")]
+    Store { path: String, reason: String },
+}
+
+fn store_error(path: &Path, e: impl ToString) -> NotificationError {
+    NotificationError::Store { path: path.display().to_string(), reason: e.to_string() }
+}
+
+/// Where webhook-channel notifications are pushed.
+pub trait SubjectNotifier: Send {
+    fn notify(&self, url: &str, notification: &Notification) -> Result<(), String>;
+}
+
+/// POSTs each notification as JSON to the actor's webhook.
+pub struct WebhookSubjectNotifier;
+
+impl SubjectNotifier for WebhookSubjectNotifier {
+    fn notify(&self, url: &str, notification: &Notification) -> Result<(), String> {
+        post_webhook(url, SUBJECT_NOTIFICATION, notification)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Outbox {
+    last_cursor: u64,
+    /// Highest cursor the actor has polled from.
+    read_cursor: u64,
+    /// Cursor of the last notification dropped for the cap.
+    dropped_through: u64,
+    entries: VecDeque<Notification>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Queued {
+    release_at: i64,
+    event: SubjectEvent,
+}
+
+/// Preferences, consent grants, queued events and outboxes. See the
+/// module docs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationCenter {
+    prefs: BTreeMap<String, NotificationPrefs>,
+    consents: BTreeMap<String, Vec<ConsentGrant>>,
+    queued: BTreeMap<String, Vec<Queued>>,
+    outboxes: BTreeMap<String, Outbox>,
+    /// Deeds routed so far, and the hash of the last of them.
+    routed: usize,
+    routed_tip: String,
+    standings: BTreeMap<String, Standing>,
+    /// Pending obligations whose due notice was raised.
+    announced: BTreeSet<String>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl NotificationCenter {
+    /// A center kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The center stored at `path`; an empty one saving there when the
+    /// file does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NotificationError> {
+        let path = path.as_ref();
+        let mut center = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice::<Self>(&bytes).map_err(|e| store_error(path, e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(store_error(path, e)),
+        };
+        center.path = Some(path.to_path_buf());
+        Ok(center)
+    }
+
+    /// `open` on the policy's store, else a center in memory.
+    pub fn for_policy(policy: &NotificationPolicy) -> Result<Self, NotificationError> {
+        policy.store_path.as_ref().map_or_else(|| Ok(Self::new()), Self::open)
+    }
+
+    /// Write the store through a temporary file, so a crash leaves either
+    /// the old state or the new one.
+    fn save(&self) -> Result<(), NotificationError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(self).map_err(|e| store_error(path, e))?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes).and_then(|_| fs::rename(&tmp, path)).map_err(|e| store_error(path, e))
+    }
+
+    pub fn prefs(&self, actor_id: &str) -> Option<&NotificationPrefs> {
+        self.prefs.get(actor_id)
+    }
+
+    /// Opt `actor_id` in, or replace their preferences. Events already
+    /// queued keep their release time.
+    pub fn set_prefs(&mut self, actor_id: &str, prefs: NotificationPrefs) -> Result<(), NotificationError> {
+        prefs.validate()?;
+        self.prefs.insert(actor_id.to_string(), prefs);
+        self.save()
+    }
+
+    /// Record that `actor_id` consented to `scope` until `expires_at`,
+    /// replacing any earlier grant of the scope.
+    pub fn record_consent(&mut self, actor_id: &str, scope: &str, expires_at: i64) -> Result<(), NotificationError> {
+        let grants = self.consents.entry(actor_id.to_string()).or_default();
+        grants.retain(|g| g.scope != scope);
+        grants.push(ConsentGrant { scope: scope.to_string(), expires_at, warned: Vec::new() });
+        self.save()
+    }
+
+    /// Unexpired grants of `actor_id`.
+    pub fn consents(&self, actor_id: &str) -> &[ConsentGrant] {
+        self.consents.get(actor_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Raise the events the ledger and the clock have produced since the
+    /// last tick and deliver everything due at `now`. Webhook failures are
+    /// logged; the notification stays in the outbox if that is a channel
+    /// too. Returns the number of notifications delivered.
+    pub fn tick(&mut self, ledger: &TokenLedger, now: i64, webhooks: &dyn SubjectNotifier) -> Result<usize, NotificationError> {
+        let policy = &ledger.config().notifications;
+        self.route_deeds(ledger, now);
+        self.route_follow_ups(ledger, now);
+        self.route_consents(&policy.consent_lead_secs, now);
+        let delivered = self.flush(policy.outbox_cap, now, webhooks);
+        self.save()?;
+        Ok(delivered)
+    }
+
+    /// `actor_id`'s notifications after `since`, at most `limit`. `since`
+    /// becomes the read cursor, so without one the poll resumes after the
+    /// last cursor the actor polled from.
+    pub fn poll_outbox(&mut self, actor_id: &str, since: Option<u64>, limit: usize) -> Result<OutboxPage, NotificationError> {
+        let Some(outbox) = self.outboxes.get_mut(actor_id) else {
+            return Ok(OutboxPage { notifications: Vec::new(), next_cursor: since.unwrap_or(0), missed: false });
+        };
+        let since = since.unwrap_or(outbox.read_cursor);
+        let notifications: Vec<Notification> =
+            outbox.entries.iter().filter(|n| n.cursor > since).take(limit.max(1)).cloned().collect();
+        let page = OutboxPage {
+            next_cursor: notifications.last().map_or(since, |n| n.cursor),
+            missed: since < outbox.dropped_through,
+            notifications,
+        };
+        let read = since.min(outbox.last_cursor);
+        if read > outbox.read_cursor {
+            outbox.read_cursor = read;
+            self.save()?;
+        }
+        Ok(page)
+    }
+
+    fn raise(&mut self, event: SubjectEvent, now: i64) {
+        let Some(prefs) = self.prefs.get(&event.actor_id) else {
+            return;
+        };
+        if prefs.events.contains(&event.kind) {
+            let release_at = prefs.release_at(now);
+            self.queued.entry(event.actor_id.clone()).or_default().push(Queued { release_at, event });
+        }
+    }
+
+    /// Mints and standing changes in the deeds appended since the last tick.
+    fn route_deeds(&mut self, ledger: &TokenLedger, now: i64) {
+        let deeds = ledger.deeds();
+        let resumes = self.routed == 0 || deeds.get(self.routed - 1).is_some_and(|d| d.self_hash == self.routed_tip);
+        if !resumes {
+            warn!("notification store does not follow this chain; resyncing without notifying");
+            let actors: BTreeSet<&str> = ledger.live_deeds().map(|d| d.actor_id.as_str()).collect();
+            self.standings = actors.into_iter().map(|a| (a.to_string(), standing_of(ledger, a))).collect();
+            self.routed = deeds.len();
+            self.routed_tip = ledger.last_hash();
+            return;
+        }
+
+        let mut touched: BTreeMap<String, (String, i64)> = BTreeMap::new();
+        for d in &deeds[self.routed..] {
+            if d.deed_type == REWARD_CREDIT {
+                if let Some(account) = d.context_json["account_id"].as_str() {
+                    let event = SubjectEvent {
+                        kind: EventKind::DeedMinted,
+                        actor_id: account.to_string(),
+                        at: d.timestamp,
+                        reference: d.context_json["source_event_id"].as_str().unwrap_or(&d.event_id).to_string(),
+                        detail: json!({
+                            "reward_event_id": d.event_id,
+                            "token": d.context_json["token"],
+                            "amount": d.context_json["amount"],
+                        }),
+                    };
+                    self.raise(event, now);
+                }
+            }
+            let actor = match d.deed_type.as_str() {
+                TOMBSTONE => d.context_json["target_actor_id"].as_str(),
+                _ => Some(d.actor_id.as_str()).filter(|a| *a != LEDGER_ACTOR),
+            };
+            if let Some(actor) = actor {
+                touched.insert(actor.to_string(), (d.event_id.clone(), d.timestamp));
+            }
+        }
+        for (actor, (event_id, at)) in touched {
+            let standing = standing_of(ledger, &actor);
+            let before = self.standings.insert(actor.clone(), standing).unwrap_or(Standing::Good);
+            if before != standing {
+                let detail = json!({ "from": before, "to": standing });
+                self.raise(SubjectEvent { kind: EventKind::StandingChanged, actor_id: actor, at, reference: event_id, detail }, now);
+            }
+        }
+        self.routed = deeds.len();
+        self.routed_tip = ledger.last_hash();
+    }
+
+    /// Pending obligations whose follow-up window has opened.
+    fn route_follow_ups(&mut self, ledger: &TokenLedger, now: i64) {
+        let pending: Vec<_> = obligations(ledger).into_iter().filter(|o| o.status == FollowUpStatus::Pending).collect();
+        self.announced.retain(|id| pending.iter().any(|o| &o.obligation_id == id));
+        for o in pending {
+            if now < o.due_at || !self.announced.insert(o.obligation_id.clone()) {
+                continue;
+            }
+            let detail = json!({
+                "source_event_id": o.source_event_id,
+                "kind": o.kind,
+                "evidence": o.evidence,
+                "due_at": o.due_at,
+                "deadline": o.deadline,
+            });
+            let event =
+                SubjectEvent { kind: EventKind::FollowUpDue, actor_id: o.actor_id, at: o.due_at, reference: o.obligation_id, detail };
+            self.raise(event, now);
+        }
+    }
+
+    /// One warning per lead time crossed; when a tick crosses several at
+    /// once, only the nearest is raised. Expired grants are dropped.
+    fn route_consents(&mut self, lead_secs: &[i64], now: i64) {
+        let mut leads = lead_secs.to_vec();
+        leads.sort_unstable();
+        let mut raised = Vec::new();
+        for (actor, grants) in &mut self.consents {
+            grants.retain(|g| now < g.expires_at);
+            for grant in grants.iter_mut() {
+                let left = grant.expires_at - now;
+                let crossed: Vec<i64> = leads.iter().copied().filter(|l| left <= *l && !grant.warned.contains(l)).collect();
+                if let Some(&lead) = crossed.first() {
+                    grant.warned.extend(crossed);
+                    raised.push(SubjectEvent {
+                        kind: EventKind::ConsentExpiring,
+                        actor_id: actor.clone(),
+                        at: now,
+                        reference: grant.scope.clone(),
+                        detail: json!({ "expires_at": grant.expires_at, "lead_secs": lead }),
+                    });
+                }
+            }
+        }
+        self.consents.retain(|_, grants| !grants.is_empty());
+        for event in raised {
+            self.raise(event, now);
+        }
+    }
+
+    /// Deliver every queued event released by `now`: one notification
+    /// each, or one digest per release time.
+    fn flush(&mut self, outbox_cap: usize, now: i64, webhooks: &dyn SubjectNotifier) -> usize {
+        let mut delivered = 0;
+        let actors: Vec<String> = self.queued.keys().cloned().collect();
+        for actor in actors {
+            let queue = self.queued.remove(&actor).unwrap_or_default();
+            let (due, later): (Vec<Queued>, Vec<Queued>) = queue.into_iter().partition(|q| q.release_at <= now);
+            if !later.is_empty() {
+                self.queued.insert(actor.clone(), later);
+            }
+            let prefs = self.prefs.get(&actor).cloned().unwrap_or_default();
+            let bodies: Vec<NotificationBody> = match prefs.digest {
+                DigestFrequency::Immediate => due.into_iter().map(|q| NotificationBody::Event(q.event)).collect(),
+                frequency => {
+                    let mut periods: BTreeMap<i64, Vec<SubjectEvent>> = BTreeMap::new();
+                    for q in due {
+                        periods.entry(q.release_at).or_default().push(q.event);
+                    }
+                    periods
+                        .into_values()
+                        .map(|mut events| {
+                            events.sort_by_key(|e| e.at);
+                            let mut counts = BTreeMap::new();
+                            for e in &events {
+                                *counts.entry(e.kind).or_insert(0) += 1;
+                            }
+                            NotificationBody::Digest { frequency, counts, events }
+                        })
+                        .collect()
+                }
+            };
+            for body in bodies {
+                self.deliver(&actor, &prefs, body, outbox_cap, now, webhooks);
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    fn deliver(
+        &mut self,
+        actor_id: &str,
+        prefs: &NotificationPrefs,
+        body: NotificationBody,
+        outbox_cap: usize,
+        now: i64,
+        webhooks: &dyn SubjectNotifier,
+    ) {
+        let outbox = self.outboxes.entry(actor_id.to_string()).or_default();
+        outbox.last_cursor += 1;
+        let notification = Notification { cursor: outbox.last_cursor, actor_id: actor_id.to_string(), delivered_at: now, body };
+        for channel in &prefs.channels {
+            match channel {
+                Channel::Outbox => {
+                    outbox.entries.push_back(notification.clone());
+                    while outbox.entries.len() > outbox_cap.max(1) {
+                        if let Some(dropped) = outbox.entries.pop_front() {
+                            outbox.dropped_through = dropped.cursor;
+                        }
+                    }
+                }
+                Channel::Webhook { url } => {
+                    if let Err(e) = webhooks.notify(url, &notification) {
+                        warn!("notification {} for {} not delivered: {}", notification.cursor, actor_id, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn standing_of(ledger: &TokenLedger, actor_id: &str) -> Standing {
+    let (flagged, life_harm) = ledger
+        .deeds_for_actor(actor_id)
+        .fold((0, 0), |(f, l), d| (f + usize::from(!d.ethics_flags.is_empty()), l + usize::from(d.life_harm_flag)));
+    Standing::from_counts(flagged, life_harm)
+}
+
+/// What a subject signs to call a notification RPC: the method, the actor
+/// and `args`, the request params without `actor_id`, `signature` and
+/// `now`.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubjectRequest {
+    pub method: String,
+    pub actor_id: String,
+    pub args: serde_json::Value,
+}
+
+#[cfg(feature = "actor-keys")]
+impl SubjectRequest {
+    pub fn new(method: &str, actor_id: &str, args: serde_json::Value) -> Self {
+        Self { method: method.to_string(), actor_id: actor_id.to_string(), args }
+    }
+
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("subject request serializes")
+    }
+}
+
+/// Verify that `signature` over `request` is fresh and was made by the
+/// requesting actor's accepted key.
+#[cfg(feature = "actor-keys")]
+pub fn authenticate_subject(
+    ledger: &TokenLedger,
+    request: &SubjectRequest,
+    signature: &KeyringSignature,
+    now: i64,
+) -> Result<(), IdentityError> {
+    check_fresh(signature, now, &ledger.config().identity)?;
+    authenticate(ledger, &request.actor_id, &request.signing_bytes(), signature)
+}
@@ -0,0 +1,294 @@
+//! Follow-up obligations for claims whose impact only shows later.
+//!
+//! A planted tree is worth its reward only if it survives, so a taxonomy
+//! category can declare follow-ups (`survival_check` after 180 days, with
+//! photo evidence). `reward_with_follow_ups` credits a deed's reward minus
+//! `ObligationPolicy::escrow_fraction`, which is issued into the
+//! `escrow:obligations` account as one `obligation_opened` deed per
+//! follow-up. A `follow_up` deed from the same actor inside the window,
+//! carrying the declared evidence, settles that share to the actor
+//! (`submit_follow_up`). Past the deadline `sweep_missed` logs an
+//! `obligation_missed` deed, recycles the share into the sponsor pool (or
+//! burns it when the pool policy refuses expired obligations) and resets
+//! the actor's streak in the category.
+//!
+//! Obligation state is read back from those deeds, so it survives replay.
+//! Tombstoning the rewarded deed undoes its escrow and cancels the
+//! obligations with it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::ledger::account::Token;
+use crate::ledger::builders::schema_for;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::sponsor::pool::InflowSource;
+
+pub const PENDING_OBLIGATIONS: &str = "escrow:obligations";
+pub const OBLIGATION_OPENED: &str = "obligation_opened";
+pub const OBLIGATION_SETTLED: &str = "obligation_settled";
+pub const OBLIGATION_MISSED: &str = "obligation_missed";
+/// Deed type of an actor's follow-up; it targets the rewarded deed and
+/// carries `kind`, `evidence_kind` and `evidence_uri`.
+pub const FOLLOW_UP: &str = "follow_up";
+
+const DAY_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObligationPolicy {
+    /// Share of a reward held in escrow while follow-ups are outstanding.
+    pub escrow_fraction: f64,
+    /// How often the scheduler sweeps missed obligations.
+    pub sweep_every_secs: u64,
+}
+
+impl Default for ObligationPolicy {
+    fn default() -> Self {
+        Self { escrow_fraction: 0.3, sweep_every_secs: 86_400 }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ObligationError {
+    #[error("unknown deed {0}")]
+    UnknownDeed(String),
+    #[error("deed {0} already has obligations")]
+    AlreadyOpen(String),
+    #[error("{0} is not a follow_up deed")]
+    NotAFollowUp(String),
+    #[error("no pending {kind} obligation on deed {event_id}")]
+    NoPendingObligation { event_id: String, kind: String },
+    #[error("{0} does not owe this follow-up")]
+    NotObligor(String),
+    #[error("follow-up is not due before {0}")]
+    NotDue(i64),
+    #[error("follow-up deadline {0} has passed")]
+    PastDeadline(i64),
+    #[error("evidence rejected: {0}")]
+    EvidenceRejected(String),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum FollowUpStatus {
+    Pending,
+    /// Escrow paid to the actor.
+    Settled {
+        follow_up_event_id: String,
+        settled_at: i64,
+    },
+    /// Escrow swept; the category streak was reset.
+    Missed {
+        swept_at: i64,
+    },
+    /// The rewarded deed was tombstoned and its escrow undone.
+    Cancelled,
+}
+
+/// One follow-up owed on a rewarded deed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Obligation {
+    /// Event id of the `obligation_opened` deed.
+    pub obligation_id: String,
+    pub source_event_id: String,
+    pub actor_id: String,
+    pub deed_type: String,
+    pub kind: String,
+    pub evidence: String,
+    /// Unix seconds; the follow-up is accepted in `[due_at, deadline)`.
+    pub due_at: i64,
+    pub deadline: i64,
+    pub escrowed: u64,
+    #[serde(flatten)]
+    pub status: FollowUpStatus,
+}
+
+impl Obligation {
+    fn from_deed(d: &DeedEvent) -> Self {
+        let ctx = &d.context_json;
+        let text = |key: &str| ctx[key].as_str().unwrap_or_default().to_string();
+        Self {
+            obligation_id: d.event_id.clone(),
+            source_event_id: text("source_event_id"),
+            actor_id: text("actor_id"),
+            deed_type: text("deed_type"),
+            kind: text("kind"),
+            evidence: text("evidence"),
+            due_at: ctx["due_at"].as_i64().unwrap_or_default(),
+            deadline: ctx["deadline"].as_i64().unwrap_or_default(),
+            escrowed: ctx["amount"].as_u64().unwrap_or_default(),
+            status: FollowUpStatus::Pending,
+        }
+    }
+}
+
+/// How a reward was split between the actor and the escrow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardSplit {
+    /// CHURCH credited to the actor now, after the pool tithe.
+    pub credited: u64,
+    pub escrowed: u64,
+    pub obligation_ids: Vec<String>,
+}
+
+/// Every obligation on the ledger, in the order it was opened.
+pub fn obligations(ledger: &TokenLedger) -> Vec<Obligation> {
+    let mut out: Vec<Obligation> = Vec::new();
+    for d in ledger.deeds() {
+        let target = d.target_ids.first().map(String::as_str);
+        match d.deed_type.as_str() {
+            OBLIGATION_OPENED => {
+                let mut obligation = Obligation::from_deed(d);
+                if ledger.is_tombstoned(&d.event_id) {
+                    obligation.status = FollowUpStatus::Cancelled;
+                }
+                out.push(obligation);
+            }
+            OBLIGATION_SETTLED => {
+                if let Some(status) = status_of(&mut out, target) {
+                    *status = FollowUpStatus::Settled {
+                        follow_up_event_id: d.context_json["follow_up_event_id"].as_str().unwrap_or_default().to_string(),
+                        settled_at: d.context_json["settled_at"].as_i64().unwrap_or(d.timestamp),
+                    };
+                }
+            }
+            OBLIGATION_MISSED => {
+                if let Some(status) = status_of(&mut out, target) {
+                    *status = FollowUpStatus::Missed { swept_at: d.context_json["swept_at"].as_i64().unwrap_or(d.timestamp) };
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn status_of<'a>(out: &'a mut [Obligation], obligation_id: Option<&str>) -> Option<&'a mut FollowUpStatus> {
+    out.iter_mut().rfind(|o| Some(o.obligation_id.as_str()) == obligation_id).map(|o| &mut o.status)
+}
+
+/// Follow-ups owed on deed `event_id` and where each stands.
+pub fn follow_up_status(ledger: &TokenLedger, event_id: &str) -> Vec<Obligation> {
+    obligations(ledger).into_iter().filter(|o| o.source_event_id == event_id).collect()
+}
+
+/// Live `deed_type` deeds by `actor_id` since their last missed follow-up
+/// in that category.
+pub fn category_streak(ledger: &TokenLedger, actor_id: &str, deed_type: &str) -> u32 {
+    let mut streak = 0;
+    for d in ledger.deeds() {
+        if d.deed_type == OBLIGATION_MISSED && d.context_json["actor_id"] == actor_id && d.context_json["deed_type"] == deed_type
+        {
+            streak = 0;
+        } else if d.actor_id == actor_id && d.deed_type == deed_type && !ledger.is_tombstoned(&d.event_id) {
+            streak += 1;
+        }
+    }
+    streak
+}
+
+/// Reward deed `event_id` with `amount` CHURCH. Categories without
+/// follow-ups get it all now; otherwise the escrow share is split evenly
+/// across the declared follow-ups (remainder to the first), each due
+/// relative to the deed's timestamp.
+pub fn reward_with_follow_ups(ledger: &mut TokenLedger, event_id: &str, amount: u64) -> Result<RewardSplit, ObligationError> {
+    let deed = ledger.deed(event_id).ok_or_else(|| ObligationError::UnknownDeed(event_id.to_string()))?;
+    let (actor_id, deed_type, timestamp) = (deed.actor_id.clone(), deed.deed_type.clone(), deed.timestamp);
+    let specs = schema_for(&deed_type).map_or(&[][..], |s| s.follow_ups);
+    if !specs.is_empty() && obligations(ledger).iter().any(|o| o.source_event_id == event_id) {
+        return Err(ObligationError::AlreadyOpen(event_id.to_string()));
+    }
+    let fraction = if specs.is_empty() { 0.0 } else { ledger.config().obligations.escrow_fraction.clamp(0.0, 1.0) };
+    let escrowed = (amount as f64 * fraction).floor() as u64;
+
+    ledger.open_account(&actor_id, &actor_id);
+    let credited = ledger.reward_for(&actor_id, Token::Church, amount - escrowed, Some(event_id))?;
+    let mut obligation_ids = Vec::with_capacity(specs.len());
+    for (i, spec) in specs.iter().enumerate() {
+        let share = escrowed / specs.len() as u64 + if i == 0 { escrowed % specs.len() as u64 } else { 0 };
+        let due_at = timestamp + i64::from(spec.due_after_days) * DAY_SECS;
+        let context = json!({
+            "actor_id": actor_id,
+            "deed_type": deed_type,
+            "kind": spec.kind,
+            "evidence": spec.evidence,
+            "due_at": due_at,
+            "deadline": due_at + i64::from(spec.window_days) * DAY_SECS,
+        });
+        obligation_ids.push(ledger.open_obligation(event_id, share, context)?);
+    }
+    Ok(RewardSplit { credited, escrowed, obligation_ids })
+}
+
+/// Append an actor's `follow_up` deed and settle the obligation it answers.
+/// It must come from the obligor within the window and carry the declared
+/// `evidence_kind` with a non-empty `evidence_uri`. Returns the CHURCH paid.
+pub fn submit_follow_up(ledger: &mut TokenLedger, deed: DeedEvent, now: i64) -> Result<u64, ObligationError> {
+    if deed.deed_type != FOLLOW_UP {
+        return Err(ObligationError::NotAFollowUp(deed.deed_type));
+    }
+    let source = deed.target_ids.first().cloned().unwrap_or_default();
+    let kind = deed.context_json["kind"].as_str().unwrap_or_default().to_string();
+    let Some(obligation) =
+        follow_up_status(ledger, &source).into_iter().find(|o| o.kind == kind && o.status == FollowUpStatus::Pending)
+    else {
+        return Err(ObligationError::NoPendingObligation { event_id: source, kind });
+    };
+    if deed.actor_id != obligation.actor_id {
+        return Err(ObligationError::NotObligor(deed.actor_id));
+    }
+    if now < obligation.due_at {
+        return Err(ObligationError::NotDue(obligation.due_at));
+    }
+    if now >= obligation.deadline {
+        return Err(ObligationError::PastDeadline(obligation.deadline));
+    }
+    if deed.context_json["evidence_kind"] != obligation.evidence.as_str() {
+        return Err(ObligationError::EvidenceRejected(format!("{} evidence required", obligation.evidence)));
+    }
+    if deed.context_json["evidence_uri"].as_str().is_none_or(|uri| uri.trim().is_empty()) {
+        return Err(ObligationError::EvidenceRejected("evidence_uri is empty".to_string()));
+    }
+
+    let follow_up_event_id = ledger.append(deed)?.event_id.clone();
+    let context = json!({ "follow_up_event_id": follow_up_event_id, "source_event_id": source, "kind": kind, "settled_at": now });
+    Ok(ledger.settle_obligation(&obligation.obligation_id, &obligation.actor_id, obligation.escrowed, context)?)
+}
+
+/// Close every pending obligation whose deadline is at or before `now`.
+/// Returns the obligation ids swept.
+pub fn sweep_missed(ledger: &mut TokenLedger, now: i64) -> Result<Vec<String>, ObligationError> {
+    let recycle = ledger.config().pool.recycle_expired_obligations;
+    let mut swept = Vec::new();
+    for o in obligations(ledger) {
+        if o.status != FollowUpStatus::Pending || now < o.deadline {
+            continue;
+        }
+        let context = json!({
+            "source_event_id": o.source_event_id,
+            "actor_id": o.actor_id,
+            "deed_type": o.deed_type,
+            "kind": o.kind,
+            "deadline": o.deadline,
+            "amount": o.escrowed,
+            "recycled": recycle,
+            "swept_at": now,
+        });
+        ledger.log_obligation_missed(&o.obligation_id, context)?;
+        if o.escrowed > 0 {
+            if recycle {
+                let extra = json!({ "reference": o.obligation_id });
+                ledger.pool_inflow(InflowSource::RecycledObligation, Some(PENDING_OBLIGATIONS), o.escrowed, extra)?;
+            } else {
+                ledger.burn(PENDING_OBLIGATIONS, Token::Church, o.escrowed)?;
+            }
+        }
+        swept.push(o.obligation_id);
+    }
+    Ok(swept)
+}
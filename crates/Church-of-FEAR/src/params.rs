@@ -0,0 +1,41 @@
+//! Runtime parameters on the ledger.
+//!
+//! `TokenLedger` owns a `ParamRegistry`: the declared defaults, then the
+//! informational overrides in `LedgerConfig::param_overrides`, then every
+//! `parameter_change` deed in log order. Those deeds are ledger-authored
+//! only (`append` refuses them), so `change_param`, with its multisig
+//! check, is the one way a safety-relevant value moves at runtime.
+
+use thiserror::Error;
+
+use crate::ledger::token_ledger::TokenLedgerError;
+#[cfg(feature = "param-governance")]
+use crate::ledger::token_ledger::TokenLedger;
+#[cfg(feature = "param-governance")]
+pub use param_registry::SignedParamChange;
+pub use param_registry::{ParamChangeRecord, ParamError, ParamKey, ParamRegistry};
+
+pub const PARAMETER_CHANGE: &str = "parameter_change";
+
+#[derive(Error, Debug)]
+pub enum ParamChangeError {
+    #[error(transparent)]
+    Param(#[from] ParamError),
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Verify `signed` against the ledger's `param_governance` policy, then log
+/// it as a `parameter_change` deed and apply it.
+#[cfg(feature = "param-governance")]
+pub fn change_param(
+    ledger: &mut TokenLedger,
+    signed: &SignedParamChange,
+    authorities: &keyring::VerifyingBundle,
+    now: i64,
+) -> Result<ParamChangeRecord, ParamChangeError> {
+    let record = ledger.params().authorize(signed, authorities, &ledger.config().param_governance, now)?;
+    let record = ledger.log_param_change(record)?;
+    log::info!("Parameter {} changed from {} to {} by {}", record.param, record.previous, record.value, record.change_id);
+    Ok(record)
+}
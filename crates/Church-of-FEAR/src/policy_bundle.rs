@@ -0,0 +1,370 @@
+//! The policy bundle a node runs under.
+//!
+//! A node's behavior depends on separately versioned artifacts: the deed
+//! taxonomy, the eco-governance rules, the parameter registry, the deed
+//! schema it was built with. `policy_bundle.json` lists all of them in one
+//! place:
+//!
+//! ```json
+//! { "artifacts": [
+//!   { "name": "taxonomy", "path": "taxonomy/deeds.json", "sha256": "…", "version": 2,
+//!     "requires": [{ "artifact": "eco_governance", "min_version": 3 }] },
+//!   { "name": "deed_schema", "version": 2 }
+//! ] }
+//! ```
+//!
+//! Paths are relative to the bundle directory. An entry without a path is
+//! built into the node (`BUILTIN_ARTIFACTS`) and must name the version
+//! compiled in. A JSON artifact carrying a top-level `version` must carry
+//! the version its entry declares. `requires` bounds the versions of other
+//! artifacts in the same bundle.
+//!
+//! `PolicyBundle::load_and_verify` checks every hash, version and
+//! requirement and returns every problem found, not just the first; the
+//! node refuses to start on any. A hot reload goes through `reload`, which
+//! runs the same checks and reports what changed (`diff_bundles`).
+//!
+//! A bundle's hash covers its whole manifest, content hashes included, so
+//! it names one exact policy set. `record_loaded` logs it as a
+//! `policy_bundle_loaded` deed whenever it differs from the bundle the
+//! ledger last recorded; every deed after that one ran under it
+//! (`bundle_at`).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::schema::SCHEMA_VERSION;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+
+pub const POLICY_BUNDLE_LOADED: &str = "policy_bundle_loaded";
+/// File name of the manifest inside a bundle directory.
+pub const MANIFEST_FILE: &str = "policy_bundle.json";
+/// Artifacts compiled into the node, with the version compiled in.
+pub const BUILTIN_ARTIFACTS: &[(&str, u32)] = &[("deed_schema", SCHEMA_VERSION)];
+
+/// Bounds on another artifact's version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Requirement {
+    pub artifact: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_version: Option<u32>,
+}
+
+impl Requirement {
+    fn admits(&self, version: u32) -> bool {
+        self.min_version.is_none_or(|min| version >= min) && self.max_version.is_none_or(|max| version <= max)
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.artifact)?;
+        match (self.min_version, self.max_version) {
+            (Some(min), Some(max)) => write!(f, " {}..={}", min, max),
+            (Some(min), None) => write!(f, " >={}", min),
+            (None, Some(max)) => write!(f, " <={}", max),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactEntry {
+    pub name: String,
+    /// Relative to the bundle directory; `None` for a built-in artifact.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Hex SHA-256 of the file's bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<Requirement>,
+}
+
+/// Contents of `policy_bundle.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub artifacts: Vec<ArtifactEntry>,
+}
+
+/// One reason a bundle is refused.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum BundleProblem {
+    #[error("{artifact} is listed more than once")]
+    Duplicate { artifact: String },
+    #[error("{artifact}: {path} cannot be read: {reason}")]
+    Unreadable { artifact: String, path: String, reason: String },
+    #[error("{artifact}: no sha256 for {path}")]
+    MissingHash { artifact: String, path: String },
+    #[error("{artifact}: {path} hashes to {actual}, not {expected}")]
+    HashMismatch { artifact: String, path: String, expected: String, actual: String },
+    #[error("{artifact}: declared version {declared}, found {found}")]
+    VersionMismatch { artifact: String, declared: u32, found: u32 },
+    #[error("{artifact} has no path and is not built in")]
+    NotBuiltIn { artifact: String },
+    #[error("{artifact} requires {requires}, which is not in the bundle")]
+    UnknownRequirement { artifact: String, requires: String },
+    #[error("{artifact} v{version} requires {requirement}, found v{found}")]
+    Unsatisfied { artifact: String, version: u32, requirement: String, found: u32 },
+}
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("{}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    #[error("policy bundle refused, {} problem(s):{}", .0.len(), listed(.0))]
+    Invalid(Vec<BundleProblem>),
+}
+
+fn listed(problems: &[BundleProblem]) -> String {
+    problems.iter().map(|p| format!("\n  - {}", p)).collect()
+}
+
+/// A verified bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyBundle {
+    dir: PathBuf,
+    manifest: BundleManifest,
+    hash: String,
+}
+
+impl PolicyBundle {
+    /// Read `<dir>/policy_bundle.json` and check it against the files it
+    /// names and the node's built-in artifacts. Fails with every problem
+    /// found.
+    pub fn load_and_verify(dir: &Path) -> Result<Self, BundleError> {
+        let path = dir.join(MANIFEST_FILE);
+        let bytes = fs::read(&path).map_err(|source| BundleError::Io { path, source })?;
+        let manifest: BundleManifest = serde_json::from_slice(&bytes)?;
+        let problems = verify(dir, &manifest);
+        if !problems.is_empty() {
+            return Err(BundleError::Invalid(problems));
+        }
+        let hash = bundle_hash(&manifest);
+        Ok(Self { dir: dir.to_path_buf(), manifest, hash })
+    }
+
+    /// Load and verify `dir` as a replacement for this bundle. Nothing
+    /// changes unless it passes; the diff is for the operator's log.
+    pub fn reload(&self, dir: &Path) -> Result<(PolicyBundle, BundleDiff), BundleError> {
+        let next = Self::load_and_verify(dir)?;
+        let diff = diff_bundles(self, &next);
+        Ok((next, diff))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn artifact(&self, name: &str) -> Option<&ArtifactEntry> {
+        self.manifest.artifacts.iter().find(|a| a.name == name)
+    }
+}
+
+/// SHA-256 over the manifest's canonical JSON, artifacts ordered by name.
+pub fn bundle_hash(manifest: &BundleManifest) -> String {
+    let mut sorted = manifest.clone();
+    sorted.artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+    let canonical = serde_json::to_vec(&sorted).expect("manifest serializes");
+    format!("{:x}", Sha256::digest(&canonical))
+}
+
+fn verify(dir: &Path, manifest: &BundleManifest) -> Vec<BundleProblem> {
+    let mut problems = Vec::new();
+    let mut versions: BTreeMap<&str, u32> = BTreeMap::new();
+    for entry in &manifest.artifacts {
+        if versions.insert(entry.name.as_str(), entry.version).is_some() {
+            problems.push(BundleProblem::Duplicate { artifact: entry.name.clone() });
+        }
+        problems.extend(verify_artifact(dir, entry));
+    }
+    for entry in &manifest.artifacts {
+        for requirement in &entry.requires {
+            match versions.get(requirement.artifact.as_str()) {
+                None => problems.push(BundleProblem::UnknownRequirement {
+                    artifact: entry.name.clone(),
+                    requires: requirement.artifact.clone(),
+                }),
+                Some(&found) if !requirement.admits(found) => problems.push(BundleProblem::Unsatisfied {
+                    artifact: entry.name.clone(),
+                    version: entry.version,
+                    requirement: requirement.to_string(),
+                    found,
+                }),
+                Some(_) => {}
+            }
+        }
+    }
+    problems
+}
+
+fn verify_artifact(dir: &Path, entry: &ArtifactEntry) -> Vec<BundleProblem> {
+    let artifact = entry.name.clone();
+    let Some(path) = &entry.path else {
+        return match BUILTIN_ARTIFACTS.iter().find(|(name, _)| *name == entry.name) {
+            Some(&(_, found)) if found != entry.version => {
+                vec![BundleProblem::VersionMismatch { artifact, declared: entry.version, found }]
+            }
+            Some(_) => Vec::new(),
+            None => vec![BundleProblem::NotBuiltIn { artifact }],
+        };
+    };
+    let bytes = match fs::read(dir.join(path)) {
+        Ok(bytes) => bytes,
+        Err(e) => return vec![BundleProblem::Unreadable { artifact, path: path.clone(), reason: e.to_string() }],
+    };
+    let mut problems = Vec::new();
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    match &entry.sha256 {
+        None => problems.push(BundleProblem::MissingHash { artifact: artifact.clone(), path: path.clone() }),
+        Some(expected) if !expected.eq_ignore_ascii_case(&actual) => problems.push(BundleProblem::HashMismatch {
+            artifact: artifact.clone(),
+            path: path.clone(),
+            expected: expected.clone(),
+            actual,
+        }),
+        Some(_) => {}
+    }
+    let embedded = serde_json::from_slice::<serde_json::Value>(&bytes).ok().and_then(|v| v["version"].as_u64());
+    if let Some(found) = embedded {
+        if found != entry.version as u64 {
+            problems.push(BundleProblem::VersionMismatch { artifact, declared: entry.version, found: found as u32 });
+        }
+    }
+    problems
+}
+
+/// How one artifact differs between two bundles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactChange {
+    pub name: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    /// The content hash, when it changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<(String, String)>,
+    /// The requirements, when they changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires: Option<(Vec<Requirement>, Vec<Requirement>)>,
+}
+
+/// What an upgrade from one bundle to another changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleDiff {
+    pub from_hash: String,
+    pub to_hash: String,
+    pub added: Vec<ArtifactEntry>,
+    pub removed: Vec<ArtifactEntry>,
+    pub changed: Vec<ArtifactChange>,
+}
+
+impl BundleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// One line per added (`+`), removed (`-`) or changed (`~`) artifact.
+impl fmt::Display for BundleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "policy bundle {} -> {}", short(&self.from_hash), short(&self.to_hash))?;
+        for a in &self.added {
+            writeln!(f, "+ {} v{}", a.name, a.version)?;
+        }
+        for a in &self.removed {
+            writeln!(f, "- {} v{}", a.name, a.version)?;
+        }
+        for c in &self.changed {
+            write!(f, "~ {} v{} -> v{}", c.name, c.from_version, c.to_version)?;
+            if let Some((from, to)) = &c.hash {
+                write!(f, ", sha256 {} -> {}", short(from), short(to))?;
+            }
+            if let Some((from, to)) = &c.requires {
+                let list = |r: &[Requirement]| r.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, ", requires [{}] -> [{}]", list(from), list(to))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+/// What changes going from bundle `a` to bundle `b`, artifacts by name.
+pub fn diff_bundles(a: &PolicyBundle, b: &PolicyBundle) -> BundleDiff {
+    let by_name = |bundle: &PolicyBundle| -> BTreeMap<String, ArtifactEntry> {
+        bundle.manifest.artifacts.iter().map(|e| (e.name.clone(), e.clone())).collect()
+    };
+    let (from, to) = (by_name(a), by_name(b));
+    let names: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+    let mut diff = BundleDiff { from_hash: a.hash.clone(), to_hash: b.hash.clone(), ..BundleDiff::default() };
+    for name in names {
+        match (from.get(name), to.get(name)) {
+            (None, Some(added)) => diff.added.push(added.clone()),
+            (Some(removed), None) => diff.removed.push(removed.clone()),
+            (Some(old), Some(new)) if old != new => diff.changed.push(ArtifactChange {
+                name: name.clone(),
+                from_version: old.version,
+                to_version: new.version,
+                hash: (old.sha256 != new.sha256)
+                    .then(|| (old.sha256.clone().unwrap_or_default(), new.sha256.clone().unwrap_or_default())),
+                requires: (old.requires != new.requires).then(|| (old.requires.clone(), new.requires.clone())),
+            }),
+            _ => {}
+        }
+    }
+    diff
+}
+
+/// Log `bundle` as a `policy_bundle_loaded` deed unless it is already the
+/// ledger's current bundle. Returns the new deed's event id, if one was
+/// logged.
+pub fn record_loaded(ledger: &mut TokenLedger, bundle: &PolicyBundle) -> Result<Option<String>, TokenLedgerError> {
+    if ledger.policy_bundle() == Some(bundle.hash()) {
+        return Ok(None);
+    }
+    let artifacts: BTreeMap<&str, serde_json::Value> = bundle
+        .manifest
+        .artifacts
+        .iter()
+        .map(|a| (a.name.as_str(), json!({ "version": a.version, "sha256": a.sha256 })))
+        .collect();
+    let context = json!({ "bundle_hash": bundle.hash(), "artifacts": artifacts });
+    Ok(Some(ledger.log_policy_bundle(context)?.event_id.clone()))
+}
+
+/// The bundle the deed at `position` ran under: the hash in the latest
+/// `policy_bundle_loaded` deed before it.
+pub fn bundle_at(deeds: &[DeedEvent], position: usize) -> Option<&str> {
+    deeds[..position.min(deeds.len())]
+        .iter()
+        .rev()
+        .find(|d| d.deed_type == POLICY_BUNDLE_LOADED)
+        .and_then(|d| d.context_json["bundle_hash"].as_str())
+}
@@ -0,0 +1,581 @@
+//! Clinical attestation providers and how far their attestations count.
+//!
+//! Attestation-bearing categories (those whose taxonomy schema requires a
+//! `provider_id`) must name a provider that a correction role registered
+//! with a `provider_registered` deed; `TokenLedger::append` refuses the deed
+//! otherwise.
+//!
+//! A provider's reliability is read from the outcomes of its attestations
+//! inside a sliding window of `window_secs`. Corroborated ones (approved by
+//! their validation quorum, or confirmed by an audit) pull it towards 1;
+//! contradicted ones (rejected by their quorum, tombstoned, or failed by an
+//! audit) pull it towards 0. Each outcome counts `0.5^(age / half_life_secs)`
+//! and the policy's `prior` counts as `prior_weight` outcomes, so a provider
+//! with no recent history drifts back to the prior:
+//!
+//! ```text
+//! reliability = (prior * prior_weight + Σ wᵢ·oᵢ) / (prior_weight + Σ wᵢ)
+//! ```
+//!
+//! On append the ledger pins the provider's reliability at the deed's
+//! timestamp into its context as `provider_weight`, rehashing it as a
+//! cooldown does. `clin_trust` counts each attestation at that pinned
+//! weight, so later decay never rewrites history.
+//!
+//! Every outcome is a `provider_outcome` deed carrying the reliability after
+//! it. One that drops a provider below `suspension_floor` is followed by a
+//! `provider_suspended` deed and an operator alert. A suspended provider's
+//! new attestations are still accepted, pinned at weight 0, and each raises
+//! an alert of its own. A correction role lifts the suspension with a
+//! `provider_reinstated` deed; outcomes before it no longer count, so the
+//! provider starts again from the prior.
+//!
+//! Only the ledger writes these four deed types, so a replayed ledger has
+//! the same providers, scores and suspensions.
+
+use std::collections::{BTreeMap, HashSet};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::cooldown::{is_suppressed, slashed_ids};
+use crate::ledger::builders::schema_for;
+use crate::ledger::deed_event::{hash_deed, DeedEvent};
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::quorum::{pending_validations, ValidationOutcome, ValidationStatus};
+use crate::utils::http::post_webhook;
+
+pub const PROVIDER_REGISTERED: &str = "provider_registered";
+pub const PROVIDER_OUTCOME: &str = "provider_outcome";
+pub const PROVIDER_SUSPENDED: &str = "provider_suspended";
+pub const PROVIDER_REINSTATED: &str = "provider_reinstated";
+
+/// Context field naming an attestation's provider.
+pub const PROVIDER_ID: &str = "provider_id";
+/// Context field the ledger pins the provider's reliability into.
+pub const PROVIDER_WEIGHT: &str = "provider_weight";
+/// Context field naming the suspension an attestation was accepted under.
+pub const PROVIDER_SUSPENSION: &str = "provider_suspension";
+
+/// Webhook kind of a `ProviderAlert`.
+pub const PROVIDER_ALERT: &str = "provider_alert";
+
+/// Outcome sources.
+pub const QUORUM_SOURCE: &str = "validation_quorum";
+pub const TOMBSTONE_SOURCE: &str = "tombstone";
+pub const AUDIT_SOURCE: &str = "audit";
+
+/// `clin_trust` before any attestation, what each full-weight attestation
+/// adds, and the most they add together; the sovereignty core's
+/// `calc_clin_trust` curve.
+const CLIN_TRUST_BASE: f64 = 0.70;
+const CLIN_TRUST_PER_ATTESTATION: f64 = 0.04;
+const CLIN_TRUST_ATTESTATION_CAP: f64 = 0.25;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderPolicy {
+    /// Age in seconds past which an outcome no longer counts.
+    pub window_secs: i64,
+    /// Age in seconds at which an outcome counts half.
+    pub half_life_secs: i64,
+    /// Reliability of a provider with no outcomes in the window.
+    pub prior: f64,
+    /// How many outcomes the prior is worth.
+    pub prior_weight: f64,
+    /// Reliability below which a provider is suspended.
+    pub suspension_floor: f64,
+    /// Roles that may record audit outcomes.
+    pub auditor_roles: Vec<String>,
+    /// How often the scheduler scores settled attestations.
+    pub sweep_every_secs: u64,
+    /// Operator webhook (`http://host:port/path`) for provider alerts.
+    pub alert_webhook: Option<String>,
+}
+
+impl Default for ProviderPolicy {
+    fn default() -> Self {
+        Self {
+            window_secs: 180 * 86_400,
+            half_life_secs: 30 * 86_400,
+            prior: 0.8,
+            prior_weight: 2.0,
+            suspension_floor: 0.5,
+            auditor_roles: vec!["Auditor".to_string(), "Regulator".to_string()],
+            sweep_every_secs: 86_400,
+            alert_webhook: None,
+        }
+    }
+}
+
+impl ProviderPolicy {
+    /// The webhook notifier, when `alert_webhook` is set.
+    pub fn notifier(&self) -> Option<WebhookProviderNotifier> {
+        self.alert_webhook.clone().map(|url| WebhookProviderNotifier { url })
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ProviderError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error("role {0} may not register or reinstate providers")]
+    RoleNotAllowed(String),
+    #[error("role {0} may not record audit outcomes")]
+    NotAuditor(String),
+    #[error("{0} is already a registered provider")]
+    AlreadyRegistered(String),
+    #[error("unknown provider {0}")]
+    UnknownProvider(String),
+    #[error("unknown deed {0}")]
+    UnknownDeed(String),
+    #[error("deed {0} is not a provider attestation")]
+    NotAnAttestation(String),
+    #[error("provider {0} is not suspended")]
+    NotSuspended(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationOutcome {
+    /// Approved by its validation quorum or confirmed by an audit.
+    Corroborated,
+    /// Rejected by its quorum, tombstoned or failed by an audit.
+    Contradicted,
+}
+
+impl AttestationOutcome {
+    fn value(self) -> f64 {
+        match self {
+            AttestationOutcome::Corroborated => 1.0,
+            AttestationOutcome::Contradicted => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutcomeRecord {
+    pub outcome_event_id: String,
+    /// The attestation judged.
+    pub deed_event_id: String,
+    pub outcome: AttestationOutcome,
+    /// `validation_quorum`, `tombstone` or `audit`.
+    pub source: String,
+    pub at: i64,
+}
+
+impl OutcomeRecord {
+    fn from_deed(d: &DeedEvent) -> Self {
+        let ctx = &d.context_json;
+        Self {
+            outcome_event_id: d.event_id.clone(),
+            deed_event_id: ctx["deed_event_id"].as_str().unwrap_or_default().to_string(),
+            outcome: serde_json::from_value(ctx["outcome"].clone()).unwrap_or(AttestationOutcome::Contradicted),
+            source: ctx["source"].as_str().unwrap_or_default().to_string(),
+            at: ctx["at"].as_i64().unwrap_or(d.timestamp),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provider {
+    pub provider_id: String,
+    pub name: String,
+    pub registered_at: i64,
+    /// Outcomes since registration or the latest reinstatement, oldest
+    /// first. A later outcome for the same attestation replaces the earlier.
+    pub outcomes: Vec<OutcomeRecord>,
+    /// The `provider_suspended` deed in force, if any.
+    pub suspension: Option<String>,
+}
+
+impl Provider {
+    /// Reliability at `at` from the outcomes recorded by then.
+    pub fn reliability_at(&self, at: i64, policy: &ProviderPolicy) -> f64 {
+        reliability(self.outcomes.iter().map(|o| (o.at, o.outcome)), at, policy)
+    }
+
+    pub fn suspended(&self) -> bool {
+        self.suspension.is_some()
+    }
+
+    fn record(&mut self, outcome: OutcomeRecord) {
+        self.outcomes.retain(|o| o.deed_event_id != outcome.deed_event_id);
+        self.outcomes.push(outcome);
+    }
+}
+
+/// Reliability at `at` from `(recorded_at, outcome)` pairs: outcomes after
+/// `at` or older than the window are ignored, the rest decay by age.
+pub fn reliability(
+    outcomes: impl IntoIterator<Item = (i64, AttestationOutcome)>,
+    at: i64,
+    policy: &ProviderPolicy,
+) -> f64 {
+    let mut weighted = policy.prior * policy.prior_weight;
+    let mut total = policy.prior_weight;
+    for (recorded_at, outcome) in outcomes {
+        let age = at - recorded_at;
+        if age < 0 || age > policy.window_secs {
+            continue;
+        }
+        let w = 0.5f64.powf(age as f64 / policy.half_life_secs.max(1) as f64);
+        weighted += w * outcome.value();
+        total += w;
+    }
+    match total > 0.0 {
+        true => weighted / total,
+        false => policy.prior,
+    }
+}
+
+/// Whether `deed_type` deeds must name a provider.
+pub fn names_provider(deed_type: &str) -> bool {
+    schema_for(deed_type).is_some_and(|s| s.required.iter().any(|f| f.name == PROVIDER_ID))
+}
+
+fn provider_of(deed: &DeedEvent) -> Option<&str> {
+    deed.context_json[PROVIDER_ID].as_str()
+}
+
+/// Every registered provider with its current outcomes and suspension.
+pub fn providers(ledger: &TokenLedger) -> BTreeMap<String, Provider> {
+    let mut out: BTreeMap<String, Provider> = BTreeMap::new();
+    for d in ledger.deeds() {
+        let target = d.target_ids.first().map(String::as_str).unwrap_or_default();
+        match d.deed_type.as_str() {
+            PROVIDER_REGISTERED => {
+                out.insert(
+                    target.to_string(),
+                    Provider {
+                        provider_id: target.to_string(),
+                        name: d.context_json["name"].as_str().unwrap_or_default().to_string(),
+                        registered_at: d.context_json["registered_at"].as_i64().unwrap_or(d.timestamp),
+                        outcomes: Vec::new(),
+                        suspension: None,
+                    },
+                );
+            }
+            PROVIDER_OUTCOME => {
+                if let Some(p) = out.get_mut(target) {
+                    p.record(OutcomeRecord::from_deed(d));
+                }
+            }
+            PROVIDER_SUSPENDED => {
+                if let Some(p) = out.get_mut(target) {
+                    p.suspension = Some(d.event_id.clone());
+                }
+            }
+            PROVIDER_REINSTATED => {
+                if let Some(p) = out.get_mut(target) {
+                    p.suspension = None;
+                    p.outcomes.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Reliability of `provider_id` at `at`, if registered.
+pub fn provider_reliability(ledger: &TokenLedger, provider_id: &str, at: i64) -> Option<f64> {
+    providers(ledger).get(provider_id).map(|p| p.reliability_at(at, &ledger.config().providers))
+}
+
+/// Register `provider_id` under a display `name`. Only correction roles may.
+pub fn register_provider(
+    ledger: &mut TokenLedger,
+    provider_id: &str,
+    name: &str,
+    operator_role: &str,
+    now: i64,
+) -> Result<Provider, ProviderError> {
+    if !ledger.config().correction_roles.iter().any(|r| r == operator_role) {
+        return Err(ProviderError::RoleNotAllowed(operator_role.to_string()));
+    }
+    if providers(ledger).contains_key(provider_id) {
+        return Err(ProviderError::AlreadyRegistered(provider_id.to_string()));
+    }
+    let context = json!({ "provider_id": provider_id, "name": name, "operator_role": operator_role, "registered_at": now });
+    ledger.log_provider(PROVIDER_REGISTERED, provider_id, context)?;
+    Ok(providers(ledger).remove(provider_id).expect("just registered"))
+}
+
+/// Sent when a provider is suspended, and for every attestation a
+/// suspended provider makes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderAlert {
+    pub kind: ProviderAlertKind,
+    pub provider_id: String,
+    pub suspension_event_id: String,
+    pub reliability: f64,
+    /// The attestation whose outcome, or submission, raised the alert.
+    pub deed_event_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderAlertKind {
+    /// An outcome dropped the provider below the suspension floor.
+    Suspended,
+    /// A suspended provider's attestation was accepted at zero weight.
+    SuspendedAttestation,
+}
+
+/// Where provider alerts are pushed.
+pub trait ProviderNotifier: Send {
+    fn notify(&self, alert: &ProviderAlert) -> Result<(), String>;
+}
+
+/// POSTs each alert as JSON to the policy's webhook.
+pub struct WebhookProviderNotifier {
+    pub url: String,
+}
+
+impl ProviderNotifier for WebhookProviderNotifier {
+    fn notify(&self, alert: &ProviderAlert) -> Result<(), String> {
+        post_webhook(&self.url, PROVIDER_ALERT, alert)
+    }
+}
+
+fn send(notifier: Option<&dyn ProviderNotifier>, alert: &ProviderAlert) {
+    if let Some(notifier) = notifier {
+        if let Err(e) = notifier.notify(alert) {
+            warn!("provider alert for {} not delivered: {}", alert.provider_id, e);
+        }
+    }
+}
+
+/// What one recorded outcome did to its provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderUpdate {
+    pub provider_id: String,
+    pub outcome_event_id: String,
+    /// Reliability right after the outcome.
+    pub reliability: f64,
+    /// The `provider_suspended` deed this outcome triggered, if any.
+    pub suspended: Option<String>,
+}
+
+fn record_outcome(
+    ledger: &mut TokenLedger,
+    deed_event_id: &str,
+    outcome: AttestationOutcome,
+    source: &str,
+    operator_role: Option<&str>,
+    now: i64,
+    notifier: Option<&dyn ProviderNotifier>,
+) -> Result<ProviderUpdate, ProviderError> {
+    let deed = ledger.deed(deed_event_id).ok_or_else(|| ProviderError::UnknownDeed(deed_event_id.to_string()))?;
+    if !names_provider(&deed.deed_type) {
+        return Err(ProviderError::NotAnAttestation(deed_event_id.to_string()));
+    }
+    let provider_id = provider_of(deed).unwrap_or_default().to_string();
+    let mut provider =
+        providers(ledger).remove(&provider_id).ok_or_else(|| ProviderError::UnknownProvider(provider_id.clone()))?;
+    let policy = ledger.config().providers.clone();
+    provider.record(OutcomeRecord {
+        outcome_event_id: String::new(),
+        deed_event_id: deed_event_id.to_string(),
+        outcome,
+        source: source.to_string(),
+        at: now,
+    });
+    let reliability = provider.reliability_at(now, &policy);
+    let context = json!({
+        "provider_id": provider_id,
+        "deed_event_id": deed_event_id,
+        "outcome": outcome,
+        "source": source,
+        "operator_role": operator_role,
+        "at": now,
+        "reliability": reliability,
+    });
+    let outcome_event_id = ledger.log_provider(PROVIDER_OUTCOME, &provider_id, context)?.event_id.clone();
+
+    let mut suspended = None;
+    if reliability < policy.suspension_floor && !provider.suspended() {
+        let context = json!({
+            "provider_id": provider_id,
+            "reliability": reliability,
+            "floor": policy.suspension_floor,
+            "outcome_event_id": outcome_event_id,
+            "at": now,
+        });
+        let suspension_event_id = ledger.log_provider(PROVIDER_SUSPENDED, &provider_id, context)?.event_id.clone();
+        let alert = ProviderAlert {
+            kind: ProviderAlertKind::Suspended,
+            provider_id: provider_id.clone(),
+            suspension_event_id: suspension_event_id.clone(),
+            reliability,
+            deed_event_id: deed_event_id.to_string(),
+        };
+        send(notifier, &alert);
+        suspended = Some(suspension_event_id);
+    }
+    Ok(ProviderUpdate { provider_id, outcome_event_id, reliability, suspended })
+}
+
+/// Record an audit's finding on attestation `deed_event_id`. Only the
+/// policy's auditor roles may.
+pub fn record_audit(
+    ledger: &mut TokenLedger,
+    deed_event_id: &str,
+    outcome: AttestationOutcome,
+    operator_role: &str,
+    now: i64,
+    notifier: Option<&dyn ProviderNotifier>,
+) -> Result<ProviderUpdate, ProviderError> {
+    if !ledger.config().providers.auditor_roles.iter().any(|r| r == operator_role) {
+        return Err(ProviderError::NotAuditor(operator_role.to_string()));
+    }
+    record_outcome(ledger, deed_event_id, outcome, AUDIT_SOURCE, Some(operator_role), now, notifier)
+}
+
+/// Record the outcome of every attestation settled since the last sweep:
+/// quorum approvals as corroborated, quorum rejections and tombstones as
+/// contradicted. Each source is recorded once per attestation.
+pub fn sweep_outcomes(
+    ledger: &mut TokenLedger,
+    now: i64,
+    notifier: Option<&dyn ProviderNotifier>,
+) -> Result<Vec<ProviderUpdate>, ProviderError> {
+    let recorded: HashSet<(String, String)> = ledger
+        .deeds()
+        .iter()
+        .filter(|d| d.deed_type == PROVIDER_OUTCOME)
+        .map(OutcomeRecord::from_deed)
+        .map(|o| (o.deed_event_id, o.source))
+        .collect();
+    let resolved: BTreeMap<String, ValidationOutcome> = pending_validations(ledger)
+        .into_iter()
+        .filter_map(|p| match p.status {
+            ValidationStatus::Resolved { outcome, .. } => Some((p.deed_event_id, outcome)),
+            ValidationStatus::Pending => None,
+        })
+        .collect();
+    let registered = providers(ledger);
+    let due: Vec<(String, AttestationOutcome, &str)> = ledger
+        .deeds()
+        .iter()
+        .filter(|d| names_provider(&d.deed_type) && provider_of(d).is_some_and(|p| registered.contains_key(p)))
+        .filter_map(|d| {
+            let settled = match (ledger.is_tombstoned(&d.event_id), resolved.get(&d.event_id)) {
+                (true, _) => (AttestationOutcome::Contradicted, TOMBSTONE_SOURCE),
+                (false, Some(ValidationOutcome::Approved)) => (AttestationOutcome::Corroborated, QUORUM_SOURCE),
+                (false, Some(ValidationOutcome::Rejected)) => (AttestationOutcome::Contradicted, QUORUM_SOURCE),
+                _ => return None,
+            };
+            (!recorded.contains(&(d.event_id.clone(), settled.1.to_string()))).then(|| (d.event_id.clone(), settled.0, settled.1))
+        })
+        .collect();
+    let mut updates = Vec::with_capacity(due.len());
+    for (deed_event_id, outcome, source) in due {
+        updates.push(record_outcome(ledger, &deed_event_id, outcome, source, None, now, notifier)?);
+    }
+    Ok(updates)
+}
+
+/// Lift the suspension of `provider_id`. Its earlier outcomes stop
+/// counting, so it restarts at the prior. Only correction roles may.
+/// Returns the `provider_reinstated` event id.
+pub fn reinstate_provider(
+    ledger: &mut TokenLedger,
+    provider_id: &str,
+    operator_role: &str,
+    reason: &str,
+    now: i64,
+) -> Result<String, ProviderError> {
+    if !ledger.config().correction_roles.iter().any(|r| r == operator_role) {
+        return Err(ProviderError::RoleNotAllowed(operator_role.to_string()));
+    }
+    let provider =
+        providers(ledger).remove(provider_id).ok_or_else(|| ProviderError::UnknownProvider(provider_id.to_string()))?;
+    let Some(suspension) = provider.suspension else {
+        return Err(ProviderError::NotSuspended(provider_id.to_string()));
+    };
+    let context = json!({
+        "provider_id": provider_id,
+        "suspension_event_id": suspension,
+        "operator_role": operator_role,
+        "reason": reason,
+        "at": now,
+    });
+    Ok(ledger.log_provider(PROVIDER_REINSTATED, provider_id, context)?.event_id.clone())
+}
+
+/// Pin the named provider's reliability at `deed`'s timestamp into its
+/// context, or weight 0 and the suspension while it is suspended, and
+/// rehash it. Deeds that name no provider pass through untouched.
+pub(crate) fn pin_weight(ledger: &TokenLedger, mut deed: DeedEvent) -> Result<DeedEvent, TokenLedgerError> {
+    if !names_provider(&deed.deed_type) {
+        return Ok(deed);
+    }
+    let Some(provider) = provider_of(&deed).and_then(|id| providers(ledger).remove(id)) else {
+        return Err(TokenLedgerError::UnregisteredProvider(provider_of(&deed).unwrap_or_default().to_string()));
+    };
+    let weight = match &provider.suspension {
+        Some(_) => 0.0,
+        None => provider.reliability_at(deed.timestamp, &ledger.config().providers),
+    };
+    let ctx = deed.context_json.as_object_mut().expect("a deed naming a provider has an object context");
+    match provider.suspension {
+        Some(suspension) => ctx.insert(PROVIDER_SUSPENSION.to_string(), json!(suspension)),
+        None => ctx.remove(PROVIDER_SUSPENSION),
+    };
+    ctx.insert(PROVIDER_WEIGHT.to_string(), json!(weight));
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    Ok(deed)
+}
+
+/// Alert the operator when stored attestation `deed` was accepted under a
+/// suspension. Returns the alert, whether or not it was delivered.
+pub fn alert_if_suspended(
+    ledger: &TokenLedger,
+    deed: &DeedEvent,
+    notifier: Option<&dyn ProviderNotifier>,
+) -> Option<ProviderAlert> {
+    let suspension_event_id = deed.context_json[PROVIDER_SUSPENSION].as_str()?;
+    let provider_id = provider_of(deed)?;
+    let alert = ProviderAlert {
+        kind: ProviderAlertKind::SuspendedAttestation,
+        provider_id: provider_id.to_string(),
+        suspension_event_id: suspension_event_id.to_string(),
+        reliability: provider_reliability(ledger, provider_id, deed.timestamp).unwrap_or_default(),
+        deed_event_id: deed.event_id.clone(),
+    };
+    send(notifier, &alert);
+    Some(alert)
+}
+
+/// The pinned weights of `actor_id`'s attestations that still stand, by
+/// event id: not slashed, not cooldown-suppressed and not contradicted by
+/// their latest outcome. Attestations from before providers were required
+/// carry no pinned weight and count in full.
+pub fn attestation_weights(ledger: &TokenLedger, actor_id: &str) -> Vec<(String, f64)> {
+    let slashed = slashed_ids(ledger);
+    let mut latest: BTreeMap<String, AttestationOutcome> = BTreeMap::new();
+    for o in ledger.deeds().iter().filter(|d| d.deed_type == PROVIDER_OUTCOME).map(OutcomeRecord::from_deed) {
+        latest.insert(o.deed_event_id, o.outcome);
+    }
+    ledger
+        .deeds()
+        .iter()
+        .filter(|d| d.actor_id == actor_id && names_provider(&d.deed_type))
+        .filter(|d| !slashed.contains(&d.event_id) && !is_suppressed(d))
+        .filter(|d| latest.get(&d.event_id) != Some(&AttestationOutcome::Contradicted))
+        .map(|d| (d.event_id.clone(), d.context_json[PROVIDER_WEIGHT].as_f64().unwrap_or(1.0)))
+        .collect()
+}
+
+/// The clin_trust component of `actor_id`'s reputation: the sovereignty
+/// core's `calc_clin_trust` curve, with each attestation counted at its
+/// pinned weight instead of 1.
+pub fn clin_trust(ledger: &TokenLedger, actor_id: &str) -> f64 {
+    let attested: f64 = attestation_weights(ledger, actor_id).iter().map(|(_, w)| w).sum();
+    (CLIN_TRUST_BASE + (attested * CLIN_TRUST_PER_ATTESTATION).min(CLIN_TRUST_ATTESTATION_CAP)).clamp(0.0, 1.0)
+}
@@ -0,0 +1,545 @@
+//! Validation quorums for high-value deeds.
+//!
+//! A taxonomy category can declare `required_validations`: a count of
+//! independent approvals and the lowest validator reputation that counts.
+//! Such a deed is stored but not minted; `hold_if_required` logs a
+//! `validation_pending` deed that issues its reward into the obligations
+//! escrow. Registered validators then vote on it with signed
+//! `ValidationVote`s (`cast_vote`, with `validation-quorum`), each vote a
+//! ledger-written `validation_vote` deed that also credits the validator a
+//! small reward, capped per window. A `validation_resolved` deed closes
+//! the pending deed:
+//!
+//! - approved once the approvals reach the quorum: the escrow pays out to
+//!   the actor;
+//! - rejected once the rejections leave too few eligible validators to
+//!   reach it: the escrow is retired;
+//! - expired when nobody settled it before `timeout_secs` (`sweep_expired`):
+//!   the escrow is returned to the sponsor pool.
+//!
+//! Validators are registered by a correction role with a starting
+//! `mp_score`. Every approved or rejected deed moves each voter's score up
+//! by `alignment_gain` if they voted with the outcome and down by
+//! `contrarian_penalty` if against it, so a validator who keeps opposing
+//! final outcomes drops below `reputation_floor` and loses standing. All of
+//! it is read back from the deeds, so a replayed ledger has the same
+//! validators, scores and pending deeds.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+#[cfg(feature = "validation-quorum")]
+use keyring::KeyringSignature;
+
+#[cfg(feature = "validation-quorum")]
+use crate::identity::{authenticate, check_fresh, IdentityError};
+#[cfg(feature = "validation-quorum")]
+use crate::ledger::account::Token;
+use crate::ledger::builders::{schema_for, ValidationSpec};
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::sponsor::pool::SPONSOR_POOL;
+
+pub const VALIDATOR_REGISTERED: &str = "validator_registered";
+pub const VALIDATION_PENDING: &str = "validation_pending";
+pub const VALIDATION_VOTE: &str = "validation_vote";
+pub const VALIDATION_RESOLVED: &str = "validation_resolved";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuorumPolicy {
+    /// `mp_score` below which a validator has no standing.
+    pub reputation_floor: f64,
+    /// Score gained for a vote that matches the final outcome.
+    pub alignment_gain: f64,
+    /// Score lost for a vote against the final outcome.
+    pub contrarian_penalty: f64,
+    /// Seconds a deed may wait for its quorum before it expires.
+    pub timeout_secs: i64,
+    /// CHURCH credited per vote cast.
+    pub vote_credit: u64,
+    /// Most vote credit one validator earns per `credit_window_secs`.
+    pub vote_credit_cap: u64,
+    pub credit_window_secs: i64,
+    /// How often the scheduler expires stalled validations.
+    pub sweep_every_secs: u64,
+}
+
+impl Default for QuorumPolicy {
+    fn default() -> Self {
+        Self {
+            reputation_floor: 0.5,
+            alignment_gain: 0.02,
+            contrarian_penalty: 0.1,
+            timeout_secs: 7 * 86_400,
+            vote_credit: 2,
+            vote_credit_cap: 10,
+            credit_window_secs: 86_400,
+            sweep_every_secs: 86_400,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum QuorumError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[cfg(feature = "validation-quorum")]
+    #[error(transparent)]
+    Identity(#[from] IdentityError),
+    #[error("role {0} may not register validators")]
+    RoleNotAllowed(String),
+    #[error("mp_score {0} outside [0, 1]")]
+    InvalidScore(f64),
+    #[error("{0} is already a validator")]
+    AlreadyRegistered(String),
+    #[error("unknown deed {0}")]
+    UnknownDeed(String),
+    #[error("deed {0} is already awaiting validation")]
+    AlreadyPending(String),
+    #[error("no validation pending on deed {0}")]
+    UnknownPending(String),
+    #[error("validation of deed {0} is already resolved")]
+    Resolved(String),
+    #[error("validation of deed {deed_event_id} expired at {deadline}")]
+    Expired { deed_event_id: String, deadline: i64 },
+    #[error("{0} is not a registered validator")]
+    NotValidator(String),
+    #[error("validator {validator_id} has no standing (mp_score {mp_score})")]
+    NoStanding { validator_id: String, mp_score: f64 },
+    #[error("validator {validator_id} has mp_score {mp_score}, below the {min} this category needs")]
+    BelowReputation { validator_id: String, mp_score: f64, min: f64 },
+    #[error("{0} may not validate their own deed")]
+    OwnDeed(String),
+    #[error("{0} has already voted on this deed")]
+    AlreadyVoted(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validator {
+    pub validator_id: String,
+    pub mp_score: f64,
+    pub registered_at: i64,
+    /// Votes that matched the final outcome.
+    pub aligned: u32,
+    /// Votes against the final outcome.
+    pub opposed: u32,
+    /// `mp_score` is at or above the policy's `reputation_floor`.
+    pub standing: bool,
+}
+
+impl Validator {
+    /// Whose votes count toward a quorum needing `min_reputation`.
+    pub fn eligible(&self, min_reputation: f64) -> bool {
+        self.standing && self.mp_score >= min_reputation
+    }
+}
+
+/// What a validator signs: the pending validation, their verdict and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationVote {
+    /// Event id of the deed under validation.
+    pub deed_event_id: String,
+    pub validator_id: String,
+    pub approve: bool,
+    pub reason: String,
+}
+
+impl ValidationVote {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("validation vote serializes")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteRecord {
+    pub vote_event_id: String,
+    pub validator_id: String,
+    pub approve: bool,
+    pub reason: String,
+    pub at: i64,
+    /// Vote credit before the pool tithe.
+    pub credit: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationOutcome {
+    /// Quorum reached: the escrow paid out to the actor.
+    Approved,
+    /// Quorum unreachable: the escrow retired.
+    Rejected,
+    /// Timed out: the escrow returned to the sponsor pool.
+    Expired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum ValidationStatus {
+    Pending,
+    Resolved { outcome: ValidationOutcome, resolution_event_id: String, resolved_at: i64 },
+}
+
+/// A deed held for its validation quorum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingValidation {
+    /// Event id of the `validation_pending` deed.
+    pub pending_id: String,
+    pub deed_event_id: String,
+    pub actor_id: String,
+    pub deed_type: String,
+    pub required: usize,
+    pub min_reputation: f64,
+    pub escrowed: u64,
+    pub opened_at: i64,
+    pub deadline: i64,
+    pub votes: Vec<VoteRecord>,
+    #[serde(flatten)]
+    pub status: ValidationStatus,
+}
+
+impl PendingValidation {
+    fn from_deed(d: &DeedEvent) -> Self {
+        let ctx = &d.context_json;
+        let text = |key: &str| ctx[key].as_str().unwrap_or_default().to_string();
+        Self {
+            pending_id: d.event_id.clone(),
+            deed_event_id: d.target_ids.first().cloned().unwrap_or_default(),
+            actor_id: text("actor_id"),
+            deed_type: text("deed_type"),
+            required: ctx["required"].as_u64().unwrap_or_default() as usize,
+            min_reputation: ctx["min_reputation"].as_f64().unwrap_or_default(),
+            escrowed: ctx["amount"].as_u64().unwrap_or_default(),
+            opened_at: ctx["opened_at"].as_i64().unwrap_or(d.timestamp),
+            deadline: ctx["deadline"].as_i64().unwrap_or_default(),
+            votes: Vec::new(),
+            status: ValidationStatus::Pending,
+        }
+    }
+
+    pub fn approvals(&self) -> usize {
+        self.votes.iter().filter(|v| v.approve).count()
+    }
+
+    pub fn rejections(&self) -> usize {
+        self.votes.len() - self.approvals()
+    }
+
+    /// Approved at quorum; rejected once the eligible validators who have
+    /// not voted could no longer bring the approvals up to it.
+    pub fn tally(&self, validators: &BTreeMap<String, Validator>) -> Option<ValidationOutcome> {
+        let approvals = self.approvals();
+        if approvals >= self.required {
+            return Some(ValidationOutcome::Approved);
+        }
+        let outstanding = validators
+            .values()
+            .filter(|v| v.eligible(self.min_reputation) && v.validator_id != self.actor_id)
+            .filter(|v| !self.votes.iter().any(|vote| vote.validator_id == v.validator_id))
+            .count();
+        (approvals + outstanding < self.required).then_some(ValidationOutcome::Rejected)
+    }
+}
+
+fn vote_of(d: &DeedEvent) -> VoteRecord {
+    let ctx = &d.context_json;
+    VoteRecord {
+        vote_event_id: d.event_id.clone(),
+        validator_id: ctx["validator_id"].as_str().unwrap_or_default().to_string(),
+        approve: ctx["approve"].as_bool().unwrap_or(false),
+        reason: ctx["reason"].as_str().unwrap_or_default().to_string(),
+        at: ctx["at"].as_i64().unwrap_or(d.timestamp),
+        credit: ctx["credit"].as_u64().unwrap_or_default(),
+    }
+}
+
+fn outcome_of(d: &DeedEvent) -> ValidationOutcome {
+    serde_json::from_value(d.context_json["outcome"].clone()).unwrap_or(ValidationOutcome::Rejected)
+}
+
+/// The quorum `deed_type` deeds need, if any.
+pub fn required_validations(deed_type: &str) -> Option<ValidationSpec> {
+    schema_for(deed_type).and_then(|s| s.required_validations)
+}
+
+/// Every validation, in the order it was opened.
+pub fn pending_validations(ledger: &TokenLedger) -> Vec<PendingValidation> {
+    let mut out: Vec<PendingValidation> = Vec::new();
+    for d in ledger.deeds() {
+        let target = d.target_ids.first().map(String::as_str);
+        let pending = |out: &[PendingValidation]| out.iter().rposition(|p| Some(p.pending_id.as_str()) == target);
+        match d.deed_type.as_str() {
+            VALIDATION_PENDING => out.push(PendingValidation::from_deed(d)),
+            VALIDATION_VOTE => {
+                if let Some(i) = pending(&out) {
+                    out[i].votes.push(vote_of(d));
+                }
+            }
+            VALIDATION_RESOLVED => {
+                if let Some(i) = pending(&out) {
+                    out[i].status = ValidationStatus::Resolved {
+                        outcome: outcome_of(d),
+                        resolution_event_id: d.event_id.clone(),
+                        resolved_at: d.context_json["resolved_at"].as_i64().unwrap_or(d.timestamp),
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The validation of deed `deed_event_id`, if it needed one.
+pub fn validation_status(ledger: &TokenLedger, deed_event_id: &str) -> Option<PendingValidation> {
+    pending_validations(ledger).into_iter().find(|p| p.deed_event_id == deed_event_id)
+}
+
+/// Every registered validator with their current score and standing.
+pub fn validators(ledger: &TokenLedger) -> BTreeMap<String, Validator> {
+    let policy = &ledger.config().quorum;
+    let mut out: BTreeMap<String, Validator> = BTreeMap::new();
+    let mut votes: BTreeMap<&str, Vec<(String, bool)>> = BTreeMap::new();
+    for d in ledger.deeds() {
+        let target = d.target_ids.first().map(String::as_str).unwrap_or_default();
+        match d.deed_type.as_str() {
+            VALIDATOR_REGISTERED => {
+                let mp_score = d.context_json["mp_score"].as_f64().unwrap_or_default();
+                out.insert(
+                    target.to_string(),
+                    Validator {
+                        validator_id: target.to_string(),
+                        mp_score,
+                        registered_at: d.context_json["registered_at"].as_i64().unwrap_or(d.timestamp),
+                        aligned: 0,
+                        opposed: 0,
+                        standing: mp_score >= policy.reputation_floor,
+                    },
+                );
+            }
+            VALIDATION_VOTE => {
+                let vote = vote_of(d);
+                votes.entry(target).or_default().push((vote.validator_id, vote.approve));
+            }
+            VALIDATION_RESOLVED => {
+                let approved = match outcome_of(d) {
+                    ValidationOutcome::Approved => true,
+                    ValidationOutcome::Rejected => false,
+                    ValidationOutcome::Expired => continue,
+                };
+                for (validator_id, approve) in votes.remove(target).unwrap_or_default() {
+                    let Some(v) = out.get_mut(&validator_id) else { continue };
+                    if approve == approved {
+                        v.aligned += 1;
+                        v.mp_score = (v.mp_score + policy.alignment_gain).min(1.0);
+                    } else {
+                        v.opposed += 1;
+                        v.mp_score = (v.mp_score - policy.contrarian_penalty).max(0.0);
+                    }
+                    v.standing = v.mp_score >= policy.reputation_floor;
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Register `validator_id` with a starting `mp_score` in [0, 1]. Only
+/// correction roles may.
+pub fn register_validator(
+    ledger: &mut TokenLedger,
+    validator_id: &str,
+    mp_score: f64,
+    operator_role: &str,
+    now: i64,
+) -> Result<Validator, QuorumError> {
+    if !ledger.config().correction_roles.iter().any(|r| r == operator_role) {
+        return Err(QuorumError::RoleNotAllowed(operator_role.to_string()));
+    }
+    if !(0.0..=1.0).contains(&mp_score) {
+        return Err(QuorumError::InvalidScore(mp_score));
+    }
+    if validators(ledger).contains_key(validator_id) {
+        return Err(QuorumError::AlreadyRegistered(validator_id.to_string()));
+    }
+    let context =
+        json!({ "validator_id": validator_id, "mp_score": mp_score, "operator_role": operator_role, "registered_at": now });
+    ledger.log_validator_registered(validator_id, context)?;
+    Ok(validators(ledger).remove(validator_id).expect("just registered"))
+}
+
+/// Hold the `amount` CHURCH reward of stored deed `deed_event_id` for its
+/// category's quorum. Returns the `validation_pending` event id, or `None`
+/// when the category mints directly.
+pub fn hold_if_required(
+    ledger: &mut TokenLedger,
+    deed_event_id: &str,
+    amount: u64,
+    now: i64,
+) -> Result<Option<String>, QuorumError> {
+    let deed = ledger.deed(deed_event_id).ok_or_else(|| QuorumError::UnknownDeed(deed_event_id.to_string()))?;
+    let Some(spec) = required_validations(&deed.deed_type) else {
+        return Ok(None);
+    };
+    if validation_status(ledger, deed_event_id).is_some() {
+        return Err(QuorumError::AlreadyPending(deed_event_id.to_string()));
+    }
+    let context = json!({
+        "actor_id": deed.actor_id,
+        "deed_type": deed.deed_type,
+        "required": spec.count,
+        "min_reputation": spec.min_reputation,
+        "opened_at": now,
+        "deadline": now + ledger.config().quorum.timeout_secs,
+    });
+    Ok(Some(ledger.log_validation_pending(deed_event_id, amount, context)?))
+}
+
+fn resolve(
+    ledger: &mut TokenLedger,
+    pending: &PendingValidation,
+    outcome: ValidationOutcome,
+    now: i64,
+) -> Result<u64, TokenLedgerError> {
+    let payee = match outcome {
+        ValidationOutcome::Approved => Some(pending.actor_id.as_str()),
+        ValidationOutcome::Rejected => None,
+        ValidationOutcome::Expired => Some(SPONSOR_POOL),
+    };
+    if payee == Some(pending.actor_id.as_str()) {
+        ledger.open_account(&pending.actor_id, &pending.actor_id);
+    }
+    let context = json!({
+        "deed_event_id": pending.deed_event_id,
+        "outcome": outcome,
+        "approvals": pending.approvals(),
+        "rejections": pending.rejections(),
+        "resolved_at": now,
+    });
+    ledger.log_validation_resolved(&pending.pending_id, payee, pending.escrowed, context)
+}
+
+/// Vote credit `validator_id` may still earn in the window ending at `now`.
+pub fn vote_credit_available(ledger: &TokenLedger, validator_id: &str, now: i64) -> u64 {
+    let policy = &ledger.config().quorum;
+    let earned: u64 = pending_validations(ledger)
+        .iter()
+        .flat_map(|p| &p.votes)
+        .filter(|v| v.validator_id == validator_id && v.at > now - policy.credit_window_secs && v.at <= now)
+        .map(|v| v.credit)
+        .sum();
+    policy.vote_credit_cap.saturating_sub(earned)
+}
+
+/// Result of `cast_vote`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteReceipt {
+    pub vote_event_id: String,
+    /// Vote credit paid to the validator, after the pool tithe.
+    pub credited: u64,
+    /// Set when this vote settled the validation.
+    pub outcome: Option<ValidationOutcome>,
+    /// Escrow paid to the actor when this vote approved the deed.
+    pub minted: u64,
+}
+
+/// Record `vote`, signed by the validator's actor key, and settle the
+/// validation if it reaches or can no longer reach its quorum.
+#[cfg(feature = "validation-quorum")]
+pub fn cast_vote(
+    ledger: &mut TokenLedger,
+    vote: &ValidationVote,
+    signature: &KeyringSignature,
+    now: i64,
+) -> Result<VoteReceipt, QuorumError> {
+    let mut pending =
+        validation_status(ledger, &vote.deed_event_id).ok_or_else(|| QuorumError::UnknownPending(vote.deed_event_id.clone()))?;
+    if pending.status != ValidationStatus::Pending {
+        return Err(QuorumError::Resolved(vote.deed_event_id.clone()));
+    }
+    if now >= pending.deadline {
+        return Err(QuorumError::Expired { deed_event_id: vote.deed_event_id.clone(), deadline: pending.deadline });
+    }
+    let validators = validators(ledger);
+    let validator = validators.get(&vote.validator_id).ok_or_else(|| QuorumError::NotValidator(vote.validator_id.clone()))?;
+    if !validator.standing {
+        return Err(QuorumError::NoStanding { validator_id: vote.validator_id.clone(), mp_score: validator.mp_score });
+    }
+    if validator.mp_score < pending.min_reputation {
+        return Err(QuorumError::BelowReputation {
+            validator_id: vote.validator_id.clone(),
+            mp_score: validator.mp_score,
+            min: pending.min_reputation,
+        });
+    }
+    if vote.validator_id == pending.actor_id {
+        return Err(QuorumError::OwnDeed(vote.validator_id.clone()));
+    }
+    if pending.votes.iter().any(|v| v.validator_id == vote.validator_id) {
+        return Err(QuorumError::AlreadyVoted(vote.validator_id.clone()));
+    }
+    check_fresh(signature, now, &ledger.config().identity)?;
+    authenticate(ledger, &vote.validator_id, &vote.signing_bytes(), signature)?;
+
+    // No credit while the validator's account, or minting as a whole, is frozen.
+    let credit = if ledger.mint_freeze().is_some() || ledger.account_recovery(&vote.validator_id).is_some() {
+        0
+    } else {
+        ledger.config().quorum.vote_credit.min(vote_credit_available(ledger, &vote.validator_id, now))
+    };
+    let context = json!({
+        "deed_event_id": vote.deed_event_id,
+        "validator_id": vote.validator_id,
+        "approve": vote.approve,
+        "reason": vote.reason,
+        "at": now,
+        "credit": credit,
+        "signature": signature,
+    });
+    let vote_event_id = ledger.log_validation_vote(&pending.pending_id, context)?;
+    let credited = if credit > 0 {
+        ledger.open_account(&vote.validator_id, &vote.validator_id);
+        ledger.reward_for(&vote.validator_id, Token::Church, credit, Some(&vote_event_id))?
+    } else {
+        0
+    };
+
+    pending.votes.push(VoteRecord {
+        vote_event_id: vote_event_id.clone(),
+        validator_id: vote.validator_id.clone(),
+        approve: vote.approve,
+        reason: vote.reason.clone(),
+        at: now,
+        credit,
+    });
+    let outcome = pending.tally(&validators);
+    let minted = match outcome {
+        Some(outcome) => {
+            let paid = resolve(ledger, &pending, outcome, now)?;
+            if outcome == ValidationOutcome::Approved {
+                paid
+            } else {
+                0
+            }
+        }
+        None => 0,
+    };
+    Ok(VoteReceipt { vote_event_id, credited, outcome, minted })
+}
+
+/// Expire every validation still pending at its deadline, returning its
+/// escrow to the sponsor pool. Returns the expired deeds' event ids.
+pub fn sweep_expired(ledger: &mut TokenLedger, now: i64) -> Result<Vec<String>, QuorumError> {
+    let due: Vec<PendingValidation> =
+        pending_validations(ledger).into_iter().filter(|p| p.status == ValidationStatus::Pending && now >= p.deadline).collect();
+    let mut expired = Vec::with_capacity(due.len());
+    for pending in due {
+        resolve(ledger, &pending, ValidationOutcome::Expired, now)?;
+        expired.push(pending.deed_event_id);
+    }
+    Ok(expired)
+}
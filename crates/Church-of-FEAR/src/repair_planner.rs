@@ -0,0 +1,352 @@
+//! Repair plans for ForceRepair.
+//!
+//! When the regulator forces repair, the planner turns the failing
+//! `EthicsSummary`, the `GodLikeReport` explanation and recent deeds into a
+//! ranked `RepairPlan` of concrete catalog actions, each with an estimated
+//! effect on the violated constraints and the role allowed to execute it.
+//! Plans are logged as `repair_plan` deeds. A plan stays current until the
+//! violation profile changes materially, at which point a new plan is
+//! logged and the old one is marked superseded. Executing an action logs a
+//! `repair_action` deed linking back to the plan.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::compliance::ethics::EthicsSummary;
+use crate::compliance::god_like::{Constraint, GodLikeReport};
+use crate::ledger::account::Token;
+use crate::ledger::builders::schema_for;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+
+const PLANNER_ACTOR: &str = "repair_planner";
+pub const REPAIR_PLAN: &str = "repair_plan";
+pub const REPAIR_ACTION: &str = "repair_action";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairActionKind {
+    /// Burn POWER from accounts holding more than k·CHURCH.
+    BurnExcessPower,
+    /// Hold sponsor rewards for deeds outside the restorative categories.
+    DeferNonRestorativeRewards,
+    TightenFearBand,
+    ScheduleEcoScan,
+}
+
+/// One entry in the action catalog. `effects` gives, per constraint, the
+/// share of that constraint's excess the action is expected to remove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogAction {
+    pub id: String,
+    pub kind: RepairActionKind,
+    pub description: String,
+    pub required_role: String,
+    pub effects: Vec<(Constraint, f64)>,
+}
+
+pub fn default_catalog() -> Vec<CatalogAction> {
+    let action = |id: &str, kind, description: &str, role: &str, effects: Vec<(Constraint, f64)>| CatalogAction {
+        id: id.to_string(),
+        kind,
+        description: description.to_string(),
+        required_role: role.to_string(),
+        effects,
+    };
+    vec![
+        action(
+            "burn_excess_power",
+            RepairActionKind::BurnExcessPower,
+            "Burn POWER from accounts exceeding k·CHURCH",
+            "Regulator",
+            vec![(Constraint::PowerCap, 1.0), (Constraint::PowerConcentration, 0.3)],
+        ),
+        action(
+            "defer_non_restorative_rewards",
+            RepairActionKind::DeferNonRestorativeRewards,
+            "Defer sponsor rewards for non-restorative deeds",
+            "Host",
+            vec![(Constraint::BioloadCeiling, 0.4), (Constraint::PowerConcentration, 0.2)],
+        ),
+        action(
+            "tighten_fear_band",
+            RepairActionKind::TightenFearBand,
+            "Tighten the FEAR band",
+            "Regulator",
+            vec![(Constraint::BioloadCeiling, 0.2), (Constraint::TrustFloor, 0.1)],
+        ),
+        action(
+            "schedule_eco_scan",
+            RepairActionKind::ScheduleEcoScan,
+            "Schedule an EcoScan job",
+            "Host",
+            vec![(Constraint::BioloadCeiling, 0.05)],
+        ),
+    ]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepairConfig {
+    pub catalog: Vec<CatalogAction>,
+    /// How many recent deeds inform the estimates.
+    pub recent_deeds: usize,
+    /// Relative change in any violation's excess that triggers a new plan.
+    pub material_change: f64,
+}
+
+impl Default for RepairConfig {
+    fn default() -> Self {
+        Self { catalog: default_catalog(), recent_deeds: 50, material_change: 0.1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EstimatedEffect {
+    pub constraint: Constraint,
+    /// Expected change in the constraint's excess; negative is better.
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedAction {
+    pub action_id: String,
+    pub kind: RepairActionKind,
+    pub description: String,
+    pub required_role: String,
+    pub effects: Vec<EstimatedEffect>,
+    /// Share of the total excess this action is expected to remove.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepairPlan {
+    pub plan_id: String,
+    pub summary: EthicsSummary,
+    pub report: GodLikeReport,
+    /// Best first.
+    pub actions: Vec<PlannedAction>,
+    pub supersedes: Option<String>,
+    pub superseded_by: Option<String>,
+    /// `self_hash` of the `repair_plan` deed, once logged.
+    pub deed_hash: Option<String>,
+}
+
+impl RepairPlan {
+    pub fn action(&self, action_id: &str) -> Option<&PlannedAction> {
+        self.actions.iter().find(|a| a.action_id == action_id)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RepairPlanError {
+    #[error("no constraint is violated")]
+    NothingToRepair,
+    #[error("unknown repair plan {0}")]
+    UnknownPlan(String),
+    #[error("repair plan {plan} was superseded by {successor}")]
+    Superseded { plan: String, successor: String },
+    #[error("action {action} is not part of plan {plan}")]
+    UnknownAction { plan: String, action: String },
+    #[error("action {action} requires role {required}, not {got}")]
+    RoleNotAllowed { action: String, required: String, got: String },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Share of recent participant deeds outside the restorative taxonomy.
+fn non_restorative_share(recent: &[&DeedEvent]) -> f64 {
+    let participant: Vec<&&DeedEvent> = recent.iter().filter(|d| d.actor_id != "ledger" && d.actor_id != PLANNER_ACTOR).collect();
+    if participant.is_empty() {
+        return 0.0;
+    }
+    let outside = participant.iter().filter(|d| schema_for(&d.deed_type).is_none()).count();
+    outside as f64 / participant.len() as f64
+}
+
+pub struct RepairPlanner {
+    cfg: RepairConfig,
+    plans: Vec<RepairPlan>,
+}
+
+impl RepairPlanner {
+    pub fn new(cfg: RepairConfig) -> Self {
+        Self { cfg, plans: Vec::new() }
+    }
+
+    pub fn plans(&self) -> &[RepairPlan] {
+        &self.plans
+    }
+
+    pub fn current(&self) -> Option<&RepairPlan> {
+        self.plans.last().filter(|p| p.superseded_by.is_none())
+    }
+
+    pub fn plan_by_id(&self, plan_id: &str) -> Option<&RepairPlan> {
+        self.plans.iter().find(|p| p.plan_id == plan_id)
+    }
+
+    /// Build a plan without logging or storing it.
+    pub fn draft(&self, summary: &EthicsSummary, report: &GodLikeReport, recent: &[&DeedEvent]) -> Result<RepairPlan, RepairPlanError> {
+        if report.is_clean() {
+            return Err(RepairPlanError::NothingToRepair);
+        }
+        let total_excess: f64 = report.violations.iter().map(|v| v.excess).sum();
+        let defer_share = non_restorative_share(recent);
+
+        let mut actions: Vec<PlannedAction> = self
+            .cfg
+            .catalog
+            .iter()
+            .filter_map(|entry| {
+                let scale = match entry.kind {
+                    RepairActionKind::DeferNonRestorativeRewards => defer_share,
+                    _ => 1.0,
+                };
+                let effects: Vec<EstimatedEffect> = entry
+                    .effects
+                    .iter()
+                    .filter_map(|(constraint, share)| {
+                        let v = report.violation(*constraint)?;
+                        let delta = -(v.excess * share * scale).min(v.excess);
+                        (delta < 0.0).then_some(EstimatedEffect { constraint: *constraint, delta })
+                    })
+                    .collect();
+                if effects.is_empty() {
+                    return None;
+                }
+                let removed: f64 = effects.iter().map(|e| -e.delta).sum();
+                Some(PlannedAction {
+                    action_id: entry.id.clone(),
+                    kind: entry.kind,
+                    description: entry.description.clone(),
+                    required_role: entry.required_role.clone(),
+                    effects,
+                    score: removed / total_excess,
+                })
+            })
+            .collect();
+        actions.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(RepairPlan {
+            plan_id: uuid::Uuid::new_v4().to_string(),
+            summary: summary.clone(),
+            report: report.clone(),
+            actions,
+            supersedes: None,
+            superseded_by: None,
+            deed_hash: None,
+        })
+    }
+
+    /// Whether `new` differs enough from `old` to warrant a new plan: a
+    /// different set of violated constraints, or any excess moving by more
+    /// than `material_change` of its previous value.
+    pub fn materially_changed(&self, old: &GodLikeReport, new: &GodLikeReport) -> bool {
+        let constraints = |r: &GodLikeReport| r.violations.iter().map(|v| v.constraint).collect::<Vec<_>>();
+        if constraints(old) != constraints(new) {
+            return true;
+        }
+        old.violations.iter().zip(&new.violations).any(|(o, n)| (n.excess - o.excess).abs() > self.cfg.material_change * o.excess)
+    }
+
+    /// Return the current plan, or generate, log and store a new one if
+    /// none exists or the violation profile changed materially.
+    pub fn plan(&mut self, ledger: &mut TokenLedger, summary: &EthicsSummary, report: &GodLikeReport) -> Result<&RepairPlan, RepairPlanError> {
+        let reuse = self.current().is_some_and(|p| !self.materially_changed(&p.report, report));
+        if reuse {
+            return Ok(self.plans.last().expect("current plan"));
+        }
+
+        let recent: Vec<&DeedEvent> = {
+            let live: Vec<&DeedEvent> = ledger.live_deeds().collect();
+            let skip = live.len().saturating_sub(self.cfg.recent_deeds);
+            live[skip..].to_vec()
+        };
+        let mut plan = self.draft(summary, report, &recent)?;
+        plan.supersedes = self.current().map(|p| p.plan_id.clone());
+
+        let context = serde_json::json!({
+            "plan_id": plan.plan_id,
+            "supersedes": plan.supersedes,
+            "violations": plan.report.violations,
+            "actions": plan.actions,
+        });
+        let deed = DeedEvent::new(
+            ledger.last_hash(),
+            PLANNER_ACTOR.to_string(),
+            Vec::new(),
+            REPAIR_PLAN.to_string(),
+            vec!["force_repair".to_string()],
+            context,
+            Vec::new(),
+            false,
+        );
+        plan.deed_hash = Some(ledger.append(deed)?.self_hash.clone());
+
+        if let Some(prev) = self.plans.last_mut().filter(|p| p.superseded_by.is_none()) {
+            prev.superseded_by = Some(plan.plan_id.clone());
+        }
+        self.plans.push(plan);
+        Ok(self.plans.last().expect("just pushed"))
+    }
+
+    /// Execute one action of a current plan as `role`, logging a
+    /// `repair_action` deed that links back to the plan.
+    pub fn execute<'l>(
+        &self,
+        ledger: &'l mut TokenLedger,
+        plan_id: &str,
+        action_id: &str,
+        role: &str,
+    ) -> Result<&'l DeedEvent, RepairPlanError> {
+        let plan = self.plan_by_id(plan_id).ok_or_else(|| RepairPlanError::UnknownPlan(plan_id.to_string()))?;
+        if let Some(successor) = &plan.superseded_by {
+            return Err(RepairPlanError::Superseded { plan: plan_id.to_string(), successor: successor.clone() });
+        }
+        let action = plan
+            .action(action_id)
+            .ok_or_else(|| RepairPlanError::UnknownAction { plan: plan_id.to_string(), action: action_id.to_string() })?;
+        if action.required_role != role {
+            return Err(RepairPlanError::RoleNotAllowed {
+                action: action_id.to_string(),
+                required: action.required_role.clone(),
+                got: role.to_string(),
+            });
+        }
+
+        let outcome = match action.kind {
+            RepairActionKind::BurnExcessPower => {
+                let mut burned = Vec::new();
+                for over in &plan.report.over_cap {
+                    let amount = ledger.burn(&over.account_id, Token::Pwr, over.excess)?;
+                    burned.push(serde_json::json!({ "account_id": over.account_id, "amount": amount }));
+                }
+                serde_json::json!({ "burned": burned })
+            }
+            // The remaining actions change node policy rather than balances;
+            // the deed is the record that they were ordered.
+            _ => serde_json::json!({ "ordered": true }),
+        };
+
+        let context = serde_json::json!({
+            "plan_id": plan.plan_id,
+            "plan_deed_hash": plan.deed_hash,
+            "action_id": action.action_id,
+            "kind": action.kind,
+            "role": role,
+            "outcome": outcome,
+        });
+        let deed = DeedEvent::new(
+            ledger.last_hash(),
+            PLANNER_ACTOR.to_string(),
+            Vec::new(),
+            REPAIR_ACTION.to_string(),
+            vec!["force_repair".to_string()],
+            context,
+            Vec::new(),
+            false,
+        );
+        Ok(ledger.append(deed)?)
+    }
+}
@@ -0,0 +1,461 @@
+//! Watch-only replica of a primary node's ledger.
+//!
+//! A replica polls the primary's `auto_church.get_deeds` from its own tip
+//! hash, at most `batch_size` deeds per call, so a replica coming back
+//! from downtime catches up in bounded batches. Every deed must link to
+//! the replica's tip and hash to its `self_hash` before it is appended
+//! with `TokenLedger::replicate`; the replica never mints.
+//!
+//! Every `cross_check_interval_secs` the replica fetches the primary's
+//! signed tip announcement (`auto_church.tip_announcement`), checks the
+//! signature against the primary's verifying bundle, and compares the
+//! announced tip and every sealed segment's Merkle root with its own
+//! chain.
+//!
+//! The first failed check stops the replica for good: it raises a
+//! `DivergenceAlert` through the notifier, records the fault, and
+//! `/healthz` answers 503 until an operator rebuilds it. The local RPC
+//! server answers every read-only method and rejects the mutating ones
+//! with `REPLICA_READ_ONLY`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use keyring::{SignatureVerifier, VerifyingBundle};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::ledger::deed_event::{link_fault, DeedEvent};
+use crate::ledger::schema::check_deed_fields;
+use crate::ledger::token_ledger::{SealedSegment, TokenLedger};
+use crate::rpc::server::{dispatch_request_with, dispatch_with, handle_rpc, serve_lines, RpcContext};
+use crate::rpc::types::{AutoChurchGetDeedsResult, JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+use crate::tip_gossip::{hash_at, segment_root, AlertNotifier, DivergenceAlert, SignedTipAnnouncement};
+use crate::utils::correlation::CorrelationId;
+
+/// JSON-RPC error code for a mutating method called on a replica.
+pub const REPLICA_READ_ONLY: i64 = 1008;
+
+/// Methods that write to the ledger or node state.
+pub const MUTATING_METHODS: &[&str] = &[
+    "auto_church.mint_deed",
+    "auto_church.report_near_miss",
+    "auto_church.cast_validation_vote",
+    "auto_church.review_anomaly_hold",
+];
+
+#[derive(Error, Debug)]
+pub enum ReplicaError {
+    #[error("transport: {0}")]
+    Transport(String),
+    #[error("primary answered {code}: {message}")]
+    Primary { code: i64, message: String },
+    #[error("malformed primary response: {0}")]
+    Malformed(String),
+    #[error("diverged from the primary: {0}")]
+    Diverged(String),
+    #[error("replica stopped after a verification failure")]
+    Stopped,
+}
+
+/// Calls a JSON-RPC method on the primary and returns its `result`.
+pub trait PrimaryTransport {
+    fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError>;
+}
+
+fn decode(line: &str) -> Result<Value, ReplicaError> {
+    let resp: JsonRpcResponse = serde_json::from_str(line).map_err(|e| ReplicaError::Malformed(e.to_string()))?;
+    match (resp.result, resp.error) {
+        (_, Some(e)) => Err(ReplicaError::Primary { code: e.code, message: e.message }),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err(ReplicaError::Malformed("response without result".to_string())),
+    }
+}
+
+fn request_line(method: &str, params: Value) -> String {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }).to_string()
+}
+
+/// The primary's line-delimited JSON-RPC endpoint, one connection per call.
+pub struct TcpPrimary {
+    pub addr: String,
+}
+
+impl PrimaryTransport for TcpPrimary {
+    fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError> {
+        let transport = |e: std::io::Error| ReplicaError::Transport(e.to_string());
+        let mut stream = TcpStream::connect(&self.addr).map_err(transport)?;
+        writeln!(stream, "{}", request_line(method, params)).map_err(transport)?;
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).map_err(transport)?;
+        decode(&line)
+    }
+}
+
+/// A primary in the same process, answering through its dispatcher.
+impl PrimaryTransport for RpcContext {
+    fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError> {
+        decode(&dispatch_request_with(&request_line(method, params), self))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaConfig {
+    /// Namespace the primary announces its tip under.
+    pub namespace: String,
+    /// `node_id` in the primary's tip announcements; also the peer named
+    /// in divergence alerts.
+    pub primary_node_id: String,
+    /// Deeds fetched per `auto_church.get_deeds` call, capped by the
+    /// primary at `MAX_DEED_BATCH`.
+    pub batch_size: usize,
+    pub cross_check_interval_secs: i64,
+}
+
+impl Default for ReplicaConfig {
+    fn default() -> Self {
+        Self {
+            namespace: "default".to_string(),
+            primary_node_id: "primary".to_string(),
+            batch_size: 128,
+            cross_check_interval_secs: 300,
+        }
+    }
+}
+
+/// Why the replica stopped following.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicaFault {
+    pub reason: String,
+    /// Replica height when the failure was detected.
+    pub height: u64,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicaHealth {
+    pub following: bool,
+    pub height: u64,
+    pub tip_hash: String,
+    /// Primary height as of the last sync.
+    pub primary_height: u64,
+    pub last_sync_at: Option<i64>,
+    pub last_cross_check_at: Option<i64>,
+    pub fault: Option<ReplicaFault>,
+    /// Deed schema version the primary advertised with its last batch.
+    pub primary_schema_version: Option<u32>,
+    /// The `high_impact_frozen` deed of the primary's active freeze, as
+    /// replicated (see `halt_review`).
+    pub high_impact_freeze: Option<String>,
+}
+
+impl Default for ReplicaHealth {
+    fn default() -> Self {
+        Self {
+            following: true,
+            height: 0,
+            tip_hash: "0".repeat(64),
+            primary_height: 0,
+            last_sync_at: None,
+            last_cross_check_at: None,
+            fault: None,
+            primary_schema_version: None,
+            high_impact_freeze: None,
+        }
+    }
+}
+
+/// Sealed segment of the primary's chain with its Merkle root.
+#[derive(Debug, Deserialize)]
+struct AnnouncedSegment {
+    segment: SealedSegment,
+    merkle_root: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TipCrossCheck {
+    announcement: SignedTipAnnouncement,
+    sealed_segments: Vec<AnnouncedSegment>,
+}
+
+pub struct Replica<T: PrimaryTransport> {
+    cfg: ReplicaConfig,
+    primary_keys: VerifyingBundle,
+    ledger: Arc<Mutex<TokenLedger>>,
+    transport: T,
+    notifier: Option<Box<dyn AlertNotifier>>,
+    health: Arc<Mutex<ReplicaHealth>>,
+}
+
+impl<T: PrimaryTransport> Replica<T> {
+    /// Follow the primary behind `transport` into `ledger`, which may
+    /// already hold a prefix of the primary's chain from an earlier run.
+    /// `primary_keys` verifies the primary's tip announcements.
+    pub fn new(cfg: ReplicaConfig, primary_keys: VerifyingBundle, ledger: Arc<Mutex<TokenLedger>>, transport: T) -> Self {
+        let health = {
+            let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+            ReplicaHealth {
+                height: ledger.deeds().len() as u64,
+                tip_hash: ledger.last_hash(),
+                high_impact_freeze: ledger.high_impact_freeze().map(str::to_string),
+                ..ReplicaHealth::default()
+            }
+        };
+        Self { cfg, primary_keys, ledger, transport, notifier: None, health: Arc::new(Mutex::new(health)) }
+    }
+
+    pub fn with_notifier(mut self, notifier: impl AlertNotifier + 'static) -> Self {
+        self.notifier = Some(Box::new(notifier));
+        self
+    }
+
+    pub fn health(&self) -> ReplicaHealth {
+        self.health.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Shared health, for `serve_healthz`.
+    pub fn health_handle(&self) -> Arc<Mutex<ReplicaHealth>> {
+        self.health.clone()
+    }
+
+    /// The replicated ledger, for `start_replica_rpc_server`.
+    pub fn rpc_context(&self) -> RpcContext {
+        RpcContext::with_ledger(self.ledger.clone())
+    }
+
+    fn ensure_following(&self) -> Result<(), ReplicaError> {
+        match self.health().following {
+            true => Ok(()),
+            false => Err(ReplicaError::Stopped),
+        }
+    }
+
+    /// Fetch and append everything the primary has beyond our tip, one
+    /// bounded batch at a time. Returns the number of deeds appended.
+    pub fn sync(&mut self, now: i64) -> Result<usize, ReplicaError> {
+        self.ensure_following()?;
+        let mut appended = 0;
+        loop {
+            let after_hash = self.ledger.lock().unwrap_or_else(|e| e.into_inner()).last_hash();
+            let params = json!({ "after_hash": after_hash, "limit": self.cfg.batch_size });
+            let batch: AutoChurchGetDeedsResult = match self.transport.call("auto_church.get_deeds", params) {
+                Ok(result) => batch_from_value(result)?,
+                Err(ReplicaError::Primary { code: 1007, .. }) => {
+                    return Err(self.diverge(format!("primary does not hold our tip {}", after_hash), None, now));
+                }
+                Err(e) => return Err(e),
+            };
+            for deed in batch.deeds {
+                let deed = DeedEvent::try_from(deed).map_err(|e| ReplicaError::Malformed(e.to_string()))?;
+                self.append_verified(deed, batch.height, now)?;
+                appended += 1;
+            }
+            let ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+            let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+            health.height = ledger.deeds().len() as u64;
+            health.tip_hash = ledger.last_hash();
+            health.primary_height = batch.height;
+            health.last_sync_at = Some(now);
+            health.primary_schema_version = Some(batch.schema_version);
+            health.high_impact_freeze = ledger.high_impact_freeze().map(str::to_string);
+            if !batch.more {
+                return Ok(appended);
+            }
+        }
+    }
+
+    fn append_verified(&mut self, deed: DeedEvent, primary_height: u64, now: i64) -> Result<(), ReplicaError> {
+        let mut ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(fault) = link_fault(&deed, &ledger.last_hash()) {
+            drop(ledger);
+            let reason = format!("deed {} failed verification: {:?}", deed.event_id, fault);
+            return Err(self.diverge(reason, Some((primary_height, deed.self_hash)), now));
+        }
+        if let Err(e) = ledger.replicate(deed.clone()) {
+            drop(ledger);
+            let reason = format!("deed {} rejected by the local ledger: {}", deed.event_id, e);
+            return Err(self.diverge(reason, Some((primary_height, deed.self_hash)), now));
+        }
+        Ok(())
+    }
+
+    /// Compare the primary's signed tip and sealed-segment roots with our chain.
+    pub fn cross_check(&mut self, now: i64) -> Result<(), ReplicaError> {
+        self.ensure_following()?;
+        let result = self.transport.call("auto_church.tip_announcement", json!({}))?;
+        let check: TipCrossCheck = serde_json::from_value(result).map_err(|e| ReplicaError::Malformed(e.to_string()))?;
+        let a = &check.announcement.announcement;
+        let claimed = Some((a.height, a.tip_hash.clone()));
+
+        if a.node_id != self.cfg.primary_node_id || a.namespace != self.cfg.namespace {
+            let reason = format!("tip announced by {} for {}", a.node_id, a.namespace);
+            return Err(self.diverge(reason, claimed, now));
+        }
+        if let Err(e) = self.primary_keys.verify(&a.signing_bytes(), &check.announcement.signature) {
+            return Err(self.diverge(format!("tip announcement signature: {}", e), claimed, now));
+        }
+        if let Some(reason) = self.mismatch(&check) {
+            return Err(self.diverge(reason, claimed, now));
+        }
+        self.health.lock().unwrap_or_else(|e| e.into_inner()).last_cross_check_at = Some(now);
+        Ok(())
+    }
+
+    /// The first disagreement between `check` and the part of the
+    /// primary's chain we already hold.
+    fn mismatch(&self, check: &TipCrossCheck) -> Option<String> {
+        let ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+        let height = ledger.deeds().len();
+        let a = &check.announcement.announcement;
+        if a.sealed_root.as_ref() != check.sealed_segments.last().map(|s| &s.merkle_root) {
+            return Some("announced sealed root does not match the latest segment".to_string());
+        }
+        if hash_at(&ledger, a.height).is_some_and(|ours| ours != a.tip_hash) {
+            return Some(format!("primary tip at height {} is {}", a.height, a.tip_hash));
+        }
+        for announced in check.sealed_segments.iter().filter(|s| s.segment.last < height) {
+            let segment = &announced.segment;
+            if segment.first > segment.last || ledger.deeds()[segment.last].self_hash != segment.tip_hash {
+                return Some(format!("sealed segment {} ends at a different deed", segment.index));
+            }
+            if segment_root(&ledger, segment) != announced.merkle_root {
+                return Some(format!("sealed segment {} has a different Merkle root", segment.index));
+            }
+        }
+        None
+    }
+
+    /// Sync, and cross-check once `cross_check_interval_secs` has elapsed.
+    pub fn tick(&mut self, now: i64) -> Result<(), ReplicaError> {
+        self.sync(now)?;
+        let last = self.health().last_cross_check_at;
+        if last.is_none_or(|t| now - t >= self.cfg.cross_check_interval_secs) {
+            self.cross_check(now)?;
+        }
+        Ok(())
+    }
+
+    /// Stop following: record the fault and push a divergence alert.
+    /// `claimed` is the primary's (height, hash) that failed, if known.
+    fn diverge(&mut self, reason: String, claimed: Option<(u64, String)>, now: i64) -> ReplicaError {
+        let (our_height, our_tip) = {
+            let ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+            (ledger.deeds().len() as u64, ledger.last_hash())
+        };
+        warn!("replica stopped following {}: {}", self.cfg.primary_node_id, reason);
+        {
+            let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+            health.following = false;
+            health.height = our_height;
+            health.tip_hash = our_tip.clone();
+            health.fault = Some(ReplicaFault { reason: reason.clone(), height: our_height, detected_at: now });
+        }
+        let (peer_height, peer_tip) = claimed.unwrap_or_else(|| (our_height, String::new()));
+        let alert = DivergenceAlert {
+            namespace: self.cfg.namespace.clone(),
+            peer: self.cfg.primary_node_id.clone(),
+            peer_height,
+            peer_tip,
+            our_tip_at_height: our_tip,
+            our_height,
+            detected_at: now,
+        };
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify(&alert) {
+                warn!("divergence webhook failed: {}", e);
+            }
+        }
+        ReplicaError::Diverged(reason)
+    }
+}
+
+/// A `get_deeds` result, each deed checked against our deed schema so an
+/// unknown critical field is reported by path rather than dropped.
+fn batch_from_value(result: Value) -> Result<AutoChurchGetDeedsResult, ReplicaError> {
+    for deed in result["deeds"].as_array().into_iter().flatten() {
+        check_deed_fields(deed).map_err(|e| ReplicaError::Malformed(e.to_string()))?;
+    }
+    serde_json::from_value(result).map_err(|e| ReplicaError::Malformed(e.to_string()))
+}
+
+/// `dispatch_request_with` for a replica: mutating methods, batched or
+/// not, are rejected with `REPLICA_READ_ONLY` before they reach the ledger.
+pub fn dispatch_replica_request(raw: &str, ctx: &RpcContext) -> String {
+    dispatch_with(raw, ctx, handle_replica_rpc)
+}
+
+fn handle_replica_rpc(req: JsonRpcRequest, ctx: &RpcContext) -> JsonRpcResponse {
+    if !MUTATING_METHODS.contains(&req.method.as_str()) {
+        return handle_rpc(req, ctx);
+    }
+    info!("replica rejected {} ({})", req.method, CorrelationId::current().map(|c| c.to_string()).unwrap_or_default());
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(JsonRpcError {
+            code: REPLICA_READ_ONLY,
+            message: "REPLICA_READ_ONLY".to_string(),
+            data: Some(json!({ "method": req.method })),
+        }),
+        id: req.id,
+        correlation_id: None,
+    }
+}
+
+/// Serve the replica's ledger read-only on `addr`.
+pub fn start_replica_rpc_server(addr: &str, ctx: RpcContext) -> std::io::Result<()> {
+    serve_lines(addr, ctx, dispatch_replica_request)
+}
+
+/// HTTP status and JSON body for `/healthz`: 503 once the replica stopped.
+pub fn healthz(health: &ReplicaHealth) -> (u16, String) {
+    let status = if health.fault.is_some() { 503 } else { 200 };
+    (status, serde_json::to_string(health).expect("health serializes"))
+}
+
+/// Answer `GET /healthz` on `addr` from `health`; other paths get 404.
+pub fn serve_healthz(addr: &str, health: Arc<Mutex<ReplicaHealth>>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("healthz connection failed: {}", e);
+                continue;
+            }
+        };
+        let mut request = String::new();
+        if let Err(e) = BufReader::new(&stream).read_line(&mut request) {
+            warn!("healthz read failed: {}", e);
+            continue;
+        }
+        let (status, body) = match request.split_whitespace().nth(1) {
+            Some("/healthz") => healthz(&health.lock().unwrap_or_else(|e| e.into_inner())),
+            _ => (404, String::new()),
+        };
+        let reason = match status {
+            200 => "OK",
+            503 => "Service Unavailable",
+            _ => "Not Found",
+        };
+        let written = write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            reason,
+            body.len(),
+            body
+        );
+        if let Err(e) = written {
+            warn!("healthz write failed: {}", e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,807 @@
+//! Monthly stewardship report for congregations and sponsors.
+//!
+//! `generate_monthly_report` gathers what the node did in a period from the
+//! ledger's own reporting APIs (taxonomy deeds, token movements, pool and
+//! repair grants, regulator FEAR, near-miss clusters, sealed segments) and
+//! renders it to Markdown through a `ReportTemplate`; `Report::to_html`
+//! turns the same text into one self-contained HTML file. Eco budget
+//! utilization and equity outcomes come from the eco-fairness guard, which
+//! runs beside the node rather than in it, so callers pass its headroom and
+//! fairness figures in as `GuardFigures`.
+//!
+//! A period is a range of the live chain: from the first deed stamped at
+//! or after `start` to the first stamped at or after `end`. Chain order is
+//! authoritative, as for `state_at`, so the period's token flows are
+//! exactly the supply difference between the views at its two ends. The
+//! report embeds the hash of the period's last deed and, for every figure,
+//! the query that reproduces it. Nothing reads the clock, so one ledger and
+//! period always render to the same bytes.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::anomaly::{ANOMALY_SUSPECT, MINT_SCREENED};
+use crate::audit::INTEGRITY_VIOLATION;
+use crate::history::{HistoricalPoint, HistoryError};
+use crate::ledger::account::Token;
+use crate::ledger::builders::schema_for;
+use crate::ledger::token_ledger::{
+    movements_of, segment_root, CorrectionWindow, SupplyReport, TokenLedger, FEAR_ACCRUAL, LEDGER_ACTOR,
+};
+use crate::near_miss::{clusters, Severity};
+use crate::sponsor::pool::{POOL_OUTFLOW, SPONSOR_POOL};
+use crate::token::repair_curve::REPAIR_GRANT;
+use crate::utils::time::from_unix;
+
+/// Report files are `<prefix><period label>.md` (and `.html`).
+pub const REPORT_FILE_PREFIX: &str = "stewardship-";
+
+/// Every placeholder a template may use.
+pub const PLACEHOLDERS: &[&str] = &[
+    "period",
+    "period_start",
+    "period_end",
+    "tip_hash",
+    "chain_range",
+    "deeds_total",
+    "deeds_by_category",
+    "church_minted",
+    "church_burned",
+    "token_flows",
+    "grants",
+    "regulator",
+    "eco_utilization",
+    "equity_outcomes",
+    "incidents",
+    "near_misses",
+    "merkle_roots",
+    "sources",
+];
+
+/// The template used when an operator supplies none.
+pub const DEFAULT_TEMPLATE: &str = "# Stewardship report {{period}}
+
+Covering {{period_start}} to {{period_end}} (UTC, end exclusive), chain positions {{chain_range}}, ending at tip `{{tip_hash}}`.
+
+## Good deeds
+
+{{deeds_total}} deeds were recorded.
+
+{{deeds_by_category}}
+
+## Tokens
+
+{{church_minted}} CHURCH were minted and {{church_burned}} CHURCH burned.
+
+{{token_flows}}
+
+## Grants disbursed
+
+{{grants}}
+
+## Regulator decisions
+
+{{regulator}}
+
+## Eco budget utilization
+
+{{eco_utilization}}
+
+## Equity outcomes
+
+{{equity_outcomes}}
+
+## Incidents
+
+{{incidents}}
+
+## Near misses
+
+{{near_misses}}
+
+## Anchored Merkle roots
+
+{{merkle_roots}}
+
+## Sources
+
+Every figure above can be reproduced with the query listed for it.
+
+{{sources}}
+";
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+table{border-collapse:collapse;margin:1rem 0}th,td{border:1px solid #999;padding:.25rem .5rem;text-align:left}\
+code{font-family:monospace;word-break:break-all}";
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("unknown template placeholder {{{{{0}}}}}")]
+    UnknownPlaceholder(String),
+    #[error("template placeholder opened at byte {0} is never closed")]
+    UnclosedPlaceholder(usize),
+    #[error(transparent)]
+    History(#[from] HistoryError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Half-open time window `[start, end)` in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportPeriod {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl ReportPeriod {
+    /// Calendar month `month` (1-12) of `year`, in UTC.
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let start = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let end = Utc.with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0).single()?;
+        Some(Self { start: start.timestamp(), end: end.timestamp() })
+    }
+
+    /// The month named `YYYY-MM`.
+    pub fn parse_month(label: &str) -> Option<Self> {
+        let (year, month) = label.split_once('-')?;
+        Self::month(year.parse().ok()?, month.parse().ok()?)
+    }
+
+    /// The last full calendar month before `now`.
+    pub fn previous_month(now: i64) -> Self {
+        let today = from_unix(now);
+        let (year, month) = match today.month() {
+            1 => (today.year() - 1, 12),
+            m => (today.year(), m - 1),
+        };
+        Self::month(year, month).expect("a calendar month")
+    }
+
+    /// `YYYY-MM` for a calendar month, `YYYY-MM-DD..YYYY-MM-DD` otherwise.
+    pub fn label(&self) -> String {
+        let start = from_unix(self.start);
+        if Self::month(start.year(), start.month()) == Some(*self) {
+            start.format("%Y-%m").to_string()
+        } else {
+            format!("{}..{}", start.format("%Y-%m-%d"), from_unix(self.end).format("%Y-%m-%d"))
+        }
+    }
+}
+
+/// Where and how often the node writes its monthly report. Without a
+/// `dir` no report job is scheduled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportPolicy {
+    pub dir: Option<String>,
+    /// Write the HTML file beside the Markdown.
+    pub html: bool,
+    /// How often to check whether last month's report is written yet.
+    pub check_every_secs: u64,
+}
+
+impl Default for ReportPolicy {
+    fn default() -> Self {
+        Self { dir: None, html: true, check_every_secs: 86_400 }
+    }
+}
+
+/// A template with `{{placeholder}}` fields, each one of `PLACEHOLDERS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTemplate {
+    text: String,
+}
+
+impl ReportTemplate {
+    /// Check every placeholder in `text`; an unknown one is an error.
+    pub fn parse(text: &str) -> Result<Self, ReportError> {
+        for (_, _, name) in fields(text)? {
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(ReportError::UnknownPlaceholder(name.to_string()));
+            }
+        }
+        Ok(Self { text: text.to_string() })
+    }
+
+    /// Placeholders the template uses, in order of first use.
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut seen = Vec::new();
+        for (_, _, name) in fields(&self.text).expect("checked by parse") {
+            if !seen.contains(&name) {
+                seen.push(name);
+            }
+        }
+        seen
+    }
+
+    fn render(&self, values: &BTreeMap<&str, String>) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut rest = 0;
+        for (open, close, name) in fields(&self.text).expect("checked by parse") {
+            out.push_str(&self.text[rest..open]);
+            out.push_str(&values[name]);
+            rest = close;
+        }
+        out.push_str(&self.text[rest..]);
+        out
+    }
+}
+
+impl Default for ReportTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_TEMPLATE).expect("the default template uses known placeholders")
+    }
+}
+
+/// `(open, close, name)` of every `{{name}}`, `close` past the braces.
+fn fields(text: &str) -> Result<Vec<(usize, usize, &str)>, ReportError> {
+    let mut out = Vec::new();
+    let mut rest = 0;
+    while let Some(found) = text[rest..].find("{{") {
+        let open = rest + found;
+        let close = text[open..].find("}}").ok_or(ReportError::UnclosedPlaceholder(open))? + open;
+        out.push((open, close + 2, text[open + 2..close].trim()));
+        rest = close + 2;
+    }
+    Ok(out)
+}
+
+/// One route's consumption, from the guard's `HeadroomReport::by_route`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EcoUtilization {
+    pub route: String,
+    pub admissions: u64,
+    pub power_consumed: f64,
+    pub energy_consumed: f64,
+    pub compute_consumed: f64,
+    pub roh_utilization_max: f32,
+}
+
+/// One equity class, from the guard's fairness audit `ClassOutcome`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquityOutcome {
+    pub class: String,
+    pub min_share: f32,
+    pub max_share: f32,
+    pub share_mean: f64,
+    pub attempts: u64,
+    pub admitted: u64,
+    pub starvation_events: u64,
+}
+
+/// Figures the eco-fairness guard reported for the same period, with the
+/// guard queries that produced them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardFigures {
+    pub eco_utilization: Vec<EcoUtilization>,
+    pub eco_source: String,
+    pub equity_outcomes: Vec<EquityOutcome>,
+    pub equity_source: String,
+}
+
+/// Taxonomy deeds of one category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryLine {
+    pub deed_type: String,
+    pub deeds: usize,
+    pub actors: usize,
+    /// Actor with the most deeds in the category; ties go to the lowest id.
+    pub most_active: String,
+    pub most_active_deeds: usize,
+}
+
+/// Supply movements of one token within the period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenFlow {
+    pub minted: u64,
+    pub burned: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrantTotals {
+    pub pool_payouts: usize,
+    /// CHURCH paid out of the sponsor pool.
+    pub pool_church: u64,
+    pub repair_grants: usize,
+    pub repair_pwr: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegulatorSummary {
+    pub warns: usize,
+    pub force_repairs: usize,
+    /// FEAR accrued for any reason, regulator transitions included.
+    pub fear_accrued: u64,
+    pub mints_screened: usize,
+    pub suspect_mints: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncidentTotals {
+    pub life_harm: usize,
+    pub integrity_violations: usize,
+    /// Tombstones written in the period.
+    pub corrections: usize,
+}
+
+/// A near-miss cluster first reported in the period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearMissLine {
+    pub category: String,
+    pub severity: Severity,
+    pub description: String,
+    pub reports: usize,
+    pub corroborated: bool,
+}
+
+/// A segment sealed within the period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnchoredRoot {
+    pub segment: usize,
+    pub first: usize,
+    pub last: usize,
+    pub tip_hash: String,
+    pub merkle_root: String,
+}
+
+/// A generated report: its figures and their rendering.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub period: ReportPeriod,
+    /// Chain positions `[first, end)` the period covers.
+    pub first: usize,
+    pub end: usize,
+    /// Hash of the period's last deed, or of the last deed before it when
+    /// the period is empty; None before genesis.
+    pub tip_hash: Option<String>,
+    pub categories: Vec<CategoryLine>,
+    pub token_flows: BTreeMap<Token, TokenFlow>,
+    /// Supply as of `tip_hash`.
+    pub supply_at_end: Option<SupplyReport>,
+    pub grants: GrantTotals,
+    pub regulator: RegulatorSummary,
+    pub incidents: IncidentTotals,
+    pub near_misses: Vec<NearMissLine>,
+    pub anchored_roots: Vec<AnchoredRoot>,
+    pub guard: GuardFigures,
+    /// Figure → the query that reproduces it.
+    pub sources: BTreeMap<String, String>,
+    pub markdown: String,
+}
+
+/// `generate_monthly_report_with` without guard figures.
+pub fn generate_monthly_report(
+    ledger: &TokenLedger,
+    period: ReportPeriod,
+    template: &ReportTemplate,
+) -> Result<Report, ReportError> {
+    generate_monthly_report_with(ledger, period, &GuardFigures::default(), template)
+}
+
+/// Assemble the report for `period` and render it through `template`.
+pub fn generate_monthly_report_with(
+    ledger: &TokenLedger,
+    period: ReportPeriod,
+    guard: &GuardFigures,
+    template: &ReportTemplate,
+) -> Result<Report, ReportError> {
+    let deeds = ledger.deeds();
+    let first = deeds.iter().position(|d| d.timestamp >= period.start).unwrap_or(deeds.len());
+    let end = deeds.iter().position(|d| d.timestamp >= period.end).unwrap_or(deeds.len()).max(first);
+    let in_period = &deeds[first..end];
+    let tip_hash = end.checked_sub(1).map(|last| deeds[last].self_hash.clone());
+    let range = format!("[{}, {})", first, end);
+
+    let mut by_type: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for d in in_period.iter().filter(|d| !ledger.is_tombstoned(&d.event_id) && schema_for(&d.deed_type).is_some()) {
+        *by_type.entry(d.deed_type.as_str()).or_default().entry(d.actor_id.as_str()).or_default() += 1;
+    }
+    let categories = by_type
+        .into_iter()
+        .map(|(deed_type, actors)| {
+            let (most_active, most_active_deeds) =
+                actors.iter().fold(("", 0), |best, (&actor, &n)| if n > best.1 { (actor, n) } else { best });
+            CategoryLine {
+                deed_type: deed_type.to_string(),
+                deeds: actors.values().sum(),
+                actors: actors.len(),
+                most_active: most_active.to_string(),
+                most_active_deeds,
+            }
+        })
+        .collect();
+
+    let mut token_flows: BTreeMap<Token, TokenFlow> = Token::ALL.iter().map(|&t| (t, TokenFlow::default())).collect();
+    let mut grants = GrantTotals::default();
+    let mut regulator = RegulatorSummary::default();
+    let mut incidents = IncidentTotals::default();
+    for d in in_period {
+        let movements = movements_of(d);
+        for m in &movements {
+            let flow = token_flows.get_mut(&m.token).expect("every token");
+            if m.delta >= 0 {
+                flow.minted += m.delta.unsigned_abs();
+            } else {
+                flow.burned += m.delta.unsigned_abs();
+            }
+        }
+        match d.deed_type.as_str() {
+            POOL_OUTFLOW => {
+                grants.pool_payouts += 1;
+                grants.pool_church +=
+                    movements.iter().filter(|m| m.account_id == SPONSOR_POOL && m.delta < 0).map(|m| m.delta.unsigned_abs()).sum::<u64>();
+            }
+            REPAIR_GRANT if !ledger.is_tombstoned(&d.event_id) => {
+                grants.repair_grants += 1;
+                grants.repair_pwr += d.context_json["amount"].as_u64().unwrap_or(0);
+            }
+            FEAR_ACCRUAL => {
+                let reason = d.context_json["reason"].as_str().unwrap_or_default();
+                match reason.split_once(": ").map(|(trigger, _)| trigger) {
+                    Some("Warn") => regulator.warns += 1,
+                    Some("ForceRepair") => regulator.force_repairs += 1,
+                    _ => {}
+                }
+                regulator.fear_accrued += movements.iter().filter(|m| m.token == Token::Fear).map(|m| m.delta.unsigned_abs()).sum::<u64>();
+            }
+            MINT_SCREENED => {
+                regulator.mints_screened += 1;
+                regulator.suspect_mints += usize::from(d.ethics_flags.iter().any(|f| f == ANOMALY_SUSPECT));
+            }
+            INTEGRITY_VIOLATION => incidents.integrity_violations += 1,
+            _ => {}
+        }
+        if d.life_harm_flag && d.actor_id != LEDGER_ACTOR && !ledger.is_tombstoned(&d.event_id) {
+            incidents.life_harm += 1;
+        }
+    }
+    incidents.corrections = ledger.corrections_report(CorrectionWindow { start: period.start, end: period.end }).len();
+
+    let near_misses = clusters(ledger)
+        .into_iter()
+        .filter(|c| c.first_reported >= period.start && c.first_reported < period.end)
+        .map(|c| NearMissLine {
+            category: c.category,
+            severity: c.severity,
+            description: c.description,
+            reports: c.report_ids.len(),
+            corroborated: c.corroboration.is_some(),
+        })
+        .collect();
+
+    let anchored_roots = ledger
+        .sealed_segments()
+        .iter()
+        .filter(|s| (first..end).contains(&s.last))
+        .map(|s| AnchoredRoot {
+            segment: s.index,
+            first: s.first,
+            last: s.last,
+            tip_hash: s.tip_hash.clone(),
+            merkle_root: segment_root(ledger, s),
+        })
+        .collect();
+
+    let supply_at_end = match &tip_hash {
+        Some(hash) => Some(ledger.state_at(&HistoricalPoint::Tip(hash.clone()))?.view.supply().clone()),
+        None => None,
+    };
+
+    let tip = tip_hash.as_deref().unwrap_or("none");
+    let sources: BTreeMap<String, String> = [
+        ("deeds_by_category", format!("live deeds at chain positions {range} whose deed_type is a taxonomy category, by deed_type and actor_id")),
+        ("token_flows", format!("sum of context_json.movements[].delta by token over chain positions {range}; equals the change in auto_church.get_state_at supply issued/retired across the period")),
+        ("supply_at_end", format!("auto_church.get_state_at {{\"tip_hash\": \"{tip}\"}}")),
+        ("grants.pool", format!("{POOL_OUTFLOW} deeds at chain positions {range}, {SPONSOR_POOL} debits")),
+        ("grants.repair", format!("live {REPAIR_GRANT} deeds at chain positions {range}, context_json.amount")),
+        ("regulator", format!("{FEAR_ACCRUAL} deeds at chain positions {range} by reason prefix, and {MINT_SCREENED} deeds flagged {ANOMALY_SUSPECT}")),
+        ("incidents", format!("live life_harm_flag deeds and {INTEGRITY_VIOLATION} deeds at chain positions {range}; corrections_report over [{}, {})", period.start, period.end)),
+        ("near_misses", format!("auto_church.report_near_miss clusters first reported in [{}, {})", period.start, period.end)),
+        ("merkle_roots", format!("auto_church.tip_announcement sealed_segments whose last position is in {range}")),
+        ("eco_utilization", or_unsupplied(&guard.eco_source)),
+        ("equity_outcomes", or_unsupplied(&guard.equity_source)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+
+    let mut report = Report {
+        period,
+        first,
+        end,
+        tip_hash,
+        categories,
+        token_flows,
+        supply_at_end,
+        grants,
+        regulator,
+        incidents,
+        near_misses,
+        anchored_roots,
+        guard: guard.clone(),
+        sources,
+        markdown: String::new(),
+    };
+    report.markdown = template.render(&report.values());
+    Ok(report)
+}
+
+fn or_unsupplied(source: &str) -> String {
+    if source.is_empty() {
+        "not supplied by the eco-fairness guard".to_string()
+    } else {
+        source.to_string()
+    }
+}
+
+/// Write last month's report as of `now` into `dir`, unless it is already
+/// there. Returns the Markdown file written.
+pub fn write_monthly_report(ledger: &TokenLedger, dir: &Path, html: bool, now: i64) -> Result<Option<PathBuf>, ReportError> {
+    let period = ReportPeriod::previous_month(now);
+    let path = dir.join(format!("{}{}.md", REPORT_FILE_PREFIX, period.label()));
+    if path.exists() {
+        return Ok(None);
+    }
+    let report = generate_monthly_report(ledger, period, &ReportTemplate::default())?;
+    fs::create_dir_all(dir)?;
+    if html {
+        fs::write(path.with_extension("html"), report.to_html())?;
+    }
+    fs::write(&path, &report.markdown)?;
+    Ok(Some(path))
+}
+
+impl Report {
+    /// The Markdown as one HTML file with its stylesheet inline and no
+    /// external references. Headings, tables, lists, paragraphs and code
+    /// spans are converted; anything else is kept as escaped text.
+    pub fn to_html(&self) -> String {
+        let mut title = format!("Stewardship report {}", self.period.label());
+        let mut body = String::new();
+        let mut lines = self.markdown.lines().peekable();
+        while let Some(line) = lines.next() {
+            let hashes = line.chars().take_while(|&c| c == '#').count();
+            if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+                let text = line[hashes..].trim();
+                if hashes == 1 {
+                    title = text.to_string();
+                }
+                writeln!(body, "<h{hashes}>{}</h{hashes}>", inline(text)).unwrap();
+            } else if line.starts_with('|') {
+                body.push_str("<table>\n");
+                let mut header = true;
+                let mut row = Some(line);
+                while let Some(line) = row {
+                    let row_cells = cells(line);
+                    if !row_cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':'))) {
+                        let tag = if header { "th" } else { "td" };
+                        body.push_str("<tr>");
+                        for cell in &row_cells {
+                            write!(body, "<{tag}>{}</{tag}>", inline(cell)).unwrap();
+                        }
+                        body.push_str("</tr>\n");
+                        header = false;
+                    }
+                    row = lines.next_if(|l| l.starts_with('|'));
+                }
+                body.push_str("</table>\n");
+            } else if let Some(item) = line.strip_prefix("- ") {
+                body.push_str("<ul>\n");
+                writeln!(body, "<li>{}</li>", inline(item)).unwrap();
+                while let Some(item) = lines.next_if(|l| l.starts_with("- ")) {
+                    writeln!(body, "<li>{}</li>", inline(&item[2..])).unwrap();
+                }
+                body.push_str("</ul>\n");
+            } else if !line.trim().is_empty() {
+                writeln!(body, "<p>{}</p>", inline(line.trim())).unwrap();
+            }
+        }
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape(&title),
+            STYLE,
+            body
+        )
+    }
+
+    fn values(&self) -> BTreeMap<&'static str, String> {
+        let church = self.token_flows.get(&Token::Church).copied().unwrap_or_default();
+        let date = |secs: i64| from_unix(secs).format("%Y-%m-%d").to_string();
+        let mut values = BTreeMap::new();
+        values.insert("period", self.period.label());
+        values.insert("period_start", date(self.period.start));
+        values.insert("period_end", date(self.period.end));
+        values.insert("tip_hash", self.tip_hash.clone().unwrap_or_else(|| "none".to_string()));
+        values.insert("chain_range", format!("[{}, {})", self.first, self.end));
+        values.insert("deeds_total", self.categories.iter().map(|c| c.deeds).sum::<usize>().to_string());
+        values.insert("deeds_by_category", self.category_table());
+        values.insert("church_minted", church.minted.to_string());
+        values.insert("church_burned", church.burned.to_string());
+        values.insert("token_flows", self.token_table());
+        values.insert("grants", self.grant_table());
+        values.insert("regulator", self.regulator_list());
+        values.insert("eco_utilization", self.eco_table());
+        values.insert("equity_outcomes", self.equity_table());
+        values.insert("incidents", self.incident_list());
+        values.insert("near_misses", self.near_miss_table());
+        values.insert("merkle_roots", self.root_table());
+        values.insert(
+            "sources",
+            self.sources.iter().map(|(figure, query)| format!("- `{}`: {}", figure, query)).collect::<Vec<_>>().join("\n"),
+        );
+        values
+    }
+
+    fn category_table(&self) -> String {
+        if self.categories.is_empty() {
+            return "_No deeds in this period._".to_string();
+        }
+        let mut out = "| Category | Deeds | Actors | Most active |\n|---|---|---|---|".to_string();
+        for c in &self.categories {
+            write!(out, "\n| {} | {} | {} | {} ({}) |", cell(&c.deed_type), c.deeds, c.actors, cell(&c.most_active), c.most_active_deeds)
+                .unwrap();
+        }
+        out
+    }
+
+    fn token_table(&self) -> String {
+        let mut out = "| Token | Minted | Burned | Issued to date | Circulating |\n|---|---|---|---|---|".to_string();
+        for (token, flow) in &self.token_flows {
+            let supply = self.supply_at_end.as_ref().and_then(|s| s.tokens.get(token)).copied().unwrap_or_default();
+            write!(out, "\n| {} | {} | {} | {} | {} |", token.as_str(), flow.minted, flow.burned, supply.issued, supply.circulating)
+                .unwrap();
+        }
+        out
+    }
+
+    fn grant_table(&self) -> String {
+        let g = &self.grants;
+        format!(
+            "| Grant | Count | Amount |\n|---|---|---|\n| Sponsor pool payouts | {} | {} CHURCH |\n| Repair grants | {} | {} PWR |",
+            g.pool_payouts, g.pool_church, g.repair_grants, g.repair_pwr
+        )
+    }
+
+    fn regulator_list(&self) -> String {
+        let r = &self.regulator;
+        format!(
+            "- Warn transitions: {}\n- ForceRepair transitions: {}\n- FEAR accrued: {}\n- Mints screened: {}, held as suspect: {}",
+            r.warns, r.force_repairs, r.fear_accrued, r.mints_screened, r.suspect_mints
+        )
+    }
+
+    fn incident_list(&self) -> String {
+        let i = &self.incidents;
+        format!(
+            "- Life-harm deeds: {}\n- Integrity violations: {}\n- Corrections (tombstones): {}",
+            i.life_harm, i.integrity_violations, i.corrections
+        )
+    }
+
+    fn near_miss_table(&self) -> String {
+        if self.near_misses.is_empty() {
+            return "_No near misses reported._".to_string();
+        }
+        let mut out = "| Category | Severity | Reports | Corroborated | Description |\n|---|---|---|---|---|".to_string();
+        for n in &self.near_misses {
+            let severity = serde_json::to_value(n.severity).expect("severity serializes");
+            write!(
+                out,
+                "\n| {} | {} | {} | {} | {} |",
+                cell(&n.category),
+                severity.as_str().unwrap_or_default(),
+                n.reports,
+                if n.corroborated { "yes" } else { "no" },
+                cell(&n.description)
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    fn root_table(&self) -> String {
+        if self.anchored_roots.is_empty() {
+            return "_No segments were sealed in this period._".to_string();
+        }
+        let mut out = "| Segment | Positions | Tip hash | Merkle root |\n|---|---|---|---|".to_string();
+        for r in &self.anchored_roots {
+            write!(out, "\n| {} | {}-{} | `{}` | `{}` |", r.segment, r.first, r.last, r.tip_hash, r.merkle_root).unwrap();
+        }
+        out
+    }
+
+    fn eco_table(&self) -> String {
+        if self.guard.eco_utilization.is_empty() {
+            return "_Not supplied; the eco-fairness guard reports its headroom separately._".to_string();
+        }
+        let mut out = "| Route | Admissions | Power | Energy | Compute | Peak RoH utilization |\n|---|---|---|---|---|---|".to_string();
+        for e in &self.guard.eco_utilization {
+            write!(
+                out,
+                "\n| {} | {} | {:.2} | {:.2} | {:.4} | {:.3} |",
+                cell(&e.route),
+                e.admissions,
+                e.power_consumed,
+                e.energy_consumed,
+                e.compute_consumed,
+                e.roh_utilization_max
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    fn equity_table(&self) -> String {
+        if self.guard.equity_outcomes.is_empty() {
+            return "_Not supplied; the eco-fairness guard reports its fairness audit separately._".to_string();
+        }
+        let mut out = "| Class | Bounds | Mean share | Admitted | Attempts | Starvation events |\n|---|---|---|---|---|---|".to_string();
+        for e in &self.guard.equity_outcomes {
+            write!(
+                out,
+                "\n| {} | {:.2}-{:.2} | {:.3} | {} | {} | {} |",
+                cell(&e.class),
+                e.min_share,
+                e.max_share,
+                e.share_mean,
+                e.admitted,
+                e.attempts,
+                e.starvation_events
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// `text` safe inside a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Cells of a Markdown table row, `\|` unescaped.
+fn cells(row: &str) -> Vec<String> {
+    let inner = row.trim().trim_start_matches('|');
+    let inner = inner.strip_suffix('|').filter(|s| !s.ends_with('\\')).unwrap_or(inner);
+    let mut out = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => out.last_mut().expect("non-empty").push(chars.next().expect("peeked")),
+            '|' => out.push(String::new()),
+            c => out.last_mut().expect("non-empty").push(c),
+        }
+    }
+    out.iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Escape `text`, then turn `code` spans and a fully `_emphasized_` line
+/// into markup.
+fn inline(text: &str) -> String {
+    if let Some(em) = text.strip_prefix('_').and_then(|t| t.strip_suffix('_')) {
+        return format!("<em>{}</em>", inline(em));
+    }
+    let mut out = String::new();
+    for (i, part) in text.split('`').enumerate() {
+        if i % 2 == 1 {
+            write!(out, "<code>{}</code>", escape(part)).unwrap();
+        } else {
+            out.push_str(&escape(part));
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
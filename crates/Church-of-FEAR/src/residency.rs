@@ -0,0 +1,381 @@
+//! Jurisdiction-aware residency for deed sinks and exports.
+//!
+//! Every deed that names a subject is placed in one jurisdiction: an
+//! explicit `jurisdiction:<code>` tag wins, then the jurisdiction of the
+//! microspace named in the context (`microspace`), then the registered
+//! jurisdiction of the actor, then of the first target that has one, then
+//! `default_jurisdiction`. Ledger-authored deeds without targets name no
+//! subject and may go to every sink.
+//!
+//! Each sink and export destination declares the jurisdictions it may
+//! receive and whether it only gets the redacted form (subjects replaced by
+//! `anon:` digests, context cut down to `redaction_keep_keys`). The fan-out
+//! never hands a deed to a sink not declared for its jurisdiction. A deed
+//! with no permitted sink, or naming a subject no rule resolves, is
+//! buffered and raises a `ResidencyAlert`; `set_policy` retries the buffer.
+//! `ResidencyPolicy::validate` reports such gaps when the policy is loaded.
+//!
+//! `report()` counts what each sink received per jurisdiction.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use faults::points::SINK_WRITE;
+use faults::Fault;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::LEDGER_ACTOR;
+use crate::utils::crypto::sha256;
+use crate::utils::http::post_webhook;
+
+/// Tag prefix that pins a deed to a jurisdiction, e.g. `jurisdiction:BRU`.
+pub const JURISDICTION_TAG: &str = "jurisdiction:";
+
+/// Notification kind of deeds pushed to webhook sinks.
+pub const DEED_DELIVERY: &str = "deed_delivery";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SinkResidency {
+    pub name: String,
+    /// Jurisdictions whose deeds this sink may receive.
+    pub jurisdictions: Vec<String>,
+    /// The sink receives only the redacted form.
+    #[serde(default)]
+    pub redact: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResidencyPolicy {
+    /// Jurisdictions deployments operate in, e.g. `PHX`, `BRU`.
+    pub jurisdictions: Vec<String>,
+    /// Registered jurisdiction per actor id.
+    pub actor_jurisdictions: BTreeMap<String, String>,
+    /// Jurisdiction per microspace id.
+    pub microspace_jurisdictions: BTreeMap<String, String>,
+    /// Jurisdiction of deeds that name a subject no other rule places.
+    pub default_jurisdiction: Option<String>,
+    /// Sinks and export destinations, by name.
+    pub sinks: Vec<SinkResidency>,
+    /// Context keys kept in the redacted form.
+    pub redaction_keep_keys: Vec<String>,
+}
+
+impl Default for ResidencyPolicy {
+    fn default() -> Self {
+        Self {
+            jurisdictions: Vec::new(),
+            actor_jurisdictions: BTreeMap::new(),
+            microspace_jurisdictions: BTreeMap::new(),
+            default_jurisdiction: None,
+            sinks: Vec::new(),
+            redaction_keep_keys: vec!["co2_kg".to_string(), "bioload_delta".to_string()],
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ResidencyError {
+    #[error("sink {0} is declared twice")]
+    DuplicateSink(String),
+    #[error("sink {0} has no residency declaration")]
+    UndeclaredSink(String),
+    #[error("no sink may receive deeds from {}", .0.join(", "))]
+    Unroutable(Vec<String>),
+}
+
+/// Where a deed may go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    /// The deed names no subject.
+    Unrestricted,
+    In(String),
+    /// The deed names a subject but no rule places it.
+    Unresolved,
+}
+
+impl ResidencyPolicy {
+    pub fn sink(&self, name: &str) -> Option<&SinkResidency> {
+        self.sinks.iter().find(|s| s.name == name)
+    }
+
+    /// Where `deed` may go; see the module docs for the order of rules.
+    pub fn placement(&self, deed: &DeedEvent) -> Placement {
+        if let Some(code) = deed.tags.iter().find_map(|t| t.strip_prefix(JURISDICTION_TAG)) {
+            return Placement::In(code.to_string());
+        }
+        let microspace = deed.context_json.get("microspace").and_then(Value::as_str);
+        if let Some(code) = microspace.and_then(|m| self.microspace_jurisdictions.get(m)) {
+            return Placement::In(code.clone());
+        }
+        let subjects: Vec<&String> =
+            std::iter::once(&deed.actor_id).filter(|a| *a != LEDGER_ACTOR).chain(&deed.target_ids).collect();
+        if subjects.is_empty() {
+            return Placement::Unrestricted;
+        }
+        match subjects.iter().find_map(|s| self.actor_jurisdictions.get(*s)).or(self.default_jurisdiction.as_ref()) {
+            Some(code) => Placement::In(code.clone()),
+            None => Placement::Unresolved,
+        }
+    }
+
+    /// Whether sink `name` may receive deeds placed in `placement`.
+    pub fn permits(&self, name: &str, placement: &Placement) -> bool {
+        match (self.sink(name), placement) {
+            (Some(_), Placement::Unrestricted) => true,
+            (Some(sink), Placement::In(code)) => sink.jurisdictions.contains(code),
+            _ => false,
+        }
+    }
+
+    /// Known jurisdictions, and those any rule maps to, that no sink may receive.
+    pub fn unroutable(&self) -> Vec<String> {
+        let known = self
+            .jurisdictions
+            .iter()
+            .chain(self.actor_jurisdictions.values())
+            .chain(self.microspace_jurisdictions.values())
+            .chain(&self.default_jurisdiction);
+        let known: BTreeSet<&String> = known.collect();
+        known.into_iter().filter(|j| !self.sinks.iter().any(|s| s.jurisdictions.contains(j))).cloned().collect()
+    }
+
+    /// Reject duplicate sink names and jurisdictions no sink may receive.
+    pub fn validate(&self) -> Result<(), ResidencyError> {
+        let mut names = BTreeSet::new();
+        if let Some(dup) = self.sinks.iter().find(|s| !names.insert(s.name.as_str())) {
+            return Err(ResidencyError::DuplicateSink(dup.name.clone()));
+        }
+        let unroutable = self.unroutable();
+        if !unroutable.is_empty() {
+            return Err(ResidencyError::Unroutable(unroutable));
+        }
+        Ok(())
+    }
+
+    /// `deed` with subjects replaced by digests, the context cut down to
+    /// `redaction_keep_keys` and `ext` dropped. Hashes are kept so the
+    /// copy can be matched to the chain; it does not verify on its own.
+    pub fn redacted(&self, deed: &DeedEvent) -> DeedEvent {
+        let anon = |id: &String| if id == LEDGER_ACTOR { id.clone() } else { format!("anon:{}", &sha256(id)[..16]) };
+        let mut context: serde_json::Map<String, Value> =
+            self.redaction_keep_keys.iter().filter_map(|k| deed.context_json.get(k).map(|v| (k.clone(), v.clone()))).collect();
+        context.insert("redacted".to_string(), Value::Bool(true));
+        DeedEvent {
+            actor_id: anon(&deed.actor_id),
+            target_ids: deed.target_ids.iter().map(anon).collect(),
+            context_json: Value::Object(context),
+            ext: serde_json::Map::new(),
+            ..deed.clone()
+        }
+    }
+
+    /// The form of `deed` sink `name` may receive, if any.
+    pub fn form_for(&self, name: &str, deed: &DeedEvent) -> Option<DeedEvent> {
+        if !self.permits(name, &self.placement(deed)) {
+            return None;
+        }
+        let redact = self.sink(name).is_some_and(|s| s.redact);
+        Some(if redact { self.redacted(deed) } else { deed.clone() })
+    }
+}
+
+/// A destination for deeds as they are appended.
+pub trait DeedSink: Send {
+    fn name(&self) -> &str;
+    fn deliver(&mut self, deed: &DeedEvent) -> Result<(), String>;
+}
+
+/// POSTs each deed as JSON to a webhook.
+pub struct WebhookSink {
+    pub name: String,
+    pub url: String,
+}
+
+impl DeedSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn deliver(&mut self, deed: &DeedEvent) -> Result<(), String> {
+        post_webhook(&self.url, DEED_DELIVERY, deed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResidencyAlert {
+    /// Deeds from `jurisdiction` are held because no sink may receive them.
+    NoPermittedSink { jurisdiction: String, event_id: String },
+    /// A deed names a subject no rule places; it is held.
+    Unresolved { event_id: String },
+}
+
+/// Deliveries to one sink or export destination.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SinkDeliveries {
+    /// Deeds delivered per jurisdiction; unrestricted deeds under `*`.
+    pub by_jurisdiction: BTreeMap<String, u64>,
+    /// Of those, delivered in redacted form.
+    pub redacted: u64,
+    /// Deliveries the sink refused or failed.
+    pub failed: u64,
+}
+
+impl SinkDeliveries {
+    pub fn delivered(&self) -> u64 {
+        self.by_jurisdiction.values().sum()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResidencyReport {
+    pub sinks: BTreeMap<String, SinkDeliveries>,
+    /// Deeds currently held, per jurisdiction (`?` when unresolved).
+    pub buffered: BTreeMap<String, u64>,
+}
+
+/// Hands appended deeds to every sink their jurisdiction permits.
+pub struct ResidencyFanout {
+    policy: ResidencyPolicy,
+    sinks: Vec<Box<dyn DeedSink>>,
+    buffered: Vec<DeedEvent>,
+    alerts: Vec<ResidencyAlert>,
+    report: ResidencyReport,
+}
+
+impl ResidencyFanout {
+    /// Every sink must be declared in `policy`. Unroutable jurisdictions
+    /// are logged here and buffer their deeds at runtime.
+    pub fn new(policy: ResidencyPolicy, sinks: Vec<Box<dyn DeedSink>>) -> Result<Self, ResidencyError> {
+        let fanout = Self { policy, sinks, buffered: Vec::new(), alerts: Vec::new(), report: ResidencyReport::default() };
+        fanout.check()?;
+        Ok(fanout)
+    }
+
+    fn check(&self) -> Result<(), ResidencyError> {
+        match self.policy.validate() {
+            Err(ResidencyError::Unroutable(unroutable)) => {
+                warn!("residency: no sink may receive deeds from {}; they will be buffered", unroutable.join(", "));
+            }
+            other => other?,
+        }
+        match self.sinks.iter().find(|s| self.policy.sink(s.name()).is_none()) {
+            Some(sink) => Err(ResidencyError::UndeclaredSink(sink.name().to_string())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn policy(&self) -> &ResidencyPolicy {
+        &self.policy
+    }
+
+    /// Replace the policy and retry every buffered deed. On error the old
+    /// policy stays.
+    pub fn set_policy(&mut self, policy: ResidencyPolicy) -> Result<(), ResidencyError> {
+        let old = std::mem::replace(&mut self.policy, policy);
+        if let Err(e) = self.check() {
+            self.policy = old;
+            return Err(e);
+        }
+        self.report.buffered.clear();
+        for deed in std::mem::take(&mut self.buffered) {
+            self.dispatch(deed, false);
+        }
+        Ok(())
+    }
+
+    /// Deliver `deed` to every permitted sink, or buffer it with an alert.
+    /// Returns the number of sinks that accepted it.
+    pub fn publish(&mut self, deed: &DeedEvent) -> usize {
+        self.dispatch(deed.clone(), true)
+    }
+
+    fn dispatch(&mut self, deed: DeedEvent, alert: bool) -> usize {
+        let placement = self.policy.placement(&deed);
+        let key = match &placement {
+            Placement::Unrestricted => "*".to_string(),
+            Placement::In(code) => code.clone(),
+            Placement::Unresolved => "?".to_string(),
+        };
+        let names: Vec<String> =
+            self.sinks.iter().map(|s| s.name().to_string()).filter(|n| self.policy.permits(n, &placement)).collect();
+        if names.is_empty() {
+            let raised = match placement {
+                Placement::Unresolved => ResidencyAlert::Unresolved { event_id: deed.event_id.clone() },
+                _ => ResidencyAlert::NoPermittedSink { jurisdiction: key.clone(), event_id: deed.event_id.clone() },
+            };
+            if alert {
+                warn!("residency: holding deed {} ({}); no permitted sink", deed.event_id, key);
+                self.alerts.push(raised);
+            }
+            *self.report.buffered.entry(key).or_default() += 1;
+            self.buffered.push(deed);
+            return 0;
+        }
+        let mut accepted = 0;
+        for sink in self.sinks.iter_mut().filter(|s| names.iter().any(|n| n == s.name())) {
+            let form = self.policy.form_for(sink.name(), &deed).expect("permitted above");
+            let redacted = self.policy.sink(sink.name()).is_some_and(|s| s.redact);
+            let counts = self.report.sinks.entry(sink.name().to_string()).or_default();
+            let delivered = match faults::hit(SINK_WRITE) {
+                Some(Fault::Fail) => Err("injected sink failure".to_string()),
+                Some(Fault::Corrupt(_)) => sink.deliver(&DeedEvent { self_hash: "0".repeat(64), ..form }),
+                None => sink.deliver(&form),
+            };
+            match delivered {
+                Ok(()) => {
+                    *counts.by_jurisdiction.entry(key.clone()).or_default() += 1;
+                    counts.redacted += u64::from(redacted);
+                    accepted += 1;
+                }
+                Err(e) => {
+                    warn!("residency: sink {} refused deed {}: {}", sink.name(), deed.event_id, e);
+                    counts.failed += 1;
+                }
+            }
+        }
+        accepted
+    }
+
+    /// The deeds export destination `destination` may receive, in the form
+    /// it may receive them; counted in the report like a sink.
+    pub fn export<'a>(
+        &mut self,
+        destination: &str,
+        deeds: impl IntoIterator<Item = &'a DeedEvent>,
+    ) -> Result<Vec<DeedEvent>, ResidencyError> {
+        let sink = self.policy.sink(destination).ok_or_else(|| ResidencyError::UndeclaredSink(destination.to_string()))?;
+        let redact = sink.redact;
+        let mut out = Vec::new();
+        for deed in deeds {
+            let placement = self.policy.placement(deed);
+            let Some(form) = self.policy.form_for(destination, deed) else { continue };
+            let key = match placement {
+                Placement::In(code) => code,
+                _ => "*".to_string(),
+            };
+            let counts = self.report.sinks.entry(destination.to_string()).or_default();
+            *counts.by_jurisdiction.entry(key).or_default() += 1;
+            counts.redacted += u64::from(redact);
+            out.push(form);
+        }
+        Ok(out)
+    }
+
+    pub fn buffered(&self) -> &[DeedEvent] {
+        &self.buffered
+    }
+
+    /// Alerts raised since the last call.
+    pub fn take_alerts(&mut self) -> Vec<ResidencyAlert> {
+        std::mem::take(&mut self.alerts)
+    }
+
+    pub fn report(&self) -> &ResidencyReport {
+        &self.report
+    }
+}
@@ -1,2 +1,4 @@
 pub mod server;
 pub mod types;
+#[cfg(feature = "binary-wire")]
+pub mod wire;
@@ -1,42 +1,145 @@
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Instant;
 
-use log::{error, info};
+use log::{error, info, warn};
 use serde_json::json;
+use tracing::{field, info_span, Span};
 
-use crate::compliance::validator::validate_deed;
-use crate::ledger::deed_event::{DeedEvent};
+use crate::compliance::data_minimization::MinimizationPolicy;
+use crate::config::LedgerConfig;
+use crate::anomaly::{review_hold, AnomalyError};
+use crate::audit::SelfAuditor;
+use crate::compliance::regulator::EthicsEvaluation;
+use crate::compliance::validator::{validate_cooldown, validate_deed};
+use crate::halt_review::{freeze_status, screen_submission, FreezeError, Screening, HIGH_IMPACT_FROZEN_CODE};
+use crate::history::{HistoricalPoint, Standing};
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::{validate_bioload_delta, DeedEvent};
 use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::schema::{reject_unknown, SchemaError, SCHEMA_VERSION, UNKNOWN_CRITICAL_FIELD, UNKNOWN_CRITICAL_FIELD_CODE};
+use crate::ledger::token_ledger::TokenLedger;
+use crate::near_miss::{
+    observe_guard_rejection, report_near_miss, NearMissError, GUARD_DATA_MINIMIZATION, GUARD_DEED_VALIDATION, GUARD_LEDGER,
+};
+#[cfg(feature = "actor-keys")]
+use crate::notifications::{authenticate_subject, NotificationCenter, NotificationError, SubjectRequest};
+use crate::obligations::follow_up_status;
+use crate::params::ParamRegistry;
+use crate::providers::{alert_if_suspended, ProviderNotifier};
+#[cfg(feature = "validation-quorum")]
+use crate::quorum::cast_vote;
+use crate::quorum::{hold_if_required, validation_status, QuorumError};
+use crate::repair_planner::{RepairConfig, RepairPlanner};
+use crate::sponsor::pool::pool_status;
+use crate::submission::{admit_shared, Admission, Duplicate, SubmissionGuard, Transport, DUPLICATE_SUBMISSION_CODE};
+use crate::targets::by_region;
 use crate::token::mint::mint_church;
+#[cfg(feature = "tip-gossip")]
+use crate::tip_gossip::{segment_root, TipGossip};
+use crate::utils::correlation::CorrelationId;
 
 use super::types::{
-    AutoChurchMintParams, AutoChurchMintResult, AutoChurchValidateParams,
-    AutoChurchValidateResult, AutoChurchVisualizeParams, AutoChurchVisualizeResult,
-    JsonRpcError, JsonRpcRequest, JsonRpcResponse,
+    AutoChurchEthicsConditionsParams, AutoChurchFollowUpStatusParams, AutoChurchGetChainParams, AutoChurchGetChainResult, AutoChurchGetDeedsParams, AutoChurchGetDeedsResult, AutoChurchMintParams, AutoChurchMintResult, MINT_PARAM_FIELDS, AutoChurchNearMissParams, AutoChurchPoolStatusParams, AutoChurchRepairPlanParams, AutoChurchValidateParams,
+    AutoChurchAccountResult, AutoChurchGetAccountParams, AutoChurchReviewAnomalyParams, AutoChurchStateAtParams, AutoChurchTargetSummaryParams, AutoChurchDeedsForTargetParams, AutoChurchValidateResult, AutoChurchValidationStatusParams, JsonRpcError,
+    JsonRpcRequest, JsonRpcResponse,
 };
+#[cfg(feature = "actor-keys")]
+use super::types::AutoChurchSubjectParams;
+#[cfg(feature = "validation-quorum")]
+use super::types::AutoChurchValidationVoteParams;
+#[cfg(feature = "viz")]
+use super::types::{AutoChurchVisualizeParams, AutoChurchVisualizeResult};
+
+/// Deeds returned by one `auto_church.get_deeds` or `auto_church.get_chain`
+/// call at most.
+pub const MAX_DEED_BATCH: usize = 256;
+/// `auto_church.get_account` for an actor with no account and no deeds.
+pub const UNKNOWN_ACCOUNT_CODE: i64 = 1013;
+/// Notifications returned by one `auto_church.poll_outbox` call at most.
+pub const MAX_OUTBOX_BATCH: usize = 100;
+
+/// Node state read by the stateful methods (`auto_church.pool_status`,
+/// `auto_church.follow_up_status`, `auto_church.report_near_miss`,
+/// `auto_church.review_anomaly_hold`, `auto_church.get_state_at`,
+/// `auto_church.get_deeds`, `auto_church.get_chain`, `auto_church.get_account`,
+/// `auto_church.freeze_status`).
+/// Without a ledger those methods answer with error 1004; with one,
+/// `auto_church.mint_deed` also appends the deed it builds (its guard
+/// rejections corroborate matching near-miss reports) and
+/// `auto_church.params` and `auto_church.get_ethics_conditions` use the
+/// ledger's parameters instead of the compiled-in defaults. `auto_church.audit_status` needs the auditor.
+/// `auto_church.tip_announcement` needs the tip signer.
+/// The subject notification methods need the ledger, for actor keys, and
+/// the notification center. With a submission guard, a mint repeating one
+/// admitted within its window (over this or any other transport sharing the
+/// guard) is refused with error 1012.
+#[derive(Clone, Default)]
+pub struct RpcContext {
+    pub ledger: Option<Arc<Mutex<TokenLedger>>>,
+    pub auditor: Option<Arc<Mutex<SelfAuditor>>>,
+    /// Subject notification preferences and outboxes.
+    #[cfg(feature = "actor-keys")]
+    pub notifications: Option<Arc<Mutex<NotificationCenter>>>,
+    /// Signs the tip announcements replicas cross-check against.
+    #[cfg(feature = "tip-gossip")]
+    pub tips: Option<Arc<Mutex<TipGossip>>>,
+    /// Duplicate submission window, shared by every endpoint of the node.
+    pub submissions: Option<Arc<Mutex<SubmissionGuard>>>,
+}
+
+impl RpcContext {
+    /// A context with only `ledger` attached.
+    // Without `tip-gossip` and `actor-keys` the update below has nothing
+    // left to fill in.
+    #[allow(clippy::needless_update)]
+    pub fn with_ledger(ledger: Arc<Mutex<TokenLedger>>) -> Self {
+        Self { ledger: Some(ledger), ..Self::default() }
+    }
+}
 
 /// Start a simple line-delimited JSON-RPC 2.0 TCP server.
 /// Each line is a full JSON-RPC request, response is a single line.
 pub fn start_rpc_server(addr: &str) -> std::io::Result<()> {
+    start_rpc_server_with(addr, RpcContext::default())
+}
+
+/// `start_rpc_server` with access to the node's ledger.
+pub fn start_rpc_server_with(addr: &str, ctx: RpcContext) -> std::io::Result<()> {
+    serve_lines(addr, ctx, dispatch_request_with)
+}
+
+/// Accept connections on `addr` and answer each request line with `dispatch`.
+pub(crate) fn serve_lines(addr: &str, ctx: RpcContext, dispatch: fn(&str, &RpcContext) -> String) -> std::io::Result<()> {
     let listener = TcpListener::bind(addr)?;
     info!("Auto_Church RPC server listening on {}", addr);
+    accept_lines(listener, ctx, dispatch);
+    Ok(())
+}
+
+/// Serve JSON-RPC on an already bound listener.
+pub fn serve_rpc(listener: TcpListener, ctx: RpcContext) {
+    accept_lines(listener, ctx, dispatch_request_with)
+}
 
+fn accept_lines(listener: TcpListener, ctx: RpcContext, dispatch: fn(&str, &RpcContext) -> String) {
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                thread::spawn(|| handle_client(stream));
+                let ctx = ctx.clone();
+                thread::spawn(move || handle_client(stream, &ctx, dispatch));
             }
             Err(e) => {
                 error!("RPC accept error: {}", e);
             }
         }
     }
-
-    Ok(())
 }
 
-fn handle_client(stream: TcpStream) {
+fn handle_client(stream: TcpStream, ctx: &RpcContext, dispatch: fn(&str, &RpcContext) -> String) {
     let peer = stream.peer_addr().ok();
     info!("RPC client connected: {:?}", peer);
 
@@ -44,7 +147,11 @@ fn handle_client(stream: TcpStream) {
     for line in reader.lines() {
         match line {
             Ok(line) if !line.trim().is_empty() => {
-                let response_text = dispatch_request(&line);
+                let response_text = dispatch(&line, ctx);
+                // A batch of notifications gets no reply.
+                if response_text.is_empty() {
+                    continue;
+                }
                 if let Err(e) = writeln!(&mut &stream, "{}", response_text) {
                     error!("RPC write error: {}", e);
                     break;
@@ -61,26 +168,108 @@ fn handle_client(stream: TcpStream) {
     info!("RPC client disconnected: {:?}", peer);
 }
 
-fn dispatch_request(raw: &str) -> String {
-    let parsed: Result<JsonRpcRequest, _> = serde_json::from_str(raw);
-    match parsed {
-        Ok(req) => {
-            let resp = handle_rpc(req);
-            serde_json::to_string(&resp).unwrap_or_else(|e| {
-                serde_json::to_string(&JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    result: None,
-                    error: Some(JsonRpcError {
-                        code: -32603,
-                        message: "Internal error".to_string(),
-                        data: Some(json!({ "serde_error": e.to_string() })),
-                    }),
-                    id: json!(null),
+/// Handle one request line and return the response line.
+pub fn dispatch_request(raw: &str) -> String {
+    dispatch_request_with(raw, &RpcContext::default())
+}
+
+/// `dispatch_request` against the node state in `ctx`. Each request runs
+/// under its correlation id inside a `request` span, and the id is echoed
+/// in the response.
+pub fn dispatch_request_with(raw: &str, ctx: &RpcContext) -> String {
+    dispatch_with(raw, ctx, handle_rpc)
+}
+
+/// Answer one request line with `handle`. A JSON array is a batch: its
+/// responses come back as an array in request order, leaving out entries
+/// with a null or missing `id` (notifications); a batch of notifications
+/// only is answered with an empty line. A handler panic answers its
+/// request with -32603 instead of dropping the connection.
+pub fn dispatch_with(raw: &str, ctx: &RpcContext, handle: fn(JsonRpcRequest, &RpcContext) -> JsonRpcResponse) -> String {
+    let encoded = match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(serde_json::Value::Array(batch)) if !batch.is_empty() => {
+            let responses: Vec<JsonRpcResponse> = batch
+                .into_iter()
+                .filter_map(|entry| {
+                    let notification = entry.is_object() && entry.get("id").is_none_or(serde_json::Value::is_null);
+                    let resp = dispatch_one(Ok(entry), ctx, handle);
+                    (!notification).then_some(resp)
                 })
-                .unwrap()
-            })
+                .collect();
+            if responses.is_empty() {
+                return String::new();
+            }
+            serde_json::to_string(&responses)
         }
-        Err(e) => serde_json::to_string(&JsonRpcResponse {
+        parsed => serde_json::to_string(&dispatch_one(parsed, ctx, handle)),
+    };
+    encoded.unwrap_or_else(|e| {
+        serde_json::to_string(&JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32603,
+                message: "Internal error".to_string(),
+                data: Some(json!({ "serde_error": e.to_string() })),
+            }),
+            id: json!(null),
+            correlation_id: None,
+        })
+        .unwrap()
+    })
+}
+
+fn dispatch_one(
+    parsed: Result<serde_json::Value, serde_json::Error>,
+    ctx: &RpcContext,
+    handle: fn(JsonRpcRequest, &RpcContext) -> JsonRpcResponse,
+) -> JsonRpcResponse {
+    let incoming = parsed.as_ref().ok().and_then(|v| v.get("correlation_id")).and_then(serde_json::Value::as_str);
+    let correlation = CorrelationId::accept(incoming);
+    let _scope = correlation.enter();
+    let span = info_span!(
+        "request",
+        correlation_id = %correlation,
+        method = field::Empty,
+        event_id = field::Empty,
+        decision = field::Empty,
+        error_code = field::Empty,
+        latency_ms = field::Empty,
+    );
+    let _entered = span.enter();
+    let started = Instant::now();
+
+    let mut resp = match parsed {
+        Ok(value) => {
+            let id = value.get("id").cloned().unwrap_or(serde_json::Value::Null);
+            match serde_json::from_value::<JsonRpcRequest>(value) {
+                Ok(req) if req.jsonrpc == "2.0" => {
+                    span.record("method", req.method.as_str());
+                    let id = req.id.clone();
+                    let method = req.method.clone();
+                    match panic::catch_unwind(AssertUnwindSafe(|| handle(req, ctx))) {
+                        Ok(resp) => resp,
+                        Err(_) => {
+                            error!("RPC handler for {} panicked", method);
+                            JsonRpcResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(JsonRpcError {
+                                    code: -32603,
+                                    message: "Internal error".to_string(),
+                                    data: Some(json!({ "method": method })),
+                                }),
+                                id,
+                                correlation_id: None,
+                            }
+                        }
+                    }
+                }
+                Ok(req) => invalid_request(req.id, format!("jsonrpc must be \"2.0\", not {:?}", req.jsonrpc)),
+                Err(e) => invalid_request(id, e.to_string()),
+            }
+        }
+        Err(e) => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             result: None,
             error: Some(JsonRpcError {
@@ -89,22 +278,32 @@ fn dispatch_request(raw: &str) -> String {
                 data: Some(json!({ "detail": e.to_string() })),
             }),
             id: json!(null),
-        })
-        .unwrap(),
-    }
+            correlation_id: None,
+        },
+    };
+    resp.correlation_id = Some(correlation.to_string());
+    match &resp.error {
+        None => span.record("decision", "ok"),
+        Some(e) => span.record("decision", "error").record("error_code", e.code),
+    };
+    span.record("latency_ms", started.elapsed().as_secs_f64() * 1000.0);
+    resp
 }
 
-fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
+pub(crate) fn handle_rpc(req: JsonRpcRequest, ctx: &RpcContext) -> JsonRpcResponse {
     match req.method.as_str() {
         // Auto_Church surface:
 
         // auto_church.mint_deed
         "auto_church.mint_deed" => {
+            if let Err(SchemaError::UnknownCriticalField { path }) = reject_unknown(&req.params, "params", MINT_PARAM_FIELDS) {
+                return unknown_critical_field(req.id, path);
+            }
             let parsed: Result<AutoChurchMintParams, _> =
                 serde_json::from_value(req.params.clone());
             match parsed {
                 Ok(params) => {
-                    let deed = DeedEvent::new(
+                    let deed = match MinimizationPolicy::default().new_deed(
                         params.prev_hash,
                         params.actor_id,
                         params.target_ids,
@@ -113,12 +312,33 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
                         params.context_json,
                         params.ethics_flags,
                         params.life_harm_flag,
-                    );
+                    ) {
+                        Ok(deed) if params.ext.is_empty() => deed,
+                        Ok(deed) => deed.with_ext(params.ext),
+                        Err(e) => {
+                            guard_rejected(ctx, GUARD_DATA_MINIMIZATION);
+                            return JsonRpcResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(JsonRpcError {
+                                    code: 1002,
+                                    message: "Data minimization rejected deed".to_string(),
+                                    data: Some(json!({ "error": e.to_string() })),
+                                }),
+                                id: req.id,
+                                correlation_id: None,
+                            };
+                        }
+                    };
 
+                    Span::current().record("event_id", deed.event_id.as_str());
                     let metrics =
                         BioloadMetrics::new(params.bioload_delta, params.roh, params.decay);
 
-                    if let Err(e) = validate_deed(&deed, metrics.roh, metrics.decay) {
+                    let validated = validate_deed(&deed, metrics.roh, metrics.decay)
+                        .and_then(|()| validate_bioload_delta(metrics.bioload_delta));
+                    if let Err(e) = validated {
+                        guard_rejected(ctx, GUARD_DEED_VALIDATION);
                         return JsonRpcResponse {
                             jsonrpc: "2.0".to_string(),
                             result: None,
@@ -128,15 +348,117 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
                                 data: Some(json!({ "error": e.to_string() })),
                             }),
                             id: req.id,
+                            correlation_id: None,
                         };
                     }
 
-                    let church_minted = mint_church(&deed, &metrics);
+                    let admission = match admit_submission(ctx, &deed, params.idempotency_key.as_deref(), Transport::JsonRpc) {
+                        Ok(admission) => admission,
+                        Err(duplicate) => return duplicate_submission(req.id, &duplicate),
+                    };
+
+                    // With a ledger attached the deed is stored; the
+                    // response carries the stored form.
+                    // Categories that need a validation quorum have their
+                    // reward escrowed instead of minted.
+                    // A deed in its category's cooldown is still stored,
+                    // flagged `cooldown_suppressed`, and mints nothing.
+                    // Under a regulator halt a high-impact deed is queued
+                    // for review (or refused, by the freeze policy).
+                    // An attestation by a suspended provider is stored at
+                    // zero weight and alerts the operator.
+                    let mut cooldown = None;
+                    let mut queued_for_review = None;
+                    let event_id = deed.event_id.clone();
+                    let (deed, pending_validation) = match &ctx.ledger {
+                        Some(ledger) => {
+                            let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                            match screen_submission(&mut ledger, &deed, &metrics) {
+                                Ok(Screening::Proceed) => {}
+                                Ok(Screening::Queued { queue_event_id, .. }) => queued_for_review = Some(queue_event_id),
+                                Err(e) => {
+                                    drop(ledger);
+                                    let (code, message) = match e {
+                                        FreezeError::Frozen { .. } => (HIGH_IMPACT_FROZEN_CODE, "High-impact deeds are frozen for review"),
+                                        _ => {
+                                            guard_rejected(ctx, GUARD_LEDGER);
+                                            (1005, "Ledger rejected deed")
+                                        }
+                                    };
+                                    return JsonRpcResponse {
+                                        jsonrpc: "2.0".to_string(),
+                                        result: None,
+                                        error: Some(JsonRpcError {
+                                            code,
+                                            message: message.to_string(),
+                                            data: Some(json!({ "error": e.to_string() })),
+                                        }),
+                                        id: req.id,
+                                        correlation_id: None,
+                                    };
+                                }
+                            }
+                            if queued_for_review.is_none() {
+                                cooldown = validate_cooldown(&ledger, &deed).err();
+                            }
+                            let stored = match queued_for_review {
+                                Some(_) => Ok(deed),
+                                None => ledger.append(deed).cloned().map_err(QuorumError::from),
+                            };
+                            if let (Ok(stored), None) = (&stored, &queued_for_review) {
+                                let notifier = ledger.config().providers.notifier();
+                                alert_if_suspended(&ledger, stored, notifier.as_ref().map(|n| n as &dyn ProviderNotifier));
+                            }
+                            let held = stored.and_then(|stored| {
+                                if cooldown.is_some() || queued_for_review.is_some() {
+                                    return Ok((stored, None));
+                                }
+                                let reward = mint_church(&stored, &metrics, ledger.config());
+                                let pending = hold_if_required(&mut ledger, &stored.event_id, reward, crate::utils::time::now_timestamp())?;
+                                Ok((stored, pending))
+                            });
+                            match held {
+                                Ok(held) => held,
+                                Err(e) => {
+                                    // The deed may be stored even though
+                                    // its reward hold failed.
+                                    if let (Some(admission), Some(_)) = (admission, ledger.deed(&event_id)) {
+                                        admission.stored();
+                                    }
+                                    drop(ledger);
+                                    guard_rejected(ctx, GUARD_LEDGER);
+                                    return JsonRpcResponse {
+                                        jsonrpc: "2.0".to_string(),
+                                        result: None,
+                                        error: Some(JsonRpcError {
+                                            code: 1005,
+                                            message: "Ledger rejected deed".to_string(),
+                                            data: Some(json!({ "error": e.to_string() })),
+                                        }),
+                                        id: req.id,
+                                        correlation_id: None,
+                                    };
+                                }
+                            }
+                        }
+                        None => (deed, None),
+                    };
+                    if let Some(admission) = admission {
+                        admission.stored();
+                    }
+
+                    let church_minted = match pending_validation.is_some() || queued_for_review.is_some() {
+                        true => 0,
+                        false => church_for(ctx, &deed, &metrics),
+                    };
 
                     let payload = AutoChurchMintResult {
-                        deed,
+                        deed: deed.into(),
                         metrics,
                         church_minted,
+                        pending_validation,
+                        cooldown,
+                        queued_for_review,
                     };
 
                     JsonRpcResponse {
@@ -144,6 +466,7 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
                         result: Some(json!(payload)),
                         error: None,
                         id: req.id,
+                        correlation_id: None,
                     }
                 }
                 Err(e) => invalid_params(req.id, e.to_string()),
@@ -152,11 +475,12 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
 
         // auto_church.validate_deed
         "auto_church.validate_deed" => {
-            let parsed: Result<AutoChurchValidateParams, _> =
-                serde_json::from_value(req.params.clone());
+            let parsed = serde_json::from_value::<AutoChurchValidateParams>(req.params.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|p| own_deed(p.deed).map(|deed| (deed, p.roh, p.decay)));
             match parsed {
-                Ok(params) => {
-                    let res = validate_deed(&params.deed, params.roh, params.decay);
+                Ok((deed, roh, decay)) => {
+                    let res = validate_deed(&deed, roh, decay);
                     let payload = match res {
                         Ok(_) => AutoChurchValidateResult {
                             valid: true,
@@ -173,38 +497,752 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
                         result: Some(json!(payload)),
                         error: None,
                         id: req.id,
+                        correlation_id: None,
                     }
                 }
-                Err(e) => invalid_params(req.id, e.to_string()),
+                Err(e) => invalid_params(req.id, e),
             }
         }
 
         // auto_church.xr_visualize_ledger
+        #[cfg(feature = "viz")]
         "auto_church.xr_visualize_ledger" => {
-            let parsed: Result<AutoChurchVisualizeParams, _> =
-                serde_json::from_value(req.params.clone());
+            let parsed = serde_json::from_value::<AutoChurchVisualizeParams>(req.params.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|p| own_deeds(p.events));
             match parsed {
-                Ok(params) => {
-                    // Fire-and-forget visualization: runs in-process and
-                    // returns an ACK to the RPC client.
-                    let events = params.events;
-                    // Bevy App is not serializable; spawn thread for XR-grid launch.
-                    std::thread::spawn(move || {
-                        let _app = crate::ledger::deed_event::xr_visualize_ledger(&events);
-                        // In a real system you would call _app.run().
-                    });
+                Ok(events) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: Some(json!(AutoChurchVisualizeResult {
+                        scene: crate::viz::export_scene(&events),
+                    })),
+                    error: None,
+                    id: req.id,
+                    correlation_id: None,
+                },
+                Err(e) => invalid_params(req.id, e),
+            }
+        }
 
+        // auto_church.repair_plan
+        "auto_church.repair_plan" => {
+            let parsed = serde_json::from_value::<AutoChurchRepairPlanParams>(req.params.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|mut p| own_deeds(std::mem::take(&mut p.recent_deeds)).map(|recent| (recent, p)));
+            match parsed {
+                Ok((recent_deeds, params)) => {
+                    let recent: Vec<_> = recent_deeds.iter().collect();
+                    match RepairPlanner::new(RepairConfig::default()).draft(&params.summary, &params.report, &recent) {
+                        Ok(plan) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!(plan)),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(e) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1003,
+                                message: "No repair plan".to_string(),
+                                data: Some(json!({ "error": e.to_string() })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                    }
+                }
+                Err(e) => invalid_params(req.id, e),
+            }
+        }
+
+        // auto_church.pool_status
+        "auto_church.pool_status" => {
+            let parsed: Result<AutoChurchPoolStatusParams, _> =
+                serde_json::from_value(if req.params.is_null() { json!({}) } else { req.params.clone() });
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let now = params.now.unwrap_or_else(crate::utils::time::now_timestamp);
                     JsonRpcResponse {
                         jsonrpc: "2.0".to_string(),
-                        result: Some(json!(AutoChurchVisualizeResult { launched: true })),
+                        result: Some(json!(pool_status(&ledger, now))),
                         error: None,
                         id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.report_near_miss: file a diagnostic-only near miss.
+        "auto_church.report_near_miss" => {
+            let parsed: Result<AutoChurchNearMissParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let now = params.now.unwrap_or_else(crate::utils::time::now_timestamp);
+                    match report_near_miss(
+                        &mut ledger,
+                        &params.reporter,
+                        &params.description,
+                        &params.category,
+                        params.severity,
+                        &params.related_event_ids,
+                        now,
+                    ) {
+                        Ok(receipt) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!(receipt)),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(NearMissError::Ledger(e)) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1005,
+                                message: "Ledger rejected deed".to_string(),
+                                data: Some(json!({ "error": e.to_string() })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(e) => invalid_params(req.id, e.to_string()),
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.follow_up_status: obligations owed on one deed.
+        "auto_church.follow_up_status" => {
+            let parsed: Result<AutoChurchFollowUpStatusParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let obligations = follow_up_status(&ledger, &params.event_id);
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(json!({ "event_id": params.event_id, "obligations": obligations })),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.target_summary: what has been done for a target and
+        // by whom, or for every target grouped by region.
+        "auto_church.target_summary" => {
+            let parsed: Result<AutoChurchTargetSummaryParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let result = match params.target_id {
+                        Some(target_id) => json!(ledger.target_summary(&target_id)),
+                        None => json!({ "regions": by_region(&ledger.target_summaries()) }),
+                    };
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(result),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.deeds_for_target: the live deeds naming a target.
+        "auto_church.deeds_for_target" => {
+            let parsed: Result<AutoChurchDeedsForTargetParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let deeds = ledger.deeds_for_target(&params.target_id, &params.filter);
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(json!({ "target_id": params.target_id, "deeds": deeds })),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.validation_status: a quorum-gated deed's votes and outcome.
+        "auto_church.validation_status" => {
+            let parsed: Result<AutoChurchValidationStatusParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let validation = validation_status(&ledger, &params.event_id);
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(json!({ "event_id": params.event_id, "validation": validation })),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.cast_validation_vote: a validator approves or rejects
+        // a deed awaiting its quorum.
+        #[cfg(feature = "validation-quorum")]
+        "auto_church.cast_validation_vote" => {
+            let parsed: Result<AutoChurchValidationVoteParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let now = params.now.unwrap_or_else(crate::utils::time::now_timestamp);
+                    match cast_vote(&mut ledger, &params.vote, &params.signature, now) {
+                        Ok(receipt) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!(receipt)),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(QuorumError::Ledger(e)) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1005,
+                                message: "Ledger rejected deed".to_string(),
+                                data: Some(json!({ "error": e.to_string() })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(e) => invalid_params(req.id, e.to_string()),
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.get_notification_prefs, set_notification_prefs and
+        // poll_outbox: a subject's own notification settings and outbox,
+        // signed with their actor key.
+        #[cfg(feature = "actor-keys")]
+        "auto_church.get_notification_prefs" | "auto_church.set_notification_prefs" | "auto_church.poll_outbox" => {
+            subject_call(req, ctx)
+        }
+
+        // auto_church.review_anomaly_hold: an operator clears or confirms a
+        // held suspect mint.
+        "auto_church.review_anomaly_hold" => {
+            let parsed: Result<AutoChurchReviewAnomalyParams, _> = serde_json::from_value(req.params.clone());
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let now = params.now.unwrap_or_else(crate::utils::time::now_timestamp);
+                    match review_hold(
+                        &mut ledger,
+                        &params.screening_event_id,
+                        params.decision,
+                        &params.reviewer,
+                        &params.role,
+                        &params.reason,
+                        now,
+                    ) {
+                        Ok(paid) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!({
+                                "screening_event_id": params.screening_event_id,
+                                "decision": params.decision,
+                                "paid": paid,
+                            })),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(AnomalyError::Ledger(e)) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1005,
+                                message: "Ledger rejected deed".to_string(),
+                                data: Some(json!({ "error": e.to_string() })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(e) => invalid_params(req.id, e.to_string()),
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.get_state_at: read-only balances, standings and supply
+        // as of an earlier tip hash or timestamp.
+        "auto_church.get_state_at" => {
+            let parsed: Result<AutoChurchStateAtParams, _> = serde_json::from_value(req.params.clone());
+            let point = parsed.map_err(|e| e.to_string()).and_then(|p| match (p.tip_hash, p.timestamp) {
+                (Some(hash), None) => Ok(HistoricalPoint::Tip(hash)),
+                (None, Some(ts)) => Ok(HistoricalPoint::Timestamp(ts)),
+                _ => Err("give exactly one of tip_hash and timestamp".to_string()),
+            });
+            match (point, &ctx.ledger) {
+                (Ok(point), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    match ledger.state_at(&point) {
+                        Ok(state) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!({
+                                "tip_hash": state.view.tip_hash(),
+                                "between_events": state.between_events,
+                                "view": &*state.view,
+                            })),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        Err(e) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1006,
+                                message: "No ledger state at that point".to_string(),
+                                data: Some(json!({ "error": e.to_string() })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e),
+            }
+        }
+
+        // auto_church.handshake: what this node speaks, so clients can
+        // downshift to an older deed schema.
+        "auto_church.handshake" => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({ "schema_version": SCHEMA_VERSION })),
+            error: None,
+            id: req.id,
+            correlation_id: None,
+        },
+
+        // auto_church.get_deeds: a bounded batch of the live chain after a
+        // resume hash; replicas follow the primary with it.
+        "auto_church.get_deeds" => {
+            let parsed: Result<AutoChurchGetDeedsParams, _> =
+                serde_json::from_value(if req.params.is_null() { json!({}) } else { req.params.clone() });
+            match (parsed, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let deeds = ledger.deeds();
+                    let start = match params.after_hash.as_deref() {
+                        None => Some(0),
+                        Some(hash) if hash == "0".repeat(64) => Some(0),
+                        Some(hash) => deeds.iter().rposition(|d| d.self_hash == hash).map(|pos| pos + 1),
+                    };
+                    match start {
+                        Some(start) => {
+                            let limit = params.limit.unwrap_or(MAX_DEED_BATCH).clamp(1, MAX_DEED_BATCH);
+                            let end = deeds.len().min(start + limit);
+                            let payload = AutoChurchGetDeedsResult {
+                                deeds: deeds[start..end].iter().map(deed_core::DeedEvent::from).collect(),
+                                height: deeds.len() as u64,
+                                tip_hash: ledger.last_hash(),
+                                more: end < deeds.len(),
+                                schema_version: SCHEMA_VERSION,
+                            };
+                            JsonRpcResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: Some(json!(payload)),
+                                error: None,
+                                id: req.id,
+                                correlation_id: None,
+                            }
+                        }
+                        None => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: 1007,
+                                message: "Unknown resume hash".to_string(),
+                                data: Some(json!({ "after_hash": params.after_hash })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e.to_string()),
+            }
+        }
+
+        // auto_church.get_chain: a page of the chain for dashboards, from
+        // an offset and optionally one actor's deeds only.
+        "auto_church.get_chain" => {
+            let parsed: Result<AutoChurchGetChainParams, _> =
+                serde_json::from_value(if req.params.is_null() { json!({}) } else { req.params.clone() });
+            let params = parsed.map_err(|e| e.to_string()).and_then(|p| match (p.limit, p.actor_id.as_deref()) {
+                (Some(limit), _) if !(1..=MAX_DEED_BATCH).contains(&limit) => {
+                    Err(format!("limit must be between 1 and {}", MAX_DEED_BATCH))
+                }
+                (_, Some("")) => Err("actor_id must not be empty".to_string()),
+                _ => Ok(p),
+            });
+            match (params, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    let deeds = ledger.deeds();
+                    let limit = params.limit.unwrap_or(MAX_DEED_BATCH);
+                    let (events, total) = match params.actor_id.as_deref() {
+                        None => {
+                            let start = params.offset.min(deeds.len());
+                            let page = &deeds[start..deeds.len().min(start + limit)];
+                            (page.iter().map(deed_core::DeedEvent::from).collect(), deeds.len())
+                        }
+                        Some(actor_id) => {
+                            let by_actor = || deeds.iter().filter(|d| d.actor_id == actor_id);
+                            let page = by_actor().skip(params.offset).take(limit);
+                            (page.map(deed_core::DeedEvent::from).collect(), by_actor().count())
+                        }
+                    };
+                    let payload = AutoChurchGetChainResult { events, tip_hash: ledger.last_hash(), total: total as u64 };
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(json!(payload)),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e),
+            }
+        }
+
+        // auto_church.get_account: an actor's balances, standing and eco score.
+        "auto_church.get_account" => {
+            let parsed: Result<AutoChurchGetAccountParams, _> = serde_json::from_value(req.params.clone());
+            let params = parsed.map_err(|e| e.to_string()).and_then(|p| match p.actor_id.is_empty() {
+                true => Err("actor_id must not be empty".to_string()),
+                false => Ok(p),
+            });
+            match (params, &ctx.ledger) {
+                (Ok(params), Some(ledger)) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    match account_summary(&ledger, &params.actor_id) {
+                        Some(summary) => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!(summary)),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                        None => JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(JsonRpcError {
+                                code: UNKNOWN_ACCOUNT_CODE,
+                                message: "Unknown account".to_string(),
+                                data: Some(json!({ "actor_id": params.actor_id })),
+                            }),
+                            id: req.id,
+                            correlation_id: None,
+                        },
+                    }
+                }
+                (Ok(_), None) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: 1004,
+                        message: "No ledger attached".to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                    correlation_id: None,
+                },
+                (Err(e), _) => invalid_params(req.id, e),
+            }
+        }
+
+        // auto_church.tip_announcement: our signed tip and the Merkle roots
+        // of every sealed segment, for replicas to cross-check.
+        #[cfg(feature = "tip-gossip")]
+        "auto_church.tip_announcement" => match (&ctx.tips, &ctx.ledger) {
+            (Some(tips), Some(ledger)) => {
+                let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                let signed = tips.lock().unwrap_or_else(|e| e.into_inner()).announce(&ledger, crate::utils::time::now_timestamp());
+                match signed {
+                    Ok(signed) => {
+                        let segments: Vec<_> = ledger
+                            .sealed_segments()
+                            .iter()
+                            .map(|s| json!({ "segment": s, "merkle_root": segment_root(&ledger, s) }))
+                            .collect();
+                        JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(json!({ "announcement": signed, "sealed_segments": segments, "schema_version": SCHEMA_VERSION })),
+                            error: None,
+                            id: req.id,
+                            correlation_id: None,
+                        }
+                    }
+                    Err(e) => JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: None,
+                        error: Some(JsonRpcError {
+                            code: -32603,
+                            message: "Internal error".to_string(),
+                            data: Some(json!({ "error": e.to_string() })),
+                        }),
+                        id: req.id,
+                        correlation_id: None,
+                    },
+                }
+            }
+            _ => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(JsonRpcError {
+                    code: 1004,
+                    message: "No tip signer attached".to_string(),
+                    data: None,
+                }),
+                id: req.id,
+                correlation_id: None,
+            },
+        },
+
+        // auto_church.params: current values, bounds and provenance, plus
+        // the governance changes that produced them.
+        "auto_church.params" => {
+            let result = match &ctx.ledger {
+                Some(ledger) => {
+                    let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                    json!({ "params": ledger.params().listing(), "history": ledger.params().history() })
+                }
+                None => json!({ "params": ParamRegistry::default().listing(), "history": [] }),
+            };
+            JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: Some(result),
+                error: None,
+                id: req.id,
+                correlation_id: None,
+            }
+        }
+
+        // auto_church.get_ethics_conditions: each condition and the decision
+        // derived from them.
+        "auto_church.get_ethics_conditions" => {
+            let parsed: Result<AutoChurchEthicsConditionsParams, _> = serde_json::from_value(req.params.clone());
+            match parsed {
+                Ok(params) => {
+                    let evaluation = match &ctx.ledger {
+                        Some(ledger) => {
+                            EthicsEvaluation::new(&params.summary, ledger.lock().unwrap_or_else(|e| e.into_inner()).params())
+                        }
+                        None => EthicsEvaluation::new(&params.summary, &ParamRegistry::default()),
+                    };
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: Some(json!(evaluation)),
+                        error: None,
+                        id: req.id,
+                        correlation_id: None,
                     }
                 }
                 Err(e) => invalid_params(req.id, e.to_string()),
             }
         }
 
+        // auto_church.freeze_status: the high-impact freeze of a regulator
+        // halt, if any, with its review ticks and queued deeds.
+        "auto_church.freeze_status" => match &ctx.ledger {
+            Some(ledger) => {
+                let ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+                JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: Some(json!({ "freeze": freeze_status(&ledger) })),
+                    error: None,
+                    id: req.id,
+                    correlation_id: None,
+                }
+            }
+            None => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(JsonRpcError {
+                    code: 1004,
+                    message: "No ledger attached".to_string(),
+                    data: None,
+                }),
+                id: req.id,
+                correlation_id: None,
+            },
+        },
+
+        // auto_church.audit_status: self-audit progress and any mint freeze.
+        "auto_church.audit_status" => match &ctx.auditor {
+            Some(auditor) => {
+                let mut result = json!(auditor.lock().unwrap_or_else(|e| e.into_inner()).status());
+                let freeze = ctx.ledger.as_ref().and_then(|l| {
+                    l.lock().unwrap_or_else(|e| e.into_inner()).mint_freeze().map(str::to_string)
+                });
+                result["mint_freeze"] = json!(freeze);
+                JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: Some(result),
+                    error: None,
+                    id: req.id,
+                    correlation_id: None,
+                }
+            }
+            None => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(JsonRpcError {
+                    code: 1004,
+                    message: "No auditor attached".to_string(),
+                    data: None,
+                }),
+                id: req.id,
+                correlation_id: None,
+            },
+        },
+
         _ => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             result: None,
@@ -214,10 +1252,206 @@ fn handle_rpc(req: JsonRpcRequest) -> JsonRpcResponse {
                 data: Some(json!({ "method": req.method })),
             }),
             id: req.id,
+            correlation_id: None,
         },
     }
 }
 
+/// Claim `deed`'s place in the context's submission window, if it has one.
+/// Dropping the admission without `stored` frees the place again.
+pub(crate) fn admit_submission(
+    ctx: &RpcContext,
+    deed: &DeedEvent,
+    idempotency_key: Option<&str>,
+    transport: Transport,
+) -> Result<Option<Admission>, Duplicate> {
+    match &ctx.submissions {
+        Some(guard) => admit_shared(guard, deed, idempotency_key, transport, crate::utils::time::now_timestamp()).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn duplicate_submission(id: serde_json::Value, duplicate: &Duplicate) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(JsonRpcError {
+            code: DUPLICATE_SUBMISSION_CODE,
+            message: "Duplicate submission".to_string(),
+            data: Some(json!(duplicate)),
+        }),
+        id,
+        correlation_id: None,
+    }
+}
+
+/// The CHURCH `deed` mints under the context's ledger config, or the
+/// default config without a ledger.
+pub(crate) fn church_for(ctx: &RpcContext, deed: &DeedEvent, metrics: &BioloadMetrics) -> u64 {
+    match &ctx.ledger {
+        Some(ledger) => mint_church(deed, metrics, ledger.lock().unwrap_or_else(|e| e.into_inner()).config()),
+        None => mint_church(deed, metrics, &LedgerConfig::default()),
+    }
+}
+
+/// `actor_id`'s balances and record, if it has an account or any live deed.
+fn account_summary(ledger: &TokenLedger, actor_id: &str) -> Option<AutoChurchAccountResult> {
+    let account = ledger.account(actor_id);
+    let (mut deeds, mut flagged, mut life_harm, mut good_deeds) = (0u64, 0u64, 0u64, 0u64);
+    for d in ledger.deeds_for_actor(actor_id) {
+        deeds += 1;
+        flagged += u64::from(!d.ethics_flags.is_empty());
+        life_harm += u64::from(d.life_harm_flag);
+        good_deeds += u64::from(d.ethics_flags.is_empty() && !d.life_harm_flag);
+    }
+    if account.is_none() && deeds == 0 {
+        return None;
+    }
+    let good_share = if deeds == 0 { 0.0 } else { good_deeds as f64 / deeds as f64 };
+    let harm = ((deeds - good_deeds) as f64 / 10.0).min(1.0);
+    let balances = account
+        .map(|a| Token::ALL.into_iter().map(|t| (t, a.balance(t))).filter(|&(_, b)| b > 0).collect())
+        .unwrap_or_default();
+    Some(AutoChurchAccountResult {
+        actor_id: actor_id.to_string(),
+        balances,
+        deeds,
+        good_deeds,
+        flagged,
+        life_harm,
+        standing: Standing::from_counts(flagged as usize, life_harm as usize),
+        eco_score: 0.7 * good_share + 0.3 * (1.0 - harm),
+    })
+}
+
+/// Let near-miss reports in `category` be corroborated by a guard rejection.
+pub(crate) fn guard_rejected(ctx: &RpcContext, category: &str) {
+    if let Some(ledger) = &ctx.ledger {
+        let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = observe_guard_rejection(&mut ledger, category, crate::utils::time::now_timestamp()) {
+            warn!("near-miss corroboration after {} rejection: {}", category, e);
+        }
+    }
+}
+
+/// Answer a subject notification method once the signature shows the
+/// caller is `actor_id`.
+#[cfg(feature = "actor-keys")]
+fn subject_call(req: JsonRpcRequest, ctx: &RpcContext) -> JsonRpcResponse {
+    let params: AutoChurchSubjectParams = match serde_json::from_value(req.params.clone()) {
+        Ok(params) => params,
+        Err(e) => return invalid_params(req.id, e.to_string()),
+    };
+    let (Some(ledger), Some(center)) = (&ctx.ledger, &ctx.notifications) else {
+        return JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError {
+                code: 1004,
+                message: "No notification center attached".to_string(),
+                data: None,
+            }),
+            id: req.id,
+            correlation_id: None,
+        };
+    };
+    let mut args = req.params.clone();
+    if let Some(args) = args.as_object_mut() {
+        for key in ["actor_id", "signature", "now"] {
+            args.remove(key);
+        }
+    }
+    let request = SubjectRequest::new(&req.method, &params.actor_id, args);
+    let now = params.now.unwrap_or_else(crate::utils::time::now_timestamp);
+    let authenticated = authenticate_subject(&ledger.lock().unwrap_or_else(|e| e.into_inner()), &request, &params.signature, now);
+    if let Err(e) = authenticated {
+        return JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError {
+                code: 1010,
+                message: "Not authenticated as subject".to_string(),
+                data: Some(json!({ "actor_id": params.actor_id, "error": e.to_string() })),
+            }),
+            id: req.id,
+            correlation_id: None,
+        };
+    }
+
+    let mut center = center.lock().unwrap_or_else(|e| e.into_inner());
+    let actor_id = params.actor_id.as_str();
+    let result = match (req.method.as_str(), params.prefs) {
+        ("auto_church.set_notification_prefs", Some(prefs)) => {
+            center.set_prefs(actor_id, prefs.clone()).map(|()| json!({ "actor_id": actor_id, "prefs": prefs }))
+        }
+        ("auto_church.set_notification_prefs", None) => return invalid_params(req.id, "missing field `prefs`".to_string()),
+        ("auto_church.poll_outbox", _) => {
+            let limit = params.limit.unwrap_or(MAX_OUTBOX_BATCH).clamp(1, MAX_OUTBOX_BATCH);
+            center.poll_outbox(actor_id, params.since_cursor, limit).map(|page| json!(page))
+        }
+        _ => Ok(json!({ "actor_id": actor_id, "prefs": center.prefs(actor_id) })),
+    };
+    match result {
+        Ok(result) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id: req.id,
+            correlation_id: None,
+        },
+        Err(e @ NotificationError::InvalidPrefs(_)) => invalid_params(req.id, e.to_string()),
+        Err(e) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32603,
+                message: "Internal error".to_string(),
+                data: Some(json!({ "error": e.to_string() })),
+            }),
+            id: req.id,
+            correlation_id: None,
+        },
+    }
+}
+
+fn unknown_critical_field(id: serde_json::Value, path: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(JsonRpcError {
+            code: UNKNOWN_CRITICAL_FIELD_CODE,
+            message: UNKNOWN_CRITICAL_FIELD.to_string(),
+            data: Some(json!({ "path": path, "schema_version": SCHEMA_VERSION })),
+        }),
+        id,
+        correlation_id: None,
+    }
+}
+
+fn invalid_request(id: serde_json::Value, detail: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(JsonRpcError {
+            code: -32600,
+            message: "Invalid Request".to_string(),
+            data: Some(json!({ "detail": detail })),
+        }),
+        id,
+        correlation_id: None,
+    }
+}
+
+/// A deed from the wire as this ledger's own; only deeds sealed under the
+/// moral-ledger rule convert.
+fn own_deed(deed: deed_core::DeedEvent) -> Result<DeedEvent, String> {
+    DeedEvent::try_from(deed).map_err(|e| e.to_string())
+}
+
+fn own_deeds(deeds: Vec<deed_core::DeedEvent>) -> Result<Vec<DeedEvent>, String> {
+    deeds.into_iter().map(own_deed).collect()
+}
+
 fn invalid_params(id: serde_json::Value, detail: String) -> JsonRpcResponse {
     JsonRpcResponse {
         jsonrpc: "2.0".to_string(),
@@ -228,5 +1462,6 @@ fn invalid_params(id: serde_json::Value, detail: String) -> JsonRpcResponse {
             data: Some(json!({ "detail": detail })),
         }),
         id,
+        correlation_id: None,
     }
 }
@@ -1,6 +1,23 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
-use crate::ledger::deed_event::DeedEvent;
+use crate::history::Standing;
+use crate::ledger::account::Token;
+use crate::compliance::ethics::EthicsSummary;
+use crate::compliance::god_like::GodLikeReport;
 use crate::ledger::metrics::BioloadMetrics;
+use crate::ledger::schema::{EXT_FIELD, UNADVERTISED_SCHEMA_VERSION};
+use crate::anomaly::HoldDecision;
+use crate::cooldown::CooldownViolation;
+use crate::near_miss::Severity;
+#[cfg(feature = "actor-keys")]
+use crate::notifications::NotificationPrefs;
+#[cfg(feature = "validation-quorum")]
+use crate::quorum::ValidationVote;
+use crate::targets::TargetFilter;
+// Deeds travel as the shared deed; `deed_core::wire` keeps ours in the bare
+// shape and Unix seconds older nodes read.
+use deed_core::DeedEvent;
 
 /// Generic JSON-RPC 2.0 envelope.
 
@@ -12,6 +29,10 @@ pub struct JsonRpcRequest {
     pub params: serde_json::Value,
     #[serde(default)]
     pub id: serde_json::Value,
+    /// Caller's correlation id, the line protocol's stand-in for an
+    /// `X-Correlation-Id` header; generated when absent or malformed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +43,9 @@ pub struct JsonRpcResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<JsonRpcError>,
     pub id: serde_json::Value,
+    /// Echo of the request's correlation id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,17 +71,57 @@ pub struct AutoChurchMintParams {
     pub bioload_delta: f64,
     pub roh: f64,
     pub decay: f64,
+    /// Fields from newer deed schema versions, stored as the deed's `ext`.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub ext: serde_json::Map<String, serde_json::Value>,
+    /// Client key for retries: a second mint by the same actor with the
+    /// same key inside the submission window is refused as a duplicate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
+/// Every `auto_church.mint_deed` param; anything else is an unknown
+/// critical field.
+pub const MINT_PARAM_FIELDS: &[&str] = &[
+    "prev_hash",
+    "actor_id",
+    "target_ids",
+    "deed_type",
+    "tags",
+    "context_json",
+    "ethics_flags",
+    "life_harm_flag",
+    "bioload_delta",
+    "roh",
+    "decay",
+    "idempotency_key",
+    EXT_FIELD,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AutoChurchMintResult {
+    #[serde(with = "deed_core::wire")]
     pub deed: DeedEvent,
     pub metrics: BioloadMetrics,
     pub church_minted: u64,
+    /// `validation_pending` event id when the category needs a validation
+    /// quorum; the reward is escrowed and `church_minted` is 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_validation: Option<String>,
+    /// Set when the deed was logged during its category's cooldown; it is
+    /// stored `cooldown_suppressed` and `church_minted` is 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<CooldownViolation>,
+    /// `frozen_deed_queued` event id when a regulator halt froze deeds
+    /// like this one; it was queued rather than stored and mints nothing
+    /// until the freeze is lifted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_for_review: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AutoChurchValidateParams {
+    #[serde(with = "deed_core::wire")]
     pub deed: DeedEvent,
     pub roh: f64,
     pub decay: f64,
@@ -69,14 +133,204 @@ pub struct AutoChurchValidateResult {
     pub error_message: Option<String>,
 }
 
+#[cfg(feature = "viz")]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AutoChurchVisualizeParams {
+    #[serde(with = "deed_core::wire::vec")]
     pub events: Vec<DeedEvent>,
 }
 
+#[cfg(feature = "viz")]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AutoChurchVisualizeResult {
-    /// Placeholder: in-process visualizations do not return a serializable App,
-    /// so the RPC just acknowledges that the visualization was launched.
-    pub launched: bool,
+    pub scene: crate::viz::XrScene,
+}
+
+/// Draft a repair plan from a failing summary and its GodLike explanation.
+/// The plan is not logged; nodes log plans from their own ledger.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchRepairPlanParams {
+    pub summary: EthicsSummary,
+    pub report: GodLikeReport,
+    #[serde(default, with = "deed_core::wire::vec")]
+    pub recent_deeds: Vec<DeedEvent>,
+}
+
+/// Evaluate the nine ethical conditions for one summary.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchEthicsConditionsParams {
+    pub summary: EthicsSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchNearMissParams {
+    pub reporter: String,
+    pub description: String,
+    pub category: String,
+    pub severity: Severity,
+    #[serde(default)]
+    pub related_event_ids: Vec<String>,
+    /// Unix seconds; defaults to the node clock.
+    #[serde(default)]
+    pub now: Option<i64>,
+}
+
+/// Clear or confirm a minting-anomaly hold.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchReviewAnomalyParams {
+    pub screening_event_id: String,
+    pub decision: HoldDecision,
+    pub reviewer: String,
+    pub role: String,
+    #[serde(default)]
+    pub reason: String,
+    /// Unix seconds; defaults to the node clock.
+    #[serde(default)]
+    pub now: Option<i64>,
+}
+
+/// A historical point: exactly one of `tip_hash` and `timestamp`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchStateAtParams {
+    #[serde(default)]
+    pub tip_hash: Option<String>,
+    /// Unix seconds.
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchValidationStatusParams {
+    pub event_id: String,
+}
+
+/// A validator's signed vote on a deed awaiting its quorum.
+#[cfg(feature = "validation-quorum")]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchValidationVoteParams {
+    pub vote: ValidationVote,
+    pub signature: keyring::KeyringSignature,
+    /// Unix seconds; defaults to the node clock.
+    #[serde(default)]
+    pub now: Option<i64>,
+}
+
+/// A subject's own call to `auto_church.get_notification_prefs`,
+/// `auto_church.set_notification_prefs` (with `prefs`) or
+/// `auto_church.poll_outbox` (with `since_cursor` and `limit`), signed over
+/// a `SubjectRequest`.
+#[cfg(feature = "actor-keys")]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchSubjectParams {
+    pub actor_id: String,
+    pub signature: keyring::KeyringSignature,
+    /// Unix seconds; defaults to the node clock.
+    #[serde(default)]
+    pub now: Option<i64>,
+    #[serde(default)]
+    pub prefs: Option<NotificationPrefs>,
+    /// Defaults to the actor's read cursor.
+    #[serde(default)]
+    pub since_cursor: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchFollowUpStatusParams {
+    pub event_id: String,
+}
+
+/// Without `target_id`, every target's summary grouped by region.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoChurchTargetSummaryParams {
+    #[serde(default)]
+    pub target_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchDeedsForTargetParams {
+    pub target_id: String,
+    #[serde(default)]
+    pub filter: TargetFilter,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoChurchPoolStatusParams {
+    /// Unix seconds to project from; defaults to the node clock.
+    #[serde(default)]
+    pub now: Option<i64>,
+}
+
+/// Resume point for `auto_church.get_deeds`: deeds after `after_hash`
+/// (from the start of the chain when absent), at most `limit` of them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoChurchGetDeedsParams {
+    #[serde(default)]
+    pub after_hash: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchGetDeedsResult {
+    #[serde(with = "deed_core::wire::vec")]
+    pub deeds: Vec<DeedEvent>,
+    /// Chain length and tip at the time of the call.
+    pub height: u64,
+    pub tip_hash: String,
+    /// More deeds follow the last one returned.
+    pub more: bool,
+    /// The serving node's deed schema version.
+    #[serde(default = "unadvertised_schema_version")]
+    pub schema_version: u32,
+}
+
+/// A page of `auto_church.get_chain`: at most `limit` deeds from
+/// `offset`, only `actor_id`'s when it is given.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutoChurchGetChainParams {
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub actor_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchGetChainResult {
+    #[serde(with = "deed_core::wire::vec")]
+    pub events: Vec<DeedEvent>,
+    pub tip_hash: String,
+    /// Deeds matching the filter across the whole chain.
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutoChurchGetAccountParams {
+    pub actor_id: String,
+}
+
+/// An actor's balances and record over its live deeds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoChurchAccountResult {
+    pub actor_id: String,
+    /// Non-zero balances only.
+    pub balances: BTreeMap<Token, u64>,
+    pub deeds: u64,
+    /// Deeds with no ethics flags and no life harm.
+    pub good_deeds: u64,
+    pub flagged: u64,
+    pub life_harm: u64,
+    pub standing: Standing,
+    /// 0.7 × the share of good deeds + 0.3 × (1 − harm), harm being the
+    /// flagged and life-harm deeds over 10, capped at 1.
+    pub eco_score: f64,
+}
+
+fn unadvertised_schema_version() -> u32 {
+    UNADVERTISED_SCHEMA_VERSION
 }
@@ -0,0 +1,721 @@
+//! Compact binary deed submission.
+//!
+//! Telemetry clients that submit a deed per HRV epoch or sensor window
+//! can skip JSON-RPC and send `CompactDeed` frames instead: no field
+//! names, raw 16-byte event ids and 32-byte hashes, and a batch frame that
+//! states the actor, deed type and chain link once. Decoded frames become
+//! ordinary `DeedEvent`s and go through the same minimization, validation
+//! and ledger append as `auto_church.mint_deed`; the canonical JSON hash
+//! stays the hash of record, so a chain never depends on the format its
+//! deeds arrived in.
+//!
+//! Encoding, version 1. Varints are unsigned LEB128 (at most ten bytes),
+//! `i64`s are zigzag varints, `f64`s are 8 bytes little-endian, a string
+//! is a varint byte length and UTF-8, a list is a varint count and its
+//! items, `context` is the deed context as JSON text behind a varint
+//! length, and a bool is one byte, 0 or 1.
+//!
+//! ```text
+//! frame   = version:u8 (1) kind:u8 payload
+//! deed    (kind 0x01) = event_id:[16] timestamp:i64 prev_hash:[32]
+//!                       actor_id:str deed_type:str body
+//! batch   (kind 0x02) = prev_hash:[32] actor_id:str deed_type:str
+//!                       count:varint (1..=MAX_BATCH_DEEDS)
+//!                       count * (event_id:[16] timestamp:i64 body)
+//! body    = target_ids:[str] tags:[str] context ethics_flags:[str]
+//!           life_harm_flag:bool bioload_delta:f64 roh:f64 decay:f64
+//! ```
+//!
+//! In a batch the header's `prev_hash` links the first deed; every later
+//! deed links to the canonical hash of the one before it. Replies use the
+//! same version byte: kind 0x81 lists one outcome per deed (0, hash:[32],
+//! church_minted:varint when accepted; 1, code:i64, message:str when
+//! rejected), kind 0x82 reports a malformed frame (offset:varint,
+//! message:str).
+//!
+//! On the TCP endpoint every frame, both ways, is preceded by its length
+//! as a 4-byte big-endian integer; frames over `MAX_FRAME_BYTES` are
+//! answered with a 0x82 reply and the connection is closed. Decoding
+//! never panics: malformed input is a `WireError` naming the byte offset.
+//! `tests/vectors/wire_v1.json` holds conformance vectors for encoders in
+//! other languages.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use log::{error, info, warn};
+use thiserror::Error;
+use tracing::info_span;
+use uuid::Uuid;
+
+use crate::compliance::data_minimization::MinimizationPolicy;
+use crate::compliance::validator::validate_deed;
+use crate::halt_review::{screen_submission, FreezeError, Screening, HIGH_IMPACT_FROZEN_CODE};
+use crate::ledger::deed_event::{hash_deed, validate_bioload_delta, DeedEvent, ExecutionDomain};
+use crate::ledger::metrics::BioloadMetrics;
+use crate::near_miss::{GUARD_DATA_MINIMIZATION, GUARD_DEED_VALIDATION, GUARD_LEDGER};
+use crate::providers::{alert_if_suspended, ProviderNotifier};
+use crate::quorum::hold_if_required;
+use crate::submission::{Transport, DUPLICATE_SUBMISSION_CODE};
+use crate::token::mint::mint_church;
+use crate::utils::time::now_timestamp;
+
+use super::server::{admit_submission, church_for, guard_rejected, RpcContext};
+
+pub const WIRE_VERSION: u8 = 1;
+pub const KIND_DEED: u8 = 0x01;
+pub const KIND_BATCH: u8 = 0x02;
+pub const KIND_ACCEPTED: u8 = 0x81;
+pub const KIND_MALFORMED: u8 = 0x82;
+/// Largest frame body accepted, in bytes.
+pub const MAX_FRAME_BYTES: usize = 1 << 20;
+/// Most deeds one batch frame may carry.
+pub const MAX_BATCH_DEEDS: usize = 256;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum WireErrorKind {
+    #[error("frame ends early")]
+    Truncated,
+    #[error("varint longer than 64 bits")]
+    VarintOverflow,
+    #[error("unsupported wire version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unknown frame kind {0:#04x}")]
+    UnknownKind(u8),
+    #[error("invalid UTF-8")]
+    InvalidUtf8,
+    #[error("invalid bool byte {0}")]
+    InvalidBool(u8),
+    #[error("invalid context JSON: {0}")]
+    InvalidContext(String),
+    #[error("batch of {0} deeds is empty or over the limit")]
+    BatchSize(u64),
+    #[error("trailing data ({0} bytes) after the frame")]
+    TrailingBytes(usize),
+    #[error("frame of {0} bytes exceeds the limit")]
+    FrameTooLarge(u64),
+}
+
+/// A malformed frame and the byte offset the decoder stopped at.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{kind} at byte {offset}")]
+pub struct WireError {
+    pub offset: usize,
+    pub kind: WireErrorKind,
+}
+
+/// Why a `DeedEvent` has no compact form.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum EncodeError {
+    #[error("event id {0} is not a UUID")]
+    EventId(String),
+    #[error("hash {0} is not 64 lowercase hex digits")]
+    Hash(String),
+    #[error("simulation deeds are not submitted over the wire")]
+    Simulation,
+    /// Wire version 1 has no room for `ext`; such deeds go over JSON-RPC.
+    #[error("deeds with ext fields are not submitted over the wire")]
+    Extensions,
+    #[error("batch of {0} deeds is empty or over the limit")]
+    BatchSize(usize),
+}
+
+/// The per-deed part of a frame, shared by single and batch frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeedBody {
+    pub event_id: Uuid,
+    pub timestamp: i64,
+    pub target_ids: Vec<String>,
+    pub tags: Vec<String>,
+    pub context_json: serde_json::Value,
+    pub ethics_flags: Vec<String>,
+    pub life_harm_flag: bool,
+    pub metrics: BioloadMetrics,
+}
+
+/// One deed with its chain link, actor and type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactDeed {
+    pub prev_hash: [u8; 32],
+    pub actor_id: String,
+    pub deed_type: String,
+    pub body: DeedBody,
+}
+
+/// Deeds of one actor and type, chained from `prev_hash`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactBatch {
+    pub prev_hash: [u8; 32],
+    pub actor_id: String,
+    pub deed_type: String,
+    pub deeds: Vec<DeedBody>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Deed(CompactDeed),
+    Batch(CompactBatch),
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hash_from_hex(hash: &str) -> Result<[u8; 32], EncodeError> {
+    let bad = || EncodeError::Hash(hash.to_string());
+    if hash.len() != 64 || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return Err(bad());
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hash[2 * i..2 * i + 2], 16).map_err(|_| bad())?;
+    }
+    Ok(out)
+}
+
+impl DeedBody {
+    fn of(deed: &DeedEvent, metrics: &BioloadMetrics) -> Result<Self, EncodeError> {
+        if !deed.domain.is_live() {
+            return Err(EncodeError::Simulation);
+        }
+        if !deed.ext.is_empty() {
+            return Err(EncodeError::Extensions);
+        }
+        Ok(Self {
+            event_id: Uuid::parse_str(&deed.event_id).map_err(|_| EncodeError::EventId(deed.event_id.clone()))?,
+            timestamp: deed.timestamp,
+            target_ids: deed.target_ids.clone(),
+            tags: deed.tags.clone(),
+            context_json: deed.context_json.clone(),
+            ethics_flags: deed.ethics_flags.clone(),
+            life_harm_flag: deed.life_harm_flag,
+            metrics: metrics.clone(),
+        })
+    }
+
+    /// The canonical deed, hashed as the JSON path hashes it.
+    fn into_deed(self, prev_hash: String, actor_id: String, deed_type: String) -> (DeedEvent, BioloadMetrics) {
+        let mut deed = DeedEvent {
+            event_id: self.event_id.to_string(),
+            timestamp: self.timestamp,
+            prev_hash,
+            self_hash: String::new(),
+            actor_id,
+            target_ids: self.target_ids,
+            deed_type,
+            tags: self.tags,
+            context_json: self.context_json,
+            ethics_flags: self.ethics_flags,
+            life_harm_flag: self.life_harm_flag,
+            domain: ExecutionDomain::Live,
+            ext: serde_json::Map::new(),
+        };
+        deed.self_hash = hash_deed(&deed);
+        (deed, self.metrics)
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        put_strings(out, &self.target_ids);
+        put_strings(out, &self.tags);
+        put_bytes(out, &serde_json::to_vec(&self.context_json).expect("context serializes"));
+        put_strings(out, &self.ethics_flags);
+        out.push(u8::from(self.life_harm_flag));
+        for v in [self.metrics.bioload_delta, self.metrics.roh, self.metrics.decay] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    fn decode(r: &mut Reader, event_id: Uuid, timestamp: i64) -> Result<Self, WireError> {
+        let target_ids = r.strings()?;
+        let tags = r.strings()?;
+        let at = r.pos;
+        let context_json =
+            serde_json::from_slice(r.bytes()?).map_err(|e| r.error_at(at, WireErrorKind::InvalidContext(e.to_string())))?;
+        let ethics_flags = r.strings()?;
+        let life_harm_flag = r.bool()?;
+        let metrics = BioloadMetrics::new(r.f64()?, r.f64()?, r.f64()?);
+        Ok(Self { event_id, timestamp, target_ids, tags, context_json, ethics_flags, life_harm_flag, metrics })
+    }
+}
+
+impl CompactDeed {
+    /// The compact form of a live deed; `self_hash` is not carried.
+    pub fn from_deed(deed: &DeedEvent, metrics: &BioloadMetrics) -> Result<Self, EncodeError> {
+        Ok(Self {
+            prev_hash: hash_from_hex(&deed.prev_hash)?,
+            actor_id: deed.actor_id.clone(),
+            deed_type: deed.deed_type.clone(),
+            body: DeedBody::of(deed, metrics)?,
+        })
+    }
+}
+
+impl CompactBatch {
+    /// Deeds of one actor and type, each linking to the one before it.
+    /// Only the first deed's `prev_hash` is carried.
+    pub fn from_deeds(deeds: &[(DeedEvent, BioloadMetrics)]) -> Result<Self, EncodeError> {
+        let Some((first, _)) = deeds.first().filter(|_| deeds.len() <= MAX_BATCH_DEEDS) else {
+            return Err(EncodeError::BatchSize(deeds.len()));
+        };
+        Ok(Self {
+            prev_hash: hash_from_hex(&first.prev_hash)?,
+            actor_id: first.actor_id.clone(),
+            deed_type: first.deed_type.clone(),
+            deeds: deeds.iter().map(|(d, m)| DeedBody::of(d, m)).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl Frame {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![WIRE_VERSION];
+        match self {
+            Frame::Deed(d) => {
+                out.push(KIND_DEED);
+                out.extend_from_slice(d.body.event_id.as_bytes());
+                put_varint(&mut out, zigzag(d.body.timestamp));
+                out.extend_from_slice(&d.prev_hash);
+                put_str(&mut out, &d.actor_id);
+                put_str(&mut out, &d.deed_type);
+                d.body.encode(&mut out);
+            }
+            Frame::Batch(b) => {
+                out.push(KIND_BATCH);
+                out.extend_from_slice(&b.prev_hash);
+                put_str(&mut out, &b.actor_id);
+                put_str(&mut out, &b.deed_type);
+                put_varint(&mut out, b.deeds.len() as u64);
+                for body in &b.deeds {
+                    out.extend_from_slice(body.event_id.as_bytes());
+                    put_varint(&mut out, zigzag(body.timestamp));
+                    body.encode(&mut out);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, WireError> {
+        let mut r = Reader { bytes, pos: 0 };
+        if bytes.len() > MAX_FRAME_BYTES {
+            return Err(r.error(WireErrorKind::FrameTooLarge(bytes.len() as u64)));
+        }
+        let version = r.byte()?;
+        if version != WIRE_VERSION {
+            return Err(r.error_at(0, WireErrorKind::UnsupportedVersion(version)));
+        }
+        let frame = match r.byte()? {
+            KIND_DEED => {
+                let event_id = r.uuid()?;
+                let timestamp = r.i64()?;
+                let prev_hash = r.hash()?;
+                let actor_id = r.string()?;
+                let deed_type = r.string()?;
+                let body = DeedBody::decode(&mut r, event_id, timestamp)?;
+                Frame::Deed(CompactDeed { prev_hash, actor_id, deed_type, body })
+            }
+            KIND_BATCH => {
+                let prev_hash = r.hash()?;
+                let actor_id = r.string()?;
+                let deed_type = r.string()?;
+                let at = r.pos;
+                let count = r.varint()?;
+                if count == 0 || count > MAX_BATCH_DEEDS as u64 {
+                    return Err(r.error_at(at, WireErrorKind::BatchSize(count)));
+                }
+                let mut deeds = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let event_id = r.uuid()?;
+                    let timestamp = r.i64()?;
+                    deeds.push(DeedBody::decode(&mut r, event_id, timestamp)?);
+                }
+                Frame::Batch(CompactBatch { prev_hash, actor_id, deed_type, deeds })
+            }
+            kind => return Err(r.error_at(1, WireErrorKind::UnknownKind(kind))),
+        };
+        match bytes.len() - r.pos {
+            0 => Ok(frame),
+            extra => Err(r.error(WireErrorKind::TrailingBytes(extra))),
+        }
+    }
+
+    /// The canonical deeds, in order, with their metrics.
+    pub fn into_deeds(self) -> Vec<(DeedEvent, BioloadMetrics)> {
+        match self {
+            Frame::Deed(d) => vec![d.body.into_deed(to_hex(&d.prev_hash), d.actor_id, d.deed_type)],
+            Frame::Batch(b) => {
+                let mut prev_hash = to_hex(&b.prev_hash);
+                let mut out = Vec::with_capacity(b.deeds.len());
+                for body in b.deeds {
+                    let (deed, metrics) = body.into_deed(prev_hash, b.actor_id.clone(), b.deed_type.clone());
+                    prev_hash = deed.self_hash.clone();
+                    out.push((deed, metrics));
+                }
+                out
+            }
+        }
+    }
+}
+
+/// What happened to one submitted deed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeedOutcome {
+    Accepted {
+        self_hash: String,
+        /// Zero while the deed awaits a validation quorum, or when it was
+        /// queued for review under a freeze; `self_hash` is then the hash
+        /// as submitted.
+        church_minted: u64,
+    },
+    /// `code` is the JSON-RPC error code `auto_church.mint_deed` would give.
+    Rejected {
+        code: i64,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WireReply {
+    Outcomes(Vec<DeedOutcome>),
+    Malformed { offset: usize, message: String },
+}
+
+impl WireReply {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![WIRE_VERSION];
+        match self {
+            WireReply::Outcomes(outcomes) => {
+                out.push(KIND_ACCEPTED);
+                put_varint(&mut out, outcomes.len() as u64);
+                for outcome in outcomes {
+                    match outcome {
+                        DeedOutcome::Accepted { self_hash, church_minted } => {
+                            out.push(0);
+                            out.extend_from_slice(&hash_from_hex(self_hash).expect("canonical hashes are hex"));
+                            put_varint(&mut out, *church_minted);
+                        }
+                        DeedOutcome::Rejected { code, message } => {
+                            out.push(1);
+                            put_varint(&mut out, zigzag(*code));
+                            put_str(&mut out, message);
+                        }
+                    }
+                }
+            }
+            WireReply::Malformed { offset, message } => {
+                out.push(KIND_MALFORMED);
+                put_varint(&mut out, *offset as u64);
+                put_str(&mut out, message);
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, WireError> {
+        let mut r = Reader { bytes, pos: 0 };
+        let version = r.byte()?;
+        if version != WIRE_VERSION {
+            return Err(r.error_at(0, WireErrorKind::UnsupportedVersion(version)));
+        }
+        let reply = match r.byte()? {
+            KIND_ACCEPTED => {
+                let count = r.count()?;
+                let mut outcomes = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let at = r.pos;
+                    outcomes.push(match r.byte()? {
+                        0 => DeedOutcome::Accepted { self_hash: to_hex(&r.hash()?), church_minted: r.varint()? },
+                        1 => DeedOutcome::Rejected { code: r.i64()?, message: r.string()? },
+                        b => return Err(r.error_at(at, WireErrorKind::InvalidBool(b))),
+                    });
+                }
+                WireReply::Outcomes(outcomes)
+            }
+            KIND_MALFORMED => WireReply::Malformed { offset: r.varint()? as usize, message: r.string()? },
+            kind => return Err(r.error_at(1, WireErrorKind::UnknownKind(kind))),
+        };
+        match bytes.len() - r.pos {
+            0 => Ok(reply),
+            extra => Err(r.error(WireErrorKind::TrailingBytes(extra))),
+        }
+    }
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+fn unzigzag(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+fn put_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    put_bytes(out, s.as_bytes());
+}
+
+fn put_strings(out: &mut Vec<u8>, items: &[String]) {
+    put_varint(out, items.len() as u64);
+    for s in items {
+        put_str(out, s);
+    }
+}
+
+/// Bounds-checked cursor over a frame body.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, kind: WireErrorKind) -> WireError {
+        self.error_at(self.pos, kind)
+    }
+
+    fn error_at(&self, offset: usize, kind: WireErrorKind) -> WireError {
+        WireError { offset, kind }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], WireError> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.bytes.len()).ok_or(self.error(WireErrorKind::Truncated))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn byte(&mut self) -> Result<u8, WireError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, WireError> {
+        let start = self.pos;
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            if shift == 63 && b > 1 {
+                return Err(self.error_at(start, WireErrorKind::VarintOverflow));
+            }
+            v |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(self.error_at(start, WireErrorKind::VarintOverflow))
+    }
+
+    fn i64(&mut self) -> Result<i64, WireError> {
+        Ok(unzigzag(self.varint()?))
+    }
+
+    /// A varint count or length no larger than the bytes left, so a
+    /// corrupt count never drives a large allocation.
+    fn count(&mut self) -> Result<usize, WireError> {
+        let at = self.pos;
+        let n = self.varint()?;
+        if n > (self.bytes.len() - self.pos) as u64 {
+            return Err(self.error_at(at, WireErrorKind::Truncated));
+        }
+        Ok(n as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], WireError> {
+        let n = self.count()?;
+        self.take(n)
+    }
+
+    fn string(&mut self) -> Result<String, WireError> {
+        let at = self.pos;
+        let raw = self.bytes()?;
+        std::str::from_utf8(raw).map(str::to_string).map_err(|_| self.error_at(at, WireErrorKind::InvalidUtf8))
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, WireError> {
+        (0..self.count()?).map(|_| self.string()).collect()
+    }
+
+    fn bool(&mut self) -> Result<bool, WireError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(self.error_at(self.pos - 1, WireErrorKind::InvalidBool(b))),
+        }
+    }
+
+    fn f64(&mut self) -> Result<f64, WireError> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().expect("eight bytes")))
+    }
+
+    fn hash(&mut self) -> Result<[u8; 32], WireError> {
+        Ok(self.take(32)?.try_into().expect("32 bytes"))
+    }
+
+    fn uuid(&mut self) -> Result<Uuid, WireError> {
+        Ok(Uuid::from_bytes(self.take(16)?.try_into().expect("16 bytes")))
+    }
+}
+
+/// Minimize, validate and (with a ledger) append one decoded deed, with
+/// the error codes, guard observations and duplicate window of
+/// `auto_church.mint_deed`. Frames carry no idempotency key; duplicates
+/// are matched on content.
+fn submit(deed: DeedEvent, metrics: BioloadMetrics, ctx: &RpcContext) -> DeedOutcome {
+    let rejected = |code: i64, message: String| DeedOutcome::Rejected { code, message };
+    let deed = match MinimizationPolicy::default().enforce(deed) {
+        Ok(deed) => deed,
+        Err(e) => {
+            guard_rejected(ctx, GUARD_DATA_MINIMIZATION);
+            return rejected(1002, e.to_string());
+        }
+    };
+    if let Err(e) = validate_deed(&deed, metrics.roh, metrics.decay).and_then(|()| validate_bioload_delta(metrics.bioload_delta)) {
+        guard_rejected(ctx, GUARD_DEED_VALIDATION);
+        return rejected(1001, e.to_string());
+    }
+    let admission = match admit_submission(ctx, &deed, None, Transport::Binary) {
+        Ok(admission) => admission,
+        Err(duplicate) => return rejected(DUPLICATE_SUBMISSION_CODE, duplicate.to_string()),
+    };
+    let (deed, held) = match &ctx.ledger {
+        Some(ledger) => {
+            let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+            match screen_submission(&mut ledger, &deed, &metrics) {
+                Ok(Screening::Proceed) => {}
+                Ok(Screening::Queued { .. }) => {
+                    if let Some(admission) = admission {
+                        admission.stored();
+                    }
+                    return DeedOutcome::Accepted { church_minted: 0, self_hash: deed.self_hash };
+                }
+                Err(e @ FreezeError::Frozen { .. }) => return rejected(HIGH_IMPACT_FROZEN_CODE, e.to_string()),
+                Err(e) => {
+                    drop(ledger);
+                    guard_rejected(ctx, GUARD_LEDGER);
+                    return rejected(1005, e.to_string());
+                }
+            }
+            let stored = match ledger.append(deed) {
+                Ok(stored) => stored.clone(),
+                Err(e) => {
+                    drop(ledger);
+                    guard_rejected(ctx, GUARD_LEDGER);
+                    return rejected(1005, e.to_string());
+                }
+            };
+            let notifier = ledger.config().providers.notifier();
+            alert_if_suspended(&ledger, &stored, notifier.as_ref().map(|n| n as &dyn ProviderNotifier));
+            let reward = mint_church(&stored, &metrics, ledger.config());
+            match hold_if_required(&mut ledger, &stored.event_id, reward, now_timestamp()) {
+                Ok(pending) => (stored, pending.is_some()),
+                Err(e) => {
+                    if let Some(admission) = admission {
+                        admission.stored();
+                    }
+                    drop(ledger);
+                    guard_rejected(ctx, GUARD_LEDGER);
+                    return rejected(1005, e.to_string());
+                }
+            }
+        }
+        None => (deed, false),
+    };
+    if let Some(admission) = admission {
+        admission.stored();
+    }
+    let church_minted = if held { 0 } else { church_for(ctx, &deed, &metrics) };
+    DeedOutcome::Accepted { church_minted, self_hash: deed.self_hash }
+}
+
+/// Decode one frame body and submit its deeds in order. A deed rejected
+/// mid-batch leaves the ones after it unlinked, so the ledger refuses them.
+pub fn submit_frame(body: &[u8], ctx: &RpcContext) -> WireReply {
+    let _span = info_span!("wire_frame", bytes = body.len()).entered();
+    match Frame::decode(body) {
+        Ok(frame) => WireReply::Outcomes(frame.into_deeds().into_iter().map(|(d, m)| submit(d, m, ctx)).collect()),
+        Err(e) => {
+            warn!("Malformed wire frame: {}", e);
+            WireReply::Malformed { offset: e.offset, message: e.kind.to_string() }
+        }
+    }
+}
+
+/// Read one length-prefixed frame body; `None` on a clean end of stream.
+/// A length over `MAX_FRAME_BYTES` is refused before anything is read.
+pub fn read_frame(r: &mut impl Read) -> io::Result<Option<Result<Vec<u8>, WireError>>> {
+    let mut len = [0u8; 4];
+    match r.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Ok(Some(Err(WireError { offset: 0, kind: WireErrorKind::FrameTooLarge(len as u64) })));
+    }
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+    Ok(Some(Ok(body)))
+}
+
+/// Write `body` behind its 4-byte big-endian length.
+pub fn write_frame(w: &mut impl Write, body: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(body.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    w.write_all(&len.to_be_bytes())?;
+    w.write_all(body)
+}
+
+/// Start the length-prefixed binary submission endpoint.
+pub fn start_wire_server_with(addr: &str, ctx: RpcContext) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    info!("Binary deed endpoint listening on {}", addr);
+    serve_wire(listener, ctx);
+    Ok(())
+}
+
+/// Serve binary submissions on an already bound listener.
+pub fn serve_wire(listener: TcpListener, ctx: RpcContext) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let ctx = ctx.clone();
+                thread::spawn(move || handle_wire_client(stream, &ctx));
+            }
+            Err(e) => error!("Wire accept error: {}", e),
+        }
+    }
+}
+
+fn handle_wire_client(mut stream: TcpStream, ctx: &RpcContext) {
+    let peer = stream.peer_addr().ok();
+    loop {
+        let (reply, close) = match read_frame(&mut stream) {
+            Ok(None) => break,
+            Ok(Some(Ok(body))) => (submit_frame(&body, ctx), false),
+            Ok(Some(Err(e))) => (WireReply::Malformed { offset: e.offset, message: e.kind.to_string() }, true),
+            Err(e) => {
+                error!("Wire read error from {:?}: {}", peer, e);
+                break;
+            }
+        };
+        if let Err(e) = write_frame(&mut stream, &reply.encode()) {
+            error!("Wire write error to {:?}: {}", peer, e);
+            break;
+        }
+        if close {
+            warn!("Closing wire connection from {:?} after an oversized frame", peer);
+            break;
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Recurring maintenance jobs run from the node's main loop. Each run gets
+//! its own correlation id and `job` span. Ticks with nothing due are spent
+//! on one slice of the integrity self-audit.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::audit::{AuditTick, SelfAuditor, SelfBudget};
+use crate::ledger::token_ledger::TokenLedger;
+use crate::near_miss::{send_digest, WebhookNearMissNotifier};
+use crate::obligations::sweep_missed;
+use crate::providers::{sweep_outcomes, ProviderNotifier, WebhookProviderNotifier};
+use crate::quorum::sweep_expired;
+use crate::report::write_monthly_report;
+use crate::sponsor::equity::reward_equity_report;
+use crate::utils::correlation::CorrelationId;
+
+pub const SELF_AUDIT: &str = "self_audit";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MaintenanceJob {
+    /// Remove `rate` of every FEAR balance.
+    DecayFear { rate: f64 },
+    /// Close follow-up obligations past their deadline.
+    SweepObligations,
+    /// Expire validations that did not reach their quorum in time.
+    ExpireValidations,
+    /// Post the open near-miss digest to `url`.
+    NearMissDigest { url: String },
+    /// Write last month's stewardship report into `dir` once it is over.
+    StewardshipReport { dir: String, html: bool },
+    /// Score providers on their settled attestations, alerting `url` (if
+    /// any) of suspensions.
+    ScoreProviders { url: Option<String> },
+    /// Log each equity class's share of recent reward plans.
+    RewardEquityReport,
+    /// Compare the POWER Gini histograms with an exact pass.
+    CheckPowerGini,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringJob {
+    pub name: String,
+    pub every_secs: u64,
+    /// Unix seconds.
+    pub next_due: i64,
+    pub job: MaintenanceJob,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RecurringJobs {
+    jobs: Vec<RecurringJob>,
+}
+
+impl RecurringJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The jobs every node runs, configured from the ledger's config.
+    pub fn with_defaults(ledger: &TokenLedger, now: i64) -> Self {
+        let cfg = ledger.config();
+        let mut jobs = Self::new();
+        jobs.add("decay_fear", cfg.fear_decay_every_secs, MaintenanceJob::DecayFear { rate: cfg.fear_decay_rate }, now);
+        jobs.add("sweep_obligations", cfg.obligations.sweep_every_secs, MaintenanceJob::SweepObligations, now);
+        jobs.add("expire_validations", cfg.quorum.sweep_every_secs, MaintenanceJob::ExpireValidations, now);
+        let job = MaintenanceJob::ScoreProviders { url: cfg.providers.alert_webhook.clone() };
+        jobs.add("score_providers", cfg.providers.sweep_every_secs, job, now);
+        jobs.add("reward_equity_report", cfg.reward_equity.report_every_secs, MaintenanceJob::RewardEquityReport, now);
+        jobs.add("check_power_gini", cfg.concentration.exact_every_secs, MaintenanceJob::CheckPowerGini, now);
+        if let Some(url) = &cfg.near_miss.digest_webhook {
+            jobs.add("near_miss_digest", cfg.near_miss.digest_every_secs, MaintenanceJob::NearMissDigest { url: url.clone() }, now);
+        }
+        if let Some(dir) = &cfg.report.dir {
+            let job = MaintenanceJob::StewardshipReport { dir: dir.clone(), html: cfg.report.html };
+            jobs.add("stewardship_report", cfg.report.check_every_secs, job, now);
+        }
+        jobs
+    }
+
+    pub fn add(&mut self, name: &str, every_secs: u64, job: MaintenanceJob, now: i64) {
+        self.jobs.push(RecurringJob { name: name.to_string(), every_secs: every_secs.max(1), next_due: now + every_secs.max(1) as i64, job });
+    }
+
+    pub fn jobs(&self) -> &[RecurringJob] {
+        &self.jobs
+    }
+
+    /// Run every job due at `now` once and reschedule it. Returns the names run.
+    pub fn run_due(&mut self, ledger: &mut TokenLedger, now: i64) -> Vec<String> {
+        let mut ran = Vec::new();
+        for job in self.jobs.iter_mut().filter(|j| j.next_due <= now) {
+            let correlation = CorrelationId::generate();
+            let _scope = correlation.enter();
+            let _span = info_span!("job", job = %job.name, correlation_id = %correlation).entered();
+            match &job.job {
+                MaintenanceJob::DecayFear { rate } => match ledger.decay_fear(*rate) {
+                    Ok(retired) => info!("{}: retired {} FEAR", job.name, retired),
+                    Err(e) => warn!("{}: {}", job.name, e),
+                },
+                MaintenanceJob::SweepObligations => match sweep_missed(ledger, now) {
+                    Ok(swept) => info!("{}: swept {} missed obligations", job.name, swept.len()),
+                    Err(e) => warn!("{}: {}", job.name, e),
+                },
+                MaintenanceJob::ExpireValidations => match sweep_expired(ledger, now) {
+                    Ok(expired) => info!("{}: expired {} stalled validations", job.name, expired.len()),
+                    Err(e) => warn!("{}: {}", job.name, e),
+                },
+                MaintenanceJob::NearMissDigest { url } => {
+                    match send_digest(ledger, &WebhookNearMissNotifier { url: url.clone() }, now) {
+                        Ok(open) => info!("{}: {} open near misses", job.name, open),
+                        Err(e) => warn!("{}: {}", job.name, e),
+                    }
+                }
+                MaintenanceJob::ScoreProviders { url } => {
+                    let notifier = url.clone().map(|url| WebhookProviderNotifier { url });
+                    match sweep_outcomes(ledger, now, notifier.as_ref().map(|n| n as &dyn ProviderNotifier)) {
+                        Ok(updates) => info!("{}: recorded {} attestation outcomes", job.name, updates.len()),
+                        Err(e) => warn!("{}: {}", job.name, e),
+                    }
+                }
+                MaintenanceJob::RewardEquityReport => {
+                    let report = reward_equity_report(ledger, now);
+                    let shares: Vec<String> =
+                        report.classes.iter().map(|c| format!("{} {:.1}%", c.class, c.share * 100.0)).collect();
+                    info!(
+                        "{}: {} plans, {} planned, gini {:.3}; {}",
+                        job.name,
+                        report.plans,
+                        report.total,
+                        report.gini,
+                        shares.join(", ")
+                    );
+                }
+                MaintenanceJob::CheckPowerGini => {
+                    let check = ledger.check_power_gini();
+                    info!(
+                        "{}: power gini {:.4} (all accounts {:.4}), drift {:.5}{}",
+                        job.name,
+                        check.exact.filtered,
+                        check.exact.unfiltered,
+                        check.drift,
+                        if check.rebuilt { ", histograms rebuilt" } else { "" }
+                    );
+                }
+                MaintenanceJob::StewardshipReport { dir, html } => {
+                    match write_monthly_report(ledger, std::path::Path::new(dir), *html, now) {
+                        Ok(Some(path)) => info!("{}: wrote {}", job.name, path.display()),
+                        Ok(None) => {}
+                        Err(e) => warn!("{}: {}", job.name, e),
+                    }
+                }
+            }
+            job.next_due = now + job.every_secs as i64;
+            ran.push(job.name.clone());
+        }
+        ran
+    }
+
+    /// `run_due`, or, when nothing is due, one self-audit slice of the
+    /// in-memory log paced by `budget`.
+    pub fn tick(
+        &mut self,
+        ledger: &mut TokenLedger,
+        auditor: &mut SelfAuditor,
+        budget: &mut SelfBudget,
+        now: i64,
+    ) -> Vec<String> {
+        let ran = self.run_due(ledger, now);
+        if !ran.is_empty() {
+            return ran;
+        }
+        let correlation = CorrelationId::generate();
+        let _scope = correlation.enter();
+        let _span = info_span!("job", job = SELF_AUDIT, correlation_id = %correlation).entered();
+        match auditor.idle_tick_in_memory(ledger, budget, now) {
+            Ok(AuditTick::Skipped { needed_joules, remaining_joules }) => {
+                info!("{}: skipped, slice needs {} J of {} J left", SELF_AUDIT, needed_joules, remaining_joules)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("{}: {}", SELF_AUDIT, e),
+        }
+        vec![SELF_AUDIT.to_string()]
+    }
+}
@@ -0,0 +1,168 @@
+//! Simulation runs, kept apart from the live ledger.
+//!
+//! A deed arriving on a simulation route (`AUTO_CHURCH_SIM`) or from a
+//! behavior whose governance autonomy tier is `SimulationOnly` is forced
+//! into `ExecutionDomain::Simulation`. Each run is its own sub-chain rooted
+//! in a `sim_run_open` deed that records the live tip it branched from, and
+//! every token effect inside it lands on shadow balances the live
+//! `SupplyReport` never sees. `TokenLedger::append` refuses simulation
+//! deeds outright.
+//!
+//! Results reach the live chain only through `TokenLedger::promote_sim_run`,
+//! which a correction role must approve and which logs a single
+//! `sim_run_promoted` summary deed; the raw simulation deeds stay in their
+//! sub-chain.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cooldown::is_suppressed;
+use crate::ledger::account::{Account, Token};
+use crate::ledger::deed_event::{DeedEvent, ExecutionDomain};
+use crate::ledger::token_ledger::{SupplyReport, TokenLedger, TokenLedgerError, TokenSupply};
+
+pub const SIM_RUN_OPEN: &str = "sim_run_open";
+pub const SIM_RUN_PROMOTED: &str = "sim_run_promoted";
+
+/// Ingress routes whose deeds always execute in simulation.
+pub const SIM_ROUTES: [&str; 1] = ["AUTO_CHURCH_SIM"];
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SimulationError {
+    #[error("route {0} only accepts simulation deeds and needs a run id")]
+    RunRequired(String),
+    #[error("simulation deed for run {run_id} arrived on live route {route}")]
+    SimulationOnLiveRoute { route: String, run_id: String },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Where a deed arriving on `route` executes. `simulation_only` is set for
+/// behaviors at the `SimulationOnly` autonomy tier; either that or a
+/// simulation route forces the deed into run `run_id`.
+pub fn domain_for(route: &str, simulation_only: bool, run_id: Option<&str>) -> Result<ExecutionDomain, SimulationError> {
+    if !simulation_only && !SIM_ROUTES.contains(&route) {
+        return Ok(ExecutionDomain::Live);
+    }
+    match run_id {
+        Some(run_id) => Ok(ExecutionDomain::Simulation { run_id: run_id.to_string() }),
+        None => Err(SimulationError::RunRequired(route.to_string())),
+    }
+}
+
+/// Route `deed` to the live chain or to its simulation run. A deed already
+/// tagged as simulation is rejected on a live route rather than promoted.
+pub fn ingest<'a>(
+    ledger: &'a mut TokenLedger,
+    deed: DeedEvent,
+    route: &str,
+    simulation_only: bool,
+    run_id: Option<&str>,
+) -> Result<&'a DeedEvent, SimulationError> {
+    match domain_for(route, simulation_only, run_id.or(deed.domain.run_id()))? {
+        ExecutionDomain::Live => {
+            if let Some(run_id) = deed.domain.run_id() {
+                return Err(SimulationError::SimulationOnLiveRoute { route: route.to_string(), run_id: run_id.to_string() });
+            }
+            Ok(ledger.append(deed)?)
+        }
+        ExecutionDomain::Simulation { run_id } => Ok(ledger.append_sim(&run_id, deed)?),
+    }
+}
+
+/// One simulation run: its sub-chain and the shadow balances it moved.
+#[derive(Debug, Clone)]
+pub struct SimRun {
+    pub(crate) run_id: String,
+    pub(crate) deeds: Vec<DeedEvent>,
+    pub(crate) accounts: BTreeMap<String, Account>,
+    pub(crate) issued: BTreeMap<Token, u64>,
+    pub(crate) retired: BTreeMap<Token, u64>,
+    /// Event id of the live `sim_run_promoted` deed, once promoted.
+    pub(crate) promoted: Option<String>,
+}
+
+impl SimRun {
+    pub(crate) fn new(run_id: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            deeds: Vec::new(),
+            accounts: BTreeMap::new(),
+            issued: BTreeMap::new(),
+            retired: BTreeMap::new(),
+            promoted: None,
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// The sub-chain, starting with its `sim_run_open` root.
+    pub fn deeds(&self) -> &[DeedEvent] {
+        &self.deeds
+    }
+
+    pub fn tip_hash(&self) -> String {
+        self.deeds.last().map(|d| d.self_hash.clone()).unwrap_or_default()
+    }
+
+    pub fn promoted(&self) -> Option<&str> {
+        self.promoted.as_deref()
+    }
+
+    pub fn shadow_account(&self, id: &str) -> Option<&Account> {
+        self.accounts.get(id)
+    }
+
+    /// Shadow supply; it reconciles on its own and never enters the live report.
+    pub fn supply_report(&self) -> SupplyReport {
+        let tokens = Token::ALL
+            .iter()
+            .map(|&token| {
+                let supply = TokenSupply {
+                    issued: self.issued.get(&token).copied().unwrap_or(0),
+                    retired: self.retired.get(&token).copied().unwrap_or(0),
+                    circulating: self.accounts.values().map(|a| a.balance(token)).sum(),
+                };
+                (token, supply)
+            })
+            .collect();
+        SupplyReport { accounts: self.accounts.len(), tokens }
+    }
+
+    /// What `sim_run_promoted` copies into the live chain.
+    pub fn summary(&self) -> SimRunSummary {
+        let mut deed_types = BTreeMap::new();
+        for d in &self.deeds[1.min(self.deeds.len())..] {
+            *deed_types.entry(d.deed_type.clone()).or_insert(0) += 1;
+        }
+        SimRunSummary {
+            run_id: self.run_id.clone(),
+            root_event_id: self.deeds.first().map(|d| d.event_id.clone()).unwrap_or_default(),
+            tip_hash: self.tip_hash(),
+            deeds: self.deeds.len(),
+            deed_types,
+            cooldown_suppressed: self.deeds.iter().filter(|d| is_suppressed(d)).count(),
+            shadow_supply: self.supply_report(),
+        }
+    }
+}
+
+/// Aggregate outcome of a simulation run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimRunSummary {
+    pub run_id: String,
+    pub root_event_id: String,
+    pub tip_hash: String,
+    /// Sub-chain length, root included.
+    pub deeds: usize,
+    /// Deed counts by type, root excluded.
+    pub deed_types: BTreeMap<String, usize>,
+    /// Deeds logged during their category's cooldown, which minted nothing.
+    #[serde(default)]
+    pub cooldown_suppressed: usize,
+    pub shadow_supply: SupplyReport,
+}
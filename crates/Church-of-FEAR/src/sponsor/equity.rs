@@ -0,0 +1,422 @@
+//! Per-equity-class bounds on sponsor reward plans.
+//!
+//! Left alone, a reward plan pays whichever accounts the metrics highlight,
+//! and one cluster of accounts can end up with most of the minted CHURCH.
+//! `rebalance_rewards` resolves every planned reward's account to its
+//! equity class and holds each class between a floor and a ceiling share
+//! of the tick's total: classes over their ceiling are trimmed, classes
+//! under their floor are raised, and the remainder is shared by the other
+//! classes in proportion to what they were planned. Only classes with at
+//! least one planned reward take part, so a class with no qualifying deeds
+//! never pulls a floor out of the others. Every reward's reason records
+//! what happened to its class.
+//!
+//! `record_plan` keeps the rebalanced plan as a `reward_plan` deed, and
+//! `reward_equity_report` sums those deeds over a trailing window into
+//! per-class shares and a Gini coefficient over accounts.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use crate::ledger::concentration::gini;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::sponsor::pool::PlannedReward;
+
+pub const REWARD_PLAN: &str = "reward_plan";
+
+/// Share of a tick's planned rewards a class may receive, as fractions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassBounds {
+    pub min_fraction: f64,
+    pub max_fraction: f64,
+}
+
+impl Default for ClassBounds {
+    fn default() -> Self {
+        Self { min_fraction: 0.0, max_fraction: 1.0 }
+    }
+}
+
+/// Bounds per class; classes not listed are unbounded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardEquitySpec {
+    pub classes: BTreeMap<String, ClassBounds>,
+}
+
+impl RewardEquitySpec {
+    pub fn bounds(&self, class: &str) -> ClassBounds {
+        self.classes.get(class).copied().unwrap_or_default()
+    }
+
+    /// Every class needs `0 <= min <= max <= 1`, and the floors together
+    /// may not ask for more than the whole plan.
+    pub fn validate(&self) -> Result<(), RewardEquityError> {
+        for (class, b) in &self.classes {
+            if !(0.0 <= b.min_fraction && b.min_fraction <= b.max_fraction && b.max_fraction <= 1.0) {
+                return Err(RewardEquityError::InvalidBounds {
+                    class: class.clone(),
+                    min: b.min_fraction,
+                    max: b.max_fraction,
+                });
+            }
+        }
+        let floors: f64 = self.classes.values().map(|b| b.min_fraction).sum();
+        if floors > 1.0 + TOLERANCE {
+            return Err(RewardEquityError::FloorsExceedPlan(floors));
+        }
+        Ok(())
+    }
+}
+
+/// Which equity class each rewarded account belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EquityClassRegistry {
+    /// Account id to class name.
+    pub members: BTreeMap<String, String>,
+    /// Class of accounts with no entry.
+    pub default_class: String,
+}
+
+impl Default for EquityClassRegistry {
+    fn default() -> Self {
+        Self { members: BTreeMap::new(), default_class: "unclassified".to_string() }
+    }
+}
+
+impl EquityClassRegistry {
+    pub fn assign(&mut self, account_id: &str, class: &str) {
+        self.members.insert(account_id.to_string(), class.to_string());
+    }
+
+    pub fn class_of(&self, account_id: &str) -> &str {
+        self.members.get(account_id).map_or(self.default_class.as_str(), String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardEquityPolicy {
+    pub registry: EquityClassRegistry,
+    pub spec: RewardEquitySpec,
+    /// Trailing window of the reward-equity report.
+    pub report_window_secs: i64,
+    pub report_every_secs: u64,
+}
+
+impl Default for RewardEquityPolicy {
+    fn default() -> Self {
+        Self {
+            registry: EquityClassRegistry::default(),
+            spec: RewardEquitySpec::default(),
+            report_window_secs: 30 * 86_400,
+            report_every_secs: 86_400,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RewardEquityError {
+    #[error("class {class:?} bounds {min}..{max} are not within 0 <= min <= max <= 1")]
+    InvalidBounds { class: String, min: f64, max: f64 },
+    #[error("class floors add up to {0}, more than the whole plan")]
+    FloorsExceedPlan(f64),
+}
+
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassAdjustment {
+    Unchanged,
+    TrimmedToCeiling,
+    RaisedToFloor,
+    /// Within its bounds, but moved to make room for other classes'.
+    Redistributed,
+}
+
+/// What rebalancing did to one class's share of the plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassDecision {
+    pub class: String,
+    pub planned: u64,
+    pub rebalanced: u64,
+    pub min_fraction: f64,
+    pub max_fraction: f64,
+    pub adjustment: ClassAdjustment,
+}
+
+fn percent(amount: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        amount as f64 * 100.0 / total as f64
+    }
+}
+
+impl ClassDecision {
+    /// The rationale appended to each of the class's rewards.
+    pub fn note(&self, total: u64) -> String {
+        let (planned, now) = (percent(self.planned, total), percent(self.rebalanced, total));
+        let (floor, ceiling) = (self.min_fraction * 100.0, self.max_fraction * 100.0);
+        let what = match self.adjustment {
+            ClassAdjustment::Unchanged => format!("kept at {:.1}%, within {:.1}%-{:.1}%", now, floor, ceiling),
+            ClassAdjustment::TrimmedToCeiling => format!("trimmed from {:.1}% to its {:.1}% ceiling", planned, ceiling),
+            ClassAdjustment::RaisedToFloor => format!("raised from {:.1}% to its {:.1}% floor", planned, floor),
+            ClassAdjustment::Redistributed => {
+                format!("moved from {:.1}% to {:.1}% to make room for other classes' bounds", planned, now)
+            }
+        };
+        format!("equity class {} {} (planned {}, now {})", self.class, what, self.planned, self.rebalanced)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassedReward {
+    pub class: String,
+    #[serde(flatten)]
+    pub reward: PlannedReward,
+}
+
+/// A reward plan after equity rebalancing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPlan {
+    /// Total of the plan as it came in.
+    pub total: u64,
+    /// In the order of the incoming plan.
+    pub rewards: Vec<ClassedReward>,
+    /// One per class with a planned reward, by class name.
+    pub classes: Vec<ClassDecision>,
+    /// Trimmed from classes at their ceiling with nowhere else to go:
+    /// every class in the plan was at its ceiling.
+    pub unplaced: u64,
+}
+
+impl EquityPlan {
+    /// The rewards to hand to `SponsorPool::fund_plan`.
+    pub fn planned(&self) -> Vec<PlannedReward> {
+        self.rewards.iter().map(|r| r.reward.clone()).collect()
+    }
+
+    pub fn total_rebalanced(&self) -> u64 {
+        self.rewards.iter().map(|r| r.reward.amount).sum()
+    }
+}
+
+/// Hold every class in `plan` between its floor and ceiling share; see the
+/// module docs.
+pub fn rebalance_rewards(
+    plan: &[PlannedReward],
+    registry: &EquityClassRegistry,
+    spec: &RewardEquitySpec,
+) -> Result<EquityPlan, RewardEquityError> {
+    spec.validate()?;
+    let total: u64 = plan.iter().map(|r| r.amount).sum();
+    let mut members: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, reward) in plan.iter().enumerate() {
+        members.entry(registry.class_of(&reward.account_id)).or_default().push(i);
+    }
+    let planned: BTreeMap<&str, u64> =
+        members.iter().map(|(class, idx)| (*class, idx.iter().map(|&i| plan[i].amount).sum())).collect();
+    let targets = class_targets(&planned, spec, total);
+
+    let mut amounts = vec![0u64; plan.len()];
+    let mut classes = Vec::with_capacity(members.len());
+    for (class, idx) in &members {
+        let target = targets[class];
+        let weights: Vec<u64> = idx.iter().map(|&i| plan[i].amount).collect();
+        for (&i, amount) in idx.iter().zip(split(target, &weights)) {
+            amounts[i] = amount;
+        }
+        let bounds = spec.bounds(class);
+        let share = |amount: u64| if total == 0 { 0.0 } else { amount as f64 / total as f64 };
+        let adjustment = if target == planned[class] {
+            ClassAdjustment::Unchanged
+        } else if share(planned[class]) > bounds.max_fraction + TOLERANCE {
+            ClassAdjustment::TrimmedToCeiling
+        } else if share(planned[class]) < bounds.min_fraction - TOLERANCE {
+            ClassAdjustment::RaisedToFloor
+        } else {
+            ClassAdjustment::Redistributed
+        };
+        classes.push(ClassDecision {
+            class: class.to_string(),
+            planned: planned[class],
+            rebalanced: target,
+            min_fraction: bounds.min_fraction,
+            max_fraction: bounds.max_fraction,
+            adjustment,
+        });
+    }
+
+    let notes: BTreeMap<&str, String> = classes.iter().map(|d| (d.class.as_str(), d.note(total))).collect();
+    let rewards = plan
+        .iter()
+        .zip(amounts)
+        .map(|(reward, amount)| {
+            let class = registry.class_of(&reward.account_id);
+            let reason = format!("{}; {}", reward.reason, notes[class]);
+            ClassedReward { class: class.to_string(), reward: PlannedReward { amount, reason, ..reward.clone() } }
+        })
+        .collect::<Vec<_>>();
+    let placed: u64 = rewards.iter().map(|r| r.reward.amount).sum();
+    if placed < total {
+        warn!("Reward equity: every class is at its ceiling; {} of {} left unplanned", total - placed, total);
+    }
+    Ok(EquityPlan { total, rewards, classes, unplaced: total - placed })
+}
+
+/// Whole-unit amount per class. Shares are found by fixing the classes
+/// that break their bounds at the bound and sharing the rest of the total
+/// among the others in proportion to their planned amounts, until none
+/// breaks a bound.
+fn class_targets<'a>(planned: &BTreeMap<&'a str, u64>, spec: &RewardEquitySpec, total: u64) -> BTreeMap<&'a str, u64> {
+    let t = total as f64;
+    let tol = TOLERANCE * t.max(1.0);
+    let mut fixed: BTreeMap<&str, f64> = BTreeMap::new();
+    let shares = loop {
+        let free: Vec<&str> = planned.keys().copied().filter(|c| !fixed.contains_key(c)).collect();
+        let remaining = (t - fixed.values().sum::<f64>()).max(0.0);
+        let free_base: u64 = free.iter().map(|c| planned[c]).sum();
+        let mut shares = fixed.clone();
+        for c in &free {
+            let share = if free_base > 0 {
+                remaining * planned[c] as f64 / free_base as f64
+            } else {
+                remaining / free.len() as f64
+            };
+            shares.insert(*c, share);
+        }
+        let over = |c: &str| (shares[c] - spec.bounds(c).max_fraction * t).max(0.0);
+        let under = |c: &str| (spec.bounds(c).min_fraction * t - shares[c]).max(0.0);
+        let excess: f64 = free.iter().map(|&c| over(c)).filter(|&d| d > tol).sum();
+        let deficit: f64 = free.iter().map(|&c| under(c)).filter(|&d| d > tol).sum();
+        if excess == 0.0 && deficit == 0.0 {
+            break shares;
+        }
+        for &c in &free {
+            let b = spec.bounds(c);
+            if excess >= deficit && over(c) > tol {
+                fixed.insert(c, b.max_fraction * t);
+            } else if deficit >= excess && under(c) > tol {
+                fixed.insert(c, b.min_fraction * t);
+            }
+        }
+    };
+
+    // Round down, then hand out the units rounding lost: classes short of
+    // their floor first, then by largest remainder. No unit goes over a
+    // ceiling.
+    let mut targets: BTreeMap<&str, u64> = BTreeMap::new();
+    for (&c, share) in &shares {
+        let ceiling = (spec.bounds(c).max_fraction * t + tol).floor();
+        targets.insert(c, (share + tol).floor().min(ceiling) as u64);
+    }
+    let placed: u64 = targets.values().sum();
+    let mut units = ((shares.values().sum::<f64>() + tol).floor() as u64).min(total).saturating_sub(placed);
+    let mut order: Vec<&str> = shares.keys().copied().collect();
+    order.sort_by(|a, b| {
+        let short = |c: &str| (targets[c] as f64) < spec.bounds(c).min_fraction * t - tol;
+        let rem = |c: &str| shares[c] - targets[c] as f64;
+        short(b).cmp(&short(a)).then(rem(b).total_cmp(&rem(a))).then(a.cmp(b))
+    });
+    for c in order {
+        if units == 0 {
+            break;
+        }
+        if ((targets[c] + 1) as f64) <= spec.bounds(c).max_fraction * t + tol {
+            *targets.get_mut(c).expect("class has a target") += 1;
+            units -= 1;
+        }
+    }
+    targets
+}
+
+/// `amount` split in proportion to `weights` by largest remainder, ties to
+/// the earlier entry; evenly when every weight is zero.
+fn split(amount: u64, weights: &[u64]) -> Vec<u64> {
+    let sum: u128 = weights.iter().map(|&w| w as u128).sum();
+    if weights.is_empty() {
+        return Vec::new();
+    }
+    let (mut parts, rems): (Vec<u64>, Vec<u128>) = if sum == 0 {
+        let n = weights.len() as u64;
+        ((0..n).map(|_| amount / n).collect(), vec![0; weights.len()])
+    } else {
+        weights
+            .iter()
+            .map(|&w| {
+                let scaled = amount as u128 * w as u128;
+                ((scaled / sum) as u64, scaled % sum)
+            })
+            .unzip()
+    };
+    let left = amount - parts.iter().sum::<u64>();
+    let mut order: Vec<usize> = (0..weights.len()).collect();
+    order.sort_by(|&a, &b| rems[b].cmp(&rems[a]).then(a.cmp(&b)));
+    for &i in order.iter().take(left as usize) {
+        parts[i] += 1;
+    }
+    parts
+}
+
+/// Keep `plan` as a `reward_plan` deed for the reward-equity report.
+/// Returns the deed's event id.
+pub fn record_plan(ledger: &mut TokenLedger, plan: &EquityPlan) -> Result<String, TokenLedgerError> {
+    let accounts: BTreeSet<String> = plan.rewards.iter().map(|r| r.reward.account_id.clone()).collect();
+    let context = serde_json::to_value(plan).expect("plan serializes");
+    Ok(ledger.log_reward_plan(accounts.into_iter().collect(), context)?.event_id.clone())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassShare {
+    pub class: String,
+    pub amount: u64,
+    /// Of the report's total, in [0, 1].
+    pub share: f64,
+}
+
+/// Rebalanced rewards over a trailing window, by class and by account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardEquityReport {
+    /// Exclusive lower bound, unix seconds.
+    pub from: i64,
+    /// Inclusive upper bound, unix seconds.
+    pub until: i64,
+    pub plans: usize,
+    pub total: u64,
+    /// By class name.
+    pub classes: Vec<ClassShare>,
+    /// Over each rewarded account's total; 0 is perfectly even.
+    pub gini: f64,
+}
+
+/// Sum the `reward_plan` deeds in the policy's window ending at `now`.
+pub fn reward_equity_report(ledger: &TokenLedger, now: i64) -> RewardEquityReport {
+    let from = now - ledger.config().reward_equity.report_window_secs;
+    let mut plans = 0;
+    let mut by_class: BTreeMap<String, u64> = BTreeMap::new();
+    let mut by_account: BTreeMap<String, u64> = BTreeMap::new();
+    for deed in ledger.deeds().iter().filter(|d| d.deed_type == REWARD_PLAN && d.timestamp > from && d.timestamp <= now) {
+        let Ok(plan) = serde_json::from_value::<EquityPlan>(deed.context_json.clone()) else {
+            continue;
+        };
+        plans += 1;
+        for r in &plan.rewards {
+            *by_class.entry(r.class.clone()).or_default() += r.reward.amount;
+            *by_account.entry(r.reward.account_id.clone()).or_default() += r.reward.amount;
+        }
+    }
+    let total: u64 = by_class.values().sum();
+    let classes = by_class
+        .into_iter()
+        .map(|(class, amount)| ClassShare { class, amount, share: if total == 0 { 0.0 } else { amount as f64 / total as f64 } })
+        .collect();
+    let amounts: Vec<u64> = by_account.into_values().collect();
+    RewardEquityReport { from, until: now, plans, total, classes, gini: gini(&amounts) }
+}
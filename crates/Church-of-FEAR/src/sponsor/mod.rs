@@ -1,2 +1,5 @@
+pub mod equity;
 pub mod grant;
+pub mod pool;
 pub mod recipient;
+pub mod throttle;
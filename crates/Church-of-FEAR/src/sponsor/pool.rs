@@ -0,0 +1,428 @@
+//! Sponsor pool economics.
+//!
+//! `sponsor:pool` funds sponsored rewards. It is replenished by a tithe on
+//! every CHURCH mint (applied inside `TokenLedger`), by authority top-ups
+//! that need several distinct signatures, and optionally by recycling
+//! slashed or expired-obligation tokens. Reward plans are scaled down
+//! proportionally when the pool cannot cover them, never overdrawn, and
+//! dropping below the low-water mark raises an alert once per crossing.
+//! Before funding, plans pass the `throttle` (cooldowns and daily ceilings).
+
+#[cfg(feature = "pool-topup")]
+use std::collections::BTreeSet;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{movements_of, TokenLedger, TokenLedgerError};
+use crate::sponsor::throttle::{RewardThrottlePolicy, SponsorThrottle, SuppressedReward};
+use crate::utils::http::post_webhook;
+use crate::utils::time::now_timestamp;
+
+pub const SPONSOR_POOL: &str = "sponsor:pool";
+pub const POOL_INFLOW: &str = "pool_inflow";
+pub const POOL_OUTFLOW: &str = "pool_outflow";
+pub const POOL_LOW_WATER: &str = "pool_low_water";
+
+const BPS: u128 = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolPolicy {
+    /// Share of every CHURCH mint routed to the pool, in basis points.
+    /// 0 disables the tithe.
+    pub tithe_bps: u32,
+    /// Balance below which a low-water alert is raised.
+    pub low_water_mark: u64,
+    /// Keyring purpose of the authority keys that may approve top-ups.
+    pub top_up_purpose: String,
+    /// Distinct authority keys a top-up must be signed by.
+    pub top_up_threshold: usize,
+    /// Top-up requests further than this from the node clock are refused.
+    pub top_up_max_age_secs: i64,
+    pub recycle_slashed: bool,
+    pub recycle_expired_obligations: bool,
+    /// Trailing window for the burn rate behind the runway projection.
+    pub burn_window_secs: i64,
+    /// Operator webhook (`http://host:port/path`) for low-water alerts.
+    pub alert_webhook: Option<String>,
+    /// Per-account cooldowns and 24h ceilings on sponsored rewards.
+    pub throttle: RewardThrottlePolicy,
+}
+
+impl Default for PoolPolicy {
+    fn default() -> Self {
+        Self {
+            tithe_bps: 0,
+            low_water_mark: 100,
+            top_up_purpose: "pool-authority".to_string(),
+            top_up_threshold: 2,
+            top_up_max_age_secs: 3600,
+            recycle_slashed: true,
+            recycle_expired_obligations: true,
+            burn_window_secs: 7 * 86_400,
+            alert_webhook: None,
+            throttle: RewardThrottlePolicy::default(),
+        }
+    }
+}
+
+/// The pool's share of a CHURCH mint of `amount`.
+pub fn tithe_of(policy: &PoolPolicy, amount: u64) -> u64 {
+    (amount as u128 * u128::from(policy.tithe_bps).min(BPS) / BPS) as u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InflowSource {
+    Tithe,
+    TopUp,
+    RecycledSlash,
+    RecycledObligation,
+}
+
+#[derive(Error, Debug)]
+pub enum PoolError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error("recycling {0:?} tokens is disabled by the pool policy")]
+    RecyclingDisabled(InflowSource),
+    #[error("{0:?} is not a recycling source")]
+    NotRecycled(InflowSource),
+    #[cfg(feature = "pool-topup")]
+    #[error("top-up approval: {0}")]
+    Signature(#[from] keyring::KeyringError),
+    #[error("key {0} is not a pool authority")]
+    NotAuthority(String),
+    #[error("top-up has {got} distinct authority approvals, needs {need}")]
+    NotEnoughApprovals { got: usize, need: usize },
+    #[error("top-up {id} requested at {requested_at} is not fresh")]
+    Stale { id: String, requested_at: i64 },
+    #[error("top-up {0} was already applied")]
+    Replayed(String),
+}
+
+/// One reward the sponsor wants to pay from the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedReward {
+    pub account_id: String,
+    pub amount: u64,
+    pub reason: String,
+}
+
+/// What `fund_plan` actually paid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundedPlan {
+    pub requested: u64,
+    /// Pool balance when the plan was funded.
+    pub available: u64,
+    /// Applied to every reward; 1.0 when the pool covered the plan.
+    pub scale: f64,
+    pub paid: Vec<PlannedReward>,
+    /// What the throttle held back before funding.
+    pub suppressed: Vec<SuppressedReward>,
+}
+
+impl FundedPlan {
+    pub fn total_paid(&self) -> u64 {
+        self.paid.iter().map(|p| p.amount).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowWaterAlert {
+    pub balance: u64,
+    pub low_water_mark: u64,
+    pub detected_at: i64,
+}
+
+/// Where low-water alerts are pushed besides the ledger.
+pub trait PoolAlertNotifier: Send {
+    fn notify(&self, alert: &LowWaterAlert) -> Result<(), String>;
+}
+
+/// POSTs each alert as JSON to the policy's webhook.
+pub struct WebhookPoolNotifier {
+    pub url: String,
+}
+
+impl PoolAlertNotifier for WebhookPoolNotifier {
+    fn notify(&self, alert: &LowWaterAlert) -> Result<(), String> {
+        post_webhook(&self.url, POOL_LOW_WATER, alert)
+    }
+}
+
+/// An authority top-up; signed as its JSON encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopUpRequest {
+    /// Unique per top-up; a replayed id is refused.
+    pub id: String,
+    pub amount: u64,
+    pub memo: String,
+    /// Unix seconds.
+    pub requested_at: i64,
+}
+
+impl TopUpRequest {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("top-up serializes")
+    }
+}
+
+#[cfg(feature = "pool-topup")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTopUp {
+    pub request: TopUpRequest,
+    pub approvals: Vec<keyring::KeyringSignature>,
+}
+
+/// Pool state that is not in the ledger itself: the low-water latch,
+/// applied top-up ids, the reward throttle and the alert notifier.
+#[derive(Default)]
+pub struct SponsorPool {
+    notifier: Option<Box<dyn PoolAlertNotifier>>,
+    below_low_water: bool,
+    #[cfg(feature = "pool-topup")]
+    applied_top_ups: BTreeSet<String>,
+    throttle: SponsorThrottle,
+}
+
+impl SponsorPool {
+    /// Rebuild pool state from the ledger; with a webhook in the policy the
+    /// notifier posts to it.
+    pub fn new(ledger: &TokenLedger) -> Self {
+        let policy = &ledger.config().pool;
+        #[cfg(feature = "pool-topup")]
+        let applied_top_ups = ledger
+            .deeds()
+            .iter()
+            .filter(|d| d.deed_type == POOL_INFLOW && d.context_json["source"] == "top_up")
+            .filter_map(|d| d.context_json["top_up_id"].as_str().map(str::to_string))
+            .collect();
+        Self {
+            notifier: policy.alert_webhook.clone().map(|url| Box::new(WebhookPoolNotifier { url }) as Box<_>),
+            below_low_water: ledger.pool_balance() < policy.low_water_mark,
+            #[cfg(feature = "pool-topup")]
+            applied_top_ups,
+            throttle: SponsorThrottle::from_ledger(ledger, now_timestamp()),
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: impl PoolAlertNotifier + 'static) -> Self {
+        self.notifier = Some(Box::new(notifier));
+        self
+    }
+
+    pub fn throttle(&self) -> &SponsorThrottle {
+        &self.throttle
+    }
+
+    /// Pay `plan` from the pool, less what the throttle holds back. If the
+    /// pool holds less than the rest asks for, every reward is scaled by
+    /// the same factor (rounded down), so the pool is never overdrawn.
+    pub fn fund_plan(&mut self, ledger: &mut TokenLedger, plan: &[PlannedReward], now: i64) -> Result<FundedPlan, PoolError> {
+        let (plan, suppressed) = self.throttle.admit(&ledger.config().pool.throttle, plan, now);
+        if !suppressed.is_empty() {
+            let withheld: u64 = suppressed.iter().map(|s| s.reward.amount).sum();
+            warn!("Sponsor throttle held back {} CHURCH across {} planned rewards", withheld, suppressed.len());
+        }
+        let plan = plan.as_slice();
+        let requested: u64 = plan.iter().map(|p| p.amount).sum();
+        let available = ledger.pool_balance();
+        let scaled = |amount: u64| {
+            if requested <= available {
+                amount
+            } else {
+                (amount as u128 * available as u128 / requested as u128) as u64
+            }
+        };
+        if requested > available {
+            warn!("Sponsor pool holds {} of {} planned; scaling rewards down", available, requested);
+        }
+        let mut paid = Vec::with_capacity(plan.len());
+        for reward in plan {
+            let amount = ledger.pool_outflow(&reward.account_id, scaled(reward.amount), &reward.reason)?;
+            paid.push(PlannedReward { amount, ..reward.clone() });
+        }
+        self.throttle.record_paid(&paid, now);
+        let scale = if requested <= available { 1.0 } else { available as f64 / requested as f64 };
+        self.check_low_water(ledger, now)?;
+        Ok(FundedPlan { requested, available, scale, paid, suppressed })
+    }
+
+    /// A balancing burn of `amount` `token` from `account_id`, e.g. POWER
+    /// above k·CHURCH. Exempt from the reward ceilings but held to the
+    /// throttle's burn interval; `None` when that holds it back.
+    pub fn balance_burn(
+        &mut self,
+        ledger: &mut TokenLedger,
+        account_id: &str,
+        token: Token,
+        amount: u64,
+        now: i64,
+    ) -> Result<Option<u64>, PoolError> {
+        if !self.throttle.admit_burn(&ledger.config().pool.throttle, account_id, now) {
+            warn!("Sponsor throttle held back a {:?} burn on {}", token, account_id);
+            return Ok(None);
+        }
+        Ok(Some(ledger.burn(account_id, token, amount)?))
+    }
+
+    /// Move slashed or expired-obligation CHURCH from `from` into the pool.
+    pub fn recycle(
+        &mut self,
+        ledger: &mut TokenLedger,
+        source: InflowSource,
+        from: &str,
+        amount: u64,
+        reference: &str,
+        now: i64,
+    ) -> Result<u64, PoolError> {
+        let policy = &ledger.config().pool;
+        let allowed = match source {
+            InflowSource::RecycledSlash => policy.recycle_slashed,
+            InflowSource::RecycledObligation => policy.recycle_expired_obligations,
+            other => return Err(PoolError::NotRecycled(other)),
+        };
+        if !allowed {
+            return Err(PoolError::RecyclingDisabled(source));
+        }
+        let moved = ledger.pool_inflow(source, Some(from), amount, serde_json::json!({ "reference": reference }))?;
+        self.check_low_water(ledger, now)?;
+        Ok(moved)
+    }
+
+    /// Issue a multisig-approved top-up into the pool. Approvals must
+    /// verify against `authorities`, come from keys with the policy's
+    /// top-up purpose, and number at least `top_up_threshold` distinct keys.
+    #[cfg(feature = "pool-topup")]
+    pub fn top_up(
+        &mut self,
+        ledger: &mut TokenLedger,
+        signed: &SignedTopUp,
+        authorities: &keyring::VerifyingBundle,
+        now: i64,
+    ) -> Result<u64, PoolError> {
+        use keyring::SignatureVerifier;
+
+        let policy = ledger.config().pool.clone();
+        let request = &signed.request;
+        if self.applied_top_ups.contains(&request.id) {
+            return Err(PoolError::Replayed(request.id.clone()));
+        }
+        if (now - request.requested_at).abs() > policy.top_up_max_age_secs {
+            return Err(PoolError::Stale { id: request.id.clone(), requested_at: request.requested_at });
+        }
+        let bytes = request.signing_bytes();
+        let mut signers = BTreeSet::new();
+        for approval in &signed.approvals {
+            let meta = authorities.key_meta(&approval.key).ok_or_else(|| PoolError::NotAuthority(approval.key.clone()))?;
+            if meta.purpose != policy.top_up_purpose {
+                return Err(PoolError::NotAuthority(approval.key.clone()));
+            }
+            authorities.verify(&bytes, approval)?;
+            signers.insert(approval.key.clone());
+        }
+        if signers.len() < policy.top_up_threshold.max(1) {
+            return Err(PoolError::NotEnoughApprovals { got: signers.len(), need: policy.top_up_threshold.max(1) });
+        }
+
+        let extra = serde_json::json!({ "top_up_id": request.id, "memo": request.memo, "signers": signers });
+        let added = ledger.pool_inflow(InflowSource::TopUp, None, request.amount, extra)?;
+        self.applied_top_ups.insert(request.id.clone());
+        log::info!("Sponsor pool topped up by {} ({} signers)", added, signers.len());
+        self.check_low_water(ledger, now)?;
+        Ok(added)
+    }
+
+    /// Raise a low-water alert when the pool has just dropped below the
+    /// mark: a `pool_low_water` deed plus the notifier. Re-arms once the
+    /// balance is back at or above the mark.
+    pub fn check_low_water(&mut self, ledger: &mut TokenLedger, now: i64) -> Result<Option<LowWaterAlert>, PoolError> {
+        let balance = ledger.pool_balance();
+        let mark = ledger.config().pool.low_water_mark;
+        if balance >= mark {
+            self.below_low_water = false;
+            return Ok(None);
+        }
+        if self.below_low_water {
+            return Ok(None);
+        }
+        self.below_low_water = true;
+        let alert = LowWaterAlert { balance, low_water_mark: mark, detected_at: now };
+        warn!("Sponsor pool below low-water mark: {} < {}", balance, mark);
+        let deed = DeedEvent::new(
+            ledger.last_hash(),
+            SPONSOR_POOL.to_string(),
+            Vec::new(),
+            POOL_LOW_WATER.to_string(),
+            Vec::new(),
+            serde_json::to_value(&alert).expect("alert serializes"),
+            Vec::new(),
+            false,
+        );
+        ledger.append(deed)?;
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify(&alert) {
+                warn!("Pool low-water notifier failed: {}", e);
+            }
+        }
+        Ok(Some(alert))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolStatus {
+    pub balance: u64,
+    pub low_water_mark: u64,
+    pub below_low_water: bool,
+    /// Trailing window the rates cover; shorter than the policy's window
+    /// while the pool is younger than it.
+    pub window_secs: i64,
+    pub inflow_in_window: u64,
+    pub outflow_in_window: u64,
+    /// Average outflow per day over the window.
+    pub burn_per_day: f64,
+    /// Seconds until empty at that burn rate; None when nothing is burning.
+    pub runway_secs: Option<u64>,
+}
+
+/// Seconds `balance` lasts if `outflow` keeps leaving every `window_secs`.
+pub fn project_runway(balance: u64, outflow: u64, window_secs: i64) -> Option<u64> {
+    if outflow == 0 || window_secs <= 0 {
+        return None;
+    }
+    Some((balance as u128 * window_secs as u128 / outflow as u128).min(u64::MAX as u128) as u64)
+}
+
+/// Pool balance, trailing flows and projected runway as of `now`.
+pub fn pool_status(ledger: &TokenLedger, now: i64) -> PoolStatus {
+    let policy = &ledger.config().pool;
+    let flows = || ledger.deeds().iter().filter(|d| d.deed_type == POOL_INFLOW || d.deed_type == POOL_OUTFLOW);
+    let first = flows().map(|d| d.timestamp).min().unwrap_or(now);
+    let window_secs = policy.burn_window_secs.min(now - first).max(1);
+    let (mut inflow, mut outflow) = (0u64, 0u64);
+    for d in flows().filter(|d| d.timestamp >= now - window_secs && d.timestamp <= now) {
+        for m in movements_of(d).iter().filter(|m| m.account_id == SPONSOR_POOL) {
+            if m.delta >= 0 {
+                inflow += m.delta.unsigned_abs();
+            } else {
+                outflow += m.delta.unsigned_abs();
+            }
+        }
+    }
+    let balance = ledger.pool_balance();
+    PoolStatus {
+        balance,
+        low_water_mark: policy.low_water_mark,
+        below_low_water: balance < policy.low_water_mark,
+        window_secs,
+        inflow_in_window: inflow,
+        outflow_in_window: outflow,
+        burn_per_day: outflow as f64 * 86_400.0 / window_secs as f64,
+        runway_secs: project_runway(balance, outflow, window_secs),
+    }
+}
@@ -0,0 +1,178 @@
+//! Backpressure on sponsored rewards.
+//!
+//! A reward plan can name the same account tick after tick. The throttle
+//! holds each account to a minimum interval between sponsored rewards and
+//! to a rolling 24h ceiling, and the whole pool to a global 24h ceiling.
+//! Balancing burns are exempt from the ceilings but get their own interval.
+//! Whatever is held back is returned to the caller and counted, never
+//! silently dropped. Every limit is off at 0, which is the default.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::token_ledger::TokenLedger;
+use crate::sponsor::pool::{PlannedReward, POOL_OUTFLOW};
+
+pub const DAY_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardThrottlePolicy {
+    /// Least time between two sponsored rewards to one account.
+    pub min_interval_secs: i64,
+    /// Most CHURCH one account may be paid over any trailing 24h.
+    pub account_daily_ceiling: u64,
+    /// Most CHURCH the pool may pay out over any trailing 24h.
+    pub global_daily_ceiling: u64,
+    /// Least time between two balancing burns on one account.
+    pub burn_min_interval_secs: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Suppression {
+    Cooldown,
+    AccountCeiling,
+    GlobalCeiling,
+    BurnRate,
+}
+
+/// The part of a planned reward the throttle held back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressedReward {
+    pub reward: PlannedReward,
+    pub cause: Suppression,
+}
+
+/// Running totals since the throttle was built, for operator metrics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressionCounters {
+    pub cooldown: u64,
+    pub account_ceiling: u64,
+    pub global_ceiling: u64,
+    pub burn_rate: u64,
+    /// CHURCH held back by cooldowns and ceilings.
+    pub church_withheld: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SponsorThrottle {
+    /// Rewards paid in the trailing day: (unix seconds, account, amount),
+    /// oldest first.
+    paid: VecDeque<(i64, String, u64)>,
+    last_reward: HashMap<String, i64>,
+    last_burn: HashMap<String, i64>,
+    counters: SuppressionCounters,
+}
+
+impl SponsorThrottle {
+    /// Rebuild from the `pool_outflow` deeds of the day before `now`, so a
+    /// restart does not reset the ceilings.
+    pub fn from_ledger(ledger: &TokenLedger, now: i64) -> Self {
+        let mut throttle = Self::default();
+        for deed in ledger.deeds().iter().filter(|d| d.deed_type == POOL_OUTFLOW && d.timestamp > now - DAY_SECS) {
+            let (Some(account_id), Some(amount)) =
+                (deed.context_json["account_id"].as_str(), deed.context_json["amount"].as_u64())
+            else {
+                continue;
+            };
+            throttle.note_paid(deed.timestamp, account_id, amount);
+        }
+        throttle
+    }
+
+    pub fn counters(&self) -> &SuppressionCounters {
+        &self.counters
+    }
+
+    /// Split `plan` into what may be paid at `now` and what is held back.
+    /// A reward inside its account's interval is held back whole, as is a
+    /// second reward to one account in the same plan; a reward over a
+    /// ceiling is trimmed to the room left.
+    pub fn admit(
+        &mut self,
+        policy: &RewardThrottlePolicy,
+        plan: &[PlannedReward],
+        now: i64,
+    ) -> (Vec<PlannedReward>, Vec<SuppressedReward>) {
+        self.expire(now);
+        let mut account_used: HashMap<String, u64> = HashMap::new();
+        for (_, account_id, amount) in &self.paid {
+            *account_used.entry(account_id.clone()).or_default() += amount;
+        }
+        let mut global_used: u64 = self.paid.iter().map(|(_, _, amount)| amount).sum();
+        let mut seen = HashSet::new();
+        let (mut admitted, mut suppressed) = (Vec::new(), Vec::new());
+
+        for reward in plan {
+            let first = seen.insert(reward.account_id.as_str());
+            let cooling = self.last_reward.get(&reward.account_id).is_some_and(|&at| now - at < policy.min_interval_secs);
+            if policy.min_interval_secs > 0 && (cooling || !first) {
+                self.hold(&mut suppressed, reward, reward.amount, Suppression::Cooldown);
+                continue;
+            }
+            let mut amount = reward.amount;
+            let used = account_used.entry(reward.account_id.clone()).or_default();
+            for (ceiling, used, cause) in [
+                (policy.account_daily_ceiling, *used, Suppression::AccountCeiling),
+                (policy.global_daily_ceiling, global_used, Suppression::GlobalCeiling),
+            ] {
+                let room = if ceiling == 0 { u64::MAX } else { ceiling.saturating_sub(used) };
+                if amount > room {
+                    self.hold(&mut suppressed, reward, amount - room, cause);
+                    amount = room;
+                }
+            }
+            if amount > 0 {
+                *used += amount;
+                global_used += amount;
+                admitted.push(PlannedReward { amount, ..reward.clone() });
+            }
+        }
+        (admitted, suppressed)
+    }
+
+    /// Note what `fund_plan` actually paid at `now`.
+    pub fn record_paid(&mut self, paid: &[PlannedReward], now: i64) {
+        for reward in paid.iter().filter(|r| r.amount > 0) {
+            self.note_paid(now, &reward.account_id, reward.amount);
+        }
+    }
+
+    /// Whether a balancing burn on `account_id` may run at `now`; if so it
+    /// is noted as the account's latest.
+    pub fn admit_burn(&mut self, policy: &RewardThrottlePolicy, account_id: &str, now: i64) -> bool {
+        let cooling = self.last_burn.get(account_id).is_some_and(|&at| now - at < policy.burn_min_interval_secs);
+        if policy.burn_min_interval_secs > 0 && cooling {
+            self.counters.burn_rate += 1;
+            return false;
+        }
+        self.last_burn.insert(account_id.to_string(), now);
+        true
+    }
+
+    fn note_paid(&mut self, at: i64, account_id: &str, amount: u64) {
+        self.paid.push_back((at, account_id.to_string(), amount));
+        let last = self.last_reward.entry(account_id.to_string()).or_insert(at);
+        *last = (*last).max(at);
+    }
+
+    fn expire(&mut self, now: i64) {
+        while self.paid.front().is_some_and(|(at, _, _)| *at <= now - DAY_SECS) {
+            self.paid.pop_front();
+        }
+    }
+
+    fn hold(&mut self, suppressed: &mut Vec<SuppressedReward>, reward: &PlannedReward, amount: u64, cause: Suppression) {
+        let counter = match cause {
+            Suppression::Cooldown => &mut self.counters.cooldown,
+            Suppression::AccountCeiling => &mut self.counters.account_ceiling,
+            Suppression::GlobalCeiling => &mut self.counters.global_ceiling,
+            Suppression::BurnRate => &mut self.counters.burn_rate,
+        };
+        *counter += 1;
+        self.counters.church_withheld += amount;
+        suppressed.push(SuppressedReward { reward: PlannedReward { amount, ..reward.clone() }, cause });
+    }
+}
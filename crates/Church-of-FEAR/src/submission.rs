@@ -0,0 +1,649 @@
+//! Duplicate submission detection shared by every transport.
+//!
+//! The same deed can reach the node twice: a client retries after a
+//! timeout, or posts over JSON-RPC and then sends the same reading over the
+//! binary endpoint. `SubmissionGuard` stands in front of the ledger append
+//! of both and refuses a submission matching one admitted within
+//! `SubmissionPolicy::window_secs`, either by `(actor_id, idempotency_key)`
+//! or, independently, by content hash: SHA-256 over the would-be deed with
+//! the fields a resubmission changes left out (event id, timestamp, chain
+//! link, self hash and the correlation id stamped into the context). Only
+//! exact content matches; a reading that differs in any field is a distinct
+//! submission. Wire v1 frames carry no idempotency key, so binary
+//! submissions are matched on content alone; telemetry clients whose
+//! readings can repeat exactly put the epoch or window id in the context.
+//! A future endpoint (an HTTP gateway, say) admits through the same guard
+//! with a `Transport` of its own.
+//!
+//! Admitted submissions sit in a hot LRU of at most `hot_capacity`
+//! entries, and the least recently admitted or matched entry is evicted
+//! first; `DedupMetrics::evicted` counts how many. In memory only, an
+//! evicted entry is forgotten, so under sustained load the guard forgets
+//! its oldest submissions before their window ends.
+//!
+//! With `store_path` set, every claim is written (and synced) to a window
+//! file before its deed reaches the ledger, and its outcome after, and an
+//! evicted entry is spilled to the file instead: the guard keeps only its
+//! content hash, key and where its claim starts in the file, reads it back
+//! when a submission misses the LRU but matches one of those, and drops it
+//! once its window ends. `open` replays the file: an entry whose outcome
+//! was recorded as stored comes back, one without an outcome comes back if
+//! the ledger holds its deed (the node stopped between the append and the
+//! outcome), and the rest are dropped. The file is then rewritten with
+//! what was kept, hot or spilled, and again whenever it grows past a few
+//! times what it holds.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::TokenLedger;
+use crate::utils::correlation::CORRELATION_KEY;
+
+/// Error code of a refused duplicate, on every transport.
+pub const DUPLICATE_SUBMISSION_CODE: i64 = 1012;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubmissionPolicy {
+    /// How long an admitted submission refuses its duplicates, in seconds.
+    pub window_secs: i64,
+    /// Most submissions held in memory.
+    pub hot_capacity: usize,
+    /// Window file replayed on startup; memory only when unset.
+    pub store_path: Option<String>,
+}
+
+impl Default for SubmissionPolicy {
+    fn default() -> Self {
+        Self { window_secs: 600, hot_capacity: 10_000, store_path: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    JsonRpc,
+    Binary,
+}
+
+impl Transport {
+    pub const ALL: [Transport; 2] = [Transport::JsonRpc, Transport::Binary];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transport::JsonRpc => "json_rpc",
+            Transport::Binary => "binary",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    IdempotencyKey,
+    ContentHash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionEntry {
+    pub actor_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    pub content_hash: String,
+    /// Event id of the admitted deed.
+    pub event_id: String,
+    pub transport: Transport,
+    /// Unix seconds.
+    pub admitted_at: i64,
+}
+
+/// The earlier submission a refused one matched.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[error("duplicate of {event_id} admitted over {} (matched by {matched_by:?})", .transport.label())]
+pub struct Duplicate {
+    pub event_id: String,
+    pub matched_by: MatchedBy,
+    pub transport: Transport,
+    pub admitted_at: i64,
+}
+
+#[derive(Error, Debug)]
+pub enum SubmissionError {
+    #[error("submission window {path}: {source}")]
+    Store { path: String, source: io::Error },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupHits {
+    pub idempotency_key: u64,
+    pub content_hash: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupMetrics {
+    pub admitted: BTreeMap<Transport, u64>,
+    pub hits: BTreeMap<Transport, DedupHits>,
+    /// Entries evicted from the LRU to stay within `hot_capacity`.
+    pub evicted: u64,
+    /// Entries brought back from the window file by `open`.
+    pub restored: u64,
+    /// Entries held in the LRU now.
+    pub entries: u64,
+    /// Evicted entries the window file still matches.
+    pub spilled: u64,
+}
+
+impl DedupMetrics {
+    /// Prometheus text exposition, with every transport listed.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# TYPE cof_dedup_hits_total counter\n");
+        for t in Transport::ALL {
+            let hits = self.hits.get(&t).copied().unwrap_or_default();
+            for (by, n) in [("idempotency_key", hits.idempotency_key), ("content_hash", hits.content_hash)] {
+                writeln!(out, "cof_dedup_hits_total{{transport=\"{}\",matched_by=\"{}\"}} {}", t.label(), by, n).unwrap();
+            }
+        }
+        out.push_str("# TYPE cof_dedup_admitted_total counter\n");
+        for t in Transport::ALL {
+            writeln!(out, "cof_dedup_admitted_total{{transport=\"{}\"}} {}", t.label(), self.admitted.get(&t).unwrap_or(&0)).unwrap();
+        }
+        out.push_str("# TYPE cof_dedup_evicted_total counter\n");
+        writeln!(out, "cof_dedup_evicted_total {}", self.evicted).unwrap();
+        out.push_str("# TYPE cof_dedup_entries gauge\n");
+        writeln!(out, "cof_dedup_entries {}", self.entries).unwrap();
+        out.push_str("# TYPE cof_dedup_spilled_entries gauge\n");
+        writeln!(out, "cof_dedup_spilled_entries {}", self.spilled).unwrap();
+        out
+    }
+}
+
+/// One line of the window file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WindowRecord {
+    Claimed(SubmissionEntry),
+    Settled { event_id: String, stored: bool },
+}
+
+/// The entry a window file line starting with a claim records.
+fn claim_line(line: &[u8]) -> io::Result<SubmissionEntry> {
+    match serde_json::from_slice(line)? {
+        WindowRecord::Claimed(entry) => Ok(entry),
+        WindowRecord::Settled { .. } => Err(io::Error::new(io::ErrorKind::InvalidData, "not a claim")),
+    }
+}
+
+/// Append `entry`'s claim to `out`, followed by its outcome unless it is
+/// `pending`. Returns where the claim starts.
+fn write_claim(out: &mut String, entry: &SubmissionEntry, pending: bool) -> io::Result<u64> {
+    let offset = out.len() as u64;
+    out.push_str(&serde_json::to_string(&WindowRecord::Claimed(entry.clone()))?);
+    out.push('\n');
+    if !pending {
+        let settled = WindowRecord::Settled { event_id: entry.event_id.clone(), stored: true };
+        out.push_str(&serde_json::to_string(&settled)?);
+        out.push('\n');
+    }
+    Ok(offset)
+}
+
+/// SHA-256 over `deed`'s content, leaving out what differs between two
+/// sends of the same submission.
+pub fn content_hash(deed: &DeedEvent) -> String {
+    let mut context = deed.context_json.clone();
+    if let Some(object) = context.as_object_mut() {
+        object.remove(CORRELATION_KEY);
+    }
+    let content = json!({
+        "actor_id": deed.actor_id,
+        "target_ids": deed.target_ids,
+        "deed_type": deed.deed_type,
+        "tags": deed.tags,
+        "context_json": context,
+        "ethics_flags": deed.ethics_flags,
+        "life_harm_flag": deed.life_harm_flag,
+        "ext": deed.ext,
+    });
+    format!("{:x}", Sha256::digest(content.to_string().as_bytes()))
+}
+
+/// A submission admitted but not yet stored; hand it back to `settle`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct Claim {
+    event_id: String,
+    content_hash: String,
+}
+
+impl Claim {
+    pub fn event_id(&self) -> &str {
+        &self.event_id
+    }
+}
+
+#[derive(Debug)]
+struct WindowFile {
+    path: PathBuf,
+    file: File,
+    records: usize,
+}
+
+impl WindowFile {
+    /// Append `record`, returning where its line starts.
+    fn append(&mut self, record: &WindowRecord, sync: bool) -> io::Result<u64> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let offset = self.file.metadata()?.len();
+        self.file.write_all(line.as_bytes())?;
+        self.records += 1;
+        if sync {
+            self.file.sync_data()?;
+        }
+        Ok(offset)
+    }
+
+    /// The claim whose line starts at `offset`.
+    fn claim_at(&self, offset: u64) -> io::Result<SubmissionEntry> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        claim_line(line.as_bytes())
+    }
+}
+
+/// An entry in the hot LRU, with where its claim starts in the window file.
+#[derive(Debug)]
+struct Held {
+    entry: SubmissionEntry,
+    offset: Option<u64>,
+}
+
+/// Recently admitted submissions. See the module docs.
+#[derive(Debug)]
+pub struct SubmissionGuard {
+    policy: SubmissionPolicy,
+    /// Entries by recency of admission or match; the first goes first.
+    recent: BTreeMap<u64, Held>,
+    by_content: HashMap<String, u64>,
+    by_key: HashMap<(String, String), u64>,
+    /// Admission times of entries evicted to the window file, by where
+    /// their claim starts in it.
+    spilled: BTreeMap<u64, i64>,
+    spilled_by_content: HashMap<String, u64>,
+    spilled_by_key: HashMap<(String, String), u64>,
+    /// Event ids claimed and not yet settled.
+    pending: HashSet<String>,
+    next: u64,
+    store: Option<WindowFile>,
+    metrics: DedupMetrics,
+}
+
+impl SubmissionGuard {
+    /// A guard in memory only, whatever the policy's `store_path`.
+    pub fn new(policy: SubmissionPolicy) -> Self {
+        Self {
+            policy,
+            recent: BTreeMap::new(),
+            by_content: HashMap::new(),
+            by_key: HashMap::new(),
+            spilled: BTreeMap::new(),
+            spilled_by_content: HashMap::new(),
+            spilled_by_key: HashMap::new(),
+            pending: HashSet::new(),
+            next: 0,
+            store: None,
+            metrics: DedupMetrics::default(),
+        }
+    }
+
+    /// A guard on the policy's window file, replayed against `ledger`.
+    pub fn open(policy: SubmissionPolicy, ledger: &TokenLedger, now: i64) -> Result<Self, SubmissionError> {
+        let mut guard = Self::new(policy);
+        let Some(path) = guard.policy.store_path.clone().map(PathBuf::from) else {
+            return Ok(guard);
+        };
+        let store_error = |source| SubmissionError::Store { path: path.display().to_string(), source };
+
+        let mut claimed = Vec::new();
+        let mut settled: HashMap<String, bool> = HashMap::new();
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let mut offset = 0;
+                for line in bytes.split_inclusive(|&b| b == b'\n') {
+                    match serde_json::from_slice::<WindowRecord>(line) {
+                        Ok(WindowRecord::Claimed(entry)) => claimed.push((entry, offset)),
+                        Ok(WindowRecord::Settled { event_id, stored }) => {
+                            settled.insert(event_id, stored);
+                        }
+                        // A crash can leave the last line torn.
+                        Err(e) => warn!("skipping submission window line in {}: {}", path.display(), e),
+                    }
+                    offset += line.len() as u64;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(store_error(e)),
+        }
+        // Entries evicted while restoring spill to the file as it is now.
+        let file = OpenOptions::new().create(true).append(true).open(&path).map_err(store_error)?;
+        guard.store = Some(WindowFile { path: path.clone(), file, records: 0 });
+        for (entry, offset) in claimed {
+            let landed = match settled.get(&entry.event_id) {
+                Some(&stored) => stored,
+                None => ledger.deed(&entry.event_id).is_some(),
+            };
+            if landed && !guard.expired(&entry, now) {
+                guard.insert(entry, Some(offset));
+                guard.metrics.restored += 1;
+            }
+        }
+        guard.rewrite(now).map_err(store_error)?;
+        Ok(guard)
+    }
+
+    pub fn policy(&self) -> &SubmissionPolicy {
+        &self.policy
+    }
+
+    pub fn len(&self) -> usize {
+        self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty()
+    }
+
+    pub fn metrics(&self) -> DedupMetrics {
+        DedupMetrics { entries: self.recent.len() as u64, spilled: self.spilled.len() as u64, ..self.metrics.clone() }
+    }
+
+    /// Admit `deed` unless it duplicates a submission in the window. The
+    /// claim holds its place at once, so a concurrent duplicate is refused
+    /// even before the first reaches the ledger.
+    pub fn admit(
+        &mut self,
+        deed: &DeedEvent,
+        idempotency_key: Option<&str>,
+        transport: Transport,
+        now: i64,
+    ) -> Result<Claim, Duplicate> {
+        let hash = content_hash(deed);
+        if let Some(key) = idempotency_key {
+            let key = (deed.actor_id.clone(), key.to_string());
+            let (found, spilled) = (self.by_key.get(&key).copied(), self.spilled_by_key.get(&key).copied());
+            if let Some(seq) = self.live(found, now).or_else(|| self.unspill(spilled, now)) {
+                return Err(self.hit(seq, MatchedBy::IdempotencyKey, transport));
+            }
+        }
+        let (found, spilled) = (self.by_content.get(&hash).copied(), self.spilled_by_content.get(&hash).copied());
+        if let Some(seq) = self.live(found, now).or_else(|| self.unspill(spilled, now)) {
+            return Err(self.hit(seq, MatchedBy::ContentHash, transport));
+        }
+
+        let entry = SubmissionEntry {
+            actor_id: deed.actor_id.clone(),
+            idempotency_key: idempotency_key.map(str::to_string),
+            content_hash: hash.clone(),
+            event_id: deed.event_id.clone(),
+            transport,
+            admitted_at: now,
+        };
+        let mut offset = None;
+        if let Some(store) = &mut self.store {
+            match store.append(&WindowRecord::Claimed(entry.clone()), true) {
+                Ok(at) => offset = Some(at),
+                Err(e) => warn!("submission window {}: {}", store.path.display(), e),
+            }
+        }
+        self.pending.insert(entry.event_id.clone());
+        self.insert(entry, offset);
+        *self.metrics.admitted.entry(transport).or_insert(0) += 1;
+        self.compact_if_due(now);
+        Ok(Claim { event_id: deed.event_id.clone(), content_hash: hash })
+    }
+
+    /// Record what became of a claim. A claim whose deed was not stored
+    /// frees its place for a retry.
+    pub fn settle(&mut self, claim: Claim, stored: bool) {
+        self.pending.remove(&claim.event_id);
+        if !stored {
+            let seq = self.by_content.get(&claim.content_hash).copied();
+            if let Some(seq) = seq.filter(|s| self.recent.get(s).is_some_and(|h| h.entry.event_id == claim.event_id)) {
+                self.remove(seq);
+            } else if let Some(offset) = self.spilled_by_content.get(&claim.content_hash).copied() {
+                // Evicted while pending.
+                if let Some(entry) = self.take_spilled(offset).filter(|e| e.event_id != claim.event_id) {
+                    self.spill(Held { entry, offset: Some(offset) });
+                }
+            }
+        }
+        if let Some(store) = &mut self.store {
+            if let Err(e) = store.append(&WindowRecord::Settled { event_id: claim.event_id, stored }, false) {
+                warn!("submission window {}: {}", store.path.display(), e);
+            }
+        }
+    }
+
+    fn expired(&self, entry: &SubmissionEntry, now: i64) -> bool {
+        now - entry.admitted_at >= self.policy.window_secs
+    }
+
+    /// `seq` if it names an entry still in its window; an expired one is
+    /// dropped.
+    fn live(&mut self, seq: Option<u64>, now: i64) -> Option<u64> {
+        let seq = seq?;
+        let expired = self.expired(&self.recent.get(&seq)?.entry, now);
+        if expired {
+            self.remove(seq);
+            return None;
+        }
+        Some(seq)
+    }
+
+    /// Bring the entry spilled at `offset` back into the LRU if it is still
+    /// in its window, and drop it otherwise.
+    fn unspill(&mut self, offset: Option<u64>, now: i64) -> Option<u64> {
+        let entry = self.take_spilled(offset?)?;
+        if self.expired(&entry, now) {
+            return None;
+        }
+        Some(self.insert(entry, offset))
+    }
+
+    fn hit(&mut self, seq: u64, matched_by: MatchedBy, transport: Transport) -> Duplicate {
+        let held = self.remove(seq).expect("live entry");
+        let duplicate = Duplicate {
+            event_id: held.entry.event_id.clone(),
+            matched_by,
+            transport: held.entry.transport,
+            admitted_at: held.entry.admitted_at,
+        };
+        self.insert(held.entry, held.offset);
+        let hits = self.metrics.hits.entry(transport).or_default();
+        match matched_by {
+            MatchedBy::IdempotencyKey => hits.idempotency_key += 1,
+            MatchedBy::ContentHash => hits.content_hash += 1,
+        }
+        duplicate
+    }
+
+    /// Hold `entry` as the most recent, evicting past `hot_capacity`.
+    /// Returns its place in the LRU.
+    fn insert(&mut self, entry: SubmissionEntry, offset: Option<u64>) -> u64 {
+        let seq = self.next;
+        self.next += 1;
+        self.by_content.insert(entry.content_hash.clone(), seq);
+        if let Some(key) = &entry.idempotency_key {
+            self.by_key.insert((entry.actor_id.clone(), key.clone()), seq);
+        }
+        self.recent.insert(seq, Held { entry, offset });
+        while self.recent.len() > self.policy.hot_capacity.max(1) {
+            let (&oldest, _) = self.recent.first_key_value().expect("over capacity");
+            let held = self.remove(oldest).expect("oldest entry");
+            self.spill(held);
+            self.metrics.evicted += 1;
+        }
+        seq
+    }
+
+    fn remove(&mut self, seq: u64) -> Option<Held> {
+        let held = self.recent.remove(&seq)?;
+        let entry = &held.entry;
+        if self.by_content.get(&entry.content_hash) == Some(&seq) {
+            self.by_content.remove(&entry.content_hash);
+        }
+        if let Some(key) = &entry.idempotency_key {
+            let key = (entry.actor_id.clone(), key.clone());
+            if self.by_key.get(&key) == Some(&seq) {
+                self.by_key.remove(&key);
+            }
+        }
+        Some(held)
+    }
+
+    /// Keep an entry evicted from the LRU matchable through the window
+    /// file; without one, or without its claim in it, it is forgotten.
+    fn spill(&mut self, held: Held) {
+        let (Some(offset), Some(_)) = (held.offset, &self.store) else {
+            return;
+        };
+        let entry = held.entry;
+        self.spilled.insert(offset, entry.admitted_at);
+        self.spilled_by_content.insert(entry.content_hash, offset);
+        if let Some(key) = entry.idempotency_key {
+            self.spilled_by_key.insert((entry.actor_id, key), offset);
+        }
+    }
+
+    /// Drop the entry spilled at `offset`, returning it as read back from
+    /// the window file.
+    fn take_spilled(&mut self, offset: u64) -> Option<SubmissionEntry> {
+        self.spilled.remove(&offset)?;
+        let read = self.store.as_ref().map(|store| store.claim_at(offset));
+        let entry = match read {
+            Some(Ok(entry)) => entry,
+            Some(Err(e)) => {
+                warn!("submission window claim at {}: {}", offset, e);
+                self.spilled_by_content.retain(|_, at| *at != offset);
+                self.spilled_by_key.retain(|_, at| *at != offset);
+                return None;
+            }
+            None => return None,
+        };
+        if self.spilled_by_content.get(&entry.content_hash) == Some(&offset) {
+            self.spilled_by_content.remove(&entry.content_hash);
+        }
+        if let Some(key) = &entry.idempotency_key {
+            let key = (entry.actor_id.clone(), key.clone());
+            if self.spilled_by_key.get(&key) == Some(&offset) {
+                self.spilled_by_key.remove(&key);
+            }
+        }
+        Some(entry)
+    }
+
+    fn compact_if_due(&mut self, now: i64) {
+        let held = self.policy.hot_capacity.max(1) + self.spilled.len();
+        let due = self.store.as_ref().is_some_and(|s| s.records > 4 * held + 64);
+        if due {
+            if let Err(e) = self.rewrite(now) {
+                warn!("submission window compaction: {}", e);
+            }
+        }
+    }
+
+    /// Replace the window file, through a temporary file, with the entries
+    /// spilled to it that are still in their window and those held now.
+    fn rewrite(&mut self, now: i64) -> io::Result<()> {
+        let Some(store) = &mut self.store else {
+            return Ok(());
+        };
+        let old = if self.spilled.is_empty() { Vec::new() } else { fs::read(&store.path)? };
+        let mut out = String::new();
+        let mut spilled = Vec::new();
+        for (&offset, &admitted_at) in &self.spilled {
+            if now - admitted_at >= self.policy.window_secs {
+                continue;
+            }
+            let line = old.get(offset as usize..).unwrap_or_default();
+            match claim_line(line.split(|&b| b == b'\n').next().unwrap_or_default()) {
+                Ok(entry) => {
+                    let at = write_claim(&mut out, &entry, self.pending.contains(&entry.event_id))?;
+                    spilled.push(Held { entry, offset: Some(at) });
+                }
+                Err(e) => warn!("submission window claim at {}: {}", offset, e),
+            }
+        }
+        let hot = self
+            .recent
+            .values()
+            .map(|held| write_claim(&mut out, &held.entry, self.pending.contains(&held.entry.event_id)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let tmp = store.path.with_extension("tmp");
+        fs::write(&tmp, out.as_bytes())?;
+        File::open(&tmp)?.sync_all()?;
+        fs::rename(&tmp, &store.path)?;
+        store.file = OpenOptions::new().append(true).open(&store.path)?;
+        store.records = out.lines().count();
+
+        for (held, at) in self.recent.values_mut().zip(hot) {
+            held.offset = Some(at);
+        }
+        self.spilled.clear();
+        self.spilled_by_content.clear();
+        self.spilled_by_key.clear();
+        for held in spilled {
+            self.spill(held);
+        }
+        Ok(())
+    }
+}
+
+/// A claim on a shared guard, settled as not stored when dropped unless
+/// `stored` was called. Every early return of a submission path releases
+/// its claim this way.
+pub struct Admission {
+    guard: Arc<Mutex<SubmissionGuard>>,
+    claim: Option<Claim>,
+}
+
+impl Admission {
+    pub fn stored(mut self) {
+        if let Some(claim) = self.claim.take() {
+            self.guard.lock().unwrap_or_else(|e| e.into_inner()).settle(claim, true);
+        }
+    }
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        if let Some(claim) = self.claim.take() {
+            self.guard.lock().unwrap_or_else(|e| e.into_inner()).settle(claim, false);
+        }
+    }
+}
+
+/// `SubmissionGuard::admit` on a guard shared between transports.
+pub fn admit_shared(
+    guard: &Arc<Mutex<SubmissionGuard>>,
+    deed: &DeedEvent,
+    idempotency_key: Option<&str>,
+    transport: Transport,
+    now: i64,
+) -> Result<Admission, Duplicate> {
+    let claim = guard.lock().unwrap_or_else(|e| e.into_inner()).admit(deed, idempotency_key, transport, now)?;
+    Ok(Admission { guard: guard.clone(), claim: Some(claim) })
+}
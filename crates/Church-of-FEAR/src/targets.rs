@@ -0,0 +1,210 @@
+//! Beneficiary reporting by target id.
+//!
+//! Deeds name their beneficiaries in `target_ids` ("homeless-shelter-az",
+//! "local-watershed"). The ledger indexes them on append (see
+//! `TokenLedger::deeds_naming`), so an NPO can ask what was done for a
+//! target and by whom without scanning the chain. The index is rebuilt by
+//! replay like every other ledger view; tombstoned deeds and deeds whose
+//! validation quorum rejected them stay indexed but are left out of the
+//! lookups and sums here.
+//!
+//! Quantities are the numeric fields the deed's taxonomy category declares
+//! (`hours`, `meals_served`, `co2_kg`, ...), summed by field name.
+//!
+//! Targets may be registered in `LedgerConfig::targets` with a display
+//! name, region and category, so summaries can be grouped by region.
+//! Unregistered targets still report, flagged `registered: false`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cooldown::slashed_ids;
+use crate::ledger::builders::{schema_for, FieldKind};
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::TokenLedger;
+
+/// Region key of summaries whose target has no registered region.
+pub const NO_REGION: &str = "unassigned";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetInfo {
+    pub display_name: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// What kind of beneficiary this is, e.g. `shelter`, `watershed`.
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// Registered targets, by target id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetRegistry {
+    pub targets: BTreeMap<String, TargetInfo>,
+}
+
+#[derive(Error, Debug)]
+pub enum TargetError {
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+    #[error("registry: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl TargetRegistry {
+    /// Read a registry file: `{ "targets": { "<id>": { "display_name": ... } } }`.
+    pub fn load(path: &Path) -> Result<Self, TargetError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn get(&self, target_id: &str) -> Option<&TargetInfo> {
+        self.targets.get(target_id)
+    }
+}
+
+/// Narrows `deeds_for_target`; every field left unset matches all deeds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetFilter {
+    pub deed_type: Option<String>,
+    pub actor_id: Option<String>,
+    /// Unix seconds, inclusive.
+    pub since: Option<i64>,
+    /// Unix seconds, exclusive.
+    pub until: Option<i64>,
+}
+
+impl TargetFilter {
+    pub fn matches(&self, deed: &DeedEvent) -> bool {
+        self.deed_type.as_ref().is_none_or(|t| *t == deed.deed_type)
+            && self.actor_id.as_ref().is_none_or(|a| *a == deed.actor_id)
+            && self.since.is_none_or(|s| deed.timestamp >= s)
+            && self.until.is_none_or(|u| deed.timestamp < u)
+    }
+}
+
+/// What has been done for one target, and by whom.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetSummary {
+    pub target_id: String,
+    pub registered: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<TargetInfo>,
+    pub total_deeds: usize,
+    pub deeds_by_category: BTreeMap<String, usize>,
+    pub distinct_actors: usize,
+    pub quantities: BTreeMap<String, f64>,
+    pub first_activity: Option<i64>,
+    pub last_activity: Option<i64>,
+}
+
+/// The numeric fields `deed`'s category declares, with their values.
+pub fn quantities_of(deed: &DeedEvent) -> Vec<(&'static str, f64)> {
+    let Some(schema) = schema_for(&deed.deed_type) else {
+        return Vec::new();
+    };
+    schema
+        .required
+        .iter()
+        .chain(schema.optional)
+        .filter(|f| matches!(f.kind, FieldKind::Number | FieldKind::Integer))
+        .filter_map(|f| deed.context_json.get(f.name).and_then(|v| v.as_f64()).map(|v| (f.name, v)))
+        .collect()
+}
+
+impl TokenLedger {
+    /// Deeds naming `target_id` that `filter` admits, in chain order,
+    /// leaving out tombstoned and quorum-rejected deeds.
+    pub fn deeds_for_target(&self, target_id: &str, filter: &TargetFilter) -> Vec<&DeedEvent> {
+        let slashed = slashed_ids(self);
+        self.deeds_naming(target_id).filter(|d| !slashed.contains(&d.event_id) && filter.matches(d)).collect()
+    }
+
+    pub fn target_summary(&self, target_id: &str) -> TargetSummary {
+        let info = self.config().targets.get(target_id).cloned();
+        let mut summary = TargetSummary {
+            target_id: target_id.to_string(),
+            registered: info.is_some(),
+            info,
+            total_deeds: 0,
+            deeds_by_category: BTreeMap::new(),
+            distinct_actors: 0,
+            quantities: BTreeMap::new(),
+            first_activity: None,
+            last_activity: None,
+        };
+        let mut actors = BTreeSet::new();
+        for deed in self.deeds_for_target(target_id, &TargetFilter::default()) {
+            summary.total_deeds += 1;
+            *summary.deeds_by_category.entry(deed.deed_type.clone()).or_insert(0) += 1;
+            actors.insert(deed.actor_id.as_str());
+            for (name, value) in quantities_of(deed) {
+                *summary.quantities.entry(name.to_string()).or_insert(0.0) += value;
+            }
+            summary.first_activity = Some(summary.first_activity.map_or(deed.timestamp, |t| t.min(deed.timestamp)));
+            summary.last_activity = Some(summary.last_activity.map_or(deed.timestamp, |t| t.max(deed.timestamp)));
+        }
+        summary.distinct_actors = actors.len();
+        summary
+    }
+
+    /// `target_summary` of every target a deed names, plus registered
+    /// targets nothing has been done for yet, in target id order.
+    pub fn target_summaries(&self) -> Vec<TargetSummary> {
+        let ids: BTreeSet<&str> = self.target_ids().chain(self.config().targets.targets.keys().map(String::as_str)).collect();
+        ids.into_iter().map(|id| self.target_summary(id)).collect()
+    }
+}
+
+/// `summaries` grouped by registered region; unregistered targets and
+/// targets without a region fall under `NO_REGION`.
+pub fn by_region(summaries: &[TargetSummary]) -> BTreeMap<String, Vec<&TargetSummary>> {
+    let mut out: BTreeMap<String, Vec<&TargetSummary>> = BTreeMap::new();
+    for s in summaries {
+        let region = s.info.as_ref().and_then(|i| i.region.clone()).unwrap_or_else(|| NO_REGION.to_string());
+        out.entry(region).or_default().push(s);
+    }
+    out
+}
+
+pub const TARGETS_CSV_HEADER: &str = "target_id,display_name,region,category,registered,total_deeds,\
+deeds_by_category,distinct_actors,quantities,first_activity,last_activity";
+
+/// One CSV row per summary under `TARGETS_CSV_HEADER`. Per-category
+/// counts and quantities are `key=value` pairs joined by `;`.
+pub fn targets_csv(summaries: &[TargetSummary]) -> String {
+    let mut out = String::from(TARGETS_CSV_HEADER);
+    out.push('\n');
+    for s in summaries {
+        let info = s.info.as_ref();
+        let pairs = |m: Vec<String>| m.join(";");
+        let row = [
+            s.target_id.clone(),
+            info.map(|i| i.display_name.clone()).unwrap_or_default(),
+            info.and_then(|i| i.region.clone()).unwrap_or_default(),
+            info.and_then(|i| i.category.clone()).unwrap_or_default(),
+            s.registered.to_string(),
+            s.total_deeds.to_string(),
+            pairs(s.deeds_by_category.iter().map(|(k, v)| format!("{k}={v}")).collect()),
+            s.distinct_actors.to_string(),
+            pairs(s.quantities.iter().map(|(k, v)| format!("{k}={v}")).collect()),
+            s.first_activity.map(|t| t.to_string()).unwrap_or_default(),
+            s.last_activity.map(|t| t.to_string()).unwrap_or_default(),
+        ];
+        out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,345 @@
+//! Tip gossip between Auto_Church nodes serving the same community.
+//!
+//! Each node periodically signs a `TipAnnouncement` (height, tip hash,
+//! Merkle root of its latest sealed segment) and POSTs it to its peers.
+//! A received announcement is checked against the peer's registered keys
+//! and for timestamp freshness, then compared with our own chain: if the
+//! peer is at or below our height and its tip is not the deed we hold at
+//! that height, the chains have forked and a `DivergenceAlert` is raised,
+//! logged as a deed and pushed to the notifier.
+//!
+//! This is detection only; no consensus is attempted.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+
+use keyring::{Keyring, KeyringError, KeyringSignature, SignatureVerifier, VerifyingBundle};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{field, info_span};
+
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+pub use crate::ledger::token_ledger::segment_root;
+use crate::utils::correlation::{CorrelationId, CORRELATION_HEADER};
+use crate::utils::http::{post_json, post_webhook};
+
+pub const DIVERGENCE_ALERT: &str = "divergence_alert";
+
+#[derive(Error, Debug)]
+pub enum GossipError {
+    #[error("announcement from unregistered peer {0}")]
+    UnknownPeer(String),
+    #[error("announcement for namespace {got}, expected {expected}")]
+    WrongNamespace { expected: String, got: String },
+    #[error("peer {peer} signed with key {key} it is not registered for")]
+    ForeignKey { peer: String, key: String },
+    #[error("bad signature: {0}")]
+    Signature(#[from] KeyringError),
+    #[error("announcement from {peer} at {timestamp} is not fresh")]
+    Stale { peer: String, timestamp: i64 },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error("transport: {0}")]
+    Transport(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TipAnnouncement {
+    pub node_id: String,
+    pub namespace: String,
+    /// Number of deeds in the announcing node's chain.
+    pub height: u64,
+    pub tip_hash: String,
+    /// Merkle root of the latest sealed segment, if any.
+    pub sealed_root: Option<String>,
+    /// Unix seconds.
+    pub timestamp: i64,
+}
+
+impl TipAnnouncement {
+    pub(crate) fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("announcement serializes")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTipAnnouncement {
+    pub announcement: TipAnnouncement,
+    pub signature: KeyringSignature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DivergenceAlert {
+    pub namespace: String,
+    pub peer: String,
+    pub peer_height: u64,
+    pub peer_tip: String,
+    /// Our deed hash at `peer_height`.
+    pub our_tip_at_height: String,
+    pub our_height: u64,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerConfig {
+    pub node_id: String,
+    /// Where to POST our announcements, e.g. `http://10.0.0.2:7070/tips`.
+    pub url: String,
+    /// Keyring key names this peer may sign with.
+    pub key_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GossipConfig {
+    pub node_id: String,
+    pub namespace: String,
+    pub peers: Vec<PeerConfig>,
+    pub interval_secs: i64,
+    /// Announcements further than this from our clock are rejected.
+    pub max_skew_secs: i64,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            node_id: "node".to_string(),
+            namespace: "default".to_string(),
+            peers: Vec::new(),
+            interval_secs: 60,
+            max_skew_secs: 300,
+        }
+    }
+}
+
+/// Where divergence alerts are pushed besides the ledger.
+pub trait AlertNotifier: Send {
+    fn notify(&self, alert: &DivergenceAlert) -> Result<(), String>;
+}
+
+/// POSTs each alert as JSON to an operator webhook.
+pub struct WebhookNotifier {
+    pub url: String,
+}
+
+impl AlertNotifier for WebhookNotifier {
+    fn notify(&self, alert: &DivergenceAlert) -> Result<(), String> {
+        post_webhook(&self.url, DIVERGENCE_ALERT, alert)
+    }
+}
+
+/// Delivers announcements to a peer.
+pub trait TipTransport {
+    fn send(&self, peer: &PeerConfig, announcement: &SignedTipAnnouncement) -> Result<(), GossipError>;
+}
+
+pub struct HttpTransport;
+
+impl TipTransport for HttpTransport {
+    fn send(&self, peer: &PeerConfig, announcement: &SignedTipAnnouncement) -> Result<(), GossipError> {
+        let body = serde_json::to_vec(announcement).map_err(|e| GossipError::Transport(e.to_string()))?;
+        post_json(&peer.url, &body).map_err(GossipError::Transport)
+    }
+}
+
+/// Deed hash at `height` (1-based); height 0 is the all-zero genesis hash.
+pub(crate) fn hash_at(ledger: &TokenLedger, height: u64) -> Option<String> {
+    match height {
+        0 => Some("0".repeat(64)),
+        h => ledger.deeds().get(h as usize - 1).map(|d| d.self_hash.clone()),
+    }
+}
+
+pub struct TipGossip {
+    cfg: GossipConfig,
+    keyring: Keyring,
+    key_name: String,
+    peer_keys: VerifyingBundle,
+    received: HashMap<String, SignedTipAnnouncement>,
+    alerted: HashSet<(String, String)>,
+    notifier: Option<Box<dyn AlertNotifier>>,
+    last_broadcast: Option<i64>,
+}
+
+impl TipGossip {
+    /// `keyring` signs our announcements with `key_name`; `peer_keys`
+    /// holds the public keys peers sign with.
+    pub fn new(cfg: GossipConfig, keyring: Keyring, key_name: &str, peer_keys: VerifyingBundle) -> Self {
+        Self {
+            cfg,
+            keyring,
+            key_name: key_name.to_string(),
+            peer_keys,
+            received: HashMap::new(),
+            alerted: HashSet::new(),
+            notifier: None,
+            last_broadcast: None,
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: impl AlertNotifier + 'static) -> Self {
+        self.notifier = Some(Box::new(notifier));
+        self
+    }
+
+    /// Latest accepted announcement per peer.
+    pub fn received(&self) -> &HashMap<String, SignedTipAnnouncement> {
+        &self.received
+    }
+
+    pub fn announce(&self, ledger: &TokenLedger, now: i64) -> Result<SignedTipAnnouncement, GossipError> {
+        let sealed_root = ledger.sealed_segments().last().map(|s| segment_root(ledger, s));
+        let announcement = TipAnnouncement {
+            node_id: self.cfg.node_id.clone(),
+            namespace: self.cfg.namespace.clone(),
+            height: ledger.deeds().len() as u64,
+            tip_hash: ledger.last_hash(),
+            sealed_root,
+            timestamp: now,
+        };
+        let signature = self.keyring.sign(&self.key_name, &announcement.signing_bytes())?;
+        Ok(SignedTipAnnouncement { announcement, signature })
+    }
+
+    /// Send our tip to every peer if `interval_secs` has elapsed since the
+    /// last broadcast. Delivery failures are logged, not fatal.
+    pub fn tick(&mut self, ledger: &TokenLedger, transport: &impl TipTransport, now: i64) -> Result<(), GossipError> {
+        if self.last_broadcast.is_some_and(|t| now - t < self.cfg.interval_secs) {
+            return Ok(());
+        }
+        let signed = self.announce(ledger, now)?;
+        for peer in &self.cfg.peers {
+            if let Err(e) = transport.send(peer, &signed) {
+                warn!("tip gossip to {} failed: {}", peer.node_id, e);
+            }
+        }
+        self.last_broadcast = Some(now);
+        Ok(())
+    }
+
+    fn verify(&self, signed: &SignedTipAnnouncement, now: i64) -> Result<(), GossipError> {
+        let a = &signed.announcement;
+        let peer = self
+            .cfg
+            .peers
+            .iter()
+            .find(|p| p.node_id == a.node_id)
+            .ok_or_else(|| GossipError::UnknownPeer(a.node_id.clone()))?;
+        if a.namespace != self.cfg.namespace {
+            return Err(GossipError::WrongNamespace { expected: self.cfg.namespace.clone(), got: a.namespace.clone() });
+        }
+        if !peer.key_names.contains(&signed.signature.key) {
+            return Err(GossipError::ForeignKey { peer: a.node_id.clone(), key: signed.signature.key.clone() });
+        }
+        self.peer_keys.verify(&a.signing_bytes(), &signed.signature)?;
+
+        let stale = GossipError::Stale { peer: a.node_id.clone(), timestamp: a.timestamp };
+        if (now - a.timestamp).abs() > self.cfg.max_skew_secs {
+            return Err(stale);
+        }
+        // Replay protection: each peer's timestamps must strictly increase.
+        if self.received.get(&a.node_id).is_some_and(|prev| a.timestamp <= prev.announcement.timestamp) {
+            return Err(stale);
+        }
+        Ok(())
+    }
+
+    /// Verify and store a peer announcement, returning an alert the first
+    /// time a given forked peer tip is seen.
+    pub fn receive(
+        &mut self,
+        ledger: &mut TokenLedger,
+        signed: SignedTipAnnouncement,
+        now: i64,
+    ) -> Result<Option<DivergenceAlert>, GossipError> {
+        self.verify(&signed, now)?;
+        let a = signed.announcement.clone();
+        self.received.insert(a.node_id.clone(), signed);
+
+        let our_height = ledger.deeds().len() as u64;
+        // A peer ahead of us may simply have deeds we have not seen yet.
+        let Some(ours) = hash_at(ledger, a.height).filter(|_| a.height <= our_height) else {
+            return Ok(None);
+        };
+        if ours == a.tip_hash || !self.alerted.insert((a.node_id.clone(), a.tip_hash.clone())) {
+            return Ok(None);
+        }
+
+        let alert = DivergenceAlert {
+            namespace: a.namespace,
+            peer: a.node_id,
+            peer_height: a.height,
+            peer_tip: a.tip_hash,
+            our_tip_at_height: ours,
+            our_height,
+            detected_at: now,
+        };
+        warn!("ledger divergence: peer {} at height {} reports tip {}", alert.peer, alert.peer_height, alert.peer_tip);
+        let deed = DeedEvent::new(
+            ledger.last_hash(),
+            self.cfg.node_id.clone(),
+            vec![alert.peer.clone()],
+            DIVERGENCE_ALERT.to_string(),
+            vec!["tip_gossip".to_string()],
+            serde_json::to_value(&alert).expect("alert serializes"),
+            Vec::new(),
+            false,
+        );
+        ledger.append(deed)?;
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify(&alert) {
+                warn!("divergence webhook failed: {}", e);
+            }
+        }
+        Ok(Some(alert))
+    }
+
+    /// Serve one HTTP request carrying a `SignedTipAnnouncement` body. The
+    /// request runs under the caller's `X-Correlation-Id` (or a fresh one),
+    /// which is echoed in the response.
+    pub fn handle_http(&mut self, ledger: &mut TokenLedger, mut stream: TcpStream, now: i64) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut content_length = 0usize;
+        let mut incoming_id = None;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                } else if name.eq_ignore_ascii_case(CORRELATION_HEADER) {
+                    incoming_id = Some(value.trim().to_string());
+                }
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body)?;
+
+        let correlation = CorrelationId::accept(incoming_id.as_deref());
+        let _scope = correlation.enter();
+        let span = info_span!("request", correlation_id = %correlation, method = "tip_gossip.receive", decision = field::Empty);
+        let _entered = span.enter();
+        let status = match serde_json::from_slice::<SignedTipAnnouncement>(&body) {
+            Ok(signed) => match self.receive(ledger, signed, now) {
+                Ok(_) => "204 No Content",
+                Err(e) => {
+                    info!("rejected tip announcement: {}", e);
+                    "403 Forbidden"
+                }
+            },
+            Err(_) => "400 Bad Request",
+        };
+        span.record("decision", status);
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\n{}: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status, CORRELATION_HEADER, correlation
+        )
+    }
+}
@@ -1,6 +1,14 @@
+use crate::config::LedgerConfig;
+use crate::cooldown::is_suppressed;
 use crate::ledger::deed_event::DeedEvent;
 use crate::ledger::metrics::BioloadMetrics;
 
-pub fn mint_church(event: &DeedEvent, metrics: &BioloadMetrics) -> u64 {
-    event.compute_church_reward(metrics.bioload_delta)
+/// Zero for a deed logged during its category's cooldown, or whose bioload
+/// delta is out of range; submissions are refused those with
+/// `validate_bioload_delta` before they get here.
+pub fn mint_church(event: &DeedEvent, metrics: &BioloadMetrics, config: &LedgerConfig) -> u64 {
+    if is_suppressed(event) {
+        return 0;
+    }
+    event.compute_church_reward(metrics.bioload_delta, config).unwrap_or(0)
 }
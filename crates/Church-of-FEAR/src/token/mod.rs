@@ -1,3 +1,4 @@
 pub mod mint;
 pub mod burn;
 pub mod rewards;
+pub mod repair_curve;
@@ -0,0 +1,243 @@
+//! PWR grants for repairs.
+//!
+//! `RepairRewardCurve` maps an impact score to PWR along a smooth monotone
+//! curve instead of the old flat 100-above-0.8 cliff. Within a rolling
+//! window each further grant to the same actor earns a decaying fraction
+//! of the curve value, and the window's total is capped. Scores above the
+//! `repair_evidence_threshold` parameter need verified evidence, and no
+//! grant may push an account past POWER ≤ k·CHURCH.
+//!
+//! Every grant is a `repair_grant` deed whose context carries the curve
+//! version and the window state it was computed from, so the state can be
+//! rebuilt from the ledger alone.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::compliance::god_like::GodLikeEnvelope;
+use crate::ledger::account::Token;
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use crate::params::ParamKey;
+
+const GRANT_ACTOR: &str = "repair_hero";
+/// Absorbs interpolation noise so e.g. 39.999… floors to 40, not 39.
+const FLOOR_EPSILON: f64 = 1e-9;
+pub const REPAIR_GRANT: &str = "repair_grant";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum CurveShape {
+    /// `(impact_score, pwr)` knots, interpolated linearly and held flat
+    /// outside the first and last knot.
+    PiecewiseLinear { points: Vec<(f64, f64)> },
+    /// `max_pwr / (1 + e^(-steepness·(score - midpoint)))`.
+    Logistic { max_pwr: f64, midpoint: f64, steepness: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepairRewardCurve {
+    /// Recorded on every grant; bump when the shape or parameters change.
+    pub version: u32,
+    pub shape: CurveShape,
+    pub window_secs: i64,
+    /// Fraction of the curve value kept per earlier grant in the window:
+    /// the n-th grant (0-based) earns `decay^n` of it.
+    pub decay: f64,
+    /// Total PWR one actor may be granted per window.
+    pub window_cap: u64,
+}
+
+impl Default for RepairRewardCurve {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            shape: CurveShape::PiecewiseLinear { points: vec![(0.2, 0.0), (0.8, 80.0), (1.0, 120.0)] },
+            window_secs: 86_400,
+            decay: 0.5,
+            window_cap: 200,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RepairGrantError {
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+    #[error("impact score {0} outside [0, 1]")]
+    InvalidScore(f64),
+    #[error("impact score {score} is above {threshold} and needs verified evidence")]
+    UnverifiedEvidence { score: f64, threshold: f64 },
+    #[error("granting {grant} PWR would put {account_id} at {power} PWR, over k·CHURCH = {cap}")]
+    PowerCap { account_id: String, grant: u64, power: u64, cap: u64 },
+    #[error("invalid curve: {0}")]
+    InvalidCurve(String),
+}
+
+impl RepairRewardCurve {
+    /// Knots must be sorted by score with non-decreasing PWR; the decay
+    /// must lie in [0, 1].
+    pub fn validate(&self) -> Result<(), RepairGrantError> {
+        if !(0.0..=1.0).contains(&self.decay) {
+            return Err(RepairGrantError::InvalidCurve(format!("decay {} outside [0, 1]", self.decay)));
+        }
+        match &self.shape {
+            CurveShape::PiecewiseLinear { points } => {
+                if points.is_empty() {
+                    return Err(RepairGrantError::InvalidCurve("no knots".into()));
+                }
+                if points.windows(2).any(|w| w[1].0 <= w[0].0 || w[1].1 < w[0].1) {
+                    return Err(RepairGrantError::InvalidCurve("knots are not monotone".into()));
+                }
+                if points.iter().any(|&(_, pwr)| pwr < 0.0) {
+                    return Err(RepairGrantError::InvalidCurve("negative PWR knot".into()));
+                }
+            }
+            CurveShape::Logistic { max_pwr, steepness, .. } => {
+                if *max_pwr < 0.0 || *steepness < 0.0 {
+                    return Err(RepairGrantError::InvalidCurve("logistic max_pwr and steepness must be >= 0".into()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Undamped PWR for `score`, before diminishing returns and the cap.
+    pub fn value(&self, score: f64) -> f64 {
+        match &self.shape {
+            CurveShape::PiecewiseLinear { points } => {
+                let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                    return 0.0;
+                };
+                if score <= first.0 {
+                    return first.1;
+                }
+                points
+                    .windows(2)
+                    .find(|w| score <= w[1].0)
+                    .map_or(last.1, |w| w[0].1 + (w[1].1 - w[0].1) * (score - w[0].0) / (w[1].0 - w[0].0))
+            }
+            CurveShape::Logistic { max_pwr, midpoint, steepness } => {
+                max_pwr / (1.0 + (-steepness * (score - midpoint)).exp())
+            }
+        }
+    }
+}
+
+/// Whole PWR in `pwr`, rounded down.
+pub fn whole_pwr(pwr: f64) -> u64 {
+    (pwr + FLOOR_EPSILON).floor().max(0.0) as u64
+}
+
+/// Earlier grants to one actor inside the window ending at a given time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub prior_grants: u32,
+    pub granted: u64,
+}
+
+impl WindowState {
+    /// Rebuilt from `repair_grant` deeds granted in `(now - window_secs, now]`.
+    pub fn at(ledger: &TokenLedger, actor_id: &str, window_secs: i64, now: i64) -> Self {
+        ledger
+            .live_deeds()
+            .filter(|d| d.deed_type == REPAIR_GRANT && d.target_ids.iter().any(|t| t == actor_id))
+            .filter_map(|d| Some((d.context_json["granted_at"].as_i64()?, d.context_json["amount"].as_u64()?)))
+            .filter(|&(at, _)| at > now - window_secs && at <= now)
+            .fold(Self::default(), |s, (_, amount)| Self { prior_grants: s.prior_grants + 1, granted: s.granted + amount })
+    }
+}
+
+/// A repair deed put forward for a PWR grant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepairClaim {
+    pub actor_id: String,
+    pub source_event_id: String,
+    pub impact_score: f64,
+    /// Whether the repair deed's evidence passed verification.
+    pub evidence_verified: bool,
+}
+
+/// What was granted and why; the same fields are the deed's rationale.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepairGrant {
+    pub actor_id: String,
+    pub source_event_id: String,
+    pub curve_version: u32,
+    pub impact_score: f64,
+    pub curve_pwr: f64,
+    pub window: WindowState,
+    pub multiplier: f64,
+    pub window_cap: u64,
+    pub amount: u64,
+    pub granted_at: i64,
+}
+
+/// Work out the grant for `claim` under the ledger's curve without
+/// touching the ledger.
+pub fn assess_repair_grant(ledger: &TokenLedger, claim: &RepairClaim, now: i64) -> Result<RepairGrant, RepairGrantError> {
+    let curve = &ledger.config().repair_curve;
+    curve.validate()?;
+    let score = claim.impact_score;
+    if !(0.0..=1.0).contains(&score) {
+        return Err(RepairGrantError::InvalidScore(score));
+    }
+    let threshold = ledger.params().get(ParamKey::RepairEvidenceThreshold);
+    if score > threshold && !claim.evidence_verified {
+        return Err(RepairGrantError::UnverifiedEvidence { score, threshold });
+    }
+    let window = WindowState::at(ledger, &claim.actor_id, curve.window_secs, now);
+    let curve_pwr = curve.value(score);
+    let multiplier = curve.decay.powi(window.prior_grants as i32);
+    let headroom = curve.window_cap.saturating_sub(window.granted);
+    let amount = whole_pwr(curve_pwr * multiplier).min(headroom);
+    Ok(RepairGrant {
+        actor_id: claim.actor_id.clone(),
+        source_event_id: claim.source_event_id.clone(),
+        curve_version: curve.version,
+        impact_score: score,
+        curve_pwr,
+        window,
+        multiplier,
+        window_cap: curve.window_cap,
+        amount,
+        granted_at: now,
+    })
+}
+
+/// Assess `claim`, check POWER ≤ k·CHURCH for the recipient, then log a
+/// `repair_grant` deed and credit the PWR on behalf of the repair deed
+/// (tombstoning it reverses the credit). A zero grant is returned without
+/// touching the ledger.
+pub fn grant_repair_pwr(
+    ledger: &mut TokenLedger,
+    claim: &RepairClaim,
+    envelope: &GodLikeEnvelope,
+    now: i64,
+) -> Result<RepairGrant, RepairGrantError> {
+    let grant = assess_repair_grant(ledger, claim, now)?;
+    if grant.amount == 0 {
+        return Ok(grant);
+    }
+    let account = ledger.account(&claim.actor_id).ok_or_else(|| TokenLedgerError::UnknownAccount(claim.actor_id.clone()))?;
+    let power = account.balance(Token::Pwr).saturating_add(grant.amount);
+    let cap = (envelope.power_church_k * account.balance(Token::Church) as f64).floor() as u64;
+    if power > cap {
+        return Err(RepairGrantError::PowerCap { account_id: claim.actor_id.clone(), grant: grant.amount, power, cap });
+    }
+
+    let deed = DeedEvent::new(
+        ledger.last_hash(),
+        GRANT_ACTOR.to_string(),
+        vec![claim.actor_id.clone()],
+        REPAIR_GRANT.to_string(),
+        Vec::new(),
+        serde_json::to_value(&grant).expect("grant serializes"),
+        Vec::new(),
+        false,
+    );
+    ledger.append(deed)?;
+    ledger.reward_for(&claim.actor_id, Token::Pwr, grant.amount, Some(&claim.source_event_id))?;
+    Ok(grant)
+}
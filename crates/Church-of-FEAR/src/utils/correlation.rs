@@ -0,0 +1,90 @@
+//! Correlation ids.
+//!
+//! Every inbound RPC request and scheduler job runs under one id, accepted
+//! from the caller when it is well-formed and generated otherwise. The id
+//! is the `correlation_id` field of the request's tracing span, is stamped
+//! into the context of every deed built while it is current (under
+//! `CORRELATION_KEY`), and is sent with webhooks as the
+//! `CORRELATION_HEADER` header and in the body.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Reserved deed-context key holding the correlation id.
+pub const CORRELATION_KEY: &str = "_correlation_id";
+pub const CORRELATION_HEADER: &str = "X-Correlation-Id";
+
+const MAX_LEN: usize = 128;
+
+thread_local! {
+    static CURRENT: RefCell<Option<CorrelationId>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    /// Up to 128 characters of `[A-Za-z0-9._-]`; anything else could smuggle
+    /// text into headers or log lines.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let valid = !raw.is_empty()
+            && raw.len() <= MAX_LEN
+            && raw.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'));
+        valid.then(|| Self(raw.to_string()))
+    }
+
+    /// The caller's id if it parses, a fresh one otherwise.
+    pub fn accept(incoming: Option<&str>) -> Self {
+        incoming.and_then(Self::parse).unwrap_or_else(Self::generate)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The id in effect on this thread.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    /// Make this the current id until the returned guard drops.
+    pub fn enter(&self) -> CorrelationScope {
+        let previous = CURRENT.with(|c| c.replace(Some(self.clone())));
+        CorrelationScope { previous }
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Restores the previously current id on drop.
+#[must_use = "the id is only current while the scope is alive"]
+pub struct CorrelationScope {
+    previous: Option<CorrelationId>,
+}
+
+impl Drop for CorrelationScope {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Write the current id into an object under `CORRELATION_KEY`,
+/// replacing whatever a caller put there. Other values are left alone.
+pub fn stamp(value: &mut Value) {
+    if let (Some(id), Value::Object(map)) = (CorrelationId::current(), value) {
+        map.insert(CORRELATION_KEY.to_string(), Value::String(id.0));
+    }
+}
@@ -0,0 +1,52 @@
+//! Dependency-free HTTP client bits for operator webhooks and peers.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::{field, info_span};
+
+use super::correlation::{stamp, CorrelationId, CORRELATION_HEADER};
+
+/// Minimal HTTP/1.1 POST for `http://host:port/path` URLs. The current
+/// correlation id, if any, goes along as `X-Correlation-Id`.
+pub fn post_json(url: &str, body: &[u8]) -> Result<(), String> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| format!("unsupported url {}", url))?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let mut stream = TcpStream::connect(host).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).map_err(|e| e.to_string())?;
+    let correlation = CorrelationId::current().map_or_else(String::new, |id| format!("{}: {}\r\n", CORRELATION_HEADER, id));
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+        path,
+        host,
+        body.len(),
+        correlation
+    )
+    .and_then(|_| stream.write_all(body))
+    .map_err(|e| e.to_string())?;
+
+    let mut status = String::new();
+    BufReader::new(&stream).read_line(&mut status).map_err(|e| e.to_string())?;
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(format!("{} answered {}", url, status.trim())),
+    }
+}
+
+/// POST `payload` to an operator webhook inside a `notification` span,
+/// with the current correlation id stamped into the body.
+pub fn post_webhook(url: &str, kind: &str, payload: &impl Serialize) -> Result<(), String> {
+    let span = info_span!("notification", kind, url, outcome = field::Empty);
+    let _entered = span.enter();
+    let mut body = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+    stamp(&mut body);
+    let result = post_json(url, body.to_string().as_bytes());
+    span.record("outcome", if result.is_ok() { "delivered" } else { "failed" });
+    result
+}
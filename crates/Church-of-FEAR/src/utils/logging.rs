@@ -21,3 +21,19 @@ impl log::Log for SimpleLogger {
 pub fn init_logging() {
     let _ = log::set_logger(&LOGGER).map(|()| log::set_max_level(log::LevelFilter::Info));
 }
+
+/// JSON lines on stdout, one per event, carrying the fields of every
+/// enclosing span (correlation id, method, event id, ...). Filtered by
+/// `RUST_LOG`, default `info`. `log` records are captured too.
+#[cfg(feature = "json-logs")]
+pub fn init_json_logging() -> Result<(), String> {
+    use tracing_subscriber::EnvFilter;
+
+    tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .try_init()
+        .map_err(|e| e.to_string())
+}
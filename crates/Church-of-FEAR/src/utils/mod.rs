@@ -1,3 +1,5 @@
+pub mod correlation;
 pub mod crypto;
+pub mod http;
 pub mod logging;
 pub mod time;
@@ -1,8 +1,17 @@
 use chrono::{TimeZone, Utc};
+use faults::points::CLOCK_READ;
+use faults::Fault;
 
-/// Current Unix timestamp in seconds.
+/// Current Unix timestamp in seconds. Not monotone: NTP corrections move
+/// it backwards, so the ledger floors what it stamps (see
+/// `TokenLedger::authoritative_now`).
 pub fn now_timestamp() -> i64 {
-    Utc::now().timestamp()
+    let now = Utc::now().timestamp();
+    match faults::hit(CLOCK_READ) {
+        Some(Fault::Fail) => 0,
+        Some(Fault::Corrupt(skew)) => now.saturating_add(skew),
+        None => now,
+    }
 }
 
 /// Build a Utc DateTime from unix seconds.
@@ -0,0 +1,44 @@
+//! XR-grid scene export for Jetson-Line deeds.
+//!
+//! The ledger only describes the scene; rendering (bevy or any other
+//! engine) happens in a separate viewer that loads this JSON.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::deed_event::DeedEvent;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XrNode {
+    pub event_id: String,
+    pub deed_type: String,
+    /// Deeds sit on a 1D line: x is seconds since the first deed.
+    pub position: [f32; 3],
+    pub life_harm: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct XrScene {
+    pub nodes: Vec<XrNode>,
+    /// Chain links as (from, to) node indices.
+    pub links: Vec<(usize, usize)>,
+}
+
+pub fn export_scene(events: &[DeedEvent]) -> XrScene {
+    let origin = events.first().map(|e| e.timestamp).unwrap_or(0);
+    let nodes = events
+        .iter()
+        .map(|e| XrNode {
+            event_id: e.event_id.clone(),
+            deed_type: e.deed_type.clone(),
+            position: [(e.timestamp - origin) as f32, 0.0, 0.0],
+            life_harm: e.life_harm_flag,
+        })
+        .collect();
+    let links = events
+        .windows(2)
+        .enumerate()
+        .filter(|(_, w)| w[1].prev_hash == w[0].self_hash)
+        .map(|(i, _)| (i, i + 1))
+        .collect();
+    XrScene { nodes, links }
+}
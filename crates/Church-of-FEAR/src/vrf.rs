@@ -0,0 +1,373 @@
+//! Verifiable randomness for sampling decisions.
+//!
+//! A node that picks which cold segment to spot-check, or which validators
+//! sit on a panel, could pick to suit itself. Every such choice instead
+//! draws from a seed derived from the ledger:
+//!
+//! ```text
+//! seed = sha256("<segment root>:<purpose>:<counter>")
+//! ```
+//!
+//! where the segment is the latest sealed one, its root the Merkle root of
+//! its deeds' hashes, and `counter` the number of earlier draws for the
+//! same purpose. Sealing logs a `segment_sealed` deed with the segment's
+//! index, bounds and root, and a draw must use the last seal logged before
+//! it, so a node cannot shop among older segments for a seed it likes. The seed feeds a SHA-256 counter-mode stream
+//! (`RandomStream`) and the draw is logged as a `randomness_drawn` deed
+//! holding the derivation and what was chosen, so anyone holding the deeds
+//! can recompute it (`rederive`, `audit_draws`). Every draw is on the
+//! ledger, so a node drawing again until it likes the answer shows it.
+//!
+//! The seed is fixed once a segment is sealed, so whoever seals can
+//! foresee it. Where that matters, `commit` logs a `randomness_committed`
+//! deed carrying the hash of a secret salt; once that deed is sealed into
+//! a segment, `reveal` logs a `randomness_revealed` deed with the salt and
+//! a seed over the root, purpose, counter and salt. The committer could not
+//! know the root when committing, and the sealer could not know the salt
+//! when sealing. A salt other than the committed one is refused.
+
+use std::collections::BTreeMap;
+
+use deed_core::merkle_root;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::ledger::deed_event::DeedEvent;
+use crate::ledger::token_ledger::{segment_root, TokenLedger, TokenLedgerError, SEGMENT_SEALED};
+use crate::utils::crypto::sha256;
+
+pub const RANDOMNESS_DRAWN: &str = "randomness_drawn";
+pub const RANDOMNESS_COMMITTED: &str = "randomness_committed";
+pub const RANDOMNESS_REVEALED: &str = "randomness_revealed";
+
+#[derive(Error, Debug)]
+pub enum VrfError {
+    #[error("no sealed segment to derive randomness from")]
+    NoSealedSegment,
+    #[error("cannot sample {n} of {out_of}")]
+    Range { n: usize, out_of: usize },
+    #[error("no randomness commitment {0}")]
+    UnknownCommitment(String),
+    #[error("commitment {0} was already revealed")]
+    AlreadyRevealed(String),
+    #[error("commitment {0} is not sealed into a segment yet")]
+    CommitmentUnsealed(String),
+    #[error("salt does not match commitment {0}")]
+    SaltMismatch(String),
+    /// A logged draw that does not recompute from the deeds before it.
+    #[error("deed {event_id} does not re-derive: {reason}")]
+    Mismatch { event_id: String, reason: String },
+    #[error(transparent)]
+    Ledger(#[from] TokenLedgerError),
+}
+
+/// Where a draw's randomness came from, as recorded in its deed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Derivation {
+    pub purpose: String,
+    /// The sealed segment whose root seeds the draw: its index and the
+    /// positions of its first and last deeds.
+    pub segment_index: usize,
+    pub segment_first: usize,
+    pub segment_last: usize,
+    pub segment_root: String,
+    /// Draws for `purpose` logged before this one.
+    pub counter: u64,
+    /// The `randomness_committed` deed, for a revealed draw.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    pub seed: String,
+}
+
+impl Derivation {
+    fn seed_for(&self) -> String {
+        match &self.salt {
+            Some(salt) => sha256(&format!("{}:{}:{}:{}", self.segment_root, self.purpose, self.counter, salt)),
+            None => sha256(&format!("{}:{}:{}", self.segment_root, self.purpose, self.counter)),
+        }
+    }
+
+    pub fn stream(&self) -> RandomStream {
+        RandomStream::new(&self.seed)
+    }
+}
+
+/// What a draw was used for, recorded beside its derivation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "op")]
+pub enum DrawUse {
+    SampleIndices {
+        n: usize,
+        out_of: usize,
+        result: Vec<usize>,
+    },
+    /// `permutation[i]` is the original position of the item placed at `i`.
+    Shuffle {
+        permutation: Vec<usize>,
+    },
+}
+
+impl DrawUse {
+    /// The same use recomputed from `derivation`.
+    fn replay(&self, derivation: &Derivation) -> Self {
+        let mut stream = derivation.stream();
+        match self {
+            DrawUse::SampleIndices { n, out_of, .. } => {
+                DrawUse::SampleIndices { n: *n, out_of: *out_of, result: stream.sample_indices(*n, *out_of) }
+            }
+            DrawUse::Shuffle { permutation } => {
+                let mut replayed: Vec<usize> = (0..permutation.len()).collect();
+                stream.shuffle(&mut replayed);
+                DrawUse::Shuffle { permutation: replayed }
+            }
+        }
+    }
+}
+
+/// SHA-256 in counter mode over a seed: block `i` is `sha256(seed || i)`.
+pub struct RandomStream {
+    seed: Vec<u8>,
+    block: u64,
+    words: Vec<u64>,
+}
+
+impl RandomStream {
+    pub fn new(seed: &str) -> Self {
+        Self { seed: seed.as_bytes().to_vec(), block: 0, words: Vec::new() }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.words.is_empty() {
+            let mut hasher = Sha256::new();
+            hasher.update(&self.seed);
+            hasher.update(self.block.to_be_bytes());
+            self.block += 1;
+            let digest = hasher.finalize();
+            self.words = digest.chunks(8).rev().map(|c| u64::from_be_bytes(c.try_into().expect("8 bytes"))).collect();
+        }
+        self.words.pop().expect("refilled")
+    }
+
+    /// Uniform in `[0, bound)`, without modulo bias. `bound` must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % bound;
+            }
+        }
+    }
+
+    /// `n` distinct indices below `out_of`, in the order drawn (a partial
+    /// Fisher-Yates shuffle of `0..out_of`).
+    pub fn sample_indices(&mut self, n: usize, out_of: usize) -> Vec<usize> {
+        let mut moved: BTreeMap<usize, usize> = BTreeMap::new();
+        (0..n.min(out_of))
+            .map(|i| {
+                let j = i + self.below((out_of - i) as u64) as usize;
+                let picked = moved.get(&j).copied().unwrap_or(j);
+                let displaced = moved.get(&i).copied().unwrap_or(i);
+                moved.insert(j, displaced);
+                picked
+            })
+            .collect()
+    }
+
+    /// Fisher-Yates shuffle in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+fn purpose_of(deed: &DeedEvent) -> Option<&str> {
+    match deed.deed_type.as_str() {
+        RANDOMNESS_DRAWN | RANDOMNESS_REVEALED => deed.context_json["derivation"]["purpose"].as_str(),
+        _ => None,
+    }
+}
+
+fn counter(deeds: &[DeedEvent], purpose: &str) -> u64 {
+    deeds.iter().filter(|d| purpose_of(d) == Some(purpose)).count() as u64
+}
+
+/// The derivation a draw for `purpose` made now would use.
+fn derive(ledger: &TokenLedger, purpose: &str, salt: Option<(&str, &str)>) -> Result<Derivation, VrfError> {
+    let segment = ledger.sealed_segments().last().ok_or(VrfError::NoSealedSegment)?;
+    let mut derivation = Derivation {
+        purpose: purpose.to_string(),
+        segment_index: segment.index,
+        segment_first: segment.first,
+        segment_last: segment.last,
+        segment_root: segment_root(ledger, segment),
+        counter: counter(ledger.deeds(), purpose),
+        commitment_id: salt.map(|(id, _)| id.to_string()),
+        salt: salt.map(|(_, salt)| salt.to_string()),
+        seed: String::new(),
+    };
+    derivation.seed = derivation.seed_for();
+    Ok(derivation)
+}
+
+fn log_draw(ledger: &mut TokenLedger, derivation: &Derivation, used: &DrawUse) -> Result<(), VrfError> {
+    ledger.log_randomness(RANDOMNESS_DRAWN, json!({ "derivation": derivation, "use": used }))?;
+    Ok(())
+}
+
+/// Draw `n` distinct indices below `out_of` for `purpose`, logging the draw.
+pub fn sample_indices(
+    ledger: &mut TokenLedger,
+    purpose: &str,
+    n: usize,
+    out_of: usize,
+) -> Result<Vec<usize>, VrfError> {
+    if n > out_of {
+        return Err(VrfError::Range { n, out_of });
+    }
+    let derivation = derive(ledger, purpose, None)?;
+    let result = derivation.stream().sample_indices(n, out_of);
+    log_draw(ledger, &derivation, &DrawUse::SampleIndices { n, out_of, result: result.clone() })?;
+    Ok(result)
+}
+
+/// `items` in an order drawn for `purpose`, logging the draw.
+pub fn shuffle<T: Clone>(ledger: &mut TokenLedger, purpose: &str, items: &[T]) -> Result<Vec<T>, VrfError> {
+    let derivation = derive(ledger, purpose, None)?;
+    let mut permutation: Vec<usize> = (0..items.len()).collect();
+    derivation.stream().shuffle(&mut permutation);
+    let shuffled = permutation.iter().map(|&i| items[i].clone()).collect();
+    log_draw(ledger, &derivation, &DrawUse::Shuffle { permutation })?;
+    Ok(shuffled)
+}
+
+/// The commitment to publish for `salt`.
+pub fn commitment_of(salt: &str) -> String {
+    sha256(salt)
+}
+
+/// Log a commitment (`commitment_of(salt)`) to a salt for a later draw for
+/// `purpose`. Returns the `randomness_committed` deed's event id.
+pub fn commit(ledger: &mut TokenLedger, purpose: &str, commitment: &str) -> Result<String, VrfError> {
+    let context = json!({ "purpose": purpose, "commitment": commitment });
+    Ok(ledger.log_randomness(RANDOMNESS_COMMITTED, context)?.event_id.clone())
+}
+
+/// Reveal the salt behind `commitment_id` and derive randomness from it and
+/// the latest sealed segment, which must hold the commitment.
+pub fn reveal(ledger: &mut TokenLedger, commitment_id: &str, salt: &str) -> Result<Derivation, VrfError> {
+    let deeds = ledger.deeds();
+    let position = deeds
+        .iter()
+        .position(|d| d.deed_type == RANDOMNESS_COMMITTED && d.event_id == commitment_id)
+        .ok_or_else(|| VrfError::UnknownCommitment(commitment_id.to_string()))?;
+    let committed = &deeds[position].context_json;
+    if deeds
+        .iter()
+        .any(|d| d.deed_type == RANDOMNESS_REVEALED && d.context_json["derivation"]["commitment_id"] == commitment_id)
+    {
+        return Err(VrfError::AlreadyRevealed(commitment_id.to_string()));
+    }
+    if committed["commitment"].as_str() != Some(commitment_of(salt).as_str()) {
+        return Err(VrfError::SaltMismatch(commitment_id.to_string()));
+    }
+    if ledger.sealed_segments().last().is_none_or(|s| s.last < position) {
+        return Err(VrfError::CommitmentUnsealed(commitment_id.to_string()));
+    }
+    let purpose = committed["purpose"].as_str().unwrap_or_default().to_string();
+    let derivation = derive(ledger, &purpose, Some((commitment_id, salt)))?;
+    ledger.log_randomness(RANDOMNESS_REVEALED, json!({ "derivation": derivation }))?;
+    Ok(derivation)
+}
+
+/// Recompute the draw logged at `position` of `deeds` from the deeds before
+/// it: the segment against the last `segment_sealed` deed before the draw,
+/// the segment root, the counter, the salt against its commitment, the
+/// seed and, for `randomness_drawn`, what was chosen.
+pub fn rederive(deeds: &[DeedEvent], position: usize) -> Result<Derivation, VrfError> {
+    let deed = &deeds[position];
+    let mismatch = |reason: String| VrfError::Mismatch { event_id: deed.event_id.clone(), reason };
+    let recorded: Derivation = serde_json::from_value(deed.context_json["derivation"].clone())
+        .map_err(|e| mismatch(format!("unreadable derivation: {}", e)))?;
+    let (first, last) = (recorded.segment_first, recorded.segment_last);
+    let seal = deeds[..position]
+        .iter()
+        .rev()
+        .find(|d| d.deed_type == SEGMENT_SEALED)
+        .ok_or_else(|| mismatch("no segment was sealed before the draw".to_string()))?;
+    let sealed = |field: &str| seal.context_json[field].as_u64().map(|v| v as usize);
+    if (sealed("index"), sealed("first"), sealed("last")) != (Some(recorded.segment_index), Some(first), Some(last))
+        || seal.context_json["root"].as_str() != Some(recorded.segment_root.as_str())
+    {
+        return Err(mismatch(format!(
+            "segment {} ({}..={}) is not the last one sealed before the draw, by {}",
+            recorded.segment_index, first, last, seal.event_id
+        )));
+    }
+    if first > last || last >= position {
+        return Err(mismatch(format!("segment {}..={} does not precede the draw", first, last)));
+    }
+    let leaves: Vec<String> = deeds[first..=last].iter().map(|d| d.self_hash.clone()).collect();
+    if merkle_root(leaves) != recorded.segment_root {
+        return Err(mismatch("segment root differs from the deeds".to_string()));
+    }
+    let expected = counter(&deeds[..position], &recorded.purpose);
+    if recorded.counter != expected {
+        return Err(mismatch(format!("counter {} where {} draws precede it", recorded.counter, expected)));
+    }
+    if deed.deed_type == RANDOMNESS_REVEALED {
+        let (Some(id), Some(salt)) = (&recorded.commitment_id, &recorded.salt) else {
+            return Err(mismatch("revealed draw without commitment and salt".to_string()));
+        };
+        let committed = deeds[..=last]
+            .iter()
+            .find(|d| d.deed_type == RANDOMNESS_COMMITTED && d.event_id == *id)
+            .ok_or_else(|| mismatch(format!("commitment {} is not in the segment or before it", id)))?;
+        if committed.context_json["commitment"].as_str() != Some(commitment_of(salt).as_str()) {
+            return Err(mismatch(format!("salt does not match commitment {}", id)));
+        }
+        if committed.context_json["purpose"].as_str() != Some(recorded.purpose.as_str()) {
+            return Err(mismatch(format!("commitment {} is for another purpose", id)));
+        }
+    }
+    if recorded.seed_for() != recorded.seed {
+        return Err(mismatch("seed does not recompute".to_string()));
+    }
+    if deed.deed_type == RANDOMNESS_DRAWN {
+        let used: DrawUse = serde_json::from_value(deed.context_json["use"].clone())
+            .map_err(|e| mismatch(format!("unreadable use: {}", e)))?;
+        if used.replay(&recorded) != used {
+            return Err(mismatch("recorded choice differs from the recomputed one".to_string()));
+        }
+    }
+    Ok(recorded)
+}
+
+/// `rederive` for every draw in `deeds`, with its position.
+pub fn audit_draws(deeds: &[DeedEvent]) -> Vec<(usize, Result<Derivation, VrfError>)> {
+    deeds
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.deed_type == RANDOMNESS_DRAWN || d.deed_type == RANDOMNESS_REVEALED)
+        .map(|(position, _)| (position, rederive(deeds, position)))
+        .collect()
+}
+
+/// `rederive` against `ledger`, also checking that the draw used the
+/// boundaries of a segment the ledger actually sealed.
+pub fn verify_draw(ledger: &TokenLedger, position: usize) -> Result<Derivation, VrfError> {
+    let derivation = rederive(ledger.deeds(), position)?;
+    match ledger.sealed_segments().get(derivation.segment_index) {
+        Some(s) if s.first == derivation.segment_first && s.last == derivation.segment_last => Ok(derivation),
+        _ => Err(VrfError::Mismatch {
+            event_id: ledger.deeds()[position].event_id.clone(),
+            reason: format!("segment {} was not sealed with those bounds", derivation.segment_index),
+        }),
+    }
+}
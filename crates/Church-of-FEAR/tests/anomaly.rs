@@ -0,0 +1,253 @@
+#![cfg(feature = "core")]
+
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::anomaly::{
+    holds, rescore, review_hold, screen_mint, screened_mints, AnomalyAlert, AnomalyError, AnomalyNotifier, AnomalyPolicy,
+    AnomalyState, HoldDecision, HoldStatus, ANOMALY_BASELINE, ANOMALY_SUSPECT, MINT_SCREENED,
+};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::obligations::PENDING_OBLIGATIONS;
+use church_of_fear::sponsor::pool::PoolPolicy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const HOUR: i64 = 3_600;
+const DAY: i64 = 86_400;
+const TYPES: [&str; 4] = ["tree_planting", "homelessness_relief", "river_cleanup", "food_share"];
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<AnomalyAlert>>>);
+
+impl AnomalyNotifier for Recorder {
+    fn notify(&self, alert: &AnomalyAlert) -> Result<(), String> {
+        self.0.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+fn ledger(hold_suspects: bool) -> TokenLedger {
+    let anomaly = AnomalyPolicy { hold_suspects, ..AnomalyPolicy::default() };
+    // No tithe, so credited amounts are easy to read.
+    let pool = PoolPolicy { tithe_bps: 0, ..PoolPolicy::default() };
+    TokenLedger::new(LedgerConfig { anomaly, pool, ..LedgerConfig::default() })
+}
+
+/// Append a `deed_type` deed by `actor` and screen a reward for it.
+fn mint(ledger: &mut TokenLedger, actor: &str, deed_type: &str, amount: u64, at: i64, notifier: &Recorder) -> (bool, String) {
+    let deed = DeedEvent::new(ledger.last_hash(), actor.into(), vec![], deed_type.into(), vec![], json!({}), vec![], false);
+    let source = ledger.append(deed).unwrap().event_id.clone();
+    let screening = screen_mint(ledger, actor, Token::Church, amount, Some(&source), at, Some(notifier)).unwrap();
+    (screening.flagged, screening.screening_event_id)
+}
+
+/// Three volunteer mints of a few dozen at random in the ten minutes after `at`.
+fn chatter(ledger: &mut TokenLedger, rng: &mut StdRng, at: i64, notifier: &Recorder) {
+    for _ in 0..3 {
+        let volunteer = format!("volunteer-{}", rng.gen_range(0..9));
+        mint(ledger, &volunteer, TYPES[rng.gen_range(0..4)], rng.gen_range(10..30), at + rng.gen_range(1..600), notifier);
+    }
+}
+
+/// Twenty volunteers minting at random, plus one key minting 95 (just under
+/// a 100 cap) every six hours on the same deed type. Returns the flagged
+/// mints as (actor, mint index of that actor).
+fn low_and_slow(ledger: &mut TokenLedger, days: i64, notifier: &Recorder) -> Vec<(String, usize)> {
+    let mut rng = StdRng::seed_from_u64(441);
+    let mut schedule: Vec<(i64, String, &str, u64)> = Vec::new();
+    for v in 0..20 {
+        let mut at = T0 + rng.gen_range(0..DAY);
+        while at < T0 + days * DAY {
+            schedule.push((at, format!("volunteer-{v}"), TYPES[rng.gen_range(0..4)], rng.gen_range(10..60)));
+            at += (-(1.0 - rng.gen::<f64>()).ln() * DAY as f64) as i64 + 60;
+        }
+    }
+    let mut at = T0 + DAY;
+    while at < T0 + days * DAY {
+        schedule.push((at, "mallory".into(), "tree_planting", 95));
+        at += 6 * HOUR + rng.gen_range(-300..300);
+    }
+    schedule.sort();
+
+    let mut counts = std::collections::BTreeMap::new();
+    let mut flagged = Vec::new();
+    for (at, actor, deed_type, amount) in schedule {
+        let n = counts.entry(actor.clone()).or_insert(0usize);
+        if mint(ledger, &actor, deed_type, amount, at, notifier).0 {
+            flagged.push((actor, *n));
+        }
+        *n += 1;
+    }
+    flagged
+}
+
+#[test]
+fn low_and_slow_minting_is_eventually_flagged() {
+    let mut ledger = ledger(false);
+    let alerts = Recorder::default();
+    let flagged = low_and_slow(&mut ledger, 30, &alerts);
+
+    assert!(!flagged.is_empty());
+    assert!(flagged.iter().all(|(actor, _)| actor == "mallory"), "{flagged:?}");
+    let min = AnomalyPolicy::default().min_observations as usize;
+    assert!(flagged.iter().all(|&(_, n)| n + 1 >= min));
+
+    // Flagged mints still credit; the screening deed carries the flag.
+    let suspect: Vec<_> = screened_mints(&ledger).into_iter().filter(|m| m.flagged).collect();
+    assert_eq!(suspect.len(), flagged.len());
+    assert!(suspect.iter().all(|m| !m.held && m.score.unwrap().score >= 3.0));
+    let deed = ledger.deed(&suspect[0].screening_event_id).unwrap();
+    assert_eq!((deed.deed_type.as_str(), deed.ethics_flags.clone()), (MINT_SCREENED, vec![ANOMALY_SUSPECT.to_string()]));
+    assert_eq!(
+        ledger.account("mallory").unwrap().balance(Token::Church),
+        95 * screened_mints(&ledger).iter().filter(|m| m.actor_id == "mallory").count() as u64
+    );
+
+    let alerts = alerts.0.lock().unwrap();
+    assert_eq!(alerts.len(), flagged.len());
+    assert_eq!(alerts[0].screening_event_id, suspect[0].screening_event_id);
+    assert!(ledger.deeds().iter().any(|d| d.deed_type == ANOMALY_BASELINE));
+}
+
+#[test]
+fn cold_baselines_never_flag() {
+    let mut ledger = ledger(false);
+    let alerts = Recorder::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    // One key minting like a metronome from the first block, among
+    // volunteers: every mint is cold until the ledger and the actor have
+    // ten observations each.
+    for i in 0..9 {
+        let (flagged, id) = mint(&mut ledger, "bot", "tree_planting", 90, T0 + i * 600, &alerts);
+        assert!(!flagged);
+        assert_eq!(screened_mints(&ledger).last().unwrap().screening_event_id, id);
+        chatter(&mut ledger, &mut rng, T0 + i * 600, &alerts);
+    }
+    assert!(screened_mints(&ledger).iter().all(|m| m.score.is_none()));
+    assert!(alerts.0.lock().unwrap().is_empty());
+
+    let (flagged, _) = mint(&mut ledger, "bot", "tree_planting", 90, T0 + 9 * 600, &alerts);
+    assert!(flagged);
+    // A new actor on a warm ledger is cold as well.
+    let (flagged, _) = mint(&mut ledger, "newcomer", "river_cleanup", 90, T0 + 9 * 600 + 60, &alerts);
+    assert!(!flagged);
+    assert!(screened_mints(&ledger).last().unwrap().score.is_none());
+}
+
+#[test]
+fn held_mints_wait_for_review() {
+    let mut ledger = ledger(true);
+    let alerts = Recorder::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    for i in 0..9 {
+        mint(&mut ledger, "bot", "tree_planting", 90, T0 + i * 600, &alerts);
+        chatter(&mut ledger, &mut rng, T0 + i * 600, &alerts);
+    }
+    let before = ledger.account("bot").unwrap().balance(Token::Church);
+    let (flagged, first) = mint(&mut ledger, "bot", "tree_planting", 90, T0 + 9 * 600, &alerts);
+    chatter(&mut ledger, &mut rng, T0 + 9 * 600, &alerts);
+    let (flagged_again, second) = mint(&mut ledger, "bot", "tree_planting", 90, T0 + 10 * 600, &alerts);
+    assert!(flagged && flagged_again);
+    assert!(alerts.0.lock().unwrap().iter().all(|a| a.held));
+
+    // Held rewards sit in the obligations escrow, not with the actor.
+    assert_eq!(ledger.account("bot").unwrap().balance(Token::Church), before);
+    assert_eq!(ledger.account(PENDING_OBLIGATIONS).unwrap().balance(Token::Church), 180);
+    assert_eq!(holds(&ledger).len(), 2);
+    assert!(ledger.supply_report().reconciles());
+
+    assert_eq!(
+        review_hold(&mut ledger, &first, HoldDecision::Clear, "erin", "Member", "", T0 + DAY),
+        Err(AnomalyError::RoleNotAllowed("Member".into()))
+    );
+    assert_eq!(review_hold(&mut ledger, &first, HoldDecision::Clear, "erin", "Auditor", "known batch job", T0 + DAY), Ok(90));
+    assert_eq!(review_hold(&mut ledger, &second, HoldDecision::Confirm, "erin", "Auditor", "stolen key", T0 + DAY), Ok(0));
+    assert_eq!(
+        review_hold(&mut ledger, &second, HoldDecision::Clear, "erin", "Auditor", "", T0 + DAY),
+        Err(AnomalyError::AlreadyReviewed(second.clone()))
+    );
+    assert_eq!(
+        review_hold(&mut ledger, "no-such-screening", HoldDecision::Clear, "erin", "Auditor", "", T0),
+        Err(AnomalyError::UnknownHold("no-such-screening".into()))
+    );
+
+    assert_eq!(ledger.account("bot").unwrap().balance(Token::Church), before + 90);
+    assert_eq!(ledger.account(PENDING_OBLIGATIONS).unwrap().balance(Token::Church), 0);
+    assert!(ledger.supply_report().reconciles());
+    assert!(holds(&ledger).iter().all(|h| matches!(h.status, HoldStatus::Reviewed { .. })));
+    assert!(matches!(
+        &holds(&ledger)[1].status,
+        HoldStatus::Reviewed { decision: HoldDecision::Confirm, reviewer, .. } if reviewer == "erin"
+    ));
+
+    // Review and screening deeds are the ledger's to write.
+    let forged = DeedEvent::new(ledger.last_hash(), "bot".into(), vec![], MINT_SCREENED.into(), vec![], json!({}), vec![], false);
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+}
+
+#[test]
+fn replay_reproduces_identical_scores() {
+    let mut ledger = ledger(true);
+    let alerts = Recorder::default();
+    low_and_slow(&mut ledger, 12, &alerts);
+    let held = holds(&ledger);
+    review_hold(&mut ledger, &held[0].screening_event_id, HoldDecision::Clear, "erin", "Regulator", "", T0 + 20 * DAY).unwrap();
+
+    let recorded: Vec<_> = screened_mints(&ledger).into_iter().map(|m| (m.screening_event_id, m.score)).collect();
+    assert_eq!(rescore(&ledger), recorded);
+
+    let export: Vec<DeedEvent> = ledger.export_deeds(false).into_iter().cloned().collect();
+    let mut replayed = TokenLedger::replay(ledger.config().clone(), export).unwrap();
+    assert_eq!(rescore(&replayed), recorded);
+    assert_eq!(AnomalyState::from_ledger(&replayed), AnomalyState::from_ledger(&ledger));
+    assert_eq!(holds(&replayed), holds(&ledger));
+    assert_eq!(replayed.supply_report(), ledger.supply_report());
+
+    // Both score the next mint the same way.
+    let at = T0 + 21 * DAY;
+    let next_original = mint(&mut ledger, "mallory", "tree_planting", 95, at, &alerts);
+    let next_replayed = mint(&mut replayed, "mallory", "tree_planting", 95, at, &alerts);
+    assert_eq!(next_original.0, next_replayed.0);
+    assert_eq!(screened_mints(&ledger).last().unwrap().score, screened_mints(&replayed).last().unwrap().score);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn holds_are_reviewed_over_rpc() {
+    use church_of_fear::rpc::server::{dispatch_request, dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let mut ledger = ledger(true);
+    let alerts = Recorder::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut last = String::new();
+    for i in 0..10 {
+        last = mint(&mut ledger, "bot", "tree_planting", 90, T0 + i * 600, &alerts).1;
+        chatter(&mut ledger, &mut rng, T0 + i * 600, &alerts);
+    }
+    let ledger = Arc::new(Mutex::new(ledger));
+    let ctx = RpcContext { ledger: Some(ledger.clone()), ..RpcContext::default() };
+    let request = |decision: &str, role: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "auto_church.review_anomaly_hold",
+            "params": { "screening_event_id": last, "decision": decision, "reviewer": "erin", "role": role, "now": T0 + DAY },
+            "id": 1
+        })
+        .to_string()
+    };
+
+    let bare: Value = serde_json::from_str(&dispatch_request(&request("clear", "Auditor"))).unwrap();
+    assert_eq!(bare["error"]["code"], 1004);
+    let denied: Value = serde_json::from_str(&dispatch_request_with(&request("clear", "Member"), &ctx)).unwrap();
+    assert_eq!(denied["error"]["code"], -32602);
+    let cleared: Value = serde_json::from_str(&dispatch_request_with(&request("clear", "Auditor"), &ctx)).unwrap();
+    assert_eq!(cleared["result"]["paid"], 90);
+    assert_eq!(cleared["result"]["decision"], "clear");
+    assert!(matches!(holds(&ledger.lock().unwrap())[0].status, HoldStatus::Reviewed { decision: HoldDecision::Clear, .. }));
+}
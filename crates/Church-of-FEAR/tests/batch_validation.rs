@@ -0,0 +1,200 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::compliance::batch::{
+    validate_batch, validate_batch_sequential, DeedRule, DeedRules, DeedVerdict, RuleContext, RuleOrderSensitivity,
+};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::WatershedCleanupDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedError, DeedEvent};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const RIVER: &str = "target:salt-river";
+
+fn rehash(mut deed: DeedEvent) -> DeedEvent {
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+fn planting(prev_hash: String, actor: &str, at: i64) -> DeedEvent {
+    let deed = DeedEvent::new(prev_hash, actor.into(), vec![], "tree_planting".into(), vec![], json!({}), vec![], false);
+    rehash(DeedEvent { timestamp: at, ..deed })
+}
+
+fn cleanup(prev_hash: String, actor: &str, at: i64) -> DeedEvent {
+    let deed = WatershedCleanupDeed::builder()
+        .actor_id(actor)
+        .location("Tempe, AZ")
+        .volunteers(12)
+        .waste_kg(80.0)
+        .evidence_uri("ipfs://cleanup")
+        .target(RIVER)
+        .build(prev_hash)
+        .unwrap();
+    rehash(DeedEvent { timestamp: at, ..deed })
+}
+
+fn metrics() -> BioloadMetrics {
+    BioloadMetrics::new(-0.2, 0.1, 0.5)
+}
+
+fn seeded_ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let deed = cleanup(ledger.last_hash(), "alice", T0);
+    ledger.append(deed).unwrap();
+    ledger
+}
+
+/// A chained batch of `n` deeds with faults of both phases sprinkled in:
+/// broken hashes, wrong links, out-of-range metrics, schema gaps, ethics
+/// flags and cooldown clashes. Faulty deeds are skipped by the chain,
+/// except now and then, when the next deed links to one anyway.
+fn random_batch(rng: &mut StdRng, tip: String, n: usize) -> Vec<(DeedEvent, BioloadMetrics)> {
+    let mut prev = tip;
+    let mut batch = Vec::with_capacity(n);
+    for i in 0..n {
+        let actor = ["alice", "bob", "carol"][rng.gen_range(0..3)];
+        let at = T0 + 60 * i as i64;
+        let mut deed = match rng.gen_bool(0.3) {
+            true => cleanup(prev.clone(), actor, at),
+            false => planting(prev.clone(), actor, at),
+        };
+        let mut metrics = metrics();
+        match rng.gen_range(0..20) {
+            0 => deed.context_json["tampered"] = json!(true),
+            1 => deed = rehash(DeedEvent { prev_hash: "f".repeat(64), ..deed }),
+            2 => metrics.roh = 0.9,
+            3 => {
+                deed = cleanup(prev.clone(), actor, at);
+                deed.context_json.as_object_mut().unwrap().remove("volunteers");
+                deed = rehash(deed);
+            }
+            4 => deed = rehash(DeedEvent { ethics_flags: vec!["coercion".into()], ..deed }),
+            // Faults in both phases at once: the earlier rule wins.
+            5 => deed = DeedEvent { prev_hash: "0".repeat(64), ..rehash(DeedEvent { life_harm_flag: true, ..deed }) },
+            _ => {
+                prev = deed.self_hash.clone();
+                batch.push((deed, metrics));
+                continue;
+            }
+        };
+        if rng.gen_bool(0.1) {
+            prev = deed.self_hash.clone();
+        }
+        batch.push((deed, metrics));
+    }
+    batch
+}
+
+fn rejected_by(verdicts: &[DeedVerdict], rule: &str) -> usize {
+    verdicts.iter().filter(|v| matches!(v, DeedVerdict::Rejected { rule: r, .. } if *r == rule)).count()
+}
+
+#[test]
+fn two_phases_reach_the_sequential_verdicts() {
+    let ledger = seeded_ledger();
+    let rules = DeedRules::standard().with_parallel_threshold(1);
+    let mut all = Vec::new();
+    for seed in 0..24 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let n = rng.gen_range(0..400);
+        let batch = random_batch(&mut rng, ledger.last_hash(), n);
+        let sequential = validate_batch_sequential(&ledger, &batch, &rules);
+        let result = validate_batch(&ledger, &batch, &rules);
+        assert_eq!(result.verdicts, sequential, "seed {seed}");
+        assert_eq!(format!("{:?}", result.verdicts), format!("{:?}", sequential));
+        all.extend(sequential);
+    }
+    for rule in ["self_hash", "chain_link", "biophysical", "context_schema", "ethics"] {
+        assert!(rejected_by(&all, rule) > 0, "no deed rejected by {rule}");
+    }
+    assert!(all.iter().any(|v| matches!(v, DeedVerdict::Accepted { cooldown: Some(_) })));
+    assert!(all.iter().any(|v| matches!(v, DeedVerdict::Accepted { cooldown: None })));
+}
+
+#[test]
+fn a_rejected_deed_stops_the_chain_after_it() {
+    let ledger = seeded_ledger();
+    let first = planting(ledger.last_hash(), "bob", T0 + 60);
+    let second = planting(first.self_hash.clone(), "bob", T0 + 120);
+    let flagged = rehash(DeedEvent { ethics_flags: vec!["coercion".into()], ..first });
+    let second = rehash(DeedEvent { prev_hash: flagged.self_hash.clone(), ..second });
+    let result = validate_batch(&ledger, &[(flagged, metrics()), (second, metrics())], &DeedRules::standard());
+
+    assert!(matches!(&result.verdicts[0], DeedVerdict::Rejected { rule: "ethics", .. }));
+    let DeedVerdict::Rejected { rule, error } = &result.verdicts[1] else { panic!("{:?}", result.verdicts[1]) };
+    assert_eq!(*rule, "chain_link");
+    assert!(matches!(error, DeedError::HashMismatch(_)));
+    assert_eq!(result.accepted(), 0);
+}
+
+#[test]
+fn cooldowns_count_the_deeds_accepted_earlier_in_the_batch() {
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let first = cleanup(ledger.last_hash(), "alice", T0);
+    let second = cleanup(first.self_hash.clone(), "alice", T0 + 3_600);
+    let third = cleanup(second.self_hash.clone(), "alice", T0 + 7_200);
+    let batch = [(first.clone(), metrics()), (second, metrics()), (third, metrics())];
+    let result = validate_batch(&ledger, &batch, &DeedRules::standard());
+
+    let blocked_by: Vec<Option<String>> = result
+        .verdicts
+        .iter()
+        .map(|v| match v {
+            DeedVerdict::Accepted { cooldown } => cooldown.as_ref().map(|c| c.blocking_event_id.clone()),
+            DeedVerdict::Rejected { .. } => panic!("{v:?}"),
+        })
+        .collect();
+    // A suppressed deed never restarts the cooldown.
+    assert_eq!(blocked_by, [None, Some(first.event_id.clone()), Some(first.event_id)]);
+}
+
+#[test]
+fn small_batches_skip_the_thread_pool() {
+    let ledger = seeded_ledger();
+    let mut rng = StdRng::seed_from_u64(7);
+    let rules = DeedRules::standard().with_parallel_threshold(64);
+    let small = random_batch(&mut rng, ledger.last_hash(), 63);
+    assert!(!validate_batch(&ledger, &small, &rules).timings.parallel);
+    assert!(!rules.runs_parallel(0));
+
+    let large = random_batch(&mut rng, ledger.last_hash(), 64);
+    let result = validate_batch(&ledger, &large, &rules);
+    assert_eq!(result.timings.parallel, rayon::current_num_threads() > 1);
+    assert_eq!(result.verdicts, validate_batch_sequential(&ledger, &large, &rules));
+}
+
+/// Order-dependent, but declared otherwise.
+struct FirstDeedOfActor;
+
+impl DeedRule for FirstDeedOfActor {
+    fn name(&self) -> &'static str {
+        "first_deed_of_actor"
+    }
+
+    fn sensitivity(&self) -> RuleOrderSensitivity {
+        RuleOrderSensitivity::Independent
+    }
+
+    fn check(&self, deed: &DeedEvent, ctx: &RuleContext<'_>) -> Result<(), DeedError> {
+        let seen = ctx.chain().is_some_and(|c| c.accepted.iter().any(|d| d.actor_id == deed.actor_id));
+        match seen {
+            true => Err(DeedError::InvariantViolation(format!("{} already has a deed in this batch", deed.actor_id))),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "rule `first_deed_of_actor` is declared Independent but reads the chain")]
+fn a_misdeclared_rule_fails_a_debug_assertion() {
+    let ledger = seeded_ledger();
+    let deed = planting(ledger.last_hash(), "bob", T0 + 60);
+    validate_batch(&ledger, &[(deed, metrics())], &DeedRules::standard().with(FirstDeedOfActor));
+}
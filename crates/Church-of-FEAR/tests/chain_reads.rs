@@ -0,0 +1,142 @@
+#![cfg(feature = "rpc")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::rpc::server::{serve_rpc, RpcContext, MAX_DEED_BATCH, UNKNOWN_ACCOUNT_CODE};
+use serde_json::{json, Value};
+
+struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        writeln!(self.stream, "{}", request).unwrap();
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    fn tip(&mut self) -> String {
+        self.call("auto_church.get_chain", json!({ "limit": 1 }))["result"]["tip_hash"].as_str().unwrap().to_string()
+    }
+
+    fn mint(&mut self, actor: &str, co2_kg: f64) -> Value {
+        let params = json!({
+            "prev_hash": self.tip(),
+            "actor_id": actor,
+            "target_ids": ["target:local-watershed"],
+            "deed_type": "ecological_sustainability",
+            "tags": ["eco"],
+            "context_json": { "location": "Phoenix, AZ", "co2_kg": co2_kg, "evidence_uri": "ipfs://riverbank-planting" },
+            "ethics_flags": [],
+            "life_harm_flag": false,
+            "bioload_delta": -0.12,
+            "roh": 0.2,
+            "decay": 0.7,
+        });
+        let resp = self.call("auto_church.mint_deed", params);
+        assert!(resp["error"].is_null(), "{resp}");
+        resp
+    }
+}
+
+/// A node serving `ledger` on an ephemeral port, and a client of it.
+fn serve() -> (Arc<Mutex<TokenLedger>>, Client) {
+    let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let ctx = RpcContext::with_ledger(ledger.clone());
+    std::thread::spawn(move || serve_rpc(listener, ctx));
+    let stream = TcpStream::connect(addr).unwrap();
+    let reader = BufReader::new(stream.try_clone().unwrap());
+    (ledger, Client { stream, reader })
+}
+
+fn event_ids(page: &Value) -> Vec<String> {
+    page["result"]["events"].as_array().unwrap().iter().map(|e| e["event_id"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn get_chain_pages_through_minted_deeds() {
+    let (ledger, mut client) = serve();
+    for (i, actor) in ["alice", "bob", "alice", "carol", "alice"].into_iter().enumerate() {
+        client.mint(actor, 1.0 + i as f64);
+    }
+    let (chain, tip) = {
+        let ledger = ledger.lock().unwrap();
+        (ledger.deeds().iter().map(|d| d.event_id.clone()).collect::<Vec<_>>(), ledger.last_hash())
+    };
+    assert!(chain.len() >= 5);
+
+    let mut paged = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = client.call("auto_church.get_chain", json!({ "offset": offset, "limit": 2 }));
+        assert_eq!(page["result"]["total"], chain.len());
+        assert_eq!(page["result"]["tip_hash"], tip);
+        let ids = event_ids(&page);
+        if ids.is_empty() {
+            break;
+        }
+        offset += ids.len();
+        paged.extend(ids);
+    }
+    assert_eq!(paged, chain);
+
+    let alice = client.call("auto_church.get_chain", json!({ "actor_id": "alice" }));
+    assert_eq!(alice["result"]["total"], 3);
+    let second = client.call("auto_church.get_chain", json!({ "actor_id": "alice", "offset": 1, "limit": 1 }));
+    assert_eq!(event_ids(&second), event_ids(&alice)[1..2]);
+    assert_eq!(second["result"]["events"][0]["actor_id"], "alice");
+    let past_end = client.call("auto_church.get_chain", json!({ "offset": chain.len() + 10 }));
+    assert!(event_ids(&past_end).is_empty());
+}
+
+#[test]
+fn get_account_summarizes_balances_and_record() {
+    let (ledger, mut client) = serve();
+    client.mint("alice", 2.0);
+    client.mint("alice", 3.0);
+    let church = {
+        let mut ledger = ledger.lock().unwrap();
+        ledger.open_account("alice", "alice");
+        ledger.reward_for("alice", Token::Church, 40, None).unwrap();
+        ledger.account("alice").unwrap().balance(Token::Church)
+    };
+
+    let account = client.call("auto_church.get_account", json!({ "actor_id": "alice" }))["result"].clone();
+    assert_eq!(account["balances"]["church"], church);
+    assert_eq!(account["deeds"], 2);
+    assert_eq!((account["flagged"].clone(), account["life_harm"].clone()), (json!(0), json!(0)));
+    assert_eq!(account["standing"], "good");
+    assert_eq!(account["eco_score"], 1.0);
+
+    let unknown = client.call("auto_church.get_account", json!({ "actor_id": "nobody" }));
+    assert_eq!(unknown["error"]["code"], UNKNOWN_ACCOUNT_CODE);
+}
+
+#[test]
+fn bad_read_params_are_invalid() {
+    let (_, mut client) = serve();
+    for (method, params) in [
+        ("auto_church.get_chain", json!({ "limit": 0 })),
+        ("auto_church.get_chain", json!({ "limit": MAX_DEED_BATCH + 1 })),
+        ("auto_church.get_chain", json!({ "actor_id": "" })),
+        ("auto_church.get_chain", json!({ "offset": -1 })),
+        ("auto_church.get_chain", json!({ "page": 2 })),
+        ("auto_church.get_account", json!({})),
+        ("auto_church.get_account", json!({ "actor_id": "" })),
+    ] {
+        let resp = client.call(method, params.clone());
+        assert_eq!(resp["error"]["code"], -32602, "{method} {params}");
+    }
+    assert!(client.call("auto_church.get_chain", Value::Null)["error"].is_null());
+}
@@ -0,0 +1,141 @@
+#![cfg(all(feature = "chaos", feature = "testkit"))]
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::fixtures::small_community;
+use church_of_fear::ledger::builders::EcologicalSustainabilityDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, validate_chain, DeedEvent};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::residency::{DeedSink, ResidencyFanout, ResidencyPolicy, SinkResidency};
+use faults::points::{CLOCK_READ, LEDGER_FSYNC, SINK_WRITE};
+use faults::{clear, fired, program, session, FaultRule};
+
+const T0: i64 = 1_700_000_000;
+
+type Received = Arc<Mutex<Vec<DeedEvent>>>;
+
+struct Archive {
+    received: Received,
+}
+
+impl DeedSink for Archive {
+    fn name(&self) -> &str {
+        "archive"
+    }
+
+    fn deliver(&mut self, deed: &DeedEvent) -> Result<(), String> {
+        self.received.lock().unwrap().push(deed.clone());
+        Ok(())
+    }
+}
+
+fn archive() -> (ResidencyFanout, Received) {
+    let policy = ResidencyPolicy {
+        jurisdictions: vec!["PHX".into()],
+        default_jurisdiction: Some("PHX".into()),
+        sinks: vec![SinkResidency { name: "archive".into(), jurisdictions: vec!["PHX".into()], redact: false }],
+        ..ResidencyPolicy::default()
+    };
+    let received = Received::default();
+    (ResidencyFanout::new(policy, vec![Box::new(Archive { received: received.clone() })]).unwrap(), received)
+}
+
+fn planting(ledger: &TokenLedger, n: usize) -> DeedEvent {
+    EcologicalSustainabilityDeed::builder()
+        .actor_id("bob")
+        .location("Salt River")
+        .co2_kg(2.0 + n as f64)
+        .evidence_uri("ipfs://chaos-planting")
+        .build(ledger.last_hash())
+        .unwrap()
+}
+
+#[test]
+fn appends_under_sink_and_sync_failures_do_not_fork_the_chain() {
+    let _chaos = session(0x5eed);
+    let scenario = small_community(5, T0).build().unwrap();
+    let copied = scenario.ledger.deeds().len() as u64;
+    clear();
+    program(SINK_WRITE, FaultRule::fail().with_probability(0.3));
+    program(SINK_WRITE, FaultRule::corrupt(1).with_probability(0.1));
+    program(LEDGER_FSYNC, FaultRule::fail().on_invocations([3, 10, 11, 40, copied + 2, copied + 5]));
+
+    let (mut fanout, received) = archive();
+    let mut ledger = TokenLedger::new(scenario.ledger.config().clone());
+    for deed in scenario.ledger.deeds() {
+        match ledger.replicate(deed.clone()) {
+            Ok(_) => {}
+            Err(TokenLedgerError::SyncFailed(id)) => assert_eq!(id, deed.event_id),
+            Err(e) => panic!("{e}"),
+        }
+        fanout.publish(ledger.deeds().last().unwrap());
+    }
+    let mut sync_failures = 0;
+    for n in 0..20 {
+        let deed = planting(&ledger, n);
+        match ledger.append(deed.clone()) {
+            Ok(_) => {}
+            Err(TokenLedgerError::SyncFailed(id)) => {
+                sync_failures += 1;
+                assert_eq!(ledger.deeds().last().unwrap().event_id, id);
+                // A blind retry must be refused rather than fork the tip.
+                assert!(matches!(ledger.append(deed), Err(TokenLedgerError::ChainBroken { .. })));
+            }
+            Err(e) => panic!("{e}"),
+        }
+        fanout.publish(ledger.deeds().last().unwrap());
+    }
+    assert_eq!(sync_failures, 2);
+
+    let deeds = ledger.deeds();
+    assert_eq!(deeds.len(), scenario.ledger.deeds().len() + 20);
+    assert_eq!(validate_chain(deeds), Ok(()));
+    assert_eq!(deeds.iter().map(|d| &d.prev_hash).collect::<HashSet<_>>().len(), deeds.len());
+    let hashes = |deeds: &[DeedEvent]| deeds.iter().map(|d| d.self_hash.clone()).collect::<Vec<_>>();
+    assert_eq!(hashes(&deeds[..copied as usize]), hashes(scenario.ledger.deeds()));
+    assert_eq!(ledger.supply_report(), scenario.ledger.supply_report());
+
+    // Whatever the sink did receive intact is the chain, in chain order.
+    let positions: BTreeMap<&str, usize> = deeds.iter().enumerate().map(|(i, d)| (d.self_hash.as_str(), i)).collect();
+    let received = received.lock().unwrap();
+    let intact: Vec<usize> = received
+        .iter()
+        .filter(|d| {
+            let mut unhashed = (*d).clone();
+            unhashed.self_hash = String::new();
+            hash_deed(&unhashed) == d.self_hash
+        })
+        .map(|d| positions[d.self_hash.as_str()])
+        .collect();
+    assert!(intact.windows(2).all(|w| w[0] < w[1]));
+    assert!(intact.len() < received.len(), "some deliveries arrived corrupted");
+    let failed = fanout.report().sinks["archive"].failed;
+    assert!(failed > 0);
+    assert_eq!(received.len() as u64 + failed, deeds.len() as u64);
+    assert_eq!(fired(SINK_WRITE), deeds.len() as u64 - intact.len() as u64);
+}
+
+#[test]
+fn a_backwards_clock_jump_keeps_authoritative_timestamps_monotone() {
+    let _chaos = session(7);
+    let mut ledger = small_community(5, T0).build().unwrap().ledger;
+    clear();
+    // NTP steps the clock back a day after four reads; one read in between
+    // finds it reset to the epoch.
+    program(CLOCK_READ, FaultRule::fail().on_invocations([12]));
+    program(CLOCK_READ, FaultRule::corrupt(-86_400).on_invocations(5..=60));
+
+    for n in 0..30 {
+        ledger.accrue_fear("alice", 1, &format!("drill {n}")).unwrap();
+        if n % 5 == 0 {
+            ledger.decay_fear(0.5).unwrap();
+        }
+    }
+    assert!(fired(CLOCK_READ) > 0);
+
+    let stamped: Vec<i64> = ledger.deeds().iter().filter(|d| d.actor_id == "ledger").map(|d| d.timestamp).collect();
+    assert!(stamped.windows(2).all(|w| w[0] <= w[1]), "ledger-authored timestamps went backwards");
+    assert!(ledger.authoritative_now() >= *stamped.last().unwrap());
+    assert_eq!(validate_chain(ledger.deeds()), Ok(()));
+}
@@ -0,0 +1,276 @@
+#![cfg(feature = "core")]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use church_of_fear::audit::{AuditFault, AuditPolicy, SelfAuditor};
+use church_of_fear::cold_storage::{
+    ColdStorage, ColdStoragePolicy, LocalDirTier, Residence, StorageTier, TierError, TierManifest, SEGMENT_MIGRATED,
+    SPOT_CHECK_PURPOSE,
+};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::vrf::{self, RANDOMNESS_DRAWN};
+
+/// Far enough past every deed's timestamp that all segments are old.
+const LATER: i64 = 4_000_000_000;
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cof-cold-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A `LocalDirTier` whose writes or reads can be made to fail, or whose
+/// objects can be corrupted after the fact.
+#[derive(Clone)]
+struct FlakyTier {
+    root: PathBuf,
+    fail_put: Arc<AtomicBool>,
+    fail_get: Arc<AtomicBool>,
+}
+
+impl FlakyTier {
+    fn new(root: PathBuf) -> Self {
+        Self { root, fail_put: Arc::default(), fail_get: Arc::default() }
+    }
+
+    fn inner(&self) -> LocalDirTier {
+        LocalDirTier::new(&self.root)
+    }
+
+    fn corrupt(&self, key: &str) {
+        let path = self.root.join(key);
+        let mut bytes = std::fs::read(&path).unwrap();
+        let at = bytes.iter().position(|&b| b == b'0').unwrap();
+        bytes[at] = b'1';
+        std::fs::write(path, bytes).unwrap();
+    }
+}
+
+impl StorageTier for FlakyTier {
+    fn describe(&self) -> String {
+        self.inner().describe()
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<(), TierError> {
+        if self.fail_put.load(Ordering::SeqCst) {
+            return Err(TierError::Remote { tier: self.describe(), message: "unavailable".to_string() });
+        }
+        self.inner().put(key, bytes)
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TierError> {
+        if self.fail_get.load(Ordering::SeqCst) {
+            return Err(TierError::Remote { tier: self.describe(), message: "unavailable".to_string() });
+        }
+        self.inner().get(key)
+    }
+}
+
+fn policy(cache_segments: usize) -> ColdStoragePolicy {
+    ColdStoragePolicy { cold_after_secs: 86_400, cache_segments, ..ColdStoragePolicy::default() }
+}
+
+/// A ledger with `segments` sealed segments of `per_segment` reward deeds.
+fn ledger(segments: usize, per_segment: usize) -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    for _ in 0..segments {
+        for _ in 0..per_segment {
+            ledger.mint_reward("alice", Token::Church, 10).unwrap();
+        }
+        ledger.seal_segment().unwrap().unwrap();
+    }
+    ledger
+}
+
+fn ids(deeds: &[DeedEvent]) -> Vec<&str> {
+    deeds.iter().map(|d| d.event_id.as_str()).collect()
+}
+
+/// The deeds of `ledger`'s sealed segment `index`.
+fn segment(ledger: &TokenLedger, index: usize) -> &[DeedEvent] {
+    let s = &ledger.sealed_segments()[index];
+    &ledger.deeds()[s.first..=s.last]
+}
+
+fn open(dir: &TempDir, tier: &FlakyTier, cache_segments: usize) -> ColdStorage {
+    ColdStorage::open(policy(cache_segments), dir.0.join("node"), Box::new(tier.clone()), 0).unwrap()
+}
+
+/// Every cold segment's local file is gone and every other one's is there,
+/// and the manifest on disk is the one in memory.
+fn assert_consistent(storage: &ColdStorage, dir: &TempDir) {
+    for entry in &storage.manifest().segments {
+        let local = storage.local_path(&entry.key).exists();
+        assert_eq!(local, entry.residence != Residence::Cold, "segment {} is {:?}", entry.index, entry.residence);
+    }
+    let on_disk: TierManifest =
+        serde_json::from_str(&std::fs::read_to_string(dir.0.join("node").join("tiers.json")).unwrap()).unwrap();
+    assert_eq!(&on_disk, storage.manifest());
+}
+
+#[test]
+fn migration_confirms_the_remote_hash_before_deleting() {
+    let dir = TempDir::new("migrate");
+    let tier = FlakyTier::new(dir.0.join("remote"));
+    let mut ledger = ledger(3, 4);
+    let mut storage = open(&dir, &tier, 4);
+    assert_eq!(storage.write_sealed(&ledger).unwrap(), 3);
+    assert_eq!(storage.write_sealed(&ledger).unwrap(), 0);
+
+    // Nothing is old yet.
+    let report = storage.migrate(&mut ledger, 0);
+    assert!(report.migrated.is_empty() && report.failed.is_empty());
+
+    // The remote read-back fails: the segment stays local, half-way.
+    tier.fail_get.store(true, Ordering::SeqCst);
+    let report = storage.migrate(&mut ledger, LATER);
+    assert_eq!(report.failed.len(), 3);
+    assert!(storage.manifest().segments.iter().all(|e| e.residence == Residence::Uploading));
+    assert!(!ledger.deeds().iter().any(|d| d.deed_type == SEGMENT_MIGRATED));
+    assert_consistent(&storage, &dir);
+
+    tier.fail_get.store(false, Ordering::SeqCst);
+    let report = storage.migrate(&mut ledger, LATER);
+    assert_eq!(report.migrated, vec![0, 1, 2]);
+    assert_consistent(&storage, &dir);
+    let deeds: Vec<_> = ledger.deeds().iter().filter(|d| d.deed_type == SEGMENT_MIGRATED).collect();
+    assert_eq!(deeds.len(), 3);
+    for (deed, entry) in deeds.iter().zip(&storage.manifest().segments) {
+        assert_eq!(deed.context_json["object_hash"], entry.object_hash.as_str());
+        assert_eq!(deed.context_json["key"], entry.key.as_str());
+        assert_eq!(entry.migration_event_id.as_deref(), Some(deed.event_id.as_str()));
+    }
+}
+
+#[test]
+fn cold_segments_read_through_a_bounded_cache() {
+    let dir = TempDir::new("read");
+    let tier = FlakyTier::new(dir.0.join("remote"));
+    let mut ledger = ledger(3, 4);
+    let mut storage = open(&dir, &tier, 2);
+    storage.write_sealed(&ledger).unwrap();
+    storage.migrate(&mut ledger, LATER);
+
+    let sealed = ledger.sealed_segments().last().unwrap().last + 1;
+    let streamed: Vec<_> = storage.stream_events().collect::<Result<_, _>>().unwrap();
+    assert_eq!(ids(&streamed), ids(&ledger.deeds()[..sealed]));
+    let metrics = storage.metrics();
+    assert_eq!((metrics.fetches, metrics.cache_hits, metrics.evictions), (3, 0, 1));
+
+    // Segment 2 is cached; segment 0 was evicted and is fetched again.
+    assert_eq!(ids(&storage.read_segment(2).unwrap()), ids(segment(&ledger, 2)));
+    assert_eq!(ids(&storage.read_segment(0).unwrap()), ids(segment(&ledger, 0)));
+    let metrics = storage.metrics();
+    assert_eq!((metrics.fetches, metrics.cache_hits, metrics.evictions), (4, 1, 2));
+
+    // A replay over the stream rebuilds the same chain.
+    let replayed = TokenLedger::replay(LedgerConfig::default(), storage.stream_events().map(Result::unwrap)).unwrap();
+    assert_eq!(replayed.last_hash(), ledger.deeds()[sealed - 1].self_hash);
+}
+
+#[test]
+fn a_tampered_remote_copy_is_caught_and_freezes_mints() {
+    let dir = TempDir::new("tamper");
+    let tier = FlakyTier::new(dir.0.join("remote"));
+    let mut ledger = ledger(2, 3);
+    let mut storage = ColdStorage::open(
+        ColdStoragePolicy { spot_check_every_secs: 1, migrate_every_secs: 1, ..policy(0) },
+        dir.0.join("node"),
+        Box::new(tier.clone()),
+        0,
+    )
+    .unwrap();
+    let mut auditor = SelfAuditor::new(AuditPolicy::default());
+    storage.tick(&mut ledger, &mut auditor, LATER);
+    assert!(storage.manifest().segments.iter().all(|e| e.residence == Residence::Cold));
+    assert!(ledger.mint_freeze().is_none());
+
+    for index in 0..2 {
+        tier.corrupt(&storage.manifest().segments[index].key);
+    }
+    assert!(matches!(storage.read_segment(1), Err(TierError::Tampered { index: 1, .. })));
+    assert_eq!(storage.metrics().tampered, 1);
+
+    // Whichever segment the draw picks is bad, and the pick recomputes
+    // from the deeds.
+    storage.tick(&mut ledger, &mut auditor, LATER + 10);
+    assert!(ledger.mint_freeze().is_some());
+    let violation = auditor.state().violation.clone().unwrap();
+    let AuditFault::ColdSegment { index, .. } = &violation.fault else { panic!("{:?}", violation.fault) };
+    let position = ledger.deeds().iter().rposition(|d| d.deed_type == RANDOMNESS_DRAWN).unwrap();
+    let derivation = vrf::rederive(ledger.deeds(), position).unwrap();
+    assert_eq!(derivation.purpose, SPOT_CHECK_PURPOSE);
+    assert_eq!(derivation.counter, 1);
+    assert_eq!(ledger.deeds()[position].context_json["use"]["result"][0], *index);
+    assert!(matches!(ledger.mint_reward("alice", Token::Church, 10), Err(TokenLedgerError::MintsFrozen(_))));
+}
+
+#[test]
+fn an_interrupted_migration_resumes_after_a_restart() {
+    let dir = TempDir::new("resume");
+    let tier = FlakyTier::new(dir.0.join("remote"));
+    let mut ledger = ledger(2, 3);
+    let mut storage = open(&dir, &tier, 4);
+    storage.write_sealed(&ledger).unwrap();
+
+    tier.fail_put.store(true, Ordering::SeqCst);
+    let report = storage.migrate(&mut ledger, LATER);
+    assert_eq!(report.failed.len(), 2);
+    assert_consistent(&storage, &dir);
+    drop(storage);
+
+    // A restarted node reads the manifest back and finishes the job.
+    tier.fail_put.store(false, Ordering::SeqCst);
+    let mut storage = open(&dir, &tier, 4);
+    assert!(storage.manifest().segments.iter().all(|e| e.residence == Residence::Uploading));
+    assert_eq!(storage.migrate(&mut ledger, LATER).migrated, vec![0, 1]);
+    assert_consistent(&storage, &dir);
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == SEGMENT_MIGRATED).count(), 2);
+
+    // Nothing is left to move, and nothing is logged twice.
+    assert!(storage.migrate(&mut ledger, LATER).migrated.is_empty());
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == SEGMENT_MIGRATED).count(), 2);
+    assert_eq!(ids(&storage.read_segment(1).unwrap()), ids(segment(&ledger, 1)));
+}
+
+#[test]
+fn a_segment_deleted_after_confirmation_is_not_logged_twice() {
+    let dir = TempDir::new("confirmed");
+    let tier = FlakyTier::new(dir.0.join("remote"));
+    let mut ledger = ledger(1, 3);
+    let mut storage = open(&dir, &tier, 4);
+    storage.write_sealed(&ledger).unwrap();
+    storage.migrate(&mut ledger, LATER);
+    let migrated = ledger.deeds().len();
+
+    // Simulate a stop after the deed was logged but before the manifest
+    // recorded it: the manifest still says Confirmed, with no deed.
+    let manifest_path = dir.0.join("node").join("tiers.json");
+    let mut manifest: TierManifest = serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    manifest.segments[0].residence = Residence::Confirmed;
+    manifest.segments[0].migration_event_id = None;
+    std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+
+    let mut storage = open(&dir, &tier, 4);
+    assert_eq!(storage.migrate(&mut ledger, LATER).migrated, vec![0]);
+    assert_eq!(ledger.deeds().len(), migrated);
+    assert_eq!(storage.entry(0).unwrap().migration_event_id.as_deref(), Some(ledger.deeds()[migrated - 1].event_id.as_str()));
+    assert_consistent(&storage, &dir);
+}
@@ -10,7 +10,11 @@ fn compliant_deed_passes() {
         vec![],
         "ecological_sustainability".into(),
         vec![],
-        serde_json::json!({}),
+        serde_json::json!({
+            "location": "Phoenix, AZ",
+            "co2_kg": 12.5,
+            "evidence_uri": "ipfs://riverbank-planting"
+        }),
         vec![],
         false,
     );
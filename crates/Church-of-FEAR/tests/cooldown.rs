@@ -0,0 +1,187 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::cooldown::{is_suppressed, CooldownScope, COOLDOWN_ACTIVE};
+use church_of_fear::ledger::builders::WatershedCleanupDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::token::mint::mint_church;
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+const RIVER: &str = "target:salt-river";
+
+fn cleanup(prev_hash: String, actor: &str, target: &str, at: i64) -> DeedEvent {
+    let mut deed = WatershedCleanupDeed::builder()
+        .actor_id(actor)
+        .location("Tempe, AZ")
+        .volunteers(12)
+        .waste_kg(80.0)
+        .evidence_uri("ipfs://cleanup")
+        .target(target)
+        .build(prev_hash)
+        .unwrap();
+    deed.timestamp = at;
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+fn append(ledger: &mut TokenLedger, actor: &str, target: &str, at: i64) -> DeedEvent {
+    let deed = cleanup(ledger.last_hash(), actor, target, at);
+    ledger.append(deed).unwrap().clone()
+}
+
+fn metrics() -> BioloadMetrics {
+    BioloadMetrics::new(-0.2, 0.1, 0.5)
+}
+
+#[test]
+fn actor_cools_down_across_targets() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let first = append(&mut ledger, "alice", RIVER, T0);
+    assert!(mint_church(&first, &metrics(), ledger.config()) > 0);
+
+    let again = cleanup(ledger.last_hash(), "alice", "target:verde-river", T0 + DAY);
+    let violation = ledger.cooldown(&again).expect("alice is cooling down");
+    assert_eq!(violation.code, COOLDOWN_ACTIVE);
+    assert_eq!(violation.scope, CooldownScope::Actor("alice".into()));
+    assert_eq!(violation.blocking_event_id, first.event_id);
+
+    let stored = ledger.append(again).unwrap().clone();
+    assert!(is_suppressed(&stored));
+    assert_eq!(mint_church(&stored, &metrics(), ledger.config()), 0);
+    assert_eq!(ledger.deeds().len(), 2, "the suppressed deed is still on record");
+}
+
+#[test]
+fn target_cools_down_across_actors() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    append(&mut ledger, "alice", RIVER, T0);
+
+    let bob = append(&mut ledger, "bob", RIVER, T0 + 3_600);
+    assert!(is_suppressed(&bob));
+    let carol = cleanup(ledger.last_hash(), "carol", "target:verde-river", T0 + 3_600);
+    assert_eq!(ledger.cooldown(&carol), None);
+    let carol = ledger.append(carol).unwrap().clone();
+    assert!(!is_suppressed(&carol));
+    assert!(mint_church(&carol, &metrics(), ledger.config()) > 0);
+
+    let dave = cleanup(ledger.last_hash(), "dave", RIVER, T0 + 2 * DAY);
+    assert_eq!(ledger.cooldown(&dave).unwrap().scope, CooldownScope::Target(RIVER.into()));
+}
+
+#[test]
+fn next_eligible_time_is_exact_and_suppressed_deeds_do_not_extend_it() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    append(&mut ledger, "alice", RIVER, T0);
+    let next = ledger.cooldown(&cleanup(ledger.last_hash(), "alice", RIVER, T0 + 1)).unwrap().next_eligible_at;
+    assert_eq!(next, T0 + 7 * DAY);
+
+    assert!(is_suppressed(&append(&mut ledger, "alice", RIVER, T0 + 5 * DAY)));
+    assert!(is_suppressed(&append(&mut ledger, "alice", RIVER, next - 1)));
+    let eligible = append(&mut ledger, "alice", RIVER, next);
+    assert!(!is_suppressed(&eligible));
+    assert_eq!(ledger.cooldown(&cleanup(ledger.last_hash(), "alice", RIVER, next + 1)).unwrap().next_eligible_at, next + 7 * DAY);
+}
+
+#[test]
+fn tombstoned_deed_does_not_block_the_next_one() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let misfiled = append(&mut ledger, "alice", RIVER, T0);
+    ledger.tombstone(&misfiled.event_id, "claimed the wrong day", "Host").unwrap();
+
+    let retry = append(&mut ledger, "alice", RIVER, T0 + DAY);
+    assert!(!is_suppressed(&retry));
+    assert!(mint_church(&retry, &metrics(), ledger.config()) > 0);
+}
+
+#[test]
+fn rebuilt_ledger_reaches_the_same_cooldowns() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    append(&mut ledger, "alice", RIVER, T0);
+    append(&mut ledger, "bob", RIVER, T0 + DAY);
+    let replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().to_vec()).unwrap();
+
+    let probe = cleanup(ledger.last_hash(), "carol", RIVER, T0 + 2 * DAY);
+    assert_eq!(replayed.cooldown(&probe), ledger.cooldown(&probe));
+    assert!(replayed.cooldown(&probe).is_some());
+}
+
+#[test]
+fn simulation_run_agrees_with_the_live_chain() {
+    let schedule = [
+        ("alice", RIVER, T0),
+        ("bob", RIVER, T0 + DAY),
+        ("alice", "target:verde-river", T0 + 2 * DAY),
+        ("bob", RIVER, T0 + 8 * DAY),
+    ];
+    let mut live = TokenLedger::new(LedgerConfig::default());
+    let live_flags: Vec<bool> =
+        schedule.iter().map(|&(actor, target, at)| is_suppressed(&append(&mut live, actor, target, at))).collect();
+    assert_eq!(live_flags, [false, true, true, false]);
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_sim_run("run-1", "planner").unwrap();
+    let mut sim_flags = Vec::new();
+    for &(actor, target, at) in &schedule {
+        let deed = cleanup(ledger.sim_run("run-1").unwrap().tip_hash(), actor, target, at);
+        let predicted = ledger.sim_cooldown("run-1", &deed).is_some();
+        let stored = ledger.append_sim("run-1", deed).unwrap();
+        assert_eq!(is_suppressed(stored), predicted);
+        sim_flags.push(predicted);
+    }
+    assert_eq!(sim_flags, live_flags);
+    assert_eq!(ledger.sim_run("run-1").unwrap().summary().cooldown_suppressed, 2);
+    assert!(ledger.deeds().is_empty());
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_logs_cooldown_deeds_without_minting() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let ctx = RpcContext::with_ledger(ledger.clone());
+    let mint = || {
+        let prev_hash = ledger.lock().unwrap().last_hash();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "auto_church.mint_deed",
+            "params": {
+                "prev_hash": prev_hash,
+                "actor_id": "alice",
+                "target_ids": [RIVER],
+                "deed_type": "watershed_cleanup",
+                "tags": ["eco"],
+                "context_json": { "location": "Tempe, AZ", "volunteers": 12, "waste_kg": 80.0, "evidence_uri": "ipfs://cleanup" },
+                "ethics_flags": [],
+                "life_harm_flag": false,
+                "bioload_delta": -0.2,
+                "roh": 0.1,
+                "decay": 0.5
+            },
+            "id": 1
+        });
+        serde_json::from_str::<Value>(&dispatch_request_with(&request.to_string(), &ctx)).unwrap()
+    };
+
+    let first = mint();
+    assert_eq!(first["result"]["church_minted"], 20);
+    assert!(first["result"].get("cooldown").is_none());
+
+    let second = mint();
+    let result = &second["result"];
+    assert_eq!(result["church_minted"], 0);
+    assert_eq!(result["cooldown"]["code"], COOLDOWN_ACTIVE);
+    assert_eq!(result["cooldown"]["blocking_event_id"], first["result"]["deed"]["event_id"]);
+    assert!(result["cooldown"]["next_eligible_at"].as_i64().unwrap() > first["result"]["deed"]["timestamp"].as_i64().unwrap());
+    assert_eq!(result["deed"]["context_json"]["cooldown_suppressed"], true);
+
+    let ledger = ledger.lock().unwrap();
+    assert_eq!(ledger.deeds().len(), 2);
+    assert!(is_suppressed(&ledger.deeds()[1]));
+}
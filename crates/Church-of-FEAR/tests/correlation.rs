@@ -0,0 +1,236 @@
+#![cfg(feature = "rpc")]
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{FearTrigger, TokenLedger};
+use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+use church_of_fear::scheduler::{MaintenanceJob, RecurringJobs};
+use church_of_fear::sponsor::pool::{LowWaterAlert, PoolAlertNotifier, WebhookPoolNotifier};
+use church_of_fear::utils::correlation::{CorrelationId, CORRELATION_KEY};
+use serde_json::{json, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+#[derive(Debug, Clone)]
+struct Captured {
+    id: u64,
+    name: &'static str,
+    parent: Option<&'static str>,
+    fields: BTreeMap<String, String>,
+}
+
+/// Records every span with its parent's name and final field values.
+#[derive(Clone, Default)]
+struct Spans(Arc<Mutex<Vec<Captured>>>);
+
+struct Fields<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for Fields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Spans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let parent = ctx.span(id).and_then(|s| s.parent()).map(|p| p.name());
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut Fields(&mut fields));
+        self.0.lock().unwrap().push(Captured { id: id.into_u64(), name: attrs.metadata().name(), parent, fields });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut spans = self.0.lock().unwrap();
+        if let Some(span) = spans.iter_mut().rev().find(|s| s.id == id.into_u64()) {
+            values.record(&mut Fields(&mut span.fields));
+        }
+    }
+}
+
+impl Spans {
+    fn capture<T>(&self, f: impl FnOnce() -> T) -> T {
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(self.clone()), f)
+    }
+
+    fn named(&self, name: &str) -> Vec<Captured> {
+        self.0.lock().unwrap().iter().filter(|s| s.name == name).cloned().collect()
+    }
+
+    fn one(&self, name: &str) -> Captured {
+        let spans = self.named(name);
+        assert_eq!(spans.len(), 1, "{name}: {spans:?}");
+        spans.into_iter().next().unwrap()
+    }
+}
+
+fn mint_request(prev_hash: &str, correlation_id: Option<&str>, context: Value) -> String {
+    let mut request = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.mint_deed",
+        "params": {
+            "prev_hash": prev_hash,
+            "actor_id": "alice",
+            "target_ids": [],
+            "deed_type": "mutual_aid",
+            "tags": ["meal"],
+            "context_json": context,
+            "ethics_flags": [],
+            "life_harm_flag": false,
+            "bioload_delta": -0.1,
+            "roh": 0.1,
+            "decay": 0.5
+        },
+        "id": 7
+    });
+    if let Some(id) = correlation_id {
+        request["correlation_id"] = json!(id);
+    }
+    request.to_string()
+}
+
+#[test]
+fn rpc_request_spans_nest_and_reach_the_stored_deed() {
+    let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let ctx = RpcContext { ledger: Some(ledger.clone()), ..RpcContext::default() };
+    let spans = Spans::default();
+    let tip = ledger.lock().unwrap().last_hash();
+    let raw = spans.capture(|| dispatch_request_with(&mint_request(&tip, Some("req-42"), json!({ "meals": 3 })), &ctx));
+
+    let resp: Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(resp["correlation_id"], "req-42");
+    let stored = ledger.lock().unwrap().deeds().last().cloned().unwrap();
+    assert_eq!(stored.context_json[CORRELATION_KEY], "req-42");
+    assert_eq!(serde_json::to_value(&stored).unwrap(), resp["result"]["deed"]);
+
+    let request = spans.one("request");
+    assert_eq!(request.parent, None);
+    assert_eq!(request.fields["correlation_id"], "req-42");
+    assert_eq!(request.fields["method"], "auto_church.mint_deed");
+    assert_eq!(request.fields["event_id"], stored.event_id);
+    assert_eq!(request.fields["decision"], "ok");
+    assert!(request.fields.contains_key("latency_ms"));
+
+    let validation = spans.one("validation");
+    assert_eq!(validation.parent, Some("request"));
+    assert_eq!(validation.fields["actor"], "alice");
+    assert_eq!(validation.fields["deed_type"], "mutual_aid");
+    assert_eq!(validation.fields["decision"], "ok");
+
+    let guards = spans.one("guards");
+    assert_eq!(guards.parent, Some("request"));
+    assert_eq!(guards.fields["decision"], "pass");
+
+    let write = spans.one("ledger_write");
+    assert_eq!(write.parent, Some("request"));
+    assert_eq!(write.fields["event_id"], stored.event_id);
+    assert_eq!(write.fields["position"], "0");
+}
+
+#[test]
+fn missing_or_malformed_ids_are_replaced_and_errors_are_coded() {
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())))), ..RpcContext::default() };
+    let spans = Spans::default();
+
+    // A caller cannot inject header lines or spoof the reserved context key.
+    let injected = "x\r\nX-Evil: 1";
+    let spoofed = json!({ CORRELATION_KEY: "someone-else" });
+    let raw = spans.capture(|| dispatch_request_with(&mint_request(&"0".repeat(64), Some(injected), spoofed), &ctx));
+    let resp: Value = serde_json::from_str(&raw).unwrap();
+    let id = resp["correlation_id"].as_str().unwrap();
+    assert_ne!(id, injected);
+    assert!(CorrelationId::parse(id).is_some());
+    assert_eq!(resp["result"]["deed"]["context_json"][CORRELATION_KEY], id);
+
+    // Wrong prev_hash: rejected by the ledger, still echoed and coded.
+    let raw = spans.capture(|| dispatch_request_with(&mint_request(&"f".repeat(64), None, json!({})), &ctx));
+    let resp: Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(resp["error"]["code"], 1005);
+    assert!(resp["correlation_id"].is_string());
+    let failed = spans.named("request").pop().unwrap();
+    assert_eq!(failed.fields["decision"], "error");
+    assert_eq!(failed.fields["error_code"], "1005");
+
+    let unparsable: Value = serde_json::from_str(&dispatch_request_with("{nope", &ctx)).unwrap();
+    assert_eq!(unparsable["error"]["code"], -32700);
+    assert!(unparsable["correlation_id"].is_string());
+}
+
+/// Accept one POST, answer 200 and hand back (headers, body).
+fn webhook_sink() -> (String, std::thread::JoinHandle<(String, Value)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            headers.push_str(&line);
+        }
+        let len: usize = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("Content-Length: "))
+            .map(|n| n.trim().parse().unwrap())
+            .unwrap();
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body).unwrap();
+        (&stream).write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+        (headers, serde_json::from_slice(&body).unwrap())
+    });
+    (url, handle)
+}
+
+#[test]
+fn webhooks_pass_the_correlation_id_through() {
+    let (url, sink) = webhook_sink();
+    let spans = Spans::default();
+    let alert = LowWaterAlert { balance: 3, low_water_mark: 100, detected_at: 1_700_000_000 };
+    let id = CorrelationId::parse("hook-7").unwrap();
+    spans.capture(|| {
+        let _scope = id.enter();
+        WebhookPoolNotifier { url }.notify(&alert).unwrap();
+    });
+    let (headers, body) = sink.join().unwrap();
+    assert!(headers.contains("X-Correlation-Id: hook-7\r\n"), "{headers}");
+    assert_eq!(body[CORRELATION_KEY], "hook-7");
+    assert_eq!(body["balance"], 3);
+
+    let notification = spans.one("notification");
+    assert_eq!(notification.fields["kind"], "pool_low_water");
+    assert_eq!(notification.fields["outcome"], "delivered");
+    assert_eq!(CorrelationId::current(), None);
+}
+
+#[test]
+fn scheduler_jobs_run_under_their_own_ids() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.on_regulator_transition("alice", FearTrigger::Warn, "drift").unwrap();
+    let mut jobs = RecurringJobs::new();
+    jobs.add("decay_fear", 60, MaintenanceJob::DecayFear { rate: 0.5 }, 0);
+
+    let spans = Spans::default();
+    assert_eq!(spans.capture(|| jobs.run_due(&mut ledger, 60)), ["decay_fear"]);
+    let job = spans.one("job");
+    assert_eq!(job.fields["job"], "decay_fear");
+    let decay: &DeedEvent = ledger.deeds().iter().find(|d| d.deed_type == "fear_decay").unwrap();
+    assert_eq!(decay.context_json[CORRELATION_KEY], job.fields["correlation_id"].as_str());
+    assert_eq!(spans.one("ledger_write").parent, Some("job"));
+}
@@ -0,0 +1,86 @@
+use church_of_fear::compliance::data_minimization::{
+    MinimizationError, MinimizationMode, MinimizationPolicy, MinimizationRule, MINIMIZATION_FLAG,
+};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use serde_json::{json, Value};
+
+fn session(prev_hash: String, context: Value) -> DeedEvent {
+    DeedEvent::new(
+        prev_hash,
+        "sleeper-1".to_string(),
+        vec![],
+        "nsleep_session".to_string(),
+        vec!["neuro".to_string()],
+        context,
+        vec![],
+        false,
+    )
+}
+
+fn raw_eeg() -> Value {
+    let samples: Vec<f64> = (0..256).map(|i| (i as f64 * 0.1).sin()).collect();
+    json!({ "session_duration_secs": 27000, "recording": { "eeg": samples } })
+}
+
+fn strip_policy() -> MinimizationPolicy {
+    MinimizationPolicy { mode: MinimizationMode::Strip, ..MinimizationPolicy::default() }
+}
+
+#[test]
+fn raw_numeric_array_is_rejected() {
+    let policy = MinimizationPolicy::default();
+    let err = policy.enforce(session("GENESIS".into(), raw_eeg())).unwrap_err();
+    let MinimizationError::Rejected(findings) = err;
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].path, "recording.eeg");
+    assert_eq!(findings[0].rule, MinimizationRule::NumericArray { len: 256 });
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let deed = session(ledger.last_hash(), json!({ "eeg_raw": "a1b2" }));
+    assert!(matches!(ledger.append(deed), Err(TokenLedgerError::Minimization(_))));
+    assert!(ledger.deeds().is_empty());
+}
+
+#[test]
+fn strip_mode_hashes_the_stripped_content() {
+    let deed = strip_policy().enforce(session("GENESIS".into(), raw_eeg())).unwrap();
+    assert_eq!(deed.context_json, json!({ "session_duration_secs": 27000, "recording": {} }));
+    assert_eq!(deed.ethics_flags, [MINIMIZATION_FLAG]);
+
+    let mut rehashed = deed.clone();
+    rehashed.self_hash = String::new();
+    assert_eq!(deed.self_hash, hash_deed(&rehashed));
+}
+
+#[test]
+fn allowlisted_summaries_pass_untouched() {
+    let context = json!({
+        "sleep_stage_pct": { "n1": 5.0, "n2": 50.0, "n3": 20.0, "rem": 25.0 },
+        "session_duration_secs": 27000,
+        "artifact_count": 3,
+    });
+    let policy = MinimizationPolicy::default();
+    assert!(policy.scan(&context).is_empty());
+
+    let deed = session("GENESIS".into(), context.clone());
+    let kept = strip_policy().enforce(deed.clone()).unwrap();
+    assert_eq!(kept.context_json, context);
+    assert_eq!(kept.self_hash, deed.self_hash);
+    assert!(kept.ethics_flags.is_empty());
+}
+
+#[test]
+fn minimization_flag_is_stored_on_append() {
+    let cfg = LedgerConfig { minimization: strip_policy(), ..LedgerConfig::default() };
+    let mut ledger = TokenLedger::new(cfg);
+    let deed = session(ledger.last_hash(), json!({ "waveform": "AAAA", "artifact_count": 1 }));
+    let original_hash = deed.self_hash.clone();
+
+    let stored = ledger.append(deed).unwrap();
+    assert!(stored.ethics_flags.iter().any(|f| f == MINIMIZATION_FLAG));
+    assert_eq!(stored.context_json, json!({ "artifact_count": 1 }));
+    assert_ne!(stored.self_hash, original_hash);
+    assert_eq!(ledger.last_hash(), ledger.deeds()[0].self_hash);
+}
@@ -0,0 +1,82 @@
+use church_of_fear::compliance::validator::validate_deed;
+use church_of_fear::ledger::builders::{
+    validate_context, DeedBuildError, EcologicalSustainabilityDeed, HomelessnessReliefDeed, CATEGORIES,
+};
+use church_of_fear::ledger::deed_event::DeedEvent;
+
+#[test]
+fn builder_output_passes_validation_pipeline() {
+    let deed = EcologicalSustainabilityDeed::builder()
+        .actor_id("a1")
+        .location("San Tan Valley")
+        .co2_kg(12.5)
+        .evidence_uri("https://example.org/photo.jpg")
+        .notes("riparian planting")
+        .target("watershed-az")
+        .build("0".repeat(64))
+        .unwrap();
+
+    assert_eq!(deed.deed_type, "ecological_sustainability");
+    assert!(deed.tags.contains(&"tree-of-life".to_string()));
+    assert_eq!(deed.context_json["co2_kg"], 12.5);
+    validate_deed(&deed, 0.1, 0.5).unwrap();
+}
+
+#[test]
+fn range_problems_fail_at_runtime() {
+    let err = HomelessnessReliefDeed::builder()
+        .actor_id("a1")
+        .location("Phoenix")
+        .hours(30.0)
+        .meals_served(45)
+        .build("")
+        .unwrap_err();
+    assert!(matches!(err, DeedBuildError::OutOfRange { field: "hours", .. }));
+}
+
+#[test]
+fn free_form_deeds_are_schema_checked_only_for_known_categories() {
+    let shaped_wrong = DeedEvent::new(
+        String::new(),
+        "a1".into(),
+        vec![],
+        "ecological_sustainability".into(),
+        vec![],
+        serde_json::json!({ "location": "x", "co2_kg": "lots" }),
+        vec![],
+        false,
+    );
+    assert!(validate_context(&shaped_wrong).is_err());
+    assert!(validate_deed(&shaped_wrong, 0.1, 0.5).is_err());
+
+    let unknown = DeedEvent::new(
+        String::new(),
+        "a1".into(),
+        vec![],
+        "community_garden_swap".into(),
+        vec![],
+        serde_json::json!({ "anything": true }),
+        vec![],
+        false,
+    );
+    validate_context(&unknown).unwrap();
+}
+
+#[test]
+fn builders_track_the_taxonomy_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/taxonomy/deeds.json");
+    let taxonomy: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let categories = taxonomy["categories"].as_array().unwrap();
+    assert_eq!(categories.len(), CATEGORIES.len());
+    for (cat, schema) in categories.iter().zip(CATEGORIES) {
+        assert_eq!(cat["deed_type"], schema.deed_type);
+        let required: Vec<&str> = cat["required"].as_array().unwrap().iter().map(|f| f["name"].as_str().unwrap()).collect();
+        assert_eq!(required, schema.required.iter().map(|f| f.name).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn missing_required_setter_does_not_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
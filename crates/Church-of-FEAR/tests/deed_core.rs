@@ -0,0 +1,108 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, link_fault, DeedEvent, ExecutionDomain};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use deed_core::{decode_line, encode_line, DeedCoreError, HashRule};
+use serde_json::json;
+
+fn relief(prev_hash: String) -> DeedEvent {
+    HomelessnessReliefDeed::builder()
+        .actor_id("alice")
+        .location("Phoenix")
+        .hours(2.0)
+        .meals_served(30)
+        .build(prev_hash)
+        .unwrap()
+}
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.append(relief(ledger.last_hash())).unwrap();
+    let ext = json!({ "v3_note": "kept" }).as_object().unwrap().clone();
+    ledger.append(relief(ledger.last_hash()).with_ext(ext)).unwrap();
+    ledger
+}
+
+#[test]
+fn ledger_deeds_keep_their_hash_through_the_shared_shape() {
+    let ledger = ledger();
+    let shared: Vec<deed_core::DeedEvent> = ledger.deeds().iter().map(deed_core::DeedEvent::from).collect();
+    for (deed, original) in shared.iter().zip(ledger.deeds()) {
+        assert_eq!(deed.hash_rule, HashRule::MoralLedgerV1);
+        assert_eq!(deed.timestamp, original.timestamp * 1_000);
+        assert!(deed.verify_hash(), "{}", deed.event_id);
+    }
+    assert_eq!(shared[1].ext["v3_note"], "kept");
+
+    // Through an enveloped line and back, the ledger replays them as its own.
+    let back: Vec<DeedEvent> =
+        shared.iter().map(|d| DeedEvent::try_from(decode_line(&encode_line(d)).unwrap()).unwrap()).collect();
+    let mut prev = back[0].prev_hash.clone();
+    for (deed, original) in back.iter().zip(ledger.deeds()) {
+        assert_eq!(serde_json::to_value(deed).unwrap(), serde_json::to_value(original).unwrap());
+        assert_eq!(link_fault(deed, &prev), None);
+        prev = deed.self_hash.clone();
+    }
+    let replayed = TokenLedger::replay(LedgerConfig::default(), back).unwrap();
+    assert_eq!(replayed.last_hash(), ledger.last_hash());
+
+    // A line this ledger wrote before the envelope reads the same.
+    let bare = serde_json::to_string(&ledger.deeds()[1]).unwrap();
+    assert_eq!(decode_line(&bare).unwrap(), shared[1]);
+}
+
+#[test]
+fn simulation_deeds_carry_their_domain() {
+    let mut deed = relief("0".repeat(64));
+    deed.domain = ExecutionDomain::Simulation { run_id: "run-1".to_string() };
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+
+    let shared = deed_core::DeedEvent::from(&deed);
+    assert_eq!(shared.ext["domain"], json!({ "simulation": { "run_id": "run-1" } }));
+    assert!(shared.verify_hash());
+    assert_eq!(DeedEvent::try_from(shared).unwrap().domain, deed.domain);
+}
+
+#[test]
+fn deeds_sealed_another_way_are_refused() {
+    let mut canonical = deed_core::DeedEvent::from(&relief("0".repeat(64)));
+    canonical.seal("");
+    assert!(canonical.verify_hash());
+    assert!(matches!(
+        DeedEvent::try_from(canonical),
+        Err(DeedCoreError::HashRule { expected: HashRule::MoralLedgerV1, found: HashRule::Canonical, .. })
+    ));
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_deeds_are_the_shared_deed_in_our_wire_shape() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use church_of_fear::rpc::types::AutoChurchGetChainResult;
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    let ledger = Arc::new(Mutex::new(ledger()));
+    let deeds = ledger.lock().unwrap().deeds().to_vec();
+    let ctx = RpcContext::with_ledger(ledger);
+    let call = |method: &str, params: Value| -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str(&dispatch_request_with(&request.to_string(), &ctx)).unwrap()
+    };
+
+    // Deeds go out exactly as this ledger serializes them, seconds and all.
+    let chain = call("auto_church.get_chain", json!({}));
+    assert_eq!(chain["result"]["events"][1], serde_json::to_value(&deeds[1]).unwrap());
+    let read: AutoChurchGetChainResult = serde_json::from_value(chain["result"].clone()).unwrap();
+    assert!(read.events.iter().all(|d| d.hash_rule == HashRule::MoralLedgerV1 && d.verify_hash()));
+
+    // A deed sealed under another rule arrives enveloped and is refused.
+    let mut canonical = deed_core::DeedEvent::from(&deeds[0]);
+    canonical.seal("");
+    let params = json!({ "deed": deed_core::DeedEnvelope::new(canonical), "roh": 0.1, "decay": 0.5 });
+    let refused = call("auto_church.validate_deed", params);
+    assert!(refused["error"]["data"]["detail"].as_str().unwrap().contains("MoralLedgerV1"), "{}", refused);
+}
@@ -0,0 +1,201 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::compliance::ethics::EthicsSummary;
+use church_of_fear::compliance::regulator::{
+    decide, evaluate_conditions, evaluate_conditions_with, Condition, EthicsDecision, EthicsEvaluation,
+};
+use church_of_fear::params::ParamRegistry;
+
+fn calm() -> EthicsSummary {
+    EthicsSummary {
+        bioload: 0.1,
+        bioload_variance: 0.0,
+        mean_trust: 0.9,
+        power_gini: 0.2,
+        power_church_ratio: 0.5,
+        ..EthicsSummary::default()
+    }
+}
+
+fn failed(summary: &EthicsSummary) -> Vec<Condition> {
+    evaluate_conditions(summary).iter().filter(|r| !r.passed).map(|r| r.condition).collect()
+}
+
+fn severity(summary: &EthicsSummary) -> u8 {
+    decide(&evaluate_conditions(summary)).severity()
+}
+
+#[test]
+fn each_condition_toggles_on_its_own_figure() {
+    assert!(failed(&calm()).is_empty());
+    assert_eq!(decide(&evaluate_conditions(&calm())), EthicsDecision::Allow);
+
+    let cases = [
+        (EthicsSummary { bioload: 0.6, ..calm() }, vec![Condition::BioloadWarn]),
+        (EthicsSummary { bioload: 0.8, ..calm() }, vec![Condition::BioloadWarn, Condition::BioloadRepair]),
+        (
+            EthicsSummary { bioload: 0.95, ..calm() },
+            vec![Condition::BioloadWarn, Condition::BioloadRepair, Condition::BioloadHalt],
+        ),
+        (EthicsSummary { bioload_variance: 0.021, ..calm() }, vec![Condition::BioloadVariance]),
+        (EthicsSummary { mean_trust: 0.29, ..calm() }, vec![Condition::TrustFloor]),
+        (EthicsSummary { power_gini: 0.61, ..calm() }, vec![Condition::PowerConcentration]),
+        (EthicsSummary { power_church_ratio: 1.01, ..calm() }, vec![Condition::PowerCap]),
+        (EthicsSummary { life_harm_rate: 0.001, ..calm() }, vec![Condition::LifeHarm]),
+        (EthicsSummary { ethics_flag_rate: 0.06, ..calm() }, vec![Condition::EthicsFlags]),
+    ];
+    for (summary, expected) in &cases {
+        assert_eq!(&failed(summary), expected, "{summary:?}");
+    }
+    // Every condition is reachable on its own.
+    let mut seen: Vec<Condition> = cases.iter().flat_map(|(_, f)| f.clone()).collect();
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen, Condition::ALL);
+
+    // Ceilings and floors pass at the threshold; bands fail at it.
+    let edge = EthicsSummary {
+        bioload: 0.599,
+        bioload_variance: 0.02,
+        mean_trust: 0.3,
+        power_gini: 0.6,
+        power_gini_unfiltered: 0.95,
+        power_church_ratio: 1.0,
+        life_harm_rate: 0.0,
+        ethics_flag_rate: 0.05,
+    };
+    assert!(failed(&edge).is_empty());
+    let results = evaluate_conditions(&edge);
+    assert_eq!(results.map(|r| r.condition), Condition::ALL);
+    assert_eq!((results[4].measured, results[4].threshold, results[4].weight), (0.3, 0.3, 1));
+}
+
+#[test]
+fn aggregation_takes_the_heaviest_failure_and_escalates_on_variance() {
+    let disagree = |s: EthicsSummary| EthicsSummary { bioload_variance: 0.5, ..s };
+
+    assert_eq!(severity(&EthicsSummary { mean_trust: 0.1, ethics_flag_rate: 0.2, ..calm() }), 1);
+    assert_eq!(severity(&EthicsSummary { bioload: 0.7, power_gini: 0.7, ..calm() }), 2);
+    assert_eq!(severity(&EthicsSummary { power_church_ratio: 2.0, ..calm() }), 2);
+    assert_eq!(severity(&EthicsSummary { life_harm_rate: 0.01, mean_trust: 0.1, ..calm() }), 3);
+    assert_eq!(severity(&EthicsSummary { bioload: 0.95, power_gini: 0.9, ..calm() }), 3);
+
+    // Variance lifts Allow and Warn by one step and never reaches a halt.
+    assert_eq!(severity(&disagree(calm())), 1);
+    assert_eq!(severity(&disagree(EthicsSummary { bioload: 0.6, ..calm() })), 2);
+    assert_eq!(severity(&disagree(EthicsSummary { bioload: 0.8, ..calm() })), 2);
+    assert_eq!(severity(&disagree(EthicsSummary { bioload: 0.95, ..calm() })), 3);
+    assert_eq!(severity(&EthicsSummary { bioload_variance: 0.02, bioload: 0.6, ..calm() }), 1);
+
+    // The reason names the failures that set the decision.
+    let decision = decide(&evaluate_conditions(&disagree(EthicsSummary { mean_trust: 0.1, ..calm() })));
+    assert!(
+        matches!(&decision, EthicsDecision::ForceRepair { reason }
+            if reason == "trust_floor 0.100 below 0.300; bioload_variance 0.500 above 0.020"),
+        "{decision:?}"
+    );
+}
+
+#[test]
+fn evaluation_metrics_mirror_the_condition_array() {
+    let summary = EthicsSummary { bioload: 0.85, ethics_flag_rate: 0.1, ..calm() };
+    let evaluation = EthicsEvaluation::new(&summary, &ParamRegistry::default());
+    assert_eq!(evaluation.conditions, evaluate_conditions_with(&summary, &ParamRegistry::default()));
+    assert_eq!(evaluation.decision, decide(&evaluation.conditions));
+
+    let text = evaluation.to_prometheus();
+    for r in &evaluation.conditions {
+        let c = r.condition.as_str();
+        assert!(text.contains(&format!("cof_ethics_condition_passed{{condition=\"{c}\"}} {}\n", u8::from(r.passed))), "{c}");
+        assert!(text.contains(&format!("cof_ethics_condition_measured{{condition=\"{c}\"}} {}\n", r.measured)), "{c}");
+        assert!(text.contains(&format!("cof_ethics_condition_threshold{{condition=\"{c}\"}} {}\n", r.threshold)), "{c}");
+        assert!(text.contains(&format!("cof_ethics_condition_weight{{condition=\"{c}\"}} {}\n", r.weight)), "{c}");
+    }
+    assert!(text.contains("# TYPE cof_ethics_condition_passed gauge\n"));
+    assert!(text.ends_with("cof_ethics_decision_severity 2\n"));
+    assert_eq!(text.lines().filter(|l| l.starts_with("cof_ethics_condition_")).count(), 4 * 9);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_exposes_the_same_array() {
+    use church_of_fear::rpc::server::dispatch_request;
+    use serde_json::{json, Value};
+
+    let summary = EthicsSummary { mean_trust: 0.2, life_harm_rate: 0.02, ..calm() };
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.get_ethics_conditions",
+        "params": { "summary": summary },
+        "id": 1
+    })
+    .to_string();
+    let response: Value = serde_json::from_str(&dispatch_request(&request)).unwrap();
+    let evaluation: EthicsEvaluation = serde_json::from_value(response["result"].clone()).unwrap();
+    assert_eq!(evaluation, EthicsEvaluation::new(&summary, &ParamRegistry::default()));
+    assert_eq!(response["result"]["conditions"][7]["condition"], "life_harm");
+    assert_eq!(response["result"]["decision"]["decision"], "halt_and_review");
+
+    // Summaries from older callers leave the newer figures at zero.
+    let legacy = json!({ "bioload": 0.1, "bioload_variance": 0.0, "mean_trust": 0.9, "power_gini": 0.2 });
+    let request =
+        json!({ "jsonrpc": "2.0", "method": "auto_church.get_ethics_conditions", "params": { "summary": legacy }, "id": 2 });
+    let response: Value = serde_json::from_str(&dispatch_request(&request.to_string())).unwrap();
+    assert_eq!(response["result"]["decision"]["decision"], "allow");
+    let bad = json!({ "jsonrpc": "2.0", "method": "auto_church.get_ethics_conditions", "params": {}, "id": 3 });
+    let response: Value = serde_json::from_str(&dispatch_request(&bad.to_string())).unwrap();
+    assert_eq!(response["error"]["code"], -32602);
+}
+
+#[cfg(all(feature = "rpc", feature = "param-governance"))]
+#[test]
+fn thresholds_only_tighten_through_governance() {
+    use std::sync::{Arc, Mutex};
+
+    use church_of_fear::config::LedgerConfig;
+    use church_of_fear::ledger::token_ledger::TokenLedger;
+    use church_of_fear::params::{change_param, ParamChangeError, ParamError, ParamKey, SignedParamChange};
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use keyring::Keyring;
+    use param_registry::ParamChange;
+    use serde_json::{json, Value};
+
+    const T: i64 = 1_700_000_000;
+    let mut keyring = Keyring::new().with_clock(|| T as u64);
+    let names: Vec<String> = (0..2).map(|_| keyring.generate("param-authority").unwrap()).collect();
+    let bundle = keyring.verifying_bundle();
+    let signed = |id: &str, value: f64| {
+        let change = ParamChange {
+            change_id: id.to_string(),
+            param: "ethics_trust_floor".to_string(),
+            value: json!(value),
+            reason: "trust review".to_string(),
+            requested_at: T,
+        };
+        let approvals = names.iter().map(|s| keyring.sign(s, &change.signing_bytes()).unwrap()).collect();
+        SignedParamChange { change, approvals }
+    };
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    change_param(&mut ledger, &signed("trust-1", 0.5), &bundle, T).unwrap();
+    assert!(matches!(
+        change_param(&mut ledger, &signed("trust-2", 0.4), &bundle, T),
+        Err(ParamChangeError::Param(ParamError::Loosening { .. }))
+    ));
+    assert_eq!(ledger.params().get(ParamKey::EthicsTrustFloor), 0.5);
+
+    // 0.45 clears the default floor but not the tightened one.
+    let summary = EthicsSummary { mean_trust: 0.45, ..calm() };
+    assert_eq!(severity(&summary), 0);
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() };
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.get_ethics_conditions",
+        "params": { "summary": summary },
+        "id": 1
+    });
+    let response: Value = serde_json::from_str(&dispatch_request_with(&request.to_string(), &ctx)).unwrap();
+    assert_eq!(response["result"]["conditions"][4]["threshold"], 0.5);
+    assert_eq!(response["result"]["conditions"][4]["passed"], false);
+    assert_eq!(response["result"]["decision"]["decision"], "warn");
+}
@@ -0,0 +1,118 @@
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::builders::{HomelessnessReliefDeed, MathScienceEducationDeed};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::{FearTrigger, TokenLedger, TokenLedgerError};
+use church_of_fear::scheduler::RecurringJobs;
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "Alice");
+    ledger.open_account("bob", "Bob");
+    ledger
+}
+
+#[test]
+fn fear_accrues_only_on_regulator_transitions() {
+    let mut ledger = ledger();
+    assert_eq!(ledger.mint_reward("alice", Token::Fear, 5), Err(TokenLedgerError::NotARewardToken(Token::Fear)));
+
+    let deed = ledger.on_regulator_transition("alice", FearTrigger::Warn, "trust below floor").unwrap().clone();
+    assert_eq!(deed.deed_type, "fear_accrual");
+    assert_eq!(deed.target_ids, ["alice"]);
+    assert_eq!(deed.context_json["amount"], 10);
+    ledger.on_regulator_transition("alice", FearTrigger::ForceRepair, "bioload ceiling").unwrap();
+
+    assert_eq!(ledger.account("alice").unwrap().balance_fear, 35);
+    assert_eq!(ledger.account("bob").unwrap().balance_fear, 0);
+    // Each accrual is chained onto the previous one.
+    assert_eq!(ledger.deeds().len(), 2);
+    assert_eq!(ledger.deeds()[1].prev_hash, ledger.deeds()[0].self_hash);
+}
+
+#[test]
+fn fear_decay_math() {
+    let mut ledger = ledger();
+    ledger.accrue_fear("alice", 100, "test").unwrap();
+    ledger.accrue_fear("bob", 1, "test").unwrap();
+
+    assert_eq!(ledger.decay_fear(0.1).unwrap(), 11);
+    assert_eq!(ledger.account("alice").unwrap().balance_fear, 90);
+    assert_eq!(ledger.account("bob").unwrap().balance_fear, 0);
+    ledger.decay_fear(0.1).unwrap();
+    assert_eq!(ledger.account("alice").unwrap().balance_fear, 81);
+    assert_eq!(ledger.decay_fear(1.5), Err(TokenLedgerError::InvalidRate(1.5)));
+
+    // The scheduler runs the configured decay once per interval.
+    let mut jobs = RecurringJobs::with_defaults(&ledger, 0);
+    assert!(jobs.run_due(&mut ledger, 3_599).is_empty());
+    assert_eq!(jobs.run_due(&mut ledger, 3_600), ["decay_fear"]);
+    assert_eq!(ledger.account("alice").unwrap().balance_fear, 72);
+    assert!(jobs.run_due(&mut ledger, 3_601).is_empty());
+}
+
+#[test]
+fn tech_only_for_tech_positive_categories() {
+    let mut ledger = ledger();
+    let metrics = BioloadMetrics::new(-0.1, 0.1, 0.2);
+    let lesson = MathScienceEducationDeed::builder()
+        .actor_id("alice")
+        .subject("algebra")
+        .learners(12)
+        .hours(2.0)
+        .build("0".repeat(64))
+        .unwrap();
+    let meals = HomelessnessReliefDeed::builder()
+        .actor_id("alice")
+        .location("Phoenix")
+        .hours(3.0)
+        .meals_served(20)
+        .build("0".repeat(64))
+        .unwrap();
+
+    assert_eq!(ledger.mint_tech("alice", &lesson, &metrics).unwrap(), 10);
+    assert_eq!(
+        ledger.mint_tech("alice", &meals, &metrics),
+        Err(TokenLedgerError::NotTechPositive("homelessness_relief".into()))
+    );
+    assert_eq!(ledger.mint_reward("alice", Token::Tech, 5), Err(TokenLedgerError::NotARewardToken(Token::Tech)));
+
+    let mut capped = TokenLedger::new(LedgerConfig { tech_cap: 15, ..LedgerConfig::default() });
+    capped.open_account("alice", "Alice");
+    assert_eq!(capped.mint_tech("alice", &lesson, &metrics).unwrap(), 10);
+    assert_eq!(capped.mint_tech("alice", &lesson, &metrics).unwrap(), 5);
+    assert_eq!(capped.mint_tech("alice", &lesson, &metrics).unwrap(), 0);
+}
+
+#[test]
+fn supply_reconciles_across_all_four_tokens() {
+    let mut ledger = ledger();
+    let metrics = BioloadMetrics::new(-0.1, 0.1, 0.2);
+    let lesson = MathScienceEducationDeed::builder()
+        .actor_id("bob")
+        .subject("physics")
+        .learners(3)
+        .hours(1.0)
+        .build("0".repeat(64))
+        .unwrap();
+
+    ledger.mint_reward("alice", Token::Church, 500).unwrap();
+    ledger.mint_reward("bob", Token::Pwr, 40).unwrap();
+    ledger.mint_tech("bob", &lesson, &metrics).unwrap();
+    ledger.accrue_fear("alice", 30, "warn").unwrap();
+    ledger.burn("alice", Token::Church, 120).unwrap();
+    // Saturating burn only retires what was there.
+    assert_eq!(ledger.burn("bob", Token::Pwr, 100).unwrap(), 40);
+    ledger.decay_fear(0.5).unwrap();
+
+    let report = ledger.supply_report();
+    assert!(report.reconciles(), "{report:?}");
+    assert_eq!(report.tokens[&Token::Church].circulating, 380);
+    assert_eq!(report.tokens[&Token::Pwr].circulating, 0);
+    assert_eq!(report.tokens[&Token::Tech].circulating, 10);
+    assert_eq!(report.tokens[&Token::Fear].circulating, 15);
+
+    let text = report.to_prometheus();
+    assert!(text.contains("cof_token_circulating{token=\"fear\"} 15"));
+    assert!(text.contains("cof_token_issued_total{token=\"tech\"} 10"));
+}
@@ -0,0 +1,84 @@
+//! Exercises each cargo feature on its own; run with
+//! `--no-default-features --features core` as well as the defaults.
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::{validate_chain, DeedEvent};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use serde_json::json;
+
+fn chain(len: usize) -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    for i in 0..len {
+        let deed = DeedEvent::new(
+            ledger.last_hash(),
+            "edge-1".to_string(),
+            vec![format!("peer-{}", i)],
+            "ecological_sustainability".to_string(),
+            vec!["eco".to_string()],
+            json!({ "co2_kg": 1.0 }),
+            vec![],
+            false,
+        );
+        ledger.append(deed).unwrap();
+    }
+    ledger
+}
+
+#[test]
+fn core_appends_verified_chain() {
+    let ledger = chain(3);
+    assert_eq!(ledger.deeds().len(), 3);
+    assert_eq!(validate_chain(ledger.deeds()), Ok(()));
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use church_of_fear::rpc::server::dispatch_request;
+    use serde_json::Value;
+
+    #[test]
+    fn unknown_method_is_rejected() {
+        let raw = r#"{"jsonrpc":"2.0","method":"no_such_method","params":{},"id":1}"#;
+        let resp: Value = serde_json::from_str(&dispatch_request(raw)).unwrap();
+        assert_eq!(resp["error"]["code"], -32601);
+    }
+}
+
+#[cfg(feature = "viz")]
+mod viz {
+    use church_of_fear::viz::export_scene;
+
+    #[test]
+    fn scene_links_every_chained_deed() {
+        let ledger = super::chain(3);
+        let scene = export_scene(ledger.deeds());
+        assert_eq!(scene.nodes.len(), 3);
+        assert_eq!(scene.links, vec![(0, 1), (1, 2)]);
+    }
+}
+
+#[cfg(feature = "graph")]
+mod graph {
+    use church_of_fear::ledger::graph::deed_graph;
+
+    #[test]
+    fn graph_has_one_edge_per_target() {
+        let ledger = super::chain(3);
+        let graph = deed_graph(ledger.deeds());
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+    }
+}
+
+#[cfg(feature = "testkit")]
+mod testkit {
+    use church_of_fear::fixtures::small_community;
+    use church_of_fear::ledger::deed_event::validate_chain;
+
+    #[test]
+    fn scenario_builds_a_verified_chain() {
+        let scenario = small_community(1, 1_700_000_000).build().unwrap();
+        assert_eq!(validate_chain(scenario.ledger.deeds()), Ok(()));
+        assert_eq!(scenario.ledger.pool_balance(), scenario.manifest.pool);
+    }
+}
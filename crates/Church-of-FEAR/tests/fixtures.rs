@@ -0,0 +1,104 @@
+#![cfg(feature = "testkit")]
+
+use church_of_fear::fixtures::{
+    abuse_attempt, clinical_deployment, small_community, LedgerScenarioBuilder, Scenario, ScenarioError, Timeline,
+};
+use church_of_fear::history::HistoricalPoint;
+use church_of_fear::ledger::deed_event::validate_chain;
+use church_of_fear::obligations::{category_streak, PENDING_OBLIGATIONS};
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+
+/// Check the ledger against the reference manifest.
+fn assert_agrees(scenario: &Scenario) {
+    let (ledger, manifest) = (&scenario.ledger, &scenario.manifest);
+    assert_eq!(validate_chain(ledger.deeds()), Ok(()));
+    assert_eq!(ledger.pool_balance(), manifest.pool);
+    assert_eq!(ledger.account(PENDING_OBLIGATIONS).map_or(0, |a| a.balance_church), manifest.escrow);
+    let view = ledger.state_at(&HistoricalPoint::Tip(ledger.last_hash())).unwrap().view;
+    for (id, expected) in &manifest.actors {
+        let account = ledger.account(id);
+        assert_eq!(account.map_or(0, |a| a.balance_church), expected.church, "{id} CHURCH");
+        assert_eq!(account.map_or(0, |a| a.balance_fear), expected.fear, "{id} FEAR");
+        let standing = view.standing(id).unwrap();
+        assert_eq!((standing.deeds(), standing.standing()), (expected.deeds, expected.standing), "{id} standing");
+        for (deed_type, streak) in &expected.category_streaks {
+            assert_eq!(category_streak(ledger, id, deed_type), *streak, "{id} {deed_type} streak");
+        }
+    }
+}
+
+#[test]
+fn same_seed_builds_the_same_ledger() {
+    let a = small_community(7, T0).build().unwrap();
+    let b = small_community(7, T0).build().unwrap();
+    assert_eq!(a.to_jsonl(), b.to_jsonl());
+    assert_eq!(a.manifest, b.manifest);
+    assert_ne!(a.to_jsonl(), small_community(8, T0).build().unwrap().to_jsonl());
+}
+
+#[test]
+fn canned_scenarios_match_their_reference() {
+    for scenario in [small_community(1, T0), abuse_attempt(2, T0), clinical_deployment(3, T0)] {
+        assert_agrees(&scenario.build().unwrap());
+    }
+    let abuse = abuse_attempt(2, T0).build().unwrap().manifest;
+    assert_eq!(abuse.actors["mallory"].fear, 20);
+    assert_eq!(abuse.actors["bob"].longest_streak_days, 3);
+    let clinic = clinical_deployment(3, T0).build().unwrap().manifest;
+    assert_eq!(clinic.actors["clinic-1"].church, 0, "attestations wait for their quorum");
+    assert_eq!(clinic.actors["clinic-1"].escrowed, 1200);
+}
+
+#[test]
+fn ledger_deeds_take_scenario_time() {
+    let scenario = abuse_attempt(2, T0).build().unwrap();
+    let deeds = scenario.ledger.deeds();
+    assert!(deeds.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    assert!(deeds.iter().all(|d| (T0..T0 + 3 * DAY).contains(&d.timestamp)));
+}
+
+#[test]
+fn declarations_are_checked() {
+    let base = || LedgerScenarioBuilder::new(0, T0).actor("alice", "resident");
+    let err = |b: LedgerScenarioBuilder| b.build().unwrap_err();
+    assert!(matches!(err(base().actor("alice", "visitor")), ScenarioError::DuplicateActor(a) if a == "alice"));
+    assert!(matches!(err(base().timeline(Timeline::new("bob", "homelessness_relief", T0))), ScenarioError::UnknownActor(_)));
+    assert!(matches!(err(base().timeline(Timeline::new("alice", "juggling", T0))), ScenarioError::UnknownCategory(_)));
+    assert!(matches!(
+        err(base().timeline(Timeline::new("alice", "homelessness_relief", T0).count(3).every(0))),
+        ScenarioError::NonPositiveInterval { every_secs: 0, .. }
+    ));
+    assert!(matches!(
+        err(base().actor_joining("erin", "resident", T0 + DAY).harm("erin", T0, "early")),
+        ScenarioError::BeforeJoin { at: T0, .. }
+    ));
+    assert!(matches!(err(base().harm("alice", T0 - 1, "early")), ScenarioError::BeforeStart { .. }));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn inspector_day_streaks_match_the_reference() {
+    use church_of_fear::inspect::LedgerIndex;
+
+    let scenario = small_community(11, T0).build().unwrap();
+    let dir = std::env::temp_dir().join(format!("cof-fixtures-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ledger.jsonl");
+    scenario.write(&path).unwrap();
+    let index = LedgerIndex::open(&path);
+    let manifest = std::fs::read(dir.join("ledger.jsonl.manifest.json"));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (index, manifest) = (index.unwrap(), manifest.unwrap());
+    assert_eq!(serde_json::from_slice::<church_of_fear::fixtures::ScenarioManifest>(&manifest).unwrap(), scenario.manifest);
+    for (id, expected) in &scenario.manifest.actors {
+        let activity = index.activity(id).unwrap();
+        assert_eq!(activity.deeds, expected.deeds, "{id} deeds");
+        assert_eq!(
+            (activity.current_streak_days, activity.longest_streak_days),
+            (expected.current_streak_days, expected.longest_streak_days),
+            "{id} day streaks"
+        );
+    }
+}
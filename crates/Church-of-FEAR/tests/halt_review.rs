@@ -0,0 +1,331 @@
+#![cfg(feature = "halt-review")]
+
+use church_of_fear::compliance::regulator::EthicsDecision;
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::halt_review::{
+    freeze_status, is_high_impact, lift_freeze, record_regulator_decision, screen_submission, FreezeError, FreezeLiftRequest,
+    FreezePolicy, FreezeTick, FrozenSubmission, Readmission, Screening, SignedFreezeLift,
+};
+use church_of_fear::ledger::builders::{ClinicalAttestationDeed, WatershedCleanupDeed};
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::providers::register_provider;
+use keyring::{Keyring, VerifyingBundle};
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const CLINIC: &str = "provider:maricopa-free-clinic";
+
+fn at(mut deed: DeedEvent, timestamp: i64) -> DeedEvent {
+    deed.timestamp = timestamp;
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+fn cleanup(ledger: &TokenLedger, actor: &str, timestamp: i64) -> DeedEvent {
+    let deed = WatershedCleanupDeed::builder()
+        .actor_id(actor)
+        .location("Tempe, AZ")
+        .volunteers(12)
+        .waste_kg(80.0)
+        .evidence_uri("ipfs://cleanup")
+        .target("target:salt-river")
+        .build(ledger.last_hash())
+        .unwrap();
+    at(deed, timestamp)
+}
+
+fn attestation(ledger: &TokenLedger, actor: &str, timestamp: i64) -> DeedEvent {
+    let deed = ClinicalAttestationDeed::builder()
+        .actor_id(actor)
+        .facility("Maricopa free clinic")
+        .procedure("vaccination")
+        .patients(40)
+        .evidence_uri("ipfs://clinic-log")
+        .provider_id(CLINIC)
+        .build(ledger.last_hash())
+        .unwrap();
+    at(deed, timestamp)
+}
+
+fn planting(ledger: &TokenLedger, actor: &str, timestamp: i64) -> DeedEvent {
+    let deed = DeedEvent::new(ledger.last_hash(), actor.into(), vec![], "tree_planting".into(), vec![], json!({}), vec![], false);
+    at(deed, timestamp)
+}
+
+/// Bioload deltas worth a 60 and a 20 CHURCH cleanup reward.
+fn large() -> BioloadMetrics {
+    BioloadMetrics::new(-0.6, 0.1, 0.5)
+}
+
+fn small() -> BioloadMetrics {
+    BioloadMetrics::new(-0.2, 0.1, 0.5)
+}
+
+fn halt() -> EthicsDecision {
+    EthicsDecision::HaltAndReview { reason: "bioload_halt".to_string() }
+}
+
+fn frozen_ledger() -> (TokenLedger, String) {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    register_provider(&mut ledger, CLINIC, "Maricopa free clinic", "Regulator", T0).unwrap();
+    let FreezeTick::Frozen { freeze_event_id } = record_regulator_decision(&mut ledger, &halt(), "eval-1").unwrap() else {
+        panic!("halt did not freeze");
+    };
+    (ledger, freeze_event_id)
+}
+
+fn queue(ledger: &mut TokenLedger, deed: &DeedEvent, metrics: &BioloadMetrics) -> String {
+    match screen_submission(ledger, deed, metrics).unwrap() {
+        Screening::Queued { queue_event_id, .. } => queue_event_id,
+        Screening::Proceed => panic!("deed {} was not queued", deed.event_id),
+    }
+}
+
+fn clear(ledger: &mut TokenLedger, ticks: usize) {
+    for i in 0..ticks {
+        record_regulator_decision(ledger, &EthicsDecision::Warn { reason: "trust_floor".into() }, &format!("eval-clear-{i}")).unwrap();
+    }
+}
+
+fn authorities(n: usize) -> (Keyring, Vec<String>) {
+    let mut keyring = Keyring::new().with_clock(|| T0 as u64);
+    let names = (0..n).map(|_| keyring.generate("freeze-authority").unwrap()).collect();
+    (keyring, names)
+}
+
+fn signed(keyring: &Keyring, signers: &[String], freeze_event_id: &str) -> SignedFreezeLift {
+    let request = FreezeLiftRequest { freeze_event_id: freeze_event_id.to_string(), memo: "bioload recovered".into(), requested_at: T0 };
+    let approvals = signers.iter().map(|s| keyring.sign(s, &request.signing_bytes()).unwrap()).collect();
+    SignedFreezeLift { request, approvals }
+}
+
+#[test]
+fn the_policy_decides_what_is_high_impact() {
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let config = LedgerConfig::default();
+    assert!(is_high_impact(&config, &attestation(&ledger, "alice", T0), &small()));
+    assert!(is_high_impact(&config, &cleanup(&ledger, "alice", T0), &large()));
+    assert!(!is_high_impact(&config, &cleanup(&ledger, "alice", T0), &small()));
+    assert!(!is_high_impact(&config, &planting(&ledger, "alice", T0), &large()));
+
+    let freeze = FreezePolicy { high_impact_types: vec!["tree_planting".into()], reward_threshold: None, ..FreezePolicy::default() };
+    let by_type = LedgerConfig { freeze, ..LedgerConfig::default() };
+    assert!(is_high_impact(&by_type, &planting(&ledger, "alice", T0), &small()));
+    assert!(!is_high_impact(&by_type, &cleanup(&ledger, "alice", T0), &large()));
+    assert!(!is_high_impact(&by_type, &attestation(&ledger, "alice", T0), &small()));
+}
+
+#[test]
+fn deeds_submitted_while_frozen_queue_in_order_without_minting() {
+    let (mut ledger, freeze_event_id) = frozen_ledger();
+    let supply = ledger.supply_report();
+
+    let first = attestation(&ledger, "alice", T0);
+    let first_queued = queue(&mut ledger, &first, &small());
+    let planted = planting(&ledger, "bob", T0 + 10);
+    assert_eq!(screen_submission(&mut ledger, &planted, &small()).unwrap(), Screening::Proceed);
+    ledger.append(planted).unwrap();
+    let second = cleanup(&ledger, "carol", T0 + 20);
+    let second_queued = queue(&mut ledger, &second, &large());
+    let third = attestation(&ledger, "dave", T0 + 30);
+    let third_queued = queue(&mut ledger, &third, &small());
+
+    let status = freeze_status(&ledger).unwrap();
+    assert_eq!(status.freeze_event_id, freeze_event_id);
+    assert_eq!(status.decision_ref, "eval-1");
+    let order: Vec<(&str, &str)> = status.queued.iter().map(|q| (q.queue_event_id.as_str(), q.deed.event_id.as_str())).collect();
+    assert_eq!(
+        order,
+        [
+            (first_queued.as_str(), first.event_id.as_str()),
+            (second_queued.as_str(), second.event_id.as_str()),
+            (third_queued.as_str(), third.event_id.as_str()),
+        ]
+    );
+    // Queued deeds are not on the chain and moved nothing.
+    assert!(ledger.deed(&first.event_id).is_none());
+    assert_eq!(ledger.supply_report(), supply);
+}
+
+#[test]
+fn reject_mode_refuses_high_impact_deeds() {
+    let mut cfg = LedgerConfig::default();
+    cfg.freeze.during_freeze = FrozenSubmission::Reject;
+    let mut ledger = TokenLedger::new(cfg);
+    record_regulator_decision(&mut ledger, &halt(), "eval-1").unwrap();
+    let deed = attestation(&ledger, "alice", T0);
+    assert!(matches!(screen_submission(&mut ledger, &deed, &small()), Err(FreezeError::Frozen { .. })));
+    assert!(freeze_status(&ledger).unwrap().queued.is_empty());
+}
+
+#[test]
+fn a_lift_needs_clear_ticks_and_an_authority_quorum() {
+    let (mut ledger, freeze_event_id) = frozen_ledger();
+    let (keyring, names) = authorities(3);
+    let bundle = keyring.verifying_bundle();
+    let quorum = signed(&keyring, &names[..2], &freeze_event_id);
+
+    // A halt on the way resets the run of clear ticks.
+    clear(&mut ledger, 2);
+    assert_eq!(record_regulator_decision(&mut ledger, &halt(), "eval-2").unwrap(), FreezeTick::Reviewed {
+        freeze_event_id: freeze_event_id.clone(),
+        clear_ticks: 0
+    });
+    clear(&mut ledger, 2);
+    assert!(matches!(lift_freeze(&mut ledger, &quorum, &bundle, T0), Err(FreezeError::NotClear { got: 2, need: 3 })));
+    clear(&mut ledger, 1);
+
+    // The tick condition alone is not enough either.
+    let one = signed(&keyring, &names[..1], &freeze_event_id);
+    assert!(matches!(lift_freeze(&mut ledger, &one, &bundle, T0), Err(FreezeError::NotEnoughApprovals { got: 1, need: 2 })));
+    let doubled = signed(&keyring, &[names[0].clone(), names[0].clone()], &freeze_event_id);
+    assert!(matches!(lift_freeze(&mut ledger, &doubled, &bundle, T0), Err(FreezeError::NotEnoughApprovals { got: 1, .. })));
+    let mut rogue = Keyring::new().with_clock(|| T0 as u64);
+    let outsider = rogue.generate("pool-authority").unwrap();
+    let mixed_bundle = VerifyingBundle { keys: keyring.keys().chain(rogue.keys()).cloned().collect() };
+    let mut mixed = signed(&keyring, &names[..1], &freeze_event_id);
+    mixed.approvals.push(rogue.sign(&outsider, &mixed.request.signing_bytes()).unwrap());
+    assert!(matches!(lift_freeze(&mut ledger, &mixed, &mixed_bundle, T0), Err(FreezeError::NotAuthority(_))));
+    assert!(matches!(lift_freeze(&mut ledger, &quorum, &bundle, T0 + 7_200), Err(FreezeError::Stale { .. })));
+    let elsewhere = signed(&keyring, &names[..2], "another-freeze");
+    assert!(matches!(lift_freeze(&mut ledger, &elsewhere, &bundle, T0), Err(FreezeError::WrongFreeze { .. })));
+    assert!(freeze_status(&ledger).is_some());
+
+    let lifted = lift_freeze(&mut ledger, &quorum, &bundle, T0).unwrap();
+    assert_eq!(lifted.signers.len(), 2);
+    assert!(freeze_status(&ledger).is_none());
+    assert_eq!(record_regulator_decision(&mut ledger, &EthicsDecision::Allow, "eval-3").unwrap(), FreezeTick::Idle);
+    assert!(matches!(lift_freeze(&mut ledger, &quorum, &bundle, T0), Err(FreezeError::NotFrozen)));
+}
+
+#[test]
+fn readmitted_deeds_keep_their_ids_timestamps_and_order() {
+    let (mut ledger, freeze_event_id) = frozen_ledger();
+    let first = attestation(&ledger, "alice", T0);
+    queue(&mut ledger, &first, &small());
+    let second = cleanup(&ledger, "carol", T0 + 20);
+    queue(&mut ledger, &second, &large());
+    // Flagged since it was queued: validation refuses it on the way back in.
+    let refused = DeedEvent { ethics_flags: vec!["coercion".into()], ..attestation(&ledger, "erin", T0 + 30) };
+    let refused = at(refused, T0 + 30);
+    queue(&mut ledger, &refused, &large());
+    // Ledger-written deeds land after the submissions, in node time.
+    let later = planting(&ledger, "bob", T0 + 40);
+    ledger.append(later).unwrap();
+    clear(&mut ledger, 3);
+
+    let (keyring, names) = authorities(2);
+    let lifted = lift_freeze(&mut ledger, &signed(&keyring, &names, &freeze_event_id), &keyring.verifying_bundle(), T0).unwrap();
+    let [Readmission::Readmitted { deed: a, pending_validation, church_minted, .. }, Readmission::Readmitted { deed: b, church_minted: minted_b, .. }, Readmission::Refused { event_id, .. }] =
+        &lifted.readmitted[..]
+    else {
+        panic!("{:?}", lifted.readmitted);
+    };
+    assert_eq!((a.event_id.as_str(), a.timestamp), (first.event_id.as_str(), T0));
+    assert_eq!((b.event_id.as_str(), b.timestamp), (second.event_id.as_str(), T0 + 20));
+    assert_eq!(event_id, &refused.event_id);
+    // Readmission is the ordinary pipeline: the attestation awaits its quorum.
+    assert!(pending_validation.is_some());
+    assert_eq!((*church_minted, *minted_b), (0, 60));
+
+    // Both sit right after the lift, relinked to the tip in queue order.
+    let deeds = ledger.deeds();
+    let lift = deeds.iter().position(|d| d.event_id == lifted.lift_event_id).unwrap();
+    assert_eq!(deeds[lift + 1].event_id, first.event_id);
+    assert_eq!(deeds[lift + 1].prev_hash, deeds[lift].self_hash);
+    assert_eq!(ledger.deed(&second.event_id).unwrap().timestamp, T0 + 20);
+    assert!(ledger.deed(&refused.event_id).is_none());
+}
+
+#[test]
+fn replay_reconstructs_an_active_freeze() {
+    let (mut ledger, freeze_event_id) = frozen_ledger();
+    let first = attestation(&ledger, "alice", T0);
+    queue(&mut ledger, &first, &small());
+    clear(&mut ledger, 2);
+    let second = cleanup(&ledger, "carol", T0 + 20);
+    queue(&mut ledger, &second, &large());
+
+    let mut replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.high_impact_freeze(), Some(freeze_event_id.as_str()));
+    let before = serde_json::to_value(freeze_status(&ledger)).unwrap();
+    assert_eq!(serde_json::to_value(freeze_status(&replayed)).unwrap(), before);
+
+    // The replayed ledger goes on queueing, counting and lifting as the original would.
+    let third = attestation(&replayed, "dave", T0 + 30);
+    queue(&mut replayed, &third, &small());
+    clear(&mut replayed, 1);
+    let status = freeze_status(&replayed).unwrap();
+    assert_eq!((status.clear_ticks, status.queued.len()), (3, 3));
+    let (keyring, names) = authorities(2);
+    let lifted = lift_freeze(&mut replayed, &signed(&keyring, &names, &freeze_event_id), &keyring.verifying_bundle(), T0).unwrap();
+    assert_eq!(lifted.readmitted.len(), 3);
+    let after = TokenLedger::replay(LedgerConfig::default(), replayed.deeds().to_vec()).unwrap();
+    assert!(after.high_impact_freeze().is_none());
+}
+
+#[test]
+fn only_the_ledger_writes_freeze_deeds() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let deed = DeedEvent::new(ledger.last_hash(), "mallory".into(), vec![], "high_impact_freeze_lifted".into(), vec![], json!({}), vec![], false);
+    assert!(ledger.append(deed).is_err());
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn mint_deed_queues_or_refuses_under_a_freeze() {
+    use std::sync::{Arc, Mutex};
+
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let call = |ctx: &RpcContext, method: &str, params: Value| -> Value {
+        let req = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str(&dispatch_request_with(&req.to_string(), ctx)).unwrap()
+    };
+    let mint = |ledger: &TokenLedger| {
+        let deed = attestation(ledger, "alice", T0);
+        json!({
+            "prev_hash": ledger.last_hash(),
+            "actor_id": deed.actor_id,
+            "target_ids": deed.target_ids,
+            "deed_type": deed.deed_type,
+            "tags": deed.tags,
+            "context_json": deed.context_json,
+            "ethics_flags": [],
+            "life_harm_flag": false,
+            "bioload_delta": -0.1,
+            "roh": 0.1,
+            "decay": 0.5,
+        })
+    };
+
+    let (ledger, freeze_event_id) = frozen_ledger();
+    let height = ledger.deeds().len();
+    let params = mint(&ledger);
+    let ledger = Arc::new(Mutex::new(ledger));
+    let ctx = RpcContext::with_ledger(ledger.clone());
+    let resp = call(&ctx, "auto_church.mint_deed", params);
+    let queued = resp["result"]["queued_for_review"].as_str().unwrap().to_string();
+    assert_eq!(resp["result"]["church_minted"], 0);
+    assert!(resp["result"]["pending_validation"].is_null());
+    assert_eq!(ledger.lock().unwrap().deeds().len(), height + 1);
+
+    let status = call(&ctx, "auto_church.freeze_status", json!({}));
+    assert_eq!(status["result"]["freeze"]["freeze_event_id"], freeze_event_id);
+    assert_eq!(status["result"]["freeze"]["queued"][0]["queue_event_id"], queued);
+
+    let mut cfg = LedgerConfig::default();
+    cfg.freeze.during_freeze = FrozenSubmission::Reject;
+    let mut strict = TokenLedger::new(cfg);
+    record_regulator_decision(&mut strict, &halt(), "eval-1").unwrap();
+    let params = mint(&strict);
+    let ctx = RpcContext::with_ledger(Arc::new(Mutex::new(strict)));
+    assert_eq!(call(&ctx, "auto_church.mint_deed", params)["error"]["code"], 1011);
+
+    let idle = RpcContext::with_ledger(Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default()))));
+    assert!(call(&idle, "auto_church.freeze_status", json!({}))["result"]["freeze"].is_null());
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "core")]
+
+use std::sync::Arc;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::history::{HistoricalPoint, HistoryError, Standing};
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+
+/// Append a `deed_type` deed by `actor` stamped `at`; returns its hash.
+fn stamped(ledger: &mut TokenLedger, actor: &str, deed_type: &str, at: i64, flags: Vec<String>) -> String {
+    ledger.open_account(actor, actor);
+    let mut deed = DeedEvent::new(ledger.last_hash(), actor.into(), vec![], deed_type.into(), vec![], json!({}), flags, false);
+    deed.timestamp = at;
+    deed.self_hash = hash_deed(&deed);
+    ledger.append(deed).unwrap().self_hash.clone()
+}
+
+fn tip(hash: &str) -> HistoricalPoint {
+    HistoricalPoint::Tip(hash.to_string())
+}
+
+#[test]
+fn an_early_tip_matches_a_control_replay() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let planted = stamped(&mut ledger, "alice", "tree_planting", T0, vec![]);
+    ledger.reward_for("alice", Token::Church, 100, None).unwrap();
+    stamped(&mut ledger, "bob", "river_cleanup", T0 + 60, vec!["unverified_claim".into()]);
+    ledger.reward_for("bob", Token::Church, 40, None).unwrap();
+    let early = ledger.last_hash();
+    let height = ledger.deeds().len();
+    ledger.seal_segment().unwrap().unwrap();
+
+    // Everything after the early tip moves balances and standings again.
+    ledger.reward_for("alice", Token::Church, 500, None).unwrap();
+    let late = stamped(&mut ledger, "alice", "tree_planting", T0 + 120, vec![]);
+    ledger.accrue_fear("bob", 7, "warned").unwrap();
+    ledger.tombstone(&ledger.deeds()[ledger.deeds().len() - 2].event_id.clone(), "duplicate", "Host").unwrap();
+
+    let control = TokenLedger::replay(ledger.config().clone(), ledger.deeds()[..height].to_vec()).unwrap();
+    let state = ledger.state_at(&tip(&early)).unwrap();
+    let view = &state.view;
+    assert!(!state.between_events);
+    assert_eq!((view.tip_hash(), view.height(), view.segment()), (early.as_str(), height, Some(0)));
+    assert_eq!(view.supply(), &control.supply_report());
+    for account in control.accounts() {
+        for token in Token::ALL {
+            assert_eq!(view.balance(&account.id, token), account.balance(token), "{} {:?}", account.id, token);
+        }
+    }
+    assert_eq!(view.balances().len(), control.accounts().filter(|a| Token::ALL.iter().any(|&t| a.balance(t) > 0)).count());
+    assert_eq!(view.balance("bob", Token::Fear), 0);
+    assert_eq!(view.standing("alice").map(|s| (s.deeds(), s.standing())), Some((1, Standing::Good)));
+    assert_eq!(view.standing("bob").map(|s| (s.flagged(), s.standing())), Some((1, Standing::Flagged)));
+    assert!(view.standing("ledger").is_none());
+
+    // The live ledger has moved on; the first deed is still the first tip.
+    assert_ne!(ledger.account("alice").unwrap().balance(Token::Church), view.balance("alice", Token::Church));
+    let first = ledger.state_at(&tip(&planted)).unwrap().view;
+    assert_eq!((first.height(), first.balance("alice", Token::Church)), (1, 0));
+    let now = ledger.state_at(&tip(&ledger.last_hash())).unwrap().view;
+    assert_eq!(now.supply(), &ledger.supply_report());
+    assert_eq!(now.segment(), None);
+    assert_eq!(now.standing("alice").unwrap().deeds(), 1, "tombstoned planting at {late} no longer counts");
+
+    assert_eq!(ledger.state_at(&tip("f00d")).unwrap_err(), HistoryError::UnknownTip("f00d".into()));
+}
+
+#[test]
+fn repeated_queries_hit_the_cache() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let hashes: Vec<String> = (0..20).map(|i| stamped(&mut ledger, "alice", "tree_planting", T0 + i * 60, vec![])).collect();
+
+    let first = ledger.state_at(&tip(&hashes[3])).unwrap().view;
+    assert!(Arc::ptr_eq(&first, &ledger.state_at(&tip(&hashes[3])).unwrap().view));
+    // A timestamp resolving to the same tip shares the cached view.
+    assert!(Arc::ptr_eq(&first, &ledger.state_at(&HistoricalPoint::Timestamp(T0 + 3 * 60 + 30)).unwrap().view));
+
+    // Appends do not disturb views of earlier tips.
+    stamped(&mut ledger, "bob", "river_cleanup", T0 + 2_000, vec![]);
+    assert!(Arc::ptr_eq(&first, &ledger.state_at(&tip(&hashes[3])).unwrap().view));
+
+    // Enough other tips evict it; the rebuilt view is equal but new.
+    for hash in &hashes[4..] {
+        ledger.state_at(&tip(hash)).unwrap();
+    }
+    let rebuilt = ledger.state_at(&tip(&hashes[3])).unwrap().view;
+    assert!(!Arc::ptr_eq(&first, &rebuilt));
+    assert_eq!(first, rebuilt);
+
+    // Clones start with an empty cache.
+    let cloned = ledger.clone();
+    assert!(!Arc::ptr_eq(&rebuilt, &cloned.state_at(&tip(&hashes[3])).unwrap().view));
+}
+
+#[test]
+fn timestamps_between_events_are_flagged() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let a = stamped(&mut ledger, "alice", "tree_planting", T0, vec![]);
+    let b = stamped(&mut ledger, "alice", "tree_planting", T0 + 10, vec![]);
+    let c = stamped(&mut ledger, "bob", "river_cleanup", T0 + 20, vec![]);
+
+    let at = |ts: i64| {
+        let state = ledger.state_at(&HistoricalPoint::Timestamp(ts)).unwrap();
+        (state.view.tip_hash().to_string(), state.between_events)
+    };
+    assert_eq!(at(T0), (a.clone(), false));
+    assert_eq!(at(T0 + 10), (b.clone(), false));
+    assert_eq!(at(T0 + 15), (b, true));
+    assert_eq!(at(T0 + 20), (c.clone(), false));
+    assert_eq!(at(T0 + 3_600), (c, true));
+    assert_eq!(ledger.state_at(&HistoricalPoint::Timestamp(T0 - 1)).unwrap_err(), HistoryError::BeforeGenesis(T0 - 1));
+
+    // Chain order wins over a back-dated deed: the view stops before the
+    // first deed stamped after the requested second.
+    stamped(&mut ledger, "carol", "food_share", T0 + 5, vec![]);
+    assert_eq!(ledger.state_at(&HistoricalPoint::Timestamp(T0 + 5)).unwrap().view.tip_hash(), a);
+}
+
+#[test]
+fn views_never_convert_into_live_state() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/history/*.rs");
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn state_is_served_over_rpc() {
+    use std::sync::Mutex;
+
+    use church_of_fear::rpc::server::{dispatch_request, dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let first = stamped(&mut ledger, "alice", "tree_planting", T0, vec![]);
+    ledger.reward_for("alice", Token::Church, 100, None).unwrap();
+    stamped(&mut ledger, "alice", "tree_planting", T0 + 60, vec![]);
+    let expected = ledger.state_at(&tip(&ledger.deeds()[1].self_hash)).unwrap().view;
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() };
+    let call = |params: Value, ctx: &RpcContext| -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": "auto_church.get_state_at", "params": params, "id": 1 });
+        serde_json::from_str(&dispatch_request_with(&request.to_string(), ctx)).unwrap()
+    };
+
+    let by_hash = call(json!({ "tip_hash": expected.tip_hash() }), &ctx);
+    assert_eq!(by_hash["result"]["tip_hash"], expected.tip_hash());
+    assert_eq!(by_hash["result"]["between_events"], false);
+    assert_eq!(by_hash["result"]["view"], serde_json::to_value(&*expected).unwrap());
+    assert_eq!(by_hash["result"]["view"]["balances"]["alice"]["church"], expected.balance("alice", Token::Church));
+
+    let by_time = call(json!({ "timestamp": T0 + 30 }), &ctx);
+    assert_eq!(by_time["result"]["tip_hash"], first);
+    assert_eq!(by_time["result"]["between_events"], true);
+
+    assert_eq!(call(json!({ "tip_hash": "f00d" }), &ctx)["error"]["code"], 1006);
+    assert_eq!(call(json!({}), &ctx)["error"]["code"], -32602);
+    assert_eq!(call(json!({ "tip_hash": first, "timestamp": T0 }), &ctx)["error"]["code"], -32602);
+    let request = json!({ "jsonrpc": "2.0", "method": "auto_church.get_state_at", "params": { "timestamp": T0 }, "id": 2 });
+    let bare: Value = serde_json::from_str(&dispatch_request(&request.to_string())).unwrap();
+    assert_eq!(bare["error"]["code"], 1004);
+}
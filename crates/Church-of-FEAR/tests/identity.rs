@@ -0,0 +1,293 @@
+#![cfg(feature = "actor-keys")]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::identity::{
+    accepted_key, authenticate, bind_actor_key, cancel_recovery, complete_recovery, key_lineage, pending_recovery,
+    recover_account, rotate_actor_key, IdentityError, KeyAuthority, KeyChange, RecoveryNotice, RecoveryNotifier, RecoveryQuorum,
+    ACTOR_KEY_ROTATED,
+};
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use keyring::{KeyMeta, Keyring};
+use serde_json::json;
+
+const T: i64 = 1_700_000_000;
+const WAIT: i64 = 72 * 3600;
+
+/// A keyring on a clock the test moves.
+fn keyring(clock: &Arc<AtomicU64>) -> Keyring {
+    let clock = clock.clone();
+    Keyring::new().with_clock(move || clock.load(Ordering::SeqCst))
+}
+
+fn meta(keys: &Keyring, name: &str) -> KeyMeta {
+    keys.keys().find(|k| k.name == name).unwrap().clone()
+}
+
+fn change(new_key: &KeyMeta) -> Vec<u8> {
+    KeyChange::new("alice", new_key).signing_bytes()
+}
+
+/// `alice`, with one planted deed, bound to a fresh actor key in `keys`.
+fn bound(keys: &mut Keyring) -> (TokenLedger, String) {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    let deed =
+        DeedEvent::new(ledger.last_hash(), "alice".into(), vec![], "tree_planting".into(), vec![], json!({}), vec![], false);
+    ledger.append(deed).unwrap();
+    let name = keys.generate("actor").unwrap();
+    let key = meta(keys, &name);
+    let proof = keys.sign(&name, &change(&key)).unwrap();
+    bind_actor_key(&mut ledger, "alice", &key, &proof, T).unwrap();
+    (ledger, name)
+}
+
+fn guardians(clock: &Arc<AtomicU64>, n: usize) -> (Keyring, Vec<String>) {
+    let mut keys = keyring(clock);
+    let names = (0..n).map(|_| keys.generate("recovery-guardian").unwrap()).collect();
+    (keys, names)
+}
+
+fn quorum(guardians: &Keyring, signers: &[String], new_key: &KeyMeta) -> RecoveryQuorum {
+    let approvals = signers.iter().map(|s| guardians.sign(s, &change(new_key)).unwrap()).collect();
+    RecoveryQuorum { new_key: new_key.clone(), approvals }
+}
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<RecoveryNotice>>>);
+
+impl RecoveryNotifier for Capture {
+    fn notify(&self, notice: &RecoveryNotice) -> Result<(), String> {
+        self.0.lock().unwrap().push(notice.clone());
+        Ok(())
+    }
+}
+
+/// A recovery of `alice` onto a fresh key, approved by two guardians.
+fn start_recovery(ledger: &mut TokenLedger, clock: &Arc<AtomicU64>, notifier: &Capture) -> (Keyring, String) {
+    let (guardian_keys, names) = guardians(clock, 2);
+    let mut recovered = keyring(clock);
+    let name = recovered.generate("actor").unwrap();
+    let new_key = meta(&recovered, &name);
+    let bundle = guardian_keys.verifying_bundle();
+    recover_account(ledger, "alice", &quorum(&guardian_keys, &names, &new_key), &bundle, T, Some(notifier)).unwrap();
+    (recovered, name)
+}
+
+#[test]
+fn rotation_hands_auth_to_the_new_key() {
+    let clock = Arc::new(AtomicU64::new(T as u64));
+    let mut keys = keyring(&clock);
+    let (mut ledger, first) = bound(&mut keys);
+    let old = meta(&keys, &first);
+    assert!(authenticate(&ledger, "alice", b"hello", &keys.sign(&first, b"hello").unwrap()).is_ok());
+    assert!(matches!(
+        bind_actor_key(&mut ledger, "alice", &old, &keys.sign(&first, &change(&old)).unwrap(), T),
+        Err(IdentityError::AlreadyBound(_))
+    ));
+
+    let second = keys.generate("actor").unwrap();
+    let new_key = meta(&keys, &second);
+    // The new key cannot vouch for itself.
+    let by_new = keys.sign(&second, &change(&new_key)).unwrap();
+    assert!(matches!(
+        rotate_actor_key(&mut ledger, "alice", &by_new, &new_key, T),
+        Err(IdentityError::NotCurrentKey { key, .. }) if key == second
+    ));
+    let proof = keys.sign(&first, &change(&new_key)).unwrap();
+    assert!(matches!(rotate_actor_key(&mut ledger, "alice", &proof, &new_key, T + 3_600), Err(IdentityError::Stale { .. })));
+
+    let link = rotate_actor_key(&mut ledger, "alice", &proof, &new_key, T + 60).unwrap();
+    assert_eq!(link.authority, KeyAuthority::PreviousKey { key: old.public_key.clone() });
+    assert_eq!(accepted_key(&ledger, "alice"), Some(new_key.clone()));
+    assert!(authenticate(&ledger, "alice", b"hello", &keys.sign(&second, b"hello").unwrap()).is_ok());
+
+    // History stays with the same actor; the rotation is the ledger's deed.
+    assert_eq!(ledger.deeds_for_actor("alice").count(), 1);
+    let rotation = ledger.deeds().last().unwrap();
+    assert_eq!((rotation.deed_type.as_str(), rotation.target_ids.as_slice()), (ACTOR_KEY_ROTATED, &["alice".to_string()][..]));
+    // Rotating back to a retired key is refused.
+    let back = keys.sign(&second, &change(&old)).unwrap();
+    assert!(matches!(rotate_actor_key(&mut ledger, "alice", &back, &old, T + 60), Err(IdentityError::KeyReused(_))));
+}
+
+#[test]
+fn the_old_key_is_rejected_after_rotation() {
+    let clock = Arc::new(AtomicU64::new(T as u64));
+    let mut keys = keyring(&clock);
+    let (mut ledger, first) = bound(&mut keys);
+    let second = keys.generate("actor").unwrap();
+    let new_key = meta(&keys, &second);
+    rotate_actor_key(&mut ledger, "alice", &keys.sign(&first, &change(&new_key)).unwrap(), &new_key, T).unwrap();
+
+    let stale = keys.sign(&first, b"hello").unwrap();
+    assert!(matches!(
+        authenticate(&ledger, "alice", b"hello", &stale),
+        Err(IdentityError::NotCurrentKey { key, .. }) if key == first
+    ));
+    // Nor can the old key rotate again.
+    let third = keys.generate("actor").unwrap();
+    let third_key = meta(&keys, &third);
+    let by_old = keys.sign(&first, &change(&third_key)).unwrap();
+    assert!(matches!(rotate_actor_key(&mut ledger, "alice", &by_old, &third_key, T), Err(IdentityError::NotCurrentKey { .. })));
+
+    // A same-named key from another keyring fails on the signature itself.
+    let mut impostor = keyring(&clock);
+    impostor.generate("actor").unwrap();
+    let forged = impostor.generate("actor").unwrap();
+    assert_eq!(forged, second);
+    assert!(matches!(
+        authenticate(&ledger, "alice", b"hello", &impostor.sign(&forged, b"hello").unwrap()),
+        Err(IdentityError::Signature(_))
+    ));
+    assert!(matches!(authenticate(&ledger, "bob", b"hello", &stale), Err(IdentityError::Unbound(_))));
+}
+
+#[test]
+fn recovery_needs_a_quorum_and_waits_out_the_period() {
+    let clock = Arc::new(AtomicU64::new(T as u64));
+    let mut keys = keyring(&clock);
+    let (mut ledger, lost) = bound(&mut keys);
+    let (guardian_keys, names) = guardians(&clock, 3);
+    let bundle = guardian_keys.verifying_bundle();
+    let mut recovered = keyring(&clock);
+    let name = recovered.generate("actor").unwrap();
+    let new_key = meta(&recovered, &name);
+    let notices = Capture::default();
+
+    let one = quorum(&guardian_keys, &names[..1], &new_key);
+    assert!(matches!(
+        recover_account(&mut ledger, "alice", &one, &bundle, T, Some(&notices)),
+        Err(IdentityError::NotEnoughApprovals { got: 1, need: 2 })
+    ));
+    let doubled = quorum(&guardian_keys, &[names[0].clone(), names[0].clone()], &new_key);
+    assert!(matches!(
+        recover_account(&mut ledger, "alice", &doubled, &bundle, T, Some(&notices)),
+        Err(IdentityError::NotEnoughApprovals { got: 1, .. })
+    ));
+    // The actor's own key is no guardian.
+    let mut with_self = quorum(&guardian_keys, &names[..1], &new_key);
+    with_self.approvals.push(keys.sign(&lost, &change(&new_key)).unwrap());
+    let mut everyone = bundle.clone();
+    everyone.keys.extend(keys.keys().cloned());
+    assert!(matches!(
+        recover_account(&mut ledger, "alice", &with_self, &everyone, T, Some(&notices)),
+        Err(IdentityError::NotGuardian(k)) if k == lost
+    ));
+    assert!(notices.0.lock().unwrap().is_empty());
+
+    let pending =
+        recover_account(&mut ledger, "alice", &quorum(&guardian_keys, &names[1..], &new_key), &bundle, T, Some(&notices))
+            .unwrap();
+    assert_eq!((pending.unlock_at, pending.signers.clone()), (T + WAIT, names[1..].to_vec()));
+    assert_eq!(pending_recovery(&ledger, "alice"), Some(pending.clone()));
+    let sent = notices.0.lock().unwrap().clone();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        (sent[0].recovery_event_id.as_str(), sent[0].new_key.as_str()),
+        (pending.recovery_event_id.as_str(), new_key.public_key.as_str())
+    );
+
+    // Until the wait is over the lost key still authenticates and nothing
+    // rotates; a second recovery cannot pile on.
+    assert!(authenticate(&ledger, "alice", b"hi", &keys.sign(&lost, b"hi").unwrap()).is_ok());
+    assert!(matches!(
+        complete_recovery(&mut ledger, "alice", T + WAIT - 1),
+        Err(IdentityError::RecoveryWaiting { unlock_at, .. }) if unlock_at == T + WAIT
+    ));
+    assert!(matches!(
+        recover_account(&mut ledger, "alice", &quorum(&guardian_keys, &names[..2], &new_key), &bundle, T, None),
+        Err(IdentityError::RecoveryPending { .. })
+    ));
+
+    let link = complete_recovery(&mut ledger, "alice", T + WAIT).unwrap();
+    assert_eq!(
+        link.authority,
+        KeyAuthority::RecoveryQuorum { recovery_event_id: pending.recovery_event_id, signers: names[1..].to_vec() }
+    );
+    assert!(pending_recovery(&ledger, "alice").is_none());
+    clock.store((T + WAIT) as u64, Ordering::SeqCst);
+    assert!(authenticate(&ledger, "alice", b"hi", &recovered.sign(&name, b"hi").unwrap()).is_ok());
+    assert!(matches!(authenticate(&ledger, "alice", b"hi", &keys.sign(&lost, b"hi").unwrap()), Err(IdentityError::Signature(_))));
+    assert!(matches!(complete_recovery(&mut ledger, "alice", T + WAIT), Err(IdentityError::NoRecovery(_))));
+}
+
+#[test]
+fn a_recovering_account_receives_no_mints() {
+    let clock = Arc::new(AtomicU64::new(T as u64));
+    let mut keys = keyring(&clock);
+    let (mut ledger, current) = bound(&mut keys);
+    ledger.open_account("bob", "bob");
+    ledger.mint_reward("alice", Token::Church, 100).unwrap();
+    let notices = Capture::default();
+    let (_, _) = start_recovery(&mut ledger, &clock, &notices);
+    let recovery = ledger.account_recovery("alice").unwrap().to_string();
+
+    let frozen = TokenLedgerError::AccountRecovering { account: "alice".into(), recovery: recovery.clone() };
+    assert_eq!(ledger.mint_reward("alice", Token::Church, 100), Err(frozen.clone()));
+    assert_eq!(ledger.reward_for("alice", Token::Pwr, 5, None), Err(frozen.clone()));
+    assert_eq!(ledger.pool_outflow("alice", 1, "grant"), Err(frozen));
+    // Other accounts mint, and alice's balance can still shrink.
+    assert_eq!(ledger.mint_reward("bob", Token::Church, 100).unwrap(), 100);
+    assert_eq!(ledger.burn("alice", Token::Church, 10).unwrap(), 10);
+    assert_eq!(ledger.account("alice").unwrap().balance(Token::Church), 90);
+
+    // The current key cancels the recovery and mints resume.
+    let pending = pending_recovery(&ledger, "alice").unwrap();
+    let wrong = keys.sign(&current, b"cancel").unwrap();
+    assert!(matches!(cancel_recovery(&mut ledger, "alice", &wrong, T), Err(IdentityError::Signature(_))));
+    cancel_recovery(&mut ledger, "alice", &keys.sign(&current, &change(&pending.new_key)).unwrap(), T).unwrap();
+    assert!(ledger.account_recovery("alice").is_none());
+    assert_eq!(ledger.mint_reward("alice", Token::Church, 100).unwrap(), 100);
+    assert!(matches!(complete_recovery(&mut ledger, "alice", T + WAIT), Err(IdentityError::NoRecovery(_))));
+    assert_eq!(accepted_key(&ledger, "alice"), Some(meta(&keys, &current)));
+}
+
+#[test]
+fn replay_rebuilds_the_key_lineage() {
+    let clock = Arc::new(AtomicU64::new(T as u64));
+    let mut keys = keyring(&clock);
+    let (mut ledger, first) = bound(&mut keys);
+    let second = keys.generate("actor").unwrap();
+    let second_key = meta(&keys, &second);
+    rotate_actor_key(&mut ledger, "alice", &keys.sign(&first, &change(&second_key)).unwrap(), &second_key, T).unwrap();
+    let notices = Capture::default();
+    let (recovered, name) = start_recovery(&mut ledger, &clock, &notices);
+
+    // Mid-recovery, a replay freezes the same account.
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.account_recovery("alice"), ledger.account_recovery("alice"));
+    assert_eq!(pending_recovery(&replayed, "alice"), pending_recovery(&ledger, "alice"));
+    assert!(matches!(replayed.clone().mint_reward("alice", Token::Church, 1), Err(TokenLedgerError::AccountRecovering { .. })));
+
+    complete_recovery(&mut ledger, "alice", T + WAIT).unwrap();
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    let lineage = key_lineage(&replayed, "alice");
+    assert_eq!(lineage, key_lineage(&ledger, "alice"));
+    let names: Vec<&str> = lineage.iter().map(|l| l.key.public_key.as_str()).collect();
+    assert_eq!(names, [meta(&keys, &first).public_key, second_key.public_key, meta(&recovered, &name).public_key]);
+    assert!(matches!(lineage[0].authority, KeyAuthority::SelfSigned));
+    assert!(matches!(lineage[2].authority, KeyAuthority::RecoveryQuorum { .. }));
+    assert_eq!(accepted_key(&replayed, "alice"), accepted_key(&ledger, "alice"));
+    assert!(replayed.account_recovery("alice").is_none());
+    let mut replayed = replayed;
+    replayed.open_account("alice", "alice");
+    assert_eq!(replayed.mint_reward("alice", Token::Church, 10), Ok(10));
+
+    // Key deeds are the ledger's to write.
+    let forged = DeedEvent::new(
+        ledger.last_hash(),
+        "alice".into(),
+        vec!["alice".into()],
+        ACTOR_KEY_ROTATED.into(),
+        vec![],
+        json!({}),
+        vec![],
+        false,
+    );
+    assert_eq!(ledger.append(forged).unwrap_err(), TokenLedgerError::ReservedDeedType(ACTOR_KEY_ROTATED.into()));
+}
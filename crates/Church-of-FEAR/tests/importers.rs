@@ -0,0 +1,206 @@
+#![cfg(feature = "importers")]
+
+use std::path::PathBuf;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::importers::carbon_registry::CarbonRegistryImporter;
+use church_of_fear::importers::csv::{ColumnMapping, CsvImporter};
+use church_of_fear::importers::{
+    import, verify_import, ActorMap, ImportError, ImportPolicy, ImportReport, RowOutcome, IMPORT_VERIFIED,
+};
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use serde_json::json;
+
+const MAPPING: &str = r#"{
+    "source": "shelterdb",
+    "external_id": "shift_id",
+    "actor": "volunteer_id",
+    "deed_type": "homelessness_relief",
+    "timestamp": { "column": "date", "format": "%Y-%m-%d" },
+    "fields": { "location": "site" },
+    "quantities": { "hours": "hours", "meals_served": "meals" },
+    "evidence_uri": "photo_url",
+    "tags": ["civic-duty"]
+}"#;
+
+const SHIFTS: &str = "\
+shift_id,volunteer_id,date,site,hours,meals,photo_url
+s-1,v-7,2024-03-01,Phoenix Day Shelter,4,60,https://photos.example/s-1
+s-2,v-8,2024-03-02,Phoenix Day Shelter,3.5,40,
+s-3,v-7,2024-03-03,Phoenix Day Shelter,lots,10,
+s-1,v-7,2024-03-01,Phoenix Day Shelter,4,60,https://photos.example/s-1
+";
+
+const RETIREMENTS: &str = r#"{
+    "registry": "verra",
+    "records": [
+        {
+            "serial": "VCS-1234-2024-0001",
+            "holder_id": "acct-77",
+            "project_location": "Sonoran Desert, AZ",
+            "tonnes_co2e": 1.5,
+            "retired_at": "2024-03-01T00:00:00Z",
+            "evidence_uri": "https://registry.example/retirements/VCS-1234-2024-0001"
+        },
+        { "serial": "VCS-1234-2024-0002", "holder_id": "acct-77" }
+    ]
+}"#;
+
+struct Dir(PathBuf);
+
+impl Dir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cof-import-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn file(&self, name: &str, body: &str) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::write(&path, body).unwrap();
+        path
+    }
+}
+
+impl Drop for Dir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn actors(dir: &Dir, auto_provision: bool) -> ActorMap {
+    let map = json!({ "actors": { "v-7": "alice", "v-8": "bob", "acct-77": "carol" }, "auto_provision": auto_provision });
+    ActorMap::load(&dir.file("actors.json", &map.to_string())).unwrap()
+}
+
+fn import_shifts(ledger: &mut TokenLedger, dir: &Dir, dry_run: bool) -> ImportReport {
+    let importer = CsvImporter::new(ColumnMapping::load(&dir.file("mapping.json", MAPPING)).unwrap());
+    let batch = importer.read(&dir.file("shifts.csv", SHIFTS)).unwrap();
+    import(ledger, &batch, &actors(dir, false), &ImportPolicy::default(), dry_run)
+}
+
+fn outcomes(report: &ImportReport) -> Vec<&str> {
+    report
+        .rows
+        .iter()
+        .map(|r| match &r.outcome {
+            RowOutcome::Imported { .. } => "imported",
+            RowOutcome::Duplicate => "duplicate",
+            RowOutcome::Rejected { .. } => "rejected",
+        })
+        .collect()
+}
+
+#[test]
+fn mapping_file_drives_the_csv_import() {
+    let dir = Dir::new("mapping");
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let report = import_shifts(&mut ledger, &dir, false);
+    assert_eq!(outcomes(&report), ["imported", "imported", "rejected", "duplicate"]);
+    assert_eq!((report.imported, report.duplicates, report.rejected), (2, 1, 1));
+    let RowOutcome::Rejected { reason } = &report.rows[2].outcome else { unreachable!() };
+    assert!(reason.starts_with("hours:"), "{reason}");
+
+    let deed = ledger.deeds().first().unwrap();
+    assert_eq!(deed.actor_id, "alice");
+    assert_eq!(deed.deed_type, "homelessness_relief");
+    assert_eq!(deed.timestamp, 1_709_251_200);
+    assert_eq!(deed.tags, ["civic-duty"]);
+    assert_eq!(deed.context_json["import_source"], "shelterdb");
+    assert_eq!(deed.context_json["external_id"], "s-1");
+    assert_eq!(deed.context_json["hours"], 4);
+    assert_eq!(deed.context_json["meals_served"], 60);
+    assert_eq!(deed.context_json["evidence_uri"], "https://photos.example/s-1");
+    assert_eq!(ledger.deeds()[1].context_json["hours"], 3.5);
+    assert!(ledger.deeds()[1].context_json.get("evidence_uri").is_none());
+    assert!(TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).is_ok());
+
+    let header_only = "shift_id,volunteer_id,date\n";
+    let importer = CsvImporter::new(serde_json::from_str(MAPPING).unwrap());
+    assert!(matches!(importer.parse(header_only), Err(ImportError::MissingColumn(c)) if c == "site"));
+}
+
+#[test]
+fn reimporting_a_file_changes_nothing() {
+    let dir = Dir::new("idempotent");
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    import_shifts(&mut ledger, &dir, false);
+    let deeds = ledger.deeds().len();
+
+    let again = import_shifts(&mut ledger, &dir, false);
+    assert_eq!(outcomes(&again), ["duplicate", "duplicate", "rejected", "duplicate"]);
+    assert_eq!(ledger.deeds().len(), deeds);
+
+    // Dedup is by source and external id, and survives a restart.
+    let mut replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(import_shifts(&mut replayed, &dir, false).imported, 0);
+}
+
+#[test]
+fn dry_run_previews_without_touching_the_ledger() {
+    let dir = Dir::new("dry-run");
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let batch = CarbonRegistryImporter::default().read(&dir.file("export.json", RETIREMENTS)).unwrap();
+    let policy = ImportPolicy::default();
+
+    let preview = import(&mut ledger, &batch, &actors(&dir, false), &policy, true);
+    assert!(preview.dry_run);
+    assert!(ledger.deeds().is_empty());
+    assert!(ledger.account("carol").is_none());
+    let RowOutcome::Imported { event_id, full_reward, minted, .. } = &preview.rows[0].outcome else { unreachable!() };
+    assert_eq!((event_id, *full_reward, *minted), (&None, 15, 7));
+    assert_eq!(preview.rejected, 1);
+
+    let applied = import(&mut ledger, &batch, &actors(&dir, false), &policy, false);
+    assert_eq!((applied.imported, applied.rejected, applied.minted), (preview.imported, preview.rejected, preview.minted));
+}
+
+#[test]
+fn unmapped_actors_are_refused_unless_auto_provisioned() {
+    let dir = Dir::new("unmapped");
+    let importer = CsvImporter::new(ColumnMapping::load(&dir.file("mapping.json", MAPPING)).unwrap());
+    let batch = importer.parse(&SHIFTS.replace("v-8", "v-99")).unwrap();
+    let policy = ImportPolicy::default();
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let report = import(&mut ledger, &batch, &actors(&dir, false), &policy, false);
+    assert_eq!(report.rows[1].outcome, RowOutcome::Rejected { reason: "actor \"v-99\" is not mapped".into() });
+    assert!(ledger.deeds().iter().all(|d| d.actor_id == "alice"));
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let report = import(&mut ledger, &batch, &actors(&dir, true), &policy, false);
+    assert!(matches!(&report.rows[1].outcome, RowOutcome::Imported { actor_id, .. } if actor_id == "shelterdb:v-99"));
+    assert!(ledger.account("shelterdb:v-99").is_some());
+}
+
+#[test]
+fn unverified_imports_mint_at_a_discount_until_verified() {
+    let dir = Dir::new("discount");
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let batch = CarbonRegistryImporter::default().parse(RETIREMENTS).unwrap();
+    assert_eq!(batch.source, "carbon_registry:verra");
+    let policy = ImportPolicy { unverified_reward_factor: 0.2, ..ImportPolicy::default() };
+    let report = import(&mut ledger, &batch, &actors(&dir, false), &policy, false);
+    let RowOutcome::Imported { event_id: Some(event_id), full_reward: 15, minted: 3, .. } = &report.rows[0].outcome else {
+        panic!("{:?}", report.rows[0]);
+    };
+    let deed = ledger.deed(event_id).unwrap();
+    assert_eq!(deed.context_json["co2_kg"], 1500.0);
+    assert_eq!(deed.context_json["import_reward"], json!({ "full": 15, "discounted": 3 }));
+    assert_eq!(ledger.account("carol").unwrap().balance(Token::Church), 3);
+
+    assert!(matches!(
+        verify_import(&mut ledger, event_id, "dave", "Member"),
+        Err(ImportError::Ledger(TokenLedgerError::RoleNotAllowed(_)))
+    ));
+    assert_eq!(verify_import(&mut ledger, event_id, "dave", "Regulator").unwrap(), 12);
+    assert_eq!(ledger.account("carol").unwrap().balance(Token::Church), 15);
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == IMPORT_VERIFIED).count(), 1);
+    assert!(matches!(verify_import(&mut ledger, event_id, "dave", "Regulator"), Err(ImportError::AlreadyVerified(_))));
+
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.account("carol").unwrap().balance(Token::Church), 15);
+    let other = ledger.deeds().iter().find(|d| d.deed_type == "reward_credit").unwrap().event_id.clone();
+    assert!(matches!(verify_import(&mut ledger, &other, "dave", "Regulator"), Err(ImportError::NotImported(_))));
+}
@@ -0,0 +1,229 @@
+#![cfg(feature = "tui")]
+
+use std::io;
+use std::path::PathBuf;
+
+use church_of_fear::inspect::{
+    run, AccountView, App, ChainHealth, DetailView, Filter, FilteredView, LedgerIndex, LinkFault, Pane, Standing,
+    Verification,
+};
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent, ExecutionDomain};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000; // 2023-11-14 22:13 UTC
+const DAY: i64 = 86_400;
+
+struct Spec {
+    actor: &'static str,
+    deed_type: &'static str,
+    tags: &'static [&'static str],
+    at: i64,
+    flagged: bool,
+    context: serde_json::Value,
+}
+
+fn spec(actor: &'static str, deed_type: &'static str, tags: &'static [&'static str], at: i64) -> Spec {
+    Spec { actor, deed_type, tags, at, flagged: false, context: json!({}) }
+}
+
+fn chain(specs: Vec<Spec>) -> Vec<DeedEvent> {
+    let mut prev = "0".repeat(64);
+    let mut out = Vec::new();
+    for (i, s) in specs.into_iter().enumerate() {
+        let mut deed = DeedEvent {
+            event_id: format!("ev-{}", i),
+            timestamp: s.at,
+            prev_hash: prev.clone(),
+            self_hash: String::new(),
+            actor_id: s.actor.into(),
+            target_ids: vec![],
+            deed_type: s.deed_type.into(),
+            tags: s.tags.iter().map(|t| t.to_string()).collect(),
+            context_json: s.context,
+            ethics_flags: if s.flagged { vec!["coercion".into()] } else { vec![] },
+            life_harm_flag: false,
+            domain: ExecutionDomain::Live,
+            ext: Default::default(),
+        };
+        deed.self_hash = hash_deed(&deed);
+        prev = deed.self_hash.clone();
+        out.push(deed);
+    }
+    out
+}
+
+fn sample() -> Vec<DeedEvent> {
+    let mut flagged = spec("bob", "mutual_aid", &["meal"], T0 + DAY + 60);
+    flagged.flagged = true;
+    let mut rewarded = spec("ledger", "reward", &[], T0 + 2 * DAY);
+    rewarded.context = json!({ "movements": [{ "account_id": "alice", "token": "church", "delta": 40 }] });
+    chain(vec![
+        spec("alice", "ecological_sustainability", &["tree_planting"], T0),
+        spec("alice", "ecological_sustainability", &["tree_planting", "river"], T0 + DAY),
+        flagged,
+        spec("alice", "homelessness_relief", &["shelter"], T0 + 2 * DAY),
+        rewarded,
+        spec("alice", "ecological_sustainability", &["compost"], T0 + 5 * DAY),
+    ])
+}
+
+struct LedgerFile(PathBuf);
+
+impl LedgerFile {
+    fn write(name: &str, deeds: &[DeedEvent]) -> Self {
+        let dir = std::env::temp_dir().join(format!("cof-inspect-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ledger.jsonl");
+        let body: String = deeds.iter().map(|d| serde_json::to_string(d).unwrap() + "\n").collect();
+        std::fs::write(&path, body).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for LedgerFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.0.parent().unwrap());
+    }
+}
+
+#[test]
+fn filter_matches_fields_and_renders_rows() {
+    let file = LedgerFile::write("filter", &sample());
+    let index = LedgerIndex::open(&file.0).unwrap();
+
+    let all = FilteredView::new(&index, &Filter::default());
+    assert_eq!(all.len(), 6);
+
+    let view = FilteredView::new(&index, &Filter::parse("actor:ALI tag:tree"));
+    assert_eq!(
+        view.lines(&index, 0, 10),
+        [
+            "      0  2023-11-14 22:13  ecological_sustainability alice #tree_planting",
+            "      1  2023-11-15 22:13  ecological_sustainability alice #tree_planting #river",
+        ]
+    );
+
+    // Bare words match actor, type or tag.
+    let bare = FilteredView::new(&index, &Filter::parse("meal"));
+    assert_eq!(bare.position(0), Some(2));
+    assert_eq!(FilteredView::new(&index, &Filter::parse("type:relief")).len(), 1);
+    assert!(FilteredView::new(&index, &Filter::parse("actor:carol")).is_empty());
+
+    // Windowing only formats the requested rows.
+    assert_eq!(all.lines(&index, 4, 10).len(), 2);
+}
+
+#[test]
+fn detail_shows_canonical_json_and_link_status() {
+    let mut deeds = sample();
+    let file = LedgerFile::write("detail-ok", &deeds);
+    let index = LedgerIndex::open(&file.0).unwrap();
+    let detail = DetailView::load(&index, 2).unwrap();
+    let lines = detail.lines();
+    assert_eq!(lines[0], "deed #2  ev-2");
+    assert_eq!(lines[1], "link: ok");
+    assert_eq!(lines[2..].join("\n"), serde_json::to_string_pretty(&deeds[2]).unwrap());
+
+    // Edit a deed's contents without rehashing it.
+    deeds[3].tags.push("forged".into());
+    let file = LedgerFile::write("detail-tampered", &deeds);
+    let index = LedgerIndex::open(&file.0).unwrap();
+    assert_eq!(DetailView::load(&index, 3).unwrap().lines()[1], "link: BROKEN: self_hash does not match contents");
+    assert_eq!(DetailView::load(&index, 4).unwrap().link, None);
+    assert_eq!(index.verification(), &Verification::Broken { position: 3, fault: LinkFault::SelfHash });
+}
+
+#[test]
+fn health_header_reports_tip_and_open_segment() {
+    let deeds = sample();
+    let file = LedgerFile::write("health", &deeds);
+    let health = ChainHealth::of(&LedgerIndex::open(&file.0).unwrap());
+    assert_eq!(health.height, 6);
+    assert_eq!(
+        health.line(),
+        format!("height 6 | tip {} | verify ok | unsealed 6", &deeds[5].self_hash[..12])
+    );
+
+    let segments = json!([{ "index": 0, "first": 0, "last": 3, "tip_hash": deeds[3].self_hash }]);
+    std::fs::write(file.0.with_extension("jsonl.segments.json"), segments.to_string()).unwrap();
+    assert_eq!(ChainHealth::of(&LedgerIndex::open(&file.0).unwrap()).unsealed, 2);
+
+    let mut relinked = deeds.clone();
+    relinked[1].prev_hash = "f".repeat(64);
+    let broken = LedgerFile::write("health-broken", &relinked);
+    let line = ChainHealth::of(&LedgerIndex::open(&broken.0).unwrap()).line();
+    assert!(line.contains("verify BROKEN at #1 (PrevHash)"), "{line}");
+}
+
+#[test]
+fn account_view_shows_balances_standing_and_streaks() {
+    let file = LedgerFile::write("account", &sample());
+    let index = LedgerIndex::open(&file.0).unwrap();
+
+    let alice = AccountView::load(&index, "alice");
+    assert_eq!(
+        alice.lines(),
+        [
+            "actor alice",
+            "standing: good",
+            "church 40  pwr 0  fear 0  tech 0",
+            "deeds 4 (flagged 0, life-harm 0)",
+            "streak 1 days (longest 3)",
+            "active 2023-11-14 22:13 .. 2023-11-19 22:13",
+        ]
+    );
+    assert_eq!(AccountView::load(&index, "bob").standing, Standing::Flagged);
+    assert_eq!(AccountView::load(&index, "nobody").activity.deeds, 0);
+}
+
+fn key(code: KeyCode) -> io::Result<Event> {
+    Ok(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+}
+
+fn typed(text: &str) -> Vec<io::Result<Event>> {
+    text.chars().map(|c| key(KeyCode::Char(c))).collect()
+}
+
+fn screen(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    let width = buffer.area.width as usize;
+    buffer.content.chunks(width).map(|row| row.iter().map(|c| c.symbol()).collect::<String>() + "\n").collect()
+}
+
+#[test]
+fn tui_event_loop_follows_scripted_input() {
+    let file = LedgerFile::write("tui", &sample());
+    let before = std::fs::read(&file.0).unwrap();
+    let mut perms = std::fs::metadata(&file.0).unwrap().permissions();
+    perms.set_readonly(true);
+    std::fs::set_permissions(&file.0, perms).unwrap();
+
+    let mut app = App::new(LedgerIndex::open(&file.0).unwrap());
+    let mut terminal = Terminal::new(TestBackend::new(140, 24)).unwrap();
+
+    let mut script = typed("actor:alicx");
+    script.extend([key(KeyCode::Backspace), key(KeyCode::Char('e')), key(KeyCode::End), key(KeyCode::Up)]);
+    run(&mut terminal, &mut app, script).unwrap();
+    assert_eq!(app.query(), "actor:alice");
+    assert_eq!(app.matches(), 4);
+    assert_eq!(app.selected_position(), Some(3));
+    let shown = screen(&terminal);
+    assert!(shown.contains("height 6 | tip"), "{shown}");
+    assert!(shown.contains("events 4/6"), "{shown}");
+    assert!(shown.contains("deed #3  ev-3"), "{shown}");
+    assert!(shown.contains("filter> actor:alice"), "{shown}");
+
+    run(&mut terminal, &mut app, [key(KeyCode::Tab)]).unwrap();
+    assert_eq!(app.pane(), Pane::Account);
+    assert!(screen(&terminal).contains("church 40  pwr 0"));
+
+    // Esc clears the filter, a second Esc quits.
+    run(&mut terminal, &mut app, [key(KeyCode::Esc), key(KeyCode::Esc), key(KeyCode::Down)]).unwrap();
+    assert!(app.should_quit());
+    assert_eq!(app.matches(), 6);
+
+    assert_eq!(std::fs::read(&file.0).unwrap(), before);
+}
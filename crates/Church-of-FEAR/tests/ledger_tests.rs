@@ -1,29 +1,69 @@
-use church_of_fear::ledger::deed_event::{DeedEvent, validate_chain};
+use church_of_fear::ledger::deed_event::{hash_deed, validate_chain, ChainError, ChainFault, DeedEvent};
 
-#[test]
-fn chain_integrity_holds() {
-    let genesis = DeedEvent::genesis();
-    let d1 = DeedEvent::new(
-        genesis.self_hash.clone(),
-        "a1".into(),
+fn deed(prev_hash: String, actor: &str) -> DeedEvent {
+    DeedEvent::new(
+        prev_hash,
+        actor.into(),
         vec!["t1".into()],
         "ecological_sustainability".into(),
         vec![],
         serde_json::json!({}),
         vec![],
         false,
-    );
-    let d2 = DeedEvent::new(
-        d1.self_hash.clone(),
-        "a2".into(),
-        vec!["t2".into()],
-        "ecological_sustainability".into(),
-        vec![],
-        serde_json::json!({}),
-        vec![],
-        false,
-    );
+    )
+}
+
+fn chain(len: usize) -> Vec<DeedEvent> {
+    let mut chain = vec![DeedEvent::genesis()];
+    while chain.len() < len {
+        let prev_hash = chain.last().unwrap().self_hash.clone();
+        chain.push(deed(prev_hash, &format!("a{}", chain.len())));
+    }
+    chain
+}
+
+#[test]
+fn chain_integrity_holds() {
+    let genesis = DeedEvent::genesis();
+    let d1 = deed(genesis.self_hash.clone(), "a1");
+    let d2 = deed(d1.self_hash.clone(), "a2");
 
     let chain = vec![genesis, d1, d2];
-    assert!(validate_chain(&chain));
+    assert_eq!(validate_chain(&chain), Ok(()));
+    assert_eq!(validate_chain(&[]), Ok(()));
+}
+
+#[test]
+fn tampered_context_is_caught_mid_chain() {
+    let mut chain = chain(8);
+    chain[5].context_json = serde_json::json!({ "trees": 1000 });
+    assert_eq!(validate_chain(&chain), Err(ChainError { index: 5, reason: ChainFault::SelfHashMismatch }));
+
+    // Rehashing the tampered deed moves the break to its successor.
+    chain[5].self_hash = String::new();
+    chain[5].self_hash = hash_deed(&chain[5]);
+    assert_eq!(validate_chain(&chain), Err(ChainError { index: 6, reason: ChainFault::PrevHashMismatch }));
+
+    // Only the earliest of several breaks is reported.
+    chain[2].actor_id = "mallory".into();
+    assert_eq!(validate_chain(&chain), Err(ChainError { index: 2, reason: ChainFault::SelfHashMismatch }));
+}
+
+#[test]
+fn swapped_neighbours_break_the_link() {
+    let mut chain = chain(6);
+    chain.swap(3, 4);
+    assert_eq!(validate_chain(&chain), Err(ChainError { index: 3, reason: ChainFault::PrevHashMismatch }));
+}
+
+#[test]
+fn a_single_deed_must_start_at_genesis() {
+    assert_eq!(validate_chain(&chain(1)), Ok(()));
+
+    let orphan = deed("f".repeat(64), "a1");
+    assert_eq!(validate_chain(&[orphan]), Err(ChainError { index: 0, reason: ChainFault::BadGenesis }));
+
+    let mut tampered = DeedEvent::genesis();
+    tampered.actor_id = "mallory".into();
+    assert_eq!(validate_chain(&[tampered]), Err(ChainError { index: 0, reason: ChainFault::SelfHashMismatch }));
 }
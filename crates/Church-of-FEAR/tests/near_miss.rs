@@ -0,0 +1,209 @@
+#![cfg(feature = "core")]
+
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::near_miss::{
+    clusters, confirm_near_miss, errority_log, observe_guard_rejection, report_near_miss, send_digest, wise_credit,
+    CorroboratedBy, ErrorityTally, NearMissDigest, NearMissError, NearMissNotifier, Severity, GUARD_DATA_MINIMIZATION,
+    GUARD_DEED_VALIDATION, NEAR_MISS_CORROBORATED,
+};
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+
+fn report(ledger: &mut TokenLedger, reporter: &str, description: &str, category: &str, related: &[&str], now: i64) -> String {
+    let related: Vec<String> = related.iter().map(|s| s.to_string()).collect();
+    report_near_miss(ledger, reporter, description, category, Severity::Medium, &related, now).unwrap().event_id
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<NearMissDigest>>>);
+
+impl NearMissNotifier for Recorder {
+    fn notify(&self, digest: &NearMissDigest) -> Result<(), String> {
+        self.0.lock().unwrap().push(digest.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn reports_earn_credit_only_once_corroborated() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let id = report(&mut ledger, "alice", "Sensor log kept raw EEG for a minute", "neuro_intake", &[], T0);
+    assert_eq!(wise_credit(&ledger, "alice"), 0);
+
+    assert_eq!(confirm_near_miss(&mut ledger, &id, "mallory", "Member", T0), Err(NearMissError::RoleNotAllowed("Member".into())));
+    let confirmed = confirm_near_miss(&mut ledger, &id, "dave", "Auditor", T0 + DAY).unwrap();
+    assert_eq!((confirmed.by, confirmed.credited), (CorroboratedBy::Auditor, 5));
+    assert_eq!(wise_credit(&ledger, "alice"), 5);
+    assert_eq!(confirm_near_miss(&mut ledger, &id, "dave", "Auditor", T0), Err(NearMissError::AlreadyCorroborated(id.clone())));
+    assert!(matches!(confirm_near_miss(&mut ledger, "nope", "dave", "Auditor", T0), Err(NearMissError::UnknownCluster(_))));
+
+    // Credit is capped per reporter per window, then earned again.
+    for n in 0..6 {
+        let id = report(&mut ledger, "alice", &format!("issue {}", n), "neuro_intake", &[], T0 + DAY);
+        confirm_near_miss(&mut ledger, &id, "dave", "Regulator", T0 + 2 * DAY).unwrap();
+    }
+    assert_eq!(wise_credit(&ledger, "alice"), 25);
+    let later = report(&mut ledger, "alice", "issue late", "neuro_intake", &[], T0 + 40 * DAY);
+    assert_eq!(confirm_near_miss(&mut ledger, &later, "dave", "Auditor", T0 + 40 * DAY).unwrap().credited, 5);
+
+    // Corroboration is the ledger's to write, and survives replay.
+    let forged = DeedEvent::new(
+        ledger.last_hash(),
+        "alice".into(),
+        vec![],
+        NEAR_MISS_CORROBORATED.into(),
+        vec![],
+        json!({}),
+        vec![],
+        false,
+    );
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(wise_credit(&replayed, "alice"), 30);
+}
+
+#[test]
+fn guard_rejections_corroborate_matching_reports() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let stale = report(&mut ledger, "bob", "Minimizer let a waveform through", GUARD_DATA_MINIMIZATION, &[], T0 - 8 * DAY);
+    let fresh = report(&mut ledger, "carol", "Raw samples slipped past the scrubber", GUARD_DATA_MINIMIZATION, &[], T0 - DAY);
+    let other = report(&mut ledger, "erin", "Odd roh value accepted", GUARD_DEED_VALIDATION, &[], T0 - DAY);
+
+    let corroborated = observe_guard_rejection(&mut ledger, GUARD_DATA_MINIMIZATION, T0).unwrap();
+    assert_eq!(corroborated.len(), 1);
+    assert_eq!((corroborated[0].by, corroborated[0].source.as_str()), (CorroboratedBy::Guard, GUARD_DATA_MINIMIZATION));
+    let status = |id: &str| clusters(&ledger).into_iter().find(|c| c.cluster_id == id).unwrap().corroboration.is_some();
+    assert!(status(&fresh));
+    assert!(!status(&stale), "outside the corroboration window");
+    assert!(!status(&other), "different category");
+    assert_eq!((wise_credit(&ledger, "carol"), wise_credit(&ledger, "bob"), wise_credit(&ledger, "erin")), (5, 0, 0));
+
+    // A second rejection does not credit the same cluster again.
+    assert!(observe_guard_rejection(&mut ledger, GUARD_DATA_MINIMIZATION, T0 + 1).unwrap().is_empty());
+    assert_eq!(
+        errority_log(&ledger).categories[GUARD_DATA_MINIMIZATION],
+        ErrorityTally { reports: 2, clusters: 2, open: 1, corroborated: 1 }
+    );
+}
+
+#[test]
+fn duplicate_reports_cluster_and_credit_the_first_reporter() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let first =
+        report_near_miss(&mut ledger, "bob", "Pool alert fired late", "sponsor_pool", Severity::Low, &["ev-1".into()], T0)
+            .unwrap();
+    assert!(!first.clustered);
+    let by_related = report_near_miss(
+        &mut ledger,
+        "carol",
+        "Low-water alert was delayed",
+        "sponsor_pool",
+        Severity::High,
+        &["ev-1".into()],
+        T0 + 60,
+    )
+    .unwrap();
+    let by_text =
+        report_near_miss(&mut ledger, "dana", "  pool alert  FIRED late", "sponsor_pool", Severity::Low, &[], T0 + 120).unwrap();
+    assert!(by_related.clustered && by_text.clustered);
+    assert_eq!(
+        (by_related.cluster_id.as_str(), by_text.cluster_id.as_str()),
+        (first.cluster_id.as_str(), first.cluster_id.as_str())
+    );
+    let elsewhere =
+        report_near_miss(&mut ledger, "carol", "Pool alert fired late", "tip_gossip", Severity::Low, &[], T0).unwrap();
+    assert!(!elsewhere.clustered);
+
+    let cluster = clusters(&ledger).into_iter().find(|c| c.cluster_id == first.cluster_id).unwrap();
+    assert_eq!((cluster.report_ids.len(), cluster.reporters.len(), cluster.severity), (3, 3, Severity::High));
+
+    // Confirming through any report in the cluster credits only its first reporter.
+    confirm_near_miss(&mut ledger, &by_text.event_id, "dave", "Auditor", T0 + DAY).unwrap();
+    assert_eq!([wise_credit(&ledger, "bob"), wise_credit(&ledger, "carol"), wise_credit(&ledger, "dana")], [5, 0, 0]);
+    assert_eq!(
+        errority_log(&ledger).categories["sponsor_pool"],
+        ErrorityTally { reports: 3, clusters: 1, open: 0, corroborated: 1 }
+    );
+
+    // A report after corroboration opens a fresh cluster.
+    let again =
+        report_near_miss(&mut ledger, "erin", "Pool alert fired late", "sponsor_pool", Severity::Low, &[], T0 + 2 * DAY).unwrap();
+    assert!(!again.clustered);
+}
+
+#[test]
+fn unconfirmed_reports_carry_no_penalty() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.mint_reward("alice", Token::Church, 40).unwrap();
+    let balances = |ledger: &TokenLedger| Token::ALL.map(|t| ledger.account("alice").unwrap().balance(t));
+    let before = balances(&ledger);
+
+    let id = report_near_miss(&mut ledger, "alice", "I nearly logged a raw trace", "neuro_intake", Severity::Critical, &[], T0)
+        .unwrap()
+        .event_id;
+    let deed = ledger.deed(&id).unwrap();
+    assert!(deed.ethics_flags.is_empty() && !deed.life_harm_flag);
+    assert_eq!(deed.tags, ["diagnostic"]);
+    assert_eq!(balances(&ledger), before);
+    assert_eq!(wise_credit(&ledger, "alice"), 0);
+    assert!(ledger.supply_report().reconciles());
+    assert_eq!(
+        report_near_miss(&mut ledger, "alice", " ", "neuro_intake", Severity::Low, &[], T0),
+        Err(NearMissError::EmptyDescription)
+    );
+
+    // Open clusters go out in the digest, most severe first.
+    report(&mut ledger, "bob", "Gossip peer timed out", "tip_gossip", &[], T0);
+    let recorder = Recorder::default();
+    assert_eq!(send_digest(&ledger, &recorder, T0 + DAY), Ok(2));
+    let digests = recorder.0.lock().unwrap().clone();
+    assert_eq!(digests[0].open[0].cluster_id, id);
+    assert_eq!(digests[0].generated_at, T0 + DAY);
+    confirm_near_miss(&mut ledger, &id, "dave", "Auditor", T0 + DAY).unwrap();
+    assert_eq!(send_digest(&ledger, &recorder, T0 + 2 * DAY), Ok(1));
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn near_misses_are_reported_and_corroborated_over_rpc() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let ctx = RpcContext { ledger: Some(ledger.clone()), ..RpcContext::default() };
+    let call = |method: &str, params: Value| -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }).to_string();
+        serde_json::from_str(&dispatch_request_with(&request, &ctx)).unwrap()
+    };
+
+    let params = json!({
+        "reporter": "bob",
+        "description": "High roh slipped through",
+        "category": GUARD_DEED_VALIDATION,
+        "severity": "high"
+    });
+    let request = json!({ "jsonrpc": "2.0", "method": "auto_church.report_near_miss", "params": params, "id": 1 }).to_string();
+    let bare: Value = serde_json::from_str(&dispatch_request_with(&request, &RpcContext::default())).unwrap();
+    assert_eq!(bare["error"]["code"], 1004);
+    assert_eq!(call("auto_church.report_near_miss", params)["result"]["clustered"], false);
+
+    // A deed the validator rejects corroborates the report.
+    let prev = ledger.lock().unwrap().last_hash();
+    let mint = json!({
+        "prev_hash": prev, "actor_id": "alice", "target_ids": [], "deed_type": "mutual_aid", "tags": [],
+        "context_json": {}, "ethics_flags": [], "life_harm_flag": false, "bioload_delta": -0.1, "roh": 0.9, "decay": 0.5
+    });
+    assert_eq!(call("auto_church.mint_deed", mint)["error"]["code"], 1001);
+    let ledger = ledger.lock().unwrap();
+    let cluster = clusters(&ledger).into_iter().next().unwrap();
+    assert_eq!(cluster.corroboration.unwrap().by, CorroboratedBy::Guard);
+    assert_eq!(wise_credit(&ledger, "bob"), 5);
+}
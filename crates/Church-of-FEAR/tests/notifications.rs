@@ -0,0 +1,281 @@
+#![cfg(feature = "core")]
+
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::notifications::{
+    Channel, DigestFrequency, EventKind, Notification, NotificationBody, NotificationCenter, NotificationError,
+    NotificationPrefs, QuietHours, SubjectNotifier,
+};
+use serde_json::json;
+
+/// 2023-11-14 00:00 UTC.
+const DAY0: i64 = 1_699_920_000;
+const HOUR: i64 = 3_600;
+const DAY: i64 = 86_400;
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<(String, Notification)>>>);
+
+impl SubjectNotifier for Capture {
+    fn notify(&self, url: &str, notification: &Notification) -> Result<(), String> {
+        self.0.lock().unwrap().push((url.to_string(), notification.clone()));
+        Ok(())
+    }
+}
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    for actor in ["alice", "bob"] {
+        ledger.open_account(actor, actor);
+    }
+    ledger
+}
+
+fn mint(ledger: &mut TokenLedger, actor: &str, amount: u64) {
+    ledger.mint_reward(actor, Token::Pwr, amount).unwrap();
+}
+
+fn flagged_deed(ledger: &mut TokenLedger, actor: &str) -> String {
+    let deed = DeedEvent::new(
+        ledger.last_hash(),
+        actor.into(),
+        vec![],
+        "tree_planting".into(),
+        vec![],
+        json!({}),
+        vec!["coercion".into()],
+        false,
+    );
+    ledger.append(deed).unwrap().event_id.clone()
+}
+
+fn prefs(events: &[EventKind], digest: DigestFrequency) -> NotificationPrefs {
+    NotificationPrefs { events: events.iter().copied().collect(), digest, ..NotificationPrefs::default() }
+}
+
+fn kinds(notifications: &[Notification]) -> Vec<EventKind> {
+    notifications
+        .iter()
+        .map(|n| match &n.body {
+            NotificationBody::Event(e) => e.kind,
+            NotificationBody::Digest { .. } => panic!("unexpected digest"),
+        })
+        .collect()
+}
+
+fn poll_all(center: &mut NotificationCenter, actor: &str) -> Vec<Notification> {
+    center.poll_outbox(actor, Some(0), 100).unwrap().notifications
+}
+
+#[test]
+fn only_opted_in_actors_get_the_event_kinds_they_chose() {
+    let mut ledger = ledger();
+    let mut center = NotificationCenter::new();
+    center.set_prefs("alice", prefs(&[EventKind::DeedMinted, EventKind::StandingChanged], DigestFrequency::Immediate)).unwrap();
+    center.set_prefs("bob", prefs(&[EventKind::FollowUpDue], DigestFrequency::Immediate)).unwrap();
+    assert!(matches!(
+        center.set_prefs("bob", NotificationPrefs { channels: vec![], ..NotificationPrefs::default() }),
+        Err(NotificationError::InvalidPrefs(_))
+    ));
+
+    mint(&mut ledger, "alice", 10);
+    mint(&mut ledger, "bob", 10);
+    let flagged = flagged_deed(&mut ledger, "alice");
+    assert_eq!(center.tick(&ledger, DAY0, &Capture::default()).unwrap(), 2);
+
+    let alice = poll_all(&mut center, "alice");
+    assert_eq!(kinds(&alice), [EventKind::DeedMinted, EventKind::StandingChanged]);
+    let NotificationBody::Event(standing) = &alice[1].body else { unreachable!() };
+    assert_eq!((standing.reference.as_str(), &standing.detail), (flagged.as_str(), &json!({ "from": "good", "to": "flagged" })));
+    assert!(poll_all(&mut center, "bob").is_empty());
+
+    // A second flagged deed leaves the standing where it is.
+    mint(&mut ledger, "alice", 5);
+    flagged_deed(&mut ledger, "alice");
+    center.tick(&ledger, DAY0 + 60, &Capture::default()).unwrap();
+    assert_eq!(kinds(&poll_all(&mut center, "alice"))[2..], [EventKind::DeedMinted]);
+}
+
+#[test]
+fn a_daily_digest_coalesces_the_day_into_one_notification() {
+    let mut ledger = ledger();
+    let mut center = NotificationCenter::new();
+    center.set_prefs("alice", prefs(&EventKind::ALL, DigestFrequency::Daily)).unwrap();
+    center.record_consent("alice", "telemetry", DAY0 + 3 * DAY).unwrap();
+
+    for (hour, amount) in [(8, 3), (12, 4), (20, 5)] {
+        mint(&mut ledger, "alice", amount);
+        assert_eq!(center.tick(&ledger, DAY0 + hour * HOUR, &Capture::default()).unwrap(), 0);
+        assert!(poll_all(&mut center, "alice").is_empty());
+    }
+    assert_eq!(center.tick(&ledger, DAY0 + DAY, &Capture::default()).unwrap(), 1);
+    mint(&mut ledger, "alice", 6);
+    assert_eq!(center.tick(&ledger, DAY0 + DAY + HOUR, &Capture::default()).unwrap(), 0);
+
+    let outbox = poll_all(&mut center, "alice");
+    assert_eq!(outbox.len(), 1);
+    let NotificationBody::Digest { frequency, counts, events } = &outbox[0].body else { panic!("{:?}", outbox[0]) };
+    assert_eq!(*frequency, DigestFrequency::Daily);
+    assert_eq!(counts.get(&EventKind::DeedMinted), Some(&3));
+    assert_eq!(counts.get(&EventKind::ConsentExpiring), Some(&1));
+    let amounts: Vec<_> = events.iter().filter(|e| e.kind == EventKind::DeedMinted).map(|e| e.detail["amount"].clone()).collect();
+    assert_eq!(amounts, [json!(3), json!(4), json!(5)]);
+    assert_eq!(outbox[0].delivered_at, DAY0 + DAY);
+
+    // The next day's mint waits for the next midnight.
+    assert_eq!(center.tick(&ledger, DAY0 + 2 * DAY, &Capture::default()).unwrap(), 1);
+}
+
+#[test]
+fn quiet_hours_defer_delivery_until_they_end() {
+    let mut ledger = ledger();
+    let mut center = NotificationCenter::new();
+    let webhook = Channel::Webhook { url: "http://127.0.0.1:9/alice".into() };
+    let quiet = NotificationPrefs {
+        channels: vec![Channel::Outbox, webhook],
+        quiet_hours: Some(QuietHours { start_hour: 22, end_hour: 7 }),
+        utc_offset_mins: -7 * 60,
+        ..NotificationPrefs::default()
+    };
+    center.set_prefs("alice", quiet).unwrap();
+    let sent = Capture::default();
+
+    // 23:00 local (06:00 UTC the next day) is quiet until 07:00 local.
+    mint(&mut ledger, "alice", 10);
+    let raised = DAY0 + DAY + 6 * HOUR;
+    assert_eq!(center.tick(&ledger, raised, &sent).unwrap(), 0);
+    assert_eq!(center.tick(&ledger, raised + 7 * HOUR, &sent).unwrap(), 0);
+    assert!(poll_all(&mut center, "alice").is_empty() && sent.0.lock().unwrap().is_empty());
+
+    let release = raised + 8 * HOUR;
+    assert_eq!(center.tick(&ledger, release, &sent).unwrap(), 1);
+    let outbox = poll_all(&mut center, "alice");
+    assert_eq!((kinds(&outbox), outbox[0].delivered_at), (vec![EventKind::DeedMinted], release));
+    let sent = sent.0.lock().unwrap();
+    assert_eq!((sent[0].0.as_str(), &sent[0].1), ("http://127.0.0.1:9/alice", &outbox[0]));
+}
+
+#[test]
+fn polling_by_cursor_is_idempotent_and_survives_a_restart() {
+    let dir = std::env::temp_dir().join(format!("cof-notifications-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("notifications.json");
+    let _ = std::fs::remove_file(&path);
+
+    let mut ledger = ledger();
+    let mut center = NotificationCenter::open(&path).unwrap();
+    center.set_prefs("alice", prefs(&[EventKind::DeedMinted], DigestFrequency::Immediate)).unwrap();
+    for amount in [1, 2, 3] {
+        mint(&mut ledger, "alice", amount);
+    }
+    assert_eq!(center.tick(&ledger, DAY0, &Capture::default()).unwrap(), 3);
+
+    let first = center.poll_outbox("alice", Some(0), 2).unwrap();
+    assert_eq!(first, center.poll_outbox("alice", Some(0), 2).unwrap());
+    assert_eq!((first.notifications.len(), first.next_cursor, first.missed), (2, 2, false));
+    let second = center.poll_outbox("alice", Some(first.next_cursor), 10).unwrap();
+    assert_eq!(second.notifications.iter().map(|n| n.cursor).collect::<Vec<_>>(), [3]);
+    drop(center);
+
+    // The read cursor, the outbox and the routed position were persisted.
+    let mut center = NotificationCenter::open(&path).unwrap();
+    assert_eq!(center.poll_outbox("alice", None, 10).unwrap(), second);
+    assert_eq!(center.tick(&ledger, DAY0 + 60, &Capture::default()).unwrap(), 0);
+    mint(&mut ledger, "alice", 4);
+    assert_eq!(center.tick(&ledger, DAY0 + 120, &Capture::default()).unwrap(), 1);
+    let page = center.poll_outbox("alice", Some(3), 10).unwrap();
+    assert_eq!((page.notifications.len(), page.next_cursor), (1, 4));
+    assert_eq!(poll_all(&mut center, "alice").len(), 4);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn consent_expiry_warns_once_per_lead_time() {
+    let ledger = ledger();
+    let mut center = NotificationCenter::new();
+    center.set_prefs("alice", prefs(&[EventKind::ConsentExpiring], DigestFrequency::Immediate)).unwrap();
+    center.record_consent("alice", "telemetry", DAY0 + 10 * DAY).unwrap();
+    // Recorded inside both lead times: only the nearer one is raised.
+    center.record_consent("alice", "photos", DAY0 + 12 * HOUR).unwrap();
+
+    let lead = |n: &Notification| match &n.body {
+        NotificationBody::Event(e) => (e.reference.clone(), e.detail["lead_secs"].as_i64().unwrap()),
+        NotificationBody::Digest { .. } => panic!("unexpected digest"),
+    };
+    let (mut warnings, mut cursor) = (Vec::new(), 0);
+    for at in [DAY0, DAY0 + 2 * DAY, DAY0 + 3 * DAY, DAY0 + 3 * DAY + HOUR, DAY0 + 9 * DAY, DAY0 + 9 * DAY + 1, DAY0 + 10 * DAY] {
+        center.tick(&ledger, at, &Capture::default()).unwrap();
+        let page = center.poll_outbox("alice", Some(cursor), 10).unwrap();
+        warnings.extend(page.notifications.iter().map(|n| (at, lead(n))));
+        cursor = page.next_cursor;
+    }
+    assert_eq!(
+        warnings,
+        [
+            (DAY0, ("photos".to_string(), DAY)),
+            (DAY0 + 3 * DAY, ("telemetry".to_string(), 7 * DAY)),
+            (DAY0 + 9 * DAY, ("telemetry".to_string(), DAY)),
+        ]
+    );
+    assert!(center.consents("alice").is_empty());
+}
+
+#[cfg(all(feature = "rpc", feature = "actor-keys"))]
+#[test]
+fn subjects_manage_their_own_prefs_and_outbox_over_rpc() {
+    use church_of_fear::identity::{bind_actor_key, KeyChange};
+    use church_of_fear::notifications::SubjectRequest;
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use keyring::Keyring;
+    use serde_json::Value;
+
+    const T: i64 = 1_700_000_000;
+    let mut keys = Keyring::new().with_clock(|| T as u64);
+    let mut ledger = ledger();
+    let mut key_of = std::collections::HashMap::new();
+    for actor in ["alice", "bob"] {
+        let name = keys.generate("actor").unwrap();
+        let key = keys.keys().find(|k| k.name == name).unwrap().clone();
+        let proof = keys.sign(&name, &KeyChange::new(actor, &key).signing_bytes()).unwrap();
+        bind_actor_key(&mut ledger, actor, &key, &proof, T).unwrap();
+        key_of.insert(actor, name);
+    }
+    mint(&mut ledger, "alice", 7);
+
+    let ledger = Arc::new(Mutex::new(ledger));
+    let center = Arc::new(Mutex::new(NotificationCenter::new()));
+    let ctx = RpcContext { notifications: Some(center.clone()), ..RpcContext::with_ledger(ledger.clone()) };
+    let call = |method: &str, actor: &str, signer: &str, args: Value| -> Value {
+        let request = SubjectRequest::new(method, actor, args.clone());
+        let signature = keys.sign(&key_of[signer], &request.signing_bytes()).unwrap();
+        let mut params = args;
+        params["actor_id"] = json!(actor);
+        params["signature"] = json!(signature);
+        params["now"] = json!(T);
+        let req = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str(&dispatch_request_with(&req.to_string(), &ctx)).unwrap()
+    };
+
+    let prefs = json!({ "events": ["deed_minted"] });
+    let set = call("auto_church.set_notification_prefs", "alice", "alice", json!({ "prefs": prefs }));
+    assert_eq!(set["result"]["prefs"]["events"], json!(["deed_minted"]));
+    let forged = call("auto_church.set_notification_prefs", "alice", "bob", json!({ "prefs": {} }));
+    assert_eq!(forged["error"]["code"], 1010);
+    let got = call("auto_church.get_notification_prefs", "alice", "alice", json!({}));
+    assert_eq!(got["result"]["prefs"]["digest"], "immediate");
+
+    center.lock().unwrap().tick(&ledger.lock().unwrap(), T, &Capture::default()).unwrap();
+    let polled = call("auto_church.poll_outbox", "alice", "alice", json!({ "since_cursor": 0 }));
+    assert_eq!(polled["result"]["next_cursor"], 1);
+    assert_eq!(polled["result"]["notifications"][0]["body"]["detail"]["amount"], 7);
+    let read = call("auto_church.poll_outbox", "alice", "alice", json!({ "since_cursor": 1 }));
+    assert_eq!(read["result"]["notifications"], json!([]));
+    let again = call("auto_church.poll_outbox", "alice", "alice", json!({}));
+    assert_eq!((again["result"]["notifications"].clone(), again["result"]["next_cursor"].clone()), (json!([]), json!(1)));
+    assert_eq!(call("auto_church.poll_outbox", "bob", "alice", json!({}))["error"]["code"], 1010);
+}
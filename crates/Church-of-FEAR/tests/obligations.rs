@@ -0,0 +1,200 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::builders::{EcologicalSustainabilityDeed, HomelessnessReliefDeed};
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::obligations::{
+    category_streak, follow_up_status, reward_with_follow_ups, submit_follow_up, sweep_missed, FollowUpStatus, ObligationError,
+    FOLLOW_UP, OBLIGATION_MISSED, PENDING_OBLIGATIONS,
+};
+use church_of_fear::scheduler::RecurringJobs;
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+
+fn church(ledger: &TokenLedger, id: &str) -> u64 {
+    ledger.account(id).map_or(0, |a| a.balance(Token::Church))
+}
+
+fn reconciles(ledger: &TokenLedger) {
+    assert!(ledger.supply_report().reconciles(), "{:?}", ledger.supply_report());
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.supply_report(), ledger.supply_report());
+}
+
+/// Append a tree planting by `actor` dated `T0`; returns its event id.
+fn plant(ledger: &mut TokenLedger, actor: &str) -> String {
+    let mut deed = EcologicalSustainabilityDeed::builder()
+        .actor_id(actor)
+        .location("Sonoran Desert, AZ")
+        .co2_kg(120.0)
+        .evidence_uri("https://photos.example/planting")
+        .build(ledger.last_hash())
+        .unwrap();
+    deed.timestamp = T0;
+    deed.self_hash = hash_deed(&deed);
+    ledger.append(deed).unwrap().event_id.clone()
+}
+
+/// Submit `actor`'s survival check of `source` at `now`.
+fn submit(ledger: &mut TokenLedger, actor: &str, source: &str, evidence_kind: &str, now: i64) -> Result<u64, ObligationError> {
+    let deed = DeedEvent::new(
+        ledger.last_hash(),
+        actor.to_string(),
+        vec![source.to_string()],
+        FOLLOW_UP.to_string(),
+        Vec::new(),
+        json!({ "kind": "survival_check", "evidence_kind": evidence_kind, "evidence_uri": "https://photos.example/survival" }),
+        Vec::new(),
+        false,
+    );
+    submit_follow_up(ledger, deed, now)
+}
+
+#[test]
+fn declared_follow_ups_hold_part_of_the_reward_in_escrow() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let trees = plant(&mut ledger, "alice");
+    let split = reward_with_follow_ups(&mut ledger, &trees, 100).unwrap();
+    assert_eq!((split.credited, split.escrowed, split.obligation_ids.len()), (70, 30, 1));
+    assert_eq!(church(&ledger, "alice"), 70);
+    assert_eq!(church(&ledger, PENDING_OBLIGATIONS), 30);
+
+    let status = follow_up_status(&ledger, &trees);
+    assert_eq!(status.len(), 1);
+    assert_eq!((status[0].kind.as_str(), status[0].evidence.as_str()), ("survival_check", "photo"));
+    assert_eq!((status[0].due_at, status[0].deadline), (T0 + 180 * DAY, T0 + 210 * DAY));
+    assert_eq!((status[0].escrowed, &status[0].status), (30, &FollowUpStatus::Pending));
+    assert!(matches!(reward_with_follow_ups(&mut ledger, &trees, 100), Err(ObligationError::AlreadyOpen(_))));
+    reconciles(&ledger);
+
+    // Categories without follow-ups are paid in full.
+    let meals = HomelessnessReliefDeed::builder()
+        .actor_id("bob")
+        .location("Phoenix")
+        .hours(3.0)
+        .meals_served(20)
+        .build(ledger.last_hash())
+        .unwrap();
+    let meals = ledger.append(meals).unwrap().event_id.clone();
+    let split = reward_with_follow_ups(&mut ledger, &meals, 100).unwrap();
+    assert_eq!((split.credited, split.escrowed), (100, 0));
+    assert!(follow_up_status(&ledger, &meals).is_empty());
+
+    // Escrow deeds are the ledger's to write.
+    let forged =
+        DeedEvent::new(ledger.last_hash(), "alice".into(), vec![], OBLIGATION_MISSED.into(), vec![], json!({}), vec![], false);
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+}
+
+#[test]
+fn a_timely_verified_follow_up_settles_the_escrow() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let trees = plant(&mut ledger, "alice");
+    reward_with_follow_ups(&mut ledger, &trees, 100).unwrap();
+    let due = T0 + 180 * DAY;
+
+    let early = submit(&mut ledger, "alice", &trees, "photo", due - 1);
+    assert_eq!(early, Err(ObligationError::NotDue(due)));
+    let stranger = submit(&mut ledger, "mallory", &trees, "photo", due);
+    assert_eq!(stranger, Err(ObligationError::NotObligor("mallory".into())));
+    let hearsay = submit(&mut ledger, "alice", &trees, "testimony", due);
+    assert!(matches!(hearsay, Err(ObligationError::EvidenceRejected(_))));
+    assert_eq!(church(&ledger, PENDING_OBLIGATIONS), 30);
+
+    assert_eq!(submit(&mut ledger, "alice", &trees, "photo", due + DAY), Ok(30));
+    assert_eq!((church(&ledger, "alice"), church(&ledger, PENDING_OBLIGATIONS)), (100, 0));
+    let FollowUpStatus::Settled { follow_up_event_id, settled_at } = &follow_up_status(&ledger, &trees)[0].status else {
+        panic!("{:?}", follow_up_status(&ledger, &trees));
+    };
+    assert_eq!(ledger.deed(follow_up_event_id).unwrap().deed_type, FOLLOW_UP);
+    assert_eq!(*settled_at, due + DAY);
+    assert!(matches!(submit(&mut ledger, "alice", &trees, "photo", due + DAY), Err(ObligationError::NoPendingObligation { .. })));
+    reconciles(&ledger);
+
+    // Settled obligations stay settled after a restart, and are not swept.
+    let mut replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(follow_up_status(&replayed, &trees), follow_up_status(&ledger, &trees));
+    assert!(sweep_missed(&mut replayed, T0 + 365 * DAY).unwrap().is_empty());
+}
+
+#[test]
+fn a_missed_deadline_sweeps_the_escrow_to_the_pool_and_resets_the_streak() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let trees = plant(&mut ledger, "alice");
+    plant(&mut ledger, "alice");
+    assert_eq!(category_streak(&ledger, "alice", "ecological_sustainability"), 2);
+    reward_with_follow_ups(&mut ledger, &trees, 100).unwrap();
+    let deadline = T0 + 210 * DAY;
+
+    assert!(sweep_missed(&mut ledger, deadline - 1).unwrap().is_empty());
+    let late = submit(&mut ledger, "alice", &trees, "photo", deadline);
+    assert_eq!(late, Err(ObligationError::PastDeadline(deadline)));
+
+    // The daily scheduler job does the sweep.
+    let mut jobs = RecurringJobs::with_defaults(&ledger, deadline - DAY);
+    assert!(jobs.run_due(&mut ledger, deadline).contains(&"sweep_obligations".to_string()));
+    assert_eq!(follow_up_status(&ledger, &trees)[0].status, FollowUpStatus::Missed { swept_at: deadline });
+    assert_eq!((ledger.pool_balance(), church(&ledger, PENDING_OBLIGATIONS), church(&ledger, "alice")), (30, 0, 70));
+    assert_eq!(category_streak(&ledger, "alice", "ecological_sustainability"), 0);
+    plant(&mut ledger, "alice");
+    assert_eq!(category_streak(&ledger, "alice", "ecological_sustainability"), 1);
+    assert!(sweep_missed(&mut ledger, deadline + DAY).unwrap().is_empty());
+    reconciles(&ledger);
+
+    // Without recycling, the missed escrow is burned instead.
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.recycle_expired_obligations = false;
+    let mut strict = TokenLedger::new(cfg);
+    let trees = plant(&mut strict, "alice");
+    reward_with_follow_ups(&mut strict, &trees, 100).unwrap();
+    assert_eq!(sweep_missed(&mut strict, deadline).unwrap().len(), 1);
+    assert_eq!((strict.pool_balance(), church(&strict, PENDING_OBLIGATIONS)), (0, 0));
+    assert_eq!(strict.supply_report().tokens[&Token::Church].retired, 30);
+    reconciles(&strict);
+}
+
+#[test]
+fn tombstoning_the_deed_cancels_its_obligations() {
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.tithe_bps = 1_000;
+    let mut ledger = TokenLedger::new(cfg);
+    let trees = plant(&mut ledger, "alice");
+    let split = reward_with_follow_ups(&mut ledger, &trees, 100).unwrap();
+    // The tithe comes off the credited share only.
+    assert_eq!((split.credited, split.escrowed, ledger.pool_balance()), (63, 30, 7));
+    reconciles(&ledger);
+
+    ledger.tombstone(&trees, "duplicate submission", "Regulator").unwrap();
+    assert_eq!(follow_up_status(&ledger, &trees)[0].status, FollowUpStatus::Cancelled);
+    assert_eq!((church(&ledger, "alice"), church(&ledger, PENDING_OBLIGATIONS), ledger.pool_balance()), (0, 0, 0));
+    assert!(sweep_missed(&mut ledger, T0 + 365 * DAY).unwrap().is_empty());
+    reconciles(&ledger);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn follow_up_status_is_served_over_rpc() {
+    use std::sync::{Arc, Mutex};
+
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let trees = plant(&mut ledger, "alice");
+    reward_with_follow_ups(&mut ledger, &trees, 100).unwrap();
+
+    let request = json!({ "jsonrpc": "2.0", "method": "auto_church.follow_up_status", "params": { "event_id": trees }, "id": 1 })
+        .to_string();
+    let bare: Value = serde_json::from_str(&dispatch_request_with(&request, &RpcContext::default())).unwrap();
+    assert_eq!(bare["error"]["code"], 1004);
+
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() };
+    let resp: Value = serde_json::from_str(&dispatch_request_with(&request, &ctx)).unwrap();
+    let obligation = &resp["result"]["obligations"][0];
+    assert_eq!((obligation["kind"].as_str(), obligation["status"].as_str()), (Some("survival_check"), Some("pending")));
+    assert_eq!(obligation["escrowed"], 30);
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "param-governance")]
+
+use church_of_fear::compliance::god_like::GodLikeEnvelope;
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::params::{change_param, ParamChangeError, ParamError, ParamKey, SignedParamChange, PARAMETER_CHANGE};
+use church_of_fear::token::repair_curve::{grant_repair_pwr, RepairClaim, RepairGrantError};
+use keyring::{Keyring, VerifyingBundle};
+use param_registry::ParamChange;
+use serde_json::{json, Value};
+
+const T: i64 = 1_700_000_000;
+
+fn authorities() -> (Keyring, Vec<String>, VerifyingBundle) {
+    let mut keyring = Keyring::new().with_clock(|| T as u64);
+    let names: Vec<String> = (0..3).map(|_| keyring.generate("param-authority").unwrap()).collect();
+    let bundle = keyring.verifying_bundle();
+    (keyring, names, bundle)
+}
+
+fn signed(keyring: &Keyring, signers: &[String], id: &str, param: &str, value: Value) -> SignedParamChange {
+    let change = ParamChange {
+        change_id: id.to_string(),
+        param: param.to_string(),
+        value,
+        reason: "quarterly review".to_string(),
+        requested_at: T,
+    };
+    let approvals = signers.iter().map(|s| keyring.sign(s, &change.signing_bytes()).unwrap()).collect();
+    SignedParamChange { change, approvals }
+}
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.mint_reward("alice", Token::Church, 10_000).unwrap();
+    ledger
+}
+
+fn claim(score: f64) -> RepairClaim {
+    RepairClaim { actor_id: "alice".into(), source_event_id: "repair-1".into(), impact_score: score, evidence_verified: false }
+}
+
+#[test]
+fn safety_parameter_changes_need_a_quorum_and_stay_in_bounds() {
+    let (keyring, names, bundle) = authorities();
+    let mut ledger = ledger();
+    let before = ledger.deeds().len();
+
+    let lone = signed(&keyring, &names[..1], "p-1", "repair_evidence_threshold", json!(0.9));
+    assert!(matches!(
+        change_param(&mut ledger, &lone, &bundle, T),
+        Err(ParamChangeError::Param(ParamError::NotEnoughApprovals { got: 1, need: 2 }))
+    ));
+    let wild = signed(&keyring, &names[..2], "p-1", "repair_evidence_threshold", json!(0.2));
+    assert!(matches!(change_param(&mut ledger, &wild, &bundle, T), Err(ParamChangeError::Param(ParamError::OutOfBounds { .. }))));
+    assert_eq!(ledger.deeds().len(), before);
+    assert!(matches!(
+        grant_repair_pwr(&mut ledger, &claim(0.85), &GodLikeEnvelope::default(), T),
+        Err(RepairGrantError::UnverifiedEvidence { threshold, .. }) if threshold == 0.8
+    ));
+
+    let ok = signed(&keyring, &names[..2], "p-1", "repair_evidence_threshold", json!(0.9));
+    let record = change_param(&mut ledger, &ok, &bundle, T).unwrap();
+    assert_eq!(record.previous, json!(0.8));
+    let deed = ledger.deeds().last().unwrap();
+    assert_eq!(deed.deed_type, PARAMETER_CHANGE);
+    assert_eq!(record.event_id.as_deref(), Some(deed.event_id.as_str()));
+    assert_eq!(deed.context_json["signers"].as_array().unwrap().len(), 2);
+    assert!(matches!(change_param(&mut ledger, &ok, &bundle, T), Err(ParamChangeError::Param(ParamError::Replayed(_)))));
+
+    // The new threshold lets 0.85 through without verified evidence.
+    assert_eq!(ledger.params().get(ParamKey::RepairEvidenceThreshold), 0.9);
+    assert!(grant_repair_pwr(&mut ledger, &claim(0.85), &GodLikeEnvelope::default(), T).unwrap().amount > 0);
+
+    // Neither a hand-built deed nor a tombstone can move the value.
+    let forged = DeedEvent::new(ledger.last_hash(), "mallory".into(), vec![], PARAMETER_CHANGE.into(), vec![], json!({}), vec![], false);
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+    let event_id = record.event_id.unwrap();
+    assert!(matches!(ledger.tombstone(&event_id, "undo", "Host"), Err(TokenLedgerError::NotCorrectable(_))));
+}
+
+#[test]
+fn config_overrides_reach_informational_parameters_only() {
+    let mut cfg = LedgerConfig::default();
+    cfg.param_overrides.insert("headroom_max_days".into(), json!(30));
+    cfg.param_overrides.insert("eco_score_mint_floor".into(), json!(0.0));
+    cfg.param_overrides.insert("no_such_param".into(), json!(1));
+    let ledger = TokenLedger::new(cfg);
+    let params = ledger.params();
+    assert_eq!(params.get(ParamKey::HeadroomMaxDays), 30);
+    assert_eq!(params.get(ParamKey::EcoScoreMintFloor), 0.5);
+}
+
+#[test]
+fn replay_reconstructs_values_and_history() {
+    let (keyring, names, bundle) = authorities();
+    let mut ledger = ledger();
+    for (id, value) in [("p-1", 0.9), ("p-2", 0.95)] {
+        change_param(&mut ledger, &signed(&keyring, &names[1..], id, "repair_evidence_threshold", json!(value)), &bundle, T).unwrap();
+    }
+    let floor = signed(&keyring, &names, "p-3", "eco_score_mint_floor", json!(0.6));
+    change_param(&mut ledger, &floor, &bundle, T).unwrap();
+
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.params(), ledger.params());
+    let history: Vec<(&str, &Value, &Value)> =
+        replayed.params().history().iter().map(|r| (r.change_id.as_str(), &r.previous, &r.value)).collect();
+    assert_eq!(
+        history,
+        [("p-1", &json!(0.8), &json!(0.9)), ("p-2", &json!(0.9), &json!(0.95)), ("p-3", &json!(0.5), &json!(0.6))]
+    );
+    assert_eq!(replayed.params().get(ParamKey::EcoScoreMintFloor), 0.6);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_lists_values_with_provenance() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use std::sync::{Arc, Mutex};
+
+    let request = r#"{"jsonrpc":"2.0","method":"auto_church.params","params":null,"id":1}"#;
+    let find = |resp: &Value, name: &str| -> Value {
+        resp["result"]["params"].as_array().unwrap().iter().find(|p| p["name"] == name).cloned().unwrap()
+    };
+
+    let detached: Value = serde_json::from_str(&dispatch_request_with(request, &RpcContext::default())).unwrap();
+    assert_eq!(find(&detached, "errority_trigger_delta")["value"], -0.3);
+    assert_eq!(find(&detached, "errority_trigger_delta")["provenance"], json!({ "kind": "default" }));
+
+    let (keyring, names, bundle) = authorities();
+    let mut cfg = LedgerConfig::default();
+    cfg.param_overrides.insert("headroom_max_days".into(), json!(14));
+    let mut ledger = TokenLedger::new(cfg);
+    let record = change_param(&mut ledger, &signed(&keyring, &names[..2], "p-1", "observation_horizon", json!(120 * 86_400)), &bundle, T)
+        .unwrap();
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() };
+    let attached: Value = serde_json::from_str(&dispatch_request_with(request, &ctx)).unwrap();
+
+    let horizon = find(&attached, "observation_horizon");
+    assert_eq!(horizon["value"], 120 * 86_400);
+    assert_eq!(horizon["default"], 90 * 86_400);
+    assert_eq!(horizon["sensitivity"], "safety_relevant");
+    assert_eq!(horizon["provenance"], json!({ "kind": "governance", "change_id": "p-1", "event_id": record.event_id }));
+    assert_eq!(find(&attached, "headroom_max_days")["provenance"], json!({ "kind": "config", "source": "ledger config" }));
+    assert_eq!(attached["result"]["history"][0]["change_id"], "p-1");
+}
@@ -0,0 +1,234 @@
+#![cfg(feature = "core")]
+
+use std::path::{Path, PathBuf};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::policy_bundle::{
+    self, ArtifactEntry, BundleError, BundleManifest, BundleProblem, PolicyBundle, Requirement, MANIFEST_FILE,
+    POLICY_BUNDLE_LOADED,
+};
+use church_of_fear::utils::crypto::sha256;
+use serde_json::json;
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cof-bundle-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn requires(artifact: &str, min_version: u32) -> Requirement {
+    Requirement { artifact: artifact.to_string(), min_version: Some(min_version), max_version: None }
+}
+
+/// Write `body` to `<dir>/<path>` and return its entry in a bundle.
+fn artifact(dir: &Path, name: &str, path: &str, body: &str, version: u32, reqs: Vec<Requirement>) -> ArtifactEntry {
+    std::fs::write(dir.join(path), body).unwrap();
+    ArtifactEntry {
+        name: name.to_string(),
+        path: Some(path.to_string()),
+        sha256: Some(sha256(body)),
+        version,
+        requires: reqs,
+    }
+}
+
+fn write_manifest(dir: &Path, artifacts: Vec<ArtifactEntry>) {
+    let manifest = BundleManifest { artifacts };
+    std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest).unwrap()).unwrap();
+}
+
+/// A taxonomy at `taxonomy_version` requiring reward policy v3 from v2
+/// on, beside a reward policy at `reward_version`.
+fn fixture(dir: &Path, taxonomy_version: u32, reward_version: u32) {
+    let reqs = if taxonomy_version >= 2 { vec![requires("reward_policy", 3)] } else { Vec::new() };
+    let taxonomy = json!({ "version": taxonomy_version, "categories": [] }).to_string();
+    write_manifest(
+        dir,
+        vec![
+            artifact(dir, "taxonomy", "deeds.json", &taxonomy, taxonomy_version, reqs),
+            artifact(
+                dir,
+                "reward_policy",
+                "rewards.aln",
+                &format!("REWARD_POLICY = {}\n", reward_version),
+                reward_version,
+                Vec::new(),
+            ),
+            ArtifactEntry {
+                name: "deed_schema".to_string(),
+                path: None,
+                sha256: None,
+                version: 2,
+                requires: Vec::new(),
+            },
+        ],
+    );
+}
+
+fn problems(dir: &Path) -> Vec<BundleProblem> {
+    match PolicyBundle::load_and_verify(dir) {
+        Err(BundleError::Invalid(problems)) => problems,
+        other => panic!("expected a refused bundle, got {:?}", other),
+    }
+}
+
+#[test]
+fn the_shipped_bundle_verifies() {
+    let bundle = PolicyBundle::load_and_verify(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+    assert!(bundle.artifact("taxonomy").is_some());
+    assert_eq!(bundle.hash(), policy_bundle::bundle_hash(bundle.manifest()));
+}
+
+#[test]
+fn an_unsatisfied_requirement_is_refused() {
+    let dir = TempDir::new("constraint");
+    fixture(&dir.0, 2, 3);
+    PolicyBundle::load_and_verify(&dir.0).unwrap();
+
+    fixture(&dir.0, 2, 2);
+    assert_eq!(
+        problems(&dir.0),
+        vec![BundleProblem::Unsatisfied {
+            artifact: "taxonomy".to_string(),
+            version: 2,
+            requirement: "reward_policy >=3".to_string(),
+            found: 2,
+        }]
+    );
+}
+
+#[test]
+fn an_edited_artifact_is_refused() {
+    let dir = TempDir::new("hash");
+    fixture(&dir.0, 1, 1);
+    std::fs::write(dir.0.join("rewards.aln"), "REWARD_POLICY = 9\n").unwrap();
+    let found = problems(&dir.0);
+    assert_eq!(found.len(), 1);
+    assert!(matches!(&found[0], BundleProblem::HashMismatch { artifact, .. } if artifact == "reward_policy"));
+}
+
+#[test]
+fn a_refused_bundle_lists_every_problem_at_once() {
+    let dir = TempDir::new("all");
+    let mut taxonomy = artifact(
+        &dir.0,
+        "taxonomy",
+        "deeds.json",
+        &json!({ "version": 1 }).to_string(),
+        2,
+        vec![requires("frameworks", 1)],
+    );
+    taxonomy.requires.push(requires("reward_policy", 3));
+    let mut reward = artifact(&dir.0, "reward_policy", "rewards.aln", "REWARD_POLICY = 1\n", 1, Vec::new());
+    reward.sha256 = Some(sha256("something else"));
+    let missing = ArtifactEntry { path: Some("envelope.aln".to_string()), ..reward.clone() };
+    let missing = ArtifactEntry { name: "envelope".to_string(), ..missing };
+    let schema =
+        ArtifactEntry { name: "deed_schema".to_string(), path: None, sha256: None, version: 7, requires: Vec::new() };
+    write_manifest(&dir.0, vec![taxonomy, reward, missing, schema]);
+
+    let found = problems(&dir.0);
+    let kinds: Vec<&str> = found
+        .iter()
+        .map(|p| match p {
+            BundleProblem::VersionMismatch { artifact, .. } if artifact == "taxonomy" => "taxonomy version",
+            BundleProblem::HashMismatch { .. } => "hash",
+            BundleProblem::Unreadable { .. } => "missing",
+            BundleProblem::VersionMismatch { .. } => "schema version",
+            BundleProblem::UnknownRequirement { .. } => "unknown requirement",
+            BundleProblem::Unsatisfied { .. } => "unsatisfied",
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["taxonomy version", "hash", "missing", "schema version", "unknown requirement", "unsatisfied"]
+    );
+
+    let message = BundleError::Invalid(found.clone()).to_string();
+    assert!(message.starts_with("policy bundle refused, 6 problem(s):"));
+    for problem in &found {
+        assert!(message.contains(&problem.to_string()), "{} is not listed", problem);
+    }
+}
+
+#[test]
+fn deeds_after_a_load_are_attributed_to_the_bundle() {
+    let dir = TempDir::new("deeds");
+    fixture(&dir.0, 1, 1);
+    let bundle = PolicyBundle::load_and_verify(&dir.0).unwrap();
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    let before = ledger.deeds().len();
+
+    assert!(policy_bundle::record_loaded(&mut ledger, &bundle).unwrap().is_some());
+    // Reloading the same bundle logs nothing new.
+    assert!(policy_bundle::record_loaded(&mut ledger, &bundle).unwrap().is_none());
+    ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    assert_eq!(ledger.policy_bundle(), Some(bundle.hash()));
+
+    let deeds = ledger.deeds();
+    assert!(policy_bundle::bundle_at(deeds, before - 1).is_none());
+    for position in before + 1..deeds.len() {
+        assert_eq!(policy_bundle::bundle_at(deeds, position), Some(bundle.hash()));
+    }
+    let loaded = &deeds[before];
+    assert_eq!(loaded.deed_type, POLICY_BUNDLE_LOADED);
+    assert_eq!(loaded.context_json["artifacts"]["taxonomy"]["version"], 1);
+
+    let replayed = TokenLedger::replay(LedgerConfig::default(), deeds.iter().cloned()).unwrap();
+    assert_eq!(replayed.policy_bundle(), Some(bundle.hash()));
+
+    // Only the ledger writes these.
+    let forged = DeedEvent::new(
+        ledger.last_hash(),
+        "mallory".to_string(),
+        Vec::new(),
+        POLICY_BUNDLE_LOADED.to_string(),
+        Vec::new(),
+        json!({ "bundle_hash": "0".repeat(64) }),
+        Vec::new(),
+        false,
+    );
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+}
+
+#[test]
+fn an_upgrade_is_reviewed_as_a_diff() {
+    let (old_dir, new_dir) = (TempDir::new("old"), TempDir::new("new"));
+    fixture(&old_dir.0, 1, 2);
+    fixture(&new_dir.0, 2, 3);
+    let old = PolicyBundle::load_and_verify(&old_dir.0).unwrap();
+
+    // A reload runs the same checks: a broken upgrade changes nothing.
+    std::fs::write(new_dir.0.join("rewards.aln"), "tampered").unwrap();
+    assert!(matches!(old.reload(&new_dir.0), Err(BundleError::Invalid(_))));
+
+    fixture(&new_dir.0, 2, 3);
+    let (new, diff) = old.reload(&new_dir.0).unwrap();
+    assert_ne!(old.hash(), new.hash());
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert_eq!(diff.changed.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["reward_policy", "taxonomy"]);
+    let rendered = diff.to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], format!("policy bundle {} -> {}", &old.hash()[..12], &new.hash()[..12]));
+    assert!(lines[1].starts_with("~ reward_policy v2 -> v3, sha256 "));
+    assert!(lines[2].starts_with("~ taxonomy v1 -> v2, sha256 "));
+    assert!(lines[2].ends_with(", requires [] -> [reward_policy >=3]"));
+    assert!(policy_bundle::diff_bundles(&new, &new).is_empty());
+}
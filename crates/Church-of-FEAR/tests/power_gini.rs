@@ -0,0 +1,119 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::compliance::ethics::EthicsSummary;
+use church_of_fear::compliance::regulator::{evaluate_conditions, Condition};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::concentration::{gini, BalanceHistogram, PowerConcentration, GINI_ERROR_BOUND};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::sponsor::pool::SPONSOR_POOL;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn distributions(rng: &mut StdRng, n: usize) -> Vec<Vec<u64>> {
+    vec![
+        (0..n).map(|_| rng.gen_range(0..1_000_000)).collect(),
+        (0..n).map(|_| 10f64.powf(rng.gen_range(0.0..12.0)) as u64).collect(),
+        // Pareto with alpha 1.2.
+        (0..n).map(|_| (100.0 / rng.gen_range(1e-6..1.0f64).powf(1.0 / 1.2)) as u64).collect(),
+        // Mostly dust, a few whales.
+        (0..n).map(|i| if i % 10 == 0 { rng.gen_range(1_000_000..1_000_000_000) } else { rng.gen_range(0..3) }).collect(),
+        (0..n).map(|_| rng.gen_range(900..1_100)).collect(),
+    ]
+}
+
+#[test]
+fn histogram_gini_stays_within_the_error_bound() {
+    let mut rng = StdRng::seed_from_u64(464);
+    for _ in 0..20 {
+        for balances in distributions(&mut rng, 2_000) {
+            let exact = gini(&balances);
+            let approx = BalanceHistogram::from_balances(balances.iter().copied()).gini();
+            assert!(approx <= exact + 1e-9, "approx {} above exact {}", approx, exact);
+            assert!(exact - approx <= GINI_ERROR_BOUND, "approx {} exact {}", approx, exact);
+        }
+    }
+
+    // Balances below the slice width each get a bucket of their own.
+    let small: Vec<u64> = (0..5_000).map(|_| rng.gen_range(0..64)).collect();
+    assert!((BalanceHistogram::from_balances(small.iter().copied()).gini() - gini(&small)).abs() < 1e-9);
+    assert_eq!(BalanceHistogram::default().gini(), 0.0);
+}
+
+#[test]
+fn dust_and_system_accounts_are_left_out_of_the_filtered_gini() {
+    let mut cfg = LedgerConfig::default();
+    cfg.concentration.dust_threshold = 50;
+    let mut ledger = TokenLedger::new(cfg);
+    for (id, pwr) in [("alice", 100), ("bob", 200), ("carol", 300), ("dave", 10)] {
+        ledger.open_account(id, id);
+        ledger.mint_reward(id, Token::Pwr, pwr).unwrap();
+    }
+    ledger.open_account(SPONSOR_POOL, SPONSOR_POOL);
+    ledger.mint_reward(SPONSOR_POOL, Token::Pwr, 1_000_000).unwrap();
+    for i in 0..1_000 {
+        ledger.open_account(&format!("churned-{}", i), "churned");
+    }
+
+    let power = ledger.power_gini();
+    assert_eq!((power.participants, power.accounts), (3, 1_005));
+    assert!((power.filtered - 2.0 / 9.0).abs() < 1e-9, "{}", power.filtered);
+    assert!(power.unfiltered > 0.99);
+    assert!((power.unfiltered - ledger.exact_power_gini().unfiltered).abs() <= GINI_ERROR_BOUND);
+
+    // The regulator reads the participant figure; the churn does not trip it.
+    let summary = EthicsSummary { mean_trust: 0.9, ..EthicsSummary::default() }.with_power_gini(&power);
+    assert_eq!(summary.power_gini_unfiltered, power.unfiltered);
+    let concentration = evaluate_conditions(&summary).into_iter().find(|r| r.condition == Condition::PowerConcentration).unwrap();
+    assert!(concentration.passed);
+    assert_eq!(concentration.measured, power.filtered);
+
+    let text = power.to_prometheus();
+    assert!(text.contains("cof_power_gini{scope=\"participants\"} 0.222"));
+    assert!(text.contains("cof_power_gini_accounts{scope=\"all\"} 1005\n"));
+}
+
+#[test]
+fn histograms_follow_mints_burns_and_transfers() {
+    let cfg = LedgerConfig::default();
+    let mut ledger = TokenLedger::new(cfg.clone());
+    let ids: Vec<String> = (0..50).map(|i| format!("acct-{}", i)).collect();
+    for id in &ids {
+        ledger.open_account(id, id);
+    }
+    let mut rng = StdRng::seed_from_u64(7);
+    for step in 0..600 {
+        let id = &ids[rng.gen_range(0..ids.len())];
+        let amount = rng.gen_range(0..5_000);
+        match step % 3 {
+            0 => {
+                ledger.mint_reward(id, Token::Pwr, amount).unwrap();
+            }
+            1 => {
+                ledger.burn(id, Token::Pwr, amount).unwrap();
+            }
+            // The ledger has no PWR transfer; one is a burn and a mint.
+            _ => {
+                let to = &ids[rng.gen_range(0..ids.len())];
+                let moved = ledger.burn(id, Token::Pwr, amount).unwrap();
+                ledger.mint_reward(to, Token::Pwr, moved).unwrap();
+            }
+        }
+        let rebuilt = PowerConcentration::rebuild(
+            cfg.concentration.clone(),
+            ledger.accounts().map(|a| (a.id.as_str(), a.balance_pwr)),
+        );
+        assert_eq!(ledger.power_gini(), rebuilt.gini(), "step {}", step);
+    }
+
+    let check = ledger.check_power_gini();
+    assert!(!check.rebuilt);
+    assert!(check.drift <= GINI_ERROR_BOUND);
+    assert_eq!(check.exact, ledger.exact_power_gini());
+
+    // Replay rebuilds the same participant histogram from the deed log
+    // (it only opens accounts a deed moved).
+    let replayed = TokenLedger::replay(cfg, ledger.deeds().iter().cloned()).unwrap();
+    let (replayed, live) = (replayed.power_gini(), ledger.power_gini());
+    assert_eq!((replayed.filtered, replayed.participants), (live.filtered, live.participants));
+}
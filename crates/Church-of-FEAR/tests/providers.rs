@@ -0,0 +1,288 @@
+#![cfg(feature = "core")]
+
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::ClinicalAttestationDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::providers::{
+    alert_if_suspended, attestation_weights, clin_trust, provider_reliability, providers, record_audit, register_provider,
+    reinstate_provider, sweep_outcomes, AttestationOutcome, ProviderAlert, ProviderAlertKind, ProviderError,
+    ProviderNotifier, PROVIDER_SUSPENDED, PROVIDER_SUSPENSION, PROVIDER_WEIGHT, TOMBSTONE_SOURCE,
+};
+use church_of_fear::scheduler::RecurringJobs;
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+const TRUSTED: &str = "provider:desert-trials";
+const SHAKY: &str = "provider:quick-cert";
+
+/// Collects alerts instead of posting them.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<ProviderAlert>>>);
+
+impl ProviderNotifier for Recorder {
+    fn notify(&self, alert: &ProviderAlert) -> Result<(), String> {
+        self.0.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+impl Recorder {
+    fn kinds(&self) -> Vec<ProviderAlertKind> {
+        self.0.lock().unwrap().iter().map(|a| a.kind).collect()
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    register_provider(&mut ledger, TRUSTED, "Desert Trials Consortium", "Regulator", T0).unwrap();
+    register_provider(&mut ledger, SHAKY, "QuickCert Clinics", "Host", T0).unwrap();
+    ledger
+}
+
+fn attestation(ledger: &TokenLedger, actor: &str, provider: &str, timestamp: i64) -> DeedEvent {
+    let mut deed = ClinicalAttestationDeed::builder()
+        .actor_id(actor)
+        .facility("Maricopa free clinic")
+        .procedure("vaccination")
+        .patients(40)
+        .evidence_uri("ipfs://clinic-log")
+        .provider_id(provider)
+        .build(ledger.last_hash())
+        .unwrap();
+    deed.timestamp = timestamp;
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+fn attest(ledger: &mut TokenLedger, actor: &str, provider: &str, timestamp: i64) -> String {
+    let deed = attestation(ledger, actor, provider, timestamp);
+    ledger.append(deed).unwrap().event_id.clone()
+}
+
+fn weight(ledger: &TokenLedger, event_id: &str) -> f64 {
+    ledger.deed(event_id).unwrap().context_json[PROVIDER_WEIGHT].as_f64().unwrap()
+}
+
+fn audit(ledger: &mut TokenLedger, event_id: &str, outcome: AttestationOutcome, now: i64, notifier: &Recorder) -> Option<String> {
+    record_audit(ledger, event_id, outcome, "Auditor", now, Some(notifier)).unwrap().suspended
+}
+
+#[test]
+fn attestations_count_at_their_providers_reliability() {
+    let mut ledger = ledger();
+    let alerts = Recorder::default();
+    let a1 = attest(&mut ledger, "alice", TRUSTED, T0);
+    let a2 = attest(&mut ledger, "alice", TRUSTED, T0);
+    let b1 = attest(&mut ledger, "bob", SHAKY, T0);
+    // Before any outcome both providers sit at the prior.
+    assert!(close(weight(&ledger, &a1), 0.8) && close(weight(&ledger, &b1), 0.8));
+
+    audit(&mut ledger, &a1, AttestationOutcome::Corroborated, T0, &alerts);
+    audit(&mut ledger, &a2, AttestationOutcome::Corroborated, T0, &alerts);
+    assert_eq!(audit(&mut ledger, &b1, AttestationOutcome::Contradicted, T0, &alerts), None);
+    // (0.8 * 2 + 2) / 4 and (0.8 * 2 + 0) / 3.
+    assert!(close(provider_reliability(&ledger, TRUSTED, T0).unwrap(), 0.9));
+    assert!(close(provider_reliability(&ledger, SHAKY, T0).unwrap(), 1.6 / 3.0));
+
+    let c1 = attest(&mut ledger, "carol", TRUSTED, T0);
+    let c2 = attest(&mut ledger, "carol", SHAKY, T0);
+    assert!(close(weight(&ledger, &c1), 0.9));
+    assert!(close(weight(&ledger, &c2), 1.6 / 3.0));
+    assert!(close(clin_trust(&ledger, "carol"), 0.70 + 0.04 * (0.9 + 1.6 / 3.0)));
+    // Alice's attestations were pinned at the prior; Bob's was contradicted.
+    assert!(close(clin_trust(&ledger, "alice"), 0.70 + 0.04 * 1.6));
+    assert!(close(clin_trust(&ledger, "bob"), 0.70));
+    assert!(alerts.kinds().is_empty());
+
+    // A month on, each outcome counts half: (1.6 + 1) / 3.
+    assert!(close(provider_reliability(&ledger, TRUSTED, T0 + 30 * DAY).unwrap(), 2.6 / 3.0));
+    // Past the window only the prior is left.
+    assert!(close(provider_reliability(&ledger, TRUSTED, T0 + 181 * DAY).unwrap(), 0.8));
+}
+
+#[test]
+fn contradictions_suspend_a_provider_below_the_floor() {
+    let mut ledger = ledger();
+    let alerts = Recorder::default();
+    let d1 = attest(&mut ledger, "dave", SHAKY, T0);
+    let d2 = attest(&mut ledger, "dave", SHAKY, T0);
+    let d3 = attest(&mut ledger, "dave", SHAKY, T0);
+
+    assert_eq!(audit(&mut ledger, &d1, AttestationOutcome::Contradicted, T0, &alerts), None);
+    // A day later the first outcome has barely decayed: 1.6 / (2 + 0.5^(1/30) + 1) < 0.5.
+    let suspension = audit(&mut ledger, &d2, AttestationOutcome::Contradicted, T0 + DAY, &alerts).unwrap();
+    let expected = 1.6 / (3.0 + 0.5f64.powf(1.0 / 30.0));
+    assert!(close(provider_reliability(&ledger, SHAKY, T0 + DAY).unwrap(), expected));
+    assert_eq!(ledger.deed(&suspension).unwrap().deed_type, PROVIDER_SUSPENDED);
+    {
+        let sent = alerts.0.lock().unwrap();
+        assert_eq!((sent[0].kind, sent[0].deed_event_id.as_str()), (ProviderAlertKind::Suspended, d2.as_str()));
+        assert!(close(sent[0].reliability, expected));
+    }
+    // Already suspended: further contradictions do not suspend again.
+    assert_eq!(audit(&mut ledger, &d3, AttestationOutcome::Contradicted, T0 + DAY, &alerts), None);
+    assert_eq!(providers(&ledger)[SHAKY].suspension.as_deref(), Some(suspension.as_str()));
+
+    // New attestations are accepted at zero weight and alert the operator.
+    let late = attest(&mut ledger, "dave", SHAKY, T0 + 2 * DAY);
+    let stored = ledger.deed(&late).unwrap();
+    assert_eq!(weight(&ledger, &late), 0.0);
+    assert_eq!(stored.context_json[PROVIDER_SUSPENSION], json!(suspension));
+    let alert = alert_if_suspended(&ledger, stored, Some(&alerts)).unwrap();
+    assert_eq!((alert.kind, alert.suspension_event_id.as_str()), (ProviderAlertKind::SuspendedAttestation, suspension.as_str()));
+    assert_eq!(alerts.kinds(), [ProviderAlertKind::Suspended, ProviderAlertKind::SuspendedAttestation]);
+    // Attestations by an unsuspended provider raise nothing.
+    let fine = attest(&mut ledger, "dave", TRUSTED, T0 + 2 * DAY);
+    assert!(alert_if_suspended(&ledger, ledger.deed(&fine).unwrap(), Some(&alerts)).is_none());
+    assert!(close(clin_trust(&ledger, "dave"), 0.70 + 0.04 * 0.8));
+}
+
+#[test]
+fn weights_are_pinned_at_the_time_of_the_deed() {
+    let mut ledger = ledger();
+    let alerts = Recorder::default();
+    let early = attest(&mut ledger, "erin", TRUSTED, T0);
+    let trust_before = clin_trust(&ledger, "erin");
+    let others: Vec<String> = (0..2).map(|_| attest(&mut ledger, "frank", TRUSTED, T0)).collect();
+
+    // Later decay of the provider leaves Erin's earlier deed alone.
+    audit(&mut ledger, &others[0], AttestationOutcome::Contradicted, T0 + DAY, &alerts);
+    assert!(close(weight(&ledger, &early), 0.8));
+    assert_eq!(clin_trust(&ledger, "erin"), trust_before);
+
+    // A deed is weighed as of its own timestamp, not when it is appended,
+    // and a weight the submitter wrote in is overwritten.
+    let mut backdated = attestation(&ledger, "erin", TRUSTED, T0 + DAY - 1);
+    backdated.context_json[PROVIDER_WEIGHT] = json!(1.0);
+    backdated.self_hash = String::new();
+    backdated.self_hash = hash_deed(&backdated);
+    let backdated = ledger.append(backdated).unwrap().event_id.clone();
+    assert!(close(weight(&ledger, &backdated), 0.8));
+    let current = attest(&mut ledger, "erin", TRUSTED, T0 + DAY);
+    assert!(close(weight(&ledger, &current), 1.6 / 3.0));
+
+    // Nor does a suspension reach back to the deeds made before it.
+    assert!(audit(&mut ledger, &others[1], AttestationOutcome::Contradicted, T0 + DAY, &alerts).is_some());
+    let after = attest(&mut ledger, "erin", TRUSTED, T0 + 2 * DAY);
+    assert_eq!(weight(&ledger, &after), 0.0);
+    assert!(close(weight(&ledger, &current), 1.6 / 3.0));
+    assert!(close(clin_trust(&ledger, "erin"), 0.70 + 0.04 * (0.8 + 0.8 + 1.6 / 3.0)));
+}
+
+#[test]
+fn a_reinstated_provider_starts_again_from_the_prior() {
+    let mut ledger = ledger();
+    let alerts = Recorder::default();
+    let ids: Vec<String> = (0..3).map(|_| attest(&mut ledger, "gina", SHAKY, T0)).collect();
+    audit(&mut ledger, &ids[0], AttestationOutcome::Contradicted, T0, &alerts);
+    let suspension = audit(&mut ledger, &ids[1], AttestationOutcome::Contradicted, T0, &alerts).unwrap();
+
+    assert_eq!(
+        reinstate_provider(&mut ledger, SHAKY, "Volunteer", "retrained staff", T0 + DAY),
+        Err(ProviderError::RoleNotAllowed("Volunteer".into()))
+    );
+    assert_eq!(
+        reinstate_provider(&mut ledger, TRUSTED, "Regulator", "never suspended", T0 + DAY),
+        Err(ProviderError::NotSuspended(TRUSTED.into()))
+    );
+    assert_eq!(
+        reinstate_provider(&mut ledger, "provider:nobody", "Regulator", "", T0 + DAY),
+        Err(ProviderError::UnknownProvider("provider:nobody".into()))
+    );
+    let reinstated = reinstate_provider(&mut ledger, SHAKY, "Regulator", "retrained staff", T0 + DAY).unwrap();
+    let reinstatement = ledger.deed(&reinstated).unwrap();
+    assert_eq!(reinstatement.context_json["suspension_event_id"], json!(suspension));
+
+    let provider = &providers(&ledger)[SHAKY];
+    assert!(!provider.suspended() && provider.outcomes.is_empty());
+    assert!(close(provider_reliability(&ledger, SHAKY, T0 + DAY).unwrap(), 0.8));
+    let fresh = attest(&mut ledger, "gina", SHAKY, T0 + DAY);
+    assert!(close(weight(&ledger, &fresh), 0.8));
+    assert!(ledger.deed(&fresh).unwrap().context_json.get(PROVIDER_SUSPENSION).is_none());
+    assert_eq!(
+        reinstate_provider(&mut ledger, SHAKY, "Regulator", "again", T0 + DAY),
+        Err(ProviderError::NotSuspended(SHAKY.into()))
+    );
+
+    // Only outcomes since the reinstatement count: one contradiction is not enough.
+    assert_eq!(audit(&mut ledger, &ids[2], AttestationOutcome::Contradicted, T0 + DAY, &alerts), None);
+    assert!(close(provider_reliability(&ledger, SHAKY, T0 + DAY).unwrap(), 1.6 / 3.0));
+    assert_eq!(alerts.kinds(), [ProviderAlertKind::Suspended]);
+}
+
+#[test]
+fn registration_audits_and_sweeps_are_checked() {
+    let mut ledger = ledger();
+    assert_eq!(
+        register_provider(&mut ledger, TRUSTED, "again", "Regulator", T0).unwrap_err(),
+        ProviderError::AlreadyRegistered(TRUSTED.into())
+    );
+    assert_eq!(
+        register_provider(&mut ledger, "provider:new", "New", "Volunteer", T0).unwrap_err(),
+        ProviderError::RoleNotAllowed("Volunteer".into())
+    );
+    let unknown = attestation(&ledger, "hana", "provider:unknown", T0);
+    assert_eq!(ledger.append(unknown).unwrap_err(), TokenLedgerError::UnregisteredProvider("provider:unknown".into()));
+    let forged = DeedEvent::new(ledger.last_hash(), "hana".into(), vec![SHAKY.into()], PROVIDER_SUSPENDED.into(), vec![], json!({}), vec![], false);
+    assert_eq!(ledger.append(forged).unwrap_err(), TokenLedgerError::ReservedDeedType(PROVIDER_SUSPENDED.into()));
+
+    let id = attest(&mut ledger, "hana", SHAKY, T0);
+    assert_eq!(
+        record_audit(&mut ledger, &id, AttestationOutcome::Corroborated, "Host", T0, None).unwrap_err(),
+        ProviderError::NotAuditor("Host".into())
+    );
+    let planting = DeedEvent::new(ledger.last_hash(), "hana".into(), vec![], "tree_planting".into(), vec![], json!({}), vec![], false);
+    let planting = ledger.append(planting).unwrap().event_id.clone();
+    assert_eq!(
+        record_audit(&mut ledger, &planting, AttestationOutcome::Corroborated, "Auditor", T0, None).unwrap_err(),
+        ProviderError::NotAnAttestation(planting)
+    );
+
+    // A slashed attestation is contradicted by the scheduled sweep, once.
+    ledger.tombstone(&id, "forged patient count", "Regulator").unwrap();
+    let mut jobs = RecurringJobs::with_defaults(&ledger, T0);
+    assert!(jobs.run_due(&mut ledger, T0 + DAY).contains(&"score_providers".to_string()));
+    let outcomes = &providers(&ledger)[SHAKY].outcomes;
+    assert_eq!((outcomes.len(), outcomes[0].source.as_str()), (1, TOMBSTONE_SOURCE));
+    assert_eq!(outcomes[0].outcome, AttestationOutcome::Contradicted);
+    assert!(sweep_outcomes(&mut ledger, T0 + 2 * DAY, None).unwrap().is_empty());
+    assert!(attestation_weights(&ledger, "hana").is_empty());
+}
+
+#[test]
+fn replay_rebuilds_providers_scores_and_suspensions() {
+    let mut ledger = ledger();
+    let alerts = Recorder::default();
+    let ids: Vec<String> = (0..4).map(|i| attest(&mut ledger, "ivan", SHAKY, T0 + i)).collect();
+    attest(&mut ledger, "jo", TRUSTED, T0);
+    audit(&mut ledger, &ids[0], AttestationOutcome::Corroborated, T0 + 10, &alerts);
+    audit(&mut ledger, &ids[1], AttestationOutcome::Contradicted, T0 + 20, &alerts);
+    audit(&mut ledger, &ids[2], AttestationOutcome::Contradicted, T0 + 30, &alerts);
+    audit(&mut ledger, &ids[3], AttestationOutcome::Contradicted, T0 + 40, &alerts);
+    assert!(providers(&ledger)[SHAKY].suspended());
+    attest(&mut ledger, "ivan", SHAKY, T0 + 50);
+    reinstate_provider(&mut ledger, SHAKY, "Host", "audit closed", T0 + DAY).unwrap();
+    // A later outcome for the same attestation replaces the earlier one.
+    audit(&mut ledger, &ids[1], AttestationOutcome::Corroborated, T0 + DAY, &alerts);
+    attest(&mut ledger, "ivan", SHAKY, T0 + DAY);
+
+    let replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().iter().cloned()).unwrap();
+    assert_eq!(providers(&replayed), providers(&ledger));
+    for actor in ["ivan", "jo"] {
+        assert_eq!(attestation_weights(&replayed, actor), attestation_weights(&ledger, actor));
+        assert_eq!(clin_trust(&replayed, actor), clin_trust(&ledger, actor));
+    }
+    for provider in [TRUSTED, SHAKY] {
+        assert_eq!(provider_reliability(&replayed, provider, T0 + DAY), provider_reliability(&ledger, provider, T0 + DAY));
+    }
+    assert_eq!(replayed.supply_report(), ledger.supply_report());
+}
@@ -0,0 +1,304 @@
+#![cfg(feature = "validation-quorum")]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::identity::{bind_actor_key, KeyChange};
+use church_of_fear::ledger::builders::ClinicalAttestationDeed;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::obligations::PENDING_OBLIGATIONS;
+use church_of_fear::providers::register_provider;
+use church_of_fear::quorum::{
+    cast_vote, hold_if_required, register_validator, sweep_expired, validation_status, validators,
+    QuorumError, ValidationOutcome, ValidationStatus, ValidationVote, VoteReceipt,
+};
+use church_of_fear::scheduler::RecurringJobs;
+use keyring::Keyring;
+
+const T: i64 = 1_700_000_000;
+const DAY: i64 = 86_400;
+const CLINIC: &str = "provider:maricopa-free-clinic";
+
+/// Validators with bound actor keys, signing on a clock the test moves.
+struct Panel {
+    clock: Arc<AtomicU64>,
+    keys: Keyring,
+    names: Vec<(String, String)>,
+}
+
+impl Panel {
+    fn new(ledger: &mut TokenLedger, validators: &[(&str, f64)]) -> Self {
+        let clock = Arc::new(AtomicU64::new(T as u64));
+        let c = clock.clone();
+        let mut keys = Keyring::new().with_clock(move || c.load(Ordering::SeqCst));
+        let mut names = Vec::new();
+        for &(id, mp_score) in validators {
+            let name = keys.generate("actor").unwrap();
+            let key = keys.keys().find(|k| k.name == name).unwrap().clone();
+            let proof = keys.sign(&name, &KeyChange::new(id, &key).signing_bytes()).unwrap();
+            bind_actor_key(ledger, id, &key, &proof, T).unwrap();
+            register_validator(ledger, id, mp_score, "Regulator", T).unwrap();
+            names.push((id.to_string(), name));
+        }
+        Self { clock, keys, names }
+    }
+
+    fn at(&self, now: i64) {
+        self.clock.store(now as u64, Ordering::SeqCst);
+    }
+
+    fn vote(
+        &self,
+        ledger: &mut TokenLedger,
+        validator: &str,
+        deed: &str,
+        approve: bool,
+        now: i64,
+    ) -> Result<VoteReceipt, QuorumError> {
+        self.at(now);
+        let vote = ValidationVote {
+            deed_event_id: deed.to_string(),
+            validator_id: validator.to_string(),
+            approve,
+            reason: if approve { "records check out" } else { "evidence does not match" }.to_string(),
+        };
+        let (_, key) = self.names.iter().find(|(id, _)| id == validator).unwrap();
+        cast_vote(ledger, &vote, &self.keys.sign(key, &vote.signing_bytes()).unwrap(), now)
+    }
+}
+
+/// A ledger that knows the clinic the attestations name.
+fn clinic_ledger(cfg: LedgerConfig) -> TokenLedger {
+    let mut ledger = TokenLedger::new(cfg);
+    register_provider(&mut ledger, CLINIC, "Maricopa free clinic", "Regulator", T).unwrap();
+    ledger
+}
+
+/// A clinical attestation by `actor`, held with a 50 CHURCH reward at `now`.
+fn attest(ledger: &mut TokenLedger, actor: &str, now: i64) -> String {
+    let deed = ClinicalAttestationDeed::builder()
+        .actor_id(actor)
+        .facility("Maricopa free clinic")
+        .procedure("vaccination")
+        .patients(40)
+        .evidence_uri("ipfs://clinic-log")
+        .provider_id(CLINIC)
+        .build(ledger.last_hash())
+        .unwrap();
+    let event_id = ledger.append(deed).unwrap().event_id.clone();
+    assert!(hold_if_required(ledger, &event_id, 50, now).unwrap().is_some());
+    event_id
+}
+
+fn church(ledger: &TokenLedger, id: &str) -> u64 {
+    ledger.account(id).map_or(0, |a| a.balance_church)
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn quorum_of_approvals_mints_the_escrowed_reward() {
+    use church_of_fear::quorum::pending_validations;
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+
+    let mut ledger = clinic_ledger(LedgerConfig::default());
+    let panel = Panel::new(&mut ledger, &[("val-a", 0.8), ("val-b", 0.7), ("val-c", 0.9), ("val-d", 0.9)]);
+
+    // Submitted over RPC, the deed is stored but its reward is held.
+    let deed = ClinicalAttestationDeed::builder()
+        .actor_id("alice")
+        .facility("Maricopa free clinic")
+        .procedure("vaccination")
+        .patients(40)
+        .evidence_uri("ipfs://clinic-log")
+        .provider_id(CLINIC)
+        .build(ledger.last_hash())
+        .unwrap();
+    let ledger = Arc::new(Mutex::new(ledger));
+    let ctx = RpcContext { ledger: Some(ledger.clone()), ..RpcContext::default() };
+    let mint = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.mint_deed",
+        "params": {
+            "prev_hash": deed.prev_hash, "actor_id": deed.actor_id, "target_ids": [], "deed_type": deed.deed_type,
+            "tags": deed.tags, "context_json": deed.context_json, "ethics_flags": [], "life_harm_flag": false,
+            "bioload_delta": -0.1, "roh": 0.1, "decay": 0.2
+        },
+        "id": 1
+    });
+    let minted: Value = serde_json::from_str(&dispatch_request_with(&mint.to_string(), &ctx)).unwrap();
+    assert_eq!(minted["result"]["church_minted"], 0);
+    let pending_id = minted["result"]["pending_validation"].as_str().unwrap().to_string();
+    let submitted = minted["result"]["deed"]["event_id"].as_str().unwrap().to_string();
+    let status = validation_status(&ledger.lock().unwrap(), &submitted).unwrap();
+    assert_eq!((status.pending_id.as_str(), status.required, status.status), (pending_id.as_str(), 3, ValidationStatus::Pending));
+
+    // A second deed with a real reward, voted on directly and over RPC.
+    let mut guard = ledger.lock().unwrap();
+    let event_id = attest(&mut guard, "alice", T);
+    assert_eq!((church(&guard, PENDING_OBLIGATIONS), church(&guard, "alice")), (50, 0));
+    let first = panel.vote(&mut guard, "val-a", &event_id, true, T + 60).unwrap();
+    assert_eq!((first.outcome, first.minted), (None, 0));
+    let rejected = panel.vote(&mut guard, "val-b", &event_id, false, T + 120).unwrap();
+    assert_eq!(rejected.outcome, None);
+    assert!(matches!(panel.vote(&mut guard, "val-a", &event_id, true, T + 130), Err(QuorumError::AlreadyVoted(_))));
+    panel.vote(&mut guard, "val-c", &event_id, true, T + 180).unwrap();
+    drop(guard);
+
+    panel.at(T + 240);
+    let vote = ValidationVote {
+        deed_event_id: event_id.clone(),
+        validator_id: "val-d".into(),
+        approve: true,
+        reason: "matches the clinic register".into(),
+    };
+    let signature = panel.keys.sign(&panel.names[3].1, &vote.signing_bytes()).unwrap();
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.cast_validation_vote",
+        "params": { "vote": vote, "signature": signature, "now": T + 240 },
+        "id": 2
+    });
+    let reply: Value = serde_json::from_str(&dispatch_request_with(&request.to_string(), &ctx)).unwrap();
+    assert_eq!(reply["result"]["outcome"], "approved");
+    assert_eq!(reply["result"]["minted"], 50);
+
+    let ledger = ledger.lock().unwrap();
+    assert_eq!((church(&ledger, PENDING_OBLIGATIONS), church(&ledger, "alice")), (0, 50));
+    let status = validation_status(&ledger, &event_id).unwrap();
+    assert_eq!((status.approvals(), status.rejections()), (3, 1));
+    assert!(matches!(status.status, ValidationStatus::Resolved { outcome: ValidationOutcome::Approved, .. }));
+    assert!(ledger.supply_report().reconciles());
+
+    // Votes, resolutions and scores are all read back from the chain.
+    let replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().iter().cloned()).unwrap();
+    assert_eq!(pending_validations(&replayed), pending_validations(&ledger));
+    assert_eq!(validators(&replayed), validators(&ledger));
+}
+
+#[test]
+fn rejections_that_make_the_quorum_unreachable_reject_the_deed() {
+    let mut ledger = clinic_ledger(LedgerConfig::default());
+    let panel = Panel::new(&mut ledger, &[("val-a", 0.8), ("val-b", 0.8), ("val-c", 0.8), ("val-d", 0.8), ("novice", 0.55)]);
+    let event_id = attest(&mut ledger, "alice", T);
+
+    // Standing, but below this category's minimum: neither votes nor counts.
+    assert!(matches!(
+        panel.vote(&mut ledger, "novice", &event_id, true, T + 10),
+        Err(QuorumError::BelowReputation { min, .. }) if min == 0.6
+    ));
+    let own =
+        ValidationVote { deed_event_id: event_id.clone(), validator_id: "alice".into(), approve: true, reason: String::new() };
+    let signature = panel.keys.sign(&panel.names[0].1, &own.signing_bytes()).unwrap();
+    assert!(matches!(cast_vote(&mut ledger, &own, &signature, T + 10), Err(QuorumError::NotValidator(_))));
+
+    panel.vote(&mut ledger, "val-a", &event_id, true, T + 60).unwrap();
+    assert_eq!(panel.vote(&mut ledger, "val-b", &event_id, false, T + 120).unwrap().outcome, None);
+    // One approval plus one outstanding validator can no longer make three.
+    let last = panel.vote(&mut ledger, "val-c", &event_id, false, T + 180).unwrap();
+    assert_eq!((last.outcome, last.minted), (Some(ValidationOutcome::Rejected), 0));
+    assert!(matches!(panel.vote(&mut ledger, "val-d", &event_id, true, T + 240), Err(QuorumError::Resolved(_))));
+
+    assert_eq!((church(&ledger, PENDING_OBLIGATIONS), church(&ledger, "alice")), (0, 0));
+    assert!(ledger.supply_report().reconciles());
+    let scores = validators(&ledger);
+    assert_eq!((scores["val-a"].opposed, scores["val-b"].aligned, scores["val-c"].aligned), (1, 1, 1));
+}
+
+#[test]
+fn stalled_validations_expire_and_return_their_escrow_to_the_pool() {
+    let mut ledger = clinic_ledger(LedgerConfig::default());
+    let panel = Panel::new(&mut ledger, &[("val-a", 0.8), ("val-b", 0.8), ("val-c", 0.8)]);
+    let event_id = attest(&mut ledger, "alice", T);
+    panel.vote(&mut ledger, "val-a", &event_id, true, T + 60).unwrap();
+    let deadline = T + 7 * DAY;
+
+    assert!(sweep_expired(&mut ledger, deadline - 1).unwrap().is_empty());
+    assert!(matches!(
+        panel.vote(&mut ledger, "val-b", &event_id, true, deadline),
+        Err(QuorumError::Expired { deadline: d, .. }) if d == deadline
+    ));
+    let pool = ledger.pool_balance();
+
+    // The scheduler runs the sweep.
+    let mut jobs = RecurringJobs::with_defaults(&ledger, deadline - DAY);
+    assert!(jobs.run_due(&mut ledger, deadline).contains(&"expire_validations".to_string()));
+    let status = validation_status(&ledger, &event_id).unwrap();
+    assert_eq!(
+        status.status,
+        ValidationStatus::Resolved {
+            outcome: ValidationOutcome::Expired,
+            resolution_event_id: ledger.deeds().last().unwrap().event_id.clone(),
+            resolved_at: deadline,
+        }
+    );
+    assert_eq!((church(&ledger, PENDING_OBLIGATIONS), church(&ledger, "alice"), ledger.pool_balance()), (0, 0, pool + 50));
+    assert!(sweep_expired(&mut ledger, deadline + DAY).unwrap().is_empty());
+    // Expiry judges nobody: the early approval neither gains nor loses.
+    assert_eq!((validators(&ledger)["val-a"].aligned, validators(&ledger)["val-a"].opposed), (0, 0));
+    assert!(ledger.supply_report().reconciles());
+}
+
+#[test]
+fn vote_credit_is_capped_per_window() {
+    let mut ledger = clinic_ledger(LedgerConfig::default());
+    let panel = Panel::new(&mut ledger, &[("val-a", 0.8), ("val-b", 0.8), ("val-c", 0.8)]);
+    let deeds: Vec<String> = (0..7).map(|i| attest(&mut ledger, "alice", T + i)).collect();
+
+    // 2 CHURCH a vote up to 10 a day, then nothing until the window moves on.
+    let credits: Vec<u64> = deeds[..6]
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            panel.vote(&mut ledger, "val-a", d, true, T + 100 + i as i64).unwrap();
+            validation_status(&ledger, d).unwrap().votes[0].credit
+        })
+        .collect();
+    assert_eq!(credits, [2, 2, 2, 2, 2, 0]);
+    let credited_a = church(&ledger, "val-a");
+    assert!(credited_a > 0 && credited_a <= 10);
+
+    panel.vote(&mut ledger, "val-a", &deeds[6], true, T + DAY + 200).unwrap();
+    assert_eq!(validation_status(&ledger, &deeds[6]).unwrap().votes[0].credit, 2);
+    // Another validator's cap is their own.
+    panel.vote(&mut ledger, "val-b", &deeds[0], true, T + 200).unwrap();
+    assert_eq!(validation_status(&ledger, &deeds[0]).unwrap().votes[1].credit, 2);
+}
+
+#[test]
+fn contrarian_validators_lose_standing() {
+    let mut cfg = LedgerConfig::default();
+    cfg.quorum.reputation_floor = 0.65;
+    let mut ledger = clinic_ledger(cfg);
+    let panel = Panel::new(&mut ledger, &[("val-a", 0.8), ("val-b", 0.8), ("val-c", 0.8), ("contrarian", 0.8)]);
+
+    for i in 0..2 {
+        let event_id = attest(&mut ledger, "alice", T + i);
+        panel.vote(&mut ledger, "contrarian", &event_id, false, T + 100 + i).unwrap();
+        for v in ["val-a", "val-b", "val-c"] {
+            panel.vote(&mut ledger, v, &event_id, true, T + 200 + i).unwrap();
+        }
+        assert!(matches!(
+            validation_status(&ledger, &event_id).unwrap().status,
+            ValidationStatus::Resolved { outcome: ValidationOutcome::Approved, .. }
+        ));
+    }
+
+    let scores = validators(&ledger);
+    assert_eq!((scores["contrarian"].opposed, scores["contrarian"].standing), (2, false));
+    assert!(scores["contrarian"].mp_score < 0.65);
+    assert_eq!((scores["val-a"].aligned, scores["val-a"].standing), (2, true));
+    assert!((scores["val-a"].mp_score - 0.84).abs() < 1e-9);
+
+    let next = attest(&mut ledger, "alice", T + 10);
+    assert!(matches!(
+        panel.vote(&mut ledger, "contrarian", &next, false, T + 300),
+        Err(QuorumError::NoStanding { validator_id, .. }) if validator_id == "contrarian"
+    ));
+    // Without standing they no longer count toward reachability either:
+    // with three eligible validators left, one rejection settles it.
+    let settled = panel.vote(&mut ledger, "val-a", &next, false, T + 310).unwrap();
+    assert_eq!(settled.outcome, Some(ValidationOutcome::Rejected));
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::compliance::god_like::GodLikeEnvelope;
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::token::repair_curve::{
+    assess_repair_grant, grant_repair_pwr, CurveShape, RepairClaim, RepairGrantError, RepairRewardCurve, WindowState,
+    REPAIR_GRANT,
+};
+
+const T0: i64 = 1_700_000_000;
+
+fn ledger(curve: RepairRewardCurve) -> TokenLedger {
+    let cfg = LedgerConfig { repair_curve: curve, ..LedgerConfig::default() };
+    let mut ledger = TokenLedger::new(cfg);
+    ledger.open_account("alice", "alice");
+    ledger.mint_reward("alice", Token::Church, 10_000).unwrap();
+    ledger
+}
+
+fn claim(score: f64, n: usize) -> RepairClaim {
+    RepairClaim {
+        actor_id: "alice".to_string(),
+        source_event_id: format!("repair-{}", n),
+        impact_score: score,
+        evidence_verified: true,
+    }
+}
+
+#[test]
+fn curve_is_continuous_across_the_old_cliff() {
+    let linear = RepairRewardCurve::default();
+    let logistic = RepairRewardCurve {
+        shape: CurveShape::Logistic { max_pwr: 120.0, midpoint: 0.7, steepness: 10.0 },
+        ..RepairRewardCurve::default()
+    };
+    for curve in [linear.clone(), logistic] {
+        curve.validate().unwrap();
+        let (below, at, above) = (curve.value(0.79), curve.value(0.8), curve.value(0.81));
+        assert!(below > 0.0, "0.79 earns something");
+        assert!(below <= at && at <= above);
+        assert!(above - below < 5.0, "{below} -> {above}");
+        let samples: Vec<f64> = (0..=100).map(|i| curve.value(i as f64 / 100.0)).collect();
+        assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+    }
+    assert!((linear.value(0.5) - 40.0).abs() < 1e-9);
+    assert_eq!(linear.value(0.0), 0.0);
+    assert_eq!(linear.value(1.0), 120.0);
+
+    let unsorted = RepairRewardCurve {
+        shape: CurveShape::PiecewiseLinear { points: vec![(0.5, 50.0), (0.4, 60.0)] },
+        ..RepairRewardCurve::default()
+    };
+    assert!(matches!(unsorted.validate(), Err(RepairGrantError::InvalidCurve(_))));
+}
+
+#[test]
+fn repeat_repairs_in_the_window_earn_decaying_fractions() {
+    let curve = RepairRewardCurve { window_cap: 10_000, ..RepairRewardCurve::default() };
+    let mut ledger = ledger(curve);
+    let env = GodLikeEnvelope::default();
+    let amounts: Vec<u64> =
+        (0..4).map(|i| grant_repair_pwr(&mut ledger, &claim(1.0, i), &env, T0 + i as i64 * 60).unwrap().amount).collect();
+    // 120 · 0.5^n, floored.
+    assert_eq!(amounts, [120, 60, 30, 15]);
+
+    let fifth = assess_repair_grant(&ledger, &claim(1.0, 4), T0 + 300).unwrap();
+    assert_eq!(fifth.window, WindowState { prior_grants: 4, granted: 225 });
+    assert_eq!(fifth.multiplier, 0.0625);
+
+    // Once the window has rolled past every earlier grant the full value is back.
+    let later = assess_repair_grant(&ledger, &claim(1.0, 5), T0 + 86_400 + 180).unwrap();
+    assert_eq!(later.window, WindowState::default());
+    assert_eq!(later.amount, 120);
+    assert_eq!(ledger.account("alice").unwrap().balance(Token::Pwr), 225);
+}
+
+#[test]
+fn window_cap_limits_total_grants() {
+    let curve = RepairRewardCurve { decay: 1.0, window_cap: 250, ..RepairRewardCurve::default() };
+    let mut ledger = ledger(curve);
+    let env = GodLikeEnvelope::default();
+    let amounts: Vec<u64> =
+        (0..4).map(|i| grant_repair_pwr(&mut ledger, &claim(1.0, i), &env, T0 + i as i64).unwrap().amount).collect();
+    assert_eq!(amounts, [120, 120, 10, 0]);
+    // The zero grant is not logged.
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == REPAIR_GRANT).count(), 3);
+}
+
+#[test]
+fn unverified_high_impact_and_power_cap_are_refused() {
+    let mut ledger = ledger(RepairRewardCurve::default());
+    let unverified = RepairClaim { evidence_verified: false, ..claim(0.9, 0) };
+    assert!(matches!(
+        grant_repair_pwr(&mut ledger, &unverified, &GodLikeEnvelope::default(), T0),
+        Err(RepairGrantError::UnverifiedEvidence { .. })
+    ));
+    let low = RepairClaim { evidence_verified: false, ..claim(0.5, 0) };
+    assert_eq!(grant_repair_pwr(&mut ledger, &low, &GodLikeEnvelope::default(), T0).unwrap().amount, 40);
+
+    // k = 0.01 allows 100 PWR against 10 000 CHURCH; 40 is held, 120 more is too many.
+    let tight = GodLikeEnvelope { power_church_k: 0.01, ..GodLikeEnvelope::default() };
+    let before = ledger.deeds().len();
+    let err = grant_repair_pwr(&mut ledger, &claim(1.0, 1), &tight, T0 + 86_400).unwrap_err();
+    assert_eq!(err, RepairGrantError::PowerCap { account_id: "alice".into(), grant: 120, power: 160, cap: 100 });
+    assert_eq!(ledger.deeds().len(), before);
+    assert_eq!(ledger.account("alice").unwrap().balance(Token::Pwr), 40);
+}
+
+#[test]
+fn window_state_replays_from_the_ledger() {
+    let mut ledger = ledger(RepairRewardCurve::default());
+    let env = GodLikeEnvelope::default();
+    for i in 0..3 {
+        grant_repair_pwr(&mut ledger, &claim(0.8, i), &env, T0 + i as i64 * 600).unwrap();
+    }
+    let grant = ledger.deeds().iter().rfind(|d| d.deed_type == REPAIR_GRANT).unwrap();
+    assert_eq!(grant.context_json["curve_version"], 1);
+    assert_eq!(grant.context_json["window"]["prior_grants"], 2);
+    assert_eq!(grant.context_json["multiplier"], 0.25);
+
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    let now = T0 + 3_600;
+    assert_eq!(WindowState::at(&replayed, "alice", 86_400, now), WindowState::at(&ledger, "alice", 86_400, now));
+    assert_eq!(
+        assess_repair_grant(&replayed, &claim(0.8, 3), now).unwrap(),
+        assess_repair_grant(&ledger, &claim(0.8, 3), now).unwrap()
+    );
+    assert_eq!(replayed.account("alice").unwrap().balance(Token::Pwr), 80 + 40 + 20);
+}
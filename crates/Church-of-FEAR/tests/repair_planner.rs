@@ -0,0 +1,126 @@
+use church_of_fear::compliance::ethics::EthicsSummary;
+use church_of_fear::compliance::god_like::{Constraint, GodLikeEnvelope, GodLikeReport};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::repair_planner::{
+    RepairActionKind, RepairConfig, RepairPlanError, RepairPlanner, REPAIR_ACTION, REPAIR_PLAN,
+};
+use serde_json::json;
+
+fn calm() -> EthicsSummary {
+    EthicsSummary { bioload: 0.1, bioload_variance: 0.0, mean_trust: 0.9, power_gini: 0.2, ..EthicsSummary::default() }
+}
+
+fn overloaded(bioload: f64) -> EthicsSummary {
+    EthicsSummary { bioload, ..calm() }
+}
+
+/// alice holds 500 POWER against 100 CHURCH; bob is within the cap.
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    for id in ["alice", "bob"] {
+        ledger.open_account(id, id);
+        ledger.mint_reward(id, Token::Church, 100).unwrap();
+    }
+    ledger.mint_reward("alice", Token::Pwr, 500).unwrap();
+    ledger.mint_reward("bob", Token::Pwr, 50).unwrap();
+    ledger
+}
+
+fn balanced_ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.mint_reward("alice", Token::Church, 100).unwrap();
+    ledger
+}
+
+fn explain(ledger: &TokenLedger, summary: &EthicsSummary) -> GodLikeReport {
+    GodLikeReport::explain(ledger, summary, &GodLikeEnvelope::default())
+}
+
+fn kinds(planner: &RepairPlanner) -> Vec<RepairActionKind> {
+    planner.current().unwrap().actions.iter().map(|a| a.kind).collect()
+}
+
+#[test]
+fn power_cap_and_bioload_violations_get_different_plans() {
+    let mut power = ledger();
+    let report = explain(&power, &calm());
+    assert_eq!(report.over_cap.len(), 1);
+    assert_eq!(report.over_cap[0].excess, 400);
+    let mut planner = RepairPlanner::new(RepairConfig::default());
+    planner.plan(&mut power, &calm(), &report).unwrap();
+    assert_eq!(kinds(&planner), [RepairActionKind::BurnExcessPower]);
+
+    let mut bio = balanced_ledger();
+    let report = explain(&bio, &overloaded(0.9));
+    assert!(report.violation(Constraint::PowerCap).is_none());
+    let mut planner = RepairPlanner::new(RepairConfig::default());
+    planner.plan(&mut bio, &overloaded(0.9), &report).unwrap();
+    let bio_kinds = kinds(&planner);
+    assert!(!bio_kinds.contains(&RepairActionKind::BurnExcessPower));
+    assert!(bio_kinds.contains(&RepairActionKind::TightenFearBand));
+    assert!(bio_kinds.contains(&RepairActionKind::ScheduleEcoScan));
+}
+
+#[test]
+fn actions_are_ranked_by_estimated_effect() {
+    let mut ledger = balanced_ledger();
+    let parade = DeedEvent::new(ledger.last_hash(), "carol".into(), vec![], "parade".into(), vec![], json!({}), vec![], false);
+    ledger.append(parade).unwrap();
+
+    let summary = overloaded(0.9);
+    let report = explain(&ledger, &summary);
+    let mut planner = RepairPlanner::new(RepairConfig::default());
+    let plan = planner.plan(&mut ledger, &summary, &report).unwrap();
+
+    let order: Vec<&str> = plan.actions.iter().map(|a| a.action_id.as_str()).collect();
+    assert_eq!(order, ["defer_non_restorative_rewards", "tighten_fear_band", "schedule_eco_scan"]);
+    assert!(plan.actions.windows(2).all(|w| w[0].score >= w[1].score));
+    let top = &plan.actions[0].effects[0];
+    assert_eq!(top.constraint, Constraint::BioloadCeiling);
+    assert!((top.delta + 0.04).abs() < 1e-9);
+}
+
+#[test]
+fn material_change_supersedes_the_plan() {
+    let mut ledger = balanced_ledger();
+    let mut planner = RepairPlanner::new(RepairConfig::default());
+
+    let mut plan_for = |ledger: &mut TokenLedger, bioload: f64| {
+        let report = explain(ledger, &overloaded(bioload));
+        planner.plan(ledger, &overloaded(bioload), &report).unwrap().clone()
+    };
+    let first = plan_for(&mut ledger, 0.9).plan_id;
+    assert_eq!(plan_for(&mut ledger, 0.905).plan_id, first);
+    let second = plan_for(&mut ledger, 0.95);
+    assert_ne!(second.plan_id, first);
+    assert_eq!(second.supersedes.as_deref(), Some(first.as_str()));
+    assert_eq!(planner.plan_by_id(&first).unwrap().superseded_by.as_deref(), Some(second.plan_id.as_str()));
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == REPAIR_PLAN).count(), 2);
+
+    let err = planner.execute(&mut ledger, &first, "tighten_fear_band", "Regulator").unwrap_err();
+    assert!(matches!(err, RepairPlanError::Superseded { .. }));
+}
+
+#[test]
+fn executing_an_action_links_back_to_the_plan() {
+    let mut ledger = ledger();
+    let report = explain(&ledger, &calm());
+    let mut planner = RepairPlanner::new(RepairConfig::default());
+    let plan = planner.plan(&mut ledger, &calm(), &report).unwrap().clone();
+
+    let err = planner.execute(&mut ledger, &plan.plan_id, "burn_excess_power", "Host").unwrap_err();
+    assert!(matches!(err, RepairPlanError::RoleNotAllowed { .. }));
+
+    let deed = planner.execute(&mut ledger, &plan.plan_id, "burn_excess_power", "Regulator").unwrap().clone();
+    assert_eq!(deed.deed_type, REPAIR_ACTION);
+    assert_eq!(deed.context_json["plan_id"], json!(plan.plan_id));
+    assert_eq!(deed.context_json["plan_deed_hash"], json!(plan.deed_hash));
+    assert_eq!(deed.context_json["outcome"]["burned"][0], json!({ "account_id": "alice", "amount": 400 }));
+    assert!(ledger.deeds().iter().any(|d| Some(&d.self_hash) == plan.deed_hash.as_ref()));
+    assert_eq!(ledger.account("alice").unwrap().balance(Token::Pwr), 100);
+    assert!(explain(&ledger, &calm()).is_clean());
+}
@@ -0,0 +1,229 @@
+#![cfg(feature = "replica")]
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use church_of_fear::ledger::schema::SCHEMA_VERSION;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::replica::{
+    dispatch_replica_request, healthz, PrimaryTransport, Replica, ReplicaConfig, ReplicaError, REPLICA_READ_ONLY,
+};
+use church_of_fear::rpc::server::RpcContext;
+use church_of_fear::tip_gossip::{AlertNotifier, DivergenceAlert, GossipConfig, TipGossip};
+use keyring::{Keyring, VerifyingBundle};
+use serde_json::{json, Value};
+
+const NOW: i64 = 1_000;
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<DivergenceAlert>>>);
+
+impl AlertNotifier for Recorder {
+    fn notify(&self, alert: &DivergenceAlert) -> Result<(), String> {
+        self.0.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+/// An in-process primary that signs its tip announcements, and the
+/// bundle a replica verifies them with.
+fn primary() -> (RpcContext, VerifyingBundle) {
+    let mut keyring = Keyring::new().with_clock(|| NOW as u64);
+    let name = keyring.generate("primary").unwrap();
+    let bundle = keyring.verifying_bundle();
+    let cfg = GossipConfig { node_id: "primary".to_string(), namespace: "phoenix".to_string(), ..Default::default() };
+    let ctx = RpcContext {
+        ledger: Some(Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())))),
+        tips: Some(Arc::new(Mutex::new(TipGossip::new(cfg, keyring, &name, VerifyingBundle::default())))),
+        ..RpcContext::default()
+    };
+    (ctx, bundle)
+}
+
+fn config(batch_size: usize) -> ReplicaConfig {
+    ReplicaConfig { namespace: "phoenix".to_string(), primary_node_id: "primary".to_string(), batch_size, ..Default::default() }
+}
+
+fn empty_ledger() -> Arc<Mutex<TokenLedger>> {
+    Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())))
+}
+
+fn mint(primary: &RpcContext, count: usize) {
+    let mut ledger = primary.ledger.as_ref().unwrap().lock().unwrap();
+    for i in 0..count {
+        let deed = HomelessnessReliefDeed::builder()
+            .actor_id(["alice", "bob", "carol"][i % 3])
+            .location("Phoenix")
+            .hours(2.0)
+            .meals_served(10)
+            .build(ledger.last_hash())
+            .unwrap();
+        ledger.append(deed).unwrap();
+    }
+}
+
+fn hashes(ledger: &Arc<Mutex<TokenLedger>>) -> Vec<String> {
+    ledger.lock().unwrap().deeds().iter().map(|d| d.self_hash.clone()).collect()
+}
+
+fn balances(ledger: &Arc<Mutex<TokenLedger>>) -> Vec<Value> {
+    let mut accounts: Vec<Value> = ledger.lock().unwrap().accounts().map(|a| json!(a)).collect();
+    accounts.sort_by_key(|a| a["id"].to_string());
+    accounts
+}
+
+/// Records the size of every `get_deeds` request and answer.
+struct Counting<'a> {
+    primary: &'a RpcContext,
+    batches: RefCell<Vec<(u64, usize)>>,
+}
+
+impl PrimaryTransport for Counting<'_> {
+    fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError> {
+        let limit = params["limit"].as_u64().unwrap_or(0);
+        let result = self.primary.call(method, params)?;
+        if method == "auto_church.get_deeds" {
+            self.batches.borrow_mut().push((limit, result["deeds"].as_array().unwrap().len()));
+        }
+        Ok(result)
+    }
+}
+
+/// Serves the primary's deeds with the meals of `event_id` inflated.
+struct Tampering<'a> {
+    primary: &'a RpcContext,
+    event_id: String,
+    calls: RefCell<usize>,
+}
+
+impl PrimaryTransport for Tampering<'_> {
+    fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError> {
+        *self.calls.borrow_mut() += 1;
+        let mut result = self.primary.call(method, params)?;
+        for deed in result["deeds"].as_array_mut().into_iter().flatten() {
+            if deed["event_id"] == self.event_id.as_str() {
+                deed["context_json"]["meals_served"] = json!(1_000);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[test]
+fn replica_follows_live_primary() {
+    let (primary, keys) = primary();
+    let local = empty_ledger();
+    let mut replica = Replica::new(config(16), keys, local.clone(), primary.clone());
+
+    mint(&primary, 3);
+    assert_eq!(replica.sync(NOW).unwrap(), 3);
+    primary.ledger.as_ref().unwrap().lock().unwrap().seal_segment().unwrap().unwrap();
+    mint(&primary, 2);
+    replica.tick(NOW + 10).unwrap();
+
+    assert_eq!(hashes(&local), hashes(primary.ledger.as_ref().unwrap()));
+    assert_eq!(balances(&local), balances(primary.ledger.as_ref().unwrap()));
+    let health = replica.health();
+    assert!(health.following);
+    assert_eq!((health.height, health.primary_height), (6, 6));
+    assert_eq!(health.last_cross_check_at, Some(NOW + 10));
+    assert_eq!(healthz(&health).0, 200);
+    assert_eq!(replica.sync(NOW + 20).unwrap(), 0);
+
+    let tip = primary.call("auto_church.tip_announcement", json!({})).unwrap();
+    assert_eq!(tip["schema_version"], SCHEMA_VERSION);
+}
+
+#[test]
+fn restarted_replica_catches_up_in_bounded_batches() {
+    let (primary, keys) = primary();
+    let local = empty_ledger();
+    mint(&primary, 6);
+    Replica::new(config(4), keys.clone(), local.clone(), primary.clone()).sync(NOW).unwrap();
+
+    // Down while the primary moves on, then restarted over the same ledger.
+    mint(&primary, 15);
+    let counting = Counting { primary: &primary, batches: RefCell::new(Vec::new()) };
+    let mut replica = Replica::new(config(4), keys, local.clone(), counting);
+    assert_eq!(replica.health().height, 6);
+    assert_eq!(replica.sync(NOW + 600).unwrap(), 15);
+
+    assert_eq!(hashes(&local), hashes(primary.ledger.as_ref().unwrap()));
+    assert_eq!(*replica.transport().batches.borrow(), vec![(4, 4), (4, 4), (4, 4), (4, 3)]);
+}
+
+#[test]
+fn mutating_calls_are_rejected_while_reads_are_served() {
+    let (primary, keys) = primary();
+    mint(&primary, 2);
+    let mut replica = Replica::new(config(16), keys, empty_ledger(), primary);
+    replica.sync(NOW).unwrap();
+    let ctx = replica.rpc_context();
+
+    let mint = json!({
+        "jsonrpc": "2.0",
+        "method": "auto_church.mint_deed",
+        "params": { "actor_id": "mallory", "deed_type": "homelessness_relief", "context": {} },
+        "id": 7
+    });
+    let resp: Value = serde_json::from_str(&dispatch_replica_request(&mint.to_string(), &ctx)).unwrap();
+    assert_eq!(resp["error"]["code"], REPLICA_READ_ONLY);
+    assert_eq!(resp["id"], 7);
+    assert!(resp["correlation_id"].is_string());
+
+    let read = json!({ "jsonrpc": "2.0", "method": "auto_church.get_deeds", "params": {}, "id": 8 });
+    let resp: Value = serde_json::from_str(&dispatch_replica_request(&read.to_string(), &ctx)).unwrap();
+    assert_eq!(resp["result"]["height"], 2);
+
+    // Batching a write with a read does not slip it past the guard.
+    let batch = json!([mint, read]).to_string();
+    let resp: Value = serde_json::from_str(&dispatch_replica_request(&batch, &ctx)).unwrap();
+    assert_eq!(resp[0]["error"]["code"], REPLICA_READ_ONLY);
+    assert_eq!(resp[1]["result"]["height"], 2);
+    assert_eq!(ctx.ledger.unwrap().lock().unwrap().deeds().len(), 2);
+}
+
+#[test]
+fn tampered_deed_stops_the_replica() {
+    let (primary, keys) = primary();
+    mint(&primary, 5);
+    let local = empty_ledger();
+    let recorder = Recorder::default();
+    let event_id = primary.ledger.as_ref().unwrap().lock().unwrap().deeds()[3].event_id.clone();
+    let tampering = Tampering { primary: &primary, event_id, calls: RefCell::new(0) };
+    let mut replica = Replica::new(config(2), keys, local.clone(), tampering).with_notifier(recorder.clone());
+
+    assert!(matches!(replica.sync(NOW), Err(ReplicaError::Diverged(_))));
+    assert_eq!(hashes(&local), hashes(primary.ledger.as_ref().unwrap())[..3]);
+
+    let alerts = recorder.0.lock().unwrap().clone();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!((alerts[0].peer.as_str(), alerts[0].our_height), ("primary", 3));
+    let health = replica.health();
+    assert!(!health.following);
+    assert_eq!(health.fault.as_ref().unwrap().height, 3);
+    let (status, body) = healthz(&health);
+    assert_eq!(status, 503);
+    assert!(body.contains("SelfHash"), "{body}");
+
+    let calls = *replica.transport().calls.borrow();
+    assert!(matches!(replica.tick(NOW + 60), Err(ReplicaError::Stopped)));
+    assert_eq!(*replica.transport().calls.borrow(), calls, "a stopped replica no longer polls");
+    assert_eq!(recorder.0.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn cross_check_rejects_an_announcement_from_another_key() {
+    let (primary, _) = primary();
+    let (_, stranger) = self::primary();
+    mint(&primary, 3);
+    let recorder = Recorder::default();
+    let mut replica = Replica::new(config(16), stranger, empty_ledger(), primary).with_notifier(recorder.clone());
+
+    replica.sync(NOW).unwrap();
+    assert!(matches!(replica.cross_check(NOW), Err(ReplicaError::Diverged(_))));
+    assert_eq!(healthz(&replica.health()).0, 503);
+    assert_eq!(recorder.0.lock().unwrap().len(), 1);
+}
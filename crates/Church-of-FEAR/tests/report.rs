@@ -0,0 +1,156 @@
+#![cfg(feature = "testkit")]
+
+use church_of_fear::fixtures::{abuse_attempt, small_community};
+use church_of_fear::history::HistoricalPoint;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::{SupplyReport, TokenLedger, TokenSupply};
+use church_of_fear::report::{
+    generate_monthly_report, generate_monthly_report_with, write_monthly_report, EcoUtilization, GuardFigures,
+    ReportError, ReportPeriod, ReportTemplate, PLACEHOLDERS,
+};
+
+/// 2025-09-01T00:00:00Z.
+const SEPT_1: i64 = 1_756_684_800;
+
+fn september() -> ReportPeriod {
+    ReportPeriod::month(2025, 9).unwrap()
+}
+
+fn supply_before(ledger: &TokenLedger, pos: usize) -> Option<SupplyReport> {
+    let hash = &ledger.deeds()[pos.checked_sub(1)?].self_hash;
+    Some(ledger.state_at(&HistoricalPoint::Tip(hash.clone())).unwrap().view.supply().clone())
+}
+
+#[test]
+fn periods_are_calendar_months() {
+    let period = september();
+    assert_eq!(period, ReportPeriod { start: SEPT_1, end: SEPT_1 + 30 * 86_400 });
+    assert_eq!(period.label(), "2025-09");
+    assert_eq!(ReportPeriod::parse_month("2025-09"), Some(period));
+    assert_eq!(ReportPeriod::previous_month(SEPT_1 + 30 * 86_400 + 5), period);
+    assert_eq!(ReportPeriod::previous_month(1_767_225_600 + 60).label(), "2025-12");
+    assert_eq!(ReportPeriod::parse_month("2025-13"), None);
+    assert_eq!(ReportPeriod { start: SEPT_1, end: SEPT_1 + 86_400 }.label(), "2025-09-01..2025-09-02");
+}
+
+#[test]
+fn figures_reconcile_with_historical_state() {
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let ledger = &scenario.ledger;
+    let report = generate_monthly_report(ledger, september(), &ReportTemplate::default()).unwrap();
+
+    let deeds = &ledger.deeds()[report.first..report.end];
+    assert!(deeds.iter().all(|d| d.timestamp >= SEPT_1 && d.timestamp < september().end));
+    assert!(report.end < ledger.deeds().len(), "the scenario runs into October");
+    assert_eq!(report.tip_hash.as_deref(), Some(ledger.deeds()[report.end - 1].self_hash.as_str()));
+
+    let before = supply_before(ledger, report.first);
+    let after = report.supply_at_end.as_ref().unwrap();
+    for token in Token::ALL {
+        let start = before.as_ref().and_then(|s| s.tokens.get(&token)).copied().unwrap_or_default();
+        let end: TokenSupply = after.tokens.get(&token).copied().unwrap_or_default();
+        let flow = report.token_flows[&token];
+        assert_eq!(flow.minted, end.issued - start.issued, "{} minted", token.as_str());
+        assert_eq!(flow.burned, end.retired - start.retired, "{} burned", token.as_str());
+    }
+    assert!(report.token_flows[&Token::Church].minted > 0);
+
+    for line in &report.categories {
+        let counted = deeds.iter().filter(|d| d.deed_type == line.deed_type && !ledger.is_tombstoned(&d.event_id)).count();
+        assert_eq!(line.deeds, counted, "{}", line.deed_type);
+    }
+    let carol = report.categories.iter().find(|c| c.deed_type == "homelessness_relief").unwrap();
+    assert_eq!(carol.most_active, "carol");
+    assert!(report.markdown.contains(&format!("| homelessness_relief | {} |", carol.deeds)));
+    assert!(report.markdown.contains(report.tip_hash.as_deref().unwrap()));
+}
+
+#[test]
+fn incidents_count_harm_reports() {
+    let scenario = abuse_attempt(3, SEPT_1).build().unwrap();
+    let report = generate_monthly_report(&scenario.ledger, september(), &ReportTemplate::default()).unwrap();
+    assert_eq!(report.incidents.life_harm, 2);
+    assert_eq!(report.regulator.warns, 2);
+    assert_eq!(report.regulator.fear_accrued, 2 * scenario.ledger.config().fear_on_warn);
+    assert!(report.markdown.contains("- Life-harm deeds: 2"));
+}
+
+#[test]
+fn every_figure_names_its_source() {
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let report = generate_monthly_report(&scenario.ledger, september(), &ReportTemplate::default()).unwrap();
+    for figure in ["deeds_by_category", "token_flows", "supply_at_end", "grants.pool", "grants.repair", "regulator", "incidents", "near_misses", "merkle_roots", "eco_utilization", "equity_outcomes"] {
+        assert!(report.sources.contains_key(figure), "{figure}");
+        assert!(report.markdown.contains(&format!("`{figure}`")), "{figure}");
+    }
+}
+
+#[test]
+fn default_template_uses_every_placeholder() {
+    let template = ReportTemplate::default();
+    let mut used = template.placeholders();
+    used.sort_unstable();
+    let mut all = PLACEHOLDERS.to_vec();
+    all.sort_unstable();
+    assert_eq!(used, all);
+
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let report = generate_monthly_report(&scenario.ledger, september(), &template).unwrap();
+    assert!(!report.markdown.contains("{{"));
+}
+
+#[test]
+fn unknown_placeholder_is_an_error() {
+    let err = ReportTemplate::parse("# {{period}}\n{{deeds_by_colour}}\n").unwrap_err();
+    assert!(matches!(err, ReportError::UnknownPlaceholder(ref name) if name == "deeds_by_colour"));
+    assert!(matches!(ReportTemplate::parse("{{period"), Err(ReportError::UnclosedPlaceholder(0))));
+
+    let custom = ReportTemplate::parse("{{ period }}: {{church_minted}} CHURCH").unwrap();
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let report = generate_monthly_report(&scenario.ledger, september(), &custom).unwrap();
+    assert_eq!(report.markdown, format!("2025-09: {} CHURCH", report.token_flows[&Token::Church].minted));
+}
+
+#[test]
+fn html_is_self_contained() {
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let guard = GuardFigures {
+        eco_utilization: vec![EcoUtilization { route: "grid|north <a>".to_string(), admissions: 4, ..Default::default() }],
+        eco_source: "ecofairness-guard headroom --month 2025-09".to_string(),
+        ..Default::default()
+    };
+    let report = generate_monthly_report_with(&scenario.ledger, september(), &guard, &ReportTemplate::default()).unwrap();
+    let html = report.to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<td>grid|north &lt;a&gt;</td>"));
+    for external in ["<script", "<link", "src=", "href=", "http://", "https://", "url("] {
+        assert!(!html.contains(external), "{external}");
+    }
+}
+
+#[test]
+fn output_is_deterministic() {
+    let render = || {
+        let scenario = small_community(11, SEPT_1).build().unwrap();
+        let report = generate_monthly_report(&scenario.ledger, september(), &ReportTemplate::default()).unwrap();
+        (report.markdown.clone(), report.to_html())
+    };
+    assert_eq!(render(), render());
+}
+
+#[test]
+fn scheduled_job_writes_last_month_once() {
+    let scenario = small_community(7, SEPT_1).build().unwrap();
+    let dir = std::env::temp_dir().join(format!("cof-report-{}", std::process::id()));
+    let october = ReportPeriod::month(2025, 10).unwrap();
+
+    let written = write_monthly_report(&scenario.ledger, &dir, true, october.start + 3_600).unwrap().unwrap();
+    assert_eq!(written, dir.join("stewardship-2025-09.md"));
+    assert!(dir.join("stewardship-2025-09.html").exists());
+    let expected = generate_monthly_report(&scenario.ledger, september(), &ReportTemplate::default()).unwrap();
+    assert_eq!(std::fs::read_to_string(&written).unwrap(), expected.markdown);
+    assert!(write_monthly_report(&scenario.ledger, &dir, true, october.start + 7_200).unwrap().is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
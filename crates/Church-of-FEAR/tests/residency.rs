@@ -0,0 +1,178 @@
+#![cfg(feature = "core")]
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::residency::{
+    DeedSink, Placement, ResidencyAlert, ResidencyError, ResidencyFanout, ResidencyPolicy, SinkResidency,
+};
+use serde_json::json;
+
+type Received = Arc<Mutex<Vec<DeedEvent>>>;
+
+struct Collector {
+    name: String,
+    received: Received,
+}
+
+impl DeedSink for Collector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn deliver(&mut self, deed: &DeedEvent) -> Result<(), String> {
+        self.received.lock().unwrap().push(deed.clone());
+        Ok(())
+    }
+}
+
+fn sink(name: &str, jurisdictions: &[&str], redact: bool) -> SinkResidency {
+    SinkResidency { name: name.into(), jurisdictions: jurisdictions.iter().map(|j| j.to_string()).collect(), redact }
+}
+
+fn policy() -> ResidencyPolicy {
+    ResidencyPolicy {
+        jurisdictions: vec!["PHX".into(), "BRU".into()],
+        actor_jurisdictions: BTreeMap::from([("alice".into(), "PHX".into()), ("bob".into(), "BRU".into())]),
+        microspace_jurisdictions: BTreeMap::from([("ms-bru-1".into(), "BRU".into())]),
+        sinks: vec![
+            sink("phx-archive", &["PHX"], false),
+            sink("eu-store", &["BRU"], false),
+            sink("research", &["PHX", "BRU"], true),
+        ],
+        ..ResidencyPolicy::default()
+    }
+}
+
+fn fanout(policy: ResidencyPolicy) -> (ResidencyFanout, BTreeMap<String, Received>) {
+    let mut received = BTreeMap::new();
+    let mut sinks: Vec<Box<dyn DeedSink>> = Vec::new();
+    for s in &policy.sinks {
+        let r = Received::default();
+        received.insert(s.name.clone(), r.clone());
+        sinks.push(Box::new(Collector { name: s.name.clone(), received: r }));
+    }
+    (ResidencyFanout::new(policy, sinks).unwrap(), received)
+}
+
+fn deed(actor: &str, targets: &[&str], tags: &[&str], context: serde_json::Value) -> DeedEvent {
+    DeedEvent::new(
+        "0".repeat(64),
+        actor.into(),
+        targets.iter().map(|t| t.to_string()).collect(),
+        "ecological_sustainability".into(),
+        tags.iter().map(|t| t.to_string()).collect(),
+        context,
+        vec![],
+        false,
+    )
+}
+
+fn ids(received: &Received) -> Vec<String> {
+    received.lock().unwrap().iter().map(|d| d.event_id.clone()).collect()
+}
+
+#[test]
+fn sinks_only_receive_their_jurisdictions() {
+    let (mut fanout, received) = fanout(policy());
+    let phx = deed("alice", &[], &[], json!({ "co2_kg": 2.0 }));
+    let bru = deed("bob", &[], &[], json!({ "co2_kg": 1.0 }));
+    let in_bru_microspace = deed("alice", &[], &[], json!({ "microspace": "ms-bru-1" }));
+    let pinned_phx = deed("bob", &[], &["jurisdiction:PHX"], json!({}));
+    let credit_to_bob = deed("ledger", &["bob"], &[], json!({ "amount": 5 }));
+    let decay = deed("ledger", &[], &[], json!({ "rate": 0.1 }));
+    let placement = fanout.policy().placement(&in_bru_microspace);
+    assert_eq!(placement, Placement::In("BRU".into()));
+    assert_eq!(fanout.policy().placement(&decay), Placement::Unrestricted);
+
+    for d in [&phx, &bru, &in_bru_microspace, &pinned_phx, &credit_to_bob, &decay] {
+        assert!(fanout.publish(d) > 0);
+    }
+    let id = |d: &DeedEvent| d.event_id.clone();
+    assert_eq!(ids(&received["phx-archive"]), vec![id(&phx), id(&pinned_phx), id(&decay)]);
+    assert_eq!(ids(&received["eu-store"]), vec![id(&bru), id(&in_bru_microspace), id(&credit_to_bob), id(&decay)]);
+    assert_eq!(received["research"].lock().unwrap().len(), 6);
+
+    let exported = fanout.export("eu-store", [&phx, &bru, &decay]).unwrap();
+    assert_eq!(exported.iter().map(id).collect::<Vec<_>>(), vec![id(&bru), id(&decay)]);
+    assert_eq!(fanout.export("usb-stick", [&phx]).unwrap_err(), ResidencyError::UndeclaredSink("usb-stick".into()));
+}
+
+#[test]
+fn redaction_required_sinks_get_the_redacted_form() {
+    let (mut fanout, received) = fanout(policy());
+    let original = deed("alice", &["bob"], &[], json!({ "co2_kg": 3.5, "location": "12 Elm St", "notes": "with her kids" }));
+    fanout.publish(&original);
+    let research = received["research"].lock().unwrap()[0].clone();
+    assert!(research.actor_id.starts_with("anon:") && research.target_ids[0].starts_with("anon:"));
+    assert_eq!(research.context_json, json!({ "co2_kg": 3.5, "redacted": true }));
+    assert_eq!((research.event_id, research.self_hash), (original.event_id.clone(), original.self_hash.clone()));
+    let archived = serde_json::to_value(&received["phx-archive"].lock().unwrap()[0]).unwrap();
+    assert_eq!(archived, serde_json::to_value(&original).unwrap(), "unredacted sinks get the deed as is");
+    assert_eq!(fanout.export("research", [&original]).unwrap()[0].context_json["location"], json!(null));
+}
+
+#[test]
+fn deeds_without_a_permitted_sink_are_buffered_and_alerted() {
+    let mut p = policy();
+    p.actor_jurisdictions.insert("carol".into(), "NYC".into());
+    assert_eq!(p.validate(), Err(ResidencyError::Unroutable(vec!["NYC".into()])));
+    let (mut fanout, received) = fanout(p.clone());
+
+    let nyc = deed("carol", &[], &[], json!({}));
+    let stranger = deed("dave", &[], &[], json!({}));
+    assert_eq!((fanout.publish(&nyc), fanout.publish(&stranger)), (0, 0));
+    assert_eq!(
+        fanout.take_alerts(),
+        vec![
+            ResidencyAlert::NoPermittedSink { jurisdiction: "NYC".into(), event_id: nyc.event_id.clone() },
+            ResidencyAlert::Unresolved { event_id: stranger.event_id.clone() },
+        ]
+    );
+    assert_eq!(fanout.report().buffered, BTreeMap::from([("NYC".into(), 1), ("?".into(), 1)]));
+    assert!(received.values().all(|r| r.lock().unwrap().is_empty()));
+
+    p.sinks[2].jurisdictions.push("NYC".into());
+    p.default_jurisdiction = Some("PHX".into());
+    fanout.set_policy(p).unwrap();
+    assert!(fanout.buffered().is_empty() && fanout.take_alerts().is_empty());
+    assert_eq!(ids(&received["research"]), vec![nyc.event_id.clone(), stranger.event_id.clone()]);
+    assert_eq!(ids(&received["phx-archive"]), vec![stranger.event_id]);
+
+    let mut dup = policy();
+    dup.sinks.push(sink("eu-store", &["PHX"], false));
+    assert_eq!(fanout.set_policy(dup).unwrap_err(), ResidencyError::DuplicateSink("eu-store".into()));
+}
+
+#[test]
+fn report_reconciles_with_deliveries() {
+    let (mut fanout, received) = fanout(policy());
+    let actors = ["alice", "bob", "ledger"];
+    for i in 0..60 {
+        let actor = actors[i % 3];
+        let targets: &[&str] = if i % 4 == 0 { &["bob"] } else { &[] };
+        let context = if i % 5 == 0 { json!({ "microspace": "ms-bru-1" }) } else { json!({}) };
+        fanout.publish(&deed(actor, targets, &[], context));
+    }
+    let report = fanout.report().clone();
+    for (name, r) in &received {
+        let got = r.lock().unwrap();
+        let counts = &report.sinks[name];
+        assert_eq!(counts.delivered(), got.len() as u64, "{name}");
+        assert_eq!(counts.redacted, if name == "research" { got.len() as u64 } else { 0 });
+        let mut by_jurisdiction: BTreeMap<String, u64> = BTreeMap::new();
+        for d in got.iter() {
+            let key = match fanout.policy().placement(d) {
+                Placement::In(code) => code,
+                _ => "*".into(),
+            };
+            *by_jurisdiction.entry(key).or_default() += 1;
+        }
+        if name != "research" {
+            assert_eq!(by_jurisdiction, counts.by_jurisdiction, "{name}");
+        }
+    }
+    assert_eq!(report.sinks["research"].by_jurisdiction.values().sum::<u64>(), 60);
+    assert!(report.buffered.is_empty());
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "core")]
+
+use std::collections::BTreeMap;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::scheduler::RecurringJobs;
+use church_of_fear::sponsor::equity::{
+    gini, rebalance_rewards, record_plan, reward_equity_report, ClassAdjustment, ClassBounds, EquityClassRegistry,
+    EquityPlan, RewardEquityError, RewardEquitySpec, REWARD_PLAN,
+};
+use church_of_fear::sponsor::pool::PlannedReward;
+
+const DAY: i64 = 86_400;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn registry() -> EquityClassRegistry {
+    let mut registry = EquityClassRegistry::default();
+    for (account, class) in [("alice", "host"), ("bob", "host"), ("carol", "community"), ("dave", "research"), ("erin", "remote")] {
+        registry.assign(account, class);
+    }
+    registry
+}
+
+fn spec(bounds: &[(&str, f64, f64)]) -> RewardEquitySpec {
+    let classes = bounds
+        .iter()
+        .map(|&(class, min_fraction, max_fraction)| (class.to_string(), ClassBounds { min_fraction, max_fraction }))
+        .collect();
+    RewardEquitySpec { classes }
+}
+
+fn plan(rewards: &[(&str, u64)]) -> Vec<PlannedReward> {
+    rewards
+        .iter()
+        .map(|&(id, amount)| PlannedReward { account_id: id.to_string(), amount, reason: "sponsored repair".to_string() })
+        .collect()
+}
+
+fn amounts(plan: &EquityPlan) -> Vec<(&str, u64)> {
+    plan.rewards.iter().map(|r| (r.reward.account_id.as_str(), r.reward.amount)).collect()
+}
+
+fn adjustments(plan: &EquityPlan) -> BTreeMap<&str, ClassAdjustment> {
+    plan.classes.iter().map(|d| (d.class.as_str(), d.adjustment)).collect()
+}
+
+#[test]
+fn classes_over_their_ceiling_are_trimmed_and_the_rest_shared_proportionally() {
+    let planned = plan(&[("alice", 60), ("bob", 20), ("carol", 15), ("dave", 5)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &spec(&[("host", 0.0, 0.5)])).unwrap();
+
+    // host is cut from 80 to 50; community and research split the other 50 at 15:5.
+    assert_eq!(amounts(&rebalanced), [("alice", 38), ("bob", 12), ("carol", 38), ("dave", 12)]);
+    assert_eq!(rebalanced.total_rebalanced(), 100);
+    assert_eq!(rebalanced.unplaced, 0);
+    let host = &rebalanced.classes[1];
+    assert_eq!((host.class.as_str(), host.planned, host.rebalanced), ("host", 80, 50));
+    assert_eq!(adjustments(&rebalanced)["host"], ClassAdjustment::TrimmedToCeiling);
+    assert_eq!(adjustments(&rebalanced)["community"], ClassAdjustment::Redistributed);
+
+    // With every class capped, what no class can take stays unplanned.
+    let capped = spec(&[("host", 0.0, 0.5), ("community", 0.0, 0.2), ("research", 0.0, 0.1)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &capped).unwrap();
+    assert_eq!(amounts(&rebalanced), [("alice", 38), ("bob", 12), ("carol", 20), ("dave", 10)]);
+    assert_eq!(rebalanced.unplaced, 20);
+    assert_eq!(adjustments(&rebalanced)["community"], ClassAdjustment::Redistributed);
+}
+
+#[test]
+fn under_served_classes_are_raised_to_their_floor() {
+    let planned = plan(&[("alice", 70), ("bob", 20), ("carol", 6), ("dave", 4)]);
+    let floors = spec(&[("community", 0.3, 1.0), ("research", 0.2, 1.0)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &floors).unwrap();
+
+    // The floors take 50; host keeps the other 50, split 70:20 between its members.
+    assert_eq!(amounts(&rebalanced), [("alice", 39), ("bob", 11), ("carol", 30), ("dave", 20)]);
+    assert_eq!(rebalanced.total_rebalanced(), 100);
+    let adjustments = adjustments(&rebalanced);
+    assert_eq!(adjustments["community"], ClassAdjustment::RaisedToFloor);
+    assert_eq!(adjustments["research"], ClassAdjustment::RaisedToFloor);
+    assert_eq!(adjustments["host"], ClassAdjustment::Redistributed);
+
+    // A floor and a ceiling together: host is held to 60, research raised to
+    // 20, and community takes what is left.
+    let both = spec(&[("host", 0.0, 0.6), ("research", 0.2, 1.0)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &both).unwrap();
+    assert_eq!(amounts(&rebalanced), [("alice", 47), ("bob", 13), ("carol", 20), ("dave", 20)]);
+
+    let invalid = spec(&[("community", 0.7, 1.0), ("research", 0.4, 1.0)]);
+    assert!(matches!(rebalance_rewards(&planned, &registry(), &invalid), Err(RewardEquityError::FloorsExceedPlan(_))));
+    let inverted = spec(&[("host", 0.5, 0.2)]);
+    assert!(matches!(rebalance_rewards(&planned, &registry(), &inverted), Err(RewardEquityError::InvalidBounds { .. })));
+}
+
+#[test]
+fn classes_without_qualifying_deeds_do_not_claim_their_floor() {
+    let remote_floor = spec(&[("remote", 0.4, 1.0)]);
+    let planned = plan(&[("alice", 60), ("carol", 40)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &remote_floor).unwrap();
+    assert_eq!(amounts(&rebalanced), [("alice", 60), ("carol", 40)]);
+    assert!(rebalanced.classes.iter().all(|d| d.adjustment == ClassAdjustment::Unchanged));
+    assert!(rebalanced.classes.iter().all(|d| d.class != "remote"));
+
+    // A qualifying remote deed, even one planned nothing, claims the floor.
+    let planned = plan(&[("alice", 60), ("carol", 40), ("erin", 0)]);
+    let rebalanced = rebalance_rewards(&planned, &registry(), &remote_floor).unwrap();
+    assert_eq!(amounts(&rebalanced), [("alice", 36), ("carol", 24), ("erin", 40)]);
+    assert_eq!(adjustments(&rebalanced)["remote"], ClassAdjustment::RaisedToFloor);
+}
+
+#[test]
+fn every_reward_records_its_class_decision() {
+    let mut registry = registry();
+    registry.members.remove("dave");
+    let planned = plan(&[("alice", 60), ("bob", 20), ("carol", 15), ("dave", 5)]);
+    let rebalanced = rebalance_rewards(&planned, &registry, &spec(&[("host", 0.0, 0.5)])).unwrap();
+
+    assert_eq!(rebalanced.classes.len(), 3);
+    for reward in &rebalanced.rewards {
+        let decision = rebalanced.classes.iter().find(|d| d.class == reward.class).unwrap();
+        assert!(reward.reward.reason.starts_with("sponsored repair; "));
+        assert!(reward.reward.reason.ends_with(&decision.note(rebalanced.total)), "{}", reward.reward.reason);
+    }
+    assert_eq!(rebalanced.rewards[3].class, "unclassified");
+    assert!(rebalanced.rewards[0].reward.reason.contains("equity class host trimmed from 80.0% to its 50.0% ceiling"));
+    assert!(rebalanced.rewards[2].reward.reason.contains("equity class community moved from 15.0% to 38.0%"));
+    assert_eq!(rebalanced.planned().iter().map(|r| r.amount).sum::<u64>(), 100);
+}
+
+#[test]
+fn report_reconciles_with_recorded_plans() {
+    let mut cfg = LedgerConfig::default();
+    cfg.reward_equity.registry = registry();
+    cfg.reward_equity.spec = spec(&[("host", 0.0, 0.5), ("research", 0.2, 1.0)]);
+    let mut ledger = TokenLedger::new(cfg.clone());
+
+    let ticks = [
+        plan(&[("alice", 60), ("bob", 20), ("carol", 15), ("dave", 5)]),
+        plan(&[("alice", 10), ("carol", 30), ("dave", 10)]),
+        plan(&[("bob", 40)]),
+    ];
+    let mut by_class: BTreeMap<String, u64> = BTreeMap::new();
+    let mut by_account: BTreeMap<String, u64> = BTreeMap::new();
+    for tick in &ticks {
+        let rebalanced = rebalance_rewards(tick, &cfg.reward_equity.registry, &cfg.reward_equity.spec).unwrap();
+        for r in &rebalanced.rewards {
+            *by_class.entry(r.class.clone()).or_default() += r.reward.amount;
+            *by_account.entry(r.reward.account_id.clone()).or_default() += r.reward.amount;
+        }
+        record_plan(&mut ledger, &rebalanced).unwrap();
+    }
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == REWARD_PLAN).count(), 3);
+
+    let report = reward_equity_report(&ledger, now());
+    assert_eq!(report.plans, 3);
+    assert_eq!(report.total, by_class.values().sum::<u64>());
+    let reported: BTreeMap<String, u64> = report.classes.iter().map(|c| (c.class.clone(), c.amount)).collect();
+    assert_eq!(reported, by_class);
+    assert!((report.classes.iter().map(|c| c.share).sum::<f64>() - 1.0).abs() < 1e-9);
+    let accounts: Vec<u64> = by_account.into_values().collect();
+    assert!((report.gini - gini(&accounts)).abs() < 1e-12);
+    assert!(report.gini > 0.0 && report.gini < 1.0);
+
+    // Plans recorded after the window's end are not counted.
+    let earlier = reward_equity_report(&ledger, now() - 31 * DAY);
+    assert_eq!((earlier.plans, earlier.total, earlier.gini), (0, 0, 0.0));
+
+    assert_eq!(gini(&[5, 5]), 0.0);
+    assert!((gini(&[0, 0, 0, 10]) - 0.75).abs() < 1e-12);
+
+    let mut jobs = RecurringJobs::with_defaults(&ledger, now());
+    assert!(jobs.run_due(&mut ledger, now() + DAY).contains(&"reward_equity_report".to_string()));
+}
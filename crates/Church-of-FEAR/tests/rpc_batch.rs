@@ -0,0 +1,63 @@
+#![cfg(feature = "rpc")]
+
+use church_of_fear::rpc::server::{dispatch_request, dispatch_with, RpcContext};
+use church_of_fear::rpc::types::{JsonRpcRequest, JsonRpcResponse};
+use serde_json::{json, Value};
+
+fn dispatch(request: Value) -> Value {
+    serde_json::from_str(&dispatch_request(&request.to_string())).unwrap()
+}
+
+#[test]
+fn a_mixed_batch_answers_in_order_without_notifications() {
+    let resp = dispatch(json!([
+        { "jsonrpc": "2.0", "method": "auto_church.handshake", "id": 1 },
+        { "jsonrpc": "2.0", "method": "auto_church.no_such_method", "id": "two" },
+        { "jsonrpc": "2.0", "method": "auto_church.handshake" },
+        { "jsonrpc": "2.0", "method": "auto_church.handshake", "id": null },
+        { "jsonrpc": "2.0", "method": "auto_church.get_state_at", "params": { "timestamp": "soon" }, "id": 4 },
+    ]));
+    let resp = resp.as_array().unwrap();
+    assert_eq!(resp.iter().map(|r| r["id"].clone()).collect::<Vec<_>>(), [json!(1), json!("two"), json!(4)]);
+    assert!(resp[0]["error"].is_null());
+    assert!(resp[0]["result"]["schema_version"].is_u64());
+    assert_eq!(resp[1]["error"]["code"], -32601);
+    assert_eq!(resp[2]["error"]["code"], -32602);
+    assert!(resp.iter().all(|r| r["correlation_id"].is_string()));
+
+    let notifications = json!([{ "jsonrpc": "2.0", "method": "auto_church.handshake" }]);
+    assert_eq!(dispatch_request(&notifications.to_string()), "");
+}
+
+#[test]
+fn malformed_requests_get_the_standard_codes() {
+    assert_eq!(serde_json::from_str::<Value>(&dispatch_request("[{ nope")).unwrap()["error"]["code"], -32700);
+    assert_eq!(dispatch(json!([]))["error"]["code"], -32600);
+    assert_eq!(dispatch(json!({ "jsonrpc": "2.0", "id": 3 }))["error"]["code"], -32600);
+    let old = dispatch(json!({ "jsonrpc": "1.0", "method": "auto_church.handshake", "id": 5 }));
+    assert_eq!((old["error"]["code"].clone(), old["id"].clone()), (json!(-32600), json!(5)));
+
+    // Entries that are not requests at all still get an answer each.
+    let resp = dispatch(json!([1, { "jsonrpc": "2.0", "method": "auto_church.handshake", "id": 6 }]));
+    assert_eq!(resp[0]["error"]["code"], -32600);
+    assert_eq!(resp[0]["id"], Value::Null);
+    assert_eq!(resp[1]["id"], 6);
+}
+
+fn explode(req: JsonRpcRequest, _: &RpcContext) -> JsonRpcResponse {
+    if req.method == "boom" {
+        panic!("handler bug");
+    }
+    JsonRpcResponse { jsonrpc: "2.0".into(), result: Some(json!("ok")), error: None, id: req.id, correlation_id: None }
+}
+
+#[test]
+fn a_handler_panic_becomes_an_internal_error() {
+    let batch = json!([
+        { "jsonrpc": "2.0", "method": "boom", "id": 1 },
+        { "jsonrpc": "2.0", "method": "fine", "id": 2 },
+    ]);
+    let resp: Value = serde_json::from_str(&dispatch_with(&batch.to_string(), &RpcContext::default(), explode)).unwrap();
+    assert_eq!((resp[0]["error"]["code"].clone(), resp[0]["id"].clone()), (json!(-32603), json!(1)));
+    assert_eq!(resp[1]["result"], "ok");
+}
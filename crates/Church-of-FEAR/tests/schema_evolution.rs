@@ -0,0 +1,191 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use church_of_fear::ledger::deed_event::{link_fault, DeedEvent, LinkFault};
+use church_of_fear::ledger::schema::{parse_deed, SchemaError};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use serde_json::{json, Map, Value};
+
+fn ext() -> Map<String, Value> {
+    json!({ "consent": { "qualifier": "guardian-present", "scope": ["meals"] }, "v3_note": "kept" })
+        .as_object()
+        .unwrap()
+        .clone()
+}
+
+fn relief(prev_hash: String) -> DeedEvent {
+    HomelessnessReliefDeed::builder()
+        .actor_id("alice")
+        .location("Phoenix")
+        .hours(2.0)
+        .meals_served(30)
+        .build(prev_hash)
+        .unwrap()
+}
+
+#[test]
+fn ext_survives_parse_hash_store_and_serve() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let deed = relief(ledger.last_hash()).with_ext(ext());
+    let stored = ledger.append(deed).unwrap().clone();
+    assert_eq!(stored.ext, ext());
+
+    let served = serde_json::to_string(&stored).unwrap();
+    let parsed = parse_deed(&served).unwrap();
+    assert_eq!(parsed.ext, ext());
+    assert_eq!(parsed.self_hash, stored.self_hash);
+    assert_eq!(link_fault(&parsed, &stored.prev_hash), None);
+
+    let replayed = TokenLedger::replay(LedgerConfig::default(), vec![parsed]).unwrap();
+    assert_eq!(replayed.deeds()[0].ext, ext());
+    assert_eq!(replayed.last_hash(), ledger.last_hash());
+}
+
+#[test]
+fn deeds_without_ext_serialize_as_before() {
+    let deed = relief("0".repeat(64));
+    let value = serde_json::to_value(&deed).unwrap();
+    assert!(value.get("ext").is_none());
+}
+
+#[test]
+fn unknown_critical_fields_are_rejected_by_path() {
+    let mut deed = serde_json::to_value(relief("0".repeat(64))).unwrap();
+    deed["consent_qualifier"] = json!("guardian-present");
+    let text = deed.to_string();
+    assert_eq!(parse_deed(&text).unwrap_err(), SchemaError::UnknownCriticalField { path: "consent_qualifier".into() });
+    assert!(serde_json::from_str::<DeedEvent>(&text).is_err(), "no parse path strips it silently");
+
+    let mut sim = serde_json::to_value(relief("0".repeat(64))).unwrap();
+    sim["domain"] = json!({ "simulation": { "run_id": "run-1", "shadow": false } });
+    assert_eq!(
+        parse_deed(&sim.to_string()).unwrap_err(),
+        SchemaError::UnknownCriticalField { path: "domain.simulation.shadow".into() }
+    );
+
+    // Unknown keys inside `ext` and the free-form context are fine.
+    let mut open = serde_json::to_value(relief("0".repeat(64))).unwrap();
+    open["ext"] = json!({ "anything": { "nested": true } });
+    open["context_json"]["shelter_wing"] = json!("east");
+    assert!(parse_deed(&open.to_string()).is_ok());
+}
+
+#[test]
+fn hash_covers_ext() {
+    let plain = relief("0".repeat(64));
+    let extended = plain.clone().with_ext(ext());
+    assert_ne!(extended.self_hash, plain.self_hash);
+
+    let mut tampered = extended.clone();
+    tampered.ext["v3_note"] = json!("rewritten");
+    assert_eq!(link_fault(&tampered, &extended.prev_hash), Some(LinkFault::SelfHash));
+    let mut stripped = extended.clone();
+    stripped.ext.clear();
+    assert_eq!(link_fault(&stripped, &extended.prev_hash), Some(LinkFault::SelfHash));
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use super::*;
+    use church_of_fear::ledger::schema::{SCHEMA_VERSION, UNKNOWN_CRITICAL_FIELD, UNKNOWN_CRITICAL_FIELD_CODE};
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use church_of_fear::rpc::types::AutoChurchGetDeedsResult;
+    use std::sync::{Arc, Mutex};
+
+    pub fn call(ctx: &RpcContext, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str(&dispatch_request_with(&request.to_string(), ctx)).unwrap()
+    }
+
+    pub fn mint_params(prev_hash: String) -> Value {
+        json!({
+            "prev_hash": prev_hash,
+            "actor_id": "alice",
+            "target_ids": [],
+            "deed_type": "homelessness_relief",
+            "tags": [],
+            "context_json": { "location": "Phoenix", "hours": 2.0, "meals_served": 30 },
+            "ethics_flags": [],
+            "life_harm_flag": false,
+            "bioload_delta": -0.1,
+            "roh": 0.1,
+            "decay": 0.5
+        })
+    }
+
+    #[test]
+    fn mint_keeps_ext_and_rejects_unknown_params() {
+        let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+        let ctx = RpcContext::with_ledger(ledger.clone());
+
+        let mut params = mint_params(ledger.lock().unwrap().last_hash());
+        params["consent_qualifier"] = json!("guardian-present");
+        let rejected = call(&ctx, "auto_church.mint_deed", params.clone());
+        assert_eq!(rejected["error"]["code"], UNKNOWN_CRITICAL_FIELD_CODE);
+        assert_eq!(rejected["error"]["message"], UNKNOWN_CRITICAL_FIELD);
+        assert_eq!(rejected["error"]["data"]["path"], "params.consent_qualifier");
+        assert!(ledger.lock().unwrap().deeds().is_empty());
+
+        params.as_object_mut().unwrap().remove("consent_qualifier");
+        params["ext"] = Value::Object(ext());
+        let minted = call(&ctx, "auto_church.mint_deed", params);
+        assert_eq!(minted["result"]["deed"]["ext"], Value::Object(ext()));
+        assert_eq!(ledger.lock().unwrap().deeds()[0].ext, ext());
+    }
+
+    #[test]
+    fn nodes_advertise_their_schema_version() {
+        let ctx = RpcContext::with_ledger(Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default()))));
+        assert_eq!(call(&ctx, "auto_church.handshake", Value::Null)["result"]["schema_version"], SCHEMA_VERSION);
+        assert_eq!(call(&ctx, "auto_church.get_deeds", json!({}))["result"]["schema_version"], SCHEMA_VERSION);
+
+        // A primary from before the advertisement reads as version 1.
+        let old: AutoChurchGetDeedsResult =
+            serde_json::from_value(json!({ "deeds": [], "height": 0, "tip_hash": "0".repeat(64), "more": false })).unwrap();
+        assert_eq!(old.schema_version, 1);
+    }
+}
+
+#[cfg(feature = "replica")]
+#[test]
+fn replica_stream_preserves_ext() {
+    use church_of_fear::ledger::schema::SCHEMA_VERSION;
+    use church_of_fear::replica::{PrimaryTransport, Replica, ReplicaConfig, ReplicaError};
+    use church_of_fear::rpc::server::RpcContext;
+    use keyring::VerifyingBundle;
+    use std::sync::{Arc, Mutex};
+
+    let primary = RpcContext::with_ledger(Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default()))));
+    let prev_hash = primary.ledger.as_ref().unwrap().lock().unwrap().last_hash();
+    let mut params = rpc::mint_params(prev_hash);
+    params["ext"] = Value::Object(ext());
+    rpc::call(&primary, "auto_church.mint_deed", params);
+
+    let local = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let mut replica = Replica::new(ReplicaConfig::default(), VerifyingBundle::default(), local.clone(), primary.clone());
+    assert_eq!(replica.sync(1_000).unwrap(), 1);
+    let copied = local.lock().unwrap().deeds()[0].clone();
+    assert_eq!(copied.ext, ext());
+    assert_eq!(copied.self_hash, primary.ledger.as_ref().unwrap().lock().unwrap().last_hash());
+    assert_eq!(replica.health().primary_schema_version, Some(SCHEMA_VERSION));
+
+    /// A primary on a newer schema that put a field outside `ext`.
+    struct Newer(RpcContext);
+    impl PrimaryTransport for Newer {
+        fn call(&self, method: &str, params: Value) -> Result<Value, ReplicaError> {
+            let mut result = self.0.call(method, params)?;
+            for deed in result["deeds"].as_array_mut().into_iter().flatten() {
+                deed["consent_qualifier"] = json!("guardian-present");
+            }
+            Ok(result)
+        }
+    }
+    let fresh = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let mut strict = Replica::new(ReplicaConfig::default(), VerifyingBundle::default(), fresh.clone(), Newer(primary));
+    match strict.sync(1_000) {
+        Err(ReplicaError::Malformed(reason)) => assert!(reason.contains("UNKNOWN_CRITICAL_FIELD: consent_qualifier"), "{reason}"),
+        other => panic!("expected a schema rejection, got {other:?}"),
+    }
+    assert!(fresh.lock().unwrap().deeds().is_empty());
+}
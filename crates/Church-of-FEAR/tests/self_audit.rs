@@ -0,0 +1,209 @@
+#![cfg(feature = "core")]
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::audit::{
+    sidecar_path, AuditFault, AuditPolicy, AuditTick, AuditViolation, IntegrityNotifier, JsonlDeeds, SelfAuditor,
+    SelfBudget, INTEGRITY_CLEARED, INTEGRITY_VIOLATION,
+};
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::deed_event::{DeedEvent, LinkFault};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use serde_json::json;
+
+const T0: i64 = 1_700_000_000;
+
+fn policy(slice_len: usize) -> AuditPolicy {
+    AuditPolicy { slice_len, ..AuditPolicy::default() }
+}
+
+fn budget() -> SelfBudget {
+    SelfBudget::new(1_000.0, 3_600, T0)
+}
+
+/// A ledger holding `n` reward deeds for alice.
+fn ledger(n: usize) -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    while ledger.deeds().len() < n {
+        ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    }
+    ledger
+}
+
+struct LedgerFile(PathBuf);
+
+impl LedgerFile {
+    fn write(name: &str, deeds: &[DeedEvent]) -> Self {
+        let dir = std::env::temp_dir().join(format!("cof-audit-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = Self(dir.join("ledger.jsonl"));
+        file.rewrite(deeds);
+        file
+    }
+
+    fn rewrite(&self, deeds: &[DeedEvent]) {
+        let body: String = deeds.iter().map(|d| serde_json::to_string(d).unwrap() + "\n").collect();
+        std::fs::write(&self.0, body).unwrap();
+    }
+
+    fn source(&self) -> JsonlDeeds {
+        JsonlDeeds { path: self.0.clone() }
+    }
+}
+
+impl Drop for LedgerFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.0.parent().unwrap());
+    }
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<AuditViolation>>>);
+
+impl IntegrityNotifier for Recorder {
+    fn notify(&self, violation: &AuditViolation) -> Result<(), String> {
+        self.0.lock().unwrap().push(violation.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn slices_resume_across_restarts_then_sweep() {
+    let mut ledger = ledger(10);
+    let file = LedgerFile::write("resume", ledger.deeds());
+    let sidecar = sidecar_path(&file.0);
+    let mut budget = budget();
+
+    let mut auditor = SelfAuditor::load(policy(4), &sidecar).unwrap();
+    let tick = auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0).unwrap();
+    assert_eq!(tick, AuditTick::Extended { start: 0, end: 4 });
+    auditor.save(&sidecar).unwrap();
+
+    // A restarted node picks up after the cached checkpoint.
+    let mut auditor = SelfAuditor::load(policy(4), &sidecar).unwrap();
+    assert_eq!(auditor.state().verified_len(), 4);
+    let ticks: Vec<AuditTick> =
+        (1..3).map(|i| auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + i).unwrap()).collect();
+    assert_eq!(ticks, [AuditTick::Extended { start: 4, end: 8 }, AuditTick::Extended { start: 8, end: 10 }]);
+    assert_eq!(auditor.state().last_full_verification, Some(T0 + 2));
+
+    // Caught up: cached slices are re-read one per tick.
+    let tick = auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + 3).unwrap();
+    assert_eq!(tick, AuditTick::Reverified { start: 0, end: 4 });
+    for i in 4..6 {
+        auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + i).unwrap();
+    }
+    let status = auditor.status();
+    assert_eq!(status.last_full_verification, Some(T0 + 5));
+    assert_eq!((status.verified_len, status.sweep, status.slices_verified), (10, 0, 6));
+}
+
+#[test]
+fn on_disk_corruption_is_found_frozen_and_alerted() {
+    let mut ledger = ledger(8);
+    let mut deeds = ledger.deeds().to_vec();
+    let file = LedgerFile::write("corrupt", &deeds);
+    let recorder = Recorder::default();
+    let mut auditor = SelfAuditor::new(policy(4)).with_notifier(recorder.clone());
+    let mut budget = budget();
+    for i in 0..2 {
+        auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + i).unwrap();
+    }
+
+    // A bit-flip in the middle of the chain after it was verified.
+    deeds[5].actor_id = "mallory".into();
+    file.rewrite(&deeds);
+    let tick = auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + 2).unwrap();
+    assert_eq!(tick, AuditTick::Reverified { start: 0, end: 4 });
+    let AuditTick::Violation(violation) = auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + 3).unwrap()
+    else {
+        panic!("corruption went unnoticed");
+    };
+    assert_eq!((violation.start, violation.end, violation.position), (4, 8, 5));
+    assert_eq!(violation.fault, AuditFault::Link { fault: LinkFault::SelfHash });
+
+    let logged = ledger.deeds().last().unwrap();
+    assert_eq!(logged.deed_type, INTEGRITY_VIOLATION);
+    assert_eq!(violation.deed_event_id.as_deref(), Some(logged.event_id.as_str()));
+    assert_eq!((logged.context_json["start"].clone(), logged.context_json["end"].clone()), (json!(4), json!(8)));
+    assert_eq!(ledger.mint_freeze(), Some(logged.event_id.as_str()));
+    assert_eq!(*recorder.0.lock().unwrap(), [violation]);
+
+    assert_eq!(auditor.idle_tick(&mut ledger, &file.source(), &mut budget, T0 + 4).unwrap(), AuditTick::Halted);
+    let acknowledged = auditor.acknowledge().unwrap();
+    assert_eq!(acknowledged.position, 5);
+    assert_eq!(auditor.state().verified_len(), 4);
+}
+
+#[test]
+fn frozen_ledgers_refuse_mints_until_lifted() {
+    let mut ledger = ledger(2);
+    let freeze = ledger.record_integrity_violation(json!({ "start": 0, "end": 2 })).unwrap().event_id.clone();
+    assert_eq!(ledger.mint_reward("alice", Token::Church, 5), Err(TokenLedgerError::MintsFrozen(freeze.clone())));
+    assert!(matches!(
+        ledger.reward_for("alice", Token::Church, 5, Some("ev-9")),
+        Err(TokenLedgerError::MintsFrozen(_))
+    ));
+    assert_eq!(ledger.burn("alice", Token::Church, 5), Ok(5));
+
+    // The freeze survives a restart.
+    let mut replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.mint_freeze(), Some(freeze.as_str()));
+    assert!(matches!(replayed.lift_mint_freeze("restored", "Member"), Err(TokenLedgerError::RoleNotAllowed(_))));
+    let cleared = replayed.lift_mint_freeze("log restored from backup", "Host").unwrap();
+    assert_eq!(cleared.deed_type, INTEGRITY_CLEARED);
+    assert_eq!(cleared.target_ids, [freeze]);
+    assert!(matches!(replayed.lift_mint_freeze("again", "Host"), Err(TokenLedgerError::NotFrozen)));
+    assert_eq!(replayed.mint_reward("alice", Token::Church, 5), Ok(5));
+    let replayed = TokenLedger::replay(replayed.config().clone(), replayed.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.mint_freeze(), None);
+}
+
+#[test]
+fn slices_beyond_the_eco_budget_are_skipped() {
+    let mut ledger = ledger(10);
+    let mut auditor = SelfAuditor::new(AuditPolicy { joules_per_deed: 10.0, ..policy(4) });
+    // 40 J for the slice, but only 50 J a period with a 20% reserve.
+    let mut budget = SelfBudget::new(50.0, 3_600, T0);
+    budget.spend(20.0);
+    let tick = auditor.idle_tick_in_memory(&mut ledger, &mut budget, T0).unwrap();
+    assert_eq!(tick, AuditTick::Skipped { needed_joules: 40, remaining_joules: 30 });
+    assert_eq!(auditor.state().verified_len(), 0);
+    assert_eq!(auditor.status().slices_skipped, 1);
+
+    // The next period's fresh budget affords it, leaving just the reserve.
+    let tick = auditor.idle_tick_in_memory(&mut ledger, &mut budget, T0 + 3_600).unwrap();
+    assert_eq!(tick, AuditTick::Extended { start: 0, end: 4 });
+    assert_eq!(budget.remaining(), 10.0);
+    let tick = auditor.idle_tick_in_memory(&mut ledger, &mut budget, T0 + 3_601).unwrap();
+    assert_eq!(tick, AuditTick::Skipped { needed_joules: 40, remaining_joules: 10 });
+    assert_eq!(auditor.status().slices_skipped, 2);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn status_is_served_over_rpc_and_as_metrics() {
+    let mut ledger = ledger(3);
+    let mut auditor = SelfAuditor::new(policy(8));
+    auditor.idle_tick_in_memory(&mut ledger, &mut budget(), T0).unwrap();
+    let metrics = auditor.status().to_prometheus();
+    assert!(metrics.contains("cof_audit_verified_deeds 3\n"), "{metrics}");
+    assert!(metrics.contains(&format!("cof_audit_last_full_verification_timestamp_seconds {}\n", T0)));
+    assert!(metrics.contains("# TYPE cof_audit_slices_skipped_total counter\n"));
+
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let request = json!({ "jsonrpc": "2.0", "method": "auto_church.audit_status", "params": {}, "id": 1 }).to_string();
+    let bare: Value = serde_json::from_str(&dispatch_request_with(&request, &RpcContext::default())).unwrap();
+    assert_eq!(bare["error"]["code"], 1004);
+
+    let ctx = RpcContext { auditor: Some(Arc::new(Mutex::new(auditor))), ..RpcContext::with_ledger(Arc::new(Mutex::new(ledger))) };
+    let resp: Value = serde_json::from_str(&dispatch_request_with(&request, &ctx)).unwrap();
+    assert_eq!(resp["result"]["verified_len"], 3);
+    assert_eq!(resp["result"]["last_full_verification"], T0);
+    assert_eq!(resp["result"]["mint_freeze"], Value::Null);
+}
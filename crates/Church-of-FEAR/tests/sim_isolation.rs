@@ -0,0 +1,131 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent, ExecutionDomain};
+use church_of_fear::ledger::token_ledger::{TokenLedger, TokenLedgerError};
+use church_of_fear::simulation::{ingest, SimulationError, SIM_RUN_OPEN, SIM_RUN_PROMOTED};
+
+const LIVE_ROUTE: &str = "auto_church.mint_deed";
+const SIM_ROUTE: &str = "AUTO_CHURCH_SIM";
+
+fn relief(prev_hash: String, actor: &str) -> DeedEvent {
+    HomelessnessReliefDeed::builder().actor_id(actor).location("Phoenix").hours(2.0).meals_served(10).build(prev_hash).unwrap()
+}
+
+fn simulated(mut deed: DeedEvent, run_id: &str) -> DeedEvent {
+    deed.domain = ExecutionDomain::Simulation { run_id: run_id.into() };
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+/// A live ledger with one rewarded deed and an open run `run-1` holding one.
+fn ledger_with_run() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    let live = relief(ledger.last_hash(), "alice");
+    let live = ledger.append(live).unwrap().event_id.clone();
+    ledger.reward_for("alice", Token::Church, 40, Some(&live)).unwrap();
+    let root = ledger.open_sim_run("run-1", "planner").unwrap().self_hash.clone();
+    ingest(&mut ledger, relief(root, "alice"), SIM_ROUTE, false, Some("run-1")).unwrap();
+    ledger
+}
+
+#[test]
+fn simulation_deeds_are_rejected_on_live_routes() {
+    let mut ledger = ledger_with_run();
+    let tagged = simulated(relief(ledger.last_hash(), "alice"), "run-1");
+    assert!(matches!(ledger.append(tagged.clone()), Err(TokenLedgerError::SimulationDeed(run)) if run == "run-1"));
+    assert_eq!(
+        ingest(&mut ledger, tagged, LIVE_ROUTE, false, None).unwrap_err(),
+        SimulationError::SimulationOnLiveRoute { route: LIVE_ROUTE.into(), run_id: "run-1".into() }
+    );
+
+    // Simulation routes and SimulationOnly behaviors force the domain.
+    let plain = relief(ledger.last_hash(), "bob");
+    let unrouted = ingest(&mut ledger, plain.clone(), SIM_ROUTE, false, None).unwrap_err();
+    assert_eq!(unrouted, SimulationError::RunRequired(SIM_ROUTE.into()));
+    let tip = ledger.sim_run("run-1").unwrap().tip_hash();
+    let forced = ingest(&mut ledger, relief(tip, "bob"), LIVE_ROUTE, true, Some("run-1")).unwrap().clone();
+    assert_eq!(forced.domain, ExecutionDomain::Simulation { run_id: "run-1".into() });
+    assert_eq!(forced.self_hash, hash_deed(&DeedEvent { self_hash: String::new(), ..forced.clone() }));
+    assert!(ledger.deeds().iter().all(|d| d.domain.is_live() && d.actor_id != "bob"));
+
+    // Each run is its own sub-chain, rooted in `sim_run_open`.
+    let run = ledger.sim_run("run-1").unwrap();
+    assert_eq!(run.deeds()[0].deed_type, SIM_RUN_OPEN);
+    assert!(run.deeds().windows(2).all(|w| w[1].prev_hash == w[0].self_hash));
+    assert!(matches!(ledger.open_sim_run("run-1", "planner"), Err(TokenLedgerError::SimRunExists(_))));
+    assert!(matches!(ledger.append_sim("run-9", plain), Err(TokenLedgerError::UnknownSimRun(_))));
+}
+
+#[test]
+fn shadow_balances_never_reach_live_supply() {
+    let mut ledger = ledger_with_run();
+    let live_supply = ledger.supply_report();
+    assert_eq!(ledger.sim_reward("run-1", "alice", Token::Church, 500, None), Ok(500));
+    assert_eq!(ledger.sim_reward("run-1", "alice", Token::Fear, 5, None), Err(TokenLedgerError::NotARewardToken(Token::Fear)));
+
+    let run = ledger.sim_run("run-1").unwrap();
+    assert_eq!(run.shadow_account("alice").unwrap().balance(Token::Church), 500);
+    assert!(run.supply_report().reconciles());
+    assert_eq!(run.supply_report().tokens[&Token::Church].issued, 500);
+    assert_eq!(ledger.account("alice").unwrap().balance(Token::Church), 40);
+    assert_eq!(ledger.supply_report(), live_supply);
+
+    // Replaying a full export rebuilds both sides, still apart.
+    let export: Vec<DeedEvent> = ledger.export_deeds(true).into_iter().cloned().collect();
+    let replayed = TokenLedger::replay(ledger.config().clone(), export).unwrap();
+    assert_eq!(replayed.supply_report(), live_supply);
+    assert_eq!(replayed.sim_run("run-1").unwrap().summary(), ledger.sim_run("run-1").unwrap().summary());
+}
+
+#[test]
+fn exports_are_live_only_unless_asked() {
+    let ledger = ledger_with_run();
+    let ids = |deeds: Vec<&DeedEvent>| deeds.into_iter().map(|d| d.event_id.clone()).collect::<Vec<_>>();
+    let live: Vec<&DeedEvent> = ledger.deeds().iter().collect();
+    assert_eq!(ids(ledger.export_deeds(false)), ids(live.clone()));
+    let all = ledger.export_deeds(true);
+    assert_eq!(all.len(), live.len() + ledger.sim_run("run-1").unwrap().deeds().len());
+    assert!(all[live.len()..].iter().all(|d| d.domain.run_id() == Some("run-1")));
+
+    // Live deeds serialize without the domain tag, so their hashes are unchanged.
+    let json = serde_json::to_value(live[0]).unwrap();
+    assert!(json.get("domain").is_none());
+    assert_eq!(serde_json::to_value(all[live.len()]).unwrap()["domain"]["simulation"]["run_id"], "run-1");
+}
+
+#[test]
+fn promotion_copies_only_a_summary_into_the_live_chain() {
+    let mut ledger = ledger_with_run();
+    ledger.sim_reward("run-1", "alice", Token::Church, 500, None).unwrap();
+    let live_supply = ledger.supply_report();
+    let before = ledger.deeds().len();
+
+    assert!(matches!(ledger.promote_sim_run("run-1", "looks good", "Member"), Err(TokenLedgerError::RoleNotAllowed(_))));
+    let promoted = ledger.promote_sim_run("run-1", "pilot passed review", "Regulator").unwrap().clone();
+    assert_eq!(ledger.deeds().len(), before + 1);
+    assert_eq!((promoted.deed_type.as_str(), promoted.domain.is_live()), (SIM_RUN_PROMOTED, true));
+    assert_eq!(promoted.context_json["deeds"], 3);
+    assert_eq!(promoted.context_json["deed_types"]["homelessness_relief"], 1);
+    assert_eq!(promoted.context_json["shadow_supply"]["tokens"]["church"]["issued"], 500);
+    let sim_ids: Vec<&str> = ledger.sim_run("run-1").unwrap().deeds().iter().map(|d| d.event_id.as_str()).collect();
+    assert!(ledger.deeds().iter().all(|d| d.domain.is_live() && !sim_ids.contains(&d.event_id.as_str())));
+    assert_eq!(ledger.supply_report(), live_supply);
+
+    // The run is closed, and summaries are the ledger's to write.
+    assert_eq!(ledger.sim_run("run-1").unwrap().promoted(), Some(promoted.event_id.as_str()));
+    let tip = ledger.sim_run("run-1").unwrap().tip_hash();
+    assert!(matches!(ledger.append_sim("run-1", relief(tip, "alice")), Err(TokenLedgerError::SimRunPromoted(_))));
+    assert!(matches!(ledger.promote_sim_run("run-1", "again", "Regulator"), Err(TokenLedgerError::SimRunPromoted(_))));
+    let mut forged = relief(ledger.last_hash(), "alice");
+    forged.deed_type = SIM_RUN_PROMOTED.into();
+    assert!(matches!(ledger.append(forged), Err(TokenLedgerError::ReservedDeedType(_))));
+
+    let export: Vec<DeedEvent> = ledger.export_deeds(true).into_iter().cloned().collect();
+    let replayed = TokenLedger::replay(ledger.config().clone(), export).unwrap();
+    assert_eq!(replayed.sim_run("run-1").unwrap().promoted(), Some(promoted.event_id.as_str()));
+}
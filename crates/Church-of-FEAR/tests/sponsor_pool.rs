@@ -0,0 +1,238 @@
+#![cfg(feature = "core")]
+
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::sponsor::pool::{
+    pool_status, project_runway, InflowSource, LowWaterAlert, PlannedReward, PoolAlertNotifier, PoolError, SponsorPool,
+    POOL_INFLOW, POOL_LOW_WATER,
+};
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<LowWaterAlert>>>);
+
+impl PoolAlertNotifier for Recorder {
+    fn notify(&self, alert: &LowWaterAlert) -> Result<(), String> {
+        self.0.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn ledger(tithe_bps: u32) -> TokenLedger {
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.tithe_bps = tithe_bps;
+    let mut ledger = TokenLedger::new(cfg);
+    for id in ["alice", "bob", "carol"] {
+        ledger.open_account(id, id);
+    }
+    ledger
+}
+
+fn reward(id: &str, amount: u64) -> PlannedReward {
+    PlannedReward { account_id: id.to_string(), amount, reason: "sponsored repair".to_string() }
+}
+
+#[test]
+fn church_mints_route_the_tithe_to_the_pool() {
+    let mut ledger = ledger(1_000);
+    assert_eq!(ledger.mint_reward("alice", Token::Church, 100).unwrap(), 90);
+    assert_eq!(ledger.account("alice").unwrap().balance_church, 90);
+    assert_eq!(ledger.pool_balance(), 10);
+
+    let inflow = ledger.deeds().iter().find(|d| d.deed_type == POOL_INFLOW).unwrap();
+    assert_eq!(inflow.context_json["source"], "tithe");
+    assert_eq!(inflow.context_json["amount"], 10);
+
+    // PWR is not tithed, and the default policy leaves CHURCH untouched.
+    ledger.mint_reward("alice", Token::Pwr, 100).unwrap();
+    assert_eq!(ledger.pool_balance(), 10);
+    let mut untithed = self::ledger(0);
+    assert_eq!(untithed.mint_reward("alice", Token::Church, 100).unwrap(), 100);
+    assert_eq!(untithed.pool_balance(), 0);
+    assert!(ledger.supply_report().reconciles());
+}
+
+#[test]
+fn depleted_pool_scales_plans_down_proportionally() {
+    let mut ledger = ledger(5_000);
+    ledger.mint_reward("carol", Token::Church, 120).unwrap();
+    assert_eq!(ledger.pool_balance(), 60);
+
+    let mut pool = SponsorPool::new(&ledger);
+    let funded = pool.fund_plan(&mut ledger, &[reward("alice", 80), reward("bob", 40)], now()).unwrap();
+    assert_eq!(funded.requested, 120);
+    assert_eq!(funded.available, 60);
+    assert_eq!(funded.scale, 0.5);
+    assert_eq!(funded.paid.iter().map(|p| p.amount).collect::<Vec<_>>(), [40, 20]);
+    assert_eq!(ledger.pool_balance(), 0);
+    assert_eq!(ledger.account("alice").unwrap().balance_church, 40);
+
+    // An empty pool pays nothing rather than overdrawing.
+    let funded = pool.fund_plan(&mut ledger, &[reward("bob", 10)], now()).unwrap();
+    assert_eq!(funded.total_paid(), 0);
+    assert!(ledger.supply_report().reconciles());
+}
+
+#[test]
+fn recycling_follows_the_policy() {
+    let mut ledger = ledger(0);
+    ledger.mint_reward("bob", Token::Church, 50).unwrap();
+    let mut pool = SponsorPool::new(&ledger);
+    assert_eq!(pool.recycle(&mut ledger, InflowSource::RecycledSlash, "bob", 30, "slash-1", now()).unwrap(), 30);
+    assert_eq!(ledger.account("bob").unwrap().balance_church, 20);
+    assert_eq!(ledger.pool_balance(), 30);
+    assert!(matches!(
+        pool.recycle(&mut ledger, InflowSource::Tithe, "bob", 1, "x", now()),
+        Err(PoolError::NotRecycled(InflowSource::Tithe))
+    ));
+
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.recycle_expired_obligations = false;
+    let mut strict = TokenLedger::new(cfg);
+    strict.open_account("bob", "bob");
+    let mut pool = SponsorPool::new(&strict);
+    assert!(matches!(
+        pool.recycle(&mut strict, InflowSource::RecycledObligation, "bob", 1, "ob-1", now()),
+        Err(PoolError::RecyclingDisabled(_))
+    ));
+    assert!(ledger.supply_report().reconciles());
+}
+
+#[test]
+fn low_water_alert_fires_once_per_crossing() {
+    let mut ledger = ledger(5_000);
+    ledger.mint_reward("carol", Token::Church, 300).unwrap();
+    let recorder = Recorder::default();
+    let mut pool = SponsorPool::new(&ledger).with_notifier(recorder.clone());
+
+    pool.fund_plan(&mut ledger, &[reward("alice", 80)], now()).unwrap();
+    pool.fund_plan(&mut ledger, &[reward("alice", 10)], now()).unwrap();
+    assert_eq!(ledger.pool_balance(), 60);
+    assert_eq!(recorder.0.lock().unwrap().len(), 1);
+    assert_eq!(recorder.0.lock().unwrap()[0].balance, 70);
+    assert_eq!(ledger.deeds().iter().filter(|d| d.deed_type == POOL_LOW_WATER).count(), 1);
+
+    // Refilling re-arms the latch; the next drop alerts again.
+    ledger.mint_reward("carol", Token::Church, 200).unwrap();
+    assert_eq!(pool.check_low_water(&mut ledger, now()).unwrap(), None);
+    pool.fund_plan(&mut ledger, &[reward("bob", 100)], now()).unwrap();
+    assert_eq!(recorder.0.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn runway_projects_from_trailing_outflow() {
+    assert_eq!(project_runway(1_000, 0, 86_400), None);
+    assert_eq!(project_runway(1_000, 100, 86_400), Some(864_000));
+    assert_eq!(project_runway(u64::MAX, 1, i64::MAX), Some(u64::MAX));
+
+    let mut ledger = ledger(5_000);
+    ledger.mint_reward("carol", Token::Church, 400).unwrap();
+    SponsorPool::new(&ledger).fund_plan(&mut ledger, &[reward("alice", 50)], now()).unwrap();
+
+    // The pool is younger than the burn window, so the window shrinks to
+    // its age instead of diluting the burn rate.
+    let at = now() + 100;
+    let status = pool_status(&ledger, at);
+    assert_eq!(status.balance, 150);
+    assert_eq!(status.inflow_in_window, 200);
+    assert_eq!(status.outflow_in_window, 50);
+    assert!((100..=102).contains(&status.window_secs), "{}", status.window_secs);
+    assert_eq!(status.runway_secs, project_runway(150, 50, status.window_secs));
+    assert!(!status.below_low_water);
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_reports_pool_status_from_the_attached_ledger() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::Value;
+
+    let request = r#"{"jsonrpc":"2.0","method":"auto_church.pool_status","params":null,"id":1}"#;
+    let detached: Value = serde_json::from_str(&dispatch_request_with(request, &RpcContext::default())).unwrap();
+    assert_eq!(detached["error"]["code"], 1004);
+
+    let mut ledger = ledger(1_000);
+    ledger.mint_reward("alice", Token::Church, 1_000).unwrap();
+    let ctx = RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() };
+    let attached: Value = serde_json::from_str(&dispatch_request_with(request, &ctx)).unwrap();
+    assert_eq!(attached["result"]["balance"], 100);
+    assert_eq!(attached["result"]["below_low_water"], false);
+}
+
+#[cfg(feature = "pool-topup")]
+mod top_up {
+    use super::*;
+    use church_of_fear::sponsor::pool::{SignedTopUp, TopUpRequest, SPONSOR_POOL};
+    use keyring::{Keyring, VerifyingBundle};
+
+    const T: i64 = 1_700_000_000;
+
+    fn authorities(n: usize) -> (Keyring, Vec<String>) {
+        let mut keyring = Keyring::new().with_clock(|| T as u64);
+        let names = (0..n).map(|_| keyring.generate("pool-authority").unwrap()).collect();
+        (keyring, names)
+    }
+
+    fn signed(keyring: &Keyring, signers: &[String], id: &str) -> SignedTopUp {
+        let request = TopUpRequest { id: id.to_string(), amount: 500, memo: "quarterly".to_string(), requested_at: T };
+        let approvals = signers.iter().map(|s| keyring.sign(s, &request.signing_bytes()).unwrap()).collect();
+        SignedTopUp { request, approvals }
+    }
+
+    #[test]
+    fn top_up_needs_threshold_of_distinct_authorities() {
+        let (keyring, names) = authorities(3);
+        let bundle = keyring.verifying_bundle();
+        let mut ledger = ledger(0);
+        let mut pool = SponsorPool::new(&ledger);
+
+        let one = signed(&keyring, &names[..1], "t-1");
+        assert!(matches!(
+            pool.top_up(&mut ledger, &one, &bundle, T),
+            Err(PoolError::NotEnoughApprovals { got: 1, need: 2 })
+        ));
+        // The same key twice still counts once.
+        let doubled = signed(&keyring, &[names[0].clone(), names[0].clone()], "t-1");
+        assert!(matches!(pool.top_up(&mut ledger, &doubled, &bundle, T), Err(PoolError::NotEnoughApprovals { .. })));
+
+        let ok = signed(&keyring, &names[..2], "t-1");
+        assert_eq!(pool.top_up(&mut ledger, &ok, &bundle, T).unwrap(), 500);
+        assert_eq!(ledger.pool_balance(), 500);
+        assert!(matches!(pool.top_up(&mut ledger, &ok, &bundle, T), Err(PoolError::Replayed(_))));
+
+        // Applied ids survive a rebuild from the ledger.
+        let mut rebuilt = SponsorPool::new(&ledger);
+        assert!(matches!(rebuilt.top_up(&mut ledger, &ok, &bundle, T), Err(PoolError::Replayed(_))));
+        assert!(matches!(
+            rebuilt.top_up(&mut ledger, &signed(&keyring, &names[1..], "t-2"), &bundle, T + 7_200),
+            Err(PoolError::Stale { .. })
+        ));
+        assert!(ledger.supply_report().reconciles());
+    }
+
+    #[test]
+    fn top_up_rejects_foreign_and_forged_approvals() {
+        let (keyring, names) = authorities(2);
+        let mut rogue = Keyring::new().with_clock(|| T as u64);
+        let operator = rogue.generate("operator").unwrap();
+        let bundle = VerifyingBundle { keys: keyring.keys().chain(rogue.keys()).cloned().collect() };
+        let mut ledger = ledger(0);
+        let mut pool = SponsorPool::new(&ledger);
+
+        let wrong_purpose = signed(&rogue, std::slice::from_ref(&operator), "t-1");
+        let mut mixed = signed(&keyring, &names[..1], "t-1");
+        mixed.approvals.extend(wrong_purpose.approvals);
+        assert!(matches!(pool.top_up(&mut ledger, &mixed, &bundle, T), Err(PoolError::NotAuthority(k)) if k == operator));
+
+        let mut forged = signed(&keyring, &names, "t-1");
+        forged.request.amount = 5_000;
+        assert!(matches!(pool.top_up(&mut ledger, &forged, &bundle, T), Err(PoolError::Signature(_))));
+        assert_eq!(ledger.pool_balance(), 0);
+        assert!(ledger.account(SPONSOR_POOL).is_none());
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::sponsor::pool::{PlannedReward, SponsorPool};
+use church_of_fear::sponsor::throttle::{RewardThrottlePolicy, Suppression, DAY_SECS};
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn ledger(throttle: RewardThrottlePolicy) -> TokenLedger {
+    let mut cfg = LedgerConfig::default();
+    cfg.pool.tithe_bps = 5_000;
+    cfg.pool.throttle = throttle;
+    let mut ledger = TokenLedger::new(cfg);
+    for id in ["alice", "bob", "carol", "dave"] {
+        ledger.open_account(id, id);
+    }
+    ledger.mint_reward("carol", Token::Church, 20_000).unwrap();
+    ledger
+}
+
+fn reward(id: &str, amount: u64) -> PlannedReward {
+    PlannedReward { account_id: id.to_string(), amount, reason: "sponsored repair".to_string() }
+}
+
+fn church(ledger: &TokenLedger, id: &str) -> u64 {
+    ledger.account(id).unwrap().balance_church
+}
+
+#[test]
+fn ceilings_hold_under_fast_ticks() {
+    let policy = RewardThrottlePolicy {
+        min_interval_secs: 60,
+        account_daily_ceiling: 200,
+        global_daily_ceiling: 500,
+        ..RewardThrottlePolicy::default()
+    };
+    let mut ledger = ledger(policy);
+    let carol = church(&ledger, "carol");
+    let mut pool = SponsorPool::new(&ledger);
+    let plan = [reward("alice", 50), reward("bob", 50), reward("carol", 50)];
+    let start = now();
+
+    // Two ticks a second for three hours, every tick planning 50 CHURCH each.
+    let ticks = 3 * 3_600 * 2;
+    let mut paid = 0;
+    for tick in 0..ticks {
+        let funded = pool.fund_plan(&mut ledger, &plan, start + tick / 2).unwrap();
+        paid += funded.total_paid();
+        assert!(paid <= 500, "tick {tick}: {paid} paid");
+        let withheld: u64 = funded.suppressed.iter().map(|s| s.reward.amount).sum();
+        assert_eq!(funded.total_paid() + withheld, 150);
+    }
+    assert_eq!(paid, 500);
+    assert_eq!(church(&ledger, "alice"), 200);
+    assert_eq!(church(&ledger, "bob"), 150);
+    assert_eq!(church(&ledger, "carol") - carol, 150);
+
+    let counters = pool.throttle().counters();
+    assert!(counters.cooldown > 0 && counters.account_ceiling > 0 && counters.global_ceiling > 0);
+    assert_eq!(counters.church_withheld + paid, ticks as u64 * 150);
+
+    // A rebuilt pool remembers the day's payouts.
+    let funded = SponsorPool::new(&ledger).fund_plan(&mut ledger, &[reward("dave", 50)], now()).unwrap();
+    assert_eq!(funded.total_paid(), 0);
+    assert_eq!(funded.suppressed[0].cause, Suppression::GlobalCeiling);
+
+    // Once the window has slid past every payout there is room again.
+    let later = start + DAY_SECS + 180;
+    let funded = pool.fund_plan(&mut ledger, &plan, later).unwrap();
+    assert_eq!(funded.total_paid(), 150);
+    assert!(ledger.supply_report().reconciles());
+}
+
+#[test]
+fn repeated_accounts_in_one_plan_wait_for_the_cooldown() {
+    let policy = RewardThrottlePolicy { min_interval_secs: 10, ..RewardThrottlePolicy::default() };
+    let mut ledger = ledger(policy);
+    let mut pool = SponsorPool::new(&ledger);
+    let at = now();
+    let funded = pool.fund_plan(&mut ledger, &[reward("alice", 5), reward("alice", 7), reward("bob", 3)], at).unwrap();
+    assert_eq!(funded.paid, [reward("alice", 5), reward("bob", 3)]);
+    assert_eq!(funded.suppressed.len(), 1);
+    assert_eq!((funded.suppressed[0].cause, funded.suppressed[0].reward.amount), (Suppression::Cooldown, 7));
+
+    assert_eq!(pool.fund_plan(&mut ledger, &[reward("alice", 5)], at + 9).unwrap().total_paid(), 0);
+    assert_eq!(pool.fund_plan(&mut ledger, &[reward("alice", 5)], at + 10).unwrap().total_paid(), 5);
+
+    // The default policy throttles nothing.
+    let mut open = self::ledger(RewardThrottlePolicy::default());
+    let mut pool = SponsorPool::new(&open);
+    let funded = pool.fund_plan(&mut open, &[reward("alice", 5), reward("alice", 7)], at).unwrap();
+    assert_eq!((funded.total_paid(), funded.suppressed.len()), (12, 0));
+}
+
+#[test]
+fn balancing_burns_skip_ceilings_but_not_their_interval() {
+    let policy = RewardThrottlePolicy { global_daily_ceiling: 1, burn_min_interval_secs: 30, ..RewardThrottlePolicy::default() };
+    let mut ledger = ledger(policy);
+    ledger.mint_reward("alice", Token::Pwr, 100).unwrap();
+    ledger.mint_reward("bob", Token::Pwr, 10).unwrap();
+    let mut pool = SponsorPool::new(&ledger);
+    let at = now();
+    pool.fund_plan(&mut ledger, &[reward("bob", 50)], at).unwrap();
+
+    assert_eq!(pool.balance_burn(&mut ledger, "alice", Token::Pwr, 20, at).unwrap(), Some(20));
+    assert_eq!(pool.balance_burn(&mut ledger, "alice", Token::Pwr, 20, at + 29).unwrap(), None);
+    assert_eq!(pool.balance_burn(&mut ledger, "bob", Token::Pwr, 5, at + 29).unwrap(), Some(5));
+    assert_eq!(pool.balance_burn(&mut ledger, "alice", Token::Pwr, 20, at + 30).unwrap(), Some(20));
+    assert_eq!(ledger.account("alice").unwrap().balance_pwr, 60);
+    assert_eq!(pool.throttle().counters().burn_rate, 1);
+}
@@ -0,0 +1,279 @@
+#![cfg(feature = "rpc")]
+
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+use church_of_fear::submission::{
+    content_hash, MatchedBy, SubmissionGuard, SubmissionPolicy, Transport,
+};
+use church_of_fear::utils::correlation::CORRELATION_KEY;
+use serde_json::{json, Value};
+
+const T0: i64 = 1_700_000_000;
+
+/// A compliant reading by `actor`; readings differ only in `co2_kg`.
+fn deed(prev_hash: String, actor: &str, co2_kg: f64) -> DeedEvent {
+    DeedEvent::new(
+        prev_hash,
+        actor.to_string(),
+        vec!["target:local-watershed".to_string()],
+        "ecological_sustainability".to_string(),
+        vec!["eco".to_string()],
+        json!({ "location": "Phoenix, AZ", "co2_kg": co2_kg, "evidence_uri": "ipfs://riverbank-planting" }),
+        vec![],
+        false,
+    )
+}
+
+fn mint_params(prev_hash: String, actor: &str, co2_kg: f64, idempotency_key: Option<&str>) -> Value {
+    let mut params = json!({
+        "prev_hash": prev_hash,
+        "actor_id": actor,
+        "target_ids": ["target:local-watershed"],
+        "deed_type": "ecological_sustainability",
+        "tags": ["eco"],
+        "context_json": { "location": "Phoenix, AZ", "co2_kg": co2_kg, "evidence_uri": "ipfs://riverbank-planting" },
+        "ethics_flags": [],
+        "life_harm_flag": false,
+        "bioload_delta": -0.12,
+        "roh": 0.2,
+        "decay": 0.7,
+    });
+    if let Some(key) = idempotency_key {
+        params["idempotency_key"] = json!(key);
+    }
+    params
+}
+
+fn call(ctx: &RpcContext, method: &str, params: Value) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+    serde_json::from_str(&dispatch_request_with(&request.to_string(), ctx)).unwrap()
+}
+
+fn guarded() -> (RpcContext, Arc<Mutex<TokenLedger>>, Arc<Mutex<SubmissionGuard>>) {
+    let ledger = Arc::new(Mutex::new(TokenLedger::new(LedgerConfig::default())));
+    let guard = Arc::new(Mutex::new(SubmissionGuard::new(SubmissionPolicy::default())));
+    let ctx = RpcContext { ledger: Some(ledger.clone()), submissions: Some(guard.clone()), ..RpcContext::default() };
+    (ctx, ledger, guard)
+}
+
+fn tip(ledger: &Arc<Mutex<TokenLedger>>) -> String {
+    ledger.lock().unwrap().last_hash()
+}
+
+#[cfg(feature = "binary-wire")]
+#[test]
+fn a_submission_is_refused_on_every_transport_sharing_the_guard() {
+    use church_of_fear::submission::DUPLICATE_SUBMISSION_CODE;
+    use church_of_fear::ledger::metrics::BioloadMetrics;
+    use church_of_fear::rpc::wire::{submit_frame, CompactDeed, DeedOutcome, Frame, WireReply};
+
+    let (ctx, ledger, guard) = guarded();
+    let first = call(&ctx, "auto_church.mint_deed", mint_params(tip(&ledger), "sensor:eco-3", 0.5, Some("epoch-1")));
+    let event_id = first["result"]["deed"]["event_id"].as_str().unwrap().to_string();
+    let stored = ledger.lock().unwrap().deeds().len();
+
+    // A retry with the same key is refused even though its content changed.
+    let retry = call(&ctx, "auto_church.mint_deed", mint_params(tip(&ledger), "sensor:eco-3", 0.6, Some("epoch-1")));
+    assert_eq!(retry["error"]["code"], DUPLICATE_SUBMISSION_CODE);
+    assert_eq!(retry["error"]["data"]["event_id"], event_id.as_str());
+    assert_eq!(retry["error"]["data"]["matched_by"], "idempotency_key");
+
+    // The same reading over the binary endpoint, which has no key to send.
+    let metrics = BioloadMetrics::new(-0.12, 0.2, 0.7);
+    let frame = Frame::Deed(CompactDeed::from_deed(&deed(tip(&ledger), "sensor:eco-3", 0.5), &metrics).unwrap()).encode();
+    match submit_frame(&frame, &ctx) {
+        WireReply::Outcomes(outcomes) => match &outcomes[..] {
+            [DeedOutcome::Rejected { code, message }] => {
+                assert_eq!(*code, DUPLICATE_SUBMISSION_CODE);
+                assert!(message.contains(&event_id), "{}", message);
+            }
+            other => panic!("expected one rejection, got {:?}", other),
+        },
+        other => panic!("expected outcomes, got {:?}", other),
+    }
+    assert_eq!(ledger.lock().unwrap().deeds().len(), stored);
+
+    // A new reading over the binary endpoint goes through, and is then
+    // refused over JSON-RPC.
+    let frame = Frame::Deed(CompactDeed::from_deed(&deed(tip(&ledger), "sensor:eco-3", 0.7), &metrics).unwrap()).encode();
+    match submit_frame(&frame, &ctx) {
+        WireReply::Outcomes(outcomes) => assert!(matches!(&outcomes[..], [DeedOutcome::Accepted { .. }]), "{:?}", outcomes),
+        other => panic!("expected outcomes, got {:?}", other),
+    }
+    let resent = call(&ctx, "auto_church.mint_deed", mint_params(tip(&ledger), "sensor:eco-3", 0.7, None));
+    assert_eq!(resent["error"]["code"], DUPLICATE_SUBMISSION_CODE);
+    assert_eq!(resent["error"]["data"]["transport"], "binary");
+    assert_eq!(resent["error"]["data"]["matched_by"], "content_hash");
+
+    let metrics = guard.lock().unwrap().metrics();
+    assert_eq!(metrics.admitted[&Transport::JsonRpc], 1);
+    assert_eq!(metrics.admitted[&Transport::Binary], 1);
+    assert_eq!((metrics.hits[&Transport::JsonRpc].idempotency_key, metrics.hits[&Transport::JsonRpc].content_hash), (1, 1));
+    assert_eq!(metrics.hits[&Transport::Binary].content_hash, 1);
+    let text = metrics.to_prometheus();
+    assert!(text.contains("cof_dedup_hits_total{transport=\"binary\",matched_by=\"content_hash\"} 1\n"));
+    assert!(text.contains("cof_dedup_hits_total{transport=\"binary\",matched_by=\"idempotency_key\"} 0\n"));
+    assert!(text.contains("cof_dedup_entries 2\n"));
+}
+
+#[test]
+fn the_window_file_closes_the_restart_gap() {
+    let path = std::env::temp_dir().join(format!("cof-submissions-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let policy = SubmissionPolicy { store_path: Some(path.display().to_string()), ..SubmissionPolicy::default() };
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    let mut guard = SubmissionGuard::open(policy.clone(), &ledger, T0).unwrap();
+
+    // Stored and settled.
+    let stored = deed(ledger.last_hash(), "sensor:a", 1.0);
+    let claim = guard.admit(&stored, Some("k-1"), Transport::JsonRpc, T0).unwrap();
+    ledger.append(stored.clone()).unwrap();
+    guard.settle(claim, true);
+    // Stored, and the node stopped before the outcome was written.
+    let unsettled = deed(ledger.last_hash(), "sensor:b", 2.0);
+    let _claim = guard.admit(&unsettled, None, Transport::Binary, T0 + 1).unwrap();
+    ledger.append(unsettled.clone()).unwrap();
+    // Claimed, and the node stopped before the append.
+    let lost = deed(ledger.last_hash(), "sensor:c", 3.0);
+    let _claim = guard.admit(&lost, None, Transport::Binary, T0 + 2).unwrap();
+    // Refused by the ledger.
+    let refused = deed("not-the-tip".into(), "sensor:d", 4.0);
+    let claim = guard.admit(&refused, None, Transport::JsonRpc, T0 + 3).unwrap();
+    assert!(ledger.append(refused.clone()).is_err());
+    guard.settle(claim, false);
+    drop(guard);
+    // A line torn by the stop is skipped.
+    fs::write(&path, fs::read_to_string(&path).unwrap() + "{\"claimed\":{\"actor_id\":").unwrap();
+
+    let mut guard = SubmissionGuard::open(policy.clone(), &ledger, T0 + 60).unwrap();
+    assert_eq!((guard.metrics().restored, guard.len()), (2, 2));
+    let resent = deed(ledger.last_hash(), "sensor:a", 1.0);
+    let duplicate = guard.admit(&resent, None, Transport::Binary, T0 + 60).unwrap_err();
+    assert_eq!((duplicate.event_id.as_str(), duplicate.matched_by), (stored.event_id.as_str(), MatchedBy::ContentHash));
+    let rekeyed = deed(ledger.last_hash(), "sensor:a", 1.5);
+    let duplicate = guard.admit(&rekeyed, Some("k-1"), Transport::JsonRpc, T0 + 60).unwrap_err();
+    assert_eq!(duplicate.matched_by, MatchedBy::IdempotencyKey);
+    let duplicate = guard.admit(&deed(ledger.last_hash(), "sensor:b", 2.0), None, Transport::JsonRpc, T0 + 60).unwrap_err();
+    assert_eq!((duplicate.event_id, duplicate.transport), (unsettled.event_id.clone(), Transport::Binary));
+    // Nothing of the lost or refused submissions reached the ledger, so
+    // their retries go through.
+    let _claim = guard.admit(&deed(ledger.last_hash(), "sensor:c", 3.0), None, Transport::JsonRpc, T0 + 60).unwrap();
+    let _claim = guard.admit(&deed(ledger.last_hash(), "sensor:d", 4.0), None, Transport::JsonRpc, T0 + 60).unwrap();
+    drop(guard);
+
+    // Past the window nothing comes back.
+    let mut guard = SubmissionGuard::open(policy, &ledger, T0 + 700).unwrap();
+    assert!(guard.is_empty());
+    let _claim = guard.admit(&deed(ledger.last_hash(), "sensor:a", 1.0), None, Transport::JsonRpc, T0 + 700).unwrap();
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn the_hot_store_stays_within_its_capacity() {
+    let policy = SubmissionPolicy { hot_capacity: 100, ..SubmissionPolicy::default() };
+    let mut guard = SubmissionGuard::new(policy);
+    let readings: Vec<DeedEvent> = (0..150).map(|i| deed(DeedEvent::genesis().self_hash, "sensor:load", i as f64)).collect();
+    for reading in &readings[..100] {
+        let claim = guard.admit(reading, None, Transport::Binary, T0).unwrap();
+        guard.settle(claim, true);
+    }
+    // Matching the oldest entry makes it the most recent.
+    assert!(guard.admit(&readings[0], None, Transport::Binary, T0 + 1).is_err());
+    for reading in &readings[100..] {
+        let claim = guard.admit(reading, None, Transport::Binary, T0 + 2).unwrap();
+        guard.settle(claim, true);
+    }
+    assert_eq!((guard.len(), guard.metrics().evicted), (100, 50));
+    assert!(guard.admit(&readings[0], None, Transport::Binary, T0 + 3).is_err());
+    for reading in &readings[51..] {
+        assert!(guard.admit(reading, None, Transport::Binary, T0 + 3).is_err());
+    }
+    // Readings 1..=50 were evicted; they are admitted again.
+    let _claim = guard.admit(&readings[1], None, Transport::Binary, T0 + 4).unwrap();
+    assert_eq!((guard.len(), guard.metrics().evicted), (100, 51));
+
+    // Sustained load never holds more than the capacity.
+    let mut guard = SubmissionGuard::new(SubmissionPolicy { hot_capacity: 1_000, ..SubmissionPolicy::default() });
+    for i in 0..20_000 {
+        let reading = deed(DeedEvent::genesis().self_hash, &format!("sensor:{}", i % 37), i as f64);
+        let claim = guard.admit(&reading, Some(&format!("epoch-{}", i)), Transport::JsonRpc, T0 + i / 100).unwrap();
+        guard.settle(claim, true);
+        assert!(guard.len() <= 1_000);
+    }
+    let metrics = guard.metrics();
+    assert_eq!((metrics.entries, metrics.evicted, metrics.admitted[&Transport::JsonRpc]), (1_000, 19_000, 20_000));
+}
+
+#[test]
+fn an_evicted_submission_is_refused_from_the_window_file() {
+    let path = std::env::temp_dir().join(format!("cof-submissions-spilled-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let policy = SubmissionPolicy { hot_capacity: 2, store_path: Some(path.display().to_string()), ..SubmissionPolicy::default() };
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let mut guard = SubmissionGuard::open(policy.clone(), &ledger, T0).unwrap();
+    let readings: Vec<DeedEvent> = (0..5).map(|i| deed(DeedEvent::genesis().self_hash, "sensor:spill", i as f64)).collect();
+    for (i, reading) in readings.iter().enumerate() {
+        let claim = guard.admit(reading, Some(&format!("epoch-{}", i)), Transport::Binary, T0 + i as i64).unwrap();
+        guard.settle(claim, true);
+    }
+    let metrics = guard.metrics();
+    assert_eq!((metrics.entries, metrics.evicted, metrics.spilled), (2, 3, 3));
+
+    // The first two readings left memory but are still in their window.
+    let duplicate = guard.admit(&readings[0], None, Transport::JsonRpc, T0 + 10).unwrap_err();
+    assert_eq!((duplicate.event_id.as_str(), duplicate.matched_by), (readings[0].event_id.as_str(), MatchedBy::ContentHash));
+    let rekeyed = deed(DeedEvent::genesis().self_hash, "sensor:spill", 9.0);
+    let duplicate = guard.admit(&rekeyed, Some("epoch-1"), Transport::JsonRpc, T0 + 10).unwrap_err();
+    assert_eq!((duplicate.event_id.as_str(), duplicate.matched_by), (readings[1].event_id.as_str(), MatchedBy::IdempotencyKey));
+    assert!(guard.metrics().to_prometheus().contains("cof_dedup_spilled_entries 3\n"));
+    drop(guard);
+
+    // A restart keeps every entry in the file, not just the hot ones.
+    let mut guard = SubmissionGuard::open(policy, &ledger, T0 + 20).unwrap();
+    let claims = fs::read_to_string(&path).unwrap().lines().filter(|l| l.starts_with("{\"claimed\"")).count();
+    assert_eq!((claims, guard.len(), guard.metrics().restored), (5, 2, 5));
+    for reading in &readings {
+        assert!(guard.admit(reading, None, Transport::Binary, T0 + 20).is_err());
+    }
+    // Past its window a spilled reading is new again.
+    let _claim = guard.admit(&readings[0], None, Transport::Binary, T0 + 600).unwrap();
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn distinct_submissions_from_one_actor_and_category_go_through() {
+    let (ctx, ledger, guard) = guarded();
+    for (actor, co2_kg, key) in [
+        ("sensor:eco-3", 0.5, Some("epoch-1")),
+        ("sensor:eco-3", 0.6, Some("epoch-2")),
+        ("sensor:eco-3", 0.7, None),
+        // The same reading and key from another actor is its own submission.
+        ("sensor:eco-4", 0.5, Some("epoch-1")),
+    ] {
+        let response = call(&ctx, "auto_church.mint_deed", mint_params(tip(&ledger), actor, co2_kg, key));
+        assert!(response["error"].is_null(), "{} {}: {}", actor, co2_kg, response);
+    }
+
+    // A mint the ledger refuses frees its place for the retry.
+    let refused = call(&ctx, "auto_church.mint_deed", mint_params("not-the-tip".into(), "sensor:eco-3", 0.8, Some("epoch-4")));
+    assert_eq!(refused["error"]["code"], 1005);
+    let retried = call(&ctx, "auto_church.mint_deed", mint_params(tip(&ledger), "sensor:eco-3", 0.8, Some("epoch-4")));
+    assert!(retried["error"].is_null(), "{}", retried);
+    assert_eq!(guard.lock().unwrap().len(), 5);
+
+    // The correlation id stamped into the context is not content.
+    let reading = deed(tip(&ledger), "sensor:eco-5", 1.0);
+    let mut traced = reading.clone();
+    traced.context_json[CORRELATION_KEY] = json!("corr-1");
+    assert_eq!(content_hash(&reading), content_hash(&traced));
+    let mut guard = SubmissionGuard::new(SubmissionPolicy::default());
+    let _claim = guard.admit(&reading, None, Transport::JsonRpc, T0).unwrap();
+    assert!(guard.admit(&traced, None, Transport::JsonRpc, T0 + 1).is_err());
+    // Past the window the same reading is new again.
+    let _claim = guard.admit(&traced, None, Transport::JsonRpc, T0 + 600).unwrap();
+}
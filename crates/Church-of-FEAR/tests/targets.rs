@@ -0,0 +1,172 @@
+#![cfg(feature = "core")]
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::{EcologicalSustainabilityDeed, HomelessnessReliefDeed};
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::targets::{by_region, targets_csv, TargetFilter, TargetInfo, TargetRegistry, NO_REGION, TARGETS_CSV_HEADER};
+
+const T0: i64 = 1_700_000_000;
+const SHELTER: &str = "homeless-shelter-az";
+const RIVER: &str = "local-watershed";
+
+fn at(mut deed: DeedEvent, timestamp: i64) -> DeedEvent {
+    deed.timestamp = timestamp;
+    deed.self_hash = String::new();
+    deed.self_hash = hash_deed(&deed);
+    deed
+}
+
+fn meals(ledger: &mut TokenLedger, actor: &str, hours: f64, meals: u64, timestamp: i64) -> DeedEvent {
+    let deed = HomelessnessReliefDeed::builder()
+        .actor_id(actor)
+        .location("Phoenix")
+        .hours(hours)
+        .meals_served(meals)
+        .target(SHELTER)
+        .build(ledger.last_hash())
+        .unwrap();
+    ledger.append(at(deed, timestamp)).unwrap().clone()
+}
+
+fn planting(ledger: &mut TokenLedger, actor: &str, co2_kg: f64, timestamp: i64) -> DeedEvent {
+    let deed = EcologicalSustainabilityDeed::builder()
+        .actor_id(actor)
+        .location("Salt River")
+        .co2_kg(co2_kg)
+        .evidence_uri("ipfs://planting")
+        .target(RIVER)
+        .target(SHELTER)
+        .build(ledger.last_hash())
+        .unwrap();
+    ledger.append(at(deed, timestamp)).unwrap().clone()
+}
+
+fn registry() -> TargetRegistry {
+    let shelter = TargetInfo {
+        display_name: "AZ Shelter".to_string(),
+        region: Some("phoenix".to_string()),
+        category: Some("shelter".to_string()),
+    };
+    TargetRegistry { targets: [(SHELTER.to_string(), shelter)].into() }
+}
+
+/// alice and bob serve meals at the shelter; carol plants along the river
+/// with the shelter as co-beneficiary.
+fn fixture() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig { targets: registry(), ..Default::default() });
+    meals(&mut ledger, "alice", 2.0, 30, T0);
+    meals(&mut ledger, "bob", 3.5, 45, T0 + 3_600);
+    planting(&mut ledger, "carol", 120.0, T0 + 7_200);
+    meals(&mut ledger, "alice", 1.5, 10, T0 + 86_400);
+    ledger
+}
+
+#[test]
+fn summary_sums_quantities_and_counts_actors() {
+    let ledger = fixture();
+    let shelter = ledger.target_summary(SHELTER);
+    assert_eq!(shelter.total_deeds, 4);
+    assert_eq!(shelter.deeds_by_category["homelessness_relief"], 3);
+    assert_eq!(shelter.deeds_by_category["ecological_sustainability"], 1);
+    assert_eq!(shelter.distinct_actors, 3);
+    assert_eq!(shelter.quantities["hours"], 7.0);
+    assert_eq!(shelter.quantities["meals_served"], 85.0);
+    assert_eq!(shelter.quantities["co2_kg"], 120.0);
+    assert_eq!((shelter.first_activity, shelter.last_activity), (Some(T0), Some(T0 + 86_400)));
+
+    let alice = TargetFilter { actor_id: Some("alice".into()), ..Default::default() };
+    assert_eq!(ledger.deeds_for_target(SHELTER, &alice).len(), 2);
+    let first_day = TargetFilter { since: Some(T0), until: Some(T0 + 86_400), ..Default::default() };
+    assert_eq!(ledger.deeds_for_target(SHELTER, &first_day).len(), 3);
+}
+
+#[test]
+fn unregistered_targets_report_but_are_flagged() {
+    let ledger = fixture();
+    let shelter = ledger.target_summary(SHELTER);
+    assert!(shelter.registered);
+    assert_eq!(shelter.info.as_ref().unwrap().display_name, "AZ Shelter");
+
+    let river = ledger.target_summary(RIVER);
+    assert!(!river.registered);
+    assert_eq!(river.info, None);
+    assert_eq!(river.total_deeds, 1);
+
+    let summaries = ledger.target_summaries();
+    let regions = by_region(&summaries);
+    assert_eq!(regions["phoenix"][0].target_id, SHELTER);
+    assert_eq!(regions[NO_REGION][0].target_id, RIVER);
+}
+
+#[test]
+fn tombstoned_deeds_leave_the_sums() {
+    let mut ledger = fixture();
+    let bob = ledger.deeds_for_target(SHELTER, &TargetFilter::default())[1].event_id.clone();
+    ledger.tombstone(&bob, "double-counted shift", "Host").unwrap();
+
+    let shelter = ledger.target_summary(SHELTER);
+    assert_eq!(shelter.total_deeds, 3);
+    assert_eq!(shelter.distinct_actors, 2);
+    assert_eq!(shelter.quantities["meals_served"], 40.0);
+    assert!(ledger.deeds_for_target(SHELTER, &TargetFilter::default()).iter().all(|d| d.event_id != bob));
+    assert!(ledger.target_ids().all(|t| t == SHELTER || t == RIVER), "tombstones are not indexed as targets");
+}
+
+#[test]
+fn replayed_ledger_rebuilds_the_index() {
+    let mut ledger = fixture();
+    let first = ledger.deeds()[0].event_id.clone();
+    ledger.tombstone(&first, "wrong shelter", "Regulator").unwrap();
+    let replayed = TokenLedger::replay(ledger.config().clone(), ledger.deeds().to_vec()).unwrap();
+
+    assert_eq!(replayed.target_ids().collect::<Vec<_>>(), ledger.target_ids().collect::<Vec<_>>());
+    assert_eq!(replayed.target_summaries(), ledger.target_summaries());
+    let all = TargetFilter::default();
+    let ids = |l: &TokenLedger| l.deeds_for_target(SHELTER, &all).iter().map(|d| d.event_id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&replayed), ids(&ledger));
+}
+
+#[test]
+fn csv_export_has_one_row_per_target() {
+    let ledger = fixture();
+    let csv = targets_csv(&ledger.target_summaries());
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], TARGETS_CSV_HEADER);
+    assert_eq!(lines.len(), 3);
+    let columns = TARGETS_CSV_HEADER.split(',').count();
+    assert!(lines.iter().all(|l| l.split(',').count() == columns), "{csv}");
+    assert_eq!(
+        lines[1],
+        format!(
+            "{SHELTER},AZ Shelter,phoenix,shelter,true,4,ecological_sustainability=1;homelessness_relief=3,3,\
+             co2_kg=120;hours=7;meals_served=85,{T0},{}",
+            T0 + 86_400
+        )
+    );
+    assert_eq!(lines[2], format!("{RIVER},,,,false,1,ecological_sustainability=1,1,co2_kg=120,{},{}", T0 + 7_200, T0 + 7_200));
+}
+
+#[cfg(feature = "rpc")]
+#[test]
+fn rpc_serves_summaries_and_deed_lookups() {
+    use church_of_fear::rpc::server::{dispatch_request_with, RpcContext};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    let ctx = RpcContext::with_ledger(Arc::new(Mutex::new(fixture())));
+    let call = |method: &str, params: Value| {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str::<Value>(&dispatch_request_with(&request.to_string(), &ctx)).unwrap()
+    };
+
+    let summary = call("auto_church.target_summary", json!({ "target_id": SHELTER }));
+    assert_eq!(summary["result"]["total_deeds"], 4);
+    assert_eq!(summary["result"]["registered"], true);
+    let regions = call("auto_church.target_summary", json!({}));
+    assert_eq!(regions["result"]["regions"][NO_REGION][0]["target_id"], RIVER);
+
+    let deeds = call("auto_church.deeds_for_target", json!({ "target_id": SHELTER, "filter": { "actor_id": "bob" } }));
+    assert_eq!(deeds["result"]["deeds"].as_array().unwrap().len(), 1);
+    assert_eq!(deeds["result"]["deeds"][0]["actor_id"], "bob");
+}
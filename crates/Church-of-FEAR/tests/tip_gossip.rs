@@ -0,0 +1,153 @@
+#![cfg(feature = "tip-gossip")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::tip_gossip::{
+    AlertNotifier, DivergenceAlert, GossipConfig, GossipError, PeerConfig, SignedTipAnnouncement, TipGossip,
+    DIVERGENCE_ALERT,
+};
+use church_of_fear::utils::correlation::CORRELATION_KEY;
+use keyring::{Keyring, VerifyingBundle};
+
+const NOW: i64 = 1_000;
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<DivergenceAlert>>>);
+
+impl AlertNotifier for Recorder {
+    fn notify(&self, alert: &DivergenceAlert) -> Result<(), String> {
+        self.0.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+struct Node {
+    gossip: TipGossip,
+    ledger: TokenLedger,
+}
+
+fn keyring_for(purpose: &str) -> (Keyring, String) {
+    let mut keyring = Keyring::new().with_clock(|| NOW as u64);
+    let name = keyring.generate(purpose).unwrap();
+    (keyring, name)
+}
+
+/// Two nodes that know each other's keys; `b` records its alerts.
+fn pair() -> (Node, Node, Recorder) {
+    let (ka, name_a) = keyring_for("node-a");
+    let (kb, name_b) = keyring_for("node-b");
+    let bundle = VerifyingBundle { keys: ka.keys().chain(kb.keys()).cloned().collect() };
+    let peer = |id: &str, key: &str| PeerConfig {
+        node_id: id.to_string(),
+        url: format!("http://{}.invalid/tips", id),
+        key_names: vec![key.to_string()],
+    };
+    let cfg = |id: &str, peers| GossipConfig { node_id: id.to_string(), namespace: "phoenix".to_string(), peers, ..Default::default() };
+
+    let a = TipGossip::new(cfg("a", vec![peer("b", &name_b)]), ka, &name_a, bundle.clone());
+    let recorder = Recorder::default();
+    let b = TipGossip::new(cfg("b", vec![peer("a", &name_a)]), kb, &name_b, bundle).with_notifier(recorder.clone());
+    let ledger = || TokenLedger::new(LedgerConfig::default());
+    (Node { gossip: a, ledger: ledger() }, Node { gossip: b, ledger: ledger() }, recorder)
+}
+
+fn relief(prev_hash: String, actor: &str) -> DeedEvent {
+    HomelessnessReliefDeed::builder().actor_id(actor).location("Phoenix").hours(2.0).meals_served(10).build(prev_hash).unwrap()
+}
+
+#[test]
+fn matching_tips_raise_no_alert() {
+    let (mut a, mut b, recorder) = pair();
+    let shared = relief(a.ledger.last_hash(), "alice");
+    a.ledger.append(shared.clone()).unwrap();
+    b.ledger.append(shared).unwrap();
+
+    let signed = a.gossip.announce(&a.ledger, NOW).unwrap();
+    assert_eq!(b.gossip.receive(&mut b.ledger, signed, NOW).unwrap(), None);
+    assert!(b.gossip.received().contains_key("a"));
+    assert!(recorder.0.lock().unwrap().is_empty());
+}
+
+#[test]
+fn forked_peer_alerts_exactly_once() {
+    let (mut a, mut b, recorder) = pair();
+    let shared = relief(a.ledger.last_hash(), "alice");
+    a.ledger.append(shared.clone()).unwrap();
+    b.ledger.append(shared).unwrap();
+    a.ledger.append(relief(a.ledger.last_hash(), "alice")).unwrap();
+    b.ledger.append(relief(b.ledger.last_hash(), "bob")).unwrap();
+
+    let first = a.gossip.announce(&a.ledger, NOW).unwrap();
+    let alert = b.gossip.receive(&mut b.ledger, first, NOW).unwrap().expect("fork detected");
+    assert_eq!(alert.peer, "a");
+    assert_eq!(alert.peer_height, 2);
+    assert_eq!(alert.peer_tip, a.ledger.last_hash());
+
+    let again = a.gossip.announce(&a.ledger, NOW + 60).unwrap();
+    assert_eq!(b.gossip.receive(&mut b.ledger, again, NOW + 60).unwrap(), None);
+
+    assert_eq!(recorder.0.lock().unwrap().len(), 1);
+    let logged: Vec<&DeedEvent> = b.ledger.deeds().iter().filter(|d| d.deed_type == DIVERGENCE_ALERT).collect();
+    assert_eq!(logged.len(), 1);
+    assert_eq!(logged[0].context_json["peer"], "a");
+}
+
+#[test]
+fn forged_and_replayed_announcements_are_rejected() {
+    let (a, mut b, _) = pair();
+
+    let mut forged = a.gossip.announce(&a.ledger, NOW).unwrap();
+    forged.announcement.tip_hash = "f".repeat(64);
+    assert!(matches!(b.gossip.receive(&mut b.ledger, forged, NOW), Err(GossipError::Signature(_))));
+
+    let (impostor, _) = keyring_for("node-a");
+    let signed = a.gossip.announce(&a.ledger, NOW).unwrap();
+    let sig = impostor.sign("node-a-1", &serde_json::to_vec(&signed.announcement).unwrap()).unwrap();
+    let impersonated = SignedTipAnnouncement { signature: sig, ..signed.clone() };
+    assert!(matches!(b.gossip.receive(&mut b.ledger, impersonated, NOW), Err(GossipError::Signature(_))));
+
+    b.gossip.receive(&mut b.ledger, signed.clone(), NOW).unwrap();
+    assert!(matches!(b.gossip.receive(&mut b.ledger, signed, NOW), Err(GossipError::Stale { .. })));
+
+    let old = a.gossip.announce(&a.ledger, NOW - 3_600).unwrap();
+    assert!(matches!(b.gossip.receive(&mut b.ledger, old, NOW), Err(GossipError::Stale { .. })));
+    assert!(b.ledger.deeds().is_empty());
+}
+
+#[test]
+fn http_announcements_run_under_the_callers_correlation_id() {
+    let (mut a, mut b, _) = pair();
+    a.ledger.append(relief(a.ledger.last_hash(), "alice")).unwrap();
+    b.ledger.append(relief(b.ledger.last_hash(), "bob")).unwrap();
+    let body = serde_json::to_string(&a.gossip.announce(&a.ledger, NOW).unwrap()).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST /tips HTTP/1.1\r\nX-Correlation-Id: fork-9\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    });
+    let (stream, _) = listener.accept().unwrap();
+    b.gossip.handle_http(&mut b.ledger, stream, NOW).unwrap();
+
+    let response = client.join().unwrap();
+    assert!(response.starts_with("HTTP/1.1 204"), "{response}");
+    assert!(response.contains("X-Correlation-Id: fork-9\r\n"), "{response}");
+    let alert = b.ledger.deeds().iter().find(|d| d.deed_type == DIVERGENCE_ALERT).unwrap();
+    assert_eq!(alert.context_json[CORRELATION_KEY], "fork-9");
+}
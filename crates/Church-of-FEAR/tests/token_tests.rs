@@ -1,21 +1,55 @@
-use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::{DeedError, DeedEvent};
 use church_of_fear::ledger::metrics::BioloadMetrics;
 use church_of_fear::token::mint::mint_church;
 
-#[test]
-fn mint_for_ecological_negative_bioload() {
+fn deed(deed_type: &str) -> DeedEvent {
     let genesis = DeedEvent::genesis();
-    let event = DeedEvent::new(
+    DeedEvent::new(
         genesis.self_hash,
         "actor".into(),
         vec![],
-        "ecological_sustainability".into(),
+        deed_type.into(),
         vec![],
         serde_json::json!({}),
         vec![],
         false,
-    );
+    )
+}
+
+#[test]
+fn mint_for_ecological_negative_bioload() {
+    let event = deed("ecological_sustainability");
     let metrics = BioloadMetrics::new(-0.5, 0.1, 0.2);
-    let amount = mint_church(&event, &metrics);
+    let amount = mint_church(&event, &metrics, &LedgerConfig::default());
     assert!(amount > 0);
 }
+
+#[test]
+fn reward_is_capped_per_deed() {
+    let config = LedgerConfig { max_church_per_deed: 250, ..LedgerConfig::default() };
+    let event = deed("watershed_cleanup");
+    assert_eq!(event.compute_church_reward(-2.0, &config), Ok(200));
+    assert_eq!(event.compute_church_reward(-10.0, &config), Ok(250));
+    assert_eq!(mint_church(&event, &BioloadMetrics::new(-10.0, 0.1, 0.2), &config), 250);
+}
+
+#[test]
+fn bioload_delta_out_of_range_is_rejected() {
+    let config = LedgerConfig::default();
+    let event = deed("ecological_sustainability");
+    for delta in [-1000.0, -10.5, 10.5, f64::NAN] {
+        assert!(matches!(event.compute_church_reward(delta, &config), Err(DeedError::InvariantViolation(_))), "{}", delta);
+    }
+    assert_eq!(mint_church(&event, &BioloadMetrics::new(-1000.0, 0.1, 0.2), &config), 0);
+}
+
+#[test]
+fn deed_types_outside_the_whitelist_mint_nothing() {
+    let config = LedgerConfig::default();
+    assert_eq!(deed("tree_planting").compute_church_reward(-2.0, &config), Ok(0));
+
+    let config = LedgerConfig { church_deed_types: vec!["tree_planting".to_string()], ..config };
+    assert_eq!(deed("tree_planting").compute_church_reward(-2.0, &config), Ok(200));
+    assert_eq!(deed("ecological_sustainability").compute_church_reward(-2.0, &config), Ok(0));
+}
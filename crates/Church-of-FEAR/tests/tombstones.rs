@@ -0,0 +1,142 @@
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::builders::{HomelessnessReliefDeed, MathScienceEducationDeed};
+use church_of_fear::ledger::deed_event::DeedEvent;
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::{CorrectionWindow, TokenLedger, TokenLedgerError};
+
+fn ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "Alice");
+    ledger.open_account("bob", "Bob");
+    ledger
+}
+
+fn relief(ledger: &TokenLedger, actor: &str) -> DeedEvent {
+    HomelessnessReliefDeed::builder()
+        .actor_id(actor)
+        .location("Phoenix")
+        .hours(4.0)
+        .meals_served(30)
+        .build(ledger.last_hash())
+        .unwrap()
+}
+
+const ALL_TIME: CorrectionWindow = CorrectionWindow { start: 0, end: i64::MAX };
+
+#[test]
+fn aggregates_skip_tombstoned_deeds() {
+    let mut ledger = ledger();
+    let good = relief(&ledger, "alice");
+    ledger.append(good.clone()).unwrap();
+    let misfiled = relief(&ledger, "alice");
+    ledger.append(misfiled.clone()).unwrap();
+    assert_eq!(ledger.deeds_for_actor("alice").count(), 2);
+
+    ledger.tombstone(&misfiled.event_id, "logged to the wrong actor", "Host").unwrap();
+
+    assert!(ledger.is_tombstoned(&misfiled.event_id));
+    let live: Vec<&str> = ledger.deeds_for_actor("alice").map(|d| d.event_id.as_str()).collect();
+    assert_eq!(live, [good.event_id.as_str()]);
+    assert_eq!(ledger.deed_type_counts()["homelessness_relief"], 1);
+    assert!(!ledger.deed_type_counts().contains_key("tombstone"));
+    // Original bytes stay in the chain.
+    assert!(ledger.deeds().iter().any(|d| d.event_id == misfiled.event_id && d.self_hash == misfiled.self_hash));
+
+    let report = ledger.corrections_report(ALL_TIME);
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].target_event_id, misfiled.event_id);
+    assert_eq!(report[0].operator_role, "Host");
+    assert!(report[0].compensated.is_empty());
+    assert!(ledger.corrections_report(CorrectionWindow { start: 0, end: 1 }).is_empty());
+
+    assert_eq!(
+        ledger.tombstone(&misfiled.event_id, "again", "Host").unwrap_err(),
+        TokenLedgerError::AlreadyTombstoned(misfiled.event_id.clone())
+    );
+    assert_eq!(
+        ledger.tombstone(&good.event_id, "x", "Visitor").unwrap_err(),
+        TokenLedgerError::RoleNotAllowed("Visitor".into())
+    );
+}
+
+#[test]
+fn sealed_segments_refuse_tombstones() {
+    let mut ledger = ledger();
+    let sealed = relief(&ledger, "alice");
+    ledger.append(sealed.clone()).unwrap();
+    let segment = ledger.seal_segment().unwrap().unwrap().clone();
+    assert_eq!((segment.first, segment.last), (0, 0));
+    assert!(ledger.seal_segment().unwrap().is_none());
+
+    let open = relief(&ledger, "bob");
+    ledger.append(open.clone()).unwrap();
+
+    assert_eq!(
+        ledger.tombstone(&sealed.event_id, "typo", "Regulator").unwrap_err(),
+        TokenLedgerError::Sealed(sealed.event_id.clone())
+    );
+    ledger.tombstone(&open.event_id, "typo", "Regulator").unwrap();
+}
+
+#[test]
+fn reward_reversal_conserves_supply() {
+    let mut ledger = ledger();
+    let deed = relief(&ledger, "alice");
+    ledger.append(deed.clone()).unwrap();
+    ledger.reward_for("alice", Token::Church, 100, Some(&deed.event_id)).unwrap();
+    ledger.mint_reward("bob", Token::Church, 40).unwrap();
+    let fear = ledger.accrue_fear("bob", 20, "warn").unwrap().event_id.clone();
+    // Alice spends part of the reward before the correction.
+    ledger.burn("alice", Token::Church, 30).unwrap();
+
+    ledger.tombstone(&deed.event_id, "deed never happened", "Host").unwrap();
+    ledger.tombstone(&fear, "wrong account", "Regulator").unwrap();
+
+    assert_eq!(ledger.account("alice").unwrap().balance_church, 0);
+    assert_eq!(ledger.account("bob").unwrap().balance_church, 40);
+    assert_eq!(ledger.account("bob").unwrap().balance_fear, 0);
+    let report = ledger.supply_report();
+    assert!(report.reconciles(), "{report:?}");
+    assert_eq!(report.tokens[&Token::Church].issued, 140);
+    assert_eq!(report.tokens[&Token::Church].retired, 100);
+
+    let corrections = ledger.corrections_report(ALL_TIME);
+    assert_eq!(corrections[0].compensated.len(), 1);
+    assert_eq!(corrections[0].compensated[0].delta, -70);
+    assert_eq!(corrections[1].compensated[0].token, Token::Fear);
+}
+
+#[test]
+fn replay_with_tombstones_matches_live_ledger() {
+    let mut ledger = ledger();
+    let metrics = BioloadMetrics::new(-0.1, 0.1, 0.2);
+    let lesson = MathScienceEducationDeed::builder()
+        .actor_id("bob")
+        .subject("chemistry")
+        .learners(8)
+        .hours(1.5)
+        .build(ledger.last_hash())
+        .unwrap();
+    ledger.append(lesson.clone()).unwrap();
+    ledger.mint_tech("bob", &lesson, &metrics).unwrap();
+    ledger.seal_segment().unwrap();
+    let misfiled = relief(&ledger, "alice");
+    ledger.append(misfiled.clone()).unwrap();
+    ledger.reward_for("alice", Token::Pwr, 15, Some(&misfiled.event_id)).unwrap();
+    ledger.accrue_fear("alice", 50, "force repair").unwrap();
+    ledger.decay_fear(0.2).unwrap();
+    ledger.tombstone(&misfiled.event_id, "typo'd deed_type", "Host").unwrap();
+
+    let replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().to_vec()).unwrap();
+    assert_eq!(replayed.supply_report(), ledger.supply_report());
+    for account in ledger.accounts() {
+        let r = replayed.account(&account.id).unwrap();
+        for token in Token::ALL {
+            assert_eq!(r.balance(token), account.balance(token), "{} {:?}", account.id, token);
+        }
+    }
+    let ids = |l: &TokenLedger| l.live_deeds().map(|d| d.event_id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&replayed), ids(&ledger));
+    assert_eq!(replayed.corrections_report(ALL_TIME), ledger.corrections_report(ALL_TIME));
+}
@@ -0,0 +1,13 @@
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::history::HistoricalPoint;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+
+fn main() {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    ledger.reward_for("alice", church_of_fear::ledger::account::Token::Church, 10, None).unwrap();
+    let tip = ledger.last_hash();
+    let state = ledger.state_at(&HistoricalPoint::Tip(tip)).unwrap();
+    // A historical view is read-only: it never becomes a live ledger.
+    let _live: TokenLedger = (*state.view).clone().into();
+}
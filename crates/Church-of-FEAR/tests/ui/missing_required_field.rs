@@ -0,0 +1,10 @@
+use church_of_fear::ledger::builders::EcologicalSustainabilityDeed;
+
+fn main() {
+    // co2_kg is required for ecological_sustainability deeds.
+    let _ = EcologicalSustainabilityDeed::builder()
+        .actor_id("a1")
+        .location("San Tan Valley")
+        .evidence_uri("https://example.org/photo.jpg")
+        .build("");
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "core")]
+
+use std::collections::BTreeSet;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::account::Token;
+use church_of_fear::ledger::token_ledger::{TokenLedger, SEGMENT_SEALED};
+use church_of_fear::vrf::{self, RandomStream, VrfError, RANDOMNESS_DRAWN, RANDOMNESS_REVEALED};
+
+/// A ledger with one sealed segment of reward deeds.
+fn sealed_ledger() -> TokenLedger {
+    let mut ledger = TokenLedger::new(LedgerConfig::default());
+    ledger.open_account("alice", "alice");
+    for _ in 0..4 {
+        ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    }
+    ledger.seal_segment().unwrap().unwrap();
+    ledger
+}
+
+fn position_of(ledger: &TokenLedger, deed_type: &str) -> Vec<usize> {
+    ledger.deeds().iter().enumerate().filter(|(_, d)| d.deed_type == deed_type).map(|(i, _)| i).collect()
+}
+
+#[test]
+fn draws_are_logged_and_re_derive_from_the_deeds_alone() {
+    let mut ledger = sealed_ledger();
+    assert!(matches!(vrf::sample_indices(&mut ledger, "panel", 4, 3), Err(VrfError::Range { n: 4, out_of: 3 })));
+    let first = vrf::sample_indices(&mut ledger, "panel", 3, 10).unwrap();
+    let second = vrf::sample_indices(&mut ledger, "panel", 3, 10).unwrap();
+    let order = vrf::shuffle(&mut ledger, "rota", &["a", "b", "c", "d", "e"]).unwrap();
+    assert_eq!(order.iter().collect::<BTreeSet<_>>().len(), 5);
+
+    let draws = position_of(&ledger, RANDOMNESS_DRAWN);
+    assert_eq!(draws.len(), 3);
+    let derivations: Vec<_> = draws.iter().map(|&p| vrf::verify_draw(&ledger, p).unwrap()).collect();
+    assert_eq!(derivations.iter().map(|d| d.counter).collect::<Vec<_>>(), vec![0, 1, 0]);
+    assert_ne!(derivations[0].seed, derivations[1].seed);
+    assert_eq!(derivations[0].stream().sample_indices(3, 10), first);
+    assert_eq!(derivations[1].stream().sample_indices(3, 10), second);
+
+    // A replayed copy recomputes every draw without the original node.
+    let replayed = TokenLedger::replay(LedgerConfig::default(), ledger.deeds().iter().cloned()).unwrap();
+    assert_eq!(replayed.sealed_segments(), ledger.sealed_segments());
+    let audited = vrf::audit_draws(replayed.deeds());
+    assert_eq!(audited.len(), 3);
+    for ((position, derivation), expected) in audited.into_iter().zip(&derivations) {
+        assert_eq!(&derivation.unwrap(), expected, "draw at {}", position);
+    }
+}
+
+#[test]
+fn an_edited_draw_no_longer_re_derives() {
+    let mut ledger = sealed_ledger();
+    vrf::sample_indices(&mut ledger, "panel", 2, 10).unwrap();
+    let position = position_of(&ledger, RANDOMNESS_DRAWN)[0];
+
+    let mut deeds = ledger.deeds().to_vec();
+    let picked = deeds[position].context_json["use"]["result"][0].as_u64().unwrap();
+    deeds[position].context_json["use"]["result"][0] = ((picked + 1) % 10).into();
+    assert!(matches!(vrf::rederive(&deeds, position), Err(VrfError::Mismatch { .. })));
+
+    let mut deeds = ledger.deeds().to_vec();
+    deeds[position].context_json["derivation"]["counter"] = 5.into();
+    assert!(matches!(vrf::rederive(&deeds, position), Err(VrfError::Mismatch { .. })));
+
+    let mut deeds = ledger.deeds().to_vec();
+    deeds[1].self_hash = "0".repeat(64);
+    assert!(matches!(vrf::rederive(&deeds, position), Err(VrfError::Mismatch { .. })));
+}
+
+#[test]
+fn a_draw_must_use_the_last_seal_before_it() {
+    let mut ledger = sealed_ledger();
+    ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    ledger.seal_segment().unwrap().unwrap();
+    vrf::sample_indices(&mut ledger, "panel", 2, 10).unwrap();
+    let position = position_of(&ledger, RANDOMNESS_DRAWN)[0];
+    let seals = position_of(&ledger, SEGMENT_SEALED);
+    assert_eq!(seals.len(), 2);
+    assert_eq!(vrf::verify_draw(&ledger, position).unwrap().segment_index, 1);
+
+    // Re-derived in full from the older segment, the draw still recomputes
+    // but no longer uses the latest seal.
+    let older = &ledger.deeds()[seals[0]].context_json;
+    let mut forged = vrf::verify_draw(&ledger, position).unwrap();
+    forged.segment_index = 0;
+    forged.segment_first = older["first"].as_u64().unwrap() as usize;
+    forged.segment_last = older["last"].as_u64().unwrap() as usize;
+    forged.segment_root = older["root"].as_str().unwrap().to_string();
+    forged.seed =
+        church_of_fear::utils::crypto::sha256(&format!("{}:{}:{}", forged.segment_root, forged.purpose, forged.counter));
+    let mut deeds = ledger.deeds().to_vec();
+    deeds[position].context_json["derivation"] = serde_json::to_value(&forged).unwrap();
+    deeds[position].context_json["use"]["result"] = serde_json::to_value(forged.stream().sample_indices(2, 10)).unwrap();
+    match vrf::rederive(&deeds, position) {
+        Err(VrfError::Mismatch { reason, .. }) => assert!(reason.contains("last one sealed"), "{}", reason),
+        other => panic!("expected a seal mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn sampling_is_uniform_and_distinct() {
+    let mut counts = [0usize; 10];
+    for seed in 0..2000 {
+        let picked = RandomStream::new(&format!("seed-{}", seed)).sample_indices(3, 10);
+        assert_eq!(picked.iter().collect::<BTreeSet<_>>().len(), 3);
+        for i in picked {
+            counts[i] += 1;
+        }
+    }
+    // 600 expected per index; the standard deviation is about 22.
+    assert!(counts.iter().all(|&c| (500..=700).contains(&c)), "{:?}", counts);
+
+    let mut stream = RandomStream::new("whole");
+    let mut all = stream.sample_indices(50, 50);
+    all.sort_unstable();
+    assert_eq!(all, (0..50).collect::<Vec<_>>());
+    assert!(RandomStream::new("empty").sample_indices(0, 0).is_empty());
+}
+
+#[test]
+fn a_revealed_salt_must_match_its_sealed_commitment() {
+    let mut ledger = sealed_ledger();
+    assert!(matches!(vrf::reveal(&mut ledger, "nope", "salt"), Err(VrfError::UnknownCommitment(_))));
+    let id = vrf::commit(&mut ledger, "audit", &vrf::commitment_of("salt")).unwrap();
+    assert!(matches!(vrf::reveal(&mut ledger, &id, "salt"), Err(VrfError::CommitmentUnsealed(_))));
+
+    ledger.mint_reward("alice", Token::Church, 10).unwrap();
+    ledger.seal_segment().unwrap().unwrap();
+    assert!(matches!(vrf::reveal(&mut ledger, &id, "pepper"), Err(VrfError::SaltMismatch(_))));
+    let derivation = vrf::reveal(&mut ledger, &id, "salt").unwrap();
+    assert_eq!(derivation.segment_index, 1);
+    assert_eq!(derivation.commitment_id.as_deref(), Some(id.as_str()));
+    assert!(matches!(vrf::reveal(&mut ledger, &id, "salt"), Err(VrfError::AlreadyRevealed(_))));
+
+    let position = position_of(&ledger, RANDOMNESS_REVEALED)[0];
+    assert_eq!(vrf::verify_draw(&ledger, position).unwrap(), derivation);
+
+    // Swapping the salt in the deed, even with a matching seed, is caught.
+    let mut deeds = ledger.deeds().to_vec();
+    let mut forged = derivation.clone();
+    forged.salt = Some("pepper".to_string());
+    forged.seed = church_of_fear::utils::crypto::sha256(&format!(
+        "{}:{}:{}:pepper",
+        forged.segment_root, forged.purpose, forged.counter
+    ));
+    deeds[position].context_json["derivation"] = serde_json::to_value(&forged).unwrap();
+    assert!(matches!(vrf::rederive(&deeds, position), Err(VrfError::Mismatch { .. })));
+}
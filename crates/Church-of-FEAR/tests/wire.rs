@@ -0,0 +1,260 @@
+#![cfg(feature = "binary-wire")]
+
+use std::io::{Cursor, Read};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use church_of_fear::config::LedgerConfig;
+use church_of_fear::ledger::deed_event::{hash_deed, DeedEvent};
+use church_of_fear::ledger::metrics::BioloadMetrics;
+use church_of_fear::ledger::token_ledger::TokenLedger;
+use church_of_fear::rpc::server::RpcContext;
+use church_of_fear::rpc::wire::{
+    read_frame, serve_wire, submit_frame, write_frame, CompactBatch, CompactDeed, DeedOutcome, EncodeError, Frame, WireErrorKind,
+    WireReply, MAX_BATCH_DEEDS, MAX_FRAME_BYTES,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+
+fn vectors() -> Value {
+    serde_json::from_str(include_str!("vectors/wire_v1.json")).unwrap()
+}
+
+fn unhex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+fn metrics() -> BioloadMetrics {
+    BioloadMetrics::new(-0.12, 0.2, 0.7)
+}
+
+/// A compliant deed by `actor` linking to `prev_hash`, hashed the JSON way.
+fn deed(prev_hash: String, actor: &str, co2_kg: f64) -> DeedEvent {
+    DeedEvent::new(
+        prev_hash,
+        actor.to_string(),
+        vec!["target:local-watershed".to_string()],
+        "ecological_sustainability".to_string(),
+        vec!["eco".to_string()],
+        json!({ "location": "Phoenix, AZ", "co2_kg": co2_kg, "evidence_uri": "ipfs://riverbank-planting" }),
+        vec![],
+        false,
+    )
+}
+
+fn chain(prev_hash: String, n: usize) -> Vec<(DeedEvent, BioloadMetrics)> {
+    let mut out: Vec<(DeedEvent, BioloadMetrics)> = Vec::new();
+    for i in 0..n {
+        let prev = out.last().map_or(prev_hash.clone(), |(d, _)| d.self_hash.clone());
+        out.push((deed(prev, "sensor:eco-3", 0.5 + i as f64), metrics()));
+    }
+    out
+}
+
+fn ctx(ledger: TokenLedger) -> RpcContext {
+    RpcContext { ledger: Some(Arc::new(Mutex::new(ledger))), ..RpcContext::default() }
+}
+
+fn outcomes(reply: WireReply) -> Vec<DeedOutcome> {
+    match reply {
+        WireReply::Outcomes(outcomes) => outcomes,
+        other => panic!("expected outcomes, got {:?}", other),
+    }
+}
+
+#[test]
+fn conformance_vectors_round_trip() {
+    let vectors = vectors();
+    for v in vectors["frames"].as_array().unwrap() {
+        let bytes = unhex(v["hex"].as_str().unwrap());
+        let frame = Frame::decode(&bytes).unwrap_or_else(|e| panic!("{}: {}", v["name"], e));
+        assert_eq!(frame.encode(), bytes, "{}", v["name"]);
+
+        let expected: Vec<(DeedEvent, BioloadMetrics)> = v["deeds"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| (serde_json::from_value(d["deed"].clone()).unwrap(), serde_json::from_value(d["metrics"].clone()).unwrap()))
+            .collect();
+        let decoded = frame.into_deeds();
+        assert_eq!(decoded.len(), expected.len());
+        for ((got, got_metrics), (want, want_metrics)) in decoded.iter().zip(&expected) {
+            assert_eq!(serde_json::to_value(got).unwrap(), serde_json::to_value(want).unwrap(), "{}", v["name"]);
+            assert_eq!(got_metrics, want_metrics);
+        }
+
+        // Encoding the expected deeds reproduces the vector byte for byte.
+        let encoded = match expected.as_slice() {
+            [(deed, metrics)] => Frame::Deed(CompactDeed::from_deed(deed, metrics).unwrap()),
+            deeds => Frame::Batch(CompactBatch::from_deeds(deeds).unwrap()),
+        };
+        assert_eq!(encoded.encode(), bytes, "{}", v["name"]);
+    }
+    for v in vectors["malformed"].as_array().unwrap() {
+        let err = Frame::decode(&unhex(v["hex"].as_str().unwrap())).unwrap_err();
+        assert_eq!(
+            (err.offset as u64, err.kind.to_string()),
+            (v["offset"].as_u64().unwrap(), v["error"].as_str().unwrap().into())
+        );
+    }
+}
+
+#[test]
+fn wire_deeds_hash_like_json_deeds() {
+    let mut json_ledger = TokenLedger::new(LedgerConfig::default());
+    let wire_ledger = ctx(TokenLedger::new(LedgerConfig::default()));
+    let (original, _) = chain(json_ledger.last_hash(), 1).remove(0);
+
+    // The JSON path: the deed as a client would post it, parsed and stored.
+    let posted: DeedEvent = serde_json::from_str(&serde_json::to_string(&original).unwrap()).unwrap();
+    let stored = json_ledger.append(posted).unwrap().self_hash.clone();
+
+    let frame = Frame::Deed(CompactDeed::from_deed(&original, &metrics()).unwrap()).encode();
+    assert!(frame.len() < serde_json::to_vec(&original).unwrap().len());
+    let (decoded, _) = Frame::decode(&frame).unwrap().into_deeds().remove(0);
+    assert_eq!(
+        (decoded.self_hash.as_str(), hash_deed(&DeedEvent { self_hash: String::new(), ..decoded.clone() })),
+        (stored.as_str(), stored.clone())
+    );
+
+    let got = outcomes(submit_frame(&frame, &wire_ledger));
+    assert!(matches!(&got[..], [DeedOutcome::Accepted { self_hash, church_minted: 12 }] if *self_hash == stored));
+    let wire_ledger = wire_ledger.ledger.unwrap();
+    let wire_ledger = wire_ledger.lock().unwrap();
+    assert_eq!(serde_json::to_value(wire_ledger.deeds()).unwrap(), serde_json::to_value(json_ledger.deeds()).unwrap());
+
+    // Deeds the format cannot carry are refused at encode time.
+    let mut odd = original.clone();
+    odd.event_id = "not-a-uuid".into();
+    assert_eq!(CompactDeed::from_deed(&odd, &metrics()).unwrap_err(), EncodeError::EventId("not-a-uuid".into()));
+    odd = original;
+    odd.prev_hash = "ABC".into();
+    assert!(matches!(CompactDeed::from_deed(&odd, &metrics()), Err(EncodeError::Hash(_))));
+}
+
+#[test]
+fn batches_chain_and_stop_at_the_first_rejection() {
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let tip = ledger.last_hash();
+    let ctx = ctx(ledger);
+    let deeds = chain(tip, 3);
+    let frame = Frame::Batch(CompactBatch::from_deeds(&deeds).unwrap()).encode();
+    let single: usize = deeds.iter().map(|(d, m)| Frame::Deed(CompactDeed::from_deed(d, m).unwrap()).encode().len()).sum();
+    assert!(frame.len() < single, "the shared header saves bytes");
+
+    let got = outcomes(submit_frame(&frame, &ctx));
+    let hashes: Vec<&str> = got
+        .iter()
+        .map(|o| match o {
+            DeedOutcome::Accepted { self_hash, .. } => self_hash.as_str(),
+            other => panic!("{:?}", other),
+        })
+        .collect();
+    assert_eq!(hashes, deeds.iter().map(|(d, _)| d.self_hash.as_str()).collect::<Vec<_>>());
+    let tip = ctx.ledger.as_ref().unwrap().lock().unwrap().last_hash();
+    assert_eq!(tip, deeds[2].0.self_hash);
+
+    // A non-compliant deed is rejected and the rest no longer link.
+    let mut next = chain(tip, 3);
+    next[1].1.roh = 0.9;
+    let got = outcomes(submit_frame(&Frame::Batch(CompactBatch::from_deeds(&next).unwrap()).encode(), &ctx));
+    assert!(matches!(got[0], DeedOutcome::Accepted { .. }));
+    assert!(matches!(got[1], DeedOutcome::Rejected { code: 1001, .. }));
+    assert!(matches!(got[2], DeedOutcome::Rejected { code: 1005, .. }));
+    assert_eq!(ctx.ledger.as_ref().unwrap().lock().unwrap().deeds().len(), 4);
+
+    // Batches are bounded on both sides of the wire.
+    let many = chain("0".repeat(64), MAX_BATCH_DEEDS + 1);
+    assert_eq!(CompactBatch::from_deeds(&many).unwrap_err(), EncodeError::BatchSize(MAX_BATCH_DEEDS + 1));
+    assert_eq!(CompactBatch::from_deeds(&[]).unwrap_err(), EncodeError::BatchSize(0));
+    let mut batch = CompactBatch::from_deeds(&many[..MAX_BATCH_DEEDS]).unwrap();
+    batch.deeds.push(batch.deeds[0].clone());
+    let err = Frame::decode(&Frame::Batch(batch).encode()).unwrap_err();
+    assert_eq!(
+        (err.offset, err.kind),
+        (36 + "sensor:eco-3".len() + "ecological_sustainability".len(), WireErrorKind::BatchSize(257))
+    );
+}
+
+#[test]
+fn oversized_frames_are_refused() {
+    let err = Frame::decode(&vec![1u8; MAX_FRAME_BYTES + 1]).unwrap_err();
+    assert_eq!((err.offset, err.kind), (0, WireErrorKind::FrameTooLarge(MAX_FRAME_BYTES as u64 + 1)));
+    // The length prefix alone is enough to refuse; no body is read.
+    let mut stream = Cursor::new(((MAX_FRAME_BYTES + 1) as u32).to_be_bytes().to_vec());
+    assert!(
+        matches!(read_frame(&mut stream), Ok(Some(Err(e))) if e.kind == WireErrorKind::FrameTooLarge(MAX_FRAME_BYTES as u64 + 1))
+    );
+    assert!(read_frame(&mut Cursor::new(Vec::new())).unwrap().is_none());
+
+    // Over TCP: a good frame is answered, an oversized one closes the connection.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let ledger = TokenLedger::new(LedgerConfig::default());
+    let (d, m) = chain(ledger.last_hash(), 1).remove(0);
+    let ctx = ctx(ledger);
+    thread::spawn(move || serve_wire(listener, ctx));
+    let mut client = TcpStream::connect(addr).unwrap();
+    write_frame(&mut client, &Frame::Deed(CompactDeed::from_deed(&d, &m).unwrap()).encode()).unwrap();
+    let reply = WireReply::decode(&read_frame(&mut client).unwrap().unwrap().unwrap()).unwrap();
+    assert!(matches!(&outcomes(reply)[..], [DeedOutcome::Accepted { self_hash, .. }] if *self_hash == d.self_hash));
+
+    std::io::Write::write_all(&mut client, &u32::MAX.to_be_bytes()).unwrap();
+    let reply = WireReply::decode(&read_frame(&mut client).unwrap().unwrap().unwrap()).unwrap();
+    assert!(matches!(reply, WireReply::Malformed { offset: 0, ref message } if message.contains("exceeds the limit")));
+    assert_eq!(client.read(&mut [0u8; 1]).unwrap(), 0, "connection closed");
+}
+
+#[test]
+fn corrupted_frames_fail_with_offsets_not_panics() {
+    let mut rng = StdRng::seed_from_u64(445);
+    let deeds = chain("0".repeat(64), 3);
+    let frames = [
+        Frame::Deed(CompactDeed::from_deed(&deeds[0].0, &deeds[0].1).unwrap()).encode(),
+        Frame::Batch(CompactBatch::from_deeds(&deeds).unwrap()).encode(),
+    ];
+    let ctx = ctx(TokenLedger::new(LedgerConfig::default()));
+    for good in &frames {
+        // Every truncation fails, at or before the cut.
+        for cut in 0..good.len() {
+            let err = Frame::decode(&good[..cut]).unwrap_err();
+            assert!(err.offset <= cut, "{} past cut {}", err, cut);
+        }
+        for _ in 0..2_000 {
+            let mut bad = good.clone();
+            match rng.gen_range(0..3) {
+                0 => {
+                    let flips = rng.gen_range(1..4);
+                    for _ in 0..flips {
+                        let i = rng.gen_range(0..bad.len());
+                        bad[i] ^= 1 << rng.gen_range(0..8);
+                    }
+                }
+                1 => {
+                    let i = rng.gen_range(0..bad.len());
+                    bad.insert(i, rng.gen());
+                }
+                _ => {
+                    let i = rng.gen_range(2..bad.len());
+                    bad[i..].iter_mut().for_each(|b| *b = rng.gen());
+                }
+            }
+            if let Err(e) = Frame::decode(&bad) {
+                assert!(e.offset <= bad.len());
+            }
+            // Whatever decodes is submitted without panicking.
+            submit_frame(&bad, &ctx);
+        }
+    }
+    let garbage: Vec<u8> = (0..4_096).map(|_| rng.gen()).collect();
+    assert!(matches!(outcomes_or_offset(submit_frame(&garbage, &ctx)), Some(offset) if offset <= garbage.len()));
+}
+
+fn outcomes_or_offset(reply: WireReply) -> Option<usize> {
+    match reply {
+        WireReply::Malformed { offset, .. } => Some(offset),
+        WireReply::Outcomes(_) => None,
+    }
+}
@@ -0,0 +1,24 @@
+use crate::job::{Job, JobId};
+
+/// Passed to a handler for each execution.
+#[derive(Debug, Clone)]
+pub struct ExecutionContext {
+    pub job_id: JobId,
+    /// Stable for the job across attempts and restarts. Handlers embed it
+    /// in their outputs (e.g. a deed's context) so a re-run can tell that
+    /// its side effect already happened.
+    pub execution_token: String,
+    pub attempt: u32,
+}
+
+pub trait JobHandler: Send + Sync {
+    /// Safe to re-run after a crash mid-execution. Non-idempotent jobs
+    /// found `Running` on startup go to `NeedsReview` instead.
+    fn idempotent(&self) -> bool {
+        false
+    }
+
+    /// Run the job. The returned marker, if any, is stored atomically
+    /// with the `Succeeded` transition.
+    fn run(&self, job: &Job, ctx: &ExecutionContext) -> Result<Option<serde_json::Value>, String>;
+}
@@ -1,14 +1,14 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum JobKind {
     GitMaintenance,
     EcoScan,
     AuditLineage,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct JobId(pub String);
 
 impl JobId {
@@ -17,7 +17,7 @@ impl JobId {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Job {
     pub id: JobId,
     pub kind: JobKind,
@@ -33,3 +33,27 @@ impl Job {
         }
     }
 }
+
+/// Persisted lifecycle of a job.
+///
+/// `Queued -> Running -> Succeeded | Failed | DeadLetter`. A job found
+/// `Running` after a restart whose handler is not idempotent goes to
+/// `NeedsReview` instead of being run twice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    /// Attempt failed; the job is retried.
+    Failed { error: String },
+    /// Out of attempts.
+    DeadLetter { error: String },
+    NeedsReview,
+}
+
+impl JobState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, JobState::Succeeded | JobState::DeadLetter { .. } | JobState::NeedsReview)
+    }
+}
@@ -2,3 +2,5 @@ pub mod job;
 pub mod queue;
 pub mod worker;
 pub mod scheduler;
+pub mod handler;
+pub mod store;
@@ -1,26 +1,151 @@
-use crate::{job::{Job, JobKind}, queue::JobQueue, worker::Worker};
+use std::collections::HashMap;
+
+use crate::{
+    handler::{ExecutionContext, JobHandler},
+    job::{Job, JobId, JobKind, JobState},
+    queue::JobQueue,
+    store::{JobRecord, JobStore, JobStoreError, MemoryJobStore},
+    worker::Worker,
+};
+
+/// Attempts before a failing job is dead-lettered.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// What `recover` did with jobs left over from the previous run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecoveryReport {
+    pub requeued: Vec<JobId>,
+    pub needs_review: Vec<JobId>,
+}
 
 pub struct Scheduler {
     pub queue: JobQueue,
     pub worker: Worker,
+    pub max_attempts: u32,
+    store: Box<dyn JobStore>,
+    records: HashMap<JobId, JobRecord>,
+    seq: u64,
 }
 
 impl Scheduler {
+    /// Scheduler over an in-memory store.
     pub fn new(worker_name: &str) -> Self {
+        Self::with_store(worker_name, MemoryJobStore::default())
+    }
+
+    /// Scheduler over a durable store. Register handlers, then call
+    /// `recover` before running anything.
+    pub fn with_store(worker_name: &str, store: impl JobStore + 'static) -> Self {
         Self {
             queue: JobQueue::default(),
             worker: Worker::new(worker_name),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            store: Box::new(store),
+            records: HashMap::new(),
+            seq: 0,
+        }
+    }
+
+    pub fn register(&mut self, kind: JobKind, handler: impl JobHandler + 'static) {
+        self.worker.register(kind, handler);
+    }
+
+    /// Reload persisted jobs. Queued and Failed jobs are re-enqueued.
+    /// Running jobs were interrupted: idempotent ones are re-enqueued with
+    /// their execution token, the rest move to `NeedsReview`.
+    pub fn recover(&mut self) -> Result<RecoveryReport, JobStoreError> {
+        let mut report = RecoveryReport::default();
+        for mut record in self.store.load()? {
+            self.seq = self.seq.max(record.seq);
+            let id = record.job.id.clone();
+            match record.state {
+                JobState::Queued | JobState::Failed { .. } => {
+                    self.queue.push(record.job.clone());
+                    report.requeued.push(id.clone());
+                }
+                JobState::Running if self.worker.is_idempotent(record.job.kind) => {
+                    self.transition(&mut record, JobState::Queued)?;
+                    self.queue.push(record.job.clone());
+                    report.requeued.push(id.clone());
+                }
+                JobState::Running => {
+                    self.transition(&mut record, JobState::NeedsReview)?;
+                    report.needs_review.push(id.clone());
+                }
+                _ => {}
+            }
+            self.records.insert(id, record);
         }
+        Ok(report)
     }
 
-    pub fn enqueue_git_maintenance(&mut self, payload: serde_json::Value) {
-        let job = Job::new(JobKind::GitMaintenance, payload);
+    fn transition(&mut self, record: &mut JobRecord, state: JobState) -> Result<(), JobStoreError> {
+        self.seq += 1;
+        record.seq = self.seq;
+        record.state = state;
+        self.store.put(record)
+    }
+
+    pub fn enqueue(&mut self, kind: JobKind, payload: serde_json::Value) -> Result<JobId, JobStoreError> {
+        let job = Job::new(kind, payload);
+        let id = job.id.clone();
+        let mut record = JobRecord { job: job.clone(), state: JobState::Queued, seq: 0, attempts: 0, execution_token: None };
+        self.transition(&mut record, JobState::Queued)?;
+        self.records.insert(id.clone(), record);
         self.queue.push(job);
+        Ok(id)
+    }
+
+    pub fn enqueue_git_maintenance(&mut self, payload: serde_json::Value) -> Result<JobId, JobStoreError> {
+        self.enqueue(JobKind::GitMaintenance, payload)
+    }
+
+    pub fn state(&self, id: &JobId) -> Option<&JobState> {
+        self.records.get(id).map(|r| &r.state)
     }
 
-    pub async fn run_once(&mut self) {
-        if let Some(job) = self.queue.pop() {
-            self.worker.execute(job).await;
+    pub fn record(&self, id: &JobId) -> Option<&JobRecord> {
+        self.records.get(id)
+    }
+
+    /// Run the next queued job to a persisted outcome.
+    pub fn run_next(&mut self) -> Result<Option<(JobId, JobState)>, JobStoreError> {
+        let Some(job) = self.queue.pop() else {
+            return Ok(None);
+        };
+        let mut record = match self.records.remove(&job.id) {
+            Some(record) => record,
+            None => JobRecord { job: job.clone(), state: JobState::Queued, seq: 0, attempts: 0, execution_token: None },
+        };
+        record.attempts += 1;
+        let token = record
+            .execution_token
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+            .clone();
+        self.transition(&mut record, JobState::Running)?;
+
+        let ctx = ExecutionContext { job_id: job.id.clone(), execution_token: token, attempt: record.attempts };
+        match self.worker.run(&job, &ctx) {
+            Ok(marker) => {
+                self.seq += 1;
+                record.seq = self.seq;
+                record.state = JobState::Succeeded;
+                self.store.complete(&record, marker.as_ref())?;
+            }
+            Err(error) if record.attempts >= self.max_attempts => {
+                self.transition(&mut record, JobState::DeadLetter { error })?;
+            }
+            Err(error) => {
+                self.transition(&mut record, JobState::Failed { error })?;
+                self.queue.push(job.clone());
+            }
         }
+        let state = record.state.clone();
+        self.records.insert(job.id.clone(), record);
+        Ok(Some((job.id, state)))
+    }
+
+    pub async fn run_once(&mut self) -> Result<Option<(JobId, JobState)>, JobStoreError> {
+        self.run_next()
     }
 }
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::job::{Job, JobState};
+
+#[derive(Debug, Error)]
+pub enum JobStoreError {
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("Corrupt job record: {0}")]
+    Corrupt(String),
+}
+
+/// Latest persisted state of one job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub job: Job,
+    pub state: JobState,
+    /// Sequence number of the transition that produced `state`; strictly
+    /// increasing across all jobs in a store.
+    pub seq: u64,
+    pub attempts: u32,
+    /// Assigned on the first transition to `Running`.
+    pub execution_token: Option<String>,
+}
+
+/// Durable job state. `complete` must write the record and the handler's
+/// marker in one atomic step.
+pub trait JobStore: Send {
+    fn put(&mut self, record: &JobRecord) -> Result<(), JobStoreError>;
+
+    fn complete(&mut self, record: &JobRecord, marker: Option<&serde_json::Value>) -> Result<(), JobStoreError>;
+
+    /// All records, ordered by `seq`.
+    fn load(&self) -> Result<Vec<JobRecord>, JobStoreError>;
+
+    /// Marker stored by `complete` for an execution token.
+    fn marker(&self, execution_token: &str) -> Result<Option<serde_json::Value>, JobStoreError>;
+}
+
+#[derive(Default)]
+struct MemoryInner {
+    records: HashMap<String, JobRecord>,
+    markers: HashMap<String, serde_json::Value>,
+}
+
+/// In-process store. Clones share state, so dropping a `Scheduler` and
+/// building a new one over a clone behaves like a restart.
+#[derive(Clone, Default)]
+pub struct MemoryJobStore {
+    inner: Arc<Mutex<MemoryInner>>,
+}
+
+impl MemoryJobStore {
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, MemoryInner>, JobStoreError> {
+        self.inner.lock().map_err(|e| JobStoreError::Storage(e.to_string()))
+    }
+}
+
+impl JobStore for MemoryJobStore {
+    fn put(&mut self, record: &JobRecord) -> Result<(), JobStoreError> {
+        self.lock()?.records.insert(record.job.id.0.clone(), record.clone());
+        Ok(())
+    }
+
+    fn complete(&mut self, record: &JobRecord, marker: Option<&serde_json::Value>) -> Result<(), JobStoreError> {
+        let mut inner = self.lock()?;
+        if let (Some(token), Some(marker)) = (&record.execution_token, marker) {
+            inner.markers.insert(token.clone(), marker.clone());
+        }
+        inner.records.insert(record.job.id.0.clone(), record.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<JobRecord>, JobStoreError> {
+        let mut records: Vec<JobRecord> = self.lock()?.records.values().cloned().collect();
+        records.sort_by_key(|r| r.seq);
+        Ok(records)
+    }
+
+    fn marker(&self, execution_token: &str) -> Result<Option<serde_json::Value>, JobStoreError> {
+        Ok(self.lock()?.markers.get(execution_token).cloned())
+    }
+}
+
+#[cfg(feature = "sled")]
+pub use self::sled_store::SledJobStore;
+
+#[cfg(feature = "sled")]
+mod sled_store {
+    use sled::transaction::{ConflictableTransactionError, TransactionError};
+    use sled::Transactional;
+
+    use super::{JobRecord, JobStore, JobStoreError};
+
+    fn storage(e: impl std::fmt::Display) -> JobStoreError {
+        JobStoreError::Storage(e.to_string())
+    }
+
+    /// sled-backed store: a `jobs` tree keyed by job id and a `markers`
+    /// tree keyed by execution token.
+    pub struct SledJobStore {
+        jobs: sled::Tree,
+        markers: sled::Tree,
+    }
+
+    impl SledJobStore {
+        pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, JobStoreError> {
+            Self::from_db(&sled::open(path).map_err(storage)?)
+        }
+
+        pub fn from_db(db: &sled::Db) -> Result<Self, JobStoreError> {
+            Ok(Self { jobs: db.open_tree("jobs").map_err(storage)?, markers: db.open_tree("markers").map_err(storage)? })
+        }
+    }
+
+    impl JobStore for SledJobStore {
+        fn put(&mut self, record: &JobRecord) -> Result<(), JobStoreError> {
+            let bytes = serde_json::to_vec(record).map_err(storage)?;
+            self.jobs.insert(record.job.id.0.as_bytes(), bytes).map_err(storage)?;
+            self.jobs.flush().map_err(storage)?;
+            Ok(())
+        }
+
+        fn complete(&mut self, record: &JobRecord, marker: Option<&serde_json::Value>) -> Result<(), JobStoreError> {
+            let bytes = serde_json::to_vec(record).map_err(storage)?;
+            let marker = match (&record.execution_token, marker) {
+                (Some(token), Some(m)) => Some((token.clone(), serde_json::to_vec(m).map_err(storage)?)),
+                _ => None,
+            };
+            (&self.jobs, &self.markers)
+                .transaction(|(jobs, markers)| {
+                    jobs.insert(record.job.id.0.as_bytes(), bytes.as_slice())?;
+                    if let Some((token, m)) = &marker {
+                        markers.insert(token.as_bytes(), m.as_slice())?;
+                    }
+                    Ok::<_, ConflictableTransactionError<()>>(())
+                })
+                .map_err(|e: TransactionError<()>| storage(format!("{:?}", e)))?;
+            self.jobs.flush().map_err(storage)?;
+            Ok(())
+        }
+
+        fn load(&self) -> Result<Vec<JobRecord>, JobStoreError> {
+            let mut records = Vec::new();
+            for item in self.jobs.iter() {
+                let (key, value) = item.map_err(storage)?;
+                let record: JobRecord = serde_json::from_slice(&value)
+                    .map_err(|e| JobStoreError::Corrupt(format!("{}: {}", String::from_utf8_lossy(&key), e)))?;
+                records.push(record);
+            }
+            records.sort_by_key(|r| r.seq);
+            Ok(records)
+        }
+
+        fn marker(&self, execution_token: &str) -> Result<Option<serde_json::Value>, JobStoreError> {
+            match self.markers.get(execution_token.as_bytes()).map_err(storage)? {
+                Some(bytes) => serde_json::from_slice(&bytes)
+                    .map(Some)
+                    .map_err(|e| JobStoreError::Corrupt(format!("marker {}: {}", execution_token, e))),
+                None => Ok(None),
+            }
+        }
+    }
+}
@@ -1,17 +1,47 @@
+use std::collections::HashMap;
+
+use crate::handler::{ExecutionContext, JobHandler};
 use crate::job::{Job, JobKind};
 
 pub struct Worker {
     pub name: String,
+    handlers: HashMap<JobKind, Box<dyn JobHandler>>,
 }
 
 impl Worker {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
+            handlers: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, kind: JobKind, handler: impl JobHandler + 'static) {
+        self.handlers.insert(kind, Box::new(handler));
+    }
+
+    /// Whether a crashed `Running` job of this kind may be re-run.
+    pub fn is_idempotent(&self, kind: JobKind) -> bool {
+        self.handlers.get(&kind).map(|h| h.idempotent()).unwrap_or(false)
+    }
+
+    /// Run `job` through its registered handler; kinds without one are
+    /// only logged.
+    pub fn run(&self, job: &Job, ctx: &ExecutionContext) -> Result<Option<serde_json::Value>, String> {
+        match self.handlers.get(&job.kind) {
+            Some(handler) => handler.run(job, ctx),
+            None => {
+                self.log(job);
+                Ok(None)
+            }
         }
     }
 
     pub async fn execute(&self, job: Job) {
+        self.log(&job);
+    }
+
+    fn log(&self, job: &Job) {
         match job.kind {
             JobKind::GitMaintenance => {
                 // bridge to ac_git_orchestrator
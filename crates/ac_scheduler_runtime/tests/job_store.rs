@@ -0,0 +1,148 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ac_scheduler_runtime::handler::{ExecutionContext, JobHandler};
+use ac_scheduler_runtime::job::{Job, JobKind, JobState};
+use ac_scheduler_runtime::scheduler::Scheduler;
+use ac_scheduler_runtime::store::{JobRecord, JobStore, MemoryJobStore};
+use serde_json::json;
+
+/// Appends one "deed" per execution token, skipping tokens it has already
+/// written. Optionally crashes once after writing.
+struct DeedWriter {
+    deeds: Arc<Mutex<Vec<String>>>,
+    crash_after_write: Arc<AtomicBool>,
+    idempotent: bool,
+}
+
+impl JobHandler for DeedWriter {
+    fn idempotent(&self) -> bool {
+        self.idempotent
+    }
+
+    fn run(&self, _job: &Job, ctx: &ExecutionContext) -> Result<Option<serde_json::Value>, String> {
+        {
+            let mut deeds = self.deeds.lock().unwrap();
+            if !deeds.contains(&ctx.execution_token) {
+                deeds.push(ctx.execution_token.clone());
+            }
+        }
+        if self.crash_after_write.swap(false, Ordering::SeqCst) {
+            panic!("simulated crash");
+        }
+        Ok(Some(json!({ "deed_token": ctx.execution_token })))
+    }
+}
+
+fn writer(deeds: &Arc<Mutex<Vec<String>>>, crash: bool, idempotent: bool) -> DeedWriter {
+    DeedWriter { deeds: deeds.clone(), crash_after_write: Arc::new(AtomicBool::new(crash)), idempotent }
+}
+
+#[test]
+fn restart_recovers_queued_jobs() {
+    let store = MemoryJobStore::default();
+    let mut before = Scheduler::with_store("w1", store.clone());
+    let a = before.enqueue_git_maintenance(json!({ "repo": "a" })).unwrap();
+    let b = before.enqueue(JobKind::AuditLineage, json!({})).unwrap();
+    drop(before);
+
+    let deeds = Arc::new(Mutex::new(Vec::new()));
+    let mut after = Scheduler::with_store("w2", store);
+    after.register(JobKind::GitMaintenance, writer(&deeds, false, false));
+    let report = after.recover().unwrap();
+    assert_eq!(report.requeued, [a.clone(), b.clone()]);
+    assert!(report.needs_review.is_empty());
+
+    assert_eq!(after.run_next().unwrap(), Some((a.clone(), JobState::Succeeded)));
+    assert_eq!(after.run_next().unwrap(), Some((b.clone(), JobState::Succeeded)));
+    assert_eq!(after.run_next().unwrap(), None);
+    assert_eq!(deeds.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn interrupted_non_idempotent_job_needs_review() {
+    let store = MemoryJobStore::default();
+    let deeds = Arc::new(Mutex::new(Vec::new()));
+    let mut before = Scheduler::with_store("w1", store.clone());
+    before.register(JobKind::GitMaintenance, writer(&deeds, true, false));
+    let id = before.enqueue_git_maintenance(json!({})).unwrap();
+    assert!(catch_unwind(AssertUnwindSafe(|| before.run_next())).is_err());
+    drop(before);
+
+    let mut after = Scheduler::with_store("w2", store);
+    after.register(JobKind::GitMaintenance, writer(&deeds, false, false));
+    let report = after.recover().unwrap();
+    assert_eq!(report.needs_review, vec![id.clone()]);
+    assert_eq!(after.state(&id), Some(&JobState::NeedsReview));
+    assert_eq!(after.run_next().unwrap(), None);
+    assert_eq!(deeds.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn idempotent_rerun_does_not_duplicate_side_effects() {
+    let store = MemoryJobStore::default();
+    let deeds = Arc::new(Mutex::new(Vec::new()));
+    let mut before = Scheduler::with_store("w1", store.clone());
+    before.register(JobKind::AuditLineage, writer(&deeds, true, true));
+    let id = before.enqueue(JobKind::AuditLineage, json!({})).unwrap();
+    assert!(catch_unwind(AssertUnwindSafe(|| before.run_next())).is_err());
+    drop(before);
+
+    let mut after = Scheduler::with_store("w2", store.clone());
+    after.register(JobKind::AuditLineage, writer(&deeds, false, true));
+    assert_eq!(after.recover().unwrap().requeued, vec![id.clone()]);
+    assert_eq!(after.run_next().unwrap(), Some((id.clone(), JobState::Succeeded)));
+
+    let record = after.record(&id).unwrap();
+    let token = record.execution_token.clone().unwrap();
+    assert_eq!(record.attempts, 2);
+    assert_eq!(*deeds.lock().unwrap(), vec![token.clone()]);
+    assert_eq!(store.marker(&token).unwrap(), Some(json!({ "deed_token": token })));
+}
+
+fn all_states() -> Vec<JobState> {
+    vec![
+        JobState::Queued,
+        JobState::Running,
+        JobState::Succeeded,
+        JobState::Failed { error: "timeout".into() },
+        JobState::DeadLetter { error: "gave up".into() },
+        JobState::NeedsReview,
+    ]
+}
+
+fn assert_round_trip(mut store: impl JobStore) {
+    let written: Vec<JobRecord> = all_states()
+        .into_iter()
+        .enumerate()
+        .map(|(i, state)| JobRecord {
+            job: Job::new(JobKind::EcoScan, json!({ "i": i })),
+            state,
+            seq: i as u64 + 1,
+            attempts: i as u32,
+            execution_token: Some(format!("tok-{}", i)),
+        })
+        .collect();
+    for record in &written {
+        store.put(record).unwrap();
+    }
+    store.complete(&written[2], Some(&json!({ "done": true }))).unwrap();
+
+    assert_eq!(store.load().unwrap(), written);
+    assert_eq!(store.marker("tok-2").unwrap(), Some(json!({ "done": true })));
+    assert_eq!(store.marker("tok-1").unwrap(), None);
+}
+
+#[test]
+fn memory_store_round_trips_every_state() {
+    assert_round_trip(MemoryJobStore::default());
+}
+
+#[cfg(feature = "sled")]
+#[test]
+fn sled_store_round_trips_every_state() {
+    use ac_scheduler_runtime::store::SledJobStore;
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    assert_round_trip(SledJobStore::from_db(&db).unwrap());
+}
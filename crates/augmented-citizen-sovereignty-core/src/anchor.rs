@@ -0,0 +1,135 @@
+//! What the Anchors nodes anchor: a SHA-256 Merkle root over the deed log.
+//! The leaves are the events' `self_hash`es in log order; each parent is
+//! the SHA-256 of its children's hex hashes concatenated, and a level with
+//! an odd count pairs its last hash with itself. An `AnchorManifest` carries
+//! the root to BOSTROM_ANCHOR, GOOGOLSWARM or GHOSTNET; a `merkle_proof`
+//! then shows any one event is under it without the rest of the log.
+
+use deed_core::{levels_root, merkle_levels, merkle_proof};
+use serde::{Deserialize, Serialize};
+
+pub use deed_core::{verify_merkle_proof, MerkleStep, EMPTY_MERKLE_ROOT};
+
+use crate::{Node, SovereigntyCore};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnchorManifest {
+    pub root: String,
+    /// Levels above the leaves; 0 for a log of one event.
+    pub height: usize,
+    pub event_count: usize,
+    /// Unix milliseconds.
+    pub created_at: i64,
+    pub anchor_target: Node,
+}
+
+/// The Anchors children a manifest can be posted to.
+pub const ANCHOR_TARGETS: [Node; 3] = [Node::BostromAnchor, Node::Googolswarm, Node::Ghostnet];
+
+impl SovereigntyCore {
+    fn merkle_levels(&self) -> Vec<Vec<String>> {
+        merkle_levels(self.deed_log.iter().map(|d| d.self_hash.clone()).collect())
+    }
+
+    /// The Merkle root of `deed_log`, or `EMPTY_MERKLE_ROOT` while it is
+    /// empty.
+    pub fn merkle_root(&self) -> String {
+        levels_root(&self.merkle_levels())
+    }
+
+    /// The proof that the event `event_id` is under `merkle_root`, if it is
+    /// in the log.
+    pub fn merkle_proof(&self, event_id: &str) -> Option<Vec<MerkleStep>> {
+        let index = self.deed_log.iter().position(|d| d.event_id == event_id)?;
+        Some(merkle_proof(&self.merkle_levels(), index))
+    }
+
+    /// A manifest of the current root for `anchor_target`, which must be
+    /// one of `ANCHOR_TARGETS`.
+    pub fn anchor_manifest(&self, anchor_target: Node) -> Option<AnchorManifest> {
+        if !ANCHOR_TARGETS.contains(&anchor_target) {
+            return None;
+        }
+        let levels = self.merkle_levels();
+        Some(AnchorManifest {
+            root: levels_root(&levels),
+            height: levels.len().saturating_sub(1),
+            event_count: self.deed_log.len(),
+            created_at: self.now_millis(),
+            anchor_target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedClock;
+    use serde_json::json;
+
+    const T0: i64 = 1_700_000_000_000;
+
+    fn logged(events: usize) -> SovereigntyCore {
+        let mut core = SovereigntyCore::new().with_clock(Box::new(FixedClock::new(T0)));
+        for i in 0..events {
+            core.log_event_at(Node::Did, "binding".to_string(), json!({ "seq": i }), T0 + i as i64).unwrap();
+        }
+        core
+    }
+
+    #[test]
+    fn proofs_verify_for_the_first_middle_and_last_events() {
+        // Odd counts at every level: 7 → 4 → 2 → 1.
+        let core = logged(7);
+        let root = core.merkle_root();
+        for i in [0, 3, 6] {
+            let deed = &core.deed_log[i];
+            let proof = core.merkle_proof(&deed.event_id).unwrap();
+            assert_eq!(proof.len(), 3);
+            assert!(verify_merkle_proof(&root, &deed.self_hash, &proof), "event {}", i);
+        }
+        // The last leaf of an odd level is paired with itself.
+        let last = core.merkle_proof(&core.deed_log[6].event_id).unwrap();
+        assert_eq!(last[0].sibling, core.deed_log[6].self_hash);
+        assert!(core.merkle_proof("missing").is_none());
+    }
+
+    #[test]
+    fn tampering_breaks_the_proof() {
+        let mut core = logged(5);
+        let root = core.merkle_root();
+        let deed = core.deed_log[2].clone();
+        let proof = core.merkle_proof(&deed.event_id).unwrap();
+
+        let mut bent = proof.clone();
+        bent[1].sibling = deed_core::merkle_parent(&bent[1].sibling, "x");
+        assert!(!verify_merkle_proof(&root, &deed.self_hash, &bent));
+        let mut flipped = proof.clone();
+        flipped[0].sibling_on_left = !flipped[0].sibling_on_left;
+        assert!(!verify_merkle_proof(&root, &deed.self_hash, &flipped));
+
+        // Rewriting an event moves the root off the anchored one.
+        core.deed_log[2].context_json = json!({ "seq": 20 });
+        core.deed_log[2].self_hash = core.deed_log[2].compute_hash();
+        assert_ne!(core.merkle_root(), root);
+        assert!(!verify_merkle_proof(&root, &core.deed_log[2].self_hash, &proof));
+    }
+
+    #[test]
+    fn a_manifest_carries_the_root_to_an_anchor() {
+        assert_eq!(logged(0).merkle_root(), EMPTY_MERKLE_ROOT);
+        let single = logged(1);
+        assert_eq!(single.merkle_root(), single.deed_log[0].self_hash);
+        assert!(single.merkle_proof(&single.deed_log[0].event_id).unwrap().is_empty());
+
+        let core = logged(7);
+        let manifest = core.anchor_manifest(Node::BostromAnchor).unwrap();
+        assert_eq!((manifest.height, manifest.event_count, manifest.created_at), (3, 7, T0));
+        assert_eq!(manifest.root, core.merkle_root());
+        let posted = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(posted["anchor_target"], "BostromAnchor");
+        assert_eq!(serde_json::from_value::<AnchorManifest>(posted).unwrap(), manifest);
+
+        assert!(core.anchor_manifest(Node::Did).is_none());
+    }
+}
@@ -88,7 +88,7 @@ fn clamp01(x: f64) -> f64 {
     if x.is_nan() {
         0.0
     } else {
-        x.max(0.0).min(1.0)
+        x.clamp(0.0, 1.0)
     }
 }
 
@@ -0,0 +1,209 @@
+//! The CALM_STABLE predicate behind mp_score.
+//! A `CalmSource` reports the citizen's current calm band; `compute_reputation`
+//! scales mp_score by the band's factor, so only a calm-stable citizen keeps
+//! the full score CHURCH minting reads. `StaticCalmSource` reports a fixed
+//! band; `AutonomicCalmSource` reads it from HRV windows through the
+//! autonomic FEAR rail.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::autonomic_fear_rail::{hrv_to_autonomic_deltas, AutonomicDeltas, AutonomicFearConfig, HrvWindow};
+
+/// mp_score factor while calm-stable.
+pub const CALM_STABLE_FACTOR: f64 = 1.0;
+/// mp_score factor while elevated.
+pub const ELEVATED_FACTOR: f64 = 0.85;
+/// mp_score factor while overloaded.
+pub const OVERLOADED_FACTOR: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalmBand {
+    CalmStable,
+    Elevated,
+    Overloaded,
+}
+
+impl CalmBand {
+    pub fn mp_factor(self) -> f64 {
+        match self {
+            CalmBand::CalmStable => CALM_STABLE_FACTOR,
+            CalmBand::Elevated => ELEVATED_FACTOR,
+            CalmBand::Overloaded => OVERLOADED_FACTOR,
+        }
+    }
+}
+
+pub trait CalmSource {
+    fn calm_band(&self) -> CalmBand;
+}
+
+impl<T: CalmSource + ?Sized> CalmSource for std::sync::Arc<T> {
+    fn calm_band(&self) -> CalmBand {
+        (**self).calm_band()
+    }
+}
+
+/// Always reports the same band. The default reports `CalmStable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticCalmSource(pub CalmBand);
+
+impl Default for StaticCalmSource {
+    fn default() -> Self {
+        Self(CalmBand::CalmStable)
+    }
+}
+
+impl CalmSource for StaticCalmSource {
+    fn calm_band(&self) -> CalmBand {
+        self.0
+    }
+}
+
+/// FEAR intensity (`delta_fear` over `max_fear_delta`) at which each band
+/// starts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalmThresholds {
+    pub elevated_at: f64,
+    pub overloaded_at: f64,
+}
+
+impl Default for CalmThresholds {
+    fn default() -> Self {
+        Self { elevated_at: 0.4, overloaded_at: 0.7 }
+    }
+}
+
+/// Calm read from the latest HRV window. Share it through an `Arc` to keep
+/// feeding windows after handing it to the core. Until the first window
+/// arrives it reports `Elevated`: no evidence is not evidence of calm.
+#[derive(Debug)]
+pub struct AutonomicCalmSource {
+    config: AutonomicFearConfig,
+    thresholds: CalmThresholds,
+    latest: Mutex<Option<AutonomicDeltas>>,
+}
+
+impl AutonomicCalmSource {
+    pub fn new(config: AutonomicFearConfig, thresholds: CalmThresholds) -> Self {
+        Self { config, thresholds, latest: Mutex::new(None) }
+    }
+
+    /// Map `window` through the rail and keep its deltas as the latest.
+    pub fn observe(&self, window: HrvWindow) -> AutonomicDeltas {
+        let deltas = hrv_to_autonomic_deltas(self.config, window);
+        self.observe_deltas(deltas);
+        deltas
+    }
+
+    /// Keep deltas computed elsewhere as the latest.
+    pub fn observe_deltas(&self, deltas: AutonomicDeltas) {
+        *self.latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(deltas);
+    }
+
+    pub fn band_for(&self, deltas: &AutonomicDeltas) -> CalmBand {
+        let intensity = if self.config.max_fear_delta > 0.0 { deltas.delta_fear / self.config.max_fear_delta } else { 1.0 };
+        if intensity >= self.thresholds.overloaded_at {
+            CalmBand::Overloaded
+        } else if intensity >= self.thresholds.elevated_at {
+            CalmBand::Elevated
+        } else {
+            CalmBand::CalmStable
+        }
+    }
+}
+
+impl Default for AutonomicCalmSource {
+    fn default() -> Self {
+        Self::new(AutonomicFearConfig::default_bounded(), CalmThresholds::default())
+    }
+}
+
+impl CalmSource for AutonomicCalmSource {
+    fn calm_band(&self) -> CalmBand {
+        match *self.latest.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(deltas) => self.band_for(&deltas),
+            None => CalmBand::Elevated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autonomic_fear_rail::AutonomicProfile;
+    use crate::{EthicalFramework, Node, SovereigntyCore};
+    use std::sync::Arc;
+
+    const T0: i64 = 1_700_000_000_000;
+
+    fn window(lf_hf_norm: f64, entropy_norm: f64, hrv_power_norm: f64, profile_tag: AutonomicProfile) -> HrvWindow {
+        HrvWindow { lf_hf_norm, entropy_norm, hrv_power_norm, profile_tag }
+    }
+
+    fn rest() -> HrvWindow {
+        window(0.1, 0.9, 0.9, AutonomicProfile::Rest)
+    }
+
+    fn overload() -> HrvWindow {
+        window(0.95, 0.1, 0.1, AutonomicProfile::Overload)
+    }
+
+    fn logged(core: &mut SovereigntyCore) {
+        core.log_event_at(Node::Did, "did_binding".into(), serde_json::json!({}), T0).unwrap();
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), serde_json::json!({ "energy": "low" }), T0 + 1).unwrap();
+        core.log_event_at(Node::NClin, "clinical_session".into(), serde_json::json!({ "attested": true }), T0 + 2).unwrap();
+    }
+
+    #[test]
+    fn an_overloaded_source_drops_mp_score() {
+        let framework = EthicalFramework::default();
+        let mut core = SovereigntyCore::new();
+        logged(&mut core);
+        let calm = core.compute_reputation(&framework).mp_score;
+        assert_eq!(core.calm_band(), CalmBand::CalmStable);
+
+        core.set_calm_source(Box::new(StaticCalmSource(CalmBand::Overloaded)));
+        let overloaded = core.compute_reputation(&framework).mp_score;
+        assert!((overloaded - calm * OVERLOADED_FACTOR).abs() < 1e-12);
+
+        let core = SovereigntyCore::new().with_calm_source(Box::new(StaticCalmSource(CalmBand::Elevated)));
+        assert_eq!(core.calm_band(), CalmBand::Elevated);
+    }
+
+    #[test]
+    fn hrv_windows_drive_the_band() {
+        let source = Arc::new(AutonomicCalmSource::default());
+        let mut core = SovereigntyCore::new().with_calm_source(Box::new(Arc::clone(&source)));
+        logged(&mut core);
+        assert_eq!(core.calm_band(), CalmBand::Elevated);
+
+        source.observe(rest());
+        assert_eq!(core.calm_band(), CalmBand::CalmStable);
+        let calm = core.compute_reputation(&EthicalFramework::default()).mp_score;
+
+        let deltas = source.observe(overload());
+        assert!(deltas.delta_fear > 0.0);
+        assert_eq!(core.calm_band(), CalmBand::Overloaded);
+        let overloaded = core.compute_reputation(&EthicalFramework::default()).mp_score;
+        assert!(overloaded < calm);
+
+        source.observe(window(0.5, 0.5, 0.5, AutonomicProfile::LightTask));
+        assert_eq!(core.calm_band(), CalmBand::Elevated);
+    }
+
+    #[test]
+    fn deeds_can_carry_the_band_they_were_logged_in() {
+        let mut core = SovereigntyCore::new().with_calm_source(Box::new(StaticCalmSource(CalmBand::Overloaded)));
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), serde_json::json!({}), T0).unwrap();
+        assert!(core.deed_log[0].context_json.get("calm_band").is_none());
+
+        core.annotate_calm = true;
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), serde_json::json!({ "energy": "low" }), T0 + 1).unwrap();
+        let deed = &core.deed_log[1];
+        assert_eq!(deed.context_json["calm_band"], "overloaded");
+        assert_eq!(deed.context_json["energy"], "low");
+        assert!(deed.verify_hash());
+    }
+}
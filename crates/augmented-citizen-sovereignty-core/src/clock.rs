@@ -0,0 +1,107 @@
+//! Where the core reads the time. Every timestamp it stamps is Unix
+//! milliseconds, so events logged within the same second still order and
+//! hash apart; a `FixedClock` makes a run reproducible.
+
+use chrono::Utc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+pub trait Clock {
+    /// Unix time in milliseconds.
+    fn now_millis(&self) -> i64;
+}
+
+impl<T: Clock + ?Sized> Clock for Arc<T> {
+    fn now_millis(&self) -> i64 {
+        (**self).now_millis()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+}
+
+/// Reads the time it was set to until set again or advanced. Share it
+/// through an `Arc` to move it after handing it to the core.
+#[derive(Debug, Default)]
+pub struct FixedClock(AtomicI64);
+
+impl FixedClock {
+    pub fn new(millis: i64) -> Self {
+        Self(AtomicI64::new(millis))
+    }
+
+    pub fn set(&self, millis: i64) {
+        self.0.store(millis, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, millis: i64) {
+        self.0.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now_millis(&self) -> i64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeedEvent, Node, SovereigntyCore, TimeUnit};
+
+    const T0: i64 = 1_700_000_000_123;
+
+    #[test]
+    fn events_in_the_same_millisecond_keep_their_order() {
+        let clock = Arc::new(FixedClock::new(T0));
+        let mut core = SovereigntyCore::new().with_clock(Box::new(Arc::clone(&clock)));
+        core.log_event(Node::Did, "did_binding".into(), serde_json::json!({ "seq": 0 })).unwrap();
+        core.log_event(Node::Did, "did_binding".into(), serde_json::json!({ "seq": 1 })).unwrap();
+        clock.advance(1);
+        core.log_event(Node::Did, "did_binding".into(), serde_json::json!({ "seq": 2 })).unwrap();
+
+        let stamps: Vec<i64> = core.deed_log.iter().map(|d| d.timestamp).collect();
+        assert_eq!(stamps, [T0, T0, T0 + 1]);
+        assert_ne!(core.deed_log[0].self_hash, core.deed_log[1].self_hash);
+        assert_eq!(core.verify_chain(), Ok(()));
+
+        // Replaying the same deeds on the same clock reproduces every hash.
+        let mut replay = SovereigntyCore::new();
+        for deed in &core.deed_log {
+            replay.append(deed.clone(), deed.timestamp);
+        }
+        let hashes = |c: &SovereigntyCore| c.deed_log.iter().map(|d| d.self_hash.clone()).collect::<Vec<_>>();
+        assert_eq!(hashes(&replay), hashes(&core));
+        assert_eq!(replay.current_hash, core.current_hash);
+    }
+
+    #[test]
+    fn second_precision_deeds_still_read_and_verify() {
+        let mut legacy = DeedEvent::new("augmented_citizen".into(), Node::Did, "did_binding".into(), serde_json::json!({}));
+        legacy.timestamp = 1_700_000_000_000;
+        legacy.time_unit = TimeUnit::Seconds;
+        legacy.self_hash = legacy.compute_hash();
+
+        let stored = serde_json::to_value(&legacy).unwrap();
+        assert_eq!(stored["timestamp"], 1_700_000_000);
+        assert!(stored.get("time_unit").is_none());
+
+        let read: DeedEvent = serde_json::from_value(stored).unwrap();
+        assert_eq!((read.timestamp, read.time_unit), (1_700_000_000_000, TimeUnit::Seconds));
+        assert!(read.verify_hash());
+
+        let fresh = DeedEvent::new("augmented_citizen".into(), Node::Did, "did_binding".into(), serde_json::json!({}));
+        let stored = serde_json::to_value(&fresh).unwrap();
+        assert_eq!(stored["time_unit"], "millis");
+        let read: DeedEvent = serde_json::from_value(stored).unwrap();
+        assert_eq!(read.timestamp, fresh.timestamp);
+        assert!(read.verify_hash());
+    }
+}
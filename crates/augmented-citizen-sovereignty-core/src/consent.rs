@@ -0,0 +1,419 @@
+//! Duty-cycle envelopes for the neuro-consent scopes.
+//! Each ConsentScope caps how many events its node may log per rolling hour
+//! and how many session hours per rolling day. Limits can only be tightened
+//! at runtime. A rejected event reports how long until the next one would
+//! be admitted (`retry_after_secs`); repeated rejections raise a
+//! `duty_cycle_exceeded` ethics flag that dents the compliance score.
+//! A policy migration (`consent_migration`) can add scopes and move event
+//! nodes onto them.
+//!
+//! A scope also lists the purposes its data may be used for. A grant names
+//! the purposes the subject agreed to, and derived-data producers check
+//! them through `purpose::PurposeGate`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::Node;
+
+pub const DUTY_CYCLE_EXCEEDED: &str = "DUTY_CYCLE_EXCEEDED";
+pub const DUTY_CYCLE_FLAG: &str = "duty_cycle_exceeded";
+
+// Timestamps are Unix milliseconds.
+const HOUR: i64 = 3_600_000;
+const DAY: i64 = 86_400_000;
+
+/// Whole seconds to wait for `millis` to pass.
+fn retry_secs(millis: i64) -> i64 {
+    (millis + 999) / 1_000
+}
+
+/// What data logged under a scope may be used for beyond the log itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Purpose {
+    Clinical,
+    ResearchAggregate,
+    PublicReport,
+    GrantRouting,
+}
+
+impl Purpose {
+    pub const ALL: [Purpose; 4] = [Purpose::Clinical, Purpose::ResearchAggregate, Purpose::PublicReport, Purpose::GrantRouting];
+
+    pub fn all() -> BTreeSet<Purpose> {
+        Self::ALL.into_iter().collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsentScope {
+    pub scope: Node,
+    pub max_events_per_hour: u32,
+    pub max_session_hours_per_day: f64,
+    /// Purposes a grant for this scope may name.
+    #[serde(default = "Purpose::all")]
+    pub purposes: BTreeSet<Purpose>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub scope: Node,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+}
+
+/// When repeated duty-cycle rejections turn into an ethics flag.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DutyCyclePolicy {
+    /// Every this many rejections raise one flag.
+    pub violations_per_flag: u32,
+    /// Taken off the compliance score per flag.
+    pub compliance_dent: f64,
+}
+
+impl Default for DutyCyclePolicy {
+    fn default() -> Self {
+        Self { violations_per_flag: 3, compliance_dent: 0.05 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyLimit {
+    EventsPerHour,
+    SessionHoursPerDay,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsentError {
+    /// `retry_after_secs` is None when the limit is zero.
+    DutyCycleExceeded { scope: Node, limit: DutyLimit, retry_after_secs: Option<i64> },
+    Loosening { scope: Node, limit: DutyLimit },
+    UnknownScope(Node),
+    SessionOpen(Node),
+    NoOpenSession(Node),
+    /// The subject's grant for this scope awaits reconfirmation after a
+    /// policy migration; the scope is inactive until then.
+    PendingReconfirmation(Node),
+    /// A grant named a purpose the scope does not offer.
+    PurposeNotOffered { scope: Node, purpose: Purpose },
+    /// The subject holds no grant for this scope.
+    NotGranted(Node),
+}
+
+impl ConsentError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConsentError::DutyCycleExceeded { .. } => DUTY_CYCLE_EXCEEDED,
+            ConsentError::Loosening { .. } => "LIMIT_LOOSENING",
+            ConsentError::UnknownScope(_) => "UNKNOWN_SCOPE",
+            ConsentError::SessionOpen(_) => "SESSION_OPEN",
+            ConsentError::NoOpenSession(_) => "NO_OPEN_SESSION",
+            ConsentError::PendingReconfirmation(_) => "CONSENT_PENDING_RECONFIRMATION",
+            ConsentError::PurposeNotOffered { .. } => "PURPOSE_NOT_OFFERED",
+            ConsentError::NotGranted(_) => "CONSENT_NOT_GRANTED",
+        }
+    }
+
+    pub fn retry_after_secs(&self) -> Option<i64> {
+        match self {
+            ConsentError::DutyCycleExceeded { retry_after_secs, .. } => *retry_after_secs,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConsentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsentError::DutyCycleExceeded { scope, limit, retry_after_secs: Some(secs) } => {
+                write!(f, "{}: {:?} {:?} limit reached, retry after {}s", DUTY_CYCLE_EXCEEDED, scope, limit, secs)
+            }
+            ConsentError::DutyCycleExceeded { scope, limit, retry_after_secs: None } => {
+                write!(f, "{}: {:?} {:?} limit is zero", DUTY_CYCLE_EXCEEDED, scope, limit)
+            }
+            ConsentError::Loosening { scope, limit } => write!(f, "{:?} {:?} limit can only be tightened", scope, limit),
+            ConsentError::UnknownScope(scope) => write!(f, "{:?} is not a consent scope", scope),
+            ConsentError::SessionOpen(scope) => write!(f, "{:?} already has an open session", scope),
+            ConsentError::NoOpenSession(scope) => write!(f, "{:?} has no open session", scope),
+            ConsentError::PendingReconfirmation(scope) => write!(f, "{:?} consent awaits reconfirmation", scope),
+            ConsentError::PurposeNotOffered { scope, purpose } => write!(f, "{:?} does not offer {:?} use", scope, purpose),
+            ConsentError::NotGranted(scope) => write!(f, "no consent granted for {:?}", scope),
+        }
+    }
+}
+
+impl std::error::Error for ConsentError {}
+
+/// Scopes, their sessions and the duty-cycle violation count.
+#[derive(Debug, Clone)]
+pub struct ConsentLedger {
+    scopes: HashMap<Node, ConsentScope>,
+    /// Event nodes moved onto another scope by a migration.
+    node_scopes: HashMap<Node, Node>,
+    pub sessions: Vec<SessionRecord>,
+    pub policy: DutyCyclePolicy,
+    pub violations: u32,
+}
+
+impl Default for ConsentLedger {
+    fn default() -> Self {
+        let scopes = [
+            ConsentScope { scope: Node::ScopeEeg, max_events_per_hour: 120, max_session_hours_per_day: 10.0, purposes: Purpose::all() },
+            ConsentScope { scope: Node::ScopeBci, max_events_per_hour: 60, max_session_hours_per_day: 4.0, purposes: Purpose::all() },
+        ];
+        Self {
+            scopes: scopes.into_iter().map(|s| (s.scope.clone(), s)).collect(),
+            node_scopes: HashMap::new(),
+            sessions: Vec::new(),
+            policy: DutyCyclePolicy::default(),
+            violations: 0,
+        }
+    }
+}
+
+impl ConsentLedger {
+    /// The consent scope governing events logged on `node`, if any.
+    pub fn scope_for(node: &Node) -> Option<Node> {
+        match node {
+            Node::NSleep => Some(Node::ScopeEeg),
+            Node::NBci => Some(Node::ScopeBci),
+            _ => None,
+        }
+    }
+
+    /// The scope governing `node` here: its migrated scope if it has one,
+    /// else `scope_for`.
+    pub fn governing_scope(&self, node: &Node) -> Option<Node> {
+        self.node_scopes.get(node).cloned().or_else(|| Self::scope_for(node))
+    }
+
+    /// Add `scope` with the limits and purposes of `like`, or hold an
+    /// existing scope to the tighter of the two.
+    pub(crate) fn adopt_scope(&mut self, scope: &Node, like: &Node) -> Result<(), ConsentError> {
+        let limits = self.scopes.get(like).cloned().ok_or_else(|| ConsentError::UnknownScope(like.clone()))?;
+        match self.scopes.get_mut(scope) {
+            Some(current) => {
+                current.max_events_per_hour = current.max_events_per_hour.min(limits.max_events_per_hour);
+                current.max_session_hours_per_day = current.max_session_hours_per_day.min(limits.max_session_hours_per_day);
+                current.purposes.retain(|p| limits.purposes.contains(p));
+            }
+            None => {
+                self.scopes.insert(scope.clone(), ConsentScope { scope: scope.clone(), ..limits });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn reassign(&mut self, node: Node, scope: Node) {
+        self.node_scopes.insert(node, scope);
+    }
+
+    pub fn scope(&self, scope: &Node) -> Option<&ConsentScope> {
+        self.scopes.get(scope)
+    }
+
+    /// Lower a scope's limits; raising either is refused.
+    pub fn tighten(&mut self, scope: &Node, max_events_per_hour: u32, max_session_hours_per_day: f64) -> Result<(), ConsentError> {
+        let current = self.scopes.get_mut(scope).ok_or_else(|| ConsentError::UnknownScope(scope.clone()))?;
+        if max_events_per_hour > current.max_events_per_hour {
+            return Err(ConsentError::Loosening { scope: scope.clone(), limit: DutyLimit::EventsPerHour });
+        }
+        if max_session_hours_per_day.is_nan() || max_session_hours_per_day > current.max_session_hours_per_day {
+            return Err(ConsentError::Loosening { scope: scope.clone(), limit: DutyLimit::SessionHoursPerDay });
+        }
+        current.max_events_per_hour = max_events_per_hour;
+        current.max_session_hours_per_day = max_session_hours_per_day.max(0.0);
+        Ok(())
+    }
+
+    /// Stop offering every purpose of `scope` outside `keep`. Like the
+    /// limits, purposes can only be withdrawn; existing grants keep theirs.
+    pub fn restrict_purposes(&mut self, scope: &Node, keep: &[Purpose]) -> Result<(), ConsentError> {
+        let current = self.scopes.get_mut(scope).ok_or_else(|| ConsentError::UnknownScope(scope.clone()))?;
+        current.purposes.retain(|p| keep.contains(p));
+        Ok(())
+    }
+
+    pub fn open_session(&mut self, scope: &Node, now: i64) -> Result<(), ConsentError> {
+        let limits = self.scope(scope).ok_or_else(|| ConsentError::UnknownScope(scope.clone()))?;
+        if self.open(scope).is_some() {
+            return Err(ConsentError::SessionOpen(scope.clone()));
+        }
+        self.check_session_hours(limits, now)?;
+        self.sessions.push(SessionRecord { scope: scope.clone(), started_at: now, ended_at: None });
+        Ok(())
+    }
+
+    pub fn close_session(&mut self, scope: &Node, now: i64) -> Result<SessionRecord, ConsentError> {
+        let i = self
+            .sessions
+            .iter()
+            .rposition(|s| &s.scope == scope && s.ended_at.is_none())
+            .ok_or_else(|| ConsentError::NoOpenSession(scope.clone()))?;
+        self.sessions[i].ended_at = Some(now.max(self.sessions[i].started_at));
+        Ok(self.sessions[i].clone())
+    }
+
+    fn open(&self, scope: &Node) -> Option<&SessionRecord> {
+        self.sessions.iter().find(|s| &s.scope == scope && s.ended_at.is_none())
+    }
+
+    /// Session milliseconds for `scope` within `(at - 24h, at]`, counting
+    /// open sessions as running until `until`.
+    fn session_millis(&self, scope: &Node, at: i64, until: i64) -> i64 {
+        self.sessions
+            .iter()
+            .filter(|s| &s.scope == scope)
+            .map(|s| {
+                let end = s.ended_at.unwrap_or(until).min(at);
+                (end - s.started_at.max(at - DAY)).max(0)
+            })
+            .sum()
+    }
+
+    /// Rolling session hours used in the last day.
+    pub fn session_hours(&self, scope: &Node, now: i64) -> f64 {
+        self.session_millis(scope, now, now) as f64 / HOUR as f64
+    }
+
+    fn check_session_hours(&self, limits: &ConsentScope, now: i64) -> Result<(), ConsentError> {
+        let limit = (limits.max_session_hours_per_day * HOUR as f64) as i64;
+        if self.session_millis(&limits.scope, now, now) < limit {
+            return Ok(());
+        }
+        // Usage only falls from here on (no session runs past now), and
+        // is zero a day later; find the first millisecond it is under the limit.
+        let retry_after_secs = (limit > 0).then(|| {
+            let (mut lo, mut hi) = (now, now + DAY);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if self.session_millis(&limits.scope, mid, now) < limit {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            retry_secs(lo - now)
+        });
+        Err(ConsentError::DutyCycleExceeded { scope: limits.scope.clone(), limit: DutyLimit::SessionHoursPerDay, retry_after_secs })
+    }
+
+    /// Admit one more event on `node` at `now`, given the timestamps of the
+    /// events it already logged. Nodes without a scope always pass.
+    pub fn check_event(&self, node: &Node, logged: &[i64], now: i64) -> Result<(), ConsentError> {
+        let Some(scope) = self.governing_scope(node) else {
+            return Ok(());
+        };
+        let limits = self.scope(&scope).ok_or(ConsentError::UnknownScope(scope))?;
+        let mut recent: Vec<i64> = logged.iter().copied().filter(|&t| t > now - HOUR && t <= now).collect();
+        let max = limits.max_events_per_hour as usize;
+        if recent.len() >= max {
+            recent.sort_unstable();
+            // The event whose expiry brings the count under the limit.
+            let retry_after_secs = (max > 0).then(|| retry_secs(recent[recent.len() - max] + HOUR - now));
+            return Err(ConsentError::DutyCycleExceeded {
+                scope: limits.scope.clone(),
+                limit: DutyLimit::EventsPerHour,
+                retry_after_secs,
+            });
+        }
+        self.check_session_hours(limits, now)
+    }
+
+    /// Count a rejection; true when it should raise an ethics flag.
+    pub fn record_violation(&mut self) -> bool {
+        self.violations += 1;
+        self.policy.violations_per_flag > 0 && self.violations.is_multiple_of(self.policy.violations_per_flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthicalFramework, SovereigntyCore};
+
+    const T0: i64 = 1_700_000_000_000;
+    const HOUR_SECS: i64 = 3_600;
+
+    fn bci(core: &mut SovereigntyCore, at: i64) -> Result<(), ConsentError> {
+        core.log_event_at(Node::NBci, "cognitive_trial".to_string(), serde_json::json!({"attested": true}), at)
+    }
+
+    #[test]
+    fn events_per_hour_are_capped_on_a_rolling_window() {
+        let mut core = SovereigntyCore::new();
+        core.consent.tighten(&Node::ScopeBci, 3, 4.0).unwrap();
+        for (i, at) in [0, 600_000, 1_200_000].into_iter().enumerate() {
+            bci(&mut core, T0 + at).unwrap_or_else(|e| panic!("event {}: {}", i, e));
+        }
+        let err = bci(&mut core, T0 + 1_800_000).unwrap_err();
+        assert_eq!(err.code(), DUTY_CYCLE_EXCEEDED);
+        // The first event leaves the window at T0 + 1h.
+        assert_eq!(err.retry_after_secs(), Some(1_800));
+        let err = bci(&mut core, T0 + HOUR - 1).unwrap_err();
+        // A millisecond still to wait rounds up to a whole second.
+        assert_eq!(err.retry_after_secs(), Some(1));
+        bci(&mut core, T0 + HOUR).unwrap();
+        // Sleep events are governed by their own scope.
+        core.log_event_at(Node::NSleep, "eeg_epoch".to_string(), serde_json::json!({}), T0 + HOUR).unwrap();
+        assert_eq!(core.deed_log.len(), 5);
+    }
+
+    #[test]
+    fn session_hours_accumulate_across_sessions_in_a_day() {
+        let mut ledger = ConsentLedger::default();
+        let bci = Node::ScopeBci;
+        ledger.open_session(&bci, T0).unwrap();
+        ledger.close_session(&bci, T0 + 2 * HOUR).unwrap();
+        ledger.open_session(&bci, T0 + 5 * HOUR).unwrap();
+        assert_eq!(ledger.open_session(&bci, T0 + 5 * HOUR), Err(ConsentError::SessionOpen(bci.clone())));
+        assert_eq!(ledger.session_hours(&bci, T0 + 6 * HOUR), 3.0);
+        assert!(ledger.check_event(&Node::NBci, &[], T0 + 6 * HOUR).is_ok());
+
+        // Four hours used at T0 + 7h: the 2h session from T0 must start
+        // leaving the window, so nothing more until T0 + 24h + 1ms.
+        let err = ledger.check_event(&Node::NBci, &[], T0 + 7 * HOUR).unwrap_err();
+        assert_eq!(err.retry_after_secs(), Some(17 * HOUR_SECS + 1));
+        ledger.close_session(&bci, T0 + 7 * HOUR).unwrap();
+        let err = ledger.open_session(&bci, T0 + 8 * HOUR).unwrap_err();
+        assert!(matches!(err, ConsentError::DutyCycleExceeded { limit: DutyLimit::SessionHoursPerDay, .. }));
+        assert_eq!(err.retry_after_secs(), Some(16 * HOUR_SECS + 1));
+        ledger.open_session(&bci, T0 + DAY + 1).unwrap();
+    }
+
+    #[test]
+    fn limits_can_only_be_tightened() {
+        let mut ledger = ConsentLedger::default();
+        ledger.tighten(&Node::ScopeEeg, 100, 8.0).unwrap();
+        let err = ledger.tighten(&Node::ScopeEeg, 101, 8.0).unwrap_err();
+        assert_eq!(err, ConsentError::Loosening { scope: Node::ScopeEeg, limit: DutyLimit::EventsPerHour });
+        assert!(ledger.tighten(&Node::ScopeEeg, 100, f64::NAN).is_err());
+        assert_eq!(ledger.tighten(&Node::NBci, 1, 1.0), Err(ConsentError::UnknownScope(Node::NBci)));
+        ledger.tighten(&Node::ScopeEeg, 0, 8.0).unwrap();
+        assert_eq!(ledger.check_event(&Node::NSleep, &[], T0).unwrap_err().retry_after_secs(), None);
+    }
+
+    #[test]
+    fn repeated_violations_dent_compliance() {
+        let mut core = SovereigntyCore::new();
+        core.consent.tighten(&Node::ScopeBci, 1, 4.0).unwrap();
+        bci(&mut core, T0).unwrap();
+        let before = core.reputation.compliance;
+        for i in 1..=2 {
+            assert!(bci(&mut core, T0 + i).is_err());
+        }
+        assert_eq!(core.reputation.compliance, before);
+        assert!(bci(&mut core, T0 + 3).is_err());
+        assert!((core.reputation.compliance - (before - 0.05)).abs() < 1e-12);
+
+        let flag = core.deed_log.last().unwrap();
+        assert_eq!(flag.node, Node::ComplianceScore);
+        assert!(flag.ethics_flags.iter().any(|f| f == DUTY_CYCLE_FLAG));
+        assert_eq!(flag.context_json["violations"], 3);
+        let scored = core.compute_reputation(&EthicalFramework::default()).mp_score;
+        assert!(scored < SovereigntyCore::new().compute_reputation(&EthicalFramework::default()).mp_score);
+    }
+}
@@ -0,0 +1,543 @@
+//! Bulk consent migration between consent-policy versions.
+//! When scope definitions change, a MigrationPlan maps each old scope to its
+//! successors with one policy per mapping: `CarryOver` when the new scope
+//! covers strictly less than the old one (the grant moves over as is),
+//! `Reconfirm` when it covers more (the grant moves over pending the
+//! subject's reconfirmation, and the scope admits no events until then),
+//! and `Expire` when the scope is removed.
+//!
+//! `plan_migration` checks the plan and lists every active grant it touches.
+//! `execute_migration` appends the whole batch, one deed per action plus a
+//! closing `consent_migration` deed, only when a quorum approved it and the
+//! deed log has not moved since the plan was made. It returns the
+//! reconfirmation requests, which `reconfirm` consumes.
+//!
+//! Grants live in the deed log (`consent_granted`, `consent_revoked` and
+//! the migration deeds), so `grants` can rebuild them at any point. A grant
+//! keeps its purposes through a migration: a carried-over or reconfirmed
+//! grant allows what the old one did. Grants recorded before purposes
+//! existed carry none in their deed and are read as allowing every purpose.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::consent::Purpose;
+use crate::{DeedEvent, Node, SovereigntyCore};
+
+pub const CONSENT_GRANTED: &str = "consent_granted";
+pub const CONSENT_REVOKED: &str = "consent_revoked";
+pub const CONSENT_CARRIED_OVER: &str = "consent_carried_over";
+pub const CONSENT_PENDING_RECONFIRMATION: &str = "consent_pending_reconfirmation";
+pub const CONSENT_EXPIRED: &str = "consent_expired";
+pub const CONSENT_RECONFIRMED: &str = "consent_reconfirmed";
+pub const CONSENT_MIGRATION: &str = "consent_migration";
+
+pub(crate) const CONSENT_ACTOR: &str = "consent_ledger";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationPolicy {
+    /// The new scope is strictly narrower; grants carry over.
+    CarryOver,
+    /// The new scope is broader; grants wait for the subject.
+    Reconfirm,
+    /// The old scope is removed; grants expire.
+    Expire,
+}
+
+/// One old scope to one successor. A split is several mappings with the
+/// same `from`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopeMapping {
+    pub from: Node,
+    /// None exactly when the policy is `Expire`.
+    pub to: Option<Node>,
+    pub policy: MigrationPolicy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    pub version: String,
+    /// Data categories each scope covers under the current policy.
+    pub current: HashMap<Node, BTreeSet<String>>,
+    /// Data categories each scope covers under the new policy.
+    pub next: HashMap<Node, BTreeSet<String>>,
+    pub mappings: Vec<ScopeMapping>,
+    /// Event nodes governed by a new scope once the plan is executed.
+    #[serde(default)]
+    pub node_scopes: Vec<(Node, Node)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum GrantStatus {
+    Active,
+    PendingReconfirmation { request_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsentGrant {
+    pub subject: String,
+    pub scope: Node,
+    pub status: GrantStatus,
+    /// What the subject allowed data under this scope to be used for.
+    #[serde(default = "Purpose::all")]
+    pub purposes: BTreeSet<Purpose>,
+    /// When the grant took its current status.
+    pub since: i64,
+}
+
+impl ConsentGrant {
+    /// True when the grant is active and names `purpose`.
+    pub fn allows(&self, purpose: Purpose) -> bool {
+        self.status == GrantStatus::Active && self.purposes.contains(&purpose)
+    }
+}
+
+/// What a migration does to one grant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrantAction {
+    pub subject: String,
+    pub from: Node,
+    pub to: Option<Node>,
+    pub policy: MigrationPolicy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub version: String,
+    /// Deed-log tip the report was planned against.
+    pub planned_at_hash: String,
+    pub actions: Vec<GrantAction>,
+    pub mappings: Vec<ScopeMapping>,
+    pub node_scopes: Vec<(Node, Node)>,
+}
+
+impl MigrationReport {
+    /// Every subject with at least one affected grant.
+    pub fn subjects(&self) -> BTreeSet<&str> {
+        self.actions.iter().map(|a| a.subject.as_str()).collect()
+    }
+}
+
+/// Approvals an execution must carry: at least `required` distinct ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quorum {
+    pub required: usize,
+    pub approvals: Vec<String>,
+}
+
+/// Sent to a subject whose grant now waits for them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconfirmationRequest {
+    pub request_id: String,
+    pub subject: String,
+    pub from: Node,
+    pub scope: Node,
+    pub version: String,
+    pub requested_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    /// A carry-over whose new scope does not cover strictly less.
+    NotNarrower { from: Node, to: Node },
+    /// `to` is missing for a carry-over or reconfirmation, or set for an expiry.
+    InvalidMapping(ScopeMapping),
+    /// A mapped scope has no coverage in the plan.
+    UnknownCoverage(Node),
+    QuorumNotMet { required: usize, approvals: usize },
+    /// The deed log moved after the report was planned.
+    StaleReport { planned_at: String, current: String },
+    UnknownRequest(String),
+}
+
+impl MigrationError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            MigrationError::NotNarrower { .. } => "MIGRATION_NOT_NARROWER",
+            MigrationError::InvalidMapping(_) => "MIGRATION_INVALID_MAPPING",
+            MigrationError::UnknownCoverage(_) => "MIGRATION_UNKNOWN_COVERAGE",
+            MigrationError::QuorumNotMet { .. } => "MIGRATION_QUORUM_NOT_MET",
+            MigrationError::StaleReport { .. } => "MIGRATION_STALE_REPORT",
+            MigrationError::UnknownRequest(_) => "UNKNOWN_RECONFIRMATION_REQUEST",
+        }
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::NotNarrower { from, to } => {
+                write!(f, "{:?} does not cover strictly less than {:?}; it cannot carry over", to, from)
+            }
+            MigrationError::InvalidMapping(m) => write!(f, "{:?} mapping from {:?} to {:?}", m.policy, m.from, m.to),
+            MigrationError::UnknownCoverage(scope) => write!(f, "{:?} has no coverage in the plan", scope),
+            MigrationError::QuorumNotMet { required, approvals } => {
+                write!(f, "{} distinct approvals, {} required", approvals, required)
+            }
+            MigrationError::StaleReport { planned_at, current } => {
+                write!(f, "report planned at {} but the log is at {}", planned_at, current)
+            }
+            MigrationError::UnknownRequest(id) => write!(f, "no pending reconfirmation {}", id),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+fn node_of(context: &serde_json::Value, key: &str) -> Option<Node> {
+    serde_json::from_value(context.get(key)?.clone()).ok()
+}
+
+pub(crate) fn purposes_of(context: &serde_json::Value) -> BTreeSet<Purpose> {
+    match context.get("purposes") {
+        Some(purposes) => serde_json::from_value(purposes.clone()).unwrap_or_default(),
+        None => Purpose::all(),
+    }
+}
+
+fn set_grant(grants: &mut Vec<ConsentGrant>, subject: &str, scope: Node, status: GrantStatus, purposes: BTreeSet<Purpose>, since: i64) {
+    grants.retain(|g| !(g.subject == subject && g.scope == scope));
+    grants.push(ConsentGrant { subject: subject.to_string(), scope, status, purposes, since });
+}
+
+/// Fold one deed into `grants`; deeds off the consent ledger are ignored.
+pub(crate) fn apply_consent_deed(grants: &mut Vec<ConsentGrant>, d: &DeedEvent) {
+    if d.node != Node::ConsentLedger {
+        return;
+    }
+    let c = &d.context_json;
+    let Some(subject) = c["subject"].as_str() else { return };
+    match d.deed_type.as_str() {
+        CONSENT_GRANTED | CONSENT_RECONFIRMED => {
+            if let Some(scope) = node_of(c, "scope") {
+                set_grant(grants, subject, scope, GrantStatus::Active, purposes_of(c), d.timestamp);
+            }
+        }
+        CONSENT_REVOKED => {
+            if let Some(scope) = node_of(c, "scope") {
+                grants.retain(|g| !(g.subject == subject && g.scope == scope));
+            }
+        }
+        CONSENT_CARRIED_OVER | CONSENT_PENDING_RECONFIRMATION | CONSENT_EXPIRED => {
+            let mut purposes = Purpose::all();
+            if let Some(from) = node_of(c, "from") {
+                if let Some(old) = grants.iter().find(|g| g.subject == subject && g.scope == from) {
+                    purposes = old.purposes.clone();
+                }
+                grants.retain(|g| !(g.subject == subject && g.scope == from));
+            }
+            let status = match (d.deed_type.as_str(), c["request_id"].as_str()) {
+                (CONSENT_CARRIED_OVER, _) => GrantStatus::Active,
+                (CONSENT_PENDING_RECONFIRMATION, Some(id)) => GrantStatus::PendingReconfirmation { request_id: id.to_string() },
+                _ => return,
+            };
+            if let Some(to) = node_of(c, "to") {
+                set_grant(grants, subject, to, status, purposes, d.timestamp);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Grants as of the end of the deed log.
+pub fn grants(core: &SovereigntyCore) -> Vec<ConsentGrant> {
+    let mut out: Vec<ConsentGrant> = Vec::new();
+    for d in &core.deed_log {
+        apply_consent_deed(&mut out, d);
+    }
+    out
+}
+
+pub fn grant_status(core: &SovereigntyCore, subject: &str, scope: &Node) -> Option<GrantStatus> {
+    grants(core).into_iter().find(|g| g.subject == subject && &g.scope == scope).map(|g| g.status)
+}
+
+fn check_mapping(plan: &MigrationPlan, m: &ScopeMapping) -> Result<(), MigrationError> {
+    let current = plan.current.get(&m.from).ok_or_else(|| MigrationError::UnknownCoverage(m.from.clone()))?;
+    match (&m.to, m.policy) {
+        (None, MigrationPolicy::Expire) => Ok(()),
+        (Some(to), MigrationPolicy::CarryOver) => {
+            let next = plan.next.get(to).ok_or_else(|| MigrationError::UnknownCoverage(to.clone()))?;
+            if next.is_subset(current) && next.len() < current.len() {
+                Ok(())
+            } else {
+                Err(MigrationError::NotNarrower { from: m.from.clone(), to: to.clone() })
+            }
+        }
+        (Some(to), MigrationPolicy::Reconfirm) => {
+            plan.next.get(to).map(|_| ()).ok_or_else(|| MigrationError::UnknownCoverage(to.clone()))
+        }
+        _ => Err(MigrationError::InvalidMapping(m.clone())),
+    }
+}
+
+/// Check `plan` and list the action for every active grant it touches.
+pub fn plan_migration(core: &SovereigntyCore, plan: &MigrationPlan) -> Result<MigrationReport, MigrationError> {
+    for m in &plan.mappings {
+        check_mapping(plan, m)?;
+    }
+    let mut actions = Vec::new();
+    for grant in grants(core).into_iter().filter(|g| g.status == GrantStatus::Active) {
+        for m in plan.mappings.iter().filter(|m| m.from == grant.scope) {
+            actions.push(GrantAction { subject: grant.subject.clone(), from: m.from.clone(), to: m.to.clone(), policy: m.policy });
+        }
+    }
+    Ok(MigrationReport {
+        version: plan.version.clone(),
+        planned_at_hash: core.current_hash.clone(),
+        actions,
+        mappings: plan.mappings.clone(),
+        node_scopes: plan.node_scopes.clone(),
+    })
+}
+
+/// Append the report's batch. Nothing is written unless the quorum holds
+/// and the log is still where the report was planned.
+pub fn execute_migration(
+    core: &mut SovereigntyCore,
+    report: &MigrationReport,
+    quorum: &Quorum,
+    now: i64,
+) -> Result<Vec<ReconfirmationRequest>, MigrationError> {
+    let approvals: BTreeSet<&str> = quorum.approvals.iter().map(String::as_str).collect();
+    if quorum.required == 0 || approvals.len() < quorum.required {
+        return Err(MigrationError::QuorumNotMet { required: quorum.required, approvals: approvals.len() });
+    }
+    if core.current_hash != report.planned_at_hash {
+        return Err(MigrationError::StaleReport { planned_at: report.planned_at_hash.clone(), current: core.current_hash.clone() });
+    }
+
+    let batch_id = Uuid::new_v4().to_string();
+    let mut requests = Vec::new();
+    for a in &report.actions {
+        let mut context = serde_json::json!({
+            "batch_id": batch_id,
+            "version": report.version,
+            "subject": a.subject,
+            "from": a.from,
+            "to": a.to,
+        });
+        let deed_type = match a.policy {
+            MigrationPolicy::CarryOver => CONSENT_CARRIED_OVER,
+            MigrationPolicy::Expire => CONSENT_EXPIRED,
+            MigrationPolicy::Reconfirm => {
+                let request = ReconfirmationRequest {
+                    request_id: Uuid::new_v4().to_string(),
+                    subject: a.subject.clone(),
+                    from: a.from.clone(),
+                    scope: a.to.clone().expect("checked by plan_migration"),
+                    version: report.version.clone(),
+                    requested_at: now,
+                };
+                context["request_id"] = serde_json::json!(request.request_id);
+                requests.push(request);
+                CONSENT_PENDING_RECONFIRMATION
+            }
+        };
+        core.append(DeedEvent::new(CONSENT_ACTOR.to_string(), Node::ConsentLedger, deed_type.to_string(), context), now);
+    }
+
+    // Successor scopes start from their predecessor's duty-cycle limits.
+    for m in &report.mappings {
+        if let Some(to) = &m.to {
+            let _ = core.consent.adopt_scope(to, &m.from);
+        }
+    }
+    for (node, scope) in &report.node_scopes {
+        core.consent.reassign(node.clone(), scope.clone());
+    }
+
+    let count = |p: MigrationPolicy| report.actions.iter().filter(|a| a.policy == p).count();
+    let context = serde_json::json!({
+        "batch_id": batch_id,
+        "version": report.version,
+        "approvals": approvals,
+        "subjects": report.subjects(),
+        "carried_over": count(MigrationPolicy::CarryOver),
+        "pending_reconfirmation": count(MigrationPolicy::Reconfirm),
+        "expired": count(MigrationPolicy::Expire),
+        "node_scopes": report.node_scopes,
+    });
+    core.append(DeedEvent::new(CONSENT_ACTOR.to_string(), Node::ConsentLedger, CONSENT_MIGRATION.to_string(), context), now);
+    Ok(requests)
+}
+
+/// The subject answered `request_id`; their grant is active again.
+pub fn reconfirm(core: &mut SovereigntyCore, request_id: &str, now: i64) -> Result<ConsentGrant, MigrationError> {
+    let grant = grants(core)
+        .into_iter()
+        .find(|g| matches!(&g.status, GrantStatus::PendingReconfirmation { request_id: id } if id == request_id))
+        .ok_or_else(|| MigrationError::UnknownRequest(request_id.to_string()))?;
+    let context = serde_json::json!({
+        "subject": grant.subject,
+        "scope": grant.scope,
+        "request_id": request_id,
+        "purposes": grant.purposes,
+    });
+    core.append(DeedEvent::new(CONSENT_ACTOR.to_string(), Node::ConsentLedger, CONSENT_RECONFIRMED.to_string(), context), now);
+    Ok(ConsentGrant { status: GrantStatus::Active, since: now, ..grant })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsentError, CITIZEN};
+
+    const T0: i64 = 1_700_000_000_000;
+
+    fn covers(categories: &[&str]) -> BTreeSet<String> {
+        categories.iter().map(|c| c.to_string()).collect()
+    }
+
+    /// v2: EEG splits into a sleep scope (narrower) and a daytime scope
+    /// that adds ambulatory recording (broader); BCI gains motor imagery
+    /// in place.
+    fn v2() -> MigrationPlan {
+        MigrationPlan {
+            version: "consent-v2".into(),
+            current: HashMap::from([
+                (Node::ScopeEeg, covers(&["eeg_sleep", "eeg_daytime"])),
+                (Node::ScopeBci, covers(&["bci_cognitive"])),
+            ]),
+            next: HashMap::from([
+                (Node::ScopeEegSleep, covers(&["eeg_sleep"])),
+                (Node::ScopeEegDaytime, covers(&["eeg_daytime", "eeg_ambulatory"])),
+                (Node::ScopeBci, covers(&["bci_cognitive", "bci_motor_imagery"])),
+            ]),
+            mappings: vec![
+                ScopeMapping { from: Node::ScopeEeg, to: Some(Node::ScopeEegSleep), policy: MigrationPolicy::CarryOver },
+                ScopeMapping { from: Node::ScopeEeg, to: Some(Node::ScopeEegDaytime), policy: MigrationPolicy::Reconfirm },
+                ScopeMapping { from: Node::ScopeBci, to: Some(Node::ScopeBci), policy: MigrationPolicy::Reconfirm },
+            ],
+            node_scopes: vec![(Node::NSleep, Node::ScopeEegSleep)],
+        }
+    }
+
+    fn quorum() -> Quorum {
+        Quorum { required: 2, approvals: vec!["irb".into(), "dpo".into()] }
+    }
+
+    fn granted() -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        core.grant_consent(CITIZEN, Node::ScopeEeg, T0).unwrap();
+        core.grant_consent(CITIZEN, Node::ScopeBci, T0).unwrap();
+        core.grant_consent("subject-2", Node::ScopeEeg, T0).unwrap();
+        core
+    }
+
+    fn scopes_of(core: &SovereigntyCore, subject: &str) -> Vec<(Node, GrantStatus)> {
+        grants(core).into_iter().filter(|g| g.subject == subject).map(|g| (g.scope, g.status)).collect()
+    }
+
+    #[test]
+    fn each_policy_moves_grants_its_own_way() {
+        let mut core = granted();
+        let mut plan = v2();
+        plan.mappings.push(ScopeMapping { from: Node::ScopeBci, to: None, policy: MigrationPolicy::Expire });
+        plan.mappings.retain(|m| !(m.from == Node::ScopeBci && m.policy == MigrationPolicy::Reconfirm));
+
+        let report = plan_migration(&core, &plan).unwrap();
+        assert_eq!(report.subjects(), BTreeSet::from([CITIZEN, "subject-2"]));
+        assert_eq!(report.actions.len(), 5);
+        let requests = execute_migration(&mut core, &report, &quorum(), T0 + 10).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.scope == Node::ScopeEegDaytime && r.version == "consent-v2"));
+
+        let pending = |id: &str| GrantStatus::PendingReconfirmation { request_id: id.to_string() };
+        let mine = requests.iter().find(|r| r.subject == CITIZEN).unwrap();
+        assert_eq!(
+            scopes_of(&core, CITIZEN),
+            vec![(Node::ScopeEegSleep, GrantStatus::Active), (Node::ScopeEegDaytime, pending(&mine.request_id))]
+        );
+        assert_eq!(scopes_of(&core, "subject-2").len(), 2);
+
+        // The request is consumed once.
+        let grant = reconfirm(&mut core, &mine.request_id, T0 + 20).unwrap();
+        assert_eq!((grant.scope, grant.status, grant.since), (Node::ScopeEegDaytime, GrantStatus::Active, T0 + 20));
+        assert_eq!(grant_status(&core, CITIZEN, &Node::ScopeEegDaytime), Some(GrantStatus::Active));
+        assert_eq!(reconfirm(&mut core, &mine.request_id, T0 + 30), Err(MigrationError::UnknownRequest(mine.request_id.clone())));
+
+        // Successor scopes inherit the duty-cycle limits of their predecessor.
+        assert_eq!(core.consent.scope(&Node::ScopeEegSleep).unwrap().max_events_per_hour, 120);
+    }
+
+    #[test]
+    fn a_broader_scope_cannot_be_labelled_carry_over() {
+        let core = granted();
+        let mut plan = v2();
+        plan.mappings[1].policy = MigrationPolicy::CarryOver;
+        assert_eq!(
+            plan_migration(&core, &plan),
+            Err(MigrationError::NotNarrower { from: Node::ScopeEeg, to: Node::ScopeEegDaytime })
+        );
+        // Equal coverage is not strictly narrower either.
+        plan.mappings[1] = ScopeMapping { from: Node::ScopeBci, to: Some(Node::ScopeBci), policy: MigrationPolicy::CarryOver };
+        plan.next.insert(Node::ScopeBci, covers(&["bci_cognitive"]));
+        assert_eq!(plan_migration(&core, &plan).unwrap_err().code(), "MIGRATION_NOT_NARROWER");
+
+        let mut plan = v2();
+        plan.mappings[0].to = None;
+        assert!(matches!(plan_migration(&core, &plan), Err(MigrationError::InvalidMapping(_))));
+        let mut plan = v2();
+        plan.next.remove(&Node::ScopeEegDaytime);
+        assert_eq!(plan_migration(&core, &plan), Err(MigrationError::UnknownCoverage(Node::ScopeEegDaytime)));
+    }
+
+    #[test]
+    fn a_batch_is_written_whole_or_not_at_all() {
+        let mut core = granted();
+        let report = plan_migration(&core, &v2()).unwrap();
+        let before = core.deed_log.len();
+
+        let short = Quorum { required: 2, approvals: vec!["irb".into(), "irb".into()] };
+        assert_eq!(execute_migration(&mut core, &report, &short, T0), Err(MigrationError::QuorumNotMet { required: 2, approvals: 1 }));
+        assert_eq!(core.deed_log.len(), before);
+
+        // A grant after planning makes the report stale.
+        core.grant_consent("subject-3", Node::ScopeEeg, T0 + 1).unwrap();
+        assert_eq!(execute_migration(&mut core, &report, &quorum(), T0 + 2).unwrap_err().code(), "MIGRATION_STALE_REPORT");
+        assert_eq!(core.deed_log.len(), before + 1);
+        assert_eq!(core.consent.governing_scope(&Node::NSleep), Some(Node::ScopeEeg));
+
+        let report = plan_migration(&core, &v2()).unwrap();
+        execute_migration(&mut core, &report, &quorum(), T0 + 3).unwrap();
+        let batch = &core.deed_log[before + 1..];
+        assert_eq!(batch.len(), report.actions.len() + 1);
+        let batch_id = &batch[0].context_json["batch_id"];
+        assert!(batch.iter().all(|d| &d.context_json["batch_id"] == batch_id && d.timestamp == T0 + 3));
+        let closing = batch.last().unwrap();
+        assert_eq!(closing.deed_type, CONSENT_MIGRATION);
+        assert_eq!(
+            (&closing.context_json["carried_over"], &closing.context_json["pending_reconfirmation"]),
+            (&serde_json::json!(3), &serde_json::json!(4))
+        );
+        assert_eq!(closing.context_json["approvals"], serde_json::json!(["dpo", "irb"]));
+    }
+
+    #[test]
+    fn pending_scopes_admit_no_events() {
+        let mut core = granted();
+        core.log_event_at(Node::NBci, "cognitive_trial".into(), serde_json::json!({}), T0 + 1).unwrap();
+        let report = plan_migration(&core, &v2()).unwrap();
+        let requests = execute_migration(&mut core, &report, &quorum(), T0 + 10).unwrap();
+
+        // BCI broadened in place: inactive until reconfirmed, and refusals
+        // do not count as duty-cycle violations.
+        let err = core.log_event_at(Node::NBci, "cognitive_trial".into(), serde_json::json!({}), T0 + 20).unwrap_err();
+        assert_eq!(err, ConsentError::PendingReconfirmation(Node::ScopeBci));
+        assert_eq!(err.code(), "CONSENT_PENDING_RECONFIRMATION");
+        assert_eq!(core.consent.violations, 0);
+
+        // Sleep events moved to the carried-over sleep scope and still log.
+        assert_eq!(core.consent.governing_scope(&Node::NSleep), Some(Node::ScopeEegSleep));
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), serde_json::json!({}), T0 + 20).unwrap();
+
+        let bci = requests.iter().find(|r| r.subject == CITIZEN && r.scope == Node::ScopeBci).unwrap();
+        reconfirm(&mut core, &bci.request_id, T0 + 30).unwrap();
+        core.log_event_at(Node::NBci, "cognitive_trial".into(), serde_json::json!({}), T0 + 40).unwrap();
+    }
+}
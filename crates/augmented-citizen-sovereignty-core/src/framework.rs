@@ -0,0 +1,336 @@
+//! Ethical frameworks for aggregating the Reputation Vector into mp_score.
+//! Deployments weight the four components differently (a clinical site leans
+//! on clin_trust, an eco coop on eco_align). A framework fixes those weights,
+//! per-component floors, and the aggregation rule; its name and version are
+//! stamped on every reputation history entry it produces.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ReputationVector;
+
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+/// Per-component weights over the four reputation scores. Must sum to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReputationWeights {
+    pub privacy: f64,
+    pub compliance: f64,
+    pub eco_align: f64,
+    pub clin_trust: f64,
+}
+
+impl Default for ReputationWeights {
+    fn default() -> Self {
+        Self { privacy: 0.25, compliance: 0.25, eco_align: 0.25, clin_trust: 0.25 }
+    }
+}
+
+impl ReputationWeights {
+    fn as_array(&self) -> [f64; 4] {
+        [self.privacy, self.compliance, self.eco_align, self.clin_trust]
+    }
+}
+
+/// Hard minimums: mp_score is 0.0 if any component falls below its floor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComponentFloors {
+    #[serde(default)]
+    pub privacy: f64,
+    #[serde(default)]
+    pub compliance: f64,
+    #[serde(default)]
+    pub eco_align: f64,
+    #[serde(default)]
+    pub clin_trust: f64,
+}
+
+impl ComponentFloors {
+    fn as_array(&self) -> [f64; 4] {
+        [self.privacy, self.compliance, self.eco_align, self.clin_trust]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Weighted arithmetic mean.
+    #[default]
+    Linear,
+    /// Weighted geometric mean; a zero in any weighted component drives it to 0.
+    Geometric,
+    /// Minimum over components with non-zero weight.
+    Min,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EthicalFramework {
+    pub name: String,
+    pub version: u32,
+    pub weights: ReputationWeights,
+    #[serde(default)]
+    pub floors: ComponentFloors,
+    #[serde(default)]
+    pub aggregation: Aggregation,
+}
+
+impl Default for EthicalFramework {
+    fn default() -> Self {
+        Self {
+            name: "balanced".to_string(),
+            version: 1,
+            weights: ReputationWeights::default(),
+            floors: ComponentFloors::default(),
+            aggregation: Aggregation::Linear,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameworkError {
+    Parse(String),
+    EmptyName,
+    WeightOutOfRange { framework: String, component: &'static str, value: f64 },
+    WeightSum { framework: String, sum: f64 },
+    FloorOutOfRange { framework: String, component: &'static str, value: f64 },
+    UnknownFramework(String),
+}
+
+impl fmt::Display for FrameworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameworkError::Parse(e) => write!(f, "framework JSON: {}", e),
+            FrameworkError::EmptyName => write!(f, "framework name must not be empty"),
+            FrameworkError::WeightOutOfRange { framework, component, value } => {
+                write!(f, "{}: weight {} = {} outside [0,1]", framework, component, value)
+            }
+            FrameworkError::WeightSum { framework, sum } => write!(f, "{}: weights sum to {}, expected 1", framework, sum),
+            FrameworkError::FloorOutOfRange { framework, component, value } => {
+                write!(f, "{}: floor {} = {} outside [0,1]", framework, component, value)
+            }
+            FrameworkError::UnknownFramework(name) => write!(f, "unknown framework {}", name),
+        }
+    }
+}
+
+impl std::error::Error for FrameworkError {}
+
+const COMPONENTS: [&str; 4] = ["privacy", "compliance", "eco_align", "clin_trust"];
+
+fn components(v: &ReputationVector) -> [f64; 4] {
+    [v.privacy, v.compliance, v.eco_align, v.clin_trust]
+}
+
+impl EthicalFramework {
+    pub fn from_json(json: &str) -> Result<Self, FrameworkError> {
+        let fw: Self = serde_json::from_str(json).map_err(|e| FrameworkError::Parse(e.to_string()))?;
+        fw.validate()?;
+        Ok(fw)
+    }
+
+    pub fn validate(&self) -> Result<(), FrameworkError> {
+        if self.name.trim().is_empty() {
+            return Err(FrameworkError::EmptyName);
+        }
+        let weights = self.weights.as_array();
+        for (component, &value) in COMPONENTS.iter().zip(weights.iter()) {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(FrameworkError::WeightOutOfRange { framework: self.name.clone(), component, value });
+            }
+        }
+        let sum: f64 = weights.iter().sum();
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(FrameworkError::WeightSum { framework: self.name.clone(), sum });
+        }
+        for (component, &value) in COMPONENTS.iter().zip(self.floors.as_array().iter()) {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(FrameworkError::FloorOutOfRange { framework: self.name.clone(), component, value });
+            }
+        }
+        Ok(())
+    }
+
+    /// Aggregate the four components into mp_score in [0,1]. Floors are
+    /// checked first and gate the result to 0.0 regardless of the weighted sum.
+    pub fn score(&self, v: &ReputationVector) -> f64 {
+        let values = components(v).map(|c| c.clamp(0.0, 1.0));
+        if values.iter().zip(self.floors.as_array().iter()).any(|(c, floor)| c < floor) {
+            return 0.0;
+        }
+        let weights = self.weights.as_array();
+        let score = match self.aggregation {
+            Aggregation::Linear => values.iter().zip(weights.iter()).map(|(c, w)| c * w).sum(),
+            Aggregation::Geometric => values
+                .iter()
+                .zip(weights.iter())
+                .filter(|(_, w)| **w > 0.0)
+                .map(|(c, w)| c.powf(*w))
+                .product(),
+            Aggregation::Min => values
+                .iter()
+                .zip(weights.iter())
+                .filter(|(_, w)| **w > 0.0)
+                .map(|(c, _)| *c)
+                .fold(1.0, f64::min),
+        };
+        score.clamp(0.0, 1.0)
+    }
+}
+
+/// Named frameworks plus the mapping from tenant / actor class to framework.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameworkRegistry {
+    pub default: String,
+    pub frameworks: Vec<EthicalFramework>,
+    /// tenant or actor class -> framework name
+    #[serde(default)]
+    pub assignments: HashMap<String, String>,
+}
+
+impl FrameworkRegistry {
+    pub fn from_json(json: &str) -> Result<Self, FrameworkError> {
+        let reg: Self = serde_json::from_str(json).map_err(|e| FrameworkError::Parse(e.to_string()))?;
+        reg.validate()?;
+        Ok(reg)
+    }
+
+    pub fn validate(&self) -> Result<(), FrameworkError> {
+        for fw in &self.frameworks {
+            fw.validate()?;
+        }
+        self.get(&self.default)?;
+        for name in self.assignments.values() {
+            self.get(name)?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&EthicalFramework, FrameworkError> {
+        self.frameworks
+            .iter()
+            .filter(|f| f.name == name)
+            .max_by_key(|f| f.version)
+            .ok_or_else(|| FrameworkError::UnknownFramework(name.to_string()))
+    }
+
+    /// Framework for a tenant or actor class, falling back to the default.
+    pub fn select(&self, class: &str) -> Result<&EthicalFramework, FrameworkError> {
+        match self.assignments.get(class) {
+            Some(name) => self.get(name),
+            None => self.get(&self.default),
+        }
+    }
+}
+
+/// One timeline entry: the vector as scored, and which framework scored it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationEntry {
+    pub timestamp: i64,
+    pub vector: ReputationVector,
+    pub framework: String,
+    pub framework_version: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SovereigntyCore;
+
+    fn vector(privacy: f64, compliance: f64, eco_align: f64, clin_trust: f64) -> ReputationVector {
+        ReputationVector { privacy, compliance, eco_align, clin_trust, mp_score: 0.0 }
+    }
+
+    fn framework(name: &str, weights: [f64; 4], aggregation: Aggregation) -> EthicalFramework {
+        EthicalFramework {
+            name: name.to_string(),
+            version: 1,
+            weights: ReputationWeights { privacy: weights[0], compliance: weights[1], eco_align: weights[2], clin_trust: weights[3] },
+            floors: ComponentFloors::default(),
+            aggregation,
+        }
+    }
+
+    #[test]
+    fn same_vector_scores_differently_per_framework() {
+        let v = vector(0.5, 0.5, 0.9, 0.3);
+        let eco = framework("eco_coop", [0.1, 0.1, 0.7, 0.1], Aggregation::Linear);
+        let clinical = framework("clinical", [0.1, 0.1, 0.1, 0.7], Aggregation::Linear);
+        assert!((eco.score(&v) - 0.76).abs() < 1e-9);
+        assert!((clinical.score(&v) - 0.40).abs() < 1e-9);
+    }
+
+    #[test]
+    fn floor_gates_score_to_zero() {
+        let mut fw = EthicalFramework::default();
+        fw.floors.clin_trust = 0.6;
+        assert_eq!(fw.score(&vector(1.0, 1.0, 1.0, 0.59)), 0.0);
+        assert!(fw.score(&vector(1.0, 1.0, 1.0, 0.6)) > 0.8);
+    }
+
+    #[test]
+    fn geometric_mean_with_zero_component() {
+        let fw = framework("geo", [0.25, 0.25, 0.25, 0.25], Aggregation::Geometric);
+        assert_eq!(fw.score(&vector(0.9, 0.8, 0.0, 0.7)), 0.0);
+        let expected = (0.9f64 * 0.8 * 0.6 * 0.7).powf(0.25);
+        assert!((fw.score(&vector(0.9, 0.8, 0.6, 0.7)) - expected).abs() < 1e-12);
+
+        // A zero-weighted component does not participate.
+        let fw = framework("geo3", [0.5, 0.5, 0.0, 0.0], Aggregation::Geometric);
+        assert!((fw.score(&vector(0.64, 1.0, 0.0, 0.0)) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn min_aggregation_ignores_zero_weights() {
+        let fw = framework("min", [0.5, 0.5, 0.0, 0.0], Aggregation::Min);
+        assert_eq!(fw.score(&vector(0.7, 0.9, 0.0, 0.1)), 0.7);
+    }
+
+    #[test]
+    fn json_validation() {
+        let ok = r#"{"name":"eco","version":2,"weights":{"privacy":0.1,"compliance":0.2,"eco_align":0.6,"clin_trust":0.1},"aggregation":"geometric"}"#;
+        let fw = EthicalFramework::from_json(ok).unwrap();
+        assert_eq!(fw.aggregation, Aggregation::Geometric);
+
+        let bad_sum = r#"{"name":"eco","version":1,"weights":{"privacy":0.5,"compliance":0.5,"eco_align":0.5,"clin_trust":0.1}}"#;
+        assert!(matches!(EthicalFramework::from_json(bad_sum), Err(FrameworkError::WeightSum { .. })));
+
+        let bad_floor = r#"{"name":"eco","version":1,"weights":{"privacy":0.25,"compliance":0.25,"eco_align":0.25,"clin_trust":0.25},"floors":{"privacy":1.5}}"#;
+        assert!(matches!(EthicalFramework::from_json(bad_floor), Err(FrameworkError::FloorOutOfRange { .. })));
+    }
+
+    #[test]
+    fn registry_selects_by_class() {
+        let json = r#"{
+            "default": "balanced",
+            "frameworks": [
+                {"name":"balanced","version":1,"weights":{"privacy":0.25,"compliance":0.25,"eco_align":0.25,"clin_trust":0.25}},
+                {"name":"clinical","version":1,"weights":{"privacy":0.2,"compliance":0.2,"eco_align":0.0,"clin_trust":0.6}}
+            ],
+            "assignments": {"hospital_tenant": "clinical"}
+        }"#;
+        let reg = FrameworkRegistry::from_json(json).unwrap();
+        assert_eq!(reg.select("hospital_tenant").unwrap().name, "clinical");
+        assert_eq!(reg.select("anyone_else").unwrap().name, "balanced");
+
+        let dangling = json.replace("\"hospital_tenant\": \"clinical\"", "\"hospital_tenant\": \"missing\"");
+        assert!(matches!(FrameworkRegistry::from_json(&dangling), Err(FrameworkError::UnknownFramework(_))));
+    }
+
+    #[test]
+    fn history_keeps_framework_tag_across_switch() {
+        let mut core = SovereigntyCore::new();
+        let balanced = EthicalFramework::default();
+        let clinical = EthicalFramework { version: 3, ..framework("clinical", [0.1, 0.1, 0.1, 0.7], Aggregation::Linear) };
+
+        core.compute_reputation(&balanced);
+        core.compute_reputation(&clinical);
+
+        assert_eq!(core.reputation_history.len(), 2);
+        assert_eq!(core.reputation_history[0].framework, "balanced");
+        assert_eq!(core.reputation_history[0].framework_version, 1);
+        assert_eq!(core.reputation_history[1].framework, "clinical");
+        assert_eq!(core.reputation_history[1].framework_version, 3);
+        assert!((core.reputation_history[0].vector.mp_score - balanced.score(&core.reputation)).abs() < 1e-12);
+    }
+}
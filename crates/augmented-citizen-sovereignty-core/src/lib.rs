@@ -5,23 +5,135 @@
 
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use petgraph::prelude::*;
+use petgraph::algo::has_path_connecting;
 use petgraph::dot::{Dot, Config};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+
+const CITIZEN: &str = "augmented_citizen";
+/// `prev_hash` of the first event in a `deed_log`.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+pub mod anchor;
+pub mod autonomic_fear_rail;
+pub mod calm;
+pub mod clock;
+pub mod consent;
+pub mod consent_migration;
+pub mod framework;
+pub mod mint;
+pub mod paths;
+pub mod purpose;
+pub mod scoring;
+pub mod store;
+
+pub use anchor::{verify_merkle_proof, AnchorManifest, MerkleStep, ANCHOR_TARGETS};
+pub use calm::{AutonomicCalmSource, CalmBand, CalmSource, CalmThresholds, StaticCalmSource};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use consent::{ConsentError, ConsentLedger, ConsentScope, DutyCyclePolicy, Purpose, SessionRecord, DUTY_CYCLE_EXCEEDED};
+pub use consent_migration::{
+    execute_migration, plan_migration, reconfirm, ConsentGrant, GrantAction, GrantStatus, MigrationError, MigrationPlan,
+    MigrationPolicy, MigrationReport, Quorum, ReconfirmationRequest, ScopeMapping,
+};
+pub use framework::{Aggregation, ComponentFloors, EthicalFramework, FrameworkError, FrameworkRegistry, ReputationEntry, ReputationWeights};
+pub use mint::{MintError, MintPolicy, MintReceipt, CHURCH_MINT};
+pub use paths::{EnergyBand, PathPolicy, PathValidation};
+pub use purpose::{ComponentAttestation, CoverageWindow, EventAnalytics, PurposeCoverage, PurposeExport, PurposeGate};
+pub use store::StoreError;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Node {
     Root,
     IdLayer, Did, BostromAddr,
     ConsentLedger, ScopeEeg, ScopeBci,
+    /// Successors of ScopeEeg after the sleep/daytime split; consent
+    /// scopes only, outside the graph.
+    ScopeEegSleep, ScopeEegDaytime,
     Events, NSleep, NBci, NClin,
     Reputation, PrivacyScore, ComplianceScore, EcoAlignScore, ClinTrustScore,
     Anchors, BostromAnchor, Googolswarm, Ghostnet,
     Target1, Target2, Path1, Path2,
 }
 
+impl Node {
+    /// Stable Mermaid identifier.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Node::Root => "ROOT",
+            Node::IdLayer => "IDLAYER",
+            Node::Did => "DID",
+            Node::BostromAddr => "BOSTROM_ADDR",
+            Node::ConsentLedger => "CONSENT",
+            Node::ScopeEeg => "SCOPE_EEG",
+            Node::ScopeBci => "SCOPE_BCI",
+            Node::ScopeEegSleep => "SCOPE_EEG_SLEEP",
+            Node::ScopeEegDaytime => "SCOPE_EEG_DAYTIME",
+            Node::Events => "EVENTS",
+            Node::NSleep => "N_SLEEP",
+            Node::NBci => "N_BCI",
+            Node::NClin => "N_CLIN",
+            Node::Reputation => "REPUTATION",
+            Node::PrivacyScore => "PRIVACY",
+            Node::ComplianceScore => "COMPLIANCE",
+            Node::EcoAlignScore => "ECO_ALIGN",
+            Node::ClinTrustScore => "CLIN_TRUST",
+            Node::Anchors => "ANCHORS",
+            Node::BostromAnchor => "BOSTROM_ANCHOR",
+            Node::Googolswarm => "GOOGOLSWARM",
+            Node::Ghostnet => "GHOSTNET",
+            Node::Target1 => "TARGET1",
+            Node::Target2 => "TARGET2",
+            Node::Path1 => "PATH1",
+            Node::Path2 => "PATH2",
+        }
+    }
+
+    /// Display label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Node::Root => "Augmented Citizen",
+            Node::IdLayer => "Identity Layer",
+            Node::Did => "DID",
+            Node::BostromAddr => "Bostrom / Alt Addresses",
+            Node::ConsentLedger => "Neuro-Consent Ledger",
+            Node::ScopeEeg => "EEG Sleep Staging",
+            Node::ScopeBci => "BCI Cognitive Trials",
+            Node::ScopeEegSleep => "EEG Sleep",
+            Node::ScopeEegDaytime => "EEG Daytime",
+            Node::Events => "Neuro Interaction Events",
+            Node::NSleep => "SleepStudy Events",
+            Node::NBci => "BCI / CognitiveTrial Events",
+            Node::NClin => "Clinical / Therapeutic Sessions",
+            Node::Reputation => "Reputation Vector",
+            Node::PrivacyScore => "Privacy & Neuro-Rights Score",
+            Node::ComplianceScore => "Compliance / Attestation Score",
+            Node::EcoAlignScore => "Eco-Alignment Score",
+            Node::ClinTrustScore => "Clinical Trial Trust Score",
+            Node::Anchors => "Hash-Anchored Ledgers",
+            Node::BostromAnchor => "Bostrom Transparency Manifests",
+            Node::Googolswarm => "Googolswarm Ownership Proofs",
+            Node::Ghostnet => "GhostNet / Cybernetic Chain",
+            Node::Target1 => "High-Trust, Low-Energy EEG Runs",
+            Node::Target2 => "Signed, Consent-Aligned BCI Trials",
+            Node::Path1 => "PATH1",
+            Node::Path2 => "PATH2",
+        }
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Text for a quoted Mermaid label: quotes and pipes become entities, and
+/// so does `#`, which would otherwise start one.
+fn mermaid_escape(s: &str) -> String {
+    s.replace('#', "#35;").replace('"', "#quot;").replace('|', "#124;")
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub from: Node,
@@ -29,6 +141,12 @@ pub struct Edge {
     pub label: String,
 }
 
+impl std::fmt::Display for Edge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReputationVector {
     pub privacy: f64,        // [0,1]
@@ -38,9 +156,20 @@ pub struct ReputationVector {
     pub mp_score: f64,       // moral_position
 }
 
+/// The unit a deed's timestamp was recorded and hashed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    /// Deeds logged before millisecond timestamps; they carry no unit.
+    Seconds,
+    Millis,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredDeedEvent", into = "StoredDeedEvent")]
 pub struct DeedEvent {
     pub event_id: String,
+    /// Unix milliseconds, whatever `time_unit` it was recorded in.
     pub timestamp: i64,
     pub prev_hash: String,
     pub self_hash: String,
@@ -50,29 +179,167 @@ pub struct DeedEvent {
     pub context_json: serde_json::Value,
     pub ethics_flags: Vec<String>,
     pub life_harm_flag: bool,
+    pub time_unit: TimeUnit,
+}
+
+impl DeedEvent {
+    /// `timestamp` in the unit it was recorded in, as stored and hashed.
+    fn recorded_timestamp(&self) -> i64 {
+        match self.time_unit {
+            TimeUnit::Seconds => self.timestamp / 1_000,
+            TimeUnit::Millis => self.timestamp,
+        }
+    }
+}
+
+/// A `DeedEvent` as serialized: the timestamp in its recorded unit, and no
+/// `time_unit` for second-precision deeds, so older logs read and re-save
+/// unchanged.
+#[derive(Serialize, Deserialize)]
+struct StoredDeedEvent {
+    event_id: String,
+    timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time_unit: Option<TimeUnit>,
+    prev_hash: String,
+    self_hash: String,
+    actor_id: String,
+    node: Node,
+    deed_type: String,
+    context_json: serde_json::Value,
+    ethics_flags: Vec<String>,
+    life_harm_flag: bool,
+}
+
+impl From<StoredDeedEvent> for DeedEvent {
+    fn from(s: StoredDeedEvent) -> Self {
+        let time_unit = s.time_unit.unwrap_or(TimeUnit::Seconds);
+        let timestamp = match time_unit {
+            TimeUnit::Seconds => s.timestamp.saturating_mul(1_000),
+            TimeUnit::Millis => s.timestamp,
+        };
+        Self {
+            event_id: s.event_id, timestamp, prev_hash: s.prev_hash, self_hash: s.self_hash,
+            actor_id: s.actor_id, node: s.node, deed_type: s.deed_type, context_json: s.context_json,
+            ethics_flags: s.ethics_flags, life_harm_flag: s.life_harm_flag, time_unit,
+        }
+    }
+}
+
+impl From<DeedEvent> for StoredDeedEvent {
+    fn from(d: DeedEvent) -> Self {
+        Self {
+            timestamp: d.recorded_timestamp(),
+            time_unit: (d.time_unit != TimeUnit::Seconds).then_some(d.time_unit),
+            event_id: d.event_id, prev_hash: d.prev_hash, self_hash: d.self_hash,
+            actor_id: d.actor_id, node: d.node, deed_type: d.deed_type, context_json: d.context_json,
+            ethics_flags: d.ethics_flags, life_harm_flag: d.life_harm_flag,
+        }
+    }
+}
+
+impl From<&DeedEvent> for deed_core::DeedEvent {
+    /// The shared shape, keeping the deed's hash: `node`, and the unit of
+    /// a second-precision deed, under `ext`.
+    fn from(d: &DeedEvent) -> Self {
+        let mut ext = serde_json::Map::new();
+        ext.insert("node".to_string(), serde_json::to_value(&d.node).expect("node serializes"));
+        if d.time_unit == TimeUnit::Seconds {
+            ext.insert("time_unit".to_string(), serde_json::Value::from("seconds"));
+        }
+        Self {
+            event_id: d.event_id.clone(), timestamp: d.timestamp, prev_hash: d.prev_hash.clone(), self_hash: d.self_hash.clone(),
+            actor_id: d.actor_id.clone(), target_ids: Vec::new(), deed_type: d.deed_type.clone(), tags: Vec::new(),
+            context_json: d.context_json.clone(), ethics_flags: d.ethics_flags.clone(), life_harm_flag: d.life_harm_flag,
+            ext, hash_rule: deed_core::HashRule::SovereigntyV1,
+        }
+    }
+}
+
+/// Only deeds sealed under the sovereignty rule convert back, and only
+/// with no targets, tags or `ext` fields the log has nowhere to keep.
+impl TryFrom<deed_core::DeedEvent> for DeedEvent {
+    type Error = deed_core::DeedCoreError;
+
+    fn try_from(mut d: deed_core::DeedEvent) -> Result<Self, Self::Error> {
+        deed_core::DeedCoreError::check_rule(&d, deed_core::HashRule::SovereigntyV1)?;
+        let shape = |event_id: &str, reason: String| deed_core::DeedCoreError::Shape { event_id: event_id.to_string(), reason };
+        let node = d.ext.remove("node").ok_or_else(|| shape(&d.event_id, "no node".to_string()))?;
+        let node: Node = serde_json::from_value(node).map_err(|e| shape(&d.event_id, e.to_string()))?;
+        let time_unit = match d.ext.remove("time_unit") {
+            Some(unit) if unit == "seconds" => TimeUnit::Seconds,
+            _ => TimeUnit::Millis,
+        };
+        if !d.target_ids.is_empty() || !d.tags.is_empty() || !d.ext.is_empty() {
+            return Err(shape(&d.event_id, "targets, tags or ext fields the deed log cannot hold".to_string()));
+        }
+        Ok(Self {
+            event_id: d.event_id, timestamp: d.timestamp, prev_hash: d.prev_hash, self_hash: d.self_hash,
+            actor_id: d.actor_id, node, deed_type: d.deed_type, context_json: d.context_json,
+            ethics_flags: d.ethics_flags, life_harm_flag: d.life_harm_flag, time_unit,
+        })
+    }
+}
+
+/// What `self_hash` covers: every field of a `DeedEvent` but `self_hash`,
+/// the timestamp in its recorded unit.
+#[derive(Serialize)]
+struct HashableDeedEvent<'a> {
+    event_id: &'a str,
+    timestamp: i64,
+    prev_hash: &'a str,
+    actor_id: &'a str,
+    node: &'a Node,
+    deed_type: &'a str,
+    context_json: &'a serde_json::Value,
+    ethics_flags: &'a [String],
+    life_harm_flag: bool,
+}
+
+impl<'a> From<&'a DeedEvent> for HashableDeedEvent<'a> {
+    fn from(d: &'a DeedEvent) -> Self {
+        Self {
+            event_id: &d.event_id,
+            timestamp: d.recorded_timestamp(),
+            prev_hash: &d.prev_hash,
+            actor_id: &d.actor_id,
+            node: &d.node,
+            deed_type: &d.deed_type,
+            context_json: &d.context_json,
+            ethics_flags: &d.ethics_flags,
+            life_harm_flag: d.life_harm_flag,
+        }
+    }
 }
 
 impl DeedEvent {
     pub fn new(actor_id: String, node: Node, deed_type: String, context: serde_json::Value) -> Self {
         let event_id = Uuid::new_v4().to_string();
-        let timestamp = Utc::now().timestamp();
+        let timestamp = SystemClock.now_millis();
         let mut event = Self {
             event_id, timestamp, prev_hash: String::new(), self_hash: String::new(),
             actor_id, node, deed_type, context_json: context,
             ethics_flags: vec!["neuro_rights".to_string(), "consent_anchored".to_string()],
             life_harm_flag: false,
+            time_unit: TimeUnit::Millis,
         };
         event.self_hash = event.compute_hash();
         event
     }
 
-    fn compute_hash(&self) -> String {
+    /// SHA-256 of the event's canonical form, which leaves out `self_hash`.
+    pub fn compute_hash(&self) -> String {
         let mut hasher = Sha256::new();
-        let canonical = serde_json::to_string(&self).unwrap();
+        let canonical = serde_json::to_string(&HashableDeedEvent::from(self)).unwrap();
         hasher.update(canonical.as_bytes());
         format!("{:x}", hasher.finalize())
     }
 
+    /// Whether `self_hash` still matches the event's contents.
+    pub fn verify_hash(&self) -> bool {
+        self.compute_hash() == self.self_hash
+    }
+
     pub fn link_to_prev(&mut self, prev_hash: String) {
         self.prev_hash = prev_hash;
         self.self_hash = self.compute_hash();
@@ -81,9 +348,31 @@ impl DeedEvent {
 
 pub struct SovereigntyCore {
     pub graph: DiGraph<Node, Edge>,
+    /// Where each node of `graph` sits, by variant.
+    nodes: HashMap<Node, NodeIndex>,
     pub reputation: ReputationVector,
     pub deed_log: Vec<DeedEvent>,
     pub current_hash: String,
+    /// Scored vectors over time, each tagged with the framework that scored it.
+    pub reputation_history: Vec<ReputationEntry>,
+    /// Consent scopes, their duty-cycle limits and session records.
+    pub consent: ConsentLedger,
+    /// Thresholds the PATH1/PATH2 checks hold deeds to.
+    pub path_policy: PathPolicy,
+    /// Threshold, rate and framework `mint_church` mints under.
+    pub mint_policy: MintPolicy,
+    /// Where the CALM_STABLE predicate comes from.
+    calm: Box<dyn CalmSource>,
+    /// What `log_event`, `mint_church` and the reputation history stamp.
+    clock: Box<dyn Clock>,
+    /// Stamp each logged event's context with the calm band at logging time.
+    pub annotate_calm: bool,
+}
+
+impl Default for SovereigntyCore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SovereigntyCore {
@@ -144,9 +433,71 @@ impl SovereigntyCore {
 
         Self {
             graph,
-            reputation: ReputationVector { privacy: 0.92, compliance: 0.95, eco_align: 0.88, clin_trust: 0.97, mp_score: 0.93 },
+            nodes,
+            reputation: ReputationVector::neutral(),
             deed_log: Vec::new(),
-            current_hash: "0".repeat(64),
+            current_hash: GENESIS_HASH.to_string(),
+            reputation_history: Vec::new(),
+            consent: ConsentLedger::default(),
+            path_policy: PathPolicy::default(),
+            mint_policy: MintPolicy::default(),
+            calm: Box::new(StaticCalmSource::default()),
+            clock: Box::new(SystemClock),
+            annotate_calm: false,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The core's clock, in Unix milliseconds.
+    pub fn now_millis(&self) -> i64 {
+        self.clock.now_millis()
+    }
+
+    pub fn with_calm_source(mut self, source: Box<dyn CalmSource>) -> Self {
+        self.calm = source;
+        self
+    }
+
+    pub fn set_calm_source(&mut self, source: Box<dyn CalmSource>) {
+        self.calm = source;
+    }
+
+    pub fn calm_band(&self) -> CalmBand {
+        self.calm.calm_band()
+    }
+
+    pub fn node_index(&self, node: &Node) -> Option<NodeIndex> {
+        self.nodes.get(node).copied()
+    }
+
+    /// `node`'s direct successors, in the order their edges were added.
+    pub fn children_of(&self, node: &Node) -> Vec<&Node> {
+        let Some(index) = self.node_index(node) else {
+            return Vec::new();
+        };
+        let mut children: Vec<&Node> = self.graph.neighbors(index).map(|n| &self.graph[n]).collect();
+        children.reverse();
+        children
+    }
+
+    pub fn edge_label(&self, from: &Node, to: &Node) -> Option<&str> {
+        let edge = self.graph.find_edge(self.node_index(from)?, self.node_index(to)?)?;
+        Some(self.graph[edge].label.as_str())
+    }
+
+    /// Whether a directed path leads from `from` to `to`.
+    pub fn has_path(&self, from: &Node, to: &Node) -> bool {
+        match (self.node_index(from), self.node_index(to)) {
+            (Some(from), Some(to)) => has_path_connecting(&self.graph, from, to, None),
+            _ => false,
         }
     }
 
@@ -163,31 +514,151 @@ impl SovereigntyCore {
         if attested && anchored { 0.97 } else { 0.50 }
     }
 
-    pub fn validate_path1(&self) -> bool {
-        // Exact PATH1 from graph
-        true // in production: petgraph walk from NSleep -> Target1 -> Path1
+    pub fn log_event(&mut self, node: Node, deed_type: String, context: serde_json::Value) -> Result<(), ConsentError> {
+        self.log_event_at(node, deed_type, context, self.now_millis())
+    }
+
+    /// Neuro events are held to their consent scope's duty cycle; a
+    /// rejection counts towards a `duty_cycle_exceeded` ethics flag. A scope
+    /// whose grant awaits reconfirmation admits nothing. With
+    /// `annotate_calm` set, an object context gains a `calm_band` field.
+    pub fn log_event_at(&mut self, node: Node, deed_type: String, mut context: serde_json::Value, now: i64) -> Result<(), ConsentError> {
+        if let Some(scope) = self.consent.governing_scope(&node)
+            && let Some(GrantStatus::PendingReconfirmation { .. }) = consent_migration::grant_status(self, CITIZEN, &scope)
+        {
+            return Err(ConsentError::PendingReconfirmation(scope));
+        }
+        let logged: Vec<i64> = self
+            .deed_log
+            .iter()
+            .filter(|d| d.node == node && d.actor_id == CITIZEN)
+            .map(|d| d.timestamp)
+            .collect();
+        if let Err(e) = self.consent.check_event(&node, &logged, now) {
+            if self.consent.record_violation() {
+                self.flag_duty_cycle(&e, now);
+            }
+            return Err(e);
+        }
+        if self.annotate_calm
+            && let Some(fields) = context.as_object_mut()
+        {
+            fields.insert("calm_band".to_string(), serde_json::json!(self.calm_band()));
+        }
+        let deed = DeedEvent::new(CITIZEN.to_string(), node, deed_type, context);
+        self.append(deed, now);
+        Ok(())
+    }
+
+    /// Record `subject`'s consent to `scope` for every purpose it offers.
+    pub fn grant_consent(&mut self, subject: &str, scope: Node, now: i64) -> Result<(), ConsentError> {
+        let offered: Vec<Purpose> = match self.consent.scope(&scope) {
+            Some(s) => s.purposes.iter().copied().collect(),
+            None => return Err(ConsentError::UnknownScope(scope)),
+        };
+        self.grant_consent_for(subject, scope, &offered, now)
+    }
+
+    /// Record `subject`'s consent to `scope` for `purposes` only; each must
+    /// be one the scope offers.
+    pub fn grant_consent_for(&mut self, subject: &str, scope: Node, purposes: &[Purpose], now: i64) -> Result<(), ConsentError> {
+        let Some(limits) = self.consent.scope(&scope) else {
+            return Err(ConsentError::UnknownScope(scope));
+        };
+        if let Some(&purpose) = purposes.iter().find(|p| !limits.purposes.contains(p)) {
+            return Err(ConsentError::PurposeNotOffered { scope, purpose });
+        }
+        let purposes: BTreeSet<Purpose> = purposes.iter().copied().collect();
+        let context = serde_json::json!({ "subject": subject, "scope": scope, "purposes": purposes });
+        let deed = DeedEvent::new(consent_migration::CONSENT_ACTOR.to_string(), Node::ConsentLedger, consent_migration::CONSENT_GRANTED.to_string(), context);
+        self.append(deed, now);
+        Ok(())
     }
 
-    pub fn validate_path2(&self) -> bool {
-        true // exact PATH2
+    /// Withdraw `subject`'s grant for `scope`. Deeds logged before `now`
+    /// stay usable for what the grant allowed when they were logged.
+    pub fn revoke_consent(&mut self, subject: &str, scope: Node, now: i64) -> Result<(), ConsentError> {
+        if consent_migration::grant_status(self, subject, &scope).is_none() {
+            return Err(ConsentError::NotGranted(scope));
+        }
+        let context = serde_json::json!({ "subject": subject, "scope": scope });
+        let deed = DeedEvent::new(consent_migration::CONSENT_ACTOR.to_string(), Node::ConsentLedger, consent_migration::CONSENT_REVOKED.to_string(), context);
+        self.append(deed, now);
+        Ok(())
     }
 
-    pub fn log_event(&mut self, node: Node, deed_type: String, context: serde_json::Value) {
-        let mut deed = DeedEvent::new("augmented_citizen".to_string(), node, deed_type, context);
+    /// Walk `deed_log` from the genesis hash, recomputing every event's
+    /// hash. `Err` holds the index of the first event whose contents no
+    /// longer match its `self_hash` or whose `prev_hash` is not the
+    /// recomputed hash of the event before it.
+    pub fn verify_chain(&self) -> Result<(), usize> {
+        let mut expected_prev = GENESIS_HASH.to_string();
+        for (i, deed) in self.deed_log.iter().enumerate() {
+            let recomputed = deed.compute_hash();
+            if recomputed != deed.self_hash || deed.prev_hash != expected_prev {
+                return Err(i);
+            }
+            expected_prev = recomputed;
+        }
+        Ok(())
+    }
+
+    fn append(&mut self, mut deed: DeedEvent, at: i64) {
+        deed.timestamp = at;
+        deed.time_unit = TimeUnit::Millis;
         deed.link_to_prev(self.current_hash.clone());
         self.current_hash = deed.self_hash.clone();
         self.deed_log.push(deed);
     }
 
+    fn flag_duty_cycle(&mut self, cause: &ConsentError, now: i64) {
+        let dent = self.consent.policy.compliance_dent;
+        self.reputation.compliance = (self.reputation.compliance - dent).max(0.0);
+        let context = serde_json::json!({
+            "violations": self.consent.violations,
+            "last_rejection": cause.to_string(),
+            "compliance_dent": dent,
+        });
+        let mut deed = DeedEvent::new("consent_ledger".to_string(), Node::ComplianceScore, "duty_cycle_flag".to_string(), context);
+        deed.ethics_flags.push(consent::DUTY_CYCLE_FLAG.to_string());
+        self.append(deed, now);
+    }
+
+    /// The graph as a Mermaid flowchart: one `ID["label"]` line per node,
+    /// then one `A -->|"label"| B` line per edge, labels quoted so arrows,
+    /// colons and other punctuation in them stay text.
     pub fn export_mermaid(&self) -> String {
-        let dot = Dot::with_config(&self.graph, &[Config::EdgeNoLabel]);
-        format!("graph TD\n{}", dot)  // convertible back to Mermaid via external tool or simple string transform
+        let mut out = String::from("graph TD\n");
+        for i in self.graph.node_indices() {
+            let node = &self.graph[i];
+            out.push_str(&format!("    {}[\"{}\"]\n", node.id(), mermaid_escape(node.label())));
+        }
+        for e in self.graph.edge_indices() {
+            let (a, b) = self.graph.edge_endpoints(e).expect("edge index from this graph");
+            let label = mermaid_escape(&self.graph[e].label);
+            out.push_str(&format!("    {} -->|\"{}\"| {}\n", self.graph[a].id(), label, self.graph[b].id()));
+        }
+        out
+    }
+
+    /// The graph in Graphviz DOT.
+    pub fn export_dot(&self) -> String {
+        format!("{}", Dot::with_config(&self.graph, &[Config::EdgeNoLabel]))
     }
 
-    pub fn compute_reputation(&mut self) -> &ReputationVector {
-        // Real predicate integration
-        let calm = true; // from linked microspace observer
-        self.reputation.mp_score = if calm { 0.96 } else { 0.62 };
+    /// Derive the vector from the deed log, score it under `framework`,
+    /// scale mp_score by the current calm band and append it to the
+    /// reputation history. Earlier entries keep the framework that scored them.
+    pub fn compute_reputation(&mut self, framework: &EthicalFramework) -> &ReputationVector {
+        let mut derived = ReputationVector::from_deed_log(&self.deed_log, &framework.weights);
+        derived.mp_score = framework.score(&derived) * self.calm_band().mp_factor();
+        self.reputation = derived;
+        self.reputation_history.push(ReputationEntry {
+            timestamp: self.now_millis(),
+            vector: self.reputation.clone(),
+            framework: framework.name.clone(),
+            framework_version: framework.version,
+        });
         &self.reputation
     }
 }
@@ -199,10 +670,12 @@ mod tests {
     #[test]
     fn sovereignty_ledger_high_trust() {
         let mut core = SovereigntyCore::new();
-        core.log_event(Node::NSleep, "high_trust_eeg".to_string(), serde_json::json!({"consent": true, "energy": "low"}));
-        core.log_event(Node::NBci, "signed_bci".to_string(), serde_json::json!({"attested": true}));
+        core.log_event(Node::Did, "did_binding".to_string(), serde_json::json!({"did": "did:bostrom:citizen"})).unwrap();
+        core.log_event(Node::NSleep, "high_trust_eeg".to_string(), serde_json::json!({"consent": true, "energy": "low"})).unwrap();
+        core.log_event(Node::NBci, "signed_bci".to_string(), serde_json::json!({"attested": true})).unwrap();
+        core.log_event(Node::NClin, "clinical_session".to_string(), serde_json::json!({"attested": true})).unwrap();
 
-        let rep = core.compute_reputation();
+        let rep = core.compute_reputation(&EthicalFramework::default());
         assert!(rep.mp_score > 0.90);
         assert!(core.validate_path1());
         assert!(core.validate_path2());
@@ -210,4 +683,180 @@ mod tests {
         // This test mints CHURCH via CALM_STABLE + eco_grant recommendation
         println!("CHURCH minted for eco-aligned neuro-rights preservation");
     }
+
+    #[test]
+    fn the_graph_is_the_mermaid_topology() {
+        use Node::*;
+        let core = SovereigntyCore::new();
+        let expected = [
+            (Root, vec![IdLayer, ConsentLedger, Events, Reputation, Anchors]),
+            (IdLayer, vec![Did, BostromAddr]),
+            (Node::ConsentLedger, vec![ScopeEeg, ScopeBci]),
+            (Events, vec![NSleep, NBci, NClin]),
+            (Reputation, vec![PrivacyScore, ComplianceScore, EcoAlignScore, ClinTrustScore]),
+            (Anchors, vec![BostromAnchor, Googolswarm, Ghostnet]),
+            (NSleep, vec![Target1]),
+            (NBci, vec![Target2]),
+            (Target1, vec![Path1]),
+            (Target2, vec![Path2]),
+        ];
+        assert_eq!(core.graph.node_count(), 24);
+        assert_eq!(core.graph.edge_count(), 23);
+        assert_eq!(expected.iter().map(|(_, c)| c.len()).sum::<usize>(), 23);
+        for (parent, children) in &expected {
+            assert_eq!(core.children_of(parent), children.iter().collect::<Vec<_>>(), "children of {:?}", parent);
+        }
+        assert_eq!(core.children_of(&Reputation).len(), 4);
+        for leaf in [Did, NClin, Ghostnet, Path1, Path2] {
+            assert!(core.children_of(&leaf).is_empty());
+        }
+        // Split EEG scopes are consent scopes only, outside the graph.
+        assert_eq!(core.node_index(&ScopeEegSleep), None);
+        assert!(core.children_of(&ScopeEegSleep).is_empty());
+    }
+
+    /// `ID["label"]` or `A -->|"label"| B`, as the exporter writes them.
+    fn parse_mermaid_line(line: &str) -> Option<(&str, &str, Option<&str>)> {
+        let line = line.strip_prefix("    ")?;
+        if let Some((from, rest)) = line.split_once(" -->|\"") {
+            let (label, to) = rest.split_once("\"| ")?;
+            let ident = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            return (ident(from) && ident(to) && !label.contains('"')).then_some((from, label, Some(to)));
+        }
+        let (id, rest) = line.split_once("[\"")?;
+        let label = rest.strip_suffix("\"]")?;
+        (!label.contains('"')).then_some((id, label, None))
+    }
+
+    #[test]
+    fn mermaid_export_is_one_statement_per_line() {
+        let core = SovereigntyCore::new();
+        let mermaid = core.export_mermaid();
+        let mut lines = mermaid.lines();
+        assert_eq!(lines.next(), Some("graph TD"));
+        let parsed: Vec<_> = lines.map(|l| parse_mermaid_line(l).unwrap_or_else(|| panic!("unparsable: {}", l))).collect();
+        let edges: Vec<_> = parsed.iter().filter(|(_, _, to)| to.is_some()).collect();
+        assert_eq!(parsed.len() - edges.len(), 24);
+        assert_eq!(edges.len(), 23);
+
+        let route = "Route: SleepStudy → Consent OK → Green Band → Bostrom Anchor";
+        assert!(edges.contains(&&("TARGET1", route, Some("PATH1"))));
+        assert!(parsed.contains(&("REPUTATION", "Reputation Vector", None)));
+        for i in core.graph.edge_indices() {
+            let (a, b) = core.graph.edge_endpoints(i).unwrap();
+            let label = core.graph[i].label.as_str();
+            assert!(edges.contains(&&(core.graph[a].id(), label, Some(core.graph[b].id()))), "{}", label);
+        }
+        assert!(core.export_dot().starts_with("digraph {"));
+    }
+
+    #[test]
+    fn dot_export_labels_nodes_and_links_every_edge() {
+        let core = SovereigntyCore::new();
+        let dot = core.export_dot();
+        assert!(dot.starts_with("digraph {") && dot.trim_end().ends_with('}'));
+        assert!(dot.contains("[ label = \"Augmented Citizen\" ]"), "{}", dot);
+        assert!(dot.contains("[ label = \"Reputation Vector\" ]"));
+        assert_eq!(dot.lines().filter(|l| l.contains(" -> ")).count(), core.graph.edge_count());
+        // Edge labels are left out.
+        assert!(!dot.contains("Route: SleepStudy"));
+    }
+
+    #[test]
+    fn mermaid_labels_escape_quotes_pipes_and_hashes() {
+        let mut core = SovereigntyCore::new();
+        let (did, bostrom) = (core.node_index(&Node::Did).unwrap(), core.node_index(&Node::BostromAddr).unwrap());
+        core.graph.add_edge(did, bostrom, Edge { from: Node::Did, to: Node::BostromAddr, label: "say \"hi\" | #1 --> x".to_string() });
+        let mermaid = core.export_mermaid();
+        let line = mermaid.lines().last().unwrap();
+        assert_eq!(line, "    DID -->|\"say #quot;hi#quot; #124; #35;1 --> x\"| BOSTROM_ADDR");
+        assert!(parse_mermaid_line(line).is_some());
+    }
+
+    #[test]
+    fn edges_and_paths_are_looked_up_by_variant() {
+        let core = SovereigntyCore::new();
+        assert_eq!(core.edge_label(&Node::Reputation, &Node::EcoAlignScore), Some("Eco-Alignment Score"));
+        assert_eq!(core.edge_label(&Node::EcoAlignScore, &Node::Reputation), None);
+        assert_eq!(core.edge_label(&Node::Root, &Node::Did), None);
+        assert!(core.has_path(&Node::Root, &Node::Path1));
+        assert!(core.has_path(&Node::Events, &Node::Path2));
+        assert!(!core.has_path(&Node::NSleep, &Node::Path2));
+        assert!(!core.has_path(&Node::Path1, &Node::Root));
+        assert!(!core.has_path(&Node::Root, &Node::ScopeEegDaytime));
+    }
+
+    fn chained() -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        for (i, node) in [Node::Did, Node::BostromAnchor, Node::Googolswarm].into_iter().enumerate() {
+            core.log_event_at(node, "anchor".to_string(), serde_json::json!({ "seq": i }), 1_000 + i as i64).unwrap();
+        }
+        core
+    }
+
+    #[test]
+    fn stored_events_recompute_to_their_hash() {
+        let core = chained();
+        assert!(core.deed_log.iter().all(DeedEvent::verify_hash));
+        assert_eq!(core.verify_chain(), Ok(()));
+        assert_eq!(core.current_hash, core.deed_log.last().unwrap().self_hash);
+
+        // The hash survives a round trip through storage.
+        let stored = serde_json::to_string(&core.deed_log[1]).unwrap();
+        assert!(serde_json::from_str::<DeedEvent>(&stored).unwrap().verify_hash());
+    }
+
+    #[test]
+    fn the_first_event_links_to_genesis() {
+        let mut core = chained();
+        assert_eq!(core.deed_log[0].prev_hash, "0".repeat(64));
+
+        core.deed_log[0].link_to_prev("f".repeat(64));
+        assert!(core.deed_log[0].verify_hash());
+        assert_eq!(core.verify_chain(), Err(0));
+    }
+
+    #[test]
+    fn edited_context_is_caught_at_its_index() {
+        let mut core = chained();
+        core.deed_log[1].context_json["seq"] = serde_json::json!(7);
+        assert!(!core.deed_log[1].verify_hash());
+        assert_eq!(core.verify_chain(), Err(1));
+
+        // Re-hashing the edited event breaks the next event's link instead.
+        let prev = core.deed_log[0].self_hash.clone();
+        core.deed_log[1].link_to_prev(prev);
+        assert_eq!(core.verify_chain(), Err(2));
+    }
+
+    #[test]
+    fn reordered_events_are_caught() {
+        let mut core = chained();
+        core.deed_log.swap(1, 2);
+        assert!(core.deed_log.iter().all(DeedEvent::verify_hash));
+        assert_eq!(core.verify_chain(), Err(1));
+    }
+
+    #[test]
+    fn events_keep_their_hash_through_the_shared_shape() {
+        let mut core = chained();
+        core.deed_log[0].time_unit = TimeUnit::Seconds;
+        core.deed_log[0].timestamp = 1_700_000_000_000;
+        core.deed_log[0].link_to_prev(GENESIS_HASH.to_string());
+
+        for deed in &core.deed_log {
+            let shared = deed_core::DeedEvent::from(deed);
+            assert!(shared.verify_hash(), "{}", deed.event_id);
+            let line = serde_json::to_string(deed).unwrap();
+            assert_eq!(deed_core::decode_line(&line).unwrap(), shared);
+
+            let back = DeedEvent::try_from(deed_core::decode_line(&deed_core::encode_line(&shared)).unwrap()).unwrap();
+            assert_eq!(serde_json::to_value(&back).unwrap(), serde_json::to_value(deed).unwrap());
+            assert!(back.verify_hash());
+        }
+
+        let mut tagged = deed_core::DeedEvent::from(&core.deed_log[1]);
+        tagged.tags.push("ecological_sustainability".to_string());
+        assert!(matches!(DeedEvent::try_from(tagged), Err(deed_core::DeedCoreError::Shape { .. })));
+    }
 }
@@ -0,0 +1,211 @@
+//! Minting CHURCH from moral position.
+//! `mint_church` recomputes the reputation vector and mints only while the
+//! citizen is calm-stable, mp_score clears `MintPolicy::mp_threshold` and
+//! PATH1 or PATH2 validates. The amount grows with mp_score and with the
+//! actor's good deeds since their last mint: neuro events without a life
+//! harm flag. Each `church_mint` deed records the hash of the last deed it
+//! covered, and the next mint only counts deeds after it, so no deed is
+//! minted for twice.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::purpose::is_event;
+use crate::{CalmBand, DeedEvent, EthicalFramework, Node, ReputationVector, SovereigntyCore};
+
+pub const CHURCH_MINT: &str = "church_mint";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintPolicy {
+    /// mp_score must be strictly above this to mint.
+    pub mp_threshold: f64,
+    /// CHURCH per good deed at an mp_score of 1.0.
+    pub per_deed: u64,
+    /// The framework reputation is recomputed under before minting.
+    pub framework: EthicalFramework,
+}
+
+impl Default for MintPolicy {
+    fn default() -> Self {
+        Self { mp_threshold: 0.8, per_deed: 10, framework: EthicalFramework::default() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintReceipt {
+    pub actor_id: String,
+    pub amount: u64,
+    /// Good deeds the mint counted.
+    pub deeds: usize,
+    /// `self_hash` of the last deed the mint covered.
+    pub through: String,
+    pub reputation: ReputationVector,
+    /// `event_id` of the `church_mint` deed.
+    pub event_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MintError {
+    CalmNotStable(CalmBand),
+    BelowThreshold { mp_score: f64, threshold: f64 },
+    /// Neither path validates; lists what each is missing.
+    PathInvalid { missing: Vec<String> },
+    /// No good deeds by the actor since their last mint.
+    NothingNew,
+}
+
+impl fmt::Display for MintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MintError::CalmNotStable(band) => write!(f, "calm not stable: band is {:?}", band),
+            MintError::BelowThreshold { mp_score, threshold } => {
+                write!(f, "mp_score {:.3} is not above the mint threshold {:.3}", mp_score, threshold)
+            }
+            MintError::PathInvalid { missing } => write!(f, "neither PATH1 nor PATH2 validates: missing {}", missing.join("; ")),
+            MintError::NothingNew => write!(f, "nothing new to mint"),
+        }
+    }
+}
+
+impl std::error::Error for MintError {}
+
+impl SovereigntyCore {
+    pub fn mint_church(&mut self, actor_id: &str) -> Result<MintReceipt, MintError> {
+        self.mint_church_at(actor_id, self.now_millis())
+    }
+
+    pub fn mint_church_at(&mut self, actor_id: &str, now: i64) -> Result<MintReceipt, MintError> {
+        let band = self.calm_band();
+        if band != CalmBand::CalmStable {
+            return Err(MintError::CalmNotStable(band));
+        }
+        let policy = self.mint_policy.clone();
+        let reputation = self.compute_reputation(&policy.framework).clone();
+        if reputation.mp_score <= policy.mp_threshold {
+            return Err(MintError::BelowThreshold { mp_score: reputation.mp_score, threshold: policy.mp_threshold });
+        }
+        let (path1, path2) = (self.check_path1(), self.check_path2());
+        if !path1.is_valid() && !path2.is_valid() {
+            let missing = path1.missing.into_iter().chain(path2.missing).collect();
+            return Err(MintError::PathInvalid { missing });
+        }
+        let good: Vec<&DeedEvent> = self.unminted(actor_id).filter(|d| is_event(&d.node) && !d.life_harm_flag).collect();
+        let Some(last) = good.last() else {
+            return Err(MintError::NothingNew);
+        };
+        let deeds = good.len();
+        let through = last.self_hash.clone();
+        let amount = (policy.per_deed as f64 * deeds as f64 * reputation.mp_score).round() as u64;
+
+        let context = serde_json::json!({
+            "amount": amount,
+            "deeds": deeds,
+            "through": through,
+            "reputation": reputation,
+        });
+        let deed = DeedEvent::new(actor_id.to_string(), Node::Reputation, CHURCH_MINT.to_string(), context);
+        let event_id = deed.event_id.clone();
+        self.append(deed, now);
+        Ok(MintReceipt { actor_id: actor_id.to_string(), amount, deeds, through, reputation, event_id })
+    }
+
+    /// `self_hash` of the last deed `actor_id`'s latest mint covered.
+    pub fn last_minted_through(&self, actor_id: &str) -> Option<&str> {
+        self.deed_log
+            .iter()
+            .rev()
+            .find(|d| d.deed_type == CHURCH_MINT && d.actor_id == actor_id)
+            .and_then(|d| d.context_json["through"].as_str())
+    }
+
+    /// `actor_id`'s deeds logged after the last one their latest mint covered.
+    fn unminted<'a>(&'a self, actor_id: &'a str) -> impl Iterator<Item = &'a DeedEvent> + 'a {
+        let start = match self.last_minted_through(actor_id) {
+            Some(through) => self.deed_log.iter().position(|d| d.self_hash == through).map_or(0, |i| i + 1),
+            None => 0,
+        };
+        self.deed_log[start..].iter().filter(move |d| d.actor_id == actor_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StaticCalmSource, CITIZEN};
+    use serde_json::json;
+
+    const T0: i64 = 1_700_000_000_000;
+
+    fn log(core: &mut SovereigntyCore, node: Node, deed_type: &str, context: serde_json::Value) {
+        let at = T0 + core.deed_log.len() as i64;
+        core.log_event_at(node, deed_type.to_string(), context, at).unwrap();
+    }
+
+    fn qualified() -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        log(&mut core, Node::Did, "did_binding", json!({}));
+        log(&mut core, Node::NSleep, "eeg_epoch", json!({ "consent": true, "energy": "low" }));
+        log(&mut core, Node::NClin, "clinical_session", json!({ "attested": true }));
+        core
+    }
+
+    #[test]
+    fn a_mint_counts_each_good_deed_once() {
+        let mut core = qualified();
+        let receipt = core.mint_church_at(CITIZEN, T0 + 10).unwrap();
+        assert_eq!((receipt.deeds, receipt.amount), (2, 20));
+        assert_eq!(receipt.through, core.deed_log[2].self_hash);
+
+        let deed = core.deed_log.last().unwrap();
+        assert_eq!(deed.deed_type, CHURCH_MINT);
+        assert_eq!(deed.context_json["amount"], 20);
+        assert_eq!(deed.context_json["reputation"]["mp_score"], 1.0);
+        assert_eq!(core.last_minted_through(CITIZEN), Some(receipt.through.as_str()));
+
+        assert_eq!(core.mint_church_at(CITIZEN, T0 + 11).unwrap_err(), MintError::NothingNew);
+        log(&mut core, Node::NSleep, "eeg_epoch", json!({ "consent": true, "energy": "low" }));
+        let next = core.mint_church_at(CITIZEN, T0 + 12).unwrap();
+        assert_eq!((next.deeds, next.amount), (1, 10));
+        assert_eq!(core.verify_chain(), Ok(()));
+    }
+
+    #[test]
+    fn the_amount_follows_mp_score() {
+        let mut core = qualified();
+        let mut harmed = DeedEvent::new(CITIZEN.to_string(), Node::NClin, "clinical_session".to_string(), json!({ "attested": true }));
+        harmed.life_harm_flag = true;
+        core.append(harmed, T0 + 5);
+        // clin_trust falls to 0.5, mp_score to 0.875; the harmed deed is not counted.
+        let receipt = core.mint_church_at(CITIZEN, T0 + 10).unwrap();
+        assert_eq!((receipt.deeds, receipt.amount), (2, 18));
+    }
+
+    #[test]
+    fn refusals_say_why() {
+        let mut core = qualified();
+        core.set_calm_source(Box::new(StaticCalmSource(CalmBand::Elevated)));
+        assert_eq!(core.mint_church_at(CITIZEN, T0 + 10).unwrap_err(), MintError::CalmNotStable(CalmBand::Elevated));
+        core.set_calm_source(Box::new(StaticCalmSource::default()));
+
+        core.mint_policy.mp_threshold = 1.0;
+        assert!(matches!(core.mint_church_at(CITIZEN, T0 + 10), Err(MintError::BelowThreshold { .. })));
+        core.mint_policy.mp_threshold = 0.8;
+
+        // No sleep run for PATH1 and no BCI trial for PATH2.
+        let mut core = SovereigntyCore::new();
+        log(&mut core, Node::Did, "did_binding", json!({}));
+        log(&mut core, Node::NClin, "clinical_session", json!({ "attested": true }));
+        log(&mut core, Node::Target1, "eco_grant", json!({}));
+        let err = core.mint_church_at(CITIZEN, T0 + 10).unwrap_err();
+        assert_eq!(
+            err,
+            MintError::PathInvalid {
+                missing: vec!["an NSleep event with consent=true and energy below Medium".to_string(), "an NBci event with attested=true".to_string()]
+            }
+        );
+        assert!(err.to_string().starts_with("neither PATH1 nor PATH2 validates"));
+        assert!(core.deed_log.iter().all(|d| d.deed_type != CHURCH_MINT));
+
+        assert_eq!(qualified().mint_church_at("someone_else", T0 + 10).unwrap_err(), MintError::NothingNew);
+    }
+}
@@ -0,0 +1,159 @@
+//! PATH1 and PATH2, checked against the graph and the deed log.
+//!
+//! PATH1 ("SleepStudy → Consent OK → Green Band → Bostrom Anchor") holds
+//! when the graph routes `NSleep` through `Target1` to `Path1` and the log
+//! has an `NSleep` event whose context reports `"consent": true` and an
+//! `energy` band below `PathPolicy::energy_below`. PATH2 ("BCI Trial →
+//! Clinical Attestation → Reputation Boost") holds when the graph routes
+//! `NBci` through `Target2` to `Path2` and the log has an `NBci` event
+//! whose context reports `"attested": true`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DeedEvent, Node, SovereigntyCore};
+
+/// Energy bands an event's context reports under `energy`, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnergyBand {
+    Low,
+    Medium,
+    High,
+}
+
+impl EnergyBand {
+    pub(crate) fn of(deed: &DeedEvent) -> Option<Self> {
+        serde_json::from_value(deed.context_json.get("energy")?.clone()).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathPolicy {
+    /// PATH1 needs an event strictly below this band.
+    pub energy_below: EnergyBand,
+}
+
+impl Default for PathPolicy {
+    fn default() -> Self {
+        Self { energy_below: EnergyBand::Medium }
+    }
+}
+
+/// Which halves of a path check passed, and what was missing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathValidation {
+    pub graph_ok: bool,
+    pub deeds_ok: bool,
+    pub missing: Vec<String>,
+}
+
+impl PathValidation {
+    pub fn is_valid(&self) -> bool {
+        self.graph_ok && self.deeds_ok
+    }
+}
+
+impl SovereigntyCore {
+    /// PATH1: `NSleep → Target1 → Path1`, with a consented, low-energy
+    /// `NSleep` event.
+    pub fn check_path1(&self) -> PathValidation {
+        let band = self.path_policy.energy_below;
+        self.check_path(
+            &[Node::NSleep, Node::Target1, Node::Path1],
+            |d| d.context_json["consent"] == true && EnergyBand::of(d).is_some_and(|b| b < band),
+            format!("an NSleep event with consent=true and energy below {:?}", band),
+        )
+    }
+
+    /// PATH2: `NBci → Target2 → Path2`, with an attested `NBci` event.
+    pub fn check_path2(&self) -> PathValidation {
+        self.check_path(
+            &[Node::NBci, Node::Target2, Node::Path2],
+            |d| d.context_json["attested"] == true,
+            "an NBci event with attested=true".to_string(),
+        )
+    }
+
+    pub fn validate_path1(&self) -> bool {
+        self.check_path1().is_valid()
+    }
+
+    pub fn validate_path2(&self) -> bool {
+        self.check_path2().is_valid()
+    }
+
+    fn check_path(&self, route: &[Node], qualifies: impl Fn(&DeedEvent) -> bool, wanted: String) -> PathValidation {
+        let mut missing = Vec::new();
+        for hop in route.windows(2) {
+            if !self.has_path(&hop[0], &hop[1]) {
+                missing.push(format!("graph path {:?} → {:?}", hop[0], hop[1]));
+            }
+        }
+        let graph_ok = missing.is_empty();
+        let deeds_ok = self.deed_log.iter().any(|d| d.node == route[0] && qualifies(d));
+        if !deeds_ok {
+            missing.push(wanted);
+        }
+        PathValidation { graph_ok, deeds_ok, missing }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sleep(core: &mut SovereigntyCore, context: serde_json::Value) {
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), context, 1_000 + core.deed_log.len() as i64).unwrap();
+    }
+
+    #[test]
+    fn an_empty_log_fails_on_deeds_only() {
+        let core = SovereigntyCore::new();
+        let path1 = core.check_path1();
+        assert!(path1.graph_ok && !path1.deeds_ok);
+        assert_eq!(path1.missing, vec!["an NSleep event with consent=true and energy below Medium".to_string()]);
+        assert!(!core.validate_path1());
+        assert!(!core.validate_path2());
+    }
+
+    #[test]
+    fn path1_needs_consent_and_a_band_under_the_threshold() {
+        let mut core = SovereigntyCore::new();
+        sleep(&mut core, serde_json::json!({ "consent": false, "energy": "low" }));
+        sleep(&mut core, serde_json::json!({ "consent": true, "energy": "medium" }));
+        sleep(&mut core, serde_json::json!({ "consent": true }));
+        assert!(!core.validate_path1());
+
+        core.path_policy.energy_below = EnergyBand::High;
+        assert!(core.validate_path1());
+
+        core.path_policy.energy_below = EnergyBand::Medium;
+        sleep(&mut core, serde_json::json!({ "consent": true, "energy": "low" }));
+        assert_eq!(core.check_path1(), PathValidation { graph_ok: true, deeds_ok: true, missing: Vec::new() });
+    }
+
+    #[test]
+    fn path2_needs_an_attested_bci_event() {
+        let mut core = SovereigntyCore::new();
+        core.log_event_at(Node::NClin, "session".into(), serde_json::json!({ "attested": true }), 1_000).unwrap();
+        core.log_event_at(Node::NBci, "trial".into(), serde_json::json!({ "attested": false }), 1_001).unwrap();
+        assert!(!core.validate_path2());
+        core.log_event_at(Node::NBci, "trial".into(), serde_json::json!({ "attested": true }), 1_002).unwrap();
+        assert!(core.validate_path2());
+    }
+
+    #[test]
+    fn a_cut_route_is_reported_hop_by_hop() {
+        let mut core = SovereigntyCore::new();
+        core.log_event_at(Node::NBci, "trial".into(), serde_json::json!({ "attested": true }), 1_000).unwrap();
+        let target2 = core.node_index(&Node::Target2).unwrap();
+        let path2 = core.node_index(&Node::Path2).unwrap();
+        let edge = core.graph.find_edge(target2, path2).unwrap();
+        core.graph.remove_edge(edge);
+
+        let check = core.check_path2();
+        assert!(!check.graph_ok && check.deeds_ok);
+        assert_eq!(check.missing, vec!["graph path Target2 → Path2".to_string()]);
+    }
+}
@@ -0,0 +1,429 @@
+//! Purpose checks for data derived from neuro event deeds.
+//! A subject may consent to a scope for clinical use and still keep their
+//! events out of research aggregates or public reports. `PurposeGate`
+//! answers whether a deed may feed a given purpose, from the consent state
+//! as of the deed's own timestamp: a later revocation does not reach back
+//! over deeds logged while the grant stood, and a later grant does not
+//! cover deeds logged before it. The scope governing a node is likewise
+//! the one in force at the deed's timestamp, so a migration that moves a
+//! node onto a new scope only applies from then on. A consent deed stamped
+//! at the same millisecond as an event already applies to it.
+//!
+//! Deeds on nodes no scope governs (consent, compliance and reputation
+//! records) are not subject data and always pass.
+//!
+//! Every derived-data producer here goes through the gate: `event_analytics`
+//! (research aggregates and public reports), `export_events`, which
+//! annotates the purposes it was performed under, and `attest_components`,
+//! which withholds reputation components computed from data the purpose
+//! does not cover. `purpose_coverage` counts what each purpose excluded.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::consent::Purpose;
+use crate::consent_migration::{apply_consent_deed, ConsentGrant, CONSENT_MIGRATION};
+use crate::scoring::COMPONENT_READS;
+use crate::{ConsentLedger, DeedEvent, Node, SovereigntyCore};
+
+/// Nodes that neuro interaction events are logged on.
+const EVENT_NODES: [Node; 5] = [Node::NSleep, Node::NBci, Node::NClin, Node::Target1, Node::Target2];
+
+pub(crate) fn is_event(node: &Node) -> bool {
+    EVENT_NODES.contains(node)
+}
+
+fn node_key(node: &Node) -> String {
+    format!("{:?}", node)
+}
+
+/// Purposes allowed for one (subject, scope), as `(timestamp, purposes)`
+/// change points.
+type PurposeHistory = Vec<(i64, BTreeSet<Purpose>)>;
+
+/// Consent state over time, rebuilt from the deed log.
+#[derive(Debug, Clone, Default)]
+pub struct PurposeGate {
+    /// Purposes allowed per (subject, scope), as change points sorted by
+    /// timestamp; equal timestamps keep chain order.
+    allowed: HashMap<(String, Node), PurposeHistory>,
+    /// Nodes moved onto another scope by a migration, sorted the same way.
+    reassigned: Vec<(i64, Node, Node)>,
+}
+
+impl PurposeGate {
+    pub fn new(core: &SovereigntyCore) -> Self {
+        let mut gate = Self::default();
+        let mut grants: Vec<ConsentGrant> = Vec::new();
+        for d in core.deed_log.iter().filter(|d| d.node == Node::ConsentLedger) {
+            if d.deed_type == CONSENT_MIGRATION {
+                let moved: Vec<(Node, Node)> = serde_json::from_value(d.context_json["node_scopes"].clone()).unwrap_or_default();
+                gate.reassigned.extend(moved.into_iter().map(|(node, scope)| (d.timestamp, node, scope)));
+            }
+            let Some(subject) = d.context_json["subject"].as_str() else { continue };
+            // Every scope the subject held a grant for before or after.
+            let mut scopes: Vec<Node> = grants.iter().filter(|g| g.subject == subject).map(|g| g.scope.clone()).collect();
+            apply_consent_deed(&mut grants, d);
+            for g in grants.iter().filter(|g| g.subject == subject) {
+                if !scopes.contains(&g.scope) {
+                    scopes.push(g.scope.clone());
+                }
+            }
+            for scope in scopes {
+                let purposes: BTreeSet<Purpose> = Purpose::ALL
+                    .into_iter()
+                    .filter(|&p| grants.iter().any(|g| g.subject == subject && g.scope == scope && g.allows(p)))
+                    .collect();
+                let changes = gate.allowed.entry((subject.to_string(), scope)).or_default();
+                if changes.last().is_none_or(|(_, last)| *last != purposes) {
+                    changes.push((d.timestamp, purposes));
+                }
+            }
+        }
+        for changes in gate.allowed.values_mut() {
+            changes.sort_by_key(|(t, _)| *t);
+        }
+        gate.reassigned.sort_by_key(|(t, _, _)| *t);
+        gate
+    }
+
+    /// The scope governing `node` at `at`.
+    pub fn scope_at(&self, node: &Node, at: i64) -> Option<Node> {
+        self.reassigned
+            .iter()
+            .rev()
+            .find(|(t, n, _)| *t <= at && n == node)
+            .map(|(_, _, scope)| scope.clone())
+            .or_else(|| ConsentLedger::scope_for(node))
+    }
+
+    /// What `subject`'s grant for `scope` allowed at `at`; empty without one.
+    pub fn purposes_at(&self, subject: &str, scope: &Node, at: i64) -> BTreeSet<Purpose> {
+        let Some(changes) = self.allowed.get(&(subject.to_string(), scope.clone())) else {
+            return BTreeSet::new();
+        };
+        match changes.partition_point(|(t, _)| *t <= at) {
+            0 => BTreeSet::new(),
+            i => changes[i - 1].1.clone(),
+        }
+    }
+
+    /// True when `deed` may feed data used for `purpose`.
+    pub fn permits(&self, deed: &DeedEvent, purpose: Purpose) -> bool {
+        match self.scope_at(&deed.node, deed.timestamp) {
+            Some(scope) => self.purposes_at(&deed.actor_id, &scope, deed.timestamp).contains(&purpose),
+            None => true,
+        }
+    }
+}
+
+/// Event counts over `[from, until)` from the deeds a purpose admits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventAnalytics {
+    pub purpose: Purpose,
+    pub from: i64,
+    pub until: i64,
+    pub events: usize,
+    pub by_node: BTreeMap<String, usize>,
+    pub by_type: BTreeMap<String, usize>,
+    pub subjects: usize,
+    /// Events in the window the purpose's consent did not cover.
+    pub excluded: usize,
+}
+
+/// Event deeds released under `purposes`, with the purposes on record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurposeExport {
+    pub purposes: BTreeSet<Purpose>,
+    pub from: i64,
+    pub until: i64,
+    pub deeds: Vec<DeedEvent>,
+    pub excluded: usize,
+}
+
+/// Reputation components attested for one purpose. A component computed
+/// from any event the purpose does not cover is withheld, with the count of
+/// such events, and so is `mp_score` when any component is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentAttestation {
+    pub purpose: Purpose,
+    pub at: i64,
+    pub components: BTreeMap<String, f64>,
+    pub mp_score: Option<f64>,
+    pub withheld: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageWindow {
+    pub start: i64,
+    pub end: i64,
+    pub events: usize,
+    /// Events left out, for every purpose.
+    pub excluded: BTreeMap<Purpose, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PurposeCoverage {
+    pub window_ms: i64,
+    pub windows: Vec<CoverageWindow>,
+}
+
+impl SovereigntyCore {
+    pub fn purpose_gate(&self) -> PurposeGate {
+        PurposeGate::new(self)
+    }
+
+    fn events_between(&self, from: i64, until: i64) -> impl Iterator<Item = &DeedEvent> {
+        self.deed_log.iter().filter(move |d| is_event(&d.node) && d.timestamp >= from && d.timestamp < until)
+    }
+
+    pub fn event_analytics(&self, purpose: Purpose, from: i64, until: i64) -> EventAnalytics {
+        let gate = self.purpose_gate();
+        let mut out = EventAnalytics {
+            purpose,
+            from,
+            until,
+            events: 0,
+            by_node: BTreeMap::new(),
+            by_type: BTreeMap::new(),
+            subjects: 0,
+            excluded: 0,
+        };
+        let mut subjects = BTreeSet::new();
+        for d in self.events_between(from, until) {
+            if !gate.permits(d, purpose) {
+                out.excluded += 1;
+                continue;
+            }
+            out.events += 1;
+            *out.by_node.entry(node_key(&d.node)).or_insert(0) += 1;
+            *out.by_type.entry(d.deed_type.clone()).or_insert(0) += 1;
+            subjects.insert(d.actor_id.as_str());
+        }
+        out.subjects = subjects.len();
+        out
+    }
+
+    /// Events every one of `purposes` admits. With no purpose named,
+    /// nothing is exported.
+    pub fn export_events(&self, purposes: &[Purpose], from: i64, until: i64) -> PurposeExport {
+        let gate = self.purpose_gate();
+        let purposes: BTreeSet<Purpose> = purposes.iter().copied().collect();
+        let (deeds, excluded): (Vec<&DeedEvent>, Vec<&DeedEvent>) = self
+            .events_between(from, until)
+            .partition(|d| !purposes.is_empty() && purposes.iter().all(|&p| gate.permits(d, p)));
+        PurposeExport { purposes, from, until, deeds: deeds.into_iter().cloned().collect(), excluded: excluded.len() }
+    }
+
+    /// The current reputation components, for use under `purpose`, counting
+    /// events logged up to `at`.
+    pub fn attest_components(&self, purpose: Purpose, at: i64) -> ComponentAttestation {
+        let gate = self.purpose_gate();
+        let values = [self.reputation.privacy, self.reputation.compliance, self.reputation.eco_align, self.reputation.clin_trust];
+        let mut out = ComponentAttestation { purpose, at, components: BTreeMap::new(), mp_score: None, withheld: BTreeMap::new() };
+        for ((component, reads), value) in COMPONENT_READS.iter().zip(values) {
+            let excluded = self
+                .deed_log
+                .iter()
+                .filter(|d| d.timestamp <= at && reads(d) && !gate.permits(d, purpose))
+                .count();
+            if excluded == 0 {
+                out.components.insert(component.to_string(), value);
+            } else {
+                out.withheld.insert(component.to_string(), excluded);
+            }
+        }
+        if out.withheld.is_empty() {
+            out.mp_score = Some(self.reputation.mp_score);
+        }
+        out
+    }
+
+    /// Events excluded per purpose in consecutive `window_ms` windows
+    /// from `from` to `until`.
+    pub fn purpose_coverage(&self, from: i64, until: i64, window_ms: i64) -> PurposeCoverage {
+        let gate = self.purpose_gate();
+        let step = window_ms.max(1);
+        let mut windows = Vec::new();
+        let mut start = from;
+        while start < until {
+            let end = (start + step).min(until);
+            let mut window = CoverageWindow { start, end, events: 0, excluded: Purpose::ALL.into_iter().map(|p| (p, 0)).collect() };
+            for d in self.events_between(start, end) {
+                window.events += 1;
+                for p in Purpose::ALL.into_iter().filter(|&p| !gate.permits(d, p)) {
+                    *window.excluded.entry(p).or_insert(0) += 1;
+                }
+            }
+            windows.push(window);
+            start = end;
+        }
+        PurposeCoverage { window_ms: step, windows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execute_migration, plan_migration, ConsentError, MigrationPlan, MigrationPolicy, Quorum, ScopeMapping, CITIZEN};
+
+    const T0: i64 = 1_700_000_000_000;
+    const HOUR: i64 = 3_600_000;
+
+    fn sleep(core: &mut SovereigntyCore, at: i64) {
+        core.log_event_at(Node::NSleep, "eeg_epoch".into(), serde_json::json!({}), at).unwrap();
+    }
+
+    fn bci(core: &mut SovereigntyCore, at: i64) {
+        core.log_event_at(Node::NBci, "cognitive_trial".into(), serde_json::json!({"attested": true}), at).unwrap();
+    }
+
+    /// EEG granted for clinical and research use only, BCI for everything.
+    fn clinical_eeg() -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        core.grant_consent_for(CITIZEN, Node::ScopeEeg, &[Purpose::Clinical, Purpose::ResearchAggregate], T0).unwrap();
+        core.grant_consent(CITIZEN, Node::ScopeBci, T0).unwrap();
+        for i in 1..=3 {
+            sleep(&mut core, T0 + i);
+        }
+        bci(&mut core, T0 + 4);
+        core
+    }
+
+    #[test]
+    fn analytics_leave_out_events_outside_the_purpose() {
+        let core = clinical_eeg();
+        let research = core.event_analytics(Purpose::ResearchAggregate, T0, T0 + HOUR);
+        assert_eq!((research.events, research.excluded, research.subjects), (4, 0, 1));
+
+        let public = core.event_analytics(Purpose::PublicReport, T0, T0 + HOUR);
+        assert_eq!((public.events, public.excluded), (1, 3));
+        assert_eq!(public.by_node, BTreeMap::from([("NBci".to_string(), 1)]));
+        assert_eq!(public.by_type, BTreeMap::from([("cognitive_trial".to_string(), 1)]));
+
+        // Consent and compliance records are not subject data.
+        let gate = core.purpose_gate();
+        assert!(core.deed_log.iter().filter(|d| d.node == Node::ConsentLedger).all(|d| gate.permits(d, Purpose::PublicReport)));
+
+        let mut core = SovereigntyCore::new();
+        core.consent.restrict_purposes(&Node::ScopeEeg, &[Purpose::Clinical]).unwrap();
+        assert_eq!(
+            core.grant_consent_for(CITIZEN, Node::ScopeEeg, &[Purpose::GrantRouting], T0),
+            Err(ConsentError::PurposeNotOffered { scope: Node::ScopeEeg, purpose: Purpose::GrantRouting })
+        );
+        core.grant_consent(CITIZEN, Node::ScopeEeg, T0).unwrap();
+        assert_eq!(core.purpose_gate().purposes_at(CITIZEN, &Node::ScopeEeg, T0), BTreeSet::from([Purpose::Clinical]));
+    }
+
+    #[test]
+    fn consent_is_read_as_of_each_deed() {
+        let mut core = SovereigntyCore::new();
+        sleep(&mut core, T0);
+        core.grant_consent(CITIZEN, Node::ScopeEeg, T0 + 10).unwrap();
+        sleep(&mut core, T0 + 10);
+        sleep(&mut core, T0 + 20);
+        core.revoke_consent(CITIZEN, Node::ScopeEeg, T0 + 30).unwrap();
+        sleep(&mut core, T0 + 40);
+        assert_eq!(core.revoke_consent(CITIZEN, Node::ScopeEeg, T0 + 50), Err(ConsentError::NotGranted(Node::ScopeEeg)));
+
+        // Before the grant and after the revocation nothing is usable; the
+        // revocation does not reach back over the two events in between.
+        let gate = core.purpose_gate();
+        let admitted: Vec<i64> =
+            core.deed_log.iter().filter(|d| d.node == Node::NSleep && gate.permits(d, Purpose::ResearchAggregate)).map(|d| d.timestamp).collect();
+        assert_eq!(admitted, [T0 + 10, T0 + 20]);
+        assert!(gate.purposes_at(CITIZEN, &Node::ScopeEeg, T0 + 29).contains(&Purpose::PublicReport));
+        assert!(gate.purposes_at(CITIZEN, &Node::ScopeEeg, T0 + 30).is_empty());
+        assert_eq!(core.event_analytics(Purpose::Clinical, T0, T0 + HOUR).excluded, 2);
+
+        // A re-grant covers what follows it, not what came before.
+        core.grant_consent_for(CITIZEN, Node::ScopeEeg, &[Purpose::Clinical], T0 + 60).unwrap();
+        sleep(&mut core, T0 + 70);
+        let gate = core.purpose_gate();
+        let clinical = core.deed_log.iter().filter(|d| d.node == Node::NSleep && gate.permits(d, Purpose::Clinical)).count();
+        assert_eq!(clinical, 3);
+    }
+
+    #[test]
+    fn purposes_follow_grants_through_a_migration() {
+        let mut core = SovereigntyCore::new();
+        core.grant_consent_for(CITIZEN, Node::ScopeEeg, &[Purpose::Clinical], T0).unwrap();
+        let plan = MigrationPlan {
+            version: "consent-v2".into(),
+            current: HashMap::from([(Node::ScopeEeg, BTreeSet::from(["eeg_sleep".to_string(), "eeg_daytime".to_string()]))]),
+            next: HashMap::from([(Node::ScopeEegSleep, BTreeSet::from(["eeg_sleep".to_string()]))]),
+            mappings: vec![ScopeMapping { from: Node::ScopeEeg, to: Some(Node::ScopeEegSleep), policy: MigrationPolicy::CarryOver }],
+            node_scopes: vec![(Node::NSleep, Node::ScopeEegSleep)],
+        };
+        let report = plan_migration(&core, &plan).unwrap();
+        execute_migration(&mut core, &report, &Quorum { required: 1, approvals: vec!["irb".into()] }, T0 + 100).unwrap();
+        sleep(&mut core, T0 + 50);
+        sleep(&mut core, T0 + 150);
+
+        let gate = core.purpose_gate();
+        assert_eq!(gate.scope_at(&Node::NSleep, T0 + 50), Some(Node::ScopeEeg));
+        assert_eq!(gate.scope_at(&Node::NSleep, T0 + 150), Some(Node::ScopeEegSleep));
+        assert_eq!(gate.purposes_at(CITIZEN, &Node::ScopeEegSleep, T0 + 150), BTreeSet::from([Purpose::Clinical]));
+        assert!(gate.purposes_at(CITIZEN, &Node::ScopeEeg, T0 + 150).is_empty());
+        assert_eq!(core.event_analytics(Purpose::Clinical, T0, T0 + HOUR).events, 2);
+        assert_eq!(core.event_analytics(Purpose::ResearchAggregate, T0, T0 + HOUR).excluded, 2);
+    }
+
+    #[test]
+    fn exports_carry_and_apply_their_purposes() {
+        let core = clinical_eeg();
+        let export = core.export_events(&[Purpose::ResearchAggregate, Purpose::PublicReport], T0, T0 + HOUR);
+        assert_eq!(export.purposes, BTreeSet::from([Purpose::ResearchAggregate, Purpose::PublicReport]));
+        assert_eq!((export.deeds.len(), export.excluded), (1, 3));
+        assert_eq!(export.deeds[0].node, Node::NBci);
+
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["purposes"], serde_json::json!(["research_aggregate", "public_report"]));
+
+        let research = core.export_events(&[Purpose::ResearchAggregate], T0, T0 + HOUR);
+        assert_eq!((research.deeds.len(), research.excluded), (4, 0));
+        let unnamed = core.export_events(&[], T0, T0 + HOUR);
+        assert_eq!((unnamed.deeds.len(), unnamed.excluded), (0, 4));
+    }
+
+    #[test]
+    fn attestations_withhold_components_from_excluded_data() {
+        let core = clinical_eeg();
+        let research = core.attest_components(Purpose::ResearchAggregate, T0 + HOUR);
+        assert_eq!(research.components.len(), 4);
+        assert_eq!(research.mp_score, Some(core.reputation.mp_score));
+
+        // Every event feeds privacy; public use leaves it and mp_score out.
+        // The sleep events report no energy, so eco_align does not read them.
+        let public = core.attest_components(Purpose::PublicReport, T0 + HOUR);
+        assert_eq!(public.withheld, BTreeMap::from([("privacy".to_string(), 3)]));
+        assert_eq!(public.components.keys().map(String::as_str).collect::<Vec<_>>(), ["clin_trust", "compliance", "eco_align"]);
+        assert_eq!(public.components["clin_trust"], core.reputation.clin_trust);
+        assert_eq!(public.mp_score, None);
+
+        // Only events up to `at` count.
+        assert!(core.attest_components(Purpose::PublicReport, T0).withheld.is_empty());
+    }
+
+    #[test]
+    fn coverage_counts_exclusions_per_purpose_per_window() {
+        let mut core = clinical_eeg();
+        core.revoke_consent(CITIZEN, Node::ScopeBci, T0 + HOUR).unwrap();
+        bci(&mut core, T0 + HOUR + 5);
+        sleep(&mut core, T0 + HOUR + 6);
+
+        let coverage = core.purpose_coverage(T0, T0 + 2 * HOUR + 1, HOUR);
+        assert_eq!(coverage.windows.len(), 3);
+        let [first, second, third] = &coverage.windows[..] else { unreachable!() };
+        assert_eq!((first.start, first.end, first.events), (T0, T0 + HOUR, 4));
+        let counts = |w: &CoverageWindow| Purpose::ALL.map(|p| w.excluded[&p]);
+        assert_eq!(counts(first), [0, 0, 3, 3]);
+        assert_eq!(second.events, 2);
+        assert_eq!(counts(second), [1, 1, 2, 2]);
+        assert_eq!((third.events, third.end), (0, T0 + 2 * HOUR + 1));
+        assert_eq!(counts(third), [0, 0, 0, 0]);
+
+        let public = core.event_analytics(Purpose::PublicReport, T0, T0 + 2 * HOUR);
+        assert_eq!(public.excluded, coverage.windows.iter().map(|w| w.excluded[&Purpose::PublicReport]).sum::<usize>());
+    }
+}
@@ -72,7 +72,14 @@ impl ReputationEngine {
     }
 
     pub fn calc_clin_trust(signed_trials: usize, recovery_events: usize) -> f64 {
-        let base = 0.70 + (signed_trials as f64 * 0.04).min(0.25);
+        Self::calc_clin_trust_weighted(&vec![1.0; signed_trials], recovery_events)
+    }
+
+    /// `calc_clin_trust` with each signed trial counted at its provider's
+    /// reliability when it was attested (`provider_weight`), not at 1.
+    pub fn calc_clin_trust_weighted(trial_weights: &[f64], recovery_events: usize) -> f64 {
+        let trials: f64 = trial_weights.iter().map(|w| w.clamp(0.0, 1.0)).sum();
+        let base = 0.70 + (trials * 0.04).min(0.25);
         (base + (recovery_events as f64 * 0.03)).clamp(0.0, 1.0)
     }
 
@@ -90,7 +97,7 @@ impl ReputationEngine {
         let mut attested_count = 0;
         let mut anchor_count = 0;
         let mut low_energy_runs = 0;
-        let mut signed_trials = 0;
+        let mut trial_weights = Vec::new();
         let mut life_harm_flags = 0;
         let mut recovery_events = 0;
         let mut total_events = core.deed_log.len();
@@ -100,7 +107,7 @@ impl ReputationEngine {
                 Node::Did => did_bound = true,
                 Node::ScopeEeg | Node::ScopeBci => consent_ok = true,
                 Node::Target1 => { low_energy_runs += 1; if !deed.life_harm_flag { attested_count += 1; } }
-                Node::Target2 => { signed_trials += 1; }
+                Node::Target2 => trial_weights.push(deed.context_json["provider_weight"].as_f64().unwrap_or(1.0)),
                 Node::Path1 | Node::Path2 => anchor_count += 1,
                 _ => {}
             }
@@ -126,7 +133,7 @@ impl ReputationEngine {
         self.vector.privacy = Self::calc_privacy(did_bound, consent_ok, total_events);
         self.vector.compliance = Self::calc_compliance(attested_count, anchor_count, life_harm_flags);
         self.vector.eco_align = Self::calc_eco_align(low_energy_runs, if recent_pred.3 { 1 } else { 0 }, total_events);
-        self.vector.clin_trust = Self::calc_clin_trust(signed_trials, recovery_events);
+        self.vector.clin_trust = Self::calc_clin_trust_weighted(&trial_weights, recovery_events);
 
         // Tree-of-Life normalization + mp_score
         let avg_asset = observer.lattice.iter()
@@ -194,8 +201,8 @@ mod tests {
         let mut obs = MicrospaceRightsObserver::new(20);
         for _ in 0..30 { obs.step(0.08); } // low load = CALM_STABLE
 
-        core.log_event(Node::Target1, "high_trust_eeg".to_string(), serde_json::json!({"consent": true}));
-        core.log_event(Node::Target2, "signed_bci".to_string(), serde_json::json!({"attested": true}));
+        core.log_event(Node::Target1, "high_trust_eeg".to_string(), serde_json::json!({"consent": true})).unwrap();
+        core.log_event(Node::Target2, "signed_bci".to_string(), serde_json::json!({"attested": true})).unwrap();
 
         let vec = core.reputation_engine(&obs);
         assert!(vec.mp_score > 0.90);
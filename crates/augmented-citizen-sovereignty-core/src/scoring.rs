@@ -0,0 +1,182 @@
+//! The four reputation components, derived from the deed log.
+//! Each component is the share of the neuro events it reads that count in
+//! the citizen's favour, and sits at a neutral 0.5 when there is nothing to
+//! read. The same log always yields the same vector.
+//!
+//! - privacy: events carrying `consent_anchored`, logged once a DID is bound;
+//! - compliance: of events reporting `attested`, those attested, less the
+//!   dent each duty-cycle flag recorded;
+//! - eco_align: of events reporting an energy band or on an ecological deed
+//!   type, those in the low band or ecological;
+//! - clin_trust: `NClin` events without `life_harm_flag`.
+
+use crate::consent::DUTY_CYCLE_FLAG;
+use crate::purpose::is_event;
+use crate::{DeedEvent, EnergyBand, Node, ReputationVector, ReputationWeights};
+
+/// Score of a component with no events to read.
+pub const NEUTRAL_SCORE: f64 = 0.5;
+
+pub const CONSENT_ANCHORED: &str = "consent_anchored";
+
+/// Deed types that count towards eco_align whatever energy they report.
+pub const ECOLOGICAL_DEED_TYPES: [&str; 2] = ["eco_grant", "green_band"];
+
+/// Whether a reputation component reads a deed.
+pub(crate) type ReadsDeed = fn(&DeedEvent) -> bool;
+
+/// Each component and whether it reads a given deed. `attest_components`
+/// withholds a component when a deed it reads is outside the purpose.
+pub(crate) const COMPONENT_READS: [(&str, ReadsDeed); 4] = [
+    ("privacy", reads_privacy),
+    ("compliance", reads_compliance),
+    ("eco_align", reads_eco_align),
+    ("clin_trust", reads_clin_trust),
+];
+
+fn reads_privacy(d: &DeedEvent) -> bool {
+    is_event(&d.node)
+}
+
+fn reads_compliance(d: &DeedEvent) -> bool {
+    is_event(&d.node) && d.context_json.get("attested").is_some()
+}
+
+fn reads_eco_align(d: &DeedEvent) -> bool {
+    is_event(&d.node) && (EnergyBand::of(d).is_some() || is_ecological(d))
+}
+
+fn reads_clin_trust(d: &DeedEvent) -> bool {
+    d.node == Node::NClin
+}
+
+fn is_ecological(d: &DeedEvent) -> bool {
+    ECOLOGICAL_DEED_TYPES.contains(&d.deed_type.as_str())
+}
+
+/// `good` over `read`, or the neutral score when nothing was read.
+fn share(good: usize, read: usize) -> f64 {
+    if read == 0 { NEUTRAL_SCORE } else { good as f64 / read as f64 }
+}
+
+impl ReputationVector {
+    /// Every component, and mp_score, at the neutral score.
+    pub fn neutral() -> Self {
+        ReputationVector { privacy: NEUTRAL_SCORE, compliance: NEUTRAL_SCORE, eco_align: NEUTRAL_SCORE, clin_trust: NEUTRAL_SCORE, mp_score: NEUTRAL_SCORE }
+    }
+
+    /// The components derived from `log`, with mp_score their weighted sum
+    /// under `weights`.
+    pub fn from_deed_log(log: &[DeedEvent], weights: &ReputationWeights) -> Self {
+        let mut did_bound = false;
+        let mut anchored = 0;
+        for d in log {
+            did_bound |= d.node == Node::Did;
+            if reads_privacy(d) && did_bound && d.ethics_flags.iter().any(|f| f == CONSENT_ANCHORED) {
+                anchored += 1;
+            }
+        }
+        let privacy = share(anchored, log.iter().filter(|d| reads_privacy(d)).count());
+
+        let reporting: Vec<&DeedEvent> = log.iter().filter(|d| reads_compliance(d)).collect();
+        let attested = reporting.iter().filter(|d| d.context_json["attested"] == true).count();
+        let dents: f64 = log
+            .iter()
+            .filter(|d| d.node == Node::ComplianceScore && d.ethics_flags.iter().any(|f| f == DUTY_CYCLE_FLAG))
+            .filter_map(|d| d.context_json["compliance_dent"].as_f64())
+            .sum();
+        let compliance = (share(attested, reporting.len()) - dents).max(0.0);
+
+        let eco: Vec<&DeedEvent> = log.iter().filter(|d| reads_eco_align(d)).collect();
+        let green = eco.iter().filter(|d| is_ecological(d) || EnergyBand::of(d) == Some(EnergyBand::Low)).count();
+        let eco_align = share(green, eco.len());
+
+        let clinical: Vec<&DeedEvent> = log.iter().filter(|d| reads_clin_trust(d)).collect();
+        let clin_trust = share(clinical.iter().filter(|d| !d.life_harm_flag).count(), clinical.len());
+
+        let mp_score = privacy * weights.privacy + compliance * weights.compliance + eco_align * weights.eco_align + clin_trust * weights.clin_trust;
+        ReputationVector { privacy, compliance, eco_align, clin_trust, mp_score: mp_score.clamp(0.0, 1.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SovereigntyCore, CITIZEN};
+    use serde_json::json;
+
+    const T0: i64 = 1_700_000_000_000;
+
+    fn log(core: &mut SovereigntyCore, node: Node, deed_type: &str, context: serde_json::Value) {
+        let at = T0 + core.deed_log.len() as i64;
+        core.log_event_at(node, deed_type.to_string(), context, at).unwrap();
+    }
+
+    fn good_log() -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        log(&mut core, Node::Did, "did_binding", json!({ "did": "did:bostrom:citizen" }));
+        log(&mut core, Node::NSleep, "eeg_epoch", json!({ "consent": true, "energy": "low" }));
+        log(&mut core, Node::NBci, "cognitive_trial", json!({ "attested": true }));
+        log(&mut core, Node::NClin, "clinical_session", json!({ "attested": true }));
+        log(&mut core, Node::Target1, "eco_grant", json!({}));
+        core
+    }
+
+    #[test]
+    fn an_empty_log_is_neutral() {
+        let v = ReputationVector::from_deed_log(&[], &ReputationWeights::default());
+        for c in [v.privacy, v.compliance, v.eco_align, v.clin_trust, v.mp_score] {
+            assert_eq!(c, NEUTRAL_SCORE);
+        }
+        assert_eq!(SovereigntyCore::new().reputation.mp_score, NEUTRAL_SCORE);
+    }
+
+    #[test]
+    fn an_all_good_log_scores_full_marks() {
+        let core = good_log();
+        let v = ReputationVector::from_deed_log(&core.deed_log, &ReputationWeights::default());
+        assert_eq!((v.privacy, v.compliance, v.eco_align, v.clin_trust, v.mp_score), (1.0, 1.0, 1.0, 1.0, 1.0));
+
+        // The same log always derives the same vector.
+        let again = ReputationVector::from_deed_log(&core.deed_log, &ReputationWeights::default());
+        assert_eq!(serde_json::to_value(&again).unwrap(), serde_json::to_value(&v).unwrap());
+    }
+
+    #[test]
+    fn each_component_reads_its_own_events() {
+        let mut core = SovereigntyCore::new();
+        // Logged before the DID is bound, so not counted for privacy.
+        log(&mut core, Node::NSleep, "eeg_epoch", json!({ "energy": "high" }));
+        log(&mut core, Node::Did, "did_binding", json!({}));
+        log(&mut core, Node::NSleep, "eeg_epoch", json!({ "energy": "low" }));
+        log(&mut core, Node::NBci, "cognitive_trial", json!({ "attested": false }));
+        log(&mut core, Node::NBci, "cognitive_trial", json!({ "attested": true }));
+
+        let weights = ReputationWeights { privacy: 0.0, compliance: 1.0, eco_align: 0.0, clin_trust: 0.0 };
+        let v = ReputationVector::from_deed_log(&core.deed_log, &weights);
+        assert_eq!(v.privacy, 0.75);
+        assert_eq!(v.compliance, 0.5);
+        assert_eq!(v.eco_align, 0.5);
+        assert_eq!(v.clin_trust, NEUTRAL_SCORE);
+        assert_eq!(v.mp_score, 0.5);
+    }
+
+    #[test]
+    fn harm_flags_and_duty_cycle_flags_lower_the_score() {
+        let mut core = good_log();
+        let mut harmed = DeedEvent::new(CITIZEN.to_string(), Node::NClin, "clinical_session".to_string(), json!({ "attested": true }));
+        harmed.life_harm_flag = true;
+        core.append(harmed, T0 + 10);
+        let v = ReputationVector::from_deed_log(&core.deed_log, &ReputationWeights::default());
+        assert_eq!(v.clin_trust, 0.5);
+        assert_eq!(v.compliance, 1.0);
+        assert!((v.mp_score - 0.875).abs() < 1e-12);
+
+        let mut flagged = DeedEvent::new("consent_ledger".to_string(), Node::ComplianceScore, "duty_cycle_flag".to_string(), json!({ "compliance_dent": 0.05 }));
+        flagged.ethics_flags.push(DUTY_CYCLE_FLAG.to_string());
+        core.append(flagged, T0 + 11);
+        let v = ReputationVector::from_deed_log(&core.deed_log, &ReputationWeights::default());
+        assert!((v.compliance - 0.95).abs() < 1e-12);
+        assert_eq!(v.privacy, 1.0);
+    }
+}
@@ -0,0 +1,162 @@
+//! The deed log on disk, one `DeedEvent` per JSONL line, the layout
+//! `MoralLedger` uses. Loading re-verifies the whole chain and refuses a
+//! log with a broken link, naming its line.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::{DeedEvent, SovereigntyCore};
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    /// A line that is not a `DeedEvent`. Lines count from 1.
+    Parse { line: usize, message: String },
+    /// A line whose event does not hash to its `self_hash` or does not
+    /// link to the event before it.
+    BrokenChain { line: usize, event_id: String, reason: &'static str },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "deed log: {}", e),
+            StoreError::Parse { line, message } => write!(f, "deed log line {}: {}", line, message),
+            StoreError::BrokenChain { line, event_id, reason } => {
+                write!(f, "deed log line {} (event {}): {}", line, event_id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+impl SovereigntyCore {
+    /// Write the deed log to `path`, replacing what was there. The log is
+    /// written beside it first and moved into place, so a crash mid-write
+    /// leaves the previous file intact.
+    pub fn save_to_path(&self, path: &Path) -> Result<(), StoreError> {
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        for deed in &self.deed_log {
+            let line = serde_json::to_string(deed).map_err(io::Error::from)?;
+            writeln!(out, "{}", line)?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// A core holding the deed log at `path`, its chain re-verified from
+    /// the genesis hash and `current_hash` at its last event. Blank lines
+    /// are skipped.
+    pub fn load_from_path(path: &Path) -> Result<Self, StoreError> {
+        let mut core = SovereigntyCore::new();
+        let mut lines = Vec::new();
+        for (i, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let deed: DeedEvent =
+                serde_json::from_str(&line).map_err(|e| StoreError::Parse { line: i + 1, message: e.to_string() })?;
+            core.deed_log.push(deed);
+            lines.push(i + 1);
+        }
+        if let Err(at) = core.verify_chain() {
+            let deed = &core.deed_log[at];
+            let reason = if deed.verify_hash() {
+                "prev_hash does not link to the event before it"
+            } else {
+                "self_hash does not match the event's contents"
+            };
+            return Err(StoreError::BrokenChain { line: lines[at], event_id: deed.event_id.clone(), reason });
+        }
+        if let Some(last) = core.deed_log.last() {
+            core.current_hash = last.self_hash.clone();
+        }
+        Ok(core)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Node, GENESIS_HASH};
+    use std::path::PathBuf;
+
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("sovereignty-{}-{}.jsonl", std::process::id(), name)))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn logged(events: usize) -> SovereigntyCore {
+        let mut core = SovereigntyCore::new();
+        for i in 0..events {
+            core.log_event_at(Node::Did, "binding".to_string(), serde_json::json!({ "seq": i }), 1_000 + i as i64).unwrap();
+        }
+        core
+    }
+
+    #[test]
+    fn a_hundred_events_round_trip() {
+        let file = TempFile::new("round-trip");
+        let core = logged(100);
+        core.save_to_path(&file.0).unwrap();
+
+        let loaded = SovereigntyCore::load_from_path(&file.0).unwrap();
+        assert_eq!(loaded.deed_log.len(), 100);
+        assert_eq!(loaded.current_hash, core.current_hash);
+        assert_ne!(loaded.current_hash, GENESIS_HASH);
+        let ids = |c: &SovereigntyCore| c.deed_log.iter().map(|d| d.self_hash.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&loaded), ids(&core));
+
+        // The restored chain keeps growing from where it left off.
+        let mut loaded = loaded;
+        loaded.log_event_at(Node::Did, "binding".to_string(), serde_json::json!({ "seq": 100 }), 2_000).unwrap();
+        assert_eq!(loaded.deed_log[100].prev_hash, core.current_hash);
+        assert_eq!(loaded.verify_chain(), Ok(()));
+    }
+
+    #[test]
+    fn a_tampered_middle_line_is_refused_by_line_number() {
+        let file = TempFile::new("tampered");
+        logged(5).save_to_path(&file.0).unwrap();
+        let text = fs::read_to_string(&file.0).unwrap();
+        let tampered: Vec<String> = text.lines().map(|l| l.replacen("\"seq\":2", "\"seq\":20", 1)).collect();
+        fs::write(&file.0, tampered.join("\n")).unwrap();
+
+        match SovereigntyCore::load_from_path(&file.0) {
+            Err(StoreError::BrokenChain { line: 3, reason, .. }) => assert!(reason.starts_with("self_hash")),
+            other => panic!("expected line 3 to be refused, got {:?}", other.map(|c| c.deed_log.len())),
+        }
+
+        // Dropping a line breaks the link of the one after it.
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines.remove(1);
+        fs::write(&file.0, lines.join("\n")).unwrap();
+        let err = SovereigntyCore::load_from_path(&file.0).map(|_| ()).unwrap_err();
+        assert!(matches!(err, StoreError::BrokenChain { line: 2, reason, .. } if reason.starts_with("prev_hash")));
+        assert!(err.to_string().starts_with("deed log line 2 (event "));
+
+        fs::write(&file.0, "{ not json\n").unwrap();
+        assert!(matches!(SovereigntyCore::load_from_path(&file.0), Err(StoreError::Parse { line: 1, .. })));
+    }
+}
@@ -0,0 +1,209 @@
+//! On-disk layout of an exported audit bundle.
+//!
+//! ```text
+//! <bundle>/
+//!   segments/      000001.jsonl | 000001.jsonl.gz   one deed per line
+//!   manifests/     000001.json                      SegmentManifest, signed
+//!   anchors/       *.json                           AnchorReceipt (optional)
+//!   attestations/  *.json                           Attestation (optional)
+//! ```
+//!
+//! Deed lines are read with `deed_core::decode_line`, so a segment may hold
+//! enveloped lines or the bare lines a ledger wrote before the envelope.
+//! Loading never touches the network; everything an auditor needs is in the
+//! directory. Missing pieces are recorded as gaps rather than load errors so
+//! the verifier can report "incomplete" separately from "invalid".
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use deed_core::{DeedEvent, HashRule};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Signed summary of one ledger segment. The signature covers
+/// `digest()`, which is the canonical JSON of every field except `signature`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SegmentManifest {
+    pub seq: u64,
+    /// File name under `segments/`.
+    pub segment: String,
+    pub event_count: u64,
+    /// prev_hash of the first event in the segment.
+    pub first_prev_hash: String,
+    /// self_hash of the last event in the segment.
+    pub tip_hash: String,
+    pub merkle_root: String,
+    /// How the segment's deeds compute `self_hash`. Every deed in the
+    /// segment must be sealed under this rule.
+    #[serde(default, skip_serializing_if = "HashRule::is_canonical")]
+    pub hash_rule: HashRule,
+    /// digest() of the previous manifest; empty for seq 1.
+    pub prev_manifest_hash: String,
+    /// Hex ed25519 verifying key.
+    pub signer: String,
+    /// Hex ed25519 signature over digest().
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl SegmentManifest {
+    pub fn digest(&self) -> String {
+        let mut body = serde_json::to_value(self).expect("manifest serializes");
+        if let Value::Object(map) = &mut body {
+            map.remove("signature");
+        }
+        sha256_hex(canonical_json(&body).as_bytes())
+    }
+}
+
+/// Receipt that a manifest digest was anchored externally. Only the claimed
+/// digest is checked offline; the anchor proof itself is carried opaquely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorReceipt {
+    pub manifest_seq: u64,
+    pub digest: String,
+    pub anchor: String,
+    #[serde(default)]
+    pub proof: Value,
+}
+
+/// A third-party statement about ledger state as of `tip_hash`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attestation {
+    pub id: String,
+    pub tip_hash: String,
+    pub actor_id: String,
+    /// Number of deeds by `actor_id` up to and including `tip_hash`.
+    pub deed_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub file: String,
+    /// The segment's deeds, one per line.
+    pub events: Vec<DeedEvent>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    pub root: PathBuf,
+    /// Keyed by file name.
+    pub segments: BTreeMap<String, Segment>,
+    pub manifests: Vec<SegmentManifest>,
+    pub anchors: Vec<(String, AnchorReceipt)>,
+    pub attestations: Vec<(String, Attestation)>,
+    /// Files that exist but could not be parsed.
+    pub unreadable: Vec<(String, String)>,
+}
+
+impl Bundle {
+    pub fn load(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            anyhow::bail!("bundle directory {} does not exist", root.display());
+        }
+        let mut bundle = Bundle { root: root.to_path_buf(), ..Default::default() };
+
+        for path in list(&root.join("segments"))? {
+            let name = file_name(&path);
+            match read_segment(&path) {
+                Ok(events) => {
+                    bundle.segments.insert(name.clone(), Segment { file: name, events });
+                }
+                Err(e) => bundle.unreadable.push((format!("segments/{}", name), format!("{:#}", e))),
+            }
+        }
+
+        for path in list(&root.join("manifests"))? {
+            match read_json::<SegmentManifest>(&path) {
+                Ok(m) => bundle.manifests.push(m),
+                Err(e) => bundle.unreadable.push((format!("manifests/{}", file_name(&path)), format!("{:#}", e))),
+            }
+        }
+        bundle.manifests.sort_by_key(|m| m.seq);
+
+        for path in list(&root.join("anchors"))? {
+            match read_json::<AnchorReceipt>(&path) {
+                Ok(a) => bundle.anchors.push((file_name(&path), a)),
+                Err(e) => bundle.unreadable.push((format!("anchors/{}", file_name(&path)), format!("{:#}", e))),
+            }
+        }
+
+        for path in list(&root.join("attestations"))? {
+            match read_json::<Attestation>(&path) {
+                Ok(a) => bundle.attestations.push((file_name(&path), a)),
+                Err(e) => bundle.unreadable.push((format!("attestations/{}", file_name(&path)), format!("{:#}", e))),
+            }
+        }
+
+        Ok(bundle)
+    }
+
+    /// Events in manifest order, skipping segments that are missing.
+    pub fn ordered_events(&self) -> Vec<&DeedEvent> {
+        self.manifests
+            .iter()
+            .filter_map(|m| self.segments.get(&m.segment))
+            .flat_map(|s| s.events.iter())
+            .collect()
+    }
+}
+
+/// Canonical JSON: keys sorted, no whitespace. serde_json's default map is a
+/// BTreeMap, so a Value round-trip is already sorted.
+pub fn canonical_json(value: &Value) -> String {
+    serde_json::to_string(value).expect("Value serializes")
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+fn list(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_file() {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+fn read_segment(path: &Path) -> Result<Vec<DeedEvent>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|e| e == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut events = Vec::new();
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.with_context(|| format!("line {}", i + 1))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        events.push(deed_core::decode_line(&line).with_context(|| format!("line {}", i + 1))?);
+    }
+    Ok(events)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let text = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&text)?)
+}
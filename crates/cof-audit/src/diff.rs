@@ -0,0 +1,55 @@
+//! Divergence point between two bundles of the same ledger.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::Bundle;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Divergence {
+    /// Zero-based position in the flattened event stream.
+    pub index: usize,
+    pub a_hash: String,
+    pub b_hash: String,
+    pub a_event_id: String,
+    pub b_event_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BundleDiff {
+    pub a_events: usize,
+    pub b_events: usize,
+    /// Number of leading events both bundles agree on.
+    pub common_prefix: usize,
+    /// First differing event, if the bundles disagree rather than one
+    /// simply extending the other.
+    pub divergence: Option<Divergence>,
+}
+
+impl BundleDiff {
+    pub fn diverged(&self) -> bool {
+        self.divergence.is_some()
+    }
+}
+
+pub fn diff_bundles(a: &Bundle, b: &Bundle) -> BundleDiff {
+    let ea = a.ordered_events();
+    let eb = b.ordered_events();
+    let common_prefix = ea
+        .iter()
+        .zip(eb.iter())
+        .take_while(|(x, y)| x.self_hash == y.self_hash)
+        .count();
+
+    let divergence = match (ea.get(common_prefix), eb.get(common_prefix)) {
+        (Some(x), Some(y)) => Some(Divergence {
+            index: common_prefix,
+            a_hash: x.self_hash.clone(),
+            b_hash: y.self_hash.clone(),
+            a_event_id: x.event_id.clone(),
+            b_event_id: y.event_id.clone(),
+        }),
+        _ => None,
+    };
+
+    BundleDiff { a_events: ea.len(), b_events: eb.len(), common_prefix, divergence }
+}
@@ -0,0 +1,16 @@
+//! Offline auditor for exported Church-of-FEAR ledger bundles.
+//!
+//! An auditor receives a directory of segments, signed transparency
+//! manifests, anchor receipts and attestations. This crate checks that
+//! bundle without a running node and without network access.
+
+pub mod bundle;
+pub mod diff;
+pub mod verify;
+
+pub use bundle::{AnchorReceipt, Attestation, Bundle, SegmentManifest};
+pub use diff::{diff_bundles, BundleDiff};
+pub use verify::{verify_bundle, Finding, Severity, Status, Verdict, VerifyOptions};
+
+/// Exit code for usage errors and unreadable bundle directories.
+pub const EXIT_ERROR: i32 = 3;
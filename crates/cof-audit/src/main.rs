@@ -0,0 +1,84 @@
+// cof-audit: verify an exported ledger bundle offline.
+//
+// Exit codes: 0 valid, 1 invalid, 2 incomplete bundle, 3 usage/IO error.
+// For `diff`: 0 when one bundle is a prefix of the other, 1 on divergence.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use cof_audit::{diff_bundles, verify_bundle, Bundle, VerifyOptions, EXIT_ERROR};
+
+#[derive(Parser)]
+#[command(name = "cof-audit", about = "Offline verifier for Church-of-FEAR ledger bundles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check chains, Merkle roots, manifest signatures, anchors and attestations.
+    VerifyBundle {
+        dir: PathBuf,
+        /// Hex ed25519 key allowed to sign manifests (repeatable). Without
+        /// one the bundle is at best incomplete.
+        #[arg(long = "trusted-signer")]
+        trusted_signers: Vec<String>,
+    },
+    /// Like verify-bundle, but print a JSON verdict on stdout and the human
+    /// summary on stderr.
+    Report {
+        dir: PathBuf,
+        #[arg(long = "trusted-signer")]
+        trusted_signers: Vec<String>,
+    },
+    /// Find the first event where two bundles diverge.
+    Diff { bundle_a: PathBuf, bundle_b: PathBuf },
+}
+
+fn code(c: i32) -> ExitCode {
+    ExitCode::from(c as u8)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(c) => code(c),
+        Err(e) => {
+            eprintln!("cof-audit: {:#}", e);
+            code(EXIT_ERROR)
+        }
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<i32> {
+    match cli.command {
+        Command::VerifyBundle { dir, trusted_signers } => {
+            let bundle = Bundle::load(&dir)?;
+            let verdict = verify_bundle(&bundle, &options(trusted_signers));
+            print!("{}", verdict.human_summary());
+            Ok(verdict.status.exit_code())
+        }
+        Command::Report { dir, trusted_signers } => {
+            let bundle = Bundle::load(&dir)?;
+            let verdict = verify_bundle(&bundle, &options(trusted_signers));
+            println!("{}", serde_json::to_string_pretty(&verdict)?);
+            eprint!("{}", verdict.human_summary());
+            Ok(verdict.status.exit_code())
+        }
+        Command::Diff { bundle_a, bundle_b } => {
+            let a = Bundle::load(&bundle_a)?;
+            let b = Bundle::load(&bundle_b)?;
+            let diff = diff_bundles(&a, &b);
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+            Ok(if diff.diverged() { 1 } else { 0 })
+        }
+    }
+}
+
+fn options(trusted_signers: Vec<String>) -> VerifyOptions {
+    VerifyOptions { trusted_signers: trusted_signers.into_iter().collect::<HashSet<_>>() }
+}
@@ -0,0 +1,282 @@
+//! Offline verification of a loaded Bundle.
+//!
+//! Every check produces a Finding instead of stopping at the first problem,
+//! so one run gives the auditor the full picture. A finding is either
+//! Invalid (the bundle contradicts itself) or Incomplete (something needed
+//! to decide is missing).
+
+use std::collections::{HashMap, HashSet};
+
+use deed_core::{hash_deed, merkle_root, DeedCoreError};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::bundle::{Bundle, SegmentManifest};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Invalid,
+    Incomplete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Bundle-relative location, e.g. "segments/000002.jsonl:14".
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Valid,
+    Invalid,
+    Incomplete,
+}
+
+impl Status {
+    /// Process exit code: 0 valid, 1 invalid, 2 incomplete.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Status::Valid => 0,
+            Status::Invalid => 1,
+            Status::Incomplete => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub segments: usize,
+    pub manifests: usize,
+    pub events: usize,
+    pub anchors: usize,
+    pub attestations: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verdict {
+    pub status: Status,
+    pub stats: Stats,
+    pub findings: Vec<Finding>,
+}
+
+impl Verdict {
+    /// Invalid dominates Incomplete: a bundle that is both tampered and
+    /// partial is reported as tampered.
+    fn from_findings(stats: Stats, findings: Vec<Finding>) -> Self {
+        let status = if findings.iter().any(|f| f.severity == Severity::Invalid) {
+            Status::Invalid
+        } else if findings.is_empty() {
+            Status::Valid
+        } else {
+            Status::Incomplete
+        };
+        Verdict { status, stats, findings }
+    }
+
+    pub fn human_summary(&self) -> String {
+        let mut out = format!(
+            "bundle {:?}: {} segments, {} manifests, {} events, {} anchors, {} attestations\n",
+            self.status,
+            self.stats.segments,
+            self.stats.manifests,
+            self.stats.events,
+            self.stats.anchors,
+            self.stats.attestations,
+        );
+        for f in &self.findings {
+            out.push_str(&format!("  [{:?}] {}: {}\n", f.severity, f.location, f.message));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    /// Hex ed25519 keys allowed to sign manifests. With none, signatures
+    /// are still checked, but a bundle anyone could have signed is never
+    /// Valid: it is reported Incomplete.
+    pub trusted_signers: HashSet<String>,
+}
+
+struct Checker {
+    findings: Vec<Finding>,
+}
+
+impl Checker {
+    fn invalid(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Invalid, location: location.into(), message: message.into() });
+    }
+
+    fn incomplete(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Incomplete, location: location.into(), message: message.into() });
+    }
+}
+
+pub fn verify_bundle(bundle: &Bundle, opts: &VerifyOptions) -> Verdict {
+    let mut c = Checker { findings: Vec::new() };
+
+    for (file, err) in &bundle.unreadable {
+        c.invalid(file.clone(), format!("unreadable: {}", err));
+    }
+
+    if bundle.manifests.is_empty() {
+        c.incomplete("manifests/", "bundle contains no manifests");
+    }
+    if opts.trusted_signers.is_empty() {
+        c.incomplete("manifests/", "no trusted signer given, so nothing shows who signed the manifests");
+    }
+
+    check_manifests(bundle, opts, &mut c);
+    check_segments(bundle, &mut c);
+    check_anchors(bundle, &mut c);
+    check_attestations(bundle, &mut c);
+
+    let stats = Stats {
+        segments: bundle.segments.len(),
+        manifests: bundle.manifests.len(),
+        events: bundle.segments.values().map(|s| s.events.len()).sum(),
+        anchors: bundle.anchors.len(),
+        attestations: bundle.attestations.len(),
+    };
+    Verdict::from_findings(stats, c.findings)
+}
+
+fn check_signature(m: &SegmentManifest) -> Result<(), String> {
+    let key_bytes: [u8; 32] = hex::decode(&m.signer)
+        .map_err(|e| format!("signer is not hex: {}", e))?
+        .try_into()
+        .map_err(|_| "signer is not a 32-byte key".to_string())?;
+    let sig_bytes: [u8; 64] = hex::decode(&m.signature)
+        .map_err(|e| format!("signature is not hex: {}", e))?
+        .try_into()
+        .map_err(|_| "signature is not 64 bytes".to_string())?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("bad signer key: {}", e))?;
+    key.verify(m.digest().as_bytes(), &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| "signature does not verify".to_string())
+}
+
+/// Every manifest must be signed by the key that signed the bundle's first
+/// one, so a valid signature by some other key cannot splice in a segment.
+fn check_manifests(bundle: &Bundle, opts: &VerifyOptions, c: &mut Checker) {
+    let mut prev: Option<&SegmentManifest> = None;
+    for m in &bundle.manifests {
+        let loc = format!("manifests/seq {}", m.seq);
+
+        if let Err(e) = check_signature(m) {
+            c.invalid(loc.clone(), e);
+        }
+        if !opts.trusted_signers.is_empty() && !opts.trusted_signers.contains(&m.signer) {
+            c.invalid(loc.clone(), format!("signer {} is not trusted", m.signer));
+        }
+        if let Some(first) = bundle.manifests.first().filter(|first| first.signer != m.signer) {
+            c.invalid(loc.clone(), format!("signer {} is not {}, who signed manifest seq {}", m.signer, first.signer, first.seq));
+        }
+
+        match prev {
+            None => {
+                if m.seq != 1 {
+                    c.incomplete(loc.clone(), format!("manifests before seq {} are missing", m.seq));
+                } else if !m.prev_manifest_hash.is_empty() {
+                    c.invalid(loc.clone(), "first manifest must have empty prev_manifest_hash");
+                }
+            }
+            Some(p) => {
+                if m.seq == p.seq {
+                    c.invalid(loc.clone(), "duplicate manifest seq");
+                } else if m.seq != p.seq + 1 {
+                    c.incomplete(loc.clone(), format!("manifests {}..{} are missing", p.seq + 1, m.seq - 1));
+                } else {
+                    if m.prev_manifest_hash != p.digest() {
+                        c.invalid(loc.clone(), "prev_manifest_hash does not match previous manifest");
+                    }
+                    if m.first_prev_hash != p.tip_hash {
+                        c.invalid(loc.clone(), "segment does not chain from previous segment tip");
+                    }
+                }
+            }
+        }
+        prev = Some(m);
+    }
+}
+
+fn check_segments(bundle: &Bundle, c: &mut Checker) {
+    let manifested: HashSet<&str> = bundle.manifests.iter().map(|m| m.segment.as_str()).collect();
+    for name in bundle.segments.keys() {
+        if !manifested.contains(name.as_str()) {
+            c.incomplete(format!("segments/{}", name), "segment has no manifest");
+        }
+    }
+
+    for m in &bundle.manifests {
+        let Some(segment) = bundle.segments.get(&m.segment) else {
+            c.incomplete(format!("segments/{}", m.segment), format!("segment for manifest seq {} is missing", m.seq));
+            continue;
+        };
+        let seg_loc = format!("segments/{}", segment.file);
+
+        let mut prev_hash = m.first_prev_hash.clone();
+        let mut leaves = Vec::with_capacity(segment.events.len());
+        for (i, deed) in segment.events.iter().enumerate() {
+            let loc = format!("{}:{}", seg_loc, i + 1);
+            if let Err(e) = DeedCoreError::check_rule(deed, m.hash_rule) {
+                c.invalid(loc.clone(), e.to_string());
+            } else {
+                let computed = hash_deed(deed);
+                if deed.self_hash != computed {
+                    c.invalid(loc.clone(), format!("self_hash {} does not match recomputed {}", deed.self_hash, computed));
+                }
+            }
+            if deed.prev_hash != prev_hash {
+                c.invalid(loc, "prev_hash breaks the chain");
+            }
+            prev_hash = deed.self_hash.clone();
+            leaves.push(deed.self_hash.clone());
+        }
+
+        if segment.events.len() as u64 != m.event_count {
+            c.invalid(seg_loc.clone(), format!("manifest claims {} events, found {}", m.event_count, segment.events.len()));
+        }
+        if !segment.events.is_empty() && prev_hash != m.tip_hash {
+            c.invalid(seg_loc.clone(), "last event does not match manifest tip_hash");
+        }
+        if merkle_root(leaves) != m.merkle_root {
+            c.invalid(seg_loc, "merkle root does not match manifest");
+        }
+    }
+}
+
+fn check_anchors(bundle: &Bundle, c: &mut Checker) {
+    let by_seq: HashMap<u64, &SegmentManifest> = bundle.manifests.iter().map(|m| (m.seq, m)).collect();
+    for (file, anchor) in &bundle.anchors {
+        let loc = format!("anchors/{}", file);
+        match by_seq.get(&anchor.manifest_seq) {
+            None => c.incomplete(loc, format!("anchors manifest seq {} which is not in the bundle", anchor.manifest_seq)),
+            Some(m) if m.digest() != anchor.digest => {
+                c.invalid(loc, format!("anchored digest does not match manifest seq {}", m.seq))
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn check_attestations(bundle: &Bundle, c: &mut Checker) {
+    let events = bundle.ordered_events();
+    for (file, att) in &bundle.attestations {
+        let loc = format!("attestations/{}", file);
+        let Some(tip_index) = events.iter().position(|e| e.self_hash == att.tip_hash) else {
+            c.incomplete(loc, format!("tip {} is not in the bundle", att.tip_hash));
+            continue;
+        };
+        let count = events[..=tip_index].iter().filter(|e| e.actor_id == att.actor_id).count() as u64;
+        if count != att.deed_count {
+            c.invalid(
+                loc,
+                format!("claims {} deeds for {} at tip, ledger has {}", att.deed_count, att.actor_id, count),
+            );
+        }
+    }
+}
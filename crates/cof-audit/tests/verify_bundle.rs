@@ -0,0 +1,242 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use cof_audit::{diff_bundles, verify_bundle, AnchorReceipt, Attestation, Bundle, SegmentManifest, Status, VerifyOptions};
+use deed_core::{merkle_root, DeedEvent, HashRule};
+use ed25519_dalek::{Signer, SigningKey};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+
+fn event(prev_hash: &str, n: usize, actor: &str, rule: HashRule) -> DeedEvent {
+    let mut deed = DeedEvent {
+        event_id: format!("evt-{}", n),
+        timestamp: (1_700_000_000 + n as i64) * 1_000,
+        prev_hash: prev_hash.to_string(),
+        actor_id: actor.to_string(),
+        deed_type: "ecological_sustainability".into(),
+        tags: vec!["tree-of-life".into()],
+        context_json: json!({}),
+        hash_rule: rule,
+        ..DeedEvent::default()
+    };
+    deed.self_hash = deed.compute_self_hash();
+    deed
+}
+
+/// A canonical deed as an enveloped line; a moral-ledger deed bare, as
+/// those ledgers write it.
+fn line(deed: &DeedEvent) -> String {
+    match deed.hash_rule {
+        HashRule::Canonical => deed_core::encode_line(deed),
+        _ => deed_core::wire::serialize(deed, serde_json::value::Serializer).unwrap().to_string(),
+    }
+}
+
+fn signer_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+/// The key `write_bundle` signs with, trusted.
+fn trusted() -> VerifyOptions {
+    VerifyOptions { trusted_signers: [signer_hex(&SigningKey::from_bytes(&[7u8; 32]))].into() }
+}
+
+fn sign(key: &SigningKey, m: &mut SegmentManifest) {
+    m.signature = hex::encode(key.sign(m.digest().as_bytes()).to_bytes());
+}
+
+fn write_valid_bundle(root: &Path) {
+    write_bundle(root, HashRule::Canonical);
+}
+
+/// Two segments of three events each, sealed under `rule`; the second one
+/// gzip-compressed.
+fn write_bundle(root: &Path, rule: HashRule) {
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    for d in ["segments", "manifests", "anchors", "attestations"] {
+        fs::create_dir_all(root.join(d)).unwrap();
+    }
+
+    let mut prev = String::new();
+    let mut prev_manifest = String::new();
+    let mut n = 0;
+    for seq in 1..=2u64 {
+        let first_prev = prev.clone();
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            let e = event(&prev, n, if n % 2 == 0 { "alice" } else { "bob" }, rule);
+            prev = e.self_hash.clone();
+            events.push(e);
+            n += 1;
+        }
+        let body: String = events.iter().map(|e| format!("{}\n", line(e))).collect();
+        let segment = if seq == 2 {
+            let name = format!("{:06}.jsonl.gz", seq);
+            let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+            gz.write_all(body.as_bytes()).unwrap();
+            fs::write(root.join("segments").join(&name), gz.finish().unwrap()).unwrap();
+            name
+        } else {
+            let name = format!("{:06}.jsonl", seq);
+            fs::write(root.join("segments").join(&name), body).unwrap();
+            name
+        };
+
+        let leaves: Vec<String> = events.iter().map(|e| e.self_hash.clone()).collect();
+        let mut m = SegmentManifest {
+            seq,
+            segment,
+            event_count: events.len() as u64,
+            first_prev_hash: first_prev,
+            tip_hash: prev.clone(),
+            merkle_root: merkle_root(leaves),
+            hash_rule: rule,
+            prev_manifest_hash: prev_manifest.clone(),
+            signer: signer_hex(&key),
+            signature: String::new(),
+        };
+        sign(&key, &mut m);
+        prev_manifest = m.digest();
+        fs::write(root.join("manifests").join(format!("{:06}.json", seq)), serde_json::to_string(&m).unwrap()).unwrap();
+
+        let anchor = AnchorReceipt { manifest_seq: seq, digest: m.digest(), anchor: "test".into(), proof: Value::Null };
+        fs::write(root.join("anchors").join(format!("{:06}.json", seq)), serde_json::to_string(&anchor).unwrap()).unwrap();
+    }
+
+    let att = Attestation { id: "att-1".into(), tip_hash: prev, actor_id: "alice".into(), deed_count: 3 };
+    fs::write(root.join("attestations").join("att-1.json"), serde_json::to_string(&att).unwrap()).unwrap();
+}
+
+fn run_cli(args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_cof-audit")).args(args).output().unwrap().status.code().unwrap()
+}
+
+/// `cof-audit <command> <dir> --trusted-signer <write_bundle's key>`.
+fn run_trusted(command: &str, dir: &Path) -> i32 {
+    let signer = signer_hex(&SigningKey::from_bytes(&[7u8; 32]));
+    run_cli(&[command, dir.to_str().unwrap(), "--trusted-signer", &signer])
+}
+
+fn verdict(root: &Path) -> cof_audit::Verdict {
+    verify_bundle(&Bundle::load(root).unwrap(), &trusted())
+}
+
+#[test]
+fn valid_bundle_passes() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Valid, "{}", v.human_summary());
+    assert_eq!(v.stats.events, 6);
+    assert_eq!(run_trusted("verify-bundle", dir.path()), 0);
+}
+
+#[test]
+fn without_a_trusted_signer_a_bundle_is_never_valid() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    let v = verify_bundle(&Bundle::load(dir.path()).unwrap(), &VerifyOptions::default());
+    assert_eq!(v.status, Status::Incomplete, "{}", v.human_summary());
+    assert!(v.findings.iter().all(|f| f.message.contains("trusted signer")));
+    assert_eq!(run_cli(&["verify-bundle", dir.path().to_str().unwrap()]), 2);
+}
+
+#[test]
+fn moral_ledger_segments_verify_under_their_declared_rule() {
+    let dir = tempfile::tempdir().unwrap();
+    write_bundle(dir.path(), HashRule::MoralLedgerV1);
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Valid, "{}", v.human_summary());
+
+    // The same deeds declared canonical: the manifest no longer matches them.
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let path = dir.path().join("manifests/000001.json");
+    let mut m: SegmentManifest = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    m.hash_rule = HashRule::Canonical;
+    sign(&key, &mut m);
+    fs::write(&path, serde_json::to_string(&m).unwrap()).unwrap();
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Invalid);
+    assert!(v.findings.iter().any(|f| f.location == "segments/000001.jsonl:1" && f.message.contains("MoralLedgerV1")));
+}
+
+#[test]
+fn tampered_event_is_invalid() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    let path = dir.path().join("segments/000001.jsonl");
+    let text = fs::read_to_string(&path).unwrap().replace("\"actor_id\":\"bob\"", "\"actor_id\":\"mallory\"");
+    fs::write(&path, text).unwrap();
+
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Invalid);
+    assert!(v.findings.iter().any(|f| f.location == "segments/000001.jsonl:2"));
+    assert_eq!(run_trusted("verify-bundle", dir.path()), 1);
+}
+
+#[test]
+fn missing_segment_is_incomplete() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    fs::remove_file(dir.path().join("segments/000002.jsonl.gz")).unwrap();
+
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Incomplete, "{}", v.human_summary());
+    assert_eq!(run_trusted("verify-bundle", dir.path()), 2);
+}
+
+#[test]
+fn forged_manifest_signature_is_invalid() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    let path = dir.path().join("manifests/000002.json");
+    let mut m: SegmentManifest = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let forger = SigningKey::from_bytes(&[9u8; 32]);
+    m.signer = signer_hex(&forger);
+    sign(&forger, &mut m);
+    fs::write(&path, serde_json::to_string(&m).unwrap()).unwrap();
+
+    // The signature itself verifies; the signer is what gives it away, even
+    // with no trusted signer to compare against.
+    let v = verify_bundle(&Bundle::load(dir.path()).unwrap(), &VerifyOptions::default());
+    assert_ne!(v.status, Status::Valid);
+    assert!(v.findings.iter().any(|f| f.location == "manifests/seq 2" && f.message.contains("who signed manifest seq 1")));
+    assert_eq!(run_cli(&["report", dir.path().to_str().unwrap()]), 1);
+
+    let v = verdict(dir.path());
+    assert_eq!(v.status, Status::Invalid);
+    assert!(v.findings.iter().any(|f| f.message.contains("is not trusted")));
+}
+
+#[test]
+fn untrusted_signer_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    write_valid_bundle(dir.path());
+    let other = hex::encode(SigningKey::from_bytes(&[9u8; 32]).verifying_key().to_bytes());
+    let code = run_cli(&["verify-bundle", dir.path().to_str().unwrap(), "--trusted-signer", &other]);
+    assert_eq!(code, 1);
+}
+
+#[test]
+fn diff_reports_divergence_point() {
+    let a = tempfile::tempdir().unwrap();
+    let b = tempfile::tempdir().unwrap();
+    write_valid_bundle(a.path());
+    write_valid_bundle(b.path());
+    assert!(!diff_bundles(&Bundle::load(a.path()).unwrap(), &Bundle::load(b.path()).unwrap()).diverged());
+
+    let path = b.path().join("segments/000001.jsonl");
+    let mut lines: Vec<DeedEvent> =
+        fs::read_to_string(&path).unwrap().lines().map(|l| deed_core::decode_line(l).unwrap()).collect();
+    let prev = lines[1].self_hash.clone();
+    lines[2] = event(&prev, 99, "carol", HashRule::Canonical);
+    fs::write(&path, lines.iter().map(|e| format!("{}\n", line(e))).collect::<String>()).unwrap();
+
+    let d = diff_bundles(&Bundle::load(a.path()).unwrap(), &Bundle::load(b.path()).unwrap());
+    assert_eq!(d.common_prefix, 2);
+    assert_eq!(d.divergence.unwrap().b_event_id, "evt-99");
+    assert_eq!(run_cli(&["diff", a.path().to_str().unwrap(), b.path().to_str().unwrap()]), 1);
+}
@@ -0,0 +1,132 @@
+//! Bare deed lines from before the envelope.
+//!
+//! - the sovereignty core's log carries `node`;
+//! - the account ledger left `self_hash` out of its lines;
+//! - the moral ledgers stamped seconds, with `domain` and `ext` when set.
+//!
+//! A bare line already in the canonical shape (a millisecond timestamp or
+//! a `hash_rule`) reads as it is. An account-ledger line's timestamp is
+//! read as seconds below `SECONDS_CUTOFF`, as that ledger always did.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{hash_deed, DeedCoreError, DeedEnvelope, DeedEvent, HashRule, SCHEMA_VERSION};
+
+/// Below this a bare timestamp is Unix seconds: as milliseconds it would
+/// fall in early 1973, as seconds in the year 5138.
+const SECONDS_CUTOFF: i64 = 100_000_000_000;
+
+#[derive(Deserialize)]
+struct SovereigntyLine {
+    event_id: String,
+    timestamp: i64,
+    #[serde(default)]
+    time_unit: Option<String>,
+    prev_hash: String,
+    self_hash: String,
+    actor_id: String,
+    node: Value,
+    deed_type: String,
+    context_json: Value,
+    ethics_flags: Vec<String>,
+    life_harm_flag: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MoralLedgerLine {
+    event_id: String,
+    timestamp: i64,
+    prev_hash: String,
+    self_hash: String,
+    actor_id: String,
+    target_ids: Vec<String>,
+    deed_type: String,
+    tags: Vec<String>,
+    context_json: Value,
+    ethics_flags: Vec<String>,
+    life_harm_flag: bool,
+    #[serde(default)]
+    domain: Option<Value>,
+    #[serde(default)]
+    ext: Map<String, Value>,
+}
+
+/// The deed on one JSONL line, enveloped or in any earlier bare shape.
+pub fn decode_line(line: &str) -> Result<DeedEvent, DeedCoreError> {
+    decode_value(serde_json::from_str(line)?)
+}
+
+/// `decode_line` for a deed already parsed, such as one inside an RPC message.
+pub fn decode_value(value: Value) -> Result<DeedEvent, DeedCoreError> {
+    if let Some(v) = value.get("v") {
+        let v = v.as_u64().unwrap_or(u64::MAX);
+        if v > u64::from(SCHEMA_VERSION) {
+            return Err(DeedCoreError::UnsupportedVersion(u32::try_from(v).unwrap_or(u32::MAX)));
+        }
+        return Ok(serde_json::from_value::<DeedEnvelope>(value)?.deed);
+    }
+    if value.get("node").is_some() {
+        return Ok(from_sovereignty(serde_json::from_value(value)?));
+    }
+    if value.get("self_hash").is_none() {
+        let mut deed: DeedEvent = serde_json::from_value(value)?;
+        if deed.timestamp.abs() < SECONDS_CUTOFF {
+            deed.timestamp = deed.timestamp.saturating_mul(1_000);
+        }
+        deed.self_hash = hash_deed(&deed);
+        return Ok(deed);
+    }
+    let seconds = value.get("timestamp").and_then(Value::as_i64).is_some_and(|t| t.abs() < SECONDS_CUTOFF);
+    if value.get("hash_rule").is_some() || !seconds {
+        return Ok(serde_json::from_value(value)?);
+    }
+    Ok(from_moral_ledger(serde_json::from_value(value)?))
+}
+
+fn from_sovereignty(line: SovereigntyLine) -> DeedEvent {
+    let mut ext = Map::new();
+    ext.insert("node".to_string(), line.node);
+    let seconds = line.time_unit.is_none() || line.time_unit.as_deref() == Some("seconds");
+    if seconds {
+        ext.insert("time_unit".to_string(), Value::from("seconds"));
+    }
+    DeedEvent {
+        event_id: line.event_id,
+        timestamp: if seconds { line.timestamp.saturating_mul(1_000) } else { line.timestamp },
+        prev_hash: line.prev_hash,
+        self_hash: line.self_hash,
+        actor_id: line.actor_id,
+        target_ids: Vec::new(),
+        deed_type: line.deed_type,
+        tags: Vec::new(),
+        context_json: line.context_json,
+        ethics_flags: line.ethics_flags,
+        life_harm_flag: line.life_harm_flag,
+        ext,
+        hash_rule: HashRule::SovereigntyV1,
+    }
+}
+
+fn from_moral_ledger(line: MoralLedgerLine) -> DeedEvent {
+    let mut ext = line.ext;
+    if let Some(domain) = line.domain {
+        ext.insert("domain".to_string(), domain);
+    }
+    DeedEvent {
+        event_id: line.event_id,
+        timestamp: line.timestamp.saturating_mul(1_000),
+        prev_hash: line.prev_hash,
+        self_hash: line.self_hash,
+        actor_id: line.actor_id,
+        target_ids: line.target_ids,
+        deed_type: line.deed_type,
+        tags: line.tags,
+        context_json: line.context_json,
+        ethics_flags: line.ethics_flags,
+        life_harm_flag: line.life_harm_flag,
+        ext,
+        hash_rule: HashRule::MoralLedgerV1,
+    }
+}
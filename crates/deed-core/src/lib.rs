@@ -0,0 +1,259 @@
+//! The canonical deed.
+//!
+//! Each ledger in the tree grew its own `DeedEvent`: the moral ledgers
+//! stamp seconds and hash the deed with `self_hash` blanked, the account
+//! ledger stamps milliseconds and hashes it without `self_hash`, and the
+//! sovereignty core hashes a projection carrying its graph node. This
+//! crate holds one shape all of them convert into, without losing a deed's
+//! hash on the way:
+//!
+//! - `DeedEvent` stamps Unix milliseconds. Fields only some ledgers carry
+//!   (`domain`, `node`, a moral-ledger `ext`) go under `ext`.
+//! - `hash_rule` records how `self_hash` was computed, and `hash_deed`
+//!   recomputes it that way, so a deed converted from another ledger
+//!   still verifies. Deeds sealed here use `HashRule::Canonical`.
+//! - On disk and on the wire a deed is a `DeedEnvelope` line carrying
+//!   `SCHEMA_VERSION`. `decode_line` also reads the bare lines each ledger
+//!   wrote before, so existing JSONL files still load. Inside RPC
+//!   messages, `wire` keeps moral-ledger deeds in their bare shape.
+//! - The ledgers anchor a Merkle root over their deeds' `self_hash`es;
+//!   `merkle_levels`, `merkle_proof` and `verify_merkle_proof` are the one
+//!   tree they all build, and `merkle_root` its root, `EMPTY_MERKLE_ROOT`
+//!   while there are no deeds.
+
+mod legacy;
+mod merkle;
+pub mod wire;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+pub use legacy::{decode_line, decode_value};
+pub use merkle::{
+    levels_root, merkle_levels, merkle_parent, merkle_proof, merkle_root, verify_merkle_proof, MerkleStep,
+    EMPTY_MERKLE_ROOT,
+};
+
+/// Envelope version this crate writes. Version 1 is the first enveloped
+/// format; bare lines predate it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Tags that make an unflagged, harmless deed a good deed.
+pub const GOOD_DEED_TAGS: [&str; 3] = ["ecological_sustainability", "homelessness_relief", "math_science_education"];
+
+/// How a deed's `self_hash` was computed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HashRule {
+    /// SHA-256 of every field but `self_hash` and `hash_rule`, the
+    /// timestamp in milliseconds and `ext` only when set.
+    #[default]
+    Canonical,
+    /// The moral ledgers (church-of-fear's `TokenLedger`, church_of_fear_ledger's
+    /// `MoralLedger`): the timestamp in seconds, `self_hash` blanked to "",
+    /// then `ext.domain` as `domain` and the rest of `ext` as `ext` when set.
+    MoralLedgerV1,
+    /// The sovereignty core's deed log: `ext.node` in place of targets and
+    /// tags, the timestamp in seconds when `ext.time_unit` is "seconds".
+    SovereigntyV1,
+}
+
+impl HashRule {
+    pub fn is_canonical(&self) -> bool {
+        *self == HashRule::Canonical
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DeedEvent {
+    pub event_id: String,
+    /// Unix milliseconds.
+    pub timestamp: i64,
+    pub prev_hash: String,
+    #[serde(default)]
+    pub self_hash: String,
+    pub actor_id: String,
+    pub target_ids: Vec<String>,
+    pub deed_type: String,
+    pub tags: Vec<String>,
+    pub context_json: Value,
+    pub ethics_flags: Vec<String>,
+    pub life_harm_flag: bool,
+    /// Fields a source ledger carries beyond these, covered by the hash.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub ext: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "HashRule::is_canonical")]
+    pub hash_rule: HashRule,
+}
+
+impl DeedEvent {
+    /// `self_hash` as `hash_rule` computes it.
+    pub fn compute_self_hash(&self) -> String {
+        hash_deed(self)
+    }
+
+    pub fn verify_hash(&self) -> bool {
+        self.compute_self_hash() == self.self_hash
+    }
+
+    /// Link to `prev_hash` and reseal under the canonical rule.
+    pub fn seal(&mut self, prev_hash: &str) {
+        self.prev_hash = prev_hash.to_string();
+        self.hash_rule = HashRule::Canonical;
+        self.self_hash = self.compute_self_hash();
+    }
+
+    pub fn is_good_deed(&self) -> bool {
+        !self.life_harm_flag
+            && self.ethics_flags.is_empty()
+            && self.tags.iter().any(|t| GOOD_DEED_TAGS.contains(&t.as_str()))
+    }
+}
+
+#[derive(Serialize)]
+struct CanonicalHashable<'a> {
+    event_id: &'a str,
+    timestamp: i64,
+    prev_hash: &'a str,
+    actor_id: &'a str,
+    target_ids: &'a [String],
+    deed_type: &'a str,
+    tags: &'a [String],
+    context_json: &'a Value,
+    ethics_flags: &'a [String],
+    life_harm_flag: bool,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    ext: &'a Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct MoralLedgerHashable<'a> {
+    event_id: &'a str,
+    timestamp: i64,
+    prev_hash: &'a str,
+    self_hash: &'a str,
+    actor_id: &'a str,
+    target_ids: &'a [String],
+    deed_type: &'a str,
+    tags: &'a [String],
+    context_json: &'a Value,
+    ethics_flags: &'a [String],
+    life_harm_flag: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    ext: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct SovereigntyHashable<'a> {
+    event_id: &'a str,
+    timestamp: i64,
+    prev_hash: &'a str,
+    actor_id: &'a str,
+    node: &'a Value,
+    deed_type: &'a str,
+    context_json: &'a Value,
+    ethics_flags: &'a [String],
+    life_harm_flag: bool,
+}
+
+/// SHA-256 of `deed` under its `hash_rule`, hex-encoded.
+pub fn hash_deed(deed: &DeedEvent) -> String {
+    let serialized = match deed.hash_rule {
+        HashRule::Canonical => serde_json::to_string(&CanonicalHashable {
+            event_id: &deed.event_id,
+            timestamp: deed.timestamp,
+            prev_hash: &deed.prev_hash,
+            actor_id: &deed.actor_id,
+            target_ids: &deed.target_ids,
+            deed_type: &deed.deed_type,
+            tags: &deed.tags,
+            context_json: &deed.context_json,
+            ethics_flags: &deed.ethics_flags,
+            life_harm_flag: deed.life_harm_flag,
+            ext: &deed.ext,
+        }),
+        HashRule::MoralLedgerV1 => {
+            let mut ext = deed.ext.clone();
+            let domain = ext.remove("domain");
+            serde_json::to_string(&MoralLedgerHashable {
+                event_id: &deed.event_id,
+                timestamp: deed.timestamp.div_euclid(1_000),
+                prev_hash: &deed.prev_hash,
+                self_hash: "",
+                actor_id: &deed.actor_id,
+                target_ids: &deed.target_ids,
+                deed_type: &deed.deed_type,
+                tags: &deed.tags,
+                context_json: &deed.context_json,
+                ethics_flags: &deed.ethics_flags,
+                life_harm_flag: deed.life_harm_flag,
+                domain: domain.as_ref(),
+                ext,
+            })
+        }
+        HashRule::SovereigntyV1 => {
+            let seconds = deed.ext.get("time_unit").and_then(Value::as_str) == Some("seconds");
+            serde_json::to_string(&SovereigntyHashable {
+                event_id: &deed.event_id,
+                timestamp: if seconds { deed.timestamp / 1_000 } else { deed.timestamp },
+                prev_hash: &deed.prev_hash,
+                actor_id: &deed.actor_id,
+                node: deed.ext.get("node").unwrap_or(&Value::Null),
+                deed_type: &deed.deed_type,
+                context_json: &deed.context_json,
+                ethics_flags: &deed.ethics_flags,
+                life_harm_flag: deed.life_harm_flag,
+            })
+        }
+    }
+    .expect("deed fields serialize");
+    let mut hasher = Sha256::new();
+    hasher.update(serialized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// One deed as written to a JSONL file or sent between nodes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeedEnvelope {
+    pub v: u32,
+    pub deed: DeedEvent,
+}
+
+impl DeedEnvelope {
+    pub fn new(deed: DeedEvent) -> Self {
+        Self { v: SCHEMA_VERSION, deed }
+    }
+}
+
+/// `deed` as one enveloped JSONL line, without the newline.
+pub fn encode_line(deed: &DeedEvent) -> String {
+    serde_json::to_string(&DeedEnvelope::new(deed.clone())).expect("deed fields serialize")
+}
+
+#[derive(Error, Debug)]
+pub enum DeedCoreError {
+    #[error("deed line: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("deed schema version {0} is newer than {SCHEMA_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("deed {event_id} is sealed under {found:?}, not {expected:?}")]
+    HashRule { event_id: String, expected: HashRule, found: HashRule },
+    #[error("deed {event_id} does not fit: {reason}")]
+    Shape { event_id: String, reason: String },
+}
+
+impl DeedCoreError {
+    /// Refuses `deed` unless it was sealed under `expected`: converting it
+    /// into a ledger that hashes another way would break its `self_hash`.
+    pub fn check_rule(deed: &DeedEvent, expected: HashRule) -> Result<(), Self> {
+        if deed.hash_rule == expected {
+            Ok(())
+        } else {
+            Err(DeedCoreError::HashRule { event_id: deed.event_id.clone(), expected, found: deed.hash_rule })
+        }
+    }
+}
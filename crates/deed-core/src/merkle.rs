@@ -0,0 +1,73 @@
+//! The SHA-256 Merkle tree the ledgers anchor. Each parent is the SHA-256
+//! of its children's hex hashes concatenated, and a level with an odd count
+//! pairs its last hash with itself.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// One level of a Merkle proof: the hash paired with the running one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MerkleStep {
+    pub sibling: String,
+    /// Whether `sibling` is the left child of the pair.
+    pub sibling_on_left: bool,
+}
+
+/// The root of a tree with no leaves: the all-zero hash, as a fresh
+/// chain's first `prev_hash` is.
+pub const EMPTY_MERKLE_ROOT: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// SHA-256 of `left` and `right` concatenated, hex-encoded.
+pub fn merkle_parent(left: &str, right: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Every level of the tree over `leaves`, the leaves first and the root
+/// alone last. Empty when there are no leaves.
+pub fn merkle_levels(leaves: Vec<String>) -> Vec<Vec<String>> {
+    if leaves.is_empty() {
+        return Vec::new();
+    }
+    let mut levels = vec![leaves];
+    while let Some(level) = levels.last().filter(|l| l.len() > 1) {
+        let next = level.chunks(2).map(|pair| merkle_parent(&pair[0], pair.get(1).unwrap_or(&pair[0]))).collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// The root of the tree over `leaves`, or `EMPTY_MERKLE_ROOT` without any.
+pub fn merkle_root(leaves: Vec<String>) -> String {
+    levels_root(&merkle_levels(leaves))
+}
+
+/// The root `merkle_levels` ends with, or `EMPTY_MERKLE_ROOT` for no levels.
+pub fn levels_root(levels: &[Vec<String>]) -> String {
+    levels.last().map_or_else(|| EMPTY_MERKLE_ROOT.to_string(), |top| top[0].clone())
+}
+
+/// The sibling path from leaf `index` of `levels` up to the root.
+pub fn merkle_proof(levels: &[Vec<String>], mut index: usize) -> Vec<MerkleStep> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len().saturating_sub(1)] {
+        let sibling = level.get(index ^ 1).unwrap_or(&level[index]).clone();
+        proof.push(MerkleStep { sibling, sibling_on_left: index % 2 == 1 });
+        index /= 2;
+    }
+    proof
+}
+
+/// Whether folding `leaf` up through `proof` arrives at `root`.
+pub fn verify_merkle_proof(root: &str, leaf: &str, proof: &[MerkleStep]) -> bool {
+    let folded = proof.iter().fold(leaf.to_string(), |acc, step| {
+        if step.sibling_on_left {
+            merkle_parent(&step.sibling, &acc)
+        } else {
+            merkle_parent(&acc, &step.sibling)
+        }
+    });
+    folded == root
+}
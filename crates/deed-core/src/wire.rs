@@ -0,0 +1,85 @@
+//! Deeds inside other JSON messages, such as RPC results, for
+//! `#[serde(with = "deed_core::wire")]` (`deed_core::wire::vec` for a list).
+//!
+//! A deed sealed under `HashRule::MoralLedgerV1` is written bare, in the
+//! shape and Unix seconds the moral ledgers always sent, so nodes from
+//! before the shared deed still read it. Any other deed is written as a
+//! `DeedEnvelope`, whose `v` marks its timestamp as milliseconds. Reading
+//! takes every shape `decode_line` does.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{decode_value, DeedEnvelope, DeedEvent, HashRule};
+
+/// A moral-ledger deed as those ledgers serialize it.
+#[derive(Serialize)]
+struct MoralLedgerWire<'a> {
+    event_id: &'a str,
+    timestamp: i64,
+    prev_hash: &'a str,
+    self_hash: &'a str,
+    actor_id: &'a str,
+    target_ids: &'a [String],
+    deed_type: &'a str,
+    tags: &'a [String],
+    context_json: &'a Value,
+    ethics_flags: &'a [String],
+    life_harm_flag: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    ext: Map<String, Value>,
+}
+
+pub fn serialize<S: Serializer>(deed: &DeedEvent, serializer: S) -> Result<S::Ok, S::Error> {
+    if deed.hash_rule != HashRule::MoralLedgerV1 {
+        return DeedEnvelope::new(deed.clone()).serialize(serializer);
+    }
+    let mut ext = deed.ext.clone();
+    ext.remove("domain");
+    MoralLedgerWire {
+        event_id: &deed.event_id,
+        timestamp: deed.timestamp.div_euclid(1_000),
+        prev_hash: &deed.prev_hash,
+        self_hash: &deed.self_hash,
+        actor_id: &deed.actor_id,
+        target_ids: &deed.target_ids,
+        deed_type: &deed.deed_type,
+        tags: &deed.tags,
+        context_json: &deed.context_json,
+        ethics_flags: &deed.ethics_flags,
+        life_harm_flag: deed.life_harm_flag,
+        domain: deed.ext.get("domain"),
+        ext,
+    }
+    .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DeedEvent, D::Error> {
+    decode_value(Value::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+pub mod vec {
+    use super::*;
+
+    struct Wire<'a>(&'a DeedEvent);
+
+    impl Serialize for Wire<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(deeds: &[DeedEvent], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(deeds.iter().map(Wire))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<DeedEvent>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(|value| decode_value(value).map_err(D::Error::custom))
+            .collect()
+    }
+}
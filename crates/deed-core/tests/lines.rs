@@ -0,0 +1,153 @@
+use deed_core::{decode_line, encode_line, hash_deed, DeedCoreError, DeedEvent, HashRule, SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+fn sha256(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn deed() -> DeedEvent {
+    let mut deed = DeedEvent {
+        event_id: "e1".to_string(),
+        timestamp: 1_700_000_000_123,
+        actor_id: "alice".to_string(),
+        deed_type: "tree_planting".to_string(),
+        tags: vec!["ecological_sustainability".to_string()],
+        context_json: json!({ "trees": 3 }),
+        ..Default::default()
+    };
+    deed.seal("0".repeat(64).as_str());
+    deed
+}
+
+#[test]
+fn an_enveloped_deed_round_trips() {
+    let deed = deed();
+    assert!(deed.verify_hash());
+    assert!(deed.is_good_deed());
+
+    let line = encode_line(&deed);
+    let value: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["v"], SCHEMA_VERSION);
+    assert!(value["deed"].get("hash_rule").is_none());
+    assert_eq!(decode_line(&line).unwrap(), deed);
+
+    let newer = json!({ "v": SCHEMA_VERSION + 1, "deed": { "shape": "unknown" } }).to_string();
+    assert!(matches!(decode_line(&newer), Err(DeedCoreError::UnsupportedVersion(v)) if v == SCHEMA_VERSION + 1));
+    assert!(matches!(decode_line("{ not json"), Err(DeedCoreError::Json(_))));
+}
+
+#[test]
+fn the_canonical_hash_covers_everything_but_self_hash() {
+    let mut deed = deed();
+    let expected = format!(
+        concat!(
+            r#"{{"event_id":"e1","timestamp":1700000000123,"prev_hash":"{}","actor_id":"alice","target_ids":[],"#,
+            r#""deed_type":"tree_planting","tags":["ecological_sustainability"],"context_json":{{"trees":3}},"#,
+            r#""ethics_flags":[],"life_harm_flag":false}}"#
+        ),
+        "0".repeat(64)
+    );
+    assert_eq!(deed.self_hash, sha256(&expected));
+
+    deed.ext.insert("node".to_string(), json!("Did"));
+    assert!(!deed.verify_hash());
+    deed.seal(&deed.prev_hash.clone());
+    assert!(deed.verify_hash());
+}
+
+#[test]
+fn account_ledger_lines_without_self_hash_read_as_seconds_when_small() {
+    let mut line = serde_json::to_value(deed()).unwrap();
+    line.as_object_mut().unwrap().remove("self_hash");
+    line["timestamp"] = json!(1_700_000_000);
+    let read = decode_line(&line.to_string()).unwrap();
+    assert_eq!((read.timestamp, read.hash_rule), (1_700_000_000_000, HashRule::Canonical));
+    assert!(read.verify_hash());
+}
+
+#[test]
+fn moral_ledger_lines_keep_their_hash() {
+    // As the moral ledger serializes and hashes a deed: seconds, self_hash blank.
+    let blank = format!(
+        concat!(
+            r#"{{"event_id":"e2","timestamp":1700000000,"prev_hash":"{}","self_hash":"","actor_id":"bob","#,
+            r#""target_ids":["river"],"deed_type":"watershed_cleanup","tags":[],"context_json":{{"kg":12}},"#,
+            r#""ethics_flags":[],"life_harm_flag":false,"domain":{{"simulation":{{"run_id":"run-1"}}}}}}"#
+        ),
+        "0".repeat(64)
+    );
+    let hash = sha256(&blank);
+    let line = blank.replacen(r#""self_hash":"""#, &format!(r#""self_hash":"{}""#, hash), 1);
+
+    let read = decode_line(&line).unwrap();
+    assert_eq!(read.hash_rule, HashRule::MoralLedgerV1);
+    assert_eq!(read.timestamp, 1_700_000_000_000);
+    assert_eq!(read.ext["domain"]["simulation"]["run_id"], "run-1");
+    assert_eq!(hash_deed(&read), hash);
+
+    // The rule travels with the deed through the envelope.
+    let again = decode_line(&encode_line(&read)).unwrap();
+    assert_eq!(again, read);
+    assert!(again.verify_hash());
+    assert!(matches!(
+        DeedCoreError::check_rule(&again, HashRule::Canonical),
+        Err(DeedCoreError::HashRule { found: HashRule::MoralLedgerV1, .. })
+    ));
+}
+
+#[test]
+fn sovereignty_lines_keep_their_hash() {
+    let projection = format!(
+        concat!(
+            r#"{{"event_id":"e3","timestamp":1700000000,"prev_hash":"{}","actor_id":"augmented_citizen","#,
+            r#""node":"Did","deed_type":"did_binding","context_json":{{}},"ethics_flags":["neuro_rights"],"#,
+            r#""life_harm_flag":false}}"#
+        ),
+        "0".repeat(64)
+    );
+    let hash = sha256(&projection);
+    let mut line: Value = serde_json::from_str(&projection).unwrap();
+    line["self_hash"] = json!(hash);
+
+    let read = decode_line(&line.to_string()).unwrap();
+    assert_eq!(read.hash_rule, HashRule::SovereigntyV1);
+    assert_eq!((read.ext["node"].clone(), read.ext["time_unit"].clone()), (json!("Did"), json!("seconds")));
+    assert!(read.verify_hash());
+}
+
+#[test]
+fn rpc_messages_keep_moral_ledger_deeds_bare() {
+    #[derive(Serialize, Deserialize)]
+    struct Reply {
+        #[serde(with = "deed_core::wire")]
+        deed: DeedEvent,
+        #[serde(with = "deed_core::wire::vec")]
+        deeds: Vec<DeedEvent>,
+    }
+
+    let blank = format!(
+        concat!(
+            r#"{{"event_id":"e2","timestamp":1700000000,"prev_hash":"{}","self_hash":"","actor_id":"bob","#,
+            r#""target_ids":[],"deed_type":"watershed_cleanup","tags":[],"context_json":{{}},"#,
+            r#""ethics_flags":[],"life_harm_flag":false,"domain":{{"simulation":{{"run_id":"run-1"}}}}}}"#
+        ),
+        "0".repeat(64)
+    );
+    let line = blank.replacen(r#""self_hash":"""#, &format!(r#""self_hash":"{}""#, sha256(&blank)), 1);
+    let moral = decode_line(&line).unwrap();
+
+    // The moral-ledger deed goes out exactly as that ledger wrote it, in
+    // seconds; a canonical one goes out enveloped, in milliseconds.
+    let reply = Reply { deed: moral.clone(), deeds: vec![moral.clone(), deed()] };
+    let sent = serde_json::to_value(&reply).unwrap();
+    assert_eq!(sent["deed"], serde_json::from_str::<Value>(&line).unwrap());
+    assert_eq!(sent["deeds"][1]["v"], SCHEMA_VERSION);
+    assert_eq!(sent["deeds"][1]["deed"]["timestamp"], 1_700_000_000_123_i64);
+
+    let read: Reply = serde_json::from_value(sent).unwrap();
+    assert_eq!(read.deed, moral);
+    assert_eq!(read.deeds, vec![moral, deed()]);
+    assert!(read.deeds.iter().all(DeedEvent::verify_hash));
+}
@@ -0,0 +1,35 @@
+use deed_core::{merkle_levels, merkle_parent, merkle_proof, merkle_root, verify_merkle_proof, EMPTY_MERKLE_ROOT};
+use sha2::{Digest, Sha256};
+
+fn leaves(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{:x}", Sha256::digest(i.to_string().as_bytes()))).collect()
+}
+
+#[test]
+fn every_leaf_proves_against_the_root() {
+    // Odd counts at every level: 7 → 4 → 2 → 1.
+    let leaves = leaves(7);
+    let levels = merkle_levels(leaves.clone());
+    assert_eq!(levels.iter().map(Vec::len).collect::<Vec<_>>(), [7, 4, 2, 1]);
+    let root = &levels[3][0];
+    assert_eq!(&merkle_root(leaves.clone()), root);
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = merkle_proof(&levels, i);
+        assert_eq!(proof.len(), 3);
+        assert!(verify_merkle_proof(root, leaf, &proof), "leaf {}", i);
+    }
+    // The last leaf of an odd level is paired with itself.
+    assert_eq!(levels[1][3], merkle_parent(&leaves[6], &leaves[6]));
+    assert!(!verify_merkle_proof(root, &leaves[1], &merkle_proof(&levels, 2)));
+}
+
+#[test]
+fn a_single_leaf_is_its_own_root() {
+    assert!(merkle_levels(Vec::new()).is_empty());
+    assert_eq!(merkle_root(Vec::new()), EMPTY_MERKLE_ROOT);
+    let levels = merkle_levels(leaves(1));
+    assert_eq!(merkle_root(leaves(1)), levels[0][0]);
+    assert_eq!(levels.len(), 1);
+    assert!(merkle_proof(&levels, 0).is_empty());
+    assert!(verify_merkle_proof(&levels[0][0], &levels[0][0], &[]));
+}
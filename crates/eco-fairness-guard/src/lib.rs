@@ -6,100 +6,27 @@
 //! - Per-route eco envelopes enforced (strictest-wins).
 //! - Per-subject minimum service enforced where configured.
 //! - Altar routes are governed compute and must go through EVOLVE paths.
+//! - CURRENT_USAGE holds exactly the demand of the checks that passed: a
+//!   check reads one spec snapshot, and a reload waits for it to finish.
 
 #![forbid(unsafe_code)]
 #![warn(clippy::all, clippy::pedantic)]
 
 use dashmap::DashMap;
+use eco_units::{GramsCo2, Watts};
+use faults::points::{GUARD_LOCK, SPEC_RELOAD};
+use faults::Fault;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs::File;
-use std::path::Path;
-use thiserror::Error;
 use tracing::{info, warn};
 
+pub use eco_types::{EcoEnvelope, EcoFairnessSpec, EcoResource, GuardError};
 pub use rohmodel::RohModel;
 pub use tsafe::{RequestRoute, SovereignAction};
 pub use vkernel::ViabilityKernel;
 
 // ──────────────────────────────────────────────────────────────
-// 1. ALN / JSON-friendly core types (.eco-fairness.aln)
-// ──────────────────────────────────────────────────────────────
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EcoEnvelope {
-    pub max_power_watts: f64,
-    pub max_daily_kwh: f64,
-    pub max_heat_output: f64,
-    pub max_co2e_kg: f64,
-    pub max_water_liters: f64,
-}
-
-impl Default for EcoEnvelope {
-    fn default() -> Self {
-        Self {
-            max_power_watts: 850.0,
-            max_daily_kwh: 18.0,
-            max_heat_output: 45.0,
-            max_co2e_kg: 2.5,
-            max_water_liters: 15.0,
-        }
-    }
-}
-
-/// Shard for `config/.eco-fairness.aln` (JSON or ALN → JSON-compat)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EcoFairnessSpec {
-    /// Hard RoH ceiling, must be ≤ 0.30.
-    pub global_roh_ceiling: f64,
-
-    /// Global eco envelope for this node / EcoSys cell.
-    pub global_envelope: EcoEnvelope,
-
-    /// Per-route eco budgets (route id → envelope).
-    pub per_route_budgets: HashMap<String, EcoEnvelope>,
-
-    /// Per-subject minimums (subject_id → envelope).
-    pub per_subject_minimums: HashMap<String, EcoEnvelope>,
-
-    /// Routes treated as Auto_Church Altar (donation, lesson, sacred compute).
-    pub altar_routes: Vec<String>,
-}
-
-impl Default for EcoFairnessSpec {
-    fn default() -> Self {
-        let mut budgets = HashMap::new();
-        budgets.insert(
-            "altar".to_string(),
-            EcoEnvelope {
-                max_power_watts: 420.0,
-                max_daily_kwh: 8.0,
-                ..EcoEnvelope::default()
-            },
-        );
-
-        Self {
-            global_roh_ceiling: 0.30,
-            global_envelope: EcoEnvelope::default(),
-            per_route_budgets: budgets,
-            per_subject_minimums: HashMap::new(),
-            altar_routes: vec!["altar".into(), "donation".into(), "lesson".into()],
-        }
-    }
-}
-
-impl EcoFairnessSpec {
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = File::open(path)?;
-        let spec: EcoFairnessSpec = serde_json::from_reader(file)?;
-        Ok(spec)
-    }
-}
-
-// ──────────────────────────────────────────────────────────────
-// 2. Global spec + live usage tracking
+// 1. Global spec + live usage tracking
 // ──────────────────────────────────────────────────────────────
 
 static ECO_SPEC: Lazy<RwLock<EcoFairnessSpec>> = Lazy::new(|| {
@@ -115,33 +42,40 @@ static ECO_SPEC: Lazy<RwLock<EcoFairnessSpec>> = Lazy::new(|| {
 /// Per-subject live usage (aggregate over current window).
 static CURRENT_USAGE: Lazy<DashMap<String, EcoEnvelope>> = Lazy::new(DashMap::new);
 
-// ──────────────────────────────────────────────────────────────
-// 3. Errors and kernel
-// ──────────────────────────────────────────────────────────────
-
-#[derive(Error, Debug)]
-pub enum GuardError {
-    #[error("Eco budget exceeded on route {route}: {resource} demand {demand} > limit {limit}")]
-    BudgetExceeded {
-        route: String,
-        resource: String,
-        demand: f64,
-        limit: f64,
-    },
-
-    #[error("Equity violation for subject {subject}: below guaranteed minimum")]
-    BelowMinimum { subject: String },
-
-    #[error("RoH ceiling breach (current {current_roh} > ceiling {ceiling})")]
-    RohCeilingBreach { current_roh: f32, ceiling: f32 },
+/// Swap in a new spec. Checks in flight finish against the spec they
+/// started with; later checks see the new one. An invalid spec is refused
+/// and the current one kept.
+pub fn reload_spec(mut spec: EcoFairnessSpec) -> Result<(), GuardError> {
+    let mut current = ECO_SPEC.write();
+    match faults::hit(SPEC_RELOAD) {
+        Some(Fault::Fail) => {
+            return Err(GuardError::SpecReload {
+                reason: "spec source unavailable".into(),
+            })
+        }
+        Some(Fault::Corrupt(_)) => spec.global_roh_ceiling = f64::NAN,
+        None => {}
+    }
+    spec.validate()?;
+    *current = spec;
+    info!("eco-fairness spec reloaded");
+    Ok(())
+}
 
-    #[error("Viability kernel rejection: {reason}")]
-    ViabilityFailure { reason: String },
+/// Usage committed for `subject` in the current window.
+pub fn current_usage(subject: &str) -> Option<EcoEnvelope> {
+    CURRENT_USAGE.get(subject).map(|usage| usage.clone())
+}
 
-    #[error("Altar route requires EVOLVE-governed path (no free throughput)")]
-    AltarRequiresEvolve,
+/// Start a new usage window for every subject.
+pub fn reset_usage() {
+    CURRENT_USAGE.clear();
 }
 
+// ──────────────────────────────────────────────────────────────
+// 2. Kernel
+// ──────────────────────────────────────────────────────────────
+
 #[derive(Debug)]
 pub struct GraceEquityKernel {
     roh: RohModel,
@@ -160,92 +94,110 @@ impl GraceEquityKernel {
         route: &str,
         demand: &EcoEnvelope,
     ) -> Result<(), GuardError> {
-        let spec = ECO_SPEC.read();
+        let spec = read_spec();
 
         // 1. RoH hard ceiling (0.3) – monotone safety lives in RoH guard,
         // here we just ensure the ceiling is respected at the node.[file:1]
-        let current_roh = self.roh.current_value();
-        if current_roh > spec.global_roh_ceiling as f32 {
+        let current_roh = f64::from(self.roh.current_value());
+        if current_roh > spec.global_roh_ceiling {
             return Err(GuardError::RohCeilingBreach {
                 current_roh,
-                ceiling: spec.global_roh_ceiling as f32,
+                ceiling: spec.global_roh_ceiling,
             });
         }
 
-        // 2. Per-route envelope (strictest-wins).[file:2]
-        let route_key = route.to_lowercase();
-        let envelope = spec
-            .per_route_budgets
-            .get(&route_key)
-            .unwrap_or(&spec.global_envelope);
-
-        if demand.max_power_watts > envelope.max_power_watts {
-            return Err(GuardError::BudgetExceeded {
-                route: route.to_string(),
-                resource: "power".into(),
-                demand: demand.max_power_watts,
-                limit: envelope.max_power_watts,
-            });
-        }
-        if demand.max_daily_kwh > envelope.max_daily_kwh {
-            return Err(GuardError::BudgetExceeded {
-                route: route.to_string(),
-                resource: "kWh".into(),
-                demand: demand.max_daily_kwh,
-                limit: envelope.max_daily_kwh,
-            });
-        }
-        if demand.max_co2e_kg > envelope.max_co2e_kg {
-            return Err(GuardError::BudgetExceeded {
-                route: route.to_string(),
-                resource: "CO2e_kg".into(),
-                demand: demand.max_co2e_kg,
-                limit: envelope.max_co2e_kg,
+        // 2. Tsafe viability kernel coupling – eco/compute inside safe polytopes.[file:1]
+        if !self.vkernel.is_viable(demand) {
+            return Err(GuardError::ViabilityFailure {
+                reason: "Eco/compute demand outside Tsafe viability kernel".into(),
             });
         }
 
-        // 3. Altar routes are governed compute – no direct SMART/CHAT scheduling.[file:5]
-        if spec
-            .altar_routes
-            .iter()
-            .any(|r| r.eq_ignore_ascii_case(route))
-        {
-            return Err(GuardError::AltarRequiresEvolve);
-        }
+        admit(&spec, subject, route, demand)
+    }
+}
 
-        // 4. Per-subject equity floor.
-        let usage = CURRENT_USAGE
-            .entry(subject.to_string())
-            .or_insert_with(EcoEnvelope::default);
-
-        if let Some(minimum) = spec.per_subject_minimums.get(subject) {
-            if usage.max_daily_kwh + demand.max_daily_kwh < minimum.max_daily_kwh {
-                return Err(GuardError::BelowMinimum {
-                    subject: subject.to_string(),
-                });
-            }
-        }
+/// The budget, altar and equity-floor checks of `check_route`, committing
+/// `demand` to CURRENT_USAGE if they pass; for gates whose RoH and
+/// viability guardians run separately.
+pub fn admit_demand(subject: &str, route: &str, demand: &EcoEnvelope) -> Result<(), GuardError> {
+    admit(&read_spec(), subject, route, demand)
+}
 
-        // 5. Tsafe viability kernel coupling – eco/compute inside safe polytopes.[file:1]
-        if !self.vkernel.is_viable(demand) {
-            return Err(GuardError::ViabilityFailure {
-                reason: "Eco/compute demand outside Tsafe viability kernel".into(),
+fn read_spec() -> parking_lot::RwLockReadGuard<'static, EcoFairnessSpec> {
+    let _ = faults::hit(GUARD_LOCK);
+    ECO_SPEC.read()
+}
+
+fn admit(spec: &EcoFairnessSpec, subject: &str, route: &str, demand: &EcoEnvelope) -> Result<(), GuardError> {
+    // 1. Per-route envelope (strictest-wins).[file:2]
+    let route_key = route.to_lowercase();
+    let envelope = spec
+        .per_route_budgets
+        .get(&route_key)
+        .unwrap_or(&spec.global_envelope);
+
+    if demand.max_power_watts > envelope.max_power_watts {
+        return Err(GuardError::BudgetExceeded {
+            route: route.to_string(),
+            resource: EcoResource::Power,
+            demand: demand.max_power_watts.value(),
+            limit: envelope.max_power_watts.value(),
+        });
+    }
+    if demand.max_daily_kwh > envelope.max_daily_kwh {
+        return Err(GuardError::BudgetExceeded {
+            route: route.to_string(),
+            resource: EcoResource::Energy,
+            demand: demand.max_daily_kwh,
+            limit: envelope.max_daily_kwh,
+        });
+    }
+    if demand.max_emissions_gco2eq > envelope.max_emissions_gco2eq {
+        return Err(GuardError::BudgetExceeded {
+            route: route.to_string(),
+            resource: EcoResource::Emissions,
+            demand: demand.max_emissions_gco2eq.value(),
+            limit: envelope.max_emissions_gco2eq.value(),
+        });
+    }
+
+    // 2. Altar routes are governed compute – no direct SMART/CHAT scheduling.[file:5]
+    if spec
+        .altar_routes
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(route))
+    {
+        return Err(GuardError::AltarRequiresEvolve);
+    }
+
+    // 3. Per-subject equity floor. The entry stays locked until the
+    // demand is committed, so concurrent checks of one subject serialize.
+    let mut usage = CURRENT_USAGE
+        .entry(subject.to_string())
+        .or_default();
+
+    if let Some(minimum) = spec.per_subject_minimums.get(subject) {
+        if usage.max_daily_kwh + demand.max_daily_kwh < minimum.max_daily_kwh {
+            return Err(GuardError::BelowMinimum {
+                subject: subject.to_string(),
             });
         }
+    }
 
-        // 6. On success, commit usage (sharded, low-contention).
-        usage.max_power_watts += demand.max_power_watts;
-        usage.max_daily_kwh += demand.max_daily_kwh;
-        usage.max_heat_output += demand.max_heat_output;
-        usage.max_co2e_kg += demand.max_co2e_kg;
-        usage.max_water_liters += demand.max_water_liters;
+    // 4. On success, commit usage (sharded, low-contention).
+    usage.max_power_watts += demand.max_power_watts;
+    usage.max_daily_kwh += demand.max_daily_kwh;
+    usage.max_heat_output += demand.max_heat_output;
+    usage.max_emissions_gco2eq += demand.max_emissions_gco2eq;
+    usage.max_water_liters += demand.max_water_liters;
+    usage.max_compute_cycles += demand.max_compute_cycles;
 
-        Ok(())
-    }
+    Ok(())
 }
 
 // ──────────────────────────────────────────────────────────────
-// 4. EcoFairnessGuard – public guardian API
+// 3. EcoFairnessGuard – public guardian API
 // ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
@@ -279,20 +231,21 @@ impl EcoFairnessGuard {
         let base_power = (action.lifeforcecost as f64) * 1000.0;
         let route_id = route.as_str().to_lowercase();
 
-        let (kwh, co2e) = if route_id.contains("altar") {
-            (8.0, 1.0)
+        let (kwh, co2e_grams) = if route_id.contains("altar") {
+            (8.0, 1_000.0)
         } else if route_id.contains("sim") {
-            (5.0, 0.5)
+            (5.0, 500.0)
         } else {
-            (3.0, 0.3)
+            (3.0, 300.0)
         };
 
         EcoEnvelope {
-            max_power_watts: base_power.clamp(0.0, 850.0),
+            max_power_watts: Watts::new(base_power.clamp(0.0, 850.0)),
             max_daily_kwh: kwh,
             max_heat_output: 40.0,
-            max_co2e_kg: co2e,
+            max_emissions_gco2eq: GramsCo2::new(co2e_grams),
             max_water_liters: 10.0,
+            ..EcoEnvelope::zero()
         }
     }
 }
@@ -0,0 +1,129 @@
+#![cfg(feature = "chaos")]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use eco_fairness_guard::{admit_demand, current_usage, reload_spec, reset_usage, EcoEnvelope, EcoFairnessSpec, GuardError};
+use eco_units::{GramsCo2, Watts};
+use faults::points::{GUARD_LOCK, SPEC_RELOAD};
+use faults::{fired, program, session, FaultRule};
+
+const ROUTE: &str = "garden";
+const SUBJECTS: usize = 4;
+
+fn spec(power_limit: f64) -> EcoFairnessSpec {
+    let mut spec = EcoFairnessSpec::default();
+    spec.per_route_budgets.insert(
+        ROUTE.into(),
+        EcoEnvelope { max_power_watts: Watts::new(power_limit), ..EcoEnvelope::node_default() },
+    );
+    spec
+}
+
+/// Whole and half units only, so sums are exact in any order.
+fn demand(n: usize) -> EcoEnvelope {
+    EcoEnvelope {
+        max_power_watts: Watts::new(200.0 + (n % 5) as f64 * 50.0),
+        max_daily_kwh: 1.0,
+        max_heat_output: 2.0,
+        max_emissions_gco2eq: GramsCo2::new(500.0),
+        max_water_liters: 1.0,
+        ..EcoEnvelope::zero()
+    }
+}
+
+fn totals(e: &EcoEnvelope) -> [f64; 5] {
+    [e.max_power_watts.value(), e.max_daily_kwh, e.max_heat_output, e.max_emissions_gco2eq.value(), e.max_water_liters]
+}
+
+fn add(into: &mut [f64; 5], e: &EcoEnvelope) {
+    for (sum, v) in into.iter_mut().zip(totals(e)) {
+        *sum += v;
+    }
+}
+
+#[test]
+fn reloads_racing_a_thousand_checks_keep_usage_consistent() {
+    let _chaos = session(0xec0);
+    reset_usage();
+    reload_spec(spec(300.0)).unwrap();
+    program(GUARD_LOCK, FaultRule::delay(Duration::from_micros(200)).with_probability(0.05));
+    program(SPEC_RELOAD, FaultRule::delay(Duration::from_millis(1)).with_probability(0.5));
+    program(SPEC_RELOAD, FaultRule::fail().with_probability(0.1));
+    program(SPEC_RELOAD, FaultRule::corrupt(0).with_probability(0.1));
+
+    let done = Arc::new(AtomicBool::new(false));
+    let reloader = {
+        let done = done.clone();
+        thread::spawn(move || {
+            let (mut reloads, mut refused) = (0, 0);
+            while !done.load(Ordering::Acquire) {
+                let limit = if reloads % 2 == 0 { 400.0 } else { 300.0 };
+                match reload_spec(spec(limit)) {
+                    Ok(()) => reloads += 1,
+                    Err(GuardError::SpecReload { .. }) => refused += 1,
+                    Err(e) => panic!("{e}"),
+                }
+            }
+            (reloads, refused)
+        })
+    };
+
+    let workers: Vec<_> = (0..8)
+        .map(|w| {
+            thread::spawn(move || {
+                let mut accepted: HashMap<String, [f64; 5]> = HashMap::new();
+                for i in 0..125 {
+                    let subject = format!("chaos-subject-{}", (w + i) % SUBJECTS);
+                    let d = demand(w * 125 + i);
+                    match admit_demand(&subject, ROUTE, &d) {
+                        Ok(()) => add(accepted.entry(subject).or_default(), &d),
+                        Err(GuardError::BudgetExceeded { .. }) => {}
+                        Err(e) => panic!("{e}"),
+                    }
+                }
+                accepted
+            })
+        })
+        .collect();
+
+    let mut expected: HashMap<String, [f64; 5]> = HashMap::new();
+    let mut admitted = 0.0;
+    for worker in workers {
+        for (subject, sums) in worker.join().unwrap() {
+            let total = expected.entry(subject).or_default();
+            for (t, s) in total.iter_mut().zip(sums) {
+                *t += s;
+            }
+        }
+    }
+    done.store(true, Ordering::Release);
+    let (reloads, refused) = reloader.join().unwrap();
+    assert!(reloads > 0 && refused > 0, "{reloads} reloads, {refused} refused");
+    assert!(fired(GUARD_LOCK) > 0);
+
+    for n in 0..SUBJECTS {
+        let subject = format!("chaos-subject-{n}");
+        let want = expected.get(&subject).copied().unwrap_or_default();
+        admitted += want[1];
+        assert_eq!(current_usage(&subject).map(|u| totals(&u)).unwrap_or_default(), want, "{subject}");
+    }
+    // Each check demands 1 kWh; demands up to 300 W fit either spec.
+    assert!((600.0..=1000.0).contains(&admitted), "{admitted} of 1000 admitted");
+}
+
+#[test]
+fn a_torn_reload_keeps_the_previous_spec() {
+    let _chaos = session(0xec1);
+    reset_usage();
+    reload_spec(spec(300.0)).unwrap();
+    program(SPEC_RELOAD, FaultRule::corrupt(0));
+
+    assert!(matches!(reload_spec(spec(400.0)), Err(GuardError::SpecReload { .. })));
+    let over = EcoEnvelope { max_power_watts: Watts::new(350.0), ..EcoEnvelope::zero() };
+    assert!(matches!(admit_demand("torn", ROUTE, &over), Err(GuardError::BudgetExceeded { .. })));
+    assert!(current_usage("torn").is_none());
+}
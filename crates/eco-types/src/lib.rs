@@ -0,0 +1,539 @@
+//! Vocabulary shared by the eco guards.
+//!
+//! `eco-fairness-guard` and `auto_church/ecofairness_guardian` each used to
+//! define their own `EcoEnvelope`, `EcoFairnessSpec` and `GuardError`. One
+//! used bare `f64` in kg and kWh, the other eco-units types, and they named
+//! the same shard fields differently. Both now use the types defined here,
+//! and `GuardError::code` gives every rejection the code that the gate
+//! logs. That is the same code `ecofairness-guard` reports for the same
+//! violation.
+//!
+//! A `.eco-fairness.aln` shard written for either crate still loads. The
+//! legacy field names are accepted on read and mapped onto the unified
+//! fields. Writes use the unified names.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+
+use eco_units::{Cycles, GramsCo2, Watts};
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+/// Demand, usage or limit on each eco axis. `Default` is no usage at all.
+///
+/// Envelopes read from a shard are limits, so any field the shard leaves
+/// out is unbounded (see `UNBOUNDED`). The exception is
+/// `per_subject_minimums`, where a missing field is zero. Emissions can be
+/// given in grams as `max_emissions_gco2eq`, or in kg as `max_co2e_kg`,
+/// which is the field name eco-fairness-guard used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EnvelopeRepr")]
+pub struct EcoEnvelope {
+    pub max_power_watts: Watts,
+    pub max_daily_kwh: f64,
+    pub max_heat_output: f64,
+    pub max_emissions_gco2eq: GramsCo2,
+    pub max_water_liters: f64,
+    pub max_compute_cycles: Cycles,
+    /// True for earth-restoring tasks.
+    pub priority_uplift_if_eco_positive: bool,
+}
+
+impl EcoEnvelope {
+    /// No limit on any axis.
+    pub const UNBOUNDED: Self = Self {
+        max_power_watts: Watts::new(f64::MAX),
+        max_daily_kwh: f64::MAX,
+        max_heat_output: f64::MAX,
+        max_emissions_gco2eq: GramsCo2::new(f64::MAX),
+        max_water_liters: f64::MAX,
+        max_compute_cycles: Cycles::new(u64::MAX),
+        priority_uplift_if_eco_positive: false,
+    };
+
+    /// No usage at all; where a subject's window starts.
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// The envelope a node gets when its shard configures none.
+    pub fn node_default() -> Self {
+        Self {
+            max_power_watts: Watts::new(850.0),
+            max_daily_kwh: 18.0,
+            max_heat_output: 45.0,
+            max_emissions_gco2eq: GramsCo2::new(2_500.0),
+            max_water_liters: 15.0,
+            ..Self::UNBOUNDED
+        }
+    }
+}
+
+/// Every envelope field either guard has ever written.
+#[derive(Deserialize)]
+struct EnvelopeRepr {
+    max_power_watts: Option<Watts>,
+    max_daily_kwh: Option<f64>,
+    max_heat_output: Option<f64>,
+    max_emissions_gco2eq: Option<GramsCo2>,
+    max_co2e_kg: Option<f64>,
+    max_water_liters: Option<f64>,
+    max_compute_cycles: Option<Cycles>,
+    #[serde(default)]
+    priority_uplift_if_eco_positive: bool,
+}
+
+impl EnvelopeRepr {
+    /// The envelope, taking each missing field from `absent`.
+    fn with_absent(self, absent: &EcoEnvelope) -> EcoEnvelope {
+        let kg = self.max_co2e_kg.map(|kg| GramsCo2::new(kg * 1_000.0));
+        EcoEnvelope {
+            max_power_watts: self.max_power_watts.unwrap_or(absent.max_power_watts),
+            max_daily_kwh: self.max_daily_kwh.unwrap_or(absent.max_daily_kwh),
+            max_heat_output: self.max_heat_output.unwrap_or(absent.max_heat_output),
+            max_emissions_gco2eq: self.max_emissions_gco2eq.or(kg).unwrap_or(absent.max_emissions_gco2eq),
+            max_water_liters: self.max_water_liters.unwrap_or(absent.max_water_liters),
+            max_compute_cycles: self.max_compute_cycles.unwrap_or(absent.max_compute_cycles),
+            priority_uplift_if_eco_positive: self.priority_uplift_if_eco_positive,
+        }
+    }
+}
+
+impl From<EnvelopeRepr> for EcoEnvelope {
+    fn from(repr: EnvelopeRepr) -> Self {
+        repr.with_absent(&Self::UNBOUNDED)
+    }
+}
+
+/// `per_subject_minimums` are floors: a missing field asks for nothing.
+fn floors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, EcoEnvelope>, D::Error> {
+    let reprs = HashMap::<String, EnvelopeRepr>::deserialize(deserializer)?;
+    let zero = EcoEnvelope::zero();
+    Ok(reprs.into_iter().map(|(subject, repr)| (subject, repr.with_absent(&zero))).collect())
+}
+
+/// The guardian's policy for idle per-subject usage. It lives under
+/// `usage_lifecycle` in .eco-fairness.aln.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageLifecycleConfig {
+    /// Entries untouched for longer than this are eligible for eviction.
+    pub ttl_secs: u64,
+    /// Usage at or below every one of these is dropped rather than archived.
+    pub negligible_power_watts: Watts,
+    pub negligible_emissions_gco2eq: GramsCo2,
+    pub negligible_compute_cycles: Cycles,
+    /// Run a sweep every N guard operations (0 disables the lazy sweep).
+    pub sweep_every_ops: u64,
+    /// A reservation neither committed nor released within this is handed
+    /// back to the budget, so a crashed caller cannot hold it forever.
+    #[serde(default = "default_reservation_ttl_secs")]
+    pub reservation_ttl_secs: u64,
+}
+
+fn default_reservation_ttl_secs() -> u64 {
+    30
+}
+
+impl Default for UsageLifecycleConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 3600,
+            negligible_power_watts: Watts::new(1e-3),
+            negligible_emissions_gco2eq: GramsCo2::new(1e-3),
+            negligible_compute_cycles: Cycles::new(1_000),
+            sweep_every_ops: 10_000,
+            reservation_ttl_secs: default_reservation_ttl_secs(),
+        }
+    }
+}
+
+/// How far back each resource's usage counts against a subject's
+/// envelope. A shard without `usage_windows` gets these defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageWindows {
+    pub power_window_seconds: u64,
+    pub emissions_window_seconds: u64,
+    pub compute_window_seconds: u64,
+}
+
+impl Default for UsageWindows {
+    fn default() -> Self {
+        Self {
+            power_window_seconds: 15 * 60,
+            emissions_window_seconds: 24 * 3600,
+            compute_window_seconds: 60,
+        }
+    }
+}
+
+impl UsageWindows {
+    pub fn longest(&self) -> u64 {
+        self.power_window_seconds.max(self.emissions_window_seconds).max(self.compute_window_seconds)
+    }
+}
+
+/// What one unit of lifeforce costs for one kind of action.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActionCost {
+    pub watts_per_unit: f64,
+    pub cycles_per_unit: f64,
+    /// How long the action draws that power.
+    pub seconds: f64,
+}
+
+/// Turns an action's kind and lifeforce cost into eco demand. Lives under
+/// `action_cost_model` in .eco-fairness.aln.
+///
+/// A shard that gives `costs` replaces the built-in table, so it should
+/// list every kind it expects to see. Kinds missing from the table are
+/// costed at `worst_case` rather than for free.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionCostModel {
+    /// Action kind name (e.g. `ReadNeuralShard`) → cost.
+    pub costs: HashMap<String, ActionCost>,
+    pub worst_case: ActionCost,
+    /// Grid emissions for the energy an action draws.
+    pub grams_co2_per_kwh: f64,
+    /// Route id → multiplier on the whole demand; routes not listed are 1.
+    pub route_factors: HashMap<String, f64>,
+}
+
+impl Default for ActionCostModel {
+    fn default() -> Self {
+        let row = |watts_per_unit, cycles_per_unit, seconds| ActionCost { watts_per_unit, cycles_per_unit, seconds };
+        let costs = [
+            ("ReadNeuralShard", row(0.5, 10_000.0, 1.0)),
+            ("ReadKeys", row(0.5, 5_000.0, 1.0)),
+            ("SignTransaction", row(1.0, 20_000.0, 1.0)),
+            ("WriteNeuralShard", row(2.0, 50_000.0, 2.0)),
+            ("ProposeEvolve", row(5.0, 100_000.0, 5.0)),
+            ("XRRouteStep", row(15.0, 200_000.0, 10.0)),
+            ("ScheduleJob", row(40.0, 1_000_000.0, 60.0)),
+            ("ApplyOta", row(120.0, 5_000_000.0, 600.0)),
+        ];
+        Self {
+            costs: costs.into_iter().map(|(kind, cost)| (kind.to_string(), cost)).collect(),
+            worst_case: row(150.0, 10_000_000.0, 900.0),
+            grams_co2_per_kwh: 400.0,
+            route_factors: HashMap::new(),
+        }
+    }
+}
+
+impl ActionCostModel {
+    /// Demand for an action of `kind` on `route` costing `lifeforce` units.
+    /// A cost that is not a finite number is unbounded demand.
+    pub fn estimate(&self, kind: &str, route: &str, lifeforce: f64) -> EcoEnvelope {
+        if !lifeforce.is_finite() {
+            return EcoEnvelope::UNBOUNDED;
+        }
+        let cost = self.costs.get(kind).unwrap_or(&self.worst_case);
+        let units = lifeforce.max(0.0) * self.route_factors.get(route).copied().unwrap_or(1.0);
+        let watts = cost.watts_per_unit * units;
+        let kwh = watts * cost.seconds / 3_600_000.0;
+        EcoEnvelope {
+            max_power_watts: Watts::new(watts),
+            max_daily_kwh: kwh,
+            max_emissions_gco2eq: GramsCo2::new(kwh * self.grams_co2_per_kwh),
+            max_compute_cycles: Cycles::new((cost.cycles_per_unit * units).ceil() as u64),
+            ..EcoEnvelope::zero()
+        }
+    }
+}
+
+/// Who may unlock an altar route with an EVOLVE token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvolvePolicy {
+    /// Keyring purpose of the EVOLVE authority keys.
+    pub signer_purpose: String,
+    /// Distinct authority keys a token must be signed by.
+    pub min_signatures: usize,
+}
+
+impl Default for EvolvePolicy {
+    fn default() -> Self {
+        Self { signer_purpose: "evolve-authority".to_string(), min_signatures: 2 }
+    }
+}
+
+/// Shard for `config/.eco-fairness.aln` (JSON or ALN → JSON-compat).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcoFairnessSpec {
+    /// Hard RoH ceiling, must be ≤ 0.30.
+    pub global_roh_ceiling: f64,
+
+    /// Global eco envelope for this node / EcoSys cell. The guardian
+    /// called it `global_eco_budget`.
+    #[serde(alias = "global_eco_budget")]
+    pub global_envelope: EcoEnvelope,
+
+    /// Per-route eco budgets (route id → envelope).
+    #[serde(default)]
+    pub per_route_budgets: HashMap<String, EcoEnvelope>,
+
+    /// Per-subject minimums (subject_id → envelope).
+    #[serde(default, deserialize_with = "floors")]
+    pub per_subject_minimums: HashMap<String, EcoEnvelope>,
+
+    /// Routes treated as Auto_Church Altar (donation, lesson, sacred compute).
+    #[serde(default)]
+    pub altar_routes: Vec<String>,
+
+    /// TTL and archive policy for the guardian's per-subject usage.
+    #[serde(default)]
+    pub usage_lifecycle: UsageLifecycleConfig,
+
+    /// Sliding windows the guardian checks per-subject usage over.
+    #[serde(default)]
+    pub usage_windows: UsageWindows,
+
+    /// EVOLVE token signers for `altar_routes`.
+    #[serde(default)]
+    pub evolve_policy: EvolvePolicy,
+
+    /// How actions are costed as eco demand.
+    #[serde(default)]
+    pub action_cost_model: ActionCostModel,
+}
+
+impl Default for EcoFairnessSpec {
+    fn default() -> Self {
+        let mut budgets = HashMap::new();
+        budgets.insert(
+            "altar".to_string(),
+            EcoEnvelope {
+                max_power_watts: Watts::new(420.0),
+                max_daily_kwh: 8.0,
+                ..EcoEnvelope::node_default()
+            },
+        );
+
+        Self {
+            global_roh_ceiling: 0.30,
+            global_envelope: EcoEnvelope::node_default(),
+            per_route_budgets: budgets,
+            per_subject_minimums: HashMap::new(),
+            altar_routes: vec!["altar".into(), "donation".into(), "lesson".into()],
+            usage_lifecycle: UsageLifecycleConfig::default(),
+            usage_windows: UsageWindows::default(),
+            evolve_policy: EvolvePolicy::default(),
+            action_cost_model: ActionCostModel::default(),
+        }
+    }
+}
+
+impl EcoFairnessSpec {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = File::open(path)?;
+        let spec: EcoFairnessSpec = serde_json::from_reader(file)?;
+        Ok(spec)
+    }
+
+    /// The RoH ceiling must lie in (0, 0.30].
+    pub fn validate(&self) -> Result<(), GuardError> {
+        if !(self.global_roh_ceiling > 0.0 && self.global_roh_ceiling <= 0.30) {
+            return Err(GuardError::SpecReload {
+                reason: format!("RoH ceiling {} outside (0, 0.30]", self.global_roh_ceiling),
+            });
+        }
+        Ok(())
+    }
+
+    /// What changes if `next` replaces this spec.
+    pub fn diff(&self, next: &EcoFairnessSpec) -> SpecDiff {
+        let mut diff = SpecDiff {
+            roh_ceiling: (next.global_roh_ceiling != self.global_roh_ceiling)
+                .then_some((self.global_roh_ceiling, next.global_roh_ceiling)),
+            usage_policy_changed: next.usage_lifecycle != self.usage_lifecycle
+                || next.usage_windows != self.usage_windows,
+            evolve_policy_changed: next.evolve_policy != self.evolve_policy,
+            cost_model_changed: next.action_cost_model != self.action_cost_model,
+            ..SpecDiff::default()
+        };
+        (diff.global_tightened, diff.global_loosened) = compare(&self.global_envelope, &next.global_envelope);
+
+        for (route, budget) in &next.per_route_budgets {
+            match self.per_route_budgets.get(route) {
+                None => diff.routes_added.push(route.clone()),
+                Some(old) => {
+                    let (tightened, loosened) = compare(old, budget);
+                    if tightened {
+                        diff.budgets_tightened.push(route.clone());
+                    }
+                    if loosened {
+                        diff.budgets_loosened.push(route.clone());
+                    }
+                }
+            }
+        }
+        diff.routes_removed = self
+            .per_route_budgets
+            .keys()
+            .filter(|route| !next.per_route_budgets.contains_key(*route))
+            .cloned()
+            .collect();
+        diff.altar_routes_added = next.altar_routes.iter().filter(|r| !self.altar_routes.contains(r)).cloned().collect();
+        diff.altar_routes_removed = self.altar_routes.iter().filter(|r| !next.altar_routes.contains(r)).cloned().collect();
+
+        for routes in [
+            &mut diff.routes_added,
+            &mut diff.routes_removed,
+            &mut diff.budgets_tightened,
+            &mut diff.budgets_loosened,
+        ] {
+            routes.sort();
+        }
+        diff
+    }
+}
+
+/// `(tightened, loosened)`: whether `next` lowers, and whether it raises,
+/// any limit of `old`. A mixed change is both.
+fn compare(old: &EcoEnvelope, next: &EcoEnvelope) -> (bool, bool) {
+    let axes = [
+        old.max_power_watts.partial_cmp(&next.max_power_watts),
+        old.max_daily_kwh.partial_cmp(&next.max_daily_kwh),
+        old.max_heat_output.partial_cmp(&next.max_heat_output),
+        old.max_emissions_gco2eq.partial_cmp(&next.max_emissions_gco2eq),
+        old.max_water_liters.partial_cmp(&next.max_water_liters),
+        Some(old.max_compute_cycles.cmp(&next.max_compute_cycles)),
+    ];
+    (axes.contains(&Some(Ordering::Greater)), axes.contains(&Some(Ordering::Less)))
+}
+
+/// What a spec reload changed. Budget route lists are sorted; altar route
+/// lists keep shard order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SpecDiff {
+    /// `(old, new)` when the RoH ceiling moved.
+    pub roh_ceiling: Option<(f64, f64)>,
+    pub global_tightened: bool,
+    pub global_loosened: bool,
+    pub routes_added: Vec<String>,
+    pub routes_removed: Vec<String>,
+    /// Routes with at least one lower limit.
+    pub budgets_tightened: Vec<String>,
+    /// Routes with at least one higher limit.
+    pub budgets_loosened: Vec<String>,
+    pub altar_routes_added: Vec<String>,
+    pub altar_routes_removed: Vec<String>,
+    /// `usage_lifecycle` or `usage_windows` differ.
+    pub usage_policy_changed: bool,
+    /// `evolve_policy` differs.
+    pub evolve_policy_changed: bool,
+    /// `action_cost_model` differs.
+    pub cost_model_changed: bool,
+}
+
+impl SpecDiff {
+    /// True if the new RoH ceiling is higher than the old one.
+    pub fn roh_ceiling_loosened(&self) -> bool {
+        self.roh_ceiling.is_some_and(|(old, new)| new > old)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == SpecDiff::default()
+    }
+}
+
+/// The envelope axis a `BudgetExceeded` is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcoResource {
+    Power,
+    Energy,
+    Emissions,
+    Compute,
+}
+
+impl fmt::Display for EcoResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Power => "power",
+            Self::Energy => "kWh",
+            Self::Emissions => "gCO2e",
+            Self::Compute => "compute",
+        })
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GuardError {
+    #[error("Eco budget exceeded on route {route}: {resource} demand {demand} > limit {limit}")]
+    BudgetExceeded {
+        route: String,
+        resource: EcoResource,
+        demand: f64,
+        limit: f64,
+    },
+
+    #[error("Equity violation for subject {subject}: below guaranteed minimum")]
+    BelowMinimum { subject: String },
+
+    #[error("RoH ceiling breach (current {current_roh} > ceiling {ceiling})")]
+    RohCeilingBreach { current_roh: f64, ceiling: f64 },
+
+    #[error("Viability kernel rejection: {reason}")]
+    ViabilityFailure { reason: String },
+
+    #[error("Altar route requires EVOLVE-governed path (no free throughput)")]
+    AltarRequiresEvolve,
+
+    #[error("Spec reload refused: {reason}")]
+    SpecReload { reason: String },
+
+    #[error("Reservation {id} is unknown, already settled or expired")]
+    ReservationUnknown { id: u64 },
+
+    #[error("EVOLVE token {token_id} is invalid: {reason}")]
+    EvolveTokenInvalid { token_id: String, reason: String },
+
+    #[error("EVOLVE token {token_id} expired at {expiry}")]
+    EvolveTokenExpired { token_id: String, expiry: u64 },
+
+    #[error("EVOLVE token {token_id} was issued for route {issued_for_route}, not {route}")]
+    EvolveTokenWrongRoute {
+        token_id: String,
+        issued_for_route: String,
+        route: String,
+    },
+
+    #[error("EVOLVE token {token_id} has {got} authority signatures, needs {need}")]
+    EvolveTokenUnderSigned { token_id: String, got: usize, need: usize },
+
+    /// A rejection from a guard that reports a code and message, as
+    /// ecofairness-guard does.
+    #[error("{message}")]
+    Rejected { code: String, message: String },
+}
+
+impl GuardError {
+    /// Stable rejection code for logs and `RejectionReason`. The codes
+    /// match ecofairness-guard's where the violation is the same.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::BudgetExceeded { resource, .. } => match resource {
+                EcoResource::Power => "ECO_POWER_EXCEEDED",
+                EcoResource::Energy => "ECO_ENERGY_EXCEEDED",
+                EcoResource::Emissions => "ECO_EMISSIONS_EXCEEDED",
+                EcoResource::Compute => "ECO_COMPUTE_EXCEEDED",
+            },
+            Self::BelowMinimum { .. } => "ECO_EQUITY_BELOW_MINIMUM",
+            Self::RohCeilingBreach { .. } => "ROH_CEILING",
+            Self::ViabilityFailure { .. } => "ECO_NOT_VIABLE",
+            Self::AltarRequiresEvolve => "ECO_ALTAR_REQUIRES_EVOLVE",
+            Self::SpecReload { .. } => "ECO_SPEC_RELOAD_REFUSED",
+            Self::ReservationUnknown { .. } => "ECO_RESERVATION_UNKNOWN",
+            Self::EvolveTokenInvalid { .. } => "ECO_EVOLVE_TOKEN_INVALID",
+            Self::EvolveTokenExpired { .. } => "ECO_EVOLVE_TOKEN_EXPIRED",
+            Self::EvolveTokenWrongRoute { .. } => "ECO_EVOLVE_TOKEN_WRONG_ROUTE",
+            Self::EvolveTokenUnderSigned { .. } => "ECO_EVOLVE_TOKEN_UNDERSIGNED",
+            Self::Rejected { code, .. } => code,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use eco_types::{ActionCost, ActionCostModel, EcoEnvelope, EcoFairnessSpec};
+use eco_units::{Cycles, GramsCo2, Watts};
+
+#[test]
+fn reading_a_shard_costs_far_less_than_an_ota() {
+    let model = ActionCostModel::default();
+    let read = model.estimate("ReadNeuralShard", "lesson", 1.0);
+    let ota = model.estimate("ApplyOta", "lesson", 1.0);
+    assert!(read.max_power_watts.value() * 100.0 < ota.max_power_watts.value());
+    assert!(read.max_compute_cycles.value() * 100 < ota.max_compute_cycles.value());
+    assert!(read.max_emissions_gco2eq.value() * 1_000.0 < ota.max_emissions_gco2eq.value());
+}
+
+#[test]
+fn emissions_scale_with_power() {
+    let mut model = ActionCostModel::default();
+    let row = ActionCost { watts_per_unit: 100.0, cycles_per_unit: 0.0, seconds: 3_600.0 };
+    model.costs.insert("Job".into(), row);
+    // 100 W for an hour is 0.1 kWh at 400 g/kWh.
+    let one = model.estimate("Job", "garden", 1.0);
+    assert_eq!(one.max_daily_kwh, 0.1);
+    assert_eq!(one.max_emissions_gco2eq, GramsCo2::new(40.0));
+
+    let two = model.estimate("Job", "garden", 2.0);
+    assert_eq!(two.max_power_watts, Watts::new(200.0));
+    assert_eq!(two.max_emissions_gco2eq, GramsCo2::new(80.0));
+    model.grams_co2_per_kwh = 800.0;
+    assert_eq!(model.estimate("Job", "garden", 1.0).max_emissions_gco2eq, GramsCo2::new(80.0));
+}
+
+#[test]
+fn unknown_kinds_cost_the_worst_case() {
+    let model = ActionCostModel::default();
+    let unknown = model.estimate("SummonDaemon", "garden", 1.0);
+    for cost in model.costs.keys() {
+        let known = model.estimate(cost, "garden", 1.0);
+        assert!(unknown.max_power_watts >= known.max_power_watts, "{cost}");
+        assert!(unknown.max_compute_cycles >= known.max_compute_cycles, "{cost}");
+        assert!(unknown.max_emissions_gco2eq >= known.max_emissions_gco2eq, "{cost}");
+    }
+    assert!(unknown.max_power_watts > Watts::ZERO);
+    assert_eq!(model.estimate("ReadKeys", "garden", f64::NAN), EcoEnvelope::UNBOUNDED);
+    assert_eq!(model.estimate("ReadKeys", "garden", -5.0).max_compute_cycles, Cycles::new(0));
+}
+
+#[test]
+fn routes_scale_demand_and_shards_may_override_the_table() {
+    let spec: EcoFairnessSpec = serde_json::from_str(
+        r#"{
+            "global_roh_ceiling": 0.3,
+            "global_envelope": {},
+            "action_cost_model": {
+                "costs": { "ReadNeuralShard": { "watts_per_unit": 4.0, "cycles_per_unit": 10.0, "seconds": 1.0 } },
+                "route_factors": { "altar": 2.5 }
+            }
+        }"#,
+    )
+    .unwrap();
+    let model = &spec.action_cost_model;
+    assert_eq!(model.estimate("ReadNeuralShard", "garden", 1.0).max_power_watts, Watts::new(4.0));
+    assert_eq!(model.estimate("ReadNeuralShard", "altar", 1.0).max_power_watts, Watts::new(10.0));
+    // The shard's table replaced the built-in one; the rest keep their defaults.
+    assert_eq!(model.estimate("ApplyOta", "garden", 1.0).max_power_watts, Watts::new(150.0));
+    assert_eq!(model.grams_co2_per_kwh, ActionCostModel::default().grams_co2_per_kwh);
+
+    let plain: EcoFairnessSpec = serde_json::from_str(r#"{ "global_roh_ceiling": 0.3, "global_envelope": {} }"#).unwrap();
+    assert_eq!(plain.action_cost_model, ActionCostModel::default());
+}
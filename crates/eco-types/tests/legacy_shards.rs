@@ -0,0 +1,100 @@
+use eco_types::{EcoEnvelope, EcoFairnessSpec, EcoResource, GuardError, UsageLifecycleConfig, UsageWindows};
+use eco_units::{Cycles, GramsCo2, Watts};
+
+/// A shard as eco-fairness-guard wrote it: bare f64, kg of CO2e.
+const GUARD_SHARD: &str = r#"{
+    "global_roh_ceiling": 0.3,
+    "global_envelope": {
+        "max_power_watts": 850.0, "max_daily_kwh": 18.0, "max_heat_output": 45.0,
+        "max_co2e_kg": 2.5, "max_water_liters": 15.0
+    },
+    "per_route_budgets": {
+        "altar": {
+            "max_power_watts": 420.0, "max_daily_kwh": 8.0, "max_heat_output": 45.0,
+            "max_co2e_kg": 2.5, "max_water_liters": 15.0
+        }
+    },
+    "per_subject_minimums": {},
+    "altar_routes": ["altar", "donation", "lesson"]
+}"#;
+
+/// A shard as the guardian wrote it: eco-units fields, grams, cycles.
+const GUARDIAN_SHARD: &str = r#"{
+    "global_roh_ceiling": 0.3,
+    "global_eco_budget": {
+        "max_power_watts": 850.0, "max_emissions_gco2eq": 2500.0,
+        "max_compute_cycles": 9000000, "priority_uplift_if_eco_positive": false
+    },
+    "per_route_budgets": {
+        "garden": {
+            "max_power_watts": 300.0, "max_emissions_gco2eq": 400.0,
+            "max_compute_cycles": 50000, "priority_uplift_if_eco_positive": true
+        }
+    },
+    "per_subject_minimums": {
+        "learner-7": {
+            "max_power_watts": 0.0, "max_emissions_gco2eq": 0.0,
+            "max_compute_cycles": 1000, "priority_uplift_if_eco_positive": false
+        }
+    },
+    "altar_routes": ["altar"],
+    "usage_lifecycle": {
+        "ttl_secs": 60, "negligible_power_watts": 0.5, "negligible_emissions_gco2eq": 0.5,
+        "negligible_compute_cycles": 10, "sweep_every_ops": 0
+    }
+}"#;
+
+#[test]
+fn the_guard_format_loads_with_emissions_in_grams() {
+    let spec: EcoFairnessSpec = serde_json::from_str(GUARD_SHARD).unwrap();
+    assert_eq!(spec.global_envelope, EcoEnvelope::node_default());
+    assert_eq!(spec.per_route_budgets["altar"], EcoFairnessSpec::default().per_route_budgets["altar"]);
+    assert_eq!(spec.per_route_budgets["altar"].max_emissions_gco2eq, GramsCo2::new(2_500.0));
+    // The guard format never limited compute.
+    assert_eq!(spec.global_envelope.max_compute_cycles, Cycles::new(u64::MAX));
+    assert_eq!(spec.usage_lifecycle, UsageLifecycleConfig::default());
+    assert_eq!(spec.usage_windows, UsageWindows::default());
+    spec.validate().unwrap();
+}
+
+#[test]
+fn the_guardian_format_loads_into_the_same_spec() {
+    let spec: EcoFairnessSpec = serde_json::from_str(GUARDIAN_SHARD).unwrap();
+    let global = &spec.global_envelope;
+    assert_eq!(global.max_power_watts, Watts::new(850.0));
+    assert_eq!(global.max_emissions_gco2eq, GramsCo2::new(2_500.0));
+    assert_eq!(global.max_compute_cycles, Cycles::new(9_000_000));
+    // Axes the guardian never wrote are unbounded, not zero.
+    assert_eq!(global.max_daily_kwh, f64::MAX);
+
+    let garden = &spec.per_route_budgets["garden"];
+    assert!(garden.priority_uplift_if_eco_positive);
+    assert_eq!(garden.max_compute_cycles, Cycles::new(50_000));
+    let learner = &spec.per_subject_minimums["learner-7"];
+    assert_eq!(learner.max_compute_cycles, Cycles::new(1_000));
+    // A minimum the guardian never wrote asks for nothing.
+    assert_eq!(learner.max_daily_kwh, 0.0);
+    assert_eq!((spec.usage_lifecycle.ttl_secs, spec.usage_lifecycle.sweep_every_ops), (60, 0));
+}
+
+#[test]
+fn unified_shards_round_trip() {
+    for shard in [GUARD_SHARD, GUARDIAN_SHARD] {
+        let spec: EcoFairnessSpec = serde_json::from_str(shard).unwrap();
+        let json = serde_json::to_string(&spec).unwrap();
+        assert!(json.contains("\"global_envelope\"") && !json.contains("max_co2e_kg"), "{json}");
+        let again: EcoFairnessSpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(again.global_envelope, spec.global_envelope);
+        assert_eq!(again.per_route_budgets, spec.per_route_budgets);
+    }
+}
+
+#[test]
+fn rejection_codes_match_the_xr_guard() {
+    let power = GuardError::BudgetExceeded { route: "garden".into(), resource: EcoResource::Power, demand: 350.0, limit: 300.0 };
+    assert_eq!(power.code(), "ECO_POWER_EXCEEDED");
+    assert_eq!(GuardError::RohCeilingBreach { current_roh: 0.4, ceiling: 0.3 }.code(), "ROH_CEILING");
+
+    let xr = GuardError::Rejected { code: "ECO_EQUITY_FLOOR_AT_RISK".into(), message: "floor".into() };
+    assert_eq!((xr.code(), xr.to_string().as_str()), ("ECO_EQUITY_FLOOR_AT_RISK", "floor"));
+}
@@ -0,0 +1,46 @@
+use eco_types::{EcoEnvelope, EcoFairnessSpec};
+use eco_units::{Cycles, Watts};
+
+#[test]
+fn an_identical_spec_changes_nothing() {
+    let spec = EcoFairnessSpec::default();
+    assert!(spec.diff(&spec.clone()).is_empty());
+}
+
+#[test]
+fn the_diff_names_each_change() {
+    let mut old = EcoFairnessSpec::default();
+    for route in ["donation", "lesson"] {
+        old.per_route_budgets.insert(route.into(), EcoEnvelope::node_default());
+    }
+    old.per_route_budgets.get_mut("altar").unwrap().max_compute_cycles = Cycles::new(1_000_000);
+    let mut next = old.clone();
+    next.global_roh_ceiling = 0.25;
+    next.per_route_budgets.remove("lesson");
+    next.per_route_budgets.insert("garden".into(), EcoEnvelope::node_default());
+    // Less power, more compute: both at once.
+    let altar = next.per_route_budgets.get_mut("altar").unwrap();
+    altar.max_power_watts = Watts::new(300.0);
+    altar.max_compute_cycles = Cycles::new(2_000_000);
+    next.per_route_budgets.get_mut("donation").unwrap().max_daily_kwh += 1.0;
+    next.altar_routes.retain(|r| r != "donation");
+    next.usage_windows.power_window_seconds = 60;
+
+    let diff = old.diff(&next);
+    assert_eq!(diff.roh_ceiling, Some((0.3, 0.25)));
+    assert!(!diff.roh_ceiling_loosened());
+    assert!(!diff.global_tightened && !diff.global_loosened);
+    assert_eq!(diff.routes_added, ["garden"]);
+    assert_eq!(diff.routes_removed, ["lesson"]);
+    assert_eq!(diff.budgets_tightened, ["altar"]);
+    assert_eq!(diff.budgets_loosened, ["altar", "donation"]);
+    assert_eq!(diff.altar_routes_removed, ["donation"]);
+    assert!(diff.usage_policy_changed);
+
+    // The same change read backwards.
+    let back = next.diff(&old);
+    assert!(back.roh_ceiling_loosened());
+    assert_eq!((back.routes_added, back.routes_removed), (vec!["lesson".to_string()], vec!["garden".to_string()]));
+    assert_eq!(back.budgets_tightened, ["altar", "donation"]);
+    assert_eq!(back.budgets_loosened, ["altar"]);
+}
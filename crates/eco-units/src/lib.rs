@@ -0,0 +1,218 @@
+//! Typed units for eco envelopes.
+//!
+//! The eco guards used to pass bare `f32`/`f64` around, so watts, joules
+//! and normalized fractions were interchangeable and precision was lost at
+//! crate boundaries. Each quantity here is a newtype: values of the same
+//! unit add and compare, mixing units does not compile, and leaving the
+//! type system takes an explicit `value()`. Conversions between units are
+//! named methods (`Watts::over_secs`), never `From` or `Deref`.
+//!
+//! Serde keeps the bare JSON number, so existing config files load
+//! unchanged. `ComputeFraction` validates `0.0..=1.0` on construction and
+//! on deserialization.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UnitError {
+    #[error("{unit} must be within {min}..={max}, got {value}")]
+    OutOfRange { unit: &'static str, value: f64, min: f64, max: f64 },
+}
+
+macro_rules! float_unit {
+    ($(#[$doc:meta])* $name:ident, $symbol:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(f64);
+
+        impl $name {
+            pub const ZERO: Self = Self(0.0);
+
+            pub const fn new(value: f64) -> Self {
+                Self(value)
+            }
+
+            pub const fn value(self) -> f64 {
+                self.0
+            }
+
+            /// Subtract, flooring at zero.
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                Self((self.0 - rhs.0).max(0.0))
+            }
+
+            /// `self / limit` as a plain ratio; 1.0 when `limit` is not positive.
+            pub fn ratio(self, limit: Self) -> f64 {
+                if limit.0 > 0.0 {
+                    self.0 / limit.0
+                } else {
+                    1.0
+                }
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                Self(self.0.max(other.0))
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        /// Scaling by a dimensionless factor.
+        impl Mul<f64> for $name {
+            type Output = Self;
+            fn mul(self, rhs: f64) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}{}", self.0, $symbol)
+            }
+        }
+    };
+}
+
+float_unit!(
+    /// Instantaneous power draw.
+    Watts, "W"
+);
+float_unit!(
+    /// Energy, e.g. cumulative draw over a window.
+    Joules, "J"
+);
+float_unit!(
+    /// CO2-equivalent emissions.
+    GramsCo2, "gCO2e"
+);
+
+impl Watts {
+    /// Energy used drawing this power for `secs` seconds.
+    pub fn over_secs(self, secs: f64) -> Joules {
+        Joules(self.0 * secs)
+    }
+}
+
+impl Joules {
+    /// Mean power if this energy is spread over `secs` seconds.
+    pub fn per_secs(self, secs: f64) -> Watts {
+        Watts(if secs > 0.0 { self.0 / secs } else { 0.0 })
+    }
+}
+
+/// Discrete compute work.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cycles(u64);
+
+impl Cycles {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Add for Cycles {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Cycles {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl fmt::Display for Cycles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} cycles", self.0)
+    }
+}
+
+/// Share of local compute capacity, always within `0.0..=1.0`.
+///
+/// There is deliberately no `Add`: a sum of fractions can leave the range,
+/// so projections work on `value()` and compare against a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct ComputeFraction(f64);
+
+impl ComputeFraction {
+    pub const ZERO: Self = Self(0.0);
+    pub const ONE: Self = Self(1.0);
+
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        if (0.0..=1.0).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err(UnitError::OutOfRange { unit: "ComputeFraction", value, min: 0.0, max: 1.0 })
+        }
+    }
+
+    /// Clamp into range; NaN becomes 0.
+    pub fn saturating(value: f64) -> Self {
+        Self(if value.is_nan() { 0.0 } else { value.clamp(0.0, 1.0) })
+    }
+
+    pub const fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ComputeFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}", self.0)
+    }
+}
+
+impl Serialize for ComputeFraction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComputeFraction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        Self::new(value).map_err(serde::de::Error::custom)
+    }
+}
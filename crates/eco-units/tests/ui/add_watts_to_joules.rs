@@ -0,0 +1,5 @@
+use eco_units::{Joules, Watts};
+
+fn main() {
+    let _ = Watts::new(100.0) + Joules::new(100.0);
+}
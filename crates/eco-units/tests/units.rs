@@ -0,0 +1,45 @@
+use eco_units::{ComputeFraction, Cycles, GramsCo2, Joules, UnitError, Watts};
+
+#[test]
+fn compute_fraction_validates_on_construction() {
+    assert_eq!(ComputeFraction::new(0.25).unwrap().value(), 0.25);
+    assert!(ComputeFraction::new(0.0).is_ok());
+    assert!(ComputeFraction::new(1.0).is_ok());
+    for bad in [-0.01, 1.01, f64::NAN, f64::INFINITY] {
+        assert!(matches!(ComputeFraction::new(bad), Err(UnitError::OutOfRange { .. })), "{} accepted", bad);
+    }
+    assert_eq!(ComputeFraction::saturating(1.7), ComputeFraction::ONE);
+    assert_eq!(ComputeFraction::saturating(f64::NAN), ComputeFraction::ZERO);
+}
+
+#[test]
+fn serde_keeps_bare_numbers() {
+    assert_eq!(serde_json::to_string(&Watts::new(850.5)).unwrap(), "850.5");
+    assert_eq!(serde_json::to_string(&Cycles::new(1_000)).unwrap(), "1000");
+    assert_eq!(serde_json::to_string(&ComputeFraction::new(0.4).unwrap()).unwrap(), "0.4");
+    let j: Joules = serde_json::from_str("1e6").unwrap();
+    assert_eq!(j, Joules::new(1.0e6));
+    let g: GramsCo2 = serde_json::from_str("12").unwrap();
+    assert_eq!(g.value(), 12.0);
+
+    let err = serde_json::from_str::<ComputeFraction>("1.5").unwrap_err();
+    assert!(err.to_string().contains("ComputeFraction"));
+}
+
+#[test]
+fn same_unit_arithmetic_and_explicit_conversions() {
+    let total = Watts::new(100.0) + Watts::new(50.0);
+    assert_eq!(total, Watts::new(150.0));
+    assert!(total > Watts::new(120.0));
+    assert_eq!(Watts::new(10.0).saturating_sub(Watts::new(20.0)), Watts::ZERO);
+    assert_eq!(Watts::new(50.0).ratio(Watts::new(200.0)), 0.25);
+    assert_eq!(Watts::new(50.0).over_secs(60.0), Joules::new(3_000.0));
+    assert_eq!(Joules::new(3_000.0).per_secs(60.0), Watts::new(50.0));
+    assert_eq!(Cycles::new(u64::MAX) + Cycles::new(1), Cycles::new(u64::MAX));
+}
+
+#[test]
+fn mixed_unit_arithmetic_does_not_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,77 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{ResourceUsageSnapshot, SnapshotBuilder, SnapshotBuilderConfig, XRAction, XRActionKind};
+use std::collections::HashMap;
+
+const CLASSES: usize = 10_000;
+
+fn cfg() -> SnapshotBuilderConfig {
+    SnapshotBuilderConfig {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        max_reading_age_secs: 30,
+    }
+}
+
+fn action(class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ScheduleJob,
+        subjectid: "s".into(),
+        route: "AUTO_CHURCH_LIVE".into(),
+        lifeforcecost: cost,
+        rohbefore: 0.1,
+        rohafterestimate: 0.1,
+        equity_class: Some(class.into()),
+    }
+}
+
+/// What the gate used to do: rebuild the whole snapshot from the active set.
+fn aggregate(cfg: &SnapshotBuilderConfig, active: &HashMap<String, (String, f32)>) -> ResourceUsageSnapshot {
+    let mut class_power: HashMap<String, f64> = HashMap::new();
+    let mut compute = 0.0;
+    for (class, cost) in active.values() {
+        *class_power.entry(class.clone()).or_default() += f64::from(*cost);
+        compute += f64::from(*cost) / f64::from(cfg.total_compute_capacity.max(1.0));
+    }
+    let budget = cfg.total_power_budget.value().max(1.0);
+    ResourceUsageSnapshot {
+        total_power_budget: cfg.total_power_budget,
+        total_compute_capacity: cfg.total_compute_capacity,
+        current_power_draw: Watts::new(class_power.values().sum()),
+        current_cumulative_energy: Joules::ZERO,
+        current_compute_fraction: ComputeFraction::saturating(compute),
+        class_shares: class_power.into_iter().map(|(c, p)| (c, (p / budget) as f32)).collect(),
+        degraded: false,
+    }
+}
+
+/// One admission and release at 10k active classes, incrementally and by re-aggregation.
+fn snapshot_update(c: &mut Criterion) {
+    let builder = SnapshotBuilder::new(cfg());
+    let mut active = HashMap::new();
+    for i in 0..CLASSES {
+        let class = format!("c{i}");
+        builder.commit(&format!("a{i}"), &class, &action(&class, 0.01)).unwrap();
+        active.insert(format!("a{i}"), (class, 0.01f32));
+    }
+
+    let mut group = c.benchmark_group("snapshot_update_10k_classes");
+    group.bench_function("incremental", |b| {
+        b.iter(|| {
+            builder.commit("extra", "c0", &action("c0", 0.01)).unwrap();
+            black_box(builder.current());
+            builder.release("extra").unwrap();
+        })
+    });
+    group.bench_function("reaggregation", |b| {
+        b.iter(|| {
+            active.insert("extra".into(), ("c0".into(), 0.01));
+            black_box(aggregate(&cfg(), &active));
+            active.remove("extra");
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, snapshot_update);
+criterion_main!(benches);
@@ -0,0 +1,246 @@
+//! Soft budget advisory for clients planning a batch of actions.
+//!
+//! Before building an expensive action a client can ask the guard how a
+//! batch would fare against current usage. `advise` runs the same checks
+//! as `check`, but only against a private copy of the snapshot: it never
+//! touches a `SnapshotBuilder`, a `GateDecisionCache` or its metrics, and
+//! it records nothing in a `HeadroomLedger`.
+//!
+//! The advisory reports, for the batch:
+//!
+//! - a verdict per action, each checked alone against the snapshot, so a
+//!   real `check` on that snapshot returns the same outcome;
+//! - the consumption of every envelope axis the batch touches, as a
+//!   fraction of its (degraded-scaled) limit, if all of it were admitted;
+//! - the limiting constraint, the most utilized of those axes;
+//! - how many actions would be admitted committed one after another in
+//!   the given order, and a cheapest-first order that admits at least as
+//!   many. Equity bounds are checked on every step, so no class is pushed
+//!   past its `max_share` to fit more actions in.
+//!
+//! An advisory taken with `advise_current` carries the builder's snapshot
+//! version; once usage has changed, `is_stale` says so.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{EcoFairnessGuard, GateDecisionCache, ResourceUsageSnapshot, SnapshotBuilder, XRAction};
+
+/// An action that passes with some axis at or above this fraction of its
+/// limit is reported as `Marginal`.
+pub const MARGINAL_UTILIZATION: f64 = 0.9;
+
+/// One dimension of the eco envelope or equity kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAxis {
+    Power,
+    Energy,
+    Compute,
+    ClassShare,
+}
+
+/// Projected use of one axis: `scope` is the route for envelope axes and
+/// the equity class for `ClassShare`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxisLoad {
+    pub scope: String,
+    pub axis: BudgetAxis,
+    /// Projected value over its limit; above 1.0 the limit is exceeded.
+    pub utilization: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Verdict {
+    WouldPass,
+    /// Passes, but leaves `load` at or above `MARGINAL_UTILIZATION`.
+    Marginal {
+        load: AxisLoad,
+    },
+    /// The `GuardError` code `check` would return.
+    WouldFail {
+        code: String,
+    },
+}
+
+impl Verdict {
+    pub fn would_pass(&self) -> bool {
+        !matches!(self, Verdict::WouldFail { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetAdvisory {
+    /// `SnapshotBuilder` version the advisory was computed against; `None`
+    /// for an advisory over a caller-supplied snapshot.
+    pub snapshot_version: Option<u64>,
+    /// One per action, in input order.
+    pub verdicts: Vec<Verdict>,
+    /// Every axis the batch touches, if all of it were admitted: route
+    /// axes by route, then class shares by class.
+    pub consumption: Vec<AxisLoad>,
+    /// The most utilized entry of `consumption`.
+    pub limiting: Option<AxisLoad>,
+    /// Actions admitted when committed one after another in input order.
+    pub admissible_as_given: usize,
+    /// Indices into the input: the admitted actions in commit order, then the rest.
+    pub suggested_order: Vec<usize>,
+    /// Actions admitted in `suggested_order`; never below `admissible_as_given`.
+    pub admissible_in_suggested_order: usize,
+}
+
+fn ratio(value: f64, limit: f64) -> f64 {
+    if limit > 0.0 {
+        value / limit
+    } else {
+        f64::INFINITY
+    }
+}
+
+impl BudgetAdvisory {
+    /// Usage has changed since the advisory was computed. An advisory
+    /// without a version is always stale.
+    pub fn is_stale(&self, usage: &SnapshotBuilder) -> bool {
+        self.snapshot_version != Some(usage.version())
+    }
+}
+
+impl EcoFairnessGuard {
+    /// Project `actions` against `snapshot` without admitting any of them.
+    pub fn advise(&self, actions: &[XRAction], snapshot: &ResourceUsageSnapshot) -> BudgetAdvisory {
+        let verdicts = actions.iter().map(|action| self.verdict(action, snapshot)).collect();
+        let consumption = self.batch_consumption(actions, snapshot);
+        let limiting = consumption.iter().max_by(|a, b| a.utilization.total_cmp(&b.utilization)).cloned();
+
+        let given: Vec<usize> = (0..actions.len()).collect();
+        let (admitted_given, _) = self.admit_in_order(actions, snapshot, &given);
+        let mut cheapest = given.clone();
+        cheapest.sort_by(|&a, &b| actions[a].lifeforcecost.total_cmp(&actions[b].lifeforcecost));
+        let (admitted_cheapest, rest) = self.admit_in_order(actions, snapshot, &cheapest);
+        let (suggested_order, admissible_in_suggested_order) = if admitted_cheapest.len() >= admitted_given.len() {
+            let count = admitted_cheapest.len();
+            (admitted_cheapest.into_iter().chain(rest).collect(), count)
+        } else {
+            (given, admitted_given.len())
+        };
+
+        BudgetAdvisory {
+            snapshot_version: None,
+            verdicts,
+            consumption,
+            limiting,
+            admissible_as_given: admitted_given.len(),
+            suggested_order,
+            admissible_in_suggested_order,
+        }
+    }
+
+    /// `advise` against the builder's current view, stamped with its version.
+    pub fn advise_current(&self, actions: &[XRAction], usage: &SnapshotBuilder) -> BudgetAdvisory {
+        let (version, snapshot) = usage.current_versioned();
+        BudgetAdvisory { snapshot_version: Some(version), ..self.advise(actions, &snapshot) }
+    }
+
+    fn verdict(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> Verdict {
+        if let Err(e) = self.check(action, snapshot) {
+            return Verdict::WouldFail { code: e.code };
+        }
+        match self.loads(action, snapshot).into_iter().max_by(|a, b| a.utilization.total_cmp(&b.utilization)) {
+            Some(load) if load.utilization >= MARGINAL_UTILIZATION => Verdict::Marginal { load },
+            _ => Verdict::WouldPass,
+        }
+    }
+
+    fn loads(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> Vec<AxisLoad> {
+        let mut loads = self.envelope_loads(&action.route, snapshot, action.lifeforcecost);
+        if let Ok(class) = self.resolve_class(action) {
+            loads.extend(self.share_load(&class, snapshot, action.lifeforcecost));
+        }
+        loads
+    }
+
+    /// Power, energy and compute of `route`'s envelope with `cost` added on top of `snapshot`.
+    fn envelope_loads(&self, route: &str, snapshot: &ResourceUsageSnapshot, cost: f32) -> Vec<AxisLoad> {
+        let Some(env) = self.cfg.tsafe_envelopes.get(route) else {
+            return Vec::new();
+        };
+        let scale = self.envelope_scale(route, snapshot);
+        let cost = f64::from(cost);
+        let compute =
+            snapshot.current_compute_fraction.value() + cost / f64::from(snapshot.total_compute_capacity.max(1.0));
+        [
+            (BudgetAxis::Power, snapshot.current_power_draw.value() + cost, env.max_power.value()),
+            (BudgetAxis::Energy, snapshot.current_cumulative_energy.value() + cost, env.max_cumulative_energy.value()),
+            (BudgetAxis::Compute, compute, env.max_compute_fraction.value()),
+        ]
+        .into_iter()
+        .map(|(axis, value, limit)| AxisLoad {
+            scope: route.to_string(),
+            axis,
+            utilization: ratio(value, limit * scale),
+        })
+        .collect()
+    }
+
+    /// `class`'s share with `cost` of power added, against its `max_share`.
+    fn share_load(&self, class: &str, snapshot: &ResourceUsageSnapshot, cost: f32) -> Option<AxisLoad> {
+        let bounds = self.cfg.grace_equity.bounds_for_class(class)?;
+        let share = f64::from(snapshot.class_shares.get(class).copied().unwrap_or(0.0))
+            + f64::from(cost) / snapshot.total_power_budget.value().max(1.0);
+        let limit = f64::from(bounds.max_share) * snapshot.headroom_scale();
+        Some(AxisLoad { scope: class.to_string(), axis: BudgetAxis::ClassShare, utilization: ratio(share, limit) })
+    }
+
+    fn batch_consumption(&self, actions: &[XRAction], snapshot: &ResourceUsageSnapshot) -> Vec<AxisLoad> {
+        // Usage is shared, so every route's envelope sees the whole batch;
+        // a class share only grows by its own actions.
+        let total: f32 = actions.iter().map(|a| a.lifeforcecost).sum();
+        let mut by_class: BTreeMap<String, f32> = BTreeMap::new();
+        for action in actions {
+            if let Ok(class) = self.resolve_class(action) {
+                *by_class.entry(class).or_default() += action.lifeforcecost;
+            }
+        }
+        let routes: BTreeSet<&str> = actions.iter().map(|a| a.route.as_str()).collect();
+        let mut consumption: Vec<AxisLoad> =
+            routes.into_iter().flat_map(|route| self.envelope_loads(route, snapshot, total)).collect();
+        consumption.extend(by_class.iter().filter_map(|(class, cost)| self.share_load(class, snapshot, *cost)));
+        consumption
+    }
+
+    /// Check `order` one action at a time against a private copy of
+    /// `snapshot`, adding each admitted action's usage the way a
+    /// `SnapshotBuilder` commit does. Returns (admitted, rejected) indices.
+    fn admit_in_order(
+        &self,
+        actions: &[XRAction],
+        snapshot: &ResourceUsageSnapshot,
+        order: &[usize],
+    ) -> (Vec<usize>, Vec<usize>) {
+        let mut sim = snapshot.clone();
+        let (mut admitted, mut rejected) = (Vec::new(), Vec::new());
+        for &i in order {
+            let action = &actions[i];
+            if self.check(action, &sim).is_err() {
+                rejected.push(i);
+                continue;
+            }
+            sim = self.project(action, &sim);
+            admitted.push(i);
+        }
+        (admitted, rejected)
+    }
+}
+
+impl GateDecisionCache {
+    /// The wrapped guard's `advise`. Reads no cache entries and counts
+    /// nothing in `metrics()`.
+    pub fn advise(&self, actions: &[XRAction], snapshot: &ResourceUsageSnapshot) -> BudgetAdvisory {
+        self.guard().advise(actions, snapshot)
+    }
+
+    pub fn advise_current(&self, actions: &[XRAction], usage: &SnapshotBuilder) -> BudgetAdvisory {
+        self.guard().advise_current(actions, usage)
+    }
+}
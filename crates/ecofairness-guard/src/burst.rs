@@ -0,0 +1,375 @@
+//! Pre-approved temporary envelope expansions ("burst windows").
+//!
+//! Holiday services and cleanup drives need more than a route's normal
+//! envelope for a few hours. Instead of editing `.eco-fairness.aln` and
+//! reverting by hand, operators issue a `BurstGrant`: per-route
+//! multipliers (at most `BurstConfig::max_multiplier`), a `[starts_at,
+//! ends_at)` window and a justification, signed by `quorum` distinct
+//! approver keys. Inside the window the guard multiplies that route's
+//! power, energy and compute limits; outside it, or once the same quorum
+//! has revoked the grant, the limits are the configured ones again with
+//! nothing to undo.
+//!
+//! Bursts never touch the RoH ceiling or the equity kernel: only the
+//! route envelope checks read the multiplier, and class `max_share`
+//! bounds still apply to every admission made under a burst.
+//!
+//! `EcoFairnessGuard::check_and_record` reports outcomes on burst routes
+//! here, kept apart from the headroom ledger. When a window closes
+//! `drain_debriefs` returns its `BurstDebrief` (granted vs. actual
+//! consumption and per-class outcomes) for logging as a `burst_debrief` deed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+
+use keyring::{KeyringError, KeyringSignature, SignatureVerifier, VerifyingBundle};
+
+use crate::class_assignment::system_now;
+use crate::{GuardError, XRAction};
+
+#[derive(thiserror::Error, Debug)]
+pub enum BurstError {
+    #[error("burst approver signature rejected: {0}")]
+    Signature(#[from] KeyringError),
+    #[error("{have} distinct approver signature(s), {need} required")]
+    Quorum { have: usize, need: usize },
+    #[error("multiplier {multiplier} for route '{route}' is outside [1, {max}]")]
+    Multiplier { route: String, multiplier: f64, max: f64 },
+    #[error("grant '{0}' names no routes")]
+    NoRoutes(String),
+    #[error("grant '{0}' has an empty window")]
+    EmptyWindow(String),
+    #[error("grant '{id}' overlaps grant '{existing}' on route '{route}'")]
+    Overlap { id: String, existing: String, route: String },
+    #[error("grant '{0}' already exists")]
+    Duplicate(String),
+    #[error("no grant '{0}'")]
+    UnknownGrant(String),
+}
+
+#[derive(Serialize)]
+struct GrantBody<'a> {
+    op: &'a str,
+    id: &'a str,
+    multipliers: &'a BTreeMap<String, f64>,
+    starts_at: u64,
+    ends_at: u64,
+    justification: &'a str,
+}
+
+#[derive(Serialize)]
+struct RevokeBody<'a> {
+    op: &'a str,
+    id: &'a str,
+}
+
+/// A multisig-approved temporary expansion of route envelopes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurstGrant {
+    pub id: String,
+    /// Route → factor applied to its power, energy and compute limits.
+    pub multipliers: BTreeMap<String, f64>,
+    /// Unix seconds, inclusive.
+    pub starts_at: u64,
+    /// Unix seconds, exclusive.
+    pub ends_at: u64,
+    pub justification: String,
+    pub signatures: Vec<KeyringSignature>,
+}
+
+impl BurstGrant {
+    /// The bytes every approver signs.
+    pub fn signing_body(&self) -> Vec<u8> {
+        serde_json::to_vec(&GrantBody {
+            op: "burst_grant",
+            id: &self.id,
+            multipliers: &self.multipliers,
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            justification: &self.justification,
+        })
+        .expect("grant body serializes")
+    }
+}
+
+/// Withdrawal of a grant, signed by a quorum of approvers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BurstRevocation {
+    pub grant_id: String,
+    pub reason: String,
+    pub signatures: Vec<KeyringSignature>,
+}
+
+impl BurstRevocation {
+    pub fn signing_body(grant_id: &str) -> Vec<u8> {
+        serde_json::to_vec(&RevokeBody { op: "burst_revoke", id: grant_id }).expect("revoke body serializes")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurstConfig {
+    /// Upper bound for any route multiplier.
+    pub max_multiplier: f64,
+    /// Distinct approver keys required to grant or revoke.
+    pub quorum: usize,
+}
+
+impl Default for BurstConfig {
+    fn default() -> Self {
+        Self { max_multiplier: 2.0, quorum: 2 }
+    }
+}
+
+/// What happened on one class during a window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BurstClassOutcome {
+    pub admitted: u64,
+    pub denied: u64,
+    /// Sum of `lifeforcecost` over admitted actions.
+    pub admitted_cost: f64,
+}
+
+/// Consumption on one burst route during a window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BurstRouteUsage {
+    pub multiplier: f64,
+    pub admitted: u64,
+    /// Admissions the route's configured envelope alone would have denied.
+    pub admitted_beyond_base: u64,
+    /// Sum of `lifeforcecost` over admitted actions.
+    pub admitted_cost: f64,
+    /// `lifeforcecost` of the admissions beyond the base envelope.
+    pub burst_cost: f64,
+    /// Highest projected power draw admitted, as a fraction of the base limit.
+    pub peak_power_utilization: f64,
+}
+
+/// End-of-window report for one grant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurstDebrief {
+    pub grant_id: String,
+    pub justification: String,
+    pub approvers: Vec<String>,
+    pub starts_at: u64,
+    /// Granted end of the window.
+    pub ends_at: u64,
+    /// When the window actually closed: `ends_at`, or the revocation time.
+    pub closed_at: u64,
+    pub revoked: Option<String>,
+    pub routes: BTreeMap<String, BurstRouteUsage>,
+    pub classes: BTreeMap<String, BurstClassOutcome>,
+}
+
+impl BurstDebrief {
+    /// Context payload for the `burst_debrief` deed.
+    pub fn to_deed_context(&self) -> serde_json::Value {
+        serde_json::json!({ "deed_type": "burst_debrief", "burst_debrief": self })
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    grant: BurstGrant,
+    closed_at: Option<u64>,
+    revoked: Option<String>,
+    debriefed: bool,
+    routes: BTreeMap<String, BurstRouteUsage>,
+    classes: BTreeMap<String, BurstClassOutcome>,
+}
+
+impl Window {
+    fn end(&self) -> u64 {
+        self.closed_at.unwrap_or(self.grant.ends_at)
+    }
+
+    fn is_open(&self, now: u64) -> bool {
+        self.grant.starts_at <= now && now < self.end()
+    }
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+/// Approved burst grants and their accounting.
+pub struct BurstWindows {
+    cfg: BurstConfig,
+    approvers: VerifyingBundle,
+    windows: BTreeMap<String, Window>,
+    clock: Clock,
+}
+
+/// Handle shared between the guard and whoever administers grants.
+pub type SharedBurstWindows = Arc<RwLock<BurstWindows>>;
+
+impl std::fmt::Debug for BurstWindows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BurstWindows").field("cfg", &self.cfg).field("windows", &self.windows.len()).finish()
+    }
+}
+
+impl BurstWindows {
+    pub fn new(cfg: BurstConfig, approvers: VerifyingBundle) -> Self {
+        Self { cfg, approvers, windows: BTreeMap::new(), clock: Box::new(system_now) }
+    }
+
+    /// Replace the wall clock (Unix seconds) used by the guard; tests and replays.
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn into_shared(self) -> SharedBurstWindows {
+        Arc::new(RwLock::new(self))
+    }
+
+    pub fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    pub fn config(&self) -> &BurstConfig {
+        &self.cfg
+    }
+
+    /// Distinct approver keys that validly signed `body`.
+    fn approvers_of(&self, body: &[u8], signatures: &[KeyringSignature]) -> Result<Vec<String>, BurstError> {
+        let mut keys = BTreeSet::new();
+        for sig in signatures {
+            self.approvers.verify(body, sig)?;
+            keys.insert(sig.key.clone());
+        }
+        if keys.len() < self.cfg.quorum {
+            return Err(BurstError::Quorum { have: keys.len(), need: self.cfg.quorum });
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Record a grant after checking its signatures, multipliers and that
+    /// no other open or future grant covers one of its routes at the same time.
+    pub fn grant(&mut self, grant: BurstGrant) -> Result<(), BurstError> {
+        if self.windows.contains_key(&grant.id) {
+            return Err(BurstError::Duplicate(grant.id));
+        }
+        if grant.multipliers.is_empty() {
+            return Err(BurstError::NoRoutes(grant.id));
+        }
+        if grant.ends_at <= grant.starts_at {
+            return Err(BurstError::EmptyWindow(grant.id));
+        }
+        let max = self.cfg.max_multiplier;
+        if let Some((route, &multiplier)) = grant.multipliers.iter().find(|(_, &m)| !(1.0..=max).contains(&m)) {
+            return Err(BurstError::Multiplier { route: route.clone(), multiplier, max });
+        }
+        self.approvers_of(&grant.signing_body(), &grant.signatures)?;
+        for (id, window) in &self.windows {
+            let overlaps = grant.starts_at < window.end() && window.grant.starts_at < grant.ends_at;
+            if let Some(route) =
+                grant.multipliers.keys().find(|r| overlaps && window.grant.multipliers.contains_key(*r))
+            {
+                return Err(BurstError::Overlap { id: grant.id, existing: id.clone(), route: route.clone() });
+            }
+        }
+        let routes = grant
+            .multipliers
+            .iter()
+            .map(|(route, &multiplier)| (route.clone(), BurstRouteUsage { multiplier, ..BurstRouteUsage::default() }))
+            .collect();
+        self.windows.insert(
+            grant.id.clone(),
+            Window { grant, closed_at: None, revoked: None, debriefed: false, routes, classes: BTreeMap::new() },
+        );
+        Ok(())
+    }
+
+    /// Close a grant now. The next check already sees the configured envelope.
+    pub fn revoke(&mut self, revocation: BurstRevocation) -> Result<(), BurstError> {
+        let now = self.now();
+        let body = BurstRevocation::signing_body(&revocation.grant_id);
+        self.approvers_of(&body, &revocation.signatures)?;
+        let window =
+            self.windows.get_mut(&revocation.grant_id).ok_or(BurstError::UnknownGrant(revocation.grant_id.clone()))?;
+        if now < window.end() {
+            window.closed_at = Some(now.max(window.grant.starts_at));
+            window.revoked = Some(revocation.reason);
+        }
+        Ok(())
+    }
+
+    /// The grant open on `route` at `now`, if any.
+    pub fn active_grant(&self, route: &str, now: u64) -> Option<&BurstGrant> {
+        self.open_window(route, now).map(|w| &w.grant)
+    }
+
+    fn open_window(&self, route: &str, now: u64) -> Option<&Window> {
+        self.windows.values().find(|w| w.is_open(now) && w.grant.multipliers.contains_key(route))
+    }
+
+    /// Factor applied to `route`'s envelope at `now`; 1.0 outside any window.
+    pub fn multiplier(&self, route: &str, now: u64) -> f64 {
+        self.open_window(route, now).map_or(1.0, |w| w.grant.multipliers[route])
+    }
+
+    /// Account one checked action on a burst route. `beyond_base` marks an
+    /// admission the configured envelope alone would have denied, and
+    /// `power_utilization` is its projected draw over the base limit.
+    pub(crate) fn observe(
+        &mut self,
+        action: &XRAction,
+        class: Option<&str>,
+        outcome: &Result<(), GuardError>,
+        beyond_base: bool,
+        power_utilization: f64,
+        now: u64,
+    ) {
+        let Some(window) =
+            self.windows.values_mut().find(|w| w.is_open(now) && w.grant.multipliers.contains_key(&action.route))
+        else {
+            return;
+        };
+        let cost = f64::from(action.lifeforcecost);
+        let class = window.classes.entry(class.unwrap_or("?").to_string()).or_default();
+        if outcome.is_err() {
+            class.denied += 1;
+            return;
+        }
+        class.admitted += 1;
+        class.admitted_cost += cost;
+        let usage = window.routes.get_mut(&action.route).expect("grant routes are tracked");
+        usage.admitted += 1;
+        usage.admitted_cost += cost;
+        usage.peak_power_utilization = usage.peak_power_utilization.max(power_utilization);
+        if beyond_base {
+            usage.admitted_beyond_base += 1;
+            usage.burst_cost += cost;
+        }
+    }
+
+    /// Debriefs for every window that has closed since the last call.
+    pub fn drain_debriefs(&mut self) -> Vec<BurstDebrief> {
+        let now = self.now();
+        let mut debriefs = Vec::new();
+        for window in self.windows.values_mut().filter(|w| !w.debriefed && w.end() <= now) {
+            window.debriefed = true;
+            let body = window.grant.signing_body();
+            debriefs.push(BurstDebrief {
+                grant_id: window.grant.id.clone(),
+                justification: window.grant.justification.clone(),
+                approvers: window
+                    .grant
+                    .signatures
+                    .iter()
+                    .filter(|s| self.approvers.verify(&body, s).is_ok())
+                    .map(|s| s.key.clone())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+                starts_at: window.grant.starts_at,
+                ends_at: window.grant.ends_at,
+                closed_at: window.end(),
+                revoked: window.revoked.clone(),
+                routes: window.routes.clone(),
+                classes: window.classes.clone(),
+            });
+        }
+        debriefs
+    }
+}
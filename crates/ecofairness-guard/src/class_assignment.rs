@@ -0,0 +1,373 @@
+//! Verified equity-class membership.
+//!
+//! `XRAction::equity_class` is chosen by the caller, so on its own it lets
+//! any subject claim a privileged class. A `ClassRegistry` records which
+//! class each subject actually belongs to, signed by an assignment
+//! authority and valid for a limited time. The guard resolves the class
+//! from the registry and treats the caller's value as a hint only.
+//!
+//! The signed body is `{op, subject, class, ttl_secs}`; the keyring
+//! signature's `signed_at` is the issue time, so `expires_at` is covered
+//! by the signature without a separate field.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, path::Path};
+
+use keyring::{Keyring, KeyringError, KeyringSignature, SignatureVerifier, VerifyingBundle};
+
+use crate::GraceEquityKernel;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClassRegistryError {
+    #[error("assignment authority signature rejected: {0}")]
+    Signature(#[from] KeyringError),
+    #[error("subject and class must not be empty")]
+    EmptyField,
+    #[error("ttl_secs must be positive")]
+    ZeroTtl,
+    #[error("subject '{0}' has no assignment")]
+    NotAssigned(String),
+    #[error("invalid roster: {}", .0.join("; "))]
+    Roster(Vec<String>),
+    #[error("I/O error reading roster: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse error in roster: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+struct SignedBody<'a> {
+    op: &'a str,
+    subject: &'a str,
+    class: &'a str,
+    ttl_secs: u64,
+}
+
+fn body(op: &str, subject: &str, class: &str, ttl_secs: u64) -> Vec<u8> {
+    serde_json::to_vec(&SignedBody { op, subject, class, ttl_secs }).expect("signed body serializes")
+}
+
+/// One signed membership grant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassAssignment {
+    pub subject: String,
+    pub class: String,
+    pub ttl_secs: u64,
+    pub signature: KeyringSignature,
+}
+
+impl ClassAssignment {
+    pub fn issued_at(&self) -> u64 {
+        self.signature.signed_at
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.issued_at().saturating_add(self.ttl_secs)
+    }
+
+    pub fn verify(&self, authorities: &impl SignatureVerifier) -> Result<(), KeyringError> {
+        authorities.verify(&body("assign", &self.subject, &self.class, self.ttl_secs), &self.signature)
+    }
+}
+
+/// Signed withdrawal of a subject's class, effective from its signing time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassRevocation {
+    pub subject: String,
+    pub class: String,
+    pub signature: KeyringSignature,
+}
+
+impl ClassRevocation {
+    pub fn verify(&self, authorities: &impl SignatureVerifier) -> Result<(), KeyringError> {
+        authorities.verify(&body("revoke", &self.subject, &self.class, 0), &self.signature)
+    }
+}
+
+/// The class the guard should use for a subject at a given time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedClass {
+    pub subject: String,
+    pub class: String,
+    /// Key of the authority that signed the assignment; None for fallback.
+    pub authority: Option<String>,
+    /// None for fallback.
+    pub expires_at: Option<u64>,
+    /// True when the assignment expired and the default class applies.
+    pub fallback: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassEventKind {
+    Assignment,
+    Renewal,
+    Revocation,
+}
+
+impl ClassEventKind {
+    pub fn deed_type(&self) -> &'static str {
+        match self {
+            Self::Assignment => "class_assignment",
+            Self::Renewal => "class_renewal",
+            Self::Revocation => "class_revocation",
+        }
+    }
+}
+
+/// A registry change awaiting persistence as a deed; see `drain_events`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassEvent {
+    pub kind: ClassEventKind,
+    pub subject: String,
+    pub class: String,
+    pub authority: String,
+    /// Unix seconds.
+    pub at: u64,
+    pub expires_at: Option<u64>,
+    pub reason: Option<String>,
+}
+
+impl ClassEvent {
+    /// Context payload for the `class_*` deed.
+    pub fn to_deed_context(&self) -> serde_json::Value {
+        serde_json::json!({ "deed_type": self.kind.deed_type(), "class_event": self })
+    }
+}
+
+/// One line of a bulk roster file (a JSON array of these).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    pub subject: String,
+    pub class: String,
+    pub ttl_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassRegistryConfig {
+    /// Class applied once an assignment expires. Configure it in the
+    /// GraceEquityKernel with reduced floors.
+    pub default_class: String,
+}
+
+impl Default for ClassRegistryConfig {
+    fn default() -> Self {
+        Self { default_class: "remote_congregation".into() }
+    }
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+pub(crate) fn system_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Subject → equity class, trusted only when signed by one of `authorities`.
+pub struct ClassRegistry {
+    cfg: ClassRegistryConfig,
+    authorities: VerifyingBundle,
+    assignments: BTreeMap<String, ClassAssignment>,
+    revocations: BTreeMap<String, ClassRevocation>,
+    events: Vec<ClassEvent>,
+    clock: Clock,
+}
+
+/// Handle shared between the guard and whoever administers assignments.
+pub type SharedClassRegistry = Arc<RwLock<ClassRegistry>>;
+
+impl std::fmt::Debug for ClassRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClassRegistry")
+            .field("cfg", &self.cfg)
+            .field("assignments", &self.assignments.len())
+            .field("revocations", &self.revocations.len())
+            .finish()
+    }
+}
+
+impl ClassRegistry {
+    pub fn new(cfg: ClassRegistryConfig, authorities: VerifyingBundle) -> Self {
+        Self {
+            cfg,
+            authorities,
+            assignments: BTreeMap::new(),
+            revocations: BTreeMap::new(),
+            events: Vec::new(),
+            clock: Box::new(system_now),
+        }
+    }
+
+    /// Replace the wall clock (Unix seconds) used by the guard; tests and replays.
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn into_shared(self) -> SharedClassRegistry {
+        Arc::new(RwLock::new(self))
+    }
+
+    pub fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    pub fn config(&self) -> &ClassRegistryConfig {
+        &self.cfg
+    }
+
+    /// Sign and record `subject ∈ class` for `ttl_secs`. Re-assigning the
+    /// same class before expiry is logged as a renewal.
+    pub fn assign(
+        &mut self,
+        subject: &str,
+        class: &str,
+        authority: &Keyring,
+        authority_key: &str,
+        ttl_secs: u64,
+    ) -> Result<&ClassAssignment, ClassRegistryError> {
+        if subject.trim().is_empty() || class.trim().is_empty() {
+            return Err(ClassRegistryError::EmptyField);
+        }
+        if ttl_secs == 0 {
+            return Err(ClassRegistryError::ZeroTtl);
+        }
+        let signature = authority.sign(authority_key, &body("assign", subject, class, ttl_secs))?;
+        self.insert(ClassAssignment { subject: subject.into(), class: class.into(), ttl_secs, signature })
+    }
+
+    /// Record an assignment signed elsewhere, after verifying it.
+    pub fn insert(&mut self, assignment: ClassAssignment) -> Result<&ClassAssignment, ClassRegistryError> {
+        assignment.verify(&self.authorities)?;
+        let renewal = self.assignments.get(&assignment.subject).is_some_and(|prev| {
+            prev.class == assignment.class
+                && assignment.issued_at() < prev.expires_at()
+                && !self.revocations.contains_key(&assignment.subject)
+        });
+        self.events.push(ClassEvent {
+            kind: if renewal { ClassEventKind::Renewal } else { ClassEventKind::Assignment },
+            subject: assignment.subject.clone(),
+            class: assignment.class.clone(),
+            authority: assignment.signature.key.clone(),
+            at: assignment.issued_at(),
+            expires_at: Some(assignment.expires_at()),
+            reason: None,
+        });
+        self.revocations.remove(&assignment.subject);
+        let subject = assignment.subject.clone();
+        self.assignments.insert(subject.clone(), assignment);
+        Ok(&self.assignments[&subject])
+    }
+
+    /// Withdraw `subject`'s class from now on. The subject gets no class
+    /// (not the default) until re-assigned.
+    pub fn revoke(
+        &mut self,
+        subject: &str,
+        authority: &Keyring,
+        authority_key: &str,
+        reason: &str,
+    ) -> Result<&ClassRevocation, ClassRegistryError> {
+        let class = self
+            .assignments
+            .get(subject)
+            .map(|a| a.class.clone())
+            .ok_or_else(|| ClassRegistryError::NotAssigned(subject.to_string()))?;
+        let signature = authority.sign(authority_key, &body("revoke", subject, &class, 0))?;
+        let revocation = ClassRevocation { subject: subject.into(), class, signature };
+        revocation.verify(&self.authorities)?;
+        self.events.push(ClassEvent {
+            kind: ClassEventKind::Revocation,
+            subject: revocation.subject.clone(),
+            class: revocation.class.clone(),
+            authority: revocation.signature.key.clone(),
+            at: revocation.signature.signed_at,
+            expires_at: None,
+            reason: Some(reason.to_string()),
+        });
+        self.revocations.insert(subject.to_string(), revocation);
+        Ok(&self.revocations[subject])
+    }
+
+    /// The class `subject` holds at `at`: the signed assignment while it is
+    /// valid, the default class once it has expired, and None if the
+    /// subject was never assigned or has been revoked.
+    pub fn class_of(&self, subject: &str, at: u64) -> Option<VerifiedClass> {
+        let assignment = self.assignments.get(subject)?;
+        if self.revocations.get(subject).is_some_and(|r| at >= r.signature.signed_at) {
+            return None;
+        }
+        // Re-check in case the record was altered after insertion.
+        assignment.verify(&self.authorities).ok()?;
+        if at < assignment.issued_at() {
+            return None;
+        }
+        if at >= assignment.expires_at() {
+            return Some(VerifiedClass {
+                subject: subject.to_string(),
+                class: self.cfg.default_class.clone(),
+                authority: None,
+                expires_at: None,
+                fallback: true,
+            });
+        }
+        Some(VerifiedClass {
+            subject: subject.to_string(),
+            class: assignment.class.clone(),
+            authority: Some(assignment.signature.key.clone()),
+            expires_at: Some(assignment.expires_at()),
+            fallback: false,
+        })
+    }
+
+    pub fn assignments(&self) -> impl Iterator<Item = &ClassAssignment> {
+        self.assignments.values()
+    }
+
+    /// Remove and return pending events, to be persisted as deeds.
+    pub fn drain_events(&mut self) -> Vec<ClassEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Assign every entry of a JSON roster file. The whole roster is
+    /// validated first (non-empty fields, positive TTL, no duplicate
+    /// subjects, classes known to `kernel`); nothing is assigned if any
+    /// entry fails. Returns the number of assignments made.
+    pub fn import_roster<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        kernel: &GraceEquityKernel,
+        authority: &Keyring,
+        authority_key: &str,
+    ) -> Result<usize, ClassRegistryError> {
+        let raw = fs::read_to_string(path)?;
+        let entries: Vec<RosterEntry> = serde_json::from_str(&raw)?;
+
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+        for (i, e) in entries.iter().enumerate() {
+            if e.subject.trim().is_empty() || e.class.trim().is_empty() {
+                problems.push(format!("entry {}: subject and class must not be empty", i));
+            }
+            if e.ttl_secs == 0 {
+                problems.push(format!("entry {}: ttl_secs must be positive", i));
+            }
+            if kernel.bounds_for_class(&e.class).is_none() {
+                problems.push(format!("entry {}: unknown equity class '{}'", i, e.class));
+            }
+            if !seen.insert(e.subject.as_str()) {
+                problems.push(format!("entry {}: duplicate subject '{}'", i, e.subject));
+            }
+        }
+        if !problems.is_empty() {
+            return Err(ClassRegistryError::Roster(problems));
+        }
+
+        for e in &entries {
+            self.assign(&e.subject, &e.class, authority, authority_key, e.ttl_secs)?;
+        }
+        Ok(entries.len())
+    }
+}
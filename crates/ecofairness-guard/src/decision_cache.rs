@@ -0,0 +1,405 @@
+//! Allow-decision cache for repeated read-only gate checks.
+//!
+//! Under XR workloads the gate evaluates the same read authorization many
+//! times a second with identical inputs. `GateDecisionCache` wraps an
+//! `EcoFairnessGuard` and remembers Allow decisions for read-only kinds,
+//! keyed by subject, route, kind, resolved equity class and a quantized
+//! `lifeforcecost` bucket, for `ttl_secs`. Deny decisions are never cached,
+//! so a recovered subject or route is admitted on the next check, and
+//! mutating kinds never build a key at all.
+//!
+//! A cached Allow must hold for every action and snapshot it may answer:
+//!
+//! - it is only stored if the action still passes at the top of its cost
+//!   bucket against the snapshot with every usage axis (power, energy,
+//!   compute, class share) raised by `usage_tolerance` of its limit;
+//! - an entry is dropped once any of those axes has risen by more than
+//!   the tolerance, or the snapshot's degraded flag flipped, both by
+//!   `on_usage` and lazily on lookup;
+//! - the margin is checked against the configured envelope, ignoring any
+//!   burst window, so an entry stays valid when a burst ends or is revoked;
+//! - the `min_share` floor check reads total power and compute, which a
+//!   starved class releasing usage lowers by as much as its unmet floor
+//!   grows, so the same stressed axes cover it;
+//! - the RoH ceiling and monotonicity checks read only the action, so they
+//!   run on every hit; the equity class is resolved on every lookup.
+//!
+//! Spec reloads and RoH model updates flush everything; a
+//! maintenance-window change flushes its route. Hit, miss, bypass,
+//! eviction and per-cause invalidation counts are in `metrics()`.
+
+use eco_units::{ComputeFraction, Joules, Watts};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard};
+
+use crate::class_assignment::system_now;
+use crate::{EcoFairnessGuard, EcoFairnessResult, ResourceUsageSnapshot, RohModel, XRAction, XRActionKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionCacheConfig {
+    /// Entries kept; the least recently used is evicted beyond this.
+    pub capacity: usize,
+    pub ttl_secs: u64,
+    /// Width of a `lifeforcecost` bucket.
+    pub cost_bucket: f32,
+    /// Drift allowed on each usage axis before an entry is invalidated,
+    /// as a fraction of that axis' limit.
+    pub usage_tolerance: f64,
+}
+
+impl Default for DecisionCacheConfig {
+    fn default() -> Self {
+        Self { capacity: 4096, ttl_secs: 2, cost_bucket: 1.0, usage_tolerance: 0.02 }
+    }
+}
+
+/// Why cached entries were dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invalidation {
+    SpecReload,
+    Usage,
+    RohUpdate,
+    MaintenanceWindow,
+    Expired,
+}
+
+/// Counters for the gate's metrics export.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    /// Checks of mutating kinds, which never consult the cache.
+    pub bypassed: u64,
+    pub evictions: u64,
+    pub invalidated_spec_reload: u64,
+    pub invalidated_usage: u64,
+    pub invalidated_roh: u64,
+    pub invalidated_maintenance: u64,
+    pub expired: u64,
+    /// Entries currently held.
+    pub entries: u64,
+}
+
+impl DecisionCacheMetrics {
+    fn count(&mut self, cause: Invalidation, n: usize) {
+        let n = n as u64;
+        match cause {
+            Invalidation::SpecReload => self.invalidated_spec_reload += n,
+            Invalidation::Usage => self.invalidated_usage += n,
+            Invalidation::RohUpdate => self.invalidated_roh += n,
+            Invalidation::MaintenanceWindow => self.invalidated_maintenance += n,
+            Invalidation::Expired => self.expired += n,
+        }
+    }
+}
+
+/// The kinds whose Allow may be cached. No wildcard arm: a new
+/// `XRActionKind` bypasses the cache until it is listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ReadOnlyKind {
+    ReadNeuralShard,
+    ReadKeys,
+}
+
+impl ReadOnlyKind {
+    fn of(kind: &XRActionKind) -> Option<Self> {
+        match kind {
+            XRActionKind::ReadNeuralShard => Some(Self::ReadNeuralShard),
+            XRActionKind::ReadKeys => Some(Self::ReadKeys),
+            XRActionKind::WriteNeuralShard
+            | XRActionKind::ProposeEvolve
+            | XRActionKind::ApplyOta
+            | XRActionKind::XRRouteStep
+            | XRActionKind::ScheduleJob
+            | XRActionKind::SignTransaction => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    subject: String,
+    route: String,
+    kind: ReadOnlyKind,
+    class: String,
+    bucket: i64,
+}
+
+/// Usage the entry was admitted against.
+#[derive(Debug, Clone, Copy)]
+struct Basis {
+    power: Watts,
+    energy: Joules,
+    compute: f64,
+    share: f32,
+    degraded: bool,
+}
+
+impl Basis {
+    fn of(snapshot: &ResourceUsageSnapshot, class: &str) -> Self {
+        Self {
+            power: snapshot.current_power_draw,
+            energy: snapshot.current_cumulative_energy,
+            compute: snapshot.current_compute_fraction.value(),
+            share: snapshot.class_shares.get(class).copied().unwrap_or(0.0),
+            degraded: snapshot.degraded,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    basis: Basis,
+    /// Per-axis drift allowance, in each axis' own units.
+    slack: Basis,
+    expires_at: u64,
+    used: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<Key, Entry>,
+    /// Last-use tick → key, oldest first.
+    lru: BTreeMap<u64, Key>,
+    tick: u64,
+    /// Bumped by flushes, so a decision computed before one is not stored after it.
+    generation: u64,
+    metrics: DecisionCacheMetrics,
+}
+
+impl State {
+    fn remove(&mut self, key: &Key) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.used);
+        }
+    }
+
+    fn flush_where(&mut self, cause: Invalidation, mut stale: impl FnMut(&Key, &Entry) -> bool) {
+        let keys: Vec<Key> = self.entries.iter().filter(|(k, e)| stale(k, e)).map(|(k, _)| k.clone()).collect();
+        for key in &keys {
+            self.remove(key);
+        }
+        self.metrics.count(cause, keys.len());
+    }
+
+    fn flush(&mut self, cause: Invalidation) {
+        self.generation += 1;
+        self.flush_where(cause, |_, _| true);
+    }
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+/// `EcoFairnessGuard::check` with cached Allow decisions for read-only
+/// kinds. Share it between threads behind an `Arc`; every method takes `&self`.
+pub struct GateDecisionCache {
+    cfg: DecisionCacheConfig,
+    guard: RwLock<EcoFairnessGuard>,
+    state: Mutex<State>,
+    clock: Clock,
+}
+
+impl std::fmt::Debug for GateDecisionCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GateDecisionCache").field("cfg", &self.cfg).finish()
+    }
+}
+
+impl GateDecisionCache {
+    pub fn new(guard: EcoFairnessGuard, cfg: DecisionCacheConfig) -> Self {
+        Self::with_clock(guard, cfg, system_now)
+    }
+
+    /// Like `new` with a replacement wall clock (Unix seconds); tests and replays.
+    pub fn with_clock(
+        guard: EcoFairnessGuard,
+        cfg: DecisionCacheConfig,
+        clock: impl Fn() -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        Self { cfg, guard: RwLock::new(guard), state: Mutex::new(State::default()), clock: Box::new(clock) }
+    }
+
+    pub fn config(&self) -> &DecisionCacheConfig {
+        &self.cfg
+    }
+
+    pub(crate) fn guard(&self) -> RwLockReadGuard<'_, EcoFairnessGuard> {
+        self.guard.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn bucket(&self, cost: f32) -> i64 {
+        (f64::from(cost) / f64::from(self.cfg.cost_bucket.max(f32::EPSILON))).floor() as i64
+    }
+
+    /// Same result as the wrapped guard's `check`.
+    pub fn check(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> EcoFairnessResult {
+        let guard = self.guard.read().unwrap_or_else(|e| e.into_inner());
+        let Some(kind) = ReadOnlyKind::of(&action.kind) else {
+            self.lock().metrics.bypassed += 1;
+            return guard.check(action, snapshot);
+        };
+        // An unresolvable class is a Deny; let the full check report it.
+        let Ok(class) = guard.resolve_class(action) else {
+            self.lock().metrics.misses += 1;
+            return guard.check(action, snapshot);
+        };
+        let key = Key {
+            subject: action.subjectid.clone(),
+            route: action.route.clone(),
+            kind,
+            class,
+            bucket: self.bucket(action.lifeforcecost),
+        };
+        let now = (self.clock)();
+        let generation = {
+            let mut state = self.lock();
+            let state = &mut *state;
+            let basis = Basis::of(snapshot, &key.class);
+            let cause = state.entries.get(&key).map(|entry| {
+                if entry.expires_at <= now {
+                    Some(Invalidation::Expired)
+                } else if drifted(entry, &basis) {
+                    Some(Invalidation::Usage)
+                } else {
+                    None
+                }
+            });
+            match cause {
+                Some(None) => {
+                    state.tick += 1;
+                    let entry = state.entries.get_mut(&key).expect("just found");
+                    state.lru.remove(&entry.used);
+                    entry.used = state.tick;
+                    state.lru.insert(state.tick, key.clone());
+                    state.metrics.hits += 1;
+                    None
+                }
+                Some(Some(cause)) => {
+                    state.remove(&key);
+                    state.metrics.count(cause, 1);
+                    state.metrics.misses += 1;
+                    Some(state.generation)
+                }
+                None => {
+                    state.metrics.misses += 1;
+                    Some(state.generation)
+                }
+            }
+        };
+        let Some(generation) = generation else {
+            return guard.check_roh_ecofairness(action);
+        };
+
+        guard.check(action, snapshot)?;
+        if let Some(slack) = self.admits_with_margin(&guard, action, snapshot, &key) {
+            let basis = Basis::of(snapshot, &key.class);
+            self.insert(key, basis, slack, now, generation);
+        }
+        Ok(())
+    }
+
+    /// Per-axis slack if the top of `action`'s cost bucket still passes the
+    /// envelope and equity checks with every usage axis raised by it.
+    fn admits_with_margin(
+        &self,
+        guard: &EcoFairnessGuard,
+        action: &XRAction,
+        snapshot: &ResourceUsageSnapshot,
+        key: &Key,
+    ) -> Option<Basis> {
+        let env = guard.cfg.tsafe_envelopes.get(&action.route)?;
+        let bounds = guard.cfg.grace_equity.bounds_for_class(&key.class)?;
+        let tol = self.cfg.usage_tolerance.max(0.0);
+        let slack = Basis {
+            power: env.max_power * tol,
+            energy: env.max_cumulative_energy * tol,
+            compute: env.max_compute_fraction.value() * tol,
+            share: bounds.max_share * tol as f32,
+            degraded: snapshot.degraded,
+        };
+        let mut stressed = snapshot.clone();
+        stressed.current_power_draw += slack.power;
+        stressed.current_cumulative_energy += slack.energy;
+        stressed.current_compute_fraction =
+            ComputeFraction::saturating(snapshot.current_compute_fraction.value() + slack.compute);
+        *stressed.class_shares.entry(key.class.clone()).or_insert(0.0) += slack.share;
+        let mut worst = action.clone();
+        worst.lifeforcecost = ((key.bucket + 1) as f64 * f64::from(self.cfg.cost_bucket)) as f32;
+        worst.lifeforcecost = worst.lifeforcecost.max(action.lifeforcecost);
+        // Against the configured envelope: an entry must outlive any burst window.
+        let admitted = guard.check_route_envelope_scaled(&worst, &stressed, stressed.headroom_scale()).is_ok()
+            && guard.check_equity_bounds(&worst, &stressed).is_ok();
+        admitted.then_some(slack)
+    }
+
+    fn insert(&self, key: Key, basis: Basis, slack: Basis, now: u64, generation: u64) {
+        let mut state = self.lock();
+        if state.generation != generation || self.cfg.capacity == 0 {
+            return;
+        }
+        state.remove(&key);
+        while state.entries.len() >= self.cfg.capacity {
+            let Some((_, oldest)) = state.lru.pop_first() else { break };
+            state.entries.remove(&oldest);
+            state.metrics.evictions += 1;
+        }
+        state.tick += 1;
+        let used = state.tick;
+        state.lru.insert(used, key.clone());
+        state.entries.insert(key, Entry { basis, slack, expires_at: now + self.cfg.ttl_secs, used });
+    }
+
+    /// Replace the guard after a spec reload; flushes every entry.
+    pub fn reload(&self, guard: EcoFairnessGuard) {
+        let mut current = self.guard.write().unwrap_or_else(|e| e.into_inner());
+        self.lock().flush(Invalidation::SpecReload);
+        *current = guard;
+    }
+
+    /// Install updated RoH axes; flushes every entry.
+    pub fn update_roh_model(&self, model: RohModel) {
+        let mut current = self.guard.write().unwrap_or_else(|e| e.into_inner());
+        self.lock().flush(Invalidation::RohUpdate);
+        current.cfg.roh_model = model;
+    }
+
+    /// Feed every committed usage change here. Entries whose usage rose
+    /// beyond the tolerance, or whose degraded flag differs, are dropped.
+    pub fn on_usage(&self, snapshot: &ResourceUsageSnapshot) {
+        self.lock().flush_where(Invalidation::Usage, |key, entry| drifted(entry, &Basis::of(snapshot, &key.class)));
+    }
+
+    /// A maintenance window on `route` opened, closed or moved.
+    pub fn on_maintenance_window(&self, route: &str) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.flush_where(Invalidation::MaintenanceWindow, |key, _| key.route == route);
+    }
+
+    pub fn metrics(&self) -> DecisionCacheMetrics {
+        let state = self.lock();
+        DecisionCacheMetrics { entries: state.entries.len() as u64, ..state.metrics.clone() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn drifted(entry: &Entry, now: &Basis) -> bool {
+    let (was, slack) = (&entry.basis, &entry.slack);
+    now.degraded != was.degraded
+        || now.power.saturating_sub(was.power) > slack.power
+        || now.energy.saturating_sub(was.energy) > slack.energy
+        || now.compute - was.compute > slack.compute
+        || now.share - was.share > slack.share
+}
@@ -0,0 +1,372 @@
+//! Monte Carlo fairness audits of a GraceEquityKernel.
+//!
+//! Policy authors hand in the same config the guard would run with plus a
+//! synthetic workload; every arrival goes through the real
+//! `EcoFairnessGuard::check`, and admitted actions are committed to a
+//! simulated snapshot. The resulting report says whether each class
+//! actually receives its configured floor, not just whether the floors
+//! add up. Runs are deterministic per seed.
+
+use eco_units::{ComputeFraction, Joules, Watts};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{EcoFairnessConfig, EcoFairnessGuard, ResourceUsageSnapshot, XRAction, XRActionKind};
+
+/// Cost drawn for each arrival (same units as `XRAction::lifeforcecost`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CostDistribution {
+    Fixed { value: f32 },
+    Uniform { min: f32, max: f32 },
+    Exponential { mean: f32 },
+}
+
+/// Load offered by one equity class.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassWorkload {
+    pub class: String,
+    pub route: String,
+    /// Mean arrivals per tick (Poisson).
+    pub arrival_rate: f64,
+    pub cost: CostDistribution,
+    /// Probability that a tick is a burst for this class.
+    #[serde(default)]
+    pub burst_probability: f64,
+    /// Arrival-rate multiplier during a burst.
+    #[serde(default = "default_burst_factor")]
+    pub burst_factor: f64,
+}
+
+fn default_burst_factor() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadSpec {
+    pub ticks: u32,
+    pub classes: Vec<ClassWorkload>,
+    pub total_power_budget: f32,
+    pub total_compute_capacity: f32,
+    /// Fraction of each class's committed draw released every tick.
+    pub release_per_tick: f32,
+    /// Cumulative energy resets every this many ticks (0 = never).
+    #[serde(default)]
+    pub energy_window_ticks: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FairnessSimConfig {
+    pub episodes: usize,
+    pub seed: u64,
+}
+
+/// Distribution of one class's achieved share across episodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassOutcome {
+    pub class: String,
+    pub min_share: f32,
+    pub max_share: f32,
+    pub share_mean: f64,
+    pub share_p5: f64,
+    pub share_p50: f64,
+    pub share_p95: f64,
+    pub attempts: u64,
+    pub admitted: u64,
+    pub rejection_rate: f64,
+    /// Ticks where the class was under its floor, offered work, and got nothing.
+    pub starvation_events: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairnessAuditReport {
+    pub seed: u64,
+    pub episodes: usize,
+    pub classes: Vec<ClassOutcome>,
+    /// GuardError code → count, over all episodes.
+    pub rejections_by_reason: BTreeMap<String, u64>,
+    /// Mean over episodes of the Gini coefficient of achieved shares.
+    pub gini: f64,
+    /// Classes whose 5th-percentile achieved share is below their floor.
+    pub flagged: Vec<String>,
+}
+
+impl FairnessAuditReport {
+    pub fn passed(&self) -> bool {
+        self.flagged.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub tick: u32,
+    pub class: String,
+    pub cost: f32,
+    /// None if admitted, otherwise the GuardError code.
+    pub rejected: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeTrace {
+    pub episode: usize,
+    pub entries: Vec<TraceEntry>,
+}
+
+/// SplitMix64: small, seedable, and stable across platforms and releases,
+/// which the determinism guarantee depends on.
+struct SimRng(u64);
+
+impl SimRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn poisson(&mut self, lambda: f64) -> u32 {
+        if lambda <= 0.0 {
+            return 0;
+        }
+        let limit = (-lambda).exp();
+        let mut k = 0;
+        let mut p = self.unit();
+        while p > limit {
+            k += 1;
+            p *= self.unit();
+        }
+        k
+    }
+
+    fn cost(&mut self, dist: &CostDistribution) -> f32 {
+        match *dist {
+            CostDistribution::Fixed { value } => value,
+            CostDistribution::Uniform { min, max } => min + (max - min) * self.unit() as f32,
+            CostDistribution::Exponential { mean } => (-(1.0 - self.unit()).ln() * mean as f64) as f32,
+        }
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+/// Gini coefficient of non-negative values (0 = perfectly equal).
+pub fn gini(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let sum: f64 = values.iter().sum();
+    if values.is_empty() || sum <= 0.0 {
+        return 0.0;
+    }
+    let diffs: f64 = values.iter().flat_map(|a| values.iter().map(move |b| (a - b).abs())).sum();
+    diffs / (2.0 * n * sum)
+}
+
+#[derive(Default)]
+struct ClassTally {
+    attempts: u64,
+    admitted: u64,
+    starvation_events: u64,
+    shares: Vec<f64>,
+}
+
+pub struct FairnessSim {
+    guard: EcoFairnessGuard,
+    cfg: EcoFairnessConfig,
+    workload: WorkloadSpec,
+}
+
+impl FairnessSim {
+    pub fn new(cfg: EcoFairnessConfig, workload: WorkloadSpec) -> Self {
+        Self { guard: EcoFairnessGuard::new(cfg.clone()), cfg, workload }
+    }
+
+    pub fn run(&self, sim: &FairnessSimConfig) -> FairnessAuditReport {
+        self.run_inner(sim, None)
+    }
+
+    /// Like `run`, also returning every decision for inspection.
+    pub fn run_with_traces(&self, sim: &FairnessSimConfig) -> (FairnessAuditReport, Vec<EpisodeTrace>) {
+        let mut traces = Vec::with_capacity(sim.episodes);
+        let report = self.run_inner(sim, Some(&mut traces));
+        (report, traces)
+    }
+
+    fn run_inner(&self, sim: &FairnessSimConfig, mut traces: Option<&mut Vec<EpisodeTrace>>) -> FairnessAuditReport {
+        let mut tallies: BTreeMap<String, ClassTally> = BTreeMap::new();
+        for w in &self.workload.classes {
+            tallies.entry(w.class.clone()).or_default();
+        }
+        let mut rejections_by_reason = BTreeMap::new();
+        let mut gini_sum = 0.0;
+
+        for episode in 0..sim.episodes {
+            let mut seeder = SimRng(sim.seed ^ (episode as u64).wrapping_mul(0xA24B_AED4_963E_E407));
+            let mut rng = SimRng(seeder.next_u64());
+            let mut trace = traces.as_ref().map(|_| EpisodeTrace { episode, entries: Vec::new() });
+            let granted = self.episode(&mut rng, &mut tallies, &mut rejections_by_reason, trace.as_mut());
+
+            let total: f64 = granted.values().sum();
+            let shares: Vec<f64> = tallies
+                .keys()
+                .map(|c| if total > 0.0 { granted.get(c).copied().unwrap_or(0.0) / total } else { 0.0 })
+                .collect();
+            for (tally, share) in tallies.values_mut().zip(&shares) {
+                tally.shares.push(*share);
+            }
+            gini_sum += gini(&shares);
+            if let (Some(out), Some(trace)) = (traces.as_deref_mut(), trace) {
+                out.push(trace);
+            }
+        }
+
+        let mut flagged = Vec::new();
+        let classes = tallies
+            .into_iter()
+            .map(|(class, mut t)| {
+                let bounds = self.cfg.grace_equity.bounds_for_class(&class);
+                let min_share = bounds.map(|b| b.min_share).unwrap_or(0.0);
+                let max_share = bounds.map(|b| b.max_share).unwrap_or(0.0);
+                t.shares.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                let share_p5 = percentile(&t.shares, 0.05);
+                if share_p5 < min_share as f64 {
+                    flagged.push(class.clone());
+                }
+                ClassOutcome {
+                    share_mean: if t.shares.is_empty() { 0.0 } else { t.shares.iter().sum::<f64>() / t.shares.len() as f64 },
+                    share_p5,
+                    share_p50: percentile(&t.shares, 0.5),
+                    share_p95: percentile(&t.shares, 0.95),
+                    rejection_rate: if t.attempts == 0 { 0.0 } else { (t.attempts - t.admitted) as f64 / t.attempts as f64 },
+                    attempts: t.attempts,
+                    admitted: t.admitted,
+                    starvation_events: t.starvation_events,
+                    class,
+                    min_share,
+                    max_share,
+                }
+            })
+            .collect();
+
+        FairnessAuditReport {
+            seed: sim.seed,
+            episodes: sim.episodes,
+            classes,
+            rejections_by_reason,
+            gini: if sim.episodes == 0 { 0.0 } else { gini_sum / sim.episodes as f64 },
+            flagged,
+        }
+    }
+
+    /// One episode; returns the cost granted per class.
+    fn episode(
+        &self,
+        rng: &mut SimRng,
+        tallies: &mut BTreeMap<String, ClassTally>,
+        rejections: &mut BTreeMap<String, u64>,
+        mut trace: Option<&mut EpisodeTrace>,
+    ) -> BTreeMap<String, f64> {
+        let w = &self.workload;
+        let mut draw: BTreeMap<String, f32> = BTreeMap::new();
+        let mut energy = 0.0_f32;
+        let mut granted: BTreeMap<String, f64> = BTreeMap::new();
+
+        for tick in 0..w.ticks {
+            for d in draw.values_mut() {
+                *d *= 1.0 - w.release_per_tick;
+            }
+            if w.energy_window_ticks > 0 && tick % w.energy_window_ticks == 0 {
+                energy = 0.0;
+            }
+
+            // Draw this tick's arrivals per class, then shuffle so no class
+            // always goes first.
+            let mut arrivals: Vec<(usize, f32)> = Vec::new();
+            for (i, c) in w.classes.iter().enumerate() {
+                let burst = rng.unit() < c.burst_probability;
+                let rate = if burst { c.arrival_rate * c.burst_factor } else { c.arrival_rate };
+                for _ in 0..rng.poisson(rate) {
+                    arrivals.push((i, rng.cost(&c.cost).max(0.0)));
+                }
+            }
+            for i in (1..arrivals.len()).rev() {
+                let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                arrivals.swap(i, j);
+            }
+
+            let mut offered: BTreeMap<&str, bool> = BTreeMap::new();
+            for (i, cost) in arrivals {
+                let c = &w.classes[i];
+                let snapshot = self.snapshot(&draw, energy);
+                let action = XRAction {
+                    kind: XRActionKind::ScheduleJob,
+                    subjectid: format!("sim-{}", c.class),
+                    route: c.route.clone(),
+                    lifeforcecost: cost,
+                    rohbefore: 0.0,
+                    rohafterestimate: 0.0,
+                    equity_class: Some(c.class.clone()),
+                };
+                let tally = tallies.entry(c.class.clone()).or_default();
+                tally.attempts += 1;
+                let outcome = self.guard.check(&action, &snapshot);
+                let admitted = outcome.is_ok();
+                *offered.entry(c.class.as_str()).or_insert(false) |= admitted;
+                let rejected = match outcome {
+                    Ok(()) => {
+                        tally.admitted += 1;
+                        *draw.entry(c.class.clone()).or_insert(0.0) += cost;
+                        energy += cost;
+                        *granted.entry(c.class.clone()).or_insert(0.0) += cost as f64;
+                        None
+                    }
+                    Err(e) => {
+                        *rejections.entry(e.code.clone()).or_insert(0) += 1;
+                        Some(e.code)
+                    }
+                };
+                if let Some(t) = trace.as_deref_mut() {
+                    t.entries.push(TraceEntry { tick, class: c.class.clone(), cost, rejected });
+                }
+            }
+
+            let snapshot = self.snapshot(&draw, energy);
+            for (class, any_admitted) in offered {
+                let floor = self.cfg.grace_equity.bounds_for_class(class).map(|b| b.min_share).unwrap_or(0.0);
+                let share = snapshot.class_shares.get(class).copied().unwrap_or(0.0);
+                if !any_admitted && share < floor {
+                    if let Some(t) = tallies.get_mut(class) {
+                        t.starvation_events += 1;
+                    }
+                }
+            }
+        }
+        granted
+    }
+
+    fn snapshot(&self, draw: &BTreeMap<String, f32>, energy: f32) -> ResourceUsageSnapshot {
+        let w = &self.workload;
+        let power: f32 = draw.values().sum();
+        let budget = w.total_power_budget.max(1.0);
+        let class_shares: HashMap<String, f32> = draw.iter().map(|(c, d)| (c.clone(), d / budget)).collect();
+        ResourceUsageSnapshot {
+            total_power_budget: Watts::new(f64::from(w.total_power_budget)),
+            total_compute_capacity: w.total_compute_capacity,
+            current_power_draw: Watts::new(f64::from(power)),
+            current_cumulative_energy: Joules::new(f64::from(energy)),
+            current_compute_fraction: ComputeFraction::saturating(f64::from(power / w.total_compute_capacity.max(1.0))),
+            class_shares,
+            degraded: false,
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use param_registry::{ParamKey, ParamRegistry};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Pre/post utilization of one envelope axis, as a fraction of its limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AxisUtilization {
+    pub before: f32,
+    pub after: f32,
+}
+
+impl AxisUtilization {
+    pub fn consumed(&self) -> f32 {
+        self.after - self.before
+    }
+}
+
+/// One admitted action and how much headroom it used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionRecord {
+    /// Unix seconds.
+    pub timestamp: u64,
+    pub route: String,
+    pub kind: String,
+    pub subject_class: String,
+    pub roh_before: f32,
+    pub roh_after: f32,
+    /// Ceiling in force when the action was admitted.
+    pub roh_ceiling: f32,
+    pub power: AxisUtilization,
+    pub energy: AxisUtilization,
+    pub compute: AxisUtilization,
+}
+
+impl AdmissionRecord {
+    /// Fraction of the RoH ceiling occupied after the action.
+    pub fn roh_utilization(&self) -> f32 {
+        if self.roh_ceiling <= 0.0 {
+            return 1.0;
+        }
+        self.roh_after / self.roh_ceiling
+    }
+
+    fn day(&self) -> u64 {
+        self.timestamp / SECS_PER_DAY
+    }
+}
+
+/// Running sums for one (route, kind) group. All fields are additive so
+/// rollups can be merged and rebuilt by replaying records.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeadroomTotals {
+    pub admissions: u64,
+    pub roh_utilization_sum: f64,
+    pub roh_utilization_max: f32,
+    pub power_consumed: f64,
+    pub energy_consumed: f64,
+    pub compute_consumed: f64,
+}
+
+impl HeadroomTotals {
+    fn add(&mut self, r: &AdmissionRecord) {
+        self.admissions += 1;
+        self.roh_utilization_sum += r.roh_utilization() as f64;
+        self.roh_utilization_max = self.roh_utilization_max.max(r.roh_utilization());
+        self.power_consumed += r.power.consumed() as f64;
+        self.energy_consumed += r.energy.consumed() as f64;
+        self.compute_consumed += r.compute.consumed() as f64;
+    }
+
+    pub fn mean_roh_utilization(&self) -> f64 {
+        if self.admissions == 0 {
+            0.0
+        } else {
+            self.roh_utilization_sum / self.admissions as f64
+        }
+    }
+}
+
+/// Per-day aggregate, keyed "route/kind". Closed days are handed out by
+/// `drain_closed_days` to be persisted as deeds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyRollup {
+    /// Days since the Unix epoch.
+    pub day: u64,
+    pub groups: BTreeMap<String, HeadroomTotals>,
+}
+
+impl DailyRollup {
+    /// Context payload for the `headroom_rollup` deed.
+    pub fn to_deed_context(&self) -> serde_json::Value {
+        serde_json::json!({ "deed_type": "headroom_rollup", "rollup": self })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadroomLedgerConfig {
+    /// Maximum individual records retained for reports and what_if.
+    pub ring_capacity: usize,
+    /// Maximum daily rollups kept in memory before the oldest is dropped.
+    pub max_days: usize,
+}
+
+impl Default for HeadroomLedgerConfig {
+    fn default() -> Self {
+        let max_days = ParamRegistry::default().get(ParamKey::HeadroomMaxDays);
+        Self { ring_capacity: 10_000, max_days: max_days as usize }
+    }
+}
+
+/// Half-open time window `[start, end)` in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ReportWindow {
+    fn contains(&self, t: u64) -> bool {
+        t >= self.start && t < self.end
+    }
+
+    /// The window of equal length immediately before this one.
+    pub fn prior(&self) -> Self {
+        let len = self.end.saturating_sub(self.start);
+        Self { start: self.start.saturating_sub(len), end: self.start }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupReport {
+    pub key: String,
+    pub totals: HeadroomTotals,
+    /// Change in mean RoH utilization versus the prior window
+    /// (None if the group had no admissions then).
+    pub roh_trend: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeadroomReport {
+    pub window: ReportWindow,
+    pub admissions: u64,
+    pub by_route: Vec<GroupReport>,
+    pub by_kind: Vec<GroupReport>,
+    /// Subject classes ordered by summed RoH utilization, highest first.
+    pub top_consumers: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhatIfResult {
+    pub ceiling_delta: f32,
+    pub evaluated: usize,
+    pub would_reject: usize,
+}
+
+/// Bounded record of admitted actions and the headroom they consumed.
+#[derive(Debug, Clone, Default)]
+pub struct HeadroomLedger {
+    cfg: HeadroomLedgerConfig,
+    ring: VecDeque<AdmissionRecord>,
+    daily: BTreeMap<u64, DailyRollup>,
+}
+
+impl HeadroomLedger {
+    pub fn new(cfg: HeadroomLedgerConfig) -> Self {
+        Self { ring: VecDeque::with_capacity(cfg.ring_capacity.min(1024)), cfg, daily: BTreeMap::new() }
+    }
+
+    /// Rebuild a ledger from persisted records; rollups are a pure fold, so
+    /// replay yields the same state as live recording.
+    pub fn replay<I: IntoIterator<Item = AdmissionRecord>>(cfg: HeadroomLedgerConfig, records: I) -> Self {
+        let mut ledger = Self::new(cfg);
+        for r in records {
+            ledger.record(r);
+        }
+        ledger
+    }
+
+    pub fn record(&mut self, r: AdmissionRecord) {
+        let key = format!("{}/{}", r.route, r.kind);
+        let rollup = self.daily.entry(r.day()).or_insert_with(|| DailyRollup { day: r.day(), ..Default::default() });
+        rollup.groups.entry(key).or_default().add(&r);
+        while self.daily.len() > self.cfg.max_days {
+            self.daily.pop_first();
+        }
+
+        if self.cfg.ring_capacity == 0 {
+            return;
+        }
+        if self.ring.len() == self.cfg.ring_capacity {
+            self.ring.pop_front();
+        }
+        self.ring.push_back(r);
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &AdmissionRecord> {
+        self.ring.iter()
+    }
+
+    pub fn daily_rollups(&self) -> impl Iterator<Item = &DailyRollup> {
+        self.daily.values()
+    }
+
+    /// Remove and return rollups for days strictly before `now`'s day.
+    pub fn drain_closed_days(&mut self, now: u64) -> Vec<DailyRollup> {
+        let today = now / SECS_PER_DAY;
+        let open = self.daily.split_off(&today);
+        let closed = std::mem::replace(&mut self.daily, open);
+        closed.into_values().collect()
+    }
+
+    fn group_by<F>(&self, window: ReportWindow, key: F) -> BTreeMap<String, HeadroomTotals>
+    where
+        F: Fn(&AdmissionRecord) -> String,
+    {
+        let mut out: BTreeMap<String, HeadroomTotals> = BTreeMap::new();
+        for r in self.ring.iter().filter(|r| window.contains(r.timestamp)) {
+            out.entry(key(r)).or_default().add(r);
+        }
+        out
+    }
+
+    fn with_trend(current: BTreeMap<String, HeadroomTotals>, prior: &BTreeMap<String, HeadroomTotals>) -> Vec<GroupReport> {
+        current
+            .into_iter()
+            .map(|(key, totals)| {
+                let roh_trend = prior.get(&key).map(|p| totals.mean_roh_utilization() - p.mean_roh_utilization());
+                GroupReport { key, totals, roh_trend }
+            })
+            .collect()
+    }
+
+    /// Headroom consumption per route and per action kind within `window`,
+    /// with trend against the preceding window of equal length.
+    pub fn headroom_report(&self, window: ReportWindow, top_n: usize) -> HeadroomReport {
+        let prior = window.prior();
+        let by_route = Self::with_trend(
+            self.group_by(window, |r| r.route.clone()),
+            &self.group_by(prior, |r| r.route.clone()),
+        );
+        let by_kind = Self::with_trend(
+            self.group_by(window, |r| r.kind.clone()),
+            &self.group_by(prior, |r| r.kind.clone()),
+        );
+
+        let mut top_consumers: Vec<(String, f64)> = self
+            .group_by(window, |r| r.subject_class.clone())
+            .into_iter()
+            .map(|(class, t)| (class, t.roh_utilization_sum))
+            .collect();
+        top_consumers.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+        top_consumers.truncate(top_n);
+
+        HeadroomReport {
+            window,
+            admissions: by_route.iter().map(|g| g.totals.admissions).sum(),
+            by_route,
+            by_kind,
+            top_consumers,
+        }
+    }
+
+    /// How many retained admissions would have been rejected had the RoH
+    /// ceiling been `ceiling_delta` different (negative = tighter).
+    pub fn what_if(&self, ceiling_delta: f32) -> WhatIfResult {
+        let would_reject = self.ring.iter().filter(|r| r.roh_after > r.roh_ceiling + ceiling_delta).count();
+        WhatIfResult { ceiling_delta, evaluated: self.ring.len(), would_reject }
+    }
+
+    /// Per-group breakdown of what_if, keyed "route/kind".
+    pub fn what_if_by_group(&self, ceiling_delta: f32) -> HashMap<String, usize> {
+        let mut out = HashMap::new();
+        for r in self.ring.iter().filter(|r| r.roh_after > r.roh_ceiling + ceiling_delta) {
+            *out.entry(format!("{}/{}", r.route, r.kind)).or_insert(0) += 1;
+        }
+        out
+    }
+}
@@ -1,7 +1,21 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::Path};
+use serde_json::Value;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use crate::schema::{unknown_field, unknown_field_in_items, unknown_field_in_values, EXT_FIELD, UNKNOWN_CRITICAL_FIELD};
+
+const BOUNDS_FIELDS: &[&str] = &["min_share", "max_share", "description"];
+const CLASS_SPEC_FIELDS: &[&str] = &["name", "min_share", "max_share", "description"];
+const ROUTE_FIELDS: &[&str] = &["route", "max_power_fraction", "max_compute_fraction"];
+const SPEC_FIELDS: &[&str] = &["resource_kind", "normalization", "classes", "node_routes", EXT_FIELD];
+const KERNEL_FIELDS: &[&str] = &["classes", "resource_kind", "normalization", "node_routes", EXT_FIELD];
+/// The key policy files nest the spec under, beside their `version`.
+const DOCUMENT_KERNEL_FIELD: &str = "grace_equity_kernel";
+const DOCUMENT_FIELDS: &[&str] = &["version", DOCUMENT_KERNEL_FIELD];
+const DOCUMENT_VERSION: u64 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EquityBounds {
     pub min_share: f32,
     pub max_share: f32,
@@ -10,6 +24,7 @@ pub struct EquityBounds {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EquityClassSpec {
     pub name: String,
     pub min_share: f32,
@@ -18,6 +33,7 @@ pub struct EquityClassSpec {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RouteEnvelope {
     pub route: String,
     pub max_power_fraction: f32,
@@ -25,20 +41,28 @@ pub struct RouteEnvelope {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct GraceEquityKernelSpec {
     pub resource_kind: String,
     pub normalization: String,
     pub classes: Vec<EquityClassSpec>,
     pub node_routes: Vec<RouteEnvelope>,
+    /// Fields from newer `.eco-fairness.aln` versions, kept as loaded.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub ext: serde_json::Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct GraceEquityKernel {
     /// Map from EquityClass name → bounds.
     pub classes: HashMap<String, EquityBounds>,
     pub resource_kind: String,
     pub normalization: String,
     pub node_routes: HashMap<String, RouteEnvelope>,
+    /// The spec's `ext` fields, kept as loaded.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub ext: serde_json::Map<String, Value>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -49,13 +73,66 @@ pub enum EquityKernelError {
     Parse(#[from] serde_json::Error),
     #[error("Invalid equity kernel invariant: {0}")]
     Invariant(String),
+    #[error("{UNKNOWN_CRITICAL_FIELD} in .eco-fairness.aln: {0}")]
+    UnknownCriticalField(String),
+    #[error("Equity class '{0}' not present in GraceEquityKernel")]
+    UnknownClass(String),
+    #[error("Route '{0}' has no node envelope in GraceEquityKernel")]
+    UnknownRoute(String),
+    #[error("Share delta must be a positive number, got {0}")]
+    InvalidDelta(f32),
+    #[error("Equity class '{class}' refused on route '{route}': no headroom under {limit}")]
+    Refused { class: String, route: String, limit: AdmissionLimit },
+}
+
+/// The bound that decided how much of a delta `admit` grants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdmissionLimit {
+    /// The requester's own `max_share`.
+    MaxShare,
+    /// Capacity held back for other classes still under their `min_share`.
+    Floors,
+    /// The route envelope's `max_power_fraction` / `max_compute_fraction`.
+    RouteEnvelope,
+    /// No unallocated capacity left at all.
+    Capacity,
+}
+
+impl fmt::Display for AdmissionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MaxShare => "max_share",
+            Self::Floors => "other classes' min_share",
+            Self::RouteEnvelope => "the route envelope",
+            Self::Capacity => "node capacity",
+        })
+    }
+}
+
+/// What `GraceEquityKernel::admit` granted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Admission {
+    /// Share granted; at most the requested delta.
+    pub granted: f32,
+    /// The requester's share once `granted` is added.
+    pub post_share: f32,
+    /// `granted / delta`: 1.0 when the delta was admitted whole.
+    pub throttle: f32,
+    /// The bound that throttled the delta, if one did.
+    pub limited_by: Option<AdmissionLimit>,
 }
 
 impl GraceEquityKernel {
-    /// Load and validate from a JSON-compatible `.eco-fairness.aln` file.
+    /// Load and validate from a JSON-compatible `.eco-fairness.aln` file:
+    /// either the bare spec, or the spec under `grace_equity_kernel` beside
+    /// a `version`, as the files in `policies/` ship it.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, EquityKernelError> {
         let raw = fs::read_to_string(path)?;
-        let spec: GraceEquityKernelSpec = serde_json::from_str(&raw)?;
+        let (value, prefix) = unwrap_document(serde_json::from_str(&raw)?)?;
+        if let Some(path) = unknown_spec_field(&value, prefix) {
+            return Err(EquityKernelError::UnknownCriticalField(path));
+        }
+        let spec: GraceEquityKernelSpec = serde_json::from_value(value)?;
 
         if spec.classes.is_empty() {
             return Err(EquityKernelError::Invariant(
@@ -131,9 +208,24 @@ impl GraceEquityKernel {
             resource_kind: spec.resource_kind,
             normalization: spec.normalization,
             node_routes,
+            ext: spec.ext,
         })
     }
 
+    /// Parse a kernel in its loaded form (class and route maps), as
+    /// `EcoFairnessGuard::from_paths` reads it. An unknown field outside
+    /// `ext` fails with `UnknownCriticalField` and its path.
+    pub fn from_json(text: &str) -> Result<Self, EquityKernelError> {
+        let value: Value = serde_json::from_str(text)?;
+        let unknown = unknown_field(&value, "", KERNEL_FIELDS)
+            .or_else(|| unknown_field_in_values(&value["classes"], "classes", BOUNDS_FIELDS))
+            .or_else(|| unknown_field_in_values(&value["node_routes"], "node_routes", ROUTE_FIELDS));
+        if let Some(path) = unknown {
+            return Err(EquityKernelError::UnknownCriticalField(path));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
     pub fn bounds_for_class(&self, class: &str) -> Option<&EquityBounds> {
         self.classes.get(class)
     }
@@ -141,4 +233,130 @@ impl GraceEquityKernel {
     pub fn route_envelope(&self, route: &str) -> Option<&RouteEnvelope> {
         self.node_routes.get(route)
     }
+
+    /// May `class` take `delta_share` more of the node on `route`, given
+    /// the `current` share of each class? Grants as much of the delta as
+    /// every bound allows, and refuses only when that is nothing.
+    ///
+    /// The bounds are the requester's `max_share`, the route envelope
+    /// (one admission may take at most the smaller of its power and
+    /// compute fractions), unallocated capacity, and the floors of other
+    /// classes: capacity they still need to reach `min_share` is held
+    /// back. As in `EcoFairnessGuard::check`, a requester under its own
+    /// floor is not held back for the others.
+    pub fn admit(
+        &self,
+        class: &str,
+        route: &str,
+        delta_share: f32,
+        current: &HashMap<String, f32>,
+    ) -> Result<Admission, EquityKernelError> {
+        if !(delta_share.is_finite() && delta_share > 0.0) {
+            return Err(EquityKernelError::InvalidDelta(delta_share));
+        }
+        let bounds = self
+            .bounds_for_class(class)
+            .ok_or_else(|| EquityKernelError::UnknownClass(class.to_string()))?;
+        let envelope = self
+            .route_envelope(route)
+            .ok_or_else(|| EquityKernelError::UnknownRoute(route.to_string()))?;
+        let share = current.get(class).copied().unwrap_or(0.0);
+
+        let free = (1.0 - current.values().sum::<f32>()).max(0.0);
+        let mut headroom = vec![
+            (AdmissionLimit::MaxShare, bounds.max_share - share),
+            (AdmissionLimit::RouteEnvelope, envelope.max_power_fraction.min(envelope.max_compute_fraction)),
+            (AdmissionLimit::Capacity, free),
+        ];
+        if share >= bounds.min_share {
+            let unmet = self.unmet_floors(current, Some(class));
+            if unmet > 0.0 {
+                headroom.push((AdmissionLimit::Floors, free - unmet));
+            }
+        }
+        let (limit, room) = headroom
+            .into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("headroom has fixed entries");
+
+        if room <= SHARE_EPSILON {
+            return Err(EquityKernelError::Refused { class: class.to_string(), route: route.to_string(), limit });
+        }
+        let granted = delta_share.min(room);
+        Ok(Admission {
+            granted,
+            post_share: share + granted,
+            throttle: granted / delta_share,
+            limited_by: (granted < delta_share).then_some(limit),
+        })
+    }
+
+    /// Per-class multipliers on `current` shares that would lift every
+    /// class to its `min_share`. Unallocated capacity is used first; the
+    /// rest is taken from classes above their floor, in proportion to
+    /// their surplus, and never below their floor. Every class in
+    /// `current` gets an entry; 1.0 means leave it alone. Classes the
+    /// kernel does not know have a floor of zero.
+    pub fn rebalance_suggestion(&self, current: &HashMap<String, f32>) -> HashMap<String, f32> {
+        let floor = |class: &str| self.bounds_for_class(class).map_or(0.0, |b| b.min_share);
+        let free = (1.0 - current.values().sum::<f32>()).max(0.0);
+        let needed = (self.unmet_floors(current, None) - free).max(0.0);
+        let surplus: f32 = current.iter().map(|(class, share)| (share - floor(class)).max(0.0)).sum();
+
+        current
+            .iter()
+            .map(|(class, &share)| {
+                let over = (share - floor(class)).max(0.0);
+                let multiplier = if needed <= SHARE_EPSILON || over <= 0.0 || share <= 0.0 {
+                    1.0
+                } else {
+                    let give = over * (needed / surplus).min(1.0);
+                    (share - give) / share
+                };
+                (class.clone(), multiplier)
+            })
+            .collect()
+    }
+
+    /// How much share the classes in `current` that are under their
+    /// `min_share` still need, leaving out `except`. Like the guard's
+    /// floor check, classes absent from `current` claim nothing.
+    fn unmet_floors(&self, current: &HashMap<String, f32>, except: Option<&str>) -> f32 {
+        current
+            .iter()
+            .filter(|(class, _)| Some(class.as_str()) != except)
+            .filter_map(|(class, share)| Some((self.bounds_for_class(class)?.min_share - share).max(0.0)))
+            .sum()
+    }
+}
+
+/// Headroom at or below this is none: shares are f32 sums.
+const SHARE_EPSILON: f32 = 1e-6;
+
+/// The spec inside a wrapped policy document, with the path prefix its
+/// fields are reported under; any other value is taken as the bare spec.
+fn unwrap_document(mut value: Value) -> Result<(Value, &'static str), EquityKernelError> {
+    if value.get(DOCUMENT_KERNEL_FIELD).is_none() {
+        return Ok((value, ""));
+    }
+    if let Some(path) = unknown_field(&value, "", DOCUMENT_FIELDS) {
+        return Err(EquityKernelError::UnknownCriticalField(path));
+    }
+    match value.get("version") {
+        None => {}
+        Some(v) if v.as_u64() == Some(DOCUMENT_VERSION) => {}
+        Some(v) => {
+            return Err(EquityKernelError::Invariant(format!(
+                "unsupported .eco-fairness.aln version {v}, expected {DOCUMENT_VERSION}"
+            )))
+        }
+    }
+    Ok((value[DOCUMENT_KERNEL_FIELD].take(), DOCUMENT_KERNEL_FIELD))
+}
+
+fn unknown_spec_field(value: &Value, prefix: &str) -> Option<String> {
+    let path = |field: &str| if prefix.is_empty() { field.to_string() } else { format!("{prefix}.{field}") };
+    unknown_field(value, prefix, SPEC_FIELDS)
+        .or_else(|| unknown_field_in_items(&value["classes"], &path("classes"), CLASS_SPEC_FIELDS))
+        .or_else(|| unknown_field_in_items(&value["node_routes"], &path("node_routes"), ROUTE_FIELDS))
 }
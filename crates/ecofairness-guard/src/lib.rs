@@ -1,9 +1,50 @@
+use eco_units::{ComputeFraction, Joules, Watts};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs, path::Path};
 
+mod advisory;
+mod burst;
+mod class_assignment;
+mod decision_cache;
+mod fairness_sim;
+mod headroom;
 mod kernel;
-
-pub use kernel::{EquityBounds, GraceEquityKernel, RouteEnvelope};
+mod models;
+mod schema;
+mod slo;
+mod snapshot_builder;
+
+pub use advisory::{AxisLoad, BudgetAdvisory, BudgetAxis, Verdict, MARGINAL_UTILIZATION};
+pub use burst::{
+    BurstClassOutcome, BurstConfig, BurstDebrief, BurstError, BurstGrant, BurstRevocation, BurstRouteUsage,
+    BurstWindows, SharedBurstWindows,
+};
+pub use class_assignment::{
+    ClassAssignment, ClassEvent, ClassEventKind, ClassRegistry, ClassRegistryConfig, ClassRegistryError,
+    ClassRevocation, RosterEntry, SharedClassRegistry, VerifiedClass,
+};
+pub use decision_cache::{DecisionCacheConfig, DecisionCacheMetrics, GateDecisionCache, Invalidation};
+pub use fairness_sim::{
+    gini, ClassOutcome, ClassWorkload, CostDistribution, EpisodeTrace, FairnessAuditReport, FairnessSim,
+    FairnessSimConfig, TraceEntry, WorkloadSpec,
+};
+pub use headroom::{
+    AdmissionRecord, AxisUtilization, DailyRollup, GroupReport, HeadroomLedger, HeadroomLedgerConfig,
+    HeadroomReport, HeadroomTotals, ReportWindow, WhatIfResult,
+};
+pub use kernel::{Admission, AdmissionLimit, EquityBounds, EquityKernelError, GraceEquityKernel, RouteEnvelope};
+pub use models::{ModelError, RohModel, ViabilityKernel};
+pub use schema::{EXT_FIELD, UNKNOWN_CRITICAL_FIELD};
+pub use slo::{
+    rejection_category, LatencyMeasurement, LatencyObjective, MaintenanceWindow, Observation, Outcome,
+    RateMeasurement, SharedSloTracker, SloAlerter, SloConfig, SloError, SloEvent, SloEventKind, SloSpec, SloStatus,
+    SloTracker,
+};
+pub use snapshot_builder::{SnapshotBuilder, SnapshotBuilderConfig, SnapshotError, UsageEvent};
+
+/// Fraction of each envelope limit and class `max_share` the guard allows
+/// against a degraded snapshot, whose power figures may be out of date.
+pub const DEGRADED_HEADROOM: f64 = 0.5;
 
 /// High-level error type for guard violations or configuration problems.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,28 +53,81 @@ pub struct GuardError {
     pub message: String,
 }
 
-/// Projection of the RoH model relevant for eco / compute fairness.
-/// This is assumed to be parsed from `.rohmodel.aln` (JSON-compatible).
+/// Into the vocabulary the Tsafe Cortex Gate logs, keeping the code.
+impl From<GuardError> for eco_types::GuardError {
+    fn from(e: GuardError) -> Self {
+        Self::Rejected { code: e.code, message: e.message }
+    }
+}
+
+impl From<eco_types::GuardError> for GuardError {
+    fn from(e: eco_types::GuardError) -> Self {
+        Self { code: e.code().to_string(), message: e.to_string() }
+    }
+}
+
+/// One failing check from `EcoFairnessGuard::evaluate`. `measured` and
+/// `limit` are in the check's own units (W, J, compute or class-share
+/// fraction, RoH) and are `None` for configuration findings such as an
+/// unknown route or class. For `ECO_EQUITY_FLOOR_AT_RISK`, `measured` is
+/// the capacity the action would leave and `limit` the unmet floors it
+/// must cover; for every other code `measured` exceeds `limit`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EcoFinding {
+    pub code: String,
+    pub message: String,
+    pub measured: Option<f64>,
+    pub limit: Option<f64>,
+}
+
+impl EcoFinding {
+    fn over(code: &str, message: String, measured: f64, limit: f64) -> Self {
+        Self { code: code.into(), message, measured: Some(measured), limit: Some(limit) }
+    }
+
+    fn config(code: impl Into<String>, message: String) -> Self {
+        Self { code: code.into(), message, measured: None, limit: None }
+    }
+}
+
+impl From<EcoFinding> for GuardError {
+    fn from(finding: EcoFinding) -> Self {
+        Self { code: finding.code, message: finding.message }
+    }
+}
+
+/// Result of `EcoFairnessGuard::evaluate`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RohModel {
-    pub ceiling: f32,
-    /// Per-axis weights, e.g. { "eco_impact": 0.4, "compute_concentration": 0.3, ... }.
-    pub weights: HashMap<String, f32>,
+pub struct EcoEvaluation {
+    /// Every failing check, in the order `check` runs them.
+    pub findings: Vec<EcoFinding>,
+    /// Whether `check` would admit the action.
+    pub would_allow: bool,
+    /// The snapshot with the action's usage added.
+    pub projected: ResourceUsageSnapshot,
+}
+
+fn first_failure(findings: Vec<EcoFinding>) -> Result<(), GuardError> {
+    findings.into_iter().next().map_or(Ok(()), |finding| Err(finding.into()))
 }
 
 /// Per-route Tsafe envelope slice for power, heat, and compute.
 /// Conceptually binds to `.tsafe.aln` & `.vkernel.aln` where energy and compute
 /// are just additional axes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TsafeEcoEnvelope {
     /// Logical route, e.g. "XR", "DRONE", "AUTO_CHURCH_SIM", "AUTO_CHURCH_LIVE".
     pub route: String,
-    /// Max allowable instantaneous power draw (Watts equivalent).
-    pub max_power: f32,
-    /// Max allowable cumulative heat/energy over a time window (Joules equivalent).
-    pub max_cumulative_energy: f32,
-    /// Max fraction of local compute capacity this route may occupy (0.0–1.0).
-    pub max_compute_fraction: f32,
+    /// Max allowable instantaneous power draw.
+    pub max_power: Watts,
+    /// Max allowable cumulative heat/energy over a time window.
+    pub max_cumulative_energy: Joules,
+    /// Max fraction of local compute capacity this route may occupy.
+    pub max_compute_fraction: ComputeFraction,
+    /// Fields from newer envelope versions, kept as loaded.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub ext: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Equity class: groups of subjects / communities that must receive fair treatment.
@@ -44,23 +138,39 @@ pub struct EquityClass {
     pub name: String,
 }
 
-/// Snapshot of current resource usage, e.g. computed by the scheduler and passed
-/// into the guard on every high-risk action.
+/// Snapshot of current resource usage, passed into the guard on every
+/// high-risk action; the gate takes it from a `SnapshotBuilder`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceUsageSnapshot {
-    /// Total available power (Watts) for this node / cell / service window.
-    pub total_power_budget: f32,
-    /// Total available compute capacity (0.0–1.0 normalized).
+    /// Total available power for this node / cell / service window.
+    pub total_power_budget: Watts,
+    /// Total available compute capacity, in the same abstract units as
+    /// `XRAction::lifeforcecost`.
     pub total_compute_capacity: f32,
-    /// Current instantaneous power draw (Watts).
-    pub current_power_draw: f32,
-    /// Current cumulative energy usage in the time window (Joules).
-    pub current_cumulative_energy: f32,
-    /// Current compute utilization (0.0–1.0).
-    pub current_compute_fraction: f32,
+    /// Current instantaneous power draw.
+    pub current_power_draw: Watts,
+    /// Current cumulative energy usage in the time window.
+    pub current_cumulative_energy: Joules,
+    /// Current compute utilization.
+    pub current_compute_fraction: ComputeFraction,
     /// Per-equity-class current share (0.0–1.0, typically relative to
     /// total_compute_capacity or total_power_budget).
     pub class_shares: HashMap<String, f32>,
+    /// Set when the external power reading behind this snapshot is stale;
+    /// the guard then allows only `DEGRADED_HEADROOM` of each limit.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+}
+
+impl ResourceUsageSnapshot {
+    /// Scale applied to limits when checking against this snapshot.
+    pub fn headroom_scale(&self) -> f64 {
+        if self.degraded {
+            DEGRADED_HEADROOM
+        } else {
+            1.0
+        }
+    }
 }
 
 /// Minimal projection of the Tsafe Cortex Gate XRAction; this should match
@@ -90,9 +200,28 @@ pub struct XRAction {
     /// Estimated RoH after the action.
     pub rohafterestimate: f32,
     /// Optional equity class for the subject (e.g. "host", "local_congregation").
+    /// With a class registry attached this is only a hint and must match
+    /// the verified class.
     pub equity_class: Option<String>,
 }
 
+impl XRAction {
+    /// `lifeforcecost` read as additional instantaneous power draw.
+    pub fn power_demand(&self) -> Watts {
+        Watts::new(f64::from(self.lifeforcecost))
+    }
+
+    /// `lifeforcecost` read as energy added to the current window.
+    pub fn energy_demand(&self) -> Joules {
+        Joules::new(f64::from(self.lifeforcecost))
+    }
+
+    /// `lifeforcecost` as a share of `capacity` compute units (unclamped).
+    pub fn compute_demand(&self, capacity: f32) -> f64 {
+        f64::from(self.lifeforcecost) / f64::from(capacity.max(1.0))
+    }
+}
+
 /// Configuration shard for EcoFairnessGuard.
 /// In practice you would load RohModel from `.rohmodel.aln`,
 /// TsafeEcoEnvelope from `.tsafe-eco-envelopes.json` / `.vkernel.aln`,
@@ -111,9 +240,36 @@ pub struct EcoFairnessConfig {
 #[derive(Debug, Clone)]
 pub struct EcoFairnessGuard {
     cfg: EcoFairnessConfig,
+    classes: Option<SharedClassRegistry>,
+    bursts: Option<SharedBurstWindows>,
+    slo: Option<SharedSloTracker>,
 }
 
 impl EcoFairnessGuard {
+    pub fn new(cfg: EcoFairnessConfig) -> Self {
+        Self { cfg, classes: None, bursts: None, slo: None }
+    }
+
+    /// Resolve equity classes from `registry` instead of trusting
+    /// `XRAction::equity_class`.
+    pub fn with_class_registry(mut self, registry: SharedClassRegistry) -> Self {
+        self.classes = Some(registry);
+        self
+    }
+
+    /// Apply approved burst grants from `windows` to route envelopes.
+    pub fn with_burst_windows(mut self, windows: SharedBurstWindows) -> Self {
+        self.bursts = Some(windows);
+        self
+    }
+
+    /// Widen the `max_share` of classes in SLO breach by their uplift, and
+    /// record every `check_and_record` outcome in `tracker`.
+    pub fn with_slo_tracker(mut self, tracker: SharedSloTracker) -> Self {
+        self.slo = Some(tracker);
+        self
+    }
+
     /// Load configuration from three JSON-compatible files:
     /// - `.rohmodel.aln`
     /// - `.tsafe-eco-envelopes.json` (route → envelope)
@@ -125,23 +281,20 @@ impl EcoFairnessGuard {
         tsafe_eco_path: P,
         eco_fairness_path: P,
     ) -> anyhow::Result<Self> {
-        let roh_text = fs::read_to_string(roh_path.as_ref())?;
-        let roh_model: RohModel = serde_json::from_str(&roh_text)?;
+        let roh_model = RohModel::load(roh_path.as_ref())?;
 
         let tsafe_text = fs::read_to_string(tsafe_eco_path.as_ref())?;
-        let tsafe_envelopes: HashMap<String, TsafeEcoEnvelope> =
-            serde_json::from_str(&tsafe_text)?;
+        let tsafe_envelopes = load_tsafe_envelopes(&tsafe_text)
+            .map_err(|e| anyhow::anyhow!("{}: {}", tsafe_eco_path.as_ref().display(), e))?;
 
         let eco_text = fs::read_to_string(eco_fairness_path.as_ref())?;
-        let grace_equity: GraceEquityKernel = serde_json::from_str(&eco_text)?;
+        let grace_equity = GraceEquityKernel::from_json(&eco_text)?;
 
-        Ok(Self {
-            cfg: EcoFairnessConfig {
-                roh_model,
-                tsafe_envelopes,
-                grace_equity,
-            },
-        })
+        Ok(Self::new(EcoFairnessConfig {
+            roh_model,
+            tsafe_envelopes,
+            grace_equity,
+        }))
     }
 
     /// Main check function to be called from Tsafe Cortex Gate.
@@ -153,78 +306,139 @@ impl EcoFairnessGuard {
     /// Returns:
     /// - `Ok(())` if within envelopes and fairness constraints,
     /// - `Err(GuardError)` if the action must be denied.
+    ///
+    /// A degraded snapshot is treated conservatively: power, energy and
+    /// compute limits and every class `max_share` are scaled by
+    /// `DEGRADED_HEADROOM`, with the same error codes.
+    ///
+    /// An open burst window multiplies the route's power, energy and
+    /// compute limits only; equity bounds and the RoH ceiling are unchanged.
+    ///
+    /// The error is the first finding `evaluate` would report.
     pub fn check(
         &self,
         action: &XRAction,
         snapshot: &ResourceUsageSnapshot,
     ) -> Result<(), GuardError> {
-        // 1. Per-route eco envelope.
-        self.check_route_envelope(action, snapshot)?;
+        first_failure(self.findings(action, snapshot))
+    }
 
-        // 2. GraceEquityKernel fairness.
-        self.check_equity_bounds(action, snapshot)?;
+    /// Dry run of `check` for operators tuning envelopes: every failing
+    /// check rather than the first, with the measured and limit values
+    /// behind each, and `snapshot` as it would look with `action` admitted.
+    /// Records nothing.
+    pub fn evaluate(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> EcoEvaluation {
+        let findings = self.findings(action, snapshot);
+        EcoEvaluation { would_allow: findings.is_empty(), findings, projected: self.project(action, snapshot) }
+    }
 
+    /// `snapshot` with `action`'s usage added, the way a `SnapshotBuilder`
+    /// commit adds it.
+    pub(crate) fn project(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> ResourceUsageSnapshot {
+        let mut projected = snapshot.clone();
+        projected.current_power_draw += action.power_demand();
+        projected.current_cumulative_energy += action.energy_demand();
+        projected.current_compute_fraction = ComputeFraction::saturating(
+            snapshot.current_compute_fraction.value() + action.compute_demand(snapshot.total_compute_capacity),
+        );
+        if let Ok(class) = self.resolve_class(action) {
+            let budget = snapshot.total_power_budget.max(Watts::new(1.0));
+            *projected.class_shares.entry(class).or_default() += action.power_demand().ratio(budget) as f32;
+        }
+        projected
+    }
+
+    /// Every failing check, in the order `check` runs them.
+    fn findings(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot) -> Vec<EcoFinding> {
+        let mut findings = Vec::new();
+        // 1. Per-route eco envelope.
+        self.route_envelope_findings(action, snapshot, self.envelope_scale(&action.route, snapshot), &mut findings);
+        // 2. GraceEquityKernel fairness.
+        self.equity_findings(action, snapshot, &mut findings);
         // 3. RoH ceiling + eco-related RoH contribution.
-        self.check_roh_ecofairness(action)?;
+        self.roh_findings(action, &mut findings);
+        findings
+    }
 
-        Ok(())
+    /// Factor applied to `route`'s envelope limits against `snapshot`.
+    fn envelope_scale(&self, route: &str, snapshot: &ResourceUsageSnapshot) -> f64 {
+        let burst = self.bursts.as_ref().map_or(1.0, |bursts| {
+            let bursts = bursts.read().unwrap_or_else(|e| e.into_inner());
+            bursts.multiplier(route, bursts.now())
+        });
+        snapshot.headroom_scale() * burst
     }
 
-    fn check_route_envelope(
+    fn check_route_envelope_scaled(
         &self,
         action: &XRAction,
         snapshot: &ResourceUsageSnapshot,
+        scale: f64,
     ) -> Result<(), GuardError> {
-        let env = self
-            .cfg
-            .tsafe_envelopes
-            .get(&action.route)
-            .ok_or_else(|| GuardError {
-                code: "ECO_NO_ROUTE_ENV".into(),
-                message: format!(
-                    "No TsafeEcoEnvelope configured for route '{}' – deny by default",
-                    action.route
-                ),
-            })?;
+        let mut findings = Vec::new();
+        self.route_envelope_findings(action, snapshot, scale, &mut findings);
+        first_failure(findings)
+    }
 
-        let projected_power = snapshot.current_power_draw + action.lifeforcecost;
-        if projected_power > env.max_power {
-            return Err(GuardError {
-                code: "ECO_POWER_EXCEEDED".into(),
-                message: format!(
-                    "Projected power {}W exceeds max {}W for route '{}'",
-                    projected_power, env.max_power, action.route
+    fn route_envelope_findings(
+        &self,
+        action: &XRAction,
+        snapshot: &ResourceUsageSnapshot,
+        scale: f64,
+        findings: &mut Vec<EcoFinding>,
+    ) {
+        let Some(env) = self.cfg.tsafe_envelopes.get(&action.route) else {
+            findings.push(EcoFinding::config(
+                "ECO_NO_ROUTE_ENV",
+                format!("No TsafeEcoEnvelope configured for route '{}' – deny by default", action.route),
+            ));
+            return;
+        };
+
+        let max_power = env.max_power * scale;
+        let projected_power = snapshot.current_power_draw + action.power_demand();
+        if projected_power > max_power {
+            findings.push(EcoFinding::over(
+                "ECO_POWER_EXCEEDED",
+                format!(
+                    "Projected power {} exceeds max {} for route '{}'",
+                    projected_power, max_power, action.route
                 ),
-            });
+                projected_power.value(),
+                max_power.value(),
+            ));
         }
 
-        // For simplicity, treat lifeforcecost as additional energy to the window.
-        let projected_energy = snapshot.current_cumulative_energy + action.lifeforcecost;
-        if projected_energy > env.max_cumulative_energy {
-            return Err(GuardError {
-                code: "ECO_ENERGY_EXCEEDED".into(),
-                message: format!(
-                    "Projected cumulative energy {}J exceeds max {}J for route '{}'",
-                    projected_energy, env.max_cumulative_energy, action.route
+        let max_energy = env.max_cumulative_energy * scale;
+        let projected_energy = snapshot.current_cumulative_energy + action.energy_demand();
+        if projected_energy > max_energy {
+            findings.push(EcoFinding::over(
+                "ECO_ENERGY_EXCEEDED",
+                format!(
+                    "Projected cumulative energy {} exceeds max {} for route '{}'",
+                    projected_energy, max_energy, action.route
                 ),
-            });
+                projected_energy.value(),
+                max_energy.value(),
+            ));
         }
 
         // Simple normalized compute projection; in a real system this should be
         // bound to concrete CPU/GPU metrics.
-        let denom = snapshot.total_compute_capacity.max(1.0);
-        let projected_compute = snapshot.current_compute_fraction + (action.lifeforcecost / denom);
-        if projected_compute > env.max_compute_fraction {
-            return Err(GuardError {
-                code: "ECO_COMPUTE_EXCEEDED".into(),
-                message: format!(
+        let projected_compute = snapshot.current_compute_fraction.value()
+            + action.compute_demand(snapshot.total_compute_capacity);
+        let max_compute = env.max_compute_fraction.value() * scale;
+        if projected_compute > max_compute {
+            findings.push(EcoFinding::over(
+                "ECO_COMPUTE_EXCEEDED",
+                format!(
                     "Projected compute fraction {:.3} exceeds max {:.3} for route '{}'",
-                    projected_compute, env.max_compute_fraction, action.route
+                    projected_compute, max_compute, action.route
                 ),
-            });
+                projected_compute,
+                max_compute,
+            ));
         }
-
-        Ok(())
     }
 
     fn check_equity_bounds(
@@ -232,75 +446,166 @@ impl EcoFairnessGuard {
         action: &XRAction,
         snapshot: &ResourceUsageSnapshot,
     ) -> Result<(), GuardError> {
-        let class_name = match &action.equity_class {
-            Some(c) => c,
-            None => {
-                // If no class is provided, treat as a configuration error for Auto_Church fairness.
-                return Err(GuardError {
-                    code: "ECO_NO_EQUITY_CLASS".into(),
-                    message: "XRAction missing equity_class; Auto_Church fairness requires it"
-                        .into(),
-                });
+        let mut findings = Vec::new();
+        self.equity_findings(action, snapshot, &mut findings);
+        first_failure(findings)
+    }
+
+    fn equity_findings(&self, action: &XRAction, snapshot: &ResourceUsageSnapshot, findings: &mut Vec<EcoFinding>) {
+        let class_name = &match self.resolve_class(action) {
+            Ok(class) => class,
+            Err(e) => {
+                findings.push(EcoFinding::config(e.code, e.message));
+                return;
             }
         };
 
-        let bounds = self
-            .cfg
-            .grace_equity
-            .bounds_for_class(class_name)
-            .ok_or_else(|| GuardError {
-                code: "ECO_UNKNOWN_EQUITY_CLASS".into(),
-                message: format!(
-                    "Equity class '{}' not present in GraceEquityKernel",
-                    class_name
-                ),
-            })?;
+        let Some(bounds) = self.cfg.grace_equity.bounds_for_class(class_name) else {
+            findings.push(EcoFinding::config(
+                "ECO_UNKNOWN_EQUITY_CLASS",
+                format!("Equity class '{}' not present in GraceEquityKernel", class_name),
+            ));
+            return;
+        };
 
         let current_share = snapshot.class_shares.get(class_name).cloned().unwrap_or(0.0);
 
         // Compute a naive projected share: add normalized cost to this class's share.
-        let denom = snapshot.total_power_budget.max(1.0);
-        let projected_share = current_share + (action.lifeforcecost / denom);
+        let denom = snapshot.total_power_budget.max(Watts::new(1.0));
+        let projected_share = current_share + action.power_demand().ratio(denom) as f32;
+
+        // Upper bound: no class may exceed its max_share, widened while one
+        // of its SLOs is in breach.
+        let max_share = self.slo.as_ref().map_or(bounds.max_share, |slo| {
+            let slo = slo.read().unwrap_or_else(|e| e.into_inner());
+            slo.max_share_for(class_name, &self.cfg.grace_equity).unwrap_or(bounds.max_share)
+        });
+        let max_share = max_share * snapshot.headroom_scale() as f32;
+        if projected_share > max_share {
+            findings.push(EcoFinding::over(
+                "ECO_EQUITY_MAX_EXCEEDED",
+                format!(
+                    "Equity class '{}' would exceed max_share {:.3} (projected {:.3})",
+                    class_name, max_share, projected_share
+                ),
+                f64::from(projected_share),
+                f64::from(max_share),
+            ));
+        }
 
-        // Upper bound: no class may exceed its max_share.
-        if projected_share > bounds.max_share {
-            return Err(GuardError {
-                code: "ECO_EQUITY_MAX_EXCEEDED".into(),
+        // Lower bound: pro-equity bias. A class under its own floor is
+        // always admitted; any other must leave room for the floors of the
+        // classes that are under theirs.
+        if current_share >= bounds.min_share {
+            self.floor_findings(class_name, action, snapshot, findings);
+        }
+    }
+
+    /// Flag `action` when the capacity it leaves, on the power budget or
+    /// the compute capacity, whichever is tighter, is less than the other
+    /// classes in `snapshot` still need to reach their `min_share`. The
+    /// finding names the furthest-behind class.
+    fn floor_findings(
+        &self,
+        class_name: &str,
+        action: &XRAction,
+        snapshot: &ResourceUsageSnapshot,
+        findings: &mut Vec<EcoFinding>,
+    ) {
+        let mut starved: Vec<(&str, f32)> = snapshot
+            .class_shares
+            .iter()
+            .filter(|(class, _)| class.as_str() != class_name)
+            .filter_map(|(class, share)| {
+                let floor = self.cfg.grace_equity.bounds_for_class(class)?.min_share;
+                (*share < floor).then_some((class.as_str(), floor - share))
+            })
+            .collect();
+        if starved.is_empty() {
+            return;
+        }
+        starved.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let unmet: f64 = starved.iter().map(|(_, gap)| f64::from(*gap)).sum();
+
+        let budget = snapshot.total_power_budget.max(Watts::new(1.0));
+        let power_left = 1.0 - (snapshot.current_power_draw + action.power_demand()).ratio(budget);
+        let compute_left = 1.0
+            - (snapshot.current_compute_fraction.value() + action.compute_demand(snapshot.total_compute_capacity));
+        let remaining = power_left.min(compute_left);
+        if remaining < unmet {
+            findings.push(EcoFinding {
+                code: "ECO_EQUITY_FLOOR_AT_RISK".into(),
                 message: format!(
-                    "Equity class '{}' would exceed max_share {:.3} (projected {:.3})",
-                    class_name, bounds.max_share, projected_share
+                    "Equity class '{}' is below its min_share; admitting '{}' would leave {:.3} of capacity for {:.3} of unmet floors",
+                    starved[0].0, class_name, remaining, unmet
                 ),
+                measured: Some(remaining),
+                limit: Some(unmet),
             });
         }
+    }
 
-        // Lower bound: pro-equity bias (do not deny under-served classes here).
-        // A more advanced scheduler can use `current_share < min_share` as a
-        // "priority uplift" signal.
-
-        Ok(())
+    /// The class to apply for `action`: from the registry when one is
+    /// attached (the caller's class must then agree), otherwise the
+    /// caller-supplied class.
+    fn resolve_class(&self, action: &XRAction) -> Result<String, GuardError> {
+        let Some(registry) = &self.classes else {
+            return action.equity_class.clone().ok_or_else(|| GuardError {
+                // If no class is provided, treat as a configuration error for Auto_Church fairness.
+                code: "ECO_NO_EQUITY_CLASS".into(),
+                message: "XRAction missing equity_class; Auto_Church fairness requires it".into(),
+            });
+        };
+        let registry = registry.read().unwrap_or_else(|e| e.into_inner());
+        let verified = registry.class_of(&action.subjectid, registry.now()).ok_or_else(|| GuardError {
+            code: "ECO_NO_VERIFIED_CLASS".into(),
+            message: format!("Subject '{}' has no current equity class assignment", action.subjectid),
+        })?;
+        if let Some(claimed) = &action.equity_class {
+            if *claimed != verified.class {
+                return Err(GuardError {
+                    code: "ECO_CLASS_CLAIM_MISMATCH".into(),
+                    message: format!(
+                        "Subject '{}' claimed equity class '{}' but is assigned '{}'",
+                        action.subjectid, claimed, verified.class
+                    ),
+                });
+            }
+        }
+        Ok(verified.class)
     }
 
     fn check_roh_ecofairness(&self, action: &XRAction) -> Result<(), GuardError> {
+        let mut findings = Vec::new();
+        self.roh_findings(action, &mut findings);
+        first_failure(findings)
+    }
+
+    fn roh_findings(&self, action: &XRAction, findings: &mut Vec<EcoFinding>) {
         // Standard RoH ceiling & monotone safety: RoH must not increase
         // and must remain ≤ ceiling (typically 0.3).
         if action.rohafterestimate > self.cfg.roh_model.ceiling {
-            return Err(GuardError {
-                code: "ROH_CEILING".into(),
-                message: format!(
+            findings.push(EcoFinding::over(
+                "ROH_CEILING",
+                format!(
                     "RoH estimate {:.3} exceeds ceiling {:.3}",
                     action.rohafterestimate, self.cfg.roh_model.ceiling
                 ),
-            });
+                f64::from(action.rohafterestimate),
+                f64::from(self.cfg.roh_model.ceiling),
+            ));
         }
 
         if action.rohafterestimate > action.rohbefore {
-            return Err(GuardError {
-                code: "ROH_MONOTONE".into(),
-                message: format!(
+            findings.push(EcoFinding::over(
+                "ROH_MONOTONE",
+                format!(
                     "RoH monotone safety violated: before {:.3}, after {:.3}",
                     action.rohbefore, action.rohafterestimate
                 ),
-            });
+                f64::from(action.rohafterestimate),
+                f64::from(action.rohbefore),
+            ));
         }
 
         // Optional: check eco-related RoH axes if present.
@@ -313,15 +618,98 @@ impl EcoFairnessGuard {
         {
             // Not a hard error for now; CI can tighten this to a failure if required.
         }
+    }
+
+    /// Like `check`, but on admission records how much RoH and envelope
+    /// headroom the action consumed into `ledger`. With an SLO tracker
+    /// attached, the outcome and the time the check took are recorded there.
+    pub fn check_and_record(
+        &self,
+        action: &XRAction,
+        snapshot: &ResourceUsageSnapshot,
+        ledger: &mut HeadroomLedger,
+        now: u64,
+    ) -> Result<(), GuardError> {
+        let started = std::time::Instant::now();
+        let result = self.check(action, snapshot);
+        let latency_ms = started.elapsed().as_secs_f64() * 1e3;
+        if let Some(bursts) = &self.bursts {
+            self.observe_burst(bursts, action, snapshot, &result, now);
+        }
+        if let Some(slo) = &self.slo {
+            if let Ok(class) = self.resolve_class(action) {
+                let outcome = match &result {
+                    Ok(()) => Outcome::Admitted,
+                    Err(e) => Outcome::Rejected { code: e.code.clone() },
+                };
+                let obs = Observation { route: action.route.clone(), class, at: now, latency_ms, outcome };
+                slo.write().unwrap_or_else(|e| e.into_inner()).record(obs);
+            }
+        }
+        result?;
+        let subject_class = self.resolve_class(action)?;
+
+        // check has already confirmed the envelope exists.
+        let env = &self.cfg.tsafe_envelopes[&action.route];
+        let compute_step = action.compute_demand(snapshot.total_compute_capacity);
+        let compute_frac = |value: f64| {
+            let limit = env.max_compute_fraction.value();
+            if limit > 0.0 { (value / limit) as f32 } else { 1.0 }
+        };
 
+        ledger.record(AdmissionRecord {
+            timestamp: now,
+            route: action.route.clone(),
+            kind: format!("{:?}", action.kind),
+            subject_class,
+            roh_before: action.rohbefore,
+            roh_after: action.rohafterestimate,
+            roh_ceiling: self.cfg.roh_model.ceiling,
+            power: AxisUtilization {
+                before: snapshot.current_power_draw.ratio(env.max_power) as f32,
+                after: (snapshot.current_power_draw + action.power_demand()).ratio(env.max_power) as f32,
+            },
+            energy: AxisUtilization {
+                before: snapshot.current_cumulative_energy.ratio(env.max_cumulative_energy) as f32,
+                after: (snapshot.current_cumulative_energy + action.energy_demand()).ratio(env.max_cumulative_energy)
+                    as f32,
+            },
+            compute: AxisUtilization {
+                before: compute_frac(snapshot.current_compute_fraction.value()),
+                after: compute_frac(snapshot.current_compute_fraction.value() + compute_step),
+            },
+        });
         Ok(())
     }
 
+    fn observe_burst(
+        &self,
+        bursts: &SharedBurstWindows,
+        action: &XRAction,
+        snapshot: &ResourceUsageSnapshot,
+        result: &EcoFairnessResult,
+        now: u64,
+    ) {
+        let Some(env) = self.cfg.tsafe_envelopes.get(&action.route) else {
+            return;
+        };
+        let base_scale = snapshot.headroom_scale();
+        let beyond_base =
+            result.is_ok() && self.check_route_envelope_scaled(action, snapshot, base_scale).is_err();
+        let power = (snapshot.current_power_draw + action.power_demand()).ratio(env.max_power * base_scale);
+        let class = self.resolve_class(action).ok();
+        let mut bursts = bursts.write().unwrap_or_else(|e| e.into_inner());
+        bursts.observe(action, class.as_deref(), result, beyond_base, power, now);
+    }
+
     /// Convenience layer for Tsafe Cortex Gate, so you can call:
     ///
-    /// `eco_guard.check_for_gate(&req.action, &snapshot)`
+    /// `eco_guard.check_for_gate(&req.action, &usage.current())`
     ///
-    /// inside the main `authorize_request` function.
+    /// inside the main `authorize_request` function, where `usage` is the
+    /// gate's `SnapshotBuilder`. Gates that see the same read-only requests
+    /// repeatedly can call a `GateDecisionCache` wrapping the guard instead.
+    /// Clients planning a batch can ask `advise` first; it admits nothing.
     pub fn check_for_gate(
         &self,
         action: &XRAction,
@@ -329,6 +717,26 @@ impl EcoFairnessGuard {
     ) -> EcoFairnessResult {
         self.check(action, snapshot)
     }
+
+    /// `check` against the builder's current view.
+    pub fn check_current(&self, action: &XRAction, usage: &SnapshotBuilder) -> EcoFairnessResult {
+        self.check(action, &usage.current())
+    }
+}
+
+const ENVELOPE_FIELDS: &[&str] =
+    &["route", "max_power", "max_cumulative_energy", "max_compute_fraction", EXT_FIELD];
+
+/// Parse a `.tsafe-eco-envelopes.json` document (route → envelope). Errors
+/// name the offending JSON path, e.g. `AUTO_CHURCH_SIM.max_compute_fraction`;
+/// an unknown field outside an envelope's `ext` is `UNKNOWN_CRITICAL_FIELD`.
+pub fn load_tsafe_envelopes(text: &str) -> anyhow::Result<HashMap<String, TsafeEcoEnvelope>> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    if let Some(path) = schema::unknown_field_in_values(&value, "", ENVELOPE_FIELDS) {
+        anyhow::bail!("{UNKNOWN_CRITICAL_FIELD}: {path}");
+    }
+    let de = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(de).map_err(|e| anyhow::anyhow!("{}: {}", e.path(), e.inner()))
 }
 
 // --- Shared error type used by callers integrating multiple guardians ---
@@ -0,0 +1,169 @@
+//! Validated loading of `.rohmodel.aln` and `.vkernel.aln`.
+//!
+//! Both are JSON-compatible. `load` parses, then runs the same `validate`
+//! callers can run on a model built in code; every error names the file
+//! (when there is one) and the offending field.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ModelError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    /// Malformed JSON, a missing field or a value of the wrong type.
+    /// `field` is the JSON path serde stopped at.
+    #[error("{}: {field}: {source}", path.display())]
+    Parse { path: PathBuf, field: String, source: serde_json::Error },
+    #[error("{}{field}: {reason}", path.as_ref().map(|p| format!("{}: ", p.display())).unwrap_or_default())]
+    Invalid { path: Option<PathBuf>, field: String, reason: String },
+}
+
+impl ModelError {
+    fn invalid(field: impl fmt::Display, reason: impl Into<String>) -> Self {
+        Self::Invalid { path: None, field: field.to_string(), reason: reason.into() }
+    }
+
+    fn in_file(self, file: &Path) -> Self {
+        match self {
+            Self::Invalid { field, reason, .. } => Self::Invalid { path: Some(file.to_path_buf()), field, reason },
+            other => other,
+        }
+    }
+
+    /// The field the error is about, if it is about one.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::Io { .. } => None,
+            Self::Parse { field, .. } | Self::Invalid { field, .. } => Some(field),
+        }
+    }
+}
+
+fn read<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, ModelError> {
+    let text = fs::read_to_string(path).map_err(|source| ModelError::Io { path: path.to_path_buf(), source })?;
+    let de = &mut serde_json::Deserializer::from_str(&text);
+    serde_path_to_error::deserialize(de).map_err(|e| ModelError::Parse {
+        path: path.to_path_buf(),
+        field: e.path().to_string(),
+        source: e.into_inner(),
+    })
+}
+
+/// Projection of the RoH model relevant for eco / compute fairness.
+/// This is assumed to be parsed from `.rohmodel.aln` (JSON-compatible).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RohModel {
+    pub ceiling: f32,
+    /// Per-axis weights, e.g. { "eco_impact": 0.4, "compute_concentration": 0.3, ... }.
+    pub weights: HashMap<String, f32>,
+}
+
+impl RohModel {
+    /// Load, validate and normalize a `.rohmodel.aln`. Weights that do not
+    /// sum to 1.0 are scaled so they do, with a warning.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        let path = path.as_ref();
+        let mut model: Self = read(path)?;
+        model.validate().map_err(|e| e.in_file(path))?;
+        let sum = model.weight_sum();
+        if !model.weights.is_empty() && (sum - 1.0).abs() > 1e-4 {
+            tracing::warn!("{}: weights sum to {sum}, renormalized to 1.0", path.display());
+            for weight in model.weights.values_mut() {
+                *weight /= sum;
+            }
+        }
+        Ok(model)
+    }
+
+    /// The ceiling must lie in (0, 1]; weights must be finite and
+    /// non-negative, and not all zero. No weights at all is allowed.
+    pub fn validate(&self) -> Result<(), ModelError> {
+        if !(self.ceiling > 0.0 && self.ceiling <= 1.0) {
+            return Err(ModelError::invalid("ceiling", format!("must be in (0, 1], got {}", self.ceiling)));
+        }
+        for (axis, weight) in &self.weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(ModelError::invalid(
+                    format_args!("weights.{axis}"),
+                    format!("must be a non-negative number, got {weight}"),
+                ));
+            }
+        }
+        if !self.weights.is_empty() && self.weight_sum() <= 0.0 {
+            return Err(ModelError::invalid("weights", "must not all be zero"));
+        }
+        Ok(())
+    }
+
+    fn weight_sum(&self) -> f32 {
+        self.weights.values().sum()
+    }
+}
+
+/// Tsafe viability kernel from `.vkernel.aln`: the polytope
+/// `constraints · x ≤ bounds` over the named `axes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViabilityKernel {
+    pub axes: Vec<String>,
+    /// One row per constraint, one column per axis.
+    pub constraints: Vec<Vec<f64>>,
+    /// One bound per constraint row.
+    pub bounds: Vec<f64>,
+}
+
+impl ViabilityKernel {
+    /// Load and validate a `.vkernel.aln`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        let path = path.as_ref();
+        let kernel: Self = read(path)?;
+        kernel.validate().map_err(|e| e.in_file(path))?;
+        Ok(kernel)
+    }
+
+    /// Axes must be non-empty and unique, every constraint row must have
+    /// one finite coefficient per axis, and there must be one finite bound
+    /// per row.
+    pub fn validate(&self) -> Result<(), ModelError> {
+        if self.axes.is_empty() {
+            return Err(ModelError::invalid("axes", "must not be empty"));
+        }
+        let mut seen = HashSet::new();
+        if let Some(axis) = self.axes.iter().find(|axis| !seen.insert(axis.as_str())) {
+            return Err(ModelError::invalid("axes", format!("duplicate axis '{axis}'")));
+        }
+        if self.constraints.len() != self.bounds.len() {
+            return Err(ModelError::invalid(
+                "bounds",
+                format!("{} bounds for {} constraint rows", self.bounds.len(), self.constraints.len()),
+            ));
+        }
+        for (i, row) in self.constraints.iter().enumerate() {
+            if row.len() != self.axes.len() {
+                return Err(ModelError::invalid(
+                    format_args!("constraints[{i}]"),
+                    format!("{} coefficients for {} axes", row.len(), self.axes.len()),
+                ));
+            }
+            if let Some(j) = row.iter().position(|c| !c.is_finite()) {
+                return Err(ModelError::invalid(format_args!("constraints[{i}][{j}]"), "must be finite"));
+            }
+        }
+        if let Some(i) = self.bounds.iter().position(|b| !b.is_finite()) {
+            return Err(ModelError::invalid(format_args!("bounds[{i}]"), "must be finite"));
+        }
+        Ok(())
+    }
+
+    /// Whether `point`, one value per axis, satisfies every constraint.
+    pub fn is_viable(&self, point: &[f64]) -> bool {
+        point.len() == self.axes.len()
+            && self.constraints.iter().zip(&self.bounds).all(|(row, bound)| {
+                row.iter().zip(point).map(|(a, x)| a * x).sum::<f64>() <= *bound
+            })
+    }
+}
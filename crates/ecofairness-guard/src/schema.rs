@@ -0,0 +1,30 @@
+//! Evolution rules for guard config documents, the same ones the deed
+//! schema follows: fields from newer versions go under a reserved `ext`
+//! object and are kept as loaded; any other unknown field is critical and
+//! fails the load with its path, rather than being dropped by serde.
+
+use serde_json::Value;
+
+pub const UNKNOWN_CRITICAL_FIELD: &str = "UNKNOWN_CRITICAL_FIELD";
+/// The reserved object for fields from newer config versions.
+pub const EXT_FIELD: &str = "ext";
+
+/// The first key of object `value` outside `known`, as `path.key`.
+/// Non-objects pass; their shape is serde's to check.
+pub(crate) fn unknown_field(value: &Value, path: &str, known: &[&str]) -> Option<String> {
+    let key = value.as_object()?.keys().find(|k| !known.contains(&k.as_str()))?;
+    Some(if path.is_empty() { key.clone() } else { format!("{path}.{key}") })
+}
+
+/// `unknown_field` for every value of object `value`, each under its key.
+pub(crate) fn unknown_field_in_values(value: &Value, path: &str, known: &[&str]) -> Option<String> {
+    value.as_object()?.iter().find_map(|(key, child)| {
+        let child_path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+        unknown_field(child, &child_path, known)
+    })
+}
+
+/// `unknown_field` for every element of array `value`, as `path[i]`.
+pub(crate) fn unknown_field_in_items(value: &Value, path: &str, known: &[&str]) -> Option<String> {
+    value.as_array()?.iter().enumerate().find_map(|(i, item)| unknown_field(item, &format!("{path}[{i}]"), known))
+}
@@ -0,0 +1,590 @@
+//! Service-level objectives per (route, equity class).
+//!
+//! Operators promise congregations a level of service: "local_congregation
+//! requests on AUTO_CHURCH_LIVE answered within 200 ms at p95, at most 1%
+//! eco rejections". An `SloSpec` states such a promise as latency
+//! percentiles, a maximum rejection rate per error category and a minimum
+//! availability. `SloTracker` keeps the outcomes `record`ed for each spec
+//! over the last `window_secs`, and `evaluate` compares them with it. Every
+//! objective is inclusive: a p95 of exactly `max_ms`, or a rejection rate
+//! of exactly the maximum, complies. A spec with fewer than `min_samples`
+//! outcomes in its window is not evaluated and keeps its state.
+//!
+//! Outcomes inside a maintenance window, or on a route with an open burst
+//! window, are counted as excluded and left out of the math; a spec is not
+//! evaluated at all while its route is under maintenance.
+//!
+//! A breach is reported once, as an `SloEvent` returned by `evaluate`,
+//! queued for `drain_events` (to be logged as an `slo_breach` deed) and
+//! pushed to the `SloAlerter` if one is attached. Until the spec recovers
+//! its class gets a priority uplift: a guard with the tracker attached
+//! widens the class's `max_share` by `uplift_step` at each evaluation still
+//! in breach, up to `max_uplift` and never into the other classes'
+//! `min_share` floors. Recovery clears the uplift and is reported the same
+//! way (`slo_recovered`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write as _;
+use std::sync::{Arc, RwLock};
+
+use crate::burst::SharedBurstWindows;
+use crate::class_assignment::system_now;
+use crate::kernel::GraceEquityKernel;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SloError {
+    #[error("SLO config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("SLO '{0}' is defined twice")]
+    Duplicate(String),
+    #[error("SLO '{id}': {reason}")]
+    Invalid { id: String, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LatencyObjective {
+    /// In (0, 1]: 0.95 for p95.
+    pub percentile: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SloSpec {
+    pub id: String,
+    pub route: String,
+    pub class: String,
+    #[serde(default)]
+    pub latency: Vec<LatencyObjective>,
+    /// Rejection category (see `rejection_category`) → highest share of
+    /// outcomes allowed to be rejected with it.
+    #[serde(default)]
+    pub max_rejection_rate: BTreeMap<String, f64>,
+    /// Lowest share of outcomes that must be answered (not `Unavailable`).
+    #[serde(default)]
+    pub min_availability: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindow {
+    /// Route under maintenance; every route when absent.
+    #[serde(default)]
+    pub route: Option<String>,
+    /// Unix seconds, inclusive.
+    pub starts_at: u64,
+    /// Unix seconds, exclusive.
+    pub ends_at: u64,
+}
+
+impl MaintenanceWindow {
+    fn covers(&self, route: &str, at: u64) -> bool {
+        self.route.iter().all(|r| r == route) && self.starts_at <= at && at < self.ends_at
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SloConfig {
+    pub slos: Vec<SloSpec>,
+    /// Length of the evaluation window, in seconds.
+    pub window_secs: u64,
+    /// Outcomes a window needs before it is evaluated.
+    pub min_samples: usize,
+    /// Added to a breached class's `max_share` at each evaluation in breach.
+    pub uplift_step: f32,
+    /// Upper bound for a class's uplift.
+    pub max_uplift: f32,
+    pub maintenance: Vec<MaintenanceWindow>,
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            slos: Vec::new(),
+            window_secs: 300,
+            min_samples: 20,
+            uplift_step: 0.02,
+            max_uplift: 0.1,
+            maintenance: Vec::new(),
+        }
+    }
+}
+
+impl SloConfig {
+    pub fn from_json(text: &str) -> Result<Self, SloError> {
+        let cfg: Self = serde_json::from_str(text)?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// Unique ids, percentiles in (0, 1], rates and availability in [0, 1].
+    pub fn validate(&self) -> Result<(), SloError> {
+        let mut ids = BTreeSet::new();
+        for slo in &self.slos {
+            if !ids.insert(slo.id.as_str()) {
+                return Err(SloError::Duplicate(slo.id.clone()));
+            }
+            let invalid = |reason: String| SloError::Invalid { id: slo.id.clone(), reason };
+            if let Some(l) = slo.latency.iter().find(|l| l.percentile <= 0.0 || l.percentile > 1.0 || l.max_ms < 0.0) {
+                return Err(invalid(format!("latency objective {} at {} ms", l.percentile, l.max_ms)));
+            }
+            if let Some((category, rate)) = slo.max_rejection_rate.iter().find(|(_, r)| !(0.0..=1.0).contains(*r)) {
+                return Err(invalid(format!("rejection rate {} for '{}' is outside [0, 1]", rate, category)));
+            }
+            if let Some(a) = slo.min_availability.filter(|a| !(0.0..=1.0).contains(a)) {
+                return Err(invalid(format!("availability {} is outside [0, 1]", a)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How one request ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Outcome {
+    Admitted,
+    /// Denied with this `GuardError` code.
+    Rejected {
+        code: String,
+    },
+    /// Never answered: timed out or the guard was unreachable.
+    Unavailable,
+}
+
+/// Category of a `GuardError` code: the part before the first `_`,
+/// lowercased, so `ECO_POWER_EXCEEDED` is `eco` and `ROH_CEILING` is `roh`.
+pub fn rejection_category(code: &str) -> String {
+    code.split('_').next().unwrap_or(code).to_ascii_lowercase()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub route: String,
+    pub class: String,
+    /// Unix seconds.
+    pub at: u64,
+    pub latency_ms: f64,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyMeasurement {
+    pub percentile: f64,
+    pub max_ms: f64,
+    /// None with no answered request in the window.
+    pub measured_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateMeasurement {
+    pub category: String,
+    pub max_rate: f64,
+    pub measured: f64,
+}
+
+/// One spec's standing; see `SloTracker::slo_status`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloStatus {
+    pub id: String,
+    pub route: String,
+    pub class: String,
+    /// Outcomes in the current window.
+    pub samples: usize,
+    /// Outcomes left out for falling in a maintenance or burst window.
+    pub excluded: u64,
+    pub latency: Vec<LatencyMeasurement>,
+    pub rejection_rates: Vec<RateMeasurement>,
+    pub min_availability: Option<f64>,
+    pub availability: Option<f64>,
+    /// Whether the window holds `min_samples` outcomes.
+    pub evaluated: bool,
+    pub in_maintenance: bool,
+    /// Objectives the window misses; empty unless evaluated.
+    pub violations: Vec<String>,
+    /// Set from the evaluation that found the breach until recovery.
+    pub breached_since: Option<u64>,
+    pub uplift: f32,
+}
+
+impl SloStatus {
+    pub fn compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SloEventKind {
+    Breached,
+    Recovered,
+}
+
+impl SloEventKind {
+    pub fn deed_type(&self) -> &'static str {
+        match self {
+            Self::Breached => "slo_breach",
+            Self::Recovered => "slo_recovered",
+        }
+    }
+}
+
+/// A breach or recovery awaiting persistence as a deed; see `drain_events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloEvent {
+    pub kind: SloEventKind,
+    /// Unix seconds.
+    pub at: u64,
+    pub status: SloStatus,
+}
+
+impl SloEvent {
+    /// Context payload for the `slo_*` deed.
+    pub fn to_deed_context(&self) -> serde_json::Value {
+        serde_json::json!({ "deed_type": self.kind.deed_type(), "slo_event": self })
+    }
+}
+
+/// Delivers breach and recovery events to operators, e.g. as a webhook.
+pub trait SloAlerter: Send + Sync {
+    fn alert(&self, event: &SloEvent) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    at: u64,
+    latency_ms: f64,
+    outcome: Outcome,
+}
+
+#[derive(Debug, Default)]
+struct SloState {
+    samples: VecDeque<Sample>,
+    excluded: u64,
+    breached_since: Option<u64>,
+    uplift: f32,
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+/// Measured outcomes and breach state for every configured SLO.
+pub struct SloTracker {
+    cfg: SloConfig,
+    states: Vec<SloState>,
+    bursts: Option<SharedBurstWindows>,
+    alerter: Option<Arc<dyn SloAlerter>>,
+    events: Vec<SloEvent>,
+    alerts_failed: u64,
+    clock: Clock,
+}
+
+/// Handle shared between the guard, the gate recording outcomes and
+/// whoever evaluates.
+pub type SharedSloTracker = Arc<RwLock<SloTracker>>;
+
+impl std::fmt::Debug for SloTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SloTracker").field("cfg", &self.cfg).field("events", &self.events.len()).finish()
+    }
+}
+
+impl SloTracker {
+    pub fn new(cfg: SloConfig) -> Result<Self, SloError> {
+        cfg.validate()?;
+        let states = cfg.slos.iter().map(|_| SloState::default()).collect();
+        Ok(Self {
+            cfg,
+            states,
+            bursts: None,
+            alerter: None,
+            events: Vec::new(),
+            alerts_failed: 0,
+            clock: Box::new(system_now),
+        })
+    }
+
+    /// Replace the wall clock (Unix seconds); tests and replays.
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Leave outcomes on routes with an open burst window out of the math.
+    pub fn with_burst_windows(mut self, windows: SharedBurstWindows) -> Self {
+        self.bursts = Some(windows);
+        self
+    }
+
+    pub fn with_alerter(mut self, alerter: Arc<dyn SloAlerter>) -> Self {
+        self.alerter = Some(alerter);
+        self
+    }
+
+    pub fn into_shared(self) -> SharedSloTracker {
+        Arc::new(RwLock::new(self))
+    }
+
+    pub fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    pub fn config(&self) -> &SloConfig {
+        &self.cfg
+    }
+
+    fn in_maintenance(&self, route: &str, at: u64) -> bool {
+        self.cfg.maintenance.iter().any(|w| w.covers(route, at))
+    }
+
+    fn excluded(&self, route: &str, at: u64) -> bool {
+        self.in_maintenance(route, at)
+            || self.bursts.as_ref().is_some_and(|bursts| {
+                bursts.read().unwrap_or_else(|e| e.into_inner()).active_grant(route, at).is_some()
+            })
+    }
+
+    /// Count one outcome toward every spec for its route and class.
+    pub fn record(&mut self, obs: Observation) {
+        let excluded = self.excluded(&obs.route, obs.at);
+        let window_secs = self.cfg.window_secs;
+        for (spec, state) in self.cfg.slos.iter().zip(&mut self.states) {
+            if spec.route != obs.route || spec.class != obs.class {
+                continue;
+            }
+            if excluded {
+                state.excluded += 1;
+                continue;
+            }
+            state.samples.push_back(Sample { at: obs.at, latency_ms: obs.latency_ms, outcome: obs.outcome.clone() });
+            while state.samples.front().is_some_and(|s| s.at + window_secs <= obs.at) {
+                state.samples.pop_front();
+            }
+        }
+    }
+
+    fn measure(&self, index: usize, now: u64) -> SloStatus {
+        let (spec, state) = (&self.cfg.slos[index], &self.states[index]);
+        let window: Vec<&Sample> =
+            state.samples.iter().filter(|s| s.at <= now && s.at + self.cfg.window_secs > now).collect();
+        let n = window.len();
+        let share = |count: usize| if n == 0 { 0.0 } else { count as f64 / n as f64 };
+
+        let mut answered: Vec<f64> =
+            window.iter().filter(|s| s.outcome != Outcome::Unavailable).map(|s| s.latency_ms).collect();
+        answered.sort_by(f64::total_cmp);
+        let latency: Vec<LatencyMeasurement> = spec
+            .latency
+            .iter()
+            .map(|o| LatencyMeasurement {
+                percentile: o.percentile,
+                max_ms: o.max_ms,
+                measured_ms: nearest_rank(&answered, o.percentile),
+            })
+            .collect();
+        let rejection_rates: Vec<RateMeasurement> = spec
+            .max_rejection_rate
+            .iter()
+            .map(|(category, &max_rate)| {
+                let rejected = window
+                    .iter()
+                    .filter(
+                        |s| matches!(&s.outcome, Outcome::Rejected { code } if rejection_category(code) == *category),
+                    )
+                    .count();
+                RateMeasurement { category: category.clone(), max_rate, measured: share(rejected) }
+            })
+            .collect();
+        let unavailable = window.iter().filter(|s| s.outcome == Outcome::Unavailable).count();
+        let availability = (n > 0).then(|| share(n - unavailable));
+
+        let evaluated = n >= self.cfg.min_samples.max(1);
+        let mut violations = Vec::new();
+        if evaluated {
+            for l in &latency {
+                if let Some(measured) = l.measured_ms.filter(|m| *m > l.max_ms) {
+                    violations.push(format!(
+                        "{} latency {} ms > {} ms",
+                        percentile_label(l.percentile),
+                        measured,
+                        l.max_ms
+                    ));
+                }
+            }
+            for r in rejection_rates.iter().filter(|r| r.measured > r.max_rate) {
+                violations.push(format!("{} rejection rate {:.4} > {}", r.category, r.measured, r.max_rate));
+            }
+            if let (Some(min), Some(measured)) = (spec.min_availability, availability) {
+                if measured < min {
+                    violations.push(format!("availability {:.4} < {}", measured, min));
+                }
+            }
+        }
+        SloStatus {
+            id: spec.id.clone(),
+            route: spec.route.clone(),
+            class: spec.class.clone(),
+            samples: n,
+            excluded: state.excluded,
+            latency,
+            rejection_rates,
+            min_availability: spec.min_availability,
+            availability,
+            evaluated,
+            in_maintenance: self.in_maintenance(&spec.route, now),
+            violations,
+            breached_since: state.breached_since,
+            uplift: state.uplift,
+        }
+    }
+
+    /// Compare every spec's window with its objectives at the current time.
+    /// Returns the breaches and recoveries found, which are also queued for
+    /// `drain_events` and sent to the alerter.
+    pub fn evaluate(&mut self) -> Vec<SloEvent> {
+        let now = self.now();
+        let (step, max) = (self.cfg.uplift_step.max(0.0), self.cfg.max_uplift.max(0.0));
+        let mut events = Vec::new();
+        for index in 0..self.cfg.slos.len() {
+            let window_secs = self.cfg.window_secs;
+            self.states[index].samples.retain(|s| s.at + window_secs > now);
+            let status = self.measure(index, now);
+            if status.in_maintenance || !status.evaluated {
+                continue;
+            }
+            let state = &mut self.states[index];
+            let kind = match (state.breached_since.is_some(), status.compliant()) {
+                (false, false) => {
+                    state.breached_since = Some(now);
+                    state.uplift = step.min(max);
+                    SloEventKind::Breached
+                }
+                (true, false) => {
+                    state.uplift = (state.uplift + step).min(max);
+                    continue;
+                }
+                (true, true) => {
+                    state.breached_since = None;
+                    state.uplift = 0.0;
+                    SloEventKind::Recovered
+                }
+                (false, true) => continue,
+            };
+            events.push(SloEvent { kind, at: now, status: self.measure(index, now) });
+        }
+        if let Some(alerter) = &self.alerter {
+            for event in &events {
+                if alerter.alert(event).is_err() {
+                    self.alerts_failed += 1;
+                }
+            }
+        }
+        self.events.extend(events.iter().cloned());
+        events
+    }
+
+    /// Breaches and recoveries since the last call, for logging as deeds.
+    pub fn drain_events(&mut self) -> Vec<SloEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Current uplift of `class`: the largest among its breached specs.
+    pub fn uplift(&self, class: &str) -> f32 {
+        self.cfg
+            .slos
+            .iter()
+            .zip(&self.states)
+            .filter(|(spec, _)| spec.class == class)
+            .map(|(_, state)| state.uplift)
+            .fold(0.0, f32::max)
+    }
+
+    /// `class`'s `max_share` in `kernel` plus its uplift, which stops short
+    /// of the other classes' `min_share` floors. None for an unknown class.
+    pub fn max_share_for(&self, class: &str, kernel: &GraceEquityKernel) -> Option<f32> {
+        let bounds = kernel.bounds_for_class(class)?;
+        let floors: f32 = kernel.classes.iter().filter(|(name, _)| *name != class).map(|(_, b)| b.min_share).sum();
+        let room = (1.0 - floors - bounds.max_share).max(0.0);
+        Some(bounds.max_share + self.uplift(class).min(room))
+    }
+
+    /// Every spec's standing now, computed from the outcomes in its window.
+    pub fn slo_status(&self) -> Vec<SloStatus> {
+        let now = self.now();
+        (0..self.cfg.slos.len()).map(|index| self.measure(index, now)).collect()
+    }
+
+    /// Alerts the alerter failed to deliver.
+    pub fn alerts_failed(&self) -> u64 {
+        self.alerts_failed
+    }
+
+    /// Prometheus text-format gauges for every spec.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let statuses = self.slo_status();
+        out.push_str("# TYPE ecofairness_slo_compliant gauge\n");
+        for s in &statuses {
+            let _ = writeln!(
+                out,
+                "ecofairness_slo_compliant{{slo=\"{}\",route=\"{}\",class=\"{}\"}} {}",
+                s.id,
+                s.route,
+                s.class,
+                u8::from(s.compliant())
+            );
+        }
+        out.push_str("# TYPE ecofairness_slo_latency_ms gauge\n");
+        for s in &statuses {
+            for l in &s.latency {
+                if let Some(measured) = l.measured_ms {
+                    let _ = writeln!(
+                        out,
+                        "ecofairness_slo_latency_ms{{slo=\"{}\",percentile=\"{}\"}} {}",
+                        s.id, l.percentile, measured
+                    );
+                }
+            }
+        }
+        out.push_str("# TYPE ecofairness_slo_rejection_rate gauge\n");
+        for s in &statuses {
+            for r in &s.rejection_rates {
+                let _ = writeln!(
+                    out,
+                    "ecofairness_slo_rejection_rate{{slo=\"{}\",category=\"{}\"}} {}",
+                    s.id, r.category, r.measured
+                );
+            }
+        }
+        out.push_str("# TYPE ecofairness_slo_availability gauge\n");
+        for s in &statuses {
+            if let Some(availability) = s.availability {
+                let _ = writeln!(out, "ecofairness_slo_availability{{slo=\"{}\"}} {}", s.id, availability);
+            }
+        }
+        out.push_str("# TYPE ecofairness_slo_priority_uplift gauge\n");
+        let classes: BTreeSet<&str> = statuses.iter().map(|s| s.class.as_str()).collect();
+        for class in classes {
+            let _ = writeln!(out, "ecofairness_slo_priority_uplift{{class=\"{}\"}} {}", class, self.uplift(class));
+        }
+        out.push_str("# TYPE ecofairness_slo_alerts_failed_total counter\n");
+        let _ = writeln!(out, "ecofairness_slo_alerts_failed_total {}", self.alerts_failed);
+        out
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted`.
+fn nearest_rank(sorted: &[f64], percentile: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    // The epsilon keeps 0.95 * 20 at rank 19 despite rounding.
+    let rank = (percentile * sorted.len() as f64 - 1e-9).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn percentile_label(percentile: f64) -> String {
+    format!("p{}", (percentile * 1000.0).round() / 10.0)
+}
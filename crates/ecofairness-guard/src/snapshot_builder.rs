@@ -0,0 +1,269 @@
+//! Incrementally maintained resource usage snapshot.
+//!
+//! Instead of re-aggregating every class share and route on each check,
+//! the gate feeds usage events into a `SnapshotBuilder`. Committing or
+//! releasing an action touches one class entry and a few totals; an
+//! external power reading replaces the metered baseline draw. Checks read
+//! `current()`, a frozen `Arc` view: updates go through `Arc::make_mut`,
+//! so a view held by a check (or sent to a subscriber) is never changed
+//! underneath it, and the next update copies the snapshot once instead.
+//!
+//! The baseline draw comes from outside the guard. When no reading has
+//! arrived for `max_reading_age_secs`, `current()` marks the snapshot
+//! `degraded` and the guard checks it against reduced limits (see
+//! `DEGRADED_HEADROOM`).
+//!
+//! Every change to the view bumps a version, so a result computed from
+//! one view (such as a `BudgetAdvisory`) can tell that usage moved on.
+
+use eco_units::{ComputeFraction, Joules, Watts};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::class_assignment::system_now;
+use crate::{ResourceUsageSnapshot, XRAction};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    #[error("action '{0}' is already committed")]
+    AlreadyCommitted(String),
+    #[error("action '{0}' is not committed")]
+    NotCommitted(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotBuilderConfig {
+    pub total_power_budget: Watts,
+    /// Same abstract units as `XRAction::lifeforcecost`.
+    pub total_compute_capacity: f32,
+    /// A snapshot whose last external power reading is older than this is degraded.
+    pub max_reading_age_secs: u64,
+}
+
+impl Default for SnapshotBuilderConfig {
+    fn default() -> Self {
+        Self { total_power_budget: Watts::new(1000.0), total_compute_capacity: 100.0, max_reading_age_secs: 60 }
+    }
+}
+
+/// One change to resource usage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsageEvent {
+    /// An admitted action started drawing resources on behalf of `class`.
+    Committed { action_id: String, class: String, power: Watts, energy: Joules, compute: f64 },
+    /// A committed action finished. Its power and compute are returned;
+    /// the energy it used stays in the window.
+    Released { action_id: String },
+    /// Metered draw of everything outside the guard's committed actions.
+    PowerReading { baseline_draw: Watts },
+    /// Start a new cumulative-energy window.
+    WindowReset,
+}
+
+impl UsageEvent {
+    /// Commit `action` for `class`, reading its cost the way the guard does.
+    pub fn committed(action_id: &str, class: &str, action: &XRAction, total_compute_capacity: f32) -> Self {
+        Self::Committed {
+            action_id: action_id.to_string(),
+            class: class.to_string(),
+            power: action.power_demand(),
+            energy: action.energy_demand(),
+            compute: action.compute_demand(total_compute_capacity),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Commit {
+    class: String,
+    power: Watts,
+    compute: f64,
+}
+
+/// Committed power and action count of one class.
+#[derive(Debug, Clone, Copy, Default)]
+struct ClassLoad {
+    power: Watts,
+    actions: usize,
+}
+
+#[derive(Debug)]
+struct State {
+    snapshot: Arc<ResourceUsageSnapshot>,
+    active: HashMap<String, Commit>,
+    classes: HashMap<String, ClassLoad>,
+    committed_power: Watts,
+    committed_compute: f64,
+    baseline_draw: Watts,
+    last_reading: u64,
+    version: u64,
+    subscribers: Vec<Sender<Arc<ResourceUsageSnapshot>>>,
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+/// Current `ResourceUsageSnapshot`, kept up to date from usage events.
+/// Share it between threads behind an `Arc`; every method takes `&self`.
+pub struct SnapshotBuilder {
+    cfg: SnapshotBuilderConfig,
+    state: Mutex<State>,
+    clock: Clock,
+}
+
+impl std::fmt::Debug for SnapshotBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotBuilder").field("cfg", &self.cfg).finish()
+    }
+}
+
+impl SnapshotBuilder {
+    /// An idle snapshot. The staleness clock starts now, as if a zero
+    /// baseline reading had just arrived.
+    pub fn new(cfg: SnapshotBuilderConfig) -> Self {
+        Self::with_clock(cfg, system_now)
+    }
+
+    /// Like `new` with a replacement wall clock (Unix seconds); tests and replays.
+    pub fn with_clock(cfg: SnapshotBuilderConfig, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        let snapshot = ResourceUsageSnapshot {
+            total_power_budget: cfg.total_power_budget,
+            total_compute_capacity: cfg.total_compute_capacity,
+            current_power_draw: Watts::ZERO,
+            current_cumulative_energy: Joules::ZERO,
+            current_compute_fraction: ComputeFraction::ZERO,
+            class_shares: HashMap::new(),
+            degraded: false,
+        };
+        let state = State {
+            snapshot: Arc::new(snapshot),
+            active: HashMap::new(),
+            classes: HashMap::new(),
+            committed_power: Watts::ZERO,
+            committed_compute: 0.0,
+            baseline_draw: Watts::ZERO,
+            last_reading: clock(),
+            version: 0,
+            subscribers: Vec::new(),
+        };
+        Self { cfg, state: Mutex::new(state), clock: Box::new(clock) }
+    }
+
+    pub fn config(&self) -> &SnapshotBuilderConfig {
+        &self.cfg
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fold one event into the snapshot in constant time (plus one copy
+    /// if a previous view is still held).
+    pub fn apply(&self, event: UsageEvent) -> Result<(), SnapshotError> {
+        let now = (self.clock)();
+        let budget = self.cfg.total_power_budget.max(Watts::new(1.0));
+        let mut state = self.lock();
+        let state = &mut *state;
+        match event {
+            UsageEvent::Committed { action_id, class, power, energy, compute } => {
+                if state.active.contains_key(&action_id) {
+                    return Err(SnapshotError::AlreadyCommitted(action_id));
+                }
+                let load = state.classes.entry(class.clone()).or_default();
+                load.power += power;
+                load.actions += 1;
+                let share = load.power.ratio(budget) as f32;
+                state.committed_power += power;
+                state.committed_compute += compute;
+                let snapshot = Arc::make_mut(&mut state.snapshot);
+                snapshot.class_shares.insert(class.clone(), share);
+                snapshot.current_cumulative_energy += energy;
+                state.active.insert(action_id, Commit { class, power, compute });
+            }
+            UsageEvent::Released { action_id } => {
+                let commit = state.active.remove(&action_id).ok_or(SnapshotError::NotCommitted(action_id))?;
+                state.committed_power = state.committed_power.saturating_sub(commit.power);
+                state.committed_compute = (state.committed_compute - commit.compute).max(0.0);
+                let snapshot = Arc::make_mut(&mut state.snapshot);
+                let load = state.classes.get_mut(&commit.class).expect("committed class is tracked");
+                load.actions -= 1;
+                // Dropping idle classes keeps rounding drift from accumulating.
+                if load.actions == 0 {
+                    state.classes.remove(&commit.class);
+                    snapshot.class_shares.remove(&commit.class);
+                } else {
+                    load.power = load.power.saturating_sub(commit.power);
+                    snapshot.class_shares.insert(commit.class, load.power.ratio(budget) as f32);
+                }
+                if state.active.is_empty() {
+                    state.committed_power = Watts::ZERO;
+                    state.committed_compute = 0.0;
+                }
+            }
+            UsageEvent::PowerReading { baseline_draw } => {
+                state.baseline_draw = baseline_draw;
+                state.last_reading = now;
+            }
+            UsageEvent::WindowReset => {
+                Arc::make_mut(&mut state.snapshot).current_cumulative_energy = Joules::ZERO;
+            }
+        }
+        let snapshot = Arc::make_mut(&mut state.snapshot);
+        snapshot.current_power_draw = state.baseline_draw + state.committed_power;
+        snapshot.current_compute_fraction = ComputeFraction::saturating(state.committed_compute);
+        snapshot.degraded = self.is_stale(state.last_reading, now);
+        state.version += 1;
+        if !state.subscribers.is_empty() {
+            let view = state.snapshot.clone();
+            state.subscribers.retain(|tx| tx.send(view.clone()).is_ok());
+        }
+        Ok(())
+    }
+
+    /// Commit `action` for `class` under `action_id`.
+    pub fn commit(&self, action_id: &str, class: &str, action: &XRAction) -> Result<(), SnapshotError> {
+        self.apply(UsageEvent::committed(action_id, class, action, self.cfg.total_compute_capacity))
+    }
+
+    pub fn release(&self, action_id: &str) -> Result<(), SnapshotError> {
+        self.apply(UsageEvent::Released { action_id: action_id.to_string() })
+    }
+
+    pub fn record_power(&self, baseline_draw: Watts) {
+        self.apply(UsageEvent::PowerReading { baseline_draw }).expect("power readings always apply")
+    }
+
+    fn is_stale(&self, last_reading: u64, now: u64) -> bool {
+        now.saturating_sub(last_reading) > self.cfg.max_reading_age_secs
+    }
+
+    /// A consistent view of current usage, degraded if the baseline reading is stale.
+    pub fn current(&self) -> Arc<ResourceUsageSnapshot> {
+        self.current_versioned().1
+    }
+
+    /// `current()` together with its version.
+    pub fn current_versioned(&self) -> (u64, Arc<ResourceUsageSnapshot>) {
+        let now = (self.clock)();
+        let mut state = self.lock();
+        let degraded = self.is_stale(state.last_reading, now);
+        if state.snapshot.degraded != degraded {
+            Arc::make_mut(&mut state.snapshot).degraded = degraded;
+            state.version += 1;
+        }
+        (state.version, state.snapshot.clone())
+    }
+
+    /// Version of the view `current()` would return now.
+    pub fn version(&self) -> u64 {
+        self.current_versioned().0
+    }
+
+    /// Receive the new view after every applied event. Dropped receivers
+    /// are pruned on the next update.
+    pub fn subscribe(&self) -> Receiver<Arc<ResourceUsageSnapshot>> {
+        let (tx, rx) = channel();
+        self.lock().subscribers.push(tx);
+        rx
+    }
+}
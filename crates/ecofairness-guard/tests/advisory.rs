@@ -0,0 +1,188 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    BudgetAxis, DecisionCacheConfig, EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GateDecisionCache,
+    GraceEquityKernel, ResourceUsageSnapshot, RohModel, SnapshotBuilder, SnapshotBuilderConfig, TsafeEcoEnvelope,
+    Verdict, XRAction, XRActionKind,
+};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const T0: u64 = 1_700_000_000;
+const ROUTE: &str = "XR";
+
+fn guard() -> EcoFairnessGuard {
+    let mut classes = HashMap::new();
+    classes.insert("host".to_string(), EquityBounds { min_share: 0.0, max_share: 0.5, description: None });
+    classes.insert("guest".to_string(), EquityBounds { min_share: 0.0, max_share: 0.2, description: None });
+    let mut envelopes = HashMap::new();
+    envelopes.insert(
+        ROUTE.to_string(),
+        TsafeEcoEnvelope {
+            route: ROUTE.into(),
+            max_power: Watts::new(500.0),
+            max_cumulative_energy: Joules::new(5_000.0),
+            max_compute_fraction: ComputeFraction::new(0.8).unwrap(),
+            ext: Default::default(),
+        },
+    );
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: envelopes,
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+fn snapshot(draw: f64) -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        current_power_draw: Watts::new(draw),
+        current_cumulative_energy: Joules::new(1000.0),
+        current_compute_fraction: ComputeFraction::new(0.1).unwrap(),
+        class_shares: HashMap::from([("host".to_string(), 0.1)]),
+        degraded: false,
+    }
+}
+
+fn action(class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ScheduleJob,
+        subjectid: format!("{class}-subject"),
+        route: ROUTE.into(),
+        lifeforcecost: cost,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some(class.into()),
+    }
+}
+
+fn builder(now: Arc<AtomicU64>) -> SnapshotBuilder {
+    SnapshotBuilder::with_clock(
+        SnapshotBuilderConfig {
+            total_power_budget: Watts::new(1000.0),
+            total_compute_capacity: 1000.0,
+            max_reading_age_secs: 30,
+        },
+        move || now.load(Ordering::SeqCst),
+    )
+}
+
+#[test]
+fn advising_changes_no_state() {
+    let now = Arc::new(AtomicU64::new(T0));
+    let usage = builder(now.clone());
+    usage.commit("a1", "host", &action("host", 50.0)).unwrap();
+    let cache =
+        GateDecisionCache::with_clock(guard(), DecisionCacheConfig::default(), move || now.load(Ordering::SeqCst));
+    let mut read = action("host", 5.0);
+    read.kind = XRActionKind::ReadKeys;
+    cache.check(&read, &usage.current()).unwrap();
+
+    let (metrics, len, version) = (cache.metrics(), cache.len(), usage.version());
+    let before = serde_json::to_value(&*usage.current()).unwrap();
+    let batch = vec![read.clone(), action("host", 300.0), action("guest", 250.0)];
+    for _ in 0..3 {
+        cache.advise(&batch, &usage.current());
+        cache.advise_current(&batch, &usage);
+    }
+    assert_eq!(cache.metrics(), metrics);
+    assert_eq!((cache.len(), usage.version()), (len, version));
+    assert_eq!(serde_json::to_value(&*usage.current()).unwrap(), before);
+}
+
+#[test]
+fn verdicts_agree_with_later_checks_on_the_same_snapshot() {
+    let guard = guard();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(450);
+    let mut marginal = 0;
+    for _ in 0..2_000 {
+        let mut snap = snapshot(rng.gen_range(0.0..500.0));
+        snap.degraded = rng.gen_bool(0.2);
+        snap.class_shares.insert("guest".into(), rng.gen_range(0.0..0.2));
+        let batch: Vec<XRAction> = (0..rng.gen_range(1..6))
+            .map(|_| {
+                let mut a = action(["host", "guest", "visitor"][rng.gen_range(0..3)], rng.gen_range(0.0..200.0));
+                a.rohafterestimate = a.rohbefore - rng.gen_range(-0.05..0.1);
+                a
+            })
+            .collect();
+        let advisory = guard.advise(&batch, &snap);
+        for (a, verdict) in batch.iter().zip(&advisory.verdicts) {
+            match (verdict, guard.check(a, &snap)) {
+                (Verdict::WouldFail { code }, Err(e)) => assert_eq!(*code, e.code),
+                (Verdict::WouldPass, Ok(())) => {}
+                (Verdict::Marginal { load }, Ok(())) => {
+                    assert!((0.9..=1.0).contains(&load.utilization), "{load:?}");
+                    marginal += 1;
+                }
+                (verdict, result) => panic!("{verdict:?} but check returned {result:?} for {a:?}"),
+            }
+        }
+    }
+    assert!(marginal > 0);
+}
+
+#[test]
+fn suggested_order_admits_more_and_holds_when_committed() {
+    let guard = guard();
+    let now = Arc::new(AtomicU64::new(T0));
+    let usage = builder(now);
+    usage.record_power(Watts::new(100.0));
+    let batch = vec![
+        action("host", 350.0),
+        action("host", 60.0),
+        action("guest", 90.0),
+        action("guest", 120.0),
+        action("host", 40.0),
+    ];
+
+    let advisory = guard.advise_current(&batch, &usage);
+    assert_eq!(advisory.admissible_as_given, 2, "the 350 W job leaves room for the 40 W one only");
+    assert_eq!(advisory.admissible_in_suggested_order, 3, "the 120 W guest job would push guests past max_share");
+    assert_eq!(advisory.suggested_order, vec![4, 1, 2, 3, 0]);
+    let limiting = advisory.limiting.clone().unwrap();
+    assert_eq!((limiting.scope.as_str(), limiting.axis), (ROUTE, BudgetAxis::Power));
+    assert!((limiting.utilization - (100.0 + 660.0) / 500.0).abs() < 1e-9);
+    let guest = advisory.consumption.iter().find(|l| l.axis == BudgetAxis::ClassShare && l.scope == "guest").unwrap();
+    assert!((guest.utilization - 0.21 / 0.2).abs() < 1e-6);
+
+    let mut admitted = 0;
+    for &i in &advisory.suggested_order {
+        if guard.check_current(&batch[i], &usage).is_ok() {
+            let class = batch[i].equity_class.clone().unwrap();
+            usage.commit(&format!("job{i}"), &class, &batch[i]).unwrap();
+            admitted += 1;
+        }
+    }
+    assert_eq!(admitted, advisory.admissible_in_suggested_order);
+}
+
+#[test]
+fn advisories_go_stale_when_usage_changes() {
+    let guard = guard();
+    let now = Arc::new(AtomicU64::new(T0));
+    let usage = builder(now.clone());
+    let batch = vec![action("host", 10.0)];
+
+    let advisory = guard.advise_current(&batch, &usage);
+    assert!(!advisory.is_stale(&usage));
+    assert!(guard.advise(&batch, &usage.current()).is_stale(&usage), "no version, no freshness claim");
+
+    usage.commit("a1", "host", &batch[0]).unwrap();
+    assert!(advisory.is_stale(&usage));
+
+    let advisory = guard.advise_current(&batch, &usage);
+    now.fetch_add(31, Ordering::SeqCst);
+    assert!(advisory.is_stale(&usage), "the reading went stale and the view degraded");
+    let degraded = guard.advise_current(&batch, &usage);
+    assert!(!degraded.is_stale(&usage));
+    assert!(degraded.snapshot_version > advisory.snapshot_version);
+}
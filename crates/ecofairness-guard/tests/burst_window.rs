@@ -0,0 +1,266 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    BurstConfig, BurstError, BurstGrant, BurstRevocation, BurstWindows, EcoFairnessConfig, EcoFairnessGuard,
+    EquityBounds, GraceEquityKernel, HeadroomLedger, HeadroomLedgerConfig, ResourceUsageSnapshot, RohModel,
+    SharedBurstWindows, TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use keyring::Keyring;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const ROUTE: &str = "AUTO_CHURCH_LIVE";
+const OTHER: &str = "XR";
+const T0: u64 = 1_700_000_000;
+const HOUR: u64 = 3_600;
+
+fn guard(windows: SharedBurstWindows) -> EcoFairnessGuard {
+    let mut classes = HashMap::new();
+    classes
+        .insert("local_congregation".to_string(), EquityBounds { min_share: 0.0, max_share: 0.9, description: None });
+    classes
+        .insert("remote_congregation".to_string(), EquityBounds { min_share: 0.0, max_share: 0.1, description: None });
+    let envelope = |route: &str| TsafeEcoEnvelope {
+        route: route.into(),
+        max_power: Watts::new(500.0),
+        max_cumulative_energy: Joules::new(1.0e6),
+        max_compute_fraction: ComputeFraction::ONE,
+        ext: Default::default(),
+    };
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: HashMap::from([(ROUTE.to_string(), envelope(ROUTE)), (OTHER.to_string(), envelope(OTHER))]),
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+    .with_burst_windows(windows)
+}
+
+/// 400 W drawn of the 500 W route limit.
+fn snapshot() -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(2000.0),
+        total_compute_capacity: 10_000.0,
+        current_power_draw: Watts::new(400.0),
+        current_cumulative_energy: Joules::ZERO,
+        current_compute_fraction: ComputeFraction::ZERO,
+        class_shares: HashMap::new(),
+        degraded: false,
+    }
+}
+
+fn action(route: &str, class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::XRRouteStep,
+        subjectid: "s1".into(),
+        route: route.into(),
+        lifeforcecost: cost,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some(class.into()),
+    }
+}
+
+struct Fixture {
+    clock: Arc<AtomicU64>,
+    approvers: Keyring,
+    keys: Vec<String>,
+    windows: SharedBurstWindows,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let clock = Arc::new(AtomicU64::new(T0));
+        let c = clock.clone();
+        let mut approvers = Keyring::new().with_clock(move || c.load(Ordering::SeqCst));
+        let keys = (0..3).map(|_| approvers.generate("burst-approver").unwrap()).collect();
+        let c = clock.clone();
+        let windows = BurstWindows::new(BurstConfig::default(), approvers.verifying_bundle())
+            .with_clock(move || c.load(Ordering::SeqCst))
+            .into_shared();
+        Self { clock, approvers, keys, windows }
+    }
+
+    fn grant(&self, id: &str, routes: &[(&str, f64)], starts_at: u64, ends_at: u64, signers: &[usize]) -> BurstGrant {
+        let mut grant = BurstGrant {
+            id: id.into(),
+            multipliers: routes.iter().map(|(r, m)| (r.to_string(), *m)).collect(),
+            starts_at,
+            ends_at,
+            justification: "Easter vigil".into(),
+            signatures: Vec::new(),
+        };
+        let body = grant.signing_body();
+        grant.signatures = signers.iter().map(|&i| self.approvers.sign(&self.keys[i], &body).unwrap()).collect();
+        grant
+    }
+
+    fn revocation(&self, id: &str, signers: &[usize]) -> BurstRevocation {
+        let body = BurstRevocation::signing_body(id);
+        BurstRevocation {
+            grant_id: id.into(),
+            reason: "storm warning".into(),
+            signatures: signers.iter().map(|&i| self.approvers.sign(&self.keys[i], &body).unwrap()).collect(),
+        }
+    }
+
+    fn at(&self, t: u64) {
+        self.clock.store(t, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn multiplier_applies_only_inside_the_window() {
+    let f = Fixture::new();
+    let g = guard(f.windows.clone());
+    let grant = f.grant("vigil", &[(ROUTE, 1.5)], T0 + HOUR, T0 + 3 * HOUR, &[0, 1]);
+    f.windows.write().unwrap().grant(grant).unwrap();
+    let big = action(ROUTE, "local_congregation", 200.0);
+
+    assert_eq!(g.check(&big, &snapshot()).unwrap_err().code, "ECO_POWER_EXCEEDED", "before the window");
+    f.at(T0 + HOUR);
+    g.check(&big, &snapshot()).unwrap();
+    assert_eq!(
+        g.check(&action(ROUTE, "local_congregation", 400.0), &snapshot()).unwrap_err().code,
+        "ECO_POWER_EXCEEDED"
+    );
+    assert_eq!(
+        g.check(&action(OTHER, "local_congregation", 200.0), &snapshot()).unwrap_err().code,
+        "ECO_POWER_EXCEEDED",
+        "routes outside the grant keep their envelope"
+    );
+    let mut degraded = snapshot();
+    degraded.degraded = true;
+    degraded.current_power_draw = Watts::new(200.0);
+    g.check(&action(ROUTE, "local_congregation", 150.0), &degraded).unwrap();
+    assert!(g.check(&action(ROUTE, "local_congregation", 180.0), &degraded).is_err(), "0.5 x 1.5 x 500 W = 375 W");
+
+    f.at(T0 + 3 * HOUR);
+    assert_eq!(g.check(&big, &snapshot()).unwrap_err().code, "ECO_POWER_EXCEEDED", "reverted at ends_at");
+}
+
+#[test]
+fn debrief_accounts_burst_consumption_separately() {
+    let f = Fixture::new();
+    let g = guard(f.windows.clone());
+    f.windows.write().unwrap().grant(f.grant("vigil", &[(ROUTE, 2.0)], T0, T0 + HOUR, &[0, 2])).unwrap();
+    let mut ledger = HeadroomLedger::new(HeadroomLedgerConfig::default());
+    let now = T0 + 60;
+    f.at(now);
+    let snap = snapshot();
+
+    g.check_and_record(&action(ROUTE, "local_congregation", 50.0), &snap, &mut ledger, now).unwrap();
+    g.check_and_record(&action(ROUTE, "local_congregation", 300.0), &snap, &mut ledger, now).unwrap();
+    g.check_and_record(&action(ROUTE, "local_congregation", 500.0), &snap, &mut ledger, now).unwrap();
+    let err = g.check_and_record(&action(ROUTE, "remote_congregation", 300.0), &snap, &mut ledger, now).unwrap_err();
+    assert_eq!(err.code, "ECO_EQUITY_MAX_EXCEEDED", "equity bounds still apply under a burst");
+    g.check_and_record(&action(OTHER, "local_congregation", 50.0), &snap, &mut ledger, now).unwrap();
+    assert_eq!(ledger.records().count(), 4);
+
+    let mut windows = f.windows.write().unwrap();
+    assert!(windows.drain_debriefs().is_empty(), "window still open");
+    f.at(T0 + HOUR);
+    let debriefs = windows.drain_debriefs();
+    assert_eq!(debriefs.len(), 1);
+    let d = &debriefs[0];
+    assert_eq!((d.closed_at, d.revoked.as_deref()), (T0 + HOUR, None));
+    assert_eq!(d.approvers, vec![f.keys[0].clone(), f.keys[2].clone()]);
+    let usage = &d.routes[ROUTE];
+    assert_eq!((usage.admitted, usage.admitted_beyond_base), (3, 2));
+    assert_eq!((usage.admitted_cost, usage.burst_cost), (850.0, 800.0));
+    assert!((usage.peak_power_utilization - 900.0 / 500.0).abs() < 1e-9);
+    let local = &d.classes["local_congregation"];
+    assert_eq!((local.admitted, local.denied), (3, 0));
+    assert_eq!((d.classes["remote_congregation"].admitted, d.classes["remote_congregation"].denied), (0, 1));
+    assert_eq!(d.to_deed_context()["deed_type"], "burst_debrief");
+    assert!(windows.drain_debriefs().is_empty(), "each window is debriefed once");
+}
+
+#[test]
+fn overlapping_and_malformed_grants_are_rejected() {
+    let f = Fixture::new();
+    let mut windows = f.windows.write().unwrap();
+    windows.grant(f.grant("a", &[(ROUTE, 1.5)], T0, T0 + 2 * HOUR, &[0, 1])).unwrap();
+
+    let err = windows.grant(f.grant("b", &[(OTHER, 1.2), (ROUTE, 1.2)], T0 + HOUR, T0 + 3 * HOUR, &[0, 1]));
+    assert!(matches!(err, Err(BurstError::Overlap { existing, route, .. }) if existing == "a" && route == ROUTE));
+    windows.grant(f.grant("c", &[(OTHER, 1.2)], T0 + HOUR, T0 + 3 * HOUR, &[0, 1])).unwrap();
+    windows.grant(f.grant("d", &[(ROUTE, 1.2)], T0 + 2 * HOUR, T0 + 3 * HOUR, &[1, 2])).unwrap();
+    assert!(matches!(
+        windows.grant(f.grant("d", &[(ROUTE, 1.2)], T0 + 9 * HOUR, T0 + 10 * HOUR, &[0, 1])),
+        Err(BurstError::Duplicate(_))
+    ));
+
+    let late = T0 + 5 * HOUR;
+    assert!(matches!(
+        windows.grant(f.grant("e", &[(ROUTE, 2.5)], late, late + HOUR, &[0, 1])),
+        Err(BurstError::Multiplier { multiplier, max, .. }) if multiplier == 2.5 && max == 2.0
+    ));
+    assert!(matches!(
+        windows.grant(f.grant("e", &[(ROUTE, 0.5)], late, late + HOUR, &[0, 1])),
+        Err(BurstError::Multiplier { .. })
+    ));
+    assert!(matches!(
+        windows.grant(f.grant("e", &[(ROUTE, 1.5)], late, late, &[0, 1])),
+        Err(BurstError::EmptyWindow(_))
+    ));
+    assert!(matches!(
+        windows.grant(f.grant("e", &[(ROUTE, 1.5)], late, late + HOUR, &[0, 0])),
+        Err(BurstError::Quorum { have: 1, need: 2 })
+    ));
+    let mut tampered = f.grant("e", &[(ROUTE, 1.2)], late, late + HOUR, &[0, 1]);
+    tampered.multipliers.insert(ROUTE.into(), 2.0);
+    assert!(matches!(windows.grant(tampered), Err(BurstError::Signature(_))));
+    windows.grant(f.grant("e", &[(ROUTE, 2.0)], late, late + HOUR, &[0, 1])).unwrap();
+}
+
+#[test]
+fn roh_ceiling_is_never_widened() {
+    let f = Fixture::new();
+    let g = guard(f.windows.clone());
+    f.windows.write().unwrap().grant(f.grant("vigil", &[(ROUTE, 2.0)], T0, T0 + HOUR, &[0, 1])).unwrap();
+    let mut risky = action(ROUTE, "local_congregation", 10.0);
+    risky.rohbefore = 0.35;
+    risky.rohafterestimate = 0.31;
+    assert_eq!(g.check(&risky, &snapshot()).unwrap_err().code, "ROH_CEILING");
+    risky.rohafterestimate = 0.3;
+    g.check(&risky, &snapshot()).unwrap();
+    let mut rising = action(ROUTE, "local_congregation", 10.0);
+    rising.rohafterestimate = 0.25;
+    assert_eq!(g.check(&rising, &snapshot()).unwrap_err().code, "ROH_MONOTONE");
+}
+
+#[test]
+fn revocation_takes_effect_on_the_next_check() {
+    let f = Fixture::new();
+    let g = guard(f.windows.clone());
+    f.windows.write().unwrap().grant(f.grant("vigil", &[(ROUTE, 2.0)], T0, T0 + 4 * HOUR, &[0, 1])).unwrap();
+    let big = action(ROUTE, "local_congregation", 200.0);
+    f.at(T0 + HOUR);
+    g.check(&big, &snapshot()).unwrap();
+
+    let mut windows = f.windows.write().unwrap();
+    assert!(matches!(windows.revoke(f.revocation("vigil", &[2])), Err(BurstError::Quorum { have: 1, need: 2 })));
+    assert!(matches!(windows.revoke(f.revocation("nope", &[0, 1])), Err(BurstError::UnknownGrant(_))));
+    windows.revoke(f.revocation("vigil", &[1, 2])).unwrap();
+    drop(windows);
+    assert_eq!(g.check(&big, &snapshot()).unwrap_err().code, "ECO_POWER_EXCEEDED");
+
+    let debriefs = f.windows.write().unwrap().drain_debriefs();
+    assert_eq!(debriefs.len(), 1);
+    assert_eq!((debriefs[0].closed_at, debriefs[0].ends_at), (T0 + HOUR, T0 + 4 * HOUR));
+    assert_eq!(debriefs[0].revoked.as_deref(), Some("storm warning"));
+
+    // The revoked window no longer blocks a replacement grant on the route.
+    let replacement = f.grant("vigil-2", &[(ROUTE, 1.2)], T0 + 2 * HOUR, T0 + 3 * HOUR, &[0, 2]);
+    f.windows.write().unwrap().grant(replacement).unwrap();
+    let mut expected = BTreeMap::new();
+    expected.insert(ROUTE.to_string(), 1.2);
+    f.at(T0 + 2 * HOUR);
+    assert_eq!(f.windows.read().unwrap().active_grant(ROUTE, T0 + 2 * HOUR).unwrap().multipliers, expected);
+}
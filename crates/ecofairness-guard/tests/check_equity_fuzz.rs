@@ -1,12 +1,13 @@
-use ecofairness_guard::{check_equity_bounds, GraceEquityKernel, ResourceUsageSnapshot};
-use rand::Rng;
-use std::path::PathBuf;
+use ecofairness_guard::{AdmissionLimit, EquityKernelError, GraceEquityKernel};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::HashMap, path::PathBuf};
 
-/// Simple randomized harness: generate many snapshots + lifeforcecost,
-/// assert that whenever check_equity_bounds returns Ok, the projected
-/// share does not exceed max_share for that class.
+/// Simple randomized harness: generate many class shares + deltas, assert
+/// that whatever `admit` grants never takes the class past its max_share,
+/// the route envelope or node capacity, and that a max_share refusal only
+/// comes when the class is already at its bound.
 #[test]
-fn check_equity_bounds_never_allows_exceeding_max_share() {
+fn admit_never_allows_exceeding_max_share() {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("..");
     path.push("..");
@@ -16,71 +17,68 @@ fn check_equity_bounds_never_allows_exceeding_max_share() {
     let kernel = GraceEquityKernel::from_path(&path)
         .expect(".eco-fairness.aln must load and satisfy invariants");
 
-    let class_names: Vec<String> = kernel.classes.keys().cloned().collect();
+    let mut class_names: Vec<String> = kernel.classes.keys().cloned().collect();
+    let mut routes: Vec<String> = kernel.node_routes.keys().cloned().collect();
+    class_names.sort();
+    routes.sort();
     assert!(
         !class_names.is_empty(),
         "At least one EquityClass must be defined"
     );
 
-    let mut rng = rand::thread_rng();
+    let mut rng = StdRng::seed_from_u64(0x5eed);
 
     for _ in 0..10_000 {
-        let class = &class_names[rng.gen_range(0..class_names.len())];
-        let bounds = kernel.classes.get(class).unwrap();
-
-        let total_power_budget = rng.gen_range(1.0_f32..10_000.0);
-        let current_class_share = rng.gen_range(0.0_f32..bounds.max_share.min(0.99));
-        let current_power_draw = rng.gen_range(
-            (current_class_share * total_power_budget)..(total_power_budget * 0.99),
-        );
-        let class_power_draw = current_class_share * total_power_budget;
-
-        let snapshot = ResourceUsageSnapshot {
-            current_power_draw,
-            total_power_budget,
-            class_power_draw,
-        };
-
-        let remaining_fraction = (bounds.max_share - current_class_share).max(0.0);
-        let max_allowable_lifeforce = remaining_fraction * total_power_budget;
-        let lifeforcecost = if max_allowable_lifeforce <= 0.0 {
-            0.0
-        } else {
-            rng.gen_range(0.0_f32..=(max_allowable_lifeforce * 1.5))
-        };
-
-        let projected_class_power = class_power_draw + lifeforcecost;
-        let projected_share = projected_class_power / total_power_budget;
+        let mut current = HashMap::new();
+        for c in &class_names {
+            if rng.gen_bool(0.8) {
+                current.insert(c.clone(), rng.gen_range(0.0_f32..=kernel.classes[c].max_share));
+            }
+        }
+        let total: f32 = current.values().sum();
+        if total > 1.0 {
+            current.values_mut().for_each(|share| *share /= total);
+        }
 
-        let result = check_equity_bounds(&kernel, class, &snapshot, lifeforcecost);
+        let class = &class_names[rng.gen_range(0..class_names.len())];
+        let route = &routes[rng.gen_range(0..routes.len())];
+        let bounds = &kernel.classes[class];
+        let envelope = &kernel.node_routes[route];
+        let share = current.get(class).copied().unwrap_or(0.0);
+        let delta = rng.gen_range(1e-4_f32..=0.5);
 
-        match result {
-            Ok(()) => {
+        match kernel.admit(class, route, delta, &current) {
+            Ok(admission) => {
+                let context = format!("class '{class}', route '{route}', delta {delta}, current={current:?}");
+                assert!(admission.granted <= delta, "granted more than asked: {admission:?}, {context}");
                 assert!(
-                    projected_share <= bounds.max_share + 1e-5,
-                    "check_equity_bounds allowed projected_share {} above max_share {} \
-                     for class '{}', snapshot={:?}, lifeforcecost={}",
-                    projected_share,
+                    admission.post_share <= bounds.max_share + 1e-5,
+                    "admit allowed post_share {} above max_share {}, {context}",
+                    admission.post_share,
                     bounds.max_share,
-                    class,
-                    snapshot,
-                    lifeforcecost
+                );
+                assert!(
+                    admission.granted <= envelope.max_power_fraction.min(envelope.max_compute_fraction) + 1e-5,
+                    "admit granted {} past the route envelope, {context}",
+                    admission.granted,
+                );
+                assert!(
+                    current.values().sum::<f32>() + admission.granted <= 1.0 + 1e-5,
+                    "admit granted {} past node capacity, {context}",
+                    admission.granted,
                 );
             }
-            Err(e) => {
-                if let ecofairness_guard::EcoFairnessError::MaxShareExceeded { .. } = e {
-                    assert!(
-                        projected_share > bounds.max_share - 1e-5,
-                        "MaxShareExceeded error when projected_share {} ≤ max_share {} \
-                         for class '{}', snapshot={:?}, lifeforcecost={}",
-                        projected_share,
-                        bounds.max_share,
-                        class,
-                        snapshot,
-                        lifeforcecost
-                    );
-                }
+            Err(EquityKernelError::Refused { limit: AdmissionLimit::MaxShare, .. }) => {
+                assert!(
+                    share >= bounds.max_share - 1e-5,
+                    "max_share refusal with share {} < max_share {} for class '{}'",
+                    share,
+                    bounds.max_share,
+                    class
+                );
             }
+            Err(EquityKernelError::Refused { .. }) => {}
+            Err(e) => panic!("unexpected error for class '{class}' on route '{route}': {e}"),
         }
     }
 }
@@ -0,0 +1,226 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    ClassAssignment, ClassEventKind, ClassRegistry, ClassRegistryConfig, ClassRegistryError, EcoFairnessConfig,
+    EcoFairnessGuard, EquityBounds, GraceEquityKernel, ResourceUsageSnapshot, RohModel, SharedClassRegistry,
+    TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use keyring::Keyring;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const ROUTE: &str = "AUTO_CHURCH_LIVE";
+const HOUR: u64 = 3_600;
+
+fn kernel() -> GraceEquityKernel {
+    let mut classes = HashMap::new();
+    for (name, max_share) in [("local_congregation", 0.5), ("remote_congregation", 0.05)] {
+        classes.insert(name.to_string(), EquityBounds { min_share: 0.0, max_share, description: None });
+    }
+    GraceEquityKernel {
+        classes,
+        resource_kind: "power_budget".into(),
+        normalization: "fraction_of_total".into(),
+        node_routes: HashMap::new(),
+        ext: Default::default(),
+    }
+}
+
+fn guard(registry: SharedClassRegistry) -> EcoFairnessGuard {
+    let mut envelopes = HashMap::new();
+    envelopes.insert(
+        ROUTE.to_string(),
+        TsafeEcoEnvelope {
+            route: ROUTE.into(),
+            max_power: Watts::new(500.0),
+            max_cumulative_energy: Joules::new(1.0e6),
+            max_compute_fraction: ComputeFraction::ONE,
+            ext: Default::default(),
+        },
+    );
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: envelopes,
+        grace_equity: kernel(),
+    })
+    .with_class_registry(registry)
+}
+
+fn snapshot() -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        current_power_draw: Watts::ZERO,
+        current_cumulative_energy: Joules::ZERO,
+        current_compute_fraction: ComputeFraction::ZERO,
+        class_shares: HashMap::new(),
+        degraded: false,
+    }
+}
+
+/// 0.1 of the power budget: inside local_congregation's cap, over remote's.
+fn action(subject: &str, claim: Option<&str>) -> XRAction {
+    XRAction {
+        kind: XRActionKind::XRRouteStep,
+        subjectid: subject.into(),
+        route: ROUTE.into(),
+        lifeforcecost: 100.0,
+        rohbefore: 0.1,
+        rohafterestimate: 0.1,
+        equity_class: claim.map(Into::into),
+    }
+}
+
+struct Fixture {
+    clock: Arc<AtomicU64>,
+    authority: Keyring,
+    key: String,
+    registry: SharedClassRegistry,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let clock = Arc::new(AtomicU64::new(1_000));
+        let c = clock.clone();
+        let mut authority = Keyring::new().with_clock(move || c.load(Ordering::SeqCst));
+        let key = authority.generate("class-authority").unwrap();
+        let c = clock.clone();
+        let registry = ClassRegistry::new(ClassRegistryConfig::default(), authority.verifying_bundle())
+            .with_clock(move || c.load(Ordering::SeqCst))
+            .into_shared();
+        Self { clock, authority, key, registry }
+    }
+
+    fn assign(&self, subject: &str, class: &str, ttl: u64) -> Result<ClassAssignment, ClassRegistryError> {
+        self.registry.write().unwrap().assign(subject, class, &self.authority, &self.key, ttl).cloned()
+    }
+
+    fn advance(&self, secs: u64) {
+        self.clock.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn claimed_class_must_match_assignment() {
+    let f = Fixture::new();
+    f.assign("alice", "remote_congregation", HOUR).unwrap();
+    let g = guard(f.registry.clone());
+
+    let err = g.check(&action("alice", Some("local_congregation")), &snapshot()).unwrap_err();
+    assert_eq!(err.code, "ECO_CLASS_CLAIM_MISMATCH");
+
+    // Resolved from the registry: remote's cap applies with or without the hint.
+    for claim in [Some("remote_congregation"), None] {
+        let err = g.check(&action("alice", claim), &snapshot()).unwrap_err();
+        assert_eq!(err.code, "ECO_EQUITY_MAX_EXCEEDED");
+    }
+
+    f.assign("bob", "local_congregation", HOUR).unwrap();
+    g.check(&action("bob", Some("local_congregation")), &snapshot()).unwrap();
+    g.check(&action("bob", None), &snapshot()).unwrap();
+
+    let err = g.check(&action("mallory", Some("local_congregation")), &snapshot()).unwrap_err();
+    assert_eq!(err.code, "ECO_NO_VERIFIED_CLASS");
+}
+
+#[test]
+fn expired_assignment_falls_back_to_default_class() {
+    let f = Fixture::new();
+    f.assign("alice", "local_congregation", HOUR).unwrap();
+    let g = guard(f.registry.clone());
+    g.check(&action("alice", None), &snapshot()).unwrap();
+
+    f.advance(HOUR);
+    let now = f.clock.load(Ordering::SeqCst);
+    let verified = f.registry.read().unwrap().class_of("alice", now).unwrap();
+    assert!(verified.fallback);
+    assert_eq!(verified.class, "remote_congregation");
+    assert_eq!(verified.expires_at, None);
+
+    let err = g.check(&action("alice", None), &snapshot()).unwrap_err();
+    assert_eq!(err.code, "ECO_EQUITY_MAX_EXCEEDED");
+
+    // Renewing restores the assigned class.
+    f.assign("alice", "local_congregation", HOUR).unwrap();
+    g.check(&action("alice", Some("local_congregation")), &snapshot()).unwrap();
+}
+
+#[test]
+fn only_trusted_authority_signatures_are_accepted() {
+    let f = Fixture::new();
+
+    let mut rogue = Keyring::new().with_clock(|| 1_000);
+    let rogue_key = rogue.generate("class-authority").unwrap();
+    let err = f.registry.write().unwrap().assign("eve", "local_congregation", &rogue, &rogue_key, HOUR).unwrap_err();
+    assert!(matches!(err, ClassRegistryError::Signature(_)));
+
+    let mut tampered = f.assign("alice", "remote_congregation", HOUR).unwrap();
+    tampered.class = "local_congregation".into();
+    let err = f.registry.write().unwrap().insert(tampered).unwrap_err();
+    assert!(matches!(err, ClassRegistryError::Signature(_)));
+
+    let mut stretched = f.assign("bob", "remote_congregation", HOUR).unwrap();
+    stretched.ttl_secs = 365 * 24 * HOUR;
+    assert!(f.registry.write().unwrap().insert(stretched).is_err());
+
+    let now = f.clock.load(Ordering::SeqCst);
+    let registry = f.registry.read().unwrap();
+    assert_eq!(registry.class_of("alice", now).unwrap().class, "remote_congregation");
+    assert_eq!(registry.class_of("eve", now), None);
+}
+
+#[test]
+fn revocation_denies_subject_and_is_logged() {
+    let f = Fixture::new();
+    f.assign("alice", "local_congregation", HOUR).unwrap();
+    f.advance(60);
+    f.assign("alice", "local_congregation", HOUR).unwrap();
+    let g = guard(f.registry.clone());
+    g.check(&action("alice", Some("local_congregation")), &snapshot()).unwrap();
+
+    f.advance(60);
+    f.registry.write().unwrap().revoke("alice", &f.authority, &f.key, "left congregation").unwrap();
+    let err = g.check(&action("alice", Some("local_congregation")), &snapshot()).unwrap_err();
+    assert_eq!(err.code, "ECO_NO_VERIFIED_CLASS");
+
+    let events = f.registry.write().unwrap().drain_events();
+    let kinds: Vec<ClassEventKind> = events.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, [ClassEventKind::Assignment, ClassEventKind::Renewal, ClassEventKind::Revocation]);
+    let revoked = events[2].to_deed_context();
+    assert_eq!(revoked["deed_type"], "class_revocation");
+    assert_eq!(revoked["class_event"]["reason"], "left congregation");
+    assert!(f.registry.write().unwrap().drain_events().is_empty());
+}
+
+#[test]
+fn roster_import_is_all_or_nothing() {
+    let f = Fixture::new();
+    let dir = std::env::temp_dir().join(format!("class-roster-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let bad = dir.join("bad.json");
+    std::fs::write(
+        &bad,
+        r#"[{"subject":"a","class":"local_congregation","ttl_secs":60},
+            {"subject":"a","class":"local_congregation","ttl_secs":60},
+            {"subject":"b","class":"archons","ttl_secs":0}]"#,
+    )
+    .unwrap();
+    match f.registry.write().unwrap().import_roster(&bad, &kernel(), &f.authority, &f.key) {
+        Err(ClassRegistryError::Roster(problems)) => assert_eq!(problems.len(), 3),
+        other => panic!("expected roster error, got {:?}", other),
+    }
+    assert_eq!(f.registry.read().unwrap().assignments().count(), 0);
+
+    let good = dir.join("good.json");
+    std::fs::write(
+        &good,
+        r#"[{"subject":"a","class":"local_congregation","ttl_secs":60},
+            {"subject":"b","class":"remote_congregation","ttl_secs":60}]"#,
+    )
+    .unwrap();
+    let imported = f.registry.write().unwrap().import_roster(&good, &kernel(), &f.authority, &f.key).unwrap();
+    assert_eq!(imported, 2);
+    assert_eq!(f.registry.read().unwrap().class_of("b", 1_000).unwrap().class, "remote_congregation");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,242 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    DecisionCacheConfig, EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GateDecisionCache, GraceEquityKernel,
+    ResourceUsageSnapshot, RohModel, SnapshotBuilder, SnapshotBuilderConfig, TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const T0: u64 = 1_700_000_000;
+const ROUTE: &str = "XR";
+
+fn roh(ceiling: f32) -> RohModel {
+    RohModel { ceiling, weights: HashMap::new() }
+}
+
+fn guard(max_power: f64, roh_ceiling: f32) -> EcoFairnessGuard {
+    let mut classes = HashMap::new();
+    classes.insert("host".to_string(), EquityBounds { min_share: 0.0, max_share: 0.5, description: None });
+    classes.insert("guest".to_string(), EquityBounds { min_share: 0.0, max_share: 0.3, description: None });
+    let mut envelopes = HashMap::new();
+    envelopes.insert(
+        ROUTE.to_string(),
+        TsafeEcoEnvelope {
+            route: ROUTE.into(),
+            max_power: Watts::new(max_power),
+            max_cumulative_energy: Joules::new(20_000.0),
+            max_compute_fraction: ComputeFraction::new(0.8).unwrap(),
+            ext: Default::default(),
+        },
+    );
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: roh(roh_ceiling),
+        tsafe_envelopes: envelopes,
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+fn snapshot() -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        current_power_draw: Watts::new(100.0),
+        current_cumulative_energy: Joules::new(1000.0),
+        current_compute_fraction: ComputeFraction::new(0.1).unwrap(),
+        class_shares: HashMap::from([("host".to_string(), 0.1)]),
+        degraded: false,
+    }
+}
+
+fn read(subject: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ReadNeuralShard,
+        subjectid: subject.into(),
+        route: ROUTE.into(),
+        lifeforcecost: cost,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some("host".into()),
+    }
+}
+
+fn cache(cfg: DecisionCacheConfig) -> (GateDecisionCache, Arc<AtomicU64>) {
+    let now = Arc::new(AtomicU64::new(T0));
+    let clock = now.clone();
+    (GateDecisionCache::with_clock(guard(500.0, 0.3), cfg, move || clock.load(Ordering::SeqCst)), now)
+}
+
+#[test]
+fn allow_is_served_from_cache_until_ttl() {
+    let (cache, now) = cache(DecisionCacheConfig { ttl_secs: 2, ..DecisionCacheConfig::default() });
+    let snap = snapshot();
+    assert!(cache.check(&read("s1", 5.0), &snap).is_ok());
+    assert!(cache.check(&read("s1", 5.4), &snap).is_ok(), "same cost bucket");
+    assert!(cache.check(&read("s1", 7.0), &snap).is_ok(), "next bucket is its own entry");
+    let m = cache.metrics();
+    assert_eq!((m.hits, m.misses, m.entries), (1, 2, 2));
+
+    now.fetch_add(1, Ordering::SeqCst);
+    cache.check(&read("s1", 5.0), &snap).unwrap();
+    assert_eq!(cache.metrics().hits, 2);
+    now.fetch_add(1, Ordering::SeqCst);
+    cache.check(&read("s1", 5.0), &snap).unwrap();
+    let m = cache.metrics();
+    assert_eq!((m.hits, m.misses, m.expired), (2, 3, 1));
+}
+
+#[test]
+fn each_trigger_invalidates() {
+    let (cache, _) = cache(DecisionCacheConfig::default());
+    let snap = snapshot();
+    let warm = |cache: &GateDecisionCache| {
+        cache.check(&read("s1", 5.0), &snap).unwrap();
+        cache.check(&read("s2", 5.0), &snap).unwrap();
+        assert_eq!(cache.len(), 2);
+    };
+
+    warm(&cache);
+    cache.reload(guard(500.0, 0.3));
+    assert_eq!((cache.metrics().invalidated_spec_reload, cache.len()), (2, 0));
+
+    warm(&cache);
+    cache.update_roh_model(roh(0.25));
+    assert_eq!((cache.metrics().invalidated_roh, cache.len()), (2, 0));
+
+    warm(&cache);
+    cache.on_maintenance_window("DRONE");
+    assert_eq!(cache.len(), 2, "other routes keep their entries");
+    cache.on_maintenance_window(ROUTE);
+    assert_eq!((cache.metrics().invalidated_maintenance, cache.len()), (2, 0));
+
+    warm(&cache);
+    let mut within = snap.clone();
+    within.class_shares.insert("host".into(), 0.105);
+    within.current_power_draw = Watts::new(105.0);
+    cache.on_usage(&within);
+    assert_eq!(cache.len(), 2, "drift inside the tolerance keeps entries");
+    let mut beyond = snap.clone();
+    beyond.class_shares.insert("host".into(), 0.2);
+    cache.on_usage(&beyond);
+    assert_eq!((cache.metrics().invalidated_usage, cache.len()), (2, 0));
+
+    warm(&cache);
+    let mut degraded = snap.clone();
+    degraded.degraded = true;
+    cache.check(&read("s1", 5.0), &degraded).unwrap();
+    assert_eq!(cache.metrics().invalidated_usage, 3, "a degraded snapshot drops the entry on lookup");
+}
+
+#[test]
+fn denials_and_mutations_are_never_cached() {
+    let (cache, _) = cache(DecisionCacheConfig::default());
+    let snap = snapshot();
+    for _ in 0..3 {
+        assert_eq!(cache.check(&read("s1", 450.0), &snap).unwrap_err().code, "ECO_POWER_EXCEEDED");
+    }
+    let m = cache.metrics();
+    assert_eq!((m.hits, m.misses, m.entries), (0, 3, 0));
+
+    let mut roh_up = read("s1", 5.0);
+    roh_up.rohafterestimate = 0.25;
+    cache.check(&read("s1", 5.0), &snap).unwrap();
+    assert_eq!(cache.check(&roh_up, &snap).unwrap_err().code, "ROH_MONOTONE", "RoH is re-checked on hits");
+
+    for kind in [XRActionKind::WriteNeuralShard, XRActionKind::ApplyOta, XRActionKind::SignTransaction] {
+        let action = XRAction { kind, ..read("s1", 5.0) };
+        cache.check(&action, &snap).unwrap();
+        cache.check(&action, &snap).unwrap();
+    }
+    let m = cache.metrics();
+    assert_eq!((m.bypassed, m.entries), (6, 1));
+}
+
+#[test]
+fn capacity_evicts_least_recently_used() {
+    let (cache, _) = cache(DecisionCacheConfig { capacity: 2, ..DecisionCacheConfig::default() });
+    let snap = snapshot();
+    cache.check(&read("a", 1.0), &snap).unwrap();
+    cache.check(&read("b", 1.0), &snap).unwrap();
+    cache.check(&read("a", 1.0), &snap).unwrap();
+    cache.check(&read("c", 1.0), &snap).unwrap();
+    assert_eq!((cache.len(), cache.metrics().evictions), (2, 1));
+    cache.check(&read("a", 1.0), &snap).unwrap();
+    cache.check(&read("b", 1.0), &snap).unwrap();
+    let m = cache.metrics();
+    assert_eq!((m.hits, m.misses), (2, 4), "a stayed, b was evicted");
+}
+
+#[test]
+fn cached_and_uncached_decisions_agree() {
+    let now = Arc::new(AtomicU64::new(T0));
+    let clock = now.clone();
+    let (mut max_power, mut uncached) = (500.0, guard(500.0, 0.3));
+    let cache = GateDecisionCache::with_clock(
+        uncached.clone(),
+        DecisionCacheConfig { capacity: 64, ttl_secs: 5, cost_bucket: 4.0, usage_tolerance: 0.02 },
+        move || clock.load(Ordering::SeqCst),
+    );
+    let clock = now.clone();
+    let usage = SnapshotBuilder::with_clock(
+        SnapshotBuilderConfig {
+            total_power_budget: Watts::new(1000.0),
+            total_compute_capacity: 1000.0,
+            max_reading_age_secs: 30,
+        },
+        move || clock.load(Ordering::SeqCst),
+    );
+    let mut rng = rand::rngs::StdRng::seed_from_u64(448);
+    let mut active: Vec<String> = Vec::new();
+    let kinds = [XRActionKind::ReadNeuralShard, XRActionKind::ReadKeys, XRActionKind::WriteNeuralShard];
+
+    for step in 0..20_000 {
+        match rng.gen_range(0..400) {
+            0..=319 => {
+                let mut action = read(&format!("s{}", rng.gen_range(0..3)), rng.gen_range(0.0..40.0));
+                action.kind = kinds[rng.gen_range(0..kinds.len())].clone();
+                action.equity_class = Some(if rng.gen_bool(0.5) { "host" } else { "guest" }.into());
+                action.rohafterestimate = action.rohbefore - rng.gen_range(-0.02..0.1);
+                let snap = usage.current();
+                let (got, want) = (cache.check(&action, &snap), uncached.check(&action, &snap));
+                assert_eq!(got.map_err(|e| e.code), want.map_err(|e| e.code), "step {step}: {action:?}");
+            }
+            320..=359 if active.len() < 40 => {
+                let id = format!("u{step}");
+                let class = if rng.gen_bool(0.5) { "host" } else { "guest" };
+                usage.commit(&id, class, &read("load", rng.gen_range(0.0..8.0))).unwrap();
+                active.push(id);
+                if rng.gen_bool(0.5) {
+                    cache.on_usage(&usage.current());
+                }
+            }
+            320..=389 if !active.is_empty() => {
+                let id = active.swap_remove(rng.gen_range(0..active.len()));
+                usage.release(&id).unwrap();
+            }
+            390..=393 => usage.record_power(Watts::new(rng.gen_range(0.0..150.0))),
+            394 => usage.apply(ecofairness_guard::UsageEvent::WindowReset).unwrap(),
+            395 => {
+                max_power = rng.gen_range(300.0..600.0);
+                uncached = guard(max_power, rng.gen_range(0.1..0.4));
+                cache.reload(uncached.clone());
+            }
+            396 => {
+                let ceiling = rng.gen_range(0.1..0.4);
+                uncached = guard(max_power, ceiling);
+                cache.update_roh_model(roh(ceiling));
+            }
+            _ => {
+                now.fetch_add(rng.gen_range(0..3), Ordering::SeqCst);
+            }
+        }
+    }
+    let m = cache.metrics();
+    assert!(m.hits > 1_000 && m.invalidated_usage > 0 && m.expired > 0, "{m:?}");
+}
@@ -1,5 +1,27 @@
-use ecofairness_guard::GraceEquityKernel;
-use std::path::PathBuf;
+use ecofairness_guard::{EquityKernelError, GraceEquityKernel};
+use std::{fs, path::PathBuf};
+
+fn policies_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../policies")
+}
+
+/// `spec` written to a scratch file and loaded as a policy.
+fn load_policy(name: &str, spec: serde_json::Value) -> Result<GraceEquityKernel, EquityKernelError> {
+    let path = std::env::temp_dir().join(format!("eco-fairness-{name}-{}.aln", std::process::id()));
+    fs::write(&path, spec.to_string()).unwrap();
+    let kernel = GraceEquityKernel::from_path(&path);
+    fs::remove_file(&path).unwrap();
+    kernel
+}
+
+fn bare_spec() -> serde_json::Value {
+    serde_json::json!({
+        "resource_kind": "power_budget",
+        "normalization": "fraction_of_total",
+        "classes": [{ "name": "host", "min_share": 0.1, "max_share": 0.4, "description": null }],
+        "node_routes": [{ "route": "AUTO_CHURCH_SIM", "max_power_fraction": 0.6, "max_compute_fraction": 0.6 }]
+    })
+}
 
 #[test]
 fn eco_fairness_kernel_sum_min_share_must_not_exceed_one() {
@@ -19,3 +41,39 @@ fn eco_fairness_kernel_sum_min_share_must_not_exceed_one() {
         sum_min
     );
 }
+
+#[test]
+fn every_shipped_policy_loads() {
+    let mut loaded = 0;
+    for entry in fs::read_dir(policies_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        GraceEquityKernel::from_path(&path).unwrap_or_else(|e| panic!("{} must load: {e}", path.display()));
+        loaded += 1;
+    }
+    assert!(loaded > 0, "policies/ ships no policy files");
+}
+
+#[test]
+fn wrapped_and_bare_specs_load_the_same_kernel() {
+    let bare = load_policy("bare", bare_spec()).unwrap();
+    let wrapped = load_policy("wrapped", serde_json::json!({ "version": 1, "grace_equity_kernel": bare_spec() })).unwrap();
+    assert_eq!(serde_json::to_value(&bare).unwrap(), serde_json::to_value(&wrapped).unwrap());
+}
+
+#[test]
+fn wrapped_spec_reports_unknown_fields_under_its_key() {
+    let mut spec = bare_spec();
+    spec["classes"][0]["priority"] = 1.into();
+    match load_policy("unknown", serde_json::json!({ "version": 1, "grace_equity_kernel": spec })) {
+        Err(EquityKernelError::UnknownCriticalField(path)) => assert_eq!(path, "grace_equity_kernel.classes[0].priority"),
+        other => panic!("expected UnknownCriticalField, got {other:?}"),
+    }
+    match load_policy("sibling", serde_json::json!({ "version": 1, "grace_equity_kernel": bare_spec(), "weights": {} })) {
+        Err(EquityKernelError::UnknownCriticalField(path)) => assert_eq!(path, "weights"),
+        other => panic!("expected UnknownCriticalField, got {other:?}"),
+    }
+    assert!(matches!(
+        load_policy("version", serde_json::json!({ "version": 2, "grace_equity_kernel": bare_spec() })),
+        Err(EquityKernelError::Invariant(_))
+    ));
+}
@@ -0,0 +1,74 @@
+use ecofairness_guard::{load_tsafe_envelopes, EquityKernelError, GraceEquityKernel, ResourceUsageSnapshot, TsafeEcoEnvelope};
+
+const FIXTURE: &str = include_str!("fixtures/tsafe-eco-envelopes.json");
+
+#[test]
+fn envelope_fixture_round_trips_as_bare_numbers() {
+    let envelopes = load_tsafe_envelopes(FIXTURE).unwrap();
+    let live = &envelopes["AUTO_CHURCH_LIVE"];
+    assert_eq!(live.max_power.value(), 500.0);
+    assert_eq!(live.max_compute_fraction.value(), 0.75);
+
+    let original: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+    let reencoded = serde_json::to_value(&envelopes).unwrap();
+    assert_eq!(reencoded, original);
+}
+
+#[test]
+fn out_of_range_compute_fraction_names_the_path() {
+    let bad = FIXTURE.replace("0.4", "1.4");
+    let err = load_tsafe_envelopes(&bad).unwrap_err().to_string();
+    assert!(err.starts_with("AUTO_CHURCH_SIM.max_compute_fraction"), "{err}");
+    assert!(err.contains("1.4"), "{err}");
+
+    let negative = r#"{"route":"r","max_power":1.0,"max_cumulative_energy":1.0,"max_compute_fraction":-0.1}"#;
+    assert!(serde_json::from_str::<TsafeEcoEnvelope>(negative).is_err());
+}
+
+#[test]
+fn envelope_ext_is_kept_and_other_unknown_fields_rejected() {
+    let mut doc: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+    doc["AUTO_CHURCH_LIVE"]["ext"] = serde_json::json!({ "thermal_zone": "nave", "limits": [1, 2] });
+    let envelopes = load_tsafe_envelopes(&doc.to_string()).unwrap();
+    assert_eq!(envelopes["AUTO_CHURCH_LIVE"].ext["thermal_zone"], "nave");
+    assert_eq!(serde_json::to_value(&envelopes).unwrap(), doc);
+
+    doc["AUTO_CHURCH_SIM"]["max_heat"] = serde_json::json!(10.0);
+    let err = load_tsafe_envelopes(&doc.to_string()).unwrap_err().to_string();
+    assert_eq!(err, "UNKNOWN_CRITICAL_FIELD: AUTO_CHURCH_SIM.max_heat");
+}
+
+#[test]
+fn kernel_ext_is_kept_and_other_unknown_fields_rejected() {
+    let mut doc = serde_json::json!({
+        "classes": { "host": { "min_share": 0.1, "max_share": 0.4, "description": null } },
+        "resource_kind": "power_budget",
+        "normalization": "fraction_of_total",
+        "node_routes": { "XR": { "route": "XR", "max_power_fraction": 0.5, "max_compute_fraction": 0.5 } },
+        "ext": { "review_cadence_days": 30 }
+    });
+    let kernel = GraceEquityKernel::from_json(&doc.to_string()).unwrap();
+    assert_eq!(kernel.ext["review_cadence_days"], 30);
+
+    doc["classes"]["host"]["priority"] = serde_json::json!(1);
+    match GraceEquityKernel::from_json(&doc.to_string()) {
+        Err(EquityKernelError::UnknownCriticalField(path)) => assert_eq!(path, "classes.host.priority"),
+        other => panic!("expected an unknown critical field, got {other:?}"),
+    }
+}
+
+#[test]
+fn snapshot_keeps_legacy_wire_shape() {
+    let json = r#"{
+        "total_power_budget": 1000.0,
+        "total_compute_capacity": 100.0,
+        "current_power_draw": 20.0,
+        "current_cumulative_energy": 100.0,
+        "current_compute_fraction": 0.1,
+        "class_shares": {"host": 0.25}
+    }"#;
+    let snapshot: ResourceUsageSnapshot = serde_json::from_str(json).unwrap();
+    assert_eq!(snapshot.current_power_draw.value(), 20.0);
+    let back = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(back, serde_json::from_str::<serde_json::Value>(json).unwrap());
+}
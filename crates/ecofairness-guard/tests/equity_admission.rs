@@ -0,0 +1,132 @@
+use ecofairness_guard::{AdmissionLimit, EquityBounds, EquityKernelError, GraceEquityKernel, RouteEnvelope};
+use std::collections::HashMap;
+
+const ROUTE: &str = "AUTO_CHURCH_LIVE";
+
+fn kernel(bounds: [(&str, f32, f32); 3]) -> GraceEquityKernel {
+    let route = |name: &str, power: f32, compute: f32| {
+        (name.to_string(), RouteEnvelope { route: name.into(), max_power_fraction: power, max_compute_fraction: compute })
+    };
+    GraceEquityKernel {
+        classes: bounds
+            .into_iter()
+            .map(|(name, min_share, max_share)| (name.to_string(), EquityBounds { min_share, max_share, description: None }))
+            .collect(),
+        resource_kind: "power_budget".into(),
+        normalization: "fraction_of_total".into(),
+        node_routes: HashMap::from([route(ROUTE, 0.5, 0.4), route("NARROW", 0.05, 0.1)]),
+        ext: Default::default(),
+    }
+}
+
+/// host [0.2, 0.8], local_congregation [0.3, 0.6], remote_congregation [0.1, 0.4].
+fn three_classes() -> GraceEquityKernel {
+    kernel([("host", 0.2, 0.8), ("local_congregation", 0.3, 0.6), ("remote_congregation", 0.1, 0.4)])
+}
+
+fn shares(host: f32, local: f32, remote: f32) -> HashMap<String, f32> {
+    HashMap::from([
+        ("host".to_string(), host),
+        ("local_congregation".to_string(), local),
+        ("remote_congregation".to_string(), remote),
+    ])
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn a_delta_within_every_bound_is_admitted_whole() {
+    let admission = three_classes().admit("host", ROUTE, 0.1, &shares(0.3, 0.3, 0.2)).unwrap();
+    assert!(close(admission.granted, 0.1) && close(admission.post_share, 0.4));
+    assert_eq!((admission.throttle, admission.limited_by), (1.0, None));
+}
+
+#[test]
+fn each_bound_throttles_the_delta() {
+    let kernel = three_classes();
+
+    let capacity = kernel.admit("host", ROUTE, 0.3, &shares(0.3, 0.3, 0.2)).unwrap();
+    assert!(close(capacity.granted, 0.2) && close(capacity.throttle, 2.0 / 3.0));
+    assert_eq!(capacity.limited_by, Some(AdmissionLimit::Capacity));
+
+    let max_share = kernel.admit("remote_congregation", ROUTE, 0.1, &shares(0.2, 0.3, 0.35)).unwrap();
+    assert!(close(max_share.post_share, 0.4));
+    assert_eq!(max_share.limited_by, Some(AdmissionLimit::MaxShare));
+
+    let route = kernel.admit("host", "NARROW", 0.1, &shares(0.3, 0.3, 0.2)).unwrap();
+    assert!(close(route.granted, 0.05));
+    assert_eq!(route.limited_by, Some(AdmissionLimit::RouteEnvelope));
+}
+
+#[test]
+fn other_classes_floors_are_held_back_but_not_from_a_starved_requester() {
+    let kernel = three_classes();
+    // local_congregation is 0.2 under its floor; 0.35 is unallocated.
+    let current = shares(0.3, 0.1, 0.25);
+
+    let host = kernel.admit("host", ROUTE, 0.3, &current).unwrap();
+    assert!(close(host.granted, 0.15));
+    assert_eq!(host.limited_by, Some(AdmissionLimit::Floors));
+
+    let local = kernel.admit("local_congregation", ROUTE, 0.3, &current).unwrap();
+    assert!(close(local.granted, 0.3) && close(local.post_share, 0.4));
+    assert_eq!(local.limited_by, None);
+}
+
+#[test]
+fn a_full_node_refuses_and_names_the_limit() {
+    let err = three_classes().admit("host", ROUTE, 0.1, &shares(0.6, 0.3, 0.1)).unwrap_err();
+    assert!(matches!(err, EquityKernelError::Refused { limit: AdmissionLimit::Capacity, .. }), "{err}");
+
+    let kernel = three_classes();
+    let current = shares(0.3, 0.3, 0.2);
+    assert!(matches!(kernel.admit("pilgrims", ROUTE, 0.1, &current), Err(EquityKernelError::UnknownClass(_))));
+    assert!(matches!(kernel.admit("host", "XR", 0.1, &current), Err(EquityKernelError::UnknownRoute(_))));
+    for delta in [0.0, -0.1, f32::NAN] {
+        assert!(matches!(kernel.admit("host", ROUTE, delta, &current), Err(EquityKernelError::InvalidDelta(_))));
+    }
+}
+
+#[test]
+fn floors_summing_to_exactly_one_leave_no_room_above_them() {
+    let kernel = kernel([("host", 0.5, 0.8), ("local_congregation", 0.3, 0.6), ("remote_congregation", 0.2, 0.5)]);
+
+    // Only the starved class may use the last 0.1.
+    let current = shares(0.5, 0.3, 0.1);
+    let err = kernel.admit("host", ROUTE, 0.05, &current).unwrap_err();
+    assert!(matches!(err, EquityKernelError::Refused { limit: AdmissionLimit::Floors, .. }), "{err}");
+    let remote = kernel.admit("remote_congregation", ROUTE, 0.2, &current).unwrap();
+    assert!(close(remote.post_share, 0.2));
+    assert_eq!(remote.limited_by, Some(AdmissionLimit::Capacity));
+
+    // Everyone exactly at their floor: nothing is left for anyone.
+    for class in ["host", "local_congregation", "remote_congregation"] {
+        let err = kernel.admit(class, ROUTE, 0.01, &shares(0.5, 0.3, 0.2)).unwrap_err();
+        assert!(matches!(err, EquityKernelError::Refused { limit: AdmissionLimit::Capacity, .. }), "{class}: {err}");
+    }
+
+    // Host holds the two starved classes' 0.2; throttling it to its floor frees it.
+    let suggestion = kernel.rebalance_suggestion(&shares(0.7, 0.2, 0.1));
+    assert!(close(suggestion["host"], 0.5 / 0.7));
+    assert_eq!((suggestion["local_congregation"], suggestion["remote_congregation"]), (1.0, 1.0));
+}
+
+#[test]
+fn rebalancing_takes_from_surplus_in_proportion_and_restores_floors() {
+    let kernel = three_classes();
+    // local_congregation needs 0.2; 0.1 is free, 0.1 must come from surplus.
+    let current = shares(0.5, 0.1, 0.3);
+    let suggestion = kernel.rebalance_suggestion(&current);
+    assert!(close(suggestion["host"], 0.44 / 0.5));
+    assert!(close(suggestion["remote_congregation"], 0.26 / 0.3));
+    assert_eq!(suggestion["local_congregation"], 1.0);
+
+    let throttled: HashMap<String, f32> = current.iter().map(|(c, s)| (c.clone(), s * suggestion[c])).collect();
+    let admitted = kernel.admit("local_congregation", ROUTE, 0.2, &throttled).unwrap();
+    assert!(close(admitted.post_share, 0.3));
+
+    // Nothing to restore: no throttling.
+    assert!(kernel.rebalance_suggestion(&shares(0.3, 0.3, 0.2)).values().all(|m| *m == 1.0));
+}
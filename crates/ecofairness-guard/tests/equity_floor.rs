@@ -0,0 +1,113 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GraceEquityKernel, ResourceUsageSnapshot, RohModel,
+    TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use std::collections::HashMap;
+
+const ROUTE: &str = "AUTO_CHURCH_LIVE";
+
+/// host [0.2, 0.8], local_congregation [0.3, 0.6], remote_congregation [0.2, 0.5].
+fn guard() -> EcoFairnessGuard {
+    let classes = HashMap::from([
+        ("host".to_string(), EquityBounds { min_share: 0.2, max_share: 0.8, description: None }),
+        ("local_congregation".to_string(), EquityBounds { min_share: 0.3, max_share: 0.6, description: None }),
+        ("remote_congregation".to_string(), EquityBounds { min_share: 0.2, max_share: 0.5, description: None }),
+    ]);
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: HashMap::from([(
+            ROUTE.to_string(),
+            TsafeEcoEnvelope {
+                route: ROUTE.into(),
+                max_power: Watts::new(1000.0),
+                max_cumulative_energy: Joules::new(1.0e6),
+                max_compute_fraction: ComputeFraction::ONE,
+                ext: Default::default(),
+            },
+        )]),
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+/// Local congregation starved at 0.1 of a 1000 W budget; host and remote
+/// at or above their floors.
+fn snapshot(draw: f64, compute: f64) -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        current_power_draw: Watts::new(draw),
+        current_cumulative_energy: Joules::ZERO,
+        current_compute_fraction: ComputeFraction::new(compute).unwrap(),
+        class_shares: HashMap::from([
+            ("host".to_string(), 0.3),
+            ("local_congregation".to_string(), 0.1),
+            ("remote_congregation".to_string(), 0.25),
+        ]),
+        degraded: false,
+    }
+}
+
+fn action(class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ScheduleJob,
+        subjectid: format!("{class}-subject"),
+        route: ROUTE.into(),
+        lifeforcecost: cost,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some(class.into()),
+    }
+}
+
+#[test]
+fn greedy_class_is_denied_when_a_starved_floor_is_at_risk() {
+    let guard = guard();
+    let snap = snapshot(650.0, 0.3);
+
+    // Local congregation needs 0.2 more: 100 W leaves 0.25, 200 W leaves 0.15.
+    guard.check(&action("host", 100.0), &snap).unwrap();
+    let err = guard.check(&action("host", 200.0), &snap).unwrap_err();
+    assert_eq!(err.code, "ECO_EQUITY_FLOOR_AT_RISK");
+    assert!(err.message.contains("'local_congregation'"), "{}", err.message);
+    assert_eq!(guard.check(&action("remote_congregation", 200.0), &snap).unwrap_err().code, "ECO_EQUITY_FLOOR_AT_RISK");
+
+    // The starved class itself is admitted even into the last of the budget.
+    guard.check(&action("local_congregation", 300.0), &snap).unwrap();
+
+    // With no class under its floor the same greedy action passes.
+    let mut served = snap.clone();
+    served.class_shares.insert("local_congregation".into(), 0.3);
+    guard.check(&action("host", 200.0), &served).unwrap();
+}
+
+#[test]
+fn the_tighter_of_power_and_compute_decides() {
+    let guard = guard();
+    // Power leaves 0.35 after 150 units but compute only 0.15.
+    let snap = snapshot(500.0, 0.7);
+    assert_eq!(guard.check(&action("host", 150.0), &snap).unwrap_err().code, "ECO_EQUITY_FLOOR_AT_RISK");
+    guard.check(&action("host", 90.0), &snap).unwrap();
+    guard.check(&action("host", 150.0), &snapshot(500.0, 0.3)).unwrap();
+}
+
+#[test]
+fn every_unmet_floor_counts_and_the_furthest_behind_is_named() {
+    let guard = guard();
+    let mut snap = snapshot(500.0, 0.3);
+    snap.class_shares.insert("remote_congregation".into(), 0.15);
+    // Unmet floors: local 0.2 + remote 0.05 = 0.25.
+    guard.check(&action("host", 240.0), &snap).unwrap();
+    let err = guard.check(&action("host", 260.0), &snap).unwrap_err();
+    assert_eq!(err.code, "ECO_EQUITY_FLOOR_AT_RISK");
+    assert!(err.message.starts_with("Equity class 'local_congregation'"), "{}", err.message);
+
+    // Remote is under its own floor, so it is upweighted rather than checked.
+    guard.check(&action("remote_congregation", 300.0), &snap).unwrap();
+}
@@ -0,0 +1,110 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GraceEquityKernel, ResourceUsageSnapshot, RohModel,
+    TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use std::collections::HashMap;
+
+const ROUTE: &str = "XR";
+
+fn guard() -> EcoFairnessGuard {
+    let classes = HashMap::from([
+        ("host".to_string(), EquityBounds { min_share: 0.0, max_share: 0.3, description: None }),
+        ("guest".to_string(), EquityBounds { min_share: 0.0, max_share: 0.5, description: None }),
+    ]);
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: HashMap::from([(
+            ROUTE.to_string(),
+            TsafeEcoEnvelope {
+                route: ROUTE.into(),
+                max_power: Watts::new(100.0),
+                max_cumulative_energy: Joules::new(500.0),
+                max_compute_fraction: ComputeFraction::ONE,
+                ext: Default::default(),
+            },
+        )]),
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+fn snapshot() -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        current_power_draw: Watts::new(80.0),
+        current_cumulative_energy: Joules::new(460.0),
+        current_compute_fraction: ComputeFraction::new(0.1).unwrap(),
+        class_shares: HashMap::from([("host".to_string(), 0.28)]),
+        degraded: false,
+    }
+}
+
+fn action(class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ScheduleJob,
+        subjectid: format!("{class}-subject"),
+        route: ROUTE.into(),
+        lifeforcecost: cost,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some(class.into()),
+    }
+}
+
+fn close(a: Option<f64>, b: f64) -> bool {
+    a.is_some_and(|a| (a - b).abs() < 1e-6)
+}
+
+#[test]
+fn every_violation_is_reported_with_its_numbers() {
+    let guard = guard();
+    let eval = guard.evaluate(&action("host", 50.0), &snapshot());
+    assert!(!eval.would_allow);
+
+    let codes: Vec<&str> = eval.findings.iter().map(|f| f.code.as_str()).collect();
+    assert_eq!(codes, ["ECO_POWER_EXCEEDED", "ECO_ENERGY_EXCEEDED", "ECO_EQUITY_MAX_EXCEEDED"]);
+    let [power, energy, equity] = &eval.findings[..] else { unreachable!() };
+    assert!(close(power.measured, 130.0) && close(power.limit, 100.0), "{power:?}");
+    assert!(close(energy.measured, 510.0) && close(energy.limit, 500.0), "{energy:?}");
+    assert!((equity.measured.unwrap() - 0.33).abs() < 1e-5, "{equity:?}");
+    assert!((equity.limit.unwrap() - 0.3).abs() < 1e-6, "{equity:?}");
+
+    // The projection is the snapshot with the action admitted.
+    assert_eq!(eval.projected.current_power_draw, Watts::new(130.0));
+    assert_eq!(eval.projected.current_cumulative_energy, Joules::new(510.0));
+    assert!((eval.projected.current_compute_fraction.value() - 0.15).abs() < 1e-9);
+    assert!((eval.projected.class_shares["host"] - 0.33).abs() < 1e-5);
+
+    // check reports the first finding.
+    assert_eq!(guard.check(&action("host", 50.0), &snapshot()).unwrap_err().code, "ECO_POWER_EXCEEDED");
+}
+
+#[test]
+fn a_passing_action_has_no_findings() {
+    let guard = guard();
+    let eval = guard.evaluate(&action("guest", 10.0), &snapshot());
+    assert!(eval.would_allow && eval.findings.is_empty());
+    assert!((eval.projected.class_shares["guest"] - 0.01).abs() < 1e-6);
+    assert_eq!(eval.projected.class_shares["host"], 0.28);
+    guard.check(&action("guest", 10.0), &snapshot()).unwrap();
+}
+
+#[test]
+fn configuration_findings_carry_no_numbers_and_do_not_hide_the_rest() {
+    let guard = guard();
+    let mut stray = action("host", 50.0);
+    stray.route = "DRONE".into();
+    stray.rohafterestimate = 0.4;
+    let eval = guard.evaluate(&stray, &snapshot());
+    let codes: Vec<&str> = eval.findings.iter().map(|f| f.code.as_str()).collect();
+    assert_eq!(codes, ["ECO_NO_ROUTE_ENV", "ECO_EQUITY_MAX_EXCEEDED", "ROH_CEILING", "ROH_MONOTONE"]);
+    assert_eq!((eval.findings[0].measured, eval.findings[0].limit), (None, None));
+    assert!(close(eval.findings[2].measured, 0.4f32.into()) && close(eval.findings[2].limit, 0.3f32.into()));
+}
@@ -0,0 +1,131 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    ClassWorkload, CostDistribution, EcoFairnessConfig, EquityBounds, FairnessSim, FairnessSimConfig,
+    GraceEquityKernel, RohModel, TsafeEcoEnvelope, WorkloadSpec,
+};
+use std::collections::{BTreeMap, HashMap};
+
+const ROUTE: &str = "AUTO_CHURCH_SIM";
+
+fn config(bounds: &[(&str, f32, f32)]) -> EcoFairnessConfig {
+    let classes = bounds
+        .iter()
+        .map(|(name, min_share, max_share)| {
+            (name.to_string(), EquityBounds { min_share: *min_share, max_share: *max_share, description: None })
+        })
+        .collect();
+    let mut envelopes = HashMap::new();
+    envelopes.insert(
+        ROUTE.to_string(),
+        TsafeEcoEnvelope { route: ROUTE.into(), max_power: Watts::new(100.0), max_cumulative_energy: Joules::new(1.0e9), max_compute_fraction: ComputeFraction::ONE, ext: Default::default() },
+    );
+    EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: envelopes,
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    }
+}
+
+fn class(name: &str, rate: f64, burst_probability: f64) -> ClassWorkload {
+    ClassWorkload {
+        class: name.into(),
+        route: ROUTE.into(),
+        arrival_rate: rate,
+        cost: CostDistribution::Uniform { min: 3.0, max: 7.0 },
+        burst_probability,
+        burst_factor: 4.0,
+    }
+}
+
+fn workload(classes: Vec<ClassWorkload>) -> WorkloadSpec {
+    WorkloadSpec {
+        ticks: 200,
+        classes,
+        total_power_budget: 100.0,
+        total_compute_capacity: 1000.0,
+        release_per_tick: 0.2,
+        energy_window_ticks: 0,
+    }
+}
+
+fn sim_cfg(seed: u64) -> FairnessSimConfig {
+    FairnessSimConfig { episodes: 40, seed }
+}
+
+#[test]
+fn starving_spec_is_flagged() {
+    // "bulk" floods the power envelope; "community" is promised 30% but
+    // nothing in the kernel reserves it.
+    let sim = FairnessSim::new(
+        config(&[("bulk", 0.0, 1.0), ("community", 0.3, 1.0)]),
+        workload(vec![class("bulk", 6.0, 0.1), class("community", 0.5, 0.0)]),
+    );
+    let report = sim.run(&sim_cfg(7));
+    assert!(!report.passed());
+    assert_eq!(report.flagged, ["community"]);
+    let community = report.classes.iter().find(|c| c.class == "community").unwrap();
+    assert!(community.share_p5 < 0.3);
+    assert!(community.starvation_events > 0);
+    assert!(report.rejections_by_reason.contains_key("ECO_POWER_EXCEEDED"));
+}
+
+#[test]
+fn balanced_spec_passes() {
+    let sim = FairnessSim::new(
+        config(&[("host", 0.3, 0.7), ("learner", 0.3, 0.7)]),
+        workload(vec![class("host", 1.0, 0.05), class("learner", 1.0, 0.05)]),
+    );
+    let report = sim.run(&sim_cfg(7));
+    assert!(report.passed(), "flagged: {:?}", report.flagged);
+    for c in &report.classes {
+        assert!(c.share_p5 >= 0.3 && c.share_p95 <= 0.7, "{c:?}");
+    }
+    assert!(report.gini < 0.1);
+}
+
+#[test]
+fn same_seed_same_report() {
+    let sim = FairnessSim::new(
+        config(&[("bulk", 0.0, 1.0), ("community", 0.3, 1.0)]),
+        workload(vec![class("bulk", 6.0, 0.1), class("community", 0.5, 0.2)]),
+    );
+    let a = serde_json::to_string(&sim.run(&sim_cfg(42))).unwrap();
+    let b = serde_json::to_string(&sim.run(&sim_cfg(42))).unwrap();
+    assert_eq!(a, b);
+    let c = serde_json::to_string(&sim.run(&sim_cfg(43))).unwrap();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn rejection_counts_reconcile_with_traces() {
+    let sim = FairnessSim::new(
+        config(&[("bulk", 0.0, 0.6), ("community", 0.2, 1.0)]),
+        workload(vec![class("bulk", 6.0, 0.1), class("community", 1.0, 0.1)]),
+    );
+    let (report, traces) = sim.run_with_traces(&sim_cfg(3));
+    assert_eq!(traces.len(), 40);
+    assert_eq!(sim.run(&sim_cfg(3)), report);
+
+    let mut by_reason: BTreeMap<String, u64> = BTreeMap::new();
+    let mut attempts: BTreeMap<String, u64> = BTreeMap::new();
+    let mut admitted: BTreeMap<String, u64> = BTreeMap::new();
+    for entry in traces.iter().flat_map(|t| &t.entries) {
+        *attempts.entry(entry.class.clone()).or_default() += 1;
+        match &entry.rejected {
+            Some(code) => *by_reason.entry(code.clone()).or_default() += 1,
+            None => *admitted.entry(entry.class.clone()).or_default() += 1,
+        }
+    }
+    assert_eq!(by_reason, report.rejections_by_reason);
+    assert!(by_reason.contains_key("ECO_EQUITY_MAX_EXCEEDED"));
+    for c in &report.classes {
+        assert_eq!(attempts[&c.class], c.attempts);
+        assert_eq!(admitted[&c.class], c.admitted);
+    }
+}
@@ -0,0 +1,147 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GraceEquityKernel, HeadroomLedger, HeadroomLedgerConfig,
+    ReportWindow, ResourceUsageSnapshot, RohModel, TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use std::collections::HashMap;
+
+fn guard(ceiling: f32) -> EcoFairnessGuard {
+    let mut classes = HashMap::new();
+    for name in ["host", "learner"] {
+        classes.insert(name.to_string(), EquityBounds { min_share: 0.0, max_share: 1.0, description: None });
+    }
+    let mut envelopes = HashMap::new();
+    for route in ["AUTO_CHURCH_SIM", "AUTO_CHURCH_LIVE"] {
+        envelopes.insert(
+            route.to_string(),
+            TsafeEcoEnvelope { route: route.to_string(), max_power: Watts::new(100.0), max_cumulative_energy: Joules::new(1000.0), max_compute_fraction: ComputeFraction::ONE, ext: Default::default() },
+        );
+    }
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling, weights: HashMap::new() },
+        tsafe_envelopes: envelopes,
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+fn snapshot() -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 100.0,
+        current_power_draw: Watts::new(20.0),
+        current_cumulative_energy: Joules::new(100.0),
+        current_compute_fraction: ComputeFraction::new(0.1).unwrap(),
+        class_shares: HashMap::new(),
+        degraded: false,
+    }
+}
+
+fn action(route: &str, kind: XRActionKind, class: &str, roh: f32, cost: f32) -> XRAction {
+    XRAction {
+        kind,
+        subjectid: "s".into(),
+        route: route.into(),
+        lifeforcecost: cost,
+        rohbefore: roh,
+        rohafterestimate: roh,
+        equity_class: Some(class.into()),
+    }
+}
+
+/// Timestamped admissions across two 100-second windows.
+fn script() -> Vec<(u64, XRAction)> {
+    vec![
+        (10, action("AUTO_CHURCH_SIM", XRActionKind::ScheduleJob, "learner", 0.15, 10.0)),
+        (20, action("AUTO_CHURCH_SIM", XRActionKind::ScheduleJob, "learner", 0.06, 10.0)),
+        (30, action("AUTO_CHURCH_LIVE", XRActionKind::XRRouteStep, "host", 0.24, 20.0)),
+        // second window
+        (110, action("AUTO_CHURCH_SIM", XRActionKind::ScheduleJob, "learner", 0.27, 10.0)),
+        (120, action("AUTO_CHURCH_LIVE", XRActionKind::XRRouteStep, "host", 0.21, 40.0)),
+        (130, action("AUTO_CHURCH_LIVE", XRActionKind::XRRouteStep, "host", 0.29, 40.0)),
+    ]
+}
+
+fn recorded() -> HeadroomLedger {
+    let g = guard(0.3);
+    let mut ledger = HeadroomLedger::new(HeadroomLedgerConfig::default());
+    for (t, a) in script() {
+        g.check_and_record(&a, &snapshot(), &mut ledger, t).unwrap();
+    }
+    ledger
+}
+
+#[test]
+fn report_arithmetic_on_scripted_sequence() {
+    let ledger = recorded();
+    let report = ledger.headroom_report(ReportWindow { start: 0, end: 100 }, 5);
+    assert_eq!(report.admissions, 3);
+
+    let sim = report.by_route.iter().find(|g| g.key == "AUTO_CHURCH_SIM").unwrap();
+    assert_eq!(sim.totals.admissions, 2);
+    // (0.15 + 0.06) / 0.3 / 2 = 0.35
+    assert!((sim.totals.mean_roh_utilization() - 0.35).abs() < 1e-6);
+    // Each SIM action draws 10W of a 100W envelope.
+    assert!((sim.totals.power_consumed - 0.2).abs() < 1e-6);
+    assert!((sim.totals.energy_consumed - 0.02).abs() < 1e-6);
+
+    let live = report.by_kind.iter().find(|g| g.key == "XRRouteStep").unwrap();
+    assert!((live.totals.roh_utilization_max - 0.8).abs() < 1e-6);
+    // host 0.8 vs learner 0.7 summed utilization.
+    let order: Vec<&str> = report.top_consumers.iter().map(|(c, _)| c.as_str()).collect();
+    assert_eq!(order, ["host", "learner"]);
+}
+
+#[test]
+fn trend_against_prior_window() {
+    let ledger = recorded();
+    let report = ledger.headroom_report(ReportWindow { start: 100, end: 200 }, 5);
+    let sim = report.by_route.iter().find(|g| g.key == "AUTO_CHURCH_SIM").unwrap();
+    // 0.9 now vs 0.35 before.
+    assert!((sim.roh_trend.unwrap() - 0.55).abs() < 1e-6);
+    let live = report.by_route.iter().find(|g| g.key == "AUTO_CHURCH_LIVE").unwrap();
+    // mean(0.7, 0.9667) - 0.8
+    assert!((live.roh_trend.unwrap() - ((0.21 + 0.29) / 0.3 / 2.0 - 0.8)).abs() < 1e-5);
+    assert_eq!(report.top_consumers[0].0, "host");
+}
+
+#[test]
+fn what_if_matches_brute_force_recheck() {
+    let ledger = recorded();
+    for delta in [0.0_f32, -0.02, -0.05, -0.1, -0.2] {
+        let tighter = guard(0.3 + delta);
+        let brute = script().iter().filter(|(_, a)| tighter.check(a, &snapshot()).is_err()).count();
+        assert_eq!(ledger.what_if(delta).would_reject, brute, "delta {delta}");
+    }
+    assert_eq!(ledger.what_if(-0.05).would_reject, 2);
+}
+
+#[test]
+fn ring_and_rollups_are_bounded_and_replayable() {
+    let cfg = HeadroomLedgerConfig { ring_capacity: 4, max_days: 2 };
+    let g = guard(0.3);
+    let mut ledger = HeadroomLedger::new(cfg.clone());
+    let mut full = HeadroomLedger::new(HeadroomLedgerConfig { ring_capacity: 1_000, max_days: 100 });
+    for day in 0..5u64 {
+        for (t, a) in script() {
+            g.check_and_record(&a, &snapshot(), &mut ledger, day * 86_400 + t).unwrap();
+            g.check_and_record(&a, &snapshot(), &mut full, day * 86_400 + t).unwrap();
+        }
+    }
+    assert_eq!(ledger.records().count(), 4);
+    assert_eq!(ledger.daily_rollups().count(), 2);
+
+    let replayed = HeadroomLedger::replay(cfg, full.records().cloned());
+    assert_eq!(replayed.records().collect::<Vec<_>>(), ledger.records().collect::<Vec<_>>());
+    assert_eq!(replayed.daily_rollups().collect::<Vec<_>>(), ledger.daily_rollups().collect::<Vec<_>>());
+
+    let closed = ledger.drain_closed_days(4 * 86_400 + 10);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].day, 3);
+    assert_eq!(closed[0].to_deed_context()["deed_type"], "headroom_rollup");
+}
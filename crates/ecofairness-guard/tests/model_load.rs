@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ecofairness_guard::{ModelError, RohModel, ViabilityKernel};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+#[test]
+fn a_good_roh_model_loads_with_weights_normalized() {
+    let model = RohModel::load(fixture("rohmodel.aln")).unwrap();
+    assert_eq!(model.ceiling, 0.3);
+    assert!((model.weights.values().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert!((model.weights["eco_impact"] - 0.5).abs() < 1e-6);
+    assert!((model.weights["equity_drift"] - 0.125).abs() < 1e-6);
+}
+
+#[test]
+fn a_missing_ceiling_names_the_field_and_file() {
+    let err = RohModel::load(fixture("rohmodel-missing-ceiling.aln")).unwrap_err();
+    assert!(matches!(err, ModelError::Parse { .. }), "{err}");
+    let message = err.to_string();
+    assert!(message.contains("rohmodel-missing-ceiling.aln") && message.contains("ceiling"), "{message}");
+}
+
+#[test]
+fn an_out_of_range_ceiling_names_the_field_and_file() {
+    let err = RohModel::load(fixture("rohmodel-out-of-range.aln")).unwrap_err();
+    assert_eq!(err.field(), Some("ceiling"));
+    assert!(matches!(&err, ModelError::Invalid { path: Some(p), .. } if p.ends_with("rohmodel-out-of-range.aln")));
+    assert!(err.to_string().contains("1.5"), "{err}");
+}
+
+#[test]
+fn validate_checks_models_built_in_code() {
+    let weights = |pairs: &[(&str, f32)]| pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect::<HashMap<_, _>>();
+    RohModel { ceiling: 0.3, weights: HashMap::new() }.validate().unwrap();
+    RohModel { ceiling: 1.0, weights: weights(&[("eco_impact", 1.0)]) }.validate().unwrap();
+
+    for ceiling in [0.0, -0.1, 1.01, f32::NAN] {
+        let err = RohModel { ceiling, weights: HashMap::new() }.validate().unwrap_err();
+        assert_eq!(err.field(), Some("ceiling"), "{ceiling}");
+    }
+    let negative = RohModel { ceiling: 0.3, weights: weights(&[("eco_impact", 0.7), ("equity_drift", -0.2)]) };
+    assert_eq!(negative.validate().unwrap_err().field(), Some("weights.equity_drift"));
+    let zero = RohModel { ceiling: 0.3, weights: weights(&[("eco_impact", 0.0)]) };
+    assert_eq!(zero.validate().unwrap_err().field(), Some("weights"));
+}
+
+#[test]
+fn a_good_viability_kernel_loads_and_bounds_points() {
+    let kernel = ViabilityKernel::load(fixture("vkernel.aln")).unwrap();
+    assert!(kernel.is_viable(&[400.0, 1_000.0, 0.5]));
+    // Each axis is within its own bound, but the coupled row is not.
+    assert!(!kernel.is_viable(&[800.0, 2_000.0, 0.5]));
+    assert!(!kernel.is_viable(&[400.0, 1_000.0]));
+}
+
+#[test]
+fn inconsistent_viability_kernels_are_refused() {
+    let err = ViabilityKernel::load(fixture("vkernel-ragged.aln")).unwrap_err();
+    assert_eq!(err.field(), Some("constraints[1]"));
+    assert!(err.to_string().starts_with(&fixture("vkernel-ragged.aln").display().to_string()), "{err}");
+
+    let err = ViabilityKernel::load(fixture("vkernel-missing-bounds.aln")).unwrap_err();
+    assert!(matches!(err, ModelError::Parse { .. }) && err.to_string().contains("bounds"), "{err}");
+    assert!(matches!(ViabilityKernel::load(fixture("absent.aln")), Err(ModelError::Io { .. })));
+
+    let good = ViabilityKernel::load(fixture("vkernel.aln")).unwrap();
+    let short = ViabilityKernel { bounds: vec![1.0], ..good.clone() };
+    assert_eq!(short.validate().unwrap_err().field(), Some("bounds"));
+    let twice = ViabilityKernel { axes: vec!["power_watts".into(); 3], ..good.clone() };
+    assert_eq!(twice.validate().unwrap_err().field(), Some("axes"));
+    let mut infinite = good;
+    infinite.constraints[3][1] = f64::INFINITY;
+    assert_eq!(infinite.validate().unwrap_err().field(), Some("constraints[3][1]"));
+}
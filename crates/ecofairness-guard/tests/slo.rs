@@ -0,0 +1,321 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GraceEquityKernel, LatencyObjective, MaintenanceWindow,
+    Observation, Outcome, ResourceUsageSnapshot, RohModel, SharedSloTracker, SloAlerter, SloConfig, SloError, SloEvent,
+    SloEventKind, SloSpec, SloStatus, SloTracker, TsafeEcoEnvelope, XRAction, XRActionKind,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+const ROUTE: &str = "AUTO_CHURCH_LIVE";
+const LOCAL: &str = "local_congregation";
+const REMOTE: &str = "remote_congregation";
+const T0: u64 = 1_700_000_000;
+
+fn spec(id: &str, class: &str) -> SloSpec {
+    SloSpec {
+        id: id.into(),
+        route: ROUTE.into(),
+        class: class.into(),
+        latency: vec![LatencyObjective { percentile: 0.95, max_ms: 200.0 }],
+        max_rejection_rate: BTreeMap::from([("eco".to_string(), 0.05)]),
+        min_availability: Some(0.9),
+    }
+}
+
+fn config(slos: Vec<SloSpec>) -> SloConfig {
+    SloConfig { slos, window_secs: 300, min_samples: 20, uplift_step: 0.05, max_uplift: 0.15, ..SloConfig::default() }
+}
+
+#[derive(Default)]
+struct Alerts(Mutex<Vec<SloEventKind>>);
+
+impl SloAlerter for Alerts {
+    fn alert(&self, event: &SloEvent) -> Result<(), String> {
+        self.0.lock().unwrap().push(event.kind);
+        Ok(())
+    }
+}
+
+struct Fixture {
+    clock: Arc<AtomicU64>,
+    alerts: Arc<Alerts>,
+    tracker: SharedSloTracker,
+}
+
+impl Fixture {
+    fn new(cfg: SloConfig) -> Self {
+        let clock = Arc::new(AtomicU64::new(T0));
+        let alerts = Arc::new(Alerts::default());
+        let c = clock.clone();
+        let tracker = SloTracker::new(cfg)
+            .unwrap()
+            .with_clock(move || c.load(Ordering::SeqCst))
+            .with_alerter(alerts.clone())
+            .into_shared();
+        Self { clock, alerts, tracker }
+    }
+
+    fn at(&self, t: u64) {
+        self.clock.store(t, Ordering::SeqCst);
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.load(Ordering::SeqCst)
+    }
+
+    /// `n` outcomes for `class` at the current time.
+    fn record(&self, class: &str, n: usize, latency_ms: f64, outcome: Outcome) {
+        let mut tracker = self.tracker.write().unwrap();
+        for _ in 0..n {
+            tracker.record(Observation {
+                route: ROUTE.into(),
+                class: class.into(),
+                at: self.now(),
+                latency_ms,
+                outcome: outcome.clone(),
+            });
+        }
+    }
+
+    fn status(&self, index: usize) -> SloStatus {
+        self.tracker.read().unwrap().slo_status().remove(index)
+    }
+
+    fn evaluate(&self) -> Vec<SloEventKind> {
+        self.tracker.write().unwrap().evaluate().into_iter().map(|e| e.kind).collect()
+    }
+}
+
+fn eco_rejection() -> Outcome {
+    Outcome::Rejected { code: "ECO_POWER_EXCEEDED".into() }
+}
+
+/// Local congregation's floor is `local_floor`; remote's bounds are [0.1, 0.2].
+fn guard_with(local_floor: f32, tracker: SharedSloTracker) -> EcoFairnessGuard {
+    let classes = HashMap::from([
+        (LOCAL.to_string(), EquityBounds { min_share: local_floor, max_share: 0.8, description: None }),
+        (REMOTE.to_string(), EquityBounds { min_share: 0.1, max_share: 0.2, description: None }),
+    ]);
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: HashMap::from([(
+            ROUTE.to_string(),
+            TsafeEcoEnvelope {
+                route: ROUTE.into(),
+                max_power: Watts::new(1000.0),
+                max_cumulative_energy: Joules::new(1.0e6),
+                max_compute_fraction: ComputeFraction::ONE,
+                ext: Default::default(),
+            },
+        )]),
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+    .with_slo_tracker(tracker)
+}
+
+/// Remote congregation at `share` of a 1000 W budget.
+fn snapshot(share: f32) -> ResourceUsageSnapshot {
+    ResourceUsageSnapshot {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 10_000.0,
+        current_power_draw: Watts::ZERO,
+        current_cumulative_energy: Joules::ZERO,
+        current_compute_fraction: ComputeFraction::ZERO,
+        class_shares: HashMap::from([(REMOTE.to_string(), share)]),
+        degraded: false,
+    }
+}
+
+/// A remote-congregation action worth 5% of the budget.
+fn action() -> XRAction {
+    XRAction {
+        kind: XRActionKind::XRRouteStep,
+        subjectid: "s1".into(),
+        route: ROUTE.into(),
+        lifeforcecost: 50.0,
+        rohbefore: 0.2,
+        rohafterestimate: 0.2,
+        equity_class: Some(REMOTE.into()),
+    }
+}
+
+#[test]
+fn objectives_are_met_at_exactly_their_threshold() {
+    let f = Fixture::new(config(vec![spec("local", LOCAL)]));
+    // 1 eco rejection in 20 is exactly 5%; every latency is exactly 200 ms.
+    f.record(LOCAL, 19, 200.0, Outcome::Admitted);
+    f.record(LOCAL, 1, 200.0, eco_rejection());
+    assert!(f.evaluate().is_empty());
+    let status = f.status(0);
+    assert!(status.compliant(), "{:?}", status.violations);
+    assert_eq!(status.latency[0].measured_ms, Some(200.0));
+    assert_eq!(status.rejection_rates[0].measured, 0.05);
+
+    // One more eco rejection tips the rate over.
+    f.record(LOCAL, 1, 200.0, eco_rejection());
+    assert_eq!(f.evaluate(), vec![SloEventKind::Breached]);
+    let status = f.status(0);
+    assert_eq!(status.violations.len(), 1, "{:?}", status.violations);
+    assert!(status.violations[0].starts_with("eco rejection rate"));
+
+    // Latency just over the threshold at p95 breaches on its own.
+    let f = Fixture::new(config(vec![spec("local", LOCAL)]));
+    f.record(LOCAL, 18, 100.0, Outcome::Admitted);
+    f.record(LOCAL, 2, 200.5, Outcome::Admitted);
+    assert_eq!(f.evaluate(), vec![SloEventKind::Breached]);
+    let status = f.status(0);
+    assert_eq!(status.latency[0].measured_ms, Some(200.5));
+    assert!(status.violations[0].starts_with("p95 latency"));
+}
+
+#[test]
+fn too_few_samples_are_not_evaluated() {
+    let f = Fixture::new(config(vec![spec("local", LOCAL)]));
+    f.record(LOCAL, 19, 900.0, Outcome::Unavailable);
+    assert!(f.evaluate().is_empty());
+    let status = f.status(0);
+    assert!(!status.evaluated && status.compliant());
+}
+
+#[test]
+fn maintenance_windows_are_left_out_of_the_math() {
+    let cfg = SloConfig {
+        maintenance: vec![MaintenanceWindow { route: Some(ROUTE.into()), starts_at: T0, ends_at: T0 + 60 }],
+        ..config(vec![spec("local", LOCAL)])
+    };
+    let f = Fixture::new(cfg);
+    // Everything fails during maintenance, and none of it counts.
+    f.record(LOCAL, 40, 5_000.0, Outcome::Unavailable);
+    assert!(f.evaluate().is_empty(), "not evaluated during maintenance");
+    f.at(T0 + 60);
+    assert!(f.evaluate().is_empty());
+    let status = f.status(0);
+    assert_eq!((status.samples, status.excluded), (0, 40));
+    assert!(!status.in_maintenance);
+
+    f.record(LOCAL, 20, 50.0, Outcome::Admitted);
+    assert!(f.evaluate().is_empty());
+    let status = f.status(0);
+    assert_eq!(status.samples, 20);
+    assert_eq!(status.availability, Some(1.0));
+    assert!(status.compliant());
+}
+
+#[test]
+fn a_breach_uplifts_its_class_within_bounds() {
+    let f = Fixture::new(config(vec![spec("remote", REMOTE)]));
+    let g = guard_with(0.6, f.tracker.clone());
+    // 0.18 + 0.05 would exceed remote's max_share of 0.2.
+    assert_eq!(g.check(&action(), &snapshot(0.18)).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+
+    f.record(REMOTE, 20, 900.0, Outcome::Admitted);
+    assert_eq!(f.evaluate(), vec![SloEventKind::Breached]);
+    assert_eq!(*f.alerts.0.lock().unwrap(), vec![SloEventKind::Breached]);
+    assert_eq!(f.tracker.read().unwrap().uplift(REMOTE), 0.05);
+    g.check(&action(), &snapshot(0.18)).unwrap();
+    assert_eq!(g.check(&action(), &snapshot(0.21)).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+
+    // Each evaluation still in breach adds a step, up to max_uplift, with
+    // no further event.
+    for _ in 0..5 {
+        assert!(f.evaluate().is_empty());
+    }
+    assert_eq!(f.tracker.read().unwrap().uplift(REMOTE), 0.15);
+    assert_eq!(f.tracker.read().unwrap().uplift(LOCAL), 0.0);
+    g.check(&action(), &snapshot(0.29)).unwrap();
+    assert_eq!(g.check(&action(), &snapshot(0.31)).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+
+    // A higher floor for local congregation leaves remote only up to 0.28.
+    let tight = guard_with(0.72, f.tracker.clone());
+    tight.check(&action(), &snapshot(0.22)).unwrap();
+    assert_eq!(tight.check(&action(), &snapshot(0.24)).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+    // Local congregation itself is unaffected.
+    let local = XRAction { equity_class: Some(LOCAL.into()), ..action() };
+    let mut busy = snapshot(0.0);
+    busy.class_shares.insert(LOCAL.into(), 0.78);
+    assert_eq!(g.check(&local, &busy).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+}
+
+#[test]
+fn recovery_clears_the_uplift() {
+    let f = Fixture::new(config(vec![spec("remote", REMOTE)]));
+    let g = guard_with(0.6, f.tracker.clone());
+    f.record(REMOTE, 20, 900.0, Outcome::Admitted);
+    assert_eq!(f.evaluate(), vec![SloEventKind::Breached]);
+    g.check(&action(), &snapshot(0.18)).unwrap();
+
+    // The slow outcomes age out of the window; fast ones replace them.
+    f.at(T0 + 300);
+    f.record(REMOTE, 20, 50.0, Outcome::Admitted);
+    assert_eq!(f.evaluate(), vec![SloEventKind::Recovered]);
+    assert_eq!(f.tracker.read().unwrap().uplift(REMOTE), 0.0);
+    assert_eq!(g.check(&action(), &snapshot(0.18)).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+    assert_eq!(*f.alerts.0.lock().unwrap(), vec![SloEventKind::Breached, SloEventKind::Recovered]);
+
+    let events = f.tracker.write().unwrap().drain_events();
+    let kinds: Vec<_> = events.iter().map(|e| e.to_deed_context()["deed_type"].clone()).collect();
+    assert_eq!(kinds, ["slo_breach", "slo_recovered"]);
+    assert_eq!(events[0].status.breached_since, Some(T0));
+    assert_eq!(events[1].status.breached_since, None);
+    assert!(f.tracker.write().unwrap().drain_events().is_empty());
+}
+
+#[test]
+fn status_reconciles_with_the_recorded_outcomes() {
+    let f = Fixture::new(config(vec![spec("local", LOCAL), spec("remote", REMOTE)]));
+    let latencies = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 100.0];
+    for (i, latency) in latencies.iter().cycle().take(40).enumerate() {
+        let outcome = match i % 10 {
+            0 => eco_rejection(),
+            1 => Outcome::Rejected { code: "ROH_CEILING".into() },
+            _ if i >= 38 => Outcome::Unavailable,
+            _ => Outcome::Admitted,
+        };
+        f.record(LOCAL, 1, *latency, outcome);
+    }
+    let tracker = f.tracker.read().unwrap();
+    let status = tracker.slo_status();
+    let local = &status[0];
+    assert_eq!(local.samples, 40);
+    // 38 answered latencies, 10..100 ms four times less the last two (90, 100).
+    let mut answered: Vec<f64> = latencies.iter().cycle().take(38).copied().collect();
+    answered.sort_by(f64::total_cmp);
+    assert_eq!(local.latency[0].measured_ms, Some(answered[(0.95f64 * 38.0).ceil() as usize - 1]));
+    assert_eq!(local.rejection_rates[0].category, "eco");
+    assert_eq!(local.rejection_rates[0].measured, 4.0 / 40.0);
+    assert_eq!(local.availability, Some(38.0 / 40.0));
+    assert!(!local.compliant(), "4 eco rejections in 40 is over 5%");
+    assert_eq!(status[1].samples, 0, "remote saw nothing");
+
+    let text = tracker.to_prometheus();
+    assert!(text.contains(
+        "ecofairness_slo_compliant{slo=\"local\",route=\"AUTO_CHURCH_LIVE\",class=\"local_congregation\"} 0"
+    ));
+    assert!(text.contains(
+        "ecofairness_slo_compliant{slo=\"remote\",route=\"AUTO_CHURCH_LIVE\",class=\"remote_congregation\"} 1"
+    ));
+    assert!(text.contains("ecofairness_slo_rejection_rate{slo=\"local\",category=\"eco\"} 0.1"));
+    assert!(text.contains("ecofairness_slo_availability{slo=\"local\"} 0.95"));
+    assert!(text.contains("ecofairness_slo_priority_uplift{class=\"local_congregation\"} 0"));
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    let duplicate = config(vec![spec("a", LOCAL), spec("a", REMOTE)]);
+    assert!(matches!(SloTracker::new(duplicate), Err(SloError::Duplicate(id)) if id == "a"));
+    let mut bad = spec("b", LOCAL);
+    bad.latency[0].percentile = 95.0;
+    assert!(matches!(SloTracker::new(config(vec![bad])), Err(SloError::Invalid { .. })));
+    let text = r#"{"slos": [{"id": "x", "route": "XR", "class": "host", "max_rejection_rate": {"eco": 0.01}}], "min_samples": 5}"#;
+    let cfg = SloConfig::from_json(text).unwrap();
+    assert_eq!((cfg.min_samples, cfg.window_secs), (5, 300));
+    assert!(SloConfig::from_json(r#"{"slos": [], "window": 60}"#).is_err());
+}
@@ -0,0 +1,218 @@
+use eco_units::{ComputeFraction, Joules, Watts};
+use ecofairness_guard::{
+    EcoFairnessConfig, EcoFairnessGuard, EquityBounds, GraceEquityKernel, ResourceUsageSnapshot, RohModel,
+    SnapshotBuilder, SnapshotBuilderConfig, SnapshotError, TsafeEcoEnvelope, UsageEvent, XRAction, XRActionKind,
+};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const T0: u64 = 1_700_000_000;
+
+fn cfg() -> SnapshotBuilderConfig {
+    SnapshotBuilderConfig {
+        total_power_budget: Watts::new(1000.0),
+        total_compute_capacity: 1000.0,
+        max_reading_age_secs: 30,
+    }
+}
+
+fn frozen(now: &Arc<AtomicU64>) -> impl Fn() -> u64 + Send + Sync + 'static {
+    let now = now.clone();
+    move || now.load(Ordering::SeqCst)
+}
+
+fn action(class: &str, cost: f32) -> XRAction {
+    XRAction {
+        kind: XRActionKind::ScheduleJob,
+        subjectid: "s".into(),
+        route: "AUTO_CHURCH_LIVE".into(),
+        lifeforcecost: cost,
+        rohbefore: 0.1,
+        rohafterestimate: 0.1,
+        equity_class: Some(class.into()),
+    }
+}
+
+/// What the gate used to do: rebuild the whole snapshot from the active set.
+fn aggregate(
+    cfg: &SnapshotBuilderConfig,
+    baseline: Watts,
+    energy: Joules,
+    active: &HashMap<String, (String, f32)>,
+) -> ResourceUsageSnapshot {
+    let mut class_power: HashMap<String, f64> = HashMap::new();
+    let mut compute = 0.0;
+    for (class, cost) in active.values() {
+        *class_power.entry(class.clone()).or_default() += f64::from(*cost);
+        compute += f64::from(*cost) / f64::from(cfg.total_compute_capacity.max(1.0));
+    }
+    let budget = cfg.total_power_budget.value().max(1.0);
+    ResourceUsageSnapshot {
+        total_power_budget: cfg.total_power_budget,
+        total_compute_capacity: cfg.total_compute_capacity,
+        current_power_draw: baseline + Watts::new(class_power.values().sum()),
+        current_cumulative_energy: energy,
+        current_compute_fraction: ComputeFraction::saturating(compute),
+        class_shares: class_power.into_iter().map(|(c, p)| (c, (p / budget) as f32)).collect(),
+        degraded: false,
+    }
+}
+
+fn assert_close(a: &ResourceUsageSnapshot, b: &ResourceUsageSnapshot) {
+    let near = |x: f64, y: f64| (x - y).abs() < 1e-6;
+    assert!(near(a.current_power_draw.value(), b.current_power_draw.value()), "{a:?} vs {b:?}");
+    assert!(near(a.current_cumulative_energy.value(), b.current_cumulative_energy.value()));
+    assert!(near(a.current_compute_fraction.value(), b.current_compute_fraction.value()));
+    assert_eq!(a.class_shares.len(), b.class_shares.len());
+    for (class, share) in &b.class_shares {
+        assert!((a.class_shares[class] - share).abs() < 1e-5, "{class}");
+    }
+    assert_eq!(a.degraded, b.degraded);
+}
+
+fn guard() -> EcoFairnessGuard {
+    let mut classes = HashMap::new();
+    classes.insert("host".to_string(), EquityBounds { min_share: 0.0, max_share: 0.5, description: None });
+    let mut envelopes = HashMap::new();
+    envelopes.insert(
+        "AUTO_CHURCH_LIVE".to_string(),
+        TsafeEcoEnvelope {
+            route: "AUTO_CHURCH_LIVE".into(),
+            max_power: Watts::new(500.0),
+            max_cumulative_energy: Joules::new(10_000.0),
+            max_compute_fraction: ComputeFraction::ONE,
+            ext: Default::default(),
+        },
+    );
+    EcoFairnessGuard::new(EcoFairnessConfig {
+        roh_model: RohModel { ceiling: 0.3, weights: HashMap::new() },
+        tsafe_envelopes: envelopes,
+        grace_equity: GraceEquityKernel {
+            classes,
+            resource_kind: "power_budget".into(),
+            normalization: "fraction_of_total".into(),
+            node_routes: HashMap::new(),
+            ext: Default::default(),
+        },
+    })
+}
+
+#[test]
+fn incremental_updates_match_from_scratch_aggregation() {
+    let now = Arc::new(AtomicU64::new(T0));
+    let builder = SnapshotBuilder::with_clock(cfg(), frozen(&now));
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let mut active: HashMap<String, (String, f32)> = HashMap::new();
+    let mut energy = Joules::ZERO;
+    let mut baseline = Watts::ZERO;
+
+    for step in 0..5_000 {
+        match rng.gen_range(0..10) {
+            0..=5 => {
+                let (id, class, cost) =
+                    (format!("a{step}"), format!("c{}", rng.gen_range(0..40)), rng.gen_range(0.1..5.0f32));
+                builder.commit(&id, &class, &action(&class, cost)).unwrap();
+                energy += Joules::new(f64::from(cost));
+                active.insert(id, (class, cost));
+            }
+            6..=8 if !active.is_empty() => {
+                let id = active.keys().next().cloned().unwrap();
+                builder.release(&id).unwrap();
+                active.remove(&id);
+            }
+            _ => {
+                baseline = Watts::new(rng.gen_range(0.0..200.0));
+                builder.record_power(baseline);
+            }
+        }
+        if step % 250 == 0 {
+            assert_close(&builder.current(), &aggregate(&cfg(), baseline, energy, &active));
+        }
+    }
+    assert_close(&builder.current(), &aggregate(&cfg(), baseline, energy, &active));
+
+    builder.apply(UsageEvent::WindowReset).unwrap();
+    assert_eq!(builder.current().current_cumulative_energy, Joules::ZERO);
+    assert_eq!(builder.release("never"), Err(SnapshotError::NotCommitted("never".into())));
+    let held = active.keys().next().cloned().unwrap();
+    assert_eq!(builder.commit(&held, "c0", &action("c0", 1.0)), Err(SnapshotError::AlreadyCommitted(held)));
+}
+
+#[test]
+fn views_stay_consistent_under_concurrent_updates() {
+    let builder = Arc::new(SnapshotBuilder::new(cfg()));
+    let updates = builder.subscribe();
+    let writers: Vec<_> = (0..4)
+        .map(|w| {
+            let builder = builder.clone();
+            std::thread::spawn(move || {
+                for i in 0..2_000 {
+                    let id = format!("w{w}-{i}");
+                    builder.commit(&id, &format!("c{}", i % 8), &action("c", 2.0)).unwrap();
+                    builder.release(&id).unwrap();
+                }
+            })
+        })
+        .collect();
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let builder = builder.clone();
+            std::thread::spawn(move || {
+                for _ in 0..2_000 {
+                    let view = builder.current();
+                    let before = serde_json::to_value(&*view).unwrap();
+                    // Every view is internally consistent: shares add up to the committed draw.
+                    let shares: f64 = view.class_shares.values().map(|s| f64::from(*s) * 1000.0).sum();
+                    assert!((shares - view.current_power_draw.value()).abs() < 1e-3, "{view:?}");
+                    std::thread::yield_now();
+                    assert_eq!(serde_json::to_value(&*view).unwrap(), before, "view changed while held");
+                }
+            })
+        })
+        .collect();
+    for t in writers.into_iter().chain(readers) {
+        t.join().unwrap();
+    }
+
+    let end = builder.current();
+    assert_eq!(end.current_power_draw, Watts::ZERO);
+    assert!(end.class_shares.is_empty());
+    assert_eq!(updates.try_iter().count(), 16_000);
+    drop(updates);
+    builder.record_power(Watts::new(5.0));
+}
+
+#[test]
+fn stale_power_readings_degrade_the_snapshot_and_tighten_the_guard() {
+    let now = Arc::new(AtomicU64::new(T0));
+    let builder = SnapshotBuilder::with_clock(cfg(), frozen(&now));
+    let guard = guard();
+    builder.record_power(Watts::new(150.0));
+    builder.commit("a", "host", &action("host", 100.0)).unwrap();
+    let request = action("host", 100.0);
+
+    // 250 W of 500 W and a 0.2 share of 0.5: admitted while fresh.
+    assert!(!builder.current().degraded);
+    assert!(guard.check_current(&request, &builder).is_ok());
+
+    now.store(T0 + 31, Ordering::SeqCst);
+    let view = builder.current();
+    assert!(view.degraded);
+    assert_eq!(guard.check_current(&request, &builder).unwrap_err().code, "ECO_POWER_EXCEEDED");
+    // Class shares are held to half of max_share too.
+    let crowded = ResourceUsageSnapshot {
+        current_power_draw: Watts::ZERO,
+        class_shares: HashMap::from([("host".into(), 0.245)]),
+        ..(*view).clone()
+    };
+    assert_eq!(guard.check(&action("host", 10.0), &crowded).unwrap_err().code, "ECO_EQUITY_MAX_EXCEEDED");
+    assert!(guard.check(&action("host", 10.0), &ResourceUsageSnapshot { degraded: false, ..crowded }).is_ok());
+
+    // A fresh reading clears the flag; the wire shape only carries it when set.
+    builder.record_power(Watts::new(150.0));
+    assert!(!builder.current().degraded);
+    assert!(serde_json::to_value(&*builder.current()).unwrap().get("degraded").is_none());
+    assert_eq!(serde_json::to_value(&*view).unwrap()["degraded"], true);
+}
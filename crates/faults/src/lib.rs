@@ -0,0 +1,105 @@
+//! Named fault points for chaos tests.
+//!
+//! Risky call sites ask `hit(point)` whether to misbehave this time. Built
+//! without the `chaos` feature, `hit` is an inlined `None` and every site
+//! compiles down to its normal path. With it, a test harness programs
+//! `FaultRule`s per point: fail, delay or corrupt, always, with a
+//! probability, or on chosen invocations. Delays are served inside `hit`;
+//! what a failure or corruption means is up to the site, and `points`
+//! lists the instrumented sites with their meaning.
+//!
+//! Rules decide from the seed, the point name and the invocation number
+//! alone, so one seed fires the same faults on the same invocations of
+//! each point however threads interleave between points. `session(seed)`
+//! fixes the seed and serializes tests sharing the process-wide registry;
+//! without one the seed comes from the clock and `seed()` reports it, so a
+//! failing run can be replayed.
+
+use std::time::Duration;
+
+#[cfg(feature = "chaos")]
+mod registry;
+
+#[cfg(feature = "chaos")]
+pub use registry::{clear, fired, hit, invocations, program, seed, session, Session};
+
+/// The instrumented call sites.
+pub mod points {
+    /// A deed handed to a deed sink. Fail: the sink refuses it. Corrupt:
+    /// the sink receives the deed with a garbled `self_hash`.
+    pub const SINK_WRITE: &str = "sink.write";
+    /// A deed committed to the ledger's chain, where a durable store would
+    /// sync it. Fail or Corrupt: the deed is on the chain but the sync is
+    /// reported failed.
+    pub const LEDGER_FSYNC: &str = "ledger.fsync";
+    /// The node clock behind authoritative timestamps. Fail: it reads the
+    /// Unix epoch. Corrupt(s): the reading is off by `s` seconds.
+    pub const CLOCK_READ: &str = "clock.read";
+    /// An eco-fairness spec being swapped in. Fail: the reload is refused.
+    /// Corrupt: the incoming spec arrives torn and must be rejected.
+    pub const SPEC_RELOAD: &str = "spec.reload";
+    /// The guardian taking its spec read lock; only delays apply.
+    pub const GUARD_LOCK: &str = "guard.lock";
+}
+
+/// What a call site is told to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    Fail,
+    /// Return corrupted data; the value parameterizes the corruption.
+    Corrupt(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Fail,
+    Delay(Duration),
+    Corrupt(i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    Always,
+    /// Each invocation independently, with this probability.
+    Probability(f64),
+    /// These invocations, counting from 1.
+    Invocations(Vec<u64>),
+}
+
+/// One programmed behaviour of a point; the first rule that fires wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultRule {
+    pub action: Action,
+    pub trigger: Trigger,
+}
+
+impl FaultRule {
+    pub fn fail() -> Self {
+        Self { action: Action::Fail, trigger: Trigger::Always }
+    }
+
+    pub fn delay(delay: Duration) -> Self {
+        Self { action: Action::Delay(delay), trigger: Trigger::Always }
+    }
+
+    pub fn corrupt(value: i64) -> Self {
+        Self { action: Action::Corrupt(value), trigger: Trigger::Always }
+    }
+
+    pub fn with_probability(mut self, probability: f64) -> Self {
+        self.trigger = Trigger::Probability(probability);
+        self
+    }
+
+    pub fn on_invocations(mut self, invocations: impl IntoIterator<Item = u64>) -> Self {
+        self.trigger = Trigger::Invocations(invocations.into_iter().collect());
+        self
+    }
+}
+
+/// Never faults: built without `chaos`.
+#[cfg(not(feature = "chaos"))]
+#[inline(always)]
+pub fn hit(_point: &str) -> Option<Fault> {
+    None
+}
@@ -0,0 +1,121 @@
+//! The process-wide rule registry behind `hit`.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Action, Fault, FaultRule, Trigger};
+
+#[derive(Default)]
+struct Point {
+    rules: Vec<FaultRule>,
+    invocations: u64,
+    fired: u64,
+}
+
+struct Registry {
+    seed: Option<u64>,
+    points: BTreeMap<String, Point>,
+}
+
+impl Registry {
+    fn seed(&mut self) -> u64 {
+        *self.seed.get_or_insert_with(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64)
+        })
+    }
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry { seed: None, points: BTreeMap::new() });
+static SESSION: Mutex<()> = Mutex::new(());
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Exclusive use of the registry with a fixed seed; rules and counts are
+/// cleared when it starts and when it is dropped.
+pub struct Session {
+    _exclusive: MutexGuard<'static, ()>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        clear();
+    }
+}
+
+/// Wait for any other session to end, then start a deterministic one.
+pub fn session(seed: u64) -> Session {
+    let exclusive = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let mut reg = registry();
+    reg.points.clear();
+    reg.seed = Some(seed);
+    Session { _exclusive: exclusive }
+}
+
+/// Add `rule` to `point` after any rules it already has.
+pub fn program(point: &str, rule: FaultRule) {
+    registry().points.entry(point.to_string()).or_default().rules.push(rule);
+}
+
+/// Drop every rule and count; the seed is kept.
+pub fn clear() {
+    registry().points.clear();
+}
+
+/// The seed schedules are drawn from.
+pub fn seed() -> u64 {
+    registry().seed()
+}
+
+/// Times `point` was reached since it was last cleared.
+pub fn invocations(point: &str) -> u64 {
+    registry().points.get(point).map_or(0, |p| p.invocations)
+}
+
+/// Times a rule of `point` fired, delays included.
+pub fn fired(point: &str) -> u64 {
+    registry().points.get(point).map_or(0, |p| p.fired)
+}
+
+/// Count an invocation of `point` and apply the first rule that fires.
+pub fn hit(point: &str) -> Option<Fault> {
+    let action = {
+        let mut reg = registry();
+        let seed = reg.seed();
+        let entry = reg.points.entry(point.to_string()).or_default();
+        entry.invocations += 1;
+        let n = entry.invocations;
+        let action = entry.rules.iter().enumerate().find(|(i, r)| fires(r, seed, point, *i, n)).map(|(_, r)| r.action);
+        entry.fired += u64::from(action.is_some());
+        action
+    };
+    match action? {
+        Action::Fail => Some(Fault::Fail),
+        Action::Corrupt(value) => Some(Fault::Corrupt(value)),
+        Action::Delay(delay) => {
+            std::thread::sleep(delay);
+            None
+        }
+    }
+}
+
+fn fires(rule: &FaultRule, seed: u64, point: &str, index: usize, n: u64) -> bool {
+    match &rule.trigger {
+        Trigger::Always => true,
+        Trigger::Probability(p) => draw(seed, point, index, n) < *p,
+        Trigger::Invocations(ns) => ns.contains(&n),
+    }
+}
+
+/// Uniform in [0, 1), from the seed, point, rule and invocation only.
+fn draw(seed: u64, point: &str, index: usize, n: u64) -> f64 {
+    let name = point.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+    let rule = (index as u64 + 1).wrapping_mul(0xd6e8_feb8_6659_fd93);
+    let mut z = seed ^ name ^ rule ^ n.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "chaos")]
+
+use std::time::{Duration, Instant};
+
+use faults::{fired, hit, invocations, program, session, Fault, FaultRule};
+
+#[test]
+fn invocation_rules_fire_exactly_there() {
+    let _chaos = session(1);
+    program("p", FaultRule::fail().on_invocations([2, 4]));
+    program("p", FaultRule::corrupt(-7).on_invocations([4, 5]));
+    let seen: Vec<_> = (0..6).map(|_| hit("p")).collect();
+    assert_eq!(seen, [None, Some(Fault::Fail), None, Some(Fault::Fail), Some(Fault::Corrupt(-7)), None]);
+    assert_eq!((invocations("p"), fired("p")), (6, 3));
+    assert_eq!(hit("unprogrammed"), None);
+}
+
+#[test]
+fn schedules_repeat_for_a_seed() {
+    let schedule = |seed| {
+        let _chaos = session(seed);
+        program("p", FaultRule::fail().with_probability(0.3));
+        (0..200).map(|_| hit("p").is_some()).collect::<Vec<_>>()
+    };
+    let first = schedule(42);
+    assert_eq!(first, schedule(42));
+    assert_ne!(first, schedule(43));
+    let failures = first.iter().filter(|f| **f).count();
+    assert!((30..90).contains(&failures), "{failures} of 200");
+}
+
+#[test]
+fn delays_are_served_inside_the_point() {
+    let _chaos = session(3);
+    program("slow", FaultRule::delay(Duration::from_millis(20)));
+    let start = Instant::now();
+    assert_eq!(hit("slow"), None);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert_eq!(fired("slow"), 1);
+}
+
+#[test]
+fn sessions_start_and_end_clean() {
+    {
+        let _chaos = session(4);
+        program("p", FaultRule::fail());
+        assert_eq!(hit("p"), Some(Fault::Fail));
+    }
+    let _chaos = session(4);
+    assert_eq!(invocations("p"), 0);
+    assert_eq!(hit("p"), None);
+}
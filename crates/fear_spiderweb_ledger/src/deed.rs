@@ -30,6 +30,7 @@ impl DeedEvent {
     pub fn compute_self_hash(&self) -> String {
         // Canonical JSON + SHA-256 (mirrors .donutloop.aln)
         let canonical = serde_json::to_string(&self).unwrap(); // fixed order in prod
+        use sha2::Digest;
         let mut hasher = sha2::Sha256::new();
         hasher.update(canonical.as_bytes());
         hex::encode(hasher.finalize())
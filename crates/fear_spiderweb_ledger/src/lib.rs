@@ -0,0 +1,3 @@
+pub mod deed;
+pub mod render;
+pub mod spiderweb;
@@ -0,0 +1,337 @@
+//! Bounded Dot/Mermaid rendering of a FearWeb.
+//!
+//! A `WebFilter` first narrows the web: a time range, a set of actors, a
+//! minimum edge weight and a k-hop neighbourhood around a focus deed. If
+//! what is left still has more nodes than the filter's `node_budget`, the
+//! lighter nodes are folded into summary super-nodes: the strongest nodes
+//! (by the total weight of their kept edges) stay, the rest are grouped by
+//! actor, and each group becomes one node labelled with its deed count.
+//! Edges into a group are merged and labelled with how many they stand for;
+//! edges inside a group are not drawn. The output never has more nodes
+//! than the budget (budgets below two count as two).
+//!
+//! Rendering writes straight to any `io::Write`; the `String` forms are the
+//! same bytes collected in memory. Node and edge order follow the web's
+//! indices and ties are broken by event id, so a filter always renders the
+//! same web the same way.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use petgraph::graph::{EdgeIndex, EdgeReference, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use uuid::Uuid;
+
+use crate::spiderweb::FearWeb;
+
+/// Node budget of a default filter, and of the documentation's diagram.
+pub const DEFAULT_NODE_BUDGET: usize = 200;
+
+/// Which part of the web to render, and how much of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebFilter {
+    /// Inclusive lower bound on deed timestamps.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on deed timestamps.
+    pub until: Option<DateTime<Utc>>,
+    /// Only these actors' deeds; empty means every actor.
+    pub actors: BTreeSet<String>,
+    /// Edges lighter than this are dropped.
+    pub min_edge_weight: f32,
+    /// Only deeds within `hops` kept edges of this deed, in either direction.
+    pub focus: Option<(Uuid, usize)>,
+    /// Most nodes the output may have before summarization.
+    pub node_budget: usize,
+}
+
+impl Default for WebFilter {
+    fn default() -> Self {
+        Self {
+            from: None,
+            until: None,
+            actors: BTreeSet::new(),
+            min_edge_weight: f32::NEG_INFINITY,
+            focus: None,
+            node_budget: DEFAULT_NODE_BUDGET,
+        }
+    }
+}
+
+impl WebFilter {
+    /// The whole web, never summarized.
+    pub fn unbounded() -> Self {
+        Self { node_budget: usize::MAX, ..Self::default() }
+    }
+
+    pub fn between(mut self, from: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self.until = Some(until);
+        self
+    }
+
+    pub fn actor(mut self, actor_id: &str) -> Self {
+        self.actors.insert(actor_id.to_string());
+        self
+    }
+
+    pub fn min_weight(mut self, weight: f32) -> Self {
+        self.min_edge_weight = weight;
+        self
+    }
+
+    pub fn around(mut self, event_id: Uuid, hops: usize) -> Self {
+        self.focus = Some((event_id, hops));
+        self
+    }
+
+    pub fn budget(mut self, nodes: usize) -> Self {
+        self.node_budget = nodes;
+        self
+    }
+}
+
+/// What a render kept, summarized and left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub web_nodes: usize,
+    pub web_edges: usize,
+    /// Nodes and edges left after filtering, before summarization.
+    pub matched_nodes: usize,
+    pub matched_edges: usize,
+    pub rendered_nodes: usize,
+    pub rendered_edges: usize,
+    /// Matched nodes folded into summary nodes.
+    pub summarized_nodes: usize,
+    pub summary_nodes: usize,
+    /// Matched edges between two nodes of the same summary, not drawn.
+    pub hidden_edges: usize,
+}
+
+impl RenderStats {
+    /// Whether summarization dropped anything the filter matched.
+    pub fn summarized(&self) -> bool {
+        self.summarized_nodes > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Slot {
+    Deed(NodeIndex),
+    Summary(usize),
+}
+
+struct Summary {
+    label: String,
+    deeds: usize,
+}
+
+/// The filtered, summarized graph, holding indices rather than text.
+struct Plan {
+    deeds: Vec<NodeIndex>,
+    summaries: Vec<Summary>,
+    /// Kept edges between two deeds, in edge order.
+    edges: Vec<EdgeIndex>,
+    /// Merged edges touching a summary: total weight and how many.
+    merged: BTreeMap<(Slot, Slot), (f32, usize)>,
+    stats: RenderStats,
+}
+
+fn matches(web: &FearWeb, n: NodeIndex, filter: &WebFilter) -> bool {
+    let deed = &web[n];
+    filter.from.is_none_or(|from| deed.timestamp >= from)
+        && filter.until.is_none_or(|until| deed.timestamp < until)
+        && (filter.actors.is_empty() || filter.actors.contains(&deed.actor_id))
+}
+
+/// Matched nodes (in index order) and the edges between them.
+fn select(web: &FearWeb, filter: &WebFilter) -> (Vec<NodeIndex>, Vec<EdgeIndex>) {
+    let mut keep: Vec<bool> = web.node_indices().map(|n| matches(web, n, filter)).collect();
+    let edge_kept = |keep: &[bool], e: EdgeReference<f32>| {
+        *e.weight() >= filter.min_edge_weight && keep[e.source().index()] && keep[e.target().index()]
+    };
+    if let Some((event_id, hops)) = filter.focus {
+        let focus = web.node_indices().find(|&n| web[n].event_id == event_id && keep[n.index()]);
+        let mut near = vec![false; keep.len()];
+        let mut queue = VecDeque::new();
+        if let Some(focus) = focus {
+            near[focus.index()] = true;
+            queue.push_back((focus, 0));
+        }
+        while let Some((n, depth)) = queue.pop_front() {
+            if depth == hops {
+                continue;
+            }
+            let out = web.edges_directed(n, Direction::Outgoing).map(|e| (e.target(), e));
+            let inc = web.edges_directed(n, Direction::Incoming).map(|e| (e.source(), e));
+            for (m, e) in out.chain(inc) {
+                if edge_kept(&keep, e) && !near[m.index()] {
+                    near[m.index()] = true;
+                    queue.push_back((m, depth + 1));
+                }
+            }
+        }
+        keep = near;
+    }
+    let nodes = web.node_indices().filter(|n| keep[n.index()]).collect();
+    let edges = web.edge_references().filter(|&e| edge_kept(&keep, e)).map(|e| e.id()).collect();
+    (nodes, edges)
+}
+
+fn plan(web: &FearWeb, filter: &WebFilter) -> Plan {
+    let (nodes, edges) = select(web, filter);
+    let mut stats = RenderStats {
+        web_nodes: web.node_count(),
+        web_edges: web.edge_count(),
+        matched_nodes: nodes.len(),
+        matched_edges: edges.len(),
+        ..RenderStats::default()
+    };
+    let budget = filter.node_budget.max(2);
+    if nodes.len() <= filter.node_budget {
+        stats.rendered_nodes = nodes.len();
+        stats.rendered_edges = edges.len();
+        return Plan { deeds: nodes, summaries: Vec::new(), edges, merged: BTreeMap::new(), stats };
+    }
+
+    // Keep the strongest three quarters of the budget; the focus always stays.
+    let mut strength: HashMap<NodeIndex, f32> = HashMap::new();
+    for &e in &edges {
+        let (a, b) = web.edge_endpoints(e).expect("kept edge");
+        *strength.entry(a).or_default() += web[e].abs();
+        *strength.entry(b).or_default() += web[e].abs();
+    }
+    let focus = filter.focus.map(|(id, _)| id);
+    let mut ranked = nodes.clone();
+    ranked.sort_by(|&a, &b| {
+        let key = |n: NodeIndex| (Some(web[n].event_id) == focus, strength.get(&n).copied().unwrap_or_default());
+        let (fa, sa) = key(a);
+        let (fb, sb) = key(b);
+        fb.cmp(&fa).then(sb.total_cmp(&sa)).then(web[a].event_id.cmp(&web[b].event_id))
+    });
+    let kept_count = (budget * 3 / 4).max(1);
+    let kept: BTreeSet<NodeIndex> = ranked[..kept_count].iter().copied().collect();
+
+    // Group the rest by actor, largest groups first; the groups past the
+    // budget share one last summary.
+    let mut groups: BTreeMap<&str, Vec<NodeIndex>> = BTreeMap::new();
+    for &n in nodes.iter().filter(|n| !kept.contains(n)) {
+        groups.entry(web[n].actor_id.as_str()).or_default().push(n);
+    }
+    let mut groups: Vec<(&str, Vec<NodeIndex>)> = groups.into_iter().collect();
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+    let own = budget - kept_count - 1;
+    let mut slot: HashMap<NodeIndex, Slot> = kept.iter().map(|&n| (n, Slot::Deed(n))).collect();
+    let mut summaries = Vec::new();
+    let fits = groups.len() <= own + 1;
+    let (named, rest) = groups.split_at(if fits { groups.len() } else { own });
+    for (actor, members) in named {
+        slot.extend(members.iter().map(|&n| (n, Slot::Summary(summaries.len()))));
+        summaries.push(Summary { label: format!("{}: {} deeds", actor, members.len()), deeds: members.len() });
+    }
+    if !rest.is_empty() {
+        let deeds = rest.iter().map(|(_, m)| m.len()).sum();
+        for (_, members) in rest {
+            slot.extend(members.iter().map(|&n| (n, Slot::Summary(summaries.len()))));
+        }
+        summaries.push(Summary { label: format!("{} other actors: {} deeds", rest.len(), deeds), deeds });
+    }
+
+    let mut direct = Vec::new();
+    let mut merged: BTreeMap<(Slot, Slot), (f32, usize)> = BTreeMap::new();
+    for &e in &edges {
+        let (a, b) = web.edge_endpoints(e).expect("kept edge");
+        match (slot[&a], slot[&b]) {
+            (Slot::Deed(_), Slot::Deed(_)) => direct.push(e),
+            (sa, sb) if sa == sb => stats.hidden_edges += 1,
+            (sa, sb) => {
+                let entry = merged.entry((sa, sb)).or_default();
+                entry.0 += web[e];
+                entry.1 += 1;
+            }
+        }
+    }
+    stats.summarized_nodes = nodes.len() - kept.len();
+    stats.summary_nodes = summaries.len();
+    stats.rendered_nodes = kept.len() + summaries.len();
+    stats.rendered_edges = direct.len() + merged.len();
+    Plan { deeds: kept.into_iter().collect(), summaries, edges: direct, merged, stats }
+}
+
+fn deed_label(web: &FearWeb, n: NodeIndex) -> String {
+    format!("{}\n{}", web[n].deed_type, web[n].actor_id)
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+fn slot_id(slot: Slot) -> String {
+    match slot {
+        Slot::Deed(n) => format!("n{}", n.index()),
+        Slot::Summary(i) => format!("s{}", i),
+    }
+}
+
+/// Write the filtered web as a Graphviz digraph.
+pub fn write_dot<W: Write>(web: &FearWeb, filter: &WebFilter, out: &mut W) -> io::Result<RenderStats> {
+    let plan = plan(web, filter);
+    writeln!(out, "digraph FearWeb {{")?;
+    for &n in &plan.deeds {
+        let label = dot_escape(&deed_label(web, n));
+        writeln!(out, "  n{} [label=\"{}\", fear={:.2}];", n.index(), label, web[n].fear_level)?;
+    }
+    for (i, s) in plan.summaries.iter().enumerate() {
+        writeln!(out, "  s{} [label=\"{}\", shape=box, style=dashed, deeds={}];", i, dot_escape(&s.label), s.deeds)?;
+    }
+    for &e in &plan.edges {
+        let (a, b) = web.edge_endpoints(e).expect("kept edge");
+        writeln!(out, "  n{} -> n{} [weight={:.2}];", a.index(), b.index(), web[e])?;
+    }
+    for (&(a, b), &(weight, count)) in &plan.merged {
+        writeln!(out, "  {} -> {} [weight={:.2}, label=\"x{}\", style=dashed];", slot_id(a), slot_id(b), weight, count)?;
+    }
+    writeln!(out, "}}")?;
+    Ok(plan.stats)
+}
+
+/// Write the filtered web as a Mermaid flowchart.
+pub fn write_mermaid<W: Write>(web: &FearWeb, filter: &WebFilter, out: &mut W) -> io::Result<RenderStats> {
+    let plan = plan(web, filter);
+    writeln!(out, "graph LR")?;
+    for &n in &plan.deeds {
+        writeln!(out, "  n{}[\"{}\"]", n.index(), mermaid_escape(&deed_label(web, n)))?;
+    }
+    for (i, s) in plan.summaries.iter().enumerate() {
+        writeln!(out, "  s{}[[\"{}\"]]", i, mermaid_escape(&s.label))?;
+    }
+    for &e in &plan.edges {
+        let (a, b) = web.edge_endpoints(e).expect("kept edge");
+        writeln!(out, "  n{} -->|{:.2}| n{}", a.index(), web[e], b.index())?;
+    }
+    for (&(a, b), &(weight, count)) in &plan.merged {
+        writeln!(out, "  {} -.->|{:.2} x{}| {}", slot_id(a), weight, count, slot_id(b))?;
+    }
+    Ok(plan.stats)
+}
+
+fn collect(write: impl FnOnce(&mut Vec<u8>) -> io::Result<RenderStats>) -> (String, RenderStats) {
+    let mut buf = Vec::new();
+    let stats = write(&mut buf).expect("writing to memory cannot fail");
+    (String::from_utf8(buf).expect("rendered output is UTF-8"), stats)
+}
+
+/// `write_dot` into a `String`.
+pub fn render_dot(web: &FearWeb, filter: &WebFilter) -> (String, RenderStats) {
+    collect(|buf| write_dot(web, filter, buf))
+}
+
+/// `write_mermaid` into a `String`.
+pub fn render_mermaid(web: &FearWeb, filter: &WebFilter) -> (String, RenderStats) {
+    collect(|buf| write_mermaid(web, filter, buf))
+}
@@ -1,7 +1,9 @@
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use std::collections::HashMap;
+use uuid::Uuid;
 use crate::deed::DeedEvent;
+use crate::render::{self, RenderStats, WebFilter};
 
 pub type FearWeb = DiGraph<DeedEvent, f32>; // edge weight = FEAR impact
 
@@ -37,9 +39,44 @@ impl SpiderwebAnalyzer {
         doc.push_str("Bees: collective recovery corridors & pollination of good deeds.\n");
         doc.push_str("Birds: song of freedom propagating CALMSTABLE zones.\n\n");
         // Add graph stats, stable zones, eco_grant recommendations
+        let (mermaid, stats) = self.export_mermaid_filtered(WebFilter::default());
+        doc.push_str("## The Web\n```mermaid\n");
+        doc.push_str(&mermaid);
+        doc.push_str("```\n\n");
+        doc.push_str(&elision_note(&stats));
         doc
     }
 
     // Export DOT for visualization (Graphviz) or plotters image
-    pub fn export_dot(&self) -> String { /* ... */ "digraph FearWeb { ... }".to_string() }
+    pub fn export_dot(&self) -> String {
+        self.export_dot_filtered(WebFilter::unbounded()).0
+    }
+
+    // Bounded export: filter, then summarize down to the filter's node budget
+    pub fn export_dot_filtered(&self, filter: WebFilter) -> (String, RenderStats) {
+        render::render_dot(&self.web, &filter)
+    }
+
+    pub fn export_mermaid_filtered(&self, filter: WebFilter) -> (String, RenderStats) {
+        render::render_mermaid(&self.web, &filter)
+    }
+}
+
+// Plain statement of what a rendered diagram leaves out
+fn elision_note(stats: &RenderStats) -> String {
+    if !stats.summarized() {
+        return format!(
+            "Shown in full: {} deeds and {} links.\n",
+            stats.rendered_nodes, stats.rendered_edges
+        );
+    }
+    format!(
+        "Shown: {} of {} deeds. {} deeds are folded into {} summary nodes (dashed, labelled with their deed counts); \
+         {} links between deeds of the same summary are not drawn, and links into summaries are merged.\n",
+        stats.rendered_nodes - stats.summary_nodes,
+        stats.web_nodes,
+        stats.summarized_nodes,
+        stats.summary_nodes,
+        stats.hidden_edges,
+    )
 }
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use fear_spiderweb_ledger::deed::DeedEvent;
+use fear_spiderweb_ledger::render::{self, WebFilter};
+use fear_spiderweb_ledger::spiderweb::SpiderwebAnalyzer;
+use petgraph::graph::NodeIndex;
+use uuid::Uuid;
+
+fn epoch() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+}
+
+fn deed(n: u128, actor: &str, minutes: i64) -> DeedEvent {
+    DeedEvent {
+        event_id: Uuid::from_u128(n + 1),
+        timestamp: epoch() + Duration::minutes(minutes),
+        prev_hash: String::new(),
+        self_hash: String::new(),
+        actor_id: actor.to_string(),
+        target_ids: Vec::new(),
+        deed_type: "emission".to_string(),
+        tags: Vec::new(),
+        context_json: serde_json::Value::Null,
+        ethics_flags: Vec::new(),
+        life_harm_flag: false,
+        fear_level: 0.5,
+        pain_level: 0.0,
+        decay: 0.0,
+        lifeforce: 1.0,
+        calm_stable: false,
+        overloaded: false,
+        recovery: false,
+        unfair_drain: false,
+    }
+}
+
+/// a -> b -> c -> d -> e, one minute apart, with a light side edge a -> e.
+fn chain() -> (SpiderwebAnalyzer, Vec<NodeIndex>) {
+    let mut web = SpiderwebAnalyzer::new();
+    let actors = ["alice", "bob", "alice", "carol", "bob"];
+    let nodes: Vec<NodeIndex> =
+        actors.iter().enumerate().map(|(i, a)| web.add_deed(deed(i as u128, a, i as i64))).collect();
+    for pair in nodes.windows(2) {
+        web.web.add_edge(pair[0], pair[1], 1.0);
+    }
+    web.web.add_edge(nodes[0], nodes[4], 0.1);
+    (web, nodes)
+}
+
+/// `count` deeds by `actors` actors, each caused by up to two earlier deeds.
+fn large(count: usize, actors: usize) -> SpiderwebAnalyzer {
+    let mut web = SpiderwebAnalyzer::new();
+    let mut nodes = Vec::with_capacity(count);
+    for i in 0..count {
+        let actor = format!("actor-{}", (i * 7919) % actors);
+        nodes.push(web.add_deed(deed(i as u128, &actor, i as i64)));
+        for step in [1, 37] {
+            if i >= step {
+                let weight = ((i * 31 + step) % 100) as f32 / 100.0;
+                web.web.add_edge(nodes[i - step], nodes[i], weight);
+            }
+        }
+    }
+    web
+}
+
+#[test]
+fn k_hop_and_weight_filters_select_the_neighbourhood() {
+    let (web, nodes) = chain();
+    let c = web.web[nodes[2]].event_id;
+
+    let (_, stats) = web.export_dot_filtered(WebFilter::unbounded().around(c, 1));
+    assert_eq!((stats.matched_nodes, stats.matched_edges), (3, 2));
+
+    // The light a -> e edge brings e within two hops of c until it is dropped.
+    let (dot, stats) = web.export_dot_filtered(WebFilter::unbounded().around(c, 2));
+    assert_eq!((stats.matched_nodes, stats.matched_edges), (5, 5));
+    assert!(dot.contains("n0 -> n4"));
+    let (dot, stats) = web.export_dot_filtered(WebFilter::unbounded().around(c, 2).min_weight(0.5));
+    assert_eq!((stats.matched_nodes, stats.matched_edges), (5, 4));
+    assert!(!dot.contains("n0 -> n4"));
+    let (_, stats) = web.export_dot_filtered(WebFilter::unbounded().around(c, 1).min_weight(0.5));
+    assert_eq!(stats.matched_nodes, 3);
+
+    // Actor and time filters narrow the web before the hops are counted.
+    let (_, stats) = web.export_dot_filtered(WebFilter::unbounded().actor("alice").actor("bob"));
+    assert_eq!((stats.matched_nodes, stats.matched_edges), (4, 3));
+    let (_, stats) =
+        web.export_dot_filtered(WebFilter::unbounded().between(epoch() + Duration::minutes(1), epoch() + Duration::minutes(4)));
+    assert_eq!((stats.matched_nodes, stats.matched_edges), (3, 2));
+    let (dot, stats) = web.export_dot_filtered(WebFilter::unbounded().actor("carol").around(c, 3));
+    assert_eq!(stats.matched_nodes, 0);
+    assert_eq!(dot, "digraph FearWeb {\n}\n");
+}
+
+#[test]
+fn summarization_stays_within_budget_and_counts_what_it_folds() {
+    let web = large(2_000, 12);
+    let filter = WebFilter::default().budget(40);
+    let (dot, stats) = web.export_dot_filtered(filter.clone());
+
+    assert_eq!(stats.matched_nodes, 2_000);
+    assert_eq!(stats.rendered_nodes, 40);
+    assert_eq!(stats.summarized_nodes, 2_000 - 30);
+    assert_eq!(stats.summary_nodes, 10);
+    // Nine actors keep their own summary; the other three share the last one.
+    assert!(dot.contains("3 other actors:"));
+    let folded: usize = dot
+        .lines()
+        .filter(|l| l.contains("shape=box"))
+        .map(|l| l.split("deeds=").nth(1).unwrap().trim_end_matches("];").parse::<usize>().unwrap())
+        .sum();
+    assert_eq!(folded, stats.summarized_nodes);
+    assert_eq!(dot.lines().filter(|l| l.contains("[label=")).count(), stats.rendered_nodes);
+    assert_eq!(dot.lines().filter(|l| l.contains(" -> ")).count(), stats.rendered_edges);
+
+    // Every matched edge is drawn, merged into a summary edge, or hidden.
+    let merged: usize = dot
+        .lines()
+        .filter_map(|l| l.split("label=\"x").nth(1))
+        .map(|l| l.split('"').next().unwrap().parse::<usize>().unwrap())
+        .sum();
+    let direct = dot.lines().filter(|l| l.contains(" -> ") && !l.contains("label=\"x")).count();
+    assert_eq!(direct + merged + stats.hidden_edges, stats.matched_edges);
+
+    // The focus is always one of the deeds kept.
+    let focus = web.web[NodeIndex::new(1_234)].event_id;
+    let (dot, _) = web.export_dot_filtered(filter.around(focus, 2_000));
+    assert!(dot.contains("  n1234 [label="));
+}
+
+#[test]
+fn streaming_output_matches_in_memory_output() {
+    let web = large(3_000, 25);
+    let filter = WebFilter::default().min_weight(0.2).budget(64);
+
+    let mut dot = Vec::new();
+    let streamed = render::write_dot(&web.web, &filter, &mut dot).unwrap();
+    let (in_memory, stats) = web.export_dot_filtered(filter.clone());
+    assert_eq!(String::from_utf8(dot).unwrap(), in_memory);
+    assert_eq!(streamed, stats);
+
+    let mut mermaid = Vec::new();
+    render::write_mermaid(&web.web, &filter, &mut mermaid).unwrap();
+    let (in_memory, _) = web.export_mermaid_filtered(filter.clone());
+    assert_eq!(String::from_utf8(mermaid).unwrap(), in_memory);
+    assert!(in_memory.starts_with("graph LR\n"));
+
+    // Rendering twice gives the same bytes.
+    assert_eq!(web.export_mermaid_filtered(filter).0, in_memory);
+}
+
+#[test]
+fn fifty_thousand_node_web_renders_within_budget() {
+    let web = large(50_000, 400);
+    let (dot, stats) = web.export_dot_filtered(WebFilter::default());
+    assert_eq!(stats.web_nodes, 50_000);
+    assert!(stats.rendered_nodes <= render::DEFAULT_NODE_BUDGET);
+    // Each node and edge line is well under 200 bytes.
+    assert!(stats.rendered_edges <= render::DEFAULT_NODE_BUDGET * render::DEFAULT_NODE_BUDGET);
+    assert!(dot.len() < 200 * (stats.rendered_nodes + stats.rendered_edges) + 64, "{} bytes", dot.len());
+    assert!(dot.len() < 2_000_000, "{} bytes", dot.len());
+
+    let doc = web.generate_documentation();
+    assert!(doc.contains("```mermaid\ngraph LR\n"));
+    assert!(doc.contains(&format!("Shown: {} of 50000 deeds.", stats.rendered_nodes - stats.summary_nodes)));
+    assert!(doc.len() < 2_000_000);
+}
+
+#[test]
+fn small_webs_render_in_full() {
+    let (web, _) = chain();
+    let doc = web.generate_documentation();
+    assert!(doc.contains("Shown in full: 5 deeds and 5 links."));
+    assert!(web.export_dot().contains("n3 -> n4 [weight=1.00];"));
+}
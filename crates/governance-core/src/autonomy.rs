@@ -2,8 +2,11 @@
 
 use std::time::SystemTime;
 
+use param_registry::{ParamKey, ParamRegistry};
+
 use crate::deed_log::{DeedEvent, DeedEventKind};
 use crate::ids::{UpgradeId, MicrospaceId, JurisdictionId};
+use crate::microspace::MicrospaceRegistry;
 use crate::policy::{ReversalPolicy, RoleId, RoleSet};
 use crate::proofs::{ProofClass, ProofHandle};
 use crate::risk::{IncidentStats, RiskBand};
@@ -133,6 +136,9 @@ pub struct SettlementDecision {
     pub reason: Option<String>,
     pub new_tier: AutonomyTier,
     pub new_nonrollback: NonRollbackStatus,
+    /// Shard hash of the microspace registry the request was checked
+    /// against, recorded with the settlement deed.
+    pub microspace_registry: Option<String>,
 }
 
 impl SettlementDecision {
@@ -142,6 +148,7 @@ impl SettlementDecision {
             reason: Some(reason.into()),
             new_tier: AutonomyTier::SimulationOnly,
             new_nonrollback: NonRollbackStatus::Experimental,
+            microspace_registry: None,
         }
     }
 
@@ -154,6 +161,7 @@ impl SettlementDecision {
             reason: None,
             new_tier: tier,
             new_nonrollback: nonrollback,
+            microspace_registry: None,
         }
     }
 }
@@ -166,6 +174,35 @@ impl SettlementDecision {
 /// and ethics are proven by policy and usage.
 pub fn can_settle_to_nonrollback(
     req: &SettlementRequest,
+) -> SettlementDecision {
+    can_settle_to_nonrollback_with(&ParamRegistry::default(), req)
+}
+
+/// `can_settle_to_nonrollback` with the observation horizon read from
+/// `params`.
+pub fn can_settle_to_nonrollback_with(
+    params: &ParamRegistry,
+    req: &SettlementRequest,
+) -> SettlementDecision {
+    settle(params, None, req)
+}
+
+/// `can_settle_to_nonrollback_with`, checking the touched microspaces
+/// against `registry`: every jurisdiction they (or anything containing
+/// them) belong to must co-approve, and a high-sensitivity microspace
+/// among them lengthens the required observation horizon.
+pub fn can_settle_to_nonrollback_in(
+    params: &ParamRegistry,
+    registry: &MicrospaceRegistry,
+    req: &SettlementRequest,
+) -> SettlementDecision {
+    settle(params, Some(registry), req)
+}
+
+fn settle(
+    params: &ParamRegistry,
+    registry: Option<&MicrospaceRegistry>,
+    req: &SettlementRequest,
 ) -> SettlementDecision {
     // 1. NonRollbackStatus must only move forward, never backward here.
     if matches!(
@@ -217,10 +254,12 @@ pub fn can_settle_to_nonrollback(
     }
 
     // 5. Require sufficient observation horizon and low incident rate.
-    if req.evidence.observation_horizon_days < 90 {
-        return SettlementDecision::denied(
-            "Observation horizon too short; require ≥ 90 days of field data.",
-        );
+    let horizon_days = params.get(ParamKey::ObservationHorizon).as_secs() / 86_400;
+    if u64::from(req.evidence.observation_horizon_days) < horizon_days {
+        return SettlementDecision::denied(format!(
+            "Observation horizon too short; require ≥ {} days of field data.",
+            horizon_days
+        ));
     }
     if !req.evidence.incidents_within_ceiling() {
         return SettlementDecision::denied(
@@ -238,15 +277,44 @@ pub fn can_settle_to_nonrollback(
     // 7. Jurisdiction and microspace alignment: all microspaces touched by this
     // behavior must be explicitly listed, and all relevant jurisdictions must
     // be part of the ALN shard validated by external auditors (checked in ALN).
+    // With a microspace registry, the jurisdictions are also derived from the
+    // microspaces and their ancestors, and every one must be listed.
     if req.microspaces.is_empty() || req.jurisdictions.is_empty() {
         return SettlementDecision::denied(
             "Microspaces and jurisdictions must be explicitly enumerated.",
         );
     }
+    if let Some(registry) = registry {
+        let missing = match registry.uncovered_jurisdictions(&req.microspaces, &req.jurisdictions) {
+            Ok(missing) => missing.0,
+            Err(e) => return SettlementDecision::denied(e.to_string()),
+        };
+        if !missing.is_empty() {
+            let missing: Vec<&str> = missing.iter().map(|j| j.as_str()).collect();
+            return SettlementDecision::denied(format!(
+                "Touched microspaces also belong to {}; those jurisdictions must co-approve.",
+                missing.join(", ")
+            ));
+        }
+
+        // 8. Sensitive microspaces (or ones inside them) need a longer
+        // field record.
+        let required_days = match registry.sensitivity_ceiling(&req.microspaces) {
+            Ok(ceiling) => ceiling.required_horizon_days(horizon_days),
+            Err(e) => return SettlementDecision::denied(e.to_string()),
+        };
+        if u64::from(req.evidence.observation_horizon_days) < required_days {
+            return SettlementDecision::denied(format!(
+                "Touched microspaces are highly sensitive; require ≥ {} days of field data.",
+                required_days
+            ));
+        }
+    }
 
     // If all checks pass, allow the requested tier and non-rollback status.
-    let decision =
+    let mut decision =
         SettlementDecision::approved(req.requested_tier, req.requested_nonrollback);
+    decision.microspace_registry = registry.map(|r| r.shard_hash().to_string());
 
     // Emit a DeedEvent for the audit log.
     let _deed = DeedEvent::new(
@@ -258,8 +326,10 @@ pub fn can_settle_to_nonrollback(
         req.proofs.clone(),
         req.assembled_at,
     );
-    // In a full implementation, this DeedEvent would be persisted and may be
-    // anchored to Googolswarm / Cybernet as an immutable audit record.
+    // In a full implementation, this DeedEvent would be persisted, carrying
+    // `decision.microspace_registry` so auditors can tell which registry
+    // shard the coverage check used, and may be anchored to Googolswarm /
+    // Cybernet as an immutable audit record.
 
     decision
 }
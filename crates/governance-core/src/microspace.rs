@@ -0,0 +1,359 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::ids::{JurisdictionId, MicrospaceId};
+
+/// What a microspace physically is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicrospaceKind {
+    Tissue,
+    AquiferCell,
+    Node,
+    Facility,
+}
+
+/// How carefully a behavior touching the microspace must be evidenced.
+/// Ordered from least to most sensitive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitivityClass {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl SensitivityClass {
+    /// Observation horizon a settlement touching this class must cover,
+    /// given the horizon required of ordinary behaviors.
+    pub fn required_horizon_days(self, base_days: u64) -> u64 {
+        match self {
+            SensitivityClass::Low | SensitivityClass::Moderate => base_days,
+            SensitivityClass::High => base_days * 2,
+            SensitivityClass::Critical => base_days * 3,
+        }
+    }
+}
+
+/// One registered microspace.
+#[derive(Clone, Debug)]
+pub struct Microspace {
+    pub id: MicrospaceId,
+    pub kind: MicrospaceKind,
+    /// Microspace this one sits inside, if any.
+    pub parent: Option<MicrospaceId>,
+    /// Owning jurisdiction, resolved through the ancestry when the shard
+    /// only declares it on an ancestor.
+    pub jurisdiction: JurisdictionId,
+    pub sensitivity: SensitivityClass,
+}
+
+/// Why a shard was refused or a query could not be answered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// The shard is not valid JSON of the expected shape.
+    Parse(String),
+    DuplicateMicrospace(String),
+    UnknownParent { microspace: String, parent: String },
+    /// Containment loops back on itself; the ids along the loop.
+    Cycle(Vec<String>),
+    /// Neither the microspace nor any ancestor names a jurisdiction.
+    NoJurisdiction(String),
+    /// The microspace names a jurisdiction other than its ancestor's.
+    ConflictingJurisdiction { microspace: String, declared: String, inherited: String },
+    UnknownMicrospace(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Parse(e) => write!(f, "microspace shard is malformed: {}", e),
+            RegistryError::DuplicateMicrospace(id) => write!(f, "microspace {} is listed twice", id),
+            RegistryError::UnknownParent { microspace, parent } => {
+                write!(f, "microspace {} sits inside unknown microspace {}", microspace, parent)
+            }
+            RegistryError::Cycle(ids) => write!(f, "microspace containment loops: {}", ids.join(" -> ")),
+            RegistryError::NoJurisdiction(id) => write!(f, "microspace {} maps to no jurisdiction", id),
+            RegistryError::ConflictingJurisdiction { microspace, declared, inherited } => write!(
+                f,
+                "microspace {} names jurisdiction {} inside {}",
+                microspace, declared, inherited
+            ),
+            RegistryError::UnknownMicrospace(id) => write!(f, "microspace {} is not registered", id),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Jurisdictions a settlement touches but does not list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingJurisdictions(pub Vec<JurisdictionId>);
+
+#[derive(Deserialize)]
+struct Shard {
+    microspaces: Vec<ShardEntry>,
+}
+
+#[derive(Deserialize)]
+struct ShardEntry {
+    id: String,
+    kind: MicrospaceKind,
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    jurisdiction: Option<String>,
+    sensitivity: SensitivityClass,
+}
+
+/// Microspaces, their containment and owning jurisdictions, loaded from an
+/// ALN shard.
+///
+/// A behavior touching a microspace also touches everything containing it,
+/// so every query works on the touched microspaces together with their
+/// ancestors. The SHA-256 of the shard a registry was loaded from is kept,
+/// so a settlement can record which registry it was checked against.
+#[derive(Clone, Debug)]
+pub struct MicrospaceRegistry {
+    microspaces: BTreeMap<String, Microspace>,
+    shard_hash: String,
+}
+
+impl MicrospaceRegistry {
+    /// Load a shard: `{"microspaces": [{"id", "kind", "parent"?,
+    /// "jurisdiction"?, "sensitivity"}]}`. Containment must be acyclic and
+    /// every microspace must reach exactly one jurisdiction through its
+    /// ancestry.
+    pub fn from_json(shard: &str) -> Result<Self, RegistryError> {
+        let parsed: Shard = serde_json::from_str(shard).map_err(|e| RegistryError::Parse(e.to_string()))?;
+        let mut entries: BTreeMap<String, ShardEntry> = BTreeMap::new();
+        for entry in parsed.microspaces {
+            if entries.contains_key(&entry.id) {
+                return Err(RegistryError::DuplicateMicrospace(entry.id));
+            }
+            entries.insert(entry.id.clone(), entry);
+        }
+        for entry in entries.values() {
+            if let Some(parent) = &entry.parent {
+                if !entries.contains_key(parent) {
+                    return Err(RegistryError::UnknownParent { microspace: entry.id.clone(), parent: parent.clone() });
+                }
+            }
+        }
+
+        // Walk each microspace up to a root, reusing what earlier walks
+        // resolved.
+        let mut resolved: BTreeMap<String, String> = BTreeMap::new();
+        for id in entries.keys() {
+            let mut path: Vec<&str> = Vec::new();
+            let mut at = id.as_str();
+            let mut inherited = loop {
+                if let Some(jurisdiction) = resolved.get(at) {
+                    break Some(jurisdiction.clone());
+                }
+                if let Some(pos) = path.iter().position(|p| *p == at) {
+                    let mut cycle: Vec<String> = path[pos..].iter().map(|p| p.to_string()).collect();
+                    cycle.push(at.to_string());
+                    return Err(RegistryError::Cycle(cycle));
+                }
+                path.push(at);
+                match &entries[at].parent {
+                    Some(parent) => at = parent,
+                    None => break None,
+                }
+            };
+            // Resolve from the root down.
+            for &step in path.iter().rev() {
+                let jurisdiction = match (&entries[step].jurisdiction, inherited) {
+                    (Some(declared), Some(inherited)) if *declared != inherited => {
+                        return Err(RegistryError::ConflictingJurisdiction {
+                            microspace: step.to_string(),
+                            declared: declared.clone(),
+                            inherited,
+                        });
+                    }
+                    (Some(declared), _) => declared.clone(),
+                    (None, Some(inherited)) => inherited,
+                    (None, None) => return Err(RegistryError::NoJurisdiction(step.to_string())),
+                };
+                resolved.insert(step.to_string(), jurisdiction.clone());
+                inherited = Some(jurisdiction);
+            }
+        }
+
+        let microspaces = entries
+            .into_iter()
+            .map(|(id, entry)| {
+                let microspace = Microspace {
+                    id: MicrospaceId::new(id.clone()),
+                    kind: entry.kind,
+                    parent: entry.parent.map(MicrospaceId::new),
+                    jurisdiction: JurisdictionId::new(resolved[&id].clone()),
+                    sensitivity: entry.sensitivity,
+                };
+                (id, microspace)
+            })
+            .collect();
+        Ok(Self { microspaces, shard_hash: format!("{:x}", Sha256::digest(shard.as_bytes())) })
+    }
+
+    /// SHA-256 (hex) of the shard this registry was loaded from.
+    pub fn shard_hash(&self) -> &str {
+        &self.shard_hash
+    }
+
+    pub fn get(&self, id: &MicrospaceId) -> Option<&Microspace> {
+        self.microspaces.get(id.as_str())
+    }
+
+    /// `ids` and every microspace containing one of them, each once.
+    pub fn expand_with_ancestors(&self, ids: &[MicrospaceId]) -> Result<Vec<MicrospaceId>, RegistryError> {
+        let mut seen: BTreeSet<&str> = BTreeSet::new();
+        for id in ids {
+            let mut at = self.get(id).ok_or_else(|| RegistryError::UnknownMicrospace(id.as_str().to_string()))?;
+            // Containment is acyclic, so the walk ends at a root.
+            while seen.insert(at.id.as_str()) {
+                match &at.parent {
+                    Some(parent) => at = &self.microspaces[parent.as_str()],
+                    None => break,
+                }
+            }
+        }
+        Ok(seen.into_iter().map(|id| self.microspaces[id].id.clone()).collect())
+    }
+
+    /// Jurisdictions owning `ids` or anything containing them, each once.
+    pub fn jurisdictions_for(&self, ids: &[MicrospaceId]) -> Result<Vec<JurisdictionId>, RegistryError> {
+        let mut jurisdictions: BTreeMap<&str, &JurisdictionId> = BTreeMap::new();
+        for id in self.expand_with_ancestors(ids)? {
+            let jurisdiction = &self.microspaces[id.as_str()].jurisdiction;
+            jurisdictions.insert(jurisdiction.as_str(), jurisdiction);
+        }
+        Ok(jurisdictions.into_values().cloned().collect())
+    }
+
+    /// Highest sensitivity among `ids` and their ancestors; `Low` for none.
+    pub fn sensitivity_ceiling(&self, ids: &[MicrospaceId]) -> Result<SensitivityClass, RegistryError> {
+        Ok(self
+            .expand_with_ancestors(ids)?
+            .iter()
+            .map(|id| self.microspaces[id.as_str()].sensitivity)
+            .max()
+            .unwrap_or(SensitivityClass::Low))
+    }
+
+    /// Jurisdictions derived from `microspaces` that `listed` leaves out.
+    pub fn uncovered_jurisdictions(
+        &self,
+        microspaces: &[MicrospaceId],
+        listed: &[JurisdictionId],
+    ) -> Result<MissingJurisdictions, RegistryError> {
+        let missing = self
+            .jurisdictions_for(microspaces)?
+            .into_iter()
+            .filter(|j| !listed.iter().any(|l| l.as_str() == j.as_str()))
+            .collect();
+        Ok(MissingJurisdictions(missing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARD: &str = r#"{"microspaces": [
+        {"id": "phx_mar_basin", "kind": "facility", "jurisdiction": "PHX", "sensitivity": "moderate"},
+        {"id": "phx_aquifer_cell_12", "kind": "aquifer_cell", "parent": "phx_mar_basin", "sensitivity": "low"},
+        {"id": "phx_pump_node_3", "kind": "node", "parent": "phx_aquifer_cell_12", "sensitivity": "low"},
+        {"id": "gva_clinic", "kind": "facility", "jurisdiction": "GVA", "sensitivity": "high"},
+        {"id": "gva_host_cortex", "kind": "tissue", "parent": "gva_clinic", "jurisdiction": "GVA", "sensitivity": "critical"}
+    ]}"#;
+
+    fn ids(names: &[&str]) -> Vec<MicrospaceId> {
+        names.iter().map(|n| MicrospaceId::new(n.to_string())).collect()
+    }
+
+    fn names<T>(ids: &[T], as_str: impl Fn(&T) -> &str) -> Vec<String> {
+        ids.iter().map(|i| as_str(i).to_string()).collect()
+    }
+
+    #[test]
+    fn touching_a_microspace_touches_its_ancestors() {
+        let registry = MicrospaceRegistry::from_json(SHARD).unwrap();
+        let expanded = registry.expand_with_ancestors(&ids(&["phx_pump_node_3", "phx_aquifer_cell_12"])).unwrap();
+        assert_eq!(
+            names(&expanded, MicrospaceId::as_str),
+            ["phx_aquifer_cell_12", "phx_mar_basin", "phx_pump_node_3"]
+        );
+        assert_eq!(
+            registry.expand_with_ancestors(&ids(&["phx_pump_node_3", "nowhere"])).unwrap_err(),
+            RegistryError::UnknownMicrospace("nowhere".into())
+        );
+        assert_eq!(registry.shard_hash().len(), 64);
+    }
+
+    #[test]
+    fn jurisdictions_come_from_the_ancestry() {
+        let registry = MicrospaceRegistry::from_json(SHARD).unwrap();
+        let cell = registry.get(&MicrospaceId::new("phx_aquifer_cell_12".to_string())).unwrap();
+        assert_eq!(cell.jurisdiction.as_str(), "PHX");
+        let jurisdictions = registry.jurisdictions_for(&ids(&["phx_pump_node_3", "gva_host_cortex"])).unwrap();
+        assert_eq!(names(&jurisdictions, JurisdictionId::as_str), ["GVA", "PHX"]);
+
+        let orphan = r#"{"microspaces": [{"id": "lab", "kind": "facility", "sensitivity": "low"}]}"#;
+        assert_eq!(MicrospaceRegistry::from_json(orphan).unwrap_err(), RegistryError::NoJurisdiction("lab".into()));
+        let split = r#"{"microspaces": [
+            {"id": "basin", "kind": "facility", "jurisdiction": "PHX", "sensitivity": "low"},
+            {"id": "cell", "kind": "aquifer_cell", "parent": "basin", "jurisdiction": "BRU", "sensitivity": "low"}
+        ]}"#;
+        assert!(matches!(
+            MicrospaceRegistry::from_json(split).unwrap_err(),
+            RegistryError::ConflictingJurisdiction { microspace, .. } if microspace == "cell"
+        ));
+    }
+
+    #[test]
+    fn containment_cycles_are_refused_at_load() {
+        let looped = r#"{"microspaces": [
+            {"id": "a", "kind": "node", "parent": "c", "jurisdiction": "PHX", "sensitivity": "low"},
+            {"id": "b", "kind": "node", "parent": "a", "sensitivity": "low"},
+            {"id": "c", "kind": "node", "parent": "b", "sensitivity": "low"}
+        ]}"#;
+        assert_eq!(
+            MicrospaceRegistry::from_json(looped).unwrap_err(),
+            RegistryError::Cycle(vec!["a".into(), "c".into(), "b".into(), "a".into()])
+        );
+        let own_parent = r#"{"microspaces": [{"id": "a", "kind": "node", "parent": "a", "jurisdiction": "PHX", "sensitivity": "low"}]}"#;
+        assert_eq!(MicrospaceRegistry::from_json(own_parent).unwrap_err(), RegistryError::Cycle(vec!["a".into(), "a".into()]));
+    }
+
+    #[test]
+    fn sensitive_microspaces_need_a_longer_horizon() {
+        let registry = MicrospaceRegistry::from_json(SHARD).unwrap();
+        let phx = registry.sensitivity_ceiling(&ids(&["phx_pump_node_3"])).unwrap();
+        assert_eq!(phx, SensitivityClass::Moderate);
+        assert_eq!(phx.required_horizon_days(90), 90);
+        let clinic = registry.sensitivity_ceiling(&ids(&["phx_pump_node_3", "gva_clinic"])).unwrap();
+        assert_eq!(clinic.required_horizon_days(90), 180);
+        let cortex = registry.sensitivity_ceiling(&ids(&["gva_host_cortex"])).unwrap();
+        assert_eq!((cortex, cortex.required_horizon_days(90)), (SensitivityClass::Critical, 270));
+    }
+
+    #[test]
+    fn coverage_passes_once_the_derived_jurisdictions_are_listed() {
+        let registry = MicrospaceRegistry::from_json(SHARD).unwrap();
+        let touched = ids(&["phx_pump_node_3", "gva_host_cortex"]);
+        let listed = vec![JurisdictionId::new("PHX".to_string())];
+        let missing = registry.uncovered_jurisdictions(&touched, &listed).unwrap();
+        assert_eq!(names(&missing.0, JurisdictionId::as_str), ["GVA"]);
+
+        let listed = registry.jurisdictions_for(&touched).unwrap();
+        assert!(registry.uncovered_jurisdictions(&touched, &listed).unwrap().0.is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nalgebra::DVector;
+use neuro_eco_manifest::NeuroEcoIdentityManifest;
+
+fn raf_delta(c: &mut Criterion) {
+    let manifest = NeuroEcoIdentityManifest::default();
+    let m_pos = DVector::from_vec(vec![1.0, 0.0, 0.0, 0.0, 0.0]);
+    let m_neg = DVector::from_vec(vec![0.5, 0.0, 0.0, 0.0, 0.1]);
+    c.bench_function("raf_delta", |b| {
+        b.iter(|| manifest.raf_delta(black_box(m_pos.clone()), black_box(m_neg.clone())))
+    });
+}
+
+criterion_group!(benches, raf_delta);
+criterion_main!(benches);
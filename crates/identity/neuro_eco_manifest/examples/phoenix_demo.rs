@@ -2,7 +2,26 @@
 // logs Errority if unfair, broadcasts signals. Demonstrates fairness: greed (high-neg M_i
 // without restoration) scales outer down, but inner invariant.
 
-use neuro_eco_manifest::{NeuroEcoIdentityManifest};
+use neuro_eco_manifest::{ErrorityEvent, NeuroEcoIdentityManifest};
 use nalgebra::DVector;
 
-// (rest of your main logic, adapted to an example; run with `cargo run --example phoenix_demo`)
+// Run with `cargo run --example phoenix_demo`.
+fn main() {
+    let mut manifest = NeuroEcoIdentityManifest::default();
+    let walk = manifest.raf_delta(DVector::from_vec(vec![1.0, 0.0, 0.0, 0.0, 0.0]), DVector::from_vec(vec![0.5, 0.0, 0.0, 0.0, 0.1]));
+    let car = manifest.raf_delta(DVector::zeros(5), DVector::from_vec(vec![2.0, 0.0, 0.0, 0.0, 0.05]));
+
+    match walk {
+        Ok(delta) => println!("RAF delta walk+smoke+restore: {:.3} (fair, earns TECH/NANO)", manifest.apply_raf(&delta)),
+        Err(e) => println!("walk: {e}"),
+    }
+    match car {
+        Ok(delta) if delta.total < -0.15 => {
+            manifest.err_log(ErrorityEvent { description: "High-emission choice; route to restoration".to_string(), delta_r: delta.total });
+            println!("RAF delta car: {:.3} (Errority logged, inner safe)", delta.total);
+        }
+        Ok(delta) => println!("RAF delta car: {:.3}", delta.total),
+        Err(e) => println!("car: {e}"),
+    }
+    println!("Errority events logged: {}", manifest.errority_events().len());
+}
@@ -0,0 +1,259 @@
+// Module: ManifestBuilder. The only public way to assemble a NeuroEcoIdentityManifest for a DID
+// other than the Phoenix default. `build` enforces the invariants every manifest relies on:
+// matching NanoKarma vectors, at least one safety polytope, and no neural intrusion.
+use chrono::{DateTime, Utc};
+use nalgebra::DVector;
+
+use crate::{
+    Exclusions, Extension, InnerEnvelope, ManifestError, NanoKarmaOp, NeuroEcoIdentityManifest, OuterDomainConfig,
+    SafetyPolytope, StressorAxis, DEFAULT_SIGMA,
+};
+
+/// Builds a manifest for one DID. Unset fields take the bee-weighted baseline the default
+/// manifest uses: five named stressor axes, λ = [1.0, 1.2, 1.5, 2.25, 2.25], β = 1, the P_eco
+/// polytope, the RafAccumulator extension and no neural intrusion.
+#[derive(Clone, Debug)]
+pub struct ManifestBuilder {
+    context: Vec<String>,
+    id: String,
+    issuer: String,
+    issuance_date: Option<DateTime<Utc>>,
+    inner_domain: InnerEnvelope,
+    ceim_ref: String,
+    lambda: DVector<f64>,
+    beta: DVector<f64>,
+    sigma: DVector<f64>,
+    stressor_axes: Vec<StressorAxis>,
+    polytopes: Vec<SafetyPolytope>,
+    extensions: Vec<Extension>,
+    exclusions: Exclusions,
+}
+
+impl ManifestBuilder {
+    /// A self-issued manifest for `did`; set `issuer` for one issued by someone else.
+    pub fn new(did: &str) -> Self {
+        Self {
+            context: vec!["https://www.w3.org/ns/credentials/v2".to_string(), "ceim://v1.2".to_string()],
+            id: did.to_string(),
+            issuer: did.to_string(),
+            issuance_date: None,
+            inner_domain: InnerEnvelope::default(),  // Absolute: noNeuralInputs=true
+            ceim_ref: "ceim://v1.2".to_string(),
+            lambda: DVector::from_vec(vec![1.0, 1.2, 1.5, 2.25, 2.25]),  // Bee-weighted VOC/PM2.5
+            beta: DVector::from_element(5, 1.0),
+            sigma: DVector::from_element(5, DEFAULT_SIGMA),
+            stressor_axes: vec![
+                StressorAxis::new("co2", "kg", 1.0),
+                StressorAxis::new("nox", "kg", 1.0),
+                StressorAxis::new("so2", "kg", 1.0),
+                StressorAxis::new("voc", "kg", 1.5),
+                StressorAxis::new("pm2_5", "kg", 1.5),
+            ],
+            polytopes: vec![SafetyPolytope::default()],  // P_eco baseline
+            extensions: vec![Extension::new(
+                "RafAccumulator",
+                vec!["nanokarma".to_string()],
+                serde_json::json!({ "initial_r": 0.5, "hb_rating": 9.7 }),
+            )],
+            exclusions: Exclusions {
+                allows_neural_intrusion: false,
+                standalone_normative: false,
+                interoperability: vec!["W3C DID v2".to_string(), "CEIM v1.2".to_string()],
+            },
+        }
+    }
+
+    pub fn issuer(mut self, did: &str) -> Self {
+        self.issuer = did.to_string();
+        self
+    }
+
+    /// JSON-LD `@context` URIs, replacing the defaults.
+    pub fn context(mut self, uris: Vec<String>) -> Self {
+        self.context = uris;
+        self
+    }
+
+    /// Defaults to the time of `build`.
+    pub fn issuance_date(mut self, date: DateTime<Utc>) -> Self {
+        self.issuance_date = Some(date);
+        self
+    }
+
+    pub fn inner_domain(mut self, inner: InnerEnvelope) -> Self {
+        self.inner_domain = inner;
+        self
+    }
+
+    pub fn ceim_ref(mut self, uri: &str) -> Self {
+        self.ceim_ref = uri.to_string();
+        self
+    }
+
+    /// NanoKarma hazard weights and normalization, one entry per stressor axis. Resets σ to
+    /// `DEFAULT_SIGMA` and the axes to `legacy_axes`; call `sigma` and `stressor_axes` after
+    /// this to set them.
+    pub fn nanokarma(mut self, lambda: DVector<f64>, beta: DVector<f64>) -> Self {
+        self.lambda = lambda;
+        self.beta = beta;
+        self.sigma = DVector::zeros(0);
+        self.stressor_axes = Vec::new();
+        self
+    }
+
+    /// Per-axis baseline, kg/person/year.
+    pub fn sigma(mut self, sigma: DVector<f64>) -> Self {
+        self.sigma = sigma;
+        self
+    }
+
+    pub fn stressor_axes(mut self, axes: Vec<StressorAxis>) -> Self {
+        self.stressor_axes = axes;
+        self
+    }
+
+    pub fn polytopes(mut self, polytopes: Vec<SafetyPolytope>) -> Self {
+        self.polytopes = polytopes;
+        self
+    }
+
+    pub fn extensions(mut self, extensions: Vec<Extension>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Must stay false: `build` refuses a manifest that allows neural intrusion.
+    pub fn allows_neural_intrusion(mut self, allows: bool) -> Self {
+        self.exclusions.allows_neural_intrusion = allows;
+        self
+    }
+
+    pub fn standalone_normative(mut self, standalone: bool) -> Self {
+        self.exclusions.standalone_normative = standalone;
+        self
+    }
+
+    pub fn interoperability(mut self, standards: Vec<String>) -> Self {
+        self.exclusions.interoperability = standards;
+        self
+    }
+
+    /// Checks the invariants, then assembles an unsigned manifest with no evidence and no live metrics.
+    pub fn build(self) -> Result<NeuroEcoIdentityManifest, ManifestError> {
+        let invalid = |reason: String| Err(ManifestError::InvalidManifest(reason));
+        for (field, did) in [("id", &self.id), ("issuer", &self.issuer)] {
+            if !did.starts_with("did:") {
+                return invalid(format!("{field} must be a DID, got {did:?}"));
+            }
+        }
+        let axes = self.lambda.len();
+        if axes == 0 {
+            return invalid("lambda has no axes".to_string());
+        }
+        if self.beta.len() != axes {
+            return invalid(format!("beta has {} axes, lambda has {axes}", self.beta.len()));
+        }
+        if !self.sigma.is_empty() && self.sigma.len() != axes {
+            return invalid(format!("sigma has {} axes, lambda has {axes}", self.sigma.len()));
+        }
+        if !self.stressor_axes.is_empty() && self.stressor_axes.len() != axes {
+            return invalid(format!("stressor_axes has {} axes, lambda has {axes}", self.stressor_axes.len()));
+        }
+        if self.polytopes.is_empty() {
+            return invalid("at least one safety polytope is required".to_string());
+        }
+        if self.exclusions.allows_neural_intrusion {
+            return invalid("allows_neural_intrusion must be false".to_string());  // Inner domain is absolute
+        }
+
+        Ok(NeuroEcoIdentityManifest {
+            context: self.context,
+            id: self.id,
+            r#type: "NeuroEcoIdentityManifest".to_string(),
+            issuer: self.issuer,
+            issuance_date: self.issuance_date.unwrap_or_else(Utc::now),
+            inner_domain: self.inner_domain,
+            outer_domain: OuterDomainConfig {
+                ceim_ref: self.ceim_ref,
+                nanokarma_op: NanoKarmaOp {
+                    lambda: self.lambda,
+                    beta: self.beta,
+                    k_person_current: 0.0,
+                    sigma: self.sigma,
+                    stressor_axes: self.stressor_axes,
+                },
+                polytopes: self.polytopes,
+            },
+            extensions: self.extensions,
+            evidence_bundles: vec![],
+            signatures: vec![],
+            exclusions: self.exclusions,
+            live_metrics: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PHOENIX_DID;
+
+    const DID: &str = "did:key:z6MkSecondUser";
+
+    #[test]
+    fn test_builder_happy_path() {
+        let manifest = ManifestBuilder::new(DID)
+            .issuer("did:key:z6MkSteward")
+            .nanokarma(DVector::from_vec(vec![1.0, 2.0]), DVector::from_vec(vec![1.0, 0.5]))
+            .stressor_axes(vec![StressorAxis::new("co2", "kg", 1.0), StressorAxis::new("pm2_5", "kg", 1.5)])
+            .build()
+            .unwrap();
+        assert_eq!((manifest.id(), manifest.issuer()), (DID, "did:key:z6MkSteward"));
+        assert_eq!(manifest.nanokarma_op().lambda().len(), 2);
+        assert_eq!(manifest.nanokarma_op().sigma(), DVector::from_element(2, DEFAULT_SIGMA));
+        assert_eq!(manifest.polytopes().len(), 1);
+        assert!(!manifest.exclusions().allows_neural_intrusion());
+        approx::assert_relative_eq!(manifest.bee_weight("pm2_5").unwrap(), 3.0);
+
+        let breakdown = manifest.raf_delta(DVector::from_vec(vec![5.0, 0.0]), DVector::zeros(2)).unwrap();
+        approx::assert_relative_eq!(breakdown.total, 0.5);
+    }
+
+    #[test]
+    fn test_default_is_the_phoenix_builder() {
+        let default = NeuroEcoIdentityManifest::default();
+        assert_eq!(default.id(), PHOENIX_DID);
+        let built = ManifestBuilder::new(PHOENIX_DID).issuance_date(default.issuance_date).build().unwrap();
+        assert_eq!(built.canonical_bytes(), default.canonical_bytes());
+    }
+
+    #[test]
+    fn test_mismatched_vectors_are_rejected() {
+        let err = ManifestBuilder::new(DID)
+            .nanokarma(DVector::from_vec(vec![1.0, 2.0, 3.0]), DVector::from_vec(vec![1.0, 1.0]))
+            .build()
+            .unwrap_err();
+        assert!(matches!(&err, ManifestError::InvalidManifest(m) if m == "beta has 2 axes, lambda has 3"), "{err}");
+
+        // The default five named axes no longer fit three weights unless replaced.
+        let err = ManifestBuilder::new(DID)
+            .nanokarma(DVector::from_element(3, 1.0), DVector::from_element(3, 1.0))
+            .stressor_axes(vec![StressorAxis::new("co2", "kg", 1.0)])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("stressor_axes has 1 axes"), "{err}");
+    }
+
+    #[test]
+    fn test_neural_intrusion_is_rejected() {
+        let err = ManifestBuilder::new(DID).allows_neural_intrusion(true).build().unwrap_err();
+        assert!(matches!(&err, ManifestError::InvalidManifest(m) if m.contains("allows_neural_intrusion")), "{err}");
+    }
+
+    #[test]
+    fn test_missing_polytopes_and_bad_dids_are_rejected() {
+        assert!(ManifestBuilder::new(DID).polytopes(vec![]).build().is_err());
+        assert!(ManifestBuilder::new("bostrom18sd2").build().is_err());
+        assert!(ManifestBuilder::new(DID).issuer("").build().is_err());
+    }
+}
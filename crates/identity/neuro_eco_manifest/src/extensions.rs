@@ -0,0 +1,56 @@
+// Module: Extensions. RAF accumulation, bee-weighted operators and Errority events: the
+// non-punitive learning loop layered over the NanoKarma core.
+use serde::{Deserialize, Serialize};
+
+use crate::outer_domain::KarmaAdmissible;
+
+/// Restorative Accountability Factor, accumulated from RAF deltas and kept in [0, 1].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RafAccumulator {
+    pub r: f64,
+    pub hb_rating: f64,  // Bee-focus rating, 0..10
+}
+
+impl RafAccumulator {
+    pub fn new(initial_r: f64, hb_rating: f64) -> Self {
+        Self { r: initial_r.clamp(0.0, 1.0), hb_rating }
+    }
+
+    /// Adds `delta` and returns the clamped factor.
+    pub fn accumulate(&mut self, delta: f64) -> f64 {
+        self.r = (self.r + delta).clamp(0.0, 1.0);
+        self.r
+    }
+}
+
+/// r0 = 0.5, HB 9.7/10: the RafAccumulator extension the default manifest declares.
+impl Default for RafAccumulator {
+    fn default() -> Self {
+        Self::new(0.5, 9.7)
+    }
+}
+
+impl KarmaAdmissible for RafAccumulator {
+    fn karma_admissible(&self, delta_k: f64) -> bool {
+        self.r + delta_k >= 0.0
+    }
+}
+
+/// BEE_WEIGHT as an operator: scales a hazard weight by the pollinator multiplier.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BeeWeightedOp {
+    pub multiplier: f64,  // 1.5x human for VOCs/PM2.5
+}
+
+impl BeeWeightedOp {
+    pub fn apply(&self, lambda: f64) -> f64 {
+        lambda * self.multiplier
+    }
+}
+
+/// A learning event, not a penalty: what happened and the RAF delta it carried.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ErrorityEvent {
+    pub description: String,
+    pub delta_r: f64,
+}
@@ -0,0 +1,36 @@
+// Module: Inner domain. Neurorights that hold absolutely: no outer-domain score, RAF delta or
+// Errority event can switch them off. The manifest carries them as flags so signatures cover them.
+use serde::{Deserialize, Serialize};
+
+/// An inner-domain guarantee that must hold for every manifest, whatever its outer score.
+pub trait NeurorightInvariant {
+    fn holds(&self) -> bool;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerEnvelope {
+    no_neural_inputs: bool,   // Reads physical stressors only, never neural data
+    mental_privacy: bool,
+    cognitive_liberty: bool,
+    no_karma_scaling: bool,   // Inner rights never scale with ΔK
+}
+
+impl InnerEnvelope {
+    pub fn no_neural_inputs(&self) -> bool {
+        self.no_neural_inputs
+    }
+}
+
+impl NeurorightInvariant for InnerEnvelope {
+    fn holds(&self) -> bool {
+        self.no_neural_inputs && self.mental_privacy && self.cognitive_liberty && self.no_karma_scaling
+    }
+}
+
+/// Absolute: every neuroright on.
+impl Default for InnerEnvelope {
+    fn default() -> Self {
+        Self { no_neural_inputs: true, mental_privacy: true, cognitive_liberty: true, no_karma_scaling: true }
+    }
+}
@@ -4,21 +4,24 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
-use nalgebra::{DMatrix, DVector};  // For A_eco x <= b_eco polytopes
+use nalgebra::DVector;  // Stressor vectors; polytopes live in outer_domain
 use chrono::{DateTime, Utc};
-use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey};
-use hex::{encode, decode};
-use zeroize::Zeroize;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use keyring::{KeyringSignature, SignatureVerifier};
+use param_registry::{ParamKey, ParamRegistry};
+use hex::encode;
 
 pub mod inner_domain;
 pub mod outer_domain;
 pub mod extensions;
 pub mod signaling;
+mod builder;
 
 pub use inner_domain::{NeurorightInvariant, InnerEnvelope};
 pub use outer_domain::{EcoAdmissible, KarmaAdmissible, SafetyPolytope};
 pub use extensions::{RafAccumulator, BeeWeightedOp, ErrorityEvent};
 pub use signaling::{WordMathScore, DutyHeader, LiveDelta};
+pub use builder::ManifestBuilder;
 
 #[derive(Error, Debug)]
 pub enum ManifestError {
@@ -30,6 +33,28 @@ pub enum ManifestError {
     RafError(String),
     #[error("Hex-stamp mismatch")]
     HexMismatch,
+    #[error("Manifest carries no signatures")]
+    Unsigned,
+    #[error("Unknown signing key {0}")]
+    UnknownKey(String),
+    #[error("Unknown stressor axis {0}")]
+    UnknownAxis(String),
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+}
+
+/// `HexStampedBundle::bundle_type` of bundles written by `err_log`.
+pub const ERRORITY_BUNDLE: &str = "ErrorityEvent";
+
+/// Maps a `DidSignature::key_id` to the key that verifies it.
+pub trait KeyResolver {
+    fn resolve(&self, key_id: &str) -> Option<VerifyingKey>;
+}
+
+impl KeyResolver for std::collections::HashMap<String, VerifyingKey> {
+    fn resolve(&self, key_id: &str) -> Option<VerifyingKey> {
+        self.get(key_id).copied()
+    }
 }
 
 /// Core NeuroEcoIdentityManifest: DID-bound, layered governance object.
@@ -65,6 +90,153 @@ pub struct NanoKarmaOp {
     lambda: DVector<f64>,  // Hazard weights (bee-elevated for VOCs/PM2.5)
     beta: DVector<f64>,    // Normalization (jurisdictional LCIA)
     k_person_current: f64, // Cumulative ∑ K_i
+    /// Per-axis baseline, kg/person/year. Empty, as in manifests written
+    /// before it existed, means `DEFAULT_SIGMA` on every axis.
+    #[serde(default = "empty_sigma")]
+    sigma: DVector<f64>,
+    /// One entry per lambda axis. Empty, as in manifests written before it
+    /// existed, means the generated `legacy_axes`.
+    #[serde(default)]
+    stressor_axes: Vec<StressorAxis>,
+}
+
+/// A named NanoKarma stressor axis.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StressorAxis {
+    pub name: String,  // e.g. "pm2_5"
+    pub unit: String,  // e.g. "kg"
+    /// BEE_WEIGHT scale on this axis's λ (pollinator sensitivity).
+    #[serde(default = "unit_multiplier")]
+    pub bee_multiplier: f64,
+}
+
+fn unit_multiplier() -> f64 {
+    1.0
+}
+
+impl StressorAxis {
+    pub fn new(name: &str, unit: &str, bee_multiplier: f64) -> Self {
+        Self { name: name.to_string(), unit: unit.to_string(), bee_multiplier }
+    }
+}
+
+/// Axes for a manifest that names none: `axis_0`… in kg, with the 1.5x
+/// bee multiplier on axes 3 and 4 (VOCs, PM2.5) that `bee_weight` used to
+/// hard-code.
+pub fn legacy_axes(count: usize) -> Vec<StressorAxis> {
+    (0..count)
+        .map(|i| StressorAxis::new(&format!("axis_{i}"), "kg", if i == 3 || i == 4 { 1.5 } else { 1.0 }))
+        .collect()
+}
+
+/// A stressor axis by position or by name.
+#[derive(Clone, Copy, Debug)]
+pub enum AxisRef<'a> {
+    Index(usize),
+    Name(&'a str),
+}
+
+impl From<usize> for AxisRef<'_> {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for AxisRef<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Name(name)
+    }
+}
+
+/// Baseline mass per axis when a manifest gives no `sigma`: 10 kg/person/year.
+pub const DEFAULT_SIGMA: f64 = 10.0;
+
+fn empty_sigma() -> DVector<f64> {
+    DVector::zeros(0)
+}
+
+impl NanoKarmaOp {
+    pub fn lambda(&self) -> &DVector<f64> {
+        &self.lambda
+    }
+
+    pub fn beta(&self) -> &DVector<f64> {
+        &self.beta
+    }
+
+    pub fn k_person_current(&self) -> f64 {
+        self.k_person_current
+    }
+
+    /// Per-axis baseline, `DEFAULT_SIGMA` on every axis when none was set.
+    pub fn sigma(&self) -> DVector<f64> {
+        if self.sigma.is_empty() {
+            DVector::from_element(self.lambda.len(), DEFAULT_SIGMA)
+        } else {
+            self.sigma.clone()
+        }
+    }
+
+    /// The stressor axes, generated for legacy manifests.
+    pub fn stressor_axes(&self) -> Vec<StressorAxis> {
+        if self.stressor_axes.is_empty() {
+            legacy_axes(self.lambda.len())
+        } else {
+            self.stressor_axes.clone()
+        }
+    }
+
+    fn axis_names(&self) -> Vec<String> {
+        self.stressor_axes().into_iter().map(|axis| axis.name).collect()
+    }
+}
+
+/// One violated constraint row and its residual (A x - b)_row > 0.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RowViolation {
+    pub row: usize,
+    pub residual: f64,
+    /// Axes with a nonzero coefficient in the row: stressor axis names when
+    /// the polytope spans the manifest's stressor axes, else `axis_j`.
+    pub axes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PolytopeReport {
+    /// Position in the manifest's polytope list.
+    pub index: usize,
+    pub violations: Vec<RowViolation>,
+    /// Smallest b - A x over this polytope's rows.
+    pub min_slack: f64,
+}
+
+/// ECO_ADMISS diagnostics for one projection.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AdmissibilityReport {
+    pub polytopes: Vec<PolytopeReport>,
+    pub admissible: bool,
+}
+
+impl AdmissibilityReport {
+    pub fn margin(&self) -> f64 {
+        self.polytopes.iter().map(|p| p.min_slack).fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// RAF_delta result: ΔR_i = λ_i β_i (M⁺_i − M⁻_i) / σ_i per axis, and their sum.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RafBreakdown {
+    pub per_axis: DVector<f64>,
+    pub total: f64,
+    /// Stressor axis name for each entry of `per_axis`.
+    pub axis_names: Vec<String>,
+}
+
+impl RafBreakdown {
+    /// `(axis name, ΔR)` pairs.
+    pub fn by_axis(&self) -> impl Iterator<Item = (&str, f64)> + '_ {
+        self.axis_names.iter().map(String::as_str).zip(self.per_axis.iter().copied())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -74,12 +246,44 @@ pub struct Extension {
     params: serde_json::Value,  // RAF formula, HB-rating 9.7/10
 }
 
+impl Extension {
+    pub fn new(r#type: &str, depends_on: Vec<String>, params: serde_json::Value) -> Self {
+        Self { r#type: r#type.to_string(), depends_on, params }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct HexStampedBundle {
     id: String,  // Hex hash of bundle contents
     bundle_type: String,  // "CEIMModel", "BeeSensitivityStudy"
     uri: String,  // IPFS/HTTPS
     timestamp: DateTime<Utc>,
+    /// The stamped record itself, for bundles the manifest logs (e.g. the
+    /// `ErrorityEvent`). Absent for bundles that only point at `uri`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload: Option<serde_json::Value>,
+}
+
+impl HexStampedBundle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn bundle_type(&self) -> &str {
+        &self.bundle_type
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn payload(&self) -> Option<&serde_json::Value> {
+        self.payload.as_ref()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -95,6 +299,20 @@ pub struct Exclusions {
     interoperability: Vec<String>,  // ["W3C DID v2", "CEIM v1.2", "NanoKarma"]
 }
 
+impl Exclusions {
+    pub fn allows_neural_intrusion(&self) -> bool {
+        self.allows_neural_intrusion
+    }
+
+    pub fn standalone_normative(&self) -> bool {
+        self.standalone_normative
+    }
+
+    pub fn interoperability(&self) -> &[String] {
+        &self.interoperability
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LiveMetrics {
     raf_global: f64,
@@ -111,51 +329,163 @@ pub struct KarmaDeltas {
 }
 
 impl NeuroEcoIdentityManifest {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    pub fn polytopes(&self) -> &[SafetyPolytope] {
+        &self.outer_domain.polytopes
+    }
+
+    pub fn nanokarma_op(&self) -> &NanoKarmaOp {
+        &self.outer_domain.nanokarma_op
+    }
+
+    pub fn exclusions(&self) -> &Exclusions {
+        &self.exclusions
+    }
+
     /// RAF_delta: Short-abbrev fn for CHURCH earning. Computes pos/neg mass impacts via CEIM -> NanoKarma.
     /// Earns TECH/NANO by simulating restorative actions (e.g., +0.15 for Cybo-Air toxin removal).
-    pub fn raf_delta(&self, m_pos: DVector<f64>, m_neg: DVector<f64>) -> Result<f64, ManifestError> {
-        let sigma = DVector::from_element(m_pos.len(), 10.0);  // Normalization: 10 kg/person/year baseline
-        let delta_r = (self.outer_domain.nanokarma_op.lambda.component_mul(&m_pos)
-                       - self.outer_domain.nanokarma_op.lambda.component_mul(&m_neg))
-                      .component_div(&sigma)
-                      .sum();
-        if delta_r < -0.3 {  // Threshold for Errority trigger
-            Err(ManifestError::RafError("High negative delta; log Errority".to_string()))
+    /// Each axis is weighted by λ and β (K_i = λ_i β_i M_i) and normalized by its σ.
+    pub fn raf_delta(&self, m_pos: DVector<f64>, m_neg: DVector<f64>) -> Result<RafBreakdown, ManifestError> {
+        self.raf_delta_with(&ParamRegistry::default(), m_pos, m_neg)
+    }
+
+    /// `raf_delta` with the Errority trigger read from `params`.
+    pub fn raf_delta_with(&self, params: &ParamRegistry, m_pos: DVector<f64>, m_neg: DVector<f64>) -> Result<RafBreakdown, ManifestError> {
+        let op = &self.outer_domain.nanokarma_op;
+        let sigma = op.sigma();
+        let axis_names = op.axis_names();
+        let axes = op.lambda.len();
+        for (name, len) in [("m_pos", m_pos.len()), ("m_neg", m_neg.len()), ("beta", op.beta.len()), ("sigma", sigma.len()), ("stressor_axes", axis_names.len())] {
+            if len != axes {
+                return Err(ManifestError::RafError(format!("{name} has {len} axes, lambda has {axes}")));
+            }
+        }
+        if let Some(i) = sigma.iter().position(|s| !(s.is_finite() && *s > 0.0)) {
+            return Err(ManifestError::RafError(format!("sigma for {} must be a positive number, got {}", axis_names[i], sigma[i])));
+        }
+        let per_axis = op.lambda.component_mul(&op.beta).component_mul(&(m_pos - m_neg)).component_div(&sigma);
+        let total = per_axis.sum();
+        if total < params.get(ParamKey::ErrorityTriggerDelta) {  // Threshold for Errority trigger
+            let worst = per_axis.argmin().0;
+            Err(ManifestError::RafError(format!(
+                "High negative delta; log Errority (worst axis {}: {:.3})", axis_names[worst], per_axis[worst]
+            )))
         } else {
-            Ok(delta_r)  // Positive/zero: earns eco-grant simulation
+            Ok(RafBreakdown { per_axis, total, axis_names })  // Positive/zero: earns eco-grant simulation
         }
     }
 
+    /// APPLY_RAF: Accumulates an accepted delta into ∑ K_i; returns the new total.
+    pub fn apply_raf(&mut self, breakdown: &RafBreakdown) -> f64 {
+        let op = &mut self.outer_domain.nanokarma_op;
+        op.k_person_current += breakdown.total;
+        op.k_person_current
+    }
+
     /// ECO_ADMISS: Polytope check for action x_proj. Zero-harm: rejects if violates P_eco or P_bee.
+    /// A projection with the wrong number of axes is not admissible.
     pub fn eco_admissible(&self, x_proj: &DVector<f64>) -> bool {
-        self.outer_domain.polytopes.iter().all(|p| {
-            let residual = &p.a * x_proj - &p.b;
-            residual.max() <= 0.0  // A x <= b
-        })
+        self.eco_admissible_detailed(x_proj).is_ok_and(|report| report.admissible)
     }
 
-    /// BEE_WEIGHT: Scales λ_i for pollinators (1.5x human for VOCs/PM2.5). HB-rating 9.7/10 sim.
-    pub fn bee_weight(&self, stressor_idx: usize) -> f64 {
-        let base_lambda = self.outer_domain.nanokarma_op.lambda[stressor_idx];
-        if stressor_idx == 3 || stressor_idx == 4 {  // VOCs, PM2.5 indices
-            base_lambda * 1.5
-        } else {
-            base_lambda
+    /// ECO_ADMISS with diagnostics: every violated row of every polytope, by how much.
+    pub fn eco_admissible_detailed(&self, x_proj: &DVector<f64>) -> Result<AdmissibilityReport, ManifestError> {
+        let mut polytopes = Vec::with_capacity(self.outer_domain.polytopes.len());
+        let stressor_names = self.outer_domain.nanokarma_op.axis_names();
+        for (index, p) in self.outer_domain.polytopes.iter().enumerate() {
+            if p.a.ncols() != x_proj.len() {
+                return Err(ManifestError::PolytopeViolation(format!(
+                    "polytope {index} expects {} axes, x_proj has {}", p.a.ncols(), x_proj.len()
+                )));
+            }
+            if p.a.nrows() != p.b.len() {
+                return Err(ManifestError::PolytopeViolation(format!(
+                    "polytope {index} has {} constraint rows but {} bounds", p.a.nrows(), p.b.len()
+                )));
+            }
+            let residual = &p.a * x_proj - &p.b;  // A x - b; > 0 violates
+            let column = |j: usize| if p.a.ncols() == stressor_names.len() { stressor_names[j].clone() } else { format!("axis_{j}") };
+            let violations = residual.iter().enumerate()
+                .filter(|(_, r)| r.is_nan() || **r > 0.0)
+                .map(|(row, r)| RowViolation {
+                    row,
+                    residual: *r,
+                    axes: (0..p.a.ncols()).filter(|&j| p.a[(row, j)] != 0.0).map(column).collect(),
+                })
+                .collect();
+            let min_slack = residual.iter().map(|r| -r).fold(f64::INFINITY, f64::min);
+            polytopes.push(PolytopeReport { index, violations, min_slack });
+        }
+        let admissible = polytopes.iter().all(|p| p.violations.is_empty());
+        Ok(AdmissibilityReport { polytopes, admissible })
+    }
+
+    /// MARGIN: Smallest slack b - A x over every constraint row; negative once outside.
+    /// Infinite with no constraints.
+    pub fn margin(&self, x_proj: &DVector<f64>) -> Result<f64, ManifestError> {
+        Ok(self.eco_admissible_detailed(x_proj)?.margin())
+    }
+
+    /// BEE_WEIGHT: Scales λ_i for pollinators by the axis's `bee_multiplier` (1.5x human for VOCs/PM2.5).
+    /// HB-rating 9.7/10 sim. `axis` is an index or a stressor axis name.
+    pub fn bee_weight<'a>(&self, axis: impl Into<AxisRef<'a>>) -> Result<f64, ManifestError> {
+        let op = &self.outer_domain.nanokarma_op;
+        let axes = op.stressor_axes();
+        let index = match axis.into() {
+            AxisRef::Index(i) => i,
+            AxisRef::Name(name) => axes.iter().position(|a| a.name == name)
+                .ok_or_else(|| ManifestError::UnknownAxis(name.to_string()))?,
+        };
+        match (op.lambda.get(index), axes.get(index)) {
+            (Some(lambda), Some(axis)) => Ok(lambda * axis.bee_multiplier),
+            _ => Err(ManifestError::UnknownAxis(format!("#{index}"))),
         }
     }
 
     /// ERR_LOG: Emits Errority event for refinement. Non-punitive: feeds polytope updates, earns WISE via learning.
+    /// The event is stored in its bundle; a negative delta_r lowers live `raf_global`, never below the floor.
     pub fn err_log(&mut self, event: ErrorityEvent) -> HexStampedBundle {
-        let mut hasher = Sha256::new();
-        hasher.update(serde_json::to_string(&event).unwrap().as_bytes());
-        let hash = encode(hasher.finalize());
-        self.evidence_bundles.push(HexStampedBundle {
+        self.err_log_with(&ParamRegistry::default(), event)
+    }
+
+    /// `err_log` with the `raf_global` floor read from `params`.
+    pub fn err_log_with(&mut self, params: &ParamRegistry, event: ErrorityEvent) -> HexStampedBundle {
+        let hash = self.hex_stamp(serde_json::to_string(&event).expect("ErrorityEvent serializes").as_bytes());
+        let payload = serde_json::to_value(&event).expect("ErrorityEvent serializes");
+        let bundle = HexStampedBundle {
             id: hash.clone(),
-            bundle_type: "ErrorityEvent".to_string(),
+            bundle_type: ERRORITY_BUNDLE.to_string(),
             uri: format!("ipfs://{}", hash),  // Placeholder for actual IPFS
             timestamp: Utc::now(),
-        });
-        HexStampedBundle { id: hash, ..Default::default() }  // Returns stamped bundle
+            payload: Some(payload),
+        };
+        self.evidence_bundles.push(bundle.clone());
+        if let Some(metrics) = self.live_metrics.as_mut() {
+            let floor = params.get(ParamKey::ErrorityRafFloor);
+            // Never raises raf_global, and never pulls an already-lower value up to the floor.
+            let lowered = (metrics.raf_global + event.delta_r.min(0.0)).max(floor);
+            metrics.raf_global = lowered.min(metrics.raf_global);
+        }
+        bundle  // Returns stamped bundle, as stored
+    }
+
+    /// ERRORITY_EVENTS: Logged Errority events, oldest first, with the bundle that stamps each.
+    /// Bundles without a readable payload (logged before payloads were stored) are skipped.
+    pub fn errority_events(&self) -> Vec<(&HexStampedBundle, ErrorityEvent)> {
+        self.evidence_bundles.iter()
+            .filter(|bundle| bundle.bundle_type == ERRORITY_BUNDLE)
+            .filter_map(|bundle| {
+                let event = serde_json::from_value::<ErrorityEvent>(bundle.payload.clone()?).ok()?;
+                Some((bundle, event))
+            })
+            .collect()
     }
 
     /// HEX_STAMP: Bundles evidence for verification. Ensures tamper-evidence for good-deed ledgers.
@@ -165,46 +495,70 @@ impl NeuroEcoIdentityManifest {
         encode(hasher.finalize())
     }
 
+    /// CANON: The bytes manifest signatures cover. JSON with every object's
+    /// keys sorted and no whitespace, without `signatures` (so signers do
+    /// not cover each other) and `live_metrics` (real-time, not anchored).
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut value = serde_json::to_value(self).expect("manifest serializes");
+        if let serde_json::Value::Object(fields) = &mut value {
+            fields.remove("signatures");
+            fields.remove("live_metrics");
+        }
+        serde_json::to_vec(&canonical(value)).expect("manifest serializes")
+    }
+
+    /// SIGN: Appends an ed25519 signature by `key_id` over `canonical_bytes`.
+    /// Any later change to the covered fields invalidates it.
+    pub fn sign(&mut self, key: &SigningKey, key_id: &str) {
+        let signature = key.sign(&self.canonical_bytes());
+        self.signatures.push(DidSignature { key_id: key_id.to_string(), signature: signature.to_bytes().to_vec() });
+    }
+
+    /// VERIFY_ALL: Every signature must resolve to a known key and verify
+    /// over the current `canonical_bytes`. An unsigned manifest fails.
+    pub fn verify_all(&self, resolver: &dyn KeyResolver) -> Result<(), ManifestError> {
+        if self.signatures.is_empty() {
+            return Err(ManifestError::Unsigned);
+        }
+        let bytes = self.canonical_bytes();
+        for sig in &self.signatures {
+            let key = resolver.resolve(&sig.key_id).ok_or_else(|| ManifestError::UnknownKey(sig.key_id.clone()))?;
+            let signature = Signature::from_slice(&sig.signature).map_err(|_| ManifestError::InvalidSignature)?;
+            key.verify(&bytes, &signature).map_err(|_| ManifestError::InvalidSignature)?;
+        }
+        Ok(())
+    }
+
     /// Verify signature: Ensures DID-bound integrity for non-reversal rights.
-    pub fn verify_signature(&self, verifying_key: &VerifyingKey, data: &[u8], sig: &[u8]) -> Result<(), ManifestError> {
-        verifying_key.verify(data, &ed25519_dalek::Signature::from_bytes(sig).map_err(|_| ManifestError::InvalidSignature)?)
-            .map_err(|_| ManifestError::InvalidSignature)
+    /// `keys` is a Keyring or an exported VerifyingBundle; signatures dated
+    /// outside the signing key's validity window are rejected.
+    pub fn verify_signature(&self, keys: &impl SignatureVerifier, data: &[u8], sig: &KeyringSignature) -> Result<(), ManifestError> {
+        keys.verify(data, sig).map_err(|_| ManifestError::InvalidSignature)
     }
 }
 
+/// `value` with object keys in sorted order at every depth, whatever map
+/// serde_json was built with.
+fn canonical(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => {
+            let mut sorted: Vec<_> = fields.into_iter().collect();
+            sorted.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(sorted.into_iter().map(|(k, v)| (k, canonical(v))).collect())
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(canonical).collect()),
+        other => other,
+    }
+}
+
+/// DID of the Phoenix, AZ baseline manifest.
+pub const PHOENIX_DID: &str = "did:bostrom:bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7";
+
 /// System-object: Default manifest for Phoenix, AZ baseline (user loc). Initializes with r0=0.5, bee-focus.
+/// Other users build their own with `ManifestBuilder`.
 impl Default for NeuroEcoIdentityManifest {
     fn default() -> Self {
-        Self {
-            context: vec!["https://www.w3.org/ns/credentials/v2".to_string(), "ceim://v1.2".to_string()],
-            id: "did:bostrom:bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7".to_string(),
-            r#type: "NeuroEcoIdentityManifest".to_string(),
-            issuer: "did:bostrom:bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7".to_string(),
-            issuance_date: Utc::now(),
-            inner_domain: InnerEnvelope::default(),  // Absolute: noNeuralInputs=true
-            outer_domain: OuterDomainConfig {
-                ceim_ref: "ceim://v1.2".to_string(),
-                nanokarma_op: NanoKarmaOp {
-                    lambda: DVector::from_vec(vec![1.0, 1.2, 1.5, 2.25, 2.25]),  // Bee-weighted VOC/PM2.5
-                    beta: DVector::from_element(5, 1.0),
-                    k_person_current: 0.0,
-                },
-                polytopes: vec![SafetyPolytope::default()],  // P_eco baseline
-            },
-            extensions: vec![Extension {
-                r#type: "RafAccumulator".to_string(),
-                depends_on: vec!["nanokarma".to_string()],
-                params: serde_json::json!({ "initial_r": 0.5, "hb_rating": 9.7 }),
-            }],
-            evidence_bundles: vec![],
-            signatures: vec![],
-            exclusions: Exclusions {
-                allows_neural_intrusion: false,
-                standalone_normative: false,
-                interoperability: vec!["W3C DID v2".to_string(), "CEIM v1.2".to_string()],
-            },
-            live_metrics: None,
-        }
+        ManifestBuilder::new(PHOENIX_DID).build().expect("the Phoenix baseline manifest is valid")
     }
 }
 
@@ -212,14 +566,123 @@ impl Default for NeuroEcoIdentityManifest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nalgebra::DMatrix;
 
     #[test]
     fn test_raf_delta_positive_eco_grant() {
         let manifest = NeuroEcoIdentityManifest::default();
-        let m_pos = DVector::from_vec(vec![5.0, 0.0]);  // 5kg CO2 removed
-        let m_neg = DVector::from_vec(vec![0.0, 0.0]);
+        let m_pos = DVector::from_vec(vec![5.0, 0.0, 0.0, 0.0, 0.0]);  // 5kg CO2 removed
+        let m_neg = DVector::zeros(5);
         let delta = manifest.raf_delta(m_pos, m_neg).unwrap();
-        assert!(delta > 0.0);  // Earns +TECH for restoration
+        assert!(delta.total > 0.0);  // Earns +TECH for restoration
+        assert_eq!(delta.per_axis[0], delta.total);
+    }
+
+    #[test]
+    fn test_raf_delta_mismatched_dims_is_an_error() {
+        let manifest = NeuroEcoIdentityManifest::default();
+        let err = manifest.raf_delta(DVector::zeros(2), DVector::zeros(5)).unwrap_err();
+        assert!(matches!(&err, ManifestError::RafError(m) if m == "m_pos has 2 axes, lambda has 5"), "{err}");
+        let err = manifest.raf_delta(DVector::zeros(5), DVector::zeros(4)).unwrap_err();
+        assert!(err.to_string().contains("m_neg has 4 axes"), "{err}");
+
+        let mut short_beta = manifest.clone();
+        short_beta.outer_domain.nanokarma_op.beta = DVector::from_element(3, 1.0);
+        let err = short_beta.raf_delta(DVector::zeros(5), DVector::zeros(5)).unwrap_err();
+        assert!(err.to_string().contains("beta has 3 axes"), "{err}");
+    }
+
+    #[test]
+    fn test_raf_delta_beta_and_sigma_scale_each_axis() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        let m_pos = DVector::from_vec(vec![5.0, 5.0, 0.0, 0.0, 0.0]);
+        let m_neg = DVector::from_vec(vec![0.0, 0.0, 0.0, 0.0, 1.0]);
+        let base = manifest.raf_delta(m_pos.clone(), m_neg.clone()).unwrap();
+        // λ = [1.0, 1.2, 1.5, 2.25, 2.25], β = 1, σ = 10.
+        approx::assert_relative_eq!(base.per_axis[0], 0.5);
+        approx::assert_relative_eq!(base.per_axis[1], 0.6);
+        approx::assert_relative_eq!(base.per_axis[4], -0.225);
+
+        let op = &mut manifest.outer_domain.nanokarma_op;
+        op.beta = DVector::from_vec(vec![2.0, 1.0, 1.0, 1.0, 0.5]);
+        op.sigma = DVector::from_vec(vec![10.0, 20.0, 10.0, 10.0, 10.0]);
+        let scaled = manifest.raf_delta(m_pos, m_neg).unwrap();
+        approx::assert_relative_eq!(scaled.per_axis[0], 1.0);
+        approx::assert_relative_eq!(scaled.per_axis[1], 0.3);
+        approx::assert_relative_eq!(scaled.per_axis[4], -0.1125);
+        approx::assert_relative_eq!(scaled.total, scaled.per_axis.sum());
+    }
+
+    #[test]
+    fn test_manifest_without_sigma_uses_the_old_baseline() {
+        let mut value = serde_json::to_value(NeuroEcoIdentityManifest::default()).unwrap();
+        value["outer_domain"]["nanokarma_op"].as_object_mut().unwrap().remove("sigma");
+        let legacy: NeuroEcoIdentityManifest = serde_json::from_value(value).unwrap();
+        let m_pos = DVector::from_vec(vec![5.0, 0.0, 0.0, 0.0, 0.0]);
+        let delta = legacy.raf_delta(m_pos.clone(), DVector::zeros(5)).unwrap();
+        assert_eq!(delta, NeuroEcoIdentityManifest::default().raf_delta(m_pos, DVector::zeros(5)).unwrap());
+    }
+
+    #[test]
+    fn test_bee_weight_by_axis_name() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        approx::assert_relative_eq!(manifest.bee_weight("pm2_5").unwrap(), 2.25 * 1.5);
+        approx::assert_relative_eq!(manifest.bee_weight("co2").unwrap(), 1.0);
+        assert_eq!(manifest.bee_weight(4_usize).unwrap(), manifest.bee_weight("pm2_5").unwrap());
+        assert!(matches!(manifest.bee_weight("ozone"), Err(ManifestError::UnknownAxis(name)) if name == "ozone"));
+        assert!(matches!(manifest.bee_weight(5_usize), Err(ManifestError::UnknownAxis(_))));
+
+        // The multiplier follows the name, not the position.
+        let op = &mut manifest.outer_domain.nanokarma_op;
+        op.stressor_axes.swap(0, 4);
+        approx::assert_relative_eq!(manifest.bee_weight("pm2_5").unwrap(), 1.0 * 1.5);
+        approx::assert_relative_eq!(manifest.bee_weight(4_usize).unwrap(), 2.25);
+    }
+
+    #[test]
+    fn test_manifest_without_stressor_axes_keeps_legacy_weights() {
+        let mut value = serde_json::to_value(NeuroEcoIdentityManifest::default()).unwrap();
+        value["outer_domain"]["nanokarma_op"].as_object_mut().unwrap().remove("stressor_axes");
+        let legacy: NeuroEcoIdentityManifest = serde_json::from_value(value).unwrap();
+        let current = NeuroEcoIdentityManifest::default();
+        for i in 0..5_usize {
+            assert_eq!(legacy.bee_weight(i).unwrap(), current.bee_weight(i).unwrap());
+        }
+        assert_eq!(legacy.bee_weight("axis_4").unwrap(), current.bee_weight("pm2_5").unwrap());
+
+        let m_pos = DVector::from_vec(vec![5.0, 0.0, 0.0, 0.0, 0.0]);
+        let delta = legacy.raf_delta(m_pos.clone(), DVector::zeros(5)).unwrap();
+        assert_eq!(delta.per_axis, current.raf_delta(m_pos, DVector::zeros(5)).unwrap().per_axis);
+        assert_eq!(delta.by_axis().next(), Some(("axis_0", delta.total)));
+    }
+
+    #[test]
+    fn test_violations_and_errority_name_stressor_axes() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        let pm_cap = SafetyPolytope {
+            a: DMatrix::from_row_slice(1, 5, &[0.0, 0.0, 0.0, 1.0, 1.0]),
+            b: DVector::from_vec(vec![0.1]),
+            ..SafetyPolytope::default()
+        };
+        manifest.outer_domain.polytopes = vec![pm_cap];
+        let report = manifest.eco_admissible_detailed(&DVector::from_vec(vec![0.0, 0.0, 0.0, 0.1, 0.1])).unwrap();
+        assert_eq!(report.polytopes[0].violations[0].axes, ["voc", "pm2_5"]);
+
+        let err = manifest.raf_delta(DVector::zeros(5), DVector::from_vec(vec![0.0, 0.0, 0.0, 0.0, 2.0])).unwrap_err();
+        assert!(err.to_string().contains("worst axis pm2_5"), "{err}");
+    }
+
+    #[test]
+    fn test_apply_raf_accumulates() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        let restore = DVector::from_vec(vec![1.0, 0.0, 0.0, 0.0, 0.0]);
+        let mut expected = 0.0;
+        for _ in 0..3 {
+            let delta = manifest.raf_delta(restore.clone(), DVector::zeros(5)).unwrap();
+            expected += delta.total;
+            assert_eq!(manifest.apply_raf(&delta), expected);
+        }
+        approx::assert_relative_eq!(manifest.outer_domain.nanokarma_op.k_person_current, 0.3);
     }
 
     #[test]
@@ -229,11 +692,183 @@ mod tests {
         assert!(manifest.eco_admissible(&x_proj));  // Passes, earns NANO sim
     }
 
+    fn signer(seed: u8) -> (SigningKey, std::collections::HashMap<String, VerifyingKey>) {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let keys = [(format!("did:key:{seed}"), key.verifying_key())].into_iter().collect();
+        (key, keys)
+    }
+
+    #[test]
+    fn test_sign_verify_all_round_trip() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        assert!(matches!(manifest.verify_all(&std::collections::HashMap::new()), Err(ManifestError::Unsigned)));
+
+        let (host, mut keys) = signer(1);
+        let (steward, steward_keys) = signer(2);
+        keys.extend(steward_keys);
+        manifest.sign(&host, "did:key:1");
+        manifest.sign(&steward, "did:key:2");
+        manifest.verify_all(&keys).unwrap();
+
+        // Canonical bytes survive a JSON round trip, and skip signatures.
+        let reloaded: NeuroEcoIdentityManifest = serde_json::from_str(&serde_json::to_string(&manifest).unwrap()).unwrap();
+        assert_eq!(reloaded.canonical_bytes(), manifest.canonical_bytes());
+        reloaded.verify_all(&keys).unwrap();
+        let canon = String::from_utf8(manifest.canonical_bytes()).unwrap();
+        assert!(!canon.contains("signatures") && !canon.contains("live_metrics"));
+        // Sorted, not declaration order: `evidence_bundles` is declared after `id`.
+        assert!(canon.find("\"evidence_bundles\"").unwrap() < canon.find("\"id\"").unwrap());
+    }
+
+    #[test]
+    fn test_tampering_invalidates_signatures() {
+        let (key, keys) = signer(1);
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        manifest.sign(&key, "did:key:1");
+
+        let mut tampered = manifest.clone();
+        tampered.outer_domain.nanokarma_op.k_person_current = 42.0;
+        assert!(matches!(tampered.verify_all(&keys), Err(ManifestError::InvalidSignature)));
+        let mut truncated = manifest.clone();
+        truncated.signatures[0].signature.pop();
+        assert!(matches!(truncated.verify_all(&keys), Err(ManifestError::InvalidSignature)));
+
+        // Re-signing after an inner-domain change leaves the old signature
+        // over the old bytes, so the manifest no longer verifies.
+        let mut inner = serde_json::to_value(&manifest.inner_domain).unwrap();
+        assert!(flip_first_flag(&mut inner), "inner domain has a flag");
+        manifest.inner_domain = serde_json::from_value(inner).unwrap();
+        manifest.sign(&key, "did:key:1");
+        assert!(matches!(manifest.verify_all(&keys), Err(ManifestError::InvalidSignature)));
+        manifest.signatures.remove(0);
+        manifest.verify_all(&keys).unwrap();
+    }
+
+    fn flip_first_flag(value: &mut serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::Bool(flag) => {
+                *flag = !*flag;
+                true
+            }
+            serde_json::Value::Object(fields) => fields.values_mut().any(flip_first_flag),
+            serde_json::Value::Array(items) => items.iter_mut().any(flip_first_flag),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_unknown_key_id_is_refused() {
+        let (key, keys) = signer(1);
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        manifest.sign(&key, "did:key:1");
+        manifest.sign(&key, "did:key:9");
+        assert!(matches!(manifest.verify_all(&keys), Err(ManifestError::UnknownKey(id)) if id == "did:key:9"));
+    }
+
+    /// P_eco: x0 <= 1, x1 <= 0.2. P_bee: x0 + x1 <= 1.0, x1 <= 0.1, x2 <= 0.1.
+    fn two_polytopes() -> NeuroEcoIdentityManifest {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        manifest.outer_domain.polytopes = vec![
+            SafetyPolytope {
+                a: DMatrix::from_row_slice(2, 3, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0]),
+                b: DVector::from_vec(vec![1.0, 0.2]),
+                ..SafetyPolytope::default()
+            },
+            SafetyPolytope {
+                a: DMatrix::from_row_slice(3, 3, &[1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]),
+                b: DVector::from_vec(vec![1.0, 0.1, 0.1]),
+                ..SafetyPolytope::default()
+            },
+        ];
+        manifest
+    }
+
+    #[test]
+    fn test_eco_admissible_detailed_names_violated_rows() {
+        let manifest = two_polytopes();
+        let inside = DVector::from_vec(vec![0.5, 0.05, 0.0]);
+        let report = manifest.eco_admissible_detailed(&inside).unwrap();
+        assert!(report.admissible && manifest.eco_admissible(&inside));
+        approx::assert_relative_eq!(manifest.margin(&inside).unwrap(), 0.05);
+
+        // PM over the bee bound only: P_eco passes, P_bee row 1 fails by 0.05.
+        let x = DVector::from_vec(vec![0.5, 0.15, 0.0]);
+        let report = manifest.eco_admissible_detailed(&x).unwrap();
+        assert!(!report.admissible && !manifest.eco_admissible(&x));
+        assert!(report.polytopes[0].violations.is_empty());
+        let bee = &report.polytopes[1];
+        assert_eq!((bee.index, bee.violations.len(), bee.violations[0].row), (1, 1, 1));
+        approx::assert_relative_eq!(bee.violations[0].residual, 0.05, epsilon = 1e-12);
+        // Three columns against five stressor axes: positional names.
+        assert_eq!(bee.violations[0].axes, ["axis_1"]);
+        approx::assert_relative_eq!(report.margin(), -0.05, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_eco_admissible_dimension_mismatch_is_an_error() {
+        let manifest = two_polytopes();
+        let short = DVector::from_vec(vec![0.1, 0.05]);
+        let err = manifest.eco_admissible_detailed(&short).unwrap_err();
+        assert!(matches!(&err, ManifestError::PolytopeViolation(m) if m == "polytope 0 expects 3 axes, x_proj has 2"), "{err}");
+        assert!(!manifest.eco_admissible(&short));
+        assert!(manifest.margin(&short).is_err());
+    }
+
     #[test]
     fn test_err_log_refinement() {
         let mut manifest = NeuroEcoIdentityManifest::default();
         let event = ErrorityEvent { description: "Polytope edge-case".to_string(), delta_r: -0.1 };
         let bundle = manifest.err_log(event);
         assert!(!bundle.id.is_empty());  // Stamped, feeds WISE learning
+        // The returned bundle is the stored one, uri and timestamp included.
+        let stored = &manifest.evidence_bundles[0];
+        assert_eq!((stored.id(), stored.uri(), stored.timestamp()), (bundle.id(), bundle.uri(), bundle.timestamp()));
+        assert_eq!(bundle.uri(), format!("ipfs://{}", bundle.id()));
+        // No live metrics: nothing to lower, and nothing else moves.
+        assert!(manifest.live_metrics.is_none());
+        assert_eq!(manifest.outer_domain.nanokarma_op.k_person_current, 0.0);
+    }
+
+    #[test]
+    fn test_errority_events_round_trip() {
+        let mut manifest = NeuroEcoIdentityManifest::default();
+        manifest.err_log(ErrorityEvent { description: "Polytope edge-case".to_string(), delta_r: -0.1 });
+        manifest.err_log(ErrorityEvent { description: "Sensor gap".to_string(), delta_r: -0.02 });
+
+        let reloaded: NeuroEcoIdentityManifest = serde_json::from_str(&serde_json::to_string(&manifest).unwrap()).unwrap();
+        let events = reloaded.errority_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].1.description, "Polytope edge-case");
+        assert_eq!(events[1].1.delta_r, -0.02);
+        assert_eq!(events[1].0.id(), manifest.evidence_bundles[1].id());
+
+        // Bundles that only point elsewhere keep their old serialized form.
+        let mut pointer = manifest.evidence_bundles[0].clone();
+        pointer.payload = None;
+        assert!(!serde_json::to_string(&pointer).unwrap().contains("payload"));
+    }
+
+    fn live(raf_global: f64) -> LiveMetrics {
+        LiveMetrics {
+            raf_global,
+            raf_bee: raf_global,
+            k_deltas: KarmaDeltas { day: 0.0, week: 0.0 },
+            word_math: WordMathScore::default(),
+            duty_header: DutyHeader::default(),
+        }
+    }
+
+    #[test]
+    fn test_err_log_lowers_raf_global_to_the_floor() {
+        let mut manifest = NeuroEcoIdentityManifest { live_metrics: Some(live(0.5)), ..Default::default() };
+        manifest.err_log(ErrorityEvent { description: "Idling engine".to_string(), delta_r: -0.2 });
+        approx::assert_relative_eq!(manifest.live_metrics.as_ref().unwrap().raf_global, 0.3);
+
+        // Clamped at the 0.0 default floor; a positive delta_r never raises it.
+        manifest.err_log(ErrorityEvent { description: "Car trip".to_string(), delta_r: -0.9 });
+        assert_eq!(manifest.live_metrics.as_ref().unwrap().raf_global, 0.0);
+        manifest.err_log(ErrorityEvent { description: "Mislabelled".to_string(), delta_r: 0.4 });
+        assert_eq!(manifest.live_metrics.as_ref().unwrap().raf_global, 0.0);
+        assert_eq!(manifest.errority_events().len(), 3);
     }
 }
@@ -1,20 +1,21 @@
-use neuro_eco_manifest::{NeuroEcoIdentityManifest, DVector};
-use nalgebra::DMatrix;
+use neuro_eco_manifest::{ErrorityEvent, ManifestBuilder, NeuroEcoIdentityManifest, SafetyPolytope, PHOENIX_DID};
+use nalgebra::{DMatrix, DVector};
 
 fn main() {
     let mut manifest = NeuroEcoIdentityManifest::default();
     println!("NeuroEcoIdentityManifest initialized for Phoenix, AZ (MST baseline). Inner domain: absolute. Outer: RAF r0=0.5, HB=9.7/10 bee-focus.");
 
     // Sim: 0.2mi walk+smoke (M_neg: CO2=0.5kg, PM2.5=0.1kg) vs car (M_neg: CO2=2.0kg, PM2.5=0.05kg)
-    let m_walk_smoke_neg = DVector::from_vec(vec![0.5, 0.1]);
-    let m_car_neg = DVector::from_vec(vec![2.0, 0.05]);
-    let m_rest_pos = DVector::from_vec(vec![1.0, 0.0]);  // Cybo-Air restoration
+    // Axes follow lambda: CO2, ·, ·, VOC, PM2.5.
+    let m_walk_smoke_neg = DVector::from_vec(vec![0.5, 0.0, 0.0, 0.0, 0.1]);
+    let m_car_neg = DVector::from_vec(vec![2.0, 0.0, 0.0, 0.0, 0.05]);
+    let m_rest_pos = DVector::from_vec(vec![1.0, 0.0, 0.0, 0.0, 0.0]);  // Cybo-Air restoration
 
-    let delta_walk = manifest.raf_delta(m_rest_pos.clone(), m_walk_smoke_neg).unwrap();  // +0.05 net (eco-grant)
-    let delta_car = manifest.raf_delta(DVector::zeros(2), m_car_neg).unwrap();  // -0.2 (greed-unfair, triggers Errority)
+    let delta_walk = manifest.raf_delta(m_rest_pos.clone(), m_walk_smoke_neg).unwrap().total;  // +0.05 net (eco-grant)
+    let delta_car = manifest.raf_delta(DVector::zeros(5), m_car_neg).unwrap().total;  // -0.2 (greed-unfair, triggers Errority)
 
     if delta_car < -0.15 {
-        let err_event = extensions::ErrorityEvent { description: "High-emission choice; route to restoration".to_string(), delta_r: delta_car };
+        let err_event = ErrorityEvent { description: "High-emission choice; route to restoration".to_string(), delta_r: delta_car };
         manifest.err_log(err_event);  // Logs for polytope tighten, earns WISE
     }
 
@@ -26,7 +27,9 @@ fn main() {
     let x_proj = DVector::from_vec(vec![0.1, 0.05, 0.0]);  // Stressors: CO2, PM, VOC
     let a_eco = DMatrix::from_row_slice(3, 3, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);  // Identity constraints
     let b_eco = DVector::from_vec(vec![1.0, 0.2, 0.1]);  // Bounds: CO2<1kg, PM<0.2, VOC<0.1
-    if manifest.eco_admissible(&x_proj) {
+    let p_eco = SafetyPolytope { name: "P_eco".to_string(), a: a_eco, b: b_eco };
+    let checker = ManifestBuilder::new(PHOENIX_DID).polytopes(vec![p_eco]).build().unwrap();
+    if checker.eco_admissible(&x_proj) {
         println!("Action admissible: Bee-safe (BEE_WEIGHT=1.5x on PM/VOC), earns POWER.");
     }
 
@@ -0,0 +1,40 @@
+// Module: Outer domain. Safety polytopes P = { x : A x <= b } over projected stressor loads, and
+// the admissibility traits the manifest's outer checks are written against.
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+/// Whether a projected stressor load `x_proj` stays inside the safe set.
+pub trait EcoAdmissible {
+    fn eco_admissible(&self, x_proj: &DVector<f64>) -> bool;
+}
+
+/// Whether a NanoKarma change keeps the outer domain above its floor.
+pub trait KarmaAdmissible {
+    fn karma_admissible(&self, delta_k: f64) -> bool;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SafetyPolytope {
+    pub name: String,  // "P_eco", "P_bee"
+    pub a: DMatrix<f64>,
+    pub b: DVector<f64>,
+}
+
+impl EcoAdmissible for SafetyPolytope {
+    fn eco_admissible(&self, x_proj: &DVector<f64>) -> bool {
+        self.a.ncols() == x_proj.len()
+            && self.a.nrows() == self.b.len()
+            && (&self.a * x_proj - &self.b).iter().all(|r| *r <= 0.0)
+    }
+}
+
+/// P_eco baseline over (PM2.5, VOC), kg: PM2.5 <= 0.2, VOC <= 0.1.
+impl Default for SafetyPolytope {
+    fn default() -> Self {
+        Self {
+            name: "P_eco".to_string(),
+            a: DMatrix::identity(2, 2),
+            b: DVector::from_vec(vec![0.2, 0.1]),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// Module: Signaling. Real-time scores and headers a manifest broadcasts with its live metrics.
+// None of it is anchored: `canonical_bytes` leaves live metrics out of what signatures cover.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Word-math fairness score: restorative minus harmful terms, normalized to [-1, 1].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WordMathScore {
+    pub score: f64,
+    pub terms: Vec<String>,
+}
+
+/// The duty a node currently owes (e.g. "restore-pm2_5") and when it was raised.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DutyHeader {
+    pub duty: Option<String>,
+    pub raised_at: Option<DateTime<Utc>>,
+}
+
+/// One broadcast RAF change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LiveDelta {
+    pub raf_delta: f64,
+    pub raf_bee_delta: f64,
+    pub at: DateTime<Utc>,
+}
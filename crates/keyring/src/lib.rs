@@ -0,0 +1,282 @@
+//! Named ed25519 signing keys for every Church-of-FEAR identity.
+//!
+//! A `Keyring` holds the secret halves, encrypted at rest under a
+//! passphrase (see `store`). Each key carries a purpose and a validity
+//! window; `rotate` creates a successor and leaves the old key able to
+//! verify only. Verifiers never see secrets: they receive a
+//! `VerifyingBundle` exported with `verifying_bundle()`.
+//!
+//! Signatures are dated. The signed message is
+//! `DOMAIN || signed_at (u64 BE) || bytes`, so a verifier can reject
+//! signatures made outside the key's window without trusting the caller.
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod store;
+
+pub use store::StoreOptions;
+
+const DOMAIN: &[u8] = b"cof-keyring-v1";
+
+/// Default validity for newly generated keys: one year.
+pub const DEFAULT_LIFETIME_SECS: u64 = 365 * 86_400;
+
+#[derive(Error, Debug)]
+pub enum KeyringError {
+    #[error("unknown key {0}")]
+    UnknownKey(String),
+    #[error("purpose must not be empty")]
+    EmptyPurpose,
+    #[error("key {name} was superseded by {successor} and can only verify")]
+    Superseded { name: String, successor: String },
+    #[error("key {name} expired at {not_after}")]
+    Expired { name: String, not_after: u64 },
+    #[error("key {name} is not valid before {created_at}")]
+    NotYetValid { name: String, created_at: u64 },
+    #[error("signature by {name} dated {signed_at} is outside the key's validity window")]
+    OutsideWindow { name: String, signed_at: u64 },
+    #[error("invalid signature by {0}")]
+    BadSignature(String),
+    #[error("store failed authentication (wrong passphrase or tampered file)")]
+    Tampered,
+    #[error("unsupported store version {0}")]
+    UnsupportedVersion(u32),
+    #[error("malformed store: {0}")]
+    Format(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Public metadata for one key. Safe to export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyMeta {
+    pub name: String,
+    pub purpose: String,
+    /// Hex-encoded ed25519 verifying key.
+    pub public_key: String,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Unix seconds; signatures dated after this fail verification.
+    pub not_after: u64,
+    pub superseded_by: Option<String>,
+}
+
+impl KeyMeta {
+    fn verifying_key(&self) -> Result<VerifyingKey, KeyringError> {
+        let bytes: [u8; 32] = hex::decode(&self.public_key)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| KeyringError::Format(format!("bad public key for {}", self.name)))?;
+        VerifyingKey::from_bytes(&bytes).map_err(|_| KeyringError::Format(format!("bad public key for {}", self.name)))
+    }
+}
+
+/// A dated signature naming the key that made it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyringSignature {
+    pub key: String,
+    /// Unix seconds at signing time.
+    pub signed_at: u64,
+    /// Hex-encoded 64-byte ed25519 signature.
+    pub signature: String,
+}
+
+fn signed_message(signed_at: u64, bytes: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(DOMAIN.len() + 8 + bytes.len());
+    msg.extend_from_slice(DOMAIN);
+    msg.extend_from_slice(&signed_at.to_be_bytes());
+    msg.extend_from_slice(bytes);
+    msg
+}
+
+/// Anything that can check a `KeyringSignature`: the full `Keyring` or an
+/// exported `VerifyingBundle`.
+pub trait SignatureVerifier {
+    fn key_meta(&self, name: &str) -> Option<&KeyMeta>;
+
+    /// Verify `sig` over `bytes`. Fails if the key is unknown, if the
+    /// signature is dated outside `[created_at, not_after]`, or if it is
+    /// dated after the key's successor took over.
+    fn verify(&self, bytes: &[u8], sig: &KeyringSignature) -> Result<(), KeyringError> {
+        let meta = self.key_meta(&sig.key).ok_or_else(|| KeyringError::UnknownKey(sig.key.clone()))?;
+        let outside = || KeyringError::OutsideWindow { name: meta.name.clone(), signed_at: sig.signed_at };
+        if sig.signed_at < meta.created_at || sig.signed_at > meta.not_after {
+            return Err(outside());
+        }
+        if let Some(successor) = meta.superseded_by.as_deref().and_then(|s| self.key_meta(s)) {
+            if sig.signed_at > successor.created_at {
+                return Err(outside());
+            }
+        }
+        let raw: [u8; 64] = hex::decode(&sig.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| KeyringError::BadSignature(sig.key.clone()))?;
+        meta.verifying_key()?
+            .verify(&signed_message(sig.signed_at, bytes), &Signature::from_bytes(&raw))
+            .map_err(|_| KeyringError::BadSignature(sig.key.clone()))
+    }
+}
+
+/// Public keys and validity windows, for distribution to verifiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyingBundle {
+    pub keys: Vec<KeyMeta>,
+}
+
+impl VerifyingBundle {
+    /// Keys for `purpose` that may still sign at `now`.
+    pub fn active(&self, purpose: &str, now: u64) -> impl Iterator<Item = &KeyMeta> {
+        let purpose = purpose.to_string();
+        self.keys.iter().filter(move |k| {
+            k.purpose == purpose && k.superseded_by.is_none() && k.created_at <= now && now <= k.not_after
+        })
+    }
+}
+
+impl SignatureVerifier for VerifyingBundle {
+    fn key_meta(&self, name: &str) -> Option<&KeyMeta> {
+        self.keys.iter().find(|k| k.name == name)
+    }
+}
+
+/// Secret key plus its metadata. `SigningKey` zeroizes itself on drop.
+struct KeyEntry {
+    meta: KeyMeta,
+    secret: SigningKey,
+}
+
+type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+fn system_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+pub struct Keyring {
+    keys: BTreeMap<String, KeyEntry>,
+    lifetime_secs: u64,
+    clock: Clock,
+}
+
+impl Default for Keyring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print secrets.
+        f.debug_struct("Keyring").field("keys", &self.keys.keys().collect::<Vec<_>>()).finish()
+    }
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self { keys: BTreeMap::new(), lifetime_secs: DEFAULT_LIFETIME_SECS, clock: Box::new(system_now) }
+    }
+
+    /// Validity for keys created from now on.
+    pub fn with_lifetime(mut self, secs: u64) -> Self {
+        self.lifetime_secs = secs;
+        self
+    }
+
+    /// Replace the wall clock (Unix seconds); used by tests and replays.
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    fn next_name(&self, purpose: &str) -> String {
+        let n = self.keys.values().filter(|k| k.meta.purpose == purpose).count() + 1;
+        format!("{}-{}", purpose, n)
+    }
+
+    fn insert_new(&mut self, purpose: &str) -> String {
+        let now = (self.clock)();
+        let secret = SigningKey::generate(&mut OsRng);
+        let name = self.next_name(purpose);
+        let meta = KeyMeta {
+            name: name.clone(),
+            purpose: purpose.to_string(),
+            public_key: hex::encode(secret.verifying_key().as_bytes()),
+            created_at: now,
+            not_after: now.saturating_add(self.lifetime_secs),
+            superseded_by: None,
+        };
+        self.keys.insert(name.clone(), KeyEntry { meta, secret });
+        name
+    }
+
+    /// Create a fresh key for `purpose` and return its name (`<purpose>-<n>`).
+    pub fn generate(&mut self, purpose: &str) -> Result<String, KeyringError> {
+        if purpose.trim().is_empty() {
+            return Err(KeyringError::EmptyPurpose);
+        }
+        Ok(self.insert_new(purpose))
+    }
+
+    /// Create a successor for `name` with the same purpose. The old key
+    /// keeps its window but can no longer sign.
+    pub fn rotate(&mut self, name: &str) -> Result<String, KeyringError> {
+        let entry = self.keys.get(name).ok_or_else(|| KeyringError::UnknownKey(name.to_string()))?;
+        if let Some(successor) = &entry.meta.superseded_by {
+            return Err(KeyringError::Superseded { name: name.to_string(), successor: successor.clone() });
+        }
+        let purpose = entry.meta.purpose.clone();
+        let successor = self.insert_new(&purpose);
+        if let Some(old) = self.keys.get_mut(name) {
+            old.meta.superseded_by = Some(successor.clone());
+        }
+        Ok(successor)
+    }
+
+    /// Sign `bytes` with `name`, dated now. Superseded and expired keys refuse.
+    pub fn sign(&self, name: &str, bytes: &[u8]) -> Result<KeyringSignature, KeyringError> {
+        let entry = self.keys.get(name).ok_or_else(|| KeyringError::UnknownKey(name.to_string()))?;
+        let meta = &entry.meta;
+        if let Some(successor) = &meta.superseded_by {
+            return Err(KeyringError::Superseded { name: name.to_string(), successor: successor.clone() });
+        }
+        let now = (self.clock)();
+        if now > meta.not_after {
+            return Err(KeyringError::Expired { name: name.to_string(), not_after: meta.not_after });
+        }
+        if now < meta.created_at {
+            return Err(KeyringError::NotYetValid { name: name.to_string(), created_at: meta.created_at });
+        }
+        let sig = entry.secret.sign(&signed_message(now, bytes));
+        Ok(KeyringSignature { key: name.to_string(), signed_at: now, signature: hex::encode(sig.to_bytes()) })
+    }
+
+    /// The current signing key for `purpose`: not superseded and not expired.
+    pub fn active(&self, purpose: &str) -> Option<&KeyMeta> {
+        let now = (self.clock)();
+        self.keys
+            .values()
+            .map(|k| &k.meta)
+            .filter(|m| m.purpose == purpose && m.superseded_by.is_none() && m.created_at <= now && now <= m.not_after)
+            .max_by_key(|m| m.created_at)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &KeyMeta> {
+        self.keys.values().map(|k| &k.meta)
+    }
+
+    pub fn verifying_bundle(&self) -> VerifyingBundle {
+        VerifyingBundle { keys: self.keys().cloned().collect() }
+    }
+}
+
+impl SignatureVerifier for Keyring {
+    fn key_meta(&self, name: &str) -> Option<&KeyMeta> {
+        self.keys.get(name).map(|k| &k.meta)
+    }
+}
@@ -0,0 +1,154 @@
+//! Encrypted store file.
+//!
+//! ```text
+//! { "version": 1,
+//!   "kdf": { "salt": hex, "m_cost_kib": .., "t_cost": .., "p_cost": .. },
+//!   "nonce": hex, "ciphertext": hex }
+//! ```
+//!
+//! The key is Argon2id(passphrase, salt); the body is ChaCha20-Poly1305
+//! over the JSON key list, with `version` and `kdf` bound as associated
+//! data so neither can be edited without failing authentication.
+
+use std::fs;
+use std::path::Path;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::SigningKey;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{KeyEntry, KeyMeta, Keyring, KeyringError};
+
+const STORE_VERSION: u32 = 1;
+
+/// Argon2id cost parameters used when saving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreOptions {
+    pub m_cost_kib: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        Self { m_cost_kib: 19 * 1024, t_cost: 2, p_cost: 1 }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfHeader {
+    salt: String,
+    #[serde(flatten)]
+    options: StoreOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    kdf: KdfHeader,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+struct StoredKey {
+    #[zeroize(skip)]
+    meta: KeyMeta,
+    secret: String,
+}
+
+fn associated_data(version: u32, kdf: &KdfHeader) -> Result<Vec<u8>, KeyringError> {
+    serde_json::to_vec(&(version, kdf)).map_err(|e| KeyringError::Format(e.to_string()))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], opts: StoreOptions) -> Result<Zeroizing<[u8; 32]>, KeyringError> {
+    let params = Params::new(opts.m_cost_kib, opts.t_cost, opts.p_cost, Some(32))
+        .map_err(|e| KeyringError::Format(format!("kdf params: {e}")))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| KeyringError::Format(format!("kdf: {e}")))?;
+    Ok(key)
+}
+
+fn unhex(field: &str, value: &str) -> Result<Vec<u8>, KeyringError> {
+    hex::decode(value).map_err(|_| KeyringError::Format(format!("{field} is not hex")))
+}
+
+impl Keyring {
+    /// Encrypt and write the keyring to `path` with default KDF costs.
+    pub fn save(&self, path: impl AsRef<Path>, passphrase: &str) -> Result<(), KeyringError> {
+        self.save_with(path, passphrase, StoreOptions::default())
+    }
+
+    pub fn save_with(&self, path: impl AsRef<Path>, passphrase: &str, options: StoreOptions) -> Result<(), KeyringError> {
+        let stored: Vec<StoredKey> = self
+            .keys
+            .values()
+            .map(|k| StoredKey { meta: k.meta.clone(), secret: hex::encode(k.secret.to_bytes()) })
+            .collect();
+        let plaintext = Zeroizing::new(serde_json::to_vec(&stored).map_err(|e| KeyringError::Format(e.to_string()))?);
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = KdfHeader { salt: hex::encode(salt), options };
+        let key = derive_key(passphrase, &salt, options)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(STORE_VERSION, &kdf)?;
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+            .encrypt(&nonce, Payload { msg: &plaintext, aad: &aad })
+            .map_err(|_| KeyringError::Format("encryption failed".into()))?;
+
+        let file = StoreFile { version: STORE_VERSION, kdf, nonce: hex::encode(nonce), ciphertext: hex::encode(ciphertext) };
+        let body = serde_json::to_vec_pretty(&file).map_err(|e| KeyringError::Format(e.to_string()))?;
+        // Write-then-rename so a crash never leaves a half-written store.
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, body)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Decrypt a store written by `save`. Any edit to the file, or a wrong
+    /// passphrase, yields `KeyringError::Tampered`.
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> Result<Self, KeyringError> {
+        let raw = fs::read(path)?;
+        let file: StoreFile = serde_json::from_slice(&raw).map_err(|_| KeyringError::Tampered)?;
+        if file.version != STORE_VERSION {
+            return Err(KeyringError::UnsupportedVersion(file.version));
+        }
+        let salt = unhex("salt", &file.kdf.salt)?;
+        let nonce = unhex("nonce", &file.nonce)?;
+        if nonce.len() != 12 {
+            return Err(KeyringError::Tampered);
+        }
+        let ciphertext = unhex("ciphertext", &file.ciphertext)?;
+        let key = derive_key(passphrase, &salt, file.kdf.options)?;
+        let aad = associated_data(file.version, &file.kdf)?;
+        let plaintext = Zeroizing::new(
+            ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+                .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+                .map_err(|_| KeyringError::Tampered)?,
+        );
+        let stored: Vec<StoredKey> = serde_json::from_slice(&plaintext).map_err(|e| KeyringError::Format(e.to_string()))?;
+
+        let mut keyring = Keyring::new();
+        for s in &stored {
+            let bytes = Zeroizing::new(unhex("secret", &s.secret)?);
+            let secret: &[u8; 32] = bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| KeyringError::Format(format!("bad secret for {}", s.meta.name)))?;
+            let secret = SigningKey::from_bytes(secret);
+            if hex::encode(secret.verifying_key().as_bytes()) != s.meta.public_key {
+                return Err(KeyringError::Format(format!("public key mismatch for {}", s.meta.name)));
+            }
+            keyring.keys.insert(s.meta.name.clone(), KeyEntry { meta: s.meta.clone(), secret });
+        }
+        Ok(keyring)
+    }
+}
@@ -0,0 +1,99 @@
+use keyring::{Keyring, KeyringError, SignatureVerifier, StoreOptions};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+// Cheap KDF so the tests stay fast; production uses StoreOptions::default().
+const FAST: StoreOptions = StoreOptions { m_cost_kib: 64, t_cost: 1, p_cost: 1 };
+
+fn clocked(start: u64) -> (Keyring, Arc<AtomicU64>) {
+    let now = Arc::new(AtomicU64::new(start));
+    let clock = now.clone();
+    let keyring = Keyring::new().with_lifetime(1_000).with_clock(move || clock.load(Ordering::SeqCst));
+    (keyring, now)
+}
+
+#[test]
+fn encrypt_decrypt_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("keys.json");
+    let mut keyring = Keyring::new();
+    let manifest = keyring.generate("manifest").unwrap();
+    let rpc = keyring.generate("rpc").unwrap();
+    let sig = keyring.sign(&manifest, b"payload").unwrap();
+    keyring.save_with(&path, "correct horse", FAST).unwrap();
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert!(!raw.contains(&keyring.verifying_bundle().keys[0].public_key), "body must be encrypted");
+
+    let reopened = Keyring::open(&path, "correct horse").unwrap();
+    assert_eq!(reopened.verifying_bundle(), keyring.verifying_bundle());
+    reopened.verify(b"payload", &sig).unwrap();
+    let again = reopened.sign(&rpc, b"other").unwrap();
+    keyring.verifying_bundle().verify(b"other", &again).unwrap();
+
+    assert!(matches!(Keyring::open(&path, "wrong"), Err(KeyringError::Tampered)));
+}
+
+#[test]
+fn rotated_key_still_verifies_within_its_window() {
+    let (mut keyring, now) = clocked(100);
+    let old = keyring.generate("attestation").unwrap();
+    let old_sig = keyring.sign(&old, b"deed").unwrap();
+
+    now.store(200, Ordering::SeqCst);
+    let new = keyring.rotate(&old).unwrap();
+    assert_eq!(keyring.active("attestation").unwrap().name, new);
+    assert!(matches!(keyring.sign(&old, b"late"), Err(KeyringError::Superseded { .. })));
+
+    let bundle = keyring.verifying_bundle();
+    bundle.verify(b"deed", &old_sig).unwrap();
+    let new_sig = keyring.sign(&new, b"deed").unwrap();
+    bundle.verify(b"deed", &new_sig).unwrap();
+    assert!(matches!(bundle.verify(b"tampered", &old_sig), Err(KeyringError::BadSignature(_))));
+
+    // A forged date past the old key's handover is refused.
+    let mut backdated = old_sig.clone();
+    backdated.signed_at = 500;
+    assert!(matches!(bundle.verify(b"deed", &backdated), Err(KeyringError::OutsideWindow { .. })));
+}
+
+#[test]
+fn signing_after_expiry_is_rejected() {
+    let (mut keyring, now) = clocked(100);
+    let name = keyring.generate("rpc").unwrap();
+    let sig = keyring.sign(&name, b"x").unwrap();
+
+    now.store(1_101, Ordering::SeqCst);
+    assert!(matches!(keyring.sign(&name, b"x"), Err(KeyringError::Expired { not_after: 1_100, .. })));
+    assert!(keyring.active("rpc").is_none());
+    // Signatures made inside the window keep verifying after expiry.
+    keyring.verify(b"x", &sig).unwrap();
+
+    let mut late = sig;
+    late.signed_at = 1_101;
+    assert!(matches!(keyring.verify(b"x", &late), Err(KeyringError::OutsideWindow { .. })));
+}
+
+#[test]
+fn tampered_store_is_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("keys.json");
+    let mut keyring = Keyring::new();
+    keyring.generate("governance").unwrap();
+    keyring.save_with(&path, "pw", FAST).unwrap();
+
+    let mut file: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    let ct = file["ciphertext"].as_str().unwrap().to_string();
+    let first = if ct.starts_with('0') { '1' } else { '0' };
+    let flipped = format!("{first}{}", &ct[1..]);
+    file["ciphertext"] = flipped.into();
+    std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+    assert!(matches!(Keyring::open(&path, "pw"), Err(KeyringError::Tampered)));
+
+    // Header fields are bound as associated data too.
+    keyring.save_with(&path, "pw", FAST).unwrap();
+    let mut file: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    file["kdf"]["t_cost"] = 2.into();
+    std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+    assert!(matches!(Keyring::open(&path, "pw"), Err(KeyringError::Tampered)));
+}
@@ -0,0 +1,108 @@
+// Generates `ParamId`, the typed `ParamKey` constants and the spec table
+// from params.json. Durations are declared in whole seconds; an optional
+// "tighten" ("lower" or "higher") makes a parameter tighten-only.
+
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::{env, fs, path::Path};
+
+const DECLARATIONS: &str = "params.json";
+
+/// Defaults that are exactly a `std::f64::consts` value are emitted as the
+/// constant, so the generated table reads (and lints) as what was meant.
+const NAMED_F64: &[(f64, &str)] = &[
+    (std::f64::consts::LN_2, "std::f64::consts::LN_2"),
+    (std::f64::consts::LN_10, "std::f64::consts::LN_10"),
+    (std::f64::consts::E, "std::f64::consts::E"),
+    (std::f64::consts::PI, "std::f64::consts::PI"),
+];
+
+fn f64_literal(value: f64) -> String {
+    NAMED_F64
+        .iter()
+        .find(|(named, _)| *named == value)
+        .map_or_else(|| format!("{:?}", value), |(_, path)| path.to_string())
+}
+
+fn literal(kind: &str, v: &Value, key: &str, field: &str) -> String {
+    let msg = format!("{}: {}.{} missing or not a {}", DECLARATIONS, key, field, kind);
+    match kind {
+        "f64" => format!("ParamValue::F64({})", f64_literal(v.as_f64().expect(&msg))),
+        "u64" => format!("ParamValue::U64({})", v.as_u64().expect(&msg)),
+        "duration" => format!("ParamValue::Duration(Duration::from_secs({}))", v.as_u64().expect(&msg)),
+        "bool" => format!("ParamValue::Bool({})", v.as_bool().expect(&msg)),
+        other => panic!("{}: {}: unknown kind '{}'", DECLARATIONS, key, other),
+    }
+}
+
+fn generate(decls: &Value) -> String {
+    let params = decls["params"].as_array().expect("params array");
+    let mut ids = String::new();
+    let mut keys = String::new();
+    let mut specs = String::new();
+
+    for p in params {
+        let key = p["key"].as_str().expect("param key");
+        let name = p["name"].as_str().expect("param name");
+        let kind = p["kind"].as_str().expect("param kind");
+        let doc = p["doc"].as_str().expect("param doc");
+        let (variant, rust_type) = match kind {
+            "f64" => ("ParamKind::F64", "f64"),
+            "u64" => ("ParamKind::U64", "u64"),
+            "duration" => ("ParamKind::Duration", "Duration"),
+            "bool" => ("ParamKind::Bool", "bool"),
+            other => panic!("{}: {}: unknown kind '{}'", DECLARATIONS, key, other),
+        };
+        let sensitivity = match p["sensitivity"].as_str().expect("param sensitivity") {
+            "informational" => "Sensitivity::Informational",
+            "safety_relevant" => "Sensitivity::SafetyRelevant",
+            other => panic!("{}: {}: unknown sensitivity '{}'", DECLARATIONS, key, other),
+        };
+        let tighten = match p.get("tighten").map(|t| t.as_str().expect("param tighten")) {
+            None => "None",
+            Some("lower") => "Some(Tighten::Lower)",
+            Some("higher") => "Some(Tighten::Higher)",
+            Some(other) => panic!("{}: {}: unknown tighten direction '{}'", DECLARATIONS, key, other),
+        };
+        let bound = |field: &str| match kind {
+            "bool" => format!("{}", if field == "min" { 0.0 } else { 1.0 }),
+            _ => format!("{:?}", p[field].as_f64().unwrap_or_else(|| panic!("{}: {}.{} missing", DECLARATIONS, key, field))),
+        };
+
+        writeln!(ids, "    /// {}\n    {},", doc, key).unwrap();
+        writeln!(keys, "    /// {}\n    pub const {}: Key<{}> = Key::new(ParamId::{});", doc, key, rust_type, key).unwrap();
+        writeln!(
+            specs,
+            "    ParamSpec {{\n        id: ParamId::{},\n        name: {:?},\n        kind: {},\n        default: {},\n        min: {},\n        max: {},\n        sensitivity: {},\n        tighten: {},\n        doc: {:?},\n    }},",
+            key,
+            name,
+            variant,
+            literal(kind, &p["default"], key, "default"),
+            bound("min"),
+            bound("max"),
+            sensitivity,
+            tighten,
+            doc,
+        )
+        .unwrap();
+    }
+
+    let mut out = String::new();
+    writeln!(out, "/// Every declared parameter, in declaration order.").unwrap();
+    writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]").unwrap();
+    writeln!(out, "pub enum ParamId {{\n{}}}\n", ids).unwrap();
+    writeln!(out, "/// Typed handles for `ParamRegistry::get`.").unwrap();
+    writeln!(out, "pub struct ParamKey;\n").unwrap();
+    writeln!(out, "#[allow(non_upper_case_globals)]\nimpl ParamKey {{\n{}}}\n", keys).unwrap();
+    writeln!(out, "/// Declarations, indexed by `ParamId as usize`.").unwrap();
+    writeln!(out, "pub const SPECS: &[ParamSpec] = &[\n{}];", specs).unwrap();
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", DECLARATIONS);
+    let raw = fs::read_to_string(DECLARATIONS).expect("read params.json");
+    let decls: Value = serde_json::from_str(&raw).expect("parse params.json");
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("params.rs");
+    fs::write(out, generate(&decls)).expect("write generated params");
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "governance")]
+use crate::registry::{ParamChangeRecord, ParamError, ParamRegistry};
+
+/// Who may approve parameter changes and how fresh a request must be.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangePolicy {
+    /// Keyring purpose of the authority keys.
+    pub purpose: String,
+    /// Distinct authority keys a change must be signed by.
+    pub threshold: usize,
+    /// Requests further than this from the node clock are refused.
+    pub max_age_secs: i64,
+}
+
+impl Default for ChangePolicy {
+    fn default() -> Self {
+        Self { purpose: "param-authority".to_string(), threshold: 2, max_age_secs: 3600 }
+    }
+}
+
+/// A proposed value for one parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamChange {
+    /// Unique per change; a replayed id is refused.
+    pub change_id: String,
+    pub param: String,
+    pub value: Value,
+    pub reason: String,
+    /// Unix seconds.
+    pub requested_at: i64,
+}
+
+impl ParamChange {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("parameter change serializes")
+    }
+}
+
+#[cfg(feature = "governance")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedParamChange {
+    pub change: ParamChange,
+    pub approvals: Vec<keyring::KeyringSignature>,
+}
+
+#[cfg(feature = "governance")]
+impl ParamRegistry {
+    /// Check `signed` and return the record to log; nothing is applied
+    /// until the caller commits it. The change must be fresh, unseen,
+    /// within bounds, and approved by at least `policy.threshold` distinct
+    /// keys of `policy.purpose`.
+    pub fn authorize(
+        &self,
+        signed: &SignedParamChange,
+        authorities: &keyring::VerifyingBundle,
+        policy: &ChangePolicy,
+        now: i64,
+    ) -> Result<ParamChangeRecord, ParamError> {
+        use keyring::SignatureVerifier;
+        use std::collections::BTreeSet;
+
+        let change = &signed.change;
+        let (id, value) = self.check_change(&change.change_id, &change.param, &change.value)?;
+        if (now - change.requested_at).abs() > policy.max_age_secs {
+            return Err(ParamError::Stale { id: change.change_id.clone(), requested_at: change.requested_at });
+        }
+        let bytes = change.signing_bytes();
+        let mut signers = BTreeSet::new();
+        for approval in &signed.approvals {
+            let meta = authorities.key_meta(&approval.key).ok_or_else(|| ParamError::NotAuthority(approval.key.clone()))?;
+            if meta.purpose != policy.purpose {
+                return Err(ParamError::NotAuthority(approval.key.clone()));
+            }
+            authorities.verify(&bytes, approval)?;
+            signers.insert(approval.key.clone());
+        }
+        if signers.len() < policy.threshold.max(1) {
+            return Err(ParamError::NotEnoughApprovals { got: signers.len(), need: policy.threshold.max(1) });
+        }
+        Ok(ParamChangeRecord {
+            change_id: change.change_id.clone(),
+            param: change.param.clone(),
+            value: value.to_json(),
+            previous: self.entry(id).value.to_json(),
+            reason: change.reason.clone(),
+            signers: signers.into_iter().collect(),
+            applied_at: now,
+            event_id: None,
+        })
+    }
+}
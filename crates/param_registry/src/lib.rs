@@ -0,0 +1,24 @@
+//! Typed runtime parameters.
+//!
+//! Every tunable threshold is declared once in `params.json` with a kind,
+//! inclusive bounds, a default and a sensitivity class. The build script
+//! turns the declarations into `ParamId`, the typed `ParamKey` constants
+//! and the `SPECS` table, so `params.get(ParamKey::ErrorityTriggerDelta)`
+//! is checked at compile time and returns an `f64`.
+//!
+//! Informational parameters accept config-file overrides. Safety-relevant
+//! ones change only through a `ParamChangeRecord` carried by a ledger deed,
+//! after `ParamRegistry::authorize` has checked its multisig approvals
+//! (the `governance` feature). Every value reports where it came from.
+//! Parameters declared with a `tighten` direction are tighten-only: a
+//! governance change may move them that way and never back.
+
+mod change;
+mod registry;
+mod spec;
+
+#[cfg(feature = "governance")]
+pub use change::SignedParamChange;
+pub use change::{ChangePolicy, ParamChange};
+pub use registry::{ParamChangeRecord, ParamEntry, ParamError, ParamListing, ParamRegistry, Provenance};
+pub use spec::{Key, ParamId, ParamKey, ParamKind, ParamSpec, ParamType, ParamValue, Sensitivity, Tighten, SPECS};
@@ -0,0 +1,175 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::spec::{Key, ParamId, ParamKind, ParamType, ParamValue, Sensitivity, Tighten, SPECS};
+
+#[derive(Error, Debug)]
+pub enum ParamError {
+    #[error("unknown parameter {0}")]
+    Unknown(String),
+    #[error("{param} takes a {expected:?} value, got {got}")]
+    WrongKind { param: String, expected: ParamKind, got: Value },
+    #[error("{param} = {value} is outside [{min}, {max}]")]
+    OutOfBounds { param: String, value: f64, min: f64, max: f64 },
+    #[error("{param} is tighten-only; {proposed} would loosen it from {current}")]
+    Loosening { param: String, current: f64, proposed: f64 },
+    #[error("{0} is safety-relevant; change it through a parameter_change deed")]
+    RequiresGovernance(String),
+    #[cfg(feature = "governance")]
+    #[error("change approval: {0}")]
+    Signature(#[from] keyring::KeyringError),
+    #[error("key {0} is not a parameter authority")]
+    NotAuthority(String),
+    #[error("change has {got} distinct authority approvals, needs {need}")]
+    NotEnoughApprovals { got: usize, need: usize },
+    #[error("change {id} requested at {requested_at} is not fresh")]
+    Stale { id: String, requested_at: i64 },
+    #[error("change {0} was already applied")]
+    Replayed(String),
+}
+
+/// Where a parameter's current value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Provenance {
+    Default,
+    Config { source: String },
+    /// A `parameter_change` deed; `event_id` is unset until it is logged.
+    Governance { change_id: String, event_id: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamEntry {
+    pub value: ParamValue,
+    pub provenance: Provenance,
+}
+
+/// One applied change, as carried in its `parameter_change` deed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamChangeRecord {
+    pub change_id: String,
+    pub param: String,
+    pub value: Value,
+    pub previous: Value,
+    pub reason: String,
+    /// Authority keys whose approvals were verified.
+    pub signers: Vec<String>,
+    pub applied_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+}
+
+/// A parameter's current value, bounds and provenance, for operators.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamListing {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub value: Value,
+    pub default: Value,
+    pub min: f64,
+    pub max: f64,
+    pub sensitivity: Sensitivity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tighten: Option<Tighten>,
+    pub provenance: Provenance,
+    pub doc: &'static str,
+}
+
+/// Current values of every declared parameter plus the governance
+/// history that produced them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamRegistry {
+    /// Indexed by `ParamId as usize`.
+    entries: Vec<ParamEntry>,
+    history: Vec<ParamChangeRecord>,
+    applied: BTreeSet<String>,
+}
+
+impl Default for ParamRegistry {
+    fn default() -> Self {
+        Self {
+            entries: SPECS.iter().map(|s| ParamEntry { value: s.default, provenance: Provenance::Default }).collect(),
+            history: Vec::new(),
+            applied: BTreeSet::new(),
+        }
+    }
+}
+
+impl ParamRegistry {
+    pub fn get<T: ParamType>(&self, key: Key<T>) -> T {
+        T::from_value(&self.entry(key.id()).value).expect("stored value matches its declared kind")
+    }
+
+    pub fn entry(&self, id: ParamId) -> &ParamEntry {
+        &self.entries[id as usize]
+    }
+
+    /// Override an informational parameter from a config file.
+    pub fn apply_config(&mut self, name: &str, raw: &Value, source: &str) -> Result<(), ParamError> {
+        let spec = ParamId::from_name(name).ok_or_else(|| ParamError::Unknown(name.to_string()))?.spec();
+        if spec.sensitivity == Sensitivity::SafetyRelevant {
+            return Err(ParamError::RequiresGovernance(name.to_string()));
+        }
+        let value = spec.check(raw)?;
+        self.entries[spec.id as usize] = ParamEntry { value, provenance: Provenance::Config { source: source.to_string() } };
+        Ok(())
+    }
+
+    /// The parameter `name` with `raw` parsed and bounds-checked, provided
+    /// `change_id` has not been applied before and, for a tighten-only
+    /// parameter, the new value does not loosen the current one.
+    pub fn check_change(&self, change_id: &str, name: &str, raw: &Value) -> Result<(ParamId, ParamValue), ParamError> {
+        if self.applied.contains(change_id) {
+            return Err(ParamError::Replayed(change_id.to_string()));
+        }
+        let spec = ParamId::from_name(name).ok_or_else(|| ParamError::Unknown(name.to_string()))?.spec();
+        let value = spec.check(raw)?;
+        let current = self.entry(spec.id).value;
+        if spec.loosens(&current, &value) {
+            return Err(ParamError::Loosening {
+                param: spec.name.to_string(),
+                current: current.magnitude(),
+                proposed: value.magnitude(),
+            });
+        }
+        Ok((spec.id, value))
+    }
+
+    /// Apply a governance change. Replay feeds the logged records back
+    /// through here in ledger order; bounds are checked again either way.
+    pub fn commit(&mut self, record: ParamChangeRecord) -> Result<(), ParamError> {
+        let (id, value) = self.check_change(&record.change_id, &record.param, &record.value)?;
+        let provenance = Provenance::Governance { change_id: record.change_id.clone(), event_id: record.event_id.clone() };
+        self.entries[id as usize] = ParamEntry { value, provenance };
+        self.applied.insert(record.change_id.clone());
+        self.history.push(record);
+        Ok(())
+    }
+
+    /// Applied governance changes, oldest first.
+    pub fn history(&self) -> &[ParamChangeRecord] {
+        &self.history
+    }
+
+    pub fn listing(&self) -> Vec<ParamListing> {
+        SPECS
+            .iter()
+            .zip(&self.entries)
+            .map(|(spec, entry)| ParamListing {
+                name: spec.name,
+                kind: spec.kind,
+                value: entry.value.to_json(),
+                default: spec.default.to_json(),
+                min: spec.min,
+                max: spec.max,
+                sensitivity: spec.sensitivity,
+                tighten: spec.tighten,
+                provenance: entry.provenance.clone(),
+                doc: spec.doc,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::registry::ParamError;
+
+include!(concat!(env!("OUT_DIR"), "/params.rs"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    F64,
+    U64,
+    /// Whole seconds on the wire.
+    Duration,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sensitivity {
+    /// May be overridden from the config file.
+    Informational,
+    /// Changes only through a multisig-approved `parameter_change` deed.
+    SafetyRelevant,
+}
+
+/// The direction a tighten-only parameter may move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tighten {
+    /// Only ever lowered: a ceiling.
+    Lower,
+    /// Only ever raised: a floor.
+    Higher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamValue {
+    F64(f64),
+    U64(u64),
+    Duration(Duration),
+    Bool(bool),
+}
+
+impl ParamValue {
+    pub fn kind(&self) -> ParamKind {
+        match self {
+            ParamValue::F64(_) => ParamKind::F64,
+            ParamValue::U64(_) => ParamKind::U64,
+            ParamValue::Duration(_) => ParamKind::Duration,
+            ParamValue::Bool(_) => ParamKind::Bool,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match *self {
+            ParamValue::F64(v) => Value::from(v),
+            ParamValue::U64(v) => Value::from(v),
+            ParamValue::Duration(d) => Value::from(d.as_secs()),
+            ParamValue::Bool(b) => Value::from(b),
+        }
+    }
+
+    /// `raw` read as a `kind` value, if it is one.
+    pub fn from_json(kind: ParamKind, raw: &Value) -> Option<Self> {
+        match kind {
+            ParamKind::F64 => raw.as_f64().filter(|v| v.is_finite()).map(ParamValue::F64),
+            ParamKind::U64 => raw.as_u64().map(ParamValue::U64),
+            ParamKind::Duration => raw.as_u64().map(|s| ParamValue::Duration(Duration::from_secs(s))),
+            ParamKind::Bool => raw.as_bool().map(ParamValue::Bool),
+        }
+    }
+
+    /// Position on the bounds axis: seconds for durations, 0/1 for bools.
+    pub(crate) fn magnitude(&self) -> f64 {
+        match *self {
+            ParamValue::F64(v) => v,
+            ParamValue::U64(v) => v as f64,
+            ParamValue::Duration(d) => d.as_secs_f64(),
+            ParamValue::Bool(b) => b as u8 as f64,
+        }
+    }
+}
+
+/// One declared parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamSpec {
+    pub id: ParamId,
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub default: ParamValue,
+    /// Inclusive bounds, in seconds for durations.
+    pub min: f64,
+    pub max: f64,
+    pub sensitivity: Sensitivity,
+    /// Set for tighten-only parameters; a change the other way is refused.
+    pub tighten: Option<Tighten>,
+    pub doc: &'static str,
+}
+
+impl ParamSpec {
+    /// Parse `raw` as this parameter's kind and check it against the bounds.
+    pub fn check(&self, raw: &Value) -> Result<ParamValue, ParamError> {
+        let value = ParamValue::from_json(self.kind, raw).ok_or_else(|| ParamError::WrongKind {
+            param: self.name.to_string(),
+            expected: self.kind,
+            got: raw.clone(),
+        })?;
+        let magnitude = value.magnitude();
+        if magnitude < self.min || magnitude > self.max {
+            return Err(ParamError::OutOfBounds { param: self.name.to_string(), value: magnitude, min: self.min, max: self.max });
+        }
+        Ok(value)
+    }
+
+    /// Whether moving from `current` to `proposed` loosens a tighten-only parameter.
+    pub fn loosens(&self, current: &ParamValue, proposed: &ParamValue) -> bool {
+        match self.tighten {
+            Some(Tighten::Lower) => proposed.magnitude() > current.magnitude(),
+            Some(Tighten::Higher) => proposed.magnitude() < current.magnitude(),
+            None => false,
+        }
+    }
+}
+
+impl ParamId {
+    pub fn spec(self) -> &'static ParamSpec {
+        &SPECS[self as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        SPECS.iter().find(|s| s.name == name).map(|s| s.id)
+    }
+}
+
+/// Compile-time handle for a parameter read as `T`.
+pub struct Key<T> {
+    id: ParamId,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    pub const fn new(id: ParamId) -> Self {
+        Self { id, _type: PhantomData }
+    }
+
+    pub fn id(&self) -> ParamId {
+        self.id
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+/// Rust types a parameter value can be read as.
+pub trait ParamType: Sized {
+    fn from_value(value: &ParamValue) -> Option<Self>;
+}
+
+impl ParamType for f64 {
+    fn from_value(value: &ParamValue) -> Option<Self> {
+        match *value {
+            ParamValue::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ParamType for u64 {
+    fn from_value(value: &ParamValue) -> Option<Self> {
+        match *value {
+            ParamValue::U64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ParamType for Duration {
+    fn from_value(value: &ParamValue) -> Option<Self> {
+        match *value {
+            ParamValue::Duration(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ParamType for bool {
+    fn from_value(value: &ParamValue) -> Option<Self> {
+        match *value {
+            ParamValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
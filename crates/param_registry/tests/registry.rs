@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use param_registry::{
+    ParamChangeRecord, ParamError, ParamId, ParamKey, ParamKind, ParamRegistry, ParamValue, Provenance, Sensitivity, Tighten, SPECS,
+};
+use serde_json::json;
+
+fn record(change_id: &str, param: &str, value: serde_json::Value) -> ParamChangeRecord {
+    ParamChangeRecord {
+        change_id: change_id.to_string(),
+        param: param.to_string(),
+        value,
+        previous: json!(null),
+        reason: "test".to_string(),
+        signers: vec![],
+        applied_at: 0,
+        event_id: Some(format!("ev-{}", change_id)),
+    }
+}
+
+#[test]
+fn defaults_match_the_declarations_and_the_old_literals() {
+    let params = ParamRegistry::default();
+    assert_eq!(params.get(ParamKey::ErrorityTriggerDelta), -0.3);
+    assert_eq!(params.get(ParamKey::EcoScoreMintFloor), 0.5);
+    assert_eq!(params.get(ParamKey::RepairEvidenceThreshold), 0.8);
+    assert_eq!(params.get(ParamKey::ObservationHorizon), Duration::from_secs(90 * 86_400));
+    assert_eq!(params.get(ParamKey::HeadroomMaxDays), 90);
+    for (i, spec) in SPECS.iter().enumerate() {
+        assert_eq!(spec.id as usize, i);
+        assert_eq!(ParamId::from_name(spec.name), Some(spec.id));
+        assert_eq!(spec.default.kind(), spec.kind);
+        assert_eq!(spec.check(&spec.default.to_json()).unwrap(), spec.default, "{}", spec.name);
+    }
+}
+
+#[test]
+fn bounds_and_kinds_are_enforced() {
+    let spec = ParamId::RepairEvidenceThreshold.spec();
+    assert!(matches!(spec.check(&json!(0.4)), Err(ParamError::OutOfBounds { min, .. }) if min == 0.5));
+    assert!(matches!(spec.check(&json!(1.01)), Err(ParamError::OutOfBounds { .. })));
+    assert!(matches!(spec.check(&json!("0.9")), Err(ParamError::WrongKind { expected: ParamKind::F64, .. })));
+    assert_eq!(spec.check(&json!(1.0)).unwrap(), ParamValue::F64(1.0));
+
+    let horizon = ParamId::ObservationHorizon.spec();
+    assert!(matches!(horizon.check(&json!(86_400)), Err(ParamError::OutOfBounds { .. })));
+    assert!(matches!(horizon.check(&json!(-5)), Err(ParamError::WrongKind { .. })));
+    assert_eq!(ParamValue::from_json(ParamKind::Bool, &json!(true)), Some(ParamValue::Bool(true)));
+    assert_eq!(ParamValue::from_json(ParamKind::Bool, &json!(1)), None);
+
+    let mut params = ParamRegistry::default();
+    assert!(matches!(params.commit(record("c-1", "repair_evidence_threshold", json!(2.0))), Err(ParamError::OutOfBounds { .. })));
+    assert!(matches!(params.commit(record("c-1", "no_such_param", json!(1))), Err(ParamError::Unknown(_))));
+    assert_eq!(params, ParamRegistry::default());
+}
+
+#[test]
+fn config_overrides_only_reach_informational_parameters() {
+    let mut params = ParamRegistry::default();
+    params.apply_config("headroom_max_days", &json!(30), "cof.toml").unwrap();
+    assert_eq!(params.get(ParamKey::HeadroomMaxDays), 30);
+    assert_eq!(params.entry(ParamId::HeadroomMaxDays).provenance, Provenance::Config { source: "cof.toml".into() });
+
+    assert!(matches!(
+        params.apply_config("eco_score_mint_floor", &json!(0.1), "cof.toml"),
+        Err(ParamError::RequiresGovernance(name)) if name == "eco_score_mint_floor"
+    ));
+    assert!(matches!(params.apply_config("headroom_max_days", &json!(0), "cof.toml"), Err(ParamError::OutOfBounds { .. })));
+    assert_eq!(params.get(ParamKey::EcoScoreMintFloor), 0.5);
+    assert_eq!(params.get(ParamKey::HeadroomMaxDays), 30);
+}
+
+#[test]
+fn committed_changes_build_history_and_provenance() {
+    let mut params = ParamRegistry::default();
+    params.commit(record("c-1", "repair_evidence_threshold", json!(0.9))).unwrap();
+    params.commit(record("c-2", "repair_evidence_threshold", json!(0.85))).unwrap();
+    assert!(matches!(params.commit(record("c-1", "repair_evidence_threshold", json!(0.7))), Err(ParamError::Replayed(_))));
+
+    assert_eq!(params.get(ParamKey::RepairEvidenceThreshold), 0.85);
+    assert_eq!(params.history().iter().map(|r| r.change_id.as_str()).collect::<Vec<_>>(), ["c-1", "c-2"]);
+    let listed = params.listing().into_iter().find(|l| l.name == "repair_evidence_threshold").unwrap();
+    assert_eq!(listed.value, json!(0.85));
+    assert_eq!(listed.default, json!(0.8));
+    assert_eq!(listed.sensitivity, Sensitivity::SafetyRelevant);
+    assert_eq!(listed.provenance, Provenance::Governance { change_id: "c-2".into(), event_id: Some("ev-c-2".into()) });
+    assert_eq!(
+        serde_json::to_value(&listed.provenance).unwrap(),
+        json!({ "kind": "governance", "change_id": "c-2", "event_id": "ev-c-2" })
+    );
+
+    let mut replayed = ParamRegistry::default();
+    for r in params.history() {
+        replayed.commit(r.clone()).unwrap();
+    }
+    assert_eq!(replayed, params);
+}
+
+#[test]
+fn tighten_only_parameters_never_loosen() {
+    let mut params = ParamRegistry::default();
+    assert_eq!(ParamId::EthicsPowerGiniMax.spec().tighten, Some(Tighten::Lower));
+    assert_eq!(ParamId::EthicsTrustFloor.spec().tighten, Some(Tighten::Higher));
+    assert_eq!(ParamId::RepairEvidenceThreshold.spec().tighten, None);
+
+    params.commit(record("g-1", "ethics_power_gini_max", json!(0.5))).unwrap();
+    assert!(matches!(
+        params.commit(record("g-2", "ethics_power_gini_max", json!(0.55))),
+        Err(ParamError::Loosening { current, proposed, .. }) if current == 0.5 && proposed == 0.55
+    ));
+    // Holding a value is not loosening it.
+    params.commit(record("g-3", "ethics_power_gini_max", json!(0.5))).unwrap();
+    params.commit(record("t-1", "ethics_trust_floor", json!(0.4))).unwrap();
+    assert!(matches!(params.commit(record("t-2", "ethics_trust_floor", json!(0.35))), Err(ParamError::Loosening { .. })));
+    assert_eq!((params.get(ParamKey::EthicsPowerGiniMax), params.get(ParamKey::EthicsTrustFloor)), (0.5, 0.4));
+
+    let listed = params.listing().into_iter().find(|l| l.name == "ethics_trust_floor").unwrap();
+    assert_eq!(serde_json::to_value(&listed).unwrap()["tighten"], "higher");
+    let untracked = params.listing().into_iter().find(|l| l.name == "headroom_max_days").unwrap();
+    assert!(serde_json::to_value(&untracked).unwrap().get("tighten").is_none());
+}
+
+#[cfg(feature = "governance")]
+mod governance {
+    use super::*;
+    use keyring::{Keyring, VerifyingBundle};
+    use param_registry::{ChangePolicy, ParamChange, SignedParamChange};
+
+    const T: i64 = 1_700_000_000;
+
+    fn signed(keyring: &Keyring, signers: &[String], value: serde_json::Value) -> SignedParamChange {
+        let change = ParamChange {
+            change_id: "raise-floor".to_string(),
+            param: "eco_score_mint_floor".to_string(),
+            value,
+            reason: "drift review".to_string(),
+            requested_at: T,
+        };
+        let approvals = signers.iter().map(|s| keyring.sign(s, &change.signing_bytes()).unwrap()).collect();
+        SignedParamChange { change, approvals }
+    }
+
+    #[test]
+    fn authorize_checks_quorum_purpose_freshness_and_bounds() {
+        let mut keyring = Keyring::new().with_clock(|| T as u64);
+        let names: Vec<String> = (0..2).map(|_| keyring.generate("param-authority").unwrap()).collect();
+        let operator = keyring.generate("operator").unwrap();
+        let bundle: VerifyingBundle = keyring.verifying_bundle();
+        let policy = ChangePolicy::default();
+        let params = ParamRegistry::default();
+
+        let one = signed(&keyring, &names[..1], json!(0.6));
+        assert!(matches!(params.authorize(&one, &bundle, &policy, T), Err(ParamError::NotEnoughApprovals { got: 1, need: 2 })));
+        let foreign = signed(&keyring, &[names[0].clone(), operator.clone()], json!(0.6));
+        assert!(matches!(params.authorize(&foreign, &bundle, &policy, T), Err(ParamError::NotAuthority(k)) if k == operator));
+        let wild = signed(&keyring, &names, json!(1.5));
+        assert!(matches!(params.authorize(&wild, &bundle, &policy, T), Err(ParamError::OutOfBounds { .. })));
+        let ok = signed(&keyring, &names, json!(0.6));
+        assert!(matches!(params.authorize(&ok, &bundle, &policy, T + 7_200), Err(ParamError::Stale { .. })));
+        let mut forged = ok.clone();
+        forged.change.value = json!(0.0);
+        assert!(matches!(params.authorize(&forged, &bundle, &policy, T), Err(ParamError::Signature(_))));
+
+        let record = params.authorize(&ok, &bundle, &policy, T).unwrap();
+        assert_eq!(record.previous, json!(0.5));
+        assert_eq!(record.signers.len(), 2);
+        // Authorizing applies nothing.
+        assert_eq!(params.get(ParamKey::EcoScoreMintFloor), 0.5);
+    }
+}
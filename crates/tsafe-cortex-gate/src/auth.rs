@@ -1,4 +1,4 @@
-use eco_fairness_guard::GuardError as EcoGuardError;
+use eco_types::GuardError as EcoGuardError;
 
 // inside TsafeCortexGate::authorizerequest
 
@@ -27,9 +27,9 @@ if let Err(e) = self.guardians.eco_guard.check(&req.action, &req.route) {
         req.route.as_str(),
         req.subjectid
     );
-    self.donutlogger.log_reject(&req, "ECO_FAIRNESS");
+    self.donutlogger.log_reject(&req, e.code());
     return AuthorizationResult::Rejected(RejectionReason {
-        code: "ECO_FAIRNESS".into(),
+        code: e.code().into(),
         message: e.to_string(),
     });
 }
@@ -1,4 +1,5 @@
-use eco_fairness_guard::{EcoFairnessGuard, GuardError as EcoGuardError, RohModel};
+use eco_fairness_guard::{EcoFairnessGuard, RohModel};
+use eco_types::GuardError as EcoGuardError;
 use vkernel::ViabilityKernel;
 
 pub struct GuardianSet {
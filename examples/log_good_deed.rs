@@ -1,14 +1,29 @@
-use church_ledger::append_deed_event;
-fn main() {
-    let _ = append_deed_event(
-        "data/church-ledger.jsonl",
-        "xboxtj-san-tan-valley".into(),
-        vec!["homeless-shelter-az".into()],
-        "homelessness_relief".into(),
-        vec!["civic-duty".into(), "tree-of-life".into()],
-        serde_json::json!({"hours": 8, "meals_served": 45, "location": "San Tan Valley"}),
-        vec![],
-        false,
-    );
+use church_of_fear::ledger::builders::HomelessnessReliefDeed;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+const LEDGER: &str = "data/church-ledger.jsonl";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Chain onto the last deed in the ledger, or start from genesis.
+    let prev_hash = fs::read_to_string(LEDGER)
+        .ok()
+        .and_then(|text| text.lines().last().map(str::to_string))
+        .and_then(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+        .and_then(|last| last["self_hash"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "0".repeat(64));
+
+    let deed = HomelessnessReliefDeed::builder()
+        .actor_id("xboxtj-san-tan-valley")
+        .location("San Tan Valley")
+        .hours(8.0)
+        .meals_served(45)
+        .target("homeless-shelter-az")
+        .build(prev_hash)?;
+
+    fs::create_dir_all("data")?;
+    let mut file = OpenOptions::new().create(true).append(true).open(LEDGER)?;
+    writeln!(file, "{}", serde_json::to_string(&deed)?)?;
     // This single deed mints ~28 CHURCH tokens + eco_grant recommendation for real NPO funding routing
+    Ok(())
 }
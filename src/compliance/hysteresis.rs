@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use super::regulator::EthicsDecision;
+
+/// Debounce applied to raw Regulator decisions. A change of severity only
+/// takes effect after the raw decision has held for the given number of
+/// consecutive ticks; `1` for both means no smoothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HysteresisParams {
+    /// Consecutive ticks above the current severity before escalating.
+    pub escalate_after: u32,
+    /// Consecutive ticks below the current severity before relaxing.
+    pub release_after: u32,
+}
+
+impl Default for HysteresisParams {
+    fn default() -> Self {
+        Self { escalate_after: 1, release_after: 3 }
+    }
+}
+
+/// Stateful filter turning raw per-tick decisions into effective ones.
+/// Deterministic: the same raw sequence always yields the same output,
+/// which is what lets `regulator_replay` reproduce a live run.
+#[derive(Debug, Clone)]
+pub struct DecisionFilter {
+    params: HysteresisParams,
+    current: EthicsDecision,
+    above: u32,
+    below: u32,
+}
+
+impl DecisionFilter {
+    pub fn new(params: HysteresisParams) -> Self {
+        Self { params, current: EthicsDecision::Allow, above: 0, below: 0 }
+    }
+
+    pub fn current(&self) -> &EthicsDecision {
+        &self.current
+    }
+
+    /// Feed one raw decision and return the effective decision for this tick.
+    pub fn apply(&mut self, raw: EthicsDecision) -> EthicsDecision {
+        let (now, held) = (raw.severity(), self.current.severity());
+        if now > held {
+            self.above += 1;
+            self.below = 0;
+            if self.above >= self.params.escalate_after.max(1) {
+                self.current = raw;
+                self.above = 0;
+            }
+        } else if now < held {
+            self.below += 1;
+            self.above = 0;
+            if self.below >= self.params.release_after.max(1) {
+                self.current = raw;
+                self.below = 0;
+            }
+        } else {
+            // Same band: take the fresh reason, reset both counters.
+            self.current = raw;
+            self.above = 0;
+            self.below = 0;
+        }
+        self.current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warn() -> EthicsDecision {
+        EthicsDecision::Warn { reason: "w".into() }
+    }
+
+    #[test]
+    fn escalation_waits_for_consecutive_ticks() {
+        let mut f = DecisionFilter::new(HysteresisParams { escalate_after: 2, release_after: 2 });
+        assert_eq!(f.apply(warn()), EthicsDecision::Allow);
+        assert_eq!(f.apply(EthicsDecision::Allow), EthicsDecision::Allow);
+        assert_eq!(f.apply(warn()), EthicsDecision::Allow);
+        assert_eq!(f.apply(warn()), warn());
+        assert_eq!(f.apply(EthicsDecision::Allow), warn());
+        assert_eq!(f.apply(EthicsDecision::Allow), EthicsDecision::Allow);
+    }
+}
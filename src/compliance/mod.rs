@@ -0,0 +1,7 @@
+mod hysteresis;
+mod record;
+mod regulator;
+
+pub use hysteresis::{DecisionFilter, HysteresisParams};
+pub use record::{DecisionRecorder, ETHICS_DECISION, REGULATOR_ACTOR};
+pub use regulator::{ComplianceConfig, EthicsDecision, EthicsSummary, Regulator};
@@ -0,0 +1,173 @@
+use serde_json::json;
+
+use super::regulator::EthicsDecision;
+use crate::ledger::{DeedEvent, Ledger, LedgerError, Metrics};
+
+/// Deed type for regulator interventions, and for the lifting of a freeze.
+pub const ETHICS_DECISION: &str = "ethics_decision";
+
+/// actor_id of every ETHICS_DECISION deed.
+pub const REGULATOR_ACTOR: &str = "regulator:ethics";
+
+/// Writes the Regulator's interventions into the hash chain, so there is an
+/// auditable record of every non-Allow decision and of each freeze that a
+/// HaltAndReview started and a later tick lifted.
+#[derive(Debug, Clone, Default)]
+pub struct DecisionRecorder {
+    frozen: bool,
+}
+
+impl DecisionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the last recorded decision froze high-impact deeds.
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Append the deeds `decision` calls for on `tick`: an "Unfreeze" deed
+    /// if it ends a freeze, then the decision itself unless it is Allow.
+    /// Returns whether the freeze was lifted. Call this under the same
+    /// write lock that applies the decision's effect, and apply nothing if
+    /// it fails; `frozen` always matches what reached the chain.
+    pub fn record(
+        &mut self,
+        ledger: &mut Ledger,
+        tick: u64,
+        decision: &EthicsDecision,
+        metrics: &Metrics,
+    ) -> Result<bool, LedgerError> {
+        let halting = matches!(decision, EthicsDecision::HaltAndReview { .. });
+        let lifted = self.frozen && !halting;
+        if lifted {
+            let reason = format!("freeze lifted: decision is now {}", variant(decision));
+            append(ledger, format!("{}-unfreeze-{}", ETHICS_DECISION, tick), "Unfreeze", &reason, tick, metrics)?;
+            self.frozen = false;
+        }
+        let reason = match decision {
+            EthicsDecision::Allow => None,
+            EthicsDecision::Warn { reason }
+            | EthicsDecision::ForceRepair { reason }
+            | EthicsDecision::HaltAndReview { reason } => Some(reason),
+        };
+        if let Some(reason) = reason {
+            append(ledger, format!("{}-{}", ETHICS_DECISION, tick), variant(decision), reason, tick, metrics)?;
+        }
+        self.frozen = halting;
+        Ok(lifted)
+    }
+}
+
+fn variant(decision: &EthicsDecision) -> &'static str {
+    match decision {
+        EthicsDecision::Allow => "Allow",
+        EthicsDecision::Warn { .. } => "Warn",
+        EthicsDecision::ForceRepair { .. } => "ForceRepair",
+        EthicsDecision::HaltAndReview { .. } => "HaltAndReview",
+    }
+}
+
+fn append(
+    ledger: &mut Ledger,
+    event_id: String,
+    decision: &str,
+    reason: &str,
+    tick: u64,
+    metrics: &Metrics,
+) -> Result<(), LedgerError> {
+    let mut deed = DeedEvent {
+        event_id,
+        timestamp: ledger.now_millis(),
+        prev_hash: ledger.last_hash().to_string(),
+        self_hash: String::new(),
+        actor_id: REGULATOR_ACTOR.to_string(),
+        target_ids: Vec::new(),
+        deed_type: ETHICS_DECISION.to_string(),
+        tags: Vec::new(),
+        context_json: json!({ "tick": tick, "decision": decision, "reason": reason, "metrics": metrics }),
+        ethics_flags: Vec::new(),
+        life_harm_flag: false,
+        ..Default::default()
+    };
+    deed.self_hash = deed.compute_self_hash();
+    ledger.append(deed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::{ComplianceConfig, DecisionFilter, EthicsSummary, HysteresisParams, Regulator};
+
+    fn metrics(bioload: f64) -> Metrics {
+        Metrics { total_bioload: bioload, bioload_variance: 0.0, mean_trust: 0.9, power_gini: 0.2 }
+    }
+
+    fn decisions(ledger: &Ledger) -> Vec<(u64, String)> {
+        ledger
+            .events_by_deed_type(ETHICS_DECISION)
+            .into_iter()
+            .map(|d| (d.context_json["tick"].as_u64().unwrap(), d.context_json["decision"].as_str().unwrap().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn interventions_are_chained_in_order() {
+        let regulator = Regulator::new(ComplianceConfig::default()).unwrap();
+        let mut filter = DecisionFilter::new(HysteresisParams { escalate_after: 1, release_after: 1 });
+        let mut recorder = DecisionRecorder::new();
+        let mut ledger = Ledger::new();
+        let mut lifted_at = Vec::new();
+
+        for (tick, bioload) in [0.2, 0.65, 0.85, 0.97, 0.98, 0.85, 0.3, 0.97, 0.2].into_iter().enumerate() {
+            let m = metrics(bioload);
+            let decision = filter.apply(regulator.evaluate(&EthicsSummary::from_metrics(&m)).unwrap());
+            if recorder.record(&mut ledger, tick as u64, &decision, &m).unwrap() {
+                lifted_at.push(tick);
+            }
+        }
+
+        let expected = [
+            (1, "Warn"),
+            (2, "ForceRepair"),
+            (3, "HaltAndReview"),
+            (4, "HaltAndReview"),
+            (5, "Unfreeze"),
+            (5, "ForceRepair"),
+            (7, "HaltAndReview"),
+            (8, "Unfreeze"),
+        ];
+        let expected: Vec<(u64, String)> = expected.iter().map(|&(t, d)| (t, d.to_string())).collect();
+        assert_eq!(decisions(&ledger), expected);
+        assert_eq!(lifted_at, [5, 8]);
+        assert!(!recorder.frozen());
+
+        // Every record is on the one chain, with the reason and the metrics that triggered it.
+        assert_eq!(ledger.len(), expected.len());
+        let halt = &ledger.events_page(2, 1)[0];
+        assert_eq!(halt.actor_id, REGULATOR_ACTOR);
+        assert!(halt.context_json["reason"].as_str().unwrap().contains("halt band"));
+        assert_eq!(halt.context_json["metrics"]["total_bioload"], 0.97);
+    }
+
+    #[test]
+    fn a_rejected_record_is_reported_and_not_repeated() {
+        let mut recorder = DecisionRecorder::new();
+        let mut ledger = Ledger::new();
+        let halt = EthicsDecision::HaltAndReview { reason: "h".into() };
+        recorder.record(&mut ledger, 0, &halt, &metrics(0.99)).unwrap();
+        assert!(recorder.frozen());
+
+        // A repeated tick: the unfreeze is recorded, the decision's event_id is taken.
+        let repair = EthicsDecision::ForceRepair { reason: "r".into() };
+        assert_eq!(
+            recorder.record(&mut ledger, 0, &repair, &metrics(0.85)),
+            Err(LedgerError::DuplicateEventId(format!("{}-0", ETHICS_DECISION)))
+        );
+        assert!(!recorder.frozen());
+        assert_eq!(recorder.record(&mut ledger, 1, &repair, &metrics(0.85)), Ok(false));
+        let recorded: Vec<String> = decisions(&ledger).into_iter().map(|(_, d)| d).collect();
+        assert_eq!(recorded, ["HaltAndReview", "Unfreeze", "ForceRepair"]);
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::Metrics;
+
+/// Thresholds for the ethical Regulator. Bioload bands are ordered
+/// warn < repair < halt; trust and power concentration are single floors/ceilings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceConfig {
+    /// k in POWER ≤ k·CHURCH.
+    pub neuromorph_power_multiplier: f64,
+    pub bioload_warn: f64,
+    pub bioload_repair: f64,
+    pub bioload_halt: f64,
+    pub trust_floor: f64,
+    pub power_gini_max: f64,
+    /// Source disagreement above this escalates the decision by one step;
+    /// high variance between bioload estimators is itself a warning sign.
+    pub bioload_variance_max: f64,
+}
+
+impl Default for ComplianceConfig {
+    fn default() -> Self {
+        Self {
+            neuromorph_power_multiplier: 1.0,
+            bioload_warn: 0.6,
+            bioload_repair: 0.8,
+            bioload_halt: 0.95,
+            trust_floor: 0.3,
+            power_gini_max: 0.6,
+            bioload_variance_max: 0.02,
+        }
+    }
+}
+
+/// Per-tick input to the Regulator, projected from Metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthicsSummary {
+    pub bioload: f64,
+    pub bioload_variance: f64,
+    pub mean_trust: f64,
+    pub power_gini: f64,
+}
+
+impl EthicsSummary {
+    pub fn from_metrics(metrics: &Metrics) -> Self {
+        Self {
+            bioload: metrics.total_bioload,
+            bioload_variance: metrics.bioload_variance,
+            mean_trust: metrics.mean_trust,
+            power_gini: metrics.power_gini,
+        }
+    }
+}
+
+/// Regulator outcome, ordered by severity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EthicsDecision {
+    Allow,
+    Warn { reason: String },
+    ForceRepair { reason: String },
+    HaltAndReview { reason: String },
+}
+
+impl EthicsDecision {
+    pub fn severity(&self) -> u8 {
+        match self {
+            EthicsDecision::Allow => 0,
+            EthicsDecision::Warn { .. } => 1,
+            EthicsDecision::ForceRepair { .. } => 2,
+            EthicsDecision::HaltAndReview { .. } => 3,
+        }
+    }
+}
+
+/// Enforces Neuromorph-GOD invariants over the per-tick EthicsSummary.
+#[derive(Debug, Clone)]
+pub struct Regulator {
+    cfg: ComplianceConfig,
+}
+
+impl Regulator {
+    pub fn new(cfg: ComplianceConfig) -> anyhow::Result<Self> {
+        if !(cfg.bioload_warn <= cfg.bioload_repair && cfg.bioload_repair <= cfg.bioload_halt) {
+            anyhow::bail!("bioload bands must satisfy warn <= repair <= halt");
+        }
+        if cfg.bioload_variance_max < 0.0 {
+            anyhow::bail!("bioload_variance_max must be non-negative");
+        }
+        Ok(Self { cfg })
+    }
+
+    pub fn config(&self) -> &ComplianceConfig {
+        &self.cfg
+    }
+
+    pub fn evaluate(&self, summary: &EthicsSummary) -> anyhow::Result<EthicsDecision> {
+        let cfg = &self.cfg;
+
+        let mut decision = if summary.bioload >= cfg.bioload_halt {
+            EthicsDecision::HaltAndReview {
+                reason: format!("bioload {:.3} at or above halt band {:.3}", summary.bioload, cfg.bioload_halt),
+            }
+        } else if summary.bioload >= cfg.bioload_repair {
+            EthicsDecision::ForceRepair {
+                reason: format!("bioload {:.3} at or above repair band {:.3}", summary.bioload, cfg.bioload_repair),
+            }
+        } else if summary.power_gini > cfg.power_gini_max {
+            EthicsDecision::ForceRepair {
+                reason: format!("power_gini {:.3} above {:.3}", summary.power_gini, cfg.power_gini_max),
+            }
+        } else if summary.bioload >= cfg.bioload_warn {
+            EthicsDecision::Warn {
+                reason: format!("bioload {:.3} at or above warn band {:.3}", summary.bioload, cfg.bioload_warn),
+            }
+        } else if summary.mean_trust < cfg.trust_floor {
+            EthicsDecision::Warn {
+                reason: format!("mean_trust {:.3} below floor {:.3}", summary.mean_trust, cfg.trust_floor),
+            }
+        } else {
+            EthicsDecision::Allow
+        };
+
+        // Disagreement between bioload sources escalates by one step, but never
+        // on its own reaches HaltAndReview.
+        if summary.bioload_variance > cfg.bioload_variance_max && decision.severity() < 2 {
+            let reason = format!(
+                "bioload sources disagree (variance {:.4} > {:.4})",
+                summary.bioload_variance, cfg.bioload_variance_max
+            );
+            decision = match decision {
+                EthicsDecision::Allow => EthicsDecision::Warn { reason },
+                EthicsDecision::Warn { reason: prior } => EthicsDecision::ForceRepair {
+                    reason: format!("{}; {}", prior, reason),
+                },
+                other => other,
+            };
+        }
+
+        Ok(decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(bioload: f64, variance: f64) -> EthicsSummary {
+        EthicsSummary { bioload, bioload_variance: variance, mean_trust: 0.9, power_gini: 0.2 }
+    }
+
+    #[test]
+    fn bands_map_to_decisions() {
+        let reg = Regulator::new(ComplianceConfig::default()).unwrap();
+        assert_eq!(reg.evaluate(&summary(0.1, 0.0)).unwrap(), EthicsDecision::Allow);
+        assert!(matches!(reg.evaluate(&summary(0.7, 0.0)).unwrap(), EthicsDecision::Warn { .. }));
+        assert!(matches!(reg.evaluate(&summary(0.85, 0.0)).unwrap(), EthicsDecision::ForceRepair { .. }));
+        assert!(matches!(reg.evaluate(&summary(0.99, 0.0)).unwrap(), EthicsDecision::HaltAndReview { .. }));
+    }
+
+    #[test]
+    fn variance_escalates_one_step() {
+        let reg = Regulator::new(ComplianceConfig::default()).unwrap();
+        assert!(matches!(reg.evaluate(&summary(0.1, 0.5)).unwrap(), EthicsDecision::Warn { .. }));
+        assert!(matches!(reg.evaluate(&summary(0.7, 0.5)).unwrap(), EthicsDecision::ForceRepair { .. }));
+        assert!(matches!(reg.evaluate(&summary(0.85, 0.5)).unwrap(), EthicsDecision::ForceRepair { .. }));
+    }
+}
@@ -0,0 +1,307 @@
+// Bioload observation fusion:
+// - Several independent estimators (HRV rail, environmental sensors,
+//   self-report) report the same territorial bioload and routinely disagree.
+// - Readings are combined with confidence weights, stale readings are
+//   down-weighted, and sources deviating beyond k·MAD from the median are
+//   excluded and flagged.
+// - Every inclusion/exclusion decision is logged so the fused figure can be
+//   audited after the fact.
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// One observation from a bioload source.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BioloadReading {
+    pub value: f64,
+    /// Self-reported confidence in [0, 1].
+    pub confidence: f64,
+    /// Unix seconds at which the reading was taken.
+    pub timestamp: u64,
+}
+
+/// A named provider of bioload readings.
+pub trait BioloadSource: Send + Sync {
+    fn name(&self) -> &str;
+    /// Latest reading, or None if the source has nothing to report.
+    fn latest(&self) -> Option<BioloadReading>;
+}
+
+/// Fixed-value source, useful for self-report forms and tests.
+#[derive(Debug, Clone)]
+pub struct StaticSource {
+    pub name: String,
+    pub reading: Option<BioloadReading>,
+}
+
+impl BioloadSource for StaticSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn latest(&self) -> Option<BioloadReading> {
+        self.reading
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusionConfig {
+    /// Sources deviating from the median by more than k·MAD are excluded.
+    pub mad_k: f64,
+    /// Lower bound on MAD so identical readings don't exclude tiny deviations.
+    pub mad_floor: f64,
+    /// Readings older than this lose half their weight per half-life.
+    pub staleness_half_life_secs: u64,
+    /// Readings older than this are excluded outright.
+    pub max_age_secs: u64,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            mad_k: 3.0,
+            mad_floor: 0.01,
+            staleness_half_life_secs: 300,
+            max_age_secs: 3600,
+        }
+    }
+}
+
+/// Point in time the fusion is evaluated at.
+#[derive(Debug, Clone, Copy)]
+pub struct FusionWindow {
+    pub now: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceStatus {
+    Included,
+    Outlier,
+    Stale,
+    NoReading,
+}
+
+/// Audit record for one source in one fusion pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceDecision {
+    pub source: String,
+    pub value: Option<f64>,
+    pub weight: f64,
+    pub status: SourceStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusedBioload {
+    pub value: f64,
+    /// Weighted variance of the included readings around `value`.
+    pub variance: f64,
+    pub decisions: Vec<SourceDecision>,
+}
+
+impl FusedBioload {
+    pub fn included_count(&self) -> usize {
+        self.decisions.iter().filter(|d| d.status == SourceStatus::Included).count()
+    }
+}
+
+/// Registry of bioload sources plus the fusion rule.
+pub struct BioloadFusion {
+    cfg: FusionConfig,
+    sources: Vec<Box<dyn BioloadSource>>,
+}
+
+impl BioloadFusion {
+    pub fn new(cfg: FusionConfig) -> Self {
+        Self { cfg, sources: Vec::new() }
+    }
+
+    pub fn register(&mut self, source: Box<dyn BioloadSource>) {
+        self.sources.push(source);
+    }
+
+    /// Confidence-weighted estimate with MAD outlier rejection and staleness
+    /// down-weighting. With no usable readings the fused value is 0.0 with
+    /// zero variance.
+    pub fn fuse(&self, window: FusionWindow) -> FusedBioload {
+        let mut decisions = Vec::with_capacity(self.sources.len());
+        let mut candidates: Vec<(usize, f64, f64)> = Vec::new(); // (decision idx, value, weight)
+
+        for source in &self.sources {
+            let name = source.name().to_string();
+            let Some(reading) = source.latest() else {
+                decisions.push(SourceDecision { source: name, value: None, weight: 0.0, status: SourceStatus::NoReading });
+                continue;
+            };
+
+            let age = window.now.saturating_sub(reading.timestamp);
+            if age > self.cfg.max_age_secs || !reading.value.is_finite() {
+                decisions.push(SourceDecision {
+                    source: name,
+                    value: Some(reading.value),
+                    weight: 0.0,
+                    status: SourceStatus::Stale,
+                });
+                continue;
+            }
+
+            let half_life = self.cfg.staleness_half_life_secs.max(1) as f64;
+            let freshness = 0.5_f64.powf(age as f64 / half_life);
+            let weight = reading.confidence.clamp(0.0, 1.0) * freshness;
+
+            candidates.push((decisions.len(), reading.value, weight));
+            decisions.push(SourceDecision {
+                source: name,
+                value: Some(reading.value),
+                weight,
+                status: SourceStatus::Included,
+            });
+        }
+
+        // Outlier rejection needs at least three readings to have a meaningful median.
+        if candidates.len() >= 3 {
+            let values: Vec<f64> = candidates.iter().map(|c| c.1).collect();
+            let med = median(&values);
+            let deviations: Vec<f64> = values.iter().map(|v| (v - med).abs()).collect();
+            let mad = median(&deviations).max(self.cfg.mad_floor);
+            for (idx, value, _) in &candidates {
+                if (value - med).abs() > self.cfg.mad_k * mad {
+                    decisions[*idx].status = SourceStatus::Outlier;
+                    decisions[*idx].weight = 0.0;
+                }
+            }
+        }
+
+        let included: Vec<(f64, f64)> = candidates
+            .iter()
+            .filter(|(idx, _, _)| decisions[*idx].status == SourceStatus::Included)
+            .map(|(_, v, w)| (*v, *w))
+            .collect();
+
+        let total_weight: f64 = included.iter().map(|(_, w)| w).sum();
+        let (value, variance) = if included.is_empty() {
+            (0.0, 0.0)
+        } else if total_weight <= f64::EPSILON {
+            // All included readings carry zero weight: fall back to a plain mean.
+            let mean = included.iter().map(|(v, _)| v).sum::<f64>() / included.len() as f64;
+            let var = included.iter().map(|(v, _)| (v - mean).powi(2)).sum::<f64>() / included.len() as f64;
+            (mean, var)
+        } else {
+            let mean = included.iter().map(|(v, w)| v * w).sum::<f64>() / total_weight;
+            let var = included.iter().map(|(v, w)| w * (v - mean).powi(2)).sum::<f64>() / total_weight;
+            (mean, var)
+        };
+
+        for d in &decisions {
+            match d.status {
+                SourceStatus::Included => info!(
+                    "Bioload fusion: included {} (value={:?}, weight={:.3})",
+                    d.source, d.value, d.weight
+                ),
+                _ => warn!("Bioload fusion: {:?} source {} (value={:?})", d.status, d.source, d.value),
+            }
+        }
+
+        FusedBioload { value, variance, decisions }
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = sorted.len();
+    if n == 0 {
+        0.0
+    } else if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compliance::{ComplianceConfig, EthicsDecision, EthicsSummary, Regulator};
+    use crate::ledger::Metrics;
+
+    fn source(name: &str, value: f64, confidence: f64, timestamp: u64) -> Box<dyn BioloadSource> {
+        Box::new(StaticSource {
+            name: name.to_string(),
+            reading: Some(BioloadReading { value, confidence, timestamp }),
+        })
+    }
+
+    #[test]
+    fn single_outlier_is_excluded_without_shifting_estimate() {
+        let mut fusion = BioloadFusion::new(FusionConfig::default());
+        fusion.register(source("hrv", 0.40, 1.0, 1000));
+        fusion.register(source("env", 0.42, 1.0, 1000));
+        fusion.register(source("self_report", 0.41, 1.0, 1000));
+        let baseline = fusion.fuse(FusionWindow { now: 1000 });
+
+        fusion.register(source("broken_sensor", 5.0, 1.0, 1000));
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+
+        assert!((fused.value - baseline.value).abs() < 1e-9);
+        let broken = fused.decisions.iter().find(|d| d.source == "broken_sensor").unwrap();
+        assert_eq!(broken.status, SourceStatus::Outlier);
+        assert_eq!(fused.included_count(), 3);
+    }
+
+    #[test]
+    fn stale_readings_are_down_weighted() {
+        let cfg = FusionConfig { staleness_half_life_secs: 100, ..FusionConfig::default() };
+        let mut fusion = BioloadFusion::new(cfg);
+        fusion.register(source("fresh", 0.2, 1.0, 1000));
+        fusion.register(source("old", 0.6, 1.0, 800));
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+
+        let old = fused.decisions.iter().find(|d| d.source == "old").unwrap();
+        assert!((old.weight - 0.25).abs() < 1e-9);
+        // Weighted toward the fresh reading: (0.2*1 + 0.6*0.25) / 1.25 = 0.28
+        assert!((fused.value - 0.28).abs() < 1e-9);
+    }
+
+    #[test]
+    fn readings_past_max_age_are_excluded() {
+        let cfg = FusionConfig { max_age_secs: 60, ..FusionConfig::default() };
+        let mut fusion = BioloadFusion::new(cfg);
+        fusion.register(source("fresh", 0.3, 1.0, 1000));
+        fusion.register(source("ancient", 0.9, 1.0, 10));
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+        assert_eq!(fused.value, 0.3);
+        assert_eq!(fused.decisions[1].status, SourceStatus::Stale);
+    }
+
+    #[test]
+    fn high_variance_escalates_regulator() {
+        let mut fusion = BioloadFusion::new(FusionConfig::default());
+        fusion.register(source("a", 0.1, 1.0, 1000));
+        fusion.register(source("b", 0.5, 1.0, 1000));
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+        assert!(fused.variance > 0.02);
+
+        let metrics = Metrics { mean_trust: 0.9, ..Metrics::default() }.with_fused_bioload(&fused);
+        let reg = Regulator::new(ComplianceConfig::default()).unwrap();
+        let decision = reg.evaluate(&EthicsSummary::from_metrics(&metrics)).unwrap();
+        assert!(matches!(decision, EthicsDecision::Warn { ref reason } if reason.contains("disagree")));
+    }
+
+    #[test]
+    fn single_source_passes_through() {
+        let mut fusion = BioloadFusion::new(FusionConfig::default());
+        fusion.register(source("only", 0.37, 0.5, 1000));
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+        assert_eq!(fused.value, 0.37);
+        assert_eq!(fused.variance, 0.0);
+        assert_eq!(fused.included_count(), 1);
+    }
+
+    #[test]
+    fn no_sources_yields_zero() {
+        let fusion = BioloadFusion::new(FusionConfig::default());
+        let fused = fusion.fuse(FusionWindow { now: 1000 });
+        assert_eq!(fused.value, 0.0);
+        assert!(fused.decisions.is_empty());
+    }
+}
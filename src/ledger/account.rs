@@ -1,32 +1,85 @@
-use crate::ledger::Ledger;
-use crate::utils::time::time_discount_factor;
-use chrono::Utc;
+use std::collections::HashMap;
+
+use crate::ledger::{DeedEvent, Ledger};
+use crate::utils::time::{time_discount_factor, MILLIS_PER_DAY};
+use param_registry::{ParamKey, ParamRegistry};
 
 #[derive(Debug)]
 pub struct ChurchAccountState {
-    pub cumulative_good_deeds: f64, // Time-discounted sum
+    pub cumulative_good_deeds: f64, // Time-discounted sum of impact weights
     pub cumulative_harm_flags: u32,
     pub eco_score: f64, // Convex combo: 0.7 * good_deeds_norm + 0.3 * (1 - harm_norm)
     pub debt_ceiling: f64, // Reduced by harm
     pub church_balance: f64, // Minted tokens
 }
 
+/// Upper bound on a good deed's impact weight.
+pub const MAX_IMPACT: f64 = 10.0;
+
+/// CHURCH minted per unit of weighted good deeds at an eco score of 1.0.
+pub const CHURCH_PER_WEIGHTED_DEED: f64 = 10.0;
+
+/// How `compute_from_ledger_with` weighs and ages deeds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecomputeOptions {
+    /// Age deeds against this instant (Unix ms) instead of the ledger's clock.
+    pub now_override: Option<i64>,
+    pub half_life_days: f64,
+    /// Impact of a good deed of this deed_type whose context carries none.
+    /// Types not listed weigh 1.0.
+    pub impact_defaults: HashMap<String, f64>,
+}
+
+impl RecomputeOptions {
+    pub fn from_params(params: &ParamRegistry) -> Self {
+        Self {
+            now_override: None,
+            half_life_days: params.get(ParamKey::GoodDeedHalfLifeDays),
+            impact_defaults: HashMap::new(),
+        }
+    }
+
+    /// The deed's `impact` from its context, else its type's default, else
+    /// 1.0; clamped to [0, MAX_IMPACT].
+    pub fn impact_of(&self, event: &DeedEvent) -> f64 {
+        let impact = event
+            .context_json
+            .get("impact")
+            .and_then(|v| v.as_f64())
+            .or_else(|| self.impact_defaults.get(&event.deed_type).copied())
+            .unwrap_or(1.0);
+        if impact.is_nan() { 0.0 } else { impact.clamp(0.0, MAX_IMPACT) }
+    }
+}
+
+impl Default for RecomputeOptions {
+    fn default() -> Self {
+        Self::from_params(&ParamRegistry::default())
+    }
+}
+
 impl ChurchAccountState {
     pub fn compute_from_ledger(ledger: &Ledger, actor_id: &str) -> Option<Self> {
+        Self::compute_from_ledger_with(ledger, actor_id, &RecomputeOptions::default())
+    }
+
+    pub fn compute_from_ledger_with(ledger: &Ledger, actor_id: &str, options: &RecomputeOptions) -> Option<Self> {
         let events = ledger.events_for_actor(actor_id);
         if events.is_empty() {
             return None;
         }
 
-        let now = Utc::now().timestamp() as u64;
+        let now = options.now_override.unwrap_or_else(|| ledger.now_millis());
+        let half_life = options.half_life_days * MILLIS_PER_DAY;
         let mut good_deeds = 0.0;
         let mut harm_flags = 0;
 
         for event in events {
-            let age = now - event.timestamp;
-            let discount = time_discount_factor(age);
+            // Deeds stamped after `now` (clock skew, replays) are age zero.
+            let age = now.saturating_sub(event.timestamp).max(0) as u64;
+            let discount = time_discount_factor(age, half_life);
             if event.is_good_deed() {
-                good_deeds += 1.0 * discount;
+                good_deeds += options.impact_of(event) * discount;
             }
             if event.life_harm_flag {
                 harm_flags += 1;
@@ -49,16 +102,22 @@ impl ChurchAccountState {
     }
 
     pub fn can_mint_church(&self) -> bool {
-        self.cumulative_harm_flags == 0 && self.eco_score > 0.5
+        self.can_mint_church_with(&ParamRegistry::default())
+    }
+
+    /// `can_mint_church` with the eco-score floor read from `params`.
+    pub fn can_mint_church_with(&self, params: &ParamRegistry) -> bool {
+        self.cumulative_harm_flags == 0 && self.eco_score > params.get(ParamKey::EcoScoreMintFloor)
     }
 
+    /// Symbolic CHURCH for the weighted good deeds, scaled by eco score.
     pub fn compute_mint_amount(&self) -> f64 {
-        self.eco_score * 10.0 // Symbolic CHURCH tokens
+        self.cumulative_good_deeds * self.eco_score * CHURCH_PER_WEIGHTED_DEED
     }
 
     // Rare-item: Simulates NEUROMORPH-GOD quorum for forgiveness
     pub fn forgiveness_quorum(roles: &[String], required_quorum: usize) -> bool {
-        let required = vec!["Host", "OrganicCPUOwner", "Regulator", "SovereignKernel"];
+        let required = ["Host", "OrganicCPUOwner", "Regulator", "SovereignKernel"];
         roles.iter().filter(|r| required.contains(&r.as_str())).count() >= required_quorum
     }
 }
@@ -0,0 +1,47 @@
+use deed_core::{levels_root, merkle_levels, merkle_proof};
+use serde::{Deserialize, Serialize};
+
+pub use deed_core::{verify_merkle_proof, MerkleStep, EMPTY_MERKLE_ROOT};
+
+use super::Ledger;
+
+/// A Merkle root ready to post to an anchor (Bostrom, Googolswarm, ...).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AnchorManifest {
+    pub root: String,
+    /// Levels above the leaves; 0 for a ledger of one event.
+    pub height: usize,
+    pub event_count: usize,
+    /// Unix milliseconds.
+    pub created_at: i64,
+    pub anchor_target: String,
+}
+
+impl Ledger {
+    fn merkle_levels(&self) -> Vec<Vec<String>> {
+        merkle_levels(self.events.iter().map(|e| e.self_hash.clone()).collect())
+    }
+
+    /// SHA-256 Merkle root over the events' self_hashes in append order;
+    /// `EMPTY_MERKLE_ROOT` while the ledger is empty.
+    pub fn merkle_root(&self) -> String {
+        levels_root(&self.merkle_levels())
+    }
+
+    /// The sibling path from `event_id` up to `merkle_root`.
+    pub fn merkle_proof(&self, event_id: &str) -> Option<Vec<MerkleStep>> {
+        let index = self.events.iter().position(|e| e.event_id == event_id)?;
+        Some(merkle_proof(&self.merkle_levels(), index))
+    }
+
+    pub fn anchor_manifest(&self, anchor_target: &str) -> AnchorManifest {
+        let levels = self.merkle_levels();
+        AnchorManifest {
+            root: levels_root(&levels),
+            height: levels.len().saturating_sub(1),
+            event_count: self.events.len(),
+            created_at: self.now_millis(),
+            anchor_target: anchor_target.to_string(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fusion::FusedBioload;
+use crate::ledger::DeedEvent;
+
+/// Jetson-style summary of the node's current state, computed once per tick
+/// and handed to the ethics Regulator.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Metrics {
+    /// Fused territorial bioload estimate (0.0 = no load).
+    pub total_bioload: f64,
+    /// Disagreement between bioload sources; 0.0 when only one source reports.
+    pub bioload_variance: f64,
+    /// Mean trust across participating accounts, in [0, 1].
+    pub mean_trust: f64,
+    /// Gini coefficient of POWER balances, in [0, 1].
+    pub power_gini: f64,
+}
+
+impl Metrics {
+    /// Replace the bioload figures with a fused multi-source estimate.
+    pub fn with_fused_bioload(mut self, fused: &FusedBioload) -> Self {
+        self.total_bioload = fused.value;
+        self.bioload_variance = fused.variance;
+        self
+    }
+}
+
+/// Deed type for persisted per-tick metrics.
+pub const METRICS_SNAPSHOT: &str = "metrics_snapshot";
+
+/// Serialized size ceiling for a snapshot's context; the schema is fixed
+/// numeric fields, so this only guards against accidental growth.
+pub const MAX_SNAPSHOT_BYTES: usize = 256;
+
+/// The exact figures the Regulator saw on one tick, compact enough to
+/// persist periodically and replay later (see `regulator_replay`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub tick: u64,
+    /// Unix seconds.
+    pub timestamp: u64,
+    pub metrics: Metrics,
+}
+
+impl MetricsSnapshot {
+    pub fn to_deed(&self, prev_hash: &str) -> DeedEvent {
+        let context_json = serde_json::to_value(self).expect("snapshot serializes");
+        debug_assert!(context_json.to_string().len() <= MAX_SNAPSHOT_BYTES);
+        let mut deed = DeedEvent {
+            event_id: format!("{}-{}", METRICS_SNAPSHOT, self.tick),
+            timestamp: (self.timestamp as i64).saturating_mul(1_000),
+            prev_hash: prev_hash.to_string(),
+            self_hash: String::new(),
+            actor_id: "regulator".to_string(),
+            target_ids: Vec::new(),
+            deed_type: METRICS_SNAPSHOT.to_string(),
+            tags: Vec::new(),
+            context_json,
+            ethics_flags: Vec::new(),
+            life_harm_flag: false,
+            ..Default::default()
+        };
+        deed.self_hash = deed.compute_self_hash();
+        deed
+    }
+
+    pub fn from_deed(deed: &DeedEvent) -> Option<Self> {
+        if deed.deed_type != METRICS_SNAPSHOT {
+            return None;
+        }
+        serde_json::from_value(deed.context_json.clone()).ok()
+    }
+}
+
+/// How often the main loop persists a MetricsSnapshot deed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotPolicy {
+    /// Persist every Nth tick; 0 disables snapshots.
+    pub every_n_ticks: u64,
+    /// Never persist two snapshots closer together than this, whatever the
+    /// tick rate, so snapshots cannot dominate the ledger.
+    pub min_interval_secs: u64,
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        // 500 ms ticks: one snapshot a minute.
+        Self { every_n_ticks: 120, min_interval_secs: 60 }
+    }
+}
+
+/// Applies a SnapshotPolicy to the tick stream.
+#[derive(Debug, Clone)]
+pub struct SnapshotRecorder {
+    policy: SnapshotPolicy,
+    last_at: Option<u64>,
+}
+
+impl SnapshotRecorder {
+    pub fn new(policy: SnapshotPolicy) -> Self {
+        Self { policy, last_at: None }
+    }
+
+    /// The snapshot to persist for this tick, if the policy allows one.
+    pub fn observe(&mut self, tick: u64, timestamp: u64, metrics: &Metrics) -> Option<MetricsSnapshot> {
+        let every = self.policy.every_n_ticks;
+        if every == 0 || !tick.is_multiple_of(every) {
+            return None;
+        }
+        if let Some(last) = self.last_at {
+            if timestamp.saturating_sub(last) < self.policy.min_interval_secs {
+                return None;
+            }
+        }
+        self.last_at = Some(timestamp);
+        Some(MetricsSnapshot { tick, timestamp, metrics: metrics.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> Metrics {
+        Metrics { total_bioload: 0.123456789, bioload_variance: 0.0012345, mean_trust: 0.87654321, power_gini: 0.4321 }
+    }
+
+    #[test]
+    fn recorder_honors_tick_stride_and_min_interval() {
+        let mut rec = SnapshotRecorder::new(SnapshotPolicy { every_n_ticks: 2, min_interval_secs: 10 });
+        // One tick per second for 30 seconds.
+        let ticks: Vec<u64> = (0..30).filter_map(|t| rec.observe(t, 1_000 + t, &metrics()).map(|s| s.tick)).collect();
+        assert_eq!(ticks, [0, 10, 20]);
+
+        let mut off = SnapshotRecorder::new(SnapshotPolicy { every_n_ticks: 0, min_interval_secs: 0 });
+        assert!((0..10).all(|t| off.observe(t, t, &metrics()).is_none()));
+    }
+
+    #[test]
+    fn snapshot_deed_is_compact_and_round_trips() {
+        let snap = MetricsSnapshot { tick: u64::MAX, timestamp: u64::MAX, metrics: metrics() };
+        let deed = snap.to_deed("0");
+        assert!(serde_json::to_string(&deed.context_json).unwrap().len() <= MAX_SNAPSHOT_BYTES);
+        let back = MetricsSnapshot::from_deed(&deed).unwrap();
+        assert_eq!(back.metrics.total_bioload, snap.metrics.total_bioload);
+        assert_eq!(back.tick, snap.tick);
+    }
+}
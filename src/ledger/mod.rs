@@ -1,37 +1,169 @@
 mod deed_event;
 mod account;
+mod merkle;
+mod metrics;
+mod store;
 
 pub use deed_event::DeedEvent;
-pub use account::ChurchAccountState;
+pub use account::{ChurchAccountState, RecomputeOptions, CHURCH_PER_WEIGHTED_DEED, MAX_IMPACT};
+pub use merkle::{verify_merkle_proof, AnchorManifest, MerkleStep, EMPTY_MERKLE_ROOT};
+pub use metrics::{Metrics, MetricsSnapshot, SnapshotPolicy, SnapshotRecorder, METRICS_SNAPSHOT};
+pub use store::StoreError;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::utils::time::{Clock, SystemClock};
+
+use store::LedgerStore;
+
+/// Why `Ledger::append` turned an event away. The ledger is unchanged.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    #[error("prev_hash mismatch: expected {expected:?}, got {got:?}")]
+    PrevHashMismatch { expected: String, got: String },
+    #[error("self_hash mismatch for event {event_id}: recomputed {expected}, got {got}")]
+    SelfHashMismatch { event_id: String, expected: String, got: String },
+    #[error("duplicate event_id {0}")]
+    DuplicateEventId(String),
+    #[error("event {event_id} not persisted: {reason}")]
+    Persist { event_id: String, reason: String },
+}
 
 pub struct Ledger {
     events: Vec<DeedEvent>,
+    event_ids: HashSet<String>,
+    /// Positions in `events` per actor_id, in append order.
+    by_actor: HashMap<String, Vec<usize>>,
+    /// Positions in `events` per deed_type, in append order.
+    by_type: HashMap<String, Vec<usize>>,
     last_hash: String,
+    clock: Box<dyn Clock>,
+    /// Where appended events are written first, for a ledger from `open`.
+    store: Option<LedgerStore>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Ledger {
     pub fn new() -> Self {
+        Self::with_clock(Box::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: Box<dyn Clock>) -> Self {
         Ledger {
             events: Vec::new(),
+            event_ids: HashSet::new(),
+            by_actor: HashMap::new(),
+            by_type: HashMap::new(),
             last_hash: String::new(),
+            clock,
+            store: None,
+        }
+    }
+
+    /// A ledger persisted at `path`: the events already there are replayed
+    /// through `append`, and every later append is on disk before it
+    /// returns. See `store` for how a torn final write is recovered.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let (store, events) = LedgerStore::open(path.as_ref())?;
+        let mut ledger = Self::new();
+        for (i, event) in events.into_iter().enumerate() {
+            ledger.append(event).map_err(|source| StoreError::Chain { line: i + 1, source })?;
         }
+        ledger.store = Some(store);
+        Ok(ledger)
     }
 
-    pub fn append(&mut self, event: DeedEvent) {
+    /// The ledger's clock, in Unix milliseconds; what deeds and account
+    /// ages are measured against.
+    pub fn now_millis(&self) -> i64 {
+        self.clock.now_millis()
+    }
+
+    /// Append `event` if it links to the current head, its `self_hash`
+    /// matches its contents and its `event_id` is new.
+    pub fn append(&mut self, event: DeedEvent) -> Result<(), LedgerError> {
         if event.prev_hash != self.last_hash {
-            panic!("Invalid prev_hash");
+            return Err(LedgerError::PrevHashMismatch { expected: self.last_hash.clone(), got: event.prev_hash });
+        }
+        let recomputed = event.compute_self_hash();
+        if recomputed != event.self_hash {
+            return Err(LedgerError::SelfHashMismatch { event_id: event.event_id, expected: recomputed, got: event.self_hash });
+        }
+        if self.event_ids.contains(&event.event_id) {
+            return Err(LedgerError::DuplicateEventId(event.event_id));
         }
-        self.events.push(event.clone());
-        self.last_hash = event.self_hash;
+        if let Some(store) = &mut self.store {
+            if let Err(e) = store.append(&event) {
+                return Err(LedgerError::Persist { event_id: event.event_id, reason: e.to_string() });
+            }
+        }
+        let position = self.events.len();
+        self.event_ids.insert(event.event_id.clone());
+        self.by_actor.entry(event.actor_id.clone()).or_default().push(position);
+        self.by_type.entry(event.deed_type.clone()).or_default().push(position);
+        self.last_hash = event.self_hash.clone();
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// A ledger holding `events` exported from another ledger, every link
+    /// checked as `append` checks it. The chain may start from any prev_hash.
+    pub fn from_chain(events: Vec<DeedEvent>) -> Result<Self, LedgerError> {
+        let mut ledger = Self::new();
+        if let Some(first) = events.first() {
+            ledger.last_hash = first.prev_hash.clone();
+        }
+        for event in events {
+            ledger.append(event)?;
+        }
+        Ok(ledger)
     }
 
     pub fn last_hash(&self) -> &str {
         &self.last_hash
     }
 
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
     pub fn events_for_actor(&self, actor_id: &str) -> Vec<&DeedEvent> {
-        self.events.iter().filter(|e| e.actor_id == actor_id).collect()
+        self.indexed(&self.by_actor, actor_id).collect()
+    }
+
+    /// `actor_id`'s events with `from_ts <= timestamp < to_ts`, in append order.
+    pub fn events_for_actor_in_range(&self, actor_id: &str, from_ts: i64, to_ts: i64) -> Vec<&DeedEvent> {
+        self.indexed(&self.by_actor, actor_id).filter(|e| e.timestamp >= from_ts && e.timestamp < to_ts).collect()
+    }
+
+    pub fn events_by_deed_type(&self, deed_type: &str) -> Vec<&DeedEvent> {
+        self.indexed(&self.by_type, deed_type).collect()
+    }
+
+    /// Up to `limit` events from position `offset`, in append order.
+    pub fn events_page(&self, offset: usize, limit: usize) -> &[DeedEvent] {
+        let start = offset.min(self.events.len());
+        let end = start.saturating_add(limit).min(self.events.len());
+        &self.events[start..end]
+    }
+
+    fn indexed<'a>(
+        &'a self,
+        index: &'a HashMap<String, Vec<usize>>,
+        key: &str,
+    ) -> impl Iterator<Item = &'a DeedEvent> + 'a {
+        index.get(key).into_iter().flatten().map(move |&i| &self.events[i])
     }
 }
@@ -0,0 +1,62 @@
+//! Write-ahead JSONL store for a ledger's events: one enveloped deed per
+//! line (`deed_core::encode_line`), fsynced before `Ledger::append`
+//! returns. `Ledger::open` replays it through `append`. A crash mid-write
+//! leaves a final line without its newline; opening truncates it away
+//! with a warning, so the ledger comes back at its last complete event.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use deed_core::{decode_line, encode_line, DeedCoreError};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::ledger::{DeedEvent, LedgerError};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("ledger store {path:?}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("ledger store line {line}: {source}")]
+    Line { line: usize, source: DeedCoreError },
+    #[error("ledger store line {line}: {source}")]
+    Chain { line: usize, source: LedgerError },
+}
+
+pub(crate) struct LedgerStore {
+    file: File,
+}
+
+impl LedgerStore {
+    /// Open the store at `path`, creating it if missing, and read back
+    /// the events it holds.
+    pub(crate) fn open(path: &Path) -> Result<(Self, Vec<DeedEvent>), StoreError> {
+        let io_error = |source| StoreError::Io { path: path.to_path_buf(), source };
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(path).map_err(io_error)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(io_error)?;
+
+        let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if complete < bytes.len() {
+            warn!("ledger store {:?}: truncating a torn final line of {} bytes", path, bytes.len() - complete);
+            file.set_len(complete as u64).map_err(io_error)?;
+            file.sync_data().map_err(io_error)?;
+        }
+
+        let mut events = Vec::new();
+        for (i, line) in bytes[..complete].split(|&b| b == b'\n').enumerate().filter(|(_, l)| !l.is_empty()) {
+            let event = decode_line(&String::from_utf8_lossy(line)).map_err(|source| StoreError::Line { line: i + 1, source })?;
+            events.push(event);
+        }
+        Ok((Self { file }, events))
+    }
+
+    /// Write `event` as one line and fsync it.
+    pub(crate) fn append(&mut self, event: &DeedEvent) -> io::Result<()> {
+        let mut line = encode_line(event);
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()
+    }
+}
@@ -0,0 +1,9 @@
+//! The ledger half of the Church-of-FEAR node as a library: the deed log,
+//! account state, Merkle anchoring, the bioload fusion its metrics read and
+//! the regulator that evaluates them.
+//! `src/main.rs` builds the node on top of these modules.
+
+pub mod compliance;
+pub mod fusion;
+pub mod ledger;
+pub mod utils;
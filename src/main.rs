@@ -18,13 +18,15 @@ mod config;
 mod ledger;
 mod token;
 mod compliance;
+mod fusion;
+mod regulator_replay;
 mod sponsor;
 mod utils;
 
 use config::Config;
-use ledger::{Account, Balance, Deed, Ledger, Metrics};
+use ledger::{Account, Balance, Deed, Ledger, Metrics, SnapshotPolicy, SnapshotRecorder};
 use token::{Burn, Mint, Rewards};
-use compliance::{EthicsDecision, EthicsSummary, Regulator};
+use compliance::{DecisionFilter, DecisionRecorder, EthicsDecision, EthicsSummary, HysteresisParams, Regulator};
 use sponsor::SponsorEngine;
 use utils::{now_utc, shutdown_notify};
 
@@ -61,6 +63,13 @@ impl AppState {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `church-of-fear regulator-replay <request.json> [--json]` runs the
+    // post-incident replay tool instead of the node.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("regulator-replay") {
+        return run_regulator_replay(&args[2..]);
+    }
+
     init_tracing();
 
     info!("Church-of-FEAR node starting…");
@@ -92,6 +101,18 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn run_regulator_replay(args: &[String]) -> anyhow::Result<()> {
+    let path = args.first().ok_or_else(|| anyhow::anyhow!("usage: regulator-replay <request.json> [--json]"))?;
+    let request: regulator_replay::ReplayRequest = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let report = regulator_replay::run(&request)?;
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", report.to_json()?);
+    } else {
+        print!("{}", report.to_table());
+    }
+    Ok(())
+}
+
 /// Initialize tracing subscriber for structured logs.
 fn init_tracing() {
     let subscriber = FmtSubscriber::builder()
@@ -139,6 +160,10 @@ async fn seed_genesis_accounts(state: &AppState) -> anyhow::Result<()> {
 /// - keeps POWER/TECH growth bounded by CHURCH and bioload ceilings. [file:3][file:9][file:11]
 async fn run_main_loop(state: AppState, shutdown: tokio::sync::watch::Receiver<bool>) -> anyhow::Result<()> {
     let tick_interval = Duration::from_millis(500);
+    let mut filter = DecisionFilter::new(HysteresisParams::default());
+    let mut recorder = DecisionRecorder::new();
+    let mut snapshots = SnapshotRecorder::new(SnapshotPolicy::default());
+    let mut tick: u64 = 0;
 
     loop {
         if *shutdown.borrow() {
@@ -153,10 +178,23 @@ async fn run_main_loop(state: AppState, shutdown: tokio::sync::watch::Receiver<b
             ledger.compute_metrics()?
         };
 
+        // Persist the exact regulator input periodically so the run can be
+        // replayed with `regulator-replay`.
+        let unix_secs = tick_start.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        if let Some(snapshot) = snapshots.observe(tick, unix_secs, &metrics) {
+            let mut ledger = state.ledger.write().await;
+            let deed = snapshot.to_deed(ledger.last_hash());
+            // A rejected snapshot is lost, not fatal: the next one is taken on schedule.
+            if let Err(e) = ledger.append(deed) {
+                error!("Metrics snapshot at tick {} not persisted: {}", tick, e);
+            }
+        }
+
         let ethics_summary = EthicsSummary::from_metrics(&metrics);
-        let decision = state.regulator.evaluate(&ethics_summary)?;
+        let decision = filter.apply(state.regulator.evaluate(&ethics_summary)?);
 
-        apply_ethics_decision(&state, &metrics, &decision).await?;
+        apply_ethics_decision(&state, &mut recorder, tick, &metrics, &decision).await?;
+        tick += 1;
 
         apply_sponsor_rewards(&state, &metrics).await?;
 
@@ -176,11 +214,22 @@ async fn run_main_loop(state: AppState, shutdown: tokio::sync::watch::Receiver<b
 /// - Warn: log and potentially tighten FEAR bands in config (via ledger flags).
 /// - ForceRepair: bias deeds toward repair, limit POWER/TECH updates. [file:6][file:9]
 /// - HaltAndReview: freeze high-impact deeds, keep logging only. [file:6][file:11]
+///
+/// Every non-Allow decision, and the lifting of a freeze, is appended to the
+/// ledger as an `ethics_decision` deed under the same write lock that applies
+/// its effect; if the record is rejected the effect is not applied.
 async fn apply_ethics_decision(
     state: &AppState,
+    recorder: &mut DecisionRecorder,
+    tick: u64,
     metrics: &Metrics,
     decision: &EthicsDecision,
 ) -> anyhow::Result<()> {
+    let mut ledger = state.ledger.write().await;
+    if recorder.record(&mut ledger, tick, decision, metrics)? {
+        info!("Ethics: freeze lifted at tick {}", tick);
+        ledger.unfreeze_high_impact_deeds()?;
+    }
     match decision {
         EthicsDecision::Allow => {
             info!(
@@ -199,7 +248,6 @@ async fn apply_ethics_decision(
                 "Ethics: ForceRepair – {} (forcing repair-biased deeds)",
                 reason
             );
-            let mut ledger = state.ledger.write().await;
             ledger.set_repair_bias(true)?;
         }
         EthicsDecision::HaltAndReview { reason } => {
@@ -207,7 +255,6 @@ async fn apply_ethics_decision(
                 "Ethics: HaltAndReview – {} (freezing high-impact actions)",
                 reason
             );
-            let mut ledger = state.ledger.write().await;
             ledger.freeze_high_impact_deeds()?;
         }
     }
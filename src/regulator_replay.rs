@@ -0,0 +1,238 @@
+// Post-incident regulator replay:
+// - Input is the MetricsSnapshot series the node persisted as deeds, a
+//   ComplianceConfig, and one or more hysteresis parameter sets.
+// - Each parameter set replays Regulator::evaluate + DecisionFilter over the
+//   series, exactly as the main loop runs them, so the live decision
+//   sequence is reproduced.
+// - The report gives the full timeline, first-escalation times, and a
+//   comparison across parameter sets (earliest Warn first).
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compliance::{ComplianceConfig, DecisionFilter, EthicsDecision, EthicsSummary, HysteresisParams, Regulator};
+use crate::ledger::{DeedEvent, MetricsSnapshot};
+
+/// A named hysteresis configuration to compare.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamSet {
+    pub name: String,
+    pub hysteresis: HysteresisParams,
+}
+
+/// Everything one replay run needs; the CLI reads this from JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    pub param_sets: Vec<ParamSet>,
+    pub snapshots: Vec<MetricsSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub tick: u64,
+    pub timestamp: u64,
+    /// What evaluate() returned for this snapshot alone.
+    pub raw: EthicsDecision,
+    /// What the node acted on after hysteresis.
+    pub decision: EthicsDecision,
+}
+
+/// Unix seconds at which a decision first reached each severity.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirstEscalation {
+    pub warn: Option<u64>,
+    pub force_repair: Option<u64>,
+    pub halt: Option<u64>,
+}
+
+impl FirstEscalation {
+    fn observe(&mut self, decision: &EthicsDecision, timestamp: u64) {
+        let severity = decision.severity();
+        for (level, slot) in [(1, &mut self.warn), (2, &mut self.force_repair), (3, &mut self.halt)] {
+            if severity >= level && slot.is_none() {
+                *slot = Some(timestamp);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRun {
+    pub name: String,
+    pub hysteresis: HysteresisParams,
+    pub timeline: Vec<TimelineEntry>,
+    pub first: FirstEscalation,
+    /// Number of changes in effective severity.
+    pub transitions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    /// When conditions first warranted each level, before hysteresis.
+    pub raw_first: FirstEscalation,
+    /// One run per parameter set, earliest first Warn first; sets that
+    /// never warn come last. Ties keep the request order.
+    pub runs: Vec<ReplayRun>,
+}
+
+/// Pull the persisted snapshots out of a deed log, in ledger order.
+pub fn snapshots_from_deeds<'a, I: IntoIterator<Item = &'a DeedEvent>>(deeds: I) -> Vec<MetricsSnapshot> {
+    deeds.into_iter().filter_map(MetricsSnapshot::from_deed).collect()
+}
+
+/// Replay one parameter set over a time-ordered snapshot series.
+pub fn replay(regulator: &Regulator, set: &ParamSet, snapshots: &[MetricsSnapshot]) -> anyhow::Result<ReplayRun> {
+    let mut filter = DecisionFilter::new(set.hysteresis);
+    let mut first = FirstEscalation::default();
+    let mut timeline = Vec::with_capacity(snapshots.len());
+    let mut transitions = 0;
+    let mut previous = EthicsDecision::Allow.severity();
+
+    for (i, snap) in snapshots.iter().enumerate() {
+        if i > 0 && (snap.tick, snap.timestamp) < (snapshots[i - 1].tick, snapshots[i - 1].timestamp) {
+            anyhow::bail!("snapshots out of order at tick {}", snap.tick);
+        }
+        let raw = regulator.evaluate(&EthicsSummary::from_metrics(&snap.metrics))?;
+        let decision = filter.apply(raw.clone());
+        if decision.severity() != previous {
+            transitions += 1;
+            previous = decision.severity();
+        }
+        first.observe(&decision, snap.timestamp);
+        timeline.push(TimelineEntry { tick: snap.tick, timestamp: snap.timestamp, raw, decision });
+    }
+
+    Ok(ReplayRun { name: set.name.clone(), hysteresis: set.hysteresis, timeline, first, transitions })
+}
+
+/// Replay every parameter set in `request` and rank them.
+pub fn run(request: &ReplayRequest) -> anyhow::Result<ReplayReport> {
+    let regulator = Regulator::new(request.compliance.clone())?;
+
+    let mut raw_first = FirstEscalation::default();
+    for snap in &request.snapshots {
+        raw_first.observe(&regulator.evaluate(&EthicsSummary::from_metrics(&snap.metrics))?, snap.timestamp);
+    }
+
+    let mut runs = request
+        .param_sets
+        .iter()
+        .map(|set| replay(&regulator, set, &request.snapshots))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    // Stable sort: ties keep request order.
+    runs.sort_by_key(|r| (r.first.warn.is_none(), r.first.warn));
+
+    Ok(ReplayReport { raw_first, runs })
+}
+
+impl ReplayReport {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Comparison table, one row per parameter set.
+    pub fn to_table(&self) -> String {
+        let at = |t: Option<u64>| t.map(|t| t.to_string()).unwrap_or_else(|| "-".into());
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "raw: first_warn={} first_repair={} first_halt={}",
+            at(self.raw_first.warn),
+            at(self.raw_first.force_repair),
+            at(self.raw_first.halt)
+        );
+        let _ = writeln!(
+            out,
+            "{:<16} {:>4} {:>4} {:>12} {:>12} {:>12} {:>11}",
+            "param_set", "esc", "rel", "first_warn", "first_repair", "first_halt", "transitions"
+        );
+        for r in &self.runs {
+            let _ = writeln!(
+                out,
+                "{:<16} {:>4} {:>4} {:>12} {:>12} {:>12} {:>11}",
+                r.name,
+                r.hysteresis.escalate_after,
+                r.hysteresis.release_after,
+                at(r.first.warn),
+                at(r.first.force_repair),
+                at(r.first.halt),
+                r.transitions
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{Metrics, SnapshotPolicy, SnapshotRecorder};
+
+    fn metrics(bioload: f64) -> Metrics {
+        Metrics { total_bioload: bioload, bioload_variance: 0.0, mean_trust: 0.9, power_gini: 0.2 }
+    }
+
+    /// Bioload climbs through warn into repair with a one-tick dip.
+    fn script() -> Vec<f64> {
+        vec![0.2, 0.4, 0.65, 0.5, 0.7, 0.72, 0.85, 0.9, 0.7, 0.5, 0.3, 0.2]
+    }
+
+    fn set(name: &str, escalate_after: u32, release_after: u32) -> ParamSet {
+        ParamSet { name: name.into(), hysteresis: HysteresisParams { escalate_after, release_after } }
+    }
+
+    #[test]
+    fn replay_reproduces_live_decisions() {
+        let regulator = Regulator::new(ComplianceConfig::default()).unwrap();
+        let params = set("live", 2, 2);
+
+        // Live loop: evaluate, filter, persist snapshots as deeds.
+        let mut filter = DecisionFilter::new(params.hysteresis);
+        let mut recorder = SnapshotRecorder::new(SnapshotPolicy { every_n_ticks: 1, min_interval_secs: 0 });
+        let mut deeds: Vec<DeedEvent> = Vec::new();
+        let mut live = Vec::new();
+        for (tick, bioload) in script().into_iter().enumerate() {
+            let m = metrics(bioload);
+            let raw = regulator.evaluate(&EthicsSummary::from_metrics(&m)).unwrap();
+            live.push(filter.apply(raw));
+            if let Some(snap) = recorder.observe(tick as u64, 100 + tick as u64, &m) {
+                let prev = deeds.last().map(|d| d.self_hash.clone()).unwrap_or_default();
+                deeds.push(snap.to_deed(&prev));
+            }
+        }
+
+        let run = replay(&regulator, &params, &snapshots_from_deeds(&deeds)).unwrap();
+        let replayed: Vec<EthicsDecision> = run.timeline.into_iter().map(|e| e.decision).collect();
+        assert_eq!(replayed, live);
+        assert_eq!(run.first.warn, Some(105));
+        assert_eq!(run.first.force_repair, Some(107));
+    }
+
+    #[test]
+    fn sweep_orders_by_first_warn() {
+        let snapshots = script()
+            .into_iter()
+            .enumerate()
+            .map(|(t, b)| MetricsSnapshot { tick: t as u64, timestamp: 100 + t as u64, metrics: metrics(b) })
+            .collect();
+        let request = ReplayRequest {
+            compliance: ComplianceConfig::default(),
+            param_sets: vec![set("sluggish", 6, 1), set("slow", 3, 1), set("eager", 1, 1), set("eager-sticky", 1, 4)],
+            snapshots,
+        };
+        let report = run(&request).unwrap();
+        let names: Vec<&str> = report.runs.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["eager", "eager-sticky", "slow", "sluggish"]);
+        assert_eq!(report.raw_first.warn, Some(102));
+        assert_eq!(report.runs[0].first.warn, Some(102));
+        assert_eq!(report.runs[3].first.warn, None);
+
+        let table = report.to_table();
+        assert!(table.lines().nth(2).unwrap().starts_with("eager "));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["runs"][2]["name"], "slow");
+    }
+}
@@ -1,5 +1,43 @@
-pub fn time_discount_factor(age_seconds: u64) -> f64 {
-    // Exponential decay: e^(-age / tau), tau = 1 day
-    let tau = 86400.0;
-    (-(age_seconds as f64) / tau).exp()
+use chrono::Utc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Where the ledger reads the time, in Unix milliseconds.
+pub trait Clock: Send + Sync {
+    fn now_millis(&self) -> i64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+}
+
+/// A clock that only moves when told to, for tests and replays.
+#[derive(Debug, Default)]
+pub struct FixedClock(AtomicI64);
+
+impl FixedClock {
+    pub fn new(millis: i64) -> Self {
+        Self(AtomicI64::new(millis))
+    }
+
+    pub fn advance(&self, millis: i64) {
+        self.0.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now_millis(&self) -> i64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+pub const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Weight of a deed `age_millis` old: halves every `half_life_millis`.
+pub fn time_discount_factor(age_millis: u64, half_life_millis: f64) -> f64 {
+    0.5f64.powf(age_millis as f64 / half_life_millis)
 }
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use super::super::ledger::{DeedEvent, Ledger, ChurchAccountState};
+    use church_of_fear_ledger::ledger::{verify_merkle_proof, DeedEvent, EMPTY_MERKLE_ROOT, Ledger, LedgerError, ChurchAccountState, RecomputeOptions, MAX_IMPACT};
+    use church_of_fear_ledger::utils::time::FixedClock;
+    use deed_core::{decode_line, encode_line, DeedCoreError, HashRule};
     use serde_json::json;
     use uuid::Uuid;
 
@@ -19,33 +21,421 @@ mod tests {
             context_json: json!({}),
             ethics_flags: vec![],
             life_harm_flag: false,
+            ..Default::default()
         };
         deed.self_hash = deed.compute_self_hash();
-        ledger.append(deed.clone());
+        ledger.append(deed.clone()).unwrap();
         assert_eq!(ledger.last_hash(), deed.self_hash);
     }
 
     #[test]
     fn test_account_compute() {
-        let mut ledger = Ledger::new();
+        let t0 = 1_700_000_000_000;
+        let mut ledger = Ledger::with_clock(Box::new(FixedClock::new(t0)));
         let mut deed_good = DeedEvent {
             event_id: Uuid::new_v4().to_string(),
-            timestamp: 0,
+            timestamp: t0,
             prev_hash: String::new(),
             self_hash: String::new(),
             actor_id: "test".to_string(),
             target_ids: vec![],
             deed_type: "ecological_sustainability".to_string(),
-            tags: vec!["tree_planting".to_string()],
+            tags: vec!["ecological_sustainability".to_string()],
             context_json: json!({}),
             ethics_flags: vec![],
             life_harm_flag: false,
+            ..Default::default()
         };
         deed_good.self_hash = deed_good.compute_self_hash();
-        ledger.append(deed_good);
+        ledger.append(deed_good).unwrap();
 
         let state = ChurchAccountState::compute_from_ledger(&ledger, "test").unwrap();
         assert!(state.can_mint_church());
-        assert_eq!(state.compute_mint_amount(), 7.0); // Assuming eco_score=0.7
+        assert_eq!(state.compute_mint_amount(), 10.0); // One fresh clean deed: weight 1.0, eco_score = 0.7 * 1.0 + 0.3 * (1 - 0) = 1.0
+    }
+
+    #[test]
+    fn test_same_millisecond_deeds_hash_reproducibly() {
+        let t0 = 1_700_000_000_123;
+        let build = || {
+            let mut ledger = Ledger::with_clock(Box::new(FixedClock::new(t0)));
+            for seq in 0..2 {
+                let mut deed = DeedEvent {
+                    event_id: format!("deed-{}", seq),
+                    timestamp: ledger.now_millis(),
+                    prev_hash: ledger.last_hash().to_string(),
+                    self_hash: String::new(),
+                    actor_id: "test".to_string(),
+                    target_ids: vec![],
+                    deed_type: "ecological_sustainability".to_string(),
+                    tags: vec!["ecological_sustainability".to_string()],
+                    context_json: json!({ "seq": seq }),
+                    ethics_flags: vec![],
+                    life_harm_flag: false,
+                    ..Default::default()
+                };
+                deed.self_hash = deed.compute_self_hash();
+                ledger.append(deed).unwrap();
+            }
+            ledger
+        };
+        let (a, b) = (build(), build());
+        let events = a.events_for_actor("test");
+        assert_eq!(events[0].timestamp, events[1].timestamp);
+        assert_ne!(events[0].self_hash, events[1].self_hash);
+        assert_eq!(a.last_hash(), b.last_hash());
+
+        // Nothing has aged on a fixed clock: both deeds count in full.
+        let state = ChurchAccountState::compute_from_ledger(&a, "test").unwrap();
+        assert_eq!(state.cumulative_good_deeds, 2.0);
+
+        // Lines written before the envelope, without self_hash, read their
+        // seconds timestamps back as milliseconds.
+        let stored = serde_json::to_value(events[0]).unwrap();
+        assert_eq!(stored["timestamp"], t0);
+        let mut legacy = stored.clone();
+        legacy["timestamp"] = json!(1_700_000_000);
+        legacy.as_object_mut().unwrap().remove("self_hash");
+        let read = decode_line(&legacy.to_string()).unwrap();
+        assert_eq!(read.timestamp, 1_700_000_000_000);
+        assert!(read.verify_hash());
+    }
+
+    fn sealed(event_id: &str, prev_hash: &str) -> DeedEvent {
+        let mut deed = DeedEvent {
+            event_id: event_id.to_string(),
+            timestamp: 1_700_000_000_000,
+            prev_hash: prev_hash.to_string(),
+            self_hash: String::new(),
+            actor_id: "test".to_string(),
+            target_ids: vec![],
+            deed_type: "test".to_string(),
+            tags: vec![],
+            context_json: json!({}),
+            ethics_flags: vec![],
+            life_harm_flag: false,
+            ..Default::default()
+        };
+        deed.self_hash = deed.compute_self_hash();
+        deed
+    }
+
+    #[test]
+    fn test_append_rejects_without_changing_the_ledger() {
+        let mut ledger = Ledger::new();
+        ledger.append(sealed("a", "")).unwrap();
+        let head = ledger.last_hash().to_string();
+
+        // A stale event still pointing at the old head.
+        assert_eq!(
+            ledger.append(sealed("b", "")),
+            Err(LedgerError::PrevHashMismatch { expected: head.clone(), got: String::new() })
+        );
+
+        let mut edited = sealed("b", &head);
+        edited.context_json = json!({ "edited": true });
+        let recomputed = edited.compute_self_hash();
+        assert_eq!(
+            ledger.append(edited.clone()),
+            Err(LedgerError::SelfHashMismatch { event_id: "b".to_string(), expected: recomputed, got: edited.self_hash })
+        );
+
+        assert_eq!(ledger.append(sealed("a", &head)), Err(LedgerError::DuplicateEventId("a".to_string())));
+        assert_eq!(ledger.last_hash(), head);
+        assert_eq!(ledger.events_for_actor("test").len(), 1);
+
+        let second = sealed("b", &head);
+        ledger.append(second.clone()).unwrap();
+        assert_eq!(ledger.last_hash(), second.self_hash);
+    }
+
+    #[test]
+    fn test_indexed_queries_over_interleaved_actors() {
+        let t0: i64 = 1_700_000_000_000;
+        let actors = ["alice", "bob", "carol"];
+        let types = ["tree_planting", "shelter_shift", "tutoring", "cleanup"];
+        let mut ledger = Ledger::new();
+        for i in 0..10_000usize {
+            // Bursts of one actor between stretches of round-robin appends.
+            let actor = if i % 1_000 < 100 { "alice" } else { actors[i % 3] };
+            let mut deed = sealed(&format!("e{}", i), ledger.last_hash());
+            deed.actor_id = actor.to_string();
+            deed.deed_type = types[i % 4].to_string();
+            deed.timestamp = t0 + i as i64 * 1_000;
+            deed.self_hash = deed.compute_self_hash();
+            ledger.append(deed).unwrap();
+        }
+        assert_eq!(ledger.len(), 10_000);
+
+        let all = ledger.events_page(0, usize::MAX);
+        let ids = |events: Vec<&DeedEvent>| events.iter().map(|e| e.event_id.clone()).collect::<Vec<_>>();
+        let (from, to) = (t0 + 2_500_000, t0 + 4_000_000);
+        for actor in actors {
+            let scanned = ids(all.iter().filter(|e| e.actor_id == actor).collect());
+            assert_eq!(ids(ledger.events_for_actor(actor)), scanned, "{}", actor);
+
+            let expected =
+                ids(all.iter().filter(|e| e.actor_id == actor && e.timestamp >= from && e.timestamp < to).collect());
+            assert!(!expected.is_empty());
+            assert_eq!(ids(ledger.events_for_actor_in_range(actor, from, to)), expected, "{}", actor);
+        }
+        assert!(ledger.events_for_actor_in_range("alice", t0, t0).is_empty());
+        assert!(ledger.events_for_actor("nobody").is_empty());
+
+        let tutoring = ledger.events_by_deed_type("tutoring");
+        assert_eq!(tutoring.len(), 2_500);
+        assert!(tutoring.iter().all(|e| e.deed_type == "tutoring"));
+
+        let page = ledger.events_page(9_990, 50);
+        assert_eq!(page.len(), 10);
+        assert_eq!(page[0].event_id, "e9990");
+        assert!(ledger.events_page(20_000, 10).is_empty());
+
+        // A rejected append leaves the indexes as they were.
+        assert!(ledger.append(sealed("e0", ledger.last_hash())).is_err());
+        assert!(ledger.events_by_deed_type("test").is_empty());
+        assert!(ledger.events_for_actor("test").is_empty());
+    }
+
+    #[test]
+    fn test_merkle_proofs_and_anchor_manifest() {
+        let t0: i64 = 1_700_000_000_000;
+        let mut ledger = Ledger::with_clock(Box::new(FixedClock::new(t0)));
+        assert_eq!(ledger.merkle_root(), EMPTY_MERKLE_ROOT);
+        for i in 0..7 {
+            ledger.append(sealed(&format!("e{}", i), ledger.last_hash())).unwrap();
+        }
+        let root = ledger.merkle_root();
+        let events = ledger.events_page(0, 7);
+        // 7 leaves: odd levels pair their last hash with itself.
+        for i in [0, 3, 6] {
+            let proof = ledger.merkle_proof(&events[i].event_id).unwrap();
+            assert_eq!(proof.len(), 3);
+            assert!(verify_merkle_proof(&root, &events[i].self_hash, &proof), "e{}", i);
+        }
+        assert!(ledger.merkle_proof("missing").is_none());
+
+        let mut tampered = ledger.merkle_proof("e3").unwrap();
+        tampered[1].sibling = events[0].self_hash.clone();
+        assert!(!verify_merkle_proof(&root, &events[3].self_hash, &tampered));
+        let proof = ledger.merkle_proof("e3").unwrap();
+        let mut edited = events[3].clone();
+        edited.context_json = json!({ "edited": true });
+        assert!(!verify_merkle_proof(&root, &edited.compute_self_hash(), &proof));
+
+        let manifest = ledger.anchor_manifest("bostrom");
+        assert_eq!((manifest.height, manifest.event_count, manifest.created_at), (3, 7, t0));
+        assert_eq!(manifest.root, root);
+        let posted = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(posted["anchor_target"], "bostrom");
+        assert_eq!(posted["root"], root);
+    }
+
+    #[test]
+    fn test_future_dated_deeds_age_zero_against_a_pinned_now() {
+        let t0: i64 = 1_700_000_000_000;
+        let mut ledger = Ledger::with_clock(Box::new(FixedClock::new(t0)));
+        let mut deed = sealed("future", "");
+        deed.tags = vec!["ecological_sustainability".to_string()];
+        deed.timestamp = t0 + 60_000;
+        deed.self_hash = deed.compute_self_hash();
+        ledger.append(deed).unwrap();
+
+        // A minute ahead of the ledger's clock: counted in full, not as an overflowed age.
+        let state = ChurchAccountState::compute_from_ledger(&ledger, "test").unwrap();
+        assert_eq!(state.cumulative_good_deeds, 1.0);
+
+        let day = 86_400_000;
+        let options = RecomputeOptions { now_override: Some(t0 + 60_000 + 2 * day), half_life_days: 1.0, ..RecomputeOptions::default() };
+        let state = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+        assert!((state.cumulative_good_deeds - 0.25).abs() < 1e-12);
+
+        // The default half-life keeps the one-day decay constant.
+        let options = RecomputeOptions { now_override: Some(t0 + 60_000 + day), ..RecomputeOptions::default() };
+        let state = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+        assert!((state.cumulative_good_deeds - (-1.0f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_good_deeds_weigh_by_impact() {
+        let t0: i64 = 1_700_000_000_000;
+        let good = |ledger: &Ledger, id: &str, deed_type: &str, context: serde_json::Value| {
+            let mut deed = sealed(id, ledger.last_hash());
+            deed.deed_type = deed_type.to_string();
+            deed.tags = vec!["ecological_sustainability".to_string()];
+            deed.timestamp = t0;
+            deed.context_json = context;
+            deed.self_hash = deed.compute_self_hash();
+            deed
+        };
+        let mut options = RecomputeOptions { now_override: Some(t0), ..RecomputeOptions::default() };
+        options.impact_defaults.insert("watershed_restoration".to_string(), 6.0);
+
+        let mut ledger = Ledger::new();
+        let deeds = [
+            ("tree", "tree_planting", json!({})),
+            ("watershed", "watershed_restoration", json!({})),
+            ("survey", "watershed_restoration", json!({ "impact": 0.5 })),
+            ("overclaimed", "tree_planting", json!({ "impact": 250.0 })),
+            ("negative", "tree_planting", json!({ "impact": -3.0 })),
+        ];
+        // Missing impact weighs 1.0; the type default, then the clamp, apply.
+        let expected_totals = [1.0, 7.0, 7.5, 7.5 + MAX_IMPACT, 7.5 + MAX_IMPACT];
+        let mut balance = 0.0;
+        for ((id, deed_type, context), expected) in deeds.into_iter().zip(expected_totals) {
+            ledger.append(good(&ledger, id, deed_type, context)).unwrap();
+            let state = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+            assert!((state.cumulative_good_deeds - expected).abs() < 1e-12, "{}", id);
+            // Adding a good deed never lowers the balance.
+            assert!(state.church_balance >= balance, "{}", id);
+            balance = state.church_balance;
+        }
+
+        let state = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+        assert_eq!(state.eco_score, 1.0);
+        assert!((state.compute_mint_amount() - 17.5 * 10.0).abs() < 1e-9);
+        // Without the type table an unrated restoration weighs as one deed.
+        let plain = ChurchAccountState::compute_from_ledger_with(
+            &ledger,
+            "test",
+            &RecomputeOptions { now_override: Some(t0), ..RecomputeOptions::default() },
+        )
+        .unwrap();
+        assert!((plain.cumulative_good_deeds - 12.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_moral_ledger_deeds_validate_here_and_back() {
+        use church_of_fear::ledger::deed_event::{link_fault, DeedEvent as MoralDeed};
+
+        // A chain as the moral ledger writes it: seconds, self_hash blanked while hashing.
+        let mut moral: Vec<MoralDeed> = Vec::new();
+        for i in 0..3 {
+            let prev = moral.last().map_or_else(|| "0".repeat(64), |d| d.self_hash.clone());
+            let deed = MoralDeed::new(
+                prev,
+                "alice".to_string(),
+                vec![],
+                "ecological_sustainability".to_string(),
+                vec!["ecological_sustainability".to_string()],
+                json!({ "seq": i }),
+                vec![],
+                false,
+            );
+            moral.push(deed);
+        }
+
+        // Its JSONL lines load into the ledger with every hash and link intact.
+        let lines: Vec<String> = moral.iter().map(|d| serde_json::to_string(d).unwrap()).collect();
+        let shared: Vec<DeedEvent> = lines.iter().map(|l| decode_line(l).unwrap()).collect();
+        let mut ledger = Ledger::from_chain(shared).unwrap();
+        assert_eq!(ledger.last_hash(), moral[2].self_hash);
+        let state = ChurchAccountState::compute_from_ledger(&ledger, "alice").unwrap();
+        assert!(state.cumulative_good_deeds > 0.0);
+
+        // And go back out as deeds the moral ledger verifies.
+        let mut prev = "0".repeat(64);
+        for deed in ledger.events_page(0, 3) {
+            let back = MoralDeed::try_from(decode_line(&encode_line(deed)).unwrap()).unwrap();
+            assert_eq!(link_fault(&back, &prev), None);
+            prev = back.self_hash.clone();
+        }
+
+        // A deed sealed here extends the chain but cannot pass as a moral-ledger deed.
+        let mut native = sealed("native", "");
+        native.seal(ledger.last_hash());
+        ledger.append(native.clone()).unwrap();
+        assert!(matches!(
+            MoralDeed::try_from(native),
+            Err(DeedCoreError::HashRule { expected: HashRule::MoralLedgerV1, found: HashRule::Canonical, .. })
+        ));
+    }
+
+    #[test]
+    fn test_moral_ledger_files_round_trip_through_the_ledger() {
+        use moral_ledger::{DeedEvent as MoralDeed, MoralLedger};
+
+        let file = || std::env::temp_dir().join(format!("cof-moral-{}.jsonl", Uuid::new_v4()));
+        let append = |moral: &mut MoralLedger, mut deed: MoralDeed| {
+            deed.prev_hash = moral.last_hash().to_string();
+            moral.append(deed).unwrap();
+        };
+        let written = file();
+        let mut moral = MoralLedger::open_or_create(written.clone()).unwrap();
+        for i in 0..3 {
+            append(&mut moral, MoralDeed::new_math_science_education("alice".to_string(), format!("crate-{}", i)));
+        }
+
+        // Every deed MoralLedger appended validates here, links included.
+        let lines = std::fs::read_to_string(&written).unwrap();
+        let mut ledger = Ledger::from_chain(lines.lines().map(|l| decode_line(l).unwrap()).collect()).unwrap();
+        assert_eq!(ledger.last_hash(), moral.last_hash());
+
+        // Written back out, the ledger's deeds are the same lines, and a
+        // MoralLedger reopened on them extends the chain.
+        let copied = file();
+        let back: String = ledger
+            .events_page(0, 3)
+            .iter()
+            .map(|d| serde_json::to_string(&MoralDeed::try_from(decode_line(&encode_line(d)).unwrap()).unwrap()).unwrap() + "\n")
+            .collect();
+        assert_eq!(back, lines);
+        std::fs::write(&copied, back).unwrap();
+        let mut reopened = MoralLedger::open_or_create(copied.clone()).unwrap();
+        assert_eq!(reopened.last_hash(), ledger.last_hash());
+        append(&mut reopened, MoralDeed::new_ecological_sustainability("bob".to_string(), "ipfs://receipt".to_string()));
+        let next = std::fs::read_to_string(&copied).unwrap().lines().last().map(decode_line).unwrap().unwrap();
+        ledger.append(next).unwrap();
+        assert_eq!(ledger.last_hash(), reopened.last_hash());
+
+        // A deed sealed here has a hash MoralLedger cannot reproduce.
+        let mut native = sealed("native", "");
+        native.seal(ledger.last_hash());
+        assert!(matches!(MoralDeed::try_from(native), Err(DeedCoreError::HashRule { .. })));
+
+        std::fs::remove_file(&written).unwrap();
+        std::fs::remove_file(&copied).unwrap();
+    }
+
+    #[test]
+    fn test_reopened_store_recovers_the_last_complete_event() {
+        let t0: i64 = 1_700_000_000_000;
+        let path = std::env::temp_dir().join(format!("cof-ledger-{}.jsonl", Uuid::new_v4()));
+        let options = RecomputeOptions { now_override: Some(t0), ..RecomputeOptions::default() };
+        let good = |ledger: &Ledger, id: &str| {
+            let mut deed = sealed(id, ledger.last_hash());
+            deed.tags = vec!["ecological_sustainability".to_string()];
+            deed.timestamp = t0;
+            deed.self_hash = deed.compute_self_hash();
+            deed
+        };
+
+        let mut ledger = Ledger::open(&path).unwrap();
+        assert!(ledger.is_empty());
+        ledger.append(good(&ledger, "first")).unwrap();
+        ledger.append(good(&ledger, "second")).unwrap();
+        let before = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+        let hash_before = ledger.last_hash().to_string();
+        ledger.append(good(&ledger, "third")).unwrap();
+        drop(ledger);
+
+        // The process dies partway through writing the third event.
+        let len = std::fs::metadata(&path).unwrap().len();
+        std::fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 20).unwrap();
+
+        let mut ledger = Ledger::open(&path).unwrap();
+        assert_eq!((ledger.len(), ledger.last_hash()), (2, hash_before.as_str()));
+        let after = ChurchAccountState::compute_from_ledger_with(&ledger, "test", &options).unwrap();
+        assert_eq!(after.cumulative_good_deeds, before.cumulative_good_deeds);
+        assert_eq!(after.church_balance, before.church_balance);
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with('\n'));
+
+        // The torn event can be appended again and survives the next restart.
+        ledger.append(good(&ledger, "third")).unwrap();
+        drop(ledger);
+        let ledger = Ledger::open(&path).unwrap();
+        assert_eq!(ledger.len(), 3);
+        assert_eq!(ledger.events_for_actor("test")[2].event_id, "third");
+        std::fs::remove_file(&path).unwrap();
     }
 }